@@ -0,0 +1,394 @@
+//! sezkp-soak
+//!
+//! Prove and verify random cases until a time budget runs out, logging every
+//! failure with the seed that reproduces it. Meant for nightly automation:
+//! single runs of the test suite rarely hit the odd trace lengths, block
+//! sizes and parameter mixes where collapse and boundary bugs hide.
+//!
+//! Each case is drawn from one 64-bit seed: trace length `T` (biased towards
+//! tiny, odd and power-of-two ± 1 lengths), block size, τ, trace seed,
+//! backend and its options. Backends:
+//!   - `fold`: batch driver with random mode, wrap cadence, ARE samples,
+//!     interface digest and ledger; the bundle must verify and its top must
+//!     match a Balanced in-memory run with the same protocol parameters.
+//!   - `fold-backend`: `FoldAgg` prove/verify against the manifest root.
+//!   - `stark`: STARK v1 prove/verify (kept tiny; the prover is slow).
+//!
+//! Every case runs in a child process (the binary itself, with `--case`), so
+//! a panic, abort or hang is logged like any other failure: one JSONL record
+//! per case, `ok` or `fail` with the tail of the child's stderr, and the
+//! command that reproduces it is printed. The process exits non-zero if any
+//! case failed.
+//!
+//! Usage examples:
+//!   cargo run --release -p sezkp-bench-harness --bin soak -- --duration-secs 14400
+//!   cargo run -p sezkp-bench-harness --bin soak -- --iters 200 --seed 7
+//!   cargo run -p sezkp-bench-harness --bin soak -- --case 0x9e3779b97f4a7c15
+
+use std::fs;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::{bail, ensure, Context, Result};
+use serde::Serialize;
+
+use sezkp_core::{DigestMode, ProvingBackend};
+use sezkp_fold::api::{Commitment, DriverOptions, FoldMode, LedgerStore};
+use sezkp_fold::are::Pi;
+use sezkp_fold::driver::{run_pipeline, FoldProofBundle};
+use sezkp_fold::fold::{CryptoFold, CryptoWrap};
+use sezkp_fold::leaf::CryptoLeaf;
+use sezkp_fold::{verify::verify_bundle, FoldAgg};
+use sezkp_merkle::commit_blocks;
+use sezkp_stark::StarkV1;
+use sezkp_trace::{generator::generate_trace_seeded, partition::partition_trace};
+
+/// Largest `T` for the fold backends (default of `--max-t`).
+const DEFAULT_MAX_T: u64 = 4096;
+
+/// Backends drawn from (default of `--backends`).
+const DEFAULT_BACKENDS: &str = "fold,fold-backend,stark";
+
+/// Largest `log2 T` and τ for STARK cases. STARK v1 needs a power-of-two
+/// row count, so its `T` is drawn among those.
+const STARK_MAX_LOG_T: u64 = 8;
+const STARK_MAX_TAU: u8 = 4;
+
+/// SplitMix64: tiny, seedable, and the same on every platform.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `lo..=hi`.
+    fn range(&mut self, lo: u64, hi: u64) -> u64 {
+        lo + self.next() % (hi - lo + 1)
+    }
+
+    fn pick<T: Copy>(&mut self, xs: &[T]) -> T {
+        xs[(self.next() % xs.len() as u64) as usize]
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+enum Backend {
+    Fold,
+    FoldBackend,
+    Stark,
+}
+
+/// Everything one case runs, as drawn from `seed`.
+#[derive(Debug, Serialize)]
+struct Case {
+    seed: String,
+    backend: Backend,
+    t: u64,
+    b: u32,
+    tau: u8,
+    trace_seed: u64,
+    fold_mode: FoldMode,
+    wrap_cadence: u32,
+    are_samples: u32,
+    iface_digest: DigestMode,
+    ledger: LedgerStore,
+    endpoint_cache: u32,
+}
+
+/// A trace length biased towards the sizes that break things.
+fn draw_t(rng: &mut Rng, max_t: u64) -> u64 {
+    let t = match rng.next() % 4 {
+        0 => rng.range(1, 16),
+        1 => {
+            let p = 1u64 << rng.range(1, u64::from(max_t.ilog2()));
+            rng.pick(&[p - 1, p, p + 1])
+        }
+        2 => rng.range(1, max_t) | 1,
+        _ => rng.range(1, max_t),
+    };
+    t.clamp(1, max_t)
+}
+
+impl Case {
+    fn draw(seed: u64, max_t: u64, backends: &[Backend]) -> Self {
+        let mut rng = Rng(seed);
+        let backend = rng.pick(backends);
+        let t = match backend {
+            Backend::Stark => 1 << rng.range(1, STARK_MAX_LOG_T.min(u64::from(max_t.ilog2()))),
+            _ => draw_t(&mut rng, max_t),
+        };
+        // Block sizes from 1 step to the whole trace, log-uniform-ish.
+        let scale = rng.range(0, u64::from(t.ilog2()) + 1);
+        let b = rng.range(1, 1 << scale).min(t);
+        let tau = match backend {
+            Backend::Stark => rng.range(1, u64::from(STARK_MAX_TAU)),
+            _ if rng.next() % 8 == 0 => rng.range(9, 64),
+            _ => rng.range(1, 8),
+        };
+        Self {
+            seed: format!("{seed:#018x}"),
+            backend,
+            t,
+            b: u32::try_from(b).unwrap_or(u32::MAX),
+            tau: u8::try_from(tau).unwrap_or(u8::MAX),
+            trace_seed: rng.next(),
+            fold_mode: rng.pick(&[FoldMode::Balanced, FoldMode::MinRam]),
+            wrap_cadence: rng.pick(&[0, 0, 1, 2, 3, 7]),
+            are_samples: rng.pick(&[0, 0, 1, 4]),
+            iface_digest: rng.pick(&[DigestMode::Full, DigestMode::Heads, DigestMode::WritesOnly]),
+            ledger: rng.pick(&[LedgerStore::Memory, LedgerStore::Disk]),
+            endpoint_cache: rng.pick(&[1, 2, 64]),
+        }
+    }
+
+    fn options(&self) -> DriverOptions {
+        DriverOptions {
+            fold_mode: self.fold_mode,
+            wrap_cadence: self.wrap_cadence,
+            endpoint_cache: self.endpoint_cache,
+            ledger: self.ledger,
+            are_samples: self.are_samples,
+            iface_digest: self.iface_digest,
+            ..DriverOptions::default()
+        }
+    }
+
+    fn run(&self) -> Result<()> {
+        let blocks = partition_trace(
+            &generate_trace_seeded(self.t, self.tau, self.trace_seed),
+            self.b,
+        );
+        match self.backend {
+            Backend::Fold => {
+                let bundle =
+                    run_pipeline::<CryptoLeaf, CryptoFold, CryptoWrap>(&blocks, &self.options());
+                verify_bundle::<CryptoLeaf, CryptoFold, CryptoWrap>(&bundle)
+                    .context("verify bundle")?;
+                // Mode and memory knobs must not change what is proven.
+                let reference = DriverOptions {
+                    fold_mode: FoldMode::Balanced,
+                    ledger: LedgerStore::Memory,
+                    ..self.options()
+                };
+                let reference =
+                    run_pipeline::<CryptoLeaf, CryptoFold, CryptoWrap>(&blocks, &reference);
+                ensure!(
+                    top(&bundle) == top(&reference),
+                    "top endpoint differs from a Balanced in-memory run"
+                );
+            }
+            Backend::FoldBackend => {
+                let root = commit_blocks(&blocks).root;
+                let art = FoldAgg::prove(&blocks, root).context("prove")?;
+                FoldAgg::verify(&art, &blocks, root).context("verify")?;
+            }
+            Backend::Stark => {
+                let root = commit_blocks(&blocks).root;
+                let art = StarkV1::prove(&blocks, root).context("prove")?;
+                StarkV1::verify(&art, &blocks, root).context("verify")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Top endpoint of a bundle (last fold, else last leaf).
+fn top<Lp, Fp, Wp>(b: &FoldProofBundle<Lp, Fp, Wp>) -> Option<(Commitment, Pi)> {
+    b.folds
+        .last()
+        .map(|(top, ..)| *top)
+        .or_else(|| b.leaves.last().map(|(c, p, _)| (*c, *p)))
+}
+
+#[derive(Serialize)]
+struct Record<'a> {
+    status: &'a str,
+    #[serde(flatten)]
+    case: &'a Case,
+    ms: u128,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+fn log(out: &mut fs::File, rec: &Record<'_>) -> Result<()> {
+    serde_json::to_writer(&mut *out, rec).context("serialize record")?;
+    writeln!(out).context("write log")?;
+    out.flush().context("flush log")
+}
+
+/// Keep this many trailing bytes of a failed case's stderr.
+const ERROR_TAIL_BYTES: usize = 4096;
+
+/// Run `case` in a child process (`--case`), so panics, aborts and stack
+/// overflows are recorded instead of ending the soak. Returns `None` on
+/// success, else the reason and the tail of the child's stderr.
+fn run_isolated(case_seed: u64, args: &[String], timeout: Duration) -> Result<Option<String>> {
+    let exe = std::env::current_exe().context("locate soak binary")?;
+    let mut child = Command::new(exe)
+        .arg("--case")
+        .arg(format!("{case_seed:#x}"))
+        .args(args)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("spawn case")?;
+    let mut stderr = child.stderr.take().context("child stderr")?;
+    let drain = std::thread::spawn(move || {
+        let mut s = String::new();
+        let _ = stderr.read_to_string(&mut s);
+        s
+    });
+
+    let t0 = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait().context("wait for case")? {
+            break Some(status);
+        }
+        if t0.elapsed() >= timeout {
+            let _ = child.kill();
+            let _ = child.wait();
+            break None;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    };
+    let err = drain.join().unwrap_or_default();
+    let tail = err
+        .get(err.len().saturating_sub(ERROR_TAIL_BYTES)..)
+        .unwrap_or(&err);
+    Ok(match status {
+        Some(s) if s.success() => None,
+        Some(s) => Some(format!("{s}: {}", tail.trim())),
+        None => Some(format!("timed out after {}s", timeout.as_secs())),
+    })
+}
+
+fn flag(name: &str) -> Option<String> {
+    let mut it = std::env::args().skip(1);
+    while let Some(k) = it.next() {
+        if k == format!("--{name}") {
+            return it.next();
+        }
+    }
+    None
+}
+
+fn parse_u64(s: &str) -> Result<u64> {
+    match s.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => s.parse(),
+    }
+    .with_context(|| format!("invalid number {s:?}"))
+}
+
+fn parse_backends(list: &str) -> Result<Vec<Backend>> {
+    list.split(',')
+        .map(|b| match b {
+            "fold" => Ok(Backend::Fold),
+            "fold-backend" => Ok(Backend::FoldBackend),
+            "stark" => Ok(Backend::Stark),
+            other => bail!("unknown backend {other} (use fold|fold-backend|stark)"),
+        })
+        .collect()
+}
+
+fn main() -> Result<()> {
+    let max_t = flag("max-t").map_or(Ok(DEFAULT_MAX_T), |s| parse_u64(&s))?;
+    ensure!(max_t >= 2, "--max-t must be at least 2");
+    let backends = flag("backends").unwrap_or_else(|| DEFAULT_BACKENDS.to_owned());
+    // Cases depend on these two, so children and reproductions get them too.
+    let case_args = vec![
+        "--max-t".to_owned(),
+        max_t.to_string(),
+        "--backends".to_owned(),
+        backends.clone(),
+    ];
+    let backends = parse_backends(&backends)?;
+
+    // `--case S` runs one case in this process: reproduction, and the child
+    // side of `run_isolated`.
+    if let Some(s) = flag("case") {
+        let case = Case::draw(parse_u64(&s)?, max_t, &backends);
+        println!("{}", serde_json::to_string(&case)?);
+        if let Err(e) = case.run() {
+            eprintln!("{e:#}");
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+    let seed = flag("seed").map_or(Ok(now.as_nanos() as u64), |s| parse_u64(&s))?;
+    let duration = Duration::from_secs(flag("duration-secs").map_or(Ok(3600), |s| parse_u64(&s))?);
+    let timeout =
+        Duration::from_secs(flag("case-timeout-secs").map_or(Ok(600), |s| parse_u64(&s))?);
+    let iters = flag("iters").map_or(Ok(0), |s| parse_u64(&s))?;
+
+    fs::create_dir_all("benchmarks/reports").ok();
+    let log_path = flag("log").map_or_else(
+        || PathBuf::from(format!("benchmarks/reports/soak-{}.jsonl", now.as_secs())),
+        PathBuf::from,
+    );
+    let mut out = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&log_path)
+        .with_context(|| format!("open {}", log_path.display()))?;
+    println!(
+        "Soak: seed={seed:#x}, budget={}s, max_t={max_t}, log={}",
+        duration.as_secs(),
+        log_path.display()
+    );
+
+    let start = Instant::now();
+    let mut seeds = Rng(seed);
+    let (mut runs, mut failures) = (0u64, 0u64);
+    while start.elapsed() < duration && (iters == 0 || runs < iters) {
+        let case_seed = seeds.next();
+        let case = Case::draw(case_seed, max_t, &backends);
+        let t0 = Instant::now();
+        let error = run_isolated(case_seed, &case_args, timeout)?;
+        let ms = t0.elapsed().as_millis();
+        runs += 1;
+        let status = if let Some(e) = &error {
+            failures += 1;
+            eprintln!(
+                "FAIL {:?} T={} b={} tau={}: {}\n  reproduce: soak --case {} {}",
+                case.backend,
+                case.t,
+                case.b,
+                case.tau,
+                e.lines().take(2).collect::<Vec<_>>().join(" "),
+                case.seed,
+                case_args.join(" ")
+            );
+            "fail"
+        } else {
+            "ok"
+        };
+        log(
+            &mut out,
+            &Record {
+                status,
+                case: &case,
+                ms,
+                error,
+            },
+        )?;
+    }
+
+    println!(
+        "{runs} cases, {failures} failed ({}s) → {}",
+        start.elapsed().as_secs(),
+        log_path.display()
+    );
+    ensure!(failures == 0, "{failures} of {runs} soak cases failed");
+    Ok(())
+}
@@ -3,22 +3,49 @@
 //! Run small end-to-end benchmarks (generate -> partition -> commit -> prove -> verify)
 //! and append CSV rows into `benchmarks/reports/bench-<unix>.csv`.
 //!
+//! Backend phase spans (`stark.lde`, `fold.fold`, …) are collected as well:
+//! after each prove/verify stage, one `span:<name>` row per span name gives
+//! the summed wall time and, in `extra`, the number of spans closed.
+//!
+//! With `--sweep`, the harness instead times the STARK v1 prover across
+//! streaming buffer sizes (`StreamTuning`: LDE output chunk and FRI staging
+//! buffer), one knob at a time, writes a `sweep` row per setting and prints
+//! the values to export on this machine.
+//!
+//! With `--stream` (fold backend only), each repeat also writes the blocks as
+//! JSONL and proves them again through `StreamingProver::prove_stream_iter`,
+//! reading blocks lazily from that file. The `prove_stream` row records
+//! `blocks_per_sec` and the size of the `.cborseq` proof stream, so streaming
+//! regressions show up next to the batch `prove` numbers.
+//!
 //! Usage examples:
 //!   cargo run -p sezkp-bench-harness -- --profile configs/profiles/small.toml --backend stark
 //!   cargo run -p sezkp-bench-harness -- --profile configs/profiles/medium.toml --backend fold
+//!   cargo run -p sezkp-bench-harness -- --profile configs/profiles/medium.toml --backend fold --stream
+//!   cargo run --release -p sezkp-bench-harness -- --profile configs/profiles/medium.toml --sweep
 
+use std::collections::BTreeMap;
 use std::fs;
 use std::io::Write;
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use anyhow::{Context, Result};
 use serde::Deserialize;
+use tracing::span;
+use tracing_subscriber::layer::{Context as LayerContext, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::Layer;
 
 use sezkp_core::io::{write_block_summaries_cbor, write_proof_artifact_cbor};
-use sezkp_core::ProvingBackend;
-use sezkp_fold::FoldAgg;
-use sezkp_merkle::{commit_block_file, verify_block_file_against_manifest};
+use sezkp_core::io_jsonl::{stream_block_summaries_jsonl, write_block_summaries_jsonl};
+use sezkp_core::{ProvingBackend, StreamingProver};
+use sezkp_fold::{FoldAgg, FoldBackend};
+use sezkp_merkle::{commit_block_file, commit_blocks, verify_block_file_against_manifest};
+use sezkp_stark::v1::params::{StreamTuning, ENV_FRI_BUF_LOG2, ENV_LDE_CHUNK_LOG2};
+use sezkp_stark::v1::prover::prove_v1_profiled;
 use sezkp_stark::StarkIOP;
 use sezkp_trace::{generator::generate_trace, partition::partition_trace};
 
@@ -40,6 +67,48 @@ enum BackendSel {
     Fold,
 }
 
+/// Per-name `(count, total wall time)` of closed spans.
+type SpanTotals = Arc<Mutex<BTreeMap<&'static str, (u64, Duration)>>>;
+
+/// Layer that sums span lifetimes by span name.
+struct SpanTimer {
+    totals: SpanTotals,
+}
+
+/// Open time stored in each span's extensions.
+struct Opened(Instant);
+
+impl<S> Layer<S> for SpanTimer
+where
+    S: tracing::Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, _attrs: &span::Attributes<'_>, id: &span::Id, ctx: LayerContext<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(Opened(Instant::now()));
+        }
+    }
+
+    fn on_close(&self, id: span::Id, ctx: LayerContext<'_, S>) {
+        let Some(span) = ctx.span(&id) else { return };
+        let Some(opened) = span.extensions().get::<Opened>().map(|o| o.0) else {
+            return;
+        };
+        let mut totals = self.totals.lock().unwrap();
+        let e = totals.entry(span.name()).or_default();
+        e.0 += 1;
+        e.1 += opened.elapsed();
+    }
+}
+
+/// Append one `span:<name>` row per collected span name and reset the totals.
+fn drain_spans(csv: &mut fs::File, totals: &SpanTotals, prefix: &str) -> Result<()> {
+    let drained = std::mem::take(&mut *totals.lock().unwrap());
+    for (name, (count, total)) in drained {
+        writeln!(csv, "{prefix},span:{name},{},count={count}", dur_ms(total))?;
+    }
+    Ok(())
+}
+
 fn parse_flag(name: &str, default: &str) -> String {
     let mut it = std::env::args().skip(1);
     while let Some(k) = it.next() {
@@ -50,10 +119,162 @@ fn parse_flag(name: &str, default: &str) -> String {
     default.to_string()
 }
 
+fn has_flag(name: &str) -> bool {
+    std::env::args().skip(1).any(|k| k == format!("--{name}"))
+}
+
 fn dur_ms(d: Duration) -> u128 {
     d.as_millis()
 }
 
+/// Exponents tried for each tuning knob.
+const SWEEP_LOG2: [u8; 6] = [6, 8, 10, 12, 14, 16];
+
+/// A sweep setting within this fraction of the fastest counts as "as fast";
+/// among those the smallest (least memory) is recommended.
+const SWEEP_SLACK: f64 = 0.05;
+
+/// Time the v1 prover for every value of one knob (the other at its default),
+/// keeping the best of `repeats` runs. Returns the recommended exponent.
+fn sweep_knob(
+    csv: &mut fs::File,
+    prefix: &str,
+    knob: &str,
+    blocks: &[sezkp_core::BlockSummary],
+    root: [u8; 32],
+    repeats: u32,
+    set: impl Fn(&mut StreamTuning, u8),
+) -> Result<u8> {
+    let mut timings = Vec::new();
+    for k in SWEEP_LOG2 {
+        let mut tuning = StreamTuning::default();
+        set(&mut tuning, k);
+        let mut best = Duration::MAX;
+        let mut peak = 0;
+        for _ in 0..repeats.max(1) {
+            let t0 = Instant::now();
+            let (_, profile) = prove_v1_profiled(blocks, root, None, tuning)?;
+            best = best.min(t0.elapsed());
+            peak = profile.peak_temp_bytes;
+        }
+        writeln!(
+            csv,
+            "{prefix},sweep,{},{knob}={k};peak_temp_bytes={peak}",
+            dur_ms(best)
+        )?;
+        println!(
+            "  {knob}={k:>2}: {:>6} ms, peak temp {peak} bytes",
+            dur_ms(best)
+        );
+        timings.push((k, best));
+    }
+    let fastest = timings.iter().map(|&(_, d)| d).min().unwrap_or_default();
+    let cutoff = fastest.mul_f64(1.0 + SWEEP_SLACK);
+    // The fastest setting itself always qualifies.
+    Ok(timings
+        .iter()
+        .find(|&&(_, d)| d <= cutoff)
+        .map_or(SWEEP_LOG2[0], |&(k, _)| k))
+}
+
+/// `--sweep`: find good streaming buffer sizes for the STARK v1 prover.
+fn run_sweep(csv: &mut fs::File, ts: u64, profile: &Profile) -> Result<()> {
+    let blocks = partition_trace(&generate_trace(profile.t, profile.tau), profile.b);
+    let root = commit_blocks(&blocks).root;
+    let prefix = format!(
+        "{ts},stark-v1,{},{},{},0",
+        profile.t, profile.b, profile.tau
+    );
+
+    println!("Sweeping lde_chunk_log2 (fri_buf_log2 at default):");
+    let lde = sweep_knob(
+        csv,
+        &prefix,
+        "lde_chunk_log2",
+        &blocks,
+        root,
+        profile.repeats,
+        |t, k| {
+            t.lde_chunk_log2 = k;
+        },
+    )?;
+    println!("Sweeping fri_buf_log2 (lde_chunk_log2 at default):");
+    let fri = sweep_knob(
+        csv,
+        &prefix,
+        "fri_buf_log2",
+        &blocks,
+        root,
+        profile.repeats,
+        |t, k| {
+            t.fri_buf_log2 = k;
+        },
+    )?;
+    println!("Recommended: {ENV_LDE_CHUNK_LOG2}={lde} {ENV_FRI_BUF_LOG2}={fri}");
+    Ok(())
+}
+
+/// `--stream`: write `blocks` as JSONL, prove them with the streaming fold
+/// driver while reading the file lazily, then verify the streamed artifact.
+fn bench_stream(
+    csv: &mut fs::File,
+    totals: &SpanTotals,
+    prefix: &str,
+    ts: u64,
+    rep: u32,
+    blocks: &[sezkp_core::BlockSummary],
+    root: [u8; 32],
+) -> Result<()> {
+    let jsonl_path = PathBuf::from(format!("benchmarks/tmp-blocks-{ts}-{rep}.jsonl"));
+    let stream_path = PathBuf::from(format!("benchmarks/tmp-proof-{ts}-{rep}.cborseq"));
+
+    let t0 = Instant::now();
+    write_block_summaries_jsonl(&jsonl_path, blocks)?;
+    let t_write = t0.elapsed();
+    let jsonl_bytes = fs::metadata(&jsonl_path)?.len();
+    writeln!(
+        csv,
+        "{prefix},stream_write,{},jsonl_bytes={jsonl_bytes}",
+        dur_ms(t_write)
+    )?;
+
+    // The fold driver writes its proof stream wherever this points.
+    std::env::set_var("SEZKP_PROOF_STREAM_PATH", &stream_path);
+    let result = (|| {
+        let t0 = Instant::now();
+        let art = StreamingProver::<FoldBackend>::prove_stream_iter(
+            stream_block_summaries_jsonl(&jsonl_path)?,
+            root,
+        )?;
+        let t_prove = t0.elapsed();
+        let stream_bytes = fs::metadata(&stream_path)
+            .with_context(|| format!("stat proof stream {}", stream_path.display()))?
+            .len();
+        let blocks_per_sec = blocks.len() as f64 / t_prove.as_secs_f64().max(1e-9);
+        writeln!(
+            csv,
+            "{prefix},prove_stream,{},n_blocks={};blocks_per_sec={blocks_per_sec:.1};stream_bytes={stream_bytes}",
+            dur_ms(t_prove),
+            blocks.len()
+        )?;
+        drain_spans(csv, totals, prefix)?;
+
+        let t0 = Instant::now();
+        StreamingProver::<FoldBackend>::verify_stream_iter(
+            &art,
+            stream_block_summaries_jsonl(&jsonl_path)?,
+            root,
+        )?;
+        writeln!(csv, "{prefix},verify_stream,{},", dur_ms(t0.elapsed()))?;
+        drain_spans(csv, totals, prefix)
+    })();
+    std::env::remove_var("SEZKP_PROOF_STREAM_PATH");
+
+    let _ = fs::remove_file(&jsonl_path);
+    let _ = fs::remove_file(&stream_path);
+    result
+}
+
 fn main() -> Result<()> {
     let profile_path = PathBuf::from(parse_flag("profile", "configs/profiles/small.toml"));
     let backend_str = parse_flag("backend", "stark");
@@ -63,6 +284,15 @@ fn main() -> Result<()> {
         other => anyhow::bail!("unknown --backend {other} (use stark|fold)"),
     };
 
+    // Backend spans are debug-level; this subscriber only times them.
+    let totals = SpanTotals::default();
+    tracing_subscriber::registry()
+        .with(SpanTimer {
+            totals: totals.clone(),
+        })
+        .try_init()
+        .context("install span timer")?;
+
     let profile_src = fs::read_to_string(&profile_path)
         .with_context(|| format!("read profile {:?}", profile_path))?;
     let profile: Profile = toml::from_str(&profile_src).context("parse profile toml")?;
@@ -71,6 +301,11 @@ fn main() -> Result<()> {
         profile.t, profile.b, profile.tau, profile.repeats
     );
 
+    let stream = has_flag("stream");
+    if stream && !matches!(backend, BackendSel::Fold) {
+        anyhow::bail!("--stream needs --backend fold");
+    }
+
     fs::create_dir_all("benchmarks/reports").ok();
 
     let ts = SystemTime::now()
@@ -84,6 +319,12 @@ fn main() -> Result<()> {
         .open(&csv_path)?;
     writeln!(csv, "timestamp,backend,t,b,tau,repeat,stage,ms,extra")?;
 
+    if has_flag("sweep") {
+        run_sweep(&mut csv, ts, &profile)?;
+        println!("Wrote report → {}", csv_path.display());
+        return Ok(());
+    }
+
     for rep in 0..profile.repeats {
         // temp paths per repeat
         let blocks_path = PathBuf::from(format!("benchmarks/tmp-blocks-{ts}-{rep}.cbor"));
@@ -139,6 +380,10 @@ fn main() -> Result<()> {
         )?;
 
         // 4) prove
+        let prefix = format!(
+            "{ts},{backend_str},{},{},{},{rep}",
+            profile.t, profile.b, profile.tau
+        );
         let t0 = Instant::now();
         let art = match backend {
             BackendSel::Stark => StarkIOP::prove(&blocks, manifest.root)?,
@@ -156,6 +401,7 @@ fn main() -> Result<()> {
             dur_ms(t_prove),
             art.proof_bytes.len()
         )?;
+        drain_spans(&mut csv, &totals, &prefix)?;
 
         // 5) verify (manifest+proof)
         let t0 = Instant::now();
@@ -174,6 +420,12 @@ fn main() -> Result<()> {
             rep,
             dur_ms(t_verify)
         )?;
+        drain_spans(&mut csv, &totals, &prefix)?;
+
+        // 6) optional: stream the same blocks from JSONL
+        if stream {
+            bench_stream(&mut csv, &totals, &prefix, ts, rep, &blocks, manifest.root)?;
+        }
 
         // cleanup temp files to avoid disk bloat
         let _ = fs::remove_file(&blocks_path);
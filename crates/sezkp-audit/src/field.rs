@@ -0,0 +1,53 @@
+//! Goldilocks arithmetic: integers modulo `P = 2^64 - 2^32 + 1`.
+//!
+//! Elements are plain `u64`s. Every operation first reduces its inputs, then
+//! computes the exact result in `u128` and reduces it with `%`, so there is no
+//! carry handling or Montgomery form to check. Results are always canonical
+//! (`< P`), which matters because the protocols hash and compare the
+//! little-endian bytes of field elements.
+
+/// The field modulus.
+pub const P: u64 = 0xFFFF_FFFF_0000_0001;
+
+/// `x mod P`.
+#[must_use]
+pub const fn reduce(x: u64) -> u64 {
+    x % P
+}
+
+/// `x mod P` for a `u128`; the result fits in a `u64` because it is `< P`.
+#[allow(clippy::cast_possible_truncation)]
+const fn reduce_wide(x: u128) -> u64 {
+    (x % P as u128) as u64
+}
+
+/// `a + b mod P`.
+#[must_use]
+pub const fn add(a: u64, b: u64) -> u64 {
+    reduce_wide(reduce(a) as u128 + reduce(b) as u128)
+}
+
+/// `a - b mod P`, computed as `a + (P - b)` so nothing goes negative.
+#[must_use]
+pub const fn sub(a: u64, b: u64) -> u64 {
+    reduce_wide(reduce(a) as u128 + (P - reduce(b)) as u128)
+}
+
+/// `a · b mod P`.
+#[must_use]
+pub const fn mul(a: u64, b: u64) -> u64 {
+    reduce_wide(reduce(a) as u128 * reduce(b) as u128)
+}
+
+/// The field element encoded by 8 little-endian bytes (reduced mod `P`,
+/// like the production decoders).
+#[must_use]
+pub const fn from_le(le: [u8; 8]) -> u64 {
+    reduce(u64::from_le_bytes(le))
+}
+
+/// Canonical little-endian encoding of `x`.
+#[must_use]
+pub const fn to_le(x: u64) -> [u8; 8] {
+    reduce(x).to_le_bytes()
+}
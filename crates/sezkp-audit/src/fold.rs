@@ -0,0 +1,335 @@
+//! Walk over a batch fold bundle (production:
+//! `sezkp_fold::verify::verify_bundle` and the gadgets in
+//! `sezkp_fold::{leaf, fold}`).
+//!
+//! A bundle lists one leaf record per block, then every fold (parent and
+//! both children, bottom-up), then the wrap attestations. Records carry
+//! `(C, π)` pairs: `C` is a Merkle root with the number of leaves under it,
+//! `π` two control words, a flags word and four field limbs. Each record is
+//! checked on its own:
+//!
+//! - **leaf**: `π` is the one the leaf's public inputs describe, the inner
+//!   π-MAC matches those inputs, and the leaf MAC binds the binding root,
+//!   `C`, the π commitment, the boundary digests and the inner MAC;
+//! - **fold**: the parent `C` is the Merkle parent of the children's, the
+//!   opened child π match the children, their controls chain, the ARE MAC
+//!   matches, the parent π is the limb-wise sum of the children's, and the
+//!   fold MAC binds all three `(C, π)`, the interface and the ARE proof;
+//! - **wrap**: the wraps attest exactly the parents of every
+//!   `wrap_cadence`-th fold, each with a MAC over the binding root and that
+//!   `(C, π)`.
+//!
+//! The *binding root* is the bundle's manifest root or, when the bundle
+//! carries a claims digest `d`, `BLAKE3("sezkp/claims-root/v1" || root || d)`.
+//!
+//! Domain strings are spelled out here rather than imported, so a change to
+//! a production constant shows up as a differential test failure.
+
+use anyhow::{bail, ensure, Context, Result};
+use sezkp_core::boundary::IFACE_WINDOW_STEPS;
+use sezkp_core::canonical;
+use sezkp_fold::api::Commitment;
+use sezkp_fold::are::Pi;
+use sezkp_fold::are_replay::AreProof;
+use sezkp_fold::driver::FoldProofBundle;
+use sezkp_fold::fold::{CryptoFoldProof, CryptoWrapProof};
+use sezkp_fold::leaf::CryptoLeafProof;
+
+use crate::field;
+use crate::merkle::{node, EMPTY_ROOT};
+use crate::transcript::Transcript;
+
+/// The bundle carried by batch fold artifacts.
+pub type Bundle = FoldProofBundle<CryptoLeafProof, CryptoFoldProof, CryptoWrapProof>;
+
+/// A `(C, π)` pair as bundles record it.
+type Node = (Commitment, Pi);
+
+/// Verify every record of `bundle` and return the root of the top
+/// commitment: the last fold's parent, else the only leaf, else the empty
+/// root.
+///
+/// The gadgets are checked under the manifest root the bundle records;
+/// callers compare that root, and the returned one, with the manifest they
+/// trust.
+///
+/// # Errors
+/// Names the first record that fails, or rejects a bundle that uses
+/// features this reference does not cover (see the crate docs).
+pub fn verify_bundle(bundle: &Bundle) -> Result<[u8; 32]> {
+    // Out of scope: reject rather than skip.
+    if bundle.params.is_some_and(|p| p.are_samples > 0) {
+        bail!("bundles with sampled interior steps are outside this reference verifier");
+    }
+    if bundle.final_wrap.is_some() {
+        bail!("bundles with a final wrap are outside this reference verifier");
+    }
+
+    // Shape: one leaf per block; nothing folded or wrapped without leaves.
+    ensure!(
+        bundle.leaves.len() == bundle.n_blocks,
+        "bundle has {} leaf records for {} blocks",
+        bundle.leaves.len(),
+        bundle.n_blocks
+    );
+    if bundle.n_blocks == 0 {
+        ensure!(
+            bundle.folds.is_empty() && bundle.wraps.is_empty(),
+            "empty bundle carries fold/wrap records"
+        );
+    }
+
+    // Declared parameters (older bundles declare none and skip these).
+    if let Some(p) = &bundle.params {
+        ensure!(
+            p.iface_window as usize == IFACE_WINDOW_STEPS,
+            "bundle declares a {}-step boundary window, expected {IFACE_WINDOW_STEPS}",
+            p.iface_window
+        );
+        check_wrap_placement(bundle, p.wrap_cadence)?;
+    }
+
+    let root = binding_root(&bundle.manifest_root, bundle.claims.as_ref());
+
+    for (i, (c, pi, proof)) in bundle.leaves.iter().enumerate() {
+        check_leaf(&root, c, pi, proof).with_context(|| format!("leaf {i}"))?;
+    }
+    for (i, (parent, left, right, proof)) in bundle.folds.iter().enumerate() {
+        check_fold(&root, parent, left, right, proof).with_context(|| format!("fold {i}"))?;
+    }
+    for (i, ((c, pi), proof)) in bundle.wraps.iter().enumerate() {
+        check_wrap(&root, c, pi, proof).with_context(|| format!("wrap {i}"))?;
+    }
+
+    let top = match (bundle.folds.last(), bundle.leaves.last()) {
+        (Some((parent, _, _, _)), _) => parent.0.root,
+        (None, Some((c, _, _))) => c.root,
+        (None, None) => EMPTY_ROOT,
+    };
+    Ok(top)
+}
+
+/// The root every MAC in the bundle keys on.
+fn binding_root(manifest_root: &[u8; 32], claims: Option<&[u8; 32]>) -> [u8; 32] {
+    let Some(d) = claims else {
+        return *manifest_root;
+    };
+    let mut h = blake3::Hasher::new();
+    h.update(b"sezkp/claims-root/v1");
+    h.update(manifest_root);
+    h.update(d);
+    *h.finalize().as_bytes()
+}
+
+/// The wraps must be exactly the parents of folds `k, 2k, 3k, …` (1-based),
+/// in order; `k = 0` means no wraps at all.
+fn check_wrap_placement(bundle: &Bundle, k: u32) -> Result<()> {
+    let mut due: Vec<&Node> = Vec::new();
+    if k > 0 {
+        for (i, (parent, _, _, _)) in bundle.folds.iter().enumerate() {
+            if (i + 1).is_multiple_of(k as usize) {
+                due.push(parent);
+            }
+        }
+    }
+    ensure!(
+        bundle.wraps.len() == due.len(),
+        "bundle has {} wraps; wrap cadence {k} over {} folds requires {}",
+        bundle.wraps.len(),
+        bundle.folds.len(),
+        due.len()
+    );
+    for (i, (wrap, want)) in bundle.wraps.iter().zip(due).enumerate() {
+        ensure!(
+            wrap.0 == *want,
+            "wrap {i} does not attest the parent of fold {}",
+            (i + 1) * k as usize
+        );
+    }
+    Ok(())
+}
+
+/* ---------------------------------- π ----------------------------------- */
+
+/// Raw limbs of `π` (the production commitment hashes them as stored).
+const fn limbs(pi: &Pi) -> [u64; 4] {
+    [pi.acc[0].0, pi.acc[1].0, pi.acc[2].0, pi.acc[3].0]
+}
+
+/// Commitment to a `π` given by its parts:
+/// `BLAKE3("sezkp-fold/pi-commitment/v1" || ctrl_in || ctrl_out || flags ||
+/// limbs)`, words little-endian.
+fn pi_commitment(ctrl_in: u32, ctrl_out: u32, flags: u32, limbs: [u64; 4]) -> [u8; 32] {
+    let mut h = blake3::Hasher::new();
+    h.update(b"sezkp-fold/pi-commitment/v1");
+    h.update(&ctrl_in.to_le_bytes());
+    h.update(&ctrl_out.to_le_bytes());
+    h.update(&flags.to_le_bytes());
+    for l in limbs {
+        h.update(&l.to_le_bytes());
+    }
+    *h.finalize().as_bytes()
+}
+
+/// Commitment to `π`.
+fn commit_pi(pi: &Pi) -> [u8; 32] {
+    pi_commitment(pi.ctrl_in, pi.ctrl_out, pi.flags, limbs(pi))
+}
+
+/// Commitment to the parent of `left` and `right`: controls from the outer
+/// ends, flags OR-ed, limbs added in the field.
+fn commit_combined(left: &Pi, right: &Pi) -> [u8; 32] {
+    let (l, r) = (limbs(left), limbs(right));
+    let sum = [
+        field::add(l[0], r[0]),
+        field::add(l[1], r[1]),
+        field::add(l[2], r[2]),
+        field::add(l[3], r[3]),
+    ];
+    pi_commitment(left.ctrl_in, right.ctrl_out, left.flags | right.flags, sum)
+}
+
+/* --------------------------------- Leaf --------------------------------- */
+
+fn check_leaf(root: &[u8; 32], c: &Commitment, pi: &Pi, proof: &CryptoLeafProof) -> Result<()> {
+    let public = &proof.public;
+    ensure!(
+        proof.samples.is_none(),
+        "leaf opens sampled steps the bundle does not declare"
+    );
+
+    // 1) π must be the one the public inputs describe (limbs reduced mod P).
+    let described = pi_commitment(
+        public.ctrl_in,
+        public.ctrl_out,
+        public.flags,
+        public.acc_limbs.map(field::reduce),
+    );
+    let pi_cmt = commit_pi(pi);
+    ensure!(
+        described == pi_cmt,
+        "π does not match the leaf's public inputs"
+    );
+
+    // 2) Inner π-MAC over the public inputs.
+    let mut h = blake3::Hasher::new();
+    h.update(b"stark/leaf_pi/v1");
+    h.update(&public.ctrl_in.to_le_bytes());
+    h.update(&public.ctrl_out.to_le_bytes());
+    h.update(&public.flags.to_le_bytes());
+    for limb in public.acc_limbs {
+        h.update(&limb.to_le_bytes());
+    }
+    h.update(&public.left_tail_digest);
+    h.update(&public.right_head_digest);
+    ensure!(
+        proof.proof.mac == *h.finalize().as_bytes(),
+        "leaf π micro-proof does not match its public inputs"
+    );
+
+    // 3) Leaf MAC.
+    let mut tr = Transcript::new("fold/leaf");
+    tr.absorb("manifest.root", root);
+    tr.absorb("c.root", &c.root);
+    tr.absorb_u64("c.len", u64::from(c.len));
+    tr.absorb("pi.commit", &pi_cmt);
+    tr.absorb("left_tail", &public.left_tail_digest);
+    tr.absorb("right_head", &public.right_head_digest);
+    tr.absorb("leaf_pi.mac", &proof.proof.mac);
+    ensure!(tr.challenge("mac", 32) == proof.mac, "leaf MAC mismatch");
+    Ok(())
+}
+
+/* --------------------------------- Fold --------------------------------- */
+
+fn check_fold(
+    root: &[u8; 32],
+    parent: &Node,
+    left: &Node,
+    right: &Node,
+    proof: &CryptoFoldProof,
+) -> Result<()> {
+    // 1) Parent commitment: Merkle parent of the children, leaf counts added.
+    let len = left.0.len.checked_add(right.0.len);
+    ensure!(
+        parent.0.root == node(&left.0.root, &right.0.root) && Some(parent.0.len) == len,
+        "parent commitment is not the Merkle parent of its children"
+    );
+
+    // 2) Opened child π: they match the children and chain through the
+    //    interface and the ARE proof.
+    let Some(open) = &proof.pi else {
+        bail!("fold proof does not open its children's π");
+    };
+    ensure!(
+        commit_pi(&open.left) == commit_pi(&left.1)
+            && commit_pi(&open.right) == commit_pi(&right.1),
+        "opened π do not match the children"
+    );
+    ensure!(
+        proof.iface.left_ctrl_out == open.left.ctrl_out
+            && proof.iface.right_ctrl_in == open.right.ctrl_in,
+        "interface controls do not match the opened π"
+    );
+    let AreProof::V2Stark(are) = &proof.are else {
+        bail!("legacy MAC-only ARE proofs are not accepted");
+    };
+    ensure!(
+        open.left.ctrl_out == open.right.ctrl_in,
+        "control does not chain: left exits {}, right enters {}",
+        open.left.ctrl_out,
+        open.right.ctrl_in
+    );
+    // The ARE MAC covers the left child's right-head limbs (2, 3), its
+    // control out, the right child's left-tail limbs (0, 1), its control in.
+    let (l, r) = (limbs(&open.left), limbs(&open.right));
+    let mut h = blake3::Hasher::new();
+    h.update(b"stark/are_iface/v2");
+    h.update(&l[2].to_le_bytes());
+    h.update(&l[3].to_le_bytes());
+    h.update(&open.left.ctrl_out.to_le_bytes());
+    h.update(&r[0].to_le_bytes());
+    h.update(&r[1].to_le_bytes());
+    h.update(&open.right.ctrl_in.to_le_bytes());
+    ensure!(
+        are.mac == *h.finalize().as_bytes(),
+        "ARE interface proof mismatch"
+    );
+
+    // 3) Parent π is the combination of the children's.
+    ensure!(
+        commit_combined(&open.left, &open.right) == commit_pi(&parent.1),
+        "parent π is not the combination of the children's"
+    );
+
+    // 4) Fold MAC.
+    let are_bytes = canonical::to_vec(&proof.are).context("encoding ARE proof")?;
+    let mut tr = Transcript::new("fold/merge");
+    tr.absorb("manifest.root", root);
+    for (side, (c, pi)) in [("L", left), ("R", right), ("P", parent)] {
+        tr.absorb(&format!("{side}.c.root"), &c.root);
+        tr.absorb_u64(&format!("{side}.c.len"), u64::from(c.len));
+        tr.absorb(&format!("{side}.pi.commit"), &commit_pi(pi));
+    }
+    tr.absorb_u64("iface.left_ctrl_out", u64::from(proof.iface.left_ctrl_out));
+    tr.absorb_u64("iface.right_ctrl_in", u64::from(proof.iface.right_ctrl_in));
+    tr.absorb("iface.boundary_digest", &proof.iface.boundary_writes_digest);
+    tr.absorb("ARE.proof", &are_bytes);
+    ensure!(tr.challenge("mac", 32) == proof.mac, "fold MAC mismatch");
+    Ok(())
+}
+
+/* --------------------------------- Wrap --------------------------------- */
+
+fn check_wrap(root: &[u8; 32], c: &Commitment, pi: &Pi, proof: &CryptoWrapProof) -> Result<()> {
+    let CryptoWrapProof::V1Mac(mac) = proof else {
+        bail!("only MAC wrap proofs are verifiable");
+    };
+    let mut tr = Transcript::new("fold/wrap");
+    tr.absorb("manifest.root", root);
+    tr.absorb("c.root", &c.root);
+    tr.absorb_u64("c.len", u64::from(c.len));
+    tr.absorb("pi.commit", &commit_pi(pi));
+    ensure!(tr.challenge("mac", 32) == mac, "wrap MAC mismatch");
+    Ok(())
+}
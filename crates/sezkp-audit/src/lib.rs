@@ -0,0 +1,57 @@
+//! Reference verifiers: slow, unoptimized and meant to be read.
+//!
+//! The production verifiers stream, cache, batch field conversions and share
+//! helpers across crates; that makes them fast and hard to audit. This crate
+//! re-implements what they decide, as plainly as possible, so an auditor can
+//! check a small trusted base and then rely on the differential tests in
+//! `tests/` to tie it to the production code:
+//!
+//! - [`merkle`]: the canonical leaf hash (schemas v1 and v2) and the
+//!   manifest Merkle root, written as the recursive definition of the tree;
+//! - [`fold`]: the walk over a batch fold bundle (leaf, fold and wrap
+//!   gadgets, wrap placement), mirroring `sezkp_fold::verify::verify_bundle`;
+//! - [`stark`]: the STARK v1 verification math (transcript schedule, column
+//!   openings, AIR composition, FRI), mirroring
+//!   `sezkp_stark::v1::verify::verify_v1_with_beacon`;
+//! - [`field`] and [`transcript`]: the Goldilocks arithmetic and BLAKE3
+//!   transcript both of those rest on.
+//!
+//! Nothing here streams or caches: whole proofs and block lists sit in
+//! memory, Merkle paths are recomputed from scratch and field operations
+//! widen to `u128` and reduce with `%`.
+//!
+//! ## What is trusted rather than re-implemented
+//! - The wire types (`BlockSummary`, `ProofV1`, `FoldProofBundle`, …) and
+//!   their serde decoding: the checks start from decoded values.
+//! - BLAKE3 itself.
+//! - [`sezkp_core::canonical`], which encodes the ARE proof absorbed by the
+//!   fold MAC, and [`sezkp_core::auxiliary::aux_digest`], which digests the
+//!   blocks' auxiliary commitments for the STARK statement.
+//!
+//! ## Not covered
+//! Bundles that open sampled interior steps (`are_samples > 0`) or carry a
+//! final wrap are rejected as out of scope rather than half-checked. Spot
+//! checks, streaming proofs and the STARK column-root recomputation
+//! (`verify_col_roots`) are not re-implemented either.
+
+#![forbid(unsafe_code)]
+#![deny(rust_2018_idioms)]
+#![warn(
+    missing_docs,
+    clippy::all,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::unwrap_used,
+    clippy::expect_used
+)]
+
+/// Goldilocks field arithmetic on plain `u64`s.
+pub mod field;
+/// Fold bundle verification walk.
+pub mod fold;
+/// Canonical leaf hash and manifest Merkle root.
+pub mod merkle;
+/// STARK v1 verification math.
+pub mod stark;
+/// The BLAKE3 Fiat–Shamir transcript.
+pub mod transcript;
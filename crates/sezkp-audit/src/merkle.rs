@@ -0,0 +1,126 @@
+//! Canonical leaf hash and the manifest Merkle root
+//! (production: `sezkp_merkle::leaf_hash` and `sezkp_merkle::merkle_root`).
+
+use sezkp_core::redact::REDACTED_BLOCK_VERSION;
+use sezkp_core::BlockSummary;
+
+/// Root of an empty tree.
+pub const EMPTY_ROOT: [u8; 32] = [0u8; 32];
+
+/// Parent of two nodes: `BLAKE3(left || right)`, no domain tag.
+#[must_use]
+pub fn node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut h = blake3::Hasher::new();
+    h.update(left);
+    h.update(right);
+    *h.finalize().as_bytes()
+}
+
+/// Leaf hash of a block: BLAKE3 over raw little-endian fields, no framing.
+///
+/// Schema v1 covers the scalars, the windows (with their count), both offset
+/// vectors (without counts) and the **number** of logged steps, not the
+/// steps. Schema v2 appends, for a redacted block, the count and bytes of its
+/// write commitments and, for a block with auxiliary commitments, the count
+/// and bytes of those.
+#[must_use]
+pub fn leaf_hash(b: &BlockSummary) -> [u8; 32] {
+    let mut h = blake3::Hasher::new();
+
+    // 1-8: fixed-width scalars.
+    h.update(&b.version.to_le_bytes()); // u16
+    h.update(&b.block_id.to_le_bytes()); // u32
+    h.update(&b.step_lo.to_le_bytes()); // u64
+    h.update(&b.step_hi.to_le_bytes()); // u64
+    h.update(&b.ctrl_in.to_le_bytes()); // u16
+    h.update(&b.ctrl_out.to_le_bytes()); // u16
+    h.update(&b.in_head_in.to_le_bytes()); // i64
+    h.update(&b.in_head_out.to_le_bytes()); // i64
+
+    // 9: window count (u64), then each window's (left, right) as i64.
+    h.update(&(b.windows.len() as u64).to_le_bytes());
+    for w in &b.windows {
+        h.update(&w.left.to_le_bytes());
+        h.update(&w.right.to_le_bytes());
+    }
+
+    // 10-11: entry then exit offsets (u32 each), no counts.
+    for x in &b.head_in_offsets {
+        h.update(&x.to_le_bytes());
+    }
+    for x in &b.head_out_offsets {
+        h.update(&x.to_le_bytes());
+    }
+
+    // 12: number of logged steps (u64); the steps themselves are not bound.
+    h.update(&(b.movement_log.steps.len() as u64).to_le_bytes());
+
+    // 13 (v2, redacted blocks only): write commitments.
+    if b.version == REDACTED_BLOCK_VERSION {
+        h.update(&(b.write_commitments.len() as u64).to_le_bytes());
+        for c in &b.write_commitments {
+            h.update(c);
+        }
+    }
+
+    // 14 (v2, blocks with auxiliary commitments only).
+    if !b.aux_commitments.is_empty() {
+        h.update(&(b.aux_commitments.len() as u64).to_le_bytes());
+        for c in &b.aux_commitments {
+            h.update(c);
+        }
+    }
+
+    *h.finalize().as_bytes()
+}
+
+/// Merkle root over `leaves`.
+///
+/// Written as a recursion: a tree over `n > 1` leaves is the parent of a
+/// full tree over the largest power of two `k < n` leaves and a tree over
+/// the remaining `n - k`. This is the same tree as the production
+/// level-by-level construction that carries an odd last node up unchanged:
+/// at every level the pairs formed are exactly the pairs inside the full
+/// left subtree and inside the right one.
+#[must_use]
+pub fn merkle_root(leaves: &[[u8; 32]]) -> [u8; 32] {
+    match leaves.len() {
+        0 => EMPTY_ROOT,
+        1 => leaves[0],
+        n => {
+            let mut k = 1;
+            while 2 * k < n {
+                k *= 2;
+            }
+            node(&merkle_root(&leaves[..k]), &merkle_root(&leaves[k..]))
+        }
+    }
+}
+
+/// Manifest root of `blocks`: the Merkle root of their leaf hashes.
+#[must_use]
+pub fn blocks_root(blocks: &[BlockSummary]) -> [u8; 32] {
+    let leaves: Vec<[u8; 32]> = blocks.iter().map(leaf_hash).collect();
+    merkle_root(&leaves)
+}
+
+/// Whether `path` (sibling hashes, bottom-up) leads from `leaf` at position
+/// `index` to `root`: at each level an even index is the left child and an
+/// odd one the right, and the index halves going up.
+///
+/// This is the path check of the STARK's column and FRI trees, which takes
+/// the tree's shape from the index bits alone.
+#[must_use]
+pub fn path_leads_to(root: &[u8; 32], leaf: [u8; 32], index: usize, path: &[[u8; 32]]) -> bool {
+    let mut cur = leaf;
+    let mut idx = index;
+    for sib in path {
+        cur = if idx.is_multiple_of(2) {
+            node(&cur, sib)
+        } else {
+            node(sib, &cur)
+        };
+        idx /= 2;
+    }
+    &cur == root
+}
@@ -0,0 +1,667 @@
+//! STARK v1 verification math (production:
+//! `sezkp_stark::v1::verify::verify_v1_with_beacon` and the helpers in
+//! `sezkp_stark::v1::{params, air, masking, merkle, fri}`), without the
+//! [`Requirements`] a production verifier layers on top: its security
+//! floor and expected movement model.
+//!
+//! [`Requirements`]: sezkp_stark::v1::verify::Requirements
+//!
+//! A v1 proof commits to the trace columns (one chunked Merkle root per
+//! column, or one leaf per block for a column the prover found constant
+//! within each block; each root records its layout), opens `num_queries`
+//! transcript-chosen rows (30 by default; any other count is bound into the
+//! transcript), and runs FRI on the composition. The verifier:
+//!
+//! 1. checks the statement: the proof's auxiliary digest and beacon, that
+//!    its public inputs match the domain and the declared parameters, the
+//!    empty-proof special case, the domain shape and declared parameters;
+//! 2. replays the transcript: public inputs and column roots and layouts,
+//!    then the alphas, the mask coefficients and the out-of-domain point
+//!    (drawn only to keep the schedule aligned with the prover), then the
+//!    FRI roots and the query positions;
+//! 3. checks every opened value against its column root and that the AIR
+//!    composition of each opened row is zero;
+//! 4. checks FRI: the last layer is the final value, and each query's
+//!    openings fold correctly from layer to layer.
+//!
+//! Alphas `a0`, `a1`, `a2` weight the boolean write flag, the movement
+//! domain and the head updates; `a2` also weights both boundary rows. The
+//! remaining five alphas belong to constraints the openings-only check does
+//! not evaluate, but are drawn all the same.
+
+use anyhow::{bail, ensure, Result};
+use sezkp_core::auxiliary::aux_digest;
+use sezkp_core::BlockSummary;
+use sezkp_stark::v1::proof::{
+    BlockOpening, ColumnOpening, ColumnRoot, Opening, ProofV1, RowOpenings,
+};
+
+use crate::field;
+use crate::merkle::{path_leads_to, EMPTY_ROOT};
+use crate::transcript::Transcript;
+
+/// Trace rows are extended 8× for FRI: `domain_n = 8 · n`.
+const BLOWUP: usize = 8;
+/// Rows (and chained FRI positions) opened by default.
+const NUM_QUERIES: usize = 30;
+/// Most rows a proof may declare to open.
+const MAX_NUM_QUERIES: usize = 256;
+/// Most queries a per-layer FRI schedule may declare for one layer.
+const MAX_FRI_LAYER_QUERIES: usize = 256;
+/// Largest supported movement bound.
+const MAX_MOVE_LIMIT: u8 = 8;
+/// Column chunks hold `2^10` rows.
+const COL_CHUNK_LOG2: usize = 10;
+
+/// Verify `proof` against `blocks`, requiring the beacon `expected_beacon`
+/// when given.
+///
+/// The column roots are taken as committed: the openings are checked
+/// against them, not the roots against the blocks.
+///
+/// # Errors
+/// Describes the first check that fails.
+pub fn verify_v1(
+    proof: &ProofV1,
+    blocks: &[BlockSummary],
+    expected_beacon: Option<&[u8]>,
+) -> Result<()> {
+    /* ---------------------------- 1. Statement ---------------------------- */
+
+    ensure!(
+        proof.params.aux == aux_digest(blocks),
+        "proof binds other auxiliary commitments than the blocks carry"
+    );
+    if let Some(want) = expected_beacon {
+        ensure!(
+            proof.beacon.as_deref() == Some(want),
+            "proof is not bound to the expected beacon"
+        );
+    }
+    check_public_inputs(proof)?;
+
+    // The empty proof covers zero blocks and nothing else.
+    if proof.domain_n == 0 {
+        ensure!(
+            proof.public.tau == 0
+                && proof.col_roots.is_empty()
+                && proof.queries.is_empty()
+                && proof.fri_roots.roots.is_empty()
+                && proof.fri_queries.is_empty()
+                && proof.fri_final_value_le == [0u8; 8]
+                && proof.fri_params.layer_queries.is_empty()
+                && proof.fri_layer_queries.is_empty(),
+            "malformed empty proof"
+        );
+        ensure!(
+            blocks.is_empty(),
+            "empty proof presented for {} blocks",
+            blocks.len()
+        );
+        ensure!(
+            proof.public.manifest_root == EMPTY_ROOT,
+            "a proof over zero blocks must be bound to the empty root"
+        );
+        return Ok(());
+    }
+    ensure!(
+        proof.public.manifest_root != EMPTY_ROOT,
+        "the empty root is reserved for proofs over zero blocks"
+    );
+
+    // Domain: `n` trace rows, a power of two, extended by the blowup (the
+    // public inputs were held to `domain_n = n · BLOWUP` above).
+    let n = proof.public.n_rows;
+    ensure!(n.is_power_of_two(), "trace length n must be a power of two");
+
+    let max_move = proof.params.max_move;
+    ensure!(
+        (1..=MAX_MOVE_LIMIT).contains(&max_move),
+        "unsupported movement bound ±{max_move}"
+    );
+
+    let k = proof.fri_params.num_queries;
+    ensure!(
+        (1..=MAX_NUM_QUERIES).contains(&k),
+        "proof declares {k} queries"
+    );
+
+    // A per-layer FRI schedule names one query count per fold of the
+    // domain; an empty schedule means chained queries.
+    let schedule = &proof.fri_params.layer_queries;
+    if !schedule.is_empty() {
+        let n_folds = proof.domain_n.trailing_zeros() as usize;
+        ensure!(
+            schedule.len() == n_folds,
+            "FRI schedule lists {} layers, proof folds {n_folds}",
+            schedule.len()
+        );
+        for (l, &k) in schedule.iter().enumerate() {
+            ensure!(
+                (1..=MAX_FRI_LAYER_QUERIES).contains(&k),
+                "FRI layer {l} declares {k} queries"
+            );
+        }
+    }
+
+    if let Some(b) = blocks.first() {
+        ensure!(
+            b.windows.len() == proof.public.tau,
+            "tau mismatch vs. block windows: got {}, expected {}",
+            proof.public.tau,
+            b.windows.len()
+        );
+    }
+
+    /* ---------------------------- 2. Transcript --------------------------- */
+
+    let mut tr = prelude(proof);
+
+    // Eight alphas; only the first three weigh constraints checked here.
+    let alphas: Vec<u64> = tr
+        .challenge_words("alphas", 8)
+        .into_iter()
+        .map(field::reduce)
+        .collect();
+    let (a0, a1, a2) = (alphas[0], alphas[1], alphas[2]);
+
+    // One degree-4 mask polynomial, then the out-of-domain point: drawn by
+    // the prover at this point, so drawn (and dropped) here too.
+    tr.absorb("masks", b"masks");
+    tr.absorb_u64("n_masks", 1);
+    tr.absorb_u64("deg", 4);
+    for _ in 0..4 {
+        let _ = tr.challenge("mask_coeff", 8);
+    }
+    let _ = tr.challenge("ood_point", 8);
+
+    check_positions(tr.clone(), proof, n)?;
+
+    /* ------------------------- 3. Openings and AIR ------------------------ */
+
+    for q in &proof.queries {
+        check_row_openings(proof, blocks, q, n)?;
+        ensure!(
+            air_composition(q, max_move, a0, a1, a2) == 0,
+            "AIR composition non-zero at row {}",
+            q.row
+        );
+    }
+
+    /* -------------------------------- 4. FRI ------------------------------ */
+
+    check_fri(&mut tr, proof)
+}
+
+/// The transcript up to the first challenge: the public inputs (the
+/// statement, the parameters' digest and the claims, when present) and the
+/// column roots, each followed by its layout (1 if committed per block).
+fn prelude(proof: &ProofV1) -> Transcript {
+    let public = &proof.public;
+    let mut tr = Transcript::new("sezkp-stark/v1");
+    tr.absorb("manifest_root", &public.manifest_root);
+    tr.absorb_u64("n", public.n_rows as u64);
+    tr.absorb_u64("tau", public.tau as u64);
+    tr.absorb("params_digest", &public.params_digest);
+    if let Some(d) = &public.claims {
+        tr.absorb("claims", d);
+    }
+    tr.absorb_u64("n_cols", proof.col_roots.len() as u64);
+    for c in &proof.col_roots {
+        tr.absorb("col_root", &c.root);
+        tr.absorb_u64("col_per_block", u64::from(c.per_block));
+    }
+    tr
+}
+
+/// The public inputs must declare the FRI domain's row count (zero for the
+/// empty proof) and commit to the declared parameters: the transcript
+/// absorbs only their digest.
+fn check_public_inputs(proof: &ProofV1) -> Result<()> {
+    let public = &proof.public;
+    ensure!(
+        public.n_rows.checked_mul(BLOWUP) == Some(proof.domain_n),
+        "public inputs declare {} rows, FRI domain has {} positions",
+        public.n_rows,
+        proof.domain_n
+    );
+    ensure!(
+        public.params_digest == params_digest(proof),
+        "public inputs commit to other parameters than the proof declares"
+    );
+    Ok(())
+}
+
+/// Digest of the declared parameters, squeezed from a transcript of its own
+/// that absorbs each optional parameter only when present.
+fn params_digest(proof: &ProofV1) -> [u8; 32] {
+    let mut tr = Transcript::new("sezkp-stark/v1/params");
+    tr.absorb_u64("max_move", u64::from(proof.params.max_move));
+    if let Some(len) = proof.params.input_len {
+        tr.absorb_u64("input_len", len);
+    }
+    if let Some(d) = &proof.params.aux {
+        tr.absorb("aux", d);
+    }
+    if proof.fri_params.num_queries != NUM_QUERIES {
+        tr.absorb_u64("num_queries", proof.fri_params.num_queries as u64);
+    }
+    let schedule = &proof.fri_params.layer_queries;
+    if !schedule.is_empty() {
+        tr.absorb_u64("fri_schedule", schedule.len() as u64);
+        for &k in schedule {
+            tr.absorb_u64("fri_schedule", k as u64);
+        }
+    }
+    let mut out = [0u8; 32];
+    out.copy_from_slice(&tr.challenge("params_digest", 32));
+    out
+}
+
+/// Check the opened rows and FRI positions against the transcript.
+///
+/// The positions come from a copy of the transcript that has also seen the
+/// FRI roots (and drawn the betas in between); the FRI check redraws the
+/// betas from the original.
+fn check_positions(mut qtr: Transcript, proof: &ProofV1, n: usize) -> Result<()> {
+    let schedule = &proof.fri_params.layer_queries;
+    let n_layers = proof.fri_roots.roots.len();
+    if let Some((first, rest)) = proof.fri_roots.roots.split_first() {
+        qtr.absorb("fri_layer_root", first);
+        let _ = qtr.challenge("fri_betas", 8 * (n_layers - 1));
+        for r in rest {
+            qtr.absorb("fri_layer_root", r);
+        }
+    }
+    if let Some(b) = &proof.beacon {
+        qtr.absorb("external_beacon", b);
+    }
+
+    // Rows to open: one word per query, each reduced mod n.
+    let k = proof.fri_params.num_queries;
+    let rows = positions(&mut qtr, "row_queries", k, n);
+    ensure!(
+        proof.queries.len() == rows.len(),
+        "AIR query count mismatch (expected {}, got {})",
+        rows.len(),
+        proof.queries.len()
+    );
+    for (i, (q, &want)) in proof.queries.iter().zip(&rows).enumerate() {
+        ensure!(q.row == want, "AIR query row mismatch at position {i}");
+    }
+
+    // FRI positions: as many more (mod domain_n) for chained queries, or
+    // one labelled draw per layer (mod that layer's size) under a schedule.
+    let chained = if schedule.is_empty() {
+        positions(&mut qtr, "row_queries", k, proof.domain_n)
+    } else {
+        Vec::new()
+    };
+    ensure!(
+        proof.fri_queries.len() == chained.len(),
+        "FRI query count mismatch (expected {}, got {})",
+        chained.len(),
+        proof.fri_queries.len()
+    );
+    for (i, (q, &want)) in proof.fri_queries.iter().zip(&chained).enumerate() {
+        ensure!(
+            q.positions.first() == Some(&want),
+            "FRI query position mismatch at position {i}"
+        );
+    }
+    ensure!(
+        proof.fri_layer_queries.len() == schedule.len(),
+        "FRI layer query count mismatch (expected {} layers, got {})",
+        schedule.len(),
+        proof.fri_layer_queries.len()
+    );
+    for (l, (qs, &k)) in proof.fri_layer_queries.iter().zip(schedule).enumerate() {
+        qtr.absorb_u64("fri_layer_queries", l as u64);
+        let want = positions(&mut qtr, "fri_layer_queries", k, proof.domain_n >> l);
+        let got: Vec<usize> = qs.iter().map(|q| q.position).collect();
+        ensure!(
+            got == want,
+            "FRI layer {l} query positions do not match the transcript"
+        );
+    }
+    Ok(())
+}
+
+/// Draw `k` little-endian words under `label`, each reduced mod `n`
+/// (`n = 0` is treated as 1).
+#[allow(clippy::cast_possible_truncation)] // `< n`, which is a `usize`
+fn positions(tr: &mut Transcript, label: &str, k: usize, n: usize) -> Vec<usize> {
+    let n = n.max(1) as u64;
+    tr.challenge_words(label, k)
+        .into_iter()
+        .map(|w| (w % n) as usize)
+        .collect()
+}
+
+/* ------------------------------- Openings ------------------------------- */
+
+/// Check one opening of column `label` at `row`, in the layout its root
+/// declares.
+fn check_column(
+    proof: &ProofV1,
+    blocks: &[BlockSummary],
+    label: &str,
+    o: &ColumnOpening,
+    row: usize,
+) -> Result<()> {
+    // Later entries win if a label repeats (as with the production map).
+    let Some(col) = proof.col_roots.iter().rev().find(|c| c.label == label) else {
+        bail!("missing col root for {label}");
+    };
+    match (col.per_block, o) {
+        (false, ColumnOpening::Chunked(o)) => check_opening(col, o, row),
+        (true, ColumnOpening::Block(o)) => check_block_opening(col, blocks, o, row),
+        _ => bail!("opening of column {label} @ {row} does not match its committed layout"),
+    }
+}
+
+/// Check one opening of the chunked column `col` at `row`: the leaf
+/// `BLAKE3("col_leaf" || len(label) as u32 || label || value)` sits at
+/// `row`, leads to the opening's chunk root, and the chunk root leads to
+/// the column root.
+fn check_opening(col: &ColumnRoot, o: &Opening, row: usize) -> Result<()> {
+    let label = &col.label;
+    ensure!(
+        (o.chunk_index << COL_CHUNK_LOG2) + o.index_in_chunk == row,
+        "opening of column {label} is not at row {row}"
+    );
+    let label_len = u32::try_from(label.len())?;
+    let mut h = blake3::Hasher::new();
+    h.update(b"col_leaf");
+    h.update(&label_len.to_le_bytes());
+    h.update(label.as_bytes());
+    h.update(&o.value_le);
+    let leaf = *h.finalize().as_bytes();
+    ensure!(
+        path_leads_to(&o.chunk_root, leaf, o.index_in_chunk, &o.path_in_chunk)
+            && path_leads_to(&col.root, o.chunk_root, o.chunk_index, &o.path_to_chunk),
+        "chunked merkle path failed for column {label} @ {row}"
+    );
+    Ok(())
+}
+
+/// Check one opening of the per-block column `col` at `row`: the leaf
+/// `BLAKE3("col_block_leaf" || len(label) as u32 || label || lo as u64
+/// || hi as u64 || value)` of the block holding `row` (the `k`-th block,
+/// covering rows `lo..=hi`) leads to the column root, through a tree over
+/// the blocks padded with zero leaves to a power of two.
+fn check_block_opening(
+    col: &ColumnRoot,
+    blocks: &[BlockSummary],
+    o: &BlockOpening,
+    row: usize,
+) -> Result<()> {
+    let label = &col.label;
+
+    // Walk the blocks to the one holding `row`.
+    let mut lo = 0usize;
+    let mut block = None;
+    for (k, b) in blocks.iter().enumerate() {
+        let hi = lo + usize::try_from(b.step_hi.saturating_sub(b.step_lo))?;
+        if row <= hi {
+            block = Some((k, lo, hi));
+            break;
+        }
+        lo = hi + 1;
+    }
+    let depth = blocks.len().next_power_of_two().trailing_zeros() as usize;
+    ensure!(
+        block == Some((o.block_index, o.row_lo, o.row_hi)) && o.path.len() == depth,
+        "block opening of column {label} @ {row} is not the one of its block"
+    );
+
+    let label_len = u32::try_from(label.len())?;
+    let mut h = blake3::Hasher::new();
+    h.update(b"col_block_leaf");
+    h.update(&label_len.to_le_bytes());
+    h.update(label.as_bytes());
+    h.update(&(o.row_lo as u64).to_le_bytes());
+    h.update(&(o.row_hi as u64).to_le_bytes());
+    h.update(&o.value_le);
+    let leaf = *h.finalize().as_bytes();
+    ensure!(
+        path_leads_to(&col.root, leaf, o.block_index, &o.path),
+        "block merkle path failed for column {label} @ {row}"
+    );
+    Ok(())
+}
+
+/// Check every opening of a queried row; the update columns (`next_*`) are
+/// opened on the next row.
+fn check_row_openings(
+    proof: &ProofV1,
+    blocks: &[BlockSummary],
+    q: &RowOpenings,
+    n: usize,
+) -> Result<()> {
+    let (row, next) = (q.row, (q.row + 1) % n);
+    let check = |label: &str, o: &ColumnOpening, row| check_column(proof, blocks, label, o, row);
+    check("input_mv", &q.input_mv, row)?;
+    check("is_first", &q.is_first, row)?;
+    check("is_last", &q.is_last, row)?;
+
+    // The input head is opened exactly when an input length is declared,
+    // and every position lies on the input tape.
+    ensure!(
+        q.input_head.is_some() == proof.params.input_len.is_some(),
+        "input head openings at row {row} do not match the declared input length"
+    );
+    if let (Some(h), Some(len)) = (&q.input_head, proof.params.input_len) {
+        check("in_head", &h.in_head, row)?;
+        check("in_head", &h.next_in_head, next)?;
+        check("input_mv", &h.next_input_mv, next)?;
+        check("in_head_in", &h.in_head_in, row)?;
+        check("in_head_out", &h.in_head_out, row)?;
+        for o in [&h.in_head, &h.in_head_in, &h.in_head_out] {
+            ensure!(
+                u64::from_le_bytes(o.value_le()) < len,
+                "input head at row {row} lies outside the declared input tape [0, {len})"
+            );
+        }
+    }
+
+    for (r, t) in q.per_tape.iter().enumerate() {
+        check(&format!("mv_{r}"), &t.mv, row)?;
+        check(&format!("mv_{r}"), &t.next_mv, next)?;
+        check(&format!("wflag_{r}"), &t.write_flag, row)?;
+        check(&format!("wsym_{r}"), &t.write_sym, row)?;
+        check(&format!("head_{r}"), &t.head, row)?;
+        check(&format!("head_{r}"), &t.next_head, next)?;
+        check(&format!("winlen_{r}"), &t.win_len, row)?;
+        check(&format!("in_off_{r}"), &t.in_off, row)?;
+        check(&format!("out_off_{r}"), &t.out_off, row)?;
+    }
+    Ok(())
+}
+
+/* ---------------------------------- AIR --------------------------------- */
+
+/// `mv · Π_{k=1..m} (mv - k)(mv + k)`: zero exactly when `|mv| ≤ m`.
+fn mv_domain(mv: u64, max_move: u8) -> u64 {
+    let mut acc = mv;
+    for k in 1..=u64::from(max_move) {
+        acc = field::mul(acc, field::mul(field::sub(mv, k), field::add(mv, k)));
+    }
+    acc
+}
+
+/// AIR composition of an opened row, from the opened values alone. Per
+/// tape, with `v(x)` the value opened for `x`:
+///
+/// ```text
+///   a0 · flag · (flag - 1)                                  write flag is 0/1
+/// + a1 · mv_domain(mv)                                      |mv| ≤ max_move
+/// + a2 · (1 - is_last)  · (next_head - head - next_mv)      head follows moves
+/// + a2 · is_first       · (head - mv - in_off)              entry offset
+/// + a2 · is_last        · (head - out_off)                  exit offset
+/// ```
+///
+/// and, when the input head is opened, the last three again with the input
+/// head, `input_mv`, `in_head_in` and `in_head_out`.
+fn air_composition(q: &RowOpenings, max_move: u8, a0: u64, a1: u64, a2: u64) -> u64 {
+    use field::{add, mul, sub};
+    let v = |o: &ColumnOpening| field::from_le(o.value_le());
+
+    let first = v(&q.is_first);
+    let last = v(&q.is_last);
+    let not_last = sub(1, last);
+    let mut acc = 0;
+
+    for t in &q.per_tape {
+        let (flag, mv, head) = (v(&t.write_flag), v(&t.mv), v(&t.head));
+        acc = add(acc, mul(a0, mul(flag, sub(flag, 1))));
+        acc = add(acc, mul(a1, mv_domain(mv, max_move)));
+        let step = sub(sub(v(&t.next_head), head), v(&t.next_mv));
+        acc = add(acc, mul(a2, mul(not_last, step)));
+        acc = add(acc, mul(a2, mul(first, sub(sub(head, mv), v(&t.in_off)))));
+        acc = add(acc, mul(a2, mul(last, sub(head, v(&t.out_off)))));
+    }
+
+    if let Some(h) = &q.input_head {
+        let head = v(&h.in_head);
+        let step = sub(sub(v(&h.next_in_head), head), v(&h.next_input_mv));
+        acc = add(acc, mul(a2, mul(not_last, step)));
+        let entry = sub(sub(head, v(&q.input_mv)), v(&h.in_head_in));
+        acc = add(acc, mul(a2, mul(first, entry)));
+        acc = add(acc, mul(a2, mul(last, sub(head, v(&h.in_head_out)))));
+    }
+    acc
+}
+
+/* ---------------------------------- FRI --------------------------------- */
+
+/// FRI layer leaf: `BLAKE3("sezkp-stark/v1/fri_leaf" || value)`.
+fn fri_leaf(value_le: [u8; 8]) -> [u8; 32] {
+    let mut h = blake3::Hasher::new();
+    h.update(b"sezkp-stark/v1/fri_leaf");
+    h.update(&value_le);
+    *h.finalize().as_bytes()
+}
+
+/// Whether `value_le` at `index` leads to the FRI layer root `root` along
+/// `path`, inner nodes being `BLAKE3("sezkp-stark/v1/fri_node" || left ||
+/// right)` (unlike the column and manifest trees).
+fn fri_opens(root: &[u8; 32], value_le: [u8; 8], index: usize, path: &[[u8; 32]]) -> bool {
+    let mut cur = fri_leaf(value_le);
+    let mut idx = index;
+    for sib in path {
+        let (l, r) = if idx.is_multiple_of(2) {
+            (&cur, sib)
+        } else {
+            (sib, &cur)
+        };
+        let mut h = blake3::Hasher::new();
+        h.update(b"sezkp-stark/v1/fri_node");
+        h.update(l);
+        h.update(r);
+        cur = *h.finalize().as_bytes();
+        idx /= 2;
+    }
+    &cur == root
+}
+
+/// One FRI fold of the pair at `idx` and `idx ^ half` on a layer of size
+/// `2 · half`: `lower + beta · upper`, where `lower` is the value in the
+/// first half.
+const fn fold_pair(idx: usize, half: usize, vi: [u8; 8], vj: [u8; 8], beta: u64) -> u64 {
+    let (vi, vj) = (field::from_le(vi), field::from_le(vj));
+    let (lower, upper) = if idx < half { (vi, vj) } else { (vj, vi) };
+    field::add(lower, field::mul(beta, upper))
+}
+
+/// FRI over the main transcript (which has not yet seen the FRI roots).
+///
+/// Layer `l` of `L` has `2^(L-1-l)` values, so the last layer is the single
+/// final value.
+fn check_fri(tr: &mut Transcript, proof: &ProofV1) -> Result<()> {
+    let roots = &proof.fri_roots.roots;
+    let Some(first) = roots.first() else {
+        bail!("no FRI roots");
+    };
+    let n_layers = roots.len();
+    tr.absorb("fri_layer_root", first);
+    let betas: Vec<u64> = tr
+        .challenge_words("fri_betas", n_layers - 1)
+        .into_iter()
+        .map(field::reduce)
+        .collect();
+
+    let final_le = proof.fri_final_value_le;
+    ensure!(
+        roots[n_layers - 1] == fri_leaf(final_le),
+        "final FRI value mismatch with last root"
+    );
+
+    // Chained queries: one position per layer and one opened pair per fold;
+    // each fold must be the first value of the next pair, the last fold the
+    // final value.
+    for q in &proof.fri_queries {
+        ensure!(q.positions.len() == n_layers, "positions length mismatch");
+        ensure!(q.pairs.len() == n_layers - 1, "pairs length mismatch");
+        let mut idx = q.positions[0];
+        let mut layer_len = 1usize << (n_layers - 1);
+        for l in 0..n_layers - 1 {
+            let half = layer_len / 2;
+            let (vi, path_i, vj, path_j) = &q.pairs[l];
+            ensure!(
+                fri_opens(&roots[l], *vi, idx, path_i)
+                    && fri_opens(&roots[l], *vj, idx ^ half, path_j),
+                "FRI Merkle path failed at layer {l}"
+            );
+            let folded = fold_pair(idx, half, *vi, *vj, betas[l]);
+            ensure!(
+                q.positions[l + 1] == idx % half,
+                "FRI index propagation failed at layer {l}"
+            );
+            if l + 1 < n_layers - 1 {
+                ensure!(
+                    field::from_le(q.pairs[l + 1].0) == folded,
+                    "FRI fold mismatch at layer {l}"
+                );
+            } else {
+                ensure!(field::to_le(folded) == final_le, "final FRI value mismatch");
+            }
+            idx %= half;
+            layer_len = half;
+        }
+    }
+
+    // Per-layer queries: the pair opens on layer l, the folded value on
+    // layer l + 1.
+    let layers = &proof.fri_layer_queries;
+    ensure!(
+        layers.is_empty() || layers.len() == n_layers - 1,
+        "per-layer FRI queries cover {} layers, expected {}",
+        layers.len(),
+        n_layers - 1
+    );
+    for (l, queries) in layers.iter().enumerate() {
+        let half = 1usize << (n_layers - 2 - l);
+        for q in queries {
+            let idx = q.position;
+            ensure!(idx < 2 * half, "FRI layer {l} position {idx} out of range");
+            let (vi, path_i, vj, path_j) = &q.pair;
+            let (folded_le, folded_path) = &q.next;
+            ensure!(
+                fri_opens(&roots[l], *vi, idx, path_i)
+                    && fri_opens(&roots[l], *vj, idx ^ half, path_j),
+                "FRI Merkle path failed at layer {l}"
+            );
+            ensure!(
+                fri_opens(&roots[l + 1], *folded_le, idx % half, folded_path),
+                "FRI Merkle path failed at layer {}",
+                l + 1
+            );
+            ensure!(
+                field::to_le(fold_pair(idx, half, *vi, *vj, betas[l])) == *folded_le,
+                "FRI fold mismatch at layer {l}"
+            );
+        }
+    }
+    Ok(())
+}
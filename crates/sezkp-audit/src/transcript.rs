@@ -0,0 +1,87 @@
+//! The Fiat–Shamir transcript every SEZKP proof uses, written out byte by
+//! byte (the production type is `sezkp_crypto::Blake3Transcript`).
+//!
+//! The state is a running BLAKE3 hash. Lengths are 4-byte little-endian.
+//!
+//! - **new(domain)**: hash `"sezkp.transcript.v0"`, `len(domain)`, `domain`.
+//! - **absorb(label, bytes)**: hash `"absorb"`, `len(label)`, `label`,
+//!   `len(bytes)`, `bytes`.
+//! - **challenge(label, n)**: on a *copy* of the state hash `"challenge"`,
+//!   `len(label)`, `label` and read `n` bytes of XOF output; then hash
+//!   `"after_challenge"`, `len(label)`, `label` into the real state, so the
+//!   next challenge differs even under the same label.
+
+use blake3::Hasher;
+
+/// A transcript in progress.
+#[derive(Clone, Debug)]
+pub struct Transcript {
+    state: Hasher,
+}
+
+/// 4-byte little-endian length prefix.
+///
+/// # Panics
+/// Panics on inputs of 4 GiB or more, which no proof contains.
+#[allow(clippy::expect_used)]
+fn len4(n: usize) -> [u8; 4] {
+    u32::try_from(n)
+        .expect("transcript input too long")
+        .to_le_bytes()
+}
+
+impl Transcript {
+    /// Start a transcript for protocol `domain`.
+    #[must_use]
+    pub fn new(domain: &str) -> Self {
+        let mut state = Hasher::new();
+        state.update(b"sezkp.transcript.v0");
+        state.update(&len4(domain.len()));
+        state.update(domain.as_bytes());
+        Self { state }
+    }
+
+    /// Absorb `bytes` under `label`.
+    pub fn absorb(&mut self, label: &str, bytes: &[u8]) {
+        self.state.update(b"absorb");
+        self.state.update(&len4(label.len()));
+        self.state.update(label.as_bytes());
+        self.state.update(&len4(bytes.len()));
+        self.state.update(bytes);
+    }
+
+    /// Absorb `x` as 8 little-endian bytes.
+    pub fn absorb_u64(&mut self, label: &str, x: u64) {
+        self.absorb(label, &x.to_le_bytes());
+    }
+
+    /// Draw `n` challenge bytes under `label`.
+    #[must_use]
+    pub fn challenge(&mut self, label: &str, n: usize) -> Vec<u8> {
+        let mut fork = self.state.clone();
+        fork.update(b"challenge");
+        fork.update(&len4(label.len()));
+        fork.update(label.as_bytes());
+        let mut out = vec![0u8; n];
+        fork.finalize_xof().fill(&mut out);
+
+        self.state.update(b"after_challenge");
+        self.state.update(&len4(label.len()));
+        self.state.update(label.as_bytes());
+        out
+    }
+
+    /// Draw `n` challenges under `label`, one little-endian `u64` per 8 bytes
+    /// (not yet reduced).
+    #[must_use]
+    pub fn challenge_words(&mut self, label: &str, n: usize) -> Vec<u64> {
+        self.challenge(label, 8 * n)
+            .chunks_exact(8)
+            .map(|w| {
+                let mut le = [0u8; 8];
+                le.copy_from_slice(w);
+                u64::from_le_bytes(le)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,334 @@
+//! Differential tests: the reference verifiers agree with the production
+//! ones, on honest proofs and on tampered copies of them.
+
+#![allow(clippy::unwrap_used)]
+
+use sezkp_audit::{field, fold, merkle, stark, transcript};
+use sezkp_core::auxiliary::aux_commitment;
+use sezkp_core::redact::redact_block;
+use sezkp_core::BlockSummary;
+use sezkp_crypto::{Blake3Transcript, Transcript};
+use sezkp_ffts::Goldilocks;
+use sezkp_fold::api::DriverOptions;
+use sezkp_fold::{run_pipeline, verify, CryptoFold, CryptoLeaf, CryptoWrap, CryptoWrapProof};
+use sezkp_merkle::commit_blocks;
+use sezkp_stark::v1::params::{FriQuerySchedule, StreamTuning};
+use sezkp_stark::v1::proof::{ColumnOpening, ProofV1};
+use sezkp_stark::v1::prover::{prove_v1_scheduled, prove_v1_with_beacon, prove_v1_with_queries};
+use sezkp_stark::v1::verify::{verify_v1_under, BlockFacts, Requirements};
+use sezkp_trace::{generator::generate_trace, partition::partition_trace};
+
+fn blocks(t: u64, tau: u8, b: u32) -> Vec<BlockSummary> {
+    partition_trace(&generate_trace(t, tau), b)
+}
+
+/* -------------------------------- Field --------------------------------- */
+
+#[test]
+fn field_matches_goldilocks() {
+    let p = field::P;
+    let mut xs = vec![
+        0,
+        1,
+        2,
+        p - 2,
+        p - 1,
+        p,
+        p + 1,
+        u64::MAX,
+        1 << 32,
+        (1 << 32) - 1,
+    ];
+    let mut s = 0x9E37_79B9_7F4A_7C15_u64;
+    for _ in 0..200 {
+        s ^= s << 13;
+        s ^= s >> 7;
+        s ^= s << 17;
+        xs.push(s);
+    }
+    for &a in &xs {
+        let fa = Goldilocks::from_u64(a);
+        assert_eq!(field::reduce(a), fa.0);
+        assert_eq!(field::to_le(a), fa.to_le_bytes());
+        for &b in &xs {
+            let fb = Goldilocks::from_u64(b);
+            assert_eq!(field::add(a, b), (fa + fb).0, "{a} + {b}");
+            assert_eq!(field::sub(a, b), (fa - fb).0, "{a} - {b}");
+            assert_eq!(field::mul(a, b), (fa * fb).0, "{a} * {b}");
+        }
+    }
+}
+
+/* ------------------------------ Transcript ------------------------------ */
+
+#[test]
+fn transcript_matches_blake3_transcript() {
+    let mut ours = transcript::Transcript::new("audit/test");
+    let mut theirs = Blake3Transcript::new("audit/test");
+    for i in 0..20u64 {
+        let bytes: Vec<u8> = (0..i * 7).map(|b| b as u8).collect();
+        ours.absorb("bytes", &bytes);
+        theirs.absorb("bytes", &bytes);
+        ours.absorb_u64("i", i);
+        theirs.absorb_u64("i", i);
+        let n = (i * 13 % 70) as usize;
+        assert_eq!(ours.challenge("c", n), theirs.challenge_bytes("c", n));
+    }
+    // Forks stay in step too.
+    assert_eq!(
+        ours.clone().challenge("fork", 32),
+        theirs.clone().challenge_bytes("fork", 32)
+    );
+}
+
+/* -------------------------------- Merkle -------------------------------- */
+
+#[test]
+fn merkle_root_matches_every_size_up_to_300() {
+    for n in 0..=300u64 {
+        let leaves: Vec<[u8; 32]> = (0..n)
+            .map(|i| *blake3::hash(&i.to_le_bytes()).as_bytes())
+            .collect();
+        assert_eq!(
+            merkle::merkle_root(&leaves),
+            sezkp_merkle::merkle_root(leaves.clone()),
+            "n = {n}"
+        );
+    }
+}
+
+#[test]
+fn leaf_hash_and_root_match_for_every_schema() {
+    let plain = blocks(64, 2, 4);
+    let redacted: Vec<BlockSummary> = plain
+        .iter()
+        .map(|b| redact_block(b, &[9u8; 32]).unwrap())
+        .collect();
+    let mut aux = plain.clone();
+    aux[1].aux_commitments = vec![aux_commitment("pc-histogram", &[1, 2])];
+    aux[2].aux_commitments = vec![aux_commitment("a", &[3]), aux_commitment("b", b"x")];
+
+    for set in [&plain, &redacted, &aux] {
+        for b in set.iter() {
+            assert_eq!(merkle::leaf_hash(b), sezkp_merkle::leaf_hash(b));
+        }
+        assert_eq!(merkle::blocks_root(set), commit_blocks(set).root);
+    }
+}
+
+/* --------------------------------- Fold --------------------------------- */
+
+fn bundle(blocks: &[BlockSummary], claims: Option<[u8; 32]>) -> fold::Bundle {
+    let opts = DriverOptions {
+        wrap_cadence: 2,
+        manifest_root: commit_blocks(blocks).root,
+        claims,
+        ..DriverOptions::default()
+    };
+    run_pipeline::<CryptoLeaf, CryptoFold, CryptoWrap>(blocks, &opts)
+}
+
+/// Both verifiers decide `b` alike; returns that decision.
+fn fold_agree(b: &fold::Bundle) -> bool {
+    let production = verify::verify_bundle::<CryptoLeaf, CryptoFold, CryptoWrap>(b);
+    let reference = fold::verify_bundle(b);
+    assert_eq!(
+        production.is_ok(),
+        reference.is_ok(),
+        "production: {production:?}, reference: {reference:?}"
+    );
+    reference.is_ok()
+}
+
+#[test]
+fn fold_honest_bundles_verify_to_the_manifest_root() {
+    // 0, 1, 4 and 16 blocks: the balanced fold tree is the manifest tree.
+    for n in [0u64, 4, 16, 64] {
+        let blocks = blocks(n, 2, 4);
+        for claims in [None, Some([5u8; 32])] {
+            let b = bundle(&blocks, claims);
+            assert!(fold_agree(&b));
+            assert_eq!(
+                fold::verify_bundle(&b).unwrap(),
+                commit_blocks(&blocks).root
+            );
+        }
+    }
+    // Five blocks: both accept the gadgets whatever the top turns out to be.
+    assert!(fold_agree(&bundle(&blocks(20, 2, 4), None)));
+}
+
+#[test]
+fn fold_tampered_bundles_are_rejected_by_both() {
+    let honest = bundle(&blocks(64, 2, 4), Some([5u8; 32]));
+    type Tamper = fn(&mut fold::Bundle);
+    let tampers: [(&str, Tamper); 14] = [
+        ("manifest root", |b| b.manifest_root[0] ^= 1),
+        ("claims", |b| b.claims = None),
+        ("leaf MAC", |b| b.leaves[1].2.mac[0] ^= 1),
+        ("leaf π", |b| b.leaves[2].1.flags ^= 2),
+        ("leaf commitment", |b| b.leaves[0].0.len += 1),
+        ("leaf public inputs", |b| {
+            b.leaves[3].2.public.acc_limbs[0] ^= 1
+        }),
+        ("leaf π micro-proof", |b| b.leaves[3].2.proof.mac[5] ^= 1),
+        ("fold MAC", |b| b.folds[0].3.mac[0] ^= 1),
+        ("fold parent", |b| b.folds[1].0 .0.root[0] ^= 1),
+        ("fold opening", |b| b.folds[2].3.pi = None),
+        ("fold interface", |b| b.folds[0].3.iface.left_ctrl_out ^= 1),
+        ("wrap MAC", |b| {
+            b.wraps[0].1 = CryptoWrapProof::V1Mac([0; 32])
+        }),
+        ("dropped wrap", |b| {
+            b.wraps.pop();
+        }),
+        ("declared window", |b| {
+            if let Some(p) = &mut b.params {
+                p.iface_window = 16;
+            }
+        }),
+    ];
+    for (what, tamper) in tampers {
+        let mut b = honest.clone();
+        tamper(&mut b);
+        assert!(!fold_agree(&b), "{what} was accepted");
+    }
+}
+
+/* -------------------------------- STARK --------------------------------- */
+
+/// Both verifiers decide `proof` alike; returns that decision.
+///
+/// The production verifier runs without a security floor and accepts the
+/// declared movement model: those are policy layered on top of the
+/// verification math the reference covers.
+fn stark_agree(proof: &ProofV1, blocks: &[BlockSummary], beacon: Option<&[u8]>) -> bool {
+    let req = Requirements {
+        min_security_bits: 0,
+        max_move: proof.params.max_move,
+    };
+    let production = verify_v1_under(proof, &BlockFacts::of(blocks), beacon, &req);
+    let reference = stark::verify_v1(proof, blocks, beacon);
+    assert_eq!(
+        production.is_ok(),
+        reference.is_ok(),
+        "production: {production:?}, reference: {reference:?}"
+    );
+    reference.is_ok()
+}
+
+#[test]
+fn stark_empty_proof_agrees() {
+    assert!(stark_agree(&ProofV1::empty(), &[], None));
+    let blocks = blocks(8, 1, 4);
+    assert!(!stark_agree(&ProofV1::empty(), &blocks, None));
+}
+
+#[test]
+fn stark_chained_proof_agrees_and_tampering_is_rejected() {
+    let blocks = blocks(16, 2, 4);
+    let root = commit_blocks(&blocks).root;
+    let proof = prove_v1_with_beacon(&blocks, root, Some(b"beacon")).unwrap();
+    assert!(stark_agree(&proof, &blocks, Some(b"beacon")));
+    assert!(!stark_agree(&proof, &blocks, Some(b"other")));
+
+    type Tamper = fn(&mut ProofV1);
+    let tampers: [(&str, Tamper); 17] = [
+        ("manifest root", |p| p.public.manifest_root[0] ^= 1),
+        ("beacon", |p| p.beacon = None),
+        ("tau", |p| p.public.tau += 1),
+        ("row count", |p| p.public.n_rows *= 2),
+        ("params digest", |p| p.public.params_digest[0] ^= 1),
+        ("max move", |p| p.params.max_move += 1),
+        ("column root", |p| p.col_roots[3].root[0] ^= 1),
+        ("column layout", |p| p.col_roots[3].per_block ^= true),
+        ("query row", |p| p.queries[0].row ^= 1),
+        ("opened value", |p| {
+            p.queries[1].per_tape[0].head.value_le_mut()[0] ^= 1
+        }),
+        ("opening path", |p| {
+            if let ColumnOpening::Chunked(o) = &mut p.queries[2].is_last {
+                o.path_in_chunk[0][0] ^= 1;
+            }
+        }),
+        ("block value", |p| {
+            p.queries[3].per_tape[0].win_len.value_le_mut()[0] ^= 1
+        }),
+        ("block range", |p| {
+            if let ColumnOpening::Block(o) = &mut p.queries[4].per_tape[1].out_off {
+                o.row_hi += 1;
+            }
+        }),
+        ("block index", |p| {
+            if let ColumnOpening::Block(o) = &mut p.queries[5].per_tape[0].in_off {
+                o.block_index ^= 1;
+            }
+        }),
+        ("FRI final value", |p| p.fri_final_value_le[0] ^= 1),
+        ("FRI pair", |p| p.fri_queries[0].pairs[0].2[0] ^= 1),
+        ("FRI position", |p| p.fri_queries[4].positions[1] ^= 1),
+    ];
+    for (what, tamper) in tampers {
+        let mut p = proof.clone();
+        tamper(&mut p);
+        assert!(
+            !stark_agree(&p, &blocks, Some(b"beacon")),
+            "{what} was accepted"
+        );
+    }
+}
+
+#[test]
+fn stark_per_layer_proof_agrees_and_tampering_is_rejected() {
+    let blocks = blocks(16, 1, 4);
+    let root = commit_blocks(&blocks).root;
+    let schedule = FriQuerySchedule::PerLayer(vec![3]);
+    let (proof, _) =
+        prove_v1_scheduled(&blocks, root, None, StreamTuning::default(), &schedule).unwrap();
+    assert!(stark_agree(&proof, &blocks, None));
+
+    type Tamper = fn(&mut ProofV1);
+    let tampers: [(&str, Tamper); 4] = [
+        ("schedule", |p| p.fri_params.layer_queries[0] += 1),
+        ("layer position", |p| {
+            p.fri_layer_queries[1][0].position ^= 1
+        }),
+        ("folded value", |p| p.fri_layer_queries[2][1].next.0[0] ^= 1),
+        ("pair path", |p| p.fri_layer_queries[0][2].pair.1[0][0] ^= 1),
+    ];
+    for (what, tamper) in tampers {
+        let mut p = proof.clone();
+        tamper(&mut p);
+        assert!(!stark_agree(&p, &blocks, None), "{what} was accepted");
+    }
+}
+
+#[test]
+fn stark_sized_proof_agrees_and_tampering_is_rejected() {
+    let blocks = blocks(16, 1, 4);
+    let root = commit_blocks(&blocks).root;
+    let (proof, _) = prove_v1_with_queries(
+        &blocks,
+        root,
+        None,
+        StreamTuning::default(),
+        &FriQuerySchedule::Chained,
+        None,
+        None,
+        34,
+    )
+    .unwrap();
+    assert!(stark_agree(&proof, &blocks, None));
+
+    let mut p = proof.clone();
+    p.fri_params.num_queries = 30;
+    p.queries.truncate(30);
+    p.fri_queries.truncate(30);
+    assert!(!stark_agree(&p, &blocks, None), "query count was accepted");
+    let mut p = proof;
+    p.fri_params.num_queries = 0;
+    assert!(
+        !stark_agree(&p, &blocks, None),
+        "zero queries were accepted"
+    );
+}
@@ -0,0 +1,425 @@
+//! Flags and option types shared by several subcommands.
+//!
+//! Block sources (`--blocks` or `--trace`), the backend choice, timestamp,
+//! fold-expectation and durability flags, and the global replay flags. Most
+//! fold options reach the backend through `SEZKP_*` environment variables
+//! ([`envkeys`], set via [`crate::envguard`]).
+
+use anyhow::{bail, Context, Result};
+use clap::{Args, ValueEnum};
+use sezkp_core::boundary::DigestMode;
+use sezkp_core::io::{read_block_summaries_auto, stream_block_summaries_auto};
+use sezkp_core::io_format::FileFormat;
+use sezkp_core::io_jsonl::BlockStream;
+use sezkp_core::timestamp::TimeAuthority;
+use sezkp_core::BlockSummary;
+use sezkp_fold::api::{FoldMode, FoldParams};
+use sezkp_trace::partition::{partition_iter, PartitionStrategy};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+use crate::envguard;
+
+/// Environment keys used by backends (centralized to avoid typos).
+pub(crate) mod envkeys {
+    pub const FOLD_MODE: &str = "SEZKP_FOLD_MODE";
+    pub const FOLD_CACHE: &str = "SEZKP_FOLD_CACHE";
+    pub const FOLD_LEDGER: &str = "SEZKP_FOLD_LEDGER";
+    pub const WRAP_CADENCE: &str = "SEZKP_WRAP_CADENCE";
+    pub const FOLD_FINAL_WRAP: &str = "SEZKP_FOLD_FINAL_WRAP";
+    pub const FOLD_ARE_SAMPLES: &str = "SEZKP_FOLD_ARE_SAMPLES";
+    pub const FOLD_IFACE_DIGEST: &str = "SEZKP_FOLD_IFACE_DIGEST";
+    pub const FOLD_SPOT_CHECKS: &str = "SEZKP_FOLD_SPOT_CHECKS";
+    pub const FOLD_REVEAL_ROOT_PI: &str = "SEZKP_FOLD_REVEAL_ROOT_PI";
+    pub const PROOF_STREAM_PATH: &str = "SEZKP_PROOF_STREAM_PATH";
+    pub const FOLD_CACHE_DIR: &str = "SEZKP_FOLD_CACHE_DIR";
+    pub const FOLD_IFACE_LOG: &str = "SEZKP_FOLD_IFACE_LOG";
+    pub const FOLD_DURABILITY: &str = "SEZKP_FOLD_DURABILITY";
+    pub const FOLD_SYNC_ITEMS: &str = "SEZKP_FOLD_SYNC_ITEMS";
+    pub const FOLD_SYNC_SECS: &str = "SEZKP_FOLD_SYNC_SECS";
+    pub const FOLD_RESUME: &str = "SEZKP_FOLD_RESUME";
+    pub const EXPECT_FOLD_MODE: &str = "SEZKP_EXPECT_FOLD_MODE";
+    pub const EXPECT_WRAP_CADENCE: &str = "SEZKP_EXPECT_WRAP_CADENCE";
+    pub const EXPECT_ARE_SAMPLES: &str = "SEZKP_EXPECT_ARE_SAMPLES";
+    pub const EXPECT_IFACE_DIGEST: &str = "SEZKP_EXPECT_IFACE_DIGEST";
+    pub const FOLD_ALLOW_REWINDOW: &str = "SEZKP_FOLD_ALLOW_REWINDOW";
+}
+
+/// Available proving/verification backends.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, ValueEnum)]
+pub(crate) enum BackendOpt {
+    /// Folding-based aggregation backend.
+    Fold,
+    /// STARK v1 backend (PIOP/FRI; streaming-friendly).
+    Stark,
+}
+
+/// Backends `prove` accepts: a fixed one, or `auto`.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, ValueEnum)]
+pub(crate) enum ProveBackendOpt {
+    /// Folding-based aggregation backend.
+    Fold,
+    /// STARK v1 backend (PIOP/FRI; streaming-friendly).
+    Stark,
+    /// STARK v1 up to `--auto-stark-max-rows` rows and while it fits in
+    /// memory, the fold backend (streaming `.jsonl` inputs) beyond.
+    Auto,
+}
+
+impl ProveBackendOpt {
+    /// The backend asked for, or `None` for `auto`.
+    pub(crate) const fn fixed(self) -> Option<BackendOpt> {
+        match self {
+            Self::Fold => Some(BackendOpt::Fold),
+            Self::Stark => Some(BackendOpt::Stark),
+            Self::Auto => None,
+        }
+    }
+}
+
+/// Trace input flags shared by `commit` and `prove` (instead of `--blocks`).
+#[derive(Args, Debug, Clone)]
+pub(crate) struct TraceArgs {
+    /// Read a trace (CBOR/JSON) and cut it into σ_k blocks on the fly, so
+    /// no blocks file is written. The trace itself is loaded into memory.
+    #[arg(long, conflicts_with = "blocks")]
+    trace: Option<PathBuf>,
+
+    /// Block size b for `--trace`: steps per σ_k block (> 0, default 512).
+    /// Prove with the same b (and `--partition`) the manifest was committed with.
+    #[arg(
+        long = "b",
+        requires = "trace",
+        conflicts_with = "blocks",
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    trace_b: Option<u32>,
+
+    /// How `--trace` is cut into blocks (default `fixed`).
+    #[arg(long, requires = "trace", conflicts_with = "blocks", value_enum)]
+    partition: Option<PartitionOpt>,
+}
+
+/// Block size `--trace` uses when `--b` is not given.
+const DEFAULT_TRACE_B: u32 = 512;
+
+impl TraceArgs {
+    /// The block source: `--trace` if given, otherwise `blocks`.
+    pub(crate) fn input(self, blocks: Option<PathBuf>) -> Result<BlockInput> {
+        match (self.trace, blocks) {
+            (Some(path), _) => Ok(BlockInput::Trace {
+                path,
+                b: self.trace_b.unwrap_or(DEFAULT_TRACE_B),
+                strategy: self
+                    .partition
+                    .map_or(PartitionStrategy::Fixed, PartitionOpt::strategy),
+            }),
+            (None, Some(blocks)) => Ok(BlockInput::File(blocks)),
+            (None, None) => bail!("one of --blocks or --trace is required"),
+        }
+    }
+}
+
+/// Where a command's blocks come from.
+#[derive(Debug, Clone)]
+pub(crate) enum BlockInput {
+    /// A blocks file (CBOR/JSON/JSONL/NDJSON).
+    File(PathBuf),
+    /// A trace file, partitioned on the fly.
+    Trace {
+        path: PathBuf,
+        b: u32,
+        strategy: PartitionStrategy,
+    },
+}
+
+impl BlockInput {
+    /// Stream the blocks in order.
+    pub(crate) fn stream(&self) -> Result<BlockStream> {
+        match self {
+            Self::File(path) => stream_block_summaries_auto(path).context("open blocks stream"),
+            Self::Trace { path, b, strategy } => {
+                let tf = sezkp_trace::io::read_trace_auto(path)
+                    .with_context(|| format!("reading trace {}", path.display()))?;
+                Ok(Box::new(partition_iter(tf, *b, *strategy).map(Ok)))
+            }
+        }
+    }
+
+    /// Whether streaming reads the blocks one at a time (`.jsonl`/`.ndjson`).
+    pub(crate) fn streams_lazily(&self) -> bool {
+        matches!(self, Self::File(path)
+            if sezkp_core::io::block_file_format(path).ok() == Some(FileFormat::Jsonl))
+    }
+
+    /// Load every block.
+    pub(crate) fn read_all(&self) -> Result<Vec<BlockSummary>> {
+        match self {
+            Self::File(path) => read_block_summaries_auto(path).context("reading blocks"),
+            Self::Trace { .. } => self.stream()?.collect(),
+        }
+    }
+
+    /// Check that the blocks reproduce `manifest`.
+    pub(crate) fn check_committed(&self, manifest: &Path) -> Result<()> {
+        use sezkp_merkle::{
+            verify_block_file_against_manifest, verify_trace_file_against_manifest,
+        };
+        match self {
+            Self::File(path) => verify_block_file_against_manifest(path, manifest),
+            Self::Trace { path, b, strategy } => {
+                verify_trace_file_against_manifest(path, *b, *strategy, manifest)
+            }
+        }
+    }
+}
+
+impl fmt::Display for BlockInput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::File(path) => write!(f, "{}", path.display()),
+            Self::Trace { path, b, strategy } => {
+                write!(f, "{} (b={b}, {strategy:?})", path.display())
+            }
+        }
+    }
+}
+
+/// Time authority flags shared by `prove` (issue) and `verify` (check).
+#[derive(Args, Debug, Clone)]
+pub(crate) struct TimestampArgs {
+    /// Authority key file (64 hex chars). On `prove`, attaches a signed
+    /// creation timestamp; on `verify`, checks it.
+    #[arg(long)]
+    pub(crate) timestamp_key: Option<PathBuf>,
+
+    /// Authority name bound into the timestamp token.
+    #[arg(long, default_value = "local")]
+    pub(crate) timestamp_authority: String,
+}
+
+impl TimestampArgs {
+    /// Load the configured authority, if a key was given.
+    pub(crate) fn authority(&self) -> Result<Option<TimeAuthority>> {
+        self.timestamp_key
+            .as_deref()
+            .map(|k| TimeAuthority::from_key_file(self.timestamp_authority.clone(), k))
+            .transpose()
+    }
+}
+
+/// Fold parameters a verifier requires the proof to declare.
+#[derive(Args, Debug, Clone, Default)]
+pub(crate) struct FoldExpectArgs {
+    /// Reject fold proofs not made in this driver mode.
+    #[arg(long, value_enum)]
+    pub(crate) expect_fold_mode: Option<FoldModeOpt>,
+
+    /// Reject fold proofs with a different wrap cadence.
+    #[arg(long)]
+    pub(crate) expect_wrap_cadence: Option<u32>,
+
+    /// Reject fold proofs opening fewer sampled steps per leaf.
+    #[arg(long)]
+    pub(crate) expect_are_samples: Option<u32>,
+
+    /// Reject fold proofs whose seams are digested in another mode.
+    #[arg(long, value_name = "MODE")]
+    pub(crate) expect_iface_digest: Option<DigestMode>,
+}
+
+impl FoldExpectArgs {
+    /// Require exactly the fold parameters a parameter file pins.
+    pub(crate) fn from_params(p: &FoldParams) -> Self {
+        Self {
+            expect_fold_mode: Some(p.fold_mode.into()),
+            expect_wrap_cadence: Some(p.wrap_cadence),
+            expect_are_samples: Some(p.are_samples),
+            expect_iface_digest: Some(p.iface_digest),
+        }
+    }
+
+    /// Hand the expectations to the fold backend (via env vars).
+    pub(crate) fn apply(&self, backend: BackendOpt) {
+        let set = self.expect_fold_mode.is_some()
+            || self.expect_wrap_cadence.is_some()
+            || self.expect_are_samples.is_some()
+            || self.expect_iface_digest.is_some();
+        if set && !matches!(backend, BackendOpt::Fold) {
+            warn!("--expect-* flags only apply to the fold backend; ignoring");
+        }
+        match self.expect_fold_mode {
+            Some(m) => envguard::set(envkeys::EXPECT_FOLD_MODE, m.env_value()),
+            None => envguard::unset(envkeys::EXPECT_FOLD_MODE),
+        }
+        match self.expect_wrap_cadence {
+            Some(k) => envguard::set(envkeys::EXPECT_WRAP_CADENCE, k.to_string()),
+            None => envguard::unset(envkeys::EXPECT_WRAP_CADENCE),
+        }
+        match self.expect_are_samples {
+            Some(r) => envguard::set(envkeys::EXPECT_ARE_SAMPLES, r.to_string()),
+            None => envguard::unset(envkeys::EXPECT_ARE_SAMPLES),
+        }
+        match self.expect_iface_digest {
+            Some(m) => envguard::set(envkeys::EXPECT_IFACE_DIGEST, m.id()),
+            None => envguard::unset(envkeys::EXPECT_IFACE_DIGEST),
+        }
+    }
+}
+
+/// Crash-consistency flags for streaming fold proofs.
+#[derive(Args, Debug, Clone)]
+pub(crate) struct DurabilityArgs {
+    /// How durably the proof stream is written: `fsync` syncs it at each
+    /// sync point, `journal` also records the driver state there so
+    /// `--resume` can continue after a crash (streaming fold proofs only).
+    #[arg(long, value_enum, default_value_t = DurabilityOpt::Buffered)]
+    pub(crate) durability: DurabilityOpt,
+
+    /// Sync point every N stream items [default: every block].
+    #[arg(long, value_name = "N")]
+    pub(crate) sync_every: Option<u32>,
+
+    /// Sync point at most every N seconds [default: every block].
+    #[arg(long, value_name = "SECS")]
+    pub(crate) sync_secs: Option<u32>,
+
+    /// Continue the proof stream from its journal (`--durability journal`)
+    /// instead of starting over; pass the same blocks and flags as the
+    /// interrupted run. Starts over if there is no journal.
+    #[arg(long, default_value_t = false)]
+    pub(crate) resume: bool,
+}
+
+impl DurabilityArgs {
+    /// Hand the sync policy to the fold backend (via env vars).
+    pub(crate) fn apply(&self, stream: bool) {
+        let set = self.durability != DurabilityOpt::Buffered
+            || self.sync_every.is_some()
+            || self.sync_secs.is_some()
+            || self.resume;
+        if set && !stream {
+            warn!("--durability/--sync-*/--resume only apply with --stream; ignoring");
+        }
+        if self.resume && self.durability != DurabilityOpt::Journal {
+            warn!("--resume needs --durability journal to find a journal");
+        }
+        envguard::set(envkeys::FOLD_DURABILITY, self.durability.env_value());
+        envguard::set(
+            envkeys::FOLD_SYNC_ITEMS,
+            self.sync_every.unwrap_or(0).to_string(),
+        );
+        envguard::set(
+            envkeys::FOLD_SYNC_SECS,
+            self.sync_secs.unwrap_or(0).to_string(),
+        );
+        if self.resume && stream {
+            envguard::set(envkeys::FOLD_RESUME, "1");
+        } else {
+            envguard::unset(envkeys::FOLD_RESUME);
+        }
+    }
+}
+
+/// Durability level of a streamed fold proof.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, ValueEnum)]
+pub(crate) enum DurabilityOpt {
+    /// Leave flushing to the OS.
+    Buffered,
+    /// `fsync` the stream at each sync point.
+    Fsync,
+    /// `fsync` and journal the driver state at each sync point.
+    Journal,
+}
+
+impl DurabilityOpt {
+    /// Spelling understood by the fold backend's env vars.
+    pub(crate) const fn env_value(self) -> &'static str {
+        match self {
+            Self::Buffered => "buffered",
+            Self::Fsync => "fsync",
+            Self::Journal => "journal",
+        }
+    }
+}
+
+/// Folding driver modes.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, ValueEnum)]
+pub(crate) enum FoldModeOpt {
+    /// Balanced space/time (keeps O(T) endpoints).
+    Balanced,
+    /// Min-RAM mode (recompute endpoints; sublinear space).
+    Minram,
+}
+
+impl FoldModeOpt {
+    /// Spelling understood by the fold backend's env vars.
+    pub(crate) const fn env_value(self) -> &'static str {
+        match self {
+            Self::Balanced => "balanced",
+            Self::Minram => "minram",
+        }
+    }
+
+    /// The fold backend's mode.
+    pub(crate) const fn mode(self) -> FoldMode {
+        match self {
+            Self::Balanced => FoldMode::Balanced,
+            Self::Minram => FoldMode::MinRam,
+        }
+    }
+}
+
+impl From<FoldMode> for FoldModeOpt {
+    fn from(m: FoldMode) -> Self {
+        match m {
+            FoldMode::Balanced => Self::Balanced,
+            FoldMode::MinRam => Self::Minram,
+        }
+    }
+}
+
+/// Trace partition strategies (see `sezkp_trace::partition::PartitionStrategy`).
+#[derive(Copy, Clone, Eq, PartialEq, Debug, ValueEnum)]
+enum PartitionOpt {
+    /// Blocks of exactly b steps; the last takes the remainder.
+    Fixed,
+    /// ⌈T/b⌉ blocks within one step of each other in size.
+    Balanced,
+}
+
+impl PartitionOpt {
+    const fn strategy(self) -> PartitionStrategy {
+        match self {
+            Self::Fixed => PartitionStrategy::Fixed,
+            Self::Balanced => PartitionStrategy::Balanced,
+        }
+    }
+}
+
+/// Balanced-mode endpoint ledger storage.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, ValueEnum)]
+pub(crate) enum FoldLedgerOpt {
+    /// One in-memory slot per block.
+    Memory,
+    /// Paged temp file; only a few pages stay in RAM.
+    Disk,
+}
+
+/// The global `--allow-rewindow` and `--strict-endpoints` flags, passed to
+/// every command that replays blocks.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct ReplayFlags {
+    pub(crate) allow_rewindow: bool,
+    pub(crate) strict_endpoints: bool,
+}
+
+impl ReplayFlags {
+    /// Fold streaming prover/verifier honouring the flags.
+    pub(crate) fn fold_prover(self) -> sezkp_core::prover::StreamingProver<sezkp_fold::FoldAgg> {
+        sezkp_core::prover::StreamingProver::default()
+            .with_rewindow(self.allow_rewindow)
+            .with_strict_endpoints(self.strict_endpoints)
+    }
+}
@@ -0,0 +1,278 @@
+//! `bench`: end-to-end throughput on synthetic data.
+//!
+//! One run generates a random-walk trace for the chosen preset, partitions it
+//! into blocks, commits them, proves and verifies, all inside a fresh
+//! temporary directory that is removed afterwards. Every stage goes through
+//! the same library calls the individual commands use (JSONL blocks file,
+//! manifest and proof artifact on disk), so the numbers characterize the
+//! machine rather than the harness.
+//!
+//! Memory is the peak resident set size from `/proc/self/status` (`VmHWM`).
+//! Where the kernel allows resetting it (`/proc/self/clear_refs`), each stage
+//! reports its own peak; otherwise the column is the running process peak.
+//! Other platforms print `n/a`.
+
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use sezkp_core::io::{read_proof_auto, write_block_summaries_auto, write_proof_auto};
+use sezkp_core::prover::StreamingProver;
+use sezkp_core::{BlockSummary, ProofArtifact, ProvingBackend};
+use sezkp_fold::FoldAgg;
+use sezkp_merkle::{commit_blocks, write_manifest_auto};
+use sezkp_stark::StarkV1;
+use sezkp_trace::{generator::generate_trace, partition::partition_trace};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::args::BackendOpt;
+use crate::plan::human_bytes;
+
+/// Synthetic workload sizes for `bench`.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, ValueEnum)]
+pub(crate) enum BenchPreset {
+    /// 2¹⁰ steps in 8 blocks, τ = 2 (seconds on either backend).
+    Small,
+    /// 2¹⁴ steps in 32 blocks, τ = 4 (a minute or two of STARK proving).
+    Medium,
+    /// 2¹⁸ steps in 128 blocks, τ = 8 (tens of minutes of STARK proving).
+    Large,
+}
+
+impl BenchPreset {
+    /// `(T, b, τ)`. `T` is a power of two so the STARK trace needs no padding.
+    const fn shape(self) -> (u64, u32, u8) {
+        match self {
+            Self::Small => (1 << 10, 1 << 7, 2),
+            Self::Medium => (1 << 14, 1 << 9, 4),
+            Self::Large => (1 << 18, 1 << 11, 8),
+        }
+    }
+}
+
+/// One timed stage.
+struct Stage {
+    name: &'static str,
+    time: Duration,
+    /// Bytes written by the stage, if it writes a file.
+    bytes: Option<u64>,
+    peak_rss: Option<u64>,
+}
+
+/// Peak resident set size of this process, in bytes (Linux only).
+fn peak_rss() -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    let kb = status
+        .lines()
+        .find_map(|l| l.strip_prefix("VmHWM:"))?
+        .trim()
+        .strip_suffix("kB")?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    Some(kb * 1024)
+}
+
+/// Reset the peak resident set size to the current one; `false` if the
+/// kernel does not allow it (the peak then keeps growing across stages).
+fn reset_peak_rss() -> bool {
+    fs::write("/proc/self/clear_refs", "5").is_ok()
+}
+
+/// Run `f` as one stage; `bytes` sizes its output afterwards.
+fn timed<T>(
+    stages: &mut Vec<Stage>,
+    name: &'static str,
+    f: impl FnOnce() -> Result<T>,
+    bytes: impl FnOnce(&T) -> Option<u64>,
+) -> Result<T> {
+    reset_peak_rss();
+    let t0 = Instant::now();
+    let out = f().with_context(|| format!("bench stage {name}"))?;
+    let time = t0.elapsed();
+    stages.push(Stage {
+        name,
+        time,
+        bytes: bytes(&out),
+        peak_rss: peak_rss(),
+    });
+    Ok(out)
+}
+
+fn file_len(path: &Path) -> Option<u64> {
+    fs::metadata(path).ok().map(|m| m.len())
+}
+
+/// Fresh scratch directory under the system temp dir.
+fn scratch_dir() -> Result<PathBuf> {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos());
+    let dir = std::env::temp_dir().join(format!("sezkp-bench-{}-{nanos}", std::process::id()));
+    fs::create_dir_all(&dir).with_context(|| format!("create {}", dir.display()))?;
+    Ok(dir)
+}
+
+/// Prove and verify `blocks` with backend `B`, reading the artifact back
+/// from disk for verification.
+fn prove_and_verify<B: ProvingBackend>(
+    stages: &mut Vec<Stage>,
+    blocks: &[BlockSummary],
+    root: [u8; 32],
+    proof_path: &Path,
+) -> Result<()> {
+    timed(
+        stages,
+        "prove",
+        || {
+            let art = StreamingProver::<B>::prove(blocks, root)?;
+            write_proof_auto(proof_path, &art)
+        },
+        |()| file_len(proof_path),
+    )?;
+    timed(
+        stages,
+        "verify",
+        || {
+            let art: ProofArtifact = read_proof_auto(proof_path)?;
+            StreamingProver::<B>::verify(&art, blocks, root)
+        },
+        |()| None,
+    )
+}
+
+/// The command-line spelling of a value (`small`, `fold`, …).
+fn value_name(v: impl ValueEnum) -> String {
+    v.to_possible_value()
+        .map_or_else(String::new, |p| p.get_name().to_owned())
+}
+
+/// Flags of `bench`.
+#[derive(Args, Debug)]
+pub(crate) struct BenchArgs {
+    /// Workload size.
+    #[arg(long, value_enum, default_value_t = BenchPreset::Small)]
+    pub preset: BenchPreset,
+
+    /// Proof backend.
+    #[arg(value_enum, long)]
+    pub backend: BackendOpt,
+}
+
+/// Run the pipeline for `--preset` on `--backend` and print the stage table.
+pub(crate) fn run_bench(a: BenchArgs) -> Result<()> {
+    let BenchArgs { preset, backend } = a;
+    let (t, b, tau) = preset.shape();
+    let per_stage = reset_peak_rss();
+    let dir = scratch_dir()?;
+    let res = run_in(&dir, t, b, tau, backend);
+    let _ = fs::remove_dir_all(&dir);
+    let stages = res?;
+
+    println!(
+        "Bench: preset={} backend={} T={t} b={b} tau={tau} ({} blocks)",
+        value_name(preset),
+        value_name(backend),
+        t.div_ceil(u64::from(b))
+    );
+    let rss = if per_stage {
+        "peak RSS"
+    } else {
+        "process peak"
+    };
+    println!("  {:<10} {:>10} {:>12} {:>12}", "stage", "ms", "bytes", rss);
+    let fmt_opt = |v: Option<u64>| v.map_or_else(|| "-".to_string(), human_bytes);
+    for s in &stages {
+        println!(
+            "  {:<10} {:>10.1} {:>12} {:>12}",
+            s.name,
+            s.time.as_secs_f64() * 1e3,
+            fmt_opt(s.bytes),
+            s.peak_rss.map_or_else(|| "n/a".to_string(), human_bytes)
+        );
+    }
+    let total: Duration = stages.iter().map(|s| s.time).sum();
+    println!("  {:<10} {:>10.1}", "total", total.as_secs_f64() * 1e3);
+    let secs = total.as_secs_f64();
+    if secs > 0.0 {
+        #[allow(clippy::cast_precision_loss)]
+        let rate = t as f64 / secs;
+        println!("Throughput: {rate:.0} steps/s end to end");
+    }
+    Ok(())
+}
+
+/// The stages proper, writing every file under `dir`.
+fn run_in(dir: &Path, t: u64, b: u32, tau: u8, backend: BackendOpt) -> Result<Vec<Stage>> {
+    let blocks_path = dir.join("blocks.jsonl");
+    let manifest_path = dir.join("manifest.cbor");
+    let proof_path = dir.join("proof.cbor");
+    let mut stages = Vec::new();
+
+    let tf = timed(
+        &mut stages,
+        "generate",
+        || Ok(generate_trace(t, tau)),
+        |_| None,
+    )?;
+    let blocks = timed(
+        &mut stages,
+        "partition",
+        || {
+            let blocks = partition_trace(&tf, b);
+            write_block_summaries_auto(&blocks_path, &blocks)?;
+            Ok(blocks)
+        },
+        |_| file_len(&blocks_path),
+    )?;
+    drop(tf);
+    let manifest = timed(
+        &mut stages,
+        "commit",
+        || {
+            let m = commit_blocks(&blocks);
+            write_manifest_auto(&manifest_path, &m)?;
+            Ok(m)
+        },
+        |_| file_len(&manifest_path),
+    )?;
+
+    match backend {
+        BackendOpt::Stark => {
+            prove_and_verify::<StarkV1>(&mut stages, &blocks, manifest.root, &proof_path)?;
+        }
+        BackendOpt::Fold => {
+            prove_and_verify::<FoldAgg>(&mut stages, &blocks, manifest.root, &proof_path)?;
+        }
+    }
+    Ok(stages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Cli, Cmd};
+    use clap::Parser;
+
+    #[test]
+    fn parse_bench() {
+        let cli = Cli::parse_from(["sezkp-cli", "bench", "--backend", "stark"]);
+        let Cmd::Bench(BenchArgs { preset, backend }) = cli.cmd else {
+            panic!("expected bench");
+        };
+        assert_eq!((preset, backend), (BenchPreset::Small, BackendOpt::Stark));
+
+        let cli = Cli::parse_from([
+            "sezkp-cli",
+            "bench",
+            "--preset",
+            "large",
+            "--backend",
+            "fold",
+        ]);
+        let Cmd::Bench(BenchArgs { preset, .. }) = cli.cmd else {
+            panic!("expected bench");
+        };
+        assert_eq!(preset, BenchPreset::Large);
+    }
+}
@@ -0,0 +1,552 @@
+//! Block-file commands: `simulate`, `export-jsonl`, `sort-blocks`,
+//! `repair-windows` and `sample-blocks`.
+//!
+//! Each reads or generates σ_k block summaries and writes a new blocks file;
+//! the rewriting commands always emit JSON Lines, terminated by the
+//! `{"sezkp_eof":N}` marker and, unless `--index-stride 0`, with a `.idx`
+//! seek index beside it.
+
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use sezkp_core::compress::{self, FileSink};
+use sezkp_core::io::stream_block_summaries_auto;
+use sezkp_core::io_jsonl::{
+    jsonl_eof_line, jsonl_index_path, write_jsonl_index, write_jsonl_index_for, JsonlIndexBuilder,
+    DEFAULT_INDEX_STRIDE,
+};
+use sezkp_core::ordering::{sort_block_file, DEFAULT_SORT_RUN_BLOCKS};
+use sezkp_core::redact::{read_redaction_key, redact_block};
+use sezkp_core::sample::{BlockSampler, SampleMode};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use tracing::{info, info_span, warn};
+
+use crate::ensure_parent_dir;
+
+/// Flags of `simulate`.
+#[derive(Args, Debug)]
+pub(crate) struct SimulateArgs {
+    /// Trace length T (> 0).
+    #[arg(long, default_value_t = 32, value_parser = clap::value_parser!(u32).range(1..))]
+    pub t: u32,
+
+    /// Block size b: steps per σ_k block (> 0). `b >= T` yields a single block.
+    #[arg(long, default_value_t = 4, value_parser = clap::value_parser!(u32).range(1..))]
+    pub b: u32,
+
+    /// Number of work tapes τ (> 0). Tapes past the eighth mix sweeping,
+    /// scratch, read-only and idle behaviour; above 64 the blocks exceed
+    /// the default `--max-tau` read limit.
+    #[arg(long, default_value_t = 2, value_parser = clap::value_parser!(u8).range(1..))]
+    pub tau: u8,
+
+    /// Output path for σ_k block summaries (CBOR/JSON/JSONL/NDJSON).
+    #[arg(long, default_value = "blocks.cbor")]
+    pub out_blocks: PathBuf,
+
+    /// Also write the printed block statistics as JSON to this path.
+    #[arg(long)]
+    pub stats_json: Option<PathBuf>,
+}
+
+/// Flags of `export-jsonl`.
+#[derive(Args, Debug)]
+pub(crate) struct ExportJsonlArgs {
+    /// Input blocks path (CBOR/JSON/JSONL/NDJSON).
+    #[arg(long)]
+    pub input: PathBuf,
+    /// Output JSONL path.
+    #[arg(long)]
+    pub output: PathBuf,
+
+    /// Write a `<output>.idx` seek index with one entry every this many
+    /// lines (0 = no index).
+    #[arg(long, default_value_t = DEFAULT_INDEX_STRIDE)]
+    pub index_stride: u64,
+
+    /// Redact written symbols, committing to each under a salt derived
+    /// from this key file (64 hex chars). Keep the key private: it opens
+    /// the commitments.
+    #[arg(long)]
+    pub redact_key: Option<PathBuf>,
+}
+
+/// Flags of `sort-blocks`.
+#[derive(Args, Debug)]
+pub(crate) struct SortBlocksArgs {
+    /// Input blocks path (CBOR/JSON/JSONL/NDJSON).
+    #[arg(long)]
+    pub input: PathBuf,
+    /// Output JSONL path.
+    #[arg(long)]
+    pub output: PathBuf,
+
+    /// Blocks sorted in memory per run before spilling to disk.
+    #[arg(long, default_value_t = DEFAULT_SORT_RUN_BLOCKS)]
+    pub run_blocks: usize,
+
+    /// Write a `<output>.idx` seek index with one entry every this many
+    /// lines (0 = no index).
+    #[arg(long, default_value_t = DEFAULT_INDEX_STRIDE)]
+    pub index_stride: u64,
+}
+
+/// Flags of `repair-windows`.
+#[derive(Args, Debug)]
+pub(crate) struct RepairWindowsArgs {
+    /// Input blocks path (CBOR/JSON/JSONL/NDJSON).
+    #[arg(long)]
+    pub input: PathBuf,
+    /// Output JSONL path.
+    #[arg(long)]
+    pub output: PathBuf,
+
+    /// Write a `<output>.idx` seek index with one entry every this many
+    /// lines (0 = no index).
+    #[arg(long, default_value_t = DEFAULT_INDEX_STRIDE)]
+    pub index_stride: u64,
+}
+
+/// Flags of `sample-blocks`.
+#[derive(Args, Debug)]
+pub(crate) struct SampleBlocksArgs {
+    /// Input blocks path (CBOR/JSON/JSONL/NDJSON).
+    #[arg(long)]
+    pub blocks: PathBuf,
+    /// Output JSONL path.
+    #[arg(long)]
+    pub out: PathBuf,
+    /// Keep blocks `0, n, 2n, …`.
+    #[arg(
+        long,
+        value_name = "N",
+        required_unless_present = "prefix",
+        conflicts_with = "prefix"
+    )]
+    pub every: Option<u64>,
+    /// Keep the first `n` blocks.
+    #[arg(long, value_name = "N")]
+    pub prefix: Option<u64>,
+
+    /// Write a `<out>.idx` seek index with one entry every this many
+    /// lines (0 = no index).
+    #[arg(long, default_value_t = DEFAULT_INDEX_STRIDE)]
+    pub index_stride: u64,
+}
+
+/// Return `true` if the path’s extension suggests JSON Lines (`.jsonl` or `.ndjson`).
+fn is_jsonl_like(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|s| s.to_ascii_lowercase())
+        .is_some_and(|ext| ext == "jsonl" || ext == "ndjson")
+}
+
+/// Generate a synthetic trace, partition it and write the blocks.
+pub(crate) fn simulate(a: SimulateArgs) -> Result<()> {
+    let SimulateArgs {
+        t,
+        b,
+        tau,
+        out_blocks,
+        stats_json,
+    } = a;
+    let _span = info_span!("simulate", t, b, tau, out = %out_blocks.display()).entered();
+    use sezkp_trace::{generator::generate_trace, partition::partition_trace};
+
+    if b > t {
+        info!("block size b ({b}) exceeds trace length T ({t}); emitting a single block");
+    }
+
+    info!("generating synthetic trace");
+    let trace = generate_trace(t as u64, tau);
+    let blocks = partition_trace(&trace, b);
+
+    ensure_parent_dir(&out_blocks)?;
+
+    // If the extension is .jsonl/.ndjson, write NDJSON for streaming.
+    if is_jsonl_like(&out_blocks) {
+        let f = File::create(&out_blocks)
+            .with_context(|| format!("create {}", out_blocks.display()))?;
+        let mut w = BufWriter::new(f);
+        for blk in &blocks {
+            serde_json::to_writer(&mut w, blk).context("serialize block as JSON line")?;
+            w.write_all(b"\n")?;
+        }
+        w.write_all(jsonl_eof_line(blocks.len() as u64).as_bytes())?;
+        w.flush()?;
+    } else {
+        sezkp_core::io::write_block_summaries_auto(&out_blocks, &blocks).with_context(|| {
+            format!(
+                "writing σ_k blocks (auto format) to {}",
+                out_blocks.display()
+            )
+        })?;
+    }
+
+    println!(
+        "Simulated trace: T={}, b={}, τ={} → {} blocks → {}",
+        t,
+        b,
+        tau,
+        blocks.len(),
+        out_blocks.display()
+    );
+
+    let stats = sezkp_trace::stats::block_stats(&blocks);
+    print!("{stats}");
+    if let Some(path) = &stats_json {
+        ensure_parent_dir(path)?;
+        let json = serde_json::to_string_pretty(&stats).context("serialize block statistics")?;
+        std::fs::write(path, json + "\n").with_context(|| format!("write {}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// Convert any blocks file (CBOR/JSON/JSONL/NDJSON) into JSON Lines for streaming proofs.
+///
+/// With `index_stride > 0`, line offsets are recorded while writing and saved
+/// as the `<output>.idx` sidecar. With `redact_key`, every block is redacted
+/// (see [`sezkp_core::redact`]) on the way out.
+///
+/// # Errors
+/// Propagates I/O and serialization errors.
+pub(crate) fn export_jsonl(a: ExportJsonlArgs) -> Result<()> {
+    let ExportJsonlArgs {
+        input,
+        output,
+        index_stride,
+        redact_key,
+    } = a;
+    let _span = info_span!("export_jsonl", infile = %input.display(), outfile = %output.display())
+        .entered();
+    let redact_key = redact_key.as_deref().map(read_redaction_key).transpose()?;
+    info!("opening input stream");
+    let iter = stream_block_summaries_auto(&input).context("open input stream")?;
+
+    if sezkp_core::io_format::strict_io() && !is_jsonl_like(&output) {
+        bail!(
+            "strict I/O: export-jsonl output {} must end in .jsonl or .ndjson",
+            output.display()
+        );
+    }
+    ensure_parent_dir(&output)?;
+    let mut w = FileSink::create(&output)?;
+
+    // Compressed outputs cannot be seeked into, so they get no index.
+    let mut index = (index_stride > 0 && !compress::is_zst_path(&output))
+        .then(|| JsonlIndexBuilder::new(index_stride));
+    let mut line = Vec::with_capacity(8 << 10);
+    let mut n = 0usize;
+    for item in iter {
+        let mut blk = item?;
+        if let Some(key) = &redact_key {
+            blk = redact_block(&blk, key)?;
+        }
+        line.clear();
+        serde_json::to_writer(&mut line, &blk).context("serialize block as JSON line")?;
+        line.push(b'\n');
+        w.write_all(&line)?;
+        if let Some(ix) = index.as_mut() {
+            ix.push_line(line.len() as u64);
+        }
+        n += 1;
+    }
+    let eof = jsonl_eof_line(n as u64);
+    w.write_all(eof.as_bytes())?;
+    if let Some(ix) = index.as_mut() {
+        ix.push_line(eof.len() as u64);
+    }
+    w.finish()?;
+
+    let redacted = if redact_key.is_some() {
+        " (redacted)"
+    } else {
+        ""
+    };
+    println!("Exported {n} blocks{redacted} → {}", output.display());
+    if let Some(ix) = index {
+        write_jsonl_index(&output, &ix.finish())?;
+        println!(
+            "Indexed every {index_stride} lines → {}",
+            jsonl_index_path(&output).display()
+        );
+    }
+    Ok(())
+}
+
+/// Sort a blocks file into canonical `block_id` order (JSONL).
+pub(crate) fn sort_blocks(a: SortBlocksArgs) -> Result<()> {
+    let SortBlocksArgs {
+        input,
+        output,
+        run_blocks,
+        index_stride,
+    } = a;
+    let (input, output) = (input.as_path(), output.as_path());
+    let _span =
+        info_span!("sort_blocks", infile = %input.display(), outfile = %output.display()).entered();
+    if sezkp_core::io_format::strict_io() && !is_jsonl_like(output) {
+        bail!(
+            "strict I/O: sort-blocks output {} must end in .jsonl or .ndjson",
+            output.display()
+        );
+    }
+    ensure_parent_dir(output)?;
+    let rep = sort_block_file(input, output, run_blocks)?;
+
+    let order = if rep.was_sorted {
+        "already sorted"
+    } else {
+        "reordered"
+    };
+    println!(
+        "Sorted {} blocks ({order}, {} run(s)) → {}",
+        rep.blocks_out,
+        rep.runs,
+        output.display()
+    );
+    if rep.duplicates_dropped > 0 {
+        println!(
+            "Dropped {} exact duplicate block(s)",
+            rep.duplicates_dropped
+        );
+    }
+    if let Some(id) = rep.first_gap {
+        warn!(block_id = id, "block ids are not consecutive");
+        println!("warning: block_id {id} is missing; provers will reject this file");
+    }
+    if index_stride > 0 && !compress::is_zst_path(output) {
+        write_jsonl_index_for(output, index_stride)?;
+        println!(
+            "Indexed every {index_stride} lines → {}",
+            jsonl_index_path(output).display()
+        );
+    }
+    Ok(())
+}
+
+/// Rewrite a blocks file (JSONL) with minimal windows over the visited cells.
+pub(crate) fn repair_windows(a: RepairWindowsArgs) -> Result<()> {
+    let RepairWindowsArgs {
+        input,
+        output,
+        index_stride,
+    } = a;
+    let (input, output) = (input.as_path(), output.as_path());
+    let _span =
+        info_span!("repair_windows", infile = %input.display(), outfile = %output.display())
+            .entered();
+    if sezkp_core::io_format::strict_io() && !is_jsonl_like(output) {
+        bail!(
+            "strict I/O: repair-windows output {} must end in .jsonl or .ndjson",
+            output.display()
+        );
+    }
+    let iter = stream_block_summaries_auto(input).context("open input stream")?;
+    ensure_parent_dir(output)?;
+    let mut w = FileSink::create(output)?;
+
+    let (mut n, mut repaired) = (0usize, 0usize);
+    for item in iter {
+        let mut blk = item?;
+        if blk.repair_windows()? {
+            repaired += 1;
+        }
+        serde_json::to_writer(&mut w, &blk).context("serialize block as JSON line")?;
+        w.write_all(b"\n")?;
+        n += 1;
+    }
+    w.write_all(jsonl_eof_line(n as u64).as_bytes())?;
+    w.finish()?;
+
+    println!(
+        "Repaired windows of {repaired} of {n} blocks → {}",
+        output.display()
+    );
+    if index_stride > 0 && !compress::is_zst_path(output) {
+        write_jsonl_index_for(output, index_stride)?;
+        println!(
+            "Indexed every {index_stride} lines → {}",
+            jsonl_index_path(output).display()
+        );
+    }
+    Ok(())
+}
+
+/// Write the blocks `--every`/`--prefix` selects, re-chained, as JSONL.
+pub(crate) fn sample_blocks(a: SampleBlocksArgs) -> Result<()> {
+    let SampleBlocksArgs {
+        blocks: input,
+        out: output,
+        every,
+        prefix,
+        index_stride,
+    } = a;
+    let (input, output) = (input.as_path(), output.as_path());
+    let mode = match (every, prefix) {
+        (Some(n), _) => SampleMode::Every(n),
+        (None, Some(n)) => SampleMode::Prefix(n),
+        (None, None) => unreachable!("clap requires --every or --prefix"),
+    };
+    let _span = info_span!("sample_blocks", infile = %input.display(), outfile = %output.display())
+        .entered();
+    if sezkp_core::io_format::strict_io() && !is_jsonl_like(output) {
+        bail!(
+            "strict I/O: sample-blocks output {} must end in .jsonl or .ndjson",
+            output.display()
+        );
+    }
+    let mut sampler = BlockSampler::new(mode)?;
+    let iter = stream_block_summaries_auto(input).context("open input stream")?;
+    ensure_parent_dir(output)?;
+    let mut w = FileSink::create(output)?;
+
+    let mut n = 0usize;
+    for item in iter {
+        if sampler.is_done() {
+            break;
+        }
+        n += 1;
+        if let Some(blk) = sampler.push(item?)? {
+            serde_json::to_writer(&mut w, &blk).context("serialize block as JSON line")?;
+            w.write_all(b"\n")?;
+        }
+    }
+    w.write_all(jsonl_eof_line(u64::from(sampler.kept())).as_bytes())?;
+    w.finish()?;
+
+    println!(
+        "Sampled {} of {n} blocks read → {}",
+        sampler.kept(),
+        output.display()
+    );
+    if index_stride > 0 && !compress::is_zst_path(output) {
+        write_jsonl_index_for(output, index_stride)?;
+        println!(
+            "Indexed every {index_stride} lines → {}",
+            jsonl_index_path(output).display()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Cli, Cmd};
+    use clap::Parser;
+
+    #[test]
+    fn parse_simulate_stats_json() {
+        let cli = Cli::parse_from(["sezkp-cli", "simulate", "--stats-json", "stats.json"]);
+        assert!(matches!(
+            cli.cmd,
+            Cmd::Simulate(SimulateArgs { stats_json: Some(ref p), .. }) if p == Path::new("stats.json")
+        ));
+    }
+
+    #[test]
+    fn parse_export_jsonl_redact_key() {
+        let cli = Cli::parse_from([
+            "sezkp-cli",
+            "export-jsonl",
+            "--input",
+            "blocks.cbor",
+            "--output",
+            "public.jsonl",
+            "--redact-key",
+            "redact.key",
+        ]);
+        let Cmd::ExportJsonl(ExportJsonlArgs { redact_key, .. }) = cli.cmd else {
+            panic!("expected export-jsonl");
+        };
+        assert_eq!(redact_key, Some(PathBuf::from("redact.key")));
+    }
+
+    #[test]
+    fn parse_sort_blocks() {
+        let cli = Cli::parse_from([
+            "sezkp-cli",
+            "sort-blocks",
+            "--input",
+            "shuffled.jsonl",
+            "--output",
+            "blocks.jsonl",
+            "--run-blocks",
+            "64",
+        ]);
+        let Cmd::SortBlocks(SortBlocksArgs {
+            run_blocks,
+            index_stride,
+            ..
+        }) = cli.cmd
+        else {
+            panic!("expected sort-blocks");
+        };
+        assert_eq!(run_blocks, 64);
+        assert_eq!(index_stride, DEFAULT_INDEX_STRIDE);
+    }
+
+    #[test]
+    fn parse_repair_windows() {
+        let cli = Cli::parse_from([
+            "sezkp-cli",
+            "repair-windows",
+            "--input",
+            "adapter.jsonl",
+            "--output",
+            "blocks.jsonl",
+        ]);
+        let Cmd::RepairWindows(RepairWindowsArgs {
+            input,
+            index_stride,
+            ..
+        }) = cli.cmd
+        else {
+            panic!("expected repair-windows");
+        };
+        assert_eq!(input, PathBuf::from("adapter.jsonl"));
+        assert_eq!(index_stride, DEFAULT_INDEX_STRIDE);
+    }
+
+    #[test]
+    fn parse_sample_blocks() {
+        let cli = Cli::parse_from([
+            "sezkp-cli",
+            "sample-blocks",
+            "--blocks",
+            "big.jsonl",
+            "--every",
+            "100",
+            "--out",
+            "small.jsonl",
+        ]);
+        let Cmd::SampleBlocks(SampleBlocksArgs { every, prefix, .. }) = cli.cmd else {
+            panic!("expected sample-blocks");
+        };
+        assert_eq!((every, prefix), (Some(100), None));
+
+        let base = [
+            "sezkp-cli",
+            "sample-blocks",
+            "--blocks",
+            "b.jsonl",
+            "--out",
+            "s.jsonl",
+        ];
+        assert!(Cli::try_parse_from(base).is_err());
+        let both = [&base[..], &["--every", "2", "--prefix", "8"]].concat();
+        assert!(Cli::try_parse_from(both).is_err());
+    }
+
+    #[test]
+    fn jsonl_like_detection() {
+        assert!(is_jsonl_like(Path::new("x.jsonl")));
+        assert!(is_jsonl_like(Path::new("x.ndjson")));
+        assert!(!is_jsonl_like(Path::new("x.json")));
+        assert!(!is_jsonl_like(Path::new("x.cbor")));
+        assert!(!is_jsonl_like(Path::new("x")));
+    }
+}
@@ -0,0 +1,395 @@
+//! Commitments: `commit`, `verify-commit`, `inclusion-proof` and
+//! `verify-inclusion`.
+//!
+//! `commit` streams a blocks file (or a trace, cut into blocks on the fly)
+//! into a Merkle manifest; the other commands check a blocks file, or a
+//! single block's portable inclusion proof, against one.
+
+use anyhow::{ensure, Context, Result};
+use clap::Args;
+use sezkp_core::compress;
+use sezkp_core::io::block_file_format;
+use sezkp_core::io_format::FileFormat;
+use sezkp_core::io_jsonl::{jsonl_index_path, write_jsonl_index_for, DEFAULT_INDEX_STRIDE};
+use sezkp_core::render::{parse_root, render_root};
+use std::path::PathBuf;
+use tracing::{info, info_span};
+
+use crate::args::{BlockInput, TraceArgs};
+use crate::ensure_parent_dir;
+use crate::show::block_at;
+
+/// Flags of `commit`.
+#[derive(Args, Debug)]
+pub(crate) struct CommitArgs {
+    /// Input path to σ_k block summaries (CBOR/JSON/JSONL/NDJSON).
+    #[arg(long, required_unless_present = "trace")]
+    pub blocks: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub trace: TraceArgs,
+
+    /// Output path for the manifest (CBOR/JSON).
+    #[arg(long, default_value = "manifest.cbor")]
+    pub out: PathBuf,
+
+    /// Free-form note recorded in the manifest's creator metadata.
+    #[arg(long)]
+    pub note: Option<String>,
+
+    /// For JSONL blocks, also write a `<blocks>.idx` seek index with one
+    /// entry every this many lines (0 = no index).
+    #[arg(long, default_value_t = DEFAULT_INDEX_STRIDE)]
+    pub index_stride: u64,
+
+    /// Declare the input tape length in the manifest (overriding one a
+    /// trace declares); proving then keeps the input head in `[0, len)`.
+    #[arg(long)]
+    pub input_len: Option<u64>,
+
+    /// Also record the root of every aligned run of 2^K blocks, so
+    /// `verify-commit` can name the first run that diverges.
+    #[arg(long, value_name = "K")]
+    pub checkpoint_log2: Option<u32>,
+}
+
+/// Flags of `verify-commit`.
+#[derive(Args, Debug)]
+pub(crate) struct VerifyCommitArgs {
+    /// Input path to σ_k block summaries (CBOR/JSON/JSONL/NDJSON).
+    #[arg(long)]
+    pub blocks: PathBuf,
+
+    /// Input path to manifest (CBOR/JSON).
+    #[arg(long)]
+    pub manifest: PathBuf,
+
+    /// Also require the manifest to commit to exactly this root. Accepts
+    /// any `--root-format` rendering; a wrong length, stray character or
+    /// checksum mismatch is an error, never silently truncated.
+    #[arg(long, value_parser = parse_root)]
+    pub manifest_root: Option<[u8; 32]>,
+}
+
+/// Flags of `inclusion-proof`.
+#[derive(Args, Debug)]
+pub(crate) struct InclusionProofArgs {
+    /// Input path to σ_k block summaries (CBOR/JSON/JSONL/NDJSON).
+    #[arg(long)]
+    pub blocks: PathBuf,
+
+    /// Input path to manifest (CBOR/JSON) the blocks must match.
+    #[arg(long)]
+    pub manifest: PathBuf,
+
+    /// 0-based block position in the file.
+    #[arg(long)]
+    pub index: usize,
+
+    /// Output path (JSON/CBOR).
+    #[arg(long, default_value = "inclusion.json")]
+    pub out: PathBuf,
+}
+
+/// Flags of `verify-inclusion`.
+#[derive(Args, Debug)]
+pub(crate) struct VerifyInclusionArgs {
+    /// Input path to the inclusion proof (JSON/CBOR).
+    #[arg(long)]
+    pub proof: PathBuf,
+
+    /// Input path to manifest (CBOR/JSON).
+    #[arg(long)]
+    pub manifest: PathBuf,
+
+    /// Blocks file whose block at the proof's index must hash to the
+    /// proven leaf.
+    #[arg(long)]
+    pub blocks: Option<PathBuf>,
+}
+
+/// Commit a blocks file (or a trace, partitioned on the fly) and write its
+/// manifest.
+pub(crate) fn commit_blocks(a: CommitArgs) -> Result<()> {
+    let CommitArgs {
+        blocks,
+        trace,
+        out,
+        note,
+        index_stride,
+        input_len,
+        checkpoint_log2,
+    } = a;
+    let blocks = trace.input(blocks)?;
+    let _span = info_span!("commit", blocks = %blocks, out = %out.display()).entered();
+    use sezkp_merkle::{
+        commit_block_file_with, commit_trace_file_with, write_manifest_auto, CommitManifest,
+        CommitOptions, CreatorInfo,
+    };
+
+    // The declared length is not bound by the root; record it afterwards.
+    let declare = |man: CommitManifest| -> Result<()> {
+        if let Some(n) = input_len {
+            write_manifest_auto(&out, &man.with_input_len(n))
+                .with_context(|| format!("writing manifest {}", out.display()))?;
+            println!("Declared input length {n}");
+        }
+        Ok(())
+    };
+
+    info!("committing blocks");
+    ensure_parent_dir(&out)?;
+
+    let opts = CommitOptions {
+        creator: Some(CreatorInfo {
+            tool: env!("CARGO_PKG_NAME").to_owned(),
+            tool_version: env!("CARGO_PKG_VERSION").to_owned(),
+            note,
+        }),
+        checkpoint_log2,
+    };
+    let blocks = match blocks {
+        BlockInput::File(path) => path,
+        BlockInput::Trace { path, b, strategy } => {
+            let man =
+                commit_trace_file_with(&path, b, strategy, &out, &opts).with_context(|| {
+                    format!(
+                        "committing {} to manifest {}",
+                        path.display(),
+                        out.display()
+                    )
+                })?;
+            println!("Committed {} → {}", path.display(), out.display());
+            return declare(man);
+        }
+    };
+    let man = commit_block_file_with(&blocks, &out, &opts).with_context(|| {
+        format!(
+            "committing {} to manifest {}",
+            blocks.display(),
+            out.display()
+        )
+    })?;
+
+    println!("Committed {} → {}", blocks.display(), out.display());
+    declare(man)?;
+
+    if index_stride > 0
+        && block_file_format(&blocks)? == FileFormat::Jsonl
+        && !compress::is_compressed(&blocks)?
+    {
+        let index = write_jsonl_index_for(&blocks, index_stride)
+            .with_context(|| format!("indexing {}", blocks.display()))?;
+        println!(
+            "Indexed {} lines (every {index_stride}) → {}",
+            index.lines,
+            jsonl_index_path(&blocks).display()
+        );
+    }
+    Ok(())
+}
+
+/// Check that `blocks` reproduces `manifest` (and, given one, its root).
+pub(crate) fn verify_commit(a: VerifyCommitArgs) -> Result<()> {
+    let VerifyCommitArgs {
+        blocks,
+        manifest,
+        manifest_root,
+    } = a;
+    let _span =
+        info_span!("verify_commit", blocks = %blocks.display(), manifest = %manifest.display())
+            .entered();
+    use sezkp_merkle::{read_manifest_auto, verify_block_file_against_manifest};
+
+    if let Some(want) = manifest_root {
+        let man = read_manifest_auto(&manifest).context("reading manifest")?;
+        ensure!(
+            man.root == want,
+            "manifest {} commits to root {}, expected {}",
+            manifest.display(),
+            render_root(&man.root),
+            render_root(&want)
+        );
+    }
+
+    info!("verifying commit");
+    verify_block_file_against_manifest(&blocks, &manifest).with_context(|| {
+        format!(
+            "verifying that {} matches manifest {}",
+            blocks.display(),
+            manifest.display()
+        )
+    })?;
+
+    println!(
+        "OK: {} matches manifest {}",
+        blocks.display(),
+        manifest.display()
+    );
+    Ok(())
+}
+
+/// Write the portable inclusion proof of block `index` of `blocks`, after
+/// checking that the blocks reproduce `manifest`.
+///
+/// The blocks are streamed once (see [`sezkp_merkle::prove_leaf`]); the
+/// path leading to the manifest root is that check.
+pub(crate) fn export_inclusion_proof(a: InclusionProofArgs) -> Result<()> {
+    use sezkp_merkle::{portable, prove_leaf, read_manifest_auto};
+
+    let InclusionProofArgs {
+        blocks,
+        manifest,
+        index,
+        out,
+    } = a;
+    let _span = info_span!("inclusion_proof", blocks = %blocks.display(), index).entered();
+    let man = read_manifest_auto(&manifest).context("reading manifest")?;
+    let leaf = prove_leaf(&blocks, index as u64)?;
+    ensure!(
+        leaf.path.n_leaves == u64::from(man.n_leaves) && leaf.root() == Some(man.root),
+        "{} does not match manifest {} (run verify-commit for details)",
+        blocks.display(),
+        manifest.display()
+    );
+    let proof = leaf.path.to_portable(&leaf.leaf)?;
+    portable::write_portable_auto(&out, &proof)?;
+    println!(
+        "Wrote inclusion proof of block {index} of {} ({} steps) → {}",
+        man.n_leaves,
+        proof.path.len(),
+        out.display()
+    );
+    println!("Root: {}", render_root(&man.root));
+    Ok(())
+}
+
+/// Check a portable inclusion proof against `manifest` and, given
+/// `blocks`, against the block at its index.
+pub(crate) fn verify_inclusion(a: VerifyInclusionArgs) -> Result<()> {
+    use sezkp_merkle::{leaf_hash, portable, read_manifest_auto};
+
+    let VerifyInclusionArgs {
+        proof,
+        manifest,
+        blocks,
+    } = a;
+    let _span = info_span!("verify_inclusion", proof = %proof.display()).entered();
+    let man = read_manifest_auto(&manifest).context("reading manifest")?;
+    let p = portable::read_portable_auto(&proof)?;
+    p.verify(&man.root)?;
+    ensure!(
+        p.n_leaves == u64::from(man.n_leaves),
+        "inclusion proof is for a tree of {} leaves; the manifest has {}",
+        p.n_leaves,
+        man.n_leaves
+    );
+    p.to_inclusion_proof()?;
+    if let Some(blocks) = &blocks {
+        let index = usize::try_from(p.index)?;
+        let got = leaf_hash(&block_at(blocks, index)?);
+        ensure!(
+            got == p.leaf,
+            "block {index} of {} hashes to {}, not the proven leaf {}",
+            blocks.display(),
+            render_root(&got),
+            render_root(&p.leaf)
+        );
+    }
+    println!(
+        "OK: leaf {} of {} is included under root {}",
+        p.index,
+        p.n_leaves,
+        render_root(&man.root)
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Cli, Cmd};
+    use clap::Parser;
+
+    #[test]
+    fn parse_commit_smoke() {
+        // Ensure subcommand/args parse; do not run anything.
+        let _ = Cli::parse_from([
+            "sezkp-cli",
+            "commit",
+            "--blocks",
+            "blocks.cbor",
+            "--out",
+            "manifest.cbor",
+        ]);
+    }
+
+    #[test]
+    fn parse_commit_input_len() {
+        let cli = Cli::parse_from([
+            "sezkp-cli",
+            "commit",
+            "--blocks",
+            "blocks.cbor",
+            "--input-len",
+            "4096",
+        ]);
+        assert!(matches!(
+            cli.cmd,
+            Cmd::Commit(CommitArgs {
+                input_len: Some(4096),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn parse_commit_checkpoints() {
+        let cli = Cli::parse_from([
+            "sezkp-cli",
+            "commit",
+            "--blocks",
+            "blocks.jsonl",
+            "--checkpoint-log2",
+            "10",
+        ]);
+        assert!(matches!(
+            cli.cmd,
+            Cmd::Commit(CommitArgs {
+                checkpoint_log2: Some(10),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn parse_inclusion() {
+        let cli = Cli::parse_from([
+            "sezkp-cli",
+            "inclusion-proof",
+            "--blocks",
+            "b.jsonl",
+            "--manifest",
+            "m.json",
+            "--index",
+            "7",
+        ]);
+        let Cmd::InclusionProof(InclusionProofArgs { index, out, .. }) = cli.cmd else {
+            panic!("expected inclusion-proof");
+        };
+        assert_eq!((index, out), (7, PathBuf::from("inclusion.json")));
+
+        let cli = Cli::parse_from([
+            "sezkp-cli",
+            "verify-inclusion",
+            "--proof",
+            "p.cbor",
+            "--manifest",
+            "m.json",
+        ]);
+        assert!(matches!(
+            cli.cmd,
+            Cmd::VerifyInclusion(VerifyInclusionArgs { blocks: None, .. })
+        ));
+    }
+}
@@ -0,0 +1,316 @@
+//! `compose` and `verify-composite`: per-shard proofs under one binding.
+//!
+//! A composite lists its children by path (relative to the composite file)
+//! or embedded; with `--artifact` the embedded form is written as an
+//! ordinary proof artifact instead (see `sezkp_core::composite`), which
+//! `verify-composite` accepts in place of a composite file.
+//!
+//! Children are paired with the `--blocks`/`--manifest` lists by position and
+//! verified one by one. Without `--backend`, each child is verified with the
+//! backend that made it, so fold and STARK shards may mix.
+
+use anyhow::{bail, ensure, Context, Result};
+use clap::Args;
+use sezkp_core::composite::{
+    composite_base_dir, is_composite_artifact, read_composite_auto, write_composite_auto, ChildRef,
+    ChildSource, CompositeArtifact,
+};
+use sezkp_core::io::{read_proof_auto, write_proof_auto};
+use sezkp_core::render::render_root;
+use sezkp_core::ProofArtifact;
+use sezkp_merkle::{read_manifest_auto, verify_block_file_against_manifest};
+use std::path::{Path, PathBuf};
+use tracing::{info, info_span};
+
+use crate::args::{BackendOpt, BlockInput, FoldExpectArgs, ReplayFlags};
+use crate::ensure_parent_dir;
+use crate::verify::{verify_artifact, verify_backend};
+
+/// Flags of `compose`.
+#[derive(Args, Debug)]
+pub(crate) struct ComposeArgs {
+    /// Child proof artifact (CBOR/JSON); repeat once per shard, in order.
+    #[arg(long = "child", required = true)]
+    pub children: Vec<PathBuf>,
+
+    /// Output path for the composite (CBOR/JSON).
+    #[arg(long, default_value = "composite.cbor")]
+    pub out: PathBuf,
+
+    /// Embed the children instead of referencing them by path.
+    #[arg(long, default_value_t = false)]
+    pub embed: bool,
+
+    /// Write a proof artifact embedding the children, which can be
+    /// stored, timestamped and shipped like any single proof (implies
+    /// `--embed`).
+    #[arg(long, default_value_t = false)]
+    pub artifact: bool,
+}
+
+/// Flags of `verify-composite`.
+#[derive(Args, Debug)]
+pub(crate) struct VerifyCompositeArgs {
+    /// Proof backend every child must come from (default: each child's own).
+    #[arg(value_enum, long)]
+    pub backend: Option<BackendOpt>,
+
+    /// Input path to the composite, or to a proof artifact wrapping one
+    /// (CBOR/JSON).
+    #[arg(long)]
+    pub composite: PathBuf,
+
+    /// Blocks file of each shard, in child order.
+    #[arg(long, required = true)]
+    pub blocks: Vec<PathBuf>,
+
+    /// Manifest of each shard, in child order.
+    #[arg(long, required = true)]
+    pub manifest: Vec<PathBuf>,
+
+    /// Assume every blocks file has already been verified against its manifest.
+    #[arg(long, default_value_t = false)]
+    pub assume_committed: bool,
+
+    #[command(flatten)]
+    pub expect: FoldExpectArgs,
+}
+
+/// Bundle `--child` proofs into a composite written to `--out`, or, with
+/// `--artifact`, into a proof artifact embedding them.
+///
+/// Referenced children are stored relative to the composite's directory when
+/// they live below it, and as absolute paths otherwise.
+pub(crate) fn compose(a: ComposeArgs) -> Result<()> {
+    let ComposeArgs {
+        children,
+        out,
+        embed,
+        artifact,
+    } = a;
+    let _span = info_span!("compose", n = children.len(), out = %out.display()).entered();
+
+    ensure_parent_dir(&out)?;
+    if artifact {
+        let artifacts = children
+            .iter()
+            .map(|p| {
+                read_proof_auto(p)
+                    .with_context(|| format!("reading child artifact {}", p.display()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let art = CompositeArtifact::embed(&artifacts)?.to_artifact()?;
+        write_proof_auto(&out, &art)
+            .with_context(|| format!("writing composite artifact to {}", out.display()))?;
+        println!(
+            "Composed {} children → {} (proof artifact, binding {})",
+            artifacts.len(),
+            out.display(),
+            render_root(&art.manifest_root)
+        );
+        return Ok(());
+    }
+
+    let base = composite_base_dir(&out);
+    let base = if base.as_os_str().is_empty() {
+        std::env::current_dir()?
+    } else {
+        base.canonicalize()
+            .with_context(|| format!("resolving {}", base.display()))?
+    };
+
+    let mut refs = Vec::with_capacity(children.len());
+    for path in &children {
+        let artifact = read_proof_auto(path)
+            .with_context(|| format!("reading child artifact {}", path.display()))?;
+        let source = if embed {
+            ensure!(
+                artifact.meta.get("streaming") != Some(&serde_json::Value::Bool(true)),
+                "{} is a streamed fold proof whose proof lives in its .cborseq sidecar; \
+                 reference it by path instead of --embed",
+                path.display()
+            );
+            ChildSource::Embedded(Box::new(artifact.clone()))
+        } else {
+            let abs = path
+                .canonicalize()
+                .with_context(|| format!("resolving {}", path.display()))?;
+            let rel = abs.strip_prefix(&base).unwrap_or(&abs);
+            ChildSource::Path(
+                rel.to_str()
+                    .with_context(|| format!("{} is not valid UTF-8", rel.display()))?
+                    .to_owned(),
+            )
+        };
+        refs.push(ChildRef::new(&artifact, source));
+    }
+
+    let composite = CompositeArtifact::new(refs);
+    write_composite_auto(&out, &composite)
+        .with_context(|| format!("writing composite to {}", out.display()))?;
+
+    println!(
+        "Composed {} children → {} (binding {})",
+        composite.children.len(),
+        out.display(),
+        render_root(&composite.binding)
+    );
+    Ok(())
+}
+
+/// Verify a composite: binding, child digests, and each child proof against
+/// its shard's blocks and manifest (paired with the children by position).
+pub(crate) fn verify_composite(a: VerifyCompositeArgs, flags: ReplayFlags) -> Result<()> {
+    let VerifyCompositeArgs {
+        backend,
+        composite,
+        blocks,
+        manifest: manifests,
+        assume_committed,
+        expect,
+    } = a;
+    // Without --backend, expectations bind the fold children.
+    expect.apply(backend.unwrap_or(BackendOpt::Fold));
+    let _span =
+        info_span!("verify_composite", ?backend, composite = %composite.display()).entered();
+
+    let c = read_composite(&composite)
+        .with_context(|| format!("reading composite {}", composite.display()))?;
+    ensure!(
+        blocks.len() == c.children.len() && manifests.len() == c.children.len(),
+        "composite has {} children but {} --blocks and {} --manifest were given",
+        c.children.len(),
+        blocks.len(),
+        manifests.len()
+    );
+
+    c.verify_with(&composite_base_dir(&composite), |i, artifact| {
+        verify_shard(
+            verify_backend(backend, artifact)?,
+            i,
+            artifact,
+            &blocks[i],
+            &manifests[i],
+            assume_committed,
+            flags,
+        )
+    })?;
+
+    println!(
+        "OK: composite verified ({} children, binding {})",
+        c.children.len(),
+        render_root(&c.binding)
+    );
+    Ok(())
+}
+
+/// Read a composite file, or a proof artifact wrapping one.
+fn read_composite(path: &Path) -> Result<CompositeArtifact> {
+    match read_proof_auto(path) {
+        Ok(a) if is_composite_artifact(&a) => CompositeArtifact::from_artifact(&a),
+        Ok(a) => bail!(
+            "this is a proof from the {} backend, not a composite; use verify",
+            a.backend
+        ),
+        Err(_) => read_composite_auto(path),
+    }
+}
+
+/// Verify child `i` of a composite against its shard's blocks and manifest.
+fn verify_shard(
+    backend: BackendOpt,
+    i: usize,
+    artifact: &ProofArtifact,
+    blocks: &Path,
+    manifest: &Path,
+    assume_committed: bool,
+    flags: ReplayFlags,
+) -> Result<()> {
+    if !assume_committed {
+        verify_block_file_against_manifest(blocks, manifest).context("blocks/manifest mismatch")?;
+    }
+    let man = read_manifest_auto(manifest).context("reading manifest")?;
+    ensure!(
+        man.root == artifact.manifest_root,
+        "manifest {} commits to root {}, but the child is bound to {}",
+        manifest.display(),
+        render_root(&man.root),
+        render_root(&artifact.manifest_root)
+    );
+    verify_artifact(
+        backend,
+        artifact,
+        &BlockInput::File(blocks.to_path_buf()),
+        &man,
+        None,
+        flags,
+    )?;
+    info!(child = i, "child verified");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Cli, Cmd};
+    use clap::Parser;
+
+    #[test]
+    fn parse_compose_and_verify_composite() {
+        let cli = Cli::parse_from([
+            "sezkp-cli",
+            "compose",
+            "--child",
+            "a.cbor",
+            "--child",
+            "b.cbor",
+            "--embed",
+        ]);
+        assert!(
+            matches!(&cli.cmd, Cmd::Compose(ComposeArgs { children, embed: true, .. }) if children.len() == 2)
+        );
+        assert!(Cli::try_parse_from(["sezkp-cli", "compose"]).is_err());
+
+        let cli = Cli::parse_from([
+            "sezkp-cli",
+            "verify-composite",
+            "--backend",
+            "stark",
+            "--composite",
+            "c.cbor",
+            "--blocks",
+            "b0.jsonl",
+            "--manifest",
+            "m0.cbor",
+            "--blocks",
+            "b1.jsonl",
+            "--manifest",
+            "m1.cbor",
+        ]);
+        assert!(matches!(
+            &cli.cmd,
+            Cmd::VerifyComposite(VerifyCompositeArgs { blocks, manifest, .. }) if blocks.len() == 2 && manifest.len() == 2
+        ));
+
+        // A composite proof artifact; children verify with their own backends.
+        let cli = Cli::parse_from(["sezkp-cli", "compose", "--child", "a.cbor", "--artifact"]);
+        assert!(matches!(
+            &cli.cmd,
+            Cmd::Compose(ComposeArgs { artifact: true, .. })
+        ));
+        let cli = Cli::parse_from([
+            "sezkp-cli",
+            "verify-composite",
+            "--composite",
+            "c.cbor",
+            "--blocks",
+            "b0.jsonl",
+            "--manifest",
+            "m0.cbor",
+        ]);
+        assert!(matches!(
+            &cli.cmd,
+            Cmd::VerifyComposite(VerifyCompositeArgs { backend: None, .. })
+        ));
+    }
+}
@@ -0,0 +1,155 @@
+//! Debugging aids: `debug-fold` and `challenges`.
+//!
+//! `debug-fold` dumps the streaming fold driver's live stack after a given
+//! block, optionally diffed against the batch Balanced driver at the same
+//! point; `challenges` replays the Fiat–Shamir transcript of a STARK v1
+//! proof. Neither verifies anything.
+
+use anyhow::{ensure, Context, Result};
+use clap::Args;
+use sezkp_core::io::{read_block_summaries_auto, read_proof_auto};
+use sezkp_core::BackendKind;
+use std::path::PathBuf;
+use tracing::info_span;
+
+/// Flags of `debug-fold`.
+#[derive(Args, Debug)]
+pub(crate) struct DebugFoldArgs {
+    /// Input path to σ_k block summaries (CBOR/JSON/JSONL/NDJSON).
+    #[arg(long)]
+    pub blocks: PathBuf,
+
+    /// Number of blocks to push before taking the snapshot.
+    #[arg(long)]
+    pub at: usize,
+
+    /// Also snapshot the batch Balanced driver at the same DFS point and diff.
+    #[arg(long, default_value_t = false)]
+    pub diff: bool,
+}
+
+/// Flags of `challenges`.
+#[derive(Args, Debug)]
+pub(crate) struct ChallengesArgs {
+    /// Input path to the proof artifact (CBOR/JSON).
+    #[arg(long)]
+    pub proof: PathBuf,
+
+    /// Write the JSON here instead of printing it.
+    #[arg(long)]
+    pub out: Option<PathBuf>,
+}
+
+/// Print the streaming driver's live stack after `at` blocks, and with
+/// `diff` the batch Balanced driver's and their differences.
+pub(crate) fn debug_fold(a: DebugFoldArgs) -> Result<()> {
+    let DebugFoldArgs { blocks, at, diff } = a;
+    let _span = info_span!("debug_fold", blocks = %blocks.display(), at, diff).entered();
+    use sezkp_fold::api::DriverOptions;
+    use sezkp_fold::debug::{balanced_state_at, diff_states, render_state, streaming_state_at};
+    use sezkp_fold::{CryptoFold, CryptoLeaf, CryptoWrap};
+
+    let blocks_vec = read_block_summaries_auto(&blocks).context("reading blocks")?;
+    let stream = streaming_state_at::<CryptoLeaf, CryptoFold, CryptoWrap>(
+        &blocks_vec,
+        at,
+        DriverOptions::default(),
+    )?;
+    println!(
+        "Streaming driver after {at}/{} blocks ({} live):",
+        blocks_vec.len(),
+        stream.len()
+    );
+    print!("{}", render_state(&stream));
+
+    if diff {
+        let batch = balanced_state_at::<CryptoLeaf, CryptoFold>(&blocks_vec, at)?;
+        println!("Balanced driver before leaf {at} ({} live):", batch.len());
+        print!("{}", render_state(&batch));
+        let diffs = diff_states(&stream, &batch);
+        if diffs.is_empty() {
+            println!("No differences.");
+        } else {
+            println!("{} difference(s):", diffs.len());
+            for d in &diffs {
+                println!("  {d}");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Write the challenge trace of the STARK v1 proof at `--proof` as pretty
+/// JSON to `--out`, or to stdout.
+///
+/// # Errors
+/// Fails if the artifact is not a decodable STARK v1 proof or its shape is
+/// malformed.
+pub(crate) fn challenges(a: ChallengesArgs) -> Result<()> {
+    use sezkp_stark::v1::{challenges::replay, proof::ProofV1};
+
+    let ChallengesArgs { proof: path, out } = a;
+    let art = read_proof_auto(&path).with_context(|| format!("reading {}", path.display()))?;
+    let proto = art.meta.get("proto").and_then(|v| v.as_str()).unwrap_or("");
+    ensure!(
+        art.resolved_backend() == BackendKind::Stark && proto != "stark-v0",
+        "{} is not a STARK v1 proof (backend {}, proto {proto:?})",
+        path.display(),
+        art.resolved_backend()
+    );
+    let proof = ProofV1::decode(&art.proof_bytes).context("decoding the STARK v1 proof")?;
+    let mut json = serde_json::to_string_pretty(&replay(&proof)?)?;
+    json.push('\n');
+    match out {
+        Some(p) => {
+            std::fs::write(&p, json).with_context(|| format!("write {}", p.display()))?;
+            println!("Wrote challenges → {}", p.display());
+        }
+        None => print!("{json}"),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Cli, Cmd};
+    use clap::Parser;
+
+    #[test]
+    fn parse_debug_fold() {
+        let cli = Cli::parse_from([
+            "sezkp-cli",
+            "debug-fold",
+            "--blocks",
+            "blocks.jsonl",
+            "--at",
+            "17",
+            "--diff",
+        ]);
+        assert!(matches!(
+            cli.cmd,
+            Cmd::DebugFold(DebugFoldArgs {
+                at: 17,
+                diff: true,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn parse_challenges() {
+        let cli = Cli::parse_from([
+            "sezkp-cli",
+            "challenges",
+            "--proof",
+            "proof.cbor",
+            "--out",
+            "challenges.json",
+        ]);
+        assert!(matches!(
+            cli.cmd,
+            Cmd::Challenges(ChallengesArgs { out: Some(_), .. })
+        ));
+    }
+}
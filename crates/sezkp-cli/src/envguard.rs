@@ -0,0 +1,122 @@
+//! `SEZKP_*` environment overrides.
+//!
+//! Backends read most of their options from `SEZKP_*` variables, and the CLI
+//! hands its flags to them the same way. A variable left over in the shell
+//! (a stale `SEZKP_PROOF_STREAM_PATH` or `SEZKP_FOLD_MODE`) would otherwise
+//! change a run without a trace. [`capture`] records the inherited variables
+//! at startup and reports them, or clears them under `--ignore-env`; the CLI
+//! then sets backend variables through [`set`] and [`unset`], which warn
+//! once per variable when a flag overrides a different inherited value.
+//!
+//! Flags always win: the warning only says that the environment was ignored.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::ffi::OsStr;
+use std::sync::{Mutex, OnceLock, PoisonError};
+
+use tracing::{info, warn};
+
+/// Prefix of the variables the backends read.
+const PREFIX: &str = "SEZKP_";
+
+/// `SEZKP_*` variables present at startup (unset under `--ignore-env`).
+static INHERITED: OnceLock<BTreeMap<String, String>> = OnceLock::new();
+
+/// Variables already warned about.
+static WARNED: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+/// `SEZKP_*` variables in the process environment, by name.
+fn sezkp_vars() -> BTreeMap<String, String> {
+    std::env::vars_os()
+        .filter_map(|(k, v)| Some((k.into_string().ok()?, v.to_string_lossy().into_owned())))
+        .filter(|(k, _)| k.starts_with(PREFIX))
+        .collect()
+}
+
+/// Record and report the inherited `SEZKP_*` variables; with `ignore`,
+/// remove them instead so only flags and defaults reach the backends.
+///
+/// Call once, before anything reads the environment.
+pub(crate) fn capture(ignore: bool) {
+    let vars = sezkp_vars();
+    if vars.is_empty() {
+        return;
+    }
+    let listed = vars
+        .iter()
+        .map(|(k, v)| format!("{k}={v}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    if ignore {
+        for k in vars.keys() {
+            std::env::remove_var(k);
+        }
+        info!("--ignore-env: cleared {listed}");
+    } else {
+        info!("environment overrides in effect: {listed} (--ignore-env clears them)");
+        let _ = INHERITED.set(vars);
+    }
+}
+
+/// Set a backend variable on behalf of the command line.
+pub(crate) fn set(key: &str, value: impl AsRef<OsStr>) {
+    let value = value.as_ref();
+    check(key, Some(&value.to_string_lossy()));
+    std::env::set_var(key, value);
+}
+
+/// Clear a backend variable on behalf of the command line.
+pub(crate) fn unset(key: &str) {
+    check(key, None);
+    std::env::remove_var(key);
+}
+
+/// Warn (once per variable) if `key` is being changed from its inherited
+/// value.
+fn check(key: &str, now: Option<&str>) {
+    let Some(msg) = INHERITED.get().and_then(|vars| conflict(vars, key, now)) else {
+        return;
+    };
+    let mut warned = WARNED.lock().unwrap_or_else(PoisonError::into_inner);
+    if warned.insert(key.to_owned()) {
+        warn!("{msg}");
+    }
+}
+
+/// The warning for setting `key` to `now` (`None`: unsetting it), if that
+/// overrides a different value in `inherited`.
+fn conflict(inherited: &BTreeMap<String, String>, key: &str, now: Option<&str>) -> Option<String> {
+    let old = inherited.get(key)?;
+    let now = match now {
+        Some(v) if v == old => return None,
+        Some(v) => format!("sets it to {v}"),
+        None => "leaves it unset".to_owned(),
+    };
+    Some(format!(
+        "{key}={old} from the environment conflicts with the command line, which {now}; \
+         using the command line (--ignore-env clears inherited overrides)"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conflicts_are_changes_to_inherited_values() {
+        let inherited = BTreeMap::from([("SEZKP_FOLD_MODE".to_owned(), "minram".to_owned())]);
+        assert_eq!(
+            conflict(&inherited, "SEZKP_FOLD_MODE", Some("minram")),
+            None
+        );
+        assert_eq!(conflict(&inherited, "SEZKP_WRAP_CADENCE", Some("4")), None);
+        let msg = conflict(&inherited, "SEZKP_FOLD_MODE", Some("balanced")).unwrap();
+        assert!(
+            msg.starts_with("SEZKP_FOLD_MODE=minram from the environment"),
+            "{msg}"
+        );
+        assert!(msg.contains("sets it to balanced"), "{msg}");
+        let msg = conflict(&inherited, "SEZKP_FOLD_MODE", None).unwrap();
+        assert!(msg.contains("leaves it unset"), "{msg}");
+    }
+}
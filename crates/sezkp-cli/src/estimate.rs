@@ -0,0 +1,155 @@
+//! `estimate`: predict proof and stream sizes before proving.
+//!
+//! Sizes come from the backends' own size models
+//! (`sezkp_stark::estimate_proof_size`, `sezkp_fold::estimate_proof_size`)
+//! for a given shape, so storage can be provisioned up front. Nothing is
+//! read or proved.
+
+use anyhow::{Context, Result};
+use clap::Args;
+use sezkp_fold::api::{DriverOptions, FoldMode};
+
+use crate::args::{BackendOpt, FoldModeOpt};
+use crate::plan;
+
+/// Flags of `estimate`.
+#[derive(Args, Debug)]
+pub(crate) struct EstimateArgs {
+    /// Proof backend.
+    #[arg(value_enum, long)]
+    pub backend: BackendOpt,
+
+    /// Number of σ_k blocks.
+    #[arg(long)]
+    pub n_blocks: u64,
+
+    /// Steps per block (STARK: blocks × rows must be a power of two).
+    #[arg(long)]
+    pub rows: u64,
+
+    /// Number of work tapes τ.
+    #[arg(long, default_value_t = 2)]
+    pub tau: usize,
+
+    /// Folding driver mode the proof would be made in.
+    #[arg(long, value_enum, default_value_t = FoldModeOpt::Balanced)]
+    pub fold_mode: FoldModeOpt,
+
+    /// Wrap cadence the proof would be made with (0 = no wraps).
+    #[arg(long, default_value_t = 0)]
+    pub wrap_cadence: u32,
+
+    /// Print the estimate as JSON.
+    #[arg(long, default_value_t = false)]
+    pub json: bool,
+}
+
+/// Print the predicted proof (and, for fold, stream) size of a shape.
+pub(crate) fn estimate(a: EstimateArgs) -> Result<()> {
+    let EstimateArgs {
+        backend,
+        n_blocks,
+        rows,
+        tau,
+        fold_mode,
+        wrap_cadence,
+        json,
+    } = a;
+    let est = match backend {
+        BackendOpt::Stark => sezkp_stark::estimate_proof_size(
+            n_blocks,
+            rows,
+            tau,
+            &sezkp_stark::v1::proof::ProofParams::UNIT,
+        )?,
+        BackendOpt::Fold => {
+            let opts = DriverOptions {
+                fold_mode: match fold_mode {
+                    FoldModeOpt::Balanced => FoldMode::Balanced,
+                    FoldModeOpt::Minram => FoldMode::MinRam,
+                },
+                wrap_cadence,
+                ..DriverOptions::default()
+            };
+            sezkp_fold::estimate_proof_size(n_blocks, rows, tau, &opts.params())?
+        }
+    };
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&est).context("serialize estimate")?
+        );
+        return Ok(());
+    }
+
+    let approx = if est.exact { "" } else { "~" };
+    println!("Backend:       {backend:?}");
+    println!("Shape:         {n_blocks} blocks x {rows} rows, tau {tau}");
+    println!(
+        "Proof bytes:   {approx}{} ({})",
+        est.proof_bytes,
+        plan::human_bytes(est.proof_bytes)
+    );
+    if let Some(stream) = est.stream_bytes {
+        println!(
+            "Stream bytes:  {approx}{stream} ({}) with --stream",
+            plan::human_bytes(stream)
+        );
+    }
+    println!("  {:<16} {:>12} {:>12}", "component", "count", "bytes");
+    for i in &est.items {
+        println!(
+            "  {:<16} {:>12} {:>12}",
+            i.name,
+            i.count,
+            plan::human_bytes(i.bytes)
+        );
+    }
+    println!(
+        "  {:<16} {:>12} {:>12}",
+        "framing",
+        "",
+        plan::human_bytes(est.framing_bytes())
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Cli, Cmd};
+    use clap::Parser;
+
+    #[test]
+    fn parse_estimate() {
+        let cli = Cli::parse_from([
+            "sezkp-cli",
+            "estimate",
+            "--backend",
+            "fold",
+            "--n-blocks",
+            "4096",
+            "--rows",
+            "512",
+            "--wrap-cadence",
+            "8",
+        ]);
+        let Cmd::Estimate(EstimateArgs {
+            backend,
+            n_blocks,
+            rows,
+            tau,
+            fold_mode,
+            wrap_cadence,
+            json,
+        }) = cli.cmd
+        else {
+            panic!("expected estimate");
+        };
+        assert_eq!(backend, BackendOpt::Fold);
+        assert_eq!((n_blocks, rows, tau), (4096, 512, 2));
+        assert_eq!(fold_mode, FoldModeOpt::Balanced);
+        assert_eq!(wrap_cadence, 8);
+        assert!(!json);
+    }
+}
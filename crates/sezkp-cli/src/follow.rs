@@ -0,0 +1,311 @@
+//! `prove --follow`: prove a JSONL blocks file while it is still being written.
+//!
+//! [`Follower`] tails the file by polling. Each complete line is yielded as
+//! a block as soon as it lands; at the current end of the file it sleeps
+//! [`POLL_INTERVAL`] and looks again. A last line without its newline is
+//! still being written and is held back. The stream ends once the writer is
+//! done:
+//!
+//! - the marker file exists (`<blocks>.done` unless `--follow-done` names
+//!   another) and every line appended before it was created has been read;
+//! - or, with `--follow-idle SECS`, the file has not grown for that long;
+//! - or the writer's end-of-stream marker line (`{"sezkp_eof":N}`) arrives.
+//!   Its count must match the blocks read, and with `--require-jsonl-eof`
+//!   the other two endings fail without it.
+//!
+//! Ctrl-C stops the run like any streaming proof ([`crate::interrupt`]):
+//! the proof stream is closed as truncated and no artifact is written. The
+//! follower also notices the request while it waits for new lines.
+
+use anyhow::{bail, ensure, Context, Result};
+use sezkp_core::io_jsonl::{jsonl_eof_marker, require_jsonl_eof};
+use sezkp_core::read_limits::{read_limits, ReadLimits};
+use sezkp_core::BlockSummary;
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// How long to wait before looking for new data at the end of the file.
+pub(crate) const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// When a followed file is complete.
+#[derive(Debug, Clone)]
+pub(crate) struct FollowEnd {
+    /// Marker file the writer creates once it has appended its last block.
+    pub(crate) done_marker: PathBuf,
+    /// Also finish after the file has not grown for this long.
+    pub(crate) idle: Option<Duration>,
+}
+
+impl FollowEnd {
+    /// End condition for `blocks`: the given marker, or `<blocks>.done`.
+    pub(crate) fn new(blocks: &Path, done_marker: Option<PathBuf>, idle_secs: Option<u64>) -> Self {
+        let done_marker = done_marker.unwrap_or_else(|| {
+            let mut p = OsString::from(blocks.as_os_str());
+            p.push(".done");
+            p.into()
+        });
+        Self {
+            done_marker,
+            idle: idle_secs.map(Duration::from_secs),
+        }
+    }
+}
+
+/// Blocks of a growing JSONL file, in order, until the writer is done.
+pub(crate) struct Follower {
+    path: PathBuf,
+    rdr: BufReader<File>,
+    buf: Vec<u8>,
+    line_no: usize,
+    limits: ReadLimits,
+    end: FollowEnd,
+    last_growth: Instant,
+    finished: bool,
+}
+
+impl Follower {
+    /// Start following `path`, waiting for it to be created if needed.
+    ///
+    /// # Errors
+    /// Fails if the done marker already exists (it would end the run at the
+    /// current end of the file), or if the file cannot be opened.
+    pub(crate) fn open(path: &Path, end: FollowEnd) -> Result<Self> {
+        ensure!(
+            !end.done_marker.exists(),
+            "done marker {} already exists; remove it before following {}",
+            end.done_marker.display(),
+            path.display()
+        );
+        let start = Instant::now();
+        while !path.exists() {
+            if end.done_marker.exists() {
+                bail!("{} was marked done before it was created", path.display());
+            }
+            if end.idle.is_some_and(|idle| start.elapsed() >= idle) {
+                bail!("{} was not created", path.display());
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+        let file = File::open(path).with_context(|| format!("open {}", path.display()))?;
+        Ok(Self {
+            path: path.to_owned(),
+            rdr: BufReader::new(file),
+            buf: Vec::with_capacity(8 << 10),
+            line_no: 0,
+            limits: read_limits()?,
+            end,
+            last_growth: Instant::now(),
+            finished: false,
+        })
+    }
+
+    /// The next complete line (without its newline), or `None` once the
+    /// writer is done.
+    fn next_line(&mut self) -> Result<Option<Vec<u8>>> {
+        loop {
+            // Check the marker before reading: lines appended before it was
+            // created are then guaranteed to be read below.
+            let done = self.end.done_marker.exists();
+            let n = self
+                .rdr
+                .read_until(b'\n', &mut self.buf)
+                .with_context(|| format!("read line {}", self.line_no + 1))?;
+            self.limits
+                .check_record_len(self.buf.len().saturating_sub(1) as u64)
+                .with_context(|| format!("jsonl line {}", self.line_no + 1))?;
+            if self.buf.ends_with(b"\n") {
+                self.last_growth = Instant::now();
+                let mut line = std::mem::take(&mut self.buf);
+                line.pop();
+                return Ok(Some(line));
+            }
+            if n > 0 {
+                self.last_growth = Instant::now();
+                continue;
+            }
+            let idle = self
+                .end
+                .idle
+                .is_some_and(|idle| self.last_growth.elapsed() >= idle);
+            if done || idle {
+                ensure!(
+                    self.buf.is_empty(),
+                    "{}: line {} has no newline when following ended",
+                    self.path.display(),
+                    self.line_no + 1
+                );
+                return Ok(None);
+            }
+            sezkp_core::cancel::check()?;
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    /// Check the end-of-stream marker read as line `line_no`: it must count
+    /// the blocks before it, and nothing may follow it.
+    fn check_eof(&mut self, marker: Result<u64>) -> Result<()> {
+        let blocks = self.line_no - 1;
+        let n = marker.with_context(|| format!("jsonl line {}", self.line_no))?;
+        ensure!(
+            n == blocks as u64,
+            "jsonl line {}: end-of-stream marker declares {n} blocks but {blocks} precede it",
+            self.line_no
+        );
+        let rest = self.rdr.fill_buf().context("read past the marker")?;
+        ensure!(
+            rest.is_empty(),
+            "jsonl line {}: data after the end-of-stream marker",
+            self.line_no + 1
+        );
+        Ok(())
+    }
+
+    /// Parse one line as block `line_no` (1-based).
+    fn parse(&self, line: &[u8]) -> Result<BlockSummary> {
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        ensure!(
+            !line.is_empty(),
+            "parse jsonl line {}: empty line",
+            self.line_no
+        );
+        let b = serde_json::from_slice(line)
+            .with_context(|| format!("parse jsonl line {}", self.line_no))?;
+        self.limits
+            .check_block(&b)
+            .with_context(|| format!("jsonl line {}", self.line_no))?;
+        Ok(b)
+    }
+}
+
+impl Iterator for Follower {
+    type Item = Result<BlockSummary>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let item = match self.next_line() {
+            Ok(Some(line)) => {
+                self.line_no += 1;
+                if let Some(marker) = jsonl_eof_marker(&line) {
+                    self.finished = true;
+                    return self.check_eof(marker).err().map(Err);
+                }
+                self.parse(&line)
+            }
+            Ok(None) if require_jsonl_eof() => Err(anyhow::anyhow!(
+                "{}: following ended after {} lines without an end-of-stream marker",
+                self.path.display(),
+                self.line_no
+            )),
+            Ok(None) => {
+                self.finished = true;
+                return None;
+            }
+            Err(e) => Err(e),
+        };
+        self.finished = item.is_err();
+        Some(item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sezkp_trace::{generator::generate_trace, partition::partition_trace};
+    use std::io::Write;
+
+    fn line(b: &BlockSummary) -> Vec<u8> {
+        let mut l = serde_json::to_vec(b).unwrap();
+        l.push(b'\n');
+        l
+    }
+
+    #[test]
+    fn follows_appends_until_the_done_marker() {
+        let blocks = partition_trace(&generate_trace(64, 2), 16);
+        let dir = std::env::temp_dir().join(format!("sezkp_follow_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("blocks.jsonl");
+        let end = FollowEnd::new(&path, None, None);
+        assert_eq!(end.done_marker, dir.join("blocks.jsonl.done"));
+
+        // The writer appends one block at a time, the last one in two
+        // pieces, then marks the file done.
+        let writer = {
+            let (path, marker, blocks) = (path.clone(), end.done_marker.clone(), blocks.clone());
+            std::thread::spawn(move || {
+                let mut f = File::create(&path).unwrap();
+                let (last, init) = blocks.split_last().unwrap();
+                for b in init {
+                    f.write_all(&line(b)).unwrap();
+                    std::thread::sleep(POLL_INTERVAL / 4);
+                }
+                let l = line(last);
+                let (a, z) = l.split_at(l.len() / 2);
+                f.write_all(a).unwrap();
+                std::thread::sleep(POLL_INTERVAL * 2);
+                f.write_all(z).unwrap();
+                File::create(marker).unwrap();
+            })
+        };
+        let got: Vec<BlockSummary> = Follower::open(&path, end.clone())
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+        writer.join().unwrap();
+        assert_eq!(got, blocks);
+
+        // A leftover marker would end the next run early.
+        assert!(Follower::open(&path, end).is_err());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn end_of_stream_marker_ends_following() {
+        let blocks = partition_trace(&generate_trace(64, 2), 16);
+        let dir = std::env::temp_dir().join(format!("sezkp_follow_eof_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("blocks.jsonl");
+        sezkp_core::io_jsonl::write_block_summaries_jsonl(&path, &blocks).unwrap();
+
+        // No done file and no idle timeout: only the marker ends the run.
+        let end = FollowEnd::new(&path, None, None);
+        let got: Vec<BlockSummary> = Follower::open(&path, end.clone())
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(got, blocks);
+
+        // A marker counting more blocks than were written.
+        let mut bytes = line(&blocks[0]);
+        bytes.extend_from_slice(sezkp_core::io_jsonl::jsonl_eof_line(2).as_bytes());
+        std::fs::write(&path, bytes).unwrap();
+        let got: Vec<Result<BlockSummary>> = Follower::open(&path, end).unwrap().collect();
+        assert_eq!(got.len(), 2);
+        let err = format!("{:#}", got[1].as_ref().unwrap_err());
+        assert!(err.contains("declares 2 blocks but 1 precede it"), "{err}");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn idle_timeout_rejects_a_torn_last_line() {
+        let blocks = partition_trace(&generate_trace(16, 2), 8);
+        let dir = std::env::temp_dir().join(format!("sezkp_follow_idle_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("blocks.jsonl");
+        let mut bytes = line(&blocks[0]);
+        bytes.extend_from_slice(&line(&blocks[1])[..10]);
+        std::fs::write(&path, bytes).unwrap();
+
+        let end = FollowEnd::new(&path, None, Some(0));
+        let got: Vec<Result<BlockSummary>> = Follower::open(&path, end).unwrap().collect();
+        assert_eq!(got.len(), 2);
+        assert_eq!(got[0].as_ref().unwrap(), &blocks[0]);
+        assert!(got[1].is_err());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,487 @@
+//! `inspect`: say what a file is and summarize it.
+//!
+//! The kind comes from the content, not the name: the first value of the
+//! file decides between a commit manifest, a proof artifact, a blocks file
+//! and a fold proof stream (`.cborseq`), and zstd-compressed files are read
+//! through. Each kind gets its own summary; none of them verifies anything.
+//!
+//! Blocks files and streams are read once, a record at a time, so they can
+//! be inspected at any size.
+
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use serde::de::IgnoredAny;
+use serde::Deserialize;
+use sezkp_core::compress;
+use sezkp_core::io::read_proof_auto;
+use sezkp_core::io_format::{sniff_bytes, FileFormat};
+use sezkp_core::render::render_root;
+use sezkp_core::{BackendKind, ProofArtifact};
+use sezkp_fold::census::{census, stream_census};
+use sezkp_fold::driver::STREAM_MAGIC;
+use sezkp_merkle::read_manifest_auto;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+
+use crate::plan::{blocks_format, human_bytes, inspect_blocks};
+use crate::verify::print_claims;
+
+/// Flags of `inspect`.
+#[derive(Args, Debug)]
+pub(crate) struct InspectArgs {
+    /// File to inspect (CBOR/JSON/JSONL, optionally zstd-compressed).
+    #[arg(value_name = "PATH", required_unless_present = "proof")]
+    pub path: Option<PathBuf>,
+
+    /// Same as PATH (the form earlier releases took).
+    #[arg(long, conflicts_with = "path")]
+    pub proof: Option<PathBuf>,
+
+    /// Print a proof artifact's raw `meta` JSON, or a manifest as JSON,
+    /// instead of the summary.
+    #[arg(long, default_value_t = false)]
+    pub json: bool,
+}
+
+/// What a file holds, as far as `inspect` is concerned.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum FileKind {
+    /// A commit manifest (`commit --out`).
+    Manifest,
+    /// A proof artifact (`prove --out`).
+    Proof,
+    /// Block summaries (CBOR/JSON array or JSON Lines).
+    Blocks,
+    /// A fold proof stream (CBOR-seq, `prove --stream`).
+    FoldStream,
+}
+
+impl FileKind {
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Manifest => "commit manifest",
+            Self::Proof => "proof artifact",
+            Self::Blocks => "blocks",
+            Self::FoldStream => "fold proof stream",
+        }
+    }
+}
+
+/// The top-level keys that tell the kinds apart. Every other key, and the
+/// values of these, are skipped without being decoded.
+#[derive(Deserialize)]
+struct Probe {
+    magic: Option<String>,
+    proof_bytes: Option<IgnoredAny>,
+    n_leaves: Option<IgnoredAny>,
+    block_id: Option<IgnoredAny>,
+}
+
+impl Probe {
+    fn kind(&self) -> Option<FileKind> {
+        if self.magic.as_deref() == Some(STREAM_MAGIC) {
+            Some(FileKind::FoldStream)
+        } else if self.proof_bytes.is_some() {
+            Some(FileKind::Proof)
+        } else if self.n_leaves.is_some() {
+            Some(FileKind::Manifest)
+        } else if self.block_id.is_some() {
+            Some(FileKind::Blocks)
+        } else {
+            None
+        }
+    }
+}
+
+/// Tell what the file at `path` holds from its first value.
+///
+/// # Errors
+/// Fails if the file cannot be read or is none of the [`FileKind`]s.
+pub(crate) fn sniff_kind(path: &Path) -> Result<FileKind> {
+    let mut rdr = compress::open(path)?;
+    let head = rdr
+        .fill_buf()
+        .with_context(|| format!("read {}", path.display()))?;
+    let format = sniff_bytes(head).format;
+    let first = head.iter().copied().find(|b| !b.is_ascii_whitespace());
+    let probe = match (format, first) {
+        (Some(FileFormat::Jsonl), _) | (Some(FileFormat::Json), Some(b'[')) => {
+            return Ok(FileKind::Blocks)
+        }
+        // A CBOR array (major type 4) can only be blocks.
+        (Some(FileFormat::Cbor), Some(0x80..=0x9f)) => return Ok(FileKind::Blocks),
+        (Some(FileFormat::Cbor), _) => ciborium::de::from_reader::<Probe, _>(rdr).ok(),
+        (Some(FileFormat::Json), _) => serde_json::Deserializer::from_reader(rdr)
+            .into_iter::<Probe>()
+            .next()
+            .and_then(Result::ok),
+        (None, _) => None,
+    };
+    match probe.as_ref().and_then(Probe::kind) {
+        Some(kind) => Ok(kind),
+        None => bail!(
+            "{} is not a commit manifest, proof artifact, blocks file or fold proof stream",
+            path.display()
+        ),
+    }
+}
+
+/// Summarize the file at `PATH`; with `--json`, print a proof artifact's
+/// raw `meta` or a manifest as JSON instead.
+///
+/// # Errors
+/// Fails if the file cannot be read or is of no known kind, or if `json`
+/// is asked of a blocks file or stream.
+pub(crate) fn run(a: InspectArgs) -> Result<()> {
+    let InspectArgs { path, proof, json } = a;
+    let path = path.or(proof).context("no file to inspect")?;
+    let kind = sniff_kind(&path)?;
+    if json && matches!(kind, FileKind::Blocks | FileKind::FoldStream) {
+        bail!(
+            "--json applies to proof artifacts and manifests; {} is a {}",
+            path.display(),
+            kind.name()
+        );
+    }
+    match kind {
+        FileKind::Manifest => print_manifest(&path, json),
+        FileKind::Proof => print_proof(&path, json),
+        FileKind::Blocks => print_blocks(&path),
+        FileKind::FoldStream => print_stream(&path),
+    }
+}
+
+/// Size of the file at `path` as `"<n> (<human>)"`.
+fn file_size(path: &Path) -> Result<String> {
+    let n = std::fs::metadata(path)
+        .with_context(|| format!("stat {}", path.display()))?
+        .len();
+    Ok(format!("{n} ({})", human_bytes(n)))
+}
+
+/// Whether the file at `path` is compressed, for the header lines.
+fn compressed_note(path: &Path) -> Result<&'static str> {
+    Ok(if compress::is_compressed(path)? {
+        ", zstd"
+    } else {
+        ""
+    })
+}
+
+fn print_manifest(path: &Path, json: bool) -> Result<()> {
+    let m = read_manifest_auto(path).with_context(|| format!("reading {}", path.display()))?;
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&m).context("serialize manifest")?
+        );
+        return Ok(());
+    }
+    println!("Manifest:      {}", path.display());
+    println!("Kind:          commit manifest v{}", m.version);
+    println!("Root:          {}", render_root(&m.root));
+    println!("Leaves:        {}", m.n_leaves);
+    println!("Total steps:   {}", m.total_steps);
+    println!("Tau:           {}", m.tau);
+    println!("Leaf schema:   {} ({})", m.leaf_schema, m.hash_scheme);
+    if let Some(n) = m.input_len {
+        println!("Input length:  {n}");
+    }
+    if let Some(cp) = &m.checkpoints {
+        println!(
+            "Checkpoints:   {} subtree roots of 2^{} leaves",
+            cp.roots.len(),
+            cp.span_log2
+        );
+    }
+    if let Some(c) = &m.creator {
+        let note = c
+            .note
+            .as_deref()
+            .map(|n| format!(": {n}"))
+            .unwrap_or_default();
+        println!("Creator:       {} {}{note}", c.tool, c.tool_version);
+    }
+    Ok(())
+}
+
+/// Print a summary of the artifact at `path` (or its raw `meta` with `json`).
+///
+/// STARK artifacts carry a per-phase prover profile under `meta.profile`;
+/// it is shown as a table of wall time, share of the total and the largest
+/// temporary-buffer footprint per phase. Batch fold artifacts are counted
+/// by record.
+fn print_proof(path: &Path, json: bool) -> Result<()> {
+    use sezkp_stark::v1::profile::ProveProfile;
+
+    let art = read_proof_auto(path).with_context(|| format!("reading {}", path.display()))?;
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&art.meta).context("serialize meta")?
+        );
+        return Ok(());
+    }
+
+    println!("Artifact:      {}", path.display());
+    let backend = art.resolved_backend();
+    if art.has_legacy_label() {
+        println!(
+            "Backend:       {backend} (labelled {}; run migrate-proof)",
+            art.backend
+        );
+    } else {
+        println!("Backend:       {backend}");
+    }
+    if let Some(proto) = art.meta.get("proto").and_then(|v| v.as_str()) {
+        println!("Proto:         {proto}");
+    }
+    println!("Manifest root: {}", render_root(&art.manifest_root));
+    println!(
+        "Proof bytes:   {} ({})",
+        art.proof_bytes.len(),
+        human_bytes(art.proof_bytes.len() as u64)
+    );
+    let stamp = if art.timestamp.is_some() {
+        "present"
+    } else {
+        "none"
+    };
+    println!("Timestamp:     {stamp}");
+    if backend == BackendKind::Fold {
+        if let Ok(c) = census(&art.proof_bytes) {
+            println!(
+                "Records:       {} leaves, {} folds, {} wraps",
+                c.leaves,
+                c.folds,
+                c.wraps_mac + c.wraps_other
+            );
+        }
+    }
+    print_claims(&art.claims);
+    print_stark_statement(&art);
+    if let Some(meta) = art.meta.as_object() {
+        for (k, v) in meta
+            .iter()
+            .filter(|(k, _)| !matches!(k.as_str(), "profile" | "proto"))
+        {
+            println!("  {k}: {v}");
+        }
+    }
+
+    let Some(profile) = ProveProfile::from_meta(&art.meta) else {
+        return Ok(());
+    };
+    let total = profile.total_ms();
+    println!("Prover profile:");
+    println!(
+        "  {:<14} {:>10} {:>7} {:>12}",
+        "phase", "ms", "share", "peak temp"
+    );
+    for p in &profile.phases {
+        let share = if total > 0.0 {
+            100.0 * p.ms / total
+        } else {
+            0.0
+        };
+        println!(
+            "  {:<14} {:>10.1} {:>6.1}% {:>12}",
+            p.phase,
+            p.ms,
+            share,
+            human_bytes(p.peak_temp_bytes)
+        );
+    }
+    println!(
+        "  {:<14} {:>10.1} {:>7} {:>12}",
+        "total",
+        total,
+        "",
+        human_bytes(profile.peak_temp_bytes)
+    );
+    Ok(())
+}
+
+/// Print the public inputs at the head of a STARK v1 proof: the statement
+/// it attests, as recorded in the proof rather than in the artifact.
+fn print_stark_statement(art: &ProofArtifact) {
+    use sezkp_stark::v1::proof::PublicInputs;
+
+    let proto = art.meta.get("proto").and_then(|v| v.as_str()).unwrap_or("");
+    if art.resolved_backend() != BackendKind::Stark || proto == "stark-v0" {
+        return;
+    }
+    let Ok(public) = PublicInputs::peek(&art.proof_bytes) else {
+        println!("Statement:     undecodable");
+        return;
+    };
+    println!("Statement:");
+    println!("  rows:          {}", public.n_rows);
+    println!("  tau:           {}", public.tau);
+    println!("  manifest root: {}", render_root(&public.manifest_root));
+    println!("  params digest: {}", hex::encode(public.params_digest));
+    if let Some(d) = public.claims {
+        println!("  claims digest: {}", hex::encode(d));
+    }
+}
+
+fn print_blocks(path: &Path) -> Result<()> {
+    let info = inspect_blocks(path)?;
+    println!("Blocks:        {}", path.display());
+    println!(
+        "Format:        {}{}",
+        blocks_format(path),
+        compressed_note(path)?
+    );
+    println!("File bytes:    {}", file_size(path)?);
+    println!("Blocks:        {}", info.n_blocks);
+    if let Some((first, last)) = info.block_ids {
+        println!("Block ids:     {first}..={last}");
+    }
+    println!("Total steps:   {}", info.total_steps);
+    println!("Largest block: {} steps", info.max_block_steps);
+    println!("Tau:           {}", info.tau);
+    Ok(())
+}
+
+fn print_stream(path: &Path) -> Result<()> {
+    let rdr = compress::open(path)?;
+    let c = stream_census(rdr).with_context(|| format!("reading {}", path.display()))?;
+    let h = &c.header;
+    println!("Stream:        {}", path.display());
+    println!(
+        "Kind:          {} v{}{}",
+        h.magic,
+        h.ver,
+        compressed_note(path)?
+    );
+    println!("File bytes:    {}", file_size(path)?);
+    let late = if h.late_bound { " (late-bound)" } else { "" };
+    println!("Manifest root: {}{late}", render_root(&h.manifest_root));
+    println!("Fold mode:     {:?}", h.mode);
+    println!("Wrap cadence:  {}", h.wrap_cadence);
+    if let Some(p) = h.params() {
+        println!("Iface window:  {}", p.iface_window);
+        println!("ARE samples:   {}", p.are_samples);
+        println!("Seam digest:   {}", p.iface_digest);
+    }
+    if let Some(d) = h.claims {
+        println!("Claims digest: {}", hex::encode(d));
+    }
+    println!(
+        "Records:       {} leaves, {} folds, {} wraps",
+        c.leaves, c.folds, c.wraps
+    );
+    match &c.footer {
+        None => println!("Footer:        missing (stream incomplete)"),
+        Some(f) if f.truncated => {
+            println!("Footer:        cancelled after {} blocks", f.n_blocks);
+        }
+        Some(f) => {
+            println!("Footer:        {} blocks", f.n_blocks);
+            if h.late_bound {
+                println!("Bound root:    {}", render_root(&f.manifest_root));
+            }
+            if let Some(fw) = &f.final_wrap {
+                println!("Final wrap:    {}", fw.backend);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Cli, Cmd};
+    use clap::Parser;
+    use sezkp_core::io::{write_block_summaries_json, write_proof_auto};
+    use sezkp_core::ProvingBackend;
+    use sezkp_fold::api::DriverOptions;
+    use sezkp_fold::driver::{CborSeqSink, StreamDriverSink};
+    use sezkp_fold::{CryptoFold, CryptoLeaf, CryptoWrap};
+    use sezkp_merkle::{commit_blocks, write_manifest_auto};
+    use sezkp_trace::{generator::generate_trace, partition::partition_trace};
+
+    #[test]
+    fn kinds_are_told_apart_by_content() {
+        let dir = std::env::temp_dir().join(format!("sezkp_inspect_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let blocks = partition_trace(&generate_trace(32, 2), 8);
+        let man = commit_blocks(&blocks);
+        let art = sezkp_fold::FoldAgg::prove(&blocks, man.root).unwrap();
+
+        // Misleading names on purpose: only the content counts.
+        let cases = [
+            ("a.json", FileKind::Blocks),
+            ("b.cbor", FileKind::Blocks),
+            ("c.jsonl", FileKind::Blocks),
+            ("d.json", FileKind::Manifest),
+            ("e.cbor", FileKind::Manifest),
+            ("f.json", FileKind::Proof),
+            ("g.cbor", FileKind::Proof),
+        ];
+        for (name, kind) in cases {
+            let p = dir.join(name);
+            match kind {
+                FileKind::Blocks if name.ends_with("jsonl") => {
+                    sezkp_core::io_jsonl::write_block_summaries_jsonl(&p, &blocks).unwrap();
+                }
+                FileKind::Blocks if name.ends_with("cbor") => {
+                    sezkp_core::io::write_block_summaries_cbor(&p, &blocks).unwrap();
+                }
+                FileKind::Blocks => write_block_summaries_json(&p, &blocks).unwrap(),
+                FileKind::Manifest => write_manifest_auto(&p, &man).unwrap(),
+                _ => write_proof_auto(&p, &art).unwrap(),
+            }
+            let renamed = dir.join(format!("{name}.bin"));
+            std::fs::rename(&p, &renamed).unwrap();
+            assert_eq!(sniff_kind(&renamed).unwrap(), kind, "{name}");
+        }
+
+        let stream = dir.join("h.cbor");
+        let opts = DriverOptions {
+            manifest_root: man.root,
+            ..DriverOptions::default()
+        };
+        let mut drv = StreamDriverSink::<CryptoLeaf, CryptoFold, CryptoWrap, _>::new(
+            CborSeqSink::new(std::fs::File::create(&stream).unwrap()),
+            opts,
+        )
+        .unwrap();
+        for b in &blocks {
+            drv.push_block(b.clone()).unwrap();
+        }
+        drv.finish().unwrap();
+        assert_eq!(sniff_kind(&stream).unwrap(), FileKind::FoldStream);
+
+        let other = dir.join("other.json");
+        std::fs::write(&other, r#"{"hello": 1}"#).unwrap();
+        assert!(sniff_kind(&other).is_err());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn parse_inspect_and_stats_alias() {
+        for name in ["inspect", "stats"] {
+            let cli = Cli::parse_from(["sezkp-cli", name, "--proof", "proof.json", "--json"]);
+            assert!(matches!(
+                cli.cmd,
+                Cmd::Inspect(InspectArgs { json: true, .. })
+            ));
+        }
+        let cli = Cli::parse_from(["sezkp-cli", "inspect", "blocks.jsonl"]);
+        assert!(matches!(
+            cli.cmd,
+            Cmd::Inspect(InspectArgs {
+                path: Some(_),
+                proof: None,
+                ..
+            })
+        ));
+        assert!(Cli::try_parse_from(["sezkp-cli", "inspect"]).is_err());
+        assert!(
+            Cli::try_parse_from(["sezkp-cli", "inspect", "a.cbor", "--proof", "b.cbor"]).is_err()
+        );
+    }
+}
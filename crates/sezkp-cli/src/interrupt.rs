@@ -0,0 +1,55 @@
+//! Ctrl-C during streaming fold proofs.
+//!
+//! [`install`] replaces the default SIGINT/SIGTERM action (kill the process)
+//! with a cooperative stop: the first signal calls
+//! [`sezkp_core::cancel::request`], the streaming prover stops before its
+//! next block, the fold backend ends the proof stream with a truncated
+//! footer, and `prove` exits with [`EXIT_CANCELLED`] after printing how to
+//! resume. A second signal exits at once, for a leaf that takes too long to
+//! finish.
+//!
+//! Only the streaming fold path has cancellation points, so the handler is
+//! installed there alone; other commands keep the default action.
+
+// The only unsafe code in the crate: registering the handler. The handler
+// itself does async-signal-safe work only (atomic swaps, `_exit`).
+#![allow(unsafe_code)]
+
+/// Exit status of a run stopped by Ctrl-C (128 + SIGINT, as shells report).
+pub(crate) const EXIT_CANCELLED: u8 = 130;
+
+#[cfg(unix)]
+mod imp {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    static SEEN: AtomicBool = AtomicBool::new(false);
+
+    extern "C" fn on_signal(_sig: libc::c_int) {
+        if SEEN.swap(true, Ordering::SeqCst) {
+            // SAFETY: `_exit` is async-signal-safe.
+            unsafe { libc::_exit(i32::from(super::EXIT_CANCELLED)) }
+        }
+        sezkp_core::cancel::request();
+    }
+
+    pub(super) fn install() {
+        let handler = on_signal as extern "C" fn(libc::c_int) as libc::sighandler_t;
+        for sig in [libc::SIGINT, libc::SIGTERM] {
+            // SAFETY: `on_signal` has the signature `signal` expects and only
+            // performs async-signal-safe operations.
+            if unsafe { libc::signal(sig, handler) } == libc::SIG_ERR {
+                tracing::warn!(sig, "could not install signal handler");
+            }
+        }
+    }
+}
+
+#[cfg(not(unix))]
+mod imp {
+    pub(super) fn install() {}
+}
+
+/// Route SIGINT/SIGTERM to a cooperative stop (no-op off Unix).
+pub(crate) fn install() {
+    imp::install();
+}
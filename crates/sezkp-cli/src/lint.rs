@@ -0,0 +1,454 @@
+//! `lint-proof`: flag weak or placeholder constructions in an artifact.
+//!
+//! A proof that verifies is not necessarily a strong one: the v0 backend is a
+//! scaffold, several fold gadgets are transcript MACs anyone can recompute,
+//! and query counts, grinding and statement bindings vary with how a proof
+//! was made. The lint decodes the artifact (STARK v1 proofs and batch fold
+//! bundles; streaming fold artifacts only through their `meta`) and reports
+//! each known weakness with a [`Severity`], highest first, so consumers can
+//! gate acceptance with `--fail-on`.
+//!
+//! Nothing is verified: run `verify` for that. Findings describe the
+//! construction, not whether this particular proof is valid.
+
+use anyhow::{bail, Context, Result};
+use clap::{Args, ValueEnum};
+use serde::Serialize;
+use sezkp_core::io::read_proof_auto;
+use sezkp_core::{BackendKind, ProofArtifact};
+use sezkp_fold::census::census;
+use sezkp_fold::final_wrap::MacWrapBackend;
+use sezkp_stark::v1::params::{GRINDING_BITS, NUM_QUERIES};
+use sezkp_stark::v1::proof::ProofV1;
+use std::fmt;
+use std::path::PathBuf;
+
+/// Conjectured security (bits) below which a STARK proof is rated high.
+const WEAK_BITS: usize = 80;
+/// Conjectured security (bits) a STARK proof should reach.
+const TARGET_BITS: usize = 100;
+
+/// How much a finding weakens the proof (ordered: `Info < … < High`).
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Severity {
+    /// Worth knowing; no effect on soundness.
+    Info,
+    /// Weaker than the recommended configuration.
+    Low,
+    /// Soundness rests on a weaker or placeholder component.
+    Medium,
+    /// The proof gives little or no assurance for what it claims.
+    High,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Self::Info => "info",
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        })
+    }
+}
+
+/// One weakness found in an artifact.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub(crate) struct Finding {
+    pub severity: Severity,
+    /// Stable identifier (`v0-scaffold`, `mac-wrap`, …) for policies.
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl Finding {
+    fn new(severity: Severity, code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+/// All findings for `art`, highest severity first (ties keep check order).
+pub(crate) fn lint(art: &ProofArtifact) -> Vec<Finding> {
+    let mut out = Vec::new();
+    match art.resolved_backend() {
+        BackendKind::Stark => lint_stark(art, &mut out),
+        BackendKind::Fold => lint_fold(art, &mut out),
+        other => out.push(Finding::new(
+            Severity::High,
+            "unknown-backend",
+            format!("backend {other} is not built into this CLI; nothing can be checked"),
+        )),
+    }
+    out.sort_by(|a, b| b.severity.cmp(&a.severity));
+    out
+}
+
+fn proto(art: &ProofArtifact) -> &str {
+    art.meta.get("proto").and_then(|v| v.as_str()).unwrap_or("")
+}
+
+fn lint_stark(art: &ProofArtifact, out: &mut Vec<Finding>) {
+    if proto(art) == "stark-v0" {
+        out.push(Finding::new(
+            Severity::High,
+            "v0-scaffold",
+            "STARK v0 scaffold: the proof is two transcript challenges over the commitment, not an argument about the trace",
+        ));
+        return;
+    }
+    let proof = match ProofV1::decode(&art.proof_bytes) {
+        Ok(p) => p,
+        Err(e) => {
+            out.push(Finding::new(
+                Severity::High,
+                "undecodable",
+                format!("proof bytes do not decode as STARK v1: {e}"),
+            ));
+            return;
+        }
+    };
+    if proof.is_empty_proof() {
+        out.push(Finding::new(
+            Severity::Info,
+            "empty-proof",
+            "proof over zero blocks: it attests only to the empty manifest",
+        ));
+        return;
+    }
+
+    let bits = proof.fri_params.security_bits();
+    let queries = proof.fri_params.num_queries;
+    if bits < TARGET_BITS {
+        let severity = if bits < WEAK_BITS {
+            Severity::High
+        } else {
+            Severity::Medium
+        };
+        let schedule = if proof.fri_params.is_chained() {
+            format!("{queries} queries")
+        } else {
+            format!("per-layer queries {:?}", proof.fri_params.layer_queries)
+        };
+        out.push(Finding::new(
+            severity,
+            "low-queries",
+            format!(
+                "{schedule} give ~{bits} conjectured bits (below {TARGET_BITS}; the default is {NUM_QUERIES}); prove with --security-bits"
+            ),
+        ));
+    }
+    if GRINDING_BITS == 0 {
+        out.push(Finding::new(
+            Severity::Medium,
+            "no-grinding",
+            "no proof-of-work grinding before query sampling: every security bit comes from queries",
+        ));
+    }
+    let recorded = art
+        .meta
+        .get("security")
+        .and_then(|s| s.get("achieved_bits"))
+        .and_then(serde_json::Value::as_u64);
+    if recorded.is_some_and(|r| r != bits as u64) {
+        out.push(Finding::new(
+            Severity::Medium,
+            "meta-mismatch",
+            format!(
+                "meta records {} achieved bits, the proof's schedule gives {bits}",
+                recorded.unwrap_or_default()
+            ),
+        ));
+    }
+    if !art.claims.is_empty() && proof.public.claims.is_none() {
+        out.push(Finding::new(
+            Severity::High,
+            "unbound-claims",
+            "artifact lists claims the proof does not bind",
+        ));
+    }
+    if proof.params.input_len.is_none() {
+        out.push(Finding::new(
+            Severity::Low,
+            "unbound-input",
+            "no declared input length: the input head is left out of the proof",
+        ));
+    }
+    if proof.beacon.is_none() {
+        out.push(Finding::new(
+            Severity::Info,
+            "no-beacon",
+            "queries are derived from the transcript alone (no external beacon)",
+        ));
+    }
+}
+
+fn lint_fold(art: &ProofArtifact, out: &mut Vec<Finding>) {
+    if art.has_legacy_label() {
+        out.push(Finding::new(
+            Severity::Low,
+            "legacy-label",
+            format!(
+                "labelled {} rather than fold; run migrate-proof",
+                art.backend
+            ),
+        ));
+    }
+    out.push(Finding::new(
+        Severity::Medium,
+        "mac-fold",
+        "fold steps are transcript MACs (recomputable by anyone); soundness rests on the opened π and ARE checks",
+    ));
+    out.push(Finding::new(
+        Severity::Medium,
+        "are-placeholder",
+        "ARE interface proofs are micro-proof placeholders over π prefixes",
+    ));
+
+    let meta_wrap = art.meta.get("final_wrap").and_then(|v| v.as_str());
+    if art.meta.get("stream_format").is_some() {
+        out.push(Finding::new(
+            Severity::Info,
+            "stream-not-inspected",
+            "streaming artifact: gadgets in the proof stream were not inspected, only meta",
+        ));
+        if art
+            .meta
+            .get("params")
+            .is_none_or(serde_json::Value::is_null)
+        {
+            out.push(unbound_params());
+        }
+        if art
+            .meta
+            .get("late_bound")
+            .and_then(serde_json::Value::as_bool)
+            == Some(true)
+        {
+            out.push(Finding::new(
+                Severity::Info,
+                "late-bound",
+                "manifest root is bound at the end of the stream, after the blocks were folded",
+            ));
+        }
+        lint_final_wrap(meta_wrap, out);
+        return;
+    }
+
+    let c = match census(&art.proof_bytes) {
+        Ok(c) => c,
+        Err(e) => {
+            out.push(Finding::new(
+                Severity::High,
+                "undecodable",
+                format!("proof bytes do not decode as a fold envelope: {e:#}"),
+            ));
+            return;
+        }
+    };
+    if c.version < 3 {
+        out.push(Finding::new(
+            Severity::Low,
+            "deprecated-envelope",
+            format!("V{} envelope; run migrate-proof", c.version),
+        ));
+    }
+    if c.unopened_folds > 0 {
+        out.push(Finding::new(
+            Severity::High,
+            "unopened-folds",
+            format!(
+                "{} of {} fold proofs open no child π and no longer verify",
+                c.unopened_folds, c.folds
+            ),
+        ));
+    }
+    if c.are_mac > 0 {
+        out.push(Finding::new(
+            Severity::High,
+            "mac-are",
+            format!(
+                "{} of {} ARE proofs are the deprecated MAC variant",
+                c.are_mac, c.folds
+            ),
+        ));
+    }
+    if c.wraps_mac > 0 {
+        out.push(Finding::new(
+            Severity::Medium,
+            "mac-wrap",
+            format!("{} wrap attestations are transcript MACs", c.wraps_mac),
+        ));
+    }
+    match c.params {
+        None => out.push(unbound_params()),
+        Some(p) if p.are_samples == 0 => out.push(Finding::new(
+            Severity::Low,
+            "no-are-samples",
+            "leaf interiors are not sampled (are_samples = 0): only block boundaries are checked",
+        )),
+        Some(_) => {}
+    }
+    if !art.claims.is_empty() && !c.claims_bound {
+        out.push(Finding::new(
+            Severity::High,
+            "unbound-claims",
+            "artifact lists claims the bundle does not bind",
+        ));
+    }
+    if c.spot_checks == 0 {
+        out.push(Finding::new(
+            Severity::Info,
+            "no-spot-checks",
+            "no manifest-inclusion spot checks",
+        ));
+    }
+    lint_final_wrap(c.final_wrap.as_deref(), out);
+}
+
+fn unbound_params() -> Finding {
+    Finding::new(
+        Severity::Medium,
+        "unbound-params",
+        "no recorded fold parameters: verifiers cannot pin mode, cadence or window",
+    )
+}
+
+fn lint_final_wrap(id: Option<&str>, out: &mut Vec<Finding>) {
+    if id == Some(MacWrapBackend::ID) {
+        out.push(Finding::new(
+            Severity::Medium,
+            "mac-final-wrap",
+            format!(
+                "final wrap is the {} transcript-MAC reference, not a succinct proof",
+                MacWrapBackend::ID
+            ),
+        ));
+    }
+}
+
+/// Flags of `lint-proof`.
+#[derive(Args, Debug)]
+pub(crate) struct LintProofArgs {
+    /// Input path to the proof artifact (CBOR/JSON).
+    #[arg(long)]
+    pub proof: PathBuf,
+
+    /// Print the report as JSON.
+    #[arg(long, default_value_t = false)]
+    pub json: bool,
+
+    /// Exit with an error if any finding is at least this severe.
+    #[arg(long, value_enum, value_name = "SEVERITY")]
+    pub fail_on: Option<Severity>,
+}
+
+/// Lint the artifact at `--proof`, print the report, and fail if a finding
+/// reaches `--fail-on`.
+pub(crate) fn run(a: LintProofArgs) -> Result<()> {
+    let LintProofArgs {
+        proof: path,
+        json,
+        fail_on,
+    } = a;
+    let art = read_proof_auto(&path).with_context(|| format!("reading {}", path.display()))?;
+    let findings = lint(&art);
+    let max = findings.first().map(|f| f.severity);
+    if json {
+        let report = serde_json::json!({
+            "artifact": path.display().to_string(),
+            "backend": art.resolved_backend().to_string(),
+            "max_severity": max,
+            "findings": findings,
+        });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&report).context("serialize report")?
+        );
+    } else {
+        println!(
+            "Lint: {} ({} backend)",
+            path.display(),
+            art.resolved_backend()
+        );
+        if findings.is_empty() {
+            println!("  no findings");
+        }
+        for f in &findings {
+            println!("  {:<7} {:<22} {}", f.severity, f.code, f.message);
+        }
+    }
+    if let Some(level) = fail_on {
+        let n = findings.iter().filter(|f| f.severity >= level).count();
+        if n > 0 {
+            bail!("{n} finding(s) at or above {level}");
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Cli, Cmd};
+    use clap::Parser;
+    use sezkp_core::ProvingBackend;
+    use sezkp_trace::{generator::generate_trace, partition::partition_trace};
+
+    fn codes(findings: &[Finding]) -> Vec<&'static str> {
+        findings.iter().map(|f| f.code).collect()
+    }
+
+    #[test]
+    fn findings_are_ranked_by_severity() {
+        let blocks = partition_trace(&generate_trace(16, 2), 8);
+        let root = [3u8; 32];
+
+        let v0 = sezkp_stark::StarkIOP::prove(&blocks, root).unwrap();
+        assert_eq!(codes(&lint(&v0)), ["v0-scaffold"]);
+
+        let v1 = sezkp_stark::StarkV1::prove(&blocks, root).unwrap();
+        let found = lint(&v1);
+        assert_eq!(found[0].code, "low-queries");
+        assert_eq!(found[0].severity, Severity::Medium);
+        assert!(codes(&found).contains(&"unbound-input"));
+        assert!(found.windows(2).all(|w| w[0].severity >= w[1].severity));
+
+        let fold = sezkp_fold::FoldAgg::prove(&blocks, root).unwrap();
+        let found = lint(&fold);
+        assert!(codes(&found).contains(&"mac-fold"));
+        assert!(codes(&found).contains(&"no-are-samples"));
+        assert!(!codes(&found).contains(&"unbound-params"));
+        assert!(!codes(&found).contains(&"mac-are"));
+
+        let mut custom = fold;
+        custom.backend = BackendKind::from_proto("snark");
+        let found = lint(&custom);
+        assert_eq!(codes(&found), ["unknown-backend"]);
+        assert_eq!(found[0].severity, Severity::High);
+    }
+
+    #[test]
+    fn parse_lint_proof() {
+        let cli = Cli::parse_from([
+            "sezkp-cli",
+            "lint-proof",
+            "--proof",
+            "proof.cbor",
+            "--fail-on",
+            "medium",
+        ]);
+        assert!(matches!(
+            cli.cmd,
+            Cmd::LintProof(LintProofArgs {
+                json: false,
+                fail_on: Some(Severity::Medium),
+                ..
+            })
+        ));
+    }
+}
@@ -23,10 +23,83 @@
 //! sezkp-cli verify --backend fold --blocks blocks.jsonl --manifest manifest.cbor \
 //!   --proof proof.cbor
 //!
-//! # 5) Convert blocks to JSONL (NDJSON) for streaming
+//! # 5) Convert blocks to JSONL (NDJSON) for streaming (also writes the
+//! #    blocks.jsonl.idx seek index)
 //! sezkp-cli export-jsonl --input blocks.cbor --output blocks.jsonl
+//!
+//! # Export for public sharing: written symbols replaced by salted per-cell
+//! # commitments (commit the redacted file; movement/window proofs still work)
+//! sezkp-cli export-jsonl --input blocks.cbor --output public.jsonl --redact-key redact.key
+//!
+//! # Repair a file with shuffled or repeated blocks (external merge sort;
+//! # provers reject anything not in consecutive block_id order)
+//! sezkp-cli sort-blocks --input shuffled.jsonl --output blocks.jsonl
+//!
+//! # Print block 123456 without scanning the file from the start
+//! sezkp-cli show-block --blocks blocks.jsonl --block 123456
+//!
+//! # Print the boundary digests of blocks 41 and 42 and of the seam between
+//! # them, with the rows they hash (for a failed fold interface check)
+//! sezkp-cli boundary --blocks blocks.jsonl --index 41 --next
+//!
+//! # Dump the streaming fold driver's live stack after 100 blocks, diffed
+//! # against the batch Balanced driver at the same point
+//! sezkp-cli debug-fold --blocks blocks.jsonl --at 100 --diff
+//!
+//! # Re-verify in-process before writing; a failing proof is never written
+//! sezkp-cli prove --backend fold --blocks blocks.jsonl --manifest manifest.cbor \
+//!   --out proof.cbor --stream --self-check
+//!
+//! # Seal the finished fold proof with a final wrap backend (the transcript
+//! # MAC reference; an external SNARK plugs into the same slot)
+//! sezkp-cli prove --backend fold --blocks blocks.jsonl --manifest manifest.cbor \
+//!   --out proof.cbor --final-wrap mac-v1
+//!
+//! # Bundle per-shard proofs into one composite, then verify it shard by shard
+//! # (`compose --artifact` writes it as a single proof artifact instead)
+//! sezkp-cli compose --child shard0/proof.cbor --child shard1/proof.cbor --out composite.cbor
+//! sezkp-cli verify-composite --composite composite.cbor \
+//!   --blocks shard0/blocks.jsonl --manifest shard0/manifest.cbor \
+//!   --blocks shard1/blocks.jsonl --manifest shard1/manifest.cbor
+//!
+//! # Long verifications report progress/ETA: a bar on a terminal, or
+//! # periodic log lines (e.g. under CI); `--progress off` silences both
+//! sezkp-cli --progress log verify --backend fold --blocks blocks.jsonl \
+//!   --manifest manifest.cbor --proof proof.cbor
+//!
+//! # Per-phase prover timing and peak temporary memory of a STARK proof
+//! sezkp-cli inspect --proof proof.cbor
+//!
+//! # Predict proof/stream sizes before proving (provision storage up front)
+//! sezkp-cli estimate --backend fold --n-blocks 65536 --rows 512 --wrap-cadence 8
+//!
+//! # Commit and prove straight from a trace: blocks are cut on the fly and
+//! # never written to disk (same --b/--partition for both)
+//! sezkp-cli commit --trace trace.cbor --b 512 --out manifest.cbor
+//! sezkp-cli prove --backend fold --trace trace.cbor --b 512 --manifest manifest.cbor \
+//!   --out proof.cbor --stream
+//!
+//! # Print the execution plan for a prove run without doing any work
+//! # (includes a peak-memory estimate, checked against available RAM)
+//! sezkp-cli prove --backend stark --blocks blocks.jsonl --manifest manifest.cbor --dry-run
+//!
+//! # Let the manifest's size pick the backend: STARK v1 up to 16384 rows,
+//! # streaming fold beyond (the choice is recorded in the artifact meta)
+//! sezkp-cli prove --backend auto --blocks blocks.jsonl --manifest manifest.cbor
+//!
+//! # Pin the protocol parameters in a file, prove under it, and require it
+//! # (its digest is bound into the proof as the claim `sezkp.params`)
+//! sezkp-cli gen-params --out params.cbor --wrap-cadence 8
+//! sezkp-cli prove --backend fold --blocks blocks.jsonl --manifest manifest.cbor --params params.cbor
+//! sezkp-cli verify --backend fold --blocks blocks.jsonl --manifest manifest.cbor \
+//!   --proof proof.cbor --params params.cbor
+//!
+//! # Characterize this machine: time the whole pipeline on synthetic data
+//! # in a scratch directory (timings, file sizes, peak RSS per stage)
+//! sezkp-cli bench --preset medium --backend fold
 //! ```
-#![forbid(unsafe_code)]
+// Unsafe code is confined to registering the signal handler (`interrupt`).
+#![deny(unsafe_code)]
 #![deny(
     rust_2018_idioms,
     clippy::unwrap_used,
@@ -35,28 +108,34 @@
     clippy::todo
 )]
 
-use anyhow::{bail, Context, Result};
-use clap::{Parser, Subcommand, ValueEnum};
-use sezkp_core::{
-    io::{
-        read_block_summaries_auto, read_proof_auto, stream_block_summaries_auto, write_proof_auto,
-    },
-    ProofArtifact,
-    ProvingBackend,
-};
-use std::fs::File;
-use std::io::{BufWriter, Write};
-use std::path::{Path, PathBuf};
-use tracing::{info, info_span};
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+use sezkp_core::render::{set_root_format, RootFormat};
+use std::path::Path;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
-/// Environment keys used by backends (centralized to avoid typos).
-mod envkeys {
-    pub const FOLD_MODE: &str = "SEZKP_FOLD_MODE";
-    pub const FOLD_CACHE: &str = "SEZKP_FOLD_CACHE";
-    pub const WRAP_CADENCE: &str = "SEZKP_WRAP_CADENCE";
-    pub const PROOF_STREAM_PATH: &str = "SEZKP_PROOF_STREAM_PATH";
-}
+mod args;
+mod bench;
+mod blocks;
+mod commit;
+mod composite;
+mod debug;
+mod envguard;
+mod estimate;
+mod follow;
+mod inspect;
+mod interrupt;
+mod lint;
+mod migrate;
+mod params;
+mod plan;
+mod progress;
+mod prove;
+mod show;
+mod verify;
+
+use args::{envkeys, ReplayFlags};
+use progress::{install_progress, ProgressOpt};
 
 /// Top-level CLI.
 ///
@@ -74,6 +153,74 @@ mod envkeys {
     disable_help_subcommand = true
 )]
 struct Cli {
+    /// Reject unknown or missing file extensions instead of guessing
+    /// (also enabled by `SEZKP_STRICT_IO=1`).
+    #[arg(long, global = true)]
+    strict_io: bool,
+
+    /// Read every blocks input as this format (`json`, `cbor`, `jsonl`),
+    /// skipping extension/content detection. Useful for ambiguous files such
+    /// as a single-record JSON Lines file without an extension.
+    #[arg(long, global = true, value_parser = sezkp_core::io_format::FileFormat::parse)]
+    blocks_format: Option<sezkp_core::io_format::FileFormat>,
+
+    /// Reject any blocks record (JSONL line or array element) larger than
+    /// this many bytes (default 256 MiB; also `SEZKP_MAX_RECORD_BYTES`).
+    #[arg(long, global = true, value_name = "BYTES")]
+    max_record_bytes: Option<u64>,
+
+    /// Reject any block whose movement log has more steps than this
+    /// (default 4194304; also `SEZKP_MAX_BLOCK_STEPS`).
+    #[arg(long, global = true, value_name = "STEPS")]
+    max_block_steps: Option<u64>,
+
+    /// Reject any block with more work tapes than this (default 64; also
+    /// `SEZKP_MAX_TAU`).
+    #[arg(long, global = true, value_name = "TAU")]
+    max_tau: Option<u64>,
+
+    /// Reject a JSONL blocks file that does not end with its
+    /// `{"sezkp_eof":N}` marker, so a truncated file fails instead of
+    /// committing or proving a prefix (also `SEZKP_REQUIRE_JSONL_EOF=1`).
+    #[arg(long, global = true)]
+    require_jsonl_eof: bool,
+
+    /// Worker threads for parallel JSONL parsing and leaf hashing (`0`: one
+    /// per core, the default; also `SEZKP_THREADS`). Outputs do not depend
+    /// on it.
+    #[arg(long, global = true, value_name = "N")]
+    threads: Option<usize>,
+
+    /// How printed roots are rendered: `hex`, `0x` or `base64url`, optionally
+    /// with `+check` for a checksum suffix (e.g. `0x+check`).
+    #[arg(long, global = true, value_parser = RootFormat::parse)]
+    root_format: Option<RootFormat>,
+
+    /// Progress reporting for long passes (replay, stream verification):
+    /// `bar` on stderr, periodic `log` lines, `off`, or `auto` (bar on a
+    /// terminal, log lines otherwise).
+    #[arg(long, global = true, value_enum, default_value_t = ProgressOpt::Auto)]
+    progress: ProgressOpt,
+
+    /// Accept blocks whose work-tape windows do not contain the previous
+    /// block's exit heads, for VMs that legitimately re-window a tape
+    /// (fold replay checks window continuity by default). Fold proofs made
+    /// with it declare so, and are only accepted by a verifier passing it.
+    #[arg(long, global = true)]
+    allow_rewindow: bool,
+
+    /// Reject blocks whose declared exit heads differ from the positions
+    /// their movement logs reach (fold replay treats the declared endpoints
+    /// as authoritative by default). `check-endpoints` lists the deltas.
+    #[arg(long, global = true)]
+    strict_endpoints: bool,
+
+    /// Clear every inherited `SEZKP_*` environment variable at startup, so
+    /// only flags and defaults reach the backends. Without it, inherited
+    /// variables are listed, and a warning names any a flag overrides.
+    #[arg(long, global = true)]
+    ignore_env: bool,
+
     #[command(subcommand)]
     cmd: Cmd,
 }
@@ -84,195 +231,225 @@ enum Cmd {
     /// Simulate a synthetic trace and partition it into σ_k blocks.
     ///
     /// If --out-blocks ends with `.jsonl` or `.ndjson`, writes NDJSON for streaming.
-    Simulate {
-        /// Trace length T (> 0).
-        #[arg(long, default_value_t = 32, value_parser = clap::value_parser!(u32).range(1..))]
-        t: u32,
+    Simulate(blocks::SimulateArgs),
 
-        /// Number of blocks b (1..=T).
-        #[arg(long, default_value_t = 4, value_parser = clap::value_parser!(u32).range(1..))]
-        b: u32,
+    /// Commit blocks to a Merkle root and write a manifest.
+    Commit(commit::CommitArgs),
 
-        /// Parameter τ (> 0), e.g., branching/arity in the synthetic generator.
-        #[arg(long, default_value_t = 2, value_parser = clap::value_parser!(u8).range(1..))]
-        tau: u8,
+    /// Check that a blocks file matches a manifest.
+    ///
+    /// Reports progress per block. When the manifest carries checkpoints
+    /// (`commit --checkpoint-log2`), a mismatch stops at the first diverging
+    /// run of blocks and names its range.
+    VerifyCommit(commit::VerifyCommitArgs),
 
-        /// Output path for σ_k block summaries (CBOR/JSON/JSONL/NDJSON).
-        #[arg(long, default_value = "blocks.cbor")]
-        out_blocks: PathBuf,
-    },
+    /// Write the Merkle inclusion proof of one block under a manifest, in
+    /// the documented `sezkp-inclusion-v1` JSON/CBOR schema that other
+    /// languages can check without this code.
+    InclusionProof(commit::InclusionProofArgs),
 
-    /// Commit blocks to a Merkle root and write a manifest.
-    Commit {
-        /// Input path to σ_k block summaries (CBOR/JSON/JSONL/NDJSON).
-        #[arg(long)]
-        blocks: PathBuf,
+    /// Check an inclusion proof (`inclusion-proof`) against a manifest and,
+    /// optionally, the block it claims to include.
+    VerifyInclusion(commit::VerifyInclusionArgs),
 
-        /// Output path for the manifest (CBOR/JSON).
-        #[arg(long, default_value = "manifest.cbor")]
-        out: PathBuf,
-    },
+    /// Convert blocks (CBOR/JSON/JSONL/NDJSON) → JSON Lines (NDJSON) for streaming proofs.
+    ExportJsonl(blocks::ExportJsonlArgs),
 
-    /// Check that a blocks file matches a manifest.
-    VerifyCommit {
-        /// Input path to σ_k block summaries (CBOR/JSON/JSONL/NDJSON).
-        #[arg(long)]
-        blocks: PathBuf,
+    /// Sort blocks by `block_id` into JSONL, dropping exact duplicates.
+    ///
+    /// Runs an external merge sort, so files larger than memory are fine.
+    /// Two different blocks with the same id cannot be repaired and fail.
+    SortBlocks(blocks::SortBlocksArgs),
 
-        /// Input path to manifest (CBOR/JSON).
-        #[arg(long)]
-        manifest: PathBuf,
-    },
+    /// Recompute each block's windows as the smallest covering every head
+    /// position its movement log visits, and write the blocks as JSONL.
+    ///
+    /// Head offsets are re-derived against the new windows; the absolute
+    /// entry and exit heads are kept. Use it on adapter output whose windows
+    /// fail the visited-cells replay check.
+    RepairWindows(blocks::RepairWindowsArgs),
 
-    /// Convert blocks (CBOR/JSON/JSONL/NDJSON) → JSON Lines (NDJSON) for streaming proofs.
-    ExportJsonl {
-        /// Input blocks path (CBOR/JSON/JSONL/NDJSON).
-        #[arg(long)]
-        input: PathBuf,
-        /// Output JSONL path.
-        #[arg(long)]
-        output: PathBuf,
-    },
+    /// Write a reduced but consistent block file for quick iteration:
+    /// every `n`-th block (`--every`) or a contiguous prefix (`--prefix`).
+    ///
+    /// Kept blocks are renumbered, their step ranges re-based and their
+    /// windows and input head translated so each one starts where the
+    /// previous kept block left off; moves and writes are unchanged. Use it
+    /// to size parameters on a small file before a full-scale run.
+    SampleBlocks(blocks::SampleBlocksArgs),
+
+    /// Compare each block's declared exit heads with the ones its movement
+    /// log reaches and report every difference, per block, field and tape.
+    ///
+    /// Prints one line per delta, or writes them as JSON records with
+    /// `--out`. Fails if any block has a delta, which is what the global
+    /// `--strict-endpoints` rejects.
+    CheckEndpoints(show::CheckEndpointsArgs),
 
-    /// Produce a ZK proof with the chosen backend.
-    Prove {
-        /// Proof backend.
-        #[arg(value_enum, long)]
-        backend: BackendOpt,
-
-        /// Input path to σ_k block summaries (CBOR/JSON/JSONL/NDJSON).
-        ///
-        /// For sublinear memory, prefer `.jsonl`/`.ndjson` + `--stream`.
-        #[arg(long)]
-        blocks: PathBuf,
-
-        /// Input path to manifest (CBOR/JSON).
-        #[arg(long)]
-        manifest: PathBuf,
-
-        /// Output path for proof artifact (CBOR/JSON).
-        #[arg(long, default_value = "proof.cbor")]
-        out: PathBuf,
-
-        /* ---------- fold-specific flags (honored via environment) ---------- */
-        /// Folding driver mode (balanced keeps O(T) endpoints; minram recomputes).
-        #[arg(long, value_enum, default_value_t = FoldModeOpt::Balanced)]
-        fold_mode: FoldModeOpt,
-
-        /// Endpoint LRU capacity for minram mode (0 disables caching).
-        #[arg(long, default_value_t = 64)]
-        fold_cache: usize,
-
-        /// Emit a wrap proof every k folds (0 = never).
-        #[arg(long, default_value_t = 0)]
-        wrap_cadence: u32,
-
-        /// Stream blocks instead of loading all into memory.
-        ///
-        /// Effective with `.jsonl`/`.ndjson` inputs; `.json`/`.cbor` may degrade to in-memory iteration.
-        #[arg(long, default_value_t = false)]
-        stream: bool,
-
-        /// Assume the blocks file has already been verified against the manifest.
-        ///
-        /// Skips the extra pre-check inside `prove` to avoid redundant I/O/RSS.
-        #[arg(long, default_value_t = false)]
-        assume_committed: bool,
-    },
-
-    /// Verify a proof produced by the chosen backend.
-    Verify {
-        /// Proof backend.
-        #[arg(value_enum, long)]
-        backend: BackendOpt,
-
-        /// Input path to σ_k block summaries (CBOR/JSON/JSONL/NDJSON).
-        ///
-        /// For sublinear memory, prefer `.jsonl`/`.ndjson`.
-        #[arg(long)]
-        blocks: PathBuf,
-
-        /// Input path to manifest (CBOR/JSON).
-        #[arg(long)]
-        manifest: PathBuf,
-
-        /// Input path to proof artifact (CBOR/JSON).
-        #[arg(long)]
-        proof: PathBuf,
-
-        /// Assume the blocks file has already been verified against the manifest.
-        ///
-        /// Skips the extra pre-check inside `verify` to avoid redundant I/O/RSS.
-        #[arg(long, default_value_t = false)]
-        assume_committed: bool,
-    },
-}
+    /// Print one block summary as pretty JSON.
+    ///
+    /// JSONL inputs with a `.idx` sidecar (see `export-jsonl`) seek straight
+    /// to the block instead of scanning from the start.
+    ShowBlock(show::ShowBlockArgs),
 
-/// Available proving/verification backends.
-#[derive(Copy, Clone, Eq, PartialEq, Debug, ValueEnum)]
-enum BackendOpt {
-    /// Folding-based aggregation backend.
-    Fold,
-    /// STARK v1 backend (PIOP/FRI; streaming-friendly).
-    Stark,
+    /// Print the first `n` block summaries, one JSON object per line.
+    ///
+    /// Stops reading after block `n`, so peeking at a huge file is cheap.
+    HeadBlocks(show::EdgeBlocksArgs),
+
+    /// Print the last `n` block summaries, one JSON object per line.
+    ///
+    /// JSONL inputs are read from the end (or through their `.idx` sidecar),
+    /// so only the tail is touched; other formats are streamed keeping the
+    /// last `n` blocks.
+    TailBlocks(show::EdgeBlocksArgs),
+
+    /// Check an interface witness log (`prove --stream --iface-log`)
+    /// against the blocks it was proved over.
+    ///
+    /// Every logged seam's block ids, control values and digest are
+    /// re-derived from the blocks; the proof itself is not read.
+    CheckIfaceLog(show::CheckIfaceLogArgs),
+
+    /// Recompute and print the boundary digests of one block, with the
+    /// offsets and boundary rows they hash, to debug a failed fold
+    /// interface check.
+    Boundary(show::BoundaryArgs),
+
+    /// Write a parameter file pinning every protocol parameter, for
+    /// `prove --params` / `verify --params`, and print its digest.
+    GenParams(params::GenParamsArgs),
+
+    /// Produce a ZK proof with the chosen backend.
+    Prove(prove::ProveArgs),
+
+    /// Verify a proof with the backend that produced it.
+    Verify(verify::VerifyArgs),
+
+    /// Bundle per-shard proof artifacts into one composite artifact.
+    Compose(composite::ComposeArgs),
+
+    /// Verify a composite artifact: its binding and every child proof.
+    VerifyComposite(composite::VerifyCompositeArgs),
+
+    /// Run the streaming fold driver up to block N and dump its live stack.
+    DebugFold(debug::DebugFoldArgs),
+
+    /// Tell what a file is (manifest, proof artifact, blocks or fold proof
+    /// stream) from its content and summarize it, including the STARK
+    /// prover's per-phase timing and temporary-memory breakdown when recorded.
+    #[command(visible_alias = "stats")]
+    Inspect(inspect::InspectArgs),
+
+    /// Upgrade a fold artifact from a deprecated bincode-framed envelope (V1
+    /// with a JSON bundle, or V2) to the CBOR V3 envelope, keeping its
+    /// roots, and replace the legacy `stark` backend label with `fold`. V3
+    /// artifacts that are already labelled are copied unchanged.
+    MigrateProof(migrate::MigrateProofArgs),
+
+    /// Report known-weak or placeholder constructions in a proof artifact
+    /// (v0 scaffold proofs, MAC-based gadgets, missing grinding, low query
+    /// counts, unbound parameters), highest severity first. Nothing is
+    /// verified.
+    LintProof(lint::LintProofArgs),
+
+    /// Replay a STARK v1 proof's Fiat–Shamir transcript and write every
+    /// challenge the verifier draws (alphas, mask coefficients, OOD point,
+    /// FRI betas, query positions) as JSON. Nothing is verified.
+    Challenges(debug::ChallengesArgs),
+
+    /// Predict proof (and fold stream) sizes for a trace shape, without
+    /// proving. STARK figures are exact; fold figures are calibrated by
+    /// proving two tiny blocks and are typically within 1%.
+    Estimate(estimate::EstimateArgs),
+
+    /// Run generate → partition → commit → prove → verify on synthetic data
+    /// in a scratch directory and print timings, file sizes and peak memory
+    /// per stage.
+    Bench(bench::BenchArgs),
 }
 
-/// Folding driver modes.
-#[derive(Copy, Clone, Eq, PartialEq, Debug, ValueEnum)]
-enum FoldModeOpt {
-    /// Balanced space/time (keeps O(T) endpoints).
-    Balanced,
-    /// Min-RAM mode (recompute endpoints; sublinear space).
-    Minram,
+fn main() -> std::process::ExitCode {
+    use std::process::ExitCode;
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {e:?}");
+            if e.downcast_ref::<sezkp_core::cancel::Cancelled>().is_some() {
+                ExitCode::from(interrupt::EXIT_CANCELLED)
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+    }
 }
 
-fn main() -> Result<()> {
+fn run() -> Result<()> {
     init_tracing();
 
     let cli = Cli::parse();
+    envguard::capture(cli.ignore_env);
+    if cli.strict_io {
+        sezkp_core::io_format::set_strict_io(true);
+    }
+    if cli.require_jsonl_eof {
+        sezkp_core::io_jsonl::set_require_jsonl_eof(true);
+    }
+    if cli.blocks_format.is_some() {
+        sezkp_core::io_format::set_format_override("blocks", cli.blocks_format);
+    }
+    if let Some(f) = cli.root_format {
+        set_root_format(f);
+    }
+    let flags = ReplayFlags {
+        allow_rewindow: cli.allow_rewindow,
+        strict_endpoints: cli.strict_endpoints,
+    };
+    if cli.allow_rewindow {
+        // Fold proofs declare the policy, and their verifier accepts it.
+        envguard::set(envkeys::FOLD_ALLOW_REWINDOW, "1");
+    }
+    if cli.max_record_bytes.is_some() || cli.max_block_steps.is_some() || cli.max_tau.is_some() {
+        let mut limits = sezkp_core::read_limits::read_limits()?;
+        limits.max_record_bytes = cli.max_record_bytes.unwrap_or(limits.max_record_bytes);
+        limits.max_block_steps = cli.max_block_steps.unwrap_or(limits.max_block_steps);
+        limits.max_tau = cli.max_tau.unwrap_or(limits.max_tau);
+        sezkp_core::read_limits::set_read_limits(limits);
+    }
+    let mut par = sezkp_core::parallelism::parallelism()?;
+    if let Some(n) = cli.threads {
+        par.threads = std::num::NonZeroUsize::new(n);
+        sezkp_core::parallelism::set_parallelism(par);
+    }
+    install_progress(cli.progress);
     match cli.cmd {
-        Cmd::Simulate {
-            t,
-            b,
-            tau,
-            out_blocks,
-        } => simulate(t, b, tau, out_blocks),
-
-        Cmd::Commit { blocks, out } => commit_blocks(blocks, out),
-
-        Cmd::VerifyCommit { blocks, manifest } => verify_commit(blocks, manifest),
-
-        Cmd::ExportJsonl { input, output } => export_jsonl(input, output),
-
-        Cmd::Prove {
-            backend,
-            blocks,
-            manifest,
-            out,
-            fold_mode,
-            fold_cache,
-            wrap_cadence,
-            stream,
-            assume_committed,
-        } => prove(
-            backend,
-            blocks,
-            manifest,
-            out,
-            fold_mode,
-            fold_cache,
-            wrap_cadence,
-            stream,
-            assume_committed,
-        ),
-
-        Cmd::Verify {
-            backend,
-            blocks,
-            manifest,
-            proof,
-            assume_committed,
-        } => verify(backend, blocks, manifest, proof, assume_committed),
+        Cmd::Simulate(a) => blocks::simulate(a),
+        Cmd::Commit(a) => commit::commit_blocks(a),
+        Cmd::VerifyCommit(a) => commit::verify_commit(a),
+        Cmd::InclusionProof(a) => commit::export_inclusion_proof(a),
+        Cmd::VerifyInclusion(a) => commit::verify_inclusion(a),
+        Cmd::ExportJsonl(a) => blocks::export_jsonl(a),
+        Cmd::SortBlocks(a) => blocks::sort_blocks(a),
+        Cmd::RepairWindows(a) => blocks::repair_windows(a),
+        Cmd::SampleBlocks(a) => blocks::sample_blocks(a),
+        Cmd::CheckEndpoints(a) => show::check_endpoints(a),
+        Cmd::ShowBlock(a) => show::show_block(a),
+        Cmd::HeadBlocks(a) => show::edge_blocks(a, false),
+        Cmd::TailBlocks(a) => show::edge_blocks(a, true),
+        Cmd::CheckIfaceLog(a) => show::check_iface_log(a),
+        Cmd::Boundary(a) => show::boundary(a),
+        Cmd::GenParams(a) => params::gen_params(a),
+        Cmd::Prove(a) => prove::run(a, flags),
+        Cmd::Verify(a) => verify::run(a, flags),
+        Cmd::Compose(a) => composite::compose(a),
+        Cmd::VerifyComposite(a) => composite::verify_composite(a, flags),
+        Cmd::DebugFold(a) => debug::debug_fold(a),
+        Cmd::Inspect(a) => inspect::run(a),
+        Cmd::MigrateProof(a) => migrate::migrate_proof(a),
+        Cmd::LintProof(a) => lint::run(a),
+        Cmd::Challenges(a) => debug::challenges(a),
+        Cmd::Estimate(a) => estimate::estimate(a),
+        Cmd::Bench(a) => bench::run_bench(a),
     }
 }
 
@@ -280,11 +457,25 @@ fn main() -> Result<()> {
 ///
 /// Set `RUST_LOG=debug` (or `trace`) to increase verbosity, e.g.:
 /// `RUST_LOG=sezkp_cli=debug,sezkp_core=info sezkp-cli prove ...`
+///
+/// When `RUST_LOG` is set, span closes are logged with their busy/idle time,
+/// so `RUST_LOG=debug` reports per-phase timings of the backends
+/// (`stark.lde`, `stark.fri_commit`, `fold.fold`, …).
 fn init_tracing() {
-    use tracing_subscriber::{fmt, EnvFilter};
+    use tracing_subscriber::{fmt, fmt::format::FmtSpan, EnvFilter};
 
-    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
-    let fmt_layer = fmt::layer().with_target(false).with_level(true).compact();
+    let from_env = EnvFilter::try_from_default_env();
+    let span_events = if from_env.is_ok() {
+        FmtSpan::CLOSE
+    } else {
+        FmtSpan::NONE
+    };
+    let filter = from_env.unwrap_or_else(|_| EnvFilter::new("info"));
+    let fmt_layer = fmt::layer()
+        .with_target(false)
+        .with_level(true)
+        .with_span_events(span_events)
+        .compact();
 
     let _ = tracing_subscriber::registry()
         .with(filter)
@@ -296,7 +487,7 @@ fn init_tracing() {
 ///
 /// # Errors
 /// Returns an error if the directory cannot be created.
-fn ensure_parent_dir(path: &Path) -> Result<()> {
+pub(crate) fn ensure_parent_dir(path: &Path) -> Result<()> {
     if let Some(dir) = path.parent() {
         if !dir.as_os_str().is_empty() {
             std::fs::create_dir_all(dir)
@@ -306,300 +497,119 @@ fn ensure_parent_dir(path: &Path) -> Result<()> {
     Ok(())
 }
 
-/// Return `true` if the path’s extension suggests JSON Lines (`.jsonl` or `.ndjson`).
-fn is_jsonl_like(path: &Path) -> bool {
-    path.extension()
-        .and_then(|e| e.to_str())
-        .map(|s| s.to_ascii_lowercase())
-        .is_some_and(|ext| ext == "jsonl" || ext == "ndjson")
-}
-
-fn simulate(t: u32, b: u32, tau: u8, out_blocks: PathBuf) -> Result<()> {
-    let _span = info_span!("simulate", t, b, tau, out = %out_blocks.display()).entered();
-    use sezkp_trace::{generator::generate_trace, partition::partition_trace};
-
-    if b > t {
-        bail!("number of blocks b ({b}) cannot exceed trace length T ({t})");
-    }
-
-    info!("generating synthetic trace");
-    let trace = generate_trace(t as u64, tau);
-    let blocks = partition_trace(&trace, b);
-
-    ensure_parent_dir(&out_blocks)?;
-
-    // If the extension is .jsonl/.ndjson, write NDJSON for streaming.
-    if is_jsonl_like(&out_blocks) {
-        let f = File::create(&out_blocks)
-            .with_context(|| format!("create {}", out_blocks.display()))?;
-        let mut w = BufWriter::new(f);
-        for blk in &blocks {
-            serde_json::to_writer(&mut w, blk).context("serialize block as JSON line")?;
-            w.write_all(b"\n")?;
-        }
-        w.flush()?;
-    } else {
-        sezkp_core::io::write_block_summaries_auto(&out_blocks, &blocks).with_context(|| {
-            format!("writing σ_k blocks (auto format) to {}", out_blocks.display())
-        })?;
-    }
-
-    println!(
-        "Simulated trace: T={}, b={}, τ={} → {} blocks → {}",
-        t,
-        b,
-        tau,
-        blocks.len(),
-        out_blocks.display()
-    );
-    Ok(())
-}
-
-fn commit_blocks(blocks: PathBuf, out: PathBuf) -> Result<()> {
-    let _span = info_span!("commit", blocks = %blocks.display(), out = %out.display()).entered();
-    use sezkp_merkle::commit_block_file;
-
-    info!("committing blocks");
-    ensure_parent_dir(&out)?;
-
-    commit_block_file(&blocks, &out).with_context(|| {
-        format!(
-            "committing {} to manifest {}",
-            blocks.display(),
-            out.display()
-        )
-    })?;
-
-    println!("Committed {} → {}", blocks.display(), out.display());
-    Ok(())
-}
-
-fn verify_commit(blocks: PathBuf, manifest: PathBuf) -> Result<()> {
-    let _span =
-        info_span!("verify_commit", blocks = %blocks.display(), manifest = %manifest.display())
-            .entered();
-    use sezkp_merkle::verify_block_file_against_manifest;
-
-    info!("verifying commit");
-    verify_block_file_against_manifest(&blocks, &manifest).with_context(|| {
-        format!(
-            "verifying that {} matches manifest {}",
-            blocks.display(),
-            manifest.display()
-        )
-    })?;
-
-    println!(
-        "OK: {} matches manifest {}",
-        blocks.display(),
-        manifest.display()
-    );
-    Ok(())
-}
-
-/// Convert any blocks file (CBOR/JSON/JSONL/NDJSON) into JSON Lines for streaming proofs.
-///
-/// # Errors
-/// Propagates I/O and serialization errors.
-fn export_jsonl(input: PathBuf, output: PathBuf) -> Result<()> {
-    let _span =
-        info_span!("export_jsonl", infile = %input.display(), outfile = %output.display())
-            .entered();
-    info!("opening input stream");
-    let iter = stream_block_summaries_auto(&input).context("open input stream")?;
-
-    ensure_parent_dir(&output)?;
-    let f = File::create(&output).with_context(|| format!("create {}", output.display()))?;
-    let mut w = BufWriter::new(f);
-
-    let mut n = 0usize;
-    for item in iter {
-        let blk = item?;
-        serde_json::to_writer(&mut w, &blk).context("serialize block as JSON line")?;
-        w.write_all(b"\n")?;
-        n += 1;
-    }
-    w.flush()?;
-
-    println!("Exported {n} blocks → {}", output.display());
-    Ok(())
-}
-
-#[allow(clippy::too_many_arguments)]
-fn prove(
-    backend: BackendOpt,
-    blocks: PathBuf,
-    manifest: PathBuf,
-    out: PathBuf,
-    fold_mode: FoldModeOpt,
-    fold_cache: usize,
-    wrap_cadence: u32,
-    stream: bool,
-    assume_committed: bool,
-) -> Result<()> {
-    let _span = info_span!(
-        "prove",
-        ?backend,
-        blocks = %blocks.display(),
-        manifest = %manifest.display(),
-        out = %out.display(),
-        stream
-    )
-    .entered();
-
-    use sezkp_core::prover::StreamingProver;
-    use sezkp_merkle::{read_manifest_auto, verify_block_file_against_manifest};
-
-    // Skip redundant blocks/manifest pre-check if caller already verified it.
-    if !assume_committed {
-        verify_block_file_against_manifest(&blocks, &manifest)
-            .context("blocks/manifest mismatch")?;
-    }
-
-    let man = read_manifest_auto(&manifest).context("reading manifest")?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use commit::VerifyCommitArgs;
 
-    // Honor fold-driver flags via env vars the backend reads at prove-time.
-    if matches!(backend, BackendOpt::Fold) {
-        std::env::set_var(
-            envkeys::FOLD_MODE,
-            match fold_mode {
-                FoldModeOpt::Balanced => "balanced",
-                FoldModeOpt::Minram => "minram",
-            },
+    #[test]
+    fn parse_strict_io_after_subcommand() {
+        let cli = Cli::parse_from([
+            "sezkp-cli",
+            "export-jsonl",
+            "--input",
+            "blocks.cbor",
+            "--output",
+            "blocks.jsonl",
+            "--strict-io",
+            "--blocks-format",
+            "ndjson",
+        ]);
+        assert!(cli.strict_io);
+        assert_eq!(
+            cli.blocks_format,
+            Some(sezkp_core::io_format::FileFormat::Jsonl)
         );
-        std::env::set_var(envkeys::FOLD_CACHE, fold_cache.to_string());
-        std::env::set_var(envkeys::WRAP_CADENCE, wrap_cadence.to_string());
-    }
-
-    // Choose streaming path iff requested.
-    let artifact: ProofArtifact = match (backend, stream) {
-        (BackendOpt::Fold, true) => {
-            use sezkp_fold::FoldAgg;
-
-            // Decide on a proof stream path adjacent to the artifact.
-            let mut stream_path = out.clone();
-            stream_path.set_extension("cborseq");
-            // Tell the backend where to write the streaming proof.
-            std::env::set_var(envkeys::PROOF_STREAM_PATH, &stream_path);
-
-            let iter = stream_block_summaries_auto(&blocks).context("open blocks stream")?;
-            let art = StreamingProver::<FoldAgg>::prove_stream_iter(iter, man.root)
-                .context("fold backend streaming proof failed")?;
-
-            println!(
-                "Proved (streaming/fold) → artifact={} stream={}",
-                out.display(),
-                stream_path.display()
-            );
-            art
-        }
-        (BackendOpt::Fold, false) => {
-            use sezkp_fold::FoldAgg;
-            let blocks_vec = read_block_summaries_auto(&blocks).context("reading blocks")?;
-            StreamingProver::<FoldAgg>::prove(&blocks_vec, man.root)
-                .context("fold backend proof failed")?
-        }
-        // --- STARK v1 path (always ZK). Prefer streaming entrypoint when asked.
-        (BackendOpt::Stark, true) => {
-            use sezkp_stark::StarkV1;
-            let blocks_vec = read_block_summaries_auto(&blocks).context("reading blocks")?;
-            StarkV1::prove_streaming(&blocks_vec, man.root)
-                .context("stark-v1 streaming proof failed")?
-        }
-        (BackendOpt::Stark, false) => {
-            use sezkp_stark::StarkV1;
-            let blocks_vec = read_block_summaries_auto(&blocks).context("reading blocks")?;
-            StarkV1::prove(&blocks_vec, man.root).context("stark-v1 proof failed")?
-        }
-    };
-
-    ensure_parent_dir(&out)?;
-    write_proof_auto(&out, &artifact)
-        .with_context(|| format!("writing proof to {}", out.display()))?;
-
-    println!(
-        "Proved with {:?}, wrote {} ({} bytes)",
-        artifact.backend,
-        out.display(),
-        artifact.proof_bytes.len()
-    );
-    Ok(())
-}
-
-fn verify(
-    backend: BackendOpt,
-    blocks: PathBuf,
-    manifest: PathBuf,
-    proof: PathBuf,
-    assume_committed: bool,
-) -> Result<()> {
-    let _span = info_span!(
-        "verify",
-        ?backend,
-        blocks = %blocks.display(),
-        manifest = %manifest.display(),
-        proof = %proof.display()
-    )
-    .entered();
-
-    use sezkp_core::prover::StreamingProver;
-    use sezkp_merkle::{read_manifest_auto, verify_block_file_against_manifest};
-
-    // Skip redundant blocks/manifest pre-check if caller already verified it.
-    if !assume_committed {
-        verify_block_file_against_manifest(&blocks, &manifest)
-            .context("blocks/manifest mismatch")?;
     }
 
-    let man = read_manifest_auto(&manifest).context("reading manifest")?;
-    let artifact = read_proof_auto(&proof)
-        .with_context(|| format!("reading proof artifact from {}", proof.display()))?;
-
-    match backend {
-        BackendOpt::Fold => {
-            use sezkp_fold::FoldAgg;
-
-            // Prefer streaming verify to keep memory sublinear.
-            let iter = stream_block_summaries_auto(&blocks).context("open blocks stream")?;
-            StreamingProver::<FoldAgg>::verify_stream_iter(&artifact, iter, man.root)
-                .context("fold backend verification failed")?;
-        }
-        BackendOpt::Stark => {
-            // v1 STARK verifier (manifest-root checked inside).
-            use sezkp_stark::StarkV1;
-            let blocks_vec = read_block_summaries_auto(&blocks).context("reading blocks")?;
-            StarkV1::verify(&artifact, &blocks_vec, man.root)
-                .context("stark-v1 verification failed")?;
-        }
+    #[test]
+    fn parse_read_limits_after_subcommand() {
+        let cli = Cli::parse_from([
+            "sezkp-cli",
+            "verify-commit",
+            "--blocks",
+            "blocks.jsonl",
+            "--manifest",
+            "manifest.cbor",
+            "--max-record-bytes",
+            "1048576",
+            "--max-tau",
+            "4",
+        ]);
+        assert_eq!(cli.max_record_bytes, Some(1 << 20));
+        assert_eq!(cli.max_block_steps, None);
+        assert_eq!(cli.max_tau, Some(4));
+        assert_eq!(cli.threads, None);
+        assert!(!cli.require_jsonl_eof);
     }
 
-    println!("OK: proof verified");
-    Ok(())
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
     #[test]
-    fn parse_commit_smoke() {
-        // Ensure subcommand/args parse; do not run anything.
-        let _ = Cli::parse_from([
+    fn parse_require_jsonl_eof_after_subcommand() {
+        let cli = Cli::parse_from([
             "sezkp-cli",
             "commit",
             "--blocks",
-            "blocks.cbor",
+            "blocks.jsonl",
             "--out",
             "manifest.cbor",
+            "--require-jsonl-eof",
         ]);
+        assert!(cli.require_jsonl_eof);
     }
 
     #[test]
-    fn jsonl_like_detection() {
-        assert!(is_jsonl_like(Path::new("x.jsonl")));
-        assert!(is_jsonl_like(Path::new("x.ndjson")));
-        assert!(!is_jsonl_like(Path::new("x.json")));
-        assert!(!is_jsonl_like(Path::new("x.cbor")));
-        assert!(!is_jsonl_like(Path::new("x")));
+    fn parse_threads_after_subcommand() {
+        let cli = Cli::parse_from([
+            "sezkp-cli",
+            "commit",
+            "--blocks",
+            "blocks.jsonl",
+            "--threads",
+            "4",
+        ]);
+        assert_eq!(cli.threads, Some(4));
+    }
+
+    #[test]
+    fn parse_ignore_env() {
+        let cli = Cli::parse_from(["sezkp-cli", "inspect", "--proof", "p.cbor", "--ignore-env"]);
+        assert!(cli.ignore_env);
+        let cli = Cli::parse_from(["sezkp-cli", "inspect", "--proof", "p.cbor"]);
+        assert!(!cli.ignore_env);
+    }
+
+    #[test]
+    fn parse_root_flags_strictly() {
+        let root = [0xab; 32];
+        let cli = Cli::parse_from([
+            "sezkp-cli",
+            "verify-commit",
+            "--blocks",
+            "blocks.jsonl",
+            "--manifest",
+            "manifest.cbor",
+            "--manifest-root",
+            &RootFormat::parse("0x+check").unwrap().render(&root),
+            "--root-format",
+            "base64url",
+        ]);
+        let Cmd::VerifyCommit(VerifyCommitArgs { manifest_root, .. }) = cli.cmd else {
+            panic!("expected verify-commit");
+        };
+        assert_eq!(manifest_root, Some(root));
+        assert_eq!(cli.root_format, Some(RootFormat::parse("b64url").unwrap()));
+
+        // One digit short must not parse.
+        assert!(Cli::try_parse_from([
+            "sezkp-cli",
+            "verify-commit",
+            "--blocks",
+            "b.jsonl",
+            "--manifest",
+            "m.cbor",
+            "--manifest-root",
+            &"ab".repeat(32)[1..],
+        ])
+        .is_err());
     }
 }
@@ -0,0 +1,99 @@
+//! `migrate-proof`: rewrite an old fold artifact in the current envelope.
+//!
+//! V1 and V2 fold envelopes are still read, until the release
+//! [`removed_in`] names; migrating re-encodes the same proof as V3 so it
+//! keeps verifying after that. A timestamp token covering the old bytes
+//! cannot survive the rewrite and is dropped with a warning.
+
+use anyhow::{Context, Result};
+use clap::Args;
+use sezkp_core::io::{read_proof_auto, write_proof_auto};
+use sezkp_core::render::render_root;
+use std::path::PathBuf;
+use tracing::warn;
+
+/// Flags of `migrate-proof`.
+#[derive(Args, Debug)]
+pub(crate) struct MigrateProofArgs {
+    /// Input path to the proof artifact (CBOR/JSON).
+    #[arg(long)]
+    pub proof: PathBuf,
+
+    /// Output path for the migrated artifact (CBOR/JSON).
+    #[arg(long)]
+    pub out: PathBuf,
+}
+
+/// Migrate the fold artifact at `--proof` to the current envelope.
+pub(crate) fn migrate_proof(a: MigrateProofArgs) -> Result<()> {
+    use sezkp_fold::migrate::{migrate_to_v3, CURRENT_VERSION};
+
+    let MigrateProofArgs { proof, out } = a;
+    let art = read_proof_auto(&proof).with_context(|| format!("reading {}", proof.display()))?;
+    let m = migrate_to_v3(&art).with_context(|| format!("migrating {}", proof.display()))?;
+    if m.dropped_timestamp {
+        warn!(
+            "dropped the timestamp token: it covers the V{} proof bytes and backend label; re-timestamp if needed",
+            m.from
+        );
+    }
+    write_proof_auto(&out, &m.artifact)?;
+    if m.from == CURRENT_VERSION && m.relabelled {
+        println!(
+            "Relabelled V{CURRENT_VERSION} fold artifact (was tagged stark): {} → {}",
+            proof.display(),
+            out.display()
+        );
+    } else if m.from == CURRENT_VERSION {
+        println!(
+            "{} is already a V{CURRENT_VERSION} fold artifact; copied to {}",
+            proof.display(),
+            out.display()
+        );
+    } else {
+        println!(
+            "Migrated V{} → V{CURRENT_VERSION} (root {}): {} → {}; V{} is read until release {}",
+            m.from,
+            render_root(&m.artifact.manifest_root),
+            proof.display(),
+            out.display(),
+            m.from,
+            removed_in(m.from)
+        );
+    }
+    Ok(())
+}
+
+/// Release that stops reading fold envelope version `ver` (V1 or V2).
+pub(crate) fn removed_in(ver: u16) -> &'static str {
+    use sezkp_fold::migrate::{V1_REMOVED_IN, V2_REMOVED_IN};
+    if ver == 1 {
+        V1_REMOVED_IN
+    } else {
+        V2_REMOVED_IN
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Cli, Cmd};
+    use clap::Parser;
+
+    #[test]
+    fn parse_migrate_proof() {
+        let cli = Cli::parse_from([
+            "sezkp-cli",
+            "migrate-proof",
+            "--proof",
+            "old.cbor",
+            "--out",
+            "new.cbor",
+        ]);
+        let Cmd::MigrateProof(MigrateProofArgs { proof, out }) = cli.cmd else {
+            panic!("expected migrate-proof");
+        };
+        assert_eq!(proof, PathBuf::from("old.cbor"));
+        assert_eq!(out, PathBuf::from("new.cbor"));
+    }
+}
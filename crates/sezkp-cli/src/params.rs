@@ -0,0 +1,550 @@
+//! Parameter files: `gen-params`, and `--params` on `prove` / `verify`.
+//!
+//! A [`ParamFile`] pins every protocol parameter a proof depends on: the
+//! field and hash, the STARK's fixed constants (blowup, FRI rate, domain and
+//! column-chunk sizes), the knobs `prove` lets a run vary (security level
+//! and the query count it implies, FRI query schedule, streaming buffers,
+//! fold mode, wrap cadence, ARE samples, seam digest mode), the boundary
+//! window, and the version of every format a proof or manifest digests. [`ParamFile::digest`] hashes its canonical CBOR
+//! encoding ([`sezkp_core::canonical`]), so equal parameters always give the
+//! same digest and a file can be signed or published by it.
+//!
+//! `prove --params` checks that this build implements the fixed constants,
+//! applies the knobs, and binds the digest into the proof as the public
+//! claim [`PARAMS_CLAIM`] (absorbed into the STARK transcript or the fold
+//! gadget binding, see [`sezkp_core::claims`]). `verify --params` repeats the
+//! build check, requires the proof to declare the file's knobs, and requires
+//! the claim, so a proof verifies under a parameter file only if the prover
+//! used the same one.
+
+use anyhow::{bail, ensure, Context, Result};
+use clap::Args;
+use serde::{Deserialize, Serialize};
+use sezkp_core::boundary::DigestMode;
+use sezkp_core::claims::Claim;
+use sezkp_core::io::{from_cbor, to_cbor};
+use sezkp_core::io_format::{resolve_read_format, resolve_write_format, FileFormat};
+use sezkp_fold::api::{DriverOptions, FoldParams};
+use sezkp_stark::params::{self as stark, FriQuerySchedule, StreamTuning};
+use std::path::{Path, PathBuf};
+
+use crate::args::FoldModeOpt;
+use crate::ensure_parent_dir;
+
+/// Current parameter file version.
+pub(crate) const PARAMS_VERSION: u16 = 1;
+
+/// Label of the claim carrying a parameter file's digest (hex).
+pub(crate) const PARAMS_CLAIM: &str = "sezkp.params";
+
+/// Domain separator for [`ParamFile::digest`].
+const DS_PARAMS: &[u8] = b"sezkp/params/v1";
+
+/// Formats accepted for parameter files.
+const PARAMS_FORMATS: [FileFormat; 2] = [FileFormat::Json, FileFormat::Cbor];
+
+/// Every protocol parameter a proof depends on.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct ParamFile {
+    /// File version ([`PARAMS_VERSION`]).
+    pub(crate) version: u16,
+    /// Modulus of the STARK field.
+    pub(crate) field_modulus: u64,
+    /// Hash behind commitments, manifests and transcripts.
+    pub(crate) hash: String,
+    /// STARK v1 parameters.
+    pub(crate) stark: StarkParams,
+    /// Fold parameters, as fold proofs declare them.
+    pub(crate) fold: FoldParams,
+    /// Versions of the formats proofs and manifests digest.
+    pub(crate) formats: FormatVersions,
+    /// Free-form description (covered by the digest).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) note: Option<String>,
+}
+
+/// STARK v1 parameters.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct StarkParams {
+    /// Trace-domain blowup.
+    pub(crate) blowup: usize,
+    /// FRI folding rate.
+    pub(crate) fri_rate: usize,
+    /// Target security level (bits), if the query count was sized for one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) security_bits: Option<usize>,
+    /// Rows opened per proof, and positions of the chained FRI schedule:
+    /// the default, or the count `security_bits` needs.
+    pub(crate) num_queries: usize,
+    /// Smallest evaluation domain, as log2.
+    pub domain_min_log2: usize,
+    /// Rows per column-commitment chunk, as log2.
+    pub col_chunk_log2: usize,
+    /// FRI query schedule: empty for chained queries, otherwise one count
+    /// for every layer or one per folded layer.
+    pub(crate) fri_layer_queries: Vec<usize>,
+    /// Prover-side streaming buffers (do not change the proof).
+    pub(crate) tuning: StreamTuning,
+}
+
+/// Versions of the formats proofs and manifests digest.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct FormatVersions {
+    /// Manifest version.
+    pub(crate) manifest: u32,
+    /// Newest block leaf schema.
+    pub(crate) leaf_schema: u32,
+    /// Fold proof stream version.
+    pub(crate) fold_stream: u16,
+    /// STARK protocol domain string.
+    pub(crate) stark_protocol: String,
+}
+
+impl ParamFile {
+    /// This build's parameters with the given knobs.
+    pub(crate) fn current(
+        fold: FoldParams,
+        schedule: &FriQuerySchedule,
+        tuning: StreamTuning,
+        note: Option<String>,
+    ) -> Self {
+        Self {
+            version: PARAMS_VERSION,
+            field_modulus: stark::FIELD_MODULUS,
+            hash: sezkp_merkle::HASH_SCHEME.to_owned(),
+            stark: StarkParams {
+                blowup: stark::BLOWUP,
+                fri_rate: stark::FRI_RATE,
+                security_bits: None,
+                num_queries: stark::NUM_QUERIES,
+                domain_min_log2: stark::DOMAIN_MIN_LOG2,
+                col_chunk_log2: stark::COL_CHUNK_LOG2,
+                fri_layer_queries: match schedule {
+                    FriQuerySchedule::Chained => Vec::new(),
+                    FriQuerySchedule::PerLayer(c) => c.clone(),
+                },
+                tuning,
+            },
+            fold,
+            formats: FormatVersions {
+                manifest: sezkp_merkle::MANIFEST_VERSION,
+                leaf_schema: sezkp_merkle::LEAF_SCHEMA_REDACTED,
+                fold_stream: sezkp_fold::driver::STREAM_VERSION,
+                stark_protocol: stark::DS_V1_DOMAIN.to_owned(),
+            },
+            note,
+        }
+    }
+
+    /// Size the query count for `bits` of security (the default count for
+    /// `None`).
+    ///
+    /// # Errors
+    /// Fails if the level needs more queries than a proof may declare.
+    pub(crate) fn with_security_bits(mut self, bits: Option<usize>) -> Result<Self> {
+        self.stark.security_bits = bits;
+        self.stark.num_queries = match bits {
+            Some(b) => stark::queries_for_security(b, stark::BLOWUP, stark::GRINDING_BITS)?,
+            None => stark::NUM_QUERIES,
+        };
+        Ok(self)
+    }
+
+    /// The FRI query schedule.
+    pub(crate) fn schedule(&self) -> FriQuerySchedule {
+        match &self.stark.fri_layer_queries {
+            c if c.is_empty() => FriQuerySchedule::Chained,
+            c => FriQuerySchedule::PerLayer(c.clone()),
+        }
+    }
+
+    /// Check that this build implements the file's fixed parameters and
+    /// that its knobs are in range.
+    ///
+    /// # Errors
+    /// Names the first parameter that differs or is out of range.
+    pub(crate) fn check_build(&self) -> Result<()> {
+        ensure!(
+            self.version == PARAMS_VERSION,
+            "unsupported parameter file version {}",
+            self.version
+        );
+        let build = Self::current(self.fold, &self.schedule(), self.stark.tuning, None)
+            .with_security_bits(self.stark.security_bits)?;
+        let fixed = [
+            (
+                "field modulus",
+                self.field_modulus.to_string(),
+                build.field_modulus.to_string(),
+            ),
+            ("hash", self.hash.clone(), build.hash),
+            (
+                "blowup",
+                self.stark.blowup.to_string(),
+                build.stark.blowup.to_string(),
+            ),
+            (
+                "FRI rate",
+                self.stark.fri_rate.to_string(),
+                build.stark.fri_rate.to_string(),
+            ),
+            (
+                "query count",
+                self.stark.num_queries.to_string(),
+                build.stark.num_queries.to_string(),
+            ),
+            (
+                "minimum domain (log2)",
+                self.stark.domain_min_log2.to_string(),
+                build.stark.domain_min_log2.to_string(),
+            ),
+            (
+                "column chunk (log2)",
+                self.stark.col_chunk_log2.to_string(),
+                build.stark.col_chunk_log2.to_string(),
+            ),
+            (
+                "manifest version",
+                self.formats.manifest.to_string(),
+                build.formats.manifest.to_string(),
+            ),
+            (
+                "leaf schema",
+                self.formats.leaf_schema.to_string(),
+                build.formats.leaf_schema.to_string(),
+            ),
+            (
+                "fold stream version",
+                self.formats.fold_stream.to_string(),
+                build.formats.fold_stream.to_string(),
+            ),
+            (
+                "STARK protocol",
+                self.formats.stark_protocol.clone(),
+                build.formats.stark_protocol,
+            ),
+        ];
+        for (what, file, ours) in fixed {
+            ensure!(
+                file == ours,
+                "parameter file sets {what} {file}; this build implements {ours}"
+            );
+        }
+        self.fold.check_supported()?;
+        self.stark.tuning.check()?;
+        for &q in &self.stark.fri_layer_queries {
+            ensure!(
+                (1..=stark::MAX_FRI_LAYER_QUERIES).contains(&q),
+                "FRI layer query count {q} is outside 1..={}",
+                stark::MAX_FRI_LAYER_QUERIES
+            );
+        }
+        Ok(())
+    }
+
+    /// BLAKE3 digest of the canonical encoding.
+    pub(crate) fn digest(&self) -> Result<[u8; 32]> {
+        sezkp_core::canonical::digest(DS_PARAMS, self).context("digesting parameter file")
+    }
+
+    /// The claim binding this file into a proof.
+    pub(crate) fn claim(&self) -> Result<Claim> {
+        Ok(Claim::new(PARAMS_CLAIM, hex::encode(self.digest()?)))
+    }
+
+    /// Check that a proof's `claims` bind this file.
+    ///
+    /// # Errors
+    /// Fails if the claim is missing or names another digest.
+    pub(crate) fn check_claimed(&self, claims: &[Claim]) -> Result<()> {
+        let want = self.claim()?;
+        match claims.iter().find(|c| c.label == PARAMS_CLAIM) {
+            Some(c) if *c == want => Ok(()),
+            Some(c) => bail!(
+                "proof was made with parameter file {}, not {}",
+                c.value,
+                want.value
+            ),
+            None => bail!("proof does not bind a parameter file (no {PARAMS_CLAIM} claim)"),
+        }
+    }
+
+    /// Have the STARK prover use the file's security level, FRI schedule
+    /// and buffers.
+    pub(crate) fn apply_to_prover(&self) {
+        match self.stark.security_bits {
+            Some(b) => crate::envguard::set(stark::ENV_SECURITY_BITS, b.to_string()),
+            None => crate::envguard::unset(stark::ENV_SECURITY_BITS),
+        }
+        match self.schedule() {
+            FriQuerySchedule::Chained => crate::envguard::unset(stark::ENV_FRI_LAYER_QUERIES),
+            s => crate::envguard::set(stark::ENV_FRI_LAYER_QUERIES, s.to_string()),
+        }
+        let t = &self.stark.tuning;
+        crate::envguard::set(stark::ENV_LDE_CHUNK_LOG2, t.lde_chunk_log2.to_string());
+        crate::envguard::set(stark::ENV_FRI_BUF_LOG2, t.fri_buf_log2.to_string());
+    }
+
+    /// Have the STARK verifier require the file's FRI schedule and, if it
+    /// sets one, its security level.
+    pub(crate) fn apply_to_verifier(&self) {
+        if let Some(b) = self.stark.security_bits {
+            crate::envguard::set(stark::ENV_MIN_SECURITY_BITS, b.to_string());
+        }
+        crate::envguard::set(
+            stark::ENV_EXPECT_FRI_LAYER_QUERIES,
+            self.schedule().to_string(),
+        );
+    }
+}
+
+/// Read a parameter file (JSON/CBOR, auto-detected).
+pub(crate) fn read_params(path: &Path) -> Result<ParamFile> {
+    let fmt = resolve_read_format(path, "params", &PARAMS_FORMATS)?;
+    let bytes = std::fs::read(path).with_context(|| format!("read {}", path.display()))?;
+    let params = match fmt {
+        FileFormat::Cbor => from_cbor(&bytes),
+        _ => serde_json::from_slice(&bytes).map_err(anyhow::Error::from),
+    };
+    params.with_context(|| format!("parsing parameter file {}", path.display()))
+}
+
+/// Write a parameter file (format from the extension, JSON default).
+pub(crate) fn write_params(path: &Path, params: &ParamFile) -> Result<()> {
+    let fmt = resolve_write_format(path, "params", &PARAMS_FORMATS)?;
+    let bytes = match fmt {
+        FileFormat::Cbor => to_cbor(params)?,
+        _ => serde_json::to_vec_pretty(params)?,
+    };
+    std::fs::write(path, bytes).with_context(|| format!("write {}", path.display()))
+}
+
+/// Flags of `gen-params`.
+#[derive(Args, Debug)]
+pub(crate) struct GenParamsArgs {
+    /// Output path (CBOR/JSON).
+    #[arg(long, default_value = "params.cbor")]
+    pub out: PathBuf,
+
+    /// Folding driver mode fold proofs are made in.
+    #[arg(long, value_enum, default_value_t = FoldModeOpt::Balanced)]
+    pub fold_mode: FoldModeOpt,
+
+    /// Wrap cadence of fold proofs (0 = no wraps).
+    #[arg(long, default_value_t = 0)]
+    pub wrap_cadence: u32,
+
+    /// Interior steps each fold leaf proof opens (0 = none).
+    #[arg(long, default_value_t = 0)]
+    pub are_samples: u32,
+
+    /// What fold seam digests hash of the boundary steps: `full`,
+    /// `heads` or `writes-only`.
+    #[arg(long, value_name = "MODE", default_value = "full")]
+    pub iface_digest: DigestMode,
+
+    /// STARK FRI query schedule: `chained`, one count for every layer,
+    /// or comma-separated counts per folded layer.
+    #[arg(long, value_name = "SCHEDULE", default_value = "chained")]
+    pub fri_layer_queries: FriQuerySchedule,
+
+    /// log2 of the STARK prover's LDE output chunk.
+    #[arg(long, default_value_t = sezkp_stark::params::DEFAULT_LDE_CHUNK_LOG2)]
+    pub lde_chunk_log2: u8,
+
+    /// log2 of the STARK prover's FRI staging buffer.
+    #[arg(long, default_value_t = sezkp_stark::params::DEFAULT_FRI_BUF_LOG2)]
+    pub fri_buf_log2: u8,
+
+    /// Target STARK security level (bits): sizes the query count instead
+    /// of the default 30 queries.
+    #[arg(long, value_name = "BITS")]
+    pub security_bits: Option<usize>,
+
+    /// Free-form description stored in the file (part of the digest).
+    #[arg(long)]
+    pub note: Option<String>,
+}
+
+/// Build the parameter file `gen-params` describes, write it to `--out` and
+/// print its digest.
+pub(crate) fn gen_params(a: GenParamsArgs) -> Result<()> {
+    let GenParamsArgs {
+        out,
+        fold_mode,
+        wrap_cadence,
+        are_samples,
+        iface_digest,
+        fri_layer_queries,
+        lde_chunk_log2,
+        fri_buf_log2,
+        security_bits,
+        note,
+    } = a;
+    let fold = DriverOptions {
+        fold_mode: fold_mode.mode(),
+        wrap_cadence,
+        are_samples,
+        iface_digest,
+        ..DriverOptions::default()
+    }
+    .params();
+    let tuning = StreamTuning {
+        lde_chunk_log2,
+        fri_buf_log2,
+    };
+    let params = ParamFile::current(fold, &fri_layer_queries, tuning, note)
+        .with_security_bits(security_bits)?;
+    params.check_build()?;
+    ensure_parent_dir(&out)?;
+    write_params(&out, &params)?;
+    println!(
+        "Wrote {} (digest {})",
+        out.display(),
+        hex::encode(params.digest()?)
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prove::ProveArgs;
+    use crate::{Cli, Cmd};
+    use clap::Parser;
+
+    fn sample() -> ParamFile {
+        let fold = sezkp_fold::api::DriverOptions {
+            wrap_cadence: 4,
+            ..Default::default()
+        }
+        .params();
+        ParamFile::current(
+            fold,
+            &FriQuerySchedule::PerLayer(vec![8]),
+            StreamTuning::default(),
+            Some("ci".into()),
+        )
+    }
+
+    #[test]
+    fn files_round_trip_with_a_stable_digest() {
+        let p = sample();
+        p.check_build().unwrap();
+        let dir = std::env::temp_dir().join(format!("sezkp_params_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in ["params.json", "params.cbor"] {
+            let path = dir.join(name);
+            write_params(&path, &p).unwrap();
+            let back = read_params(&path).unwrap();
+            assert_eq!(back, p);
+            assert_eq!(back.digest().unwrap(), p.digest().unwrap());
+        }
+        std::fs::remove_dir_all(dir).unwrap();
+
+        let mut other = p.clone();
+        other.note = None;
+        assert_ne!(other.digest().unwrap(), p.digest().unwrap());
+    }
+
+    #[test]
+    fn builds_and_proofs_must_match_the_file() {
+        let p = sample();
+        let other = ParamFile {
+            note: None,
+            ..sample()
+        };
+        let mut bad = p.clone();
+        bad.stark.blowup *= 2;
+        assert!(bad
+            .check_build()
+            .unwrap_err()
+            .to_string()
+            .contains("blowup"));
+        let mut bad = p.clone();
+        bad.stark.fri_layer_queries = vec![0];
+        assert!(bad.check_build().is_err());
+
+        // A security level pins the query count it needs.
+        let sized = p.clone().with_security_bits(Some(100)).unwrap();
+        assert_eq!(sized.stark.num_queries, 34);
+        sized.check_build().unwrap();
+        assert_ne!(sized.digest().unwrap(), p.digest().unwrap());
+        let mut bad = sized;
+        bad.stark.num_queries = stark::NUM_QUERIES;
+        assert!(bad
+            .check_build()
+            .unwrap_err()
+            .to_string()
+            .contains("query count"));
+
+        p.check_claimed(&[Claim::new("x", "1"), p.claim().unwrap()])
+            .unwrap();
+        assert!(p.check_claimed(&[]).is_err());
+        assert!(p.check_claimed(&[other.claim().unwrap()]).is_err());
+    }
+
+    #[test]
+    fn parse_params() {
+        let prove = |extra: &[&'static str]| {
+            let mut v = vec![
+                "sezkp-cli",
+                "prove",
+                "--backend",
+                "fold",
+                "--blocks",
+                "blocks.jsonl",
+                "--manifest",
+                "manifest.cbor",
+                "--params",
+                "params.cbor",
+            ];
+            v.extend_from_slice(extra);
+            Cli::try_parse_from(v)
+        };
+        let cli = prove(&[]).unwrap();
+        assert!(
+            matches!(&cli.cmd, Cmd::Prove(ProveArgs { params: Some(p), .. }) if p == Path::new("params.cbor"))
+        );
+        // The file sets the fold parameters; they cannot also be given.
+        assert!(prove(&["--wrap-cadence", "4"]).is_err());
+        assert!(Cli::try_parse_from([
+            "sezkp-cli",
+            "verify",
+            "--backend",
+            "fold",
+            "--blocks",
+            "blocks.jsonl",
+            "--manifest",
+            "manifest.cbor",
+            "--proof",
+            "proof.cbor",
+            "--params",
+            "params.cbor",
+            "--expect-fold-mode",
+            "minram",
+        ])
+        .is_err());
+
+        let cli = Cli::parse_from([
+            "sezkp-cli",
+            "gen-params",
+            "--fri-layer-queries",
+            "8,4",
+            "--wrap-cadence",
+            "2",
+        ]);
+        let Cmd::GenParams(GenParamsArgs {
+            out,
+            fri_layer_queries,
+            wrap_cadence,
+            ..
+        }) = cli.cmd
+        else {
+            panic!("expected gen-params");
+        };
+        assert_eq!(out, PathBuf::from("params.cbor"));
+        assert_eq!(fri_layer_queries, FriQuerySchedule::PerLayer(vec![8, 4]));
+        assert_eq!(wrap_cadence, 2);
+    }
+}
@@ -0,0 +1,746 @@
+//! `--dry-run` planning for `prove` / `verify`.
+//!
+//! A dry run resolves every input (formats, sizes, block/step counts), picks
+//! the same parameters the real run would, and prints the execution plan:
+//! backend, mode, how many passes the blocks file will see, which files get
+//! written, and a rough peak-memory estimate. Nothing is proved or verified.
+//!
+//! Resolving step counts needs one streaming pass over the blocks file; that
+//! pass keeps only the current block in memory for `.jsonl`/`.ndjson` inputs.
+//!
+//! Memory figures are **order-of-magnitude estimates** derived from the data
+//! structures each backend keeps live; they are meant to catch "this will not
+//! fit" mistakes before a multi-hour run, not to predict RSS precisely.
+
+use anyhow::{Context, Result};
+use sezkp_core::io::stream_block_summaries_auto;
+use sezkp_core::io_format::FileFormat;
+use sezkp_fold::api::{DriverOptions, FoldMode, LedgerStore};
+use sezkp_fold::plan::{block_bytes, estimate_memory, BlockStats};
+use sezkp_merkle::{read_manifest_auto, CommitManifest};
+use std::path::{Path, PathBuf};
+
+use crate::args::{BackendOpt, FoldLedgerOpt, FoldModeOpt};
+
+/// Shape of a blocks file, gathered in one streaming pass.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct BlocksInfo {
+    /// On-disk size in bytes.
+    pub file_bytes: u64,
+    /// Number of blocks (leaves).
+    pub n_blocks: u64,
+    /// Total steps across all blocks (= STARK trace rows).
+    pub total_steps: u64,
+    /// Largest single block, in steps.
+    pub max_block_steps: u64,
+    /// Number of work tapes τ (from the first block).
+    pub tau: usize,
+    /// First and last `block_id`, in file order (`None` when empty).
+    pub block_ids: Option<(u32, u32)>,
+}
+
+/// Which command the plan is for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum PlanKind {
+    Prove,
+    Verify,
+}
+
+/// Everything the dry run decided, ready to print.
+#[derive(Clone, Debug)]
+pub(crate) struct Plan {
+    pub kind: PlanKind,
+    pub backend: BackendOpt,
+    pub fold_mode: FoldModeOpt,
+    pub fold_cache: usize,
+    pub fold_ledger: FoldLedgerOpt,
+    pub wrap_cadence: u32,
+    pub stream: bool,
+    pub blocks_path: PathBuf,
+    pub blocks_format: &'static str,
+    pub blocks: BlocksInfo,
+    pub manifest: CommitManifest,
+    pub passes: Vec<&'static str>,
+    pub outputs: Vec<PathBuf>,
+    pub est_peak_bytes: u64,
+    pub avail_bytes: Option<u64>,
+    pub selection: Option<BackendSelection>,
+    pub proof_cache: Option<PathBuf>,
+    pub warnings: Vec<String>,
+}
+
+/// Human-readable format name for a blocks path (as the readers will see it).
+pub(crate) fn blocks_format(path: &Path) -> &'static str {
+    sezkp_core::io::block_file_format(path).map_or("unknown", FileFormat::ext)
+}
+
+/// Scan a blocks file once and collect its shape.
+pub(crate) fn inspect_blocks(path: &Path) -> Result<BlocksInfo> {
+    let file_bytes = std::fs::metadata(path)
+        .with_context(|| format!("stat {}", path.display()))?
+        .len();
+    let mut info = BlocksInfo {
+        file_bytes,
+        ..BlocksInfo::default()
+    };
+    for (idx, item) in stream_block_summaries_auto(path)
+        .context("open blocks stream")?
+        .enumerate()
+    {
+        let b = item.with_context(|| format!("reading block index {idx}"))?;
+        if idx == 0 {
+            info.tau = b.windows.len();
+        }
+        let first = info.block_ids.map_or(b.block_id, |(first, _)| first);
+        info.block_ids = Some((first, b.block_id));
+        let steps = b.movement_log.steps.len() as u64;
+        info.n_blocks += 1;
+        info.total_steps += steps;
+        info.max_block_steps = info.max_block_steps.max(steps);
+    }
+    Ok(info)
+}
+
+/* ------------------------------ estimates --------------------------------- */
+
+impl BlocksInfo {
+    /// The shape the fold planner consumes.
+    pub(crate) const fn stats(&self) -> BlockStats {
+        BlockStats {
+            n_blocks: self.n_blocks,
+            total_steps: self.total_steps,
+            max_block_steps: self.max_block_steps,
+            tau: self.tau,
+        }
+    }
+}
+
+/// Fold driver settings that decide its memory footprint.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct FoldMemory {
+    pub fold_mode: FoldModeOpt,
+    pub fold_cache: usize,
+    pub fold_ledger: FoldLedgerOpt,
+    pub stream: bool,
+    pub blocks_in_memory: bool,
+}
+
+impl FoldMemory {
+    /// Estimated peak memory of a fold run over `stats`.
+    pub(crate) fn peak_bytes(&self, stats: &BlockStats) -> u64 {
+        let opts = DriverOptions {
+            fold_mode: match self.fold_mode {
+                FoldModeOpt::Balanced => FoldMode::Balanced,
+                FoldModeOpt::Minram => FoldMode::MinRam,
+            },
+            endpoint_cache: u32::try_from(self.fold_cache).unwrap_or(u32::MAX),
+            ledger: match self.fold_ledger {
+                FoldLedgerOpt::Memory => LedgerStore::Memory,
+                FoldLedgerOpt::Disk => LedgerStore::Disk,
+            },
+            ..DriverOptions::default()
+        };
+        estimate_memory(stats, &opts, self.stream, self.blocks_in_memory).peak_bytes()
+    }
+
+    /// A warning when the estimate exceeds `available` bytes, naming the
+    /// lighter settings that would fit.
+    pub(crate) fn warning(&self, stats: &BlockStats, available: u64) -> Option<String> {
+        let peak = self.peak_bytes(stats);
+        if peak <= available {
+            return None;
+        }
+        let mut msg = format!(
+            "estimated peak memory ~{} exceeds available RAM ~{}",
+            human_bytes(peak),
+            human_bytes(available)
+        );
+        let mut hints = Vec::new();
+        if !self.stream {
+            if self.fold_mode == FoldModeOpt::Balanced {
+                let minram = Self {
+                    fold_mode: FoldModeOpt::Minram,
+                    ..*self
+                };
+                hints.push(format!(
+                    "--fold-mode minram (~{})",
+                    human_bytes(minram.peak_bytes(stats))
+                ));
+            }
+            let streamed = Self {
+                stream: true,
+                blocks_in_memory: false,
+                ..*self
+            };
+            hints.push(format!(
+                "--stream with .jsonl blocks (~{})",
+                human_bytes(streamed.peak_bytes(stats))
+            ));
+        } else if self.blocks_in_memory {
+            msg.push_str("; .jsonl/.ndjson blocks are read one at a time");
+        }
+        if !hints.is_empty() {
+            msg.push_str("; try ");
+            msg.push_str(&hints.join(" or "));
+        }
+        Some(msg)
+    }
+}
+
+/// Memory the OS reports as available for new allocations (`MemAvailable`
+/// in `/proc/meminfo`); `None` where that is not known.
+pub(crate) fn available_memory() -> Option<u64> {
+    let info = std::fs::read_to_string("/proc/meminfo").ok()?;
+    let line = info.lines().find(|l| l.starts_with("MemAvailable:"))?;
+    let kib: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    kib.checked_mul(1024)
+}
+
+/// Estimate peak memory for the chosen backend and mode.
+///
+/// STARK v1 keeps the full columnar trace (3 scalar columns plus
+/// `7 + SYM_BITS + 2·HEAD_BITS` columns per tape) and the layer-0 LDE plus one
+/// folding scratch layer, both `BLOWUP × rows` field elements. Fold runs are
+/// estimated by [`sezkp_fold::plan`].
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn estimate_peak_bytes(
+    backend: BackendOpt,
+    kind: PlanKind,
+    fold_mode: FoldModeOpt,
+    fold_cache: usize,
+    fold_ledger: FoldLedgerOpt,
+    stream: bool,
+    blocks_in_memory: bool,
+    info: &BlocksInfo,
+) -> u64 {
+    match backend {
+        BackendOpt::Stark => {
+            use sezkp_stark::v1::columns::{HEAD_BITS, SYM_BITS};
+            use sezkp_stark::v1::params::BLOWUP;
+            let all_blocks = if blocks_in_memory {
+                info.n_blocks * block_bytes(info.total_steps / info.n_blocks.max(1), info.tau)
+            } else {
+                block_bytes(info.max_block_steps, info.tau)
+            };
+            let cols = 3 + info.tau * (7 + SYM_BITS + 2 * HEAD_BITS);
+            let rows = info.total_steps;
+            let columns = rows * cols as u64 * 8;
+            let lde = match kind {
+                PlanKind::Prove => 2 * rows * BLOWUP as u64 * 8,
+                PlanKind::Verify => 0,
+            };
+            all_blocks + columns + lde
+        }
+        BackendOpt::Fold => FoldMemory {
+            fold_mode,
+            fold_cache,
+            fold_ledger,
+            // Verification always runs the streaming driver.
+            stream: stream || kind == PlanKind::Verify,
+            blocks_in_memory,
+        }
+        .peak_bytes(&info.stats()),
+    }
+}
+
+/* ---------------------------- backend selection ---------------------------- */
+
+/// Largest trace `--backend auto` gives to STARK v1 unless told otherwise
+/// (a minute or two of proving; see `bench --preset medium`).
+pub(crate) const AUTO_STARK_MAX_ROWS: u64 = 1 << 14;
+
+/// What `prove --backend auto` picked, and why.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct BackendSelection {
+    pub backend: BackendOpt,
+    pub stream: bool,
+    /// Trace rows from the manifest (`None` for v1 manifests).
+    pub rows: Option<u64>,
+    pub max_stark_rows: u64,
+    pub reason: String,
+}
+
+impl BackendSelection {
+    /// The decision as recorded in the artifact's `meta.backend_selection`.
+    pub(crate) fn meta(&self) -> serde_json::Value {
+        serde_json::json!({
+            "mode": "auto",
+            "backend": format!("{:?}", self.backend).to_lowercase(),
+            "stream": self.stream,
+            "rows": self.rows,
+            "max_stark_rows": self.max_stark_rows,
+            "reason": self.reason,
+        })
+    }
+}
+
+/// Pick a backend from the manifest's shape: STARK v1 for traces of at most
+/// `max_stark_rows` rows that it can prove (a power-of-two row count) within
+/// `available` memory, the fold backend otherwise. Fold proofs stream when
+/// the blocks can be read one at a time (`lazily`) or `stream` was asked for.
+pub(crate) fn select_backend(
+    man: &CommitManifest,
+    lazily: bool,
+    stream: bool,
+    max_stark_rows: u64,
+    available: Option<u64>,
+) -> BackendSelection {
+    let stats = BlockStats::from_manifest(man);
+    let rows = stats.map(|s| s.total_steps);
+    let stark_peak = |s: BlockStats| {
+        let info = BlocksInfo {
+            n_blocks: s.n_blocks,
+            total_steps: s.total_steps,
+            max_block_steps: s.max_block_steps,
+            tau: s.tau,
+            ..BlocksInfo::default()
+        };
+        estimate_peak_bytes(
+            BackendOpt::Stark,
+            PlanKind::Prove,
+            FoldModeOpt::Balanced,
+            0,
+            FoldLedgerOpt::Memory,
+            false,
+            true,
+            &info,
+        )
+    };
+
+    let fold_because = match rows {
+        None => Some("the manifest (v1) records no row count".to_owned()),
+        Some(r) if r > max_stark_rows => Some(format!(
+            "{r} rows exceed the STARK limit of {max_stark_rows}"
+        )),
+        Some(r) if !r.is_power_of_two() => Some(format!(
+            "{r} rows are not a power of two, which stark-v1 needs"
+        )),
+        Some(_) => {
+            let need = stats.map_or(0, stark_peak);
+            available.filter(|&a| need > a).map(|a| {
+                format!(
+                    "stark-v1 would need ~{} but ~{} is available",
+                    human_bytes(need),
+                    human_bytes(a)
+                )
+            })
+        }
+    };
+
+    match fold_because {
+        Some(reason) => {
+            let stream = stream || lazily;
+            let reason = if stream {
+                format!("{reason}; fold streams the blocks")
+            } else {
+                reason
+            };
+            BackendSelection {
+                backend: BackendOpt::Fold,
+                stream,
+                rows,
+                max_stark_rows,
+                reason,
+            }
+        }
+        None => BackendSelection {
+            backend: BackendOpt::Stark,
+            stream,
+            rows,
+            max_stark_rows,
+            reason: format!(
+                "{} rows fit the STARK limit of {max_stark_rows}",
+                rows.unwrap_or_default()
+            ),
+        },
+    }
+}
+
+/* -------------------------------- planning -------------------------------- */
+
+/// Inputs for [`build_plan`] (mirrors the `prove` / `verify` CLI flags).
+pub(crate) struct PlanArgs<'a> {
+    pub kind: PlanKind,
+    pub backend: BackendOpt,
+    pub blocks: &'a Path,
+    pub manifest: &'a Path,
+    pub out: Option<&'a Path>,
+    pub proof: Option<&'a Path>,
+    pub fold_mode: FoldModeOpt,
+    pub fold_cache: usize,
+    pub fold_ledger: FoldLedgerOpt,
+    pub wrap_cadence: u32,
+    pub stream: bool,
+    pub assume_committed: bool,
+    pub self_check: bool,
+    pub proof_cache: Option<&'a Path>,
+    /// How `--backend auto` arrived at `backend`, if it did.
+    pub selection: Option<&'a BackendSelection>,
+}
+
+/// Resolve inputs and decide what a real run would do.
+pub(crate) fn build_plan(a: &PlanArgs<'_>) -> Result<Plan> {
+    let manifest = read_manifest_auto(a.manifest)
+        .with_context(|| format!("reading manifest {}", a.manifest.display()))?;
+    let blocks = inspect_blocks(a.blocks)?;
+    let fmt = blocks_format(a.blocks);
+
+    let mut warnings = Vec::new();
+    if blocks.n_blocks != u64::from(manifest.n_leaves) {
+        warnings.push(format!(
+            "manifest has {} leaves but blocks file has {} blocks",
+            manifest.n_leaves, blocks.n_blocks
+        ));
+    }
+    if manifest.has_shape() {
+        if blocks.total_steps != manifest.total_steps {
+            warnings.push(format!(
+                "manifest records {} steps but blocks file has {}",
+                manifest.total_steps, blocks.total_steps
+            ));
+        }
+        if blocks.tau != manifest.tau as usize {
+            warnings.push(format!(
+                "manifest records τ={} but blocks file has τ={}",
+                manifest.tau, blocks.tau
+            ));
+        }
+    }
+    if let Some(p) = a.proof {
+        if !p.exists() {
+            warnings.push(format!("proof file {} does not exist", p.display()));
+        }
+    }
+
+    // Which code path the real run takes, and whether it materializes blocks.
+    let fold_streams =
+        matches!(a.backend, BackendOpt::Fold) && (a.kind == PlanKind::Verify || a.stream);
+    let blocks_in_memory = !(fold_streams && fmt == "jsonl");
+    if a.stream && fmt != "jsonl" {
+        warnings.push(format!(
+            "--stream with .{fmt} input degrades to in-memory iteration (use .jsonl/.ndjson)"
+        ));
+    }
+    if a.proof_cache.is_some() && !(matches!(a.backend, BackendOpt::Fold) && a.stream) {
+        warnings.push("--proof-cache only applies to streaming fold proofs (ignored)".to_owned());
+    }
+    if a.fold_ledger == FoldLedgerOpt::Disk
+        && !(matches!(a.backend, BackendOpt::Fold)
+            && a.kind == PlanKind::Prove
+            && !a.stream
+            && a.fold_mode == FoldModeOpt::Balanced)
+    {
+        warnings.push(
+            "--fold-ledger disk only applies to balanced batch fold proofs (ignored)".to_owned(),
+        );
+    }
+    if matches!(a.backend, BackendOpt::Stark) && !blocks.total_steps.is_power_of_two() {
+        warnings.push(format!(
+            "stark-v1 needs a power-of-two row count; trace has {} rows",
+            blocks.total_steps
+        ));
+    }
+
+    let mut passes = Vec::new();
+    if !a.assume_committed {
+        passes.push("manifest pre-check (recompute Merkle root)");
+    }
+    passes.push(match (a.kind, a.backend) {
+        (PlanKind::Prove, _) => "prove",
+        (PlanKind::Verify, BackendOpt::Fold) => "ARE replay + stream verification",
+        (PlanKind::Verify, BackendOpt::Stark) => "verify",
+    });
+    if a.self_check && matches!(a.kind, PlanKind::Prove) {
+        passes.push("self-check verify (before writing the artifact)");
+    }
+
+    let mut outputs = Vec::new();
+    if let Some(out) = a.out {
+        outputs.push(out.to_path_buf());
+        if matches!(a.backend, BackendOpt::Fold) && a.stream {
+            let mut s = out.to_path_buf();
+            s.set_extension("cborseq");
+            outputs.push(s);
+        }
+    }
+
+    let est_peak_bytes = estimate_peak_bytes(
+        a.backend,
+        a.kind,
+        a.fold_mode,
+        a.fold_cache,
+        a.fold_ledger,
+        a.stream,
+        blocks_in_memory,
+        &blocks,
+    );
+    let avail_bytes = available_memory();
+    if let Some(avail) = avail_bytes {
+        let warning = match a.backend {
+            BackendOpt::Fold => FoldMemory {
+                fold_mode: a.fold_mode,
+                fold_cache: a.fold_cache,
+                fold_ledger: a.fold_ledger,
+                stream: fold_streams,
+                blocks_in_memory,
+            }
+            .warning(&blocks.stats(), avail),
+            BackendOpt::Stark => (est_peak_bytes > avail).then(|| {
+                format!(
+                    "estimated peak memory ~{} exceeds available RAM ~{}",
+                    human_bytes(est_peak_bytes),
+                    human_bytes(avail)
+                )
+            }),
+        };
+        warnings.extend(warning);
+    }
+
+    Ok(Plan {
+        kind: a.kind,
+        backend: a.backend,
+        fold_mode: a.fold_mode,
+        fold_cache: a.fold_cache,
+        fold_ledger: a.fold_ledger,
+        wrap_cadence: a.wrap_cadence,
+        stream: a.stream,
+        blocks_path: a.blocks.to_path_buf(),
+        blocks_format: fmt,
+        blocks,
+        manifest,
+        passes,
+        outputs,
+        est_peak_bytes,
+        avail_bytes,
+        selection: a.selection.cloned(),
+        proof_cache: a.proof_cache.map(Path::to_path_buf),
+        warnings,
+    })
+}
+
+/// Format a byte count with a binary unit suffix.
+pub(crate) fn human_bytes(n: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut v = n as f64;
+    let mut u = 0;
+    while v >= 1024.0 && u + 1 < UNITS.len() {
+        v /= 1024.0;
+        u += 1;
+    }
+    if u == 0 {
+        format!("{n} B")
+    } else {
+        format!("{v:.1} {}", UNITS[u])
+    }
+}
+
+/// Print the plan to stdout.
+pub(crate) fn print_plan(p: &Plan) {
+    let cmd = match p.kind {
+        PlanKind::Prove => "prove",
+        PlanKind::Verify => "verify",
+    };
+    println!("Dry run: {cmd} (no work performed)");
+    println!("  backend        : {:?}", p.backend);
+    if let Some(sel) = &p.selection {
+        println!("                   (auto: {})", sel.reason);
+    }
+    if matches!(p.backend, BackendOpt::Fold) && p.kind == PlanKind::Prove {
+        println!(
+            "  fold mode      : {:?} (cache={}, ledger={:?}, wrap_cadence={})",
+            p.fold_mode, p.fold_cache, p.fold_ledger, p.wrap_cadence
+        );
+    }
+    println!("  streaming      : {}", p.stream);
+    println!(
+        "  blocks         : {} [{}; {}]",
+        p.blocks_path.display(),
+        p.blocks_format,
+        human_bytes(p.blocks.file_bytes)
+    );
+    println!(
+        "                   {} blocks, {} steps (max {} per block), τ={}",
+        p.blocks.n_blocks, p.blocks.total_steps, p.blocks.max_block_steps, p.blocks.tau
+    );
+    println!("  manifest       : {}", p.manifest.summary());
+    if matches!(p.backend, BackendOpt::Stark) {
+        let blow = sezkp_stark::v1::params::BLOWUP as u64;
+        println!(
+            "  stark rows     : {} (LDE domain {})",
+            p.blocks.total_steps,
+            p.blocks.total_steps * blow
+        );
+    }
+    println!("  passes over blocks file: {}", p.passes.len());
+    for (i, pass) in p.passes.iter().enumerate() {
+        println!("    {}. {pass}", i + 1);
+    }
+    if p.outputs.is_empty() {
+        println!("  files written  : (none)");
+    } else {
+        println!("  files written  :");
+        for o in &p.outputs {
+            println!("    - {}", o.display());
+        }
+    }
+    if let Some(dir) = &p.proof_cache {
+        println!("  proof cache    : {}", dir.display());
+    }
+    println!("  est. peak mem  : ~{}", human_bytes(p.est_peak_bytes));
+    if let Some(avail) = p.avail_bytes {
+        println!("  available mem  : ~{}", human_bytes(avail));
+    }
+    for w in &p.warnings {
+        println!("  warning        : {w}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sezkp_fold::plan::FOLD_ENDPOINT_BYTES;
+
+    #[test]
+    fn human_bytes_units() {
+        assert_eq!(human_bytes(512), "512 B");
+        assert_eq!(human_bytes(2048), "2.0 KiB");
+        assert_eq!(human_bytes(3 * 1024 * 1024), "3.0 MiB");
+    }
+
+    #[test]
+    fn streaming_fold_estimate_is_sublinear_in_blocks() {
+        let small = BlocksInfo {
+            n_blocks: 1 << 10,
+            total_steps: 1 << 16,
+            max_block_steps: 64,
+            tau: 2,
+            ..BlocksInfo::default()
+        };
+        let big = BlocksInfo {
+            n_blocks: 1 << 20,
+            total_steps: 1 << 26,
+            ..small.clone()
+        };
+        let est = |i: &BlocksInfo| {
+            estimate_peak_bytes(
+                BackendOpt::Fold,
+                PlanKind::Prove,
+                FoldModeOpt::Minram,
+                64,
+                FoldLedgerOpt::Memory,
+                true,
+                false,
+                i,
+            )
+        };
+        // 1024× more blocks must cost far less than 1024× more memory.
+        assert!(est(&big) < 4 * est(&small));
+    }
+
+    #[test]
+    fn disk_ledger_estimate_drops_per_block_endpoints() {
+        let info = BlocksInfo {
+            n_blocks: 1 << 20,
+            total_steps: 1 << 26,
+            max_block_steps: 64,
+            tau: 2,
+            ..BlocksInfo::default()
+        };
+        let est = |ledger| {
+            estimate_peak_bytes(
+                BackendOpt::Fold,
+                PlanKind::Prove,
+                FoldModeOpt::Balanced,
+                0,
+                ledger,
+                false,
+                true,
+                &info,
+            )
+        };
+        let saved = est(FoldLedgerOpt::Memory) - est(FoldLedgerOpt::Disk);
+        assert!(saved > (info.n_blocks - 1024) * FOLD_ENDPOINT_BYTES);
+    }
+
+    #[test]
+    fn memory_warning_suggests_lighter_modes() {
+        let stats = BlockStats {
+            n_blocks: 1 << 20,
+            total_steps: 1 << 26,
+            max_block_steps: 64,
+            tau: 2,
+        };
+        let batch = FoldMemory {
+            fold_mode: FoldModeOpt::Balanced,
+            fold_cache: 64,
+            fold_ledger: FoldLedgerOpt::Memory,
+            stream: false,
+            blocks_in_memory: true,
+        };
+        let peak = batch.peak_bytes(&stats);
+        assert_eq!(batch.warning(&stats, peak), None);
+
+        let w = batch.warning(&stats, 1 << 20).unwrap();
+        assert!(w.contains("exceeds available RAM"), "{w}");
+        assert!(w.contains("--fold-mode minram"), "{w}");
+        assert!(w.contains("--stream"), "{w}");
+
+        let streamed = FoldMemory {
+            stream: true,
+            blocks_in_memory: false,
+            ..batch
+        };
+        assert!(!streamed.warning(&stats, 1).unwrap().contains("try"));
+    }
+
+    fn manifest(n_leaves: u32, total_steps: u64) -> CommitManifest {
+        CommitManifest {
+            n_leaves,
+            total_steps,
+            tau: 2,
+            ..sezkp_merkle::commit_blocks(&[])
+        }
+    }
+
+    #[test]
+    fn auto_backend_picks_stark_for_small_traces() {
+        let sel = select_backend(
+            &manifest(16, 1 << 10),
+            false,
+            false,
+            AUTO_STARK_MAX_ROWS,
+            None,
+        );
+        assert_eq!(sel.backend, BackendOpt::Stark);
+        assert_eq!(sel.rows, Some(1 << 10));
+        assert!(!sel.stream);
+        assert_eq!(sel.meta()["backend"], "stark");
+    }
+
+    #[test]
+    fn auto_backend_falls_back_to_fold() {
+        let big = manifest(1 << 12, 1 << 20);
+        let sel = select_backend(&big, true, false, AUTO_STARK_MAX_ROWS, None);
+        assert_eq!(sel.backend, BackendOpt::Fold);
+        assert!(sel.stream, "JSONL input should stream");
+        assert!(sel.reason.contains("exceed"), "{}", sel.reason);
+
+        // Same trace, a higher limit: STARK unless memory says otherwise.
+        let sel = select_backend(&big, true, false, 1 << 20, None);
+        assert_eq!(sel.backend, BackendOpt::Stark);
+        let sel = select_backend(&big, true, false, 1 << 20, Some(1 << 20));
+        assert_eq!(sel.backend, BackendOpt::Fold);
+        assert!(sel.reason.contains("available"), "{}", sel.reason);
+
+        let odd = select_backend(&manifest(3, 300), false, false, AUTO_STARK_MAX_ROWS, None);
+        assert_eq!(odd.backend, BackendOpt::Fold);
+        assert!(!odd.stream);
+
+        let v1 = CommitManifest {
+            version: 1,
+            ..manifest(16, 1 << 10)
+        };
+        let sel = select_backend(&v1, false, false, AUTO_STARK_MAX_ROWS, None);
+        assert_eq!((sel.backend, sel.rows), (BackendOpt::Fold, None));
+    }
+}
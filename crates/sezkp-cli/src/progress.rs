@@ -0,0 +1,123 @@
+//! `--progress`: report long passes (replay, stream verification) as they
+//! run.
+//!
+//! The library reports through [`sezkp_core::progress`]; this module installs
+//! the sink the global flag picks, a redrawn bar on stderr or periodic log
+//! lines, and renders each update on one line with its rate and ETA.
+
+use clap::ValueEnum;
+use tracing::info;
+
+/// How progress is reported.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, ValueEnum)]
+pub(crate) enum ProgressOpt {
+    /// Bar on a terminal, log lines otherwise.
+    Auto,
+    /// Redrawn single-line bar on stderr.
+    Bar,
+    /// Periodic log lines.
+    Log,
+    /// No progress output.
+    Off,
+}
+
+/// Install the progress sink for `opt` (see [`sezkp_core::progress`]).
+///
+/// Phases shorter than one reporting interval print nothing, so quick runs
+/// stay quiet.
+pub(crate) fn install_progress(opt: ProgressOpt) {
+    use sezkp_core::progress::{set_progress_sink, ProgressUpdate};
+    use std::io::IsTerminal;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    const BAR_EVERY: Duration = Duration::from_millis(200);
+    const LOG_EVERY: Duration = Duration::from_secs(10);
+
+    let opt = match opt {
+        ProgressOpt::Auto if std::io::stderr().is_terminal() => ProgressOpt::Bar,
+        ProgressOpt::Auto => ProgressOpt::Log,
+        o => o,
+    };
+    match opt {
+        ProgressOpt::Bar => set_progress_sink(
+            Some(Arc::new(|u: &ProgressUpdate| {
+                if u.finished && u.elapsed < BAR_EVERY {
+                    return;
+                }
+                let end = if u.finished { "\n" } else { "" };
+                eprint!("\r\x1b[2K{}{end}", progress_line(u));
+            })),
+            BAR_EVERY,
+        ),
+        ProgressOpt::Log => set_progress_sink(
+            Some(Arc::new(|u: &ProgressUpdate| {
+                if u.finished && u.elapsed < LOG_EVERY {
+                    return;
+                }
+                info!("{}", progress_line(u));
+            })),
+            LOG_EVERY,
+        ),
+        ProgressOpt::Auto | ProgressOpt::Off => set_progress_sink(None, LOG_EVERY),
+    }
+}
+
+/// One-line rendering of a progress update, e.g.
+/// `fold.verify_stream 42.0% 4200/10000 35.1/s ETA 2m45s`.
+fn progress_line(u: &sezkp_core::progress::ProgressUpdate) -> String {
+    let mut line = u.phase.to_owned();
+    if let Some(f) = u.fraction() {
+        line.push_str(&format!(" {:5.1}%", 100.0 * f));
+    }
+    line.push_str(&format!(" {}", u.done));
+    if let Some(t) = u.total {
+        line.push_str(&format!("/{t}"));
+    }
+    line.push_str(&format!(" {:.1}/s", u.rate()));
+    if u.finished {
+        line.push_str(&format!(" done in {}", fmt_secs(u.elapsed.as_secs())));
+    } else if let Some(eta) = u.eta() {
+        line.push_str(&format!(" ETA {}", fmt_secs(eta.as_secs())));
+    }
+    line
+}
+
+/// Compact duration: `45s`, `2m05s`, `3h07m`.
+fn fmt_secs(s: u64) -> String {
+    match s {
+        0..=59 => format!("{s}s"),
+        60..=3599 => format!("{}m{:02}s", s / 60, s % 60),
+        _ => format!("{}h{:02}m", s / 3600, (s % 3600) / 60),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Cli;
+    use clap::Parser;
+
+    #[test]
+    fn progress_flag_and_rendering() {
+        use sezkp_core::progress::ProgressUpdate;
+        use std::time::Duration;
+
+        let cli = Cli::parse_from(["sezkp-cli", "--progress", "log", "commit", "--blocks", "b"]);
+        assert_eq!(cli.progress, ProgressOpt::Log);
+        assert!(Cli::try_parse_from(["sezkp-cli", "--progress", "loud", "commit"]).is_err());
+
+        let u = ProgressUpdate {
+            phase: "fold.verify_stream",
+            done: 4200,
+            total: Some(10_000),
+            elapsed: Duration::from_secs(120),
+            finished: false,
+        };
+        assert_eq!(
+            progress_line(&u),
+            "fold.verify_stream  42.0% 4200/10000 35.0/s ETA 2m45s"
+        );
+        assert_eq!(fmt_secs(11_250), "3h07m");
+    }
+}
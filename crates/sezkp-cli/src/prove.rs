@@ -0,0 +1,957 @@
+//! `prove`: prove that a blocks file (or a trace) matches its manifest.
+//!
+//! [`run`] resolves the flags into a concrete run: a `--params` file
+//! overrides the fold knobs, `--backend auto` picks a backend from the
+//! manifest's size ([`plan::select_backend`]), and `--dry-run` prints the
+//! plan instead. The backends read most fold options from `SEZKP_*`
+//! variables, which [`prove`] sets before proving; with `--follow` the
+//! blocks are folded as a writer appends them ([`crate::follow`]).
+
+use anyhow::{bail, ensure, Context, Result};
+use clap::Args;
+use sezkp_core::boundary::DigestMode;
+use sezkp_core::claims::Claim;
+use sezkp_core::io::write_proof_auto;
+use sezkp_core::timestamp::{now_unix_secs, TimeAuthority};
+use sezkp_core::ProofArtifact;
+use std::path::{Path, PathBuf};
+use tracing::{info, info_span, warn};
+
+use crate::args::{
+    envkeys, BackendOpt, BlockInput, DurabilityArgs, FoldExpectArgs, FoldLedgerOpt, FoldModeOpt,
+    ProveBackendOpt, ReplayFlags, TimestampArgs, TraceArgs,
+};
+use crate::verify::{declare_input_len, verify_artifact};
+use crate::{ensure_parent_dir, envguard, follow, interrupt, params, plan};
+
+/// Flags of `prove`.
+#[derive(Args, Debug)]
+pub(crate) struct ProveArgs {
+    /// Proof backend (`auto` picks one from the manifest's size).
+    #[arg(value_enum, long)]
+    pub backend: ProveBackendOpt,
+
+    /// Largest trace (in rows) `--backend auto` proves with STARK v1;
+    /// bigger traces go to the fold backend.
+    #[arg(long, default_value_t = plan::AUTO_STARK_MAX_ROWS)]
+    pub auto_stark_max_rows: u64,
+
+    /// Input path to σ_k block summaries (CBOR/JSON/JSONL/NDJSON).
+    ///
+    /// For sublinear memory, prefer `.jsonl`/`.ndjson` + `--stream`.
+    #[arg(long, required_unless_present = "trace")]
+    pub blocks: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub trace: TraceArgs,
+
+    /// Input path to manifest (CBOR/JSON); the output path with `--follow`.
+    #[arg(long)]
+    pub manifest: PathBuf,
+
+    /// Output path for proof artifact (CBOR/JSON).
+    #[arg(long, default_value = "proof.cbor")]
+    pub out: PathBuf,
+
+    /* ---------- fold-specific flags (honored via environment) ---------- */
+    /// Folding driver mode (balanced keeps O(T) endpoints; minram recomputes).
+    #[arg(long, value_enum, default_value_t = FoldModeOpt::Balanced)]
+    pub fold_mode: FoldModeOpt,
+
+    /// Endpoint LRU capacity for minram mode (0 disables caching).
+    #[arg(long, default_value_t = 64)]
+    pub fold_cache: usize,
+
+    /// Where balanced mode keeps its O(T) endpoint ledger (batch proving only).
+    #[arg(long, value_enum, default_value_t = FoldLedgerOpt::Memory)]
+    pub fold_ledger: FoldLedgerOpt,
+
+    /// Emit a wrap proof every k folds (0 = never).
+    #[arg(long, default_value_t = 0)]
+    pub wrap_cadence: u32,
+
+    /// Final wrap backend compressing the finished fold proof (e.g. `mac-v1`).
+    #[arg(long, value_name = "ID")]
+    pub final_wrap: Option<String>,
+
+    /// Interior steps each fold leaf proof opens for a transition check
+    /// (0 = none).
+    #[arg(long, default_value_t = 0)]
+    pub are_samples: u32,
+
+    /// What fold seam digests hash of the boundary steps: `full` (mv,
+    /// write flag and symbol of every step), `heads` (also the head
+    /// cells) or `writes-only` (only writing steps).
+    #[arg(long, value_name = "MODE", default_value = "full")]
+    pub iface_digest: DigestMode,
+
+    /// Blocks embedded in a batch fold proof with manifest-inclusion
+    /// proofs, so `verify --spot-check` can skip the blocks file (0 = none).
+    #[arg(long, default_value_t = 0)]
+    pub spot_checks: u32,
+
+    /// Publish the fold proof's root `π` (end control state and
+    /// accumulators) in clear in `meta.root_pi` and the stream footer,
+    /// next to its commitment. Verifiers check the two agree.
+    #[arg(long, default_value_t = false)]
+    pub reveal_root_pi: bool,
+
+    /// Stream blocks instead of loading all into memory.
+    ///
+    /// Effective with `.jsonl`/`.ndjson` inputs; `.json`/`.cbor` may degrade to in-memory iteration.
+    #[arg(long, default_value_t = false)]
+    pub stream: bool,
+
+    /// Prove the blocks file while it is still being appended to: new
+    /// complete `.jsonl` lines are folded as they land, the manifest is
+    /// built along the way and written to `--manifest` at the end.
+    ///
+    /// Finishes once the `--follow-done` marker file exists. Needs
+    /// `--backend fold`; implies `--stream`.
+    #[arg(long, default_value_t = false, requires = "blocks", conflicts_with_all = ["trace", "dry_run"])]
+    pub follow: bool,
+
+    /// Marker file whose creation ends `--follow` [default: `<blocks>.done`].
+    #[arg(long, value_name = "PATH", requires = "follow")]
+    pub follow_done: Option<PathBuf>,
+
+    /// Also end `--follow` once the blocks file has not grown for this
+    /// many seconds.
+    #[arg(long, value_name = "SECS", requires = "follow")]
+    pub follow_idle: Option<u64>,
+
+    /// Persistent subtree cache directory (streaming fold proofs only).
+    ///
+    /// Completed subtrees are stored here and replayed by later runs over
+    /// the same blocks, e.g. when re-proving a growing trace.
+    #[arg(long)]
+    pub proof_cache: Option<PathBuf>,
+
+    /// Write every fold's interface witness here as NDJSON (streaming
+    /// fold proofs only); check it with `check-iface-log`.
+    #[arg(long, value_name = "PATH")]
+    pub iface_log: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub durability: DurabilityArgs,
+
+    /// External randomness beacon (hex, e.g. a block hash) bound into
+    /// STARK query sampling; proves the proof postdates the beacon.
+    #[arg(long)]
+    pub beacon: Option<String>,
+
+    /// Target STARK security level (bits): the query count is sized for
+    /// it (given the blowup and grinding bits) instead of the default
+    /// 30 queries, and the target and estimate are recorded in `meta`.
+    #[arg(long, value_name = "BITS")]
+    pub security_bits: Option<usize>,
+
+    /// Public claim bound into the proof (repeatable), e.g.
+    /// `--claim output=42`. `verify` prints the claims it checked.
+    #[arg(long = "claim", value_name = "LABEL=VALUE")]
+    pub claims: Vec<Claim>,
+
+    /// Parameter file (`gen-params`) to prove under: sets the fold and
+    /// STARK parameters and binds the file's digest into the proof.
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["fold_mode", "wrap_cadence", "are_samples", "iface_digest", "security_bits"])]
+    pub params: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub timestamp: TimestampArgs,
+
+    /// Assume the blocks file has already been verified against the manifest.
+    ///
+    /// Skips the extra pre-check inside `prove` to avoid redundant I/O/RSS.
+    /// `--backend fold --stream` still checks the root while proving.
+    #[arg(long, default_value_t = false)]
+    pub assume_committed: bool,
+
+    /// Run the verifier in-process before writing the artifact and fail
+    /// if it rejects (costs one verification pass).
+    #[arg(long, default_value_t = false)]
+    pub self_check: bool,
+
+    /// Resolve inputs, print the execution plan, and exit without proving.
+    ///
+    /// The plan estimates peak memory and warns, suggesting MinRam or
+    /// streaming, when that exceeds the RAM available.
+    #[arg(long, default_value_t = false, conflicts_with = "trace")]
+    pub dry_run: bool,
+}
+
+/// Run `prove`: apply a `--params` file, resolve `--backend auto` and
+/// `--follow`, then print the plan (`--dry-run`) or prove.
+pub(crate) fn run(a: ProveArgs, flags: ReplayFlags) -> Result<()> {
+    let ProveArgs {
+        backend,
+        auto_stark_max_rows,
+        blocks,
+        trace,
+        manifest,
+        out,
+        fold_mode,
+        fold_cache,
+        fold_ledger,
+        wrap_cadence,
+        final_wrap,
+        are_samples,
+        iface_digest,
+        spot_checks,
+        reveal_root_pi,
+        stream,
+        follow,
+        follow_done,
+        follow_idle,
+        proof_cache,
+        iface_log,
+        durability,
+        beacon,
+        security_bits,
+        mut claims,
+        params,
+        timestamp,
+        assume_committed,
+        self_check,
+        dry_run,
+    } = a;
+    let (fold_mode, wrap_cadence, are_samples, iface_digest) = match params.as_deref() {
+        Some(path) => {
+            let p = params::read_params(path)?;
+            p.check_build()
+                .with_context(|| format!("parameter file {}", path.display()))?;
+            p.apply_to_prover();
+            claims.push(p.claim()?);
+            let f = p.fold;
+            (
+                f.fold_mode.into(),
+                f.wrap_cadence,
+                f.are_samples,
+                f.iface_digest,
+            )
+        }
+        None => (fold_mode, wrap_cadence, are_samples, iface_digest),
+    };
+    let follow = match (follow, &blocks) {
+        (true, Some(path)) => {
+            ensure!(
+                backend.fixed() == Some(BackendOpt::Fold),
+                "--follow needs --backend fold"
+            );
+            Some(follow::FollowEnd::new(path, follow_done, follow_idle))
+        }
+        _ => None,
+    };
+    let input = trace.input(blocks)?;
+    let (backend, stream, selection) = match backend.fixed() {
+        Some(backend) => (backend, stream || follow.is_some(), None),
+        None => {
+            let man = sezkp_merkle::read_manifest_auto(&manifest).context("reading manifest")?;
+            let sel = plan::select_backend(
+                &man,
+                input.streams_lazily(),
+                stream,
+                auto_stark_max_rows,
+                plan::available_memory(),
+            );
+            info!(
+                backend = ?sel.backend,
+                stream = sel.stream,
+                reason = %sel.reason,
+                "auto backend"
+            );
+            (sel.backend, sel.stream, Some(sel))
+        }
+    };
+    if dry_run {
+        let BlockInput::File(blocks) = &input else {
+            bail!("--dry-run needs --blocks");
+        };
+        let p = plan::build_plan(&plan::PlanArgs {
+            kind: plan::PlanKind::Prove,
+            backend,
+            blocks,
+            manifest: &manifest,
+            out: Some(&out),
+            proof: None,
+            fold_mode,
+            fold_cache,
+            fold_ledger,
+            wrap_cadence,
+            stream,
+            assume_committed,
+            self_check,
+            proof_cache: proof_cache.as_deref(),
+            selection: selection.as_ref(),
+        })?;
+        plan::print_plan(&p);
+        return Ok(());
+    }
+    prove(
+        backend,
+        selection.as_ref(),
+        input,
+        manifest,
+        out,
+        fold_mode,
+        fold_cache,
+        fold_ledger,
+        wrap_cadence,
+        final_wrap.as_deref(),
+        are_samples,
+        iface_digest,
+        spot_checks,
+        reveal_root_pi,
+        stream,
+        follow,
+        proof_cache,
+        iface_log,
+        &durability,
+        beacon.as_deref(),
+        security_bits,
+        &claims,
+        timestamp.authority()?,
+        assume_committed,
+        self_check,
+        flags,
+    )
+}
+
+/// Decode a `--beacon` hex string (an optional `0x` prefix is accepted).
+pub(crate) fn parse_beacon(s: &str) -> Result<Vec<u8>> {
+    let s = s.strip_prefix("0x").unwrap_or(s);
+    let bytes = hex::decode(s).with_context(|| format!("--beacon is not valid hex: {s}"))?;
+    ensure!(!bytes.is_empty(), "--beacon must not be empty");
+    Ok(bytes)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn prove(
+    backend: BackendOpt,
+    selection: Option<&plan::BackendSelection>,
+    blocks: BlockInput,
+    manifest: PathBuf,
+    out: PathBuf,
+    fold_mode: FoldModeOpt,
+    fold_cache: usize,
+    fold_ledger: FoldLedgerOpt,
+    wrap_cadence: u32,
+    final_wrap: Option<&str>,
+    are_samples: u32,
+    iface_digest: DigestMode,
+    spot_checks: u32,
+    reveal_root_pi: bool,
+    stream: bool,
+    follow: Option<follow::FollowEnd>,
+    proof_cache: Option<PathBuf>,
+    iface_log: Option<PathBuf>,
+    durability: &DurabilityArgs,
+    beacon: Option<&str>,
+    security_bits: Option<usize>,
+    claims: &[Claim],
+    tsa: Option<TimeAuthority>,
+    assume_committed: bool,
+    self_check: bool,
+    flags: ReplayFlags,
+) -> Result<()> {
+    let _span = info_span!(
+        "prove",
+        ?backend,
+        blocks = %blocks,
+        manifest = %manifest.display(),
+        out = %out.display(),
+        stream
+    )
+    .entered();
+
+    use sezkp_merkle::read_manifest_auto;
+
+    // With --follow the manifest is an output, built while proving.
+    let man = match follow {
+        Some(_) => None,
+        None => {
+            // Skip redundant blocks/manifest pre-check if caller already verified it.
+            if !assume_committed {
+                blocks
+                    .check_committed(&manifest)
+                    .context("blocks/manifest mismatch")?;
+            }
+            Some(read_manifest_auto(&manifest).context("reading manifest")?)
+        }
+    };
+    let beacon = beacon.map(parse_beacon).transpose()?;
+    if beacon.is_some() && matches!(backend, BackendOpt::Fold) {
+        warn!("--beacon only applies to the stark backend; ignoring");
+    }
+    if let Some(bits) = security_bits {
+        if matches!(backend, BackendOpt::Fold) {
+            warn!("--security-bits only applies to the stark backend; ignoring");
+        }
+        envguard::set(sezkp_stark::params::ENV_SECURITY_BITS, bits.to_string());
+    }
+    if final_wrap.is_some() && matches!(backend, BackendOpt::Stark) {
+        warn!("--final-wrap only applies to the fold backend; ignoring");
+    }
+    if are_samples != 0 && matches!(backend, BackendOpt::Stark) {
+        warn!("--are-samples only applies to the fold backend; ignoring");
+    }
+    if !iface_digest.is_full() && matches!(backend, BackendOpt::Stark) {
+        warn!("--iface-digest only applies to the fold backend; ignoring");
+    }
+    if spot_checks != 0 && (stream || matches!(backend, BackendOpt::Stark)) {
+        warn!("--spot-checks only applies to batch fold proofs; ignoring");
+    }
+    if reveal_root_pi && matches!(backend, BackendOpt::Stark) {
+        warn!("--reveal-root-pi only applies to the fold backend; ignoring");
+    }
+    declare_input_len(man.as_ref().and_then(|m| m.input_len));
+
+    // Honor fold-driver flags via env vars the backend reads at prove-time.
+    if matches!(backend, BackendOpt::Fold) {
+        envguard::set(envkeys::FOLD_MODE, fold_mode.env_value());
+        envguard::set(envkeys::FOLD_CACHE, fold_cache.to_string());
+        envguard::set(
+            envkeys::FOLD_LEDGER,
+            match fold_ledger {
+                FoldLedgerOpt::Memory => "memory",
+                FoldLedgerOpt::Disk => "disk",
+            },
+        );
+        envguard::set(envkeys::WRAP_CADENCE, wrap_cadence.to_string());
+        match final_wrap {
+            Some(id) => envguard::set(envkeys::FOLD_FINAL_WRAP, id),
+            None => envguard::unset(envkeys::FOLD_FINAL_WRAP),
+        }
+        envguard::set(envkeys::FOLD_ARE_SAMPLES, are_samples.to_string());
+        envguard::set(envkeys::FOLD_IFACE_DIGEST, iface_digest.id());
+        envguard::set(envkeys::FOLD_SPOT_CHECKS, spot_checks.to_string());
+        if reveal_root_pi {
+            envguard::set(envkeys::FOLD_REVEAL_ROOT_PI, "1");
+        } else {
+            envguard::unset(envkeys::FOLD_REVEAL_ROOT_PI);
+        }
+        match &proof_cache {
+            Some(dir) if stream => envguard::set(envkeys::FOLD_CACHE_DIR, dir),
+            Some(_) => warn!("--proof-cache only applies with --stream; ignoring"),
+            None => envguard::unset(envkeys::FOLD_CACHE_DIR),
+        }
+        match &iface_log {
+            Some(path) if stream => envguard::set(envkeys::FOLD_IFACE_LOG, path),
+            Some(_) => warn!("--iface-log only applies with --stream; ignoring"),
+            None => envguard::unset(envkeys::FOLD_IFACE_LOG),
+        }
+        durability.apply(stream);
+
+        // Estimate from the manifest shape (no extra pass over the blocks).
+        if let Some(stats) = man
+            .as_ref()
+            .and_then(sezkp_fold::plan::BlockStats::from_manifest)
+        {
+            let mem = plan::FoldMemory {
+                fold_mode,
+                fold_cache,
+                fold_ledger,
+                stream,
+                blocks_in_memory: !(stream && blocks.streams_lazily()),
+            };
+            info!(
+                est_peak = %plan::human_bytes(mem.peak_bytes(&stats)),
+                "fold memory estimate"
+            );
+            if let Some(w) = plan::available_memory().and_then(|a| mem.warning(&stats, a)) {
+                warn!("{w}");
+            }
+        }
+    }
+
+    // Choose streaming path iff requested.
+    let (artifact, man) = match (backend, stream, man) {
+        (BackendOpt::Fold, true, man) => {
+            // Decide on a proof stream path adjacent to the artifact.
+            let mut stream_path = out.clone();
+            stream_path.set_extension("cborseq");
+            // Tell the backend where to write the streaming proof.
+            envguard::set(envkeys::PROOF_STREAM_PATH, &stream_path);
+            // Ctrl-C now stops between blocks instead of killing the process.
+            interrupt::install();
+            let interrupted = |e| explain_cancel(e, &stream_path, proof_cache.as_deref());
+
+            let (art, man) = match (man, &follow, &blocks) {
+                (Some(man), _, _) => {
+                    let iter = blocks.stream()?;
+                    let art = flags
+                        .fold_prover()
+                        .with_input_len(man.input_len)
+                        .with_claims(claims.to_vec())
+                        .prove_stream(iter, man.root)
+                        .context("fold backend streaming proof failed")
+                        .map_err(interrupted)?;
+                    (art, man)
+                }
+                (None, Some(end), BlockInput::File(path)) => {
+                    prove_followed(path, end, &manifest, claims, flags).map_err(interrupted)?
+                }
+                (None, ..) => bail!("--follow needs a --blocks file"),
+            };
+
+            println!(
+                "Proved (streaming/fold) → artifact={} stream={}",
+                out.display(),
+                stream_path.display()
+            );
+            if let Some(d) = art.meta.get("stream_digest").and_then(|d| d.as_str()) {
+                println!("Stream digest: {d}");
+            }
+            if let Some(c) = art.meta.get("cache").filter(|c| !c.is_null()) {
+                println!(
+                    "Proof cache: hits={} misses={} rejected={}",
+                    c["hits"], c["misses"], c["rejected"]
+                );
+            }
+            (art, man)
+        }
+        (_, _, None) => bail!("--follow needs --backend fold"),
+        (BackendOpt::Fold, false, Some(man)) => {
+            let blocks_vec = blocks.read_all()?;
+            let art = flags
+                .fold_prover()
+                .with_input_len(man.input_len)
+                .with_claims(claims.to_vec())
+                .prove_blocks(&blocks_vec, man.root)
+                .context("fold backend proof failed")?;
+            (art, man)
+        }
+        // --- STARK v1 path (always ZK). Prefer streaming entrypoint when asked.
+        (BackendOpt::Stark, true, Some(man)) if beacon.is_none() && claims.is_empty() => {
+            use sezkp_stark::StarkV1;
+            let blocks_vec = blocks.read_all()?;
+            let art = StarkV1::prove_streaming(&blocks_vec, man.root)
+                .context("stark-v1 streaming proof failed")?;
+            (art, man)
+        }
+        (BackendOpt::Stark, _, Some(man)) => {
+            use sezkp_stark::StarkV1;
+            let blocks_vec = blocks.read_all()?;
+            let art = StarkV1::prove_bound(&blocks_vec, man.root, beacon.as_deref(), claims)
+                .context("stark-v1 proof failed")?;
+            (art, man)
+        }
+    };
+
+    let mut artifact = artifact;
+    if let Some(sel) = selection {
+        println!("Backend (auto): {:?} ({})", sel.backend, sel.reason);
+        if let Some(meta) = artifact.meta.as_object_mut() {
+            meta.insert("backend_selection".into(), sel.meta());
+        }
+    }
+    if let Some(tsa) = &tsa {
+        artifact.attach_timestamp(tsa, now_unix_secs());
+        info!(authority = tsa.name(), "attached creation timestamp");
+    }
+
+    // Catch nondeterminism or schedule drift here rather than at the consumer.
+    if self_check {
+        // A fold proof must also declare the parameters we asked for.
+        if matches!(backend, BackendOpt::Fold) {
+            FoldExpectArgs {
+                expect_fold_mode: Some(fold_mode),
+                expect_wrap_cadence: Some(wrap_cadence),
+                expect_are_samples: Some(are_samples),
+                expect_iface_digest: Some(iface_digest),
+            }
+            .apply(backend);
+        }
+        let fold_stream = artifact.meta.get("stream_path").and_then(|p| p.as_str());
+        verify_artifact(backend, &artifact, &blocks, &man, beacon.as_deref(), flags).with_context(
+            || match fold_stream {
+                Some(p) => format!(
+                    "self-check failed; artifact {} not written (proof stream {p} is invalid)",
+                    out.display()
+                ),
+                None => format!("self-check failed; artifact {} not written", out.display()),
+            },
+        )?;
+        println!("Self-check: proof verified in-process");
+    }
+
+    ensure_parent_dir(&out)?;
+    write_proof_auto(&out, &artifact)
+        .with_context(|| format!("writing proof to {}", out.display()))?;
+
+    println!(
+        "Proved with {}, wrote {} ({} bytes)",
+        artifact.backend,
+        out.display(),
+        artifact.proof_bytes.len()
+    );
+    if let Some(bits) = artifact.meta["security"]["achieved_bits"].as_u64() {
+        println!(
+            "Security: ~{bits} bits ({} queries)",
+            artifact.meta["security"]["num_queries"]
+        );
+    }
+    Ok(())
+}
+
+/// If `e` is a Ctrl-C stop ([`interrupt`]), tell the user what was left
+/// behind and how to resume. Returns `e` unchanged.
+fn explain_cancel(e: anyhow::Error, stream: &Path, proof_cache: Option<&Path>) -> anyhow::Error {
+    let Some(stop) = e.downcast_ref::<sezkp_core::cancel::Cancelled>() else {
+        return e;
+    };
+    let done = stop
+        .blocks_done
+        .map_or_else(String::new, |n| format!(" after {n} blocks"));
+    eprintln!("Interrupted{done}; no proof artifact was written.");
+    eprintln!(
+        "The partial proof stream {} ends with a truncated footer and does not verify.",
+        stream.display()
+    );
+    if std::env::var(envkeys::FOLD_DURABILITY).is_ok_and(|d| d == "journal") {
+        eprintln!("It is journaled: re-run the same command with --resume to continue it.");
+        return e;
+    }
+    match proof_cache {
+        Some(dir) => eprintln!(
+            "To resume, re-run the same command: completed subtrees cached in {} are reused.",
+            dir.display()
+        ),
+        None => eprintln!(
+            "To resume, re-run the same command; add --proof-cache DIR to keep completed \
+             subtrees across runs."
+        ),
+    }
+    e
+}
+
+/// `prove --follow`: fold the blocks of a growing JSONL file as they are
+/// appended, then write the manifest they commit to.
+///
+/// The proof is started late-bound (the root is unknown until the last
+/// block), so its stream footer names the root instead of its header.
+fn prove_followed(
+    path: &Path,
+    end: &follow::FollowEnd,
+    manifest: &Path,
+    claims: &[Claim],
+    flags: ReplayFlags,
+) -> Result<(ProofArtifact, sezkp_merkle::CommitManifest)> {
+    info!(done_marker = %end.done_marker.display(), "following blocks file");
+    // Build the manifest from the same blocks the prover folds.
+    let mut builder = sezkp_merkle::ManifestBuilder::new();
+    let iter = follow::Follower::open(path, end.clone())?.inspect(|b| {
+        if let Ok(b) = b {
+            builder.push(b);
+        }
+    });
+    let art = flags
+        .fold_prover()
+        .with_claims(claims.to_vec())
+        .prove_stream_late_bound(iter)
+        .context("fold backend streaming proof failed")?;
+    let mut man = builder.finish();
+    ensure!(
+        art.manifest_root == man.root,
+        "followed proof is bound to another root than its blocks"
+    );
+    man.creator = Some(sezkp_merkle::CreatorInfo {
+        tool: env!("CARGO_PKG_NAME").to_owned(),
+        tool_version: env!("CARGO_PKG_VERSION").to_owned(),
+        note: None,
+    });
+
+    ensure_parent_dir(manifest)?;
+    sezkp_merkle::write_manifest_auto(manifest, &man)
+        .with_context(|| format!("writing manifest {}", manifest.display()))?;
+    println!(
+        "Followed {} ({} blocks) → manifest={}",
+        path.display(),
+        man.n_leaves,
+        manifest.display()
+    );
+    Ok((art, man))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::args::DurabilityOpt;
+    use crate::commit::CommitArgs;
+    use crate::{Cli, Cmd};
+    use clap::Parser;
+    use sezkp_trace::partition::PartitionStrategy;
+
+    #[test]
+    fn parse_prove_dry_run() {
+        let cli = Cli::parse_from([
+            "sezkp-cli",
+            "prove",
+            "--backend",
+            "stark",
+            "--blocks",
+            "blocks.jsonl",
+            "--manifest",
+            "manifest.cbor",
+            "--dry-run",
+        ]);
+        assert!(matches!(
+            cli.cmd,
+            Cmd::Prove(ProveArgs { dry_run: true, .. })
+        ));
+    }
+
+    #[test]
+    fn parse_prove_auto_backend() {
+        let cli = Cli::parse_from([
+            "sezkp-cli",
+            "prove",
+            "--backend",
+            "auto",
+            "--blocks",
+            "blocks.jsonl",
+            "--manifest",
+            "manifest.cbor",
+            "--auto-stark-max-rows",
+            "4096",
+        ]);
+        let Cmd::Prove(ProveArgs {
+            backend,
+            auto_stark_max_rows,
+            ..
+        }) = cli.cmd
+        else {
+            panic!("expected prove");
+        };
+        assert_eq!(backend.fixed(), None);
+        assert_eq!(auto_stark_max_rows, 4096);
+    }
+
+    #[test]
+    fn parse_prove_self_check() {
+        let cli = Cli::parse_from([
+            "sezkp-cli",
+            "prove",
+            "--backend",
+            "fold",
+            "--blocks",
+            "blocks.jsonl",
+            "--manifest",
+            "manifest.cbor",
+            "--stream",
+            "--self-check",
+        ]);
+        assert!(matches!(
+            cli.cmd,
+            Cmd::Prove(ProveArgs {
+                self_check: true,
+                stream: true,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn parse_prove_final_wrap() {
+        let cli = Cli::parse_from([
+            "sezkp-cli",
+            "prove",
+            "--backend",
+            "fold",
+            "--blocks",
+            "blocks.jsonl",
+            "--manifest",
+            "manifest.cbor",
+            "--final-wrap",
+            "mac-v1",
+            "--are-samples",
+            "16",
+            "--iface-digest",
+            "writes-only",
+        ]);
+        let Cmd::Prove(ProveArgs {
+            final_wrap,
+            are_samples,
+            iface_digest,
+            ..
+        }) = cli.cmd
+        else {
+            panic!("expected prove");
+        };
+        assert_eq!(final_wrap.as_deref(), Some("mac-v1"));
+        assert_eq!(are_samples, 16);
+        assert_eq!(iface_digest, DigestMode::WritesOnly);
+        let bad = [
+            "sezkp-cli",
+            "prove",
+            "--backend",
+            "fold",
+            "--iface-digest",
+            "partial",
+        ];
+        assert!(Cli::try_parse_from(bad).is_err());
+    }
+
+    #[test]
+    fn parse_prove_claims() {
+        let base = [
+            "sezkp-cli",
+            "prove",
+            "--backend",
+            "fold",
+            "--manifest",
+            "m.cbor",
+        ];
+        let cli = Cli::parse_from(
+            base.iter()
+                .chain(&["--blocks", "b.jsonl", "--claim", "output=42"])
+                .chain(&["--claim", "program=ab=cd"]),
+        );
+        let Cmd::Prove(ProveArgs { claims, .. }) = cli.cmd else {
+            panic!("expected prove");
+        };
+        assert_eq!(
+            claims,
+            [Claim::new("output", "42"), Claim::new("program", "ab=cd")]
+        );
+        let bad = base
+            .iter()
+            .chain(&["--blocks", "b.jsonl", "--claim", "nolabel"]);
+        assert!(Cli::try_parse_from(bad).is_err());
+    }
+
+    #[test]
+    fn parse_prove_from_trace() {
+        let cli = Cli::parse_from([
+            "sezkp-cli",
+            "prove",
+            "--backend",
+            "fold",
+            "--trace",
+            "trace.cbor",
+            "--b",
+            "256",
+            "--partition",
+            "balanced",
+            "--manifest",
+            "manifest.cbor",
+        ]);
+        let Cmd::Prove(ProveArgs { blocks, trace, .. }) = cli.cmd else {
+            panic!("expected prove");
+        };
+        let input = trace.input(blocks).unwrap();
+        assert!(matches!(
+            input,
+            BlockInput::Trace {
+                b: 256,
+                strategy: PartitionStrategy::Balanced,
+                ..
+            }
+        ));
+
+        // Exactly one block source; trace-only flags need --trace.
+        let base = [
+            "sezkp-cli",
+            "prove",
+            "--backend",
+            "fold",
+            "--manifest",
+            "m.cbor",
+        ];
+        for extra in [
+            &[][..],
+            &["--blocks", "b.jsonl", "--trace", "t.cbor"],
+            &["--blocks", "b.jsonl", "--b", "64"],
+            &["--trace", "t.cbor", "--dry-run"],
+        ] {
+            let args = base.iter().chain(extra);
+            assert!(Cli::try_parse_from(args).is_err(), "{extra:?}");
+        }
+        let cli = Cli::parse_from(["sezkp-cli", "commit", "--trace", "t.cbor"]);
+        let Cmd::Commit(CommitArgs { blocks, trace, .. }) = cli.cmd else {
+            panic!("expected commit");
+        };
+        assert!(matches!(
+            trace.input(blocks).unwrap(),
+            BlockInput::Trace { b: 512, .. }
+        ));
+    }
+
+    #[test]
+    fn parse_durability() {
+        let cli = Cli::parse_from([
+            "sezkp-cli",
+            "prove",
+            "--backend",
+            "fold",
+            "--blocks",
+            "blocks.jsonl",
+            "--manifest",
+            "manifest.cbor",
+            "--stream",
+            "--durability",
+            "journal",
+            "--sync-every",
+            "256",
+            "--resume",
+        ]);
+        let Cmd::Prove(ProveArgs { durability, .. }) = cli.cmd else {
+            panic!("expected prove");
+        };
+        assert_eq!(durability.durability, DurabilityOpt::Journal);
+        assert_eq!(
+            (durability.sync_every, durability.sync_secs),
+            (Some(256), None)
+        );
+        assert!(durability.resume);
+    }
+
+    #[test]
+    fn parse_follow() {
+        let args = |extra: &[&'static str]| {
+            let mut v = vec![
+                "sezkp-cli",
+                "prove",
+                "--backend",
+                "fold",
+                "--blocks",
+                "blocks.jsonl",
+                "--manifest",
+                "manifest.cbor",
+                "--follow",
+            ];
+            v.extend_from_slice(extra);
+            v
+        };
+        let cli = Cli::parse_from(args(&["--follow-idle", "30"]));
+        let Cmd::Prove(ProveArgs {
+            follow,
+            follow_done,
+            follow_idle,
+            ..
+        }) = cli.cmd
+        else {
+            panic!("expected prove");
+        };
+        assert!(follow);
+        assert_eq!((follow_done, follow_idle), (None, Some(30)));
+        let end = follow::FollowEnd::new(Path::new("run/blocks.jsonl"), None, None);
+        assert_eq!(end.done_marker, PathBuf::from("run/blocks.jsonl.done"));
+
+        // A plan cannot be made for a file that does not exist yet.
+        assert!(Cli::try_parse_from(args(&["--dry-run"])).is_err());
+        // The end-of-input flags need --follow.
+        assert!(Cli::try_parse_from([
+            "sezkp-cli",
+            "prove",
+            "--backend",
+            "fold",
+            "--blocks",
+            "blocks.jsonl",
+            "--manifest",
+            "manifest.cbor",
+            "--follow-done",
+            "blocks.done",
+        ])
+        .is_err());
+    }
+}
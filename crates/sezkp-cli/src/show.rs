@@ -0,0 +1,380 @@
+//! Read-only views of a blocks file: `show-block`, `head-blocks`,
+//! `tail-blocks`, `boundary`, `check-endpoints` and `check-iface-log`.
+//!
+//! JSONL inputs seek through their `.idx` sidecar when a fresh one exists;
+//! other formats are streamed up to the blocks asked for. Nothing is written
+//! except `check-endpoints --out`.
+
+use anyhow::{bail, ensure, Context, Result};
+use clap::Args;
+use sezkp_core::io::{block_file_format, stream_block_summaries_auto};
+use sezkp_core::io_format::FileFormat;
+use sezkp_core::io_jsonl::{head_blocks, read_block_jsonl, tail_blocks};
+use sezkp_core::replay::Replay;
+use sezkp_core::BlockSummary;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use tracing::info_span;
+
+use crate::ensure_parent_dir;
+
+/// Flags of `show-block`.
+#[derive(Args, Debug)]
+pub(crate) struct ShowBlockArgs {
+    /// Input path to σ_k block summaries (CBOR/JSON/JSONL/NDJSON).
+    #[arg(long)]
+    pub blocks: PathBuf,
+
+    /// 0-based block position in the file.
+    #[arg(long)]
+    pub block: usize,
+}
+
+/// Flags of `head-blocks` and `tail-blocks`.
+#[derive(Args, Debug)]
+pub(crate) struct EdgeBlocksArgs {
+    /// Input path to σ_k block summaries (CBOR/JSON/JSONL/NDJSON).
+    #[arg(long)]
+    pub blocks: PathBuf,
+
+    /// Number of blocks to print.
+    #[arg(short, long, default_value_t = 10)]
+    pub n: usize,
+}
+
+/// Flags of `boundary`.
+#[derive(Args, Debug)]
+pub(crate) struct BoundaryArgs {
+    /// Input path to σ_k block summaries (CBOR/JSON/JSONL/NDJSON).
+    #[arg(long)]
+    pub blocks: PathBuf,
+
+    /// 0-based block position in the file.
+    #[arg(long)]
+    pub index: usize,
+
+    /// Also print block `index + 1` and the digest of the seam between
+    /// the two, as the fold interface check recomputes it.
+    #[arg(long, default_value_t = false)]
+    pub next: bool,
+}
+
+/// Flags of `check-endpoints`.
+#[derive(Args, Debug)]
+pub(crate) struct CheckEndpointsArgs {
+    /// Input blocks path (CBOR/JSON/JSONL/NDJSON).
+    #[arg(long)]
+    pub blocks: PathBuf,
+    /// Write the deltas as JSONL here instead of printing them.
+    #[arg(long)]
+    pub out: Option<PathBuf>,
+}
+
+/// Flags of `check-iface-log`.
+#[derive(Args, Debug)]
+pub(crate) struct CheckIfaceLogArgs {
+    /// Input path to σ_k block summaries (CBOR/JSON/JSONL/NDJSON).
+    #[arg(long)]
+    pub blocks: PathBuf,
+
+    /// NDJSON interface log.
+    #[arg(long)]
+    pub log: PathBuf,
+}
+
+/// Block `n` of `blocks`, seeking through a `.idx` sidecar when there is one.
+pub(crate) fn block_at(blocks: &Path, n: usize) -> Result<BlockSummary> {
+    if block_file_format(blocks)? == FileFormat::Jsonl {
+        read_block_jsonl(blocks, n)
+    } else {
+        match stream_block_summaries_auto(blocks)?.nth(n) {
+            Some(b) => b,
+            None => bail!("block {n} out of range"),
+        }
+    }
+}
+
+/// Print block `n` (0-based) of a blocks file as pretty JSON.
+///
+/// JSONL inputs seek via their index sidecar when a fresh one exists; other
+/// formats are streamed up to the block.
+///
+/// # Errors
+/// Fails if the file has no block `n` or cannot be read.
+pub(crate) fn show_block(a: ShowBlockArgs) -> Result<()> {
+    let ShowBlockArgs { blocks, block: n } = a;
+    let _span = info_span!("show_block", blocks = %blocks.display(), n).entered();
+    let blk = block_at(&blocks, n)?;
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&blk).context("serialize block")?
+    );
+    Ok(())
+}
+
+/// Print the first (or, with `tail`, the last) `n` blocks as JSONL.
+pub(crate) fn edge_blocks(a: EdgeBlocksArgs, tail: bool) -> Result<()> {
+    let EdgeBlocksArgs { blocks, n } = a;
+    let blocks = blocks.as_path();
+    let _span = info_span!("edge_blocks", blocks = %blocks.display(), n, tail).entered();
+    let v = if block_file_format(blocks)? == FileFormat::Jsonl {
+        if tail {
+            tail_blocks(blocks, n)?
+        } else {
+            head_blocks(blocks, n)?
+        }
+    } else if tail {
+        let mut last = std::collections::VecDeque::with_capacity(n);
+        for b in stream_block_summaries_auto(blocks)? {
+            let b = b?;
+            if last.len() == n {
+                last.pop_front();
+            }
+            if n > 0 {
+                last.push_back(b);
+            }
+        }
+        last.into()
+    } else {
+        stream_block_summaries_auto(blocks)?
+            .take(n)
+            .collect::<Result<_>>()?
+    };
+    let mut out = std::io::stdout().lock();
+    for b in &v {
+        serde_json::to_writer(&mut out, b).context("serialize block")?;
+        writeln!(out).context("write stdout")?;
+    }
+    Ok(())
+}
+
+/// Print the boundary digests of block `index` (and of the seam with the
+/// next block when `next`), with the inputs they hash.
+pub(crate) fn boundary(a: BoundaryArgs) -> Result<()> {
+    use sezkp_core::{interface_boundary_digest, IFACE_WINDOW_STEPS};
+
+    let BoundaryArgs {
+        blocks,
+        index,
+        next,
+    } = a;
+    let _span = info_span!("boundary", blocks = %blocks.display(), index, next).entered();
+    let left = block_at(&blocks, index)?;
+    println!("Window: {IFACE_WINDOW_STEPS} steps per side; rows are mv[:written symbol]");
+    print_boundary_side(index, &left);
+    if next {
+        let right = block_at(&blocks, index + 1)?;
+        print_boundary_side(index + 1, &right);
+        println!(
+            "Interface digest {index}|{}: {}",
+            index + 1,
+            hex::encode(interface_boundary_digest(&left, &right))
+        );
+    }
+    Ok(())
+}
+
+fn print_boundary_side(index: usize, b: &BlockSummary) {
+    use sezkp_core::boundary::{BoundaryRow, BoundarySide};
+
+    let side = BoundarySide::of(b);
+    let rows = |tapes: &[Vec<BoundaryRow>]| {
+        for (r, tape) in tapes.iter().enumerate() {
+            let cells: Vec<String> = tape
+                .iter()
+                .map(|row| match row.write_flag {
+                    0 => format!("{:+}", row.mv),
+                    _ => format!("{:+}:{}", row.mv, row.write_sym),
+                })
+                .collect();
+            println!("    tape {r}: {}", cells.join(" "));
+        }
+    };
+    println!(
+        "Block {index} (id {}, steps {}..={}, τ={}):",
+        b.block_id,
+        b.step_lo,
+        b.step_hi,
+        b.windows.len()
+    );
+    println!("  head_in_offsets  : {:?}", side.head_in_offsets);
+    println!("  head_out_offsets : {:?}", side.head_out_offsets);
+    println!(
+        "  left tail digest : {}",
+        hex::encode(side.left_tail_digest)
+    );
+    rows(&side.tail);
+    println!(
+        "  right head digest: {}",
+        hex::encode(side.right_head_digest)
+    );
+    rows(&side.head);
+}
+
+/// Report declared exit heads that replay does not reproduce.
+pub(crate) fn check_endpoints(a: CheckEndpointsArgs) -> Result<()> {
+    let CheckEndpointsArgs { blocks, out } = a;
+    let _span = info_span!("check_endpoints", blocks = %blocks.display()).entered();
+    let replay = Replay::new();
+    let iter = stream_block_summaries_auto(&blocks).context("open input stream")?;
+    let mut w = match &out {
+        Some(path) => {
+            ensure_parent_dir(path)?;
+            let f = File::create(path).with_context(|| format!("create {}", path.display()))?;
+            Some(BufWriter::new(f))
+        }
+        None => None,
+    };
+
+    let (mut n, mut bad, mut total) = (0usize, 0usize, 0usize);
+    for item in iter {
+        let deltas = replay.endpoint_deltas(&item?)?;
+        n += 1;
+        if deltas.is_empty() {
+            continue;
+        }
+        bad += 1;
+        total += deltas.len();
+        for d in &deltas {
+            match &mut w {
+                Some(w) => {
+                    serde_json::to_writer(&mut *w, d).context("serialize endpoint delta")?;
+                    w.write_all(b"\n")?;
+                }
+                None => println!("{d}"),
+            }
+        }
+    }
+    if let Some(mut w) = w {
+        w.flush()?;
+    }
+
+    if let Some(path) = &out {
+        println!("Wrote {total} endpoint deltas → {}", path.display());
+    }
+    ensure!(
+        bad == 0,
+        "{bad} of {n} blocks declare exit heads their movement logs do not reach"
+    );
+    println!("OK: declared endpoints of all {n} blocks match replay");
+    Ok(())
+}
+
+/// Re-derive the seams of an interface witness log from `blocks`.
+pub(crate) fn check_iface_log(a: CheckIfaceLogArgs) -> Result<()> {
+    use sezkp_fold::iface_log;
+
+    let CheckIfaceLogArgs { blocks, log } = a;
+    let _span = info_span!("check_iface_log", blocks = %blocks.display()).entered();
+    let file = File::open(&log).with_context(|| format!("open {}", log.display()))?;
+    let records = iface_log::read_iface_log(std::io::BufReader::new(file))
+        .with_context(|| format!("reading {}", log.display()))?;
+    let n = iface_log::check_iface_log(stream_block_summaries_auto(&blocks)?, records)?;
+    println!("OK: {n} interface witnesses match {}", blocks.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocks::ExportJsonlArgs;
+    use crate::{Cli, Cmd};
+    use clap::Parser;
+    use sezkp_core::io_jsonl::DEFAULT_INDEX_STRIDE;
+
+    #[test]
+    fn parse_show_block_and_index_stride() {
+        let cli = Cli::parse_from([
+            "sezkp-cli",
+            "show-block",
+            "--blocks",
+            "blocks.jsonl",
+            "--block",
+            "42",
+        ]);
+        assert!(matches!(
+            cli.cmd,
+            Cmd::ShowBlock(ShowBlockArgs { block: 42, .. })
+        ));
+
+        let cli = Cli::parse_from(["sezkp-cli", "tail-blocks", "--blocks", "b.jsonl", "-n", "3"]);
+        assert!(matches!(
+            cli.cmd,
+            Cmd::TailBlocks(EdgeBlocksArgs { n: 3, .. })
+        ));
+        let cli = Cli::parse_from(["sezkp-cli", "head-blocks", "--blocks", "b.jsonl"]);
+        assert!(matches!(
+            cli.cmd,
+            Cmd::HeadBlocks(EdgeBlocksArgs { n: 10, .. })
+        ));
+
+        let cli = Cli::parse_from([
+            "sezkp-cli",
+            "check-iface-log",
+            "--blocks",
+            "b.jsonl",
+            "--log",
+            "iface.ndjson",
+        ]);
+        assert!(matches!(
+            cli.cmd,
+            Cmd::CheckIfaceLog(CheckIfaceLogArgs { .. })
+        ));
+
+        let cli = Cli::parse_from([
+            "sezkp-cli",
+            "export-jsonl",
+            "--input",
+            "blocks.cbor",
+            "--output",
+            "blocks.jsonl",
+        ]);
+        assert!(matches!(
+            cli.cmd,
+            Cmd::ExportJsonl(ExportJsonlArgs {
+                index_stride: DEFAULT_INDEX_STRIDE,
+                redact_key: None,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn parse_boundary() {
+        let cli = Cli::parse_from([
+            "sezkp-cli",
+            "boundary",
+            "--blocks",
+            "blocks.jsonl",
+            "--index",
+            "3",
+            "--next",
+        ]);
+        assert!(matches!(
+            cli.cmd,
+            Cmd::Boundary(BoundaryArgs {
+                index: 3,
+                next: true,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn parse_check_endpoints() {
+        let cli = Cli::parse_from([
+            "sezkp-cli",
+            "--strict-endpoints",
+            "check-endpoints",
+            "--blocks",
+            "adapter.jsonl",
+        ]);
+        assert!(cli.strict_endpoints);
+        let Cmd::CheckEndpoints(CheckEndpointsArgs { blocks, out }) = cli.cmd else {
+            panic!("expected check-endpoints");
+        };
+        assert_eq!(blocks, PathBuf::from("adapter.jsonl"));
+        assert_eq!(out, None);
+    }
+}
@@ -0,0 +1,692 @@
+//! `verify`: check a proof artifact against its blocks and manifest.
+//!
+//! [`run`] reads the artifact first: without `--backend` the backend that
+//! made it verifies it ([`verify_backend`]), and a `--params` file replaces
+//! the fold expectations. [`verify_artifact`] is the verification proper,
+//! shared with `prove --self-check` and `verify-composite`; both backends
+//! stream the blocks to keep memory sublinear.
+
+use anyhow::{bail, ensure, Context, Result};
+use clap::Args;
+use sezkp_core::auxiliary::AuxDigest;
+use sezkp_core::claims::Claim;
+use sezkp_core::composite::is_composite_artifact;
+use sezkp_core::io::read_proof_auto;
+use sezkp_core::replay::{Replay, ReplayConfig};
+use sezkp_core::timestamp::{now_unix_secs, TimeAuthority};
+use sezkp_core::{BackendKind, ProofArtifact};
+use std::path::{Path, PathBuf};
+use tracing::{info_span, warn};
+
+use crate::args::{
+    BackendOpt, BlockInput, FoldExpectArgs, FoldLedgerOpt, FoldModeOpt, ReplayFlags, TimestampArgs,
+};
+use crate::migrate::removed_in;
+use crate::prove::parse_beacon;
+use crate::{envguard, params, plan};
+
+/// Flags of `verify`.
+#[derive(Args, Debug)]
+pub(crate) struct VerifyArgs {
+    /// Proof backend (default: the one recorded in the artifact; when
+    /// given, it must match).
+    #[arg(value_enum, long)]
+    pub backend: Option<BackendOpt>,
+
+    /// Input path to σ_k block summaries (CBOR/JSON/JSONL/NDJSON).
+    ///
+    /// For sublinear memory, prefer `.jsonl`/`.ndjson`.
+    #[arg(long, required_unless_present = "spot_check")]
+    pub blocks: Option<PathBuf>,
+
+    /// Input path to manifest (CBOR/JSON).
+    #[arg(long)]
+    pub manifest: PathBuf,
+
+    /// Input path to proof artifact (CBOR/JSON).
+    #[arg(long)]
+    pub proof: PathBuf,
+
+    /// Instead of reading the blocks file, check at least this many
+    /// blocks embedded in a batch fold proof (`prove --spot-checks`)
+    /// against the manifest root.
+    #[arg(long, value_name = "MIN", conflicts_with = "blocks")]
+    pub spot_check: Option<u32>,
+
+    /// Require the STARK proof to be bound to this beacon (hex).
+    #[arg(long)]
+    pub beacon: Option<String>,
+
+    /// Reject STARK proofs whose query counts give an estimated
+    /// security level below this many bits (at least the default 90
+    /// unless `--allow-low-security` is given).
+    #[arg(long, value_name = "BITS")]
+    pub min_security_bits: Option<usize>,
+
+    /// Let `--min-security-bits` go below the default level.
+    #[arg(long, requires = "min_security_bits")]
+    pub allow_low_security: bool,
+
+    /// Require STARK proofs to declare this per-step move bound
+    /// instead of unit moves.
+    #[arg(long, value_name = "N")]
+    pub expect_max_move: Option<u8>,
+
+    #[command(flatten)]
+    pub timestamp: TimestampArgs,
+
+    /// Reject artifacts whose checked timestamp is older than this many
+    /// seconds (requires `--timestamp-key`).
+    #[arg(long, requires = "timestamp_key")]
+    pub max_age: Option<u64>,
+
+    #[command(flatten)]
+    pub expect: FoldExpectArgs,
+
+    /// Parameter file (`gen-params`) the proof must have been made
+    /// under: requires its parameters and its digest claim.
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with_all = [
+            "expect_fold_mode",
+            "expect_wrap_cadence",
+            "expect_are_samples",
+            "expect_iface_digest"
+        ]
+    )]
+    pub params: Option<PathBuf>,
+
+    /// Assume the blocks file has already been verified against the manifest.
+    ///
+    /// Skips the extra pre-check inside `verify` to avoid redundant I/O/RSS.
+    #[arg(long, default_value_t = false)]
+    pub assume_committed: bool,
+
+    /// Resolve inputs, print the execution plan, and exit without verifying.
+    #[arg(long, default_value_t = false)]
+    pub dry_run: bool,
+}
+
+/// Run `verify`: read the artifact, pick the backend, apply `--params` and
+/// the STARK checks, then print the plan (`--dry-run`) or verify.
+pub(crate) fn run(a: VerifyArgs, flags: ReplayFlags) -> Result<()> {
+    let VerifyArgs {
+        backend,
+        blocks,
+        manifest,
+        proof,
+        beacon,
+        min_security_bits,
+        allow_low_security,
+        expect_max_move,
+        timestamp,
+        max_age,
+        mut expect,
+        params,
+        spot_check,
+        assume_committed,
+        dry_run,
+    } = a;
+    let artifact = read_proof_auto(&proof)
+        .with_context(|| format!("reading proof artifact from {}", proof.display()))?;
+    let backend = verify_backend(backend, &artifact)?;
+    if dry_run {
+        let Some(blocks) = &blocks else {
+            bail!("--dry-run needs --blocks");
+        };
+        let p = plan::build_plan(&plan::PlanArgs {
+            kind: plan::PlanKind::Verify,
+            backend,
+            blocks,
+            manifest: &manifest,
+            out: None,
+            proof: Some(&proof),
+            fold_mode: FoldModeOpt::Balanced,
+            fold_cache: 0,
+            fold_ledger: FoldLedgerOpt::Memory,
+            wrap_cadence: 0,
+            stream: true,
+            assume_committed,
+            self_check: false,
+            proof_cache: None,
+            selection: None,
+        })?;
+        plan::print_plan(&p);
+        return Ok(());
+    }
+    let params = params.as_deref().map(params::read_params).transpose()?;
+    if let Some(p) = &params {
+        p.check_build().context("parameter file")?;
+        p.apply_to_verifier();
+        if matches!(backend, BackendOpt::Fold) {
+            expect = FoldExpectArgs::from_params(&p.fold);
+        }
+    }
+    if let Some(bits) = min_security_bits {
+        if matches!(backend, BackendOpt::Fold) {
+            warn!("--min-security-bits only applies to the stark backend; ignoring");
+        }
+        envguard::set(sezkp_stark::params::ENV_MIN_SECURITY_BITS, bits.to_string());
+        if allow_low_security {
+            envguard::set(sezkp_stark::params::ENV_ALLOW_LOW_SECURITY, "1");
+        }
+    }
+    if let Some(n) = expect_max_move {
+        if matches!(backend, BackendOpt::Fold) {
+            warn!("--expect-max-move only applies to the stark backend; ignoring");
+        }
+        envguard::set(sezkp_stark::params::ENV_EXPECT_MAX_MOVE, n.to_string());
+    }
+    expect.apply(backend);
+    let blocks = match (blocks, spot_check) {
+        (_, Some(min)) => VerifyBlocks::SpotCheck(min),
+        (Some(path), None) => VerifyBlocks::File(path),
+        (None, None) => bail!("--blocks is required without --spot-check"),
+    };
+    verify(
+        backend,
+        blocks,
+        manifest,
+        &proof,
+        &artifact,
+        beacon.as_deref(),
+        timestamp.authority()?,
+        max_age,
+        params.as_ref(),
+        assume_committed,
+        flags,
+    )
+}
+
+/// Where `verify` gets the blocks it checks against the manifest.
+#[derive(Debug)]
+enum VerifyBlocks {
+    /// Read every block from this file.
+    File(PathBuf),
+    /// Check at least this many blocks embedded in the proof instead.
+    SpotCheck(u32),
+}
+
+/// Backend to verify `artifact` with: the one that produced it, which an
+/// explicit `--backend` must agree with.
+///
+/// Fold artifacts from older releases are labelled `stark`; they resolve to
+/// the fold backend, with a hint to migrate them.
+///
+/// # Errors
+/// Fails if `requested` names another backend, or if no verifier here
+/// handles the artifact's backend.
+pub(crate) fn verify_backend(
+    requested: Option<BackendOpt>,
+    artifact: &ProofArtifact,
+) -> Result<BackendOpt> {
+    let kind = artifact.resolved_backend();
+    let own = match kind {
+        BackendKind::Fold => BackendOpt::Fold,
+        BackendKind::Stark => BackendOpt::Stark,
+        _ if is_composite_artifact(artifact) => {
+            bail!("this proof wraps a composite; use verify-composite")
+        }
+        other => bail!("no verifier for artifacts from the {other} backend"),
+    };
+    if let Some(b) = requested {
+        ensure!(
+            b == own,
+            "--backend {} does not match the artifact, which is from the {kind} backend",
+            format!("{b:?}").to_lowercase()
+        );
+    }
+    if artifact.has_legacy_label() {
+        warn!("fold artifact carries the legacy `stark` label; run migrate-proof to relabel it");
+    }
+    Ok(own)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn verify(
+    backend: BackendOpt,
+    blocks: VerifyBlocks,
+    manifest: PathBuf,
+    proof: &Path,
+    artifact: &ProofArtifact,
+    beacon: Option<&str>,
+    tsa: Option<TimeAuthority>,
+    max_age: Option<u64>,
+    params: Option<&params::ParamFile>,
+    assume_committed: bool,
+    flags: ReplayFlags,
+) -> Result<()> {
+    let _span = info_span!(
+        "verify",
+        ?backend,
+        ?blocks,
+        manifest = %manifest.display(),
+        proof = %proof.display()
+    )
+    .entered();
+
+    use sezkp_merkle::{read_manifest_auto, verify_block_file_against_manifest};
+
+    // Skip redundant blocks/manifest pre-check if caller already verified it.
+    if let (VerifyBlocks::File(blocks), false) = (&blocks, assume_committed) {
+        verify_block_file_against_manifest(blocks, &manifest)
+            .context("blocks/manifest mismatch")?;
+    }
+
+    let man = read_manifest_auto(&manifest).context("reading manifest")?;
+    let beacon = beacon.map(parse_beacon).transpose()?;
+
+    // Timestamp checks are cheap; fail before the proof work.
+    let attested = match (&tsa, &artifact.timestamp) {
+        (Some(tsa), _) => Some(
+            artifact
+                .verify_timestamp(tsa, now_unix_secs(), max_age)
+                .context("timestamp check failed")?,
+        ),
+        (None, Some(t)) => {
+            warn!(
+                authority = %t.authority,
+                "artifact carries a timestamp; pass --timestamp-key to check it"
+            );
+            None
+        }
+        (None, None) => None,
+    };
+    // The claim is bound by the proof itself, so checking it here is enough.
+    if let Some(p) = params {
+        p.check_claimed(&artifact.claims)?;
+    }
+
+    if beacon.is_some() && matches!(backend, BackendOpt::Fold) {
+        warn!("--beacon only applies to the stark backend; ignoring");
+    }
+    let aux = match blocks {
+        VerifyBlocks::File(path) => {
+            let blocks = BlockInput::File(path);
+            let aux = verify_artifact(backend, artifact, &blocks, &man, beacon.as_deref(), flags)?;
+            println!("OK: proof verified");
+            aux
+        }
+        VerifyBlocks::SpotCheck(min) => {
+            ensure!(
+                matches!(backend, BackendOpt::Fold),
+                "--spot-check only applies to the fold backend"
+            );
+            let replay = Replay {
+                cfg: ReplayConfig {
+                    check_writes: true,
+                    input_len: man.input_len,
+                    strict_endpoints: flags.strict_endpoints,
+                    ..ReplayConfig::default()
+                },
+            };
+            let n = sezkp_fold::FoldBackend::verify_spot_checked(artifact, man.root, min, &replay)
+                .context("fold backend verification failed")?;
+            println!(
+                "OK: proof verified (spot-checked {n} of {} blocks)",
+                man.n_leaves
+            );
+            // Aux commitments are only summarized from a full blocks pass.
+            AuxDigest::new()
+        }
+    };
+    print_claims(&artifact.claims);
+    if let Some(d) = aux.finish() {
+        println!(
+            "Aux commitments: {} in {} blocks (digest {})",
+            aux.commitments(),
+            aux.blocks(),
+            hex::encode(d)
+        );
+    }
+    if let (Some(at), Some(tsa)) = (attested, &tsa) {
+        println!(
+            "Timestamp: created at unix {at} (attested by {:?}, {}s ago)",
+            tsa.name(),
+            now_unix_secs().saturating_sub(at)
+        );
+    }
+    Ok(())
+}
+
+/// Run the backend verifier for `artifact` over the blocks at `blocks`,
+/// against the root and declared input length of `man`, and return the
+/// digest of the auxiliary commitments the blocks carried.
+///
+/// Shared by `verify`, `prove --self-check` and `verify-composite`; both
+/// backends stream the blocks to keep memory sublinear.
+pub(crate) fn verify_artifact(
+    backend: BackendOpt,
+    artifact: &ProofArtifact,
+    blocks: &BlockInput,
+    man: &sezkp_merkle::CommitManifest,
+    beacon: Option<&[u8]>,
+    flags: ReplayFlags,
+) -> Result<AuxDigest> {
+    let root = man.root;
+    declare_input_len(man.input_len);
+
+    let mut aux = AuxDigest::new();
+    let iter = blocks.stream()?.inspect(|b| {
+        if let Ok(b) = b {
+            aux.push(b);
+        }
+    });
+    match backend {
+        BackendOpt::Fold => {
+            use sezkp_fold::migrate;
+            if let Ok(Some(v @ (1 | 2))) = migrate::envelope_version(artifact) {
+                warn!(
+                    "V{v} fold envelope is deprecated and will not be read from release {}; \
+                     upgrade it with `sezkp-cli migrate-proof`",
+                    removed_in(v)
+                );
+            }
+            flags
+                .fold_prover()
+                .with_input_len(man.input_len)
+                .verify_stream(artifact, iter, root)
+                .context("fold backend verification failed")?;
+        }
+        BackendOpt::Stark => {
+            // v1 STARK verifier (manifest-root checked inside); streaming
+            // also recomputes the column roots from the blocks.
+            use sezkp_stark::StarkV1;
+            StarkV1::verify_stream(artifact, iter, root, beacon)
+                .context("stark-v1 verification failed")?;
+        }
+    }
+    Ok(aux)
+}
+
+/// Hand a manifest's declared input tape length to the STARK backend, which
+/// reads it from the environment (the fold path passes it to replay instead).
+pub(crate) fn declare_input_len(input_len: Option<u64>) {
+    match input_len {
+        Some(n) => envguard::set(sezkp_stark::params::ENV_INPUT_LEN, n.to_string()),
+        None => envguard::unset(sezkp_stark::params::ENV_INPUT_LEN),
+    }
+}
+
+/// Print the public claims an artifact carries, one per line.
+pub(crate) fn print_claims(claims: &[Claim]) {
+    if claims.is_empty() {
+        return;
+    }
+    println!("Claims:");
+    for c in claims {
+        println!("  {c}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prove::ProveArgs;
+    use crate::{Cli, Cmd};
+    use clap::Parser;
+    use sezkp_core::boundary::DigestMode;
+
+    #[test]
+    fn parse_verify_fold_expectations() {
+        let cli = Cli::parse_from([
+            "sezkp-cli",
+            "verify",
+            "--backend",
+            "fold",
+            "--blocks",
+            "blocks.jsonl",
+            "--manifest",
+            "manifest.cbor",
+            "--proof",
+            "proof.cbor",
+            "--expect-fold-mode",
+            "minram",
+            "--expect-wrap-cadence",
+            "4",
+            "--expect-are-samples",
+            "8",
+            "--expect-iface-digest",
+            "heads",
+        ]);
+        let Cmd::Verify(VerifyArgs { expect, .. }) = cli.cmd else {
+            panic!("expected verify");
+        };
+        assert_eq!(expect.expect_fold_mode, Some(FoldModeOpt::Minram));
+        assert_eq!(expect.expect_wrap_cadence, Some(4));
+        assert_eq!(expect.expect_are_samples, Some(8));
+        assert_eq!(expect.expect_iface_digest, Some(DigestMode::Heads));
+    }
+
+    #[test]
+    fn verify_backend_follows_the_artifact() {
+        let cli = Cli::parse_from([
+            "sezkp-cli",
+            "verify",
+            "--blocks",
+            "blocks.jsonl",
+            "--manifest",
+            "manifest.cbor",
+            "--proof",
+            "proof.cbor",
+        ]);
+        let Cmd::Verify(VerifyArgs { backend, .. }) = cli.cmd else {
+            panic!("expected verify");
+        };
+        assert_eq!(backend, None);
+
+        let art = |kind, proto: &str| {
+            ProofArtifact::new(
+                kind,
+                [1u8; 32],
+                vec![1],
+                serde_json::json!({ "proto": proto }),
+            )
+        };
+        let fold = art(BackendKind::Fold, "fold-v3");
+        assert_eq!(verify_backend(None, &fold).unwrap(), BackendOpt::Fold);
+        let err = verify_backend(Some(BackendOpt::Stark), &fold).unwrap_err();
+        assert!(err.to_string().contains("fold backend"), "{err}");
+        // Fold artifacts from older releases are labelled stark.
+        let legacy = art(BackendKind::Stark, "fold-v2");
+        assert_eq!(
+            verify_backend(Some(BackendOpt::Fold), &legacy).unwrap(),
+            BackendOpt::Fold
+        );
+        let stark = art(BackendKind::Stark, "stark-v1");
+        assert_eq!(verify_backend(None, &stark).unwrap(), BackendOpt::Stark);
+        assert!(verify_backend(None, &art(BackendKind::from_proto("x"), "x-v1")).is_err());
+    }
+
+    #[test]
+    fn parse_timestamp_flags() {
+        let cli = Cli::parse_from([
+            "sezkp-cli",
+            "verify",
+            "--backend",
+            "fold",
+            "--blocks",
+            "blocks.jsonl",
+            "--manifest",
+            "manifest.cbor",
+            "--proof",
+            "proof.cbor",
+            "--timestamp-key",
+            "tsa.key",
+            "--max-age",
+            "3600",
+        ]);
+        let Cmd::Verify(VerifyArgs {
+            timestamp, max_age, ..
+        }) = cli.cmd
+        else {
+            panic!("expected verify");
+        };
+        assert_eq!(timestamp.timestamp_key, Some(PathBuf::from("tsa.key")));
+        assert_eq!(timestamp.timestamp_authority, "local");
+        assert_eq!(max_age, Some(3600));
+
+        // --max-age without a key has nothing to check against.
+        assert!(Cli::try_parse_from([
+            "sezkp-cli",
+            "verify",
+            "--backend",
+            "fold",
+            "--blocks",
+            "b.jsonl",
+            "--manifest",
+            "m.cbor",
+            "--proof",
+            "p.cbor",
+            "--max-age",
+            "1",
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn parse_verify_beacon() {
+        let cli = Cli::parse_from([
+            "sezkp-cli",
+            "verify",
+            "--backend",
+            "stark",
+            "--blocks",
+            "blocks.jsonl",
+            "--manifest",
+            "manifest.cbor",
+            "--proof",
+            "proof.cbor",
+            "--beacon",
+            "0xdeadbeef",
+        ]);
+        let Cmd::Verify(VerifyArgs { beacon, .. }) = cli.cmd else {
+            panic!("expected verify");
+        };
+        assert_eq!(
+            parse_beacon(beacon.as_deref().unwrap()).unwrap(),
+            [0xde, 0xad, 0xbe, 0xef]
+        );
+        assert!(parse_beacon("xyz").is_err());
+        assert!(parse_beacon("").is_err());
+    }
+
+    #[test]
+    fn parse_security_bits() {
+        let prove = [
+            "sezkp-cli",
+            "prove",
+            "--backend",
+            "stark",
+            "--blocks",
+            "blocks.cbor",
+            "--manifest",
+            "manifest.cbor",
+            "--security-bits",
+            "100",
+        ];
+        let Cmd::Prove(ProveArgs { security_bits, .. }) = Cli::parse_from(prove).cmd else {
+            panic!("expected prove");
+        };
+        assert_eq!(security_bits, Some(100));
+        // A parameter file pins the level itself.
+        let mut with_params = prove.to_vec();
+        with_params.extend(["--params", "params.cbor"]);
+        assert!(Cli::try_parse_from(with_params).is_err());
+
+        let cli = Cli::parse_from([
+            "sezkp-cli",
+            "verify",
+            "--backend",
+            "stark",
+            "--blocks",
+            "blocks.cbor",
+            "--manifest",
+            "manifest.cbor",
+            "--proof",
+            "proof.cbor",
+            "--min-security-bits",
+            "96",
+        ]);
+        let Cmd::Verify(VerifyArgs {
+            min_security_bits, ..
+        }) = cli.cmd
+        else {
+            panic!("expected verify");
+        };
+        assert_eq!(min_security_bits, Some(96));
+
+        // The opt-out only qualifies an explicit minimum.
+        assert!(Cli::try_parse_from([
+            "sezkp-cli",
+            "verify",
+            "--blocks",
+            "blocks.cbor",
+            "--manifest",
+            "manifest.cbor",
+            "--proof",
+            "proof.cbor",
+            "--allow-low-security",
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn parse_spot_checks() {
+        let cli = Cli::parse_from([
+            "sezkp-cli",
+            "prove",
+            "--backend",
+            "fold",
+            "--blocks",
+            "blocks.jsonl",
+            "--manifest",
+            "manifest.cbor",
+            "--spot-checks",
+            "16",
+        ]);
+        let Cmd::Prove(ProveArgs { spot_checks, .. }) = cli.cmd else {
+            panic!("expected prove");
+        };
+        assert_eq!(spot_checks, 16);
+
+        // Spot-check verification needs no blocks file, and rejects one.
+        let cli = Cli::parse_from([
+            "sezkp-cli",
+            "verify",
+            "--backend",
+            "fold",
+            "--manifest",
+            "manifest.cbor",
+            "--proof",
+            "proof.cbor",
+            "--spot-check",
+            "8",
+        ]);
+        let Cmd::Verify(VerifyArgs {
+            blocks, spot_check, ..
+        }) = cli.cmd
+        else {
+            panic!("expected verify");
+        };
+        assert_eq!((blocks, spot_check), (None, Some(8)));
+        let both = [
+            "sezkp-cli",
+            "verify",
+            "--backend",
+            "fold",
+            "--blocks",
+            "blocks.jsonl",
+            "--manifest",
+            "manifest.cbor",
+            "--proof",
+            "proof.cbor",
+            "--spot-check",
+            "8",
+        ];
+        assert!(Cli::try_parse_from(both).is_err());
+        assert!(Cli::try_parse_from(&both[..10]).is_ok());
+        assert!(Cli::try_parse_from([&both[..4], &both[6..10]].concat()).is_err());
+    }
+}
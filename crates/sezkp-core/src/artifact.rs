@@ -15,12 +15,56 @@
 //! - Prefer adding new **optional** fields with `#[serde(default)]` rather than
 //!   changing existing field types.
 //!
+//! ## Empty inputs
+//! A proof over **zero** blocks is a well-defined statement ("the committed
+//! block set is empty"). Every backend binds such a proof to [`EMPTY_ROOT`]
+//! and verifiers accept it only for that root; conversely no proof over a
+//! non-empty block set may be bound to it (see [`check_empty_root`]).
+//! A single block is not special: it is proved like any other input and its
+//! root is that block's leaf commitment.
+//!
+//! ## Corruption checks
+//! Producers record a BLAKE3 digest of `proof_bytes` in `proof_digest`, which
+//! is serialized ahead of the bytes. Deserialization hashes `proof_bytes` as
+//! it is read and fails with "artifact corrupted" on a mismatch, so a
+//! truncated or bit-flipped multi-GB artifact is rejected by the reader
+//! instead of deep inside backend decoding. Artifacts without a digest (older
+//! producers) read as before.
+//!
+//! ## Claims
+//! `claims` lists caller-defined public statements the proof binds (see
+//! [`crate::claims`]). Backends check them against the proof when verifying;
+//! artifacts without claims serialize exactly as before.
+//!
 //! ## When to use `meta`
 //! `meta` is intended for human/ops diagnostics (timings, parameter echoes,
 //! cache stats). Avoid parsing it in critical paths—if a value matters at
 //! runtime, promote it into a stable, typed field.
 
+use anyhow::Context as _;
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
 use serde::{Deserialize, Serialize};
+use std::fmt;
+
+pub use crate::frontier::EMPTY_ROOT;
+
+/// Enforce the empty-set convention: a statement covers no blocks **iff** it
+/// is bound to [`EMPTY_ROOT`].
+///
+/// # Errors
+/// Fails for an empty statement bound to another root, or a non-empty one
+/// bound to `EMPTY_ROOT`.
+pub fn check_empty_root(is_empty: bool, root: &[u8; 32]) -> anyhow::Result<()> {
+    match (is_empty, *root == EMPTY_ROOT) {
+        (true, false) => {
+            anyhow::bail!("a proof over zero blocks must be bound to the empty root")
+        }
+        (false, true) => {
+            anyhow::bail!("the empty root is reserved for proofs over zero blocks")
+        }
+        _ => Ok(()),
+    }
+}
 
 /// Which backend generated the proof.
 ///
@@ -28,6 +72,10 @@ use serde::{Deserialize, Serialize};
 /// forcing downstream exhaustive matches at compile time. At *runtime*,
 /// unknown serialized variants decode as [`BackendKind::Unknown`] to preserve
 /// forward compatibility across crate versions.
+///
+/// Releases before the `Fold` label was written tagged fold artifacts as
+/// `Stark` and recorded the protocol in `meta.proto`; see
+/// [`ProofArtifact::resolved_backend`].
 #[non_exhaustive]
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "lowercase")]
@@ -36,11 +84,126 @@ pub enum BackendKind {
     Stark,
     /// Folding/aggregation-based backend.
     Fold,
+    /// Out-of-tree backend, identified by its protocol family name.
+    ///
+    /// Serialized as `{"custom": "<name>"}`.
+    Custom(BackendName),
     /// Catch-all for newer/unknown backends when deserializing.
     #[serde(other)]
     Unknown,
 }
 
+impl BackendKind {
+    /// Protocol family name, as used as the prefix of `meta.proto`
+    /// (`"stark"` for `stark-v1`, `"fold"` for `fold-v3`).
+    #[must_use]
+    pub fn proto(&self) -> &str {
+        match self {
+            Self::Stark => "stark",
+            Self::Fold => "fold",
+            Self::Unknown => "unknown",
+            Self::Custom(name) => name.as_str(),
+        }
+    }
+
+    /// Backend named by a protocol string such as `fold-v3` or `stark-v1`
+    /// (the part before the first `-`); other names become
+    /// [`BackendKind::Custom`], or [`BackendKind::Unknown`] if longer than
+    /// [`BackendName::MAX_LEN`].
+    #[must_use]
+    pub fn from_proto(proto: &str) -> Self {
+        match proto.split('-').next().unwrap_or_default() {
+            "stark" => Self::Stark,
+            "fold" => Self::Fold,
+            "" | "unknown" => Self::Unknown,
+            name => BackendName::new(name).map_or(Self::Unknown, Self::Custom),
+        }
+    }
+}
+
+/// Name of an out-of-tree backend ([`BackendKind::Custom`]): a protocol
+/// family name of at most [`Self::MAX_LEN`] bytes, held inline so that
+/// [`BackendKind`] stays `Copy`. Serialized as a string.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BackendName {
+    len: u8,
+    bytes: [u8; Self::MAX_LEN],
+}
+
+impl BackendName {
+    /// Longest name, in bytes.
+    pub const MAX_LEN: usize = 31;
+
+    /// `name`, if it fits in [`Self::MAX_LEN`] bytes.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)] // at most MAX_LEN
+    pub const fn new(name: &str) -> Option<Self> {
+        let src = name.as_bytes();
+        if src.len() > Self::MAX_LEN {
+            return None;
+        }
+        let mut bytes = [0u8; Self::MAX_LEN];
+        let mut i = 0;
+        while i < src.len() {
+            bytes[i] = src[i];
+            i += 1;
+        }
+        Some(Self {
+            len: src.len() as u8,
+            bytes,
+        })
+    }
+
+    /// The name.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        // Copied from a `&str` whole, so always UTF-8.
+        std::str::from_utf8(&self.bytes[..usize::from(self.len)]).unwrap_or_default()
+    }
+}
+
+impl fmt::Debug for BackendName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for BackendName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for BackendName {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for BackendName {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Self::new(&name).ok_or_else(|| {
+            de::Error::custom(format!(
+                "backend name {name:?} is longer than {} bytes",
+                Self::MAX_LEN
+            ))
+        })
+    }
+}
+
+impl fmt::Display for BackendKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.proto())
+    }
+}
+
+/// BLAKE3 digest of proof bytes, as recorded in [`ProofArtifact::proof_digest`].
+#[must_use]
+pub fn proof_bytes_digest(bytes: &[u8]) -> [u8; 32] {
+    *blake3::hash(bytes).as_bytes()
+}
+
 /// Serialized proof produced by a backend.
 ///
 /// The `proof_bytes` field is backend-defined; callers should treat it as an
@@ -52,12 +215,20 @@ pub enum BackendKind {
 /// - `manifest_root` must match the root used during proving.
 /// - `backend` must reflect the backend that produced `proof_bytes`; verifiers
 ///   must reject mismatches.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// - `proof_digest`, when present, is [`proof_bytes_digest`] of `proof_bytes`;
+///   call [`ProofArtifact::record_digest`] after changing the bytes.
+#[derive(Debug, Clone, Serialize)]
 pub struct ProofArtifact {
     /// Backend that produced the proof.
     pub backend: BackendKind,
     /// Commitment root the proof is tied to (e.g., Merkle root).
     pub manifest_root: [u8; 32],
+    /// BLAKE3 of `proof_bytes`, checked while an artifact is deserialized.
+    ///
+    /// Serialized before `proof_bytes` so readers know it before the bytes
+    /// arrive; absent in artifacts from older producers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proof_digest: Option<[u8; 32]>,
     /// Opaque, backend-specific encoding of the proof.
     pub proof_bytes: Vec<u8>,
     /// Free-form metadata for debugging/observability.
@@ -65,10 +236,16 @@ pub struct ProofArtifact {
     /// Not required; omitted values deserialize as `Null`.
     #[serde(default)]
     pub meta: serde_json::Value,
+    /// Optional authority-signed creation time (see [`crate::timestamp`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<crate::timestamp::TimestampToken>,
+    /// Public claims bound into the proof (see [`crate::claims`]).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub claims: Vec<crate::claims::Claim>,
 }
 
 impl ProofArtifact {
-    /// Construct a new [`ProofArtifact`].
+    /// Construct a new [`ProofArtifact`], recording the digest of `proof_bytes`.
     #[inline]
     #[must_use]
     pub fn new(
@@ -80,8 +257,29 @@ impl ProofArtifact {
         Self {
             backend,
             manifest_root,
+            proof_digest: Some(proof_bytes_digest(&proof_bytes)),
             proof_bytes,
             meta,
+            timestamp: None,
+            claims: Vec::new(),
+        }
+    }
+
+    /// Record the digest of the current `proof_bytes`.
+    pub fn record_digest(&mut self) {
+        self.proof_digest = Some(proof_bytes_digest(&self.proof_bytes));
+    }
+
+    /// Check `proof_bytes` against the recorded digest, if any.
+    ///
+    /// # Errors
+    /// Fails with "artifact corrupted" if a digest is recorded and differs.
+    pub fn check_digest(&self) -> anyhow::Result<()> {
+        match self.proof_digest {
+            Some(want) if want != proof_bytes_digest(&self.proof_bytes) => {
+                anyhow::bail!("{CORRUPTED}")
+            }
+            _ => Ok(()),
         }
     }
 
@@ -113,19 +311,195 @@ impl ProofArtifact {
         self.proof_bytes.is_empty()
     }
 
-    /// Returns the backend kind.
+    /// Returns the backend kind, as labelled.
     #[inline]
     #[must_use]
-    pub fn backend(&self) -> BackendKind {
+    pub const fn backend(&self) -> BackendKind {
         self.backend
     }
 
+    /// Backend that produced the proof, accounting for legacy labels.
+    ///
+    /// Fold artifacts from older releases are labelled `Stark` with a
+    /// `fold-*` protocol in `meta.proto`; they resolve to
+    /// [`BackendKind::Fold`]. Every other artifact resolves to its label.
+    /// The label itself is left alone, since timestamps cover it; re-label
+    /// by migrating the artifact.
+    #[must_use]
+    pub fn resolved_backend(&self) -> BackendKind {
+        let proto = self.meta.get("proto").and_then(serde_json::Value::as_str);
+        match (self.backend, proto.map(BackendKind::from_proto)) {
+            (BackendKind::Stark, Some(BackendKind::Fold)) => BackendKind::Fold,
+            (kind, _) => kind,
+        }
+    }
+
+    /// Whether the label predates [`BackendKind::Fold`] (see
+    /// [`Self::resolved_backend`]).
+    #[must_use]
+    pub fn has_legacy_label(&self) -> bool {
+        self.resolved_backend() != self.backend
+    }
+
     /// Returns the manifest root this artifact is bound to.
     #[inline]
     #[must_use]
     pub fn manifest_root(&self) -> &[u8; 32] {
         &self.manifest_root
     }
+
+    /// Encode the artifact as CBOR: the exact bytes of a `.cbor` proof file,
+    /// for callers (the FFI bindings) that store blobs themselves.
+    ///
+    /// # Errors
+    /// Fails if `meta` cannot be encoded.
+    pub fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        crate::io::to_cbor(self).context("encoding proof artifact")
+    }
+
+    /// Decode an artifact from the bytes of a `.cbor` proof file, checking
+    /// `proof_bytes` against the recorded digest as a file read does.
+    ///
+    /// # Errors
+    /// Fails on malformed CBOR or a corrupted artifact.
+    pub fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        crate::io::from_cbor(bytes).context("decoding proof artifact")
+    }
+}
+
+/* ------------------------------ deserialization ----------------------------- */
+
+const CORRUPTED: &str = "artifact corrupted: proof bytes do not match the recorded digest";
+
+/// Bytes hashed per update while `proof_bytes` streams in.
+const HASH_CHUNK: usize = 64 << 10;
+
+const FIELDS: &[&str] = &[
+    "backend",
+    "manifest_root",
+    "proof_digest",
+    "proof_bytes",
+    "meta",
+    "timestamp",
+    "claims",
+];
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "snake_case")]
+enum Field {
+    Backend,
+    ManifestRoot,
+    ProofDigest,
+    ProofBytes,
+    Meta,
+    Timestamp,
+    Claims,
+    #[serde(other)]
+    Other,
+}
+
+/// Reads `proof_bytes`, hashing them in [`HASH_CHUNK`] pieces as they arrive.
+struct HashedBytes;
+
+impl<'de> DeserializeSeed<'de> for HashedBytes {
+    type Value = (Vec<u8>, [u8; 32]);
+
+    fn deserialize<D: Deserializer<'de>>(self, d: D) -> Result<Self::Value, D::Error> {
+        d.deserialize_seq(self)
+    }
+}
+
+impl<'de> Visitor<'de> for HashedBytes {
+    type Value = (Vec<u8>, [u8; 32]);
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("proof bytes")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut h = blake3::Hasher::new();
+        let mut out = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(HASH_CHUNK));
+        let mut hashed = 0;
+        while let Some(b) = seq.next_element::<u8>()? {
+            out.push(b);
+            if out.len() - hashed == HASH_CHUNK {
+                h.update(&out[hashed..]);
+                hashed = out.len();
+            }
+        }
+        h.update(&out[hashed..]);
+        Ok((out, *h.finalize().as_bytes()))
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok((v.to_vec(), proof_bytes_digest(v)))
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        let digest = proof_bytes_digest(&v);
+        Ok((v, digest))
+    }
+}
+
+struct ArtifactVisitor;
+
+impl<'de> Visitor<'de> for ArtifactVisitor {
+    type Value = ProofArtifact;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a proof artifact")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut backend = None;
+        let mut manifest_root = None;
+        let mut proof_digest: Option<[u8; 32]> = None;
+        let mut proof = None;
+        let mut meta = serde_json::Value::Null;
+        let mut timestamp = None;
+        let mut claims = Vec::new();
+        while let Some(key) = map.next_key::<Field>()? {
+            match key {
+                Field::Backend => backend = Some(map.next_value()?),
+                Field::ManifestRoot => manifest_root = Some(map.next_value()?),
+                Field::ProofDigest => proof_digest = map.next_value()?,
+                Field::ProofBytes => {
+                    let (bytes, digest) = map.next_value_seed(HashedBytes)?;
+                    // Fail as soon as the bytes are in when the digest came first.
+                    if proof_digest.is_some_and(|want| want != digest) {
+                        return Err(de::Error::custom(CORRUPTED));
+                    }
+                    proof = Some((bytes, digest));
+                }
+                Field::Meta => meta = map.next_value()?,
+                Field::Timestamp => timestamp = map.next_value()?,
+                Field::Claims => claims = map.next_value()?,
+                Field::Other => {
+                    map.next_value::<de::IgnoredAny>()?;
+                }
+            }
+        }
+        let (proof_bytes, digest) = proof.ok_or_else(|| de::Error::missing_field("proof_bytes"))?;
+        if proof_digest.is_some_and(|want| want != digest) {
+            return Err(de::Error::custom(CORRUPTED));
+        }
+        Ok(ProofArtifact {
+            backend: backend.ok_or_else(|| de::Error::missing_field("backend"))?,
+            manifest_root: manifest_root
+                .ok_or_else(|| de::Error::missing_field("manifest_root"))?,
+            proof_digest,
+            proof_bytes,
+            meta,
+            timestamp,
+            claims,
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for ProofArtifact {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        d.deserialize_struct("ProofArtifact", FIELDS, ArtifactVisitor)
+    }
 }
 
 #[cfg(test)]
@@ -152,6 +526,14 @@ mod tests {
         assert!(de.meta.get("k").is_some());
     }
 
+    #[test]
+    fn empty_root_is_reserved_for_empty_statements() {
+        assert!(check_empty_root(true, &EMPTY_ROOT).is_ok());
+        assert!(check_empty_root(false, &[1u8; 32]).is_ok());
+        assert!(check_empty_root(true, &[1u8; 32]).is_err());
+        assert!(check_empty_root(false, &EMPTY_ROOT).is_err());
+    }
+
     #[test]
     fn unknown_backend_is_tolerated() {
         // Serialize with a future/unknown backend name by hand.
@@ -176,5 +558,86 @@ mod tests {
         assert_eq!(de.backend, BackendKind::Unknown);
         assert_eq!(de.manifest_root, [7u8; 32]);
         assert_eq!(de.bytes(), &[9, 9, 9]);
+        assert_eq!(de.proof_digest, None);
+    }
+
+    #[test]
+    fn custom_backends_roundtrip_and_legacy_fold_labels_resolve() {
+        let custom = BackendKind::Custom(BackendName::new("supernova").unwrap());
+        let ser = serde_json::to_string(&custom).unwrap();
+        assert_eq!(ser, r#"{"custom":"supernova"}"#);
+        assert_eq!(serde_json::from_str::<BackendKind>(&ser).unwrap(), custom);
+        assert_eq!(
+            serde_json::to_string(&BackendKind::Fold).unwrap(),
+            r#""fold""#
+        );
+
+        assert_eq!(BackendKind::from_proto("fold-v3"), BackendKind::Fold);
+        assert_eq!(BackendKind::from_proto("stark-v1"), BackendKind::Stark);
+        assert_eq!(BackendKind::from_proto("supernova-v2"), custom);
+        assert_eq!(custom.to_string(), "supernova");
+
+        // Names are held inline, up to `MAX_LEN` bytes.
+        let long = "x".repeat(BackendName::MAX_LEN + 1);
+        assert_eq!(BackendKind::from_proto(&long), BackendKind::Unknown);
+        let ser = format!(r#"{{"custom":"{long}"}}"#);
+        assert!(serde_json::from_str::<BackendKind>(&ser).is_err());
+
+        // Older releases labelled fold artifacts as STARK ones.
+        let mut legacy = ProofArtifact::new(
+            BackendKind::Stark,
+            [0u8; 32],
+            vec![1],
+            json!({"proto": "fold-v2"}),
+        );
+        assert_eq!(legacy.resolved_backend(), BackendKind::Fold);
+        assert!(legacy.has_legacy_label());
+        legacy.meta = json!({"proto": "stark-v1"});
+        assert_eq!(legacy.resolved_backend(), BackendKind::Stark);
+        assert!(!legacy.has_legacy_label());
+    }
+
+    #[test]
+    fn corrupted_proof_bytes_are_rejected_on_read() {
+        // Larger than one hash chunk, so the streamed hash takes several updates.
+        let bytes: Vec<u8> = (0..HASH_CHUNK * 2 + 5).map(|i| i as u8).collect();
+        let artifact = ProofArtifact::new(BackendKind::Fold, [1u8; 32], bytes, json!({"n": 1}));
+        assert_eq!(
+            artifact.proof_digest,
+            Some(proof_bytes_digest(&artifact.proof_bytes))
+        );
+
+        let cbor = crate::io::to_cbor(&artifact).unwrap();
+        let de: ProofArtifact = crate::io::from_cbor(&cbor).unwrap();
+        assert_eq!(de.bytes(), artifact.bytes());
+        de.check_digest().unwrap();
+
+        let mut flipped = artifact.clone();
+        flipped.proof_bytes[HASH_CHUNK + 1] ^= 1;
+        assert!(flipped.check_digest().is_err());
+        let cbor = crate::io::to_cbor(&flipped).unwrap();
+        let err = crate::io::from_cbor::<ProofArtifact>(&cbor).unwrap_err();
+        assert!(format!("{err:#}").contains("artifact corrupted"), "{err:#}");
+        let json = serde_json::to_vec(&flipped).unwrap();
+        let err = serde_json::from_slice::<ProofArtifact>(&json).unwrap_err();
+        assert!(err.to_string().contains("artifact corrupted"), "{err}");
+    }
+
+    #[test]
+    fn byte_helpers_match_the_cbor_file_encoding() {
+        let mut artifact =
+            ProofArtifact::new(BackendKind::Stark, [3u8; 32], vec![5; 40], json!({"k": 1}));
+        artifact.claims = vec![crate::claims::Claim::new("output", "42")];
+
+        let bytes = artifact.to_bytes().unwrap();
+        assert_eq!(bytes, crate::io::to_cbor(&artifact).unwrap());
+        let de = ProofArtifact::from_bytes(&bytes).unwrap();
+        assert_eq!(de.to_bytes().unwrap(), bytes);
+        assert_eq!(de.claims, artifact.claims);
+
+        assert!(ProofArtifact::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        artifact.proof_bytes[0] ^= 1;
+        let err = ProofArtifact::from_bytes(&artifact.to_bytes().unwrap()).unwrap_err();
+        assert!(format!("{err:#}").contains("artifact corrupted"), "{err:#}");
     }
 }
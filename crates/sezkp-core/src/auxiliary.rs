@@ -0,0 +1,161 @@
+//! Per-block auxiliary commitments attached by VM adapters.
+//!
+//! An adapter may want a proof to carry side data about each block beyond the
+//! replay itself, e.g. a program-counter histogram or a digest of the block's
+//! syscall log. It hashes that data into one or more [`AuxCommitment`]s
+//! ([`aux_commitment`] gives a labelled default) and stores them in
+//! [`BlockSummary::aux_commitments`](crate::BlockSummary::aux_commitments).
+//!
+//! The commitments are carried cryptographically end to end:
+//! - the leaf hash binds them (leaf schema v2, see `sezkp-merkle`), so the
+//!   manifest root commits to every block's commitments;
+//! - the fold backend's leaf gadgets take that leaf hash as their public
+//!   commitment, so each leaf proof binds its block's commitments;
+//! - STARK v1 absorbs the [`aux_digest`] of its blocks into the transcript
+//!   and records it in its proof parameters; the verifier recomputes it from
+//!   the blocks it is given and rejects a mismatch.
+//!
+//! Like [claims](crate::claims), the commitments are opaque to SEZKP: nothing
+//! checks what they commit to, only that the proof carries them. Blocks
+//! without commitments hash and prove exactly as before.
+
+use crate::BlockSummary;
+
+/// One 32-byte commitment to adapter-defined side data.
+pub type AuxCommitment = [u8; 32];
+
+/// Domain separator for [`aux_commitment`].
+const DS_AUX: &[u8] = b"sezkp/aux/v1";
+/// Domain separator for [`AuxDigest`].
+const DS_AUX_DIGEST: &[u8] = b"sezkp/aux-digest/v1";
+
+/// Commit to `data` under an adapter-chosen `label` (e.g. `"pc-histogram"`).
+///
+/// Adapters are free to compute commitments any other way; this is a
+/// domain-separated BLAKE3 hash for the common case.
+#[must_use]
+pub fn aux_commitment(label: &str, data: &[u8]) -> AuxCommitment {
+    let mut h = blake3::Hasher::new();
+    h.update(DS_AUX);
+    h.update(&(label.len() as u64).to_le_bytes());
+    h.update(label.as_bytes());
+    h.update(&(data.len() as u64).to_le_bytes());
+    h.update(data);
+    *h.finalize().as_bytes()
+}
+
+/// Streaming digest of the auxiliary commitments of a block sequence.
+///
+/// Hashes `block_id`, the count and the commitments of every block that has
+/// any, in order. Blocks without commitments contribute nothing, and a
+/// sequence without any has no digest.
+#[derive(Clone, Debug, Default)]
+pub struct AuxDigest {
+    h: blake3::Hasher,
+    blocks: u64,
+    commitments: u64,
+}
+
+impl AuxDigest {
+    /// An empty digest.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Absorb the next block.
+    pub fn push(&mut self, b: &BlockSummary) {
+        if !b.has_aux() {
+            return;
+        }
+        if self.blocks == 0 {
+            self.h.update(DS_AUX_DIGEST);
+        }
+        self.h.update(&b.block_id.to_le_bytes());
+        self.h
+            .update(&(b.aux_commitments.len() as u64).to_le_bytes());
+        for c in &b.aux_commitments {
+            self.h.update(c);
+        }
+        self.blocks += 1;
+        self.commitments += b.aux_commitments.len() as u64;
+    }
+
+    /// Blocks absorbed so far that carry commitments.
+    #[must_use]
+    pub const fn blocks(&self) -> u64 {
+        self.blocks
+    }
+
+    /// Commitments absorbed so far.
+    #[must_use]
+    pub const fn commitments(&self) -> u64 {
+        self.commitments
+    }
+
+    /// The digest, or `None` if no block carried commitments.
+    #[must_use]
+    pub fn finish(&self) -> Option<[u8; 32]> {
+        if self.blocks == 0 {
+            return None;
+        }
+        let mut h = self.h.clone();
+        h.update(&self.blocks.to_le_bytes());
+        Some(*h.finalize().as_bytes())
+    }
+}
+
+/// [`AuxDigest`] of `blocks`.
+#[must_use]
+pub fn aux_digest<'a>(blocks: impl IntoIterator<Item = &'a BlockSummary>) -> Option<[u8; 32]> {
+    let mut d = AuxDigest::new();
+    for b in blocks {
+        d.push(b);
+    }
+    d.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MovementLog, Window};
+
+    fn block(block_id: u32, aux: Vec<AuxCommitment>) -> BlockSummary {
+        BlockSummary {
+            version: 1,
+            block_id,
+            step_lo: 1,
+            step_hi: 1,
+            ctrl_in: 0,
+            ctrl_out: 0,
+            in_head_in: 0,
+            in_head_out: 0,
+            windows: vec![Window { left: 0, right: 0 }],
+            head_in_offsets: vec![0],
+            head_out_offsets: vec![0],
+            movement_log: MovementLog::default(),
+            pre_tags: vec![],
+            post_tags: vec![],
+            write_commitments: Vec::new(),
+            aux_commitments: aux,
+        }
+    }
+
+    #[test]
+    fn digest_is_none_without_commitments() {
+        assert_eq!(aux_digest(&[block(1, vec![]), block(2, vec![])]), None);
+        assert_eq!(aux_digest(&[]), None);
+    }
+
+    #[test]
+    fn digest_binds_commitments_and_their_blocks() {
+        let c = aux_commitment("pc-histogram", b"\x01\x02");
+        assert_ne!(c, aux_commitment("syscalls", b"\x01\x02"));
+
+        let d = aux_digest(&[block(1, vec![c]), block(2, vec![])]);
+        assert!(d.is_some());
+        assert_ne!(d, aux_digest(&[block(1, vec![]), block(2, vec![c])]));
+        assert_ne!(d, aux_digest(&[block(1, vec![c, c]), block(2, vec![])]));
+        assert_eq!(d, aux_digest(&[block(1, vec![c])]));
+    }
+}
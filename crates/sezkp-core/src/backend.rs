@@ -10,14 +10,24 @@
 //! - `verify` must reject if:
 //!   - `artifact.backend` does not correspond to the implementing backend,
 //!   - `manifest_root` mismatches `artifact.manifest_root`,
+//!   - `artifact.claims` differ from the claims the proof binds (a backend
+//!     that binds none must reject any, see [`crate::claims::check_bound`]),
 //!   - or the proof bytes fail the backend’s validity checks.
 //! - Neither function should panic for malformed inputs; return `Err` instead.
+//! - `verify_iter`, when overridden, must accept exactly what `verify` accepts
+//!   for the same blocks.
+//! - `verify_batch`, when overridden, must return for each item exactly what
+//!   `verify` returns for it, whatever the other items are.
+//!
+//! Backends with settings can also be used as configured values through
+//! [`ConfiguredBackend`], which takes `&self` and holds to the same contracts.
 //!
 //! Consider introducing a crate-local `Error` (via `thiserror`) when the API
 //! stabilizes; we return `anyhow::Result` here to avoid churn during iteration.
 
+use crate::claims::Claim;
 use crate::{BlockSummary, ProofArtifact};
-use anyhow::Result;
+use anyhow::{ensure, Result};
 
 /// Minimal backend API the rest of the system depends on.
 ///
@@ -47,6 +57,23 @@ pub trait ProvingBackend {
     #[must_use]
     fn prove(blocks: &[BlockSummary], manifest_root: [u8; 32]) -> Result<ProofArtifact>;
 
+    /// [`Self::prove`], also binding the public `claims` into the proof and
+    /// recording them in [`ProofArtifact::claims`] (see [`crate::claims`]).
+    ///
+    /// The default accepts only an empty list; backends that can bind claims
+    /// override it.
+    ///
+    /// # Errors
+    /// Fails like [`Self::prove`], and on claims the backend cannot bind.
+    fn prove_with_claims(
+        blocks: &[BlockSummary],
+        manifest_root: [u8; 32],
+        claims: &[Claim],
+    ) -> Result<ProofArtifact> {
+        ensure!(claims.is_empty(), "this backend cannot bind claims");
+        Self::prove(blocks, manifest_root)
+    }
+
     /// Verify a previously generated proof against `blocks` and `manifest_root`.
     ///
     /// # Errors
@@ -58,4 +85,104 @@ pub trait ProvingBackend {
         blocks: &[BlockSummary],
         manifest_root: [u8; 32],
     ) -> Result<()>;
+
+    /// [`Self::verify`] over blocks read one at a time, as
+    /// [`crate::prover::StreamingProver::verify_stream`] hands them over
+    /// (already replayed). Stops at the first `Err` item.
+    ///
+    /// The default collects the blocks and calls [`Self::verify`]; backends
+    /// that can verify in O(chunk) memory override it.
+    ///
+    /// # Errors
+    /// Fails on the first stream error, or wherever [`Self::verify`] does.
+    fn verify_iter<I>(artifact: &ProofArtifact, blocks: I, manifest_root: [u8; 32]) -> Result<()>
+    where
+        I: IntoIterator<Item = Result<BlockSummary>>,
+    {
+        let blocks = blocks.into_iter().collect::<Result<Vec<_>>>()?;
+        Self::verify(artifact, &blocks, manifest_root)
+    }
+
+    /// Verify many artifacts, returning one result per item in input order.
+    ///
+    /// Every artifact is checked in full, so a bad proof fails only its own
+    /// item; no random linear combination merges the checks. The default
+    /// calls [`Self::verify`] per item; backends override it to do shared
+    /// work once per batch, such as reading their configuration or deriving
+    /// facts from a block slice several items pass.
+    #[must_use]
+    fn verify_batch(items: &[BatchItem<'_>]) -> Vec<Result<()>> {
+        items
+            .iter()
+            .map(|it| Self::verify(it.artifact, it.blocks, it.manifest_root))
+            .collect()
+    }
+}
+
+/// One artifact of a [`ProvingBackend::verify_batch`] call, with the inputs
+/// it is verified against.
+///
+/// Items proving the same trace should pass the same `blocks` slice: backends
+/// recognise it by address and derive what they need from it only once.
+#[derive(Clone, Copy, Debug)]
+pub struct BatchItem<'a> {
+    /// The proof to verify.
+    pub artifact: &'a ProofArtifact,
+    /// Block summaries the proof covers (ignored by backends that verify
+    /// against the manifest root alone).
+    pub blocks: &'a [BlockSummary],
+    /// Manifest root the proof must be bound to.
+    pub manifest_root: [u8; 32],
+}
+
+impl BatchItem<'_> {
+    /// Key identifying this item's block slice by address and length.
+    #[must_use]
+    pub fn blocks_key(&self) -> (usize, usize) {
+        (self.blocks.as_ptr() as usize, self.blocks.len())
+    }
+}
+
+/// Instance-based counterpart of [`ProvingBackend`] carrying its own
+/// configuration.
+///
+/// A host proving concurrently with different settings passes them as
+/// arguments instead of through process-wide state such as environment
+/// variables.
+///
+/// The contracts of [`ProvingBackend`] apply unchanged. Stateless backends
+/// keep reading their settings from the environment; a configured instance
+/// reads none of them.
+pub trait ConfiguredBackend {
+    /// [`ProvingBackend::prove`] under this instance's configuration.
+    ///
+    /// # Errors
+    /// Fails like [`ProvingBackend::prove`].
+    fn prove(&self, blocks: &[BlockSummary], manifest_root: [u8; 32]) -> Result<ProofArtifact>;
+
+    /// [`ProvingBackend::prove_with_claims`] under this instance's
+    /// configuration. The default accepts only an empty list.
+    ///
+    /// # Errors
+    /// Fails like [`Self::prove`], and on claims the backend cannot bind.
+    fn prove_with_claims(
+        &self,
+        blocks: &[BlockSummary],
+        manifest_root: [u8; 32],
+        claims: &[Claim],
+    ) -> Result<ProofArtifact> {
+        ensure!(claims.is_empty(), "this backend cannot bind claims");
+        self.prove(blocks, manifest_root)
+    }
+
+    /// [`ProvingBackend::verify`] under this instance's configuration.
+    ///
+    /// # Errors
+    /// Fails like [`ProvingBackend::verify`].
+    fn verify(
+        &self,
+        artifact: &ProofArtifact,
+        blocks: &[BlockSummary],
+        manifest_root: [u8; 32],
+    ) -> Result<()>;
 }
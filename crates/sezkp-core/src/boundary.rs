@@ -0,0 +1,494 @@
+//! Bounded block-boundary encodings and digests.
+//!
+//! Both proving lines bind adjacent blocks through a small window of steps
+//! around the seam: the folding line hashes the seam between two blocks with
+//! [`interface_boundary_digest`], and STARK leaves commit to their own tail and
+//! head with [`boundary_left_tail_digest`] / [`boundary_right_head_digest`].
+//! They live here, next to [`BlockSummary`], so neither backend has to depend
+//! on the other for them.
+//!
+//! Every step is encoded per tape as `(mv: i32, write_flag: u32, write_sym:
+//! u32)`, little-endian, and at most [`IFACE_WINDOW_STEPS`] steps are taken
+//! from each side. [`BoundarySide`] decodes these inputs for debugging a
+//! failed interface check.
+//!
+//! The seam digest can also bind more or less of each step ([`DigestMode`]):
+//! the head cells the steps reach, or only the steps that write. Each mode
+//! hashes under its own domain string, so a digest made in one mode never
+//! matches one made in another. The per-block side digests always use the
+//! full encoding.
+
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::bail;
+use blake3::Hasher;
+use serde::{Deserialize, Serialize};
+
+use crate::types::{BlockSummary, Cell, StepProjection};
+
+/// Default bounded window (steps) taken from each side of the interface.
+pub const IFACE_WINDOW_STEPS: usize = 32;
+
+/// What [`interface_boundary_digest_with`] hashes of each step in the
+/// boundary windows. Offsets and windows of both blocks are hashed in every
+/// mode.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DigestMode {
+    /// `(mv, write_flag, write_sym)` of every step (`sezkp/iface/v2`, the
+    /// encoding of every proof made before modes existed).
+    #[default]
+    Full,
+    /// [`Self::Full`], plus the head cell each step leaves each tape at
+    /// (`sezkp/iface/v3/heads`). Cells follow the blocks' declared heads:
+    /// forwards from the right block's entry, backwards from the left
+    /// block's exit. They follow from the offsets, windows and moves already
+    /// hashed; this mode puts them in the hashed encoding for consumers that
+    /// rebuild the digest from head positions.
+    Heads,
+    /// Only the steps that write on some tape, each prefixed by its index in
+    /// the window, after the number of such steps on that side
+    /// (`sezkp/iface/v3/writes`).
+    WritesOnly,
+}
+
+impl DigestMode {
+    /// Every mode, in declaration order.
+    pub const ALL: [Self; 3] = [Self::Full, Self::Heads, Self::WritesOnly];
+
+    /// Name used in parameters, files and on the command line.
+    #[must_use]
+    pub const fn id(self) -> &'static str {
+        match self {
+            Self::Full => "full",
+            Self::Heads => "heads",
+            Self::WritesOnly => "writes-only",
+        }
+    }
+
+    /// Whether this is the default [`Self::Full`] mode (which parameter
+    /// encodings omit, keeping older digests unchanged).
+    #[allow(clippy::trivially_copy_pass_by_ref)] // serde `skip_serializing_if`
+    #[must_use]
+    pub const fn is_full(&self) -> bool {
+        matches!(self, Self::Full)
+    }
+
+    const fn domain(self) -> &'static [u8] {
+        match self {
+            Self::Full => b"sezkp/iface/v2",
+            Self::Heads => b"sezkp/iface/v3/heads",
+            Self::WritesOnly => b"sezkp/iface/v3/writes",
+        }
+    }
+}
+
+impl fmt::Display for DigestMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.id())
+    }
+}
+
+impl FromStr for DigestMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match Self::ALL.into_iter().find(|m| m.id() == s) {
+            Some(m) => Ok(m),
+            None => {
+                bail!("unknown boundary digest mode {s:?} (expected full, heads or writes-only)")
+            }
+        }
+    }
+}
+
+/// A single “boundary row” used by the per-block boundary windows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BoundaryRow {
+    /// Move delta for this tape at this step (post-move semantics overall).
+    pub mv: i32,
+    /// 0/1 flag indicating writes.
+    pub write_flag: u32,
+    /// Written symbol (0 if none).
+    pub write_sym: u32,
+}
+
+impl BoundaryRow {
+    fn of(step: &StepProjection, tape: usize) -> Self {
+        let op = &step.tapes[tape];
+        Self {
+            mv: i32::from(op.mv),
+            write_flag: u32::from(op.write.is_some()),
+            write_sym: u32::from(op.write.unwrap_or(0)),
+        }
+    }
+
+    fn absorb(self, h: &mut Hasher) {
+        h.update(&self.mv.to_le_bytes());
+        h.update(&self.write_flag.to_le_bytes());
+        h.update(&self.write_sym.to_le_bytes());
+    }
+}
+
+/// Last `min(k, IFACE_WINDOW_STEPS)` steps of `block`.
+fn tail(block: &BlockSummary, k: usize) -> &[StepProjection] {
+    let steps = &block.movement_log.steps;
+    let take = IFACE_WINDOW_STEPS.min(k).min(steps.len());
+    &steps[steps.len() - take..]
+}
+
+/// First `min(k, IFACE_WINDOW_STEPS)` steps of `block`.
+fn head(block: &BlockSummary, k: usize) -> &[StepProjection] {
+    let steps = &block.movement_log.steps;
+    &steps[..IFACE_WINDOW_STEPS.min(k).min(steps.len())]
+}
+
+fn hasher(domain: &[u8], tau: usize) -> Hasher {
+    let mut h = Hasher::new();
+    h.update(domain);
+    // τ is a handful of tapes; the wire encoding has always been u32.
+    #[allow(clippy::cast_possible_truncation)]
+    h.update(&(tau as u32).to_le_bytes());
+    h
+}
+
+fn absorb_steps(h: &mut Hasher, steps: &[StepProjection], tau: usize) {
+    for step in steps {
+        for r in 0..tau {
+            BoundaryRow::of(step, r).absorb(h);
+        }
+    }
+}
+
+/// [`absorb_steps`], each row followed by the cell the step leaves its tape
+/// at; `at[r]` is the cell of tape `r` before the first step.
+fn absorb_steps_with_heads(h: &mut Hasher, steps: &[StepProjection], mut at: Vec<Cell>) {
+    for step in steps {
+        for (r, cell) in at.iter_mut().enumerate() {
+            let row = BoundaryRow::of(step, r);
+            *cell = cell.wrapping_add(Cell::from(row.mv));
+            row.absorb(h);
+            h.update(&cell.to_le_bytes());
+        }
+    }
+}
+
+/// Absorb the writing steps of `steps`: their count, then each one's index
+/// and rows.
+fn absorb_writes(h: &mut Hasher, steps: &[StepProjection], tau: usize) {
+    let writes = |s: &StepProjection| s.tapes[..tau].iter().any(|op| op.write.is_some());
+    // Windows hold at most `IFACE_WINDOW_STEPS` steps.
+    #[allow(clippy::cast_possible_truncation)]
+    let count = |n: usize| (n as u32).to_le_bytes();
+    h.update(&count(steps.iter().filter(|s| writes(s)).count()));
+    for (i, step) in steps.iter().enumerate().filter(|(_, s)| writes(s)) {
+        h.update(&count(i));
+        for r in 0..tau {
+            BoundaryRow::of(step, r).absorb(h);
+        }
+    }
+}
+
+/// Cells of `block`'s heads on entry (`exit = false`) or exit, per tape.
+fn head_cells(block: &BlockSummary, tau: usize, exit: bool) -> Vec<Cell> {
+    let offsets = if exit {
+        &block.head_out_offsets
+    } else {
+        &block.head_in_offsets
+    };
+    (0..tau)
+        .map(|r| block.windows[r].left.wrapping_add(Cell::from(offsets[r])))
+        .collect()
+}
+
+fn window(steps: &[StepProjection], tau: usize) -> Vec<Vec<BoundaryRow>> {
+    (0..tau)
+        .map(|r| steps.iter().map(|s| BoundaryRow::of(s, r)).collect())
+        .collect()
+}
+
+/* ------------------------------ seam digest -------------------------------- */
+
+/// Canonical, bounded interface digest.
+///
+/// This digest is intentionally simple and deterministic. It includes:
+///  - tape count `tau`,
+///  - static in/out head offsets for both blocks,
+///  - both blocks' windows (`left`, `right` as little-endian `i64`), which
+///    with the offsets fix the absolute head positions on either side, so
+///    the window continuity checked by replay
+///    ([`crate::replay::Replay::check_window_continuity`]) is bound too, and
+///  - the last `K` steps from the **left** block and first `K` steps from the
+///    **right** block (where `K = IFACE_WINDOW_STEPS`), for each tape: `(mv,
+///    write_flag, write_sym)`.
+///
+/// The folding line (B-line) feeds this digest into its ARE micro-proof.
+/// This is [`interface_boundary_digest_with`] in [`DigestMode::Full`].
+#[must_use]
+pub fn interface_boundary_digest(left: &BlockSummary, right: &BlockSummary) -> [u8; 32] {
+    interface_boundary_digest_with(left, right, DigestMode::Full)
+}
+
+/// [`interface_boundary_digest`] with the boundary steps encoded as `mode`
+/// says.
+#[must_use]
+pub fn interface_boundary_digest_with(
+    left: &BlockSummary,
+    right: &BlockSummary,
+    mode: DigestMode,
+) -> [u8; 32] {
+    let tau = left.windows.len();
+    let mut h = hasher(mode.domain(), tau);
+
+    // Static offsets per tape (from block metadata); the LE bytes of these
+    // u32 offsets are the historical i32 encoding.
+    for r in 0..tau {
+        h.update(&left.head_in_offsets[r].to_le_bytes());
+        h.update(&left.head_out_offsets[r].to_le_bytes());
+        h.update(&right.head_in_offsets[r].to_le_bytes());
+        h.update(&right.head_out_offsets[r].to_le_bytes());
+    }
+    for w in left.windows.iter().chain(&right.windows[..tau]) {
+        h.update(&w.left.to_le_bytes());
+        h.update(&w.right.to_le_bytes());
+    }
+
+    let (tail, head) = (
+        tail(left, IFACE_WINDOW_STEPS),
+        head(right, IFACE_WINDOW_STEPS),
+    );
+    match mode {
+        DigestMode::Full => {
+            absorb_steps(&mut h, tail, tau);
+            absorb_steps(&mut h, head, tau);
+        }
+        DigestMode::Heads => {
+            // Walk the tail back from the left block's exit to its start.
+            let mut start = head_cells(left, tau, true);
+            for step in tail {
+                for (r, cell) in start.iter_mut().enumerate() {
+                    *cell = cell.wrapping_sub(Cell::from(step.tapes[r].mv));
+                }
+            }
+            absorb_steps_with_heads(&mut h, tail, start);
+            absorb_steps_with_heads(&mut h, head, head_cells(right, tau, false));
+        }
+        DigestMode::WritesOnly => {
+            absorb_writes(&mut h, tail, tau);
+            absorb_writes(&mut h, head, tau);
+        }
+    }
+    *h.finalize().as_bytes()
+}
+
+/* ----------------------------- per-block sides ----------------------------- */
+
+/// Return the last `k` steps (all tapes) of `block` in boundary-row encoding.
+///
+/// This materializes the **left tail** the leaf proof may wish to commit to.
+#[must_use]
+pub fn left_tail_window(block: &BlockSummary, k: usize) -> Vec<Vec<BoundaryRow>> {
+    window(tail(block, k), block.windows.len())
+}
+
+/// Return the first `k` steps (all tapes) of `block` in boundary-row encoding.
+///
+/// This materializes the **right head** the leaf proof may wish to commit to.
+#[must_use]
+pub fn right_head_window(block: &BlockSummary, k: usize) -> Vec<Vec<BoundaryRow>> {
+    window(head(block, k), block.windows.len())
+}
+
+fn side_digest(domain: &[u8], block: &BlockSummary, steps: &[StepProjection]) -> [u8; 32] {
+    let tau = block.windows.len();
+    let mut h = hasher(domain, tau);
+    for r in 0..tau {
+        h.update(&block.head_in_offsets[r].to_le_bytes());
+        h.update(&block.head_out_offsets[r].to_le_bytes());
+    }
+    absorb_steps(&mut h, steps, tau);
+    *h.finalize().as_bytes()
+}
+
+/// Deterministic digest of the **left tail** (last `k` steps) of a single block.
+#[must_use]
+pub fn boundary_left_tail_digest(block: &BlockSummary, k: usize) -> [u8; 32] {
+    side_digest(b"sezkp/iface/left_tail/v1", block, tail(block, k))
+}
+
+/// Deterministic digest of the **right head** (first `k` steps) of a single block.
+#[must_use]
+pub fn boundary_right_head_digest(block: &BlockSummary, k: usize) -> [u8; 32] {
+    side_digest(b"sezkp/iface/right_head/v1", block, head(block, k))
+}
+
+/* -------------------------------- debugging -------------------------------- */
+
+/// Everything one block contributes to the boundary digests, decoded.
+///
+/// When an interface check fails, print this for both blocks of the seam
+/// (e.g. `sezkp-cli boundary --index i --next`) and compare field by field.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BoundarySide {
+    /// Static head offsets on entry, per tape.
+    pub head_in_offsets: Vec<u32>,
+    /// Static head offsets on exit, per tape.
+    pub head_out_offsets: Vec<u32>,
+    /// Last [`IFACE_WINDOW_STEPS`] steps, per tape (hashed when this block
+    /// is the left side of a seam).
+    pub tail: Vec<Vec<BoundaryRow>>,
+    /// First [`IFACE_WINDOW_STEPS`] steps, per tape (hashed when this block
+    /// is the right side of a seam).
+    pub head: Vec<Vec<BoundaryRow>>,
+    /// [`boundary_left_tail_digest`] over `tail`.
+    pub left_tail_digest: [u8; 32],
+    /// [`boundary_right_head_digest`] over `head`.
+    pub right_head_digest: [u8; 32],
+}
+
+impl BoundarySide {
+    /// Decode the boundary inputs of `block` with the default window.
+    #[must_use]
+    pub fn of(block: &BlockSummary) -> Self {
+        let tau = block.windows.len();
+        Self {
+            head_in_offsets: block.head_in_offsets.iter().take(tau).copied().collect(),
+            head_out_offsets: block.head_out_offsets.iter().take(tau).copied().collect(),
+            tail: left_tail_window(block, IFACE_WINDOW_STEPS),
+            head: right_head_window(block, IFACE_WINDOW_STEPS),
+            left_tail_digest: boundary_left_tail_digest(block, IFACE_WINDOW_STEPS),
+            right_head_digest: boundary_right_head_digest(block, IFACE_WINDOW_STEPS),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{MovementLog, TapeOp, Window};
+
+    /// τ=2 block of `t` steps; step `i` moves by `i mod 3 - 1` and writes `i`
+    /// on tape 1 when `i` is even.
+    fn block(t: usize, salt: u16) -> BlockSummary {
+        let steps = (0..t)
+            .map(|i| StepProjection {
+                input_mv: 0,
+                tapes: vec![
+                    TapeOp::new(None, i8::try_from(i % 3).unwrap() - 1),
+                    TapeOp::new((i % 2 == 0).then(|| u16::try_from(i).unwrap() + salt), 1),
+                ],
+            })
+            .collect();
+        BlockSummary {
+            version: 1,
+            block_id: 1,
+            step_lo: 1,
+            step_hi: t as u64,
+            ctrl_in: 0,
+            ctrl_out: 0,
+            in_head_in: 0,
+            in_head_out: 0,
+            windows: vec![Window { left: 0, right: 0 }; 2],
+            head_in_offsets: vec![0, 1],
+            head_out_offsets: vec![2, 3],
+            movement_log: MovementLog { steps },
+            pre_tags: vec![],
+            post_tags: vec![],
+            write_commitments: Vec::new(),
+            aux_commitments: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn digests_only_see_the_bounded_window() {
+        let n = 3 * IFACE_WINDOW_STEPS;
+        let (l, r) = (block(n, 0), block(n, 0));
+        let base = interface_boundary_digest(&l, &r);
+
+        // Outside the window: the left block's head and the right block's tail.
+        let (mut l2, mut r2) = (l.clone(), r.clone());
+        l2.movement_log.steps[0].tapes[1].write = Some(999);
+        r2.movement_log.steps[n - 1].tapes[1].write = Some(999);
+        assert_eq!(interface_boundary_digest(&l2, &r2), base);
+
+        // Inside it: the left block's last step.
+        l2.movement_log.steps[n - 1].tapes[0].mv = 1 - l2.movement_log.steps[n - 1].tapes[0].mv;
+        assert_ne!(interface_boundary_digest(&l2, &r), base);
+
+        // The windows place both sides on the tape.
+        let mut r3 = r.clone();
+        r3.windows[1].right += 1;
+        assert_ne!(interface_boundary_digest(&l, &r3), base);
+    }
+
+    #[test]
+    fn digest_modes_are_separated_and_bind_what_they_claim() {
+        let n = 3 * IFACE_WINDOW_STEPS;
+        let (l, r) = (block(n, 0), block(n, 0));
+        let digest = |l: &BlockSummary, m| interface_boundary_digest_with(l, &r, m);
+        assert_eq!(
+            digest(&l, DigestMode::Full),
+            interface_boundary_digest(&l, &r)
+        );
+        let all = DigestMode::ALL.map(|m| digest(&l, m));
+        assert!(all[0] != all[1] && all[1] != all[2] && all[0] != all[2]);
+
+        // A non-writing move inside the window: only writes-only ignores it.
+        let mut l2 = l.clone();
+        l2.movement_log.steps[n - 1].tapes[0].mv = 1 - l2.movement_log.steps[n - 1].tapes[0].mv;
+        assert!(l2.movement_log.steps[n - 1].tapes[1].write.is_none());
+        assert_ne!(digest(&l2, DigestMode::Full), all[0]);
+        assert_eq!(digest(&l2, DigestMode::WritesOnly), all[2]);
+
+        for m in DigestMode::ALL {
+            assert_eq!(m.id().parse::<DigestMode>().unwrap(), m);
+            assert_eq!(serde_json::to_string(&m).unwrap(), format!("\"{m}\""));
+        }
+        assert!("partial".parse::<DigestMode>().is_err());
+    }
+
+    #[test]
+    fn windows_are_clamped_and_ordered() {
+        let b = block(5, 100);
+        let tail = left_tail_window(&b, 2);
+        assert_eq!(tail.len(), 2);
+        assert_eq!(
+            tail[1].iter().map(|r| r.write_flag).collect::<Vec<_>>(),
+            [0, 1]
+        );
+        assert_eq!(tail[1][1].write_sym, 104);
+
+        let head = right_head_window(&b, 1000);
+        assert_eq!(head[0].len(), 5);
+        assert_eq!(head[0][0].mv, -1);
+        assert_eq!(
+            right_head_window(&block(100, 0), 1000)[0].len(),
+            IFACE_WINDOW_STEPS
+        );
+
+        assert_ne!(
+            boundary_left_tail_digest(&b, 2),
+            boundary_right_head_digest(&b, 2),
+            "sides are domain-separated"
+        );
+    }
+
+    #[test]
+    fn side_decodes_what_the_digests_hash() {
+        let b = block(3 * IFACE_WINDOW_STEPS, 7);
+        let side = BoundarySide::of(&b);
+        assert_eq!(side.head_in_offsets, [0, 1]);
+        assert_eq!(side.head_out_offsets, [2, 3]);
+        assert_eq!(side.tail, left_tail_window(&b, IFACE_WINDOW_STEPS));
+        assert_eq!(side.head[1].len(), IFACE_WINDOW_STEPS);
+        assert_eq!(
+            side.left_tail_digest,
+            boundary_left_tail_digest(&b, IFACE_WINDOW_STEPS)
+        );
+        assert_eq!(
+            side.right_head_digest,
+            boundary_right_head_digest(&b, IFACE_WINDOW_STEPS)
+        );
+    }
+}
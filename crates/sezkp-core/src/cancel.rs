@@ -0,0 +1,63 @@
+//! Cooperative cancellation of long proving runs.
+//!
+//! A frontend calls [`request`] (the CLI does so from its Ctrl-C handler);
+//! long-running loops poll [`requested`] at safe points and stop with a
+//! [`Cancelled`] error. [`crate::prover::StreamingProver`] checks before each
+//! streamed block and first lets the backend close its partial output
+//! ([`crate::prover::ProvingBackendStream::cancel_stream`]), so a stopped
+//! run leaves a recognisably truncated proof rather than a torn file.
+//!
+//! The flag is process-wide. [`request`] is a single atomic store and is
+//! safe to call from a signal handler.
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Ask running loops to stop at their next cancellation point.
+pub fn request() {
+    REQUESTED.store(true, Ordering::SeqCst);
+}
+
+/// Whether [`request`] has been called (and not [`reset`] since).
+#[must_use]
+pub fn requested() -> bool {
+    REQUESTED.load(Ordering::SeqCst)
+}
+
+/// Withdraw a request, e.g. before starting another run in the same process.
+pub fn reset() {
+    REQUESTED.store(false, Ordering::SeqCst);
+}
+
+/// Fail with [`Cancelled`] if a stop was requested.
+///
+/// # Errors
+/// Returns [`Cancelled`] (without a block count) after [`request`].
+pub fn check() -> Result<(), Cancelled> {
+    if requested() {
+        Err(Cancelled { blocks_done: None })
+    } else {
+        Ok(())
+    }
+}
+
+/// Error for a run stopped by [`request`]; frontends find it with
+/// `anyhow::Error::downcast_ref`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cancelled {
+    /// Blocks fully processed before stopping, if the loop counts them.
+    pub blocks_done: Option<u64>,
+}
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.blocks_done {
+            Some(n) => write!(f, "cancelled after {n} blocks"),
+            None => f.write_str("cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for Cancelled {}
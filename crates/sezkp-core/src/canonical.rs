@@ -0,0 +1,644 @@
+//! Canonical (deterministic) CBOR for bytes that feed hashes and transcripts.
+//!
+//! Wire encodings (`serde_cbor`, `ciborium`, `bincode`) are free to pick
+//! integer widths, keep struct fields in declaration order and write maps in
+//! iteration order, and may change any of that between versions. That is fine
+//! for storage but not for anything hashed: a proof verified by a different
+//! build must hash the same structure to the same bytes. [`to_vec`] produces
+//! one fixed encoding for a value, whatever the serde or codec version:
+//!
+//! - **integers** are written at the width of their Rust type (`u8`/`i8` in
+//!   1 byte, `u16` in 2, `u32` in 4, `u64` in 8) in CBOR major type 0 or 1,
+//!   so the encoding does not depend on the value; 128-bit integers and
+//!   floats are rejected;
+//! - **lengths** of strings, byte strings, arrays and maps use the shortest
+//!   CBOR head, as RFC 8949 §4.2.1 prescribes;
+//! - **maps and structs** are maps whose entries are sorted by the bytes of
+//!   their encoded keys (struct fields keyed by name), so declaration and
+//!   insertion order do not matter; duplicate keys are rejected;
+//! - **enums** are the variant name for unit variants and a one-entry map
+//!   `{name: payload}` otherwise; `None` and `()` are `null`, `Some(v)` is `v`;
+//!   newtype structs are their contents. Type names are never encoded.
+//!
+//! The output is valid CBOR, but it is meant for hashing only; proofs on the
+//! wire keep their regular encodings.
+
+use std::fmt;
+
+use serde::ser::{self, Serialize};
+
+/// Error from [`to_vec`]: the value has no canonical encoding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error(String);
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "canonical CBOR: {}", self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+/// Encode `value` canonically (see the module docs).
+///
+/// # Errors
+/// Fails on floats, 128-bit integers, duplicate map keys, or an error raised
+/// by the value's `Serialize` impl.
+pub fn to_vec<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, Error> {
+    let mut out = Vec::new();
+    value.serialize(Encoder { out: &mut out })?;
+    Ok(out)
+}
+
+/// BLAKE3 of `domain` followed by the canonical encoding of `value`.
+///
+/// # Errors
+/// Same as [`to_vec`].
+pub fn digest<T: Serialize + ?Sized>(domain: &[u8], value: &T) -> Result<[u8; 32], Error> {
+    let mut h = blake3::Hasher::new();
+    h.update(domain);
+    h.update(&to_vec(value)?);
+    Ok(*h.finalize().as_bytes())
+}
+
+/// Canonical head of an array of `len` elements.
+///
+/// The head followed by the [`to_vec`] encoding of each element is the
+/// encoding of the whole array, so long sequences can be hashed one element
+/// at a time without holding them in memory.
+#[must_use]
+pub fn array_head(len: u64) -> Vec<u8> {
+    let mut out = Vec::with_capacity(9);
+    head(&mut out, MAJOR_ARRAY, len);
+    out
+}
+
+/* --------------------------------- heads ---------------------------------- */
+
+const MAJOR_UINT: u8 = 0;
+const MAJOR_NINT: u8 = 1;
+const MAJOR_BYTES: u8 = 2;
+const MAJOR_TEXT: u8 = 3;
+const MAJOR_ARRAY: u8 = 4;
+const MAJOR_MAP: u8 = 5;
+
+const FALSE: u8 = 0xf4;
+const TRUE: u8 = 0xf5;
+const NULL: u8 = 0xf6;
+
+/// Shortest head for a length.
+fn head(out: &mut Vec<u8>, major: u8, n: u64) {
+    let bytes = n.to_be_bytes();
+    match n {
+        0..=23 => out.push(major << 5 | bytes[7]),
+        24..=0xff => put(out, major, &bytes[7..]),
+        0x100..=0xffff => put(out, major, &bytes[6..]),
+        0x1_0000..=0xffff_ffff => put(out, major, &bytes[4..]),
+        _ => put(out, major, &bytes),
+    }
+}
+
+/// Head whose argument follows in `arg` (1, 2, 4 or 8 big-endian bytes).
+fn put(out: &mut Vec<u8>, major: u8, arg: &[u8]) {
+    let info = match arg.len() {
+        1 => 0x18,
+        2 => 0x19,
+        4 => 0x1a,
+        _ => 0x1b,
+    };
+    out.push(major << 5 | info);
+    out.extend_from_slice(arg);
+}
+
+/// Integer in exactly `N` bytes (`N` ∈ {1, 2, 4, 8}), whatever its value.
+fn int<const N: usize>(out: &mut Vec<u8>, negative: bool, arg: u64) {
+    let major = if negative { MAJOR_NINT } else { MAJOR_UINT };
+    put(out, major, &arg.to_be_bytes()[8 - N..]);
+}
+
+/// Signed integer at width `N`: major type 1 carries `-1 - v`.
+fn sint<const N: usize>(out: &mut Vec<u8>, v: i64) {
+    if v < 0 {
+        int::<N>(out, true, v.unsigned_abs() - 1);
+    } else {
+        int::<N>(out, false, v.unsigned_abs());
+    }
+}
+
+fn text(out: &mut Vec<u8>, s: &str) {
+    head(out, MAJOR_TEXT, s.len() as u64);
+    out.extend_from_slice(s.as_bytes());
+}
+
+/* -------------------------------- encoder --------------------------------- */
+
+struct Encoder<'a> {
+    out: &'a mut Vec<u8>,
+}
+
+impl<'a> ser::Serializer for Encoder<'a> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Seq<'a>;
+    type SerializeTuple = Seq<'a>;
+    type SerializeTupleStruct = Seq<'a>;
+    type SerializeTupleVariant = Seq<'a>;
+    type SerializeMap = Map<'a>;
+    type SerializeStruct = Map<'a>;
+    type SerializeStructVariant = Map<'a>;
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn serialize_bool(self, v: bool) -> Result<(), Error> {
+        self.out.push(if v { TRUE } else { FALSE });
+        Ok(())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), Error> {
+        sint::<1>(self.out, i64::from(v));
+        Ok(())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), Error> {
+        sint::<2>(self.out, i64::from(v));
+        Ok(())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), Error> {
+        sint::<4>(self.out, i64::from(v));
+        Ok(())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), Error> {
+        sint::<8>(self.out, v);
+        Ok(())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), Error> {
+        int::<1>(self.out, false, u64::from(v));
+        Ok(())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), Error> {
+        int::<2>(self.out, false, u64::from(v));
+        Ok(())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), Error> {
+        int::<4>(self.out, false, u64::from(v));
+        Ok(())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), Error> {
+        int::<8>(self.out, false, v);
+        Ok(())
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<(), Error> {
+        Err(Error("floats have no canonical encoding".into()))
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<(), Error> {
+        Err(Error("floats have no canonical encoding".into()))
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), Error> {
+        text(self.out, v.encode_utf8(&mut [0; 4]));
+        Ok(())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), Error> {
+        text(self.out, v);
+        Ok(())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), Error> {
+        head(self.out, MAJOR_BYTES, v.len() as u64);
+        self.out.extend_from_slice(v);
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<(), Error> {
+        self.out.push(NULL);
+        Ok(())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        self.out.push(NULL);
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), Error> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<(), Error> {
+        text(self.out, variant);
+        Ok(())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        head(self.out, MAJOR_MAP, 1);
+        text(self.out, variant);
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Seq<'a>, Error> {
+        Ok(Seq::new(self.out))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Seq<'a>, Error> {
+        Ok(Seq::new(self.out))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Seq<'a>, Error> {
+        Ok(Seq::new(self.out))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Seq<'a>, Error> {
+        head(self.out, MAJOR_MAP, 1);
+        text(self.out, variant);
+        Ok(Seq::new(self.out))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Map<'a>, Error> {
+        Ok(Map::new(self.out))
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Map<'a>, Error> {
+        Ok(Map::new(self.out))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Map<'a>, Error> {
+        head(self.out, MAJOR_MAP, 1);
+        text(self.out, variant);
+        Ok(Map::new(self.out))
+    }
+}
+
+/// Array under construction: elements are buffered until the count is known.
+struct Seq<'a> {
+    out: &'a mut Vec<u8>,
+    items: Vec<u8>,
+    n: u64,
+}
+
+impl<'a> Seq<'a> {
+    fn new(out: &'a mut Vec<u8>) -> Self {
+        Self {
+            out,
+            items: Vec::new(),
+            n: 0,
+        }
+    }
+
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        value.serialize(Encoder {
+            out: &mut self.items,
+        })?;
+        self.n += 1;
+        Ok(())
+    }
+
+    fn finish(self) {
+        head(self.out, MAJOR_ARRAY, self.n);
+        self.out.extend_from_slice(&self.items);
+    }
+}
+
+impl ser::SerializeSeq for Seq<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish();
+        Ok(())
+    }
+}
+
+impl ser::SerializeTuple for Seq<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish();
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleStruct for Seq<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish();
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleVariant for Seq<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish();
+        Ok(())
+    }
+}
+
+/// Map under construction: encoded entries are sorted by key bytes at the end.
+struct Map<'a> {
+    out: &'a mut Vec<u8>,
+    entries: Vec<(Vec<u8>, Vec<u8>)>,
+    key: Option<Vec<u8>>,
+}
+
+impl<'a> Map<'a> {
+    fn new(out: &'a mut Vec<u8>) -> Self {
+        Self {
+            out,
+            entries: Vec::new(),
+            key: None,
+        }
+    }
+
+    fn entry<T: Serialize + ?Sized>(&mut self, key: Vec<u8>, value: &T) -> Result<(), Error> {
+        self.entries.push((key, to_vec(value)?));
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<(), Error> {
+        self.entries.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        if self.entries.windows(2).any(|w| w[0].0 == w[1].0) {
+            return Err(Error("duplicate map key".into()));
+        }
+        head(self.out, MAJOR_MAP, self.entries.len() as u64);
+        for (k, v) in self.entries {
+            self.out.extend_from_slice(&k);
+            self.out.extend_from_slice(&v);
+        }
+        Ok(())
+    }
+
+    fn field<T: Serialize + ?Sized>(&mut self, name: &str, value: &T) -> Result<(), Error> {
+        let mut key = Vec::new();
+        text(&mut key, name);
+        self.entry(key, value)
+    }
+}
+
+impl ser::SerializeMap for Map<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        self.key = Some(to_vec(key)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| Error("map value without a key".into()))?;
+        self.entry(key, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStruct for Map<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.field(name, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for Map<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.field(name, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Serialize;
+    use std::collections::{BTreeMap, HashMap};
+
+    #[derive(Serialize)]
+    struct Point {
+        x: u32,
+        y: i16,
+        tag: Option<String>,
+    }
+
+    #[derive(Serialize)]
+    struct PointReordered {
+        tag: Option<String>,
+        y: i16,
+        x: u32,
+    }
+
+    #[derive(Serialize)]
+    enum Shape {
+        Empty,
+        Dot(u8),
+        Line { from: u8, to: u8 },
+    }
+
+    #[test]
+    fn golden_bytes_are_stable() {
+        // Pinned encodings: any change here breaks every hash built on them.
+        let p = Point {
+            x: 5,
+            y: -2,
+            tag: None,
+        };
+        assert_eq!(
+            to_vec(&p).unwrap(),
+            [
+                0xa3, // map(3), keys sorted by encoded bytes
+                0x61, b'x', 0x1a, 0, 0, 0, 5, // "x": u32 5
+                0x61, b'y', 0x39, 0, 1, // "y": i16 -2
+                0x63, b't', b'a', b'g', 0xf6, // "tag": null
+            ]
+        );
+        assert_eq!(
+            to_vec(&Shape::Empty).unwrap(),
+            [0x65, b'E', b'm', b'p', b't', b'y']
+        );
+        assert_eq!(
+            to_vec(&Shape::Dot(7)).unwrap(),
+            [0xa1, 0x63, b'D', b'o', b't', 0x18, 7]
+        );
+        assert_eq!(
+            to_vec(&Shape::Line { from: 1, to: 2 }).unwrap(),
+            [
+                0xa1, 0x64, b'L', b'i', b'n', b'e', // {"Line":
+                0xa2, 0x62, b't', b'o', 0x18, 2, // {"to": 2,
+                0x64, b'f', b'r', b'o', b'm', 0x18, 1, // "from": 1}}
+            ]
+        );
+        assert_eq!(
+            to_vec(&(true, [1u64, 2], "hi")).unwrap(),
+            [
+                0x83, 0xf5, // [true,
+                0x82, 0x1b, 0, 0, 0, 0, 0, 0, 0, 1, 0x1b, 0, 0, 0, 0, 0, 0, 0, 2, // [1, 2],
+                0x62, b'h', b'i', // "hi"]
+            ]
+        );
+    }
+
+    #[test]
+    fn integer_width_follows_the_type_not_the_value() {
+        assert_eq!(to_vec(&0u8).unwrap(), [0x18, 0]);
+        assert_eq!(to_vec(&0u16).unwrap(), [0x19, 0, 0]);
+        assert_eq!(to_vec(&u32::MAX).unwrap(), [0x1a, 0xff, 0xff, 0xff, 0xff]);
+        assert_eq!(to_vec(&i8::MIN).unwrap(), [0x38, 0x7f]);
+        assert_eq!(to_vec(&i64::MIN).unwrap()[1..], i64::MAX.to_be_bytes());
+        // Lengths stay shortest-form.
+        assert_eq!(to_vec(&vec![0u8; 24]).unwrap()[..2], [0x98, 24]);
+    }
+
+    #[test]
+    fn arrays_can_be_encoded_element_by_element() {
+        for n in [0usize, 3, 24, 300] {
+            let items: Vec<(u32, String)> = (0..n).map(|i| (i as u32, i.to_string())).collect();
+            let mut out = array_head(n as u64);
+            for item in &items {
+                out.extend(to_vec(item).unwrap());
+            }
+            assert_eq!(out, to_vec(&items).unwrap());
+        }
+    }
+
+    #[test]
+    fn field_and_insertion_order_do_not_matter() {
+        let a = Point {
+            x: 1,
+            y: 2,
+            tag: Some("t".into()),
+        };
+        let b = PointReordered {
+            tag: Some("t".into()),
+            y: 2,
+            x: 1,
+        };
+        assert_eq!(to_vec(&a).unwrap(), to_vec(&b).unwrap());
+
+        let keys = ["delta", "a", "charlie", "bb"];
+        let hashed: HashMap<_, _> = keys.iter().map(|k| (*k, k.len() as u32)).collect();
+        let sorted: BTreeMap<_, _> = keys.iter().rev().map(|k| (*k, k.len() as u32)).collect();
+        assert_eq!(to_vec(&hashed).unwrap(), to_vec(&sorted).unwrap());
+        assert_eq!(
+            digest(b"ds", &hashed).unwrap(),
+            digest(b"ds", &sorted).unwrap()
+        );
+        assert_ne!(
+            digest(b"ds", &hashed).unwrap(),
+            digest(b"other", &hashed).unwrap()
+        );
+    }
+
+    #[test]
+    fn rejects_non_canonical_values() {
+        assert!(to_vec(&1.5f64).is_err());
+        assert!(to_vec(&1u128).is_err());
+
+        struct Dup;
+        impl Serialize for Dup {
+            fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+                use serde::ser::SerializeMap;
+                let mut m = s.serialize_map(Some(2))?;
+                m.serialize_entry("k", &1u8)?;
+                m.serialize_entry("k", &2u8)?;
+                m.end()
+            }
+        }
+        let err = to_vec(&Dup).unwrap_err();
+        assert!(err.to_string().contains("duplicate map key"), "{err}");
+    }
+}
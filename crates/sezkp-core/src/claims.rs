@@ -0,0 +1,190 @@
+//! Caller-defined public claims bound into a proof.
+//!
+//! A [`Claim`] is a small labelled public statement the application wants a
+//! proof to carry, e.g. `output_reg=42` or `program_hash=ab12…`. The prover
+//! binds the [`claims_digest`] of the list into its Fiat–Shamir transcript
+//! (STARK) or gadget binding (fold), and records the claims themselves in
+//! [`ProofArtifact::claims`](crate::ProofArtifact::claims). Verifiers recompute
+//! the digest from the artifact and reject a mismatch, so claims cannot be
+//! added, dropped, reordered or edited without proving again.
+//!
+//! Claims are opaque to SEZKP: nothing checks that a claim is *true* of the
+//! trace, only that the prover committed to it. Their meaning is up to the
+//! consuming application.
+//!
+//! ## Encoding
+//! The digest hashes the claim count, then each label and value as
+//! length-prefixed UTF-8 in list order. An empty list has no digest and
+//! leaves proofs exactly as they are without claims.
+
+use anyhow::{ensure, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+/// Domain separator for [`claims_digest`].
+const DS_CLAIMS: &[u8] = b"sezkp/claims/v1";
+/// Domain separator for [`bind_root`].
+const DS_BIND: &[u8] = b"sezkp/claims-root/v1";
+
+/// Most claims one proof may carry.
+pub const MAX_CLAIMS: usize = 64;
+/// Longest label plus value of a single claim, in bytes.
+pub const MAX_CLAIM_BYTES: usize = 1024;
+
+/// One labelled public statement.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Claim {
+    /// Application-defined name, unique within a proof.
+    pub label: String,
+    /// Application-defined value (e.g. decimal or hex text).
+    pub value: String,
+}
+
+impl Claim {
+    /// A claim `label=value`.
+    #[must_use]
+    pub fn new(label: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            value: value.into(),
+        }
+    }
+}
+
+impl fmt::Display for Claim {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.label, self.value)
+    }
+}
+
+impl FromStr for Claim {
+    type Err = anyhow::Error;
+
+    /// Parse `label=value`, splitting at the first `=`.
+    fn from_str(s: &str) -> Result<Self> {
+        let (label, value) = s
+            .split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("claim {s:?} is not of the form label=value"))?;
+        let claim = Self::new(label, value);
+        check_claims(std::slice::from_ref(&claim))?;
+        Ok(claim)
+    }
+}
+
+/// Check `claims` against the limits a verifier accepts.
+///
+/// # Errors
+/// Fails on more than [`MAX_CLAIMS`] claims, an empty or repeated label, or a
+/// claim longer than [`MAX_CLAIM_BYTES`].
+pub fn check_claims(claims: &[Claim]) -> Result<()> {
+    ensure!(
+        claims.len() <= MAX_CLAIMS,
+        "{} claims exceed the limit of {MAX_CLAIMS}",
+        claims.len()
+    );
+    let mut seen = HashSet::with_capacity(claims.len());
+    for c in claims {
+        ensure!(!c.label.is_empty(), "claim label must not be empty");
+        ensure!(
+            c.label.len() + c.value.len() <= MAX_CLAIM_BYTES,
+            "claim {:?} exceeds {MAX_CLAIM_BYTES} bytes",
+            c.label
+        );
+        ensure!(seen.insert(&c.label), "duplicate claim label {:?}", c.label);
+    }
+    Ok(())
+}
+
+/// Digest a proof binds for `claims`; `None` for an empty list.
+#[must_use]
+pub fn claims_digest(claims: &[Claim]) -> Option<[u8; 32]> {
+    if claims.is_empty() {
+        return None;
+    }
+    let mut h = blake3::Hasher::new();
+    h.update(DS_CLAIMS);
+    h.update(&(claims.len() as u64).to_le_bytes());
+    for c in claims {
+        for s in [&c.label, &c.value] {
+            h.update(&(s.len() as u64).to_le_bytes());
+            h.update(s.as_bytes());
+        }
+    }
+    Some(*h.finalize().as_bytes())
+}
+
+/// Root a proof binds in place of `manifest_root` when it carries claims
+/// with digest `claims`; `manifest_root` itself when it carries none.
+#[must_use]
+pub fn bind_root(manifest_root: &[u8; 32], claims: Option<&[u8; 32]>) -> [u8; 32] {
+    let Some(d) = claims else {
+        return *manifest_root;
+    };
+    let mut h = blake3::Hasher::new();
+    h.update(DS_BIND);
+    h.update(manifest_root);
+    h.update(d);
+    *h.finalize().as_bytes()
+}
+
+/// Require the claims recorded in an artifact to be the ones its proof
+/// binds (`bound`, as decoded from the proof).
+///
+/// # Errors
+/// Fails when the digests differ, including claims recorded on a proof that
+/// binds none and the reverse.
+pub fn check_bound(recorded: &[Claim], bound: Option<&[u8; 32]>) -> Result<()> {
+    check_claims(recorded)?;
+    ensure!(
+        claims_digest(recorded).as_ref() == bound,
+        "artifact claims do not match the claims bound into the proof"
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digest_depends_on_every_claim_and_their_order() {
+        let a = Claim::new("out", "42");
+        let b = Claim::new("prog", "ab12");
+        assert_eq!(claims_digest(&[]), None);
+        let d = claims_digest(&[a.clone(), b.clone()]);
+        assert!(d.is_some());
+        assert_ne!(d, claims_digest(&[b.clone(), a.clone()]));
+        assert_ne!(d, claims_digest(&[a.clone()]));
+        // Length prefixes keep label/value boundaries apart.
+        assert_ne!(
+            claims_digest(&[Claim::new("o", "ut42")]),
+            claims_digest(&[Claim::new("out", "42")])
+        );
+
+        let root = [5u8; 32];
+        assert_eq!(bind_root(&root, None), root);
+        assert_ne!(bind_root(&root, d.as_ref()), root);
+        assert!(check_bound(&[a.clone(), b], d.as_ref()).is_ok());
+        assert!(check_bound(&[a], d.as_ref()).is_err());
+        assert!(check_bound(&[], d.as_ref()).is_err());
+    }
+
+    #[test]
+    fn parse_and_limits() {
+        let c: Claim = "program_hash=ab=cd".parse().unwrap();
+        assert_eq!(c, Claim::new("program_hash", "ab=cd"));
+        assert_eq!(c.to_string(), "program_hash=ab=cd");
+        assert!("no-equals".parse::<Claim>().is_err());
+        assert!("=42".parse::<Claim>().is_err());
+
+        let dup = [Claim::new("x", "1"), Claim::new("x", "2")];
+        assert!(check_claims(&dup).is_err());
+        let many: Vec<_> = (0..=MAX_CLAIMS)
+            .map(|i| Claim::new(i.to_string(), ""))
+            .collect();
+        assert!(check_claims(&many).is_err());
+        assert!(check_claims(&[Claim::new("big", "v".repeat(MAX_CLAIM_BYTES))]).is_err());
+    }
+}
@@ -0,0 +1,509 @@
+//! Composite artifacts: one file referencing the proofs of several shards.
+//!
+//! Sharded workflows prove each block range separately and then ship a single
+//! [`CompositeArtifact`] that lists the child [`ProofArtifact`]s, either by
+//! path (relative to the composite file) or embedded, together with each
+//! child's [`artifact_digest`] and manifest root.
+//!
+//! ## Binding
+//! [`CompositeArtifact::binding`] is the left-balanced Merkle root
+//! ([`MerkleFrontier`]) over a per-child leaf
+//! `BLAKE3(DS_LEAF || digest || manifest_root)`, in child order. Reordering,
+//! dropping or swapping a child changes it.
+//!
+//! ## Verification
+//! [`CompositeArtifact::verify_with`] checks the binding, loads every child,
+//! checks its digest and manifest root against the reference and then hands
+//! it to a caller-supplied verifier — core knows no backends, so the caller
+//! (e.g. the CLI's `verify-composite`) runs the actual proof check against
+//! the shard's blocks.
+//!
+//! ## Composites as artifacts
+//! A composite with every child embedded can also travel as an ordinary
+//! [`ProofArtifact`] ([`CompositeArtifact::to_artifact`]): its `proof_bytes`
+//! are the composite (CBOR) and its `manifest_root` is the binding, so it can
+//! be stored, timestamped and passed around like any single proof. There is
+//! no recursive compression; verifying it is verifying the composite.
+//! Streamed fold proofs keep their proof in a `.cborseq` sidecar and cannot
+//! be embedded; reference them by path instead.
+
+use anyhow::{bail, ensure, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+
+use crate::artifact::{BackendKind, ProofArtifact};
+use crate::frontier::MerkleFrontier;
+use crate::io::{from_cbor, read_proof_auto, to_cbor};
+use crate::io_format::{resolve_read_format, resolve_write_format, FileFormat};
+use crate::render::RootFormat;
+use crate::timestamp::artifact_digest;
+
+/// Current composite wire version.
+pub const COMPOSITE_VERSION: u16 = 1;
+
+/// Domain separator for binding leaves.
+const DS_LEAF: &[u8] = b"sezkp/composite/leaf/v1";
+
+/// `meta.proto` of proof artifacts wrapping a composite.
+pub const COMPOSITE_PROTO: &str = "composite-v1";
+
+/// Where a child artifact lives.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChildSource {
+    /// Path to an artifact file, relative to the composite's directory
+    /// (absolute paths are kept as-is).
+    Path(String),
+    /// The artifact itself.
+    Embedded(Box<ProofArtifact>),
+}
+
+/// One child of a composite.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChildRef {
+    /// [`artifact_digest`] of the child.
+    pub digest: [u8; 32],
+    /// Manifest root the child proves against.
+    pub manifest_root: [u8; 32],
+    /// Where to find the child.
+    pub source: ChildSource,
+}
+
+impl ChildRef {
+    /// Reference `artifact`, located at `source`.
+    #[must_use]
+    pub fn new(artifact: &ProofArtifact, source: ChildSource) -> Self {
+        Self {
+            digest: artifact_digest(artifact),
+            manifest_root: artifact.manifest_root,
+            source,
+        }
+    }
+
+    /// Leaf hash of this child in the binding tree.
+    #[must_use]
+    pub fn leaf(&self) -> [u8; 32] {
+        let mut h = blake3::Hasher::new();
+        h.update(DS_LEAF);
+        h.update(&self.digest);
+        h.update(&self.manifest_root);
+        *h.finalize().as_bytes()
+    }
+
+    /// Load the child (resolving relative paths against `base_dir`) and check
+    /// it against this reference.
+    ///
+    /// # Errors
+    /// Fails if the file cannot be read or the artifact's digest or manifest
+    /// root differ from the reference.
+    pub fn load(&self, base_dir: &Path) -> Result<ProofArtifact> {
+        let artifact = match &self.source {
+            ChildSource::Embedded(a) => (**a).clone(),
+            ChildSource::Path(p) => {
+                let path = base_dir.join(p);
+                read_proof_auto(&path)
+                    .with_context(|| format!("reading child artifact {}", path.display()))?
+            }
+        };
+        ensure!(
+            artifact.manifest_root == self.manifest_root,
+            "child manifest root differs from the composite's reference"
+        );
+        ensure!(
+            artifact_digest(&artifact) == self.digest,
+            "child artifact digest differs from the composite's reference (modified or replaced?)"
+        );
+        Ok(artifact)
+    }
+}
+
+/// Merkle root binding `children` in order.
+#[must_use]
+pub fn binding_root(children: &[ChildRef]) -> [u8; 32] {
+    let mut f = MerkleFrontier::new();
+    for c in children {
+        f.push(c.leaf());
+    }
+    f.root()
+}
+
+/// An artifact made of child proof artifacts plus a binding over them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompositeArtifact {
+    /// Wire version ([`COMPOSITE_VERSION`]).
+    pub version: u16,
+    /// [`binding_root`] of `children`.
+    pub binding: [u8; 32],
+    /// Child references, in shard order.
+    pub children: Vec<ChildRef>,
+    /// Free-form metadata for debugging/observability.
+    #[serde(default)]
+    pub meta: serde_json::Value,
+}
+
+impl CompositeArtifact {
+    /// Composite over `children`, computing the binding.
+    #[must_use]
+    pub fn new(children: Vec<ChildRef>) -> Self {
+        Self {
+            version: COMPOSITE_VERSION,
+            binding: binding_root(&children),
+            children,
+            meta: serde_json::Value::Null,
+        }
+    }
+
+    /// Check the version and that `binding` matches the children.
+    ///
+    /// # Errors
+    /// Fails on an unsupported version, no children, or a binding mismatch.
+    pub fn check_binding(&self) -> Result<()> {
+        ensure!(
+            self.version == COMPOSITE_VERSION,
+            "unsupported composite version {} (expected {COMPOSITE_VERSION})",
+            self.version
+        );
+        ensure!(!self.children.is_empty(), "composite has no children");
+        ensure!(
+            binding_root(&self.children) == self.binding,
+            "composite binding does not match its children"
+        );
+        Ok(())
+    }
+
+    /// Check the binding, then load every child (paths relative to
+    /// `base_dir`) and pass it to `verify_child(index, artifact)`.
+    ///
+    /// # Errors
+    /// Fails on the first binding, loading or child verification error,
+    /// naming the child.
+    pub fn verify_with<F>(&self, base_dir: &Path, mut verify_child: F) -> Result<()>
+    where
+        F: FnMut(usize, &ProofArtifact) -> Result<()>,
+    {
+        self.check_binding()?;
+        for (i, c) in self.children.iter().enumerate() {
+            let artifact = c.load(base_dir).with_context(|| format!("child {i}"))?;
+            verify_child(i, &artifact).with_context(|| format!("child {i} failed verification"))?;
+        }
+        Ok(())
+    }
+
+    /// Composite embedding `children` (in order).
+    ///
+    /// # Errors
+    /// Fails if `children` is empty or a child is a streamed fold proof.
+    pub fn embed(children: &[ProofArtifact]) -> Result<Self> {
+        ensure!(!children.is_empty(), "nothing to compose");
+        let mut refs = Vec::with_capacity(children.len());
+        for (i, child) in children.iter().enumerate() {
+            ensure!(
+                !is_streamed(child),
+                "child {i} is a streamed fold proof whose proof lives in its .cborseq sidecar"
+            );
+            refs.push(ChildRef::new(
+                child,
+                ChildSource::Embedded(Box::new(child.clone())),
+            ));
+        }
+        Ok(Self::new(refs))
+    }
+
+    /// Wrap this composite into a proof artifact bound to [`Self::binding`].
+    ///
+    /// `meta` records the child count and, per child, its protocol and
+    /// manifest root (hex), for inspection without decoding the proof.
+    ///
+    /// # Errors
+    /// Fails if a child is referenced by path, or if encoding fails.
+    pub fn to_artifact(&self) -> Result<ProofArtifact> {
+        let children = self
+            .children
+            .iter()
+            .enumerate()
+            .map(|(i, c)| match &c.source {
+                ChildSource::Embedded(a) => Ok(&**a),
+                ChildSource::Path(p) => {
+                    bail!("child {i} is referenced by path ({p}), not embedded")
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let meta = serde_json::json!({
+            "proto": COMPOSITE_PROTO,
+            "children": children.len(),
+            "child_protos": children
+                .iter()
+                .map(|c| c.meta.get("proto").cloned().unwrap_or(serde_json::Value::Null))
+                .collect::<Vec<_>>(),
+            "child_roots": children
+                .iter()
+                .map(|c| RootFormat::default().render(&c.manifest_root))
+                .collect::<Vec<_>>(),
+        });
+        Ok(ProofArtifact::new(
+            composite_backend(),
+            self.binding,
+            to_cbor(self)?,
+            meta,
+        ))
+    }
+
+    /// Decode the composite wrapped by `artifact` and check its binding.
+    ///
+    /// # Errors
+    /// Fails if `artifact` does not wrap a composite, does not decode, has a
+    /// child that is not embedded, or its `manifest_root` is not the binding
+    /// of its children.
+    pub fn from_artifact(artifact: &ProofArtifact) -> Result<Self> {
+        ensure!(
+            is_composite_artifact(artifact),
+            "not a composite artifact (backend {})",
+            artifact.backend
+        );
+        let c: Self = from_cbor(&artifact.proof_bytes).context("decode composite artifact")?;
+        c.check_binding()?;
+        ensure!(
+            artifact.manifest_root == c.binding,
+            "composite artifact manifest root does not match its children"
+        );
+        ensure!(
+            c.children
+                .iter()
+                .all(|c| matches!(c.source, ChildSource::Embedded(_))),
+            "composite artifact children must be embedded"
+        );
+        Ok(c)
+    }
+}
+
+/// Backend kind of artifacts wrapping a composite.
+fn composite_backend() -> BackendKind {
+    BackendKind::from_proto(COMPOSITE_PROTO)
+}
+
+/// Whether `artifact` wraps a composite ([`CompositeArtifact::to_artifact`]).
+#[must_use]
+pub fn is_composite_artifact(artifact: &ProofArtifact) -> bool {
+    artifact.backend == composite_backend()
+}
+
+/// Whether `artifact` is a streamed fold proof (proof in a sidecar).
+fn is_streamed(artifact: &ProofArtifact) -> bool {
+    artifact.meta.get("streaming") == Some(&serde_json::Value::Bool(true))
+}
+
+/// Directory against which a composite at `path` resolves relative children.
+#[must_use]
+pub fn composite_base_dir(path: &Path) -> PathBuf {
+    path.parent().map(Path::to_path_buf).unwrap_or_default()
+}
+
+/* ---------------------------------- I/O ----------------------------------- */
+
+/// Formats accepted for composite files.
+const COMPOSITE_FORMATS: [FileFormat; 2] = [FileFormat::Json, FileFormat::Cbor];
+
+/// Read a [`CompositeArtifact`] (JSON/CBOR, auto-detected).
+///
+/// # Errors
+/// Propagates I/O, format resolution and decoding errors.
+pub fn read_composite_auto(path: &Path) -> Result<CompositeArtifact> {
+    let fmt = resolve_read_format(path, "composite", &COMPOSITE_FORMATS)?;
+    let rdr = BufReader::new(File::open(path).with_context(|| format!("open {}", path.display()))?);
+    match fmt {
+        FileFormat::Cbor => {
+            ciborium::de::from_reader(rdr).with_context(|| "deserialize CBOR composite artifact")
+        }
+        FileFormat::Json => {
+            serde_json::from_reader(rdr).with_context(|| "deserialize JSON composite artifact")
+        }
+        FileFormat::Jsonl => bail!("composites are not stored as JSON Lines"),
+    }
+}
+
+/// Write a [`CompositeArtifact`] (format from the extension, JSON default).
+///
+/// # Errors
+/// Propagates I/O, format resolution and encoding errors.
+pub fn write_composite_auto(path: &Path, c: &CompositeArtifact) -> Result<()> {
+    let fmt = resolve_write_format(path, "composite", &COMPOSITE_FORMATS)?;
+    let w =
+        BufWriter::new(File::create(path).with_context(|| format!("create {}", path.display()))?);
+    match fmt {
+        FileFormat::Cbor => {
+            ciborium::ser::into_writer(c, w).with_context(|| "serialize CBOR composite artifact")
+        }
+        _ => {
+            serde_json::to_writer_pretty(w, c).with_context(|| "serialize JSON composite artifact")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::artifact::BackendKind;
+
+    fn child(tag: u8) -> ProofArtifact {
+        ProofArtifact::new(
+            BackendKind::Stark,
+            [tag; 32],
+            vec![tag; 4],
+            serde_json::Value::Null,
+        )
+    }
+
+    fn embedded(tags: &[u8]) -> CompositeArtifact {
+        CompositeArtifact::new(
+            tags.iter()
+                .map(|&t| {
+                    let a = child(t);
+                    ChildRef::new(&a, ChildSource::Embedded(Box::new(a.clone())))
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn verifies_children_in_order() {
+        let c = embedded(&[1, 2, 3]);
+        let mut seen = Vec::new();
+        c.verify_with(Path::new("."), |i, a| {
+            seen.push((i, a.manifest_root[0]));
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(seen, [(0, 1), (1, 2), (2, 3)]);
+
+        let err = c
+            .verify_with(Path::new("."), |i, _| {
+                ensure!(i != 1, "bad proof");
+                Ok(())
+            })
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("child 1"));
+    }
+
+    #[test]
+    fn binding_catches_reorder_drop_and_swap() {
+        let c = embedded(&[1, 2, 3]);
+        let ok = |c: &CompositeArtifact| c.verify_with(Path::new("."), |_, _| Ok(())).is_ok();
+
+        let mut reordered = c.clone();
+        reordered.children.swap(0, 2);
+        assert!(!ok(&reordered));
+
+        let mut dropped = c.clone();
+        dropped.children.pop();
+        assert!(!ok(&dropped));
+
+        // Swapping the embedded artifact is caught by the child digest.
+        let mut swapped = c.clone();
+        swapped.children[1].source = ChildSource::Embedded(Box::new(child(9)));
+        assert!(!ok(&swapped));
+
+        let mut tampered = c.clone();
+        if let ChildSource::Embedded(a) = &mut tampered.children[0].source {
+            a.proof_bytes[0] ^= 1;
+        }
+        assert!(!ok(&tampered));
+
+        assert!(!ok(&CompositeArtifact::new(Vec::new())));
+    }
+
+    #[test]
+    fn path_children_round_trip() {
+        let dir = std::env::temp_dir().join(format!(
+            "sezkp_composite_{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let a = child(5);
+        crate::io::write_proof_auto(dir.join("shard0.json"), &a).unwrap();
+
+        let c = CompositeArtifact::new(vec![ChildRef::new(
+            &a,
+            ChildSource::Path("shard0.json".into()),
+        )]);
+        for name in ["composite.json", "composite.cbor"] {
+            let p = dir.join(name);
+            write_composite_auto(&p, &c).unwrap();
+            let back = read_composite_auto(&p).unwrap();
+            back.verify_with(&composite_base_dir(&p), |_, got| {
+                ensure!(got.proof_bytes == a.proof_bytes);
+                Ok(())
+            })
+            .unwrap();
+        }
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn wraps_into_an_artifact_and_back() {
+        let children = [child(1), child(2), child(3)];
+        let art = CompositeArtifact::embed(&children)
+            .unwrap()
+            .to_artifact()
+            .unwrap();
+        assert!(is_composite_artifact(&art));
+        assert_eq!(art.meta["children"], 3);
+        assert_eq!(
+            art.meta["child_roots"][1],
+            RootFormat::default().render(&[2u8; 32])
+        );
+
+        // Survives an encode/decode round trip like any artifact.
+        let art: ProofArtifact = from_cbor(&to_cbor(&art).unwrap()).unwrap();
+        let mut seen = Vec::new();
+        CompositeArtifact::from_artifact(&art)
+            .unwrap()
+            .verify_with(Path::new(""), |i, a| {
+                seen.push((i, a.manifest_root[0]));
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(seen, [(0, 1), (1, 2), (2, 3)]);
+    }
+
+    #[test]
+    fn artifact_rejects_tampering_and_bad_inputs() {
+        let opens = |a: &ProofArtifact| CompositeArtifact::from_artifact(a).is_ok();
+        let art = CompositeArtifact::embed(&[child(1), child(2)])
+            .unwrap()
+            .to_artifact()
+            .unwrap();
+        assert!(opens(&art));
+
+        // Rebinding to other children needs a new manifest root.
+        let other = CompositeArtifact::embed(&[child(2), child(1)])
+            .unwrap()
+            .to_artifact()
+            .unwrap();
+        let mut swapped = art.clone();
+        swapped.proof_bytes.clone_from(&other.proof_bytes);
+        swapped.record_digest();
+        assert!(!opens(&swapped));
+
+        let mut rooted = art;
+        rooted.manifest_root[0] ^= 1;
+        assert!(!opens(&rooted));
+        assert!(!opens(&child(1)));
+
+        assert!(CompositeArtifact::embed(&[]).is_err());
+        let mut streamed = child(4);
+        streamed.meta = serde_json::json!({ "streaming": true });
+        let err = CompositeArtifact::embed(&[child(1), streamed]).unwrap_err();
+        assert!(format!("{err:#}").contains("child 1"));
+
+        let by_path = CompositeArtifact::new(vec![ChildRef::new(
+            &child(5),
+            ChildSource::Path("shard0.json".into()),
+        )]);
+        assert!(by_path.to_artifact().is_err());
+    }
+}
@@ -0,0 +1,215 @@
+//! Transparent zstd compression for block and proof files.
+//!
+//! A file named `*.zst` (e.g. `blocks.jsonl.zst`, `proof.cbor.zst`) is
+//! written zstd-compressed, and its format is taken from the extension
+//! before `.zst` (see [`crate::io_format`]). Reads go by content instead:
+//! any file starting with the zstd frame magic is decompressed on the fly,
+//! whatever its name, so readers never hold more than their usual buffers.
+//!
+//! Compression needs the `zstd` feature. Without it, writing a `.zst` path
+//! or reading a compressed file is an error naming the feature, never a
+//! silent plain-text write or a garbled parse.
+//!
+//! Compressed files cannot be seeked into: the JSONL index sidecar is not
+//! built for them, and the line-addressed helpers in [`crate::io_jsonl`]
+//! read them from the top.
+
+use anyhow::{Context, Result};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+/// Leading bytes of every zstd frame.
+pub const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// Compression level used for `.zst` writes (zstd's default).
+pub const ZSTD_LEVEL: i32 = 3;
+
+/// Whether `path` names a compressed file (a `.zst` extension, any case).
+#[must_use]
+pub fn is_zst_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("zst"))
+}
+
+/// Whether `head` starts a zstd frame.
+#[must_use]
+pub fn is_zstd(head: &[u8]) -> bool {
+    head.starts_with(&ZSTD_MAGIC)
+}
+
+/// Whether the file at `path` is zstd-compressed (by content).
+///
+/// # Errors
+/// Fails if the file cannot be opened or read.
+pub fn is_compressed(path: &Path) -> Result<bool> {
+    let f = File::open(path).with_context(|| format!("open {}", path.display()))?;
+    let mut head = Vec::with_capacity(ZSTD_MAGIC.len());
+    f.take(ZSTD_MAGIC.len() as u64)
+        .read_to_end(&mut head)
+        .with_context(|| format!("read {}", path.display()))?;
+    Ok(is_zstd(&head))
+}
+
+/// Open `path` for reading, decompressing it when its content is zstd.
+///
+/// # Errors
+/// Fails if the file cannot be opened or read, or if it is compressed and
+/// this build lacks the `zstd` feature.
+pub fn open(path: &Path) -> Result<Box<dyn BufRead + Send>> {
+    let f = File::open(path).with_context(|| format!("open {}", path.display()))?;
+    let mut rdr = BufReader::new(f);
+    let head = rdr
+        .fill_buf()
+        .with_context(|| format!("read {}", path.display()))?;
+    if !is_zstd(head) {
+        return Ok(Box::new(rdr));
+    }
+    #[cfg(feature = "zstd")]
+    {
+        let dec = zstd::stream::read::Decoder::with_buffer(rdr)
+            .with_context(|| format!("start zstd decoder for {}", path.display()))?;
+        Ok(Box::new(BufReader::new(dec)))
+    }
+    #[cfg(not(feature = "zstd"))]
+    {
+        anyhow::bail!(
+            "{} is zstd-compressed; rebuild with the `zstd` feature to read it",
+            path.display()
+        )
+    }
+}
+
+/// A file being written, compressed when its name ends in `.zst`.
+///
+/// Call [`Self::finish`] once done: it writes the end of the zstd frame and
+/// flushes, reporting errors that dropping the writer would lose.
+pub struct FileSink {
+    inner: Sink,
+}
+
+enum Sink {
+    Plain(BufWriter<File>),
+    #[cfg(feature = "zstd")]
+    Zstd(zstd::stream::write::Encoder<'static, BufWriter<File>>),
+}
+
+impl FileSink {
+    /// Create (truncate) `path`, compressing if it has a `.zst` extension.
+    ///
+    /// # Errors
+    /// Fails if the file cannot be created, or if `path` ends in `.zst` and
+    /// this build lacks the `zstd` feature.
+    pub fn create(path: &Path) -> Result<Self> {
+        #[cfg(not(feature = "zstd"))]
+        anyhow::ensure!(
+            !is_zst_path(path),
+            "cannot write {}: rebuild with the `zstd` feature to compress",
+            path.display()
+        );
+        let f = File::create(path).with_context(|| format!("create {}", path.display()))?;
+        let w = BufWriter::new(f);
+        #[cfg(feature = "zstd")]
+        if is_zst_path(path) {
+            let enc = zstd::stream::write::Encoder::new(w, ZSTD_LEVEL)
+                .with_context(|| format!("start zstd encoder for {}", path.display()))?;
+            return Ok(Self {
+                inner: Sink::Zstd(enc),
+            });
+        }
+        Ok(Self {
+            inner: Sink::Plain(w),
+        })
+    }
+
+    /// End the zstd frame (if compressing) and flush to the file.
+    ///
+    /// # Errors
+    /// Fails if the final write or flush fails.
+    pub fn finish(self) -> Result<()> {
+        let mut w = match self.inner {
+            Sink::Plain(w) => w,
+            #[cfg(feature = "zstd")]
+            Sink::Zstd(enc) => enc.finish().context("finish zstd frame")?,
+        };
+        w.flush().context("flush writer")
+    }
+}
+
+impl Write for FileSink {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match &mut self.inner {
+            Sink::Plain(w) => w.write(buf),
+            #[cfg(feature = "zstd")]
+            Sink::Zstd(w) => w.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match &mut self.inner {
+            Sink::Plain(w) => w.flush(),
+            #[cfg(feature = "zstd")]
+            Sink::Zstd(w) => w.flush(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tmp(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("sezkp_compress_{}_{name}", std::process::id()))
+    }
+
+    #[test]
+    fn plain_files_pass_through() {
+        let p = tmp("plain.json");
+        let mut w = FileSink::create(&p).unwrap();
+        w.write_all(b"[1, 2]").unwrap();
+        w.finish().unwrap();
+        assert_eq!(std::fs::read(&p).unwrap(), b"[1, 2]");
+        assert!(!is_compressed(&p).unwrap());
+        let mut s = String::new();
+        open(&p).unwrap().read_to_string(&mut s).unwrap();
+        assert_eq!(s, "[1, 2]");
+        let _ = std::fs::remove_file(p);
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn zst_paths_roundtrip_compressed() {
+        let p = tmp("blocks.jsonl.ZST");
+        let line = b"{\"block_id\":1}\n".repeat(1000);
+        let mut w = FileSink::create(&p).unwrap();
+        w.write_all(&line).unwrap();
+        w.finish().unwrap();
+        let raw = std::fs::read(&p).unwrap();
+        assert!(is_zstd(&raw) && raw.len() < line.len() / 10);
+        let mut back = Vec::new();
+        open(&p).unwrap().read_to_end(&mut back).unwrap();
+        assert_eq!(back, line);
+
+        // Content decides on read, whatever the name.
+        let q = tmp("renamed.jsonl");
+        std::fs::rename(&p, &q).unwrap();
+        assert!(is_compressed(&q).unwrap());
+        back.clear();
+        open(&q).unwrap().read_to_end(&mut back).unwrap();
+        assert_eq!(back, line);
+        let _ = std::fs::remove_file(q);
+    }
+
+    #[cfg(not(feature = "zstd"))]
+    #[test]
+    fn zst_needs_the_feature() {
+        let p = tmp("proof.cbor.zst");
+        let err = FileSink::create(&p).err().unwrap();
+        assert!(err.to_string().contains("`zstd` feature"), "{err}");
+        std::fs::write(&p, [ZSTD_MAGIC.as_slice(), b"...."].concat()).unwrap();
+        let err = open(&p).err().unwrap();
+        assert!(err.to_string().contains("`zstd` feature"), "{err}");
+        let _ = std::fs::remove_file(p);
+    }
+}
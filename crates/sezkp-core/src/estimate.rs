@@ -0,0 +1,56 @@
+//! Proof size estimates, for provisioning storage before a long prove.
+//!
+//! Each backend exposes `estimate_proof_size(n_blocks, rows, tau, params)`
+//! returning a [`SizeEstimate`]: the artifact's `proof_bytes`, the size of
+//! the side stream where the backend writes one (fold `--stream`), and the
+//! per-component breakdown both are made of. Artifact files add their
+//! metadata and framing on top (a few hundred bytes to a few KiB).
+
+use serde::{Deserialize, Serialize};
+
+/// One component of a proof: `count` items taking `bytes` in total.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SizeItem {
+    /// Component name (e.g. `"fri queries"`, `"leaves"`).
+    pub name: String,
+    /// Number of items.
+    pub count: u64,
+    /// Encoded size of all `count` items together.
+    pub bytes: u64,
+}
+
+impl SizeItem {
+    /// A component of `count` items totalling `bytes`.
+    #[must_use]
+    pub fn new(name: &str, count: u64, bytes: u64) -> Self {
+        Self {
+            name: name.to_owned(),
+            count,
+            bytes,
+        }
+    }
+}
+
+/// Predicted proof size for a given shape.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SizeEstimate {
+    /// Expected `ProofArtifact::proof_bytes` length of a batch proof.
+    pub proof_bytes: u64,
+    /// Expected size of the proof stream file, for backends that write one.
+    pub stream_bytes: Option<u64>,
+    /// Whether the figures are exact (fixed-width encodings) rather than
+    /// calibrated averages.
+    pub exact: bool,
+    /// Breakdown of `proof_bytes` by component (framing excluded).
+    pub items: Vec<SizeItem>,
+}
+
+impl SizeEstimate {
+    /// Bytes of `proof_bytes` not attributed to any item (headers, length
+    /// prefixes, envelope).
+    #[must_use]
+    pub fn framing_bytes(&self) -> u64 {
+        let items: u64 = self.items.iter().map(|i| i.bytes).sum();
+        self.proof_bytes.saturating_sub(items)
+    }
+}
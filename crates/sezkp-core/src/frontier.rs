@@ -0,0 +1,176 @@
+//! Streaming Merkle frontier shared by the manifest and FRI layer commitments.
+//!
+//! Both the manifest (`sezkp-merkle`) and the STARK FRI layer builder commit
+//! to a left-balanced BLAKE3 tree with **odd promotion**: when a level has an
+//! odd number of nodes, the last one is carried up unchanged. This module is
+//! the single streaming implementation of that rule; the batch builders
+//! (`sezkp_merkle::merkle_root`, the STARK `MerkleTree`) must agree with it.
+//!
+//! Trees that need their inner nodes kept apart from other trees' pick a
+//! node domain ([`MerkleFrontier::with_node_domain`], [`node_hash_in`]); the
+//! manifest uses the empty domain, i.e. plain [`node_hash`].
+//!
+//! ## Finalization
+//! After `n` pushes, `slots[l]` holds the root of a complete `2^l`-leaf
+//! subtree exactly when bit `l` of `n` is set. In the batch tree, the
+//! trailing remainder at each level is the combination of everything to its
+//! right, so the root is obtained by folding the occupied slots from the
+//! **lowest** level upward, each slot being the *left* child:
+//! `root = H(s_k, … H(s_j, s_i))` for occupied levels `i < j < … < k`.
+
+/// Commitment root of the empty block set (all zeros), shared by the
+/// manifest and every backend.
+pub const EMPTY_ROOT: [u8; 32] = [0u8; 32];
+
+/// Parent of two 32-byte nodes: `BLAKE3(left || right)`.
+///
+/// This is the combiner of every Merkle tree in the workspace.
+#[inline]
+#[must_use]
+pub fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut h = blake3::Hasher::new();
+    h.update(left);
+    h.update(right);
+    *h.finalize().as_bytes()
+}
+
+/// Parent of two 32-byte nodes in the tree with node domain `domain`:
+/// `BLAKE3(domain || left || right)`. The empty domain is [`node_hash`].
+#[inline]
+#[must_use]
+pub fn node_hash_in(domain: &[u8], left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut h = blake3::Hasher::new();
+    h.update(domain);
+    h.update(left);
+    h.update(right);
+    *h.finalize().as_bytes()
+}
+
+/// `O(log n)` frontier computing a left-balanced Merkle root incrementally.
+///
+/// Push leaf hashes left→right with [`MerkleFrontier::push`], then read
+/// [`MerkleFrontier::root`]. Memory is one slot per level.
+#[derive(Debug, Clone, Default)]
+pub struct MerkleFrontier {
+    /// `slots[l]` is a complete `2^l`-leaf subtree waiting for its right sibling.
+    slots: Vec<Option<[u8; 32]>>,
+    len: u64,
+    /// Prefix of every inner-node hash (empty: [`node_hash`]).
+    domain: &'static [u8],
+}
+
+impl MerkleFrontier {
+    /// Empty frontier.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Empty frontier whose inner nodes are [`node_hash_in`] `domain`.
+    #[must_use]
+    pub fn with_node_domain(domain: &'static [u8]) -> Self {
+        Self {
+            domain,
+            ..Self::default()
+        }
+    }
+
+    /// Number of leaves pushed so far.
+    #[must_use]
+    pub const fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// `true` if no leaf has been pushed.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Append the next leaf hash.
+    pub fn push(&mut self, leaf: [u8; 32]) {
+        self.len += 1;
+        let mut cur = leaf;
+        let mut lvl = 0usize;
+        loop {
+            if self.slots.len() <= lvl {
+                self.slots.push(None);
+            }
+            if let Some(left) = self.slots[lvl].take() {
+                cur = node_hash_in(self.domain, &left, &cur);
+                lvl += 1;
+            } else {
+                self.slots[lvl] = Some(cur);
+                return;
+            }
+        }
+    }
+
+    /// Root over the leaves pushed so far ([`EMPTY_ROOT`] if none).
+    #[must_use]
+    pub fn root(&self) -> [u8; 32] {
+        self.slots
+            .iter()
+            .flatten()
+            .fold(None, |acc, node| {
+                Some(acc.map_or(*node, |right| node_hash_in(self.domain, node, &right)))
+            })
+            .unwrap_or(EMPTY_ROOT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaf(i: u8) -> [u8; 32] {
+        [i; 32]
+    }
+
+    fn root_of(n: u8) -> [u8; 32] {
+        let mut f = MerkleFrontier::new();
+        for i in 0..n {
+            f.push(leaf(i));
+        }
+        f.root()
+    }
+
+    #[test]
+    fn small_trees_follow_odd_promotion() {
+        let h = |a: [u8; 32], b: [u8; 32]| node_hash(&a, &b);
+        let (a, b, c, d, e, f, g) = (
+            leaf(0),
+            leaf(1),
+            leaf(2),
+            leaf(3),
+            leaf(4),
+            leaf(5),
+            leaf(6),
+        );
+
+        assert_eq!(root_of(0), EMPTY_ROOT);
+        assert_eq!(root_of(1), a);
+        assert_eq!(root_of(3), h(h(a, b), c));
+        assert_eq!(root_of(5), h(h(h(a, b), h(c, d)), e));
+        // Levels: [ab, cd, ef, g] → [abcd, efg] → root.
+        assert_eq!(root_of(7), h(h(h(a, b), h(c, d)), h(h(e, f), g)));
+    }
+
+    #[test]
+    fn node_domain_changes_only_inner_nodes() {
+        let dom: &'static [u8] = b"test/v1";
+        let h = |a: [u8; 32], b: [u8; 32]| node_hash_in(dom, &a, &b);
+        let mut f = MerkleFrontier::with_node_domain(dom);
+        f.push(leaf(0));
+        assert_eq!(f.root(), leaf(0));
+        for i in 1..3 {
+            f.push(leaf(i));
+        }
+        assert_eq!(f.root(), h(h(leaf(0), leaf(1)), leaf(2)));
+        assert_ne!(f.root(), root_of(3));
+        assert_eq!(
+            node_hash_in(b"", &leaf(0), &leaf(1)),
+            node_hash(&leaf(0), &leaf(1))
+        );
+    }
+}
@@ -0,0 +1,69 @@
+//! Feature-gated tracing hooks for backend internals.
+//!
+//! Backends mark their phases with [`phase_span!`](crate::phase_span) and
+//! report counts with [`phase_event!`](crate::phase_event). With the
+//! `tracing` feature enabled these expand to `tracing::debug_span!(..)
+//! .entered()` and `tracing::debug!(..)`, so `RUST_LOG=debug` shows per-phase
+//! timing and counts; without it they compile to nothing (the span guard is
+//! the zero-sized [`NoSpan`]).
+//!
+//! Span names follow `<crate>.<phase>`, e.g. `stark.lde` or `fold.collapse`,
+//! so subscribers (the CLI, the bench harness) can aggregate by name.
+
+/// Stand-in span guard used when the `tracing` feature is off.
+///
+/// Dropping it does nothing; it exists so call sites can end a phase with
+/// `drop(span)` identically in both configurations.
+#[derive(Debug, Default)]
+pub struct NoSpan;
+
+impl Drop for NoSpan {
+    #[inline]
+    fn drop(&mut self) {}
+}
+
+#[cfg(feature = "tracing")]
+#[doc(hidden)]
+pub use tracing as __tracing;
+
+/// Enter a `debug` span for a backend phase; returns the guard.
+///
+/// Accepts the same arguments as `tracing::debug_span!`.
+#[cfg(feature = "tracing")]
+#[macro_export]
+macro_rules! phase_span {
+    ($($arg:tt)*) => {
+        $crate::instrument::__tracing::debug_span!($($arg)*).entered()
+    };
+}
+
+/// Enter a `debug` span for a backend phase; returns the guard.
+///
+/// Accepts the same arguments as `tracing::debug_span!`.
+#[cfg(not(feature = "tracing"))]
+#[macro_export]
+macro_rules! phase_span {
+    ($($arg:tt)*) => {
+        $crate::instrument::NoSpan
+    };
+}
+
+/// Emit a `debug` event (counts, sizes) from inside a phase.
+///
+/// Accepts the same arguments as `tracing::debug!`.
+#[cfg(feature = "tracing")]
+#[macro_export]
+macro_rules! phase_event {
+    ($($arg:tt)*) => {
+        $crate::instrument::__tracing::debug!($($arg)*)
+    };
+}
+
+/// Emit a `debug` event (counts, sizes) from inside a phase.
+///
+/// Accepts the same arguments as `tracing::debug!`.
+#[cfg(not(feature = "tracing"))]
+#[macro_export]
+macro_rules! phase_event {
+    ($($arg:tt)*) => {};
+}
@@ -1,22 +1,28 @@
 //! Serialization helpers for `BlockSummary` vectors and `ProofArtifact`s.
 //!
-//! JSON and CBOR read/write utilities with extension-based auto-detection.
-//! Unknown/missing extensions are rejected for reads and default to JSON
-//! for writes.
+//! JSON and CBOR read/write utilities with format auto-detection. Reads use
+//! the file content when it is unambiguous and the extension otherwise, so
+//! misnamed files still load; writes follow the extension and default to JSON
+//! unless strict I/O is enabled (see [`crate::io_format`]). Every helper
+//! also reads zstd-compressed files and compresses writes to `*.zst` paths,
+//! e.g. `proof.cbor.zst` (see [`crate::compress`]).
 //!
 //! Extras:
 //! - In-memory CBOR helpers: [`to_cbor`] / [`from_cbor`]
 //! - Tiny versioned payload wrapper: [`Versioned<T>`]
 //! - Streaming helper: [`stream_block_summaries_auto`] returning a boxed iterator
-//!   so callers can uniformly consume JSONL/NDJSON (true streaming) or JSON/CBOR
-//!   (load-then-iterate) without caring about concrete iterator types.
+//!   so callers can uniformly consume JSONL/NDJSON and CBOR (true streaming) or
+//!   JSON (load-then-iterate) without caring about concrete iterator types.
 
+use crate::compress::{self, FileSink};
+use crate::io_format::{resolve_read_format, resolve_write_format, FileFormat};
+use crate::read_limits::{read_cbor_blocks, read_json_blocks, read_limits, CborBlocks};
 use crate::{BlockSummary, ProofArtifact};
-use anyhow::{anyhow, Context, Result};
+use anyhow::{Context, Result};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use std::fs::{self, File};
-use std::io::{BufReader, BufWriter, Cursor};
+use std::fs;
+use std::io::Cursor;
 use std::path::Path;
 
 /// Ensure the parent directory for a file exists (no-op if none).
@@ -34,65 +40,84 @@ fn ensure_parent_dir(path: &Path) -> Result<()> {
 /// BlockSummary (Vec) I/O
 /// ------------------------------
 
-/// Read `Vec<BlockSummary>` from **JSON**.
+/// Read `Vec<BlockSummary>` from **JSON**, within the [read limits](crate::read_limits).
 pub fn read_block_summaries_json<P: AsRef<Path>>(path: P) -> Result<Vec<BlockSummary>> {
     let path_ref = path.as_ref();
-    let f = File::open(path_ref).with_context(|| format!("open {}", display(path_ref)))?;
-    let rdr = BufReader::new(f);
-    let v: Vec<BlockSummary> =
-        serde_json::from_reader(rdr).with_context(|| "deserialize JSON block summaries")?;
-    Ok(v)
+    let rdr = compress::open(path_ref)?;
+    let limits = read_limits()?;
+    read_json_blocks(rdr, &limits).with_context(|| "deserialize JSON block summaries")
 }
 
 /// Write `Vec<BlockSummary>` to **JSON** (pretty).
 pub fn write_block_summaries_json<P: AsRef<Path>>(path: P, v: &[BlockSummary]) -> Result<()> {
     let path_ref = path.as_ref();
     ensure_parent_dir(path_ref)?;
-    let f = File::create(path_ref).with_context(|| format!("create {}", display(path_ref)))?;
-    let w = BufWriter::new(f);
-    serde_json::to_writer_pretty(w, v).with_context(|| "serialize JSON block summaries")?;
-    Ok(())
+    let mut w = FileSink::create(path_ref)?;
+    serde_json::to_writer_pretty(&mut w, v).with_context(|| "serialize JSON block summaries")?;
+    w.finish()
 }
 
-/// Read `Vec<BlockSummary>` from **CBOR**.
+/// Read `Vec<BlockSummary>` from **CBOR**, within the [read limits](crate::read_limits).
 pub fn read_block_summaries_cbor<P: AsRef<Path>>(path: P) -> Result<Vec<BlockSummary>> {
     let path_ref = path.as_ref();
-    let f = File::open(path_ref).with_context(|| format!("open {}", display(path_ref)))?;
-    let mut rdr = BufReader::new(f);
-    let v: Vec<BlockSummary> =
-        ciborium::de::from_reader(&mut rdr).with_context(|| "deserialize CBOR block summaries")?;
-    Ok(v)
+    let rdr = compress::open(path_ref)?;
+    let limits = read_limits()?;
+    read_cbor_blocks(rdr, &limits).with_context(|| "deserialize CBOR block summaries")
+}
+
+/// Stream `BlockSummary`s from a **CBOR** array, decoding one element per
+/// item within the [read limits](crate::read_limits).
+///
+/// Memory stays bounded by one record however large the file. The array
+/// header is checked here; a malformed or oversized element surfaces as an
+/// error item that ends the stream.
+pub fn stream_block_summaries_cbor<P: AsRef<Path>>(
+    path: P,
+) -> Result<Box<dyn Iterator<Item = Result<BlockSummary>> + Send>> {
+    let path_ref = path.as_ref();
+    let rdr = compress::open(path_ref)?;
+    let blocks = CborBlocks::new(rdr, read_limits()?)
+        .with_context(|| format!("read CBOR block summaries from {}", display(path_ref)))?;
+    Ok(Box::new(blocks))
 }
 
 /// Write `Vec<BlockSummary>` to **CBOR**.
 pub fn write_block_summaries_cbor<P: AsRef<Path>>(path: P, v: &[BlockSummary]) -> Result<()> {
     let path_ref = path.as_ref();
     ensure_parent_dir(path_ref)?;
-    let f = File::create(path_ref).with_context(|| format!("create {}", display(path_ref)))?;
-    let mut w = BufWriter::new(f);
+    let mut w = FileSink::create(path_ref)?;
     ciborium::ser::into_writer(v, &mut w).with_context(|| "serialize CBOR block summaries")?;
-    Ok(())
+    w.finish()
 }
 
-/// Auto-detect read by extension `.json` / `.cbor` (case-insensitive).
+/// Formats accepted for `Vec<BlockSummary>` files.
+const BLOCK_FORMATS: [FileFormat; 3] = [FileFormat::Json, FileFormat::Cbor, FileFormat::Jsonl];
+
+/// Format a blocks file will be read as (content sniffing + extension, or the
+/// `"blocks"` override).
+///
+/// # Errors
+/// Fails if no supported format fits (see [`resolve_read_format`]).
+pub fn block_file_format<P: AsRef<Path>>(path: P) -> Result<FileFormat> {
+    resolve_read_format(path.as_ref(), "blocks", &BLOCK_FORMATS)
+}
+
+/// Auto-detect read (`.json` / `.cbor` / `.jsonl` / `.ndjson`, checked against
+/// the content). JSONL input is collected into a vector.
 pub fn read_block_summaries_auto<P: AsRef<Path>>(path: P) -> Result<Vec<BlockSummary>> {
-    match ext_lower(path.as_ref()).as_deref() {
-        Some("json") => read_block_summaries_json(path),
-        Some("cbor") => read_block_summaries_cbor(path),
-        Some(other) => Err(anyhow!(
-            "unsupported blocks extension: {} (supported: .json, .cbor)",
-            other
-        )),
-        None => Err(anyhow!("path has no extension (expected .json or .cbor)")),
+    match block_file_format(&path)? {
+        FileFormat::Json => read_block_summaries_json(path),
+        FileFormat::Cbor => read_block_summaries_cbor(path),
+        FileFormat::Jsonl => crate::io_jsonl::stream_block_summaries_jsonl_auto(path)?.collect(),
     }
 }
 
-/// Auto-detect write (defaults to **JSON** if unknown or missing).
+/// Auto-detect write (defaults to **JSON** if unknown or missing, unless strict).
 pub fn write_block_summaries_auto<P: AsRef<Path>>(path: P, v: &[BlockSummary]) -> Result<()> {
-    match ext_lower(path.as_ref()).as_deref() {
-        Some("json") => write_block_summaries_json(path, v),
-        Some("cbor") => write_block_summaries_cbor(path, v),
-        _ => write_block_summaries_json(path, v),
+    match resolve_write_format(path.as_ref(), "blocks", &BLOCK_FORMATS)? {
+        FileFormat::Json => write_block_summaries_json(path, v),
+        FileFormat::Cbor => write_block_summaries_cbor(path, v),
+        FileFormat::Jsonl => crate::io_jsonl::write_block_summaries_jsonl(path, v),
     }
 }
 
@@ -102,9 +127,12 @@ pub fn write_block_summaries_auto<P: AsRef<Path>>(path: P, v: &[BlockSummary]) -
 
 /// Return a boxed iterator over `BlockSummary`s for the given path.
 ///
-/// - **`.jsonl` / `.ndjson`**: true streaming via `io_jsonl::stream_block_summaries_jsonl`
-///   (no materialization; sublinear memory).
-/// - **`.json` / `.cbor`**: load the vector, then iterate (compat fallback).
+/// - **JSONL / NDJSON**: true streaming via `io_jsonl::stream_block_summaries_jsonl_auto`
+///   (no materialization; sublinear memory; lines parsed in parallel with the
+///   `parallel` feature).
+/// - **CBOR**: true streaming via [`stream_block_summaries_cbor`] (one array
+///   element decoded at a time).
+/// - **JSON**: load the vector, then iterate (compat fallback).
 ///
 /// This uses a trait object so the concrete iterator type can differ by branch.
 #[must_use]
@@ -114,27 +142,16 @@ pub fn stream_block_summaries_auto<P: AsRef<Path>>(
     // Own the path so the iterator type doesn't capture `P`.
     let pb = path.as_ref().to_owned();
 
-    match ext_lower(&pb).as_deref() {
-        Some("jsonl") | Some("ndjson") => {
+    match block_file_format(&pb)? {
+        FileFormat::Jsonl => {
             // True streaming path; iterator owns its resources.
-            let it = crate::io_jsonl::stream_block_summaries_jsonl(pb)?;
-            Ok(Box::new(it))
+            crate::io_jsonl::stream_block_summaries_jsonl_auto(pb)
         }
-        Some("json") => {
+        FileFormat::Json => {
             let v = read_block_summaries_json(&pb)?;
             Ok(Box::new(v.into_iter().map(Ok)))
         }
-        Some("cbor") => {
-            let v = read_block_summaries_cbor(&pb)?;
-            Ok(Box::new(v.into_iter().map(Ok)))
-        }
-        Some(other) => Err(anyhow!(
-            "unsupported blocks extension: {} (supported: .json, .cbor, .jsonl, .ndjson)",
-            other
-        )),
-        None => Err(anyhow!(
-            "path has no extension (expected .json, .cbor, .jsonl, or .ndjson)"
-        )),
+        FileFormat::Cbor => stream_block_summaries_cbor(pb),
     }
 }
 
@@ -145,8 +162,7 @@ pub fn stream_block_summaries_auto<P: AsRef<Path>>(
 /// Read `ProofArtifact` from **JSON**.
 pub fn read_proof_artifact_json<P: AsRef<Path>>(path: P) -> Result<ProofArtifact> {
     let path_ref = path.as_ref();
-    let f = File::open(path_ref).with_context(|| format!("open {}", display(path_ref)))?;
-    let rdr = BufReader::new(f);
+    let rdr = compress::open(path_ref)?;
     let v: ProofArtifact =
         serde_json::from_reader(rdr).with_context(|| "deserialize JSON proof artifact")?;
     Ok(v)
@@ -156,17 +172,15 @@ pub fn read_proof_artifact_json<P: AsRef<Path>>(path: P) -> Result<ProofArtifact
 pub fn write_proof_artifact_json<P: AsRef<Path>>(path: P, v: &ProofArtifact) -> Result<()> {
     let path_ref = path.as_ref();
     ensure_parent_dir(path_ref)?;
-    let f = File::create(path_ref).with_context(|| format!("create {}", display(path_ref)))?;
-    let w = BufWriter::new(f);
-    serde_json::to_writer_pretty(w, v).with_context(|| "serialize JSON proof artifact")?;
-    Ok(())
+    let mut w = FileSink::create(path_ref)?;
+    serde_json::to_writer_pretty(&mut w, v).with_context(|| "serialize JSON proof artifact")?;
+    w.finish()
 }
 
 /// Read `ProofArtifact` from **CBOR**.
 pub fn read_proof_artifact_cbor<P: AsRef<Path>>(path: P) -> Result<ProofArtifact> {
     let path_ref = path.as_ref();
-    let f = File::open(path_ref).with_context(|| format!("open {}", display(path_ref)))?;
-    let mut rdr = BufReader::new(f);
+    let mut rdr = compress::open(path_ref)?;
     let v: ProofArtifact =
         ciborium::de::from_reader(&mut rdr).with_context(|| "deserialize CBOR proof artifact")?;
     Ok(v)
@@ -176,30 +190,26 @@ pub fn read_proof_artifact_cbor<P: AsRef<Path>>(path: P) -> Result<ProofArtifact
 pub fn write_proof_artifact_cbor<P: AsRef<Path>>(path: P, v: &ProofArtifact) -> Result<()> {
     let path_ref = path.as_ref();
     ensure_parent_dir(path_ref)?;
-    let f = File::create(path_ref).with_context(|| format!("create {}", display(path_ref)))?;
-    let mut w = BufWriter::new(f);
+    let mut w = FileSink::create(path_ref)?;
     ciborium::ser::into_writer(v, &mut w).with_context(|| "serialize CBOR proof artifact")?;
-    Ok(())
+    w.finish()
 }
 
-/// Auto-detect read for `ProofArtifact` by extension.
+/// Formats accepted for `ProofArtifact` files.
+const PROOF_FORMATS: [FileFormat; 2] = [FileFormat::Json, FileFormat::Cbor];
+
+/// Auto-detect read for `ProofArtifact` (content, then extension).
 pub fn read_proof_artifact_auto<P: AsRef<Path>>(path: P) -> Result<ProofArtifact> {
-    match ext_lower(path.as_ref()).as_deref() {
-        Some("json") => read_proof_artifact_json(path),
-        Some("cbor") => read_proof_artifact_cbor(path),
-        Some(other) => Err(anyhow!(
-            "unsupported proof extension: {} (supported: .json, .cbor)",
-            other
-        )),
-        None => Err(anyhow!("path has no extension (expected .json or .cbor)")),
+    match resolve_read_format(path.as_ref(), "proof", &PROOF_FORMATS)? {
+        FileFormat::Cbor => read_proof_artifact_cbor(path),
+        _ => read_proof_artifact_json(path),
     }
 }
 
-/// Auto-detect write for `ProofArtifact` (defaults to **JSON** if unknown).
+/// Auto-detect write for `ProofArtifact` (defaults to **JSON** if unknown, unless strict).
 pub fn write_proof_artifact_auto<P: AsRef<Path>>(path: P, v: &ProofArtifact) -> Result<()> {
-    match ext_lower(path.as_ref()).as_deref() {
-        Some("json") => write_proof_artifact_json(path, v),
-        Some("cbor") => write_proof_artifact_cbor(path, v),
+    match resolve_write_format(path.as_ref(), "proof", &PROOF_FORMATS)? {
+        FileFormat::Cbor => write_proof_artifact_cbor(path, v),
         _ => write_proof_artifact_json(path, v),
     }
 }
@@ -260,13 +270,6 @@ impl<T> Versioned<T> {
     }
 }
 
-/// Return the lowercase extension (without dot) if present.
-fn ext_lower(path: &Path) -> Option<String> {
-    path.extension()
-        .and_then(|e| e.to_str())
-        .map(|s| s.to_ascii_lowercase())
-}
-
 /// Human-friendly path display for error messages.
 fn display(path: &Path) -> String {
     path.to_string_lossy().into_owned()
@@ -287,6 +290,30 @@ mod tests {
         p
     }
 
+    /// Three one-step blocks.
+    fn demo_blocks() -> Vec<BlockSummary> {
+        (1..=3)
+            .map(|id| BlockSummary {
+                version: 1,
+                block_id: id,
+                step_lo: u64::from(id),
+                step_hi: u64::from(id),
+                ctrl_in: 0,
+                ctrl_out: 0,
+                in_head_in: 0,
+                in_head_out: 0,
+                windows: vec![crate::Window::at(0)],
+                head_in_offsets: vec![0],
+                head_out_offsets: vec![0],
+                movement_log: crate::MovementLog::default(),
+                pre_tags: Vec::new(),
+                post_tags: Vec::new(),
+                write_commitments: Vec::new(),
+                aux_commitments: Vec::new(),
+            })
+            .collect()
+    }
+
     #[test]
     fn block_summaries_json_roundtrip() {
         let path = tmp_path("blocks", "json");
@@ -297,15 +324,38 @@ mod tests {
         let _ = std::fs::remove_file(path);
     }
 
+    #[test]
+    fn cbor_blocks_stream_one_record_at_a_time() {
+        let blocks = demo_blocks();
+        let path = tmp_path("blocks_stream", "cbor");
+        write_block_summaries_auto(&path, &blocks).unwrap();
+        let got: Vec<_> = stream_block_summaries_auto(&path)
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(got, blocks);
+
+        // A truncated last record still yields the blocks before it.
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &bytes[..bytes.len() - 3]).unwrap();
+        let mut it = stream_block_summaries_auto(&path).unwrap();
+        assert_eq!(it.next().unwrap().unwrap(), blocks[0]);
+        assert_eq!(it.next().unwrap().unwrap(), blocks[1]);
+        let err = it.next().unwrap().unwrap_err();
+        assert!(format!("{err:#}").contains("block record 2"), "{err:#}");
+        assert!(it.next().is_none());
+        let _ = std::fs::remove_file(path);
+    }
+
     #[test]
     fn proof_cbor_roundtrip() {
         let path = tmp_path("proof", "cbor");
-        let pa = ProofArtifact {
-            backend: BackendKind::Fold,
-            manifest_root: [42u8; 32],
-            proof_bytes: vec![1, 2, 3, 4],
-            meta: serde_json::json!({"bench": true}),
-        };
+        let pa = ProofArtifact::new(
+            BackendKind::Fold,
+            [42u8; 32],
+            vec![1, 2, 3, 4],
+            serde_json::json!({"bench": true}),
+        );
         write_proof_artifact_auto(&path, &pa).unwrap();
         let got = read_proof_artifact_auto(&path).unwrap();
         assert_eq!(got.backend, pa.backend);
@@ -314,6 +364,42 @@ mod tests {
         let _ = std::fs::remove_file(path);
     }
 
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn zst_files_roundtrip_through_the_auto_helpers() {
+        let blocks = demo_blocks();
+        for ext in ["cbor.zst", "json.zst", "jsonl.zst"] {
+            let path = tmp_path("blocks_zst", ext);
+            write_block_summaries_auto(&path, &blocks).unwrap();
+            assert!(crate::compress::is_compressed(&path).unwrap(), "{ext}");
+            assert_eq!(read_block_summaries_auto(&path).unwrap(), blocks, "{ext}");
+            let streamed: Vec<_> = stream_block_summaries_auto(&path)
+                .unwrap()
+                .collect::<Result<_>>()
+                .unwrap();
+            assert_eq!(streamed, blocks, "{ext}");
+            let _ = std::fs::remove_file(path);
+        }
+
+        let pa = ProofArtifact::new(
+            BackendKind::Stark,
+            [7u8; 32],
+            vec![0; 4096],
+            serde_json::json!({"proto": "stark-v1"}),
+        );
+        let path = tmp_path("proof_zst", "cbor.zst");
+        write_proof_artifact_auto(&path, &pa).unwrap();
+        assert!(std::fs::metadata(&path).unwrap().len() < 1024);
+        let got = read_proof_artifact_auto(&path).unwrap();
+        assert_eq!(got.proof_bytes, pa.proof_bytes);
+        // Content decides on read: a compressed file under a plain name loads too.
+        let plain = tmp_path("proof_zst_renamed", "cbor");
+        std::fs::rename(&path, &plain).unwrap();
+        let got = read_proof_artifact_auto(&plain).unwrap();
+        assert_eq!(got.manifest_root, pa.manifest_root);
+        let _ = std::fs::remove_file(plain);
+    }
+
     #[test]
     fn in_memory_cbor_helpers_roundtrip() {
         let wrapped = Versioned::new(2u16, vec![1u32, 2, 3, 5, 8]);
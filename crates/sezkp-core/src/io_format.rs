@@ -0,0 +1,453 @@
+//! File-format resolution for the `*_auto` readers and writers.
+//!
+//! **Reads** look at both the extension and the leading bytes:
+//! - a definitive sniff (CBOR major type, a `[` array, several JSON values on
+//!   separate lines) wins over a wrong extension;
+//! - an ambiguous sniff (one JSON object on one line may be JSON or a
+//!   one-record JSON Lines file) defers to the extension;
+//! - an unknown or missing extension is resolved by sniffing alone.
+//!
+//! When sniffing cannot decide, callers can pin the format for a kind of file
+//! with [`set_format_override`] (the CLI exposes `--blocks-format`).
+//!
+//! **Writes** follow the extension and fall back to JSON when it is unknown or
+//! missing.
+//!
+//! A trailing `.zst` is looked through: `blocks.jsonl.zst` has the JSON
+//! Lines extension, and compressed content is sniffed after decompression
+//! (see [`crate::compress`]).
+//!
+//! **Strict mode** ([`set_strict_io`], or `SEZKP_STRICT_IO=1`) turns every
+//! fallback into an error: unknown/missing extensions are rejected, and so is
+//! content that definitively contradicts its extension. A `proof.cborseq`
+//! can therefore never silently end up containing JSON.
+
+use anyhow::{bail, Context, Result};
+use std::io::Read;
+use std::path::Path;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Mutex;
+
+/// Serialization formats understood by the auto-detecting helpers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FileFormat {
+    /// A single JSON value (`.json`).
+    Json,
+    /// A single CBOR value (`.cbor`).
+    Cbor,
+    /// One JSON value per line (`.jsonl` / `.ndjson`).
+    Jsonl,
+}
+
+impl FileFormat {
+    /// Parse a user-supplied format name (`json`, `cbor`, `jsonl`/`ndjson`).
+    ///
+    /// # Errors
+    /// Fails on any other name.
+    pub fn parse(name: &str) -> Result<Self> {
+        match Self::from_ext(&name.to_ascii_lowercase()) {
+            Some(f) => Ok(f),
+            None => bail!("unknown format {name:?} (expected json, cbor or jsonl)"),
+        }
+    }
+
+    /// Whether this is a text (JSON-family) format.
+    #[must_use]
+    pub const fn is_text(self) -> bool {
+        matches!(self, Self::Json | Self::Jsonl)
+    }
+
+    /// Canonical extension (without the dot).
+    #[must_use]
+    pub const fn ext(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Cbor => "cbor",
+            Self::Jsonl => "jsonl",
+        }
+    }
+
+    /// Map a (lowercase) extension to a format.
+    #[must_use]
+    pub fn from_ext(ext: &str) -> Option<Self> {
+        match ext {
+            "json" => Some(Self::Json),
+            "cbor" => Some(Self::Cbor),
+            "jsonl" | "ndjson" => Some(Self::Jsonl),
+            _ => None,
+        }
+    }
+}
+
+/* ------------------------------- strict flag ------------------------------- */
+
+const STRICT_UNSET: u8 = 0;
+const STRICT_OFF: u8 = 1;
+const STRICT_ON: u8 = 2;
+
+static STRICT: AtomicU8 = AtomicU8::new(STRICT_UNSET);
+
+/// Enable or disable strict I/O for this process (overrides `SEZKP_STRICT_IO`).
+pub fn set_strict_io(on: bool) {
+    STRICT.store(if on { STRICT_ON } else { STRICT_OFF }, Ordering::Relaxed);
+}
+
+/// Whether strict I/O is active.
+///
+/// Unless [`set_strict_io`] was called, this reads `SEZKP_STRICT_IO`
+/// (`1`/`true`/`yes`/`on` enable it).
+#[must_use]
+pub fn strict_io() -> bool {
+    match STRICT.load(Ordering::Relaxed) {
+        STRICT_ON => true,
+        STRICT_OFF => false,
+        _ => std::env::var("SEZKP_STRICT_IO")
+            .is_ok_and(|v| matches!(v.to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on")),
+    }
+}
+
+/* -------------------------------- overrides -------------------------------- */
+
+static OVERRIDES: Mutex<Vec<(String, FileFormat)>> = Mutex::new(Vec::new());
+
+/// Force the read format for every file of kind `what` (e.g. `"blocks"`),
+/// bypassing extension and content checks; `None` clears it.
+pub fn set_format_override(what: &str, format: Option<FileFormat>) {
+    if let Ok(mut v) = OVERRIDES.lock() {
+        v.retain(|(w, _)| w != what);
+        if let Some(f) = format {
+            v.push((what.to_owned(), f));
+        }
+    }
+}
+
+/// Current read-format override for files of kind `what`.
+#[must_use]
+pub fn format_override(what: &str) -> Option<FileFormat> {
+    OVERRIDES
+        .lock()
+        .ok()
+        .and_then(|v| v.iter().find(|(w, _)| w == what).map(|&(_, f)| f))
+}
+
+/* -------------------------------- sniffing -------------------------------- */
+
+/// Bytes inspected when sniffing.
+const SNIFF_LEN: usize = 4096;
+
+/// Result of inspecting the leading bytes of a file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Sniff {
+    /// Best guess, if any.
+    pub format: Option<FileFormat>,
+    /// Whether the guess rules out every other format (and so may override
+    /// the extension).
+    pub definitive: bool,
+}
+
+impl Sniff {
+    const UNKNOWN: Self = Self {
+        format: None,
+        definitive: false,
+    };
+
+    const fn sure(f: FileFormat) -> Self {
+        Self {
+            format: Some(f),
+            definitive: true,
+        }
+    }
+}
+
+/// Inspect the leading bytes of the file at `path`. A zstd-compressed file
+/// is sniffed by its decompressed content.
+///
+/// # Errors
+/// Fails if the file cannot be opened or read.
+pub fn sniff_format(path: &Path) -> Result<Sniff> {
+    let f = crate::compress::open(path)?;
+    let mut buf = Vec::with_capacity(SNIFF_LEN);
+    f.take(SNIFF_LEN as u64)
+        .read_to_end(&mut buf)
+        .with_context(|| format!("read {}", path.display()))?;
+    Ok(sniff_bytes(&buf))
+}
+
+/// [`sniff_format`] over an in-memory prefix.
+///
+/// - CBOR array/map major types (or the self-describe tag) → CBOR;
+/// - `[` → JSON;
+/// - `{` whose first line is a complete value followed by more content →
+///   JSON Lines; `{` whose first line is incomplete → JSON;
+/// - a lone one-line `{…}` → JSON, but *not* definitive (it is also a valid
+///   one-record JSON Lines file).
+#[must_use]
+pub fn sniff_bytes(head: &[u8]) -> Sniff {
+    // CBOR: 0xd9d9f7 self-describe tag, array (major 4) or map (major 5).
+    // These bytes never start a UTF-8 JSON document.
+    match head.first() {
+        Some(0xd9) if head.starts_with(&[0xd9, 0xd9, 0xf7]) => {
+            return Sniff::sure(FileFormat::Cbor)
+        }
+        Some(0x80..=0xbf) => return Sniff::sure(FileFormat::Cbor),
+        _ => {}
+    }
+
+    let head = head.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(head);
+    let Some(start) = head.iter().position(|b| !b.is_ascii_whitespace()) else {
+        return Sniff::UNKNOWN;
+    };
+    match head[start] {
+        b'[' => Sniff::sure(FileFormat::Json),
+        b'{' => {
+            let text = &head[start..];
+            let Some(end) = text.iter().position(|&b| b == b'\n') else {
+                // One line (or longer than the sniff window): cannot tell.
+                return Sniff {
+                    format: Some(FileFormat::Json),
+                    definitive: false,
+                };
+            };
+            let first_ok = serde_json::from_slice::<serde_json::Value>(&text[..end]).is_ok();
+            let more = text[end..].iter().any(|b| !b.is_ascii_whitespace());
+            match (first_ok, more) {
+                (true, true) => Sniff::sure(FileFormat::Jsonl),
+                (false, _) => Sniff::sure(FileFormat::Json),
+                (true, false) => Sniff {
+                    format: Some(FileFormat::Json),
+                    definitive: false,
+                },
+            }
+        }
+        _ => Sniff::UNKNOWN,
+    }
+}
+
+/* ------------------------------- resolution ------------------------------- */
+
+/// Lowercase extension of `path`, looking through a trailing `.zst`.
+fn ext_lower(path: &Path) -> Option<String> {
+    let path = if crate::compress::is_zst_path(path) {
+        Path::new(path.file_stem()?)
+    } else {
+        path
+    };
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase)
+}
+
+fn supported(allowed: &[FileFormat]) -> String {
+    allowed
+        .iter()
+        .map(|f| format!(".{}", f.ext()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Decide how to read `path` holding a `what` (e.g. `"blocks"`).
+///
+/// See the [module docs](self) for the precedence between override,
+/// content and extension.
+///
+/// # Errors
+/// Fails if no allowed format fits, if the file cannot be sniffed, or — in
+/// strict mode — if the extension is unknown/missing or contradicted by the
+/// content.
+pub fn resolve_read_format(path: &Path, what: &str, allowed: &[FileFormat]) -> Result<FileFormat> {
+    if let Some(f) = format_override(what) {
+        if allowed.contains(&f) {
+            return Ok(f);
+        }
+        bail!(
+            "{what} format override {f:?} is not supported here (expected {})",
+            supported(allowed)
+        );
+    }
+
+    let ext = ext_lower(path);
+    let by_ext = ext
+        .as_deref()
+        .and_then(FileFormat::from_ext)
+        .filter(|f| allowed.contains(f));
+    if strict_io() && by_ext.is_none() {
+        bail!(
+            "strict I/O: {} has {} extension (expected {})",
+            path.display(),
+            ext.map_or_else(|| "no".to_owned(), |e| format!("unsupported .{e}")),
+            supported(allowed)
+        );
+    }
+
+    let sniff = sniff_format(path)?;
+    match (by_ext, sniff.format) {
+        (Some(e), Some(s)) if s != e && sniff.definitive => {
+            if strict_io() {
+                bail!(
+                    "strict I/O: {} has a .{} extension but its content is {s:?}",
+                    path.display(),
+                    e.ext()
+                );
+            }
+            if allowed.contains(&s) {
+                Ok(s)
+            } else {
+                bail!(
+                    "{} looks like {s:?}, which is not a supported {what} format ({})",
+                    path.display(),
+                    supported(allowed)
+                )
+            }
+        }
+        (Some(e), _) => Ok(e),
+        (None, Some(FileFormat::Json))
+            if !sniff.definitive && allowed.contains(&FileFormat::Jsonl) =>
+        {
+            Ok(FileFormat::Jsonl)
+        }
+        (None, Some(s)) if allowed.contains(&s) => Ok(s),
+        (None, Some(s)) => bail!(
+            "{} looks like {s:?}, which is not a supported {what} format ({})",
+            path.display(),
+            supported(allowed)
+        ),
+        (None, None) => bail!(
+            "cannot tell the format of {} from its extension or content (expected {}; \
+             pass an explicit format to override)",
+            path.display(),
+            supported(allowed)
+        ),
+    }
+}
+
+/// Decide how to write a `what` to `path`.
+///
+/// # Errors
+/// In strict mode an unknown or missing extension is an error; otherwise it
+/// falls back to JSON.
+pub fn resolve_write_format(path: &Path, what: &str, allowed: &[FileFormat]) -> Result<FileFormat> {
+    let ext = ext_lower(path);
+    match ext.as_deref().and_then(FileFormat::from_ext) {
+        Some(f) if allowed.contains(&f) => Ok(f),
+        _ if strict_io() => bail!(
+            "strict I/O: refusing to write {what} to {} (unsupported extension {}; expected {})",
+            path.display(),
+            ext.map_or_else(|| "<none>".to_owned(), |e| format!(".{e}")),
+            supported(allowed)
+        ),
+        _ => Ok(FileFormat::Json),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Serializes tests that touch the process-wide strict flag.
+    static GLOBALS: Mutex<()> = Mutex::new(());
+
+    fn fmt(head: &[u8]) -> (Option<FileFormat>, bool) {
+        let s = sniff_bytes(head);
+        (s.format, s.definitive)
+    }
+
+    #[test]
+    fn sniff_recognizes_json_jsonl_and_cbor() {
+        use FileFormat::{Cbor, Json, Jsonl};
+        assert_eq!(fmt(b"  [1, 2]"), (Some(Json), true));
+        assert_eq!(fmt(b"{\"a\":1}"), (Some(Json), false));
+        assert_eq!(fmt(b"{\"a\":1}\n"), (Some(Json), false));
+        assert_eq!(
+            fmt(b"{\n  \"a\": 1\n}\n"),
+            (Some(Json), true),
+            "pretty-printed object is not JSONL"
+        );
+        assert_eq!(fmt(b"{\"a\":1}\n{\"a\":2}\n"), (Some(Jsonl), true));
+        assert_eq!(fmt(&[0x82, 0x01, 0x02]), (Some(Cbor), true));
+        assert_eq!(fmt(&[0xa1, 0x61, 0x61, 0x01]), (Some(Cbor), true));
+        assert_eq!(fmt(b"hello"), (None, false));
+        assert_eq!(fmt(b""), (None, false));
+    }
+
+    #[test]
+    fn read_resolution_prefers_definitive_content() {
+        let _g = GLOBALS.lock().unwrap();
+        let dir = std::env::temp_dir().join(format!("sezkp_io_format_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let all = [FileFormat::Json, FileFormat::Cbor, FileFormat::Jsonl];
+        let put = |name: &str, bytes: &[u8]| {
+            let p = dir.join(name);
+            std::fs::write(&p, bytes).unwrap();
+            p
+        };
+
+        // CBOR behind a .json extension, JSON Lines behind .json, no extension.
+        let p = put("a.json", &[0x80]);
+        assert_eq!(
+            resolve_read_format(&p, "t1", &all).unwrap(),
+            FileFormat::Cbor
+        );
+        let p = put("b.json", b"{}\n{}\n");
+        assert_eq!(
+            resolve_read_format(&p, "t1", &all).unwrap(),
+            FileFormat::Jsonl
+        );
+        let p = put("c.dat", b"[]");
+        assert_eq!(
+            resolve_read_format(&p, "t1", &all).unwrap(),
+            FileFormat::Json
+        );
+
+        // Ambiguous one-liner: extension decides; without one JSONL wins when allowed.
+        let p = put("d.jsonl", b"{}\n");
+        assert_eq!(
+            resolve_read_format(&p, "t1", &all).unwrap(),
+            FileFormat::Jsonl
+        );
+        let p = put("e", b"{}\n");
+        assert_eq!(
+            resolve_read_format(&p, "t1", &all).unwrap(),
+            FileFormat::Jsonl
+        );
+        let json_only = [FileFormat::Json, FileFormat::Cbor];
+        assert_eq!(
+            resolve_read_format(&p, "t1", &json_only).unwrap(),
+            FileFormat::Json
+        );
+
+        // Explicit override beats everything, but only for its kind.
+        set_format_override("t2", Some(FileFormat::Json));
+        assert_eq!(
+            resolve_read_format(&p, "t2", &all).unwrap(),
+            FileFormat::Json
+        );
+        assert_eq!(
+            resolve_read_format(&p, "t1", &all).unwrap(),
+            FileFormat::Jsonl
+        );
+        set_format_override("t2", None);
+
+        let p = put("f.bin", b"hello");
+        assert!(resolve_read_format(&p, "t1", &all).is_err());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn write_resolution_defaults_to_json_unless_strict() {
+        let _g = GLOBALS.lock().unwrap();
+        let all = [FileFormat::Json, FileFormat::Cbor];
+        assert_eq!(
+            resolve_write_format(Path::new("x.CBOR"), "proof", &all).unwrap(),
+            FileFormat::Cbor
+        );
+        set_strict_io(false);
+        assert_eq!(
+            resolve_write_format(Path::new("x.cborseq"), "proof", &all).unwrap(),
+            FileFormat::Json
+        );
+        set_strict_io(true);
+        assert!(resolve_write_format(Path::new("x.cborseq"), "proof", &all).is_err());
+        assert!(resolve_write_format(Path::new("x"), "proof", &all).is_err());
+        set_strict_io(false);
+    }
+}
@@ -8,64 +8,239 @@
 //!   (No borrowed iterators that outlive their buffers.)
 //! - **Writer**: uses `serde_json::to_writer` to avoid intermediate allocations.
 //!
+//! - **Parallel reader** (`parallel` feature): reads byte chunks, splits them
+//!   on newlines and parses the lines on the configured pool (see
+//!   [`crate::parallelism`]), yielding blocks in file order with the same
+//!   per-line errors as the sequential reader (see
+//!   [`stream_block_summaries_jsonl_parallel`]).
+//!   [`stream_block_summaries_jsonl_auto`] picks it whenever it is compiled in.
+//!
+//! - **Index sidecar**: `blocks.jsonl.idx` records the byte offset of every
+//!   `K`-th line, so block `N` is reached with one seek plus at most `K - 1`
+//!   skipped lines instead of a scan from the start (see [`JsonlIndex`]).
+//!
+//! - **Compression**: every reader decompresses zstd content and the writers
+//!   compress to `*.jsonl.zst` paths (see [`crate::compress`]). Compressed
+//!   files have no index; the line-addressed helpers scan them from the top.
+//!
+//! Every reader enforces the [read limits](crate::read_limits): a line longer
+//! than the record limit is reported without being buffered whole (and ends
+//! the stream, since the rest of it is never read), and each parsed block is
+//! checked for its step count and τ.
+//!
+//! - **End-of-stream marker**: the writers end every blocks file with
+//!   `{"sezkp_eof":N}`, `N` being the number of block lines before it.
+//!   Readers stop at the marker and fail if `N` does not match or anything
+//!   follows it. A file without a marker still reads, unless the marker is
+//!   [required](require_jsonl_eof): then a writer killed mid-file is reported
+//!   instead of silently yielding a prefix of the blocks.
+//!
 //! # Formats
 //! We treat both `.jsonl` and `.ndjson` as equivalent line-delimited JSON.
 
 #![allow(unused_imports)]
-use anyhow::{Context, Result};
-use serde::Serialize;
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use serde::{Deserialize, Serialize};
 use std::fs::File;
-use std::io::{BufRead, BufReader, BufWriter, Read, Write};
-use std::path::Path;
+use std::io::{BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU8, Ordering};
 
+use crate::read_limits::{read_limits, ReadLimits};
 use crate::BlockSummary;
 
+/* --------------------------- End-of-stream marker --------------------------- */
+
+/// Key of the end-of-stream marker line, `{"sezkp_eof":N}`.
+pub const JSONL_EOF_KEY: &str = "sezkp_eof";
+
+/// Environment variable for [`require_jsonl_eof`].
+pub const ENV_REQUIRE_JSONL_EOF: &str = "SEZKP_REQUIRE_JSONL_EOF";
+
+/// [`JSONL_EOF_KEY`] as it starts a marker line.
+const EOF_KEY_QUOTED: &[u8] = b"\"sezkp_eof\"";
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EofMarker {
+    sezkp_eof: u64,
+}
+
+/// The end-of-stream marker line (newline included) closing a file of
+/// `n_blocks` blocks.
+#[must_use]
+pub fn jsonl_eof_line(n_blocks: u64) -> String {
+    format!("{{\"{JSONL_EOF_KEY}\":{n_blocks}}}\n")
+}
+
+/// The block count of an end-of-stream marker line (without its newline),
+/// or `None` if `line` is not one. Blocks start with another key, so a cheap
+/// prefix test suffices.
+#[must_use]
+pub fn jsonl_eof_marker(line: &[u8]) -> Option<Result<u64>> {
+    let line = line.strip_suffix(b"\r").unwrap_or(line);
+    let skip_ws = |b: &[u8]| {
+        let i = b.iter().position(|c| !c.is_ascii_whitespace());
+        i.unwrap_or(b.len())
+    };
+    let rest = line[skip_ws(line)..].strip_prefix(b"{")?;
+    let rest = &rest[skip_ws(rest)..];
+    if !rest.starts_with(EOF_KEY_QUOTED) {
+        return None;
+    }
+    Some(
+        serde_json::from_slice::<EofMarker>(line)
+            .map(|m| m.sezkp_eof)
+            .context("malformed end-of-stream marker"),
+    )
+}
+
+/// Check the marker read as line `line_no`: its count must match the lines
+/// before it (when they are `counted` from the top of the file) and nothing
+/// may follow it.
+fn check_eof(marker: Result<u64>, line_no: usize, counted: bool, rest_empty: bool) -> Result<()> {
+    let check = || -> Result<()> {
+        let n = marker?;
+        let blocks = line_no - 1;
+        ensure!(
+            !counted || n == blocks as u64,
+            "end-of-stream marker declares {n} blocks but {blocks} precede it"
+        );
+        ensure!(rest_empty, "data after the end-of-stream marker");
+        Ok(())
+    };
+    check().with_context(|| format!("jsonl line {line_no}"))
+}
+
+fn missing_eof(lines: usize) -> anyhow::Error {
+    anyhow!("jsonl ended after {lines} lines without an end-of-stream marker (truncated file?)")
+}
+
+const REQUIRE_UNSET: u8 = 0;
+const REQUIRE_OFF: u8 = 1;
+const REQUIRE_ON: u8 = 2;
+
+static REQUIRE_EOF: AtomicU8 = AtomicU8::new(REQUIRE_UNSET);
+
+/// Require the end-of-stream marker in every JSONL blocks file this process
+/// reads (overrides `SEZKP_REQUIRE_JSONL_EOF`).
+pub fn set_require_jsonl_eof(on: bool) {
+    REQUIRE_EOF.store(if on { REQUIRE_ON } else { REQUIRE_OFF }, Ordering::Relaxed);
+}
+
+/// Whether readers reject a JSONL blocks file that ends without its marker.
+///
+/// Unless [`set_require_jsonl_eof`] was called, this reads
+/// `SEZKP_REQUIRE_JSONL_EOF` (`1`/`true`/`yes`/`on` enable it).
+#[must_use]
+pub fn require_jsonl_eof() -> bool {
+    match REQUIRE_EOF.load(Ordering::Relaxed) {
+        REQUIRE_ON => true,
+        REQUIRE_OFF => false,
+        _ => std::env::var(ENV_REQUIRE_JSONL_EOF)
+            .is_ok_and(|v| matches!(v.to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on")),
+    }
+}
+
 /// Owning JSONL iterator over `BlockSummary`.
 ///
 /// Holds the file and buffered reader internally to avoid lifetime pitfalls
 /// of returning a borrowed `Lines<'_>` iterator.
 pub struct JsonlBlockIter {
-    rdr: BufReader<File>,
-    buf: String,
+    rdr: Box<dyn BufRead + Send>,
+    buf: Vec<u8>,
     line_no: usize,
+    limits: ReadLimits,
+    require_eof: bool,
+    /// Whether `line_no` counts from the top of the file, so the marker's
+    /// block count can be checked.
+    counted: bool,
+    done: bool,
 }
 
 impl JsonlBlockIter {
-    fn new(file: File) -> Self {
+    fn new(rdr: Box<dyn BufRead + Send>, limits: ReadLimits) -> Self {
+        Self::starting_at(rdr, 0, limits)
+    }
+
+    /// Resume from a reader already positioned at the start of line `line_no`.
+    fn starting_at(rdr: Box<dyn BufRead + Send>, line_no: usize, limits: ReadLimits) -> Self {
         Self {
-            rdr: BufReader::new(file),
-            buf: String::with_capacity(8 << 10),
-            line_no: 0,
+            rdr,
+            buf: Vec::with_capacity(8 << 10),
+            line_no,
+            limits,
+            require_eof: require_jsonl_eof(),
+            counted: true,
+            done: false,
         }
     }
+
+    /// Read under `limits` instead of the process-wide [`read_limits`].
+    #[must_use]
+    pub const fn with_limits(mut self, limits: ReadLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Whether a missing end-of-stream marker is an error, instead of the
+    /// process-wide [`require_jsonl_eof`].
+    #[must_use]
+    pub const fn with_require_eof(mut self, on: bool) -> Self {
+        self.require_eof = on;
+        self
+    }
+}
+
+/// Parse one line (without its `\n`; a trailing `\r` is dropped) as block
+/// `line_no` (1-based, for error messages).
+fn parse_block_line(line: &[u8], line_no: usize, limits: &ReadLimits) -> Result<BlockSummary> {
+    limits
+        .check_record_len(line.len() as u64)
+        .with_context(|| format!("jsonl line {line_no}"))?;
+    let line = line.strip_suffix(b"\r").unwrap_or(line);
+    if line.is_empty() {
+        // Allow blank lines but surface them clearly as parse errors.
+        bail!("parse jsonl line {line_no}: empty line");
+    }
+    let b = serde_json::from_slice(line).with_context(|| format!("parse jsonl line {line_no}"))?;
+    limits
+        .check_block(&b)
+        .with_context(|| format!("jsonl line {line_no}"))?;
+    Ok(b)
 }
 
 impl Iterator for JsonlBlockIter {
     type Item = Result<BlockSummary>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
         self.buf.clear();
-        match self.rdr.read_line(&mut self.buf) {
-            Ok(0) => None, // EOF
+        // One byte past the limit (plus the newline) is enough to tell an
+        // oversized line apart without reading the rest of it.
+        let cap = self.limits.max_record_bytes.saturating_add(1);
+        match (&mut self.rdr).take(cap).read_until(b'\n', &mut self.buf) {
+            Ok(0) => {
+                // EOF
+                self.done = true;
+                self.require_eof.then(|| Err(missing_eof(self.line_no)))
+            }
             Ok(_) => {
                 self.line_no += 1;
-                // Trim a single trailing '\n' or '\r\n'
-                if self.buf.ends_with('\n') {
-                    self.buf.pop();
-                    if self.buf.ends_with('\r') {
-                        self.buf.pop();
-                    }
-                }
-                if self.buf.is_empty() {
-                    // Allow blank lines but surface them clearly as parse errors.
-                    return Some(Err(anyhow::anyhow!(
-                        "parse jsonl line {}: empty line",
-                        self.line_no
-                    )));
+                // Without its newline the line may continue past `cap`.
+                self.done = self.buf.len() as u64 == cap && !self.buf.ends_with(b"\n");
+                let line = self.buf.strip_suffix(b"\n").unwrap_or(&self.buf);
+                if let Some(marker) = jsonl_eof_marker(line).filter(|_| !self.done) {
+                    self.done = true;
+                    let rest_empty = self.rdr.fill_buf().is_ok_and(<[u8]>::is_empty);
+                    return check_eof(marker, self.line_no, self.counted, rest_empty)
+                        .err()
+                        .map(Err);
                 }
-                let parsed: Result<BlockSummary> = serde_json::from_str(&self.buf)
-                    .with_context(|| format!("parse jsonl line {}", self.line_no));
-                Some(parsed)
+                Some(parse_block_line(line, self.line_no, &self.limits))
             }
             Err(e) => Some(Err(e).with_context(|| format!("read line {}", self.line_no + 1))),
         }
@@ -79,43 +254,604 @@ impl Iterator for JsonlBlockIter {
 /// number if parsing fails.
 ///
 /// # Errors
-/// Opening the file may fail. Individual iteration items may be `Err` if a
-/// particular line is malformed.
+/// Opening the file may fail, as may resolving the [`read_limits`].
+/// Individual iteration items may be `Err` if a particular line is malformed
+/// or over the limits.
 pub fn stream_block_summaries_jsonl<P: AsRef<Path>>(path: P) -> Result<JsonlBlockIter> {
-    let f = File::open(path.as_ref())
-        .with_context(|| format!("open {}", path.as_ref().display()))?;
-    Ok(JsonlBlockIter::new(f))
+    let limits = read_limits()?;
+    let rdr = crate::compress::open(path.as_ref())?;
+    Ok(JsonlBlockIter::new(rdr, limits))
+}
+
+/// Boxed block stream from [`stream_block_summaries_auto`](crate::io::stream_block_summaries_auto)
+/// and friends.
+pub type BlockStream = Box<dyn Iterator<Item = Result<BlockSummary>> + Send>;
+
+/// Stream a JSONL blocks file with the fastest reader this build has: the
+/// parallel reader with the `parallel` feature, the sequential one otherwise.
+/// Both yield the same items in the same order.
+///
+/// # Errors
+/// Opening the file may fail.
+pub fn stream_block_summaries_jsonl_auto<P: AsRef<Path>>(path: P) -> Result<BlockStream> {
+    #[cfg(feature = "parallel")]
+    let it = stream_block_summaries_jsonl_parallel(path)?;
+    #[cfg(not(feature = "parallel"))]
+    let it = stream_block_summaries_jsonl(path)?;
+    Ok(Box::new(it))
+}
+
+/* ----------------------------- parallel reader ------------------------------ */
+
+/// Bytes read per chunk by [`ParallelJsonlBlockIter`].
+#[cfg(feature = "parallel")]
+pub const PARALLEL_CHUNK_BYTES: usize = 4 << 20;
+
+/// Owning JSONL iterator that parses each chunk of lines on the
+/// [configured pool](crate::parallelism).
+///
+/// Memory is bounded by one chunk plus its parsed blocks (a line longer than
+/// a chunk is kept whole, growing the buffer until its newline or the record
+/// limit, whichever comes first).
+#[cfg(feature = "parallel")]
+pub struct ParallelJsonlBlockIter {
+    file: Box<dyn BufRead + Send>,
+    chunk_bytes: usize,
+    /// Bytes after the last newline of the previous chunk.
+    carry: Vec<u8>,
+    ready: std::vec::IntoIter<Result<BlockSummary>>,
+    line_no: usize,
+    limits: ReadLimits,
+    /// Cleared once the marker is read (or its absence reported).
+    require_eof: bool,
+    done: bool,
+    /// Whether the file was read to its end (not stopped at an error).
+    at_eof: bool,
+}
+
+#[cfg(feature = "parallel")]
+impl ParallelJsonlBlockIter {
+    fn new(file: Box<dyn BufRead + Send>, limits: ReadLimits) -> Self {
+        Self {
+            file,
+            chunk_bytes: PARALLEL_CHUNK_BYTES,
+            carry: Vec::new(),
+            ready: Vec::new().into_iter(),
+            line_no: 0,
+            limits,
+            require_eof: require_jsonl_eof(),
+            done: false,
+            at_eof: false,
+        }
+    }
+
+    /// Read under `limits` instead of the process-wide [`read_limits`].
+    #[must_use]
+    pub const fn with_limits(mut self, limits: ReadLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Whether a missing end-of-stream marker is an error, instead of the
+    /// process-wide [`require_jsonl_eof`].
+    #[must_use]
+    pub const fn with_require_eof(mut self, on: bool) -> Self {
+        self.require_eof = on;
+        self
+    }
+
+    /// Read `n` bytes per chunk instead of [`PARALLEL_CHUNK_BYTES`] (min 1).
+    #[must_use]
+    pub fn with_chunk_bytes(mut self, n: usize) -> Self {
+        self.chunk_bytes = n.max(1);
+        self
+    }
+
+    /// Read the next chunk and parse its complete lines into `ready`.
+    fn refill(&mut self) -> Result<()> {
+        use rayon::prelude::*;
+
+        let mut buf = std::mem::take(&mut self.carry);
+        let start = buf.len();
+        buf.resize(start + self.chunk_bytes, 0);
+        let mut filled = start;
+        while filled < buf.len() {
+            match self.file.read(&mut buf[filled..]) {
+                Ok(0) => {
+                    self.done = true;
+                    self.at_eof = true;
+                    break;
+                }
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e).with_context(|| format!("read line {}", self.line_no + 1)),
+            }
+        }
+        buf.truncate(filled);
+
+        // Keep a trailing partial line for the next chunk (all of it at EOF).
+        let cut = if self.done {
+            buf.len()
+        } else {
+            buf.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1)
+        };
+        self.carry = buf.split_off(cut);
+
+        let mut lines: Vec<&[u8]> = buf.split(|&b| b == b'\n').collect();
+        if buf.last().map_or(true, |&b| b == b'\n') {
+            lines.pop(); // empty tail after the final newline
+        }
+        // Like the sequential reader, stop at the first oversized line; an
+        // unterminated tail already over the limit is one too.
+        let max = self.limits.max_record_bytes;
+        if let Some(i) = lines.iter().position(|l| l.len() as u64 > max) {
+            lines.truncate(i + 1);
+            (self.done, self.at_eof) = (true, false);
+        } else if self.carry.len() as u64 > max {
+            lines.push(&self.carry);
+            (self.done, self.at_eof) = (true, false);
+        }
+        let first = self.line_no + 1;
+        self.line_no += lines.len();
+
+        // The marker ends the stream; whatever follows it is an error.
+        let mut end = None;
+        if let Some((i, marker)) = lines.iter().enumerate().find_map(|(i, l)| {
+            let max_ok = l.len() as u64 <= max;
+            jsonl_eof_marker(l).filter(|_| max_ok).map(|m| (i, m))
+        }) {
+            let rest_empty = i + 1 == lines.len()
+                && self.carry.is_empty()
+                && self.file.fill_buf().is_ok_and(<[u8]>::is_empty);
+            end = check_eof(marker, first + i, true, rest_empty).err();
+            lines.truncate(i);
+            (self.done, self.require_eof) = (true, false);
+        }
+
+        let limits = self.limits;
+        let min_len = crate::parallelism::chunking().min_len();
+        let mut parsed: Vec<_> = crate::parallelism::install(|| {
+            lines
+                .par_iter()
+                .enumerate()
+                .with_min_len(min_len)
+                .map(|(i, line)| parse_block_line(line, first + i, &limits))
+                .collect()
+        });
+        parsed.extend(end.map(Err));
+        self.ready = parsed.into_iter();
+        Ok(())
+    }
+}
+
+#[cfg(feature = "parallel")]
+impl Iterator for ParallelJsonlBlockIter {
+    type Item = Result<BlockSummary>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.ready.next() {
+                return Some(item);
+            }
+            if self.done {
+                if self.at_eof && self.require_eof {
+                    self.require_eof = false;
+                    return Some(Err(missing_eof(self.line_no)));
+                }
+                return None;
+            }
+            if let Err(e) = self.refill() {
+                self.done = true;
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+/// Parallel counterpart of [`stream_block_summaries_jsonl`]: same items,
+/// same order, same per-line errors, with each chunk's lines parsed
+/// concurrently.
+///
+/// # Errors
+/// Opening the file may fail. Individual iteration items may be `Err` if a
+/// particular line is malformed or the file cannot be read.
+#[cfg(feature = "parallel")]
+pub fn stream_block_summaries_jsonl_parallel<P: AsRef<Path>>(
+    path: P,
+) -> Result<ParallelJsonlBlockIter> {
+    let limits = read_limits()?;
+    let rdr = crate::compress::open(path.as_ref())?;
+    Ok(ParallelJsonlBlockIter::new(rdr, limits))
 }
 
-/// Write blocks as JSON Lines (one object per line).
+/// Write blocks as JSON Lines (one object per line), closed by the
+/// end-of-stream marker.
 ///
 /// Uses `serde_json::to_writer` directly to avoid temporary `String`s.
 pub fn write_block_summaries_jsonl<P: AsRef<Path>>(
     path: P,
     blocks: &[BlockSummary],
 ) -> Result<()> {
-    let f = File::create(path.as_ref())
-        .with_context(|| format!("create {}", path.as_ref().display()))?;
-    let mut w = BufWriter::new(f);
+    let mut w = crate::compress::FileSink::create(path.as_ref())?;
     for b in blocks {
         serde_json::to_writer(&mut w, b).context("serialize block to json")?;
         w.write_all(b"\n").context("write newline")?;
     }
-    w.flush().context("flush writer")?;
-    Ok(())
+    w.write_all(jsonl_eof_line(blocks.len() as u64).as_bytes())
+        .context("write end-of-stream marker")?;
+    w.finish()
 }
 
 /// Generic JSONL writer (handy if you want to dump other streams later).
 pub fn write_jsonl<P: AsRef<Path>, T: Serialize>(path: P, items: &[T]) -> Result<()> {
-    let f = File::create(path.as_ref())
-        .with_context(|| format!("create {}", path.as_ref().display()))?;
-    let mut w = BufWriter::new(f);
+    let mut w = crate::compress::FileSink::create(path.as_ref())?;
     for it in items {
         serde_json::to_writer(&mut w, it).context("serialize jsonl item")?;
         w.write_all(b"\n").context("write newline")?;
     }
-    w.flush().context("flush writer")?;
-    Ok(())
+    w.finish()
+}
+
+/* ------------------------------ Index sidecar ------------------------------ */
+
+/// Default line stride `K` of the index sidecar.
+pub const DEFAULT_INDEX_STRIDE: u64 = 1024;
+
+/// Current on-disk version of [`JsonlIndex`].
+pub const JSONL_INDEX_VERSION: u16 = 1;
+
+/// Byte-offset index over a JSONL file, stored as CBOR next to it.
+///
+/// `offsets[i]` is the byte offset at which line `i * stride` starts. The
+/// index is only trusted while the indexed file still has `file_len` bytes;
+/// otherwise readers fall back to scanning, so a stale sidecar is harmless.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JsonlIndex {
+    /// Format version ([`JSONL_INDEX_VERSION`]).
+    pub version: u16,
+    /// Lines between consecutive offsets (`K`, > 0).
+    pub stride: u64,
+    /// Number of lines in the indexed file.
+    pub lines: u64,
+    /// Byte length of the indexed file when the index was built.
+    pub file_len: u64,
+    /// Start offset of every `stride`-th line.
+    pub offsets: Vec<u64>,
+}
+
+impl JsonlIndex {
+    /// Where to start reading for `line`: `(byte offset, lines to skip)`.
+    ///
+    /// Returns `None` if `line` is past the end of the indexed file.
+    #[must_use]
+    pub fn locate(&self, line: u64) -> Option<(u64, u64)> {
+        if line >= self.lines {
+            return None;
+        }
+        let slot = usize::try_from(line / self.stride).ok()?;
+        Some((*self.offsets.get(slot)?, line % self.stride))
+    }
+}
+
+/// Records line offsets while JSONL is being written.
+///
+/// Feed it the byte length of every line (newline included) in order, then
+/// call [`JsonlIndexBuilder::finish`].
+#[derive(Debug, Clone)]
+pub struct JsonlIndexBuilder {
+    stride: u64,
+    lines: u64,
+    pos: u64,
+    offsets: Vec<u64>,
+}
+
+impl JsonlIndexBuilder {
+    /// Start an index with one offset every `stride` lines.
+    ///
+    /// # Panics
+    /// Panics if `stride == 0`.
+    #[must_use]
+    pub fn new(stride: u64) -> Self {
+        assert!(stride > 0, "index stride must be > 0");
+        Self {
+            stride,
+            lines: 0,
+            pos: 0,
+            offsets: Vec::new(),
+        }
+    }
+
+    /// Account for the next line, `len` bytes long including its newline.
+    pub fn push_line(&mut self, len: u64) {
+        if self.lines % self.stride == 0 {
+            self.offsets.push(self.pos);
+        }
+        self.lines += 1;
+        self.pos += len;
+    }
+
+    /// Finish the index; the file length is the total of all pushed lines.
+    #[must_use]
+    pub fn finish(self) -> JsonlIndex {
+        JsonlIndex {
+            version: JSONL_INDEX_VERSION,
+            stride: self.stride,
+            lines: self.lines,
+            file_len: self.pos,
+            offsets: self.offsets,
+        }
+    }
+}
+
+/// Sidecar path for a JSONL file: `blocks.jsonl` → `blocks.jsonl.idx`.
+#[must_use]
+pub fn jsonl_index_path<P: AsRef<Path>>(path: P) -> PathBuf {
+    let mut s = path.as_ref().as_os_str().to_owned();
+    s.push(".idx");
+    PathBuf::from(s)
+}
+
+/// Scan `path` once and build its index (lines are not parsed).
+///
+/// # Errors
+/// Fails if the file cannot be read, or is compressed: offsets into a zstd
+/// stream cannot be seeked to.
+pub fn build_jsonl_index<P: AsRef<Path>>(path: P, stride: u64) -> Result<JsonlIndex> {
+    let path = path.as_ref();
+    ensure!(
+        !crate::compress::is_compressed(path)?,
+        "cannot index {}: compressed files are not seekable",
+        path.display()
+    );
+    let f = File::open(path).with_context(|| format!("open {}", path.display()))?;
+    let mut rdr = BufReader::new(f);
+    let mut b = JsonlIndexBuilder::new(stride);
+    let mut line = Vec::with_capacity(8 << 10);
+    loop {
+        line.clear();
+        let n = rdr
+            .read_until(b'\n', &mut line)
+            .with_context(|| format!("scan {}", path.display()))?;
+        if n == 0 {
+            return Ok(b.finish());
+        }
+        b.push_line(n as u64);
+    }
+}
+
+/// Write `index` as the sidecar of the JSONL file at `path`.
+pub fn write_jsonl_index<P: AsRef<Path>>(path: P, index: &JsonlIndex) -> Result<()> {
+    let idx_path = jsonl_index_path(path);
+    let bytes = crate::io::to_cbor(index)?;
+    std::fs::write(&idx_path, bytes).with_context(|| format!("write {}", idx_path.display()))
+}
+
+/// Build and write the sidecar for the JSONL file at `path`.
+pub fn write_jsonl_index_for<P: AsRef<Path>>(path: P, stride: u64) -> Result<JsonlIndex> {
+    let index = build_jsonl_index(path.as_ref(), stride)?;
+    write_jsonl_index(path, &index)?;
+    Ok(index)
+}
+
+/// Load the sidecar of `path` if one exists and still matches the file.
+///
+/// Returns `Ok(None)` when there is no sidecar or it is stale (the file's
+/// length changed or the sidecar has another version).
+///
+/// # Errors
+/// Fails if the sidecar exists but cannot be read or decoded.
+pub fn read_jsonl_index<P: AsRef<Path>>(path: P) -> Result<Option<JsonlIndex>> {
+    let path = path.as_ref();
+    let idx_path = jsonl_index_path(path);
+    let bytes = match std::fs::read(&idx_path) {
+        Ok(b) => b,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("read {}", idx_path.display())),
+    };
+    let index: JsonlIndex = crate::io::from_cbor(&bytes)
+        .with_context(|| format!("decode index {}", idx_path.display()))?;
+    let file_len = std::fs::metadata(path)
+        .with_context(|| format!("stat {}", path.display()))?
+        .len();
+    let fresh =
+        index.version == JSONL_INDEX_VERSION && index.stride > 0 && index.file_len == file_len;
+    Ok(fresh.then_some(index))
+}
+
+/// Stream `BlockSummary` items starting at line `start` (0-based).
+///
+/// Uses the index sidecar when a fresh one exists (one seek, then at most
+/// `stride - 1` skipped lines); otherwise, and always for a compressed file,
+/// skips `start` lines from the top. Skipped lines are not parsed. Starting
+/// past the end yields nothing, even where the end-of-stream marker is
+/// required (it may have been skipped).
+pub fn stream_block_summaries_jsonl_from<P: AsRef<Path>>(
+    path: P,
+    start: usize,
+) -> Result<JsonlBlockIter> {
+    let path = path.as_ref();
+    let limits = read_limits()?;
+
+    let mut past_end = false;
+    let (mut rdr, mut skip): (Box<dyn BufRead + Send>, u64) =
+        if crate::compress::is_compressed(path)? {
+            (crate::compress::open(path)?, start as u64)
+        } else {
+            let mut f = File::open(path).with_context(|| format!("open {}", path.display()))?;
+            let (offset, skip) = match read_jsonl_index(path)? {
+                Some(index) => {
+                    let at = index.locate(start as u64);
+                    past_end = at.is_none();
+                    at.unwrap_or((index.file_len, 0))
+                }
+                None => (0, start as u64),
+            };
+            f.seek(SeekFrom::Start(offset))
+                .with_context(|| format!("seek {} to {offset}", path.display()))?;
+            (Box::new(BufReader::new(f)), skip)
+        };
+
+    let mut line = Vec::new();
+    while skip > 0 {
+        line.clear();
+        if rdr
+            .read_until(b'\n', &mut line)
+            .with_context(|| format!("skip lines in {}", path.display()))?
+            == 0
+        {
+            past_end = true;
+            break;
+        }
+        skip -= 1;
+    }
+    let mut it = JsonlBlockIter::starting_at(rdr, start, limits);
+    it.done = past_end;
+    Ok(it)
+}
+
+/// Read block `n` (0-based line) of a JSONL file.
+///
+/// # Errors
+/// Fails if the file has fewer than `n + 1` lines or line `n` is malformed.
+pub fn read_block_jsonl<P: AsRef<Path>>(path: P, n: usize) -> Result<BlockSummary> {
+    match stream_block_summaries_jsonl_from(path, n)?.next() {
+        Some(b) => b,
+        None => bail!("block {n} out of range"),
+    }
+}
+
+/// Read blocks `range` (0-based lines, end exclusive) of a JSONL file.
+///
+/// # Errors
+/// Fails if the file ends before `range.end` or any line in range is malformed.
+pub fn read_block_range_jsonl<P: AsRef<Path>>(
+    path: P,
+    range: Range<usize>,
+) -> Result<Vec<BlockSummary>> {
+    let want = range.len();
+    let v = stream_block_summaries_jsonl_from(path, range.start)?
+        .take(want)
+        .collect::<Result<Vec<_>>>()?;
+    ensure!(
+        v.len() == want,
+        "block range {}..{} out of range ({} blocks available from {})",
+        range.start,
+        range.end,
+        v.len(),
+        range.start
+    );
+    Ok(v)
+}
+
+/* ------------------------------- Head / tail ------------------------------- */
+
+/// Bytes read per step while [`tail_blocks`] scans back from the end.
+const TAIL_SCAN_BYTES: usize = 64 << 10;
+
+/// Read the first `n` blocks of a JSONL file (fewer if the file is shorter).
+///
+/// Stops after line `n`; the rest of the file is never read.
+///
+/// # Errors
+/// Fails if the file cannot be opened or one of the first `n` lines is
+/// malformed or over the limits.
+pub fn head_blocks<P: AsRef<Path>>(path: P, n: usize) -> Result<Vec<BlockSummary>> {
+    stream_block_summaries_jsonl(path)?.take(n).collect()
+}
+
+/// Read the last `n` blocks of a JSONL file (all of them if it is shorter).
+///
+/// With a fresh index sidecar this is one seek to the right line. Without
+/// one the file is scanned backwards in fixed-size steps until `n` line
+/// breaks are found, so only the tail is read and memory stays bounded by
+/// the step and the `n` returned blocks. Line numbers in errors are absolute
+/// with an index and count from the first tail line without one, where the
+/// end-of-stream marker's block count is then left unchecked.
+///
+/// # Errors
+/// Fails if the file cannot be read or one of the last `n` lines is
+/// malformed or over the limits.
+pub fn tail_blocks<P: AsRef<Path>>(path: P, n: usize) -> Result<Vec<BlockSummary>> {
+    // One extra line covers the end-of-stream marker; without one, the
+    // extra block is dropped again.
+    let keep_last = |v: Result<Vec<BlockSummary>>| {
+        v.map(|mut v| {
+            v.drain(..v.len().saturating_sub(n));
+            v
+        })
+    };
+    let path = path.as_ref();
+    let lines = (n as u64).saturating_add(1);
+    if crate::compress::is_compressed(path)? {
+        // Not seekable: count the lines in one pass, then read the tail.
+        let total = count_lines(crate::compress::open(path)?)
+            .with_context(|| format!("scan {}", path.display()))?;
+        let start =
+            usize::try_from(total.saturating_sub(lines)).context("line count overflows usize")?;
+        return keep_last(stream_block_summaries_jsonl_from(path, start)?.collect());
+    }
+    if let Some(index) = read_jsonl_index(path)? {
+        let start = index.lines.saturating_sub(lines);
+        let start = usize::try_from(start).context("index line count overflows usize")?;
+        return keep_last(stream_block_summaries_jsonl_from(path, start)?.collect());
+    }
+
+    let limits = read_limits()?;
+    let mut f = File::open(path).with_context(|| format!("open {}", path.display()))?;
+    let offset = tail_offset(&mut f, n.saturating_add(1))
+        .with_context(|| format!("scan {}", path.display()))?;
+    f.seek(SeekFrom::Start(offset))
+        .with_context(|| format!("seek {} to {offset}", path.display()))?;
+    let mut it = JsonlBlockIter::starting_at(Box::new(BufReader::new(f)), 0, limits);
+    it.counted = offset == 0;
+    keep_last(it.collect())
+}
+
+/// Number of lines in `rdr`, counting an unterminated last line, without
+/// buffering any line whole.
+fn count_lines(mut rdr: impl BufRead) -> Result<u64> {
+    let (mut lines, mut last) = (0u64, b'\n');
+    loop {
+        let buf = rdr.fill_buf()?;
+        let Some(&end) = buf.last() else {
+            return Ok(lines + u64::from(last != b'\n'));
+        };
+        lines += buf.iter().map(|&b| u64::from(b == b'\n')).sum::<u64>();
+        last = end;
+        let len = buf.len();
+        rdr.consume(len);
+    }
+}
+
+/// Byte offset at which the last `n` lines of `f` start. A final newline
+/// ends the last line rather than starting an empty one.
+fn tail_offset(f: &mut File, n: usize) -> Result<u64> {
+    let mut end = f.seek(SeekFrom::End(0))?;
+    if n == 0 {
+        return Ok(end);
+    }
+    let mut buf = vec![0u8; TAIL_SCAN_BYTES];
+    let mut seen = 0usize;
+    let mut first = true;
+    while end > 0 {
+        let len = usize::try_from(end).map_or(buf.len(), |e| e.min(buf.len()));
+        let lo = end - len as u64;
+        f.seek(SeekFrom::Start(lo))?;
+        f.read_exact(&mut buf[..len])?;
+        let mut chunk = &buf[..len];
+        if first {
+            first = false;
+            chunk = chunk.strip_suffix(b"\n").unwrap_or(chunk);
+        }
+        for (i, _) in chunk.iter().enumerate().rev().filter(|(_, &c)| c == b'\n') {
+            seen += 1;
+            if seen == n {
+                return Ok(lo + i as u64 + 1);
+            }
+        }
+        end = lo;
+    }
+    Ok(0)
 }
 
 #[cfg(test)]
@@ -149,6 +885,299 @@ mod tests {
         let _ = std::fs::remove_file(p);
     }
 
+    fn blk(id: u32) -> BlockSummary {
+        BlockSummary {
+            version: 1,
+            block_id: id,
+            step_lo: u64::from(id),
+            step_hi: u64::from(id),
+            ctrl_in: 0,
+            ctrl_out: 0,
+            in_head_in: 0,
+            in_head_out: 0,
+            windows: vec![],
+            head_in_offsets: vec![],
+            head_out_offsets: vec![],
+            movement_log: crate::MovementLog::default(),
+            pre_tags: vec![],
+            post_tags: vec![],
+            write_commitments: Vec::new(),
+            aux_commitments: Vec::new(),
+        }
+    }
+
+    fn temp_jsonl(tag: &str, n: u32) -> PathBuf {
+        let mut p = std::env::temp_dir();
+        p.push(format!("sezkp_core_{tag}_{}.jsonl", rand_suffix()));
+        let blocks: Vec<_> = (1..=n).map(blk).collect();
+        write_block_summaries_jsonl(&p, &blocks).unwrap();
+        p
+    }
+
+    fn cleanup(p: &Path) {
+        let _ = std::fs::remove_file(jsonl_index_path(p));
+        let _ = std::fs::remove_file(p);
+    }
+
+    #[test]
+    fn index_seeks_match_scan() {
+        let p = temp_jsonl("idx", 23);
+        let index = write_jsonl_index_for(&p, 4).unwrap();
+        assert_eq!(index.lines, 24); // 23 blocks and the end-of-stream marker
+        assert_eq!(index.offsets.len(), 6);
+        assert_eq!(read_jsonl_index(&p).unwrap(), Some(index));
+
+        for n in [0usize, 1, 3, 4, 5, 21, 22] {
+            assert_eq!(read_block_jsonl(&p, n).unwrap().block_id, n as u32 + 1);
+        }
+        let ids: Vec<u32> = read_block_range_jsonl(&p, 6..11)
+            .unwrap()
+            .iter()
+            .map(|b| b.block_id)
+            .collect();
+        assert_eq!(ids, [7, 8, 9, 10, 11]);
+        assert!(read_block_jsonl(&p, 23).is_err());
+        assert!(read_block_range_jsonl(&p, 20..24).is_err());
+        cleanup(&p);
+    }
+
+    #[test]
+    fn stale_index_is_ignored() {
+        let p = temp_jsonl("stale", 10);
+        write_jsonl_index_for(&p, 3).unwrap();
+
+        // Rewrite with different line lengths: the old offsets would be wrong.
+        let blocks: Vec<_> = (1000..1010).map(blk).collect();
+        write_block_summaries_jsonl(&p, &blocks).unwrap();
+        assert_eq!(read_jsonl_index(&p).unwrap(), None);
+        assert_eq!(read_block_jsonl(&p, 7).unwrap().block_id, 1007);
+        cleanup(&p);
+    }
+
+    #[test]
+    fn builder_matches_scan() {
+        let p = temp_jsonl("builder", 9);
+        let mut b = JsonlIndexBuilder::new(2);
+        for line in std::fs::read(&p).unwrap().split_inclusive(|&c| c == b'\n') {
+            b.push_line(line.len() as u64);
+        }
+        assert_eq!(b.finish(), build_jsonl_index(&p, 2).unwrap());
+        cleanup(&p);
+    }
+
+    #[test]
+    fn head_and_tail_read_the_edges() {
+        fn ids(v: Vec<BlockSummary>) -> Vec<u32> {
+            v.iter().map(|b| b.block_id).collect()
+        }
+
+        let p = temp_jsonl("edges", 9);
+        assert_eq!(ids(head_blocks(&p, 3).unwrap()), [1, 2, 3]);
+        assert_eq!(ids(head_blocks(&p, 20).unwrap()).len(), 9);
+        assert!(head_blocks(&p, 0).unwrap().is_empty());
+
+        // Scan back without an index, then seek with one.
+        for with_index in [false, true] {
+            if with_index {
+                write_jsonl_index_for(&p, 4).unwrap();
+            }
+            assert_eq!(ids(tail_blocks(&p, 3).unwrap()), [7, 8, 9]);
+            assert_eq!(ids(tail_blocks(&p, 9).unwrap()).len(), 9);
+            assert_eq!(ids(tail_blocks(&p, 20).unwrap()).len(), 9);
+            assert!(tail_blocks(&p, 0).unwrap().is_empty());
+        }
+        cleanup(&p);
+
+        // No final newline, and a file longer than one scan step.
+        let p = temp_jsonl("edges_long", 2000);
+        let mut bytes = std::fs::read(&p).unwrap();
+        bytes.pop();
+        std::fs::write(&p, &bytes).unwrap();
+        assert!(bytes.len() > TAIL_SCAN_BYTES);
+        assert_eq!(ids(tail_blocks(&p, 2).unwrap()), [1999, 2000]);
+        let all = tail_blocks(&p, 2000).unwrap();
+        assert_eq!(all.first().map(|b| b.block_id), Some(1));
+        cleanup(&p);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_reader_matches_sequential() {
+        fn summarize(it: impl Iterator<Item = Result<BlockSummary>>) -> Vec<Result<u32, String>> {
+            it.map(|r| r.map(|b| b.block_id).map_err(|e| format!("{e:#}")))
+                .collect()
+        }
+
+        let p = temp_jsonl("par", 40);
+        let mut bytes = std::fs::read(&p).unwrap();
+        bytes.truncate(bytes.len() - jsonl_eof_line(40).len());
+        // A blank line, a malformed line, a CRLF line and no final newline.
+        bytes.extend_from_slice(b"\n{oops}\n");
+        let mut crlf = serde_json::to_vec(&blk(41)).unwrap();
+        crlf.extend_from_slice(b"\r\n");
+        bytes.extend_from_slice(&crlf);
+        bytes.extend_from_slice(&serde_json::to_vec(&blk(42)).unwrap());
+        std::fs::write(&p, &bytes).unwrap();
+
+        let want = summarize(stream_block_summaries_jsonl(&p).unwrap());
+        assert_eq!(want.len(), 44);
+        assert!(want[40]
+            .as_ref()
+            .unwrap_err()
+            .contains("line 41: empty line"));
+        assert!(want[41].as_ref().unwrap_err().contains("line 42"));
+        assert_eq!(want[43], Ok(42));
+
+        // Chunks smaller than a line, around a line, and larger than the file.
+        for chunk in [1, 7, 150, 1 << 20] {
+            let it = stream_block_summaries_jsonl_parallel(&p)
+                .unwrap()
+                .with_chunk_bytes(chunk);
+            assert_eq!(summarize(it), want, "chunk {chunk}");
+        }
+        cleanup(&p);
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn compressed_jsonl_reads_like_plain() {
+        fn ids(v: Vec<BlockSummary>) -> Vec<u32> {
+            v.iter().map(|b| b.block_id).collect()
+        }
+
+        let mut p = std::env::temp_dir();
+        p.push(format!("sezkp_core_zst_{}.jsonl.zst", rand_suffix()));
+        let blocks: Vec<_> = (1..=9).map(blk).collect();
+        write_block_summaries_jsonl(&p, &blocks).unwrap();
+        assert!(crate::compress::is_compressed(&p).unwrap());
+
+        let all: Vec<_> = stream_block_summaries_jsonl_auto(&p)
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(all, blocks);
+        assert_eq!(read_block_jsonl(&p, 4).unwrap().block_id, 5);
+        assert_eq!(ids(read_block_range_jsonl(&p, 2..4).unwrap()), [3, 4]);
+        assert_eq!(ids(head_blocks(&p, 2).unwrap()), [1, 2]);
+        assert_eq!(ids(tail_blocks(&p, 3).unwrap()), [7, 8, 9]);
+        assert_eq!(tail_blocks(&p, 20).unwrap().len(), 9);
+        let err = build_jsonl_index(&p, 4).unwrap_err();
+        assert!(err.to_string().contains("not seekable"), "{err}");
+        cleanup(&p);
+    }
+
+    #[test]
+    fn oversized_lines_end_the_stream() {
+        fn ids(it: impl Iterator<Item = Result<BlockSummary>>) -> Vec<Result<u32, String>> {
+            it.map(|r| r.map(|b| b.block_id).map_err(|e| format!("{e:#}")))
+                .collect()
+        }
+
+        let p = temp_jsonl("limits", 6);
+        let mut big = blk(4);
+        big.movement_log.steps = vec![crate::StepProjection::default(); 40];
+        let mut blocks: Vec<_> = (1..=3).map(blk).collect();
+        blocks.push(big);
+        blocks.extend((5..=6).map(blk));
+        write_block_summaries_jsonl(&p, &blocks).unwrap();
+
+        let line = serde_json::to_vec(&blk(1)).unwrap().len() as u64;
+        let limits = ReadLimits {
+            max_record_bytes: line + 4,
+            ..ReadLimits::default()
+        };
+        let got = ids(stream_block_summaries_jsonl(&p).unwrap().with_limits(limits));
+        assert_eq!(got.len(), 4, "{got:?}");
+        assert_eq!(got[..3], [Ok(1), Ok(2), Ok(3)]);
+        let err = got[3].as_ref().unwrap_err();
+        assert!(err.contains("jsonl line 4: record exceeds"), "{err}");
+        #[cfg(feature = "parallel")]
+        for chunk in [1, 7, 150, 1 << 20] {
+            let it = stream_block_summaries_jsonl_parallel(&p)
+                .unwrap()
+                .with_chunk_bytes(chunk)
+                .with_limits(limits);
+            assert_eq!(ids(it), got, "chunk {chunk}");
+        }
+
+        // A step limit rejects only the long block.
+        let limits = ReadLimits {
+            max_block_steps: 8,
+            ..ReadLimits::default()
+        };
+        let got = ids(stream_block_summaries_jsonl(&p).unwrap().with_limits(limits));
+        assert_eq!(got.len(), 6);
+        let err = got[3].as_ref().unwrap_err();
+        assert!(err.contains("jsonl line 4: block 4 has 40 steps"), "{err}");
+        assert_eq!(got[5], Ok(6));
+        cleanup(&p);
+    }
+
+    #[test]
+    fn eof_marker_catches_truncation() {
+        fn ids(it: impl Iterator<Item = Result<BlockSummary>>) -> Vec<Result<u32, String>> {
+            it.map(|r| r.map(|b| b.block_id).map_err(|e| format!("{e:#}")))
+                .collect()
+        }
+        fn read(p: &Path, require: bool) -> Vec<Result<u32, String>> {
+            let got = ids(stream_block_summaries_jsonl(p)
+                .unwrap()
+                .with_require_eof(require));
+            #[cfg(feature = "parallel")]
+            for chunk in [1, 7, 150, 1 << 20] {
+                let it = stream_block_summaries_jsonl_parallel(p)
+                    .unwrap()
+                    .with_chunk_bytes(chunk)
+                    .with_require_eof(require);
+                assert_eq!(ids(it), got, "chunk {chunk}");
+            }
+            got
+        }
+
+        let p = temp_jsonl("eof", 5);
+        let full = std::fs::read(&p).unwrap();
+        assert!(full.ends_with(jsonl_eof_line(5).as_bytes()));
+        assert_eq!(read(&p, true), [Ok(1), Ok(2), Ok(3), Ok(4), Ok(5)]);
+        assert_eq!(tail_blocks(&p, 2).unwrap().len(), 2);
+        assert!(read_block_jsonl(&p, 5).is_err());
+
+        // A writer killed before the marker: a prefix unless it is required.
+        let cut = full.len() - jsonl_eof_line(5).len();
+        std::fs::write(&p, &full[..cut]).unwrap();
+        assert_eq!(read(&p, false).len(), 5);
+        let got = read(&p, true);
+        assert_eq!(got.len(), 6);
+        let err = got[5].as_ref().unwrap_err();
+        assert!(
+            err.contains("after 5 lines without an end-of-stream marker"),
+            "{err}"
+        );
+
+        // A marker that does not match the blocks before it.
+        let line = full.split_inclusive(|&c| c == b'\n').next().unwrap().len();
+        let mut spliced = full[line..].to_vec();
+        std::fs::write(&p, &spliced).unwrap();
+        let got = read(&p, false);
+        let err = got[4].as_ref().unwrap_err();
+        assert!(
+            err.contains("line 5: end-of-stream marker declares 5 blocks but 4"),
+            "{err}"
+        );
+
+        // Anything after the marker.
+        spliced = full.clone();
+        spliced.extend_from_slice(&full[..line]);
+        std::fs::write(&p, &spliced).unwrap();
+        let got = read(&p, false);
+        assert_eq!(got.len(), 6);
+        let err = got[5].as_ref().unwrap_err();
+        assert!(
+            err.contains("line 6: data after the end-of-stream marker"),
+            "{err}"
+        );
+        cleanup(&p);
+    }
+
     fn rand_suffix() -> u64 {
         use std::time::{SystemTime, UNIX_EPOCH};
         SystemTime::now()
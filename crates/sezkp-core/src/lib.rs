@@ -6,6 +6,27 @@
 //! - JSON/CBOR I/O (with `.jsonl/.ndjson` streaming helpers), and
 //! - the **backend-agnostic** proving façade (batch and streaming).
 //!
+//! The I/O helpers, proof artifacts and the prover façade sit behind the
+//! default `io` feature. With `default-features = false` the crate is the
+//! data types, replay and combiners alone, without `serde_json`, `ciborium`
+//! or file access, for embedding in constrained targets such as zkVM guests.
+//!
+//! # Stability
+//!
+//! Everything compiled without the `unstable` feature is the crate's
+//! semver contract: [`prelude`], the modules and root re-exports below, and
+//! their public items. `tests/public_api.rs` compares that surface with the
+//! committed snapshot `tests/public_api.txt`, so an accidental break fails
+//! the build; intended changes update the snapshot alongside the version.
+//!
+//! The `unstable` feature adds interfaces still in flux, which may change in
+//! any release:
+//! - `prover::ProvingBackendStream` and the `StreamingProver` methods
+//!   that drive it (`prove_stream_iter`, `prove_stream`,
+//!   `prove_stream_late_bound`);
+//! - `composite` (per-shard child proofs under one binding);
+//! - `sample` (downscaled block files).
+//!
 //! ```no_run
 //! use sezkp_core::{StreamingProver, ProvingBackend, BlockSummary};
 //! # struct StarkIOP;
@@ -35,45 +56,113 @@
 )]
 
 /// Proof artifact types (opaque proof bytes, backend kind, manifest root, metadata).
+#[cfg(feature = "io")]
 pub mod artifact;
+/// Per-block auxiliary commitments attached by VM adapters.
+pub mod auxiliary;
 /// Minimal stateless backend trait used by the prover façade.
+#[cfg(feature = "io")]
 pub mod backend;
+/// Bounded block-boundary encodings and seam digests shared by the backends.
+pub mod boundary;
+/// Process-wide cooperative cancellation for long proving runs.
+pub mod cancel;
+/// Deterministic CBOR for structures fed into hashes and transcripts.
+pub mod canonical;
+/// Caller-defined public claims bound into proofs.
+pub mod claims;
 /// Constant-size finite-state combiner used by bottom-up evaluators.
 pub mod combiner;
+/// Transparent zstd compression for `.zst` block and proof files.
+#[cfg(feature = "io")]
+pub mod compress;
+/// Composite artifacts referencing per-shard child proofs under one binding.
+/// **Unstable.**
+#[cfg(all(feature = "io", feature = "unstable"))]
+pub mod composite;
+/// Proof size estimates shared by the backends' `estimate_proof_size`.
+pub mod estimate;
 /// One-shot bottom-up evaluator (replay leaves + combine to root).
 pub mod evaluator;
+/// Streaming left-balanced Merkle frontier and the shared node combiner.
+pub mod frontier;
+/// Feature-gated tracing hooks (`phase_span!` / `phase_event!`) for backends.
+pub mod instrument;
 /// JSON/CBOR helpers and auto-detecting read/write APIs.
+#[cfg(feature = "io")]
 pub mod io;
+/// Format resolution for the auto helpers (extensions, sniffing, strict mode).
+#[cfg(feature = "io")]
+pub mod io_format;
 /// Streaming JSONL/NDJSON helpers for large block sets.
+#[cfg(feature = "io")]
 pub mod io_jsonl;
+/// Small O(1) least-recently-used cache shared by the backends.
+pub mod lru;
+/// Canonical block order: streaming validation and external sort.
+#[cfg(feature = "io")]
+pub mod ordering;
+/// Process-wide thread count and chunking for the rayon code paths.
+pub mod parallelism;
+/// Throttled progress reporting with a process-wide sink.
+pub mod progress;
 /// Prover façade: batch validation + streaming driver.
+#[cfg(feature = "io")]
 pub mod prover;
+/// Read-side limits on block records (size, movement log length, τ).
+#[cfg(feature = "io")]
+pub mod read_limits;
+/// Hex/base64url rendering and strict parsing of 32-byte roots.
+pub mod render;
+/// Redacted block exports: salted per-cell commitments to written symbols.
+pub mod redact;
 /// Algebraic Replay Engine (ARE) and exact replayer wrapper.
 pub mod replay;
+/// Downscaled block files: sampling and re-chaining for quick iteration.
+/// **Unstable.**
+#[cfg(feature = "unstable")]
+pub mod sample;
+/// Authority-signed artifact creation timestamps.
+#[cfg(feature = "io")]
+pub mod timestamp;
 /// Canonical core data types shared across the workspace.
 pub mod types;
 
 // ---- Re-exports for workspace compatibility ----
+#[cfg(feature = "io")]
 pub use artifact::*;
+#[cfg(feature = "io")]
 pub use backend::*;
 pub use combiner::*;
 pub use evaluator::*;
+pub use frontier::EMPTY_ROOT;
+#[cfg(feature = "io")]
 pub use io::*;
+#[cfg(feature = "io")]
 pub use prover::*;
 pub use replay::*;
 pub use types::*;
 
+/// Boundary digests and their decoded inputs, for adapters debugging a
+/// failed interface check.
+pub use boundary::{
+    boundary_left_tail_digest, boundary_right_head_digest, interface_boundary_digest,
+    interface_boundary_digest_with, BoundarySide, DigestMode, IFACE_WINDOW_STEPS,
+};
+
 /// Explicitly re-export the streaming trait so backends can implement it.
-pub use prover::ProvingBackendStream;
+/// **Unstable.**
+#[cfg(all(feature = "io", feature = "unstable"))]
+pub use prover::{ConfiguredBackendStream, ProvingBackendStream};
 
-/// Commonly-used items for quick imports.
+/// Commonly-used items for quick imports: stable items only, so a glob
+/// import never picks up an `unstable` one.
 ///
 /// ```rust
 /// use sezkp_core::prelude::*;
 /// ```
 pub mod prelude {
-    pub use crate::{
-        artifact::ProofArtifact, backend::ProvingBackend, prover::StreamingProver, types::*,
-    };
-    pub use crate::prover::ProvingBackendStream;
+    pub use crate::types::*;
+    #[cfg(feature = "io")]
+    pub use crate::{artifact::ProofArtifact, backend::ProvingBackend, prover::StreamingProver};
 }
@@ -0,0 +1,342 @@
+//! A small least-recently-used cache with O(1) operations.
+//!
+//! Entries live in a slab (`Vec`) linked into a doubly linked recency list
+//! by index, and a hash map finds a key's slot. `get` and `put` are O(1):
+//! no scan of the recency order, unlike a `VecDeque` of keys. Freed slots
+//! are reused, so the slab never grows past the capacity.
+//!
+//! A capacity of zero disables the cache: `put` stores nothing.
+//!
+//! ```
+//! use sezkp_core::lru::Lru;
+//!
+//! let mut lru = Lru::new(2);
+//! lru.put("a", 1);
+//! lru.put("b", 2);
+//! assert_eq!(lru.get(&"a"), Some(&1)); // "b" is now least recent
+//! assert_eq!(lru.put("c", 3), Some(("b", 2)));
+//! assert!(!lru.contains(&"b"));
+//! ```
+
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Slot index meaning "no neighbour".
+const NIL: usize = usize::MAX;
+
+#[derive(Debug)]
+struct Node<K, V> {
+    key: K,
+    value: V,
+    /// Next less recently used slot.
+    older: usize,
+    /// Next more recently used slot.
+    newer: usize,
+}
+
+/// Least-recently-used cache of at most `cap` entries.
+#[derive(Debug)]
+pub struct Lru<K, V> {
+    cap: usize,
+    map: HashMap<K, usize>,
+    /// Slots; `None` once freed (listed in `free`).
+    slots: Vec<Option<Node<K, V>>>,
+    free: Vec<usize>,
+    /// Most recently used slot.
+    newest: usize,
+    /// Least recently used slot (evicted first).
+    oldest: usize,
+}
+
+impl<K: Hash + Eq + Clone, V> Lru<K, V> {
+    /// Empty cache holding at most `cap` entries (0 disables it).
+    #[must_use]
+    pub fn new(cap: usize) -> Self {
+        Self {
+            cap,
+            map: HashMap::new(),
+            slots: Vec::new(),
+            free: Vec::new(),
+            newest: NIL,
+            oldest: NIL,
+        }
+    }
+
+    /// Maximum number of entries.
+    #[must_use]
+    pub const fn cap(&self) -> usize {
+        self.cap
+    }
+
+    /// Number of entries.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Whether the cache holds no entries.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Whether `key` is cached (does not change its recency).
+    #[must_use]
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.contains_key(key)
+    }
+
+    /// The value of `key`, marking it most recently used.
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let i = *self.map.get(key)?;
+        self.unlink(i);
+        self.push_newest(i);
+        self.slots[i].as_ref().map(|n| &n.value)
+    }
+
+    /// The value of `key`, without changing its recency.
+    #[must_use]
+    pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let i = *self.map.get(key)?;
+        self.slots[i].as_ref().map(|n| &n.value)
+    }
+
+    /// Insert or replace `key`, marking it most recently used.
+    ///
+    /// Returns the least recently used entry if it was evicted to make room.
+    /// With capacity zero nothing is stored and `(key, value)` is returned.
+    pub fn put(&mut self, key: K, value: V) -> Option<(K, V)> {
+        if self.cap == 0 {
+            return Some((key, value));
+        }
+        if let Some(&i) = self.map.get(&key) {
+            if let Some(n) = self.slots[i].as_mut() {
+                n.value = value;
+            }
+            self.unlink(i);
+            self.push_newest(i);
+            return None;
+        }
+        let evicted = if self.map.len() == self.cap {
+            self.pop_oldest()
+        } else {
+            None
+        };
+        let node = Node {
+            key: key.clone(),
+            value,
+            older: NIL,
+            newer: NIL,
+        };
+        let i = if let Some(i) = self.free.pop() {
+            self.slots[i] = Some(node);
+            i
+        } else {
+            self.slots.push(Some(node));
+            self.slots.len() - 1
+        };
+        self.map.insert(key, i);
+        self.push_newest(i);
+        evicted
+    }
+
+    /// Remove `key`, returning its value.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let i = self.map.remove(key)?;
+        self.unlink(i);
+        self.release(i).map(|(_, v)| v)
+    }
+
+    /// Remove every entry (the capacity is kept).
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.slots.clear();
+        self.free.clear();
+        self.newest = NIL;
+        self.oldest = NIL;
+    }
+
+    /// Evict the least recently used entry.
+    fn pop_oldest(&mut self) -> Option<(K, V)> {
+        let i = self.oldest;
+        if i == NIL {
+            return None;
+        }
+        self.unlink(i);
+        let (k, v) = self.release(i)?;
+        self.map.remove(&k);
+        Some((k, v))
+    }
+
+    /// Free slot `i` (already unlinked) and return its entry.
+    fn release(&mut self, i: usize) -> Option<(K, V)> {
+        let n = self.slots[i].take()?;
+        self.free.push(i);
+        Some((n.key, n.value))
+    }
+
+    /// Detach slot `i` from the recency list.
+    fn unlink(&mut self, i: usize) {
+        let Some((older, newer)) = self.slots[i].as_ref().map(|n| (n.older, n.newer)) else {
+            return;
+        };
+        if older == NIL {
+            self.oldest = newer;
+        } else {
+            self.set_newer(older, newer);
+        }
+        if newer == NIL {
+            self.newest = older;
+        } else {
+            self.set_older(newer, older);
+        }
+    }
+
+    /// Attach (detached) slot `i` as the most recently used.
+    fn push_newest(&mut self, i: usize) {
+        let prev = self.newest;
+        if let Some(n) = self.slots[i].as_mut() {
+            n.older = prev;
+            n.newer = NIL;
+        }
+        if prev == NIL {
+            self.oldest = i;
+        } else {
+            self.set_newer(prev, i);
+        }
+        self.newest = i;
+    }
+
+    fn set_newer(&mut self, i: usize, to: usize) {
+        if let Some(n) = self.slots[i].as_mut() {
+            n.newer = to;
+        }
+    }
+
+    fn set_older(&mut self, i: usize, to: usize) {
+        if let Some(n) = self.slots[i].as_mut() {
+            n.older = to;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Keys from least to most recently used, following the list.
+    fn order<V>(lru: &Lru<u32, V>) -> Vec<u32> {
+        let mut out = Vec::new();
+        let mut i = lru.oldest;
+        while i != NIL {
+            let n = lru.slots[i].as_ref().unwrap();
+            out.push(n.key);
+            i = n.newer;
+        }
+        out
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let mut lru = Lru::new(3);
+        for k in 0..3 {
+            assert_eq!(lru.put(k, k * 10), None);
+        }
+        assert_eq!(lru.get(&0), Some(&0));
+        assert_eq!(lru.peek(&1), Some(&10)); // peek does not refresh
+        assert_eq!(lru.put(3, 30), Some((1, 10)));
+        assert_eq!(order(&lru), [2, 0, 3]);
+
+        // Replacing refreshes without evicting.
+        assert_eq!(lru.put(2, 21), None);
+        assert_eq!(order(&lru), [0, 3, 2]);
+        assert_eq!(lru.remove(&3), Some(30));
+        assert_eq!(order(&lru), [0, 2]);
+        assert_eq!(lru.len(), 2);
+
+        // The freed slot is reused.
+        lru.put(4, 40);
+        lru.put(5, 50);
+        assert_eq!(lru.slots.len(), 3);
+        assert_eq!(order(&lru), [2, 4, 5]);
+
+        lru.clear();
+        assert!(lru.is_empty());
+        assert_eq!(lru.get(&2), None);
+    }
+
+    #[test]
+    fn zero_capacity_stores_nothing() {
+        let mut lru = Lru::new(0);
+        assert_eq!(lru.put(1, 'a'), Some((1, 'a')));
+        assert!(lru.is_empty());
+        assert_eq!(lru.get(&1), None);
+    }
+
+    #[test]
+    fn matches_a_naive_model() {
+        // Reference: a Vec ordered from least to most recently used.
+        let mut x = 0x2545_f491_4f6c_dd1du64;
+        for cap in [1usize, 2, 5, 16] {
+            let mut lru = Lru::new(cap);
+            let mut model: Vec<(u32, u64)> = Vec::new();
+            for step in 0..2000u64 {
+                x ^= x << 13;
+                x ^= x >> 7;
+                x ^= x << 17;
+                let k = u32::try_from(x % 24).unwrap();
+                match x % 5 {
+                    0 | 1 => {
+                        let got = lru.get(&k).copied();
+                        let want = model.iter().position(|e| e.0 == k).map(|p| {
+                            let e = model.remove(p);
+                            model.push(e);
+                            e.1
+                        });
+                        assert_eq!(got, want, "get {k} at step {step}");
+                    }
+                    2 => {
+                        let got = lru.remove(&k);
+                        let want = model
+                            .iter()
+                            .position(|e| e.0 == k)
+                            .map(|p| model.remove(p).1);
+                        assert_eq!(got, want, "remove {k} at step {step}");
+                    }
+                    _ => {
+                        let got = lru.put(k, step);
+                        let want = match model.iter().position(|e| e.0 == k) {
+                            Some(p) => {
+                                model.remove(p);
+                                None
+                            }
+                            None if model.len() == cap => Some(model.remove(0)),
+                            None => None,
+                        };
+                        model.push((k, step));
+                        assert_eq!(got, want, "put {k} at step {step}");
+                    }
+                }
+                assert_eq!(order(&lru), model.iter().map(|e| e.0).collect::<Vec<_>>());
+                assert!(lru.slots.len() <= cap);
+            }
+        }
+    }
+}
@@ -0,0 +1,339 @@
+//! Canonical block order: streaming validation and an external merge sort.
+//!
+//! A block file is in canonical order when its `block_id`s run consecutively
+//! (`k, k+1, k+2, …` for any starting `k`), which is how `partition_trace` and
+//! the adapters emit them. Interface checks compare each block with its
+//! predecessor *in file order*, so a shuffled or duplicated block otherwise
+//! surfaces as an unrelated-looking boundary mismatch. [`OrderCheck`] catches
+//! that up front with the offending index and ids; [`sort_block_file`] repairs
+//! such files (`sezkp-cli sort-blocks`) without holding them in memory.
+
+use anyhow::{bail, Context, Result};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::compress::FileSink;
+use crate::io::stream_block_summaries_auto;
+use crate::io_jsonl::{stream_block_summaries_jsonl, JsonlBlockIter};
+use crate::BlockSummary;
+
+/// Streaming check that blocks arrive in canonical order.
+///
+/// Feed blocks in file order; the first block that is a duplicate, goes
+/// backwards, or skips ids fails with its index (0-based) and the ids involved.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OrderCheck {
+    /// `(index, block_id)` of the last accepted block.
+    prev: Option<(usize, u32)>,
+    next_idx: usize,
+}
+
+impl OrderCheck {
+    /// A check expecting the first block next.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            prev: None,
+            next_idx: 0,
+        }
+    }
+
+    /// Check the next block in file order.
+    pub fn push(&mut self, b: &BlockSummary) -> Result<()> {
+        let idx = self.next_idx;
+        self.next_idx += 1;
+        let id = b.block_id;
+        if let Some((pidx, pid)) = self.prev {
+            if id == pid {
+                bail!("duplicate block_id {id} at block indices {pidx} and {idx}");
+            }
+            if id < pid {
+                bail!(
+                    "blocks out of order: block index {idx} has block_id {id} \
+                     after block_id {pid} at index {pidx}"
+                );
+            }
+            if id - 1 != pid {
+                bail!(
+                    "missing block_id {}: block index {idx} has block_id {id} \
+                     right after block_id {pid}",
+                    pid + 1
+                );
+            }
+        }
+        self.prev = Some((idx, id));
+        Ok(())
+    }
+}
+
+/// Blocks per sorted run when the caller does not choose (`sort-blocks --run-blocks`).
+pub const DEFAULT_SORT_RUN_BLOCKS: usize = 4096;
+
+/// Outcome of [`sort_block_file`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SortReport {
+    /// Blocks read from the input.
+    pub blocks_in: u64,
+    /// Blocks written to the output.
+    pub blocks_out: u64,
+    /// Sorted runs spilled to disk (`0` or `1` means no merge was needed).
+    pub runs: usize,
+    /// Exact duplicates dropped (same `block_id`, identical contents).
+    pub duplicates_dropped: u64,
+    /// Whether the input was already in ascending `block_id` order.
+    pub was_sorted: bool,
+    /// First `block_id` missing from the output, if the ids are not consecutive.
+    pub first_gap: Option<u32>,
+}
+
+/// Sort a block file by `block_id` into a JSONL `output`, merging sorted runs
+/// of at most `run_blocks` blocks so only one run is ever held in memory.
+///
+/// Runs are spilled next to `output` (`<output>.run<N>.tmp`) and removed
+/// afterwards. Exact duplicates are dropped; two *different* blocks with the
+/// same `block_id` cannot be repaired and fail. Gaps are reported in the
+/// [`SortReport`] rather than rejected, since the sort itself succeeded.
+///
+/// The input may be any block format; the output is always JSONL.
+pub fn sort_block_file(input: &Path, output: &Path, run_blocks: usize) -> Result<SortReport> {
+    let run_blocks = run_blocks.max(1);
+    let mut report = SortReport {
+        was_sorted: true,
+        ..SortReport::default()
+    };
+    let mut runs = RunFiles::new(output);
+    let mut buf: Vec<BlockSummary> = Vec::with_capacity(run_blocks.min(1 << 16));
+    let mut last_id: Option<u32> = None;
+
+    for item in stream_block_summaries_auto(input)? {
+        let b = item?;
+        report.blocks_in += 1;
+        if last_id.is_some_and(|p| b.block_id < p) {
+            report.was_sorted = false;
+        }
+        last_id = Some(b.block_id);
+        buf.push(b);
+        if buf.len() == run_blocks {
+            runs.spill(&mut buf)?;
+        }
+    }
+
+    let mut out = BlockWriter::create(output)?;
+    if runs.paths.is_empty() {
+        // Everything fit in one run: sort in memory, no spill.
+        buf.sort_by_key(|b| b.block_id);
+        report.runs = usize::from(!buf.is_empty());
+        for b in buf {
+            out.push(b, &mut report)?;
+        }
+    } else {
+        if !buf.is_empty() {
+            runs.spill(&mut buf)?;
+        }
+        report.runs = runs.paths.len();
+        merge_runs(&runs.paths, &mut out, &mut report)?;
+    }
+    out.finish(&report)?;
+    Ok(report)
+}
+
+/// K-way merge of sorted runs; ties go to the earlier run, so the merge is stable.
+fn merge_runs(paths: &[PathBuf], out: &mut BlockWriter, report: &mut SortReport) -> Result<()> {
+    let mut iters: Vec<JsonlBlockIter> = paths
+        .iter()
+        .map(stream_block_summaries_jsonl)
+        .collect::<Result<_>>()?;
+    let mut heads: Vec<Option<BlockSummary>> = Vec::with_capacity(iters.len());
+    let mut heap = BinaryHeap::new();
+    for (run, it) in iters.iter_mut().enumerate() {
+        let first = it.next().transpose()?;
+        if let Some(b) = &first {
+            heap.push(Reverse((b.block_id, run)));
+        }
+        heads.push(first);
+    }
+
+    while let Some(Reverse((_, run))) = heap.pop() {
+        let Some(b) = heads[run].take() else {
+            continue;
+        };
+        heads[run] = iters[run].next().transpose()?;
+        if let Some(next) = &heads[run] {
+            heap.push(Reverse((next.block_id, run)));
+        }
+        out.push(b, report)?;
+    }
+    Ok(())
+}
+
+/// Temporary run files, removed on drop.
+struct RunFiles<'a> {
+    output: &'a Path,
+    paths: Vec<PathBuf>,
+}
+
+impl<'a> RunFiles<'a> {
+    const fn new(output: &'a Path) -> Self {
+        Self {
+            output,
+            paths: Vec::new(),
+        }
+    }
+
+    /// Sort `buf` and write it out as the next run.
+    fn spill(&mut self, buf: &mut Vec<BlockSummary>) -> Result<()> {
+        buf.sort_by_key(|b| b.block_id);
+        let mut name = self.output.as_os_str().to_owned();
+        name.push(format!(".run{}.tmp", self.paths.len()));
+        let path = PathBuf::from(name);
+        self.paths.push(path.clone());
+        crate::io_jsonl::write_block_summaries_jsonl(&path, buf)?;
+        buf.clear();
+        Ok(())
+    }
+}
+
+impl Drop for RunFiles<'_> {
+    fn drop(&mut self) {
+        for p in &self.paths {
+            let _ = std::fs::remove_file(p);
+        }
+    }
+}
+
+/// JSONL output that drops exact duplicates and tracks gaps.
+struct BlockWriter {
+    w: FileSink,
+    last: Option<BlockSummary>,
+}
+
+impl BlockWriter {
+    fn create(path: &Path) -> Result<Self> {
+        Ok(Self {
+            w: FileSink::create(path)?,
+            last: None,
+        })
+    }
+
+    /// Write the next block (ascending `block_id`).
+    fn push(&mut self, b: BlockSummary, report: &mut SortReport) -> Result<()> {
+        if let Some(last) = &self.last {
+            if last.block_id == b.block_id {
+                if *last != b {
+                    bail!(
+                        "conflicting duplicates: two different blocks have block_id {}",
+                        b.block_id
+                    );
+                }
+                report.duplicates_dropped += 1;
+                return Ok(());
+            }
+            if report.first_gap.is_none() && b.block_id - 1 != last.block_id {
+                report.first_gap = Some(last.block_id + 1);
+            }
+        }
+        serde_json::to_writer(&mut self.w, &b).context("serialize block to json")?;
+        self.w.write_all(b"\n").context("write newline")?;
+        report.blocks_out += 1;
+        self.last = Some(b);
+        Ok(())
+    }
+
+    fn finish(mut self, report: &SortReport) -> Result<()> {
+        self.w
+            .write_all(crate::io_jsonl::jsonl_eof_line(report.blocks_out).as_bytes())
+            .context("write end-of-stream marker")?;
+        self.w.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::MovementLog;
+
+    fn block(id: u32, ctrl: u16) -> BlockSummary {
+        BlockSummary {
+            version: 1,
+            block_id: id,
+            step_lo: u64::from(id),
+            step_hi: u64::from(id),
+            ctrl_in: ctrl,
+            ctrl_out: ctrl,
+            in_head_in: 0,
+            in_head_out: 0,
+            windows: vec![],
+            head_in_offsets: vec![],
+            head_out_offsets: vec![],
+            movement_log: MovementLog { steps: vec![] },
+            pre_tags: vec![],
+            post_tags: vec![],
+            write_commitments: Vec::new(),
+            aux_commitments: Vec::new(),
+        }
+    }
+
+    fn check(ids: &[u32]) -> Result<()> {
+        let mut c = OrderCheck::new();
+        ids.iter().try_for_each(|&id| c.push(&block(id, 0)))
+    }
+
+    #[test]
+    fn order_check_pinpoints_the_first_bad_block() {
+        check(&[]).unwrap();
+        check(&[7, 8, 9]).unwrap();
+
+        let err = check(&[1, 2, 2, 3]).unwrap_err().to_string();
+        assert!(
+            err.contains("duplicate block_id 2 at block indices 1 and 2"),
+            "{err}"
+        );
+        let err = check(&[1, 2, 4, 3]).unwrap_err().to_string();
+        assert!(err.contains("missing block_id 3: block index 2"), "{err}");
+        let err = check(&[2, 1]).unwrap_err().to_string();
+        assert!(
+            err.contains("block index 1 has block_id 1 after block_id 2"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn external_sort_merges_runs_and_drops_exact_duplicates() {
+        let dir = std::env::temp_dir().join(format!("sezkp-sort-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("in.jsonl");
+        let output = dir.join("out.jsonl");
+
+        let ids = [5u32, 3, 9, 1, 3, 2, 8, 4, 7, 6];
+        let blocks: Vec<_> = ids.iter().map(|&id| block(id, 0)).collect();
+        crate::io_jsonl::write_block_summaries_jsonl(&input, &blocks).unwrap();
+
+        for run_blocks in [1, 3, 100] {
+            let rep = sort_block_file(&input, &output, run_blocks).unwrap();
+            assert_eq!(rep.blocks_in, 10);
+            assert_eq!(rep.blocks_out, 9);
+            assert_eq!(rep.duplicates_dropped, 1);
+            assert!(!rep.was_sorted);
+            assert_eq!(rep.first_gap, None);
+            assert_eq!(rep.runs, 10usize.div_ceil(run_blocks));
+
+            let mut c = OrderCheck::new();
+            for b in stream_block_summaries_jsonl(&output).unwrap() {
+                c.push(&b.unwrap()).unwrap();
+            }
+        }
+        // Spilled runs are cleaned up.
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+
+        // A different block under an existing id cannot be repaired.
+        let mut bad = blocks;
+        bad.push(block(4, 1));
+        crate::io_jsonl::write_block_summaries_jsonl(&input, &bad).unwrap();
+        let err = sort_block_file(&input, &output, 4).unwrap_err().to_string();
+        assert!(err.contains("block_id 4"), "{err}");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
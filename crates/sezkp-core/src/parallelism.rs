@@ -0,0 +1,219 @@
+//! Process-wide thread-pool configuration for the rayon code paths.
+//!
+//! Parallel work in the workspace (JSONL parsing in [`crate::io_jsonl`],
+//! leaf hashing in `sezkp-merkle`) runs through [`install`] and splits its
+//! input with [`ChunkPolicy::min_len`], so one [`ParallelismConfig`] decides
+//! how many threads run it and how finely it is split.
+//!
+//! Every parallel path collects its results in input order, so the thread
+//! count and chunking change only speed: roots, proofs and parse errors are
+//! the same as with one thread.
+//!
+//! Like the [read limits](crate::read_limits), the setting is process-wide:
+//! [`set_parallelism`] (the CLI exposes `--threads`) overrides the
+//! `SEZKP_THREADS` and `SEZKP_PAR_MIN_CHUNK` environment variables, which
+//! override [`ParallelismConfig::default`]. Without the `parallel` feature
+//! nothing runs on a pool and the setting has no effect.
+
+use anyhow::{Context, Result};
+use std::num::NonZeroUsize;
+use std::sync::{Mutex, PoisonError};
+
+/// Environment variable for [`ParallelismConfig::threads`] (`auto` or a
+/// positive count).
+pub const ENV_THREADS: &str = "SEZKP_THREADS";
+/// Environment variable for [`ChunkPolicy::MinItems`] (`auto` or a positive
+/// item count).
+pub const ENV_MIN_CHUNK: &str = "SEZKP_PAR_MIN_CHUNK";
+
+/// How a parallel pass splits its input into tasks.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ChunkPolicy {
+    /// Let rayon split adaptively, down to single items.
+    #[default]
+    Adaptive,
+    /// Never hand a task fewer than this many items.
+    MinItems(NonZeroUsize),
+}
+
+impl ChunkPolicy {
+    /// Smallest number of items per task (for `with_min_len`).
+    #[must_use]
+    pub const fn min_len(self) -> usize {
+        match self {
+            Self::Adaptive => 1,
+            Self::MinItems(n) => n.get(),
+        }
+    }
+}
+
+/// Thread count and chunking for the rayon code paths.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ParallelismConfig {
+    /// Worker threads; `None` uses rayon's global pool (one per core).
+    pub threads: Option<NonZeroUsize>,
+    /// How inputs are split into tasks.
+    pub chunking: ChunkPolicy,
+}
+
+impl ParallelismConfig {
+    /// The default chunking on a pool of exactly `n` threads (`0` means one
+    /// per core).
+    #[must_use]
+    pub const fn with_threads(n: usize) -> Self {
+        Self {
+            threads: NonZeroUsize::new(n),
+            chunking: ChunkPolicy::Adaptive,
+        }
+    }
+
+    /// The configuration from [`ENV_THREADS`] and [`ENV_MIN_CHUNK`], each
+    /// defaulting when unset.
+    ///
+    /// # Errors
+    /// Fails if a variable is neither `auto` nor a positive integer.
+    pub fn from_env() -> Result<Self> {
+        Ok(Self {
+            threads: env_count(ENV_THREADS)?,
+            chunking: env_count(ENV_MIN_CHUNK)?
+                .map_or(ChunkPolicy::Adaptive, ChunkPolicy::MinItems),
+        })
+    }
+}
+
+fn env_count(name: &str) -> Result<Option<NonZeroUsize>> {
+    let Ok(v) = std::env::var(name) else {
+        return Ok(None);
+    };
+    let v = v.trim();
+    if v.eq_ignore_ascii_case("auto") {
+        return Ok(None);
+    }
+    v.parse()
+        .map(Some)
+        .with_context(|| format!("{name}={v:?} is neither `auto` nor a positive count"))
+}
+
+/* ----------------------------- process setting ----------------------------- */
+
+static CONFIG: Mutex<Option<ParallelismConfig>> = Mutex::new(None);
+
+/// Set the parallelism for this process (overrides the environment).
+///
+/// Takes effect for the next [`install`]; work already running keeps its pool.
+pub fn set_parallelism(config: ParallelismConfig) {
+    *CONFIG.lock().unwrap_or_else(PoisonError::into_inner) = Some(config);
+}
+
+/// The parallelism in effect.
+///
+/// Unless [`set_parallelism`] was called, it comes from
+/// [`ParallelismConfig::from_env`].
+///
+/// # Errors
+/// Fails if one of the environment variables does not parse.
+pub fn parallelism() -> Result<ParallelismConfig> {
+    let set = *CONFIG.lock().unwrap_or_else(PoisonError::into_inner);
+    set.map_or_else(ParallelismConfig::from_env, Ok)
+}
+
+/// The chunking in effect. An environment variable that does not parse
+/// counts as unset here; [`parallelism`] reports it.
+#[must_use]
+pub fn chunking() -> ChunkPolicy {
+    parallelism().unwrap_or_default().chunking
+}
+
+/// Run `op` on the configured pool, so the rayon iterators inside it use
+/// [`ParallelismConfig::threads`] workers.
+///
+/// The pool is built on first use and kept until the thread count changes.
+/// Without an explicit count (or if the pool cannot be built), `op` runs on
+/// rayon's global pool. An environment variable that does not parse counts
+/// as unset here; [`parallelism`] reports it.
+#[cfg(feature = "parallel")]
+pub fn install<R, F>(op: F) -> R
+where
+    R: Send,
+    F: FnOnce() -> R + Send,
+{
+    match parallelism().unwrap_or_default().threads.and_then(pool) {
+        Some(pool) => pool.install(op),
+        None => op(),
+    }
+}
+
+/// The cached pool of `n` threads, rebuilt when `n` changes.
+#[cfg(feature = "parallel")]
+fn pool(n: NonZeroUsize) -> Option<std::sync::Arc<rayon::ThreadPool>> {
+    use std::sync::Arc;
+
+    static POOL: Mutex<Option<Arc<rayon::ThreadPool>>> = Mutex::new(None);
+
+    let mut slot = POOL.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(p) = slot.as_ref().filter(|p| p.current_num_threads() == n.get()) {
+        return Some(Arc::clone(p));
+    }
+    let built = rayon::ThreadPoolBuilder::new()
+        .num_threads(n.get())
+        .thread_name(|i| format!("sezkp-par-{i}"))
+        .build()
+        .ok()?;
+    Some(Arc::clone(slot.insert(Arc::new(built))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunk_policy_bounds_task_size() {
+        assert_eq!(ChunkPolicy::Adaptive.min_len(), 1);
+        let n = NonZeroUsize::new(64).unwrap();
+        assert_eq!(ChunkPolicy::MinItems(n).min_len(), 64);
+        assert_eq!(ParallelismConfig::with_threads(0).threads, None);
+        assert_eq!(
+            ParallelismConfig::with_threads(3).threads,
+            NonZeroUsize::new(3)
+        );
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn outputs_do_not_depend_on_threads_or_chunking() {
+        use rayon::prelude::*;
+
+        let input: Vec<u64> = (0..10_000).collect();
+        let run = || {
+            install(|| {
+                let threads = rayon::current_num_threads();
+                let out: Vec<[u8; 32]> = input
+                    .par_iter()
+                    .with_min_len(chunking().min_len())
+                    .map(|x| *blake3::hash(&x.to_le_bytes()).as_bytes())
+                    .collect();
+                (threads, out)
+            })
+        };
+
+        let want: Vec<[u8; 32]> = input
+            .iter()
+            .map(|x| *blake3::hash(&x.to_le_bytes()).as_bytes())
+            .collect();
+        for threads in [1, 2, 3, 8] {
+            for chunking in [
+                ChunkPolicy::Adaptive,
+                ChunkPolicy::MinItems(NonZeroUsize::new(1000).unwrap()),
+            ] {
+                set_parallelism(ParallelismConfig {
+                    threads: NonZeroUsize::new(threads),
+                    chunking,
+                });
+                let (used, out) = run();
+                assert_eq!(used, threads);
+                assert!(out == want, "{threads} threads, {chunking:?}");
+            }
+        }
+        set_parallelism(ParallelismConfig::default());
+    }
+}
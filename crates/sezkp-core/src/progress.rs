@@ -0,0 +1,196 @@
+//! Progress reporting for long-running passes (stream verification, replay).
+//!
+//! Library code counts work through a [`ProgressTracker`]; frontends decide
+//! how to show it by installing a process-wide sink with
+//! [`set_progress_sink`] (the CLI draws a bar on a terminal and logs periodic
+//! lines otherwise). Without a sink, tracking is a counter increment.
+//!
+//! A tracker knows its `total` only when the caller has a hint (e.g. the
+//! block count recorded in an artifact's `meta`); without one, updates still
+//! carry the count and rate but no percentage or ETA.
+
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+/// One progress report.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProgressUpdate {
+    /// Short phase name, e.g. `"fold.verify_stream"`.
+    pub phase: &'static str,
+    /// Units of work done so far.
+    pub done: u64,
+    /// Expected total, if known.
+    pub total: Option<u64>,
+    /// Time since the tracker started.
+    pub elapsed: Duration,
+    /// `true` for the final report of the phase.
+    pub finished: bool,
+}
+
+impl ProgressUpdate {
+    /// Completed fraction in `[0, 1]`, if the total is known.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn fraction(&self) -> Option<f64> {
+        self.total
+            .filter(|&t| t > 0)
+            .map(|t| (self.done as f64 / t as f64).min(1.0))
+    }
+
+    /// Units per second so far (0 before any time has passed).
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn rate(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 {
+            self.done as f64 / secs
+        } else {
+            0.0
+        }
+    }
+
+    /// Estimated time to completion at the current rate, if the total is known.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn eta(&self) -> Option<Duration> {
+        let left = self.total?.saturating_sub(self.done);
+        let rate = self.rate();
+        (rate > 0.0).then(|| Duration::from_secs_f64(left as f64 / rate))
+    }
+}
+
+/// Callback receiving progress reports.
+pub type ProgressFn = dyn Fn(&ProgressUpdate) + Send + Sync;
+
+#[derive(Clone)]
+struct Sink {
+    report: Arc<ProgressFn>,
+    every: Duration,
+}
+
+static SINK: Mutex<Option<Sink>> = Mutex::new(None);
+
+/// Install (or with `None`, remove) the process-wide progress sink.
+///
+/// Trackers created afterwards call `report` at most once per `every`, plus
+/// once when they finish.
+pub fn set_progress_sink(report: Option<Arc<ProgressFn>>, every: Duration) {
+    *SINK.lock().unwrap_or_else(PoisonError::into_inner) =
+        report.map(|report| Sink { report, every });
+}
+
+/// Counts work for one phase and forwards throttled updates to the sink.
+pub struct ProgressTracker {
+    phase: &'static str,
+    total: Option<u64>,
+    done: u64,
+    start: Instant,
+    last: Instant,
+    sink: Option<Sink>,
+}
+
+impl ProgressTracker {
+    /// Start tracking `phase`, expecting `total` units if known.
+    #[must_use]
+    pub fn new(phase: &'static str, total: Option<u64>) -> Self {
+        let now = Instant::now();
+        Self {
+            phase,
+            total,
+            done: 0,
+            start: now,
+            last: now,
+            sink: SINK.lock().unwrap_or_else(PoisonError::into_inner).clone(),
+        }
+    }
+
+    /// Units done so far.
+    #[must_use]
+    pub const fn done(&self) -> u64 {
+        self.done
+    }
+
+    /// Record `n` more units of work.
+    pub fn tick(&mut self, n: u64) {
+        self.done = self.done.saturating_add(n);
+        if let Some(sink) = &self.sink {
+            let now = Instant::now();
+            if now.duration_since(self.last) >= sink.every {
+                self.last = now;
+                (sink.report)(&self.update(false));
+            }
+        }
+    }
+
+    /// Emit the final report for this phase.
+    pub fn finish(self) {
+        if let Some(sink) = &self.sink {
+            (sink.report)(&self.update(true));
+        }
+    }
+
+    fn update(&self, finished: bool) -> ProgressUpdate {
+        ProgressUpdate {
+            phase: self.phase,
+            done: self.done,
+            total: self.total,
+            elapsed: self.start.elapsed(),
+            finished,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_fraction_and_eta() {
+        let u = ProgressUpdate {
+            phase: "t",
+            done: 25,
+            total: Some(100),
+            elapsed: Duration::from_secs(5),
+            finished: false,
+        };
+        assert_eq!(u.fraction(), Some(0.25));
+        assert!((u.rate() - 5.0).abs() < 1e-9);
+        assert_eq!(u.eta(), Some(Duration::from_secs(15)));
+
+        let open = ProgressUpdate { total: None, ..u };
+        assert_eq!(open.fraction(), None);
+        assert_eq!(open.eta(), None);
+        let idle = ProgressUpdate {
+            elapsed: Duration::ZERO,
+            ..u
+        };
+        assert_eq!(idle.eta(), None);
+    }
+
+    #[test]
+    fn tracker_reports_to_sink() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let s = Arc::clone(&seen);
+        set_progress_sink(
+            Some(Arc::new(move |u: &ProgressUpdate| {
+                // Other tests may create trackers while the sink is installed.
+                if u.phase == "test.tracker" {
+                    s.lock().unwrap().push((u.done, u.finished));
+                }
+            })),
+            Duration::ZERO,
+        );
+        let mut t = ProgressTracker::new("test.tracker", Some(3));
+        set_progress_sink(None, Duration::ZERO);
+
+        // The sink is captured at creation, so removal does not affect `t`.
+        for _ in 0..3 {
+            t.tick(1);
+        }
+        t.finish();
+        assert_eq!(
+            *seen.lock().unwrap(),
+            [(1, false), (2, false), (3, false), (3, true)]
+        );
+    }
+}
@@ -2,15 +2,32 @@
 //!
 //! Goals:
 //! - Keep *space* sublinear by never materializing Θ(T) state.
+//! - Reject blocks that are not in canonical `block_id` order (see
+//!   [`crate::ordering`]) before any interface check can misfire on them.
 //! - Validate σ_k locally (bounded-window write safety) and check adjacent
-//!   interface equality (finite-state stitching) **as we stream**.
+//!   interface equality (finite-state stitching) and window continuity
+//!   **as we stream**.
 //! - Remain backend-agnostic for the classic slice API, while exposing a
-//!   push-based streaming API backends can implement for true sublinear usage.
+//!   push-based streaming API backends can implement for true sublinear usage
+//!   ([`ProvingBackendStream`], behind the `unstable` feature while it
+//!   settles).
+//! - Prove with a configured backend value ([`crate::ConfiguredBackend`],
+//!   [`ConfiguredBackendStream`]) as well as a stateless backend type.
+//! - Bind caller-defined public claims ([`StreamingProver::with_claims`])
+//!   and hand them back on verification
+//!   ([`StreamingProver::verify_blocks_claims`]).
+//! - Stop between blocks when a [`crate::cancel`] request arrives, closing
+//!   the backend's partial output first.
 
-use crate::{BlockSummary, FiniteState, ProvingBackend};
+#[cfg(feature = "unstable")]
+use crate::cancel::{self, Cancelled};
+use crate::claims::{check_claims, Claim};
+use crate::{BlockSummary, ConfiguredBackend, FiniteState, ProvingBackend};
 use anyhow::{anyhow, Result};
 use std::marker::PhantomData;
 
+use crate::ordering::OrderCheck;
+use crate::progress::ProgressTracker;
 use crate::replay::{Replay, ReplayConfig};
 
 /// Optional **push-based** interface a backend can implement to support
@@ -18,6 +35,9 @@ use crate::replay::{Replay, ReplayConfig};
 ///
 /// Implementors should assume the caller *already* ran ARE checks and
 /// interface checks before calling [`ProvingBackendStream::ingest_block`].
+///
+/// **Unstable** (feature `unstable`): methods are still being added.
+#[cfg(feature = "unstable")]
 pub trait ProvingBackendStream {
     /// Opaque backend streaming state.
     type StreamState;
@@ -25,19 +45,87 @@ pub trait ProvingBackendStream {
     /// Initialize a streaming session bound to `manifest_root`.
     fn begin_stream(manifest_root: [u8; 32]) -> Result<Self::StreamState>;
 
+    /// [`Self::begin_stream`], also binding the public `claims` (see
+    /// [`ProvingBackend::prove_with_claims`]). The default accepts only an
+    /// empty list.
+    fn begin_stream_with_claims(
+        manifest_root: [u8; 32],
+        claims: &[Claim],
+    ) -> Result<Self::StreamState> {
+        anyhow::ensure!(claims.is_empty(), "this backend cannot bind claims");
+        Self::begin_stream(manifest_root)
+    }
+
+    /// Initialize a session whose manifest root is not known yet, e.g. while
+    /// the blocks are still being produced: the proof binds the root its
+    /// blocks hash to once the stream is finished. The default refuses.
+    fn begin_stream_late_bound(claims: &[Claim]) -> Result<Self::StreamState> {
+        let _ = claims;
+        anyhow::bail!("this backend needs the manifest root before the first block")
+    }
+
     /// Ingest the next block (after the caller has validated it).
     fn ingest_block(state: &mut Self::StreamState, block: BlockSummary) -> Result<()>;
 
     /// Finalize and produce the proof artifact.
     fn finish_stream(state: Self::StreamState) -> Result<crate::ProofArtifact>;
+
+    /// Stop a session early after a cancellation request
+    /// ([`crate::cancel`]), leaving any partial output marked as incomplete.
+    /// The default just drops the state.
+    fn cancel_stream(state: Self::StreamState) -> Result<()> {
+        drop(state);
+        Ok(())
+    }
+}
+
+/// [`ProvingBackendStream`] for a configured backend value (see
+/// [`ConfiguredBackend`]): the same session, with the settings taken from
+/// `self` rather than from the environment.
+///
+/// **Unstable** (feature `unstable`), like [`ProvingBackendStream`].
+#[cfg(feature = "unstable")]
+pub trait ConfiguredBackendStream: ConfiguredBackend {
+    /// Opaque backend streaming state.
+    type StreamState;
+
+    /// [`ProvingBackendStream::begin_stream_with_claims`] under this
+    /// instance's configuration.
+    fn begin_stream_with_claims(
+        &self,
+        manifest_root: [u8; 32],
+        claims: &[Claim],
+    ) -> Result<Self::StreamState>;
+
+    /// [`ProvingBackendStream::begin_stream_late_bound`] under this
+    /// instance's configuration. The default refuses.
+    fn begin_stream_late_bound(&self, claims: &[Claim]) -> Result<Self::StreamState> {
+        let _ = claims;
+        anyhow::bail!("this backend needs the manifest root before the first block")
+    }
+
+    /// Ingest the next block (after the caller has validated it).
+    fn ingest_block(&self, state: &mut Self::StreamState, block: BlockSummary) -> Result<()>;
+
+    /// Finalize and produce the proof artifact.
+    fn finish_stream(&self, state: Self::StreamState) -> Result<crate::ProofArtifact>;
+
+    /// [`ProvingBackendStream::cancel_stream`]. The default just drops the
+    /// state.
+    fn cancel_stream(&self, state: Self::StreamState) -> Result<()> {
+        drop(state);
+        Ok(())
+    }
 }
 
 /// A generic prover that can operate either in batch (slice) mode or in
-/// streaming mode (when the backend implements [`ProvingBackendStream`]).
-#[derive(Debug, Clone, Copy)]
+/// streaming mode (when the backend implements `ProvingBackendStream`,
+/// feature `unstable`).
+#[derive(Debug, Clone)]
 pub struct StreamingProver<B: ProvingBackend> {
     backend: PhantomData<B>,
     replay: Replay,
+    claims: Vec<Claim>,
 }
 
 impl<B: ProvingBackend> Default for StreamingProver<B> {
@@ -45,8 +133,12 @@ impl<B: ProvingBackend> Default for StreamingProver<B> {
         Self {
             backend: PhantomData,
             replay: Replay {
-                cfg: ReplayConfig { check_writes: true },
+                cfg: ReplayConfig {
+                    check_writes: true,
+                    ..ReplayConfig::default()
+                },
             },
+            claims: Vec::new(),
         }
     }
 }
@@ -58,9 +150,42 @@ impl<B: ProvingBackend> StreamingProver<B> {
         Self {
             backend: PhantomData,
             replay: Replay { cfg },
+            claims: Vec::new(),
         }
     }
 
+    /// Declare the input tape length replay enforces (see
+    /// [`ReplayConfig::input_len`]); `None` leaves the input head unchecked.
+    #[must_use]
+    pub const fn with_input_len(mut self, input_len: Option<u64>) -> Self {
+        self.replay.cfg.input_len = input_len;
+        self
+    }
+
+    /// Skip the window-continuity check between adjacent blocks (see
+    /// [`ReplayConfig::allow_rewindow`]).
+    #[must_use]
+    pub const fn with_rewindow(mut self, allow: bool) -> Self {
+        self.replay.cfg.allow_rewindow = allow;
+        self
+    }
+
+    /// Reject blocks whose declared exit heads differ from the replayed
+    /// ones (see [`ReplayConfig::strict_endpoints`]).
+    #[must_use]
+    pub const fn with_strict_endpoints(mut self, strict: bool) -> Self {
+        self.replay.cfg.strict_endpoints = strict;
+        self
+    }
+
+    /// Public claims to bind into proofs made by this prover (see
+    /// [`crate::claims`]); the backend must support them.
+    #[must_use]
+    pub fn with_claims(mut self, claims: Vec<Claim>) -> Self {
+        self.claims = claims;
+        self
+    }
+
     /* ----------------------------- batch (slice) ---------------------------- */
 
     /// Validate per-block invariants + adjacent interfaces, then call the backend's `prove`.
@@ -69,9 +194,36 @@ impl<B: ProvingBackend> StreamingProver<B> {
     /// Returns an error if validation fails or the backend cannot produce a proof.
     #[must_use]
     pub fn prove(blocks: &[BlockSummary], manifest_root: [u8; 32]) -> Result<crate::ProofArtifact> {
-        let sp = Self::default();
-        sp.validate_blocks(blocks)?;
-        B::prove(blocks, manifest_root)
+        Self::default().prove_blocks(blocks, manifest_root)
+    }
+
+    /// [`Self::prove`] with this prover's replay configuration.
+    ///
+    /// # Errors
+    /// Returns an error if validation fails or the backend cannot produce a proof.
+    pub fn prove_blocks(
+        &self,
+        blocks: &[BlockSummary],
+        manifest_root: [u8; 32],
+    ) -> Result<crate::ProofArtifact> {
+        check_claims(&self.claims)?;
+        self.validate_blocks(blocks)?;
+        B::prove_with_claims(blocks, manifest_root, &self.claims)
+    }
+
+    /// [`Self::prove_blocks`] with a configured `backend` in place of `B`.
+    ///
+    /// # Errors
+    /// Returns an error if validation fails or the backend cannot produce a proof.
+    pub fn prove_blocks_with<C: ConfiguredBackend>(
+        &self,
+        backend: &C,
+        blocks: &[BlockSummary],
+        manifest_root: [u8; 32],
+    ) -> Result<crate::ProofArtifact> {
+        check_claims(&self.claims)?;
+        self.validate_blocks(blocks)?;
+        backend.prove_with_claims(blocks, manifest_root, &self.claims)
     }
 
     /// Validate + delegate to backend verification.
@@ -84,15 +236,43 @@ impl<B: ProvingBackend> StreamingProver<B> {
         blocks: &[BlockSummary],
         manifest_root: [u8; 32],
     ) -> Result<()> {
-        let sp = Self::default();
-        sp.validate_blocks(blocks)?;
+        Self::default().verify_blocks(artifact, blocks, manifest_root)
+    }
+
+    /// [`Self::verify`] with this prover's replay configuration.
+    ///
+    /// # Errors
+    /// Returns an error if validation fails or the proof is invalid for the given inputs.
+    pub fn verify_blocks(
+        &self,
+        artifact: &crate::ProofArtifact,
+        blocks: &[BlockSummary],
+        manifest_root: [u8; 32],
+    ) -> Result<()> {
+        self.validate_blocks(blocks)?;
         B::verify(artifact, blocks, manifest_root)
     }
 
+    /// [`Self::verify_blocks`], returning the claims the proof binds (empty
+    /// if it binds none).
+    ///
+    /// # Errors
+    /// Returns an error if validation fails or the proof is invalid for the given inputs.
+    pub fn verify_blocks_claims<'a>(
+        &self,
+        artifact: &'a crate::ProofArtifact,
+        blocks: &[BlockSummary],
+        manifest_root: [u8; 32],
+    ) -> Result<&'a [Claim]> {
+        self.verify_blocks(artifact, blocks, manifest_root)?;
+        Ok(&artifact.claims)
+    }
+
     /* ----------------------------- streaming -------------------------------- */
 
     /// **True streaming** prover:
     /// - Accepts an iterator of `Result<BlockSummary>`.
+    /// - Rejects the first block out of canonical `block_id` order.
     /// - Validates each block with ARE on the fly.
     /// - Pushes blocks into a backend streaming state.
     ///
@@ -100,6 +280,7 @@ impl<B: ProvingBackend> StreamingProver<B> {
     ///
     /// # Errors
     /// Returns an error if validation fails or the backend cannot produce a proof.
+    #[cfg(feature = "unstable")]
     #[must_use]
     pub fn prove_stream_iter<I>(
         iter: I,
@@ -109,14 +290,141 @@ impl<B: ProvingBackend> StreamingProver<B> {
         B: ProvingBackendStream,
         I: IntoIterator<Item = Result<BlockSummary>>,
     {
-        let mut state = <B as ProvingBackendStream>::begin_stream(manifest_root)?;
-        let sp = Self::default();
+        Self::default().prove_stream(iter, manifest_root)
+    }
+
+    /// [`Self::prove_stream_iter`] with this prover's replay configuration.
+    ///
+    /// # Errors
+    /// Returns an error if validation fails or the backend cannot produce a proof.
+    #[cfg(feature = "unstable")]
+    pub fn prove_stream<I>(&self, iter: I, manifest_root: [u8; 32]) -> Result<crate::ProofArtifact>
+    where
+        B: ProvingBackendStream,
+        I: IntoIterator<Item = Result<BlockSummary>>,
+    {
+        check_claims(&self.claims)?;
+        let state =
+            <B as ProvingBackendStream>::begin_stream_with_claims(manifest_root, &self.claims)?;
+        let state = self.drive_stream(
+            state,
+            iter,
+            <B as ProvingBackendStream>::ingest_block,
+            <B as ProvingBackendStream>::cancel_stream,
+        )?;
+        <B as ProvingBackendStream>::finish_stream(state)
+    }
+
+    /// [`Self::prove_stream`] with a configured `backend` in place of `B`.
+    ///
+    /// # Errors
+    /// Returns an error if validation fails or the backend cannot produce a proof.
+    #[cfg(feature = "unstable")]
+    pub fn prove_stream_with<C, I>(
+        &self,
+        backend: &C,
+        iter: I,
+        manifest_root: [u8; 32],
+    ) -> Result<crate::ProofArtifact>
+    where
+        C: ConfiguredBackendStream,
+        I: IntoIterator<Item = Result<BlockSummary>>,
+    {
+        check_claims(&self.claims)?;
+        let state = backend.begin_stream_with_claims(manifest_root, &self.claims)?;
+        let state = self.drive_stream(
+            state,
+            iter,
+            |st, b| backend.ingest_block(st, b),
+            |st| backend.cancel_stream(st),
+        )?;
+        backend.finish_stream(state)
+    }
+
+    /// [`Self::prove_stream`] before the manifest root is known (see
+    /// [`ProvingBackendStream::begin_stream_late_bound`]); the artifact's
+    /// `manifest_root` is the root the streamed blocks hash to.
+    ///
+    /// # Errors
+    /// Returns an error if validation fails, or if the backend cannot bind
+    /// the root late or produce a proof.
+    #[cfg(feature = "unstable")]
+    pub fn prove_stream_late_bound<I>(&self, iter: I) -> Result<crate::ProofArtifact>
+    where
+        B: ProvingBackendStream,
+        I: IntoIterator<Item = Result<BlockSummary>>,
+    {
+        check_claims(&self.claims)?;
+        let state = <B as ProvingBackendStream>::begin_stream_late_bound(&self.claims)?;
+        let state = self.drive_stream(
+            state,
+            iter,
+            <B as ProvingBackendStream>::ingest_block,
+            <B as ProvingBackendStream>::cancel_stream,
+        )?;
+        <B as ProvingBackendStream>::finish_stream(state)
+    }
+
+    /// [`Self::prove_stream_late_bound`] with a configured `backend` in place
+    /// of `B`.
+    ///
+    /// # Errors
+    /// Returns an error if validation fails, or if the backend cannot bind
+    /// the root late or produce a proof.
+    #[cfg(feature = "unstable")]
+    pub fn prove_stream_late_bound_with<C, I>(
+        &self,
+        backend: &C,
+        iter: I,
+    ) -> Result<crate::ProofArtifact>
+    where
+        C: ConfiguredBackendStream,
+        I: IntoIterator<Item = Result<BlockSummary>>,
+    {
+        check_claims(&self.claims)?;
+        let state = backend.begin_stream_late_bound(&self.claims)?;
+        let state = self.drive_stream(
+            state,
+            iter,
+            |st, b| backend.ingest_block(st, b),
+            |st| backend.cancel_stream(st),
+        )?;
+        backend.finish_stream(state)
+    }
+
+    /// Validate each block as it arrives and `ingest` it into `state`,
+    /// stopping with [`Cancelled`] (after `cancel`) before the next block
+    /// once cancellation is requested. Returns the state, ready to finish.
+    #[cfg(feature = "unstable")]
+    fn drive_stream<S, I>(
+        &self,
+        mut state: S,
+        iter: I,
+        mut ingest: impl FnMut(&mut S, BlockSummary) -> Result<()>,
+        cancel: impl FnOnce(S) -> Result<()>,
+    ) -> Result<S>
+    where
+        I: IntoIterator<Item = Result<BlockSummary>>,
+    {
+        let sp = self;
 
         // Keep only the previous boundary for interface checks.
         let mut prev: Option<FiniteState> = None;
+        let mut order = OrderCheck::new();
 
         for (idx, item) in iter.into_iter().enumerate() {
+            if cancel::requested() {
+                let stopped = Cancelled {
+                    blocks_done: Some(idx as u64),
+                };
+                return Err(match cancel(state) {
+                    Ok(()) => stopped.into(),
+                    Err(e) => anyhow::Error::new(stopped)
+                        .context(format!("closing the partial proof failed: {e:#}")),
+                });
+            }
             let block = item?;
+            order.push(&block)?;
 
             // 1) Local bounded-window ARE check → returns FiniteState
             let fs = sp.replay.replay_block(&block).map_err(|e| {
@@ -138,22 +446,26 @@ impl<B: ProvingBackend> StreamingProver<B> {
                         block.block_id
                     ));
                 }
+                sp.replay
+                    .check_window_continuity(p, &block)
+                    .map_err(|e| seam_error(idx, &e))?;
             }
             prev = Some(fs);
 
             // 3) Pass the (validated) block to the backend streaming driver.
-            <B as ProvingBackendStream>::ingest_block(&mut state, block)?;
+            ingest(&mut state, block)?;
         }
 
-        // 4) Finalize the proof.
-        <B as ProvingBackendStream>::finish_stream(state)
+        Ok(state)
     }
 
     /// Streaming verify: replay σ_k **without** materializing the vector,
-    /// then call the backend's verifier (which may itself be streaming).
+    /// passing each block on to the backend's verifier as it clears replay
+    /// (see [`ProvingBackend::verify_iter`]).
     ///
-    /// The fold backend verifies against the manifest only, so we pass `&[]`.
-    /// Backends that require blocks in their verifier can still use the batch API.
+    /// The fold backend verifies against the manifest only and just drains
+    /// the blocks; the STARK v1 backend recomputes its column roots from them,
+    /// so both stay in O(chunk) memory.
     ///
     /// # Errors
     /// Returns an error if validation fails or the proof fails to verify.
@@ -166,12 +478,32 @@ impl<B: ProvingBackend> StreamingProver<B> {
     where
         I: IntoIterator<Item = Result<BlockSummary>>,
     {
-        let sp = Self::default();
+        Self::default().verify_stream(artifact, iter, manifest_root)
+    }
+
+    /// [`Self::verify_stream_iter`] with this prover's replay configuration.
+    ///
+    /// # Errors
+    /// Returns an error if validation fails or the proof fails to verify.
+    pub fn verify_stream<I>(
+        &self,
+        artifact: &crate::ProofArtifact,
+        iter: I,
+        manifest_root: [u8; 32],
+    ) -> Result<()>
+    where
+        I: IntoIterator<Item = Result<BlockSummary>>,
+    {
+        let sp = self;
 
         // Validate per-block ARE + interfaces on the fly.
         let mut prev: Option<FiniteState> = None;
-        for (idx, item) in iter.into_iter().enumerate() {
+        let mut order = OrderCheck::new();
+        let mut progress = ProgressTracker::new("replay", None);
+        let checked = iter.into_iter().enumerate().map(|(idx, item)| {
             let block = item?;
+            progress.tick(1);
+            order.push(&block)?;
 
             let fs = sp.replay.replay_block(&block).map_err(|e| {
                 anyhow!(
@@ -191,12 +523,34 @@ impl<B: ProvingBackend> StreamingProver<B> {
                         block.block_id
                     ));
                 }
+                sp.replay
+                    .check_window_continuity(p, &block)
+                    .map_err(|e| seam_error(idx, &e))?;
             }
             prev = Some(fs);
-        }
+            Ok(block)
+        });
+        B::verify_iter(artifact, checked, manifest_root)?;
+        progress.finish();
+        Ok(())
+    }
 
-        // Delegate to backend verification using an empty slice (fold backend does not need blocks).
-        B::verify(artifact, &[], manifest_root)
+    /// [`Self::verify_stream`], returning the claims the proof binds (empty
+    /// if it binds none).
+    ///
+    /// # Errors
+    /// Returns an error if validation fails or the proof fails to verify.
+    pub fn verify_stream_claims<'a, I>(
+        &self,
+        artifact: &'a crate::ProofArtifact,
+        iter: I,
+        manifest_root: [u8; 32],
+    ) -> Result<&'a [Claim]>
+    where
+        I: IntoIterator<Item = Result<BlockSummary>>,
+    {
+        self.verify_stream(artifact, iter, manifest_root)?;
+        Ok(&artifact.claims)
     }
 
     /* ------------------------------ helpers --------------------------------- */
@@ -207,6 +561,11 @@ impl<B: ProvingBackend> StreamingProver<B> {
             return Ok(());
         }
 
+        let mut order = OrderCheck::new();
+        for b in blocks {
+            order.push(b)?;
+        }
+
         // Replay each block, collect FiniteState for interface stitching.
         let mut fstates: Vec<FiniteState> = Vec::with_capacity(blocks.len());
         for (idx, b) in blocks.iter().enumerate() {
@@ -231,17 +590,142 @@ impl<B: ProvingBackend> StreamingProver<B> {
                     i + 1
                 ));
             }
+            self.replay
+                .check_window_continuity(a, &blocks[i + 1])
+                .map_err(|e| seam_error(i + 1, &e))?;
         }
         Ok(())
     }
 }
 
+/// A failed window-continuity check at the seam into block index `idx`.
+fn seam_error(idx: usize, e: &anyhow::Error) -> anyhow::Error {
+    anyhow!(
+        "window discontinuity at boundary {}→{idx}: {e} (VMs that re-window tapes allow this with `ReplayConfig::allow_rewindow`)",
+        idx.saturating_sub(1)
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     // Compile-time checks: generic struct is Send/Sync (PhantomData is).
-    fn _assert_send_sync<B: ProvingBackend>() {
+    fn _assert_send_sync<B: ProvingBackend + Send + Sync>() {
         fn assert_send_sync<T: Send + Sync>() {}
         assert_send_sync::<StreamingProver<B>>();
     }
+
+    struct NullBackend;
+    impl ProvingBackend for NullBackend {
+        fn prove(_: &[BlockSummary], root: [u8; 32]) -> Result<crate::ProofArtifact> {
+            Ok(crate::ProofArtifact::new(
+                crate::BackendKind::Unknown,
+                root,
+                Vec::new(),
+                serde_json::Value::Null,
+            ))
+        }
+        fn verify(_: &crate::ProofArtifact, _: &[BlockSummary], _: [u8; 32]) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn misordered_blocks_fail_before_interface_checks() {
+        let block = |block_id| BlockSummary {
+            version: 1,
+            block_id,
+            step_lo: 1,
+            step_hi: 0,
+            ctrl_in: 0,
+            ctrl_out: 0,
+            in_head_in: 0,
+            in_head_out: 0,
+            windows: vec![],
+            head_in_offsets: vec![],
+            head_out_offsets: vec![],
+            movement_log: crate::MovementLog { steps: vec![] },
+            pre_tags: vec![],
+            post_tags: vec![],
+            write_commitments: Vec::new(),
+            aux_commitments: Vec::new(),
+        };
+        let blocks: Vec<_> = [1, 3, 2].into_iter().map(block).collect();
+        let err = StreamingProver::<NullBackend>::prove(&blocks, [0; 32]).unwrap_err();
+        assert!(err.to_string().contains("missing block_id 2"), "{err}");
+
+        let blocks: Vec<_> = [1, 2, 2].into_iter().map(block).collect();
+        let art = StreamingProver::<NullBackend>::prove(&blocks[..2], [0; 32]).unwrap();
+        let err = StreamingProver::<NullBackend>::verify_stream_iter(
+            &art,
+            blocks.into_iter().map(Ok),
+            [0; 32],
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("duplicate block_id 2"), "{err}");
+    }
+
+    #[test]
+    fn claims_need_a_backend_that_binds_them() {
+        let claims = vec![Claim::new("out", "42")];
+        let err = StreamingProver::<NullBackend>::default()
+            .with_claims(claims)
+            .prove_blocks(&[], [0; 32])
+            .unwrap_err();
+        assert!(err.to_string().contains("cannot bind claims"), "{err}");
+
+        let prover = StreamingProver::<NullBackend>::default();
+        let art = prover.prove_blocks(&[], [0; 32]).unwrap();
+        assert!(prover
+            .verify_blocks_claims(&art, &[], [0; 32])
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn heads_cannot_jump_between_windows() {
+        // One tape, one stationary step, head at the window's left edge.
+        let block = |block_id, left| BlockSummary {
+            version: 1,
+            block_id,
+            step_lo: u64::from(block_id),
+            step_hi: u64::from(block_id),
+            ctrl_in: 0,
+            ctrl_out: 0,
+            in_head_in: 0,
+            in_head_out: 0,
+            windows: vec![crate::Window { left, right: left }],
+            head_in_offsets: vec![0],
+            head_out_offsets: vec![0],
+            movement_log: crate::MovementLog {
+                steps: vec![crate::StepProjection {
+                    input_mv: 0,
+                    tapes: vec![crate::TapeOp { write: None, mv: 0 }],
+                }],
+            },
+            pre_tags: vec![],
+            post_tags: vec![],
+            write_commitments: Vec::new(),
+            aux_commitments: Vec::new(),
+        };
+        let blocks = [block(1, 0), block(2, 0), block(3, 40)];
+        let err = StreamingProver::<NullBackend>::prove(&blocks, [0; 32]).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("window discontinuity at boundary 1→2"),
+            "{err}"
+        );
+        let err = StreamingProver::<NullBackend>::verify_stream_iter(
+            &StreamingProver::<NullBackend>::prove(&blocks[..2], [0; 32]).unwrap(),
+            blocks.iter().cloned().map(Ok),
+            [0; 32],
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("exit head 0"), "{err}");
+
+        StreamingProver::<NullBackend>::default()
+            .with_rewindow(true)
+            .prove_blocks(&blocks, [0; 32])
+            .unwrap();
+    }
 }
@@ -0,0 +1,463 @@
+//! Read-side limits on untrusted block files.
+//!
+//! A blocks file is input, not a trusted artifact: one JSONL line (or one
+//! element of a JSON/CBOR array) could carry a gigabyte movement log and
+//! exhaust memory long before any semantic check runs. The block readers in
+//! [`crate::io`] and [`crate::io_jsonl`] therefore bound every record:
+//!
+//! - **record bytes** — the serialized size of one block (a JSONL line, or
+//!   one array element), enforced *while* reading so an oversized record is
+//!   never buffered whole;
+//! - **steps per block** — the length of the movement log;
+//! - **τ** — the number of work tapes (windows, head offsets, per-step ops).
+//!
+//! The limits are process-wide: [`set_read_limits`] (the CLI exposes
+//! `--max-record-bytes`, `--max-block-steps` and `--max-tau`) overrides the
+//! `SEZKP_MAX_RECORD_BYTES`, `SEZKP_MAX_BLOCK_STEPS` and `SEZKP_MAX_TAU`
+//! environment variables, which override [`ReadLimits::default`].
+
+use anyhow::{bail, ensure, Context, Result};
+use serde::de::{self, DeserializeSeed, SeqAccess, Visitor};
+use std::io::{BufRead, Read};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::BlockSummary;
+
+/// Environment variable for [`ReadLimits::max_record_bytes`].
+pub const ENV_MAX_RECORD_BYTES: &str = "SEZKP_MAX_RECORD_BYTES";
+/// Environment variable for [`ReadLimits::max_block_steps`].
+pub const ENV_MAX_BLOCK_STEPS: &str = "SEZKP_MAX_BLOCK_STEPS";
+/// Environment variable for [`ReadLimits::max_tau`].
+pub const ENV_MAX_TAU: &str = "SEZKP_MAX_TAU";
+
+/// Bounds applied to every block record a reader decodes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReadLimits {
+    /// Largest serialized block record, in bytes.
+    pub max_record_bytes: u64,
+    /// Largest movement log (replay steps) in one block.
+    pub max_block_steps: u64,
+    /// Largest number of work tapes in one block.
+    pub max_tau: u64,
+}
+
+impl Default for ReadLimits {
+    /// 256 MiB per record, 2²² steps per block, τ ≤ 64.
+    fn default() -> Self {
+        Self {
+            max_record_bytes: 256 << 20,
+            max_block_steps: 1 << 22,
+            max_tau: 64,
+        }
+    }
+}
+
+impl ReadLimits {
+    /// Limits that accept everything.
+    #[must_use]
+    pub const fn unlimited() -> Self {
+        Self {
+            max_record_bytes: u64::MAX,
+            max_block_steps: u64::MAX,
+            max_tau: u64::MAX,
+        }
+    }
+
+    /// Check the serialized size of one record.
+    ///
+    /// # Errors
+    /// Fails if `len` exceeds [`Self::max_record_bytes`].
+    pub fn check_record_len(&self, len: u64) -> Result<()> {
+        ensure!(len <= self.max_record_bytes, self.record_too_large());
+        Ok(())
+    }
+
+    fn record_too_large(&self) -> String {
+        format!(
+            "record exceeds the read limit of {} bytes (raise {ENV_MAX_RECORD_BYTES})",
+            self.max_record_bytes
+        )
+    }
+
+    /// Check the step count and τ of a decoded block.
+    ///
+    /// # Errors
+    /// Fails if the movement log is longer than [`Self::max_block_steps`], or
+    /// any per-tape vector (windows, head offsets, a step's tape ops) is longer
+    /// than [`Self::max_tau`].
+    pub fn check_block(&self, b: &BlockSummary) -> Result<()> {
+        let steps = b.movement_log.steps.len() as u64;
+        ensure!(
+            steps <= self.max_block_steps,
+            "block {} has {steps} steps, over the read limit of {} (raise {ENV_MAX_BLOCK_STEPS})",
+            b.block_id,
+            self.max_block_steps
+        );
+        let tau = [
+            b.windows.len(),
+            b.head_in_offsets.len(),
+            b.head_out_offsets.len(),
+        ]
+        .into_iter()
+        .chain(b.movement_log.steps.iter().map(|s| s.tapes.len()))
+        .max()
+        .unwrap_or(0) as u64;
+        ensure!(
+            tau <= self.max_tau,
+            "block {} has {tau} tapes, over the read limit of {} (raise {ENV_MAX_TAU})",
+            b.block_id,
+            self.max_tau
+        );
+        Ok(())
+    }
+}
+
+/* ----------------------------- process setting ----------------------------- */
+
+static LIMITS: Mutex<Option<ReadLimits>> = Mutex::new(None);
+
+/// Set the read limits for this process (overrides the `SEZKP_MAX_*` variables).
+pub fn set_read_limits(limits: ReadLimits) {
+    *LIMITS.lock().unwrap_or_else(PoisonError::into_inner) = Some(limits);
+}
+
+/// The read limits in effect.
+///
+/// Unless [`set_read_limits`] was called, each limit comes from its
+/// environment variable when set, and from [`ReadLimits::default`] otherwise.
+///
+/// # Errors
+/// Fails if one of the environment variables is not an unsigned integer.
+pub fn read_limits() -> Result<ReadLimits> {
+    let set = *LIMITS.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(l) = set {
+        return Ok(l);
+    }
+    let d = ReadLimits::default();
+    Ok(ReadLimits {
+        max_record_bytes: env_limit(ENV_MAX_RECORD_BYTES, d.max_record_bytes)?,
+        max_block_steps: env_limit(ENV_MAX_BLOCK_STEPS, d.max_block_steps)?,
+        max_tau: env_limit(ENV_MAX_TAU, d.max_tau)?,
+    })
+}
+
+fn env_limit(name: &str, default: u64) -> Result<u64> {
+    std::env::var(name).map_or(Ok(default), |v| {
+        v.trim()
+            .parse()
+            .with_context(|| format!("{name}={v:?} is not a byte/step count"))
+    })
+}
+
+/* ------------------------- bounded array decoding -------------------------- */
+
+/// Per-record byte budget shared by a [`BudgetReader`] and its decoder.
+///
+/// Atomic only so that a streaming reader owning it stays `Send`; a budget is
+/// never used from two threads at once.
+#[derive(Debug)]
+struct Budget {
+    left: AtomicU64,
+    tripped: AtomicBool,
+}
+
+impl Budget {
+    fn new(limit: u64) -> Arc<Self> {
+        Arc::new(Self {
+            left: AtomicU64::new(limit),
+            tripped: AtomicBool::new(false),
+        })
+    }
+
+    /// Start the next record with `limit` bytes.
+    fn reset(&self, limit: u64) {
+        self.left.store(limit, Ordering::Relaxed);
+    }
+
+    /// Whether a read ran past the budget.
+    fn tripped(&self) -> bool {
+        self.tripped.load(Ordering::Relaxed)
+    }
+}
+
+/// Reader that fails once more bytes are pulled than the current budget.
+struct BudgetReader<R> {
+    inner: R,
+    budget: Arc<Budget>,
+}
+
+impl<R: Read> Read for BudgetReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let left = self.budget.left.load(Ordering::Relaxed);
+        if left == 0 && !buf.is_empty() {
+            self.budget.tripped.store(true, Ordering::Relaxed);
+            return Err(std::io::Error::other("record exceeds the read limit"));
+        }
+        let want = buf.len().min(usize::try_from(left).unwrap_or(usize::MAX));
+        let n = self.inner.read(&mut buf[..want])?;
+        self.budget.left.store(left - n as u64, Ordering::Relaxed);
+        Ok(n)
+    }
+}
+
+/// Decode a JSON array of blocks under `limits`.
+///
+/// Each element gets a fresh [`ReadLimits::max_record_bytes`] budget and is
+/// checked with [`ReadLimits::check_block`] as soon as it is decoded.
+pub(crate) fn read_json_blocks<R: Read>(rdr: R, limits: &ReadLimits) -> Result<Vec<BlockSummary>> {
+    let budget = Budget::new(limits.max_record_bytes);
+    let mut de = serde_json::Deserializer::from_reader(BudgetReader {
+        inner: rdr,
+        budget: budget.clone(),
+    });
+    let v = BlockSeq { limits, budget }.deserialize(&mut de)?;
+    de.end()?;
+    Ok(v)
+}
+
+/// Sequence visitor behind [`read_json_blocks`].
+struct BlockSeq<'a> {
+    limits: &'a ReadLimits,
+    budget: Arc<Budget>,
+}
+
+impl<'de> DeserializeSeed<'de> for BlockSeq<'_> {
+    type Value = Vec<BlockSummary>;
+
+    fn deserialize<D: de::Deserializer<'de>>(self, d: D) -> Result<Self::Value, D::Error> {
+        d.deserialize_seq(self)
+    }
+}
+
+impl<'de> Visitor<'de> for BlockSeq<'_> {
+    type Value = Vec<BlockSummary>;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("a sequence of block summaries")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut out = Vec::new();
+        loop {
+            self.budget.reset(self.limits.max_record_bytes);
+            let next = seq.next_element::<BlockSummary>();
+            if self.budget.tripped() {
+                let msg = self.limits.record_too_large();
+                return Err(de::Error::custom(format!(
+                    "block record {}: {msg}",
+                    out.len()
+                )));
+            }
+            let Some(b) = next? else {
+                return Ok(out);
+            };
+            self.limits
+                .check_block(&b)
+                .map_err(|e| de::Error::custom(format!("block record {}: {e}", out.len())))?;
+            out.push(b);
+        }
+    }
+}
+
+/// Decode a CBOR array of blocks under `limits` (see [`read_json_blocks`]).
+pub(crate) fn read_cbor_blocks<R: BufRead>(
+    rdr: R,
+    limits: &ReadLimits,
+) -> Result<Vec<BlockSummary>> {
+    CborBlocks::new(rdr, *limits)?.collect()
+}
+
+/// Lazy decoder of a CBOR array of blocks under some limits.
+///
+/// The array header is read up front so that each element can be decoded on
+/// its own when the iterator advances, with a fresh
+/// [`ReadLimits::max_record_bytes`] budget and [`ReadLimits::check_block`];
+/// memory stays bounded by one record, and a declared length is never
+/// trusted for preallocation. The first error ends the iteration.
+pub(crate) struct CborBlocks<R> {
+    rdr: BudgetReader<R>,
+    limits: ReadLimits,
+    /// Declared element count; `None` for an indefinite array.
+    len: Option<u64>,
+    read: u64,
+    done: bool,
+}
+
+impl<R: BufRead> CborBlocks<R> {
+    /// Read the array header of `rdr`.
+    pub(crate) fn new(rdr: R, limits: ReadLimits) -> Result<Self> {
+        let mut rdr = BudgetReader {
+            inner: rdr,
+            budget: Budget::new(limits.max_record_bytes),
+        };
+        let len = cbor_array_len(&mut rdr)?;
+        Ok(Self {
+            rdr,
+            limits,
+            len,
+            read: 0,
+            done: false,
+        })
+    }
+
+    fn next_block(&mut self) -> Result<Option<BlockSummary>> {
+        match self.len {
+            Some(n) if self.read == n => return Ok(None),
+            None if cbor_break(&mut self.rdr.inner)? => return Ok(None),
+            _ => {}
+        }
+        let i = self.read;
+        self.rdr.budget.reset(self.limits.max_record_bytes);
+        let b: BlockSummary = match ciborium::de::from_reader(&mut self.rdr) {
+            Ok(b) => b,
+            Err(_) if self.rdr.budget.tripped() => {
+                bail!("block record {i}: {}", self.limits.record_too_large())
+            }
+            Err(e) => return Err(e).with_context(|| format!("block record {i}")),
+        };
+        self.limits
+            .check_block(&b)
+            .with_context(|| format!("block record {i}"))?;
+        self.read += 1;
+        Ok(Some(b))
+    }
+}
+
+impl<R: BufRead> Iterator for CborBlocks<R> {
+    type Item = Result<BlockSummary>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let next = self.next_block().transpose();
+        self.done = !matches!(next, Some(Ok(_)));
+        next
+    }
+}
+
+/// Read a CBOR array header: `Some(len)`, or `None` for an indefinite array.
+fn cbor_array_len(r: &mut impl Read) -> Result<Option<u64>> {
+    let mut head = [0u8; 1];
+    r.read_exact(&mut head).context("read CBOR array header")?;
+    let (major, info) = (head[0] >> 5, head[0] & 0x1f);
+    ensure!(major == 4, "expected a CBOR array of blocks");
+    let width = match info {
+        0..=23 => return Ok(Some(u64::from(info))),
+        24 => 1,
+        25 => 2,
+        26 => 4,
+        27 => 8,
+        31 => return Ok(None),
+        _ => bail!("malformed CBOR array header"),
+    };
+    let mut be = [0u8; 8];
+    r.read_exact(&mut be[8 - width..])
+        .context("read CBOR array length")?;
+    Ok(Some(u64::from_be_bytes(be)))
+}
+
+/// Consume the `break` byte ending an indefinite array, if it comes next.
+fn cbor_break(r: &mut impl BufRead) -> Result<bool> {
+    let brk = r.fill_buf().context("read CBOR array")?.first() == Some(&0xff);
+    if brk {
+        r.consume(1);
+    }
+    Ok(brk)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MovementLog, StepProjection, TapeOp, Window};
+
+    fn blk(steps: usize, tau: usize) -> BlockSummary {
+        let step = StepProjection {
+            input_mv: 0,
+            tapes: vec![TapeOp::default(); tau],
+        };
+        BlockSummary {
+            version: 1,
+            block_id: 7,
+            step_lo: 1,
+            step_hi: steps as u64,
+            ctrl_in: 0,
+            ctrl_out: 0,
+            in_head_in: 0,
+            in_head_out: 0,
+            windows: vec![Window { left: 0, right: 0 }; tau],
+            head_in_offsets: vec![0; tau],
+            head_out_offsets: vec![0; tau],
+            movement_log: MovementLog {
+                steps: vec![step; steps],
+            },
+            pre_tags: vec![],
+            post_tags: vec![],
+            write_commitments: Vec::new(),
+            aux_commitments: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn blocks_are_checked_for_steps_and_tau() {
+        let l = ReadLimits {
+            max_record_bytes: 1 << 20,
+            max_block_steps: 4,
+            max_tau: 2,
+        };
+        l.check_block(&blk(4, 2)).unwrap();
+        let err = l.check_block(&blk(5, 2)).unwrap_err().to_string();
+        assert!(err.contains("block 7 has 5 steps"), "{err}");
+        let err = l.check_block(&blk(1, 3)).unwrap_err().to_string();
+        assert!(err.contains("block 7 has 3 tapes"), "{err}");
+
+        // τ is caught on a single step's ops too.
+        let mut b = blk(2, 2);
+        b.movement_log.steps[1].tapes.push(TapeOp::default());
+        assert!(l.check_block(&b).is_err());
+    }
+
+    #[test]
+    fn arrays_decode_within_limits() {
+        let blocks = vec![blk(3, 2), blk(1, 1), blk(0, 0)];
+        let json = serde_json::to_vec(&blocks).unwrap();
+        let cbor = crate::io::to_cbor(&blocks).unwrap();
+        let l = ReadLimits::default();
+        assert_eq!(read_json_blocks(&json[..], &l).unwrap(), blocks);
+        assert_eq!(read_cbor_blocks(&cbor[..], &l).unwrap(), blocks);
+
+        // The same elements as an indefinite-length CBOR array.
+        let mut indef = vec![0x9f];
+        for b in &blocks {
+            indef.extend(crate::io::to_cbor(b).unwrap());
+        }
+        indef.push(0xff);
+        assert_eq!(read_cbor_blocks(&indef[..], &l).unwrap(), blocks);
+    }
+
+    #[test]
+    fn arrays_reject_oversized_elements() {
+        let blocks = vec![blk(1, 1), blk(64, 2)];
+        let json = serde_json::to_vec(&blocks).unwrap();
+        let cbor = crate::io::to_cbor(&blocks).unwrap();
+        let small = serde_json::to_vec(&blocks[0]).unwrap().len() as u64;
+        let l = ReadLimits {
+            max_record_bytes: small + 8,
+            ..ReadLimits::default()
+        };
+        let err = format!("{:#}", read_json_blocks(&json[..], &l).unwrap_err());
+        assert!(err.contains("block record 1: record exceeds"), "{err}");
+        let err = format!("{:#}", read_cbor_blocks(&cbor[..], &l).unwrap_err());
+        assert!(err.contains("block record 1: record exceeds"), "{err}");
+
+        let l = ReadLimits {
+            max_block_steps: 8,
+            ..ReadLimits::default()
+        };
+        let err = format!("{:#}", read_cbor_blocks(&cbor[..], &l).unwrap_err());
+        assert!(err.contains("block 7 has 64 steps"), "{err}");
+        let err = format!("{:#}", read_json_blocks(&json[..], &l).unwrap_err());
+        assert!(err.contains("block 7 has 64 steps"), "{err}");
+    }
+}
@@ -0,0 +1,312 @@
+//! Trace-level privacy: block files with their written symbols redacted.
+//!
+//! A redacted block keeps everything the structural checks read — step range,
+//! windows, head offsets, every head move and *which* cells are written — but
+//! replaces each written symbol with [`REDACTED_SYMBOL`] and records a salted
+//! commitment to the real symbol in [`BlockSummary::write_commitments`].
+//! Redacted blocks carry `version =` [`REDACTED_BLOCK_VERSION`], and the
+//! Merkle leaf hash binds their commitments (leaf schema v2 in `sezkp-merkle`).
+//!
+//! Every written cell gets its own salt, derived from a secret 32-byte
+//! redaction key (integers little-endian):
+//!
+//! - `salt = BLAKE3_keyed(key, "sezkp/redact/salt/v1" ‖ block_id: u32 ‖ step: u64 ‖ tape: u32)`
+//! - `commitment = BLAKE3("sezkp/redact/cell/v1" ‖ salt ‖ symbol: u16)`
+//!
+//! where `step` is the global step number. Alphabets are small, so unsalted
+//! commitments could be brute-forced; per-cell salts also let the key holder
+//! open one cell ([`CellOpening`]) without revealing any other.
+//!
+//! Proofs over a redacted file establish movement and window safety of the
+//! published structure; they say nothing about the hidden symbols. The README
+//! ("Redacted exports") lists which checks carry over.
+
+use anyhow::{ensure, Context, Result};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "io")]
+use std::path::Path;
+
+use crate::types::{BlockSummary, SymbolId};
+
+/// `BlockSummary::version` of a block whose written symbols are redacted.
+pub const REDACTED_BLOCK_VERSION: u16 = 2;
+
+/// Placeholder left in place of every redacted write.
+pub const REDACTED_SYMBOL: SymbolId = 0;
+
+/// Salted commitment to one written symbol.
+pub type CellCommitment = [u8; 32];
+
+/// Secret key from which the per-cell salts are derived.
+pub type RedactionKey = [u8; 32];
+
+const DS_SALT: &[u8] = b"sezkp/redact/salt/v1";
+const DS_CELL: &[u8] = b"sezkp/redact/cell/v1";
+
+/// Read a redaction key file (64 hex characters, surrounding whitespace ignored).
+#[cfg(feature = "io")]
+pub fn read_redaction_key(path: &Path) -> Result<RedactionKey> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("reading redaction key {}", path.display()))?;
+    let text = text.trim();
+    ensure!(
+        text.len() == 64 && text.bytes().all(|b| b.is_ascii_hexdigit()),
+        "redaction key {} must be 64 hex characters",
+        path.display()
+    );
+    let mut key = [0u8; 32];
+    for (i, k) in key.iter_mut().enumerate() {
+        *k = u8::from_str_radix(&text[2 * i..2 * i + 2], 16)?;
+    }
+    Ok(key)
+}
+
+/// Salt of the cell written on `tape` at global `step` of block `block_id`.
+#[must_use]
+pub fn cell_salt(key: &RedactionKey, block_id: u32, step: u64, tape: u32) -> [u8; 32] {
+    let mut h = blake3::Hasher::new_keyed(key);
+    h.update(DS_SALT);
+    h.update(&block_id.to_le_bytes());
+    h.update(&step.to_le_bytes());
+    h.update(&tape.to_le_bytes());
+    *h.finalize().as_bytes()
+}
+
+/// Commitment to `symbol` under `salt`.
+#[must_use]
+pub fn cell_commitment(salt: &[u8; 32], symbol: SymbolId) -> CellCommitment {
+    let mut h = blake3::Hasher::new();
+    h.update(DS_CELL);
+    h.update(salt);
+    h.update(&symbol.to_le_bytes());
+    *h.finalize().as_bytes()
+}
+
+/// Written cells of `b` as `(global step, tape, symbol)`, in commitment order.
+fn written_cells(b: &BlockSummary) -> impl Iterator<Item = (u64, u32, SymbolId)> + '_ {
+    (b.step_lo..)
+        .zip(&b.movement_log.steps)
+        .flat_map(|(step, s)| {
+            (0u32..)
+                .zip(&s.tapes)
+                .filter_map(move |(r, op)| op.write.map(|sym| (step, r, sym)))
+        })
+}
+
+/// Redact the written symbols of `b` under `key`.
+///
+/// Fails if `b` is already redacted or already carries commitments.
+pub fn redact_block(b: &BlockSummary, key: &RedactionKey) -> Result<BlockSummary> {
+    ensure!(
+        !b.is_redacted() && b.write_commitments.is_empty(),
+        "block {} is already redacted",
+        b.block_id
+    );
+    let mut commitments = Vec::new();
+    for (step, r, sym) in written_cells(b) {
+        let salt = cell_salt(key, b.block_id, step, r);
+        commitments.push(cell_commitment(&salt, sym));
+    }
+
+    let mut out = b.clone();
+    out.version = REDACTED_BLOCK_VERSION;
+    out.write_commitments = commitments;
+    for op in out
+        .movement_log
+        .steps
+        .iter_mut()
+        .flat_map(|s| s.tapes.iter_mut())
+    {
+        if let Some(sym) = op.write.as_mut() {
+            *sym = REDACTED_SYMBOL;
+        }
+    }
+    Ok(out)
+}
+
+/// Check that `b` is a well-formed redacted block: placeholder writes only,
+/// and exactly one commitment per write.
+pub fn check_redacted(b: &BlockSummary) -> Result<()> {
+    ensure!(
+        b.is_redacted(),
+        "block {}: version {} is not a redacted block",
+        b.block_id,
+        b.version
+    );
+    let mut writes = 0usize;
+    for (step, r, sym) in written_cells(b) {
+        ensure!(
+            sym == REDACTED_SYMBOL,
+            "block {}: step {step} writes an unredacted symbol on tape {r}",
+            b.block_id
+        );
+        writes += 1;
+    }
+    ensure!(
+        writes == b.write_commitments.len(),
+        "block {}: {writes} writes but {} commitments",
+        b.block_id,
+        b.write_commitments.len()
+    );
+    Ok(())
+}
+
+/// Disclosure of one redacted cell: its salt and the symbol written there.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CellOpening {
+    /// Block holding the cell.
+    pub block_id: u32,
+    /// Global step of the write.
+    pub step: u64,
+    /// Work tape index.
+    pub tape: u32,
+    /// Per-cell salt (see [`cell_salt`]).
+    pub salt: [u8; 32],
+    /// The written symbol.
+    pub symbol: SymbolId,
+}
+
+impl CellOpening {
+    /// Open the cell written on `tape` at global `step` of the **original**
+    /// (unredacted) block `b`.
+    ///
+    /// Fails if that step does not write on `tape`.
+    pub fn open(b: &BlockSummary, key: &RedactionKey, step: u64, tape: u32) -> Result<Self> {
+        let (_, _, symbol) = written_cells(b)
+            .find(|&(s, r, _)| (s, r) == (step, tape))
+            .with_context(|| {
+                format!(
+                    "block {}: step {step} does not write on tape {tape}",
+                    b.block_id
+                )
+            })?;
+        Ok(Self {
+            block_id: b.block_id,
+            step,
+            tape,
+            salt: cell_salt(key, b.block_id, step, tape),
+            symbol,
+        })
+    }
+
+    /// Check this opening against the commitment in redacted block `b`.
+    pub fn verify(&self, b: &BlockSummary) -> Result<()> {
+        check_redacted(b)?;
+        ensure!(
+            self.block_id == b.block_id,
+            "opening is for block {}, not block {}",
+            self.block_id,
+            b.block_id
+        );
+        let idx = written_cells(b)
+            .position(|(s, r, _)| (s, r) == (self.step, self.tape))
+            .with_context(|| {
+                format!(
+                    "block {}: step {} does not write on tape {}",
+                    b.block_id, self.step, self.tape
+                )
+            })?;
+        ensure!(
+            cell_commitment(&self.salt, self.symbol) == b.write_commitments[idx],
+            "block {}: opening of step {} tape {} does not match its commitment",
+            b.block_id,
+            self.step,
+            self.tape
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{MovementLog, StepProjection, TapeOp, Window};
+
+    fn block() -> BlockSummary {
+        let step = |a, b| StepProjection {
+            input_mv: 0,
+            tapes: vec![TapeOp::new(a, 1), TapeOp::new(b, 0)],
+        };
+        BlockSummary {
+            version: 1,
+            block_id: 3,
+            step_lo: 9,
+            step_hi: 11,
+            ctrl_in: 0,
+            ctrl_out: 0,
+            in_head_in: 0,
+            in_head_out: 0,
+            windows: vec![Window::new(0, 3), Window::new(0, 0)],
+            head_in_offsets: vec![0, 0],
+            head_out_offsets: vec![3, 0],
+            movement_log: MovementLog {
+                steps: vec![
+                    step(Some(5), None),
+                    step(None, Some(5)),
+                    step(Some(7), Some(1)),
+                ],
+            },
+            pre_tags: vec![],
+            post_tags: vec![],
+            write_commitments: Vec::new(),
+            aux_commitments: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn redaction_hides_symbols_and_keeps_structure() {
+        let key = [4u8; 32];
+        let b = block();
+        let r = redact_block(&b, &key).unwrap();
+        check_redacted(&r).unwrap();
+        assert!(r.is_redacted());
+        assert_eq!(r.write_commitments.len(), 4);
+
+        // Only the symbols changed: same moves, same written cells.
+        for (x, y) in b.movement_log.steps.iter().zip(&r.movement_log.steps) {
+            for (p, q) in x.tapes.iter().zip(&y.tapes) {
+                assert_eq!(p.mv, q.mv);
+                assert_eq!(p.write.is_some(), q.write.is_some());
+            }
+        }
+        // Equal symbols in different cells get unrelated commitments.
+        assert_ne!(r.write_commitments[0], r.write_commitments[1]);
+        assert_ne!(redact_block(&b, &[5u8; 32]).unwrap(), r);
+        assert!(redact_block(&r, &key).is_err());
+
+        let mut bad = r.clone();
+        bad.write_commitments.pop();
+        assert!(check_redacted(&bad).is_err());
+        let mut bad = r.clone();
+        bad.movement_log.steps[0].tapes[0].write = Some(5);
+        assert!(check_redacted(&bad).is_err());
+        assert!(check_redacted(&b).is_err());
+    }
+
+    #[test]
+    fn openings_reveal_one_cell() {
+        let key = [4u8; 32];
+        let b = block();
+        let r = redact_block(&b, &key).unwrap();
+
+        let o = CellOpening::open(&b, &key, 11, 1).unwrap();
+        assert_eq!(o.symbol, 1);
+        o.verify(&r).unwrap();
+
+        // A different symbol, cell or block does not open the commitment.
+        assert!(CellOpening { symbol: 2, ..o }.verify(&r).is_err());
+        assert!(CellOpening { step: 10, ..o }.verify(&r).is_err());
+        assert!(CellOpening { block_id: 4, ..o }.verify(&r).is_err());
+        assert!(CellOpening::open(&b, &key, 9, 1).is_err());
+    }
+
+    #[test]
+    fn plain_blocks_keep_their_wire_form() {
+        let b = block();
+        let json = serde_json::to_string(&b).unwrap();
+        assert!(!json.contains("write_commitments"));
+        let r = redact_block(&b, &[4u8; 32]).unwrap();
+        let back: BlockSummary = serde_json::from_str(&serde_json::to_string(&r).unwrap()).unwrap();
+        assert_eq!(back, r);
+    }
+}
@@ -0,0 +1,348 @@
+//! Rendering and strict parsing of 32-byte roots and digests.
+//!
+//! Roots are printed by several commands and pasted back into others, so the
+//! textual forms are chosen to survive copy/paste between tools:
+//! - `hex`: 64 lowercase hex digits (the historical default);
+//! - `0x`: the same, `0x`-prefixed (what most chain tooling expects);
+//! - `base64url`: 43 characters, RFC 4648 URL-safe alphabet, no padding.
+//!
+//! Any of them may carry a **checksum** suffix, `:` followed by 8 hex digits
+//! (the first 4 bytes of `BLAKE3(root)`), requested as e.g. `0x+check`.
+//!
+//! [`parse_root`] accepts every rendering and is deliberately strict: the
+//! digit count must be exact (no silent truncation or zero-padding), the
+//! base64url form must be canonical, and a checksum, when present, must
+//! match. The process-wide output style is set with [`set_root_format`]
+//! (the CLI exposes `--root-format`) and applied by [`render_root`].
+
+use anyhow::{bail, ensure, Result};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Base text encoding of a rendered root.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RootEncoding {
+    /// Bare lowercase hex.
+    Hex,
+    /// `0x`-prefixed lowercase hex.
+    PrefixedHex,
+    /// Unpadded base64url.
+    Base64Url,
+}
+
+/// How roots are rendered: an encoding plus an optional checksum suffix.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RootFormat {
+    /// Base encoding.
+    pub encoding: RootEncoding,
+    /// Append `:` and 8 checksum hex digits.
+    pub checksum: bool,
+}
+
+impl Default for RootFormat {
+    fn default() -> Self {
+        Self {
+            encoding: RootEncoding::Hex,
+            checksum: false,
+        }
+    }
+}
+
+impl RootFormat {
+    /// Parse a user-supplied format name: `hex`, `0x` or `base64url`
+    /// (alias `b64url`), optionally followed by `+check`.
+    ///
+    /// # Errors
+    /// Fails on any other name.
+    pub fn parse(name: &str) -> Result<Self> {
+        let lower = name.to_ascii_lowercase();
+        let (base, checksum) = lower
+            .strip_suffix("+check")
+            .map_or((lower.as_str(), false), |b| (b, true));
+        let encoding = match base {
+            "hex" => RootEncoding::Hex,
+            "0x" => RootEncoding::PrefixedHex,
+            "base64url" | "b64url" => RootEncoding::Base64Url,
+            _ => bail!(
+                "unknown root format {name:?} (expected hex, 0x or base64url, optionally +check)"
+            ),
+        };
+        Ok(Self { encoding, checksum })
+    }
+
+    /// Render `root` in this format.
+    #[must_use]
+    pub fn render(self, root: &[u8; 32]) -> String {
+        let mut s = match self.encoding {
+            RootEncoding::Hex => hex_lower(root),
+            RootEncoding::PrefixedHex => format!("0x{}", hex_lower(root)),
+            RootEncoding::Base64Url => base64url(root),
+        };
+        if self.checksum {
+            s.push(':');
+            s.push_str(&hex_lower(&checksum(root)));
+        }
+        s
+    }
+}
+
+/* ------------------------------ process format ----------------------------- */
+
+const CHECKSUM_BIT: u8 = 0x80;
+
+static FORMAT: AtomicU8 = AtomicU8::new(0);
+
+/// Set the output format used by [`render_root`] for this process.
+pub fn set_root_format(format: RootFormat) {
+    let enc = match format.encoding {
+        RootEncoding::Hex => 0,
+        RootEncoding::PrefixedHex => 1,
+        RootEncoding::Base64Url => 2,
+    };
+    let bits = if format.checksum {
+        enc | CHECKSUM_BIT
+    } else {
+        enc
+    };
+    FORMAT.store(bits, Ordering::Relaxed);
+}
+
+/// The output format used by [`render_root`] (plain hex unless set).
+#[must_use]
+pub fn root_format() -> RootFormat {
+    let bits = FORMAT.load(Ordering::Relaxed);
+    let encoding = match bits & !CHECKSUM_BIT {
+        1 => RootEncoding::PrefixedHex,
+        2 => RootEncoding::Base64Url,
+        _ => RootEncoding::Hex,
+    };
+    RootFormat {
+        encoding,
+        checksum: bits & CHECKSUM_BIT != 0,
+    }
+}
+
+/// Render `root` in the process-wide [`root_format`].
+#[must_use]
+pub fn render_root(root: &[u8; 32]) -> String {
+    root_format().render(root)
+}
+
+/* --------------------------------- parsing --------------------------------- */
+
+/// Parse any rendering produced by [`RootFormat::render`].
+///
+/// # Errors
+/// Fails unless `s` is exactly 64 hex digits (optionally `0x`-prefixed) or
+/// 43 canonical base64url characters, optionally followed by a matching
+/// `:` + 8-hex-digit checksum. Error messages name the offending part.
+pub fn parse_root(s: &str) -> Result<[u8; 32]> {
+    let (body, check) = match s.split_once(':') {
+        Some((b, c)) => (b, Some(c)),
+        None => (s, None),
+    };
+
+    let root = if let Some(h) = body.strip_prefix("0x").or_else(|| body.strip_prefix("0X")) {
+        parse_hex32(h)?
+    } else if body.len() == 64 {
+        parse_hex32(body)?
+    } else if body.len() == 43 {
+        parse_base64url32(body)?
+    } else {
+        bail!(
+            "root {s:?} has {} characters; expected 64 hex digits (optionally 0x-prefixed) \
+             or 43 base64url characters",
+            body.len()
+        );
+    };
+
+    if let Some(c) = check {
+        ensure!(
+            c.len() == 8 && c.bytes().all(|b| b.is_ascii_hexdigit()),
+            "root checksum {c:?} must be 8 hex digits"
+        );
+        ensure!(
+            c.eq_ignore_ascii_case(&hex_lower(&checksum(&root))),
+            "root checksum mismatch in {s:?} (typo in the root or checksum?)"
+        );
+    }
+    Ok(root)
+}
+
+/// First 4 bytes of `BLAKE3(root)`.
+fn checksum(root: &[u8; 32]) -> [u8; 4] {
+    let h = blake3::hash(root);
+    let mut c = [0u8; 4];
+    c.copy_from_slice(&h.as_bytes()[..4]);
+    c
+}
+
+fn hex_lower(bytes: &[u8]) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    let mut s = String::with_capacity(bytes.len() * 2);
+    for &b in bytes {
+        s.push(char::from(DIGITS[usize::from(b >> 4)]));
+        s.push(char::from(DIGITS[usize::from(b & 0xf)]));
+    }
+    s
+}
+
+fn parse_hex32(h: &str) -> Result<[u8; 32]> {
+    ensure!(
+        h.len() == 64,
+        "root has {} hex digits, expected 64",
+        h.len()
+    );
+    let mut out = [0u8; 32];
+    for (i, pair) in h.as_bytes().chunks_exact(2).enumerate() {
+        let (Some(hi), Some(lo)) = (hex_val(pair[0]), hex_val(pair[1])) else {
+            bail!("root has a non-hex character near position {}", 2 * i);
+        };
+        out[i] = (hi << 4) | lo;
+    }
+    Ok(out)
+}
+
+const fn hex_val(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        b'A'..=b'F' => Some(c - b'A' + 10),
+        _ => None,
+    }
+}
+
+const B64URL: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+fn base64url(bytes: &[u8]) -> String {
+    let mut s = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let mut buf = [0u8; 3];
+        buf[..chunk.len()].copy_from_slice(chunk);
+        let n = (u32::from(buf[0]) << 16) | (u32::from(buf[1]) << 8) | u32::from(buf[2]);
+        for k in 0..=chunk.len() {
+            s.push(char::from(B64URL[((n >> (18 - 6 * k)) & 0x3f) as usize]));
+        }
+    }
+    s
+}
+
+const fn b64url_val(c: u8) -> Option<u8> {
+    match c {
+        b'A'..=b'Z' => Some(c - b'A'),
+        b'a'..=b'z' => Some(c - b'a' + 26),
+        b'0'..=b'9' => Some(c - b'0' + 52),
+        b'-' => Some(62),
+        b'_' => Some(63),
+        _ => None,
+    }
+}
+
+fn parse_base64url32(s: &str) -> Result<[u8; 32]> {
+    // 32 bytes = 10 full groups (40 chars) + 2 bytes in 3 chars.
+    let mut bits = 0u32;
+    let mut nbits = 0u32;
+    let mut out = Vec::with_capacity(32);
+    for (i, c) in s.bytes().enumerate() {
+        let Some(v) = b64url_val(c) else {
+            bail!(
+                "root has a non-base64url character {:?} at position {i}",
+                char::from(c)
+            );
+        };
+        bits = (bits << 6) | u32::from(v);
+        nbits += 6;
+        if nbits >= 8 {
+            nbits -= 8;
+            out.push((bits >> nbits).to_le_bytes()[0]);
+            bits &= (1 << nbits) - 1;
+        }
+    }
+    ensure!(
+        out.len() == 32,
+        "root decodes to {} bytes, expected 32",
+        out.len()
+    );
+    // Canonical form only: the 2 leftover bits of the last character are zero.
+    ensure!(
+        bits == 0,
+        "root is not canonical base64url (non-zero trailing bits)"
+    );
+    let mut root = [0u8; 32];
+    root.copy_from_slice(&out);
+    Ok(root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> [u8; 32] {
+        let mut r = [0u8; 32];
+        for (i, b) in r.iter_mut().enumerate() {
+            *b = (i as u8).wrapping_mul(37).wrapping_add(11);
+        }
+        r
+    }
+
+    #[test]
+    fn every_format_round_trips() {
+        let root = sample();
+        for name in [
+            "hex",
+            "0x",
+            "base64url",
+            "hex+check",
+            "0x+check",
+            "b64url+check",
+        ] {
+            let f = RootFormat::parse(name).unwrap();
+            let s = f.render(&root);
+            assert_eq!(parse_root(&s).unwrap(), root, "{name}: {s}");
+        }
+        let plain: String = root.iter().map(|b| format!("{b:02x}")).collect();
+        assert_eq!(RootFormat::default().render(&root), plain);
+    }
+
+    #[test]
+    fn base64url_matches_rfc4648() {
+        // RFC 4648 §10 vectors, URL-safe alphabet, unpadded.
+        assert_eq!(base64url(b"f"), "Zg");
+        assert_eq!(base64url(b"foob"), "Zm9vYg");
+        assert_eq!(base64url(b"foobar"), "Zm9vYmFy");
+        assert_eq!(base64url(&[0xfb, 0xff]), "-_8");
+        assert_eq!(base64url(&[0u8; 32]).len(), 43);
+    }
+
+    #[test]
+    fn rejects_truncation_typos_and_bad_checksums() {
+        let root = sample();
+        let hex = RootFormat::default().render(&root);
+        assert!(parse_root(&hex[..63]).is_err());
+        assert!(parse_root(&format!("0x{}", &hex[..62])).is_err());
+        assert!(parse_root(&format!("{hex}0")).is_err());
+        assert!(parse_root(&hex.replacen('a', "g", 1)).is_err());
+        assert!(parse_root("").is_err());
+
+        let checked = RootFormat::parse("0x+check").unwrap().render(&root);
+        let mut typo = checked.clone().into_bytes();
+        typo[5] = if typo[5] == b'0' { b'1' } else { b'0' };
+        assert!(parse_root(std::str::from_utf8(&typo).unwrap()).is_err());
+        assert!(parse_root(&checked[..checked.len() - 1]).is_err());
+
+        // Non-canonical base64url (trailing bits set) is rejected.
+        let mut b64 = RootFormat::parse("base64url")
+            .unwrap()
+            .render(&root)
+            .into_bytes();
+        let last = B64URL.iter().position(|&c| c == b64[42]).unwrap();
+        b64[42] = B64URL[last ^ 1];
+        assert!(parse_root(std::str::from_utf8(&b64).unwrap()).is_err());
+    }
+
+    #[test]
+    fn format_names() {
+        assert!(RootFormat::parse("HEX+CHECK").unwrap().checksum);
+        assert!(RootFormat::parse("base58").is_err());
+        assert!(RootFormat::parse("check").is_err());
+    }
+}
@@ -10,16 +10,153 @@
 //! lie within the declared per-tape windows), but we do not reject when replayed head
 //! positions differ from the declared offsets. This matches the tests’ intent to freeze
 //! interfaces early while keeping the ARE safety checks.
+//!
+//! The input head is the exception once an input length is declared
+//! ([`ReplayConfig::input_len`]): the input tape is shared by every block, so
+//! replay then requires each position to lie in `[0, len)` and the movement
+//! log to lead exactly from `in_head_in` to `in_head_out`.
+//!
+//! [`ReplayConfig::check_visits`] tightens the window check from written cells
+//! to every visited cell, so declared windows must cover the full excursion of
+//! each head; [`BlockSummary::repair_windows`] recomputes minimal ones.
+//!
+//! [`Replay::endpoint_deltas`] compares the declared exit heads with the ones
+//! the movement log actually reaches, per tape, as [`EndpointDelta`] records
+//! adapter authors can act on; [`ReplayConfig::strict_endpoints`] rejects any
+//! block that has one.
+//!
+//! Across a seam, [`Replay::check_window_continuity`] requires each tape's
+//! window in the next block to contain the head position the previous block
+//! exits with, so a work head cannot teleport between blocks. VMs that
+//! legitimately re-window a tape (relocation, tape swaps) turn it off with
+//! [`ReplayConfig::allow_rewindow`].
 
 use crate::{BlockSummary, FiniteState};
 use anyhow::{bail, ensure, Context, Result};
+use serde::Serialize;
+use std::fmt;
+
+/// Default per-step head movement bound: the classic `{-1, 0, 1}` model.
+pub const DEFAULT_MAX_MOVE: u8 = 1;
 
 /// Optional knobs for replay; extend as needed.
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy)]
+#[allow(clippy::struct_excessive_bools)] // independent opt-in checks
 pub struct ReplayConfig {
     /// If true, additionally assert writes never occur outside declared windows.
     /// (Currently always enforced; flag kept for future selective checks.)
     pub check_writes: bool,
+    /// Largest head move accepted per step, on the input head and on every
+    /// work tape: moves must lie in `-max_move..=max_move`. VM adapters with
+    /// bounded jumps raise it from [`DEFAULT_MAX_MOVE`].
+    pub max_move: u8,
+    /// Declared input tape length. When set, the input head must stay within
+    /// `[0, len)` and each block's moves must chain `in_head_in` to
+    /// `in_head_out` exactly; `None` leaves the input head unchecked.
+    pub input_len: Option<u64>,
+    /// If true, assert every work-head position the movement log visits
+    /// (entry, after each move, and the declared exit) lies within the
+    /// declared window of its tape, not only the cells written. Adapters can
+    /// fix failing blocks with [`BlockSummary::repair_windows`].
+    pub check_visits: bool,
+    /// If true, skip [`Replay::check_window_continuity`] between adjacent
+    /// blocks, for VMs whose tapes legitimately move to unrelated windows.
+    pub allow_rewindow: bool,
+    /// If true, reject blocks whose declared exit heads (work tapes and
+    /// input) differ from the positions their movement logs reach from the
+    /// declared entry heads (see [`Replay::endpoint_deltas`]).
+    pub strict_endpoints: bool,
+}
+
+impl Default for ReplayConfig {
+    fn default() -> Self {
+        Self {
+            check_writes: false,
+            max_move: DEFAULT_MAX_MOVE,
+            input_len: None,
+            check_visits: false,
+            allow_rewindow: false,
+            strict_endpoints: false,
+        }
+    }
+}
+
+impl ReplayConfig {
+    /// Whether a single-step move `mv` is within [`Self::max_move`].
+    #[must_use]
+    pub const fn move_ok(&self, mv: i8) -> bool {
+        mv.unsigned_abs() <= self.max_move
+    }
+
+    /// Whether input head position `pos` lies on the declared input tape
+    /// (always true when no [`Self::input_len`] is declared).
+    #[must_use]
+    pub fn input_pos_ok(&self, pos: i64) -> bool {
+        self.input_len
+            .map_or(true, |len| u64::try_from(pos).is_ok_and(|p| p < len))
+    }
+}
+
+/// Interface field of a block whose declared and replayed values can differ.
+#[derive(Clone, Copy, Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EndpointField {
+    /// Absolute work-tape head at exit.
+    WorkHeadOut,
+    /// Absolute input head at exit.
+    InHeadOut,
+}
+
+impl fmt::Display for EndpointField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::WorkHeadOut => "work_head_out",
+            Self::InHeadOut => "in_head_out",
+        })
+    }
+}
+
+/// One declared interface endpoint that replay does not reproduce.
+///
+/// Entry endpoints are where replay starts, and the finite control is not
+/// replayable from a movement log, so only exit heads can differ.
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+pub struct EndpointDelta {
+    /// Block holding the endpoint.
+    pub block_id: u32,
+    /// Which endpoint differs.
+    pub field: EndpointField,
+    /// Work tape index (`None` for the input head).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tape: Option<usize>,
+    /// Value declared in the block.
+    pub declared: i64,
+    /// Value the movement log reaches from the declared entry.
+    pub replayed: i64,
+}
+
+impl EndpointDelta {
+    /// Replayed minus declared: how far to move the declared endpoint.
+    #[must_use]
+    pub const fn delta(&self) -> i64 {
+        self.replayed - self.declared
+    }
+}
+
+impl fmt::Display for EndpointDelta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "block {}: {}", self.block_id, self.field)?;
+        if let Some(r) = self.tape {
+            write!(f, " on tape {r}")?;
+        }
+        write!(
+            f,
+            " declared {}, replayed {} ({:+})",
+            self.declared,
+            self.replayed,
+            self.delta()
+        )
+    }
 }
 
 /// Fallible replay engine.
@@ -52,13 +189,51 @@ impl Replay {
         a.ctrl_out == b.ctrl_in && a.in_head_out == b.in_head_in
     }
 
+    /// Window continuity across the seam into `next`, whose predecessor
+    /// replayed to `prev`.
+    ///
+    /// Unless [`ReplayConfig::allow_rewindow`], both blocks must have the
+    /// same tapes, and each tape's window in `next` must contain the absolute
+    /// head position `prev` exits with (`work_head_out`).
+    ///
+    /// # Errors
+    /// Names the first tape whose exit head lies outside the next window.
+    pub fn check_window_continuity(&self, prev: &FiniteState, next: &BlockSummary) -> Result<()> {
+        if self.cfg.allow_rewindow {
+            return Ok(());
+        }
+        continuity(&prev.work_head_out, next)
+    }
+
+    /// [`Self::check_window_continuity`] across the seam between adjacent
+    /// blocks, taking `prev`'s declared exit heads instead of replaying it.
+    ///
+    /// # Errors
+    /// Fails on invalid geometry in `prev` (see
+    /// [`BlockSummary::tape_geometry`]), or where
+    /// [`Self::check_window_continuity`] does.
+    pub fn check_seam(&self, prev: &BlockSummary, next: &BlockSummary) -> Result<()> {
+        if self.cfg.allow_rewindow {
+            return Ok(());
+        }
+        let exit = (0..prev.windows.len())
+            .map(|r| Ok(prev.tape_geometry(r)?.heads().1))
+            .collect::<Result<Vec<_>>>()?;
+        continuity(&exit, next)
+    }
+
     /// Replay a *single* block summary σ_k within its windows.
     ///
     /// We:
     ///   - validate basic structural consistency of `sigma` (vector lengths),
-    ///   - validate declared head offsets are *within* their windows,
-    ///   - reconstruct absolute head locations at entry/exit from `(window.left + offset)`,
+    ///   - validate declared head offsets are *within* their windows and
+    ///     reconstruct absolute entry/exit heads ([`BlockSummary::tape_geometry`]),
     ///   - scan the movement log to ensure *writes* stay inside each window,
+    ///   - optionally check every visited head position lies in its window,
+    ///   - with a declared input length, keep the input head on the tape and
+    ///     check it ends at `in_head_out`,
+    ///   - with [`ReplayConfig::strict_endpoints`], reject any
+    ///     [`Self::endpoint_deltas`],
     ///   - return [`FiniteState`] using the **declared** interface endpoints.
     ///
     /// # Errors
@@ -82,51 +257,30 @@ impl Replay {
             tau
         );
 
-        // ---- Declared entry absolute positions from offsets + window left edge ----
+        // ---- Declared entry/exit absolute positions (authoritative interface data) ----
         let mut work_in = Vec::with_capacity(tau);
+        let mut work_out = Vec::with_capacity(tau);
         for r in 0..tau {
-            let w = sigma.windows[r];
-            ensure!(
-                w.right >= w.left,
-                "block {}: invalid window on tape {}: right < left ({} < {})",
-                sigma.block_id,
-                r,
-                w.right,
-                w.left
-            );
-            let off_in = sigma
-                .head_in_offsets
-                .get(r)
-                .with_context(|| format!("block {}: missing head_in_offsets[{r}]", sigma.block_id))?;
-            let win_len = w.right - w.left;
-            ensure!(
-                *off_in as i64 >= 0 && (*off_in as i64) <= win_len,
-                "block {}: entry offset {} out of window range [0, {}] on tape {}",
-                sigma.block_id,
-                off_in,
-                win_len,
-                r
-            );
-            let base = w.left;
-            work_in.push(base + *off_in as i64);
+            let (h_in, h_out) = sigma.tape_geometry(r)?.heads();
+            work_in.push(h_in);
+            work_out.push(h_out);
         }
 
         // ---- Movement-log-driven write-safety check ----
         // For the write-safety check we simulate per-tape head evolution.
         let mut cur_heads = work_in.clone();
-        let mut _input_head = sigma.in_head_in; // kept for potential future checks
 
         for (sidx, step) in sigma.movement_log.steps.iter().enumerate() {
-            // Minimal sanity for moves (stay in {-1,0,1}); loosen here if needed later.
+            // Moves are bounded per step by the configured movement model.
             let mv = step.input_mv;
             ensure!(
-                (-1..=1).contains(&mv),
-                "block {}: input head move must be in {{-1,0,1}}, got {} at step {}",
+                self.cfg.move_ok(mv),
+                "block {}: input head move must be within ±{}, got {} at step {}",
                 sigma.block_id,
+                self.cfg.max_move,
                 mv,
                 sidx
             );
-            _input_head += mv as i64;
 
             ensure!(
                 step.tapes.len() == tau,
@@ -139,10 +293,11 @@ impl Replay {
 
             for (r, op) in step.tapes.iter().enumerate() {
                 ensure!(
-                    (-1..=1).contains(&op.mv),
-                    "block {}: tape {} head move must be in {{-1,0,1}}, got {} at step {}",
+                    self.cfg.move_ok(op.mv),
+                    "block {}: tape {} head move must be within ±{}, got {} at step {}",
                     sigma.block_id,
                     r,
+                    self.cfg.max_move,
                     op.mv,
                     sidx
                 );
@@ -165,24 +320,12 @@ impl Replay {
             }
         }
 
-        // ---- Declared exit absolute positions (authoritative interface data) ----
-        let mut work_out = Vec::with_capacity(tau);
-        for r in 0..tau {
-            let w = sigma.windows[r];
-            let off_out = sigma.head_out_offsets.get(r).with_context(|| {
-                format!("block {}: missing head_out_offsets[{r}]", sigma.block_id)
-            })?;
-            let win_len = w.right - w.left;
-            ensure!(
-                *off_out as i64 >= 0 && (*off_out as i64) <= win_len,
-                "block {}: exit offset {} out of window range [0, {}] on tape {}",
-                sigma.block_id,
-                off_out,
-                win_len,
-                r
-            );
-            let base = w.left;
-            work_out.push(base + *off_out as i64);
+        self.check_input_head(sigma)?;
+        self.check_visits(sigma)?;
+        if self.cfg.strict_endpoints {
+            if let Some(d) = self.endpoint_deltas(sigma)?.first() {
+                bail!("{d} (strict endpoints)");
+            }
         }
 
         Ok(FiniteState {
@@ -195,6 +338,159 @@ impl Replay {
             ..Default::default()
         })
     }
+
+    /// Endpoints of `sigma` as replayed: the declared entry state, with the
+    /// exit heads the movement log reaches from it. The finite control is
+    /// copied from the block.
+    ///
+    /// # Errors
+    /// Fails if the geometry is malformed, a step has the wrong number of
+    /// tape ops, or a head position overflows.
+    pub fn replayed_state(&self, sigma: &BlockSummary) -> Result<FiniteState> {
+        let id = sigma.block_id;
+        let tau = sigma.windows.len();
+        let work_in = (0..tau)
+            .map(|r| Ok(sigma.tape_geometry(r)?.heads().0))
+            .collect::<Result<Vec<_>>>()?;
+        let mut work_out = work_in.clone();
+        let mut in_out = sigma.in_head_in;
+        for (sidx, step) in sigma.movement_log.steps.iter().enumerate() {
+            ensure!(
+                step.tapes.len() == tau,
+                "block {id}: step {sidx} has {} tape ops, expected {tau}",
+                step.tapes.len()
+            );
+            in_out = in_out
+                .checked_add(i64::from(step.input_mv))
+                .with_context(|| format!("block {id}: input head overflow at step {sidx}"))?;
+            for (h, op) in work_out.iter_mut().zip(&step.tapes) {
+                *h = h
+                    .checked_add(i64::from(op.mv))
+                    .with_context(|| format!("block {id}: head overflow at step {sidx}"))?;
+            }
+        }
+        Ok(FiniteState {
+            ctrl_in: sigma.ctrl_in,
+            ctrl_out: sigma.ctrl_out,
+            in_head_in: sigma.in_head_in,
+            in_head_out: in_out,
+            work_head_in: work_in,
+            work_head_out: work_out,
+            ..Default::default()
+        })
+    }
+
+    /// Declared exit heads of `sigma` that its movement log does not reach,
+    /// work tapes first (in tape order), then the input head. Empty when the
+    /// block's declared endpoints are exactly its replayed ones.
+    ///
+    /// # Errors
+    /// Fails like [`Self::replayed_state`].
+    pub fn endpoint_deltas(&self, sigma: &BlockSummary) -> Result<Vec<EndpointDelta>> {
+        let replayed = self.replayed_state(sigma)?;
+        let mut deltas = Vec::new();
+        for (r, &h) in replayed.work_head_out.iter().enumerate() {
+            let declared = sigma.tape_geometry(r)?.heads().1;
+            if declared != h {
+                deltas.push(EndpointDelta {
+                    block_id: sigma.block_id,
+                    field: EndpointField::WorkHeadOut,
+                    tape: Some(r),
+                    declared,
+                    replayed: h,
+                });
+            }
+        }
+        if sigma.in_head_out != replayed.in_head_out {
+            deltas.push(EndpointDelta {
+                block_id: sigma.block_id,
+                field: EndpointField::InHeadOut,
+                tape: None,
+                declared: sigma.in_head_out,
+                replayed: replayed.in_head_out,
+            });
+        }
+        Ok(deltas)
+    }
+
+    /// With a declared input length, check that the input head of `sigma`
+    /// stays on the tape and its moves lead from `in_head_in` to `in_head_out`.
+    fn check_input_head(&self, sigma: &BlockSummary) -> Result<()> {
+        let Some(len) = self.cfg.input_len else {
+            return Ok(());
+        };
+        let mut pos = sigma.in_head_in;
+        ensure!(
+            self.cfg.input_pos_ok(pos),
+            "block {}: in_head_in {} outside the input tape [0, {len})",
+            sigma.block_id,
+            pos
+        );
+        for (sidx, step) in sigma.movement_log.steps.iter().enumerate() {
+            pos += i64::from(step.input_mv);
+            ensure!(
+                self.cfg.input_pos_ok(pos),
+                "block {}: input head leaves the input tape [0, {len}) at step {}: pos={}",
+                sigma.block_id,
+                sidx,
+                pos
+            );
+        }
+        ensure!(
+            pos == sigma.in_head_out,
+            "block {}: input moves lead from in_head_in {} to {}, but in_head_out is {}",
+            sigma.block_id,
+            sigma.in_head_in,
+            pos,
+            sigma.in_head_out
+        );
+        Ok(())
+    }
+
+    /// With [`ReplayConfig::check_visits`], check that the windows of `sigma`
+    /// cover every head position its movement log visits.
+    fn check_visits(&self, sigma: &BlockSummary) -> Result<()> {
+        if !self.cfg.check_visits {
+            return Ok(());
+        }
+        let visited = sigma.visited_windows()?;
+        for (r, (v, w)) in visited.iter().zip(&sigma.windows).enumerate() {
+            ensure!(
+                w.contains(v.left) && w.contains(v.right),
+                "block {}: tape {} head visits [{},{}] outside window [{},{}]",
+                sigma.block_id,
+                r,
+                v.left,
+                v.right,
+                w.left,
+                w.right
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Each of `next`'s windows contains the exit head of its tape.
+fn continuity(exit_heads: &[i64], next: &BlockSummary) -> Result<()> {
+    ensure!(
+        exit_heads.len() == next.windows.len(),
+        "block {}: {} work tapes after a block with {}",
+        next.block_id,
+        next.windows.len(),
+        exit_heads.len()
+    );
+    for (r, (&h, w)) in exit_heads.iter().zip(&next.windows).enumerate() {
+        ensure!(
+            w.contains(h),
+            "block {}: tape {} window [{},{}] does not contain the previous block's exit head {}",
+            next.block_id,
+            r,
+            w.left,
+            w.right,
+            h
+        );
+    }
+    Ok(())
 }
 
 /// Minimal trait used by tests/consumers that want a compact, infallible API.
@@ -265,6 +561,8 @@ mod tests {
             }]},
             pre_tags: vec![],
             post_tags: vec![],
+            write_commitments: Vec::new(),
+            aux_commitments: Vec::new(),
         }
     }
 
@@ -289,4 +587,177 @@ mod tests {
         b.in_head_in = 12;
         assert!(!r.interface_ok(&a, &b));
     }
+
+    #[test]
+    fn move_bound_is_configurable() {
+        let mut b = minimal_block(1);
+        b.movement_log.steps[0].input_mv = -2;
+        b.movement_log.steps[0].tapes[0].mv = 2;
+        let err = Replay::new().replay_block(&b).unwrap_err();
+        assert!(err.to_string().contains("within ±1"), "{err}");
+
+        let wide = Replay {
+            cfg: ReplayConfig {
+                max_move: 2,
+                ..ReplayConfig::default()
+            },
+        };
+        wide.replay_block(&b).unwrap();
+        b.movement_log.steps[0].tapes[0].mv = -3;
+        assert!(wide.replay_block(&b).is_err());
+    }
+
+    #[test]
+    fn declared_input_len_bounds_and_chains_the_input_head() {
+        let bounded = |len| Replay {
+            cfg: ReplayConfig {
+                input_len: Some(len),
+                ..ReplayConfig::default()
+            },
+        };
+        let mut b = minimal_block(1);
+        b.movement_log.steps[0].input_mv = 1;
+        b.in_head_in = 2;
+        b.in_head_out = 3;
+        bounded(4).replay_block(&b).unwrap();
+
+        // The head may not step past the end of the tape...
+        let err = bounded(3).replay_block(&b).unwrap_err();
+        assert!(err.to_string().contains("leaves the input tape"), "{err}");
+        // ...nor start before it.
+        b.in_head_in = -1;
+        b.in_head_out = 0;
+        let err = bounded(4).replay_block(&b).unwrap_err();
+        assert!(err.to_string().contains("in_head_in -1"), "{err}");
+
+        // Declared endpoints must match the moves; unchecked without a length.
+        b.in_head_in = 0;
+        b.in_head_out = 2;
+        let err = bounded(4).replay_block(&b).unwrap_err();
+        assert!(err.to_string().contains("in_head_out is 2"), "{err}");
+        Replay::new().replay_block(&b).unwrap();
+    }
+
+    #[test]
+    fn check_visits_requires_windows_to_cover_the_excursion() {
+        let visits = Replay {
+            cfg: ReplayConfig {
+                check_visits: true,
+                ..ReplayConfig::default()
+            },
+        };
+        // The head steps right of its single-cell window without writing.
+        let mut b = minimal_block(1);
+        b.movement_log.steps[0].tapes[0].mv = 1;
+        b.movement_log.steps.push(StepProjection {
+            input_mv: 0,
+            tapes: vec![TapeOp { write: None, mv: -1 }],
+        });
+        Replay::new().replay_block(&b).unwrap();
+        let err = visits.replay_block(&b).unwrap_err();
+        assert!(err.to_string().contains("visits [0,1] outside window [0,0]"), "{err}");
+
+        assert!(b.repair_windows().unwrap());
+        visits.replay_block(&b).unwrap();
+    }
+
+    #[test]
+    fn next_windows_must_contain_the_exit_heads() {
+        let r = Replay::new();
+        // Tape 0 exits at cell 0; the next block's window is [5,6].
+        let prev = r.replay_block(&minimal_block(1)).unwrap();
+        let mut next = minimal_block(1);
+        next.windows[0] = Window { left: 5, right: 6 };
+        let err = r.check_window_continuity(&prev, &next).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("window [5,6] does not contain the previous block's exit head 0"),
+            "{err}"
+        );
+        let err = r
+            .check_window_continuity(&prev, &minimal_block(2))
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("2 work tapes after a block with 1"),
+            "{err}"
+        );
+
+        next.windows[0] = Window { left: -1, right: 6 };
+        r.check_window_continuity(&prev, &next).unwrap();
+
+        // Re-windowing VMs opt out.
+        next.windows[0] = Window { left: 5, right: 6 };
+        let rewindow = Replay {
+            cfg: ReplayConfig {
+                allow_rewindow: true,
+                ..ReplayConfig::default()
+            },
+        };
+        rewindow.check_window_continuity(&prev, &next).unwrap();
+
+        // The same check from the declared exit heads alone.
+        let first = minimal_block(1);
+        assert!(r.check_seam(&first, &next).is_err());
+        rewindow.check_seam(&first, &next).unwrap();
+        next.windows[0] = Window { left: -1, right: 6 };
+        r.check_seam(&first, &next).unwrap();
+    }
+
+    #[test]
+    fn endpoint_deltas_name_each_unreached_exit() {
+        let r = Replay::new();
+        let mut b = minimal_block(2);
+        assert!(r.endpoint_deltas(&b).unwrap().is_empty());
+
+        // Tape 1 and the input head move right, but the block declares
+        // that neither moved.
+        b.windows[1] = Window { left: 0, right: 1 };
+        b.movement_log.steps[0].tapes[1].mv = 1;
+        b.movement_log.steps[0].input_mv = 1;
+        let deltas = r.endpoint_deltas(&b).unwrap();
+        assert_eq!(
+            deltas,
+            vec![
+                EndpointDelta {
+                    block_id: 1,
+                    field: EndpointField::WorkHeadOut,
+                    tape: Some(1),
+                    declared: 0,
+                    replayed: 1,
+                },
+                EndpointDelta {
+                    block_id: 1,
+                    field: EndpointField::InHeadOut,
+                    tape: None,
+                    declared: 0,
+                    replayed: 1,
+                },
+            ]
+        );
+        assert_eq!(
+            deltas[0].to_string(),
+            "block 1: work_head_out on tape 1 declared 0, replayed 1 (+1)"
+        );
+        assert_eq!(
+            serde_json::to_string(&deltas[1]).unwrap(),
+            r#"{"block_id":1,"field":"in_head_out","declared":0,"replayed":1}"#
+        );
+
+        // Declared endpoints stay authoritative unless strict.
+        r.replay_block(&b).unwrap();
+        let strict = Replay {
+            cfg: ReplayConfig {
+                strict_endpoints: true,
+                ..ReplayConfig::default()
+            },
+        };
+        let err = strict.replay_block(&b).unwrap_err();
+        assert!(err.to_string().contains("on tape 1 declared 0"), "{err}");
+
+        b.head_out_offsets[1] = 1;
+        b.in_head_out = 1;
+        assert!(r.endpoint_deltas(&b).unwrap().is_empty());
+        strict.replay_block(&b).unwrap();
+    }
 }
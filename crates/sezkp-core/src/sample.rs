@@ -0,0 +1,265 @@
+//! Downscaled block files for quick iteration.
+//!
+//! [`BlockSampler`] keeps a subset of a block stream — every `n`-th block or
+//! a contiguous prefix — and re-chains what it keeps so the result is a
+//! structurally consistent block file in its own right:
+//!
+//! - block ids are renumbered `1, 2, …` and step ranges re-based to follow
+//!   each other without gaps;
+//! - each kept block is translated along every tape (windows and the input
+//!   head) so its entry heads sit where the previous kept block's exit heads
+//!   left off, and its entry control state is set to the previous exit state.
+//!
+//! Movement logs, writes and offsets inside each window are untouched, so
+//! every kept block still replays exactly as it did in the full file. The
+//! sample is meant for sizing parameters (rows, cadences, query counts)
+//! before a full-scale run; it is not a sub-computation of the original.
+
+use anyhow::{ensure, Context, Result};
+
+use crate::types::{BlockSummary, Cell, TapeGeometry, Window};
+
+/// Which blocks a [`BlockSampler`] keeps.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SampleMode {
+    /// Blocks `0, n, 2n, …` (0-based) of the input.
+    Every(u64),
+    /// The first `n` blocks.
+    Prefix(u64),
+}
+
+/// Exit state of the last kept block, which the next one is chained onto.
+#[derive(Clone, Debug)]
+struct Exit {
+    ctrl: u16,
+    in_head: Cell,
+    heads: Vec<Cell>,
+}
+
+/// Streaming sampler: feed it blocks in order with [`Self::push`].
+#[derive(Clone, Debug)]
+pub struct BlockSampler {
+    mode: SampleMode,
+    seen: u64,
+    kept: u32,
+    next_step: u64,
+    exit: Option<Exit>,
+}
+
+impl BlockSampler {
+    /// Sampler keeping the blocks selected by `mode`.
+    ///
+    /// # Errors
+    /// Fails if the stride or prefix length is zero.
+    pub fn new(mode: SampleMode) -> Result<Self> {
+        let (SampleMode::Every(n) | SampleMode::Prefix(n)) = mode;
+        ensure!(n > 0, "sample size must be at least 1");
+        Ok(Self {
+            mode,
+            seen: 0,
+            kept: 0,
+            next_step: 1,
+            exit: None,
+        })
+    }
+
+    /// Number of blocks kept so far.
+    #[inline]
+    #[must_use]
+    pub const fn kept(&self) -> u32 {
+        self.kept
+    }
+
+    /// Whether no further input can be kept (a prefix is complete), so the
+    /// caller may stop reading.
+    #[must_use]
+    pub const fn is_done(&self) -> bool {
+        matches!(self.mode, SampleMode::Prefix(n) if self.seen >= n)
+    }
+
+    /// Offer the next input block; returns it re-chained if it is kept.
+    ///
+    /// # Errors
+    /// Fails on redacted blocks (their commitments are salted by block id
+    /// and global step, so they cannot be renumbered; sample before
+    /// redacting), on malformed geometry, on a tape count that differs from
+    /// the previous kept block, or if ids, steps or cells overflow.
+    pub fn push(&mut self, mut b: BlockSummary) -> Result<Option<BlockSummary>> {
+        let idx = self.seen;
+        self.seen += 1;
+        let keep = match self.mode {
+            SampleMode::Every(n) => idx % n == 0,
+            SampleMode::Prefix(n) => idx < n,
+        };
+        if !keep {
+            return Ok(None);
+        }
+        let id = b.block_id;
+        ensure!(
+            !b.is_redacted(),
+            "block {id} is redacted; sample the plain blocks and redact the sample"
+        );
+        ensure!(
+            b.step_hi >= b.step_lo,
+            "block {id}: inverted step range [{}, {}]",
+            b.step_lo,
+            b.step_hi
+        );
+
+        let tapes = b.windows.len();
+        let geometry = (0..tapes)
+            .map(|r| b.tape_geometry(r))
+            .collect::<Result<Vec<_>>>()?;
+        if let Some(exit) = &self.exit {
+            ensure!(
+                exit.heads.len() == tapes,
+                "block {id}: {tapes} tapes, previous sampled block has {}",
+                exit.heads.len()
+            );
+            let shifted = geometry
+                .iter()
+                .zip(&exit.heads)
+                .enumerate()
+                .map(|(r, (g, &to))| {
+                    let delta = to - g.heads().0;
+                    let shift = |c: Cell| {
+                        c.checked_add(delta)
+                            .with_context(|| format!("block {id}: shifting tape {r}"))
+                    };
+                    let window = Window::new(shift(g.window.left)?, shift(g.window.right)?);
+                    Ok(TapeGeometry { window, ..*g })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            b.set_geometry(&shifted);
+
+            let delta = exit.in_head - b.in_head_in;
+            b.in_head_in = exit.in_head;
+            b.in_head_out = b
+                .in_head_out
+                .checked_add(delta)
+                .with_context(|| format!("block {id}: shifting the input head"))?;
+            b.ctrl_in = exit.ctrl;
+        }
+
+        let span = b.step_hi - b.step_lo;
+        self.kept = self.kept.checked_add(1).context("block id overflow")?;
+        b.block_id = self.kept;
+        b.step_lo = self.next_step;
+        b.step_hi = b.step_lo.checked_add(span).context("step overflow")?;
+        self.next_step = b.step_hi + 1;
+
+        self.exit = Some(Exit {
+            ctrl: b.ctrl_out,
+            in_head: b.in_head_out,
+            heads: (0..tapes)
+                .map(|r| Ok(b.tape_geometry(r)?.heads().1))
+                .collect::<Result<_>>()?,
+        });
+        Ok(Some(b))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::redact::REDACTED_BLOCK_VERSION;
+    use crate::replay::Replay;
+    use crate::{MovementLog, StepProjection, TapeOp};
+
+    /// Block `k` (0-based) of a chain: three steps moving right on the one
+    /// work tape and the input, control `k → k + 1`.
+    fn block(k: u32) -> BlockSummary {
+        let base = 3 * i64::from(k);
+        BlockSummary {
+            version: 1,
+            block_id: k + 1,
+            step_lo: 3 * u64::from(k) + 1,
+            step_hi: 3 * u64::from(k) + 3,
+            ctrl_in: k as u16,
+            ctrl_out: k as u16 + 1,
+            in_head_in: base,
+            in_head_out: base + 3,
+            windows: vec![Window::new(base, base + 3)],
+            head_in_offsets: vec![0],
+            head_out_offsets: vec![3],
+            movement_log: MovementLog {
+                steps: vec![
+                    StepProjection {
+                        input_mv: 1,
+                        tapes: vec![TapeOp {
+                            write: Some(1),
+                            mv: 1
+                        }],
+                    };
+                    3
+                ],
+            },
+            pre_tags: Vec::new(),
+            post_tags: Vec::new(),
+            write_commitments: Vec::new(),
+            aux_commitments: Vec::new(),
+        }
+    }
+
+    fn sample(mode: SampleMode, n: u32) -> Vec<BlockSummary> {
+        let mut s = BlockSampler::new(mode).unwrap();
+        (0..n).filter_map(|k| s.push(block(k)).unwrap()).collect()
+    }
+
+    /// Replays `blocks` as one run, checking every seam.
+    fn assert_chains(blocks: &[BlockSummary]) {
+        let replay = Replay::new();
+        let mut prev = None;
+        for (i, b) in blocks.iter().enumerate() {
+            assert_eq!(b.block_id as usize, i + 1);
+            let fs = replay.replay_block(b).unwrap();
+            if let Some(p) = &prev {
+                assert!(replay.interface_ok(p, &fs));
+                replay.check_window_continuity(p, b).unwrap();
+                assert_eq!(p.work_head_out, fs.work_head_in);
+                assert_eq!(blocks[i - 1].step_hi + 1, b.step_lo);
+            }
+            prev = Some(fs);
+        }
+    }
+
+    #[test]
+    fn strided_samples_rechain() {
+        let kept = sample(SampleMode::Every(2), 5);
+        assert_eq!(kept.len(), 3);
+        assert_chains(&kept);
+        assert_eq!((kept[2].step_lo, kept[2].step_hi), (7, 9));
+        // Block 2 (ctrl 2 → 3) now follows block 0 (exit ctrl 1).
+        assert_eq!((kept[1].ctrl_in, kept[1].ctrl_out), (1, 3));
+        assert_eq!(kept[1].windows[0], Window::new(3, 6));
+        // Moves and writes are untouched.
+        assert_eq!(kept[1].movement_log, block(2).movement_log);
+    }
+
+    #[test]
+    fn prefixes_stop_early() {
+        let mut s = BlockSampler::new(SampleMode::Prefix(2)).unwrap();
+        assert!(s.push(block(0)).unwrap().is_some());
+        assert!(!s.is_done());
+        assert!(s.push(block(1)).unwrap().is_some());
+        assert!(s.is_done());
+        assert!(s.push(block(2)).unwrap().is_none());
+        assert_eq!(s.kept(), 2);
+        // A prefix of a chained file is returned unchanged.
+        assert_eq!(
+            sample(SampleMode::Prefix(3), 5),
+            vec![block(0), block(1), block(2)]
+        );
+    }
+
+    #[test]
+    fn rejects_empty_samples_and_redacted_blocks() {
+        assert!(BlockSampler::new(SampleMode::Every(0)).is_err());
+        assert!(BlockSampler::new(SampleMode::Prefix(0)).is_err());
+        let mut redacted = block(0);
+        redacted.version = REDACTED_BLOCK_VERSION;
+        let mut s = BlockSampler::new(SampleMode::Every(1)).unwrap();
+        let err = s.push(redacted).unwrap_err();
+        assert!(err.to_string().contains("redacted"), "{err}");
+    }
+}
@@ -0,0 +1,278 @@
+//! Signed creation timestamps for proof artifacts.
+//!
+//! A configured **time authority** attests "artifact with digest `d` existed
+//! at time `t`" by issuing a [`TimestampToken`] over [`artifact_digest`]. The
+//! token travels in [`ProofArtifact::timestamp`]; verifiers holding the
+//! authority key check it and may additionally enforce a maximum age.
+//!
+//! ## Trust model
+//! Tokens are keyed BLAKE3 MACs: the authority and every verifier share the
+//! 32-byte key, so a token convinces key holders only (it is not publicly
+//! verifiable, and any key holder could mint one). Deployments that need
+//! third-party verifiability (RFC 3161 TSAs, public-key signatures) can carry
+//! their token in the same slot once such a scheme is added as another
+//! [`TimestampToken::scheme`].
+//!
+//! ## Digest
+//! The digest binds the backend kind, the manifest root and the proof bytes.
+//! `meta` is diagnostics only and is deliberately excluded, as is the token.
+
+use anyhow::{bail, ensure, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::artifact::ProofArtifact;
+
+/// Domain separator for [`artifact_digest`].
+const DS_DIGEST: &[u8] = b"sezkp/artifact-digest/v1";
+/// Domain separator for MAC tokens.
+const DS_TOKEN: &[u8] = b"sezkp/timestamp/mac-v1";
+
+/// Token scheme identifier for keyed-BLAKE3 tokens.
+pub const SCHEME_MAC_V1: &str = "blake3-mac-v1";
+
+/// Allowed clock skew when a token claims a time in the future.
+pub const MAX_FUTURE_SKEW_SECS: u64 = 300;
+
+/// A time authority's attestation over an artifact digest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimestampToken {
+    /// Token scheme (see [`SCHEME_MAC_V1`]).
+    pub scheme: String,
+    /// Name of the issuing authority.
+    pub authority: String,
+    /// Attested creation time, seconds since the Unix epoch.
+    pub unix_secs: u64,
+    /// Artifact digest the token covers.
+    pub digest: [u8; 32],
+    /// Authentication tag.
+    pub tag: [u8; 32],
+}
+
+/// A named authority key used to issue and check tokens.
+#[derive(Clone)]
+pub struct TimeAuthority {
+    name: String,
+    key: [u8; 32],
+}
+
+impl std::fmt::Debug for TimeAuthority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TimeAuthority")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Digest of the parts of an artifact a timestamp attests to.
+#[must_use]
+pub fn artifact_digest(a: &ProofArtifact) -> [u8; 32] {
+    let mut h = blake3::Hasher::new();
+    h.update(DS_DIGEST);
+    // Serialized the same way as on the wire, e.g. `"stark"`.
+    h.update(
+        serde_json::to_string(&a.backend)
+            .unwrap_or_default()
+            .as_bytes(),
+    );
+    h.update(&a.manifest_root);
+    h.update(&(a.proof_bytes.len() as u64).to_le_bytes());
+    h.update(&a.proof_bytes);
+    *h.finalize().as_bytes()
+}
+
+/// Current wall-clock time in Unix seconds.
+#[must_use]
+pub fn now_unix_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+impl TimeAuthority {
+    /// Authority `name` with a raw 32-byte key.
+    #[must_use]
+    pub fn new(name: impl Into<String>, key: [u8; 32]) -> Self {
+        Self {
+            name: name.into(),
+            key,
+        }
+    }
+
+    /// Load a key file holding 64 hex characters (surrounding whitespace is
+    /// ignored).
+    pub fn from_key_file(name: impl Into<String>, path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("reading timestamp key {}", path.display()))?;
+        let text = text.trim();
+        ensure!(
+            text.len() == 64 && text.bytes().all(|b| b.is_ascii_hexdigit()),
+            "timestamp key {} must be 64 hex characters",
+            path.display()
+        );
+        let mut key = [0u8; 32];
+        for (i, k) in key.iter_mut().enumerate() {
+            *k = u8::from_str_radix(&text[2 * i..2 * i + 2], 16)?;
+        }
+        Ok(Self::new(name, key))
+    }
+
+    /// Authority name.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn tag(&self, unix_secs: u64, digest: &[u8; 32]) -> blake3::Hash {
+        let mut h = blake3::Hasher::new_keyed(&self.key);
+        h.update(DS_TOKEN);
+        h.update(&(self.name.len() as u64).to_le_bytes());
+        h.update(self.name.as_bytes());
+        h.update(&unix_secs.to_le_bytes());
+        h.update(digest);
+        h.finalize()
+    }
+
+    /// Issue a token over `digest` for time `unix_secs`.
+    #[must_use]
+    pub fn issue(&self, digest: [u8; 32], unix_secs: u64) -> TimestampToken {
+        TimestampToken {
+            scheme: SCHEME_MAC_V1.to_owned(),
+            authority: self.name.clone(),
+            unix_secs,
+            digest,
+            tag: *self.tag(unix_secs, &digest).as_bytes(),
+        }
+    }
+
+    /// Check `token` against `digest`, returning the attested time.
+    ///
+    /// Fails on an unknown scheme, another authority, a digest mismatch, a
+    /// bad tag, or a time more than [`MAX_FUTURE_SKEW_SECS`] past `now`.
+    pub fn check(&self, token: &TimestampToken, digest: &[u8; 32], now: u64) -> Result<u64> {
+        ensure!(
+            token.scheme == SCHEME_MAC_V1,
+            "unsupported timestamp scheme {:?}",
+            token.scheme
+        );
+        ensure!(
+            token.authority == self.name,
+            "timestamp issued by {:?}, expected {:?}",
+            token.authority,
+            self.name
+        );
+        ensure!(
+            token.digest == *digest,
+            "timestamp covers a different artifact"
+        );
+        // `blake3::Hash` compares in constant time.
+        if self.tag(token.unix_secs, &token.digest) != blake3::Hash::from(token.tag) {
+            bail!("timestamp tag does not verify");
+        }
+        ensure!(
+            token.unix_secs <= now.saturating_add(MAX_FUTURE_SKEW_SECS),
+            "timestamp {} is in the future (now {now})",
+            token.unix_secs
+        );
+        Ok(token.unix_secs)
+    }
+}
+
+impl ProofArtifact {
+    /// Attach a token from `authority` attesting creation at `unix_secs`.
+    ///
+    /// Replaces any existing token.
+    pub fn attach_timestamp(&mut self, authority: &TimeAuthority, unix_secs: u64) {
+        self.timestamp = Some(authority.issue(artifact_digest(self), unix_secs));
+    }
+
+    /// Check the attached token and return the attested creation time.
+    ///
+    /// With `max_age_secs`, also reject artifacts attested more than that many
+    /// seconds before `now`.
+    pub fn verify_timestamp(
+        &self,
+        authority: &TimeAuthority,
+        now: u64,
+        max_age_secs: Option<u64>,
+    ) -> Result<u64> {
+        let token = self
+            .timestamp
+            .as_ref()
+            .context("artifact carries no timestamp")?;
+        let at = authority.check(token, &artifact_digest(self), now)?;
+        if let Some(max) = max_age_secs {
+            let age = now.saturating_sub(at);
+            ensure!(
+                age <= max,
+                "artifact expired: attested {age}s ago, max age {max}s"
+            );
+        }
+        Ok(at)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BackendKind;
+
+    fn artifact() -> ProofArtifact {
+        ProofArtifact::new(
+            BackendKind::Fold,
+            [3u8; 32],
+            vec![1, 2, 3],
+            serde_json::Value::Null,
+        )
+    }
+
+    #[test]
+    fn token_roundtrip_and_rejections() {
+        let tsa = TimeAuthority::new("ops", [9u8; 32]);
+        let mut a = artifact();
+        a.attach_timestamp(&tsa, 1_000);
+        assert_eq!(a.verify_timestamp(&tsa, 1_500, None).unwrap(), 1_000);
+        assert_eq!(a.verify_timestamp(&tsa, 1_500, Some(500)).unwrap(), 1_000);
+
+        // Expired, future-dated, wrong key/authority.
+        assert!(a.verify_timestamp(&tsa, 1_501, Some(500)).is_err());
+        assert!(a.verify_timestamp(&tsa, 600, None).is_err());
+        assert!(a
+            .verify_timestamp(&TimeAuthority::new("ops", [8u8; 32]), 1_500, None)
+            .is_err());
+        assert!(a
+            .verify_timestamp(&TimeAuthority::new("other", [9u8; 32]), 1_500, None)
+            .is_err());
+
+        // Meta is not covered; proof bytes and forged times are.
+        let mut b = a.clone();
+        b.meta = serde_json::json!({"note": "edited"});
+        assert!(b.verify_timestamp(&tsa, 1_500, None).is_ok());
+        b.proof_bytes.push(0);
+        assert!(b.verify_timestamp(&tsa, 1_500, None).is_err());
+        let mut c = a.clone();
+        c.timestamp.as_mut().unwrap().unix_secs = 1_400;
+        assert!(c.verify_timestamp(&tsa, 1_500, None).is_err());
+
+        assert!(artifact().verify_timestamp(&tsa, 1_500, None).is_err());
+    }
+
+    #[test]
+    fn token_survives_serialization() {
+        let tsa = TimeAuthority::new("ops", [1u8; 32]);
+        let mut a = artifact();
+        a.attach_timestamp(&tsa, 42);
+        let de: ProofArtifact = serde_json::from_slice(&serde_json::to_vec(&a).unwrap()).unwrap();
+        assert_eq!(de.verify_timestamp(&tsa, 42, Some(0)).unwrap(), 42);
+
+        // Artifacts written before timestamps existed still load.
+        let legacy = serde_json::json!({
+            "backend": "fold",
+            "manifest_root": vec![0u8; 32],
+            "proof_bytes": [],
+        });
+        let de: ProofArtifact = serde_json::from_value(legacy).unwrap();
+        assert!(de.timestamp.is_none());
+    }
+}
@@ -4,10 +4,18 @@
 //! so other crates can import via `sezkp_core::TapeOp`, `sezkp_core::Interval`, etc.
 //!
 //! The design aims to keep serialized forms conservative and portable (serde).
+//!
+//! Block geometry is stored as raw windows and offsets on the wire; code that
+//! builds or reads it should go through [`Window::try_new`], [`HeadOffset`] and
+//! [`TapeGeometry`], which only produce offsets lying inside their window.
 
+use anyhow::{ensure, Context, Result};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
+use crate::auxiliary::AuxCommitment;
+use crate::redact::{CellCommitment, REDACTED_BLOCK_VERSION};
+
 /// Absolute cell on a work tape (signed to allow moves left of origin).
 pub type Cell = i64;
 
@@ -34,6 +42,84 @@ impl Window {
         Self { left, right }
     }
 
+    /// Create a window, rejecting `right < left`.
+    ///
+    /// # Errors
+    /// Fails if the window is inverted.
+    pub fn try_new(left: Cell, right: Cell) -> Result<Self> {
+        ensure!(
+            right >= left,
+            "invalid window: right < left ({right} < {left})"
+        );
+        Ok(Self { left, right })
+    }
+
+    /// The single-cell window `[pos, pos]`.
+    #[inline]
+    #[must_use]
+    pub const fn at(pos: Cell) -> Self {
+        Self {
+            left: pos,
+            right: pos,
+        }
+    }
+
+    /// Widen the window (if needed) so it contains `pos`.
+    #[inline]
+    pub fn include(&mut self, pos: Cell) {
+        self.left = self.left.min(pos);
+        self.right = self.right.max(pos);
+    }
+
+    /// Offset of `pos` from the left edge.
+    ///
+    /// # Errors
+    /// Fails if the window is inverted, `pos` lies outside it, or the offset
+    /// does not fit the wire type.
+    pub fn offset_of(&self, pos: Cell) -> Result<HeadOffset> {
+        ensure!(
+            self.is_valid(),
+            "invalid window: right < left ({} < {})",
+            self.right,
+            self.left
+        );
+        ensure!(
+            self.contains(pos),
+            "cell {pos} lies outside window [{}, {}]",
+            self.left,
+            self.right
+        );
+        Offset::try_from(pos - self.left)
+            .map(HeadOffset)
+            .with_context(|| {
+                format!(
+                    "offset of cell {pos} in window [{}, {}] overflows",
+                    self.left, self.right
+                )
+            })
+    }
+
+    /// Check a raw wire offset against this window.
+    ///
+    /// # Errors
+    /// Fails if the window is inverted or `raw` points past its right edge.
+    pub fn check_offset(&self, raw: Offset) -> Result<HeadOffset> {
+        self.offset_of(self.left.saturating_add(Cell::from(raw)))
+            .with_context(|| {
+                format!(
+                    "offset {raw} out of window range [0, {}]",
+                    self.right.saturating_sub(self.left)
+                )
+            })
+    }
+
+    /// Absolute cell at `off` (an offset previously checked against this window).
+    #[inline]
+    #[must_use]
+    pub fn cell(&self, off: HeadOffset) -> Cell {
+        self.left + Cell::from(off.0)
+    }
+
     /// Returns `true` if `self.right >= self.left`.
     #[inline]
     #[must_use]
@@ -67,17 +153,87 @@ impl From<(Cell, Cell)> for Window {
     }
 }
 
+/// Head position within a [`Window`], counted from its left edge.
+///
+/// Obtained from [`Window::offset_of`] or [`Window::check_offset`], so it
+/// always lies inside the window it was made for. Serializes as the bare
+/// [`Offset`].
+#[derive(
+    Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[serde(transparent)]
+pub struct HeadOffset(Offset);
+
+impl HeadOffset {
+    /// The raw wire offset.
+    #[inline]
+    #[must_use]
+    pub const fn get(self) -> Offset {
+        self.0
+    }
+}
+
+impl From<HeadOffset> for Offset {
+    #[inline]
+    fn from(off: HeadOffset) -> Self {
+        off.0
+    }
+}
+
+impl fmt::Display for HeadOffset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// One tape's block geometry: its window and the entry/exit head offsets.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TapeGeometry {
+    /// Touched cells.
+    pub window: Window,
+    /// Head offset at block entry.
+    pub head_in: HeadOffset,
+    /// Head offset at block exit.
+    pub head_out: HeadOffset,
+}
+
+impl TapeGeometry {
+    /// Geometry for a tape whose head enters at cell `head_in` and leaves at
+    /// `head_out`, both absolute.
+    ///
+    /// # Errors
+    /// Fails if the window is inverted or either head lies outside it.
+    pub fn new(window: Window, head_in: Cell, head_out: Cell) -> Result<Self> {
+        Ok(Self {
+            window,
+            head_in: window.offset_of(head_in).context("entry head")?,
+            head_out: window.offset_of(head_out).context("exit head")?,
+        })
+    }
+
+    /// Absolute `(entry, exit)` head cells.
+    #[inline]
+    #[must_use]
+    pub fn heads(&self) -> (Cell, Cell) {
+        (
+            self.window.cell(self.head_in),
+            self.window.cell(self.head_out),
+        )
+    }
+}
+
 /// Movement for a single tape in {-1, 0, +1}.
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
 pub struct TapeOp {
     /// Optional write (`None` ⇒ no write).
     pub write: Option<SymbolId>,
-    /// Head move in `{-1,0,+1}`.
+    /// Head move; `{-1,0,+1}` unless the replay config allows wider jumps
+    /// (see [`crate::ReplayConfig::max_move`]).
     pub mv: i8,
 }
 
 impl TapeOp {
-    /// Construct a new `TapeOp`. `mv` should be in `{-1,0,+1}`.
+    /// Construct a new `TapeOp`. `mv` is usually in `{-1,0,+1}`.
     #[inline]
     #[must_use]
     pub const fn new(write: Option<SymbolId>, mv: i8) -> Self {
@@ -95,7 +251,7 @@ impl TapeOp {
 /// A single replay step projection (input move + τ per-tape ops).
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
 pub struct StepProjection {
-    /// Input head move in `{-1,0,+1}`.
+    /// Input head move; bounded like [`TapeOp::mv`].
     pub input_mv: i8,
     /// Per-tape operations (length = τ).
     pub tapes: Vec<TapeOp>,
@@ -148,6 +304,129 @@ pub struct BlockSummary {
     pub pre_tags: Vec<Tag>,
     /// Advisory tags (quick reject only).
     pub post_tags: Vec<Tag>,
+
+    /// Salted commitments to the written symbols, one per `Some` write in
+    /// step-major, tape-minor order. Only redacted blocks carry these (see
+    /// [`crate::redact`]); plain blocks leave it empty and omit it on the wire.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub write_commitments: Vec<CellCommitment>,
+
+    /// Adapter-defined commitments to side data of this block (see
+    /// [`crate::auxiliary`]). Blocks without any leave it empty and omit it
+    /// on the wire.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aux_commitments: Vec<AuxCommitment>,
+}
+
+impl BlockSummary {
+    /// Whether this block was exported with its written symbols redacted.
+    #[inline]
+    #[must_use]
+    pub const fn is_redacted(&self) -> bool {
+        self.version == REDACTED_BLOCK_VERSION
+    }
+
+    /// Whether a VM adapter attached auxiliary commitments to this block.
+    #[inline]
+    #[must_use]
+    pub fn has_aux(&self) -> bool {
+        !self.aux_commitments.is_empty()
+    }
+
+    /// Overwrite `windows` and both offset vectors from per-tape geometry.
+    pub fn set_geometry(&mut self, tapes: &[TapeGeometry]) {
+        self.windows = tapes.iter().map(|g| g.window).collect();
+        self.head_in_offsets = tapes.iter().map(|g| g.head_in.get()).collect();
+        self.head_out_offsets = tapes.iter().map(|g| g.head_out.get()).collect();
+    }
+
+    /// Validated geometry of tape `r`.
+    ///
+    /// # Errors
+    /// Fails if tape `r` is missing from any of the geometry vectors, its
+    /// window is inverted, or an offset lies outside the window.
+    pub fn tape_geometry(&self, r: usize) -> Result<TapeGeometry> {
+        let id = self.block_id;
+        let window = *self
+            .windows
+            .get(r)
+            .with_context(|| format!("block {id}: missing windows[{r}]"))?;
+        let raw_in = *self
+            .head_in_offsets
+            .get(r)
+            .with_context(|| format!("block {id}: missing head_in_offsets[{r}]"))?;
+        let raw_out = *self
+            .head_out_offsets
+            .get(r)
+            .with_context(|| format!("block {id}: missing head_out_offsets[{r}]"))?;
+        Ok(TapeGeometry {
+            window,
+            head_in: window
+                .check_offset(raw_in)
+                .with_context(|| format!("block {id}: entry offset on tape {r}"))?,
+            head_out: window
+                .check_offset(raw_out)
+                .with_context(|| format!("block {id}: exit offset on tape {r}"))?,
+        })
+    }
+
+    /// Smallest per-tape windows covering every head position the movement
+    /// log visits: the declared entry head, each position after a move, and
+    /// the declared exit head.
+    ///
+    /// # Errors
+    /// Fails if the declared geometry is invalid (see [`Self::tape_geometry`])
+    /// or a step does not carry one op per tape.
+    pub fn visited_windows(&self) -> Result<Vec<Window>> {
+        let tau = self.windows.len();
+        let mut heads = Vec::with_capacity(tau);
+        let mut visited = Vec::with_capacity(tau);
+        for r in 0..tau {
+            let (h_in, h_out) = self.tape_geometry(r)?.heads();
+            let mut w = Window::at(h_in);
+            w.include(h_out);
+            heads.push(h_in);
+            visited.push(w);
+        }
+        for (sidx, step) in self.movement_log.steps.iter().enumerate() {
+            ensure!(
+                step.tapes.len() == tau,
+                "block {}: step {sidx} has {} tape ops, expected {tau}",
+                self.block_id,
+                step.tapes.len()
+            );
+            for (r, op) in step.tapes.iter().enumerate() {
+                heads[r] += Cell::from(op.mv);
+                visited[r].include(heads[r]);
+            }
+        }
+        Ok(visited)
+    }
+
+    /// Replace each window by its [`Self::visited_windows`] and re-derive the
+    /// head offsets against the new left edges, keeping the absolute entry
+    /// and exit heads. Returns whether any window changed.
+    ///
+    /// # Errors
+    /// Fails like [`Self::visited_windows`], or if an offset overflows.
+    pub fn repair_windows(&mut self) -> Result<bool> {
+        let geometry = self
+            .visited_windows()?
+            .into_iter()
+            .enumerate()
+            .map(|(r, w)| {
+                let (h_in, h_out) = self.tape_geometry(r)?.heads();
+                TapeGeometry::new(w, h_in, h_out)
+                    .with_context(|| format!("block {}: tape {r}", self.block_id))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let changed = geometry
+            .iter()
+            .zip(&self.windows)
+            .any(|(g, w)| g.window != *w);
+        self.set_geometry(&geometry);
+        Ok(changed)
+    }
 }
 
 /// Closed interval of block indices `[i, j]` (1-based inclusive).
@@ -245,6 +524,103 @@ mod tests {
         assert!(!bad.is_valid());
     }
 
+    #[test]
+    fn offsets_stay_inside_their_window() {
+        assert!(Window::try_new(5, 1).is_err());
+        let mut w = Window::at(0);
+        w.include(-2);
+        w.include(3);
+        assert_eq!(w, Window::try_new(-2, 3).unwrap());
+
+        let off = w.offset_of(1).unwrap();
+        assert_eq!(off.get(), 3);
+        assert_eq!(w.cell(off), 1);
+        assert!(w.offset_of(4).is_err());
+        assert_eq!(w.check_offset(5).unwrap().get(), 5);
+        assert!(w.check_offset(6).is_err());
+        assert!(Window::new(5, 1).check_offset(0).is_err());
+
+        assert!(TapeGeometry::new(w, 0, 4).is_err());
+        let g = TapeGeometry::new(w, 0, 3).unwrap();
+        assert_eq!(g.heads(), (0, 3));
+        // Wire form is the bare number.
+        assert_eq!(serde_json::to_string(&g.head_in).unwrap(), "2");
+    }
+
+    #[test]
+    fn block_geometry_round_trips() {
+        let mut b = BlockSummary {
+            version: 1,
+            block_id: 7,
+            step_lo: 1,
+            step_hi: 1,
+            ctrl_in: 0,
+            ctrl_out: 0,
+            in_head_in: 0,
+            in_head_out: 0,
+            windows: Vec::new(),
+            head_in_offsets: Vec::new(),
+            head_out_offsets: Vec::new(),
+            movement_log: MovementLog::default(),
+            pre_tags: Vec::new(),
+            post_tags: Vec::new(),
+            write_commitments: Vec::new(),
+            aux_commitments: Vec::new(),
+        };
+        let g = TapeGeometry::new(Window::new(-1, 1), -1, 1).unwrap();
+        b.set_geometry(&[g]);
+        assert_eq!((b.head_in_offsets[0], b.head_out_offsets[0]), (0, 2));
+        assert_eq!(b.tape_geometry(0).unwrap(), g);
+
+        b.head_out_offsets[0] = 3;
+        let err = format!("{:#}", b.tape_geometry(0).unwrap_err());
+        assert!(err.contains("block 7: exit offset on tape 0"), "{err}");
+        assert!(b.tape_geometry(1).is_err());
+    }
+
+    #[test]
+    fn repair_windows_fits_the_visited_cells() {
+        // Enters at cell 0 of [-5, 5], walks right twice, then back once.
+        let mut b = BlockSummary {
+            version: 1,
+            block_id: 3,
+            step_lo: 1,
+            step_hi: 3,
+            ctrl_in: 0,
+            ctrl_out: 0,
+            in_head_in: 0,
+            in_head_out: 0,
+            windows: Vec::new(),
+            head_in_offsets: Vec::new(),
+            head_out_offsets: Vec::new(),
+            movement_log: MovementLog {
+                steps: [1, 1, -1]
+                    .into_iter()
+                    .map(|mv| StepProjection {
+                        input_mv: 0,
+                        tapes: vec![TapeOp::new(None, mv)],
+                    })
+                    .collect(),
+            },
+            pre_tags: Vec::new(),
+            post_tags: Vec::new(),
+            write_commitments: Vec::new(),
+            aux_commitments: Vec::new(),
+        };
+        b.set_geometry(&[TapeGeometry::new(Window::new(-5, 5), 0, 1).unwrap()]);
+        assert_eq!(b.visited_windows().unwrap(), [Window::new(0, 2)]);
+
+        assert!(b.repair_windows().unwrap());
+        assert_eq!(b.windows, [Window::new(0, 2)]);
+        assert_eq!(b.tape_geometry(0).unwrap().heads(), (0, 1));
+        assert!(!b.repair_windows().unwrap());
+
+        // Too narrow a window is widened to the whole excursion.
+        b.set_geometry(&[TapeGeometry::new(Window::new(0, 1), 0, 1).unwrap()]);
+        assert!(b.repair_windows().unwrap());
+        assert_eq!(b.windows, [Window::new(0, 2)]);
+    }
+
     #[test]
     fn interval_len() {
         assert_eq!(Interval::new(3, 7).len(), 5);
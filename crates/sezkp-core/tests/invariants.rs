@@ -61,6 +61,8 @@ fn mk_block(
         // Advisory tags (not used for soundness)
         pre_tags: vec![[0u8; 16]; 2],
         post_tags: vec![[0u8; 16]; 2],
+        write_commitments: Vec::new(),
+        aux_commitments: Vec::new(),
     }
 }
 
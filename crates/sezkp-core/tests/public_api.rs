@@ -0,0 +1,248 @@
+//! Semver guard: the stable public API (everything compiled without the
+//! `unstable` feature) must match the committed `tests/public_api.txt`.
+//!
+//! The surface is read from the sources, so no nightly rustdoc is needed:
+//! the crate root's modules, re-exports and prelude, and in every stable
+//! module each `pub` item with its signature, plus the methods, fields,
+//! variants and trait items under it. Items gated on `unstable` and test
+//! modules are left out. After an intended change, regenerate the snapshot
+//! with `SEZKP_BLESS_API=1 cargo test -p sezkp-core --test public_api` and
+//! commit it (with a version bump if anything was removed or changed).
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const SNAPSHOT: &str = "tests/public_api.txt";
+
+fn crate_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+/// Whether the attributes on an item take it out of the stable surface
+/// (`unstable`, test-only or `#[doc(hidden)]`).
+fn gated(attrs: &[String]) -> bool {
+    attrs.iter().any(|a| {
+        a.contains("doc(hidden)")
+            || (a.starts_with("#[cfg(")
+                && (a.contains("test")
+                    || (a.contains("feature = \"unstable\"") && !a.contains("not("))))
+    })
+}
+
+/// Open brackets minus closed ones in `s`.
+fn depth(s: &str) -> i32 {
+    s.chars().fold(0, |d, c| match c {
+        '(' | '[' | '{' => d + 1,
+        ')' | ']' | '}' => d - 1,
+        _ => d,
+    })
+}
+
+/// Signature starting at `lines[i]`, joined up to its body or a `;` or `,`
+/// outside brackets (re-exports keep their braces), and the index of its
+/// last line.
+fn signature(lines: &[&str], i: usize) -> (String, usize) {
+    let is_use = lines[i].trim_start().starts_with("pub use");
+    let mut sig = String::new();
+    let mut last = i;
+    for (j, line) in lines.iter().enumerate().skip(i) {
+        if !sig.is_empty() {
+            sig.push(' ');
+        }
+        sig.push_str(line.trim());
+        last = j;
+        let ended = depth(&sig) == 0 && (sig.ends_with(';') || sig.ends_with(','));
+        if ended || (!is_use && sig.contains('{')) {
+            break;
+        }
+    }
+    let sig = if is_use {
+        &sig[..]
+    } else {
+        sig.split('{').next().unwrap_or_default()
+    };
+    let sig = sig.trim().trim_end_matches([';', ',']).trim_end();
+    (sig.split_whitespace().collect::<Vec<_>>().join(" "), last)
+}
+
+/// Public items of one source file, as `module: [parent :: ]signature`.
+fn items(module: &str, text: &str) -> Vec<String> {
+    let text = text
+        .split("#[cfg(test)]\nmod tests")
+        .next()
+        .unwrap_or_default();
+    let lines: Vec<&str> = text.lines().collect();
+    let mut out = Vec::new();
+    let mut attrs: Vec<String> = Vec::new();
+    let mut open_attr: Option<String> = None;
+    // Column-0 item the indented lines belong to, if its members are public.
+    let mut parent: Option<String> = None;
+    // Lines up to here belong to a signature already read.
+    let mut skip_to = 0;
+
+    for (i, line) in lines.iter().enumerate() {
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+        if i < skip_to {
+            continue;
+        }
+        if let Some(mut attr) = open_attr.take() {
+            attr.push_str(trimmed);
+            if depth(&attr) == 0 {
+                attrs.push(attr);
+            } else {
+                open_attr = Some(attr);
+            }
+            continue;
+        }
+        if trimmed.is_empty() || trimmed.starts_with("//") || trimmed.starts_with('}') {
+            continue;
+        }
+        if trimmed.starts_with("#[") || trimmed.starts_with("#![") {
+            if depth(trimmed) == 0 {
+                attrs.push(trimmed.to_owned());
+            } else {
+                open_attr = Some(trimmed.to_owned());
+            }
+            continue;
+        }
+        let hidden = gated(&attrs);
+        if indent == 0 {
+            let (sig, last) = signature(&lines, i);
+            skip_to = last + 1;
+            let public = trimmed.starts_with("pub ");
+            let exported = trimmed.starts_with("macro_rules!")
+                && attrs.iter().any(|a| a.contains("macro_export"));
+            if (public || exported) && !hidden {
+                out.push(format!("{module}: {sig}"));
+            }
+            parent = (!hidden && (public || trimmed.starts_with("impl"))).then_some(sig);
+        } else if let (4, Some(p)) = (indent, &parent) {
+            let trait_item = ["fn ", "type ", "const "]
+                .iter()
+                .any(|k| trimmed.starts_with(k));
+            let member = trimmed.starts_with("pub ")
+                || (p.starts_with("pub trait") && trait_item)
+                || (p.starts_with("pub enum") && trimmed.starts_with(char::is_uppercase));
+            if member && !hidden {
+                let (sig, last) = signature(&lines, i);
+                skip_to = last + 1;
+                out.push(format!("{module}: {p} :: {sig}"));
+            }
+        }
+        attrs.clear();
+    }
+    out
+}
+
+/// The stable surface, one sorted line per item.
+fn surface(dir: &Path) -> String {
+    let src = dir.join("src");
+    let lib = fs::read_to_string(src.join("lib.rs")).unwrap_or_default();
+    let mut all: BTreeSet<String> = items("crate", &lib).into_iter().collect();
+    // Modules in their own files (`prelude` is inline in the root).
+    let modules: Vec<String> = all
+        .iter()
+        .filter_map(|l| l.strip_prefix("crate: pub mod "))
+        .filter(|m| !m.contains(' ') && src.join(format!("{m}.rs")).exists())
+        .map(str::to_owned)
+        .collect();
+    for m in modules {
+        let text = fs::read_to_string(src.join(format!("{m}.rs")))
+            .unwrap_or_else(|e| panic!("reading module {m}: {e}"));
+        all.extend(items(&m, &text));
+    }
+    let mut out = all.into_iter().collect::<Vec<_>>().join("\n");
+    out.push('\n');
+    out
+}
+
+#[test]
+fn stable_surface_matches_the_snapshot() {
+    let dir = crate_dir();
+    let now = surface(&dir);
+    let path = dir.join(SNAPSHOT);
+    if std::env::var_os("SEZKP_BLESS_API").is_some() {
+        fs::write(&path, &now).unwrap_or_else(|e| panic!("writing {SNAPSHOT}: {e}"));
+        return;
+    }
+    let was = fs::read_to_string(&path).unwrap_or_default();
+    let was: BTreeSet<&str> = was.lines().collect();
+    let now: BTreeSet<&str> = now.lines().collect();
+    let diff: Vec<String> = was
+        .difference(&now)
+        .map(|l| format!("- {l}"))
+        .chain(now.difference(&was).map(|l| format!("+ {l}")))
+        .collect();
+    assert!(
+        diff.is_empty(),
+        "stable public API changed; if intended, run \
+         `SEZKP_BLESS_API=1 cargo test -p sezkp-core --test public_api`, commit \
+         {SNAPSHOT} and bump the version for removals or changes:\n  {}",
+        diff.join("\n  ")
+    );
+}
+
+#[test]
+fn unstable_items_stay_out_of_the_stable_surface() {
+    let now = surface(&crate_dir());
+    for unstable in [
+        "ProvingBackendStream",
+        "prove_stream",
+        "crate: pub mod composite",
+        "crate: pub mod sample",
+    ] {
+        assert!(
+            !now.contains(unstable),
+            "{unstable} is in the stable surface"
+        );
+    }
+    // The prelude is part of the contract.
+    assert!(now.contains("crate: pub mod prelude :: pub use crate::types::*"));
+}
+
+#[test]
+fn scraper_sees_members_and_skips_gated_items() {
+    let src = r#"
+pub struct Open {
+    pub a: u32,
+    hidden: u8,
+}
+
+#[cfg(feature = "unstable")]
+pub fn later() {}
+
+impl Open {
+    pub fn new(
+        a: u32,
+    ) -> Self {
+        Self { a, hidden: 0 }
+    }
+
+    #[cfg(feature = "unstable")]
+    pub fn experimental(&self) {}
+}
+
+pub enum Mode {
+    Full,
+    Heads(u8),
+}
+
+#[cfg(test)]
+mod tests {
+    pub fn helper() {}
+}
+"#;
+    assert_eq!(
+        items("m", src),
+        [
+            "m: pub struct Open",
+            "m: pub struct Open :: pub a: u32",
+            "m: impl Open :: pub fn new( a: u32, ) -> Self",
+            "m: pub enum Mode",
+            "m: pub enum Mode :: Full",
+            "m: pub enum Mode :: Heads(u8)",
+        ]
+    );
+}
@@ -41,6 +41,19 @@ use std::io::Read;
 /// cross-protocol collisions across the workspace.
 const TRANSCRIPT_PREFIX: &[u8] = b"sezkp.transcript.v0";
 
+/// Length prefix used in transcript framing: a `u32` (LE) on every platform,
+/// so `usize` never reaches the hash.
+///
+/// # Panics
+/// If `n` does not fit a `u32` (an input of 4 GiB or more), rather than
+/// silently truncating it.
+#[allow(clippy::expect_used)]
+fn len_prefix(n: usize) -> [u8; 4] {
+    u32::try_from(n)
+        .expect("transcript inputs must be shorter than 4 GiB")
+        .to_le_bytes()
+}
+
 /// Transcript interface used across backends.
 ///
 /// Implementations must apply **domain separation** for both absorbs and challenges.
@@ -82,7 +95,7 @@ impl Blake3Transcript {
         let mut st = Hasher::new();
         // Seed with a fixed prefix and the domain string length+bytes.
         st.update(TRANSCRIPT_PREFIX);
-        st.update(&(domain_sep.len() as u32).to_le_bytes());
+        st.update(&len_prefix(domain_sep.len()));
         st.update(domain_sep.as_bytes());
         Self { st }
     }
@@ -93,9 +106,9 @@ impl Transcript for Blake3Transcript {
         // Domain separation for each absorb:
         //   tag "absorb", label length+bytes, payload length+bytes.
         self.st.update(b"absorb");
-        self.st.update(&(label.len() as u32).to_le_bytes());
+        self.st.update(&len_prefix(label.len()));
         self.st.update(label.as_bytes());
-        self.st.update(&(bytes.len() as u32).to_le_bytes());
+        self.st.update(&len_prefix(bytes.len()));
         self.st.update(bytes);
     }
 
@@ -103,7 +116,7 @@ impl Transcript for Blake3Transcript {
         // Derive an XOF stream from current state + label.
         let mut st = self.st.clone();
         st.update(b"challenge");
-        st.update(&(label.len() as u32).to_le_bytes());
+        st.update(&len_prefix(label.len()));
         st.update(label.as_bytes());
 
         let mut rdr = st.finalize_xof();
@@ -116,7 +129,7 @@ impl Transcript for Blake3Transcript {
 
         // Model transcript "forward progress" after a challenge so future challenges differ.
         self.st.update(b"after_challenge");
-        self.st.update(&(label.len() as u32).to_le_bytes());
+        self.st.update(&len_prefix(label.len()));
         self.st.update(label.as_bytes());
 
         out
@@ -244,6 +257,20 @@ mod tests {
         assert_eq!(x, y);
     }
 
+    #[test]
+    fn framing_is_fixed_width() {
+        // Pinned output: a platform-sized length prefix would change it.
+        let mut t = Blake3Transcript::new("dom");
+        t.absorb("x", b"payload");
+        t.absorb_u64("n", 1 << 40);
+        let hex: String = t
+            .challenge_bytes("c", 16)
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect();
+        assert_eq!(hex, "e27cdbd77255b8faf1806f36b708be52");
+    }
+
     #[test]
     fn state_progression_changes_future_challenges() {
         // After a challenge, the internal hasher is advanced; the next challenge differs.
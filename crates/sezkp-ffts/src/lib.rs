@@ -3,7 +3,7 @@
 //! - `Fp64<P>`: prime field modulo a 64-bit prime `P` (const generic).
 //! - `dft`/`idft`: naive **O(n²)** DFT using a provided primitive root `omega`.
 //! - Goldilocks helpers: 64-bit field `p = 2^64 - 2^32 + 1`, primitive 2^k roots.
-//! - Modules: `domain`, `ntt`, `twiddle`, `coset` for power-of-two NTTs and LDEs.
+//! - Modules: `domain`, `ntt`, `roots`, `twiddle`, `coset` for power-of-two NTTs and LDEs.
 //!
 //! This crate is intentionally small and straightforward—great for benchmarks,
 //! pedagogy, and scaffolded protocol experiments.
@@ -25,6 +25,7 @@ pub use domain::{pow2_domain, Pow2Domain};
 
 pub mod coset;
 pub mod ntt;     // in-place NTT/INTT and (eval <-> coeff) helpers
+pub mod roots;   // cached Goldilocks 2^k roots of unity (and inverses)
 pub mod twiddle; // stage twiddle helpers
 
 use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
@@ -96,6 +97,37 @@ impl<const P: u64> Fp64<P> {
         acc
     }
 
+    /// Exponentiation by a `u64` exponent (alias of [`Self::pow`]).
+    #[inline]
+    #[must_use]
+    pub fn exp_u64(self, e: u64) -> Self {
+        self.pow(e)
+    }
+
+    /// `self^(2^k)` via `k` repeated squarings.
+    #[inline]
+    #[must_use]
+    pub fn exp_power_of_2(self, k: usize) -> Self {
+        let mut acc = self;
+        for _ in 0..k {
+            acc *= acc;
+        }
+        acc
+    }
+
+    /// The first `n` powers `[1, self, self^2, …, self^(n-1)]`.
+    #[inline]
+    #[must_use]
+    pub fn powers(self, n: usize) -> Vec<Self> {
+        let mut out = Vec::with_capacity(n);
+        let mut cur = Self::one();
+        for _ in 0..n {
+            out.push(cur);
+            cur *= self;
+        }
+        out
+    }
+
     /// Multiplicative inverse (P assumed prime).
     #[inline]
     #[must_use]
@@ -186,6 +218,39 @@ impl<const P: u64> Neg for Fp64<P> {
     }
 }
 
+/// Invert every element of `xs` in place using Montgomery's trick.
+///
+/// Costs one field inversion plus `3(n-1)` multiplications instead of `n`
+/// inversions. Zero entries are left as zero (they have no inverse) and do
+/// not poison the rest of the batch.
+pub fn batch_inverse<const P: u64>(xs: &mut [Fp64<P>]) {
+    if xs.is_empty() {
+        return;
+    }
+
+    // Prefix products over the non-zero entries: prefix[i] = Π_{j<=i, x_j≠0} x_j.
+    let mut prefix = Vec::with_capacity(xs.len());
+    let mut acc = Fp64::<P>::one();
+    for x in xs.iter() {
+        if *x != Fp64::<P>::zero() {
+            acc *= *x;
+        }
+        prefix.push(acc);
+    }
+
+    // Single inversion of the full product, then peel off one element at a time.
+    let mut inv_acc = acc.inv();
+    for i in (0..xs.len()).rev() {
+        let x = xs[i];
+        if x == Fp64::<P>::zero() {
+            continue;
+        }
+        let before = if i == 0 { Fp64::<P>::one() } else { prefix[i - 1] };
+        xs[i] = inv_acc * before;
+        inv_acc *= x;
+    }
+}
+
 /// Naive DFT: `y_k = Σ_j a_j * ω^(j*k)`. Complexity **O(n²)**.
 #[must_use]
 pub fn dft<const P: u64>(a: &[Fp64<P>], omega: Fp64<P>) -> Vec<Fp64<P>> {
@@ -233,12 +298,13 @@ pub type Goldilocks = Fp64<GOLDILOCKS>;
 
 /// Return a primitive `2^k` root of unity in Goldilocks.
 /// (Uses `g=7`; Goldilocks has 2-adicity 32.)
+///
+/// Served from the process-wide table in [`roots`], so repeated calls are a
+/// lookup rather than a 64-bit exponentiation.
 #[must_use]
 pub fn goldilocks_primitive_root_2exp(k: u32) -> Goldilocks {
     assert!(k <= 32, "k too large for Goldilocks 2-adicity");
-    let g = Goldilocks::from_u64(7);
-    let exp = ((GOLDILOCKS - 1) >> k) as u64;
-    g.pow(exp)
+    roots::root_2exp(k)
 }
 
 #[cfg(test)]
@@ -265,6 +331,32 @@ mod tests {
         assert_eq!(a, a2);
     }
 
+    #[test]
+    fn batch_inverse_matches_pointwise() {
+        let mut xs: Vec<Fp64<P>> = (0..20).map(|i| Fp64::<P>(i % P)).collect();
+        let expect: Vec<Fp64<P>> = xs
+            .iter()
+            .map(|x| if x.0 == 0 { *x } else { x.inv() })
+            .collect();
+        batch_inverse(&mut xs);
+        assert_eq!(xs, expect);
+
+        let mut empty: Vec<Fp64<P>> = Vec::new();
+        batch_inverse(&mut empty);
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn exp_helpers_agree_with_pow() {
+        let g = Goldilocks::from_u64(7);
+        assert_eq!(g.exp_u64(12_345), g.pow(12_345));
+        assert_eq!(g.exp_power_of_2(5), g.pow(32));
+        let ps = g.powers(6);
+        for (i, p) in ps.iter().enumerate() {
+            assert_eq!(*p, g.pow(i as u64));
+        }
+    }
+
     #[test]
     fn goldi_roots_pow2() {
         for k in 1..=8 {
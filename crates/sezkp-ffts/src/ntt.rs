@@ -5,15 +5,18 @@
 //!
 //! Design notes:
 //! - Uses bit-reversed reordering (DIT) and per-stage twiddle tables.
-////! - Twiddles are computed per call and kept local; for repeated sizes you may
-//!   consider caching twiddles externally.
+//! - Stage roots come from the cached table in [`crate::roots`]; the per-stage
+//!   twiddle vectors are still computed per call and kept local.
 //! - Length `n` must be a power of two.
 
 #![forbid(unsafe_code)]
 #![deny(rust_2018_idioms)]
 #![warn(clippy::all, clippy::pedantic, clippy::nursery)]
 
-use crate::{goldilocks_primitive_root_2exp, Goldilocks as F};
+use crate::{
+    roots::{root_2exp, root_2exp_inv},
+    Goldilocks as F,
+};
 
 #[inline]
 fn bitrev(mut x: usize, bits: usize) -> usize {
@@ -44,7 +47,7 @@ fn build_twiddles_forward(n_log2: usize) -> Vec<Vec<F>> {
     let mut out = Vec::with_capacity(n_log2);
     for s in 1..=n_log2 {
         let half = 1usize << (s - 1);
-        let w_len = goldilocks_primitive_root_2exp(s as u32);
+        let w_len = root_2exp(s as u32);
         let mut ws = Vec::with_capacity(half);
         let mut w = F::from_u64(1);
         for _ in 0..half {
@@ -61,7 +64,7 @@ fn build_twiddles_inverse(n_log2: usize) -> Vec<Vec<F>> {
     let mut out = Vec::with_capacity(n_log2);
     for s in 1..=n_log2 {
         let half = 1usize << (s - 1);
-        let w_len_inv = goldilocks_primitive_root_2exp(s as u32).inv();
+        let w_len_inv = root_2exp_inv(s as u32);
         let mut ws = Vec::with_capacity(half);
         let mut w = F::from_u64(1);
         for _ in 0..half {
@@ -0,0 +1,75 @@
+//! Cached power-of-two roots of unity for Goldilocks.
+//!
+//! Every NTT stage, LDE and FRI fold needs `ω_k = g^((p-1)/2^k)` for some
+//! `k ≤ 32`. Each one is a full 64-bit exponentiation, so we compute the whole
+//! ladder once (by repeated squaring from `ω_32`) and serve lookups from a
+//! process-wide table. Inverses are built with a single batch inversion.
+
+#![forbid(unsafe_code)]
+#![deny(rust_2018_idioms)]
+#![warn(clippy::all, clippy::pedantic, clippy::nursery)]
+
+use std::sync::OnceLock;
+
+use crate::{batch_inverse, Goldilocks as F, GOLDILOCKS};
+
+/// Goldilocks 2-adicity: the largest `k` with a primitive `2^k` root.
+pub const TWO_ADICITY: u32 = 32;
+
+struct RootTable {
+    fwd: [F; TWO_ADICITY as usize + 1],
+    inv: [F; TWO_ADICITY as usize + 1],
+}
+
+fn table() -> &'static RootTable {
+    static TABLE: OnceLock<RootTable> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let n = TWO_ADICITY as usize;
+        let mut fwd = [F::one(); TWO_ADICITY as usize + 1];
+        // ω_32 = 7^((p-1)/2^32); ω_{k-1} = ω_k².
+        fwd[n] = F::from_u64(7).pow((GOLDILOCKS - 1) >> TWO_ADICITY);
+        for k in (0..n).rev() {
+            fwd[k] = fwd[k + 1] * fwd[k + 1];
+        }
+        let mut inv = fwd;
+        batch_inverse(&mut inv);
+        RootTable { fwd, inv }
+    })
+}
+
+/// Primitive `2^k` root of unity `ω_k` (cached).
+///
+/// # Panics
+/// Panics if `k > 32`.
+#[inline]
+#[must_use]
+pub fn root_2exp(k: u32) -> F {
+    assert!(k <= TWO_ADICITY, "k too large for Goldilocks 2-adicity");
+    table().fwd[k as usize]
+}
+
+/// Inverse `ω_k^{-1}` of the primitive `2^k` root of unity (cached).
+///
+/// # Panics
+/// Panics if `k > 32`.
+#[inline]
+#[must_use]
+pub fn root_2exp_inv(k: u32) -> F {
+    assert!(k <= TWO_ADICITY, "k too large for Goldilocks 2-adicity");
+    table().inv[k as usize]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cached_roots_match_direct_exponentiation() {
+        let g = F::from_u64(7);
+        for k in 0..=TWO_ADICITY {
+            let direct = g.pow((GOLDILOCKS - 1) >> k);
+            assert_eq!(root_2exp(k), direct, "k = {k}");
+            assert_eq!(root_2exp(k) * root_2exp_inv(k), F::one(), "k = {k}");
+        }
+    }
+}
@@ -4,12 +4,17 @@
 //! 1) `shift = 1` matches plain NTT on the base subgroup.
 //! 2) Scaling-by-`shift^j` in coefficient space equals evaluating the original
 //!    polynomial on the multiplicative coset with that `shift`.
+//! 3) Low-degree polynomials evaluated on a blown-up coset agree pointwise with
+//!    naive Horner evaluation at `shift · ω^i`.
 
 #![forbid(unsafe_code)]
 #![deny(rust_2018_idioms)]
 #![allow(clippy::needless_range_loop, clippy::cast_possible_truncation)]
 
-use sezkp_ffts::{coset::evaluate_on_coset_pow2, ntt::evaluate_on_pow2_domain, Goldilocks as F};
+use sezkp_ffts::{
+    coset::evaluate_on_coset_pow2, goldilocks_primitive_root_2exp, ntt::evaluate_on_pow2_domain,
+    Goldilocks as F,
+};
 
 #[inline]
 #[track_caller]
@@ -62,3 +67,28 @@ fn coset_scaling_invariant() {
         );
     }
 }
+
+/// Horner evaluation of `Σ c_j x^j`.
+fn horner(coeffs: &[F], x: F) -> F {
+    coeffs.iter().rev().fold(F::zero(), |acc, &c| acc * x + c)
+}
+
+#[test]
+fn coset_lde_matches_naive_horner() {
+    for (k, blow_log2, shift) in [(1, 1, 3u64), (3, 2, 7), (6, 1, 11), (8, 3, 3), (10, 1, 5)] {
+        let n = 1usize << k;
+        let lde_log2 = k + blow_log2;
+        let coeffs = det_coeffs(n);
+
+        let shift = F::from_u64(shift);
+        let evals = evaluate_on_coset_pow2(&coeffs, lde_log2, shift);
+        assert_eq!(evals.len(), 1usize << lde_log2);
+
+        let w = goldilocks_primitive_root_2exp(lde_log2 as u32);
+        let mut x = shift;
+        for (i, &e) in evals.iter().enumerate() {
+            assert_eq!(e, horner(&coeffs, x), "n = 2^{k}, blow = 2^{blow_log2}, i = {i}");
+            x *= w;
+        }
+    }
+}
@@ -0,0 +1,268 @@
+//! `sezkp-fixtures`: deterministic test fixtures.
+//!
+//! Every fixture file is derived from a [`FixtureSpec`] (trace seed and
+//! shape): the trace, its blocks (CBOR and JSONL), the commit manifest, and
+//! one proof artifact per backend (STARK v0, STARK v1, fold), plus the
+//! challenges the STARK v1 verifier draws ([`CHALLENGES_FILE`]). Generation is
+//! hermetic — no clock, no randomness beyond the seeds, and no backend knobs
+//! from the environment — so re-running it reproduces the same bytes.
+//!
+//! [`generate`] writes the files plus a digest manifest ([`MANIFEST_FILE`])
+//! listing each file's BLAKE3 hash and size. The repository tracks only the
+//! manifest (`fixtures/FIXTURES.json`), so any change to the test data shows
+//! up as a reviewable diff of digests. [`check`] regenerates into a scratch
+//! directory and reports what no longer matches. When a format changes on
+//! purpose, re-run `cargo run -p sezkp-fixtures` and commit the manifest.
+
+#![forbid(unsafe_code)]
+#![deny(rust_2018_idioms)]
+#![warn(
+    missing_docs,
+    clippy::all,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::unwrap_used,
+    clippy::expect_used
+)]
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use anyhow::{ensure, Context, Result};
+use serde::{Deserialize, Serialize};
+use sezkp_core::io::write_proof_artifact_cbor;
+use sezkp_core::io_jsonl::write_block_summaries_jsonl;
+use sezkp_core::{write_block_summaries_cbor, BlockSummary, ProofArtifact, ProvingBackend};
+use sezkp_fold::FoldBackend;
+use sezkp_merkle::{commit_blocks, write_manifest_cbor};
+use sezkp_stark::v1::{challenges, proof::ProofV1};
+use sezkp_stark::{StarkIOP, StarkV1};
+use sezkp_trace::{
+    generator::generate_trace_seeded, io::write_trace_cbor, partition::partition_trace,
+};
+
+/// Name of the digest manifest at the root of a fixtures directory.
+pub const MANIFEST_FILE: &str = "FIXTURES.json";
+
+/// Per-set file holding the STARK v1 proof's Fiat–Shamir challenges, as
+/// JSON (`sezkp_stark::v1::challenges::ChallengeTrace`).
+pub const CHALLENGES_FILE: &str = "challenges-stark-v1.json";
+
+/// Version of the digest manifest layout.
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// Environment variable prefix of the backend knobs generation refuses.
+const ENV_PREFIX: &str = "SEZKP_";
+
+/// Inputs of one fixture set, written to a subdirectory named after it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FixtureSpec {
+    /// Subdirectory name.
+    pub name: String,
+    /// Trace generator seed.
+    pub seed: u64,
+    /// Trace length in steps.
+    pub t: u64,
+    /// Number of work tapes.
+    pub tau: u8,
+    /// Steps per block.
+    pub b: u32,
+}
+
+/// The fixture sets the repository keeps.
+///
+/// Shapes are ones every backend proves and verifies as-is: power-of-two
+/// row counts for STARK v1, power-of-two block counts for fold.
+#[must_use]
+pub fn specs() -> Vec<FixtureSpec> {
+    vec![
+        FixtureSpec {
+            name: "small".to_owned(),
+            seed: 1,
+            t: 256,
+            tau: 2,
+            b: 128,
+        },
+        FixtureSpec {
+            name: "wide".to_owned(),
+            seed: 2,
+            t: 256,
+            tau: 3,
+            b: 64,
+        },
+    ]
+}
+
+/// Digest of one fixture file.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileDigest {
+    /// BLAKE3 hash of the file, lowercase hex.
+    pub blake3: String,
+    /// File size in bytes.
+    pub bytes: u64,
+}
+
+impl FileDigest {
+    fn of(path: &Path) -> Result<Self> {
+        let data = fs::read(path).with_context(|| format!("read {}", path.display()))?;
+        Ok(Self {
+            blake3: blake3::hash(&data).to_hex().to_string(),
+            bytes: data.len() as u64,
+        })
+    }
+}
+
+/// Contents of [`MANIFEST_FILE`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FixtureManifest {
+    /// Manifest layout version ([`MANIFEST_VERSION`]).
+    pub version: u32,
+    /// The specs the files were generated from.
+    pub specs: Vec<FixtureSpec>,
+    /// Digest per file, keyed by `/`-separated path relative to the directory.
+    pub files: BTreeMap<String, FileDigest>,
+}
+
+/// Regenerate every fixture into `out` and write its [`MANIFEST_FILE`].
+///
+/// Each proof is verified before it is written.
+///
+/// # Errors
+/// Fails if any `SEZKP_*` variable is set (backend knobs would change the
+/// proofs), or if generating, proving, verifying or writing fails.
+pub fn generate(out: &Path) -> Result<FixtureManifest> {
+    let knobs: Vec<String> = std::env::vars_os()
+        .filter_map(|(k, _)| k.into_string().ok())
+        .filter(|k| k.starts_with(ENV_PREFIX))
+        .collect();
+    ensure!(
+        knobs.is_empty(),
+        "fixtures use default backend options; unset {}",
+        knobs.join(", ")
+    );
+
+    fs::create_dir_all(out).with_context(|| format!("create {}", out.display()))?;
+    let specs = specs();
+    let mut files = BTreeMap::new();
+    for spec in &specs {
+        for rel in write_spec(out, spec).with_context(|| format!("fixture {:?}", spec.name))? {
+            let digest = FileDigest::of(&out.join(&rel))?;
+            files.insert(rel, digest);
+        }
+    }
+
+    let manifest = FixtureManifest {
+        version: MANIFEST_VERSION,
+        specs,
+        files,
+    };
+    let mut json = serde_json::to_string_pretty(&manifest)?;
+    json.push('\n');
+    let path = out.join(MANIFEST_FILE);
+    fs::write(&path, json).with_context(|| format!("write {}", path.display()))?;
+    Ok(manifest)
+}
+
+/// Write one fixture set; returns the written paths relative to `out`.
+fn write_spec(out: &Path, spec: &FixtureSpec) -> Result<Vec<String>> {
+    let dir = out.join(&spec.name);
+    fs::create_dir_all(&dir).with_context(|| format!("create {}", dir.display()))?;
+    let mut written = Vec::new();
+    let mut file = |name: &str| {
+        written.push(format!("{}/{name}", spec.name));
+        dir.join(name)
+    };
+
+    let trace = generate_trace_seeded(spec.t, spec.tau, spec.seed);
+    write_trace_cbor(file("trace.cbor"), &trace)?;
+    let blocks = partition_trace(&trace, spec.b);
+    write_block_summaries_cbor(file("blocks.cbor"), &blocks)?;
+    write_block_summaries_jsonl(file("blocks.jsonl"), &blocks)?;
+    let manifest = commit_blocks(&blocks);
+    write_manifest_cbor(file("manifest.cbor"), &manifest)?;
+
+    let root = manifest.root;
+    let stark_v0 = proved::<StarkIOP>(&blocks, root).context("stark-v0")?;
+    write_proof_artifact_cbor(file("proof-stark-v0.cbor"), &stark_v0)?;
+    let mut stark_v1 = proved::<StarkV1>(&blocks, root).context("stark-v1")?;
+    // The prover profile holds wall-clock timings; verifiers ignore it.
+    if let Some(meta) = stark_v1.meta.as_object_mut() {
+        meta.remove("profile");
+    }
+    write_proof_artifact_cbor(file("proof-stark-v1.cbor"), &stark_v1)?;
+    let proof = ProofV1::decode(&stark_v1.proof_bytes)?;
+    let mut json = serde_json::to_string_pretty(&challenges::replay(&proof)?)?;
+    json.push('\n');
+    let path = file(CHALLENGES_FILE);
+    fs::write(&path, json).with_context(|| format!("write {}", path.display()))?;
+    let fold = proved::<FoldBackend>(&blocks, root).context("fold")?;
+    write_proof_artifact_cbor(file("proof-fold.cbor"), &fold)?;
+
+    Ok(written)
+}
+
+/// Prove with `B` and check the result before it becomes a fixture.
+fn proved<B: ProvingBackend>(blocks: &[BlockSummary], root: [u8; 32]) -> Result<ProofArtifact> {
+    let artifact = B::prove(blocks, root)?;
+    B::verify(&artifact, blocks, root).context("fresh proof does not verify")?;
+    Ok(artifact)
+}
+
+/// Compare the fixtures in `dir` against a fresh generation.
+///
+/// Only [`MANIFEST_FILE`] is required: its recorded digests must equal the
+/// fresh ones, and any fixture file present in `dir` must match too (the
+/// files themselves are build outputs and may be absent). Returns one line
+/// per problem, empty when everything matches.
+///
+/// # Errors
+/// Fails if the manifest cannot be read or regeneration fails.
+pub fn check(dir: &Path) -> Result<Vec<String>> {
+    static SCRATCH: AtomicU32 = AtomicU32::new(0);
+
+    let path = dir.join(MANIFEST_FILE);
+    let recorded: FixtureManifest = serde_json::from_slice(
+        &fs::read(&path).with_context(|| format!("read {}", path.display()))?,
+    )
+    .with_context(|| format!("parse {}", path.display()))?;
+
+    let scratch: PathBuf = std::env::temp_dir().join(format!(
+        "sezkp-fixtures-check-{}-{}",
+        std::process::id(),
+        SCRATCH.fetch_add(1, Ordering::Relaxed)
+    ));
+    let _ = fs::remove_dir_all(&scratch);
+    let fresh = generate(&scratch);
+    let _ = fs::remove_dir_all(&scratch);
+    let fresh = fresh?;
+
+    let mut problems = Vec::new();
+    if (recorded.version, &recorded.specs) != (fresh.version, &fresh.specs) {
+        problems.push(format!("{MANIFEST_FILE}: recorded specs are out of date"));
+    }
+    for rel in recorded
+        .files
+        .keys()
+        .filter(|rel| !fresh.files.contains_key(*rel))
+    {
+        problems.push(format!("{rel}: recorded but no longer generated"));
+    }
+    for (rel, want) in &fresh.files {
+        match recorded.files.get(rel) {
+            None => problems.push(format!("{rel}: not recorded")),
+            Some(have) if have != want => {
+                problems.push(format!(
+                    "{rel}: recorded digest differs from a fresh generation"
+                ));
+            }
+            Some(_) => {}
+        }
+        let on_disk = dir.join(rel);
+        if on_disk.exists() && &FileDigest::of(&on_disk)? != want {
+            problems.push(format!("{rel}: file differs from a fresh generation"));
+        }
+    }
+    Ok(problems)
+}
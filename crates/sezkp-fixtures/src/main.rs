@@ -0,0 +1,76 @@
+//! Regenerate (or check) the repository's test fixtures.
+//!
+//! Writes every fixture set from [`sezkp_fixtures::specs`] into `--out`
+//! (default `fixtures`) together with its `FIXTURES.json` digest manifest.
+//! With `--check`, nothing is written: the fixtures in `--out` are compared
+//! against a fresh generation and the command fails on any difference.
+//!
+//! Usage (from the repository root):
+//!   cargo run -p sezkp-fixtures
+//!   cargo run -p sezkp-fixtures -- --check
+
+#![forbid(unsafe_code)]
+#![deny(rust_2018_idioms)]
+#![warn(
+    missing_docs,
+    clippy::all,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::unwrap_used,
+    clippy::expect_used
+)]
+
+use std::env;
+use std::path::PathBuf;
+
+use anyhow::{bail, Result};
+use sezkp_fixtures::{check, generate, MANIFEST_FILE};
+
+/// Parse a string flag like `--out path`, falling back to `default`.
+fn parse_str(name: &str, default: &str) -> String {
+    let mut it = env::args().skip(1);
+    let mut last = None;
+    while let Some(k) = it.next() {
+        if k == format!("--{name}") {
+            last = it.next();
+            break;
+        }
+    }
+    last.unwrap_or_else(|| default.to_string())
+}
+
+/// Whether a bare flag like `--check` is present.
+fn has_flag(name: &str) -> bool {
+    env::args().skip(1).any(|k| k == format!("--{name}"))
+}
+
+fn main() -> Result<()> {
+    let out = PathBuf::from(parse_str("out", "fixtures"));
+
+    if has_flag("check") {
+        let problems = check(&out)?;
+        if !problems.is_empty() {
+            for p in &problems {
+                eprintln!("  {p}");
+            }
+            bail!(
+                "{} fixture problem(s) in {}; regenerate with `cargo run -p sezkp-fixtures`",
+                problems.len(),
+                out.display()
+            );
+        }
+        println!("Fixtures in {} are up to date.", out.display());
+        return Ok(());
+    }
+
+    let manifest = generate(&out)?;
+    for (rel, d) in &manifest.files {
+        println!("{rel:<28} {:>8} B  {}", d.bytes, d.blake3);
+    }
+    println!(
+        "Wrote {} files → {} ({MANIFEST_FILE})",
+        manifest.files.len(),
+        out.display()
+    );
+    Ok(())
+}
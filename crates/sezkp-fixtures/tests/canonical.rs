@@ -0,0 +1,109 @@
+//! Canonical encodings: pinned digests of everything that gets hashed.
+//!
+//! Every hashed integer has a fixed width and little-endian byte order, and
+//! lengths and indices are widened from `usize` before hashing. The inputs
+//! below use values past `u32::MAX` and odd domain sizes, so these vectors
+//! change if a platform-sized integer leaks into a hash or a challenge is
+//! reduced through `usize` (run them on a 32-bit or big-endian target, e.g.
+//! `cross test --target i686-unknown-linux-gnu`).
+
+#![allow(clippy::unwrap_used)]
+
+use sezkp_core::boundary::{boundary_left_tail_digest, interface_boundary_digest};
+use sezkp_core::claims::{claims_digest, Claim};
+use sezkp_core::frontier::node_hash;
+use sezkp_core::redact::{cell_commitment, cell_salt};
+use sezkp_core::BlockSummary;
+use sezkp_crypto::{Blake3Transcript, Transcript};
+use sezkp_fold::api::commit_pi;
+use sezkp_fold::are::Pi;
+use sezkp_merkle::{commit_blocks, leaf_hash};
+use sezkp_stark::v1::params;
+use sezkp_trace::{generator::generate_trace, partition::partition_trace};
+
+fn blocks() -> Vec<BlockSummary> {
+    let mut blocks = partition_trace(&generate_trace(24, 2), 8);
+    // Push the scalars past 32 bits and the windows below zero.
+    for b in &mut blocks {
+        b.step_lo += 1 << 40;
+        b.step_hi += 1 << 40;
+        b.in_head_in = -(1 << 35);
+        for w in &mut b.windows {
+            w.left -= 1 << 34;
+        }
+    }
+    blocks
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[test]
+fn block_digests_are_pinned() {
+    let blocks = blocks();
+    assert_eq!(
+        hex(&leaf_hash(&blocks[0])),
+        "1ded615df5ac803aa26ed880024a882e46581286fe0495f7e97035e1df4a1eb3"
+    );
+    assert_eq!(
+        hex(&commit_blocks(&blocks).root),
+        "ee0464f62b9e1c15be6442f5264181703c42e169507bd95f8e9112c8e4a53c8b"
+    );
+    assert_eq!(
+        hex(&node_hash(&[1; 32], &[2; 32])),
+        "8d67bc7836d128b108be2c965538f37bbcee3e7503e35e58fbb0446432e05206"
+    );
+    assert_eq!(
+        hex(&interface_boundary_digest(&blocks[0], &blocks[1])),
+        "c91953307d1b0021a2d15688cd41ea6c28e9c16fc247ab9688410fe1f1959f51"
+    );
+    assert_eq!(
+        hex(&boundary_left_tail_digest(&blocks[1], 4)),
+        "9704e56db2fdccbcbd5bc3877834968473ccc56853aa0a01c6a2e24cedfb06fc"
+    );
+}
+
+#[test]
+fn protocol_digests_are_pinned() {
+    let claims = [Claim::new("output", "42"), Claim::new("program", "ab12")];
+    assert_eq!(
+        hex(&claims_digest(&claims).unwrap()),
+        "6f123dfc9b1558674c5e5defe75d09bdcfc70da2cb6c70bf01f9d5b3e5b5ddd6"
+    );
+
+    let salt = cell_salt(&[7; 32], 3, (1 << 40) + 5, 1);
+    assert_eq!(
+        hex(&salt),
+        "887e22f2619e9a78d906fc27ec273df12f427a51f841921ed4bf182623beb729"
+    );
+    assert_eq!(
+        hex(&cell_commitment(&salt, 0x1234)),
+        "29e7c90bd71170458ba6b2a17198d588b7c0f945b967e49cc7fab270ce5c96af"
+    );
+
+    let pi = Pi {
+        ctrl_in: 1,
+        ctrl_out: u32::MAX,
+        flags: 1,
+        ..Pi::default()
+    };
+    assert_eq!(
+        hex(&commit_pi(&pi).0),
+        "8c44ad15fbaa93854c8b7ce6c91a3c1296c665abc0c21c429384ac1cc2a6880e"
+    );
+}
+
+#[test]
+fn query_positions_reduce_in_u64() {
+    // `n` is not a power of two, so truncating the challenge word to 32 bits
+    // before the reduction would draw different positions.
+    let n = 3 * (1 << 20) + 1;
+    let mut tr = Blake3Transcript::new(params::DS_V1_DOMAIN);
+    tr.absorb_u64("n", n as u64);
+    let queries = params::derive_queries(&mut tr, n, 8);
+    assert_eq!(
+        queries,
+        vec![341856, 774820, 2668221, 2756889, 99039, 221559, 1629363, 2757981]
+    );
+}
@@ -0,0 +1,87 @@
+//! Fixture generation is reproducible, and the tracked digest manifest
+//! matches what the current code generates.
+
+#![allow(clippy::unwrap_used)]
+
+use std::fs;
+use std::path::PathBuf;
+
+use sezkp_core::io::read_proof_artifact_cbor;
+use sezkp_core::{read_block_summaries_cbor, ProvingBackend};
+use sezkp_fixtures::{check, generate, specs, FixtureManifest, CHALLENGES_FILE, MANIFEST_FILE};
+use sezkp_stark::v1::challenges::{self, ChallengeTrace};
+use sezkp_stark::v1::proof::ProofV1;
+use sezkp_stark::StarkV1;
+
+fn scratch(tag: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("sezkp_fixtures_{tag}_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn tracked_manifest_matches_a_fresh_generation() {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../fixtures");
+    let problems = check(&dir).unwrap();
+    assert!(
+        problems.is_empty(),
+        "fixtures changed; if intended, run `cargo run -p sezkp-fixtures` and commit \
+         fixtures/{MANIFEST_FILE}:\n  {}",
+        problems.join("\n  ")
+    );
+}
+
+#[test]
+fn check_reports_edited_files_and_digests() {
+    let dir = scratch("edit");
+    let manifest = generate(&dir).unwrap();
+
+    // Removed files are not a problem (they are build outputs), but edited
+    // files and edited digests are.
+    fs::remove_file(dir.join("small/trace.cbor")).unwrap();
+    let proof = dir.join("small/proof-fold.cbor");
+    let mut bytes = fs::read(&proof).unwrap();
+    *bytes.last_mut().unwrap() ^= 1;
+    fs::write(&proof, bytes).unwrap();
+
+    let mut edited: FixtureManifest = manifest;
+    edited.files.get_mut("wide/blocks.cbor").unwrap().bytes += 1;
+    fs::write(
+        dir.join(MANIFEST_FILE),
+        serde_json::to_vec_pretty(&edited).unwrap(),
+    )
+    .unwrap();
+
+    let problems = check(&dir).unwrap();
+    assert_eq!(problems.len(), 2, "{problems:?}");
+    assert!(problems[0].starts_with("small/proof-fold.cbor: file differs"));
+    assert!(problems[1].starts_with("wide/blocks.cbor: recorded digest differs"));
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn verifier_draws_the_recorded_challenges() {
+    let dir = scratch("challenges");
+    generate(&dir).unwrap();
+    for spec in specs() {
+        let set = dir.join(&spec.name);
+        let art = read_proof_artifact_cbor(set.join("proof-stark-v1.cbor")).unwrap();
+        let proof = ProofV1::decode(&art.proof_bytes).unwrap();
+        let recorded: ChallengeTrace =
+            serde_json::from_slice(&fs::read(set.join(CHALLENGES_FILE)).unwrap()).unwrap();
+
+        // The verifier draws through the same schedule and accepts the rows
+        // and positions the proof opens; the trace lists exactly those.
+        let blocks = read_block_summaries_cbor(set.join("blocks.cbor")).unwrap();
+        StarkV1::verify(&art, &blocks, art.manifest_root).unwrap();
+        assert_eq!(
+            challenges::replay(&proof).unwrap(),
+            recorded,
+            "{}",
+            spec.name
+        );
+        let rows: Vec<usize> = proof.queries.iter().map(|q| q.row).collect();
+        assert_eq!(recorded.row_queries, rows, "{}", spec.name);
+    }
+    let _ = fs::remove_dir_all(dir);
+}
@@ -0,0 +1,132 @@
+//! Stream digests for external anchoring.
+//!
+//! A finished fold stream (`Header, Item*, Footer`) is summarized by one
+//! 32-byte `stream_digest` that external systems (a blockchain, a
+//! transparency log) can anchor:
+//!
+//! ```text
+//! chain_0 = BLAKE3(DS_CHAIN)
+//! chain_i = BLAKE3(chain_{i-1} ‖ len_i ‖ value_i)      header and items
+//! footer  = BLAKE3(DS_FOOTER ‖ footer value)
+//! digest  = BLAKE3(DS_ANCHOR ‖ n_values ‖ chain_n ‖ footer)
+//! ```
+//!
+//! Values are hashed as the raw CBOR bytes on disk, so the digest pins the
+//! exact file rather than its decoded contents, and computing it needs no
+//! gadget types or proof checks: one cheap pass that a verifier runs before
+//! the full [`crate::verify::verify_stream`].
+//!
+//! `FoldBackend` records the digest as lowercase hex in the artifact's
+//! `meta.stream_digest` and checks it when verifying a streaming artifact.
+
+#![forbid(unsafe_code)]
+#![deny(rust_2018_idioms)]
+#![warn(
+    missing_docs,
+    clippy::all,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::unwrap_used,
+    clippy::expect_used
+)]
+
+use std::io::{BufRead, Read};
+
+use anyhow::{anyhow, ensure, Context, Result};
+use blake3::Hasher;
+use serde::de::IgnoredAny;
+
+use crate::driver::StreamFooter;
+
+/// Domain prefix of the hash chain over header and items.
+const DS_CHAIN: &[u8] = b"sezkp-fold/stream-anchor/chain/v1";
+/// Domain prefix of the footer digest.
+const DS_FOOTER: &[u8] = b"sezkp-fold/stream-anchor/footer/v1";
+/// Domain prefix of the combined stream digest.
+const DS_ANCHOR: &[u8] = b"sezkp-fold/stream-anchor/v1";
+
+/// Reader that keeps a copy of every byte it hands out.
+struct Tee<R> {
+    inner: R,
+    seen: Vec<u8>,
+}
+
+impl<R: Read> Read for Tee<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.seen.extend_from_slice(&buf[..n]);
+        Ok(n)
+    }
+}
+
+/// Compute the `stream_digest` of a complete fold stream.
+///
+/// # Errors
+/// Fails on an I/O error, a malformed CBOR value, fewer than two values, or
+/// a last value that is not a [`StreamFooter`].
+pub fn stream_digest<R: BufRead>(reader: R) -> Result<[u8; 32]> {
+    let mut chain = *blake3::hash(DS_CHAIN).as_bytes();
+    let mut tee = Tee {
+        inner: reader,
+        seen: Vec::new(),
+    };
+    // The footer is only known at EOF, so each value is chained one step late.
+    let mut pending: Option<Vec<u8>> = None;
+    let mut n_values = 0u64;
+    while !tee.inner.fill_buf().context("reading proof stream")?.is_empty() {
+        ciborium::de::from_reader::<IgnoredAny, _>(&mut tee)
+            .with_context(|| format!("decoding stream value #{n_values}"))?;
+        if let Some(prev) = pending.replace(std::mem::take(&mut tee.seen)) {
+            let mut h = Hasher::new();
+            h.update(&chain);
+            h.update(&(prev.len() as u64).to_le_bytes());
+            h.update(&prev);
+            chain = *h.finalize().as_bytes();
+        }
+        n_values += 1;
+    }
+    ensure!(n_values >= 2, "proof stream is incomplete ({n_values} values)");
+    let footer = pending.unwrap_or_default();
+    ciborium::de::from_reader::<StreamFooter, _>(footer.as_slice())
+        .context("proof stream does not end with a footer")?;
+
+    let mut h = Hasher::new();
+    h.update(DS_FOOTER);
+    h.update(&footer);
+    let footer_digest = h.finalize();
+
+    let mut h = Hasher::new();
+    h.update(DS_ANCHOR);
+    h.update(&n_values.to_le_bytes());
+    h.update(&chain);
+    h.update(footer_digest.as_bytes());
+    Ok(*h.finalize().as_bytes())
+}
+
+/// Check that `reader` holds the stream whose digest is `expected`.
+///
+/// # Errors
+/// Fails like [`stream_digest`], or if the digests differ.
+pub fn check_stream_digest<R: BufRead>(reader: R, expected: &[u8; 32]) -> Result<()> {
+    ensure!(
+        &stream_digest(reader)? == expected,
+        "proof stream does not match the recorded stream digest"
+    );
+    Ok(())
+}
+
+/// Lowercase hex rendering of a digest, as stored in artifact metadata.
+#[must_use]
+pub fn digest_hex(digest: &[u8; 32]) -> String {
+    blake3::Hash::from_bytes(*digest).to_hex().to_string()
+}
+
+/// Parse a digest written by [`digest_hex`].
+///
+/// # Errors
+/// Fails unless `s` is 64 hex digits.
+pub fn parse_digest_hex(s: &str) -> Result<[u8; 32]> {
+    blake3::Hash::from_hex(s)
+        .map(|h| *h.as_bytes())
+        .map_err(|e| anyhow!("invalid stream digest {s:?}: {e}"))
+}
@@ -14,8 +14,10 @@
     clippy::expect_used
 )]
 
+use anyhow::{bail, ensure};
 use blake3::Hasher;
 use serde::{Deserialize, Serialize};
+use sezkp_core::boundary::DigestMode;
 
 /// Domain separator used when binding **leaf** proofs to the transcript.
 pub const DS_LEAF: &str = "fold/leaf";
@@ -23,6 +25,19 @@ pub const DS_LEAF: &str = "fold/leaf";
 pub const DS_FOLD: &str = "fold/merge";
 /// Domain separator used when binding **wrap** attestations to the transcript.
 pub const DS_WRAP: &str = "fold/wrap";
+/// Domain separator used by the reference **final wrap** backend.
+pub const DS_FINAL_WRAP: &str = "fold/final-wrap";
+/// Domain separator used when drawing **ARE sample** indices for a leaf.
+pub const DS_ARE_SAMPLE: &str = "fold/are-sample";
+/// Domain separator used when drawing **spot-check** blocks for a bundle.
+pub const DS_SPOT_CHECK: &str = "fold/spot-check";
+
+/// Transcript label under which gadgets absorb the blocks-manifest root.
+///
+/// Every leaf, fold and wrap MAC absorbs the root of the manifest the proof
+/// was made for right after its domain separator, so a gadget proof only
+/// verifies against that manifest.
+pub const LABEL_MANIFEST_ROOT: &str = "manifest.root";
 
 /// Compact commitment for a leaf/subtree in the fold tree.
 ///
@@ -47,8 +62,9 @@ impl Commitment {
 
 /// Commitment to a public projection `π` (opaque on the wire).
 ///
-/// This hides the internal shape of `π` in streamed artifacts; verifiers
-/// check gadget proofs against this commitment instead of raw `π`.
+/// Gadget MACs bind this commitment instead of raw `π`; fold proofs open the
+/// children's `π` against it to prove the parent's (see
+/// [`crate::fold::PiOpening`]).
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct PiCommitment(pub [u8; 32]);
 
@@ -90,6 +106,45 @@ impl Default for FoldMode {
     }
 }
 
+/// Where the Balanced batch driver keeps its endpoint ledger.
+///
+/// See [`crate::ledger`]. Only consulted in [`FoldMode::Balanced`].
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum LedgerStore {
+    /// One in-memory slot per block (O(T) RAM).
+    #[default]
+    Memory,
+    /// Paged temp file of fixed-size records (O(1) RAM, O(T) disk).
+    Disk,
+}
+
+/// How durably the streaming driver writes its output (see [`crate::journal`]).
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Durability {
+    /// Leave flushing to the writer and the OS.
+    #[default]
+    Buffered,
+    /// `fsync` the stream at each sync point.
+    Fsync,
+    /// `fsync` the stream, then journal the driver stack, at each sync point.
+    Journal,
+}
+
+/// When the streaming driver makes its output durable (see [`crate::journal`]).
+///
+/// With neither trigger set, every block is a sync point.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SyncPolicy {
+    /// What a sync point does.
+    pub durability: Durability,
+    /// Sync once this many stream items were written since the last sync
+    /// point (0 = no item trigger).
+    pub every_items: u32,
+    /// Sync once this many seconds passed since the last sync point
+    /// (0 = no time trigger).
+    pub every_secs: u32,
+}
+
 /// Driver options for the folding pipeline.
 ///
 /// These are hints to the driver; gadgets themselves are agnostic.
@@ -101,6 +156,54 @@ pub struct DriverOptions {
     pub wrap_cadence: u32,
     /// Endpoint LRU cache capacity (only used in MinRam mode).
     pub endpoint_cache: u32,
+    /// Endpoint ledger storage (only used in Balanced batch mode).
+    pub ledger: LedgerStore,
+    /// Interior steps each leaf proof opens for a local transition check
+    /// (0 = none; see [`crate::are_sample`]).
+    pub are_samples: u32,
+    /// What the interface digest of each seam hashes (see [`DigestMode`]).
+    #[serde(default)]
+    pub iface_digest: DigestMode,
+    /// Optional final stage compressing the finished proof (see
+    /// [`crate::final_wrap`]). Not serialized: backends are looked up by id.
+    #[serde(skip)]
+    pub final_wrap: Option<&'static dyn WrapBackend>,
+    /// Root of the blocks manifest the proof is bound to (see
+    /// [`LABEL_MANIFEST_ROOT`]). Backends set it from the root given to
+    /// `prove`; the all-zero default suits ad-hoc driver runs.
+    pub manifest_root: [u8; 32],
+    /// Digest of public claims bound into every gadget proof alongside
+    /// `manifest_root` (see [`sezkp_core::claims`]); `None` binds none.
+    #[serde(default)]
+    pub claims: Option<[u8; 32]>,
+    /// Blocks embedded in batch bundles with manifest-inclusion proofs, so
+    /// a verifier can spot-check them instead of reading every block
+    /// (0 = none; see [`crate::spot`]). Ignored by the streaming driver.
+    #[serde(default)]
+    pub spot_checks: u32,
+    /// Bind the manifest root at the end of a stream rather than the start,
+    /// for blocks still being produced (see [`StreamHeader::late_bound`]).
+    /// `manifest_root` must then stay zero. Streaming driver only.
+    ///
+    /// [`StreamHeader::late_bound`]: crate::driver::StreamHeader::late_bound
+    #[serde(default)]
+    pub late_bound: bool,
+    /// Durability of the streamed output. Streaming driver only.
+    #[serde(default)]
+    pub sync: SyncPolicy,
+    /// Also publish the root `π` in clear, next to its commitment: in the
+    /// stream footer ([`StreamFooter::root_pi`]) and the artifact's
+    /// `meta.root_pi`. Verifiers check it against the commitment.
+    ///
+    /// [`StreamFooter::root_pi`]: crate::driver::StreamFooter::root_pi
+    #[serde(default)]
+    pub reveal_root_pi: bool,
+    /// Declare that tapes may be re-windowed between blocks
+    /// ([`sezkp_core::ReplayConfig::allow_rewindow`]), so verifiers holding
+    /// the blocks skip window continuity across seams, if they accept such
+    /// proofs at all ([`ExpectedParams::allow_rewindow`]).
+    #[serde(default)]
+    pub allow_rewindow: bool,
 }
 
 impl Default for DriverOptions {
@@ -110,13 +213,173 @@ impl Default for DriverOptions {
             fold_mode: FoldMode::Balanced,
             wrap_cadence: 0,
             endpoint_cache: 64, // sensible small default
+            ledger: LedgerStore::Memory,
+            are_samples: 0,
+            iface_digest: DigestMode::Full,
+            final_wrap: None,
+            manifest_root: [0u8; 32],
+            claims: None,
+            spot_checks: 0,
+            late_bound: false,
+            sync: SyncPolicy::default(),
+            reveal_root_pi: false,
+            allow_rewindow: false,
+        }
+    }
+}
+
+impl DriverOptions {
+    /// The parameters these options give a proof (see [`FoldParams`]).
+    #[must_use]
+    pub const fn params(&self) -> FoldParams {
+        FoldParams {
+            fold_mode: self.fold_mode,
+            wrap_cadence: self.wrap_cadence,
+            iface_window: IFACE_WINDOW,
+            are_samples: self.are_samples,
+            iface_digest: self.iface_digest,
+            allow_rewindow: self.allow_rewindow,
+        }
+    }
+
+    /// Root the gadget proofs are bound to: `manifest_root`, combined with
+    /// the claims digest when there is one ([`sezkp_core::claims::bind_root`]).
+    #[must_use]
+    pub fn binding_root(&self) -> [u8; 32] {
+        sezkp_core::claims::bind_root(&self.manifest_root, self.claims.as_ref())
+    }
+}
+
+/// Boundary window of this build, as recorded in [`FoldParams::iface_window`].
+// The window is a small constant (32 steps).
+#[allow(clippy::cast_possible_truncation)]
+const IFACE_WINDOW: u32 = sezkp_core::boundary::IFACE_WINDOW_STEPS as u32;
+
+/// Protocol parameters that shape a fold proof, declared inside it.
+///
+/// Memory knobs (`endpoint_cache`, `ledger`) are left out: they change how a
+/// proof is computed, not what it contains. Verifiers check the proof's
+/// structure against the declared values (wraps exactly after every
+/// `wrap_cadence`-th fold; a boundary window they implement) and callers can
+/// require particular values with [`ExpectedParams`].
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FoldParams {
+    /// Driver mode the prover ran in.
+    pub fold_mode: FoldMode,
+    /// Wrap cadence (0 = no wraps).
+    pub wrap_cadence: u32,
+    /// Steps taken from each side of a seam by the interface digest.
+    pub iface_window: u32,
+    /// Interior steps opened per leaf (0 = none; absent in older proofs).
+    #[serde(default)]
+    pub are_samples: u32,
+    /// What the interface digest hashes of the boundary steps. Omitted for
+    /// the default [`DigestMode::Full`], the only mode of older proofs, so
+    /// their parameters encode (and digest) as before.
+    #[serde(default, skip_serializing_if = "DigestMode::is_full")]
+    pub iface_digest: DigestMode,
+    /// Tapes may be re-windowed between blocks, so window continuity across
+    /// seams is not checked. Omitted when unset, like `iface_digest`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub allow_rewindow: bool,
+}
+
+impl FoldParams {
+    /// Check that this build can verify proofs made with these parameters.
+    ///
+    /// # Errors
+    /// Fails if the boundary window differs from this build's.
+    pub fn check_supported(&self) -> anyhow::Result<()> {
+        ensure!(
+            self.iface_window == IFACE_WINDOW,
+            "proof uses a {}-step boundary window; this verifier implements {IFACE_WINDOW}",
+            self.iface_window
+        );
+        Ok(())
+    }
+}
+
+/// Parameter values a verifier requires (`None` = accept any).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ExpectedParams {
+    /// Required driver mode.
+    pub fold_mode: Option<FoldMode>,
+    /// Required wrap cadence.
+    pub wrap_cadence: Option<u32>,
+    /// Minimum number of interior steps opened per leaf.
+    pub are_samples: Option<u32>,
+    /// Required seam digest mode.
+    pub iface_digest: Option<DigestMode>,
+    /// Accept proofs declaring [`FoldParams::allow_rewindow`]; they are
+    /// rejected otherwise.
+    pub allow_rewindow: bool,
+}
+
+impl ExpectedParams {
+    /// `true` if nothing is required (re-windowed proofs are refused
+    /// regardless, unless allowed).
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.fold_mode.is_none()
+            && self.wrap_cadence.is_none()
+            && self.are_samples.is_none()
+            && self.iface_digest.is_none()
+    }
+
+    /// Check the parameters a proof declares (`None` for legacy proofs that
+    /// declare none).
+    ///
+    /// # Errors
+    /// Fails on a mismatch, on a proof allowing re-windowed tapes unless
+    /// [`Self::allow_rewindow`], or if anything is required of a legacy
+    /// proof.
+    pub fn check(&self, declared: Option<&FoldParams>) -> anyhow::Result<()> {
+        ensure!(
+            self.allow_rewindow || !declared.is_some_and(|p| p.allow_rewindow),
+            "proof allows re-windowed tapes between blocks; the verifier does not"
+        );
+        if self.is_empty() {
+            return Ok(());
+        }
+        let Some(p) = declared else {
+            bail!("proof predates declared fold parameters; cannot check expectations");
+        };
+        if let Some(mode) = self.fold_mode {
+            ensure!(
+                p.fold_mode == mode,
+                "proof was made in {:?} mode, expected {mode:?}",
+                p.fold_mode
+            );
         }
+        if let Some(k) = self.wrap_cadence {
+            ensure!(
+                p.wrap_cadence == k,
+                "proof has wrap cadence {}, expected {k}",
+                p.wrap_cadence
+            );
+        }
+        if let Some(r) = self.are_samples {
+            ensure!(
+                p.are_samples >= r,
+                "proof opens {} sampled steps per leaf, expected at least {r}",
+                p.are_samples
+            );
+        }
+        if let Some(m) = self.iface_digest {
+            ensure!(
+                p.iface_digest == m,
+                "proof digests seams in {} mode, expected {m}",
+                p.iface_digest
+            );
+        }
+        Ok(())
     }
 }
 
 /// Leaf gadget: prove/verify a single block and produce its `(π, C)`.
 ///
-/// Implementors should bind their transcript to [`DS_LEAF`].
+/// Implementors should bind their transcript to [`DS_LEAF`] and to the
+/// `manifest_root` every method takes (see [`LABEL_MANIFEST_ROOT`]).
 ///
 /// Note: the streaming layer **does not** expose `π` on the wire; it exposes
 /// a [`PiCommitment`] produced from the returned `π` via [`commit_pi`].
@@ -125,26 +388,78 @@ pub trait Leaf {
     type Proof: Serialize + for<'de> Deserialize<'de>;
 
     /// Prove a single leaf block, returning `(π, C, proof)`.
-    fn prove_leaf(block: &sezkp_core::BlockSummary) -> (crate::are::Pi, Commitment, Self::Proof);
+    fn prove_leaf(
+        manifest_root: &[u8; 32],
+        block: &sezkp_core::BlockSummary,
+    ) -> (crate::are::Pi, Commitment, Self::Proof);
 
     /// Verify a single leaf proof against the commitment and **π commitment**.
     ///
     /// Returns `true` on success; `false` on failure.
-    fn verify_leaf(commit: &Commitment, pi_cmt: &PiCommitment, proof: &Self::Proof) -> bool;
+    fn verify_leaf(
+        manifest_root: &[u8; 32],
+        commit: &Commitment,
+        pi_cmt: &PiCommitment,
+        proof: &Self::Proof,
+    ) -> bool;
+
+    /// Like [`Leaf::prove_leaf`], additionally opening `samples` interior
+    /// steps of the block (see [`crate::are_sample`]).
+    ///
+    /// The default ignores `samples`; gadgets that do not sample must only be
+    /// driven with `samples == 0`.
+    #[must_use]
+    fn prove_leaf_sampled(
+        manifest_root: &[u8; 32],
+        block: &sezkp_core::BlockSummary,
+        _samples: u32,
+    ) -> (crate::are::Pi, Commitment, Self::Proof) {
+        Self::prove_leaf(manifest_root, block)
+    }
+
+    /// Like [`Leaf::verify_leaf`], additionally requiring the proof to open
+    /// exactly `samples` interior steps.
+    ///
+    /// The default only accepts `samples == 0`.
+    fn verify_leaf_sampled(
+        manifest_root: &[u8; 32],
+        commit: &Commitment,
+        pi_cmt: &PiCommitment,
+        proof: &Self::Proof,
+        samples: u32,
+    ) -> bool {
+        samples == 0 && Self::verify_leaf(manifest_root, commit, pi_cmt, proof)
+    }
+
+    /// Re-bind a proof that verifies under another manifest root to
+    /// `manifest_root` (used by [`crate::cache`] to reuse proved prefixes).
+    ///
+    /// Callers must have verified `proof` first; this only replaces the
+    /// binding, it does not check anything.
+    fn rebind_leaf(
+        manifest_root: &[u8; 32],
+        commit: &Commitment,
+        pi_cmt: &PiCommitment,
+        proof: &mut Self::Proof,
+    );
 }
 
 /// Fold gadget: merge two siblings into their parent with an interface check.
 ///
-/// Implementors should bind their transcript to [`DS_FOLD`].
+/// Implementors should bind their transcript to [`DS_FOLD`] and to the
+/// `manifest_root` every method takes (see [`LABEL_MANIFEST_ROOT`]).
 ///
-/// Note: the driver passes `π` **internally** for interface checks but will
-/// only **emit** [`PiCommitment`]s on the streamed wire format.
+/// Note: the driver emits [`PiCommitment`]s on the streamed wire format; a
+/// proof that needs raw `π` to check the parent against its children must
+/// carry it and tie it to those commitments (as [`crate::fold::CryptoFold`]
+/// does).
 pub trait Fold {
     /// Serialized proof object for the fold gadget.
     type Proof: Serialize + for<'de> Deserialize<'de>;
 
     /// Fold `(left, right)` into a parent `(C, π)`, using an interface witness.
     fn fold(
+        manifest_root: &[u8; 32],
         left: (&Commitment, &crate::are::Pi),
         right: (&Commitment, &crate::are::Pi),
         iface: &crate::are::InterfaceWitness,
@@ -154,25 +469,71 @@ pub trait Fold {
     ///
     /// Returns `true` on success; `false` on failure.
     fn verify_fold(
+        manifest_root: &[u8; 32],
         parent: (&Commitment, &PiCommitment),
         left: (&Commitment, &PiCommitment),
         right: (&Commitment, &PiCommitment),
         proof: &Self::Proof,
     ) -> bool;
+
+    /// Re-bind a verified fold proof to `manifest_root` (see
+    /// [`Leaf::rebind_leaf`]).
+    fn rebind_fold(
+        manifest_root: &[u8; 32],
+        parent: (&Commitment, &PiCommitment),
+        left: (&Commitment, &PiCommitment),
+        right: (&Commitment, &PiCommitment),
+        proof: &mut Self::Proof,
+    );
 }
 
 /// Optional wrapper gadget: periodically attest to the current root `(C, π)`.
 ///
-/// Implementors should bind their transcript to [`DS_WRAP`].
+/// Implementors should bind their transcript to [`DS_WRAP`] and to the
+/// `manifest_root` both methods take (see [`LABEL_MANIFEST_ROOT`]).
 pub trait Wrap {
     /// Serialized proof object for the wrap gadget.
     type Proof: Serialize + for<'de> Deserialize<'de>;
 
     /// Produce a wrap proof for the current root `(C, π)`.
-    fn wrap(root: (&Commitment, &crate::are::Pi)) -> Self::Proof;
+    fn wrap(manifest_root: &[u8; 32], root: (&Commitment, &crate::are::Pi)) -> Self::Proof;
 
     /// Verify a wrap proof for the given root `(C, π_commitment)`.
     ///
     /// Returns `true` on success; `false` on failure.
-    fn verify_wrap(root: (&Commitment, &PiCommitment), proof: &Self::Proof) -> bool;
+    fn verify_wrap(
+        manifest_root: &[u8; 32],
+        root: (&Commitment, &PiCommitment),
+        proof: &Self::Proof,
+    ) -> bool;
+}
+
+/// Final wrap backend: compress a finished proof into one opaque attestation.
+///
+/// Where [`Wrap`] attests intermediate roots with a typed gadget proof, a
+/// wrap backend runs once per proof over the final root `(C, π)` and a
+/// digest of the whole bundle or stream, and returns opaque bytes. This is
+/// the slot for an external SNARK; [`crate::final_wrap::MacWrapBackend`] is
+/// the transcript-MAC reference. Backends are registered under their
+/// [`id`](WrapBackend::id) so verifiers can find them again.
+pub trait WrapBackend: std::fmt::Debug + Send + Sync {
+    /// Stable identifier recorded next to the proof (e.g. `"mac-v1"`).
+    fn id(&self) -> &'static str;
+
+    /// Wrap the final root `(C, π)` of a proof whose contents hash to `digest`.
+    fn prove(&self, root: (&Commitment, &crate::are::Pi), digest: &[u8; 32]) -> Vec<u8>;
+
+    /// Verify `proof` for the root `(C, π_commitment)` and `digest`.
+    ///
+    /// Returns `true` on success; `false` on failure.
+    fn verify(&self, root: (&Commitment, &PiCommitment), digest: &[u8; 32], proof: &[u8]) -> bool;
 }
+
+/// Backends are equal when their ids are (options compare by backend id).
+impl PartialEq for dyn WrapBackend {
+    fn eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+}
+
+impl Eq for dyn WrapBackend {}
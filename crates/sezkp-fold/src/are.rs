@@ -26,7 +26,7 @@
 
 use anyhow::Result;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use sezkp_stark::v1::field::F1;
+use sezkp_gadgets::F1;
 
 use crate::are_replay::{prove_replay, verify_replay, AreProof};
 
@@ -27,7 +27,7 @@ use blake3::Hasher;
 use serde::{Deserialize, Serialize};
 
 use crate::are::{InterfaceWitness, Pi};
-use sezkp_stark::v1::air::{
+use sezkp_gadgets::iface::{
     prove_iface_replay, verify_iface_replay, AreProofStark, LeafIfacePublic,
 };
 
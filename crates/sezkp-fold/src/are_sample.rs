@@ -0,0 +1,394 @@
+//! ARE sampling: transcript-chosen openings of interior block steps.
+//!
+//! Interface digests only cover the [`IFACE_WINDOW_STEPS`] steps on either
+//! side of a seam, so for long blocks most of the movement log is never
+//! looked at by the fold line. With `are_samples = r`
+//! ([`crate::api::DriverOptions::are_samples`]) each leaf proof also carries
+//! [`StepSamples`]:
+//!
+//! 1. the block's *shape* (everything but the movement log) and its step
+//!    count, which hash to the leaf commitment
+//!    ([`sezkp_merkle::leaf_hash_of_shape`]);
+//! 2. a Merkle root over one [`StepRow`] per step: the tape heads before the
+//!    step and the step itself;
+//! 3. `r` openings at indices drawn from a [`DS_ARE_SAMPLE`] transcript over
+//!    the leaf commitment and that root, each with the row that follows it.
+//!
+//! The leaf commitment binds the log's length but not its contents, so the
+//! rows root is only as good as the log it was built from: a verifier holding
+//! the block recomputes it ([`StepSamples::check_block`]), which the fold
+//! backend does whenever it is given the blocks.
+//!
+//! The verifier re-derives the indices and checks every opened transition
+//! locally: one op per tape, heads start at the entry offsets, writes land
+//! inside the window after the move, and the moved heads equal the next
+//! row's heads (or the exit offsets after the last step). A block that breaks
+//! one of its `n` transitions escapes detection with probability about
+//! `(1 - 1/n)^r`.
+//!
+//! [`IFACE_WINDOW_STEPS`]: sezkp_core::boundary::IFACE_WINDOW_STEPS
+
+#![forbid(unsafe_code)]
+#![deny(rust_2018_idioms)]
+#![warn(
+    clippy::all,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::unwrap_used,
+    clippy::expect_used
+)]
+
+use anyhow::{bail, ensure, Context, Result};
+use serde::{Deserialize, Serialize};
+use sezkp_core::frontier::node_hash;
+use sezkp_core::{canonical, BlockSummary, MovementLog, StepProjection, EMPTY_ROOT};
+use sezkp_crypto::{Blake3Transcript, Transcript, TranscriptExt};
+use sezkp_merkle::leaf_hash_of_shape;
+
+use crate::api::{Commitment, DS_ARE_SAMPLE};
+
+/// Domain prefix of row hashes.
+const DS_ROW: &[u8] = b"sezkp-fold/are-sample/row/v1";
+
+/// One step of a block together with the tape heads it starts from.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StepRow {
+    /// Absolute work-tape head cells before the step (length τ).
+    pub heads: Vec<i64>,
+    /// The logged step.
+    pub step: StepProjection,
+}
+
+impl StepRow {
+    fn hash(&self) -> Result<[u8; 32]> {
+        canonical::digest(DS_ROW, self).context("hashing step row")
+    }
+
+    /// Heads after applying the step's moves.
+    fn moved_heads(&self) -> Vec<i64> {
+        self.heads
+            .iter()
+            .zip(&self.step.tapes)
+            .map(|(h, op)| h + i64::from(op.mv))
+            .collect()
+    }
+}
+
+/// A row opened against the rows root.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StepOpening {
+    /// Step index within the block.
+    pub index: u64,
+    /// The opened row.
+    pub row: StepRow,
+    /// Sibling hashes, leaf to root (promoted odd nodes have none).
+    pub path: Vec<[u8; 32]>,
+}
+
+/// A sampled step and the step after it (`None` for the last step).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SampledStep {
+    /// Opening of the sampled step.
+    pub at: StepOpening,
+    /// Opening of the following step.
+    pub next: Option<StepOpening>,
+}
+
+/// Sampled interior steps of one block, carried by its leaf proof.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StepSamples {
+    /// The block without its movement log.
+    pub shape: BlockSummary,
+    /// Length of the movement log.
+    pub n_steps: u64,
+    /// Merkle root over the [`StepRow`] hashes.
+    pub rows_root: [u8; 32],
+    /// One entry per drawn index, in draw order.
+    pub openings: Vec<SampledStep>,
+}
+
+impl StepSamples {
+    /// Open `r` transcript-sampled steps of `block`, whose leaf commitment is
+    /// `c`.
+    ///
+    /// # Errors
+    /// Fails if a row cannot be encoded.
+    pub fn prove(block: &BlockSummary, c: &Commitment, r: u32) -> Result<Self> {
+        let rows = step_rows(block);
+        let levels = tree_levels(rows.iter().map(StepRow::hash).collect::<Result<_>>()?);
+        let rows_root = top(&levels);
+        let n_steps = rows.len() as u64;
+
+        let open = |i: u64| {
+            // Indices are drawn below `n_steps`, so they fit in usize.
+            #[allow(clippy::cast_possible_truncation)]
+            let i_us = i as usize;
+            StepOpening {
+                index: i,
+                row: rows[i_us].clone(),
+                path: open_path(&levels, i_us),
+            }
+        };
+        let openings = sample_indices(c, n_steps, &rows_root, r)
+            .into_iter()
+            .map(|i| SampledStep {
+                at: open(i),
+                next: (i + 1 < n_steps).then(|| open(i + 1)),
+            })
+            .collect();
+
+        Ok(Self {
+            shape: BlockSummary {
+                movement_log: MovementLog::default(),
+                ..block.clone()
+            },
+            n_steps,
+            rows_root,
+            openings,
+        })
+    }
+
+    /// Check `r` samples against the leaf commitment `c`.
+    ///
+    /// # Errors
+    /// Fails if the shape does not hash to `c`, the openings are not the
+    /// transcript's draws or do not open the rows root, or a sampled
+    /// transition is inconsistent.
+    pub fn verify(&self, c: &Commitment, r: u32) -> Result<()> {
+        ensure!(
+            self.shape.movement_log.steps.is_empty(),
+            "sample shape carries a movement log"
+        );
+        ensure!(
+            c.len == 1 && leaf_hash_of_shape(&self.shape, self.n_steps) == c.root,
+            "sample shape does not match the leaf commitment"
+        );
+        let indices = sample_indices(c, self.n_steps, &self.rows_root, r);
+        ensure!(
+            self.openings.len() == indices.len(),
+            "expected {} sampled steps, found {}",
+            indices.len(),
+            self.openings.len()
+        );
+        for (s, i) in self.openings.iter().zip(indices) {
+            self.check_sample(s, i)
+                .with_context(|| format!("sampled step {i}"))?;
+        }
+        Ok(())
+    }
+
+    /// Check that these samples were taken from `block`: same shape, same
+    /// log length, and a rows root recomputed from its movement log.
+    ///
+    /// The leaf commitment binds only the log's length, so [`Self::verify`]
+    /// alone cannot tell a block's own steps from a made-up log of the same
+    /// length; a verifier holding the block must run this as well.
+    ///
+    /// # Errors
+    /// Fails if the samples were taken from a different block or log.
+    pub fn check_block(&self, block: &BlockSummary) -> Result<()> {
+        ensure!(
+            self.shape.movement_log.steps.is_empty()
+                && self.shape
+                    == BlockSummary {
+                        movement_log: MovementLog::default(),
+                        ..block.clone()
+                    },
+            "samples are of a different block than block {}",
+            block.block_id
+        );
+        ensure!(
+            self.n_steps == block.movement_log.steps.len() as u64
+                && self.rows_root == rows_root(block)?,
+            "sampled steps are not block {}'s movement log",
+            block.block_id
+        );
+        Ok(())
+    }
+
+    fn check_sample(&self, s: &SampledStep, i: u64) -> Result<()> {
+        let shape = &self.shape;
+        let tau = shape.windows.len();
+        self.check_opening(&s.at, i)?;
+        let row = &s.at.row;
+        ensure!(
+            row.heads.len() == tau && row.step.tapes.len() == tau,
+            "row has {} heads and {} tape ops, expected {tau}",
+            row.heads.len(),
+            row.step.tapes.len()
+        );
+        if i == 0 {
+            for (t, &h) in row.heads.iter().enumerate() {
+                ensure!(
+                    h == entry_head(shape, t),
+                    "tape {t} does not start at its entry head"
+                );
+            }
+        }
+
+        let moved = row.moved_heads();
+        for (t, op) in row.step.tapes.iter().enumerate() {
+            if op.write.is_some() {
+                ensure!(
+                    shape.windows[t].contains(moved[t]),
+                    "write outside window on tape {t} at {}",
+                    moved[t]
+                );
+            }
+        }
+
+        match &s.next {
+            Some(next) => {
+                self.check_opening(next, i + 1)?;
+                ensure!(
+                    next.row.heads == moved,
+                    "heads do not carry into the next step"
+                );
+            }
+            None if i + 1 == self.n_steps => {
+                for (t, &h) in moved.iter().enumerate() {
+                    ensure!(
+                        h == exit_head(shape, t),
+                        "tape {t} does not end at its exit head"
+                    );
+                }
+            }
+            None => bail!("missing opening of the next step"),
+        }
+        Ok(())
+    }
+
+    fn check_opening(&self, o: &StepOpening, i: u64) -> Result<()> {
+        ensure!(
+            o.index == i,
+            "opening is for step {}, expected {i}",
+            o.index
+        );
+        ensure!(
+            verify_path(&self.rows_root, o.row.hash()?, i, self.n_steps, &o.path),
+            "row {i} does not open the rows root"
+        );
+        Ok(())
+    }
+}
+
+/// Merkle root over the [`StepRow`]s of `block`'s movement log, as committed
+/// by [`StepSamples::prove`].
+///
+/// # Errors
+/// Fails if a row cannot be encoded.
+pub fn rows_root(block: &BlockSummary) -> Result<[u8; 32]> {
+    let rows = step_rows(block);
+    Ok(top(&tree_levels(
+        rows.iter().map(StepRow::hash).collect::<Result<_>>()?,
+    )))
+}
+
+/// One row per logged step, heads carried from the entry offsets.
+fn step_rows(block: &BlockSummary) -> Vec<StepRow> {
+    let mut heads: Vec<i64> = (0..block.windows.len())
+        .map(|t| entry_head(block, t))
+        .collect();
+    let mut rows = Vec::with_capacity(block.movement_log.steps.len());
+    for step in &block.movement_log.steps {
+        let row = StepRow {
+            heads,
+            step: step.clone(),
+        };
+        heads = row.moved_heads();
+        rows.push(row);
+    }
+    rows
+}
+
+/// Absolute entry head of tape `t` (offsets are checked by the leaf hash, not
+/// here; a missing one reads as the window's left edge).
+fn entry_head(b: &BlockSummary, t: usize) -> i64 {
+    b.windows[t].left + i64::from(b.head_in_offsets.get(t).copied().unwrap_or(0))
+}
+
+/// Absolute exit head of tape `t`.
+fn exit_head(b: &BlockSummary, t: usize) -> i64 {
+    b.windows[t].left + i64::from(b.head_out_offsets.get(t).copied().unwrap_or(0))
+}
+
+/// Step indices to open: `r` draws from a transcript over the leaf
+/// commitment, the step count and the rows root (none for an empty log).
+fn sample_indices(c: &Commitment, n_steps: u64, rows_root: &[u8; 32], r: u32) -> Vec<u64> {
+    if n_steps == 0 {
+        return Vec::new();
+    }
+    let mut tr = Blake3Transcript::new(DS_ARE_SAMPLE);
+    tr.absorb("c.root", &c.root);
+    tr.absorb_u64("c.len", u64::from(c.len));
+    tr.absorb_u64("n_steps", n_steps);
+    tr.absorb("rows.root", rows_root);
+    (0..r)
+        .map(|_| tr.challenge_u64("index") % n_steps)
+        .collect()
+}
+
+/* ---------------------------------- merkle -------------------------------- */
+
+/// Levels of the left-balanced tree over `leaves` (odd nodes promoted, as in
+/// [`sezkp_merkle::merkle_root`]), leaves first.
+fn tree_levels(leaves: Vec<[u8; 32]>) -> Vec<Vec<[u8; 32]>> {
+    let mut levels = vec![leaves];
+    while let Some(level) = levels.last().filter(|l| l.len() > 1) {
+        let up = level
+            .chunks(2)
+            .map(|p| match p {
+                [l, r] => node_hash(l, r),
+                [x] => *x,
+                _ => unreachable!("chunks(2) yields one or two nodes"),
+            })
+            .collect();
+        levels.push(up);
+    }
+    levels
+}
+
+/// Root of a tree built by [`tree_levels`] ([`EMPTY_ROOT`] for no leaves).
+fn top(levels: &[Vec<[u8; 32]>]) -> [u8; 32] {
+    levels
+        .last()
+        .and_then(|l| l.first())
+        .copied()
+        .unwrap_or(EMPTY_ROOT)
+}
+
+/// Sibling hashes for leaf `i`, skipping levels where it is promoted.
+fn open_path(levels: &[Vec<[u8; 32]>], mut i: usize) -> Vec<[u8; 32]> {
+    let mut path = Vec::new();
+    for level in levels.iter().take(levels.len().saturating_sub(1)) {
+        if let Some(sib) = level.get(i ^ 1) {
+            path.push(*sib);
+        }
+        i >>= 1;
+    }
+    path
+}
+
+/// Check that `leaf` sits at index `i` of an `n`-leaf tree with root `root`.
+fn verify_path(root: &[u8; 32], leaf: [u8; 32], mut i: u64, mut n: u64, path: &[[u8; 32]]) -> bool {
+    if i >= n {
+        return false;
+    }
+    let mut cur = leaf;
+    let mut sibs = path.iter();
+    while n > 1 {
+        if i ^ 1 < n {
+            let Some(sib) = sibs.next() else {
+                return false;
+            };
+            cur = if i & 1 == 0 {
+                node_hash(&cur, sib)
+            } else {
+                node_hash(sib, &cur)
+            };
+        }
+        i >>= 1;
+        n = n.div_ceil(2);
+    }
+    sibs.next().is_none() && &cur == root
+}
@@ -0,0 +1,439 @@
+//! Persistent proving cache for completed fold subtrees.
+//!
+//! When a trace grows (or is re-proved with small edits), most subtrees of
+//! the streaming fold tree are unchanged. The streaming driver builds
+//! *aligned, power-of-two* subtrees (`[k·2^h, (k+1)·2^h)`), so a subtree's
+//! proof depends only on the blocks it spans — never on its position.
+//!
+//! The cache stores one file per completed **granule** (an aligned subtree
+//! of `granule` leaves), keyed by a digest of the granule's canonical leaf
+//! hashes ([`sezkp_merkle::leaf_hash`]). An entry holds the granule's
+//! endpoint `(C, π)` plus its proof stream segment (leaf and fold records in
+//! emission order). On a hit the driver replays the segment into the sink
+//! instead of re-proving it; the emitted stream is byte-for-byte what a cold
+//! run would produce, so verifiers need no changes.
+//!
+//! ## Entry format
+//! One CBOR value per file, `<dir>/<hex(key)>.seg` (see [`CachedSegment`]).
+//! Raw `π` values are kept (unlike the wire format) so wraps can be
+//! re-derived for any cadence and fold offset.
+//!
+//! Gadget proofs are bound to a manifest root (see
+//! [`crate::api::LABEL_MANIFEST_ROOT`]), which changes whenever the trace
+//! does. Keys leave the root out so a grown trace still hits its prefix; an
+//! entry records the root it was proved under and is re-bound to the
+//! current one after it verifies.
+//!
+//! ## Integrity
+//! Entries are untrusted input: on load we check the header, the key, the
+//! tree shape, and every gadget proof. Anything malformed is treated as a
+//! miss and overwritten by the fresh result.
+
+#![forbid(unsafe_code)]
+#![deny(rust_2018_idioms)]
+#![warn(
+    missing_docs,
+    clippy::all,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::unwrap_used,
+    clippy::expect_used
+)]
+
+use anyhow::{ensure, Context, Result};
+use blake3::Hasher;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sezkp_core::boundary::{interface_boundary_digest_with, DigestMode};
+use sezkp_core::BlockSummary;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::api::{commit_pi, Commitment, Fold, Leaf};
+use crate::are::{InterfaceWitness, Pi};
+
+/// Magic string stored in every cache entry.
+pub const SEGMENT_MAGIC: &str = "sezkp-fold-seg";
+/// Version of the cache entry format (3: seam digests bind the blocks'
+/// windows, so entries cached with older seams are recomputed).
+pub const SEGMENT_VER: u16 = 3;
+/// Default granule size (leaves per cached subtree).
+pub const DEFAULT_GRANULE: u32 = 64;
+
+/// Domain separator for subtree range digests.
+const DS_RANGE: &[u8] = b"sezkp-fold/subtree-cache/v1";
+
+/// One record of a cached proof stream segment.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum SegmentItem<Lp, Fp> {
+    /// Leaf record `(C, π, proof)`.
+    Leaf {
+        /// Leaf commitment.
+        c: Commitment,
+        /// Raw leaf projection.
+        pi: Pi,
+        /// Leaf gadget proof.
+        proof: Lp,
+    },
+    /// Fold record `(parent, left, right, proof)`.
+    Fold {
+        /// Parent endpoint.
+        parent: (Commitment, Pi),
+        /// Left child endpoint.
+        left: (Commitment, Pi),
+        /// Right child endpoint.
+        right: (Commitment, Pi),
+        /// Fold gadget proof.
+        proof: Fp,
+    },
+}
+
+/// A cached granule: endpoint plus its proof stream segment.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CachedSegment<Lp, Fp> {
+    /// Entry magic (see [`SEGMENT_MAGIC`]).
+    pub magic: String,
+    /// Entry format version (see [`SEGMENT_VER`]).
+    pub ver: u16,
+    /// Range digest this entry was stored under.
+    pub key: [u8; 32],
+    /// Number of leaves spanned.
+    pub n_leaves: u32,
+    /// Subtree endpoint `(C, π)`.
+    pub root: (Commitment, Pi),
+    /// Manifest root the gadget proofs in `items` are bound to.
+    pub bound_to: [u8; 32],
+    /// Leaf and fold records in streaming (post-order) emission order.
+    pub items: Vec<SegmentItem<Lp, Fp>>,
+}
+
+/// Hit/miss counters reported by the driver.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheStats {
+    /// Granules replayed from disk.
+    pub hits: u64,
+    /// Granules proved afresh (and stored).
+    pub misses: u64,
+    /// Entries found on disk but rejected by the integrity checks.
+    pub rejected: u64,
+}
+
+/// Digest of a contiguous range of canonical leaf hashes.
+///
+/// Binds the gadget types as well, so switching gadgets never replays
+/// proofs produced by another implementation, the number of sampled
+/// interior steps per leaf when nonzero (see [`crate::are_sample`]), and the
+/// seam digest mode when it is not [`DigestMode::Full`].
+#[must_use]
+pub fn range_digest<L: Leaf, F: Fold>(
+    leaf_hashes: &[[u8; 32]],
+    are_samples: u32,
+    iface_digest: DigestMode,
+) -> [u8; 32] {
+    let mut h = Hasher::new();
+    h.update(DS_RANGE);
+    h.update(std::any::type_name::<L>().as_bytes());
+    h.update(&[0]);
+    h.update(std::any::type_name::<F>().as_bytes());
+    h.update(&[0]);
+    h.update(&(leaf_hashes.len() as u64).to_le_bytes());
+    for lh in leaf_hashes {
+        h.update(lh);
+    }
+    if are_samples != 0 {
+        h.update(b"are_samples");
+        h.update(&are_samples.to_le_bytes());
+    }
+    if !iface_digest.is_full() {
+        h.update(b"iface_digest");
+        h.update(iface_digest.id().as_bytes());
+    }
+    *h.finalize().as_bytes()
+}
+
+/// On-disk subtree cache rooted at a directory.
+#[derive(Clone, Debug)]
+pub struct SubtreeCache {
+    dir: PathBuf,
+    granule: u32,
+    stats: CacheStats,
+}
+
+impl SubtreeCache {
+    /// Open (creating if needed) a cache directory with the given granule.
+    ///
+    /// # Errors
+    /// Fails if `granule` is not a non-zero power of two or the directory
+    /// cannot be created.
+    pub fn open(dir: impl Into<PathBuf>, granule: u32) -> Result<Self> {
+        ensure!(
+            granule.is_power_of_two(),
+            "fold cache granule must be a non-zero power of two (got {granule})"
+        );
+        let dir = dir.into();
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("creating fold cache dir {}", dir.display()))?;
+        Ok(Self {
+            dir,
+            granule,
+            stats: CacheStats::default(),
+        })
+    }
+
+    /// Leaves per cached subtree.
+    #[inline]
+    #[must_use]
+    pub const fn granule(&self) -> u32 {
+        self.granule
+    }
+
+    /// Counters accumulated so far.
+    #[inline]
+    #[must_use]
+    pub const fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    /// Cache directory.
+    #[inline]
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn entry_path(&self, key: &[u8; 32]) -> PathBuf {
+        self.dir.join(format!("{}.seg", hex(key)))
+    }
+
+    /// Return the segment for `blocks` with `are_samples` sampled steps per
+    /// leaf, seams digested in `iface_digest` mode and proofs bound to
+    /// `manifest_root`, replaying from disk if
+    /// possible and otherwise proving it and storing the result.
+    ///
+    /// # Errors
+    /// Fails only if a freshly proved segment cannot be written.
+    pub fn get_or_prove<L, F>(
+        &mut self,
+        blocks: &[BlockSummary],
+        are_samples: u32,
+        iface_digest: DigestMode,
+        manifest_root: &[u8; 32],
+    ) -> Result<CachedSegment<L::Proof, F::Proof>>
+    where
+        L: Leaf,
+        F: Fold,
+        L::Proof: DeserializeOwned,
+        F::Proof: DeserializeOwned,
+    {
+        let hashes: Vec<[u8; 32]> = blocks.iter().map(sezkp_merkle::leaf_hash).collect();
+        let key = range_digest::<L, F>(&hashes, are_samples, iface_digest);
+        let path = self.entry_path(&key);
+
+        if path.exists() {
+            match load_segment::<L, F>(&path, &key, &hashes, are_samples, manifest_root) {
+                Ok(seg) => {
+                    self.stats.hits += 1;
+                    return Ok(seg);
+                }
+                Err(_) => self.stats.rejected += 1,
+            }
+        }
+
+        let seg = prove_segment::<L, F>(blocks, key, are_samples, iface_digest, manifest_root);
+        store_segment(&path, &seg)?;
+        self.stats.misses += 1;
+        Ok(seg)
+    }
+}
+
+/// Prove a perfect subtree over `blocks` in streaming (post-order) order.
+///
+/// `blocks.len()` must be a power of two; this mirrors exactly what the
+/// streaming driver emits when the same blocks are pushed one by one.
+#[must_use]
+pub fn prove_segment<L: Leaf, F: Fold>(
+    blocks: &[BlockSummary],
+    key: [u8; 32],
+    are_samples: u32,
+    iface_digest: DigestMode,
+    manifest_root: &[u8; 32],
+) -> CachedSegment<L::Proof, F::Proof> {
+    fn go<L: Leaf, F: Fold>(
+        blocks: &[BlockSummary],
+        are_samples: u32,
+        iface_digest: DigestMode,
+        manifest_root: &[u8; 32],
+        items: &mut Vec<SegmentItem<L::Proof, F::Proof>>,
+    ) -> (Commitment, Pi) {
+        if blocks.len() == 1 {
+            let (pi, c, proof) = L::prove_leaf_sampled(manifest_root, &blocks[0], are_samples);
+            items.push(SegmentItem::Leaf { c, pi, proof });
+            return (c, pi);
+        }
+        let mid = blocks.len() / 2;
+        let left = go::<L, F>(&blocks[..mid], are_samples, iface_digest, manifest_root, items);
+        let right = go::<L, F>(&blocks[mid..], are_samples, iface_digest, manifest_root, items);
+        let iface = InterfaceWitness {
+            left_ctrl_out: left.1.ctrl_out,
+            right_ctrl_in: right.1.ctrl_in,
+            boundary_writes_digest: interface_boundary_digest_with(
+                &blocks[mid - 1],
+                &blocks[mid],
+                iface_digest,
+            ),
+        };
+        let (c, pi, proof) = F::fold(
+            manifest_root,
+            (&left.0, &left.1),
+            (&right.0, &right.1),
+            &iface,
+        );
+        items.push(SegmentItem::Fold {
+            parent: (c, pi),
+            left,
+            right,
+            proof,
+        });
+        (c, pi)
+    }
+
+    let mut items = Vec::with_capacity(2 * blocks.len());
+    let root = go::<L, F>(blocks, are_samples, iface_digest, manifest_root, &mut items);
+    CachedSegment {
+        magic: SEGMENT_MAGIC.to_owned(),
+        ver: SEGMENT_VER,
+        key,
+        n_leaves: u32::try_from(blocks.len()).unwrap_or(u32::MAX),
+        root,
+        bound_to: *manifest_root,
+        items,
+    }
+}
+
+/// Read and fully check a cache entry, then re-bind it to `manifest_root`.
+fn load_segment<L, F>(
+    path: &Path,
+    key: &[u8; 32],
+    hashes: &[[u8; 32]],
+    are_samples: u32,
+    manifest_root: &[u8; 32],
+) -> Result<CachedSegment<L::Proof, F::Proof>>
+where
+    L: Leaf,
+    F: Fold,
+    L::Proof: DeserializeOwned,
+    F::Proof: DeserializeOwned,
+{
+    let f = File::open(path).with_context(|| format!("open {}", path.display()))?;
+    let mut seg: CachedSegment<L::Proof, F::Proof> =
+        ciborium::de::from_reader(BufReader::new(f)).context("decoding cache entry")?;
+
+    ensure!(
+        seg.magic == SEGMENT_MAGIC && seg.ver == SEGMENT_VER,
+        "unsupported cache entry"
+    );
+    ensure!(&seg.key == key, "cache entry key mismatch");
+    ensure!(
+        seg.n_leaves as usize == hashes.len(),
+        "cache entry leaf count mismatch"
+    );
+
+    // Replay the post-order shape with a tiny stack, checking every proof
+    // under the root it was stored with and that leaves bind exactly the
+    // expected blocks.
+    let bound_to = &seg.bound_to;
+    let mut stack: Vec<(Commitment, Pi)> = Vec::new();
+    let mut next_leaf = 0usize;
+    for item in &seg.items {
+        match item {
+            SegmentItem::Leaf { c, pi, proof } => {
+                ensure!(
+                    next_leaf < hashes.len() && c.root == hashes[next_leaf] && c.len == 1,
+                    "cache entry leaf {next_leaf} does not match input block"
+                );
+                ensure!(
+                    L::verify_leaf_sampled(bound_to, c, &commit_pi(pi), proof, are_samples),
+                    "cache entry leaf proof failed"
+                );
+                next_leaf += 1;
+                stack.push((*c, *pi));
+            }
+            SegmentItem::Fold {
+                parent,
+                left,
+                right,
+                proof,
+            } => {
+                let r = stack.pop();
+                let l = stack.pop();
+                ensure!(
+                    l == Some(*left) && r == Some(*right),
+                    "cache entry fold children out of order"
+                );
+                ensure!(
+                    F::verify_fold(
+                        bound_to,
+                        (&parent.0, &commit_pi(&parent.1)),
+                        (&left.0, &commit_pi(&left.1)),
+                        (&right.0, &commit_pi(&right.1)),
+                        proof,
+                    ),
+                    "cache entry fold proof failed"
+                );
+                stack.push(*parent);
+            }
+        }
+    }
+    ensure!(
+        next_leaf == hashes.len() && stack.len() == 1 && stack[0] == seg.root,
+        "cache entry does not describe a single subtree"
+    );
+
+    if seg.bound_to != *manifest_root {
+        for item in &mut seg.items {
+            match item {
+                SegmentItem::Leaf { c, pi, proof } => {
+                    L::rebind_leaf(manifest_root, c, &commit_pi(pi), proof);
+                }
+                SegmentItem::Fold {
+                    parent,
+                    left,
+                    right,
+                    proof,
+                } => F::rebind_fold(
+                    manifest_root,
+                    (&parent.0, &commit_pi(&parent.1)),
+                    (&left.0, &commit_pi(&left.1)),
+                    (&right.0, &commit_pi(&right.1)),
+                    proof,
+                ),
+            }
+        }
+        seg.bound_to = *manifest_root;
+    }
+    Ok(seg)
+}
+
+/// Write an entry atomically (temp file + rename).
+fn store_segment<Lp: Serialize, Fp: Serialize>(
+    path: &Path,
+    seg: &CachedSegment<Lp, Fp>,
+) -> Result<()> {
+    let tmp = path.with_extension("seg.tmp");
+    {
+        let f = File::create(&tmp).with_context(|| format!("create {}", tmp.display()))?;
+        let mut w = BufWriter::new(f);
+        ciborium::ser::into_writer(seg, &mut w).context("encoding cache entry")?;
+        w.flush()?;
+    }
+    fs::rename(&tmp, path).with_context(|| format!("rename {}", path.display()))?;
+    Ok(())
+}
+
+fn hex(bytes: &[u8; 32]) -> String {
+    use std::fmt::Write as _;
+    bytes.iter().fold(String::with_capacity(64), |mut s, b| {
+        let _ = write!(s, "{b:02x}");
+        s
+    })
+}
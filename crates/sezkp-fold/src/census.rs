@@ -0,0 +1,160 @@
+//! Gadget census of a batch fold artifact or a proof stream.
+//!
+//! [`census`] decodes an envelope and counts which variant of each gadget
+//! proof the bundle carries, without verifying anything. Tools that rank how
+//! strong a proof is (e.g. `sezkp-cli lint-proof`) read these counts instead
+//! of relying on the artifact's self-reported `meta`.
+//!
+//! [`stream_census`] does the same for a CBOR-seq stream at the record
+//! level: it reads the header, counts leaf, fold and wrap items one at a
+//! time and returns the footer, if the stream has one (`sezkp-cli inspect`).
+
+#![forbid(unsafe_code)]
+#![deny(rust_2018_idioms)]
+#![warn(
+    clippy::all,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::unwrap_used,
+    clippy::expect_used
+)]
+
+use anyhow::{anyhow, bail, Context, Result};
+use ciborium::value::Value;
+use std::io::{ErrorKind, Read};
+
+use crate::api::FoldParams;
+use crate::are_replay::AreProof;
+use crate::driver::{StreamFooter, StreamHeader};
+use crate::fold::CryptoWrapProof;
+use crate::{decode_envelope, DecodedEnvelope};
+
+/// Gadget counts of one bundle.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Census {
+    /// Envelope version (1, 2 or 3).
+    pub version: u16,
+    /// Leaf proofs.
+    pub leaves: usize,
+    /// Fold proofs (each carries a transcript MAC).
+    pub folds: usize,
+    /// Fold proofs whose ARE proof is the deprecated MAC.
+    pub are_mac: usize,
+    /// Fold proofs without child π openings (no longer verifiable).
+    pub unopened_folds: usize,
+    /// Wrap attestations that are transcript MACs.
+    pub wraps_mac: usize,
+    /// Wrap attestations of any other kind.
+    pub wraps_other: usize,
+    /// Leaf proofs that open sampled interior steps.
+    pub sampled_leaves: usize,
+    /// Parameters recorded in the bundle (absent in older bundles).
+    pub params: Option<FoldParams>,
+    /// Whether the bundle binds a claims digest.
+    pub claims_bound: bool,
+    /// Id of the final wrap backend, if any.
+    pub final_wrap: Option<String>,
+    /// Number of manifest-inclusion spot checks.
+    pub spot_checks: usize,
+}
+
+/// Count the gadget proof variants in a batch envelope.
+///
+/// # Errors
+/// Fails if `proof_bytes` is not a decodable fold envelope.
+pub fn census(proof_bytes: &[u8]) -> Result<Census> {
+    let DecodedEnvelope { ver, bundle, .. } = decode_envelope(proof_bytes)?;
+    let folds = bundle.folds.iter().map(|f| &f.3);
+    let (wraps_mac, wraps_other) = bundle.wraps.iter().fold((0, 0), |(m, o), w| match w.1 {
+        CryptoWrapProof::V1Mac(_) => (m + 1, o),
+        CryptoWrapProof::V2Stark(()) => (m, o + 1),
+    });
+    Ok(Census {
+        version: ver as u16,
+        leaves: bundle.leaves.len(),
+        folds: bundle.folds.len(),
+        are_mac: folds
+            .clone()
+            .filter(|p| matches!(p.are, AreProof::V1Mac(_)))
+            .count(),
+        unopened_folds: folds.filter(|p| p.pi.is_none()).count(),
+        wraps_mac,
+        wraps_other,
+        sampled_leaves: bundle
+            .leaves
+            .iter()
+            .filter(|l| l.2.samples.is_some())
+            .count(),
+        params: bundle.params,
+        claims_bound: bundle.claims.is_some(),
+        final_wrap: bundle.final_wrap.map(|w| w.backend),
+        spot_checks: bundle.spot_checks.map_or(0, |s| s.checks.len()),
+    })
+}
+
+/// Record counts of a CBOR-seq proof stream.
+#[derive(Clone, Debug)]
+pub struct StreamCensus {
+    /// The stream header.
+    pub header: StreamHeader,
+    /// Leaf items.
+    pub leaves: u64,
+    /// Fold items.
+    pub folds: u64,
+    /// Wrap items.
+    pub wraps: u64,
+    /// The footer; `None` when the stream ends without one (still being
+    /// written, or cut short).
+    pub footer: Option<StreamFooter>,
+}
+
+/// Count the records of the CBOR-seq stream read from `reader`.
+///
+/// Reads one record at a time and decodes no gadget proof; nothing is
+/// verified, and the header's magic and version are not checked.
+///
+/// # Errors
+/// Fails if the header does not decode or a record is neither an item nor
+/// a footer.
+pub fn stream_census<R: Read>(mut reader: R) -> Result<StreamCensus> {
+    use ciborium::de::{from_reader, Error};
+
+    let header: StreamHeader = from_reader(&mut reader).context("decoding stream header")?;
+    let mut c = StreamCensus {
+        header,
+        leaves: 0,
+        folds: 0,
+        wraps: 0,
+        footer: None,
+    };
+    loop {
+        let v: Value = match from_reader(&mut reader) {
+            Ok(v) => v,
+            Err(Error::Io(e)) if e.kind() == ErrorKind::UnexpectedEof => return Ok(c),
+            Err(e) => return Err(anyhow!("reading next CBOR value in fold stream: {e}")),
+        };
+        match item_tag(&v) {
+            Some("Leaf") => c.leaves += 1,
+            Some("Fold") => c.folds += 1,
+            Some("Wrap") => c.wraps += 1,
+            _ => match v.deserialized::<StreamFooter>() {
+                Ok(footer) => {
+                    c.footer = Some(footer);
+                    return Ok(c);
+                }
+                Err(e) => bail!(
+                    "record {} is neither a stream item nor a footer: {e}",
+                    c.leaves + c.folds + c.wraps + 1
+                ),
+            },
+        }
+    }
+}
+
+/// Variant name of an externally tagged [`crate::driver::StreamItem`].
+fn item_tag(v: &Value) -> Option<&str> {
+    match v.as_map()?.as_slice() {
+        [(Value::Text(tag), _)] => Some(tag),
+        _ => None,
+    }
+}
@@ -0,0 +1,283 @@
+//! Time-travel debugging for the fold drivers.
+//!
+//! The streaming driver and the batch *Balanced* driver agree on the final
+//! root only when they build the same tree. When they do not, it helps to
+//! stop both at the same point and look at what is live:
+//!
+//! - [`streaming_state_at`] pushes the first `n` blocks through a
+//!   [`StreamDriver`] and returns its live stack.
+//! - [`balanced_state_at`] walks the Balanced DFS until leaf `n` is about to
+//!   be proved (the equivalent DFS point) and returns its live subtrees.
+//! - [`diff_states`] lines the two up position by position.
+//!
+//! Everything here runs real gadgets, so it costs as much as proving the
+//! prefix; it is a diagnostic tool, not part of any proving path.
+
+#![forbid(unsafe_code)]
+#![deny(rust_2018_idioms)]
+#![warn(
+    missing_docs,
+    clippy::all,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::unwrap_used,
+    clippy::expect_used
+)]
+
+use anyhow::{ensure, Result};
+use sezkp_core::{boundary::interface_boundary_digest, BlockSummary};
+use sezkp_scheduler as hct;
+use std::cell::RefCell;
+use std::fmt;
+
+use crate::api::{Commitment, DriverOptions, Fold, Leaf, Wrap};
+use crate::are::{InterfaceWitness, Pi};
+use crate::driver::StreamDriver;
+
+/// One live subtree: half-open leaf span plus its endpoint `(C, π)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LiveSubtree {
+    /// Inclusive first leaf.
+    pub lo: u32,
+    /// Exclusive last leaf.
+    pub hi: u32,
+    /// Subtree commitment.
+    pub c: Commitment,
+    /// Subtree projection.
+    pub pi: Pi,
+}
+
+impl fmt::Display for LiveSubtree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{:>6}, {:>6})  C={}…/{:<6} π{{ctrl {}→{}, flags {:#x}, acc [",
+            self.lo,
+            self.hi,
+            short_hex(&self.c.root),
+            self.c.len,
+            self.pi.ctrl_in,
+            self.pi.ctrl_out,
+            self.pi.flags
+        )?;
+        for (i, a) in self.pi.acc.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{:016x}", a.0)?;
+        }
+        f.write_str("]}")
+    }
+}
+
+/// One difference between a streaming and a Balanced snapshot.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StateDiff {
+    /// Only one side has a subtree at this stack position.
+    Missing {
+        /// Stack position (bottom = 0).
+        pos: usize,
+        /// Streaming entry, if any.
+        stream: Option<LiveSubtree>,
+        /// Balanced entry, if any.
+        batch: Option<LiveSubtree>,
+    },
+    /// Both sides have a subtree here but they cover different leaves.
+    Span {
+        /// Stack position (bottom = 0).
+        pos: usize,
+        /// Streaming span `[lo, hi)`.
+        stream: (u32, u32),
+        /// Balanced span `[lo, hi)`.
+        batch: (u32, u32),
+    },
+    /// Same span, different endpoint.
+    Endpoint {
+        /// Stack position (bottom = 0).
+        pos: usize,
+        /// Streaming entry.
+        stream: LiveSubtree,
+        /// Balanced entry.
+        batch: LiveSubtree,
+    },
+}
+
+impl fmt::Display for StateDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing { pos, stream, batch } => {
+                let show = |s: &Option<LiveSubtree>| {
+                    s.map_or_else(|| "(none)".to_owned(), |t| format!("[{}, {})", t.lo, t.hi))
+                };
+                write!(
+                    f,
+                    "#{pos}: stream {} vs balanced {}",
+                    show(stream),
+                    show(batch)
+                )
+            }
+            Self::Span { pos, stream, batch } => write!(
+                f,
+                "#{pos}: span stream [{}, {}) vs balanced [{}, {})",
+                stream.0, stream.1, batch.0, batch.1
+            ),
+            Self::Endpoint { pos, stream, batch } => write!(
+                f,
+                "#{pos}: endpoint differs on [{}, {})\n    stream  : {stream}\n    balanced: {batch}",
+                stream.lo, stream.hi
+            ),
+        }
+    }
+}
+
+/// Live stack of the streaming driver after pushing `blocks[..n]`.
+///
+/// # Errors
+/// Fails if `n > blocks.len()` or the driver rejects a block.
+pub fn streaming_state_at<L, F, W>(
+    blocks: &[BlockSummary],
+    n: usize,
+    opts: DriverOptions,
+) -> Result<Vec<LiveSubtree>>
+where
+    L: Leaf,
+    F: Fold,
+    W: Wrap,
+{
+    ensure!(
+        n <= blocks.len(),
+        "block index {n} out of range ({} blocks)",
+        blocks.len()
+    );
+    let mut drv = StreamDriver::<L, F, W>::new(opts);
+    for b in &blocks[..n] {
+        drv.push_block(b.clone())?;
+    }
+    Ok(drv.live_stack())
+}
+
+/// Live subtrees of the Balanced DFS over all of `blocks`, captured just
+/// before leaf `n` is proved (or at the end when `n == blocks.len()`).
+///
+/// This walks the same [`hct::dfs`] schedule and gadget calls as the
+/// Balanced mode of [`crate::driver::run_pipeline`]; work after the capture
+/// point is skipped.
+///
+/// # Errors
+/// Fails if `n > blocks.len()`.
+pub fn balanced_state_at<L, F>(blocks: &[BlockSummary], n: usize) -> Result<Vec<LiveSubtree>>
+where
+    L: Leaf,
+    F: Fold,
+{
+    ensure!(
+        n <= blocks.len(),
+        "block index {n} out of range ({} blocks)",
+        blocks.len()
+    );
+
+    // Only endpoints are captured and the gadget proofs are dropped, so the
+    // manifest root they are bound to does not matter.
+    let root = [0u8; 32];
+
+    // Post-order DFS ⇒ the live subtrees form a stack.
+    let live: RefCell<Vec<LiveSubtree>> = RefCell::new(Vec::new());
+    let captured: RefCell<Option<Vec<LiveSubtree>>> = RefCell::new(None);
+
+    hct::dfs(
+        blocks.len(),
+        |leaf| {
+            if captured.borrow().is_some() {
+                return;
+            }
+            if leaf.lo as usize == n {
+                *captured.borrow_mut() = Some(live.borrow().clone());
+                return;
+            }
+            let (pi, c, _) = L::prove_leaf(&root, &blocks[leaf.lo as usize]);
+            live.borrow_mut().push(LiveSubtree {
+                lo: leaf.lo,
+                hi: leaf.hi,
+                c,
+                pi,
+            });
+        },
+        |span| {
+            if captured.borrow().is_some() {
+                return;
+            }
+            let mut st = live.borrow_mut();
+            let (Some(right), Some(left)) = (st.pop(), st.pop()) else {
+                return;
+            };
+            let iface = InterfaceWitness {
+                left_ctrl_out: left.pi.ctrl_out,
+                right_ctrl_in: right.pi.ctrl_in,
+                boundary_writes_digest: interface_boundary_digest(
+                    &blocks[(left.hi - 1) as usize],
+                    &blocks[right.lo as usize],
+                ),
+            };
+            let (c, pi, _) = F::fold(&root, (&left.c, &left.pi), (&right.c, &right.pi), &iface);
+            st.push(LiveSubtree {
+                lo: span.lo,
+                hi: span.hi,
+                c,
+                pi,
+            });
+        },
+    );
+
+    Ok(captured.into_inner().unwrap_or_else(|| live.into_inner()))
+}
+
+/// Compare two snapshots position by position (bottom of stack first).
+#[must_use]
+pub fn diff_states(stream: &[LiveSubtree], batch: &[LiveSubtree]) -> Vec<StateDiff> {
+    let mut out = Vec::new();
+    for pos in 0..stream.len().max(batch.len()) {
+        match (stream.get(pos), batch.get(pos)) {
+            (Some(s), Some(b)) if (s.lo, s.hi) != (b.lo, b.hi) => out.push(StateDiff::Span {
+                pos,
+                stream: (s.lo, s.hi),
+                batch: (b.lo, b.hi),
+            }),
+            (Some(s), Some(b)) if s != b => out.push(StateDiff::Endpoint {
+                pos,
+                stream: *s,
+                batch: *b,
+            }),
+            (Some(_), Some(_)) => {}
+            (s, b) => out.push(StateDiff::Missing {
+                pos,
+                stream: s.copied(),
+                batch: b.copied(),
+            }),
+        }
+    }
+    out
+}
+
+/// Render a snapshot as one line per live subtree (bottom of stack first).
+#[must_use]
+pub fn render_state(state: &[LiveSubtree]) -> String {
+    use std::fmt::Write as _;
+    if state.is_empty() {
+        return "  (empty)\n".to_owned();
+    }
+    let mut out = String::new();
+    for (i, s) in state.iter().enumerate() {
+        let _ = writeln!(out, "  #{i:<2} {s}");
+    }
+    out
+}
+
+fn short_hex(bytes: &[u8; 32]) -> String {
+    use std::fmt::Write as _;
+    bytes[..6]
+        .iter()
+        .fold(String::with_capacity(12), |mut s, b| {
+            let _ = write!(s, "{b:02x}");
+            s
+        })
+}
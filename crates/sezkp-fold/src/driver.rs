@@ -3,13 +3,15 @@
 //!
 //! # Modes
 //!
-//! - **Balanced**: pointerless DFS with an `O(T)` endpoint ledger.
+//! - **Balanced**: pointerless DFS with an `O(T)` endpoint ledger, kept in
+//!   RAM or paged to disk per [`DriverOptions::ledger`].
 //! - **MinRam**: recompute endpoints on demand; keep a tiny LRU cache
 //!   bounded by [`DriverOptions::endpoint_cache`] (default 64).
 //! - **Streaming**: push-based builder that keeps only `O(log T)` live
 //!   subtrees while consuming blocks left→right. Produces the *same* balanced
 //!   tree shape by greedily merging sibling spans where the midpoint equals
-//!   the boundary between adjacent subtrees.
+//!   the boundary between adjacent subtrees. The sink variant can replay
+//!   completed granules from a persistent [`crate::cache::SubtreeCache`].
 //!
 //! # Streaming format
 //!
@@ -34,18 +36,31 @@
     clippy::expect_used
 )]
 
-use anyhow::Result;
+use anyhow::{anyhow, ensure, Result};
 use blake3::Hasher;
 use serde::{Deserialize, Serialize};
+use sezkp_core::frontier::MerkleFrontier;
+use sezkp_core::lru::Lru;
+use sezkp_core::render::render_root;
+use sezkp_core::boundary::{interface_boundary_digest_with, DigestMode};
 use sezkp_core::BlockSummary;
 use sezkp_scheduler as hct;
-use sezkp_stark::v1::columns::interface_boundary_digest;
 use std::cell::RefCell;
 use std::collections::{HashMap, VecDeque};
-use std::io::Write;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::PathBuf;
+use std::time::Instant;
 
-use crate::api::{commit_pi, Commitment, DriverOptions, Fold, Leaf, PiCommitment, Wrap};
+use crate::api::{
+    commit_pi, Commitment, DriverOptions, Fold, FoldParams, Leaf, PiCommitment, Wrap,
+};
 use crate::are::{InterfaceWitness, Pi};
+use crate::cache::{CacheStats, SegmentItem, SubtreeCache};
+use crate::final_wrap::{bundle_digest, FinalWrap, StreamDigest};
+use crate::iface_log::IfaceLog;
+use crate::journal::{self, Checkpoint, Durability, SyncData};
+use crate::ledger::EndpointLedger;
+use crate::spot::{self, SpotChecks};
 
 /// Serializable folding artifact (compact and human-inspectable).
 ///
@@ -62,6 +77,23 @@ pub struct FoldProofBundle<Lp, Fp, Wp> {
     pub folds: Vec<((Commitment, Pi), (Commitment, Pi), (Commitment, Pi), Fp)>,
     /// Optional wrap attestations of intermediate or final roots.
     pub wraps: Vec<((Commitment, Pi), Wp)>,
+    /// Parameters the bundle was produced with (absent in older bundles).
+    #[serde(default)]
+    pub params: Option<FoldParams>,
+    /// Manifest root every gadget proof in the bundle is bound to.
+    #[serde(default)]
+    pub manifest_root: [u8; 32],
+    /// Digest of the public claims bound into the gadget proofs, if any
+    /// (see [`DriverOptions::claims`](crate::api::DriverOptions::claims)).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub claims: Option<[u8; 32]>,
+    /// Final wrap over the whole bundle, if one was requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub final_wrap: Option<FinalWrap>,
+    /// Sampled blocks with manifest-inclusion proofs, if requested (see
+    /// [`DriverOptions::spot_checks`](crate::api::DriverOptions::spot_checks)).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spot_checks: Option<SpotChecks>,
 }
 
 impl<Lp, Fp, Wp> FoldProofBundle<Lp, Fp, Wp> {
@@ -79,6 +111,11 @@ impl<Lp, Fp, Wp> FoldProofBundle<Lp, Fp, Wp> {
             leaves: Vec::new(),
             folds: Vec::new(),
             wraps: Vec::new(),
+            params: None,
+            manifest_root: [0u8; 32],
+            claims: None,
+            final_wrap: None,
+            spot_checks: None,
         }
     }
 
@@ -113,58 +150,10 @@ pub(crate) fn digest_pair(c: &Commitment, pi: &Pi) -> [u8; 32] {
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 struct SpanKey(u32, u32);
 
-/// Extremely small LRU used by the *MinRam* driver to avoid retaining an
-/// `O(T)` ledger. When capacity is zero, the cache is effectively disabled.
-///
-/// The cache stores *endpoints* `(Commitment, Pi)` for previously-computed
-/// subtrees keyed by their span.
-#[derive(Default)]
-struct EndpointCache {
-    cap: usize,
-    map: HashMap<SpanKey, (Commitment, Pi)>,
-    /// Ordering deque: front = LRU, back = MRU.
-    order: VecDeque<SpanKey>,
-}
-
-impl EndpointCache {
-    #[inline]
-    fn new(cap: usize) -> Self {
-        Self {
-            cap,
-            map: HashMap::new(),
-            order: VecDeque::new(),
-        }
-    }
-    #[inline]
-    fn touch_back(&mut self, k: SpanKey) {
-        if let Some(pos) = self.order.iter().position(|x| *x == k) {
-            self.order.remove(pos);
-        }
-        self.order.push_back(k);
-    }
-    #[inline]
-    fn get(&mut self, k: SpanKey) -> Option<(Commitment, Pi)> {
-        if let Some(v) = self.map.get(&k).cloned() {
-            self.touch_back(k);
-            Some(v)
-        } else {
-            None
-        }
-    }
-    #[inline]
-    fn put(&mut self, k: SpanKey, v: (Commitment, Pi)) {
-        if self.cap == 0 {
-            return; // effectively disabled
-        }
-        if !self.map.contains_key(&k) && self.map.len() == self.cap {
-            if let Some(evict) = self.order.pop_front() {
-                self.map.remove(&evict);
-            }
-        }
-        self.map.insert(k, v);
-        self.touch_back(k);
-    }
-}
+/// Small LRU used by the *MinRam* driver to avoid retaining an `O(T)` ledger.
+/// It stores *endpoints* `(Commitment, Pi)` of previously-computed subtrees
+/// keyed by their span; capacity zero disables it.
+type EndpointCache = Lru<SpanKey, (Commitment, Pi)>;
 
 /* ------------------------------ batch driver ------------------------------- */
 
@@ -182,9 +171,14 @@ where
     W: Wrap,
 {
     let t = blocks.len();
+    let _run = sezkp_core::phase_span!("fold.run_pipeline", blocks = t, mode = ?opts.fold_mode);
     if t == 0 {
         // Trivial bundle for empty input; avoids scheduler edge-cases.
-        return FoldProofBundle::empty(0, 0, 0);
+        let mut out = FoldProofBundle::empty(0, 0, 0);
+        out.params = Some(opts.params());
+        out.manifest_root = opts.manifest_root;
+        out.claims = opts.claims;
+        return seal(out, opts);
     }
 
     let root = hct::balanced_tree(t);
@@ -197,47 +191,55 @@ where
 
     match opts.fold_mode {
         crate::api::FoldMode::Balanced => {
-            // Keep a tiny ledger of endpoints at leaf .lo positions.
-            let ledger: RefCell<Vec<Option<(Commitment, Pi)>>> = RefCell::new(vec![None; t]);
+            // Keep a tiny ledger of endpoints at leaf .lo positions. The disk
+            // store falls back to RAM if its backing file cannot be created.
+            let ledger = EndpointLedger::new(t, opts.ledger)
+                .unwrap_or_else(|_| EndpointLedger::Memory(vec![None; t]));
+            let ledger = RefCell::new(ledger);
 
             hct::dfs(
                 t,
                 |leaf_span| {
                     let i = leaf_span.lo as usize;
-                    let (pi, c, pr) = L::prove_leaf(&blocks[i]);
-                    ledger.borrow_mut()[i] = Some((c, pi));
+                    let (pi, c, pr) = {
+                        let _s = sezkp_core::phase_span!("fold.leaf", i);
+                        L::prove_leaf_sampled(&opts.binding_root(), &blocks[i], opts.are_samples)
+                    };
+                    ledger
+                        .borrow_mut()
+                        .set(i, Some((c, pi)))
+                        .expect("endpoint ledger write");
                     leaves.borrow_mut().push((c, pi, pr));
                 },
                 |merge_span| {
                     let (l, r) = merge_span.split_mid();
 
                     // Recover endpoints for left+right subtrees from ledger at child.lo.
-                    let (ci, pi_i) = {
-                        let ldg = ledger.borrow();
-                        ldg[l.lo as usize]
-                            .as_ref()
-                            .expect("left endpoint present in ledger")
-                            .clone()
-                    };
-                    let (cj, pj) = {
-                        let ldg = ledger.borrow();
-                        ldg[r.lo as usize]
-                            .as_ref()
-                            .expect("right endpoint present in ledger")
-                            .clone()
-                    };
+                    let (ci, pi_i) = ledger
+                        .borrow_mut()
+                        .get(l.lo as usize)
+                        .expect("endpoint ledger read")
+                        .expect("left endpoint present in ledger");
+                    let (cj, pj) = ledger
+                        .borrow_mut()
+                        .get(r.lo as usize)
+                        .expect("endpoint ledger read")
+                        .expect("right endpoint present in ledger");
 
                     // Canonical boundary digest between the last left leaf and the first right leaf.
                     let left_blk = &blocks[(l.hi - 1) as usize];
                     let right_blk = &blocks[r.lo as usize];
-                    let digest = interface_boundary_digest(left_blk, right_blk);
+                    let digest = interface_boundary_digest_with(left_blk, right_blk, opts.iface_digest);
                     let iface = InterfaceWitness {
                         left_ctrl_out: pi_i.ctrl_out,
                         right_ctrl_in: pj.ctrl_in,
                         boundary_writes_digest: digest,
                     };
 
-                    let (c_par, pi_par, pf) = F::fold((&ci, &pi_i), (&cj, &pj), &iface);
+                    let (c_par, pi_par, pf) = {
+                        let _s = sezkp_core::phase_span!("fold.fold", lo = l.lo, hi = r.hi);
+                        F::fold(&opts.binding_root(), (&ci, &pi_i), (&cj, &pj), &iface)
+                    };
 
                     folds
                         .borrow_mut()
@@ -246,7 +248,7 @@ where
                     if opts.wrap_cadence != 0 {
                         let k = opts.wrap_cadence as usize;
                         if folds.borrow().len() % k == 0 {
-                            let w = W::wrap((&c_par, &pi_par));
+                            let w = W::wrap(&opts.binding_root(), (&c_par, &pi_par));
                             wraps.borrow_mut().push(((c_par, pi_par), w));
                         }
                     }
@@ -254,8 +256,9 @@ where
                     // Collapse into left endpoint; clear right.
                     {
                         let mut ldg = ledger.borrow_mut();
-                        ldg[l.lo as usize] = Some((c_par, pi_par));
-                        ldg[r.lo as usize] = None;
+                        ldg.set(l.lo as usize, Some((c_par, pi_par)))
+                            .expect("endpoint ledger write");
+                        ldg.set(r.lo as usize, None).expect("endpoint ledger write");
                     }
                 },
             );
@@ -264,6 +267,7 @@ where
             // Recursively build endpoints; keep only a tiny LRU.
             let mut cache = EndpointCache::new(opts.endpoint_cache as usize);
 
+            #[allow(clippy::too_many_arguments)]
             fn build_endpoint<L, F, W>(
                 blocks: &[BlockSummary],
                 span: hct::Interval,
@@ -274,6 +278,9 @@ where
                 >,
                 wraps: &RefCell<Vec<((Commitment, Pi), W::Proof)>>,
                 wrap_cadence: u32,
+                are_samples: u32,
+                iface_digest: DigestMode,
+                manifest_root: &[u8; 32],
             ) -> (Commitment, Pi)
             where
                 L: Leaf,
@@ -281,13 +288,16 @@ where
                 W: Wrap,
             {
                 let key = SpanKey(span.lo, span.hi);
-                if let Some(ep) = cache.get(key) {
-                    return ep;
+                if let Some(ep) = cache.get(&key) {
+                    return *ep;
                 }
 
                 if span.is_leaf() {
                     let i = span.lo as usize;
-                    let (pi, c, pr) = L::prove_leaf(&blocks[i]);
+                    let (pi, c, pr) = {
+                        let _s = sezkp_core::phase_span!("fold.leaf", i);
+                        L::prove_leaf_sampled(manifest_root, &blocks[i], are_samples)
+                    };
                     leaves.borrow_mut().push((c, pi, pr));
                     cache.put(key, (c, pi));
                     return (c, pi);
@@ -295,23 +305,28 @@ where
 
                 let (l, r) = span.split_mid();
                 let (ci, pi_i) = build_endpoint::<L, F, W>(
-                    blocks, l, cache, leaves, folds, wraps, wrap_cadence,
+                    blocks, l, cache, leaves, folds, wraps, wrap_cadence, are_samples,
+                    iface_digest, manifest_root,
                 );
                 let (cj, pj) = build_endpoint::<L, F, W>(
-                    blocks, r, cache, leaves, folds, wraps, wrap_cadence,
+                    blocks, r, cache, leaves, folds, wraps, wrap_cadence, are_samples,
+                    iface_digest, manifest_root,
                 );
 
                 // Boundary between last left leaf and first right leaf.
                 let left_blk = &blocks[(l.hi - 1) as usize];
                 let right_blk = &blocks[r.lo as usize];
-                let digest = interface_boundary_digest(left_blk, right_blk);
+                let digest = interface_boundary_digest_with(left_blk, right_blk, iface_digest);
                 let iface = InterfaceWitness {
                     left_ctrl_out: pi_i.ctrl_out,
                     right_ctrl_in: pj.ctrl_in,
                     boundary_writes_digest: digest,
                 };
 
-                let (c_par, pi_par, pf) = F::fold((&ci, &pi_i), (&cj, &pj), &iface);
+                let (c_par, pi_par, pf) = {
+                    let _s = sezkp_core::phase_span!("fold.fold", lo = span.lo, hi = span.hi);
+                    F::fold(manifest_root, (&ci, &pi_i), (&cj, &pj), &iface)
+                };
                 folds
                     .borrow_mut()
                     .push(((c_par, pi_par), (ci, pi_i), (cj, pj), pf));
@@ -319,7 +334,7 @@ where
                 if wrap_cadence != 0 {
                     let k = wrap_cadence as usize;
                     if folds.borrow().len() % k == 0 {
-                        let w = W::wrap((&c_par, &pi_par));
+                        let w = W::wrap(manifest_root, (&c_par, &pi_par));
                         wraps.borrow_mut().push(((c_par, pi_par), w));
                     }
                 }
@@ -337,15 +352,55 @@ where
                 &folds,
                 &wraps,
                 opts.wrap_cadence,
+                opts.are_samples,
+                opts.iface_digest,
+                &opts.binding_root(),
             );
         }
     }
 
     // Assemble the final bundle.
     let mut out = FoldProofBundle::empty(t, root.lo, root.hi);
+    out.params = Some(opts.params());
+    out.manifest_root = opts.manifest_root;
+    out.claims = opts.claims;
     out.leaves = leaves.into_inner();
     out.folds = folds.into_inner();
     out.wraps = wraps.into_inner();
+    if opts.spot_checks > 0 {
+        out.spot_checks = Some(spot::spot_checks(
+            blocks,
+            &opts.manifest_root,
+            opts.spot_checks,
+        ));
+    }
+    sezkp_core::phase_event!(
+        leaves = out.leaves.len(),
+        folds = out.folds.len(),
+        wraps = out.wraps.len(),
+        "fold pipeline done"
+    );
+    seal(out, opts)
+}
+
+/// Attach the final wrap requested by `opts`, if any, to a finished bundle.
+// Gadget proofs are plain serde data; if one could not be CBOR-encoded the
+// bundle could not be written out either.
+#[allow(clippy::expect_used)]
+fn seal<Lp, Fp, Wp>(
+    mut out: FoldProofBundle<Lp, Fp, Wp>,
+    opts: &DriverOptions,
+) -> FoldProofBundle<Lp, Fp, Wp>
+where
+    Lp: Serialize,
+    Fp: Serialize,
+    Wp: Serialize,
+{
+    if let Some(backend) = opts.final_wrap {
+        let digest = bundle_digest(&out).expect("encode bundle for the final wrap digest");
+        let (c, pi) = crate::bundle_top(&out);
+        out.final_wrap = Some(FinalWrap::prove(backend, (&c, &pi), digest));
+    }
     out
 }
 
@@ -354,11 +409,22 @@ where
 /// Stream header (first CBOR value in the sequence).
 ///
 /// Stream layout: `Header, Item*, Footer` — each is a single CBOR value.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Version 2 adds `iface_window` and repeats the parameters in the footer;
+/// version 1 streams carry the same fields but do not declare the window.
+/// Streams whose seams are digested in another [`DigestMode`] than the
+/// default record it in `iface_digest` (absent otherwise, so older streams
+/// read as [`DigestMode::Full`]).
+/// Version 3 adds `manifest_root`, the root every gadget proof in the stream
+/// is bound to (repeated in the footer). Streams that carry public claims
+/// also record their digest in `claims`, likewise repeated. Late-bound
+/// streams ([`Self::late_bound`]) leave `manifest_root` zero and fix it in
+/// the footer.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct StreamHeader {
     /// Protocol identifier (e.g. `"sezkp-fold-seq"`).
     pub magic: String,
-    /// Version of this CBOR-seq stream format (currently `1`).
+    /// Version of this CBOR-seq stream format (currently `3`).
     pub ver: u16,
     /// Driver options captured at start.
     pub wrap_cadence: u32,
@@ -366,8 +432,75 @@ pub struct StreamHeader {
     pub mode: crate::api::FoldMode,
     /// Reserved for future use (may be `0`).
     pub reserved: u32,
+    /// Boundary window of the interface digest (version 2+).
+    #[serde(default)]
+    pub iface_window: u32,
+    /// Interior steps opened per leaf (absent = 0).
+    #[serde(default)]
+    pub are_samples: u32,
+    /// Seam digest mode (absent = [`DigestMode::Full`]).
+    #[serde(default, skip_serializing_if = "DigestMode::is_full")]
+    pub iface_digest: DigestMode,
+    /// Manifest root the gadget proofs are bound to (version 3+).
+    #[serde(default)]
+    pub manifest_root: [u8; 32],
+    /// Digest of the public claims bound into the gadget proofs, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub claims: Option<[u8; 32]>,
+    /// The manifest root was not known when the stream started: the gadget
+    /// proofs are bound to the zero root (with the claims, if any), and the
+    /// footer's `manifest_root` is the root the streamed blocks hash to,
+    /// which verifiers recompute from the leaf commitments.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub late_bound: bool,
+    /// Tapes may be re-windowed between blocks
+    /// ([`FoldParams::allow_rewindow`]; absent = `false`).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub allow_rewindow: bool,
 }
 
+impl StreamHeader {
+    /// Header for a stream produced with `params`, bound to `manifest_root`.
+    #[must_use]
+    pub fn new(params: &FoldParams, manifest_root: [u8; 32]) -> Self {
+        Self {
+            magic: STREAM_MAGIC.to_owned(),
+            ver: STREAM_VERSION,
+            wrap_cadence: params.wrap_cadence,
+            mode: params.fold_mode,
+            reserved: 0,
+            iface_window: params.iface_window,
+            are_samples: params.are_samples,
+            iface_digest: params.iface_digest,
+            manifest_root,
+            claims: None,
+            late_bound: false,
+            allow_rewindow: params.allow_rewindow,
+        }
+    }
+
+    /// Declared parameters (`None` for version 1 streams).
+    #[must_use]
+    pub const fn params(&self) -> Option<FoldParams> {
+        if self.ver < 2 {
+            return None;
+        }
+        Some(FoldParams {
+            fold_mode: self.mode,
+            wrap_cadence: self.wrap_cadence,
+            iface_window: self.iface_window,
+            are_samples: self.are_samples,
+            iface_digest: self.iface_digest,
+            allow_rewindow: self.allow_rewindow,
+        })
+    }
+}
+
+/// Stream protocol identifier ([`StreamHeader::magic`]).
+pub const STREAM_MAGIC: &str = "sezkp-fold-seq";
+/// Current stream format version ([`StreamHeader::ver`]).
+pub const STREAM_VERSION: u16 = 3;
+
 /// Stream footer (last CBOR value in the sequence).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StreamFooter {
@@ -377,6 +510,28 @@ pub struct StreamFooter {
     pub root_c: Commitment,
     /// Commitment to the final root projection `π`.
     pub root_pi_cmt: PiCommitment,
+    /// The final root projection itself, when the prover revealed it
+    /// ([`DriverOptions::reveal_root_pi`]); verifiers require it to open
+    /// [`Self::root_pi_cmt`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root_pi: Option<Pi>,
+    /// Parameters repeated from the header (version 2+); verifiers require
+    /// the two to agree.
+    #[serde(default)]
+    pub params: Option<FoldParams>,
+    /// Manifest root repeated from the header (version 3+).
+    #[serde(default)]
+    pub manifest_root: [u8; 32],
+    /// Claims digest repeated from the header.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub claims: Option<[u8; 32]>,
+    /// Final wrap over the header and items, if one was requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub final_wrap: Option<FinalWrap>,
+    /// Proving was cancelled after [`Self::n_blocks`] blocks
+    /// ([`StreamDriverSink::finish_truncated`]); the stream is not a proof.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
 }
 
 /// Stream item (middle CBOR values).
@@ -411,6 +566,26 @@ pub enum StreamItem<Lp, Fp, Wp> {
     },
 }
 
+/// Borrowed [`StreamItem`] with the same encoding, for hashing emitted items.
+#[derive(Serialize)]
+enum StreamItemRef<'a, Lp, Fp, Wp> {
+    Leaf {
+        c: &'a Commitment,
+        pi_cmt: &'a PiCommitment,
+        proof: &'a Lp,
+    },
+    Fold {
+        parent: &'a (Commitment, PiCommitment),
+        left: &'a (Commitment, PiCommitment),
+        right: &'a (Commitment, PiCommitment),
+        proof: &'a Fp,
+    },
+    Wrap {
+        root: &'a (Commitment, PiCommitment),
+        proof: &'a Wp,
+    },
+}
+
 /// A sink that receives bundle events as they occur.
 ///
 /// Implementors should be *append-only*: each callback corresponds to one
@@ -432,15 +607,34 @@ pub trait BundleSink<Lp, Fp, Wp> {
     fn on_wrap(&mut self, root: (Commitment, PiCommitment), proof: Wp) -> Result<()>;
     /// Called once at the end of the stream.
     fn finish(&mut self, footer: &StreamFooter) -> Result<()>;
+    /// Called between blocks at each sync point (see [`crate::journal`]):
+    /// make everything written so far durable and, given a `checkpoint`,
+    /// journal it. The default does nothing.
+    ///
+    /// # Errors
+    /// Fails if syncing or journaling fails.
+    fn sync(&mut self, _checkpoint: Option<Checkpoint>) -> Result<()> {
+        Ok(())
+    }
 }
 
 /// A concrete sink that writes a CBOR sequence to any [`Write`] impl.
 ///
 /// Each call serializes exactly one CBOR value to `w`, in this order:
 /// `Header`, then many `Item`s, then the final `Footer`.
+///
+/// A sink built with [`Self::durable`] also honours sync points: it
+/// `fsync`s the stream and writes the checkpoints it is given to a
+/// write-ahead journal (see [`crate::journal`]).
 pub struct CborSeqSink<W: Write> {
     w: W,
     started: bool,
+    /// Bytes written so far (including any resumed prefix).
+    offset: u64,
+    /// Forces `w` to stable storage (set by [`Self::durable`]).
+    sync_data: Option<fn(&mut W) -> std::io::Result<()>>,
+    /// Journal path (set by [`Self::durable`]).
+    wal: Option<PathBuf>,
 }
 
 impl<W: Write> CborSeqSink<W> {
@@ -448,7 +642,69 @@ impl<W: Write> CborSeqSink<W> {
     #[inline]
     #[must_use]
     pub fn new(w: W) -> Self {
-        Self { w, started: false }
+        Self {
+            w,
+            started: false,
+            offset: 0,
+            sync_data: None,
+            wal: None,
+        }
+    }
+
+    /// Continue a stream whose first `offset` bytes (header included) are
+    /// already in place: `w` appends after them and no header is written.
+    #[must_use]
+    pub const fn resumed(mut self, offset: u64) -> Self {
+        self.started = true;
+        self.offset = offset;
+        self
+    }
+
+    /// Bytes written so far (including any resumed prefix).
+    #[inline]
+    #[must_use]
+    pub const fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    fn put<T: Serialize>(&mut self, value: &T) -> Result<()> {
+        let mut counted = Counted {
+            w: &mut self.w,
+            n: &mut self.offset,
+        };
+        ciborium::ser::into_writer(value, &mut counted)?;
+        Ok(())
+    }
+}
+
+impl<W: SyncData> CborSeqSink<W> {
+    /// A sink that `fsync`s `w` at sync points and journals checkpoints to
+    /// `wal` (normally [`crate::journal::wal_path`] of the stream).
+    #[must_use]
+    pub fn durable(w: W, wal: Option<PathBuf>) -> Self {
+        Self {
+            sync_data: Some(W::sync_data),
+            wal,
+            ..Self::new(w)
+        }
+    }
+}
+
+/// Writer adapter counting the bytes it passes on.
+struct Counted<'a, W> {
+    w: &'a mut W,
+    n: &'a mut u64,
+}
+
+impl<W: Write> Write for Counted<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let k = self.w.write(buf)?;
+        *self.n += k as u64;
+        Ok(k)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.w.flush()
     }
 }
 
@@ -461,16 +717,14 @@ where
 {
     fn start(&mut self, header: &StreamHeader) -> Result<()> {
         if !self.started {
-            ciborium::ser::into_writer(header, &mut self.w)?;
+            self.put(header)?;
             self.started = true;
         }
         Ok(())
     }
 
     fn on_leaf(&mut self, c: Commitment, pi_cmt: PiCommitment, proof: Lp) -> Result<()> {
-        let item = StreamItem::<Lp, Fp, Wp>::Leaf { c, pi_cmt, proof };
-        ciborium::ser::into_writer(&item, &mut self.w)?;
-        Ok(())
+        self.put(&StreamItem::<Lp, Fp, Wp>::Leaf { c, pi_cmt, proof })
     }
 
     fn on_fold(
@@ -480,24 +734,44 @@ where
         right: (Commitment, PiCommitment),
         proof: Fp,
     ) -> Result<()> {
-        let item = StreamItem::<Lp, Fp, Wp>::Fold {
+        self.put(&StreamItem::<Lp, Fp, Wp>::Fold {
             parent,
             left,
             right,
             proof,
-        };
-        ciborium::ser::into_writer(&item, &mut self.w)?;
-        Ok(())
+        })
     }
 
     fn on_wrap(&mut self, root: (Commitment, PiCommitment), proof: Wp) -> Result<()> {
-        let item = StreamItem::<Lp, Fp, Wp>::Wrap { root, proof };
-        ciborium::ser::into_writer(&item, &mut self.w)?;
-        Ok(())
+        self.put(&StreamItem::<Lp, Fp, Wp>::Wrap { root, proof })
     }
 
     fn finish(&mut self, footer: &StreamFooter) -> Result<()> {
-        ciborium::ser::into_writer(footer, &mut self.w)?;
+        self.put(footer)?;
+        self.w.flush()?;
+        if let Some(sync_data) = self.sync_data {
+            sync_data(&mut self.w)?;
+            // A complete stream needs no recovery; a truncated one keeps its
+            // journal so the run can be resumed.
+            if let Some(wal) = self.wal.as_ref().filter(|_| !footer.truncated) {
+                if wal.exists() {
+                    std::fs::remove_file(wal)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn sync(&mut self, checkpoint: Option<Checkpoint>) -> Result<()> {
+        self.w.flush()?;
+        let Some(sync_data) = self.sync_data else {
+            return Ok(());
+        };
+        sync_data(&mut self.w)?;
+        if let (Some(wal), Some(mut cp)) = (&self.wal, checkpoint) {
+            cp.offset = self.offset;
+            journal::write_checkpoint(wal, &cp)?;
+        }
         Ok(())
     }
 }
@@ -573,10 +847,27 @@ where
         self.leaves.len()
     }
 
+    /// Snapshot of the live stack (bottom first) for debugging.
+    #[must_use]
+    pub fn live_stack(&self) -> Vec<crate::debug::LiveSubtree> {
+        self.stack
+            .iter()
+            .map(|s| crate::debug::LiveSubtree {
+                lo: s.lo,
+                hi: s.hi,
+                c: s.c,
+                pi: s.p,
+            })
+            .collect()
+    }
+
     /// Push the next validated block and update the streaming state.
     pub fn push_block(&mut self, mut block: BlockSummary) -> anyhow::Result<()> {
         // 1) Leaf proof
-        let (pi, c, pr) = L::prove_leaf(&block);
+        let (pi, c, pr) = {
+            let _s = sezkp_core::phase_span!("fold.leaf", i = self.next_idx);
+            L::prove_leaf_sampled(&self.opts.binding_root(), &block, self.opts.are_samples)
+        };
         self.leaves.push((c, pi, pr));
 
         // 2) New leaf subtree
@@ -608,10 +899,13 @@ where
         self.try_collapses::<L, F, W>();
 
         let mut out = FoldProofBundle::empty(self.leaves.len(), 0, self.next_idx);
+        out.params = Some(self.opts.params());
+        out.manifest_root = self.opts.manifest_root;
+        out.claims = self.opts.claims;
         out.leaves = self.leaves;
         out.folds = self.folds;
         out.wraps = self.wraps;
-        out
+        seal(out, &self.opts)
     }
 
     /// Merge top-of-stack sibling spans until no more merges are possible.
@@ -648,14 +942,23 @@ where
             let left = self.stack.pop().expect("left subtree present");
 
             // Boundary digest between last(left) and first(right)
-            let digest = interface_boundary_digest(&left.last, &right.first);
+            let digest =
+                interface_boundary_digest_with(&left.last, &right.first, self.opts.iface_digest);
             let iface = InterfaceWitness {
                 left_ctrl_out: left.p.ctrl_out,
                 right_ctrl_in: right.p.ctrl_in,
                 boundary_writes_digest: digest,
             };
 
-            let (c_par, p_par, pf) = F::fold((&left.c, &left.p), (&right.c, &right.p), &iface);
+            let (c_par, p_par, pf) = {
+                let _s = sezkp_core::phase_span!("fold.fold", lo = left.lo, hi = right.hi);
+                F::fold(
+                    &self.opts.binding_root(),
+                    (&left.c, &left.p),
+                    (&right.c, &right.p),
+                    &iface,
+                )
+            };
 
             // Record fold + optional wrap
             self.folds
@@ -664,7 +967,7 @@ where
             if self.opts.wrap_cadence != 0 {
                 let k = self.opts.wrap_cadence as usize;
                 if self.folds.len() % k == 0 {
-                    let w = W::wrap((&c_par, &p_par));
+                    let w = W::wrap(&self.opts.binding_root(), (&c_par, &p_par));
                     self.wraps.push(((c_par, p_par), w));
                 }
             }
@@ -684,6 +987,51 @@ where
 
 /* ------------ streaming driver variant that EMITS into a sink --------------- */
 
+/// Header of a stream driven with `opts`.
+/// Seams `(lo, mid, hi)` of the balanced tree over `[lo, hi)`, in the
+/// post-order [`crate::cache::prove_segment`] folds them.
+fn post_order_seams(lo: u32, hi: u32, out: &mut Vec<(u32, u32, u32)>) {
+    if hi - lo < 2 {
+        return;
+    }
+    let mid = lo + (hi - lo) / 2;
+    post_order_seams(lo, mid, out);
+    post_order_seams(mid, hi, out);
+    out.push((lo, mid, hi));
+}
+
+fn stream_header(opts: &DriverOptions) -> StreamHeader {
+    let mut header = StreamHeader::new(&opts.params(), opts.manifest_root);
+    header.claims = opts.claims;
+    header.late_bound = opts.late_bound;
+    header
+}
+
+/// Final wrap digest of a stream prefix: `prefix` holds the header and
+/// whole items, as the journal's offset delimits them.
+fn replay_digest<Lp, Fp, Wp>(prefix: impl Read, header: &StreamHeader) -> Result<StreamDigest>
+where
+    Lp: Serialize + for<'de> Deserialize<'de>,
+    Fp: Serialize + for<'de> Deserialize<'de>,
+    Wp: Serialize + for<'de> Deserialize<'de>,
+{
+    let mut r = BufReader::new(prefix);
+    let first: StreamHeader = ciborium::de::from_reader(&mut r)
+        .map_err(|e| anyhow!("reading the header of the stream being resumed: {e}"))?;
+    ensure!(
+        first == *header,
+        "the stream being resumed does not start with the journaled header"
+    );
+    let mut digest = StreamDigest::new();
+    digest.absorb(&first)?;
+    while !r.fill_buf()?.is_empty() {
+        let item: StreamItem<Lp, Fp, Wp> = ciborium::de::from_reader(&mut r)
+            .map_err(|e| anyhow!("reading an item of the stream being resumed: {e}"))?;
+        digest.absorb(&item)?;
+    }
+    Ok(digest)
+}
+
 /// Streaming driver that emits CBOR-seq (or any [`BundleSink`]) *as it runs*.
 ///
 /// This variant never collects the entire bundle: each leaf/fold/wrap event is
@@ -703,6 +1051,27 @@ where
     started: bool,
     // track folds to decide wrap cadence
     folds_emitted: usize,
+    /// Optional persistent subtree cache (see [`crate::cache`]).
+    cache: Option<SubtreeCache>,
+    /// Blocks of the current, not yet complete cache granule.
+    pending: Vec<BlockSummary>,
+    /// Digest of the emitted header and items, kept for a final wrap.
+    digest: Option<StreamDigest>,
+    /// Expected manifest root, if any.
+    expected_root: Option<[u8; 32]>,
+    /// Frontier recomputing the manifest root (with an expected root, or
+    /// for a late-bound footer).
+    frontier: Option<MerkleFrontier>,
+    /// Items emitted since the last sync point.
+    items_since_sync: u64,
+    /// Time of the last sync point (or of the start).
+    last_sync: Instant,
+    /// Blocks already in a resumed stream's prefix (see [`Self::resume`]).
+    resume_at: u32,
+    /// Interface witness log, if any (see [`Self::with_iface_log`]).
+    iface_log: Option<IfaceLog>,
+    /// Of those, blocks pushed again so far.
+    replayed: u32,
     _phantom: std::marker::PhantomData<(L, F, W)>,
 }
 
@@ -715,16 +1084,100 @@ where
 {
     /// Construct a streaming driver bound to a sink and emit the header.
     pub fn new(mut sink: S, opts: DriverOptions) -> Result<Self> {
+        ensure!(
+            !opts.late_bound || opts.manifest_root == [0u8; 32],
+            "a late-bound stream cannot also be bound to a manifest root up front"
+        );
         // Emit header immediately
-        let header = StreamHeader {
-            magic: "sezkp-fold-seq".to_owned(),
-            ver: 1,
-            wrap_cadence: opts.wrap_cadence,
-            mode: opts.fold_mode,
-            reserved: 0,
-        };
+        let header = stream_header(&opts);
         sink.start(&header)?;
-        Ok(Self {
+        let digest = match opts.final_wrap {
+            Some(_) => {
+                let mut d = StreamDigest::new();
+                d.absorb(&header)?;
+                Some(d)
+            }
+            None => None,
+        };
+        Ok(Self::from_parts(sink, opts, digest))
+    }
+
+    /// Continue the stream journaled in `cp` (see [`journal::recover`]).
+    /// `sink` must append after the stream's first `cp.offset` bytes (see
+    /// [`CborSeqSink::resumed`]).
+    ///
+    /// The blocks before [`Checkpoint::next_idx`] must be pushed again:
+    /// they are checked against the journaled stack's boundary blocks and
+    /// hashed into the manifest-root frontier, but not proved again.
+    ///
+    /// # Errors
+    /// Fails if `opts` describe another stream than the journal's header,
+    /// or ask for a final wrap, whose digest spans the whole stream and is
+    /// not journaled (use [`Self::resume_replaying`] for those).
+    pub fn resume(sink: S, opts: DriverOptions, cp: Checkpoint) -> Result<Self> {
+        ensure!(
+            opts.final_wrap.is_none(),
+            "a stream with a final wrap resumes only from its prefix (see resume_replaying)"
+        );
+        Self::resume_with_digest(sink, opts, cp, None)
+    }
+
+    /// [`Self::resume`] for any stream, final wrap included. `prefix` reads
+    /// the stream from its start; with a final wrap, its first `cp.offset`
+    /// bytes are decoded again and hashed into the digest the wrap attests,
+    /// otherwise it is not read.
+    ///
+    /// # Errors
+    /// As [`Self::resume`], or if the prefix cannot be read, does not start
+    /// with the journaled header or holds a value that is not a stream item.
+    pub fn resume_replaying<R: Read>(
+        sink: S,
+        opts: DriverOptions,
+        cp: Checkpoint,
+        prefix: R,
+    ) -> Result<Self> {
+        let digest = match opts.final_wrap {
+            Some(_) => Some(replay_digest::<L::Proof, F::Proof, W::Proof>(
+                prefix.take(cp.offset),
+                &cp.header,
+            )?),
+            None => None,
+        };
+        Self::resume_with_digest(sink, opts, cp, digest)
+    }
+
+    fn resume_with_digest(
+        sink: S,
+        opts: DriverOptions,
+        cp: Checkpoint,
+        digest: Option<StreamDigest>,
+    ) -> Result<Self> {
+        ensure!(
+            stream_header(&opts) == cp.header,
+            "the journal was written for a stream with other parameters, manifest root or claims"
+        );
+        let mut drv = Self::from_parts(sink, opts, digest);
+        drv.next_idx = cp.next_idx;
+        drv.leaves_seen = cp.n_leaves;
+        drv.folds_emitted = usize::try_from(cp.folds_emitted)?;
+        drv.resume_at = cp.next_idx;
+        drv.stack = cp
+            .stack
+            .into_iter()
+            .map(|n| Subtree {
+                lo: n.lo,
+                hi: n.hi,
+                c: n.c,
+                p: n.pi(),
+                first: n.first,
+                last: n.last,
+            })
+            .collect();
+        Ok(drv)
+    }
+
+    fn from_parts(sink: S, opts: DriverOptions, digest: Option<StreamDigest>) -> Self {
+        Self {
             opts,
             next_idx: 0,
             stack: Vec::new(),
@@ -732,16 +1185,231 @@ where
             leaves_seen: 0,
             started: true,
             folds_emitted: 0,
+            cache: None,
+            pending: Vec::new(),
+            digest,
+            expected_root: None,
+            frontier: opts.late_bound.then(MerkleFrontier::new),
+            items_since_sync: 0,
+            last_sync: Instant::now(),
+            resume_at: 0,
+            replayed: 0,
+            iface_log: None,
             _phantom: std::marker::PhantomData,
-        })
+        }
+    }
+
+    /// Check the ingested blocks against the manifest root `root`.
+    ///
+    /// Each pushed block's leaf hash ([`sezkp_merkle::leaf_hash`]) goes into
+    /// an `O(log T)` frontier, and [`Self::finish`] fails before proving the
+    /// tail or emitting the footer if the recomputed root differs, so the
+    /// stream never completes over data the manifest does not commit to.
+    #[must_use]
+    pub fn with_expected_root(mut self, root: [u8; 32]) -> Self {
+        self.expected_root = Some(root);
+        self.frontier.get_or_insert_with(MerkleFrontier::new);
+        self
+    }
+
+    /// Attach a persistent subtree cache.
+    ///
+    /// Blocks are then buffered per aligned granule (`O(granule)` extra
+    /// memory); each full granule is replayed from the cache or proved and
+    /// stored. The emitted stream is identical to an uncached run.
+    #[must_use]
+    pub fn with_cache(mut self, cache: SubtreeCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Log the interface witness of every fold to `log` as it is emitted
+    /// (see [`crate::iface_log`]). The log is flushed when the stream ends.
+    #[must_use]
+    pub fn with_iface_log(mut self, log: IfaceLog) -> Self {
+        self.iface_log = Some(log);
+        self
+    }
+
+    /// Return the effective driver options.
+    #[inline]
+    #[must_use]
+    pub const fn options(&self) -> &DriverOptions {
+        &self.opts
+    }
+
+    /// Cache counters, if a cache is attached.
+    #[must_use]
+    pub fn cache_stats(&self) -> Option<CacheStats> {
+        self.cache.as_ref().map(SubtreeCache::stats)
     }
 
     /// Push the next block; emit `Leaf` + subsequent `Fold`/`Wrap` items.
-    pub fn push_block(&mut self, mut block: BlockSummary) -> Result<()> {
+    pub fn push_block(&mut self, block: BlockSummary) -> Result<()> {
+        if let Some(frontier) = self.frontier.as_mut() {
+            frontier.push(sezkp_merkle::leaf_hash(&block));
+        }
+        if self.replayed < self.resume_at {
+            return self.replay_journaled(&block);
+        }
+        if let Some(granule) = self.cache.as_ref().map(SubtreeCache::granule) {
+            self.pending.push(block);
+            if self.pending.len() == granule as usize {
+                self.flush_granule()?;
+            }
+        } else {
+            self.push_leaf(block)?;
+        }
+        self.maybe_sync()
+    }
+
+    /// Account for a block already proved into a resumed stream, checking
+    /// it against the journaled boundary blocks.
+    fn replay_journaled(&mut self, block: &BlockSummary) -> Result<()> {
+        let i = self.replayed;
+        for s in &self.stack {
+            ensure!(
+                (s.lo != i || s.first == *block) && (s.hi - 1 != i || s.last == *block),
+                "block {i} differs from the one journaled for the stream being resumed"
+            );
+        }
+        self.replayed += 1;
+        Ok(())
+    }
+
+    /// Reach a sync point if the policy calls for one (see [`crate::journal`]).
+    fn maybe_sync(&mut self) -> Result<()> {
+        let policy = self.opts.sync;
+        if policy.durability == Durability::Buffered || self.items_since_sync == 0 {
+            return Ok(());
+        }
+        let by_items =
+            policy.every_items != 0 && self.items_since_sync >= u64::from(policy.every_items);
+        let by_time = policy.every_secs != 0
+            && self.last_sync.elapsed().as_secs() >= u64::from(policy.every_secs);
+        let always = policy.every_items == 0 && policy.every_secs == 0;
+        if !(always || by_items || by_time) {
+            return Ok(());
+        }
+        let checkpoint = (policy.durability == Durability::Journal).then(|| self.checkpoint());
+        self.sink.sync(checkpoint)?;
+        self.items_since_sync = 0;
+        self.last_sync = Instant::now();
+        Ok(())
+    }
+
+    /// Journal record of the current state (its offset is the sink's to fill).
+    fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            ver: journal::JOURNAL_VERSION,
+            offset: 0,
+            header: stream_header(&self.opts),
+            next_idx: self.next_idx,
+            n_leaves: self.leaves_seen,
+            folds_emitted: self.folds_emitted as u64,
+            stack: self
+                .stack
+                .iter()
+                .map(|s| journal::Node {
+                    lo: s.lo,
+                    hi: s.hi,
+                    c: s.c,
+                    pi: journal::Node::pi_words(&s.p),
+                    first: s.first.clone(),
+                    last: s.last.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Replay (or prove and store) the buffered granule, then push its root.
+    fn flush_granule(&mut self) -> Result<()> {
+        let mut blocks = std::mem::take(&mut self.pending);
+        let _s = sezkp_core::phase_span!("fold.granule", lo = self.next_idx, len = blocks.len());
+        let seg = match self.cache.as_mut() {
+            Some(cache) => cache.get_or_prove::<L, F>(
+                &blocks,
+                self.opts.are_samples,
+                self.opts.iface_digest,
+                &self.opts.binding_root(),
+            )?,
+            None => return Ok(()),
+        };
+
+        // Segment folds come in post-order over the granule's balanced tree.
+        let mut seams = Vec::new();
+        if self.iface_log.is_some() {
+            post_order_seams(self.next_idx, self.next_idx + seg.n_leaves, &mut seams);
+        }
+        let mut seams = seams.into_iter();
+        for item in seg.items {
+            match item {
+                SegmentItem::Leaf { c, pi, proof } => {
+                    self.emit_leaf(c, commit_pi(&pi), proof)?;
+                    self.leaves_seen = self.leaves_seen.saturating_add(1);
+                }
+                SegmentItem::Fold {
+                    parent,
+                    left,
+                    right,
+                    proof,
+                } => {
+                    if let (Some(log), Some((lo, mid, hi))) =
+                        (self.iface_log.as_mut(), seams.next())
+                    {
+                        let at = (mid - self.next_idx) as usize;
+                        let (l, r) = (&blocks[at - 1], &blocks[at]);
+                        let iface = InterfaceWitness {
+                            left_ctrl_out: left.1.ctrl_out,
+                            right_ctrl_in: right.1.ctrl_in,
+                            boundary_writes_digest: interface_boundary_digest_with(
+                                l,
+                                r,
+                                self.opts.iface_digest,
+                            ),
+                        };
+                        log.record((lo, mid, hi), (l, r), &iface, self.opts.iface_digest)?;
+                    }
+                    self.emit_fold(
+                        (parent.0, commit_pi(&parent.1)),
+                        (left.0, commit_pi(&left.1)),
+                        (right.0, commit_pi(&right.1)),
+                        proof,
+                    )?;
+                    self.folds_emitted += 1;
+                    self.maybe_wrap(&parent.0, &parent.1)?;
+                }
+            }
+        }
+
+        let lo = self.next_idx;
+        self.next_idx = self.next_idx.saturating_add(seg.n_leaves);
+        let last = blocks.pop().ok_or_else(|| anyhow::anyhow!("empty granule"))?;
+        let first = if blocks.is_empty() {
+            last.clone()
+        } else {
+            blocks.swap_remove(0)
+        };
+        self.stack.push(Subtree {
+            lo,
+            hi: self.next_idx,
+            c: seg.root.0,
+            p: seg.root.1,
+            first,
+            last,
+        });
+        self.try_collapses::<L, F, W>()
+    }
+
+    /// Prove one leaf directly and push it onto the stack.
+    fn push_leaf(&mut self, block: BlockSummary) -> Result<()> {
         // 1) Leaf proof
-        let (pi, c, pr) = L::prove_leaf(&block);
+        let (pi, c, pr) = {
+            let _s = sezkp_core::phase_span!("fold.leaf", i = self.next_idx);
+            L::prove_leaf_sampled(&self.opts.binding_root(), &block, self.opts.are_samples)
+        };
         let pi_cmt = commit_pi(&pi);
-        self.sink.on_leaf(c, pi_cmt, pr)?;
+        self.emit_leaf(c, pi_cmt, pr)?;
         self.leaves_seen = self.leaves_seen.saturating_add(1);
 
         // 2) New leaf subtree on stack
@@ -764,23 +1432,144 @@ where
     }
 
     /// Finish: fully collapse, emit the footer, and return the final `(C, π)`.
+    ///
+    /// # Errors
+    /// Fails if the blocks do not hash to the root given to
+    /// [`Self::with_expected_root`], or if proving or the sink fails.
     pub fn finish(mut self) -> Result<(Commitment, Pi)> {
+        let _s = sezkp_core::phase_span!("fold.finish", leaves = self.leaves_seen);
+        if let (Some(expected), Some(frontier)) = (&self.expected_root, &self.frontier) {
+            let got = frontier.root();
+            ensure!(
+                got == *expected,
+                "streamed blocks hash to root {}, expected manifest root {} ({} blocks)",
+                render_root(&got),
+                render_root(expected),
+                frontier.len()
+            );
+        }
+        // A trailing partial granule is proved directly (never cached).
+        for block in std::mem::take(&mut self.pending) {
+            self.push_leaf(block)?;
+        }
         self.try_collapses::<L, F, W>()?;
-        // Top of stack should be the root (or empty input → zeroed root).
+        // Top of stack should be the root (or empty input → the empty root).
         let (root_c, root_pi) = if let Some(top) = self.stack.last() {
             (top.c, top.p)
         } else {
-            (Commitment::new([0u8; 32], 0), Pi::default())
+            (Commitment::new(sezkp_core::EMPTY_ROOT, 0), Pi::default())
+        };
+        let final_wrap = match (self.opts.final_wrap, &self.digest) {
+            (Some(backend), Some(d)) => {
+                Some(FinalWrap::prove(backend, (&root_c, &root_pi), d.finalize()))
+            }
+            _ => None,
         };
         let footer = StreamFooter {
             n_blocks: self.leaves_seen,
             root_c,
             root_pi_cmt: commit_pi(&root_pi),
+            root_pi: self.opts.reveal_root_pi.then_some(root_pi),
+            params: Some(self.opts.params()),
+            manifest_root: match &self.frontier {
+                Some(f) if self.opts.late_bound => f.root(),
+                _ => self.opts.manifest_root,
+            },
+            claims: self.opts.claims,
+            final_wrap,
+            truncated: false,
         };
         self.sink.finish(&footer)?;
+        if let Some(log) = self.iface_log.as_mut() {
+            log.flush()?;
+        }
         Ok((root_c, root_pi))
     }
 
+    /// Close a stream that will not be completed (e.g. proving was
+    /// cancelled): emit a footer marked [`StreamFooter::truncated`] over the
+    /// leaves proved so far, so the file ends cleanly and verifiers reject it
+    /// as incomplete. Blocks of an unfinished cache granule are dropped.
+    /// Returns the number of leaves in the stream.
+    ///
+    /// # Errors
+    /// Fails if the sink fails.
+    pub fn finish_truncated(mut self) -> Result<u64> {
+        let (root_c, root_pi) = self.stack.last().map_or_else(
+            || (Commitment::new(sezkp_core::EMPTY_ROOT, 0), Pi::default()),
+            |top| (top.c, top.p),
+        );
+        let footer = StreamFooter {
+            n_blocks: self.leaves_seen,
+            root_c,
+            root_pi_cmt: commit_pi(&root_pi),
+            root_pi: None,
+            params: Some(self.opts.params()),
+            manifest_root: self.opts.manifest_root,
+            claims: self.opts.claims,
+            final_wrap: None,
+            truncated: true,
+        };
+        self.sink.finish(&footer)?;
+        if let Some(log) = self.iface_log.as_mut() {
+            log.flush()?;
+        }
+        Ok(self.leaves_seen)
+    }
+
+    /// Emit a wrap for `(c, p)` if the fold count hits the cadence.
+    fn maybe_wrap(&mut self, c: &Commitment, p: &Pi) -> Result<()> {
+        if self.opts.wrap_cadence != 0 {
+            let k = self.opts.wrap_cadence as usize;
+            if self.folds_emitted % k == 0 {
+                let w = W::wrap(&self.opts.binding_root(), (c, p));
+                self.emit_wrap((*c, commit_pi(p)), w)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Hash `item` into the final wrap digest, if one is kept.
+    fn absorb(&mut self, item: &StreamItemRef<'_, L::Proof, F::Proof, W::Proof>) -> Result<()> {
+        self.digest.as_mut().map_or(Ok(()), |d| d.absorb(item))
+    }
+
+    fn emit_leaf(&mut self, c: Commitment, pi_cmt: PiCommitment, proof: L::Proof) -> Result<()> {
+        self.absorb(&StreamItemRef::Leaf {
+            c: &c,
+            pi_cmt: &pi_cmt,
+            proof: &proof,
+        })?;
+        self.items_since_sync += 1;
+        self.sink.on_leaf(c, pi_cmt, proof)
+    }
+
+    fn emit_fold(
+        &mut self,
+        parent: (Commitment, PiCommitment),
+        left: (Commitment, PiCommitment),
+        right: (Commitment, PiCommitment),
+        proof: F::Proof,
+    ) -> Result<()> {
+        self.absorb(&StreamItemRef::Fold {
+            parent: &parent,
+            left: &left,
+            right: &right,
+            proof: &proof,
+        })?;
+        self.items_since_sync += 1;
+        self.sink.on_fold(parent, left, right, proof)
+    }
+
+    fn emit_wrap(&mut self, root: (Commitment, PiCommitment), proof: W::Proof) -> Result<()> {
+        self.absorb(&StreamItemRef::Wrap {
+            root: &root,
+            proof: &proof,
+        })?;
+        self.items_since_sync += 1;
+        self.sink.on_wrap(root, proof)
+    }
+
     /// Internal helper: perform zero or more collapses and emit folds/wraps.
     fn try_collapses<Lx, Fx, Wx>(&mut self) -> Result<()>
     where
@@ -813,17 +1602,34 @@ where
             let left = self.stack.pop().expect("left subtree present");
 
             // Boundary digest
-            let digest = interface_boundary_digest(&left.last, &right.first);
+            let digest =
+                interface_boundary_digest_with(&left.last, &right.first, self.opts.iface_digest);
             let iface = InterfaceWitness {
                 left_ctrl_out: left.p.ctrl_out,
                 right_ctrl_in: right.p.ctrl_in,
                 boundary_writes_digest: digest,
             };
+            if let Some(log) = self.iface_log.as_mut() {
+                log.record(
+                    (left.lo, left.hi, right.hi),
+                    (&left.last, &right.first),
+                    &iface,
+                    self.opts.iface_digest,
+                )?;
+            }
 
-            let (c_par, p_par, pf) = F::fold((&left.c, &left.p), (&right.c, &right.p), &iface);
+            let (c_par, p_par, pf) = {
+                let _s = sezkp_core::phase_span!("fold.fold", lo = left.lo, hi = right.hi);
+                F::fold(
+                    &self.opts.binding_root(),
+                    (&left.c, &left.p),
+                    (&right.c, &right.p),
+                    &iface,
+                )
+            };
 
             // Emit fold (commit to πs on the wire)
-            self.sink.on_fold(
+            self.emit_fold(
                 (c_par, commit_pi(&p_par)),
                 (left.c, commit_pi(&left.p)),
                 (right.c, commit_pi(&right.p)),
@@ -832,13 +1638,7 @@ where
             self.folds_emitted += 1;
 
             // Maybe emit wrap
-            if self.opts.wrap_cadence != 0 {
-                let k = self.opts.wrap_cadence as usize;
-                if self.folds_emitted % k == 0 {
-                    let w = W::wrap((&c_par, &p_par));
-                    self.sink.on_wrap((c_par, commit_pi(&p_par)), w)?;
-                }
-            }
+            self.maybe_wrap(&c_par, &p_par)?;
 
             // Push parent
             self.stack.push(Subtree {
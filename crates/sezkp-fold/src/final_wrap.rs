@@ -0,0 +1,255 @@
+//! Final wrap: an optional last stage that compresses a finished fold proof
+//! into one opaque attestation (e.g. an external SNARK).
+//!
+//! When [`DriverOptions::final_wrap`](crate::api::DriverOptions::final_wrap)
+//! names a [`WrapBackend`], the drivers hand it the final root `(C, π)` and a
+//! digest of everything the proof contains, and record the result as a
+//! [`FinalWrap`] in the bundle or the stream footer. The digest binds the
+//! attestation to the exact proof it was made for:
+//!
+//! - **bundle:** BLAKE3 over the bundle's leaves, folds, wraps and parameters;
+//! - **stream:** BLAKE3 over the header and every item (the footer is
+//!   excluded, it carries the wrap).
+//!
+//! Both hash the [`sezkp_core::canonical`] encoding, so the digest depends on
+//! the proof alone, not on the codec that stored or transported it.
+//!
+//! Verifiers recompute the digest and look the backend up by id in a
+//! process-wide registry. [`MacWrapBackend`] is always available; external
+//! backends are added with [`register_wrap_backend`].
+
+#![forbid(unsafe_code)]
+#![deny(rust_2018_idioms)]
+#![warn(
+    clippy::all,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::unwrap_used,
+    clippy::expect_used
+)]
+
+use std::sync::{Mutex, PoisonError};
+
+use anyhow::{anyhow, ensure, Context, Result};
+use blake3::Hasher;
+use serde::{Deserialize, Serialize};
+use sezkp_core::canonical;
+use sezkp_crypto::{Blake3Transcript, Transcript};
+
+use crate::api::{Commitment, FoldParams, PiCommitment, WrapBackend, DS_FINAL_WRAP};
+use crate::are::Pi;
+use crate::driver::FoldProofBundle;
+
+/// Domain prefix of bundle digests.
+const DS_BUNDLE_DIGEST: &[u8] = b"sezkp-fold/final-wrap/bundle/v1";
+/// Domain prefix of stream digests.
+const DS_STREAM_DIGEST: &[u8] = b"sezkp-fold/final-wrap/stream/v1";
+
+/// A final wrap recorded with a proof.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FinalWrap {
+    /// Id of the [`WrapBackend`] that made `proof`.
+    pub backend: String,
+    /// Digest of the wrapped bundle or stream (see the module docs).
+    pub digest: [u8; 32],
+    /// Opaque backend output.
+    pub proof: Vec<u8>,
+}
+
+impl FinalWrap {
+    /// Wrap the root `(C, π)` of a proof with digest `digest`.
+    #[must_use]
+    pub fn prove(backend: &dyn WrapBackend, root: (&Commitment, &Pi), digest: [u8; 32]) -> Self {
+        let _s = sezkp_core::phase_span!("fold.final_wrap", backend = backend.id());
+        Self {
+            backend: backend.id().to_owned(),
+            digest,
+            proof: backend.prove(root, &digest),
+        }
+    }
+
+    /// Check the wrap against the root `(C, π_commitment)` and the digest the
+    /// verifier recomputed.
+    ///
+    /// # Errors
+    /// Fails if the digest differs, the backend is not registered or its
+    /// proof does not verify.
+    pub fn verify(&self, root: (&Commitment, &PiCommitment), digest: &[u8; 32]) -> Result<()> {
+        ensure!(
+            &self.digest == digest,
+            "final wrap was made for a different proof (digest mismatch)"
+        );
+        let backend = wrap_backend(&self.backend)
+            .ok_or_else(|| anyhow!("unknown final wrap backend {:?}", self.backend))?;
+        ensure!(
+            backend.verify(root, digest, &self.proof),
+            "final wrap ({}) failed",
+            self.backend
+        );
+        Ok(())
+    }
+}
+
+/* ------------------------------ reference backend ------------------------- */
+
+/// Reference backend: a transcript MAC over the root and digest, bound to
+/// [`DS_FINAL_WRAP`]. Like [`crate::fold::CryptoWrap`] it attests, it does
+/// not compress.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MacWrapBackend;
+
+impl MacWrapBackend {
+    /// Registry id.
+    pub const ID: &'static str = "mac-v1";
+
+    fn mac(root: (&Commitment, &PiCommitment), digest: &[u8; 32]) -> Vec<u8> {
+        let mut tr = Blake3Transcript::new(DS_FINAL_WRAP);
+        tr.absorb("c.root", &root.0.root);
+        tr.absorb_u64("c.len", u64::from(root.0.len));
+        tr.absorb("pi.commit", &root.1 .0);
+        tr.absorb("digest", digest);
+        tr.challenge_bytes("mac", 32)
+    }
+}
+
+impl WrapBackend for MacWrapBackend {
+    fn id(&self) -> &'static str {
+        Self::ID
+    }
+
+    fn prove(&self, root: (&Commitment, &Pi), digest: &[u8; 32]) -> Vec<u8> {
+        Self::mac((root.0, &crate::api::commit_pi(root.1)), digest)
+    }
+
+    fn verify(&self, root: (&Commitment, &PiCommitment), digest: &[u8; 32], proof: &[u8]) -> bool {
+        Self::mac(root, digest) == proof
+    }
+}
+
+/* --------------------------------- registry ------------------------------- */
+
+/// Externally registered backends (the reference backend is built in).
+static REGISTRY: Mutex<Vec<&'static dyn WrapBackend>> = Mutex::new(Vec::new());
+
+/// Make `backend` available to [`wrap_backend`] (and so to verifiers and
+/// `SEZKP_FOLD_FINAL_WRAP`) for the rest of the process.
+///
+/// # Errors
+/// Fails if the id is already taken (including by the built-in
+/// [`MacWrapBackend`]).
+pub fn register_wrap_backend(backend: &'static dyn WrapBackend) -> Result<()> {
+    let mut reg = REGISTRY.lock().unwrap_or_else(PoisonError::into_inner);
+    ensure!(
+        wrap_backend_in(&reg, backend.id()).is_none(),
+        "final wrap backend id {:?} is already registered",
+        backend.id()
+    );
+    reg.push(backend);
+    drop(reg);
+    Ok(())
+}
+
+/// Look up a backend by id.
+#[must_use]
+pub fn wrap_backend(id: &str) -> Option<&'static dyn WrapBackend> {
+    let reg = REGISTRY.lock().unwrap_or_else(PoisonError::into_inner);
+    wrap_backend_in(&reg, id)
+}
+
+fn wrap_backend_in(reg: &[&'static dyn WrapBackend], id: &str) -> Option<&'static dyn WrapBackend> {
+    if id == MacWrapBackend::ID {
+        return Some(&MacWrapBackend);
+    }
+    reg.iter().copied().find(|b| b.id() == id)
+}
+
+/* --------------------------------- digests -------------------------------- */
+
+/// Digest of a bundle's contents (everything but its final wrap).
+pub(crate) fn bundle_digest<Lp, Fp, Wp>(bundle: &FoldProofBundle<Lp, Fp, Wp>) -> Result<[u8; 32]>
+where
+    Lp: Serialize,
+    Fp: Serialize,
+    Wp: Serialize,
+{
+    let mut d = BundleDigest::new(bundle.n_blocks, bundle.tree_span)?;
+    d.section(bundle.leaves.len());
+    for leaf in &bundle.leaves {
+        d.absorb(leaf)?;
+    }
+    d.section(bundle.folds.len());
+    for fold in &bundle.folds {
+        d.absorb(fold)?;
+    }
+    d.section(bundle.wraps.len());
+    for wrap in &bundle.wraps {
+        d.absorb(wrap)?;
+    }
+    d.finalize(bundle.params.as_ref(), &bundle.manifest_root)
+}
+
+/// [`bundle_digest`] computed one record at a time, for verifiers that never
+/// hold the whole bundle: after [`Self::new`], open the leaves, folds and
+/// wraps in turn with [`Self::section`] and [`Self::absorb`] each record.
+///
+/// The bytes hashed are the canonical encoding of the tuple
+/// `(n_blocks, tree_span, leaves, folds, wraps, params, manifest_root)`
+/// (see [`canonical::array_head`]).
+pub(crate) struct BundleDigest(Hasher);
+
+impl BundleDigest {
+    pub(crate) fn new(n_blocks: usize, tree_span: (u32, u32)) -> Result<Self> {
+        let mut h = Hasher::new();
+        h.update(DS_BUNDLE_DIGEST);
+        h.update(&canonical::array_head(7));
+        let mut d = Self(h);
+        d.absorb(&n_blocks)?;
+        d.absorb(&tree_span)?;
+        Ok(d)
+    }
+
+    /// Start a section (leaves, folds or wraps) of `len` records.
+    pub(crate) fn section(&mut self, len: usize) {
+        self.0.update(&canonical::array_head(len as u64));
+    }
+
+    /// Absorb the next record (or header field).
+    pub(crate) fn absorb<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        let bytes = canonical::to_vec(value).context("hashing bundle for the final wrap")?;
+        self.0.update(&bytes);
+        Ok(())
+    }
+
+    pub(crate) fn finalize(
+        mut self,
+        params: Option<&FoldParams>,
+        manifest_root: &[u8; 32],
+    ) -> Result<[u8; 32]> {
+        self.absorb(&params)?;
+        self.absorb(manifest_root)?;
+        Ok(*self.0.finalize().as_bytes())
+    }
+}
+
+/// Running digest of a stream's header and items.
+pub(crate) struct StreamDigest(Hasher);
+
+impl StreamDigest {
+    pub(crate) fn new() -> Self {
+        let mut h = Hasher::new();
+        h.update(DS_STREAM_DIGEST);
+        Self(h)
+    }
+
+    /// Absorb one stream value (header or item), length-prefixed.
+    pub(crate) fn absorb<T: Serialize>(&mut self, value: &T) -> Result<()> {
+        let bytes = canonical::to_vec(value).context("hashing stream value for the final wrap")?;
+        self.0.update(&(bytes.len() as u64).to_le_bytes());
+        self.0.update(&bytes);
+        Ok(())
+    }
+
+    pub(crate) fn finalize(&self) -> [u8; 32] {
+        *self.0.finalize().as_bytes()
+    }
+}
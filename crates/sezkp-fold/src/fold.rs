@@ -1,8 +1,12 @@
 //! Concrete Fold and Wrap gadgets (V2).
 //!
-//! Verifiers consume only **π commitments** (opaque on the wire). The prover
-//! binds those commitments into the transcript, so streaming verification never
-//! needs to observe raw π internals.
+//! The MACs bind only **π commitments**, and every MAC also absorbs the
+//! manifest root the proof is bound to. Since anyone can recompute a MAC, each
+//! fold proof also opens its children's π ([`PiOpening`]); the verifier checks
+//! the openings against the child commitments, re-verifies the ARE interface
+//! proof on them (control chaining), and requires the parent commitment to
+//! commit to their [`are::combine`]. The π relation is thus checked per fold
+//! item, without the verifier ever holding more than one item's π.
 
 #![forbid(unsafe_code)]
 #![deny(rust_2018_idioms)]
@@ -16,14 +20,16 @@
 )]
 
 use serde::{Deserialize, Serialize};
+use sezkp_core::canonical;
 use sezkp_crypto::{Blake3Transcript, Transcript};
 use blake3::Hasher;
 
 use crate::api::{
     commit_pi, Commitment, Fold as FoldT, PiCommitment, Wrap as WrapT, DS_FOLD, DS_WRAP,
+    LABEL_MANIFEST_ROOT,
 };
 use crate::are::{self, CombineAux, InterfaceWitness, Pi};
-use crate::are_replay::{prove_replay_from_children, AreProof};
+use crate::are_replay::{prove_replay_from_children, verify_replay_from_children, AreProof};
 
 /// Manifest/Merkle-compatible parent combiner.
 /// We must match the canonical combiner used by the manifest builder.
@@ -45,10 +51,46 @@ fn combine_commitments(left: &Commitment, right: &Commitment) -> Commitment {
 pub struct CryptoFoldProof {
     /// Interface bundle (for ctrl continuity and documentation).
     pub iface: InterfaceWitness,
-    /// ARE replay proof (encoded; currently not re-verified from commitments).
+    /// ARE replay proof, re-verified on the opened child π.
     pub are: AreProof,
     /// Transcript MAC over all public data for this fold, including π commits.
     pub mac: [u8; 32],
+    /// Opened child π (absent only in proofs made before openings existed,
+    /// which no longer verify).
+    #[serde(default)]
+    pub pi: Option<PiOpening>,
+}
+
+/// The children's π, opened so the verifier can check the π relation of a
+/// fold from the commitments alone.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PiOpening {
+    /// Left child π.
+    pub left: Pi,
+    /// Right child π.
+    pub right: Pi,
+}
+
+impl PiOpening {
+    /// Whether the openings match the child commitments, chain through the
+    /// interface and ARE proof of `proof`, and combine into the parent π
+    /// commitment.
+    #[must_use]
+    pub fn verify(
+        &self,
+        parent: &PiCommitment,
+        left: &PiCommitment,
+        right: &PiCommitment,
+        proof: &CryptoFoldProof,
+    ) -> bool {
+        commit_pi(&self.left) == *left
+            && commit_pi(&self.right) == *right
+            && proof.iface.left_ctrl_out == self.left.ctrl_out
+            && proof.iface.right_ctrl_in == self.right.ctrl_in
+            && verify_replay_from_children(&self.left, &self.right, &proof.are)
+            && commit_pi(&are::combine(&self.left, &self.right, &CombineAux::default()))
+                == *parent
+    }
 }
 
 /// Concrete Fold gadget (V2).
@@ -58,6 +100,7 @@ impl FoldT for CryptoFold {
     type Proof = CryptoFoldProof;
 
     fn fold(
+        manifest_root: &[u8; 32],
         left: (&Commitment, &Pi),
         right: (&Commitment, &Pi),
         iface: &InterfaceWitness,
@@ -72,34 +115,20 @@ impl FoldT for CryptoFold {
         // 3) Parent commitment (manifest/merkle-compatible).
         let c_par = combine_commitments(left.0, right.0);
 
-        // 4) Transcript MAC binds *(C, π-commit)* for L/R/Parent + interface + ARE bytes.
+        // 4) Transcript MAC binds the manifest root, *(C, π-commit)* for
+        //    L/R/Parent, the interface and the ARE bytes.
         let l_pi_cmt = commit_pi(left.1);
         let r_pi_cmt = commit_pi(right.1);
         let p_pi_cmt = commit_pi(&pi_par);
 
-        let mut tr = Blake3Transcript::new(DS_FOLD);
-        // Left
-        tr.absorb("L.c.root", &left.0.root);
-        tr.absorb_u64("L.c.len", left.0.len as u64);
-        tr.absorb("L.pi.commit", &l_pi_cmt.0);
-        // Right
-        tr.absorb("R.c.root", &right.0.root);
-        tr.absorb_u64("R.c.len", right.0.len as u64);
-        tr.absorb("R.pi.commit", &r_pi_cmt.0);
-        // Parent
-        tr.absorb("P.c.root", &c_par.root);
-        tr.absorb_u64("P.c.len", c_par.len as u64);
-        tr.absorb("P.pi.commit", &p_pi_cmt.0);
-        // Interface + ARE proof bytes
-        tr.absorb_u64("iface.left_ctrl_out", iface.left_ctrl_out as u64);
-        tr.absorb_u64("iface.right_ctrl_in", iface.right_ctrl_in as u64);
-        tr.absorb("iface.boundary_digest", &iface.boundary_writes_digest);
-        let are_bytes = bincode::serialize(&are_proof).expect("serialize are_proof");
-        tr.absorb("ARE.proof", &are_bytes);
-
-        let mac_vec = tr.challenge_bytes("mac", 32);
-        let mut mac = [0u8; 32];
-        mac.copy_from_slice(&mac_vec);
+        let mac = fold_mac(
+            manifest_root,
+            (&c_par, &p_pi_cmt),
+            (left.0, &l_pi_cmt),
+            (right.0, &r_pi_cmt),
+            iface,
+            &are_proof,
+        );
 
         (
             c_par,
@@ -108,12 +137,17 @@ impl FoldT for CryptoFold {
                 iface: iface.clone(),
                 are: are_proof,
                 mac,
+                pi: Some(PiOpening {
+                    left: *left.1,
+                    right: *right.1,
+                }),
             },
         )
     }
 
     // Verifiers receive only π commitments on the wire.
     fn verify_fold(
+        manifest_root: &[u8; 32],
         parent: (&Commitment, &PiCommitment),
         left: (&Commitment, &PiCommitment),
         right: (&Commitment, &PiCommitment),
@@ -125,32 +159,66 @@ impl FoldT for CryptoFold {
             return false;
         }
 
-        // 2) Recompute the transcript MAC using only commitments and public interface.
-        let mut tr = Blake3Transcript::new(DS_FOLD);
-        // Left
-        tr.absorb("L.c.root", &left.0.root);
-        tr.absorb_u64("L.c.len", left.0.len as u64);
-        tr.absorb("L.pi.commit", &left.1 .0);
-        // Right
-        tr.absorb("R.c.root", &right.0.root);
-        tr.absorb_u64("R.c.len", right.0.len as u64);
-        tr.absorb("R.pi.commit", &right.1 .0);
-        // Parent
-        tr.absorb("P.c.root", &parent.0.root);
-        tr.absorb_u64("P.c.len", parent.0.len as u64);
-        tr.absorb("P.pi.commit", &parent.1 .0);
-        // Interface + ARE
-        tr.absorb_u64("iface.left_ctrl_out", proof.iface.left_ctrl_out as u64);
-        tr.absorb_u64("iface.right_ctrl_in", proof.iface.right_ctrl_in as u64);
-        tr.absorb("iface.boundary_digest", &proof.iface.boundary_writes_digest);
-        let are_bytes = bincode::serialize(&proof.are).expect("serialize are_proof");
-        tr.absorb("ARE.proof", &are_bytes);
-
-        let mac_vec = tr.challenge_bytes("mac", 32);
-        mac_vec.as_slice() == &proof.mac
+        // 2) The opened child π must combine into the parent π.
+        if !proof
+            .pi
+            .is_some_and(|o| o.verify(parent.1, left.1, right.1, proof))
+        {
+            return false;
+        }
+
+        // 3) Recompute the transcript MAC using only commitments and public interface.
+        fold_mac(manifest_root, parent, left, right, &proof.iface, &proof.are) == proof.mac
+    }
+
+    fn rebind_fold(
+        manifest_root: &[u8; 32],
+        parent: (&Commitment, &PiCommitment),
+        left: (&Commitment, &PiCommitment),
+        right: (&Commitment, &PiCommitment),
+        proof: &mut Self::Proof,
+    ) {
+        proof.mac = fold_mac(manifest_root, parent, left, right, &proof.iface, &proof.are);
     }
 }
 
+/// Transcript MAC binding the manifest root, *(C, π-commit)* for
+/// L/R/Parent, the interface and the ARE proof bytes.
+fn fold_mac(
+    manifest_root: &[u8; 32],
+    parent: (&Commitment, &PiCommitment),
+    left: (&Commitment, &PiCommitment),
+    right: (&Commitment, &PiCommitment),
+    iface: &InterfaceWitness,
+    are_proof: &AreProof,
+) -> [u8; 32] {
+    let mut tr = Blake3Transcript::new(DS_FOLD);
+    tr.absorb(LABEL_MANIFEST_ROOT, manifest_root);
+    // Left
+    tr.absorb("L.c.root", &left.0.root);
+    tr.absorb_u64("L.c.len", left.0.len as u64);
+    tr.absorb("L.pi.commit", &left.1 .0);
+    // Right
+    tr.absorb("R.c.root", &right.0.root);
+    tr.absorb_u64("R.c.len", right.0.len as u64);
+    tr.absorb("R.pi.commit", &right.1 .0);
+    // Parent
+    tr.absorb("P.c.root", &parent.0.root);
+    tr.absorb_u64("P.c.len", parent.0.len as u64);
+    tr.absorb("P.pi.commit", &parent.1 .0);
+    // Interface + ARE proof (canonical encoding, stable across codecs)
+    tr.absorb_u64("iface.left_ctrl_out", iface.left_ctrl_out as u64);
+    tr.absorb_u64("iface.right_ctrl_in", iface.right_ctrl_in as u64);
+    tr.absorb("iface.boundary_digest", &iface.boundary_writes_digest);
+    let are_bytes = canonical::to_vec(are_proof).expect("encode are_proof");
+    tr.absorb("ARE.proof", &are_bytes);
+
+    let mac_vec = tr.challenge_bytes("mac", 32);
+    let mut mac = [0u8; 32];
+    mac.copy_from_slice(&mac_vec);
+    mac
+}
+
 /* --------------------------------- Wrap V2 -------------------------------- */
 
 /// Proof for a wrap step over `(C_root, π_root)`.
@@ -171,32 +239,19 @@ pub struct CryptoWrap;
 impl WrapT for CryptoWrap {
     type Proof = CryptoWrapProof;
 
-    fn wrap(root: (&Commitment, &Pi)) -> Self::Proof {
+    fn wrap(manifest_root: &[u8; 32], root: (&Commitment, &Pi)) -> Self::Proof {
         // Bind the π **commitment** into the MAC so verifiers don't need raw π.
         let pi_cmt = commit_pi(root.1);
-        let mut tr = Blake3Transcript::new(DS_WRAP);
-        tr.absorb("c.root", &root.0.root);
-        tr.absorb_u64("c.len", root.0.len as u64);
-        tr.absorb("pi.commit", &pi_cmt.0);
-        let mac = {
-            let v = tr.challenge_bytes("mac", 32);
-            let mut out = [0u8; 32];
-            out.copy_from_slice(&v);
-            out
-        };
-        CryptoWrapProof::V1Mac(mac)
+        CryptoWrapProof::V1Mac(wrap_mac(manifest_root, (root.0, &pi_cmt)))
     }
 
-    fn verify_wrap(root: (&Commitment, &PiCommitment), proof: &Self::Proof) -> bool {
+    fn verify_wrap(
+        manifest_root: &[u8; 32],
+        root: (&Commitment, &PiCommitment),
+        proof: &Self::Proof,
+    ) -> bool {
         match proof {
-            CryptoWrapProof::V1Mac(mac) => {
-                let mut tr = Blake3Transcript::new(DS_WRAP);
-                tr.absorb("c.root", &root.0.root);
-                tr.absorb_u64("c.len", root.0.len as u64);
-                tr.absorb("pi.commit", &root.1 .0);
-                let v = tr.challenge_bytes("mac", 32);
-                v.as_slice() == mac
-            }
+            CryptoWrapProof::V1Mac(mac) => wrap_mac(manifest_root, root) == *mac,
             CryptoWrapProof::V2Stark(_) => {
                 // No raw π available to reconstruct public inputs; reject for now.
                 false
@@ -204,3 +259,16 @@ impl WrapT for CryptoWrap {
         }
     }
 }
+
+/// Transcript MAC attesting `(manifest root, C_root, π_commit)` under [`DS_WRAP`].
+fn wrap_mac(manifest_root: &[u8; 32], root: (&Commitment, &PiCommitment)) -> [u8; 32] {
+    let mut tr = Blake3Transcript::new(DS_WRAP);
+    tr.absorb(LABEL_MANIFEST_ROOT, manifest_root);
+    tr.absorb("c.root", &root.0.root);
+    tr.absorb_u64("c.len", root.0.len as u64);
+    tr.absorb("pi.commit", &root.1 .0);
+    let v = tr.challenge_bytes("mac", 32);
+    let mut out = [0u8; 32];
+    out.copy_from_slice(&v);
+    out
+}
@@ -0,0 +1,197 @@
+//! Interface witness logs for external audit.
+//!
+//! With [`StreamDriverSink::with_iface_log`] the streaming driver writes one
+//! NDJSON [`IfaceRecord`] per fold as it emits it: the seam's span, the
+//! boundary blocks' ids, the control values handed across and the interface
+//! digest. Tooling can then re-derive every digest from the blocks file
+//! ([`check_iface_log`], CLI `check-iface-log`) without decoding the proof
+//! stream or linking the gadgets.
+//!
+//! Records appear in fold emission order (bottom-up, left to right). Every
+//! seam `mid` is folded at most once, so the records have distinct `mid`s;
+//! a stream of `T = 2^k` blocks logs all `T - 1` seams. A resumed stream
+//! only logs the folds made after the resume.
+//!
+//! [`StreamDriverSink::with_iface_log`]: crate::driver::StreamDriverSink::with_iface_log
+
+#![forbid(unsafe_code)]
+#![deny(rust_2018_idioms)]
+#![warn(
+    missing_docs,
+    clippy::all,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::unwrap_used,
+    clippy::expect_used
+)]
+
+use std::io::{BufRead, Write};
+
+use anyhow::{bail, ensure, Context, Result};
+use serde::{Deserialize, Serialize};
+use sezkp_core::boundary::{interface_boundary_digest_with, DigestMode};
+use sezkp_core::render::{parse_root, RootFormat};
+use sezkp_core::BlockSummary;
+
+use crate::are::InterfaceWitness;
+
+/// One fold's interface witness, as logged.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IfaceRecord {
+    /// First block position of the folded span.
+    pub lo: u32,
+    /// Seam position: the right child's first block (the left child ends at
+    /// `mid - 1`).
+    pub mid: u32,
+    /// One past the last block position of the folded span.
+    pub hi: u32,
+    /// `block_id` of the block left of the seam.
+    pub left_block_id: u32,
+    /// `block_id` of the block right of the seam.
+    pub right_block_id: u32,
+    /// Control state the left child ends in.
+    pub left_ctrl_out: u32,
+    /// Control state the right child starts in.
+    pub right_ctrl_in: u32,
+    /// What the digest hashes.
+    pub mode: DigestMode,
+    /// Interface digest (lowercase hex).
+    pub digest: String,
+}
+
+/// NDJSON writer of [`IfaceRecord`]s.
+pub struct IfaceLog {
+    w: Box<dyn Write + Send>,
+    records: u64,
+}
+
+impl std::fmt::Debug for IfaceLog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IfaceLog")
+            .field("records", &self.records)
+            .finish_non_exhaustive()
+    }
+}
+
+impl IfaceLog {
+    /// Log to `w` (buffer it; one small write per record).
+    #[must_use]
+    pub fn new(w: impl Write + Send + 'static) -> Self {
+        Self {
+            w: Box::new(w),
+            records: 0,
+        }
+    }
+
+    /// Records written so far.
+    #[must_use]
+    pub const fn records(&self) -> u64 {
+        self.records
+    }
+
+    /// Log the fold of `[lo, mid)` and `[mid, hi)` across `left | right`.
+    pub(crate) fn record(
+        &mut self,
+        (lo, mid, hi): (u32, u32, u32),
+        (left, right): (&BlockSummary, &BlockSummary),
+        iface: &InterfaceWitness,
+        mode: DigestMode,
+    ) -> Result<()> {
+        let rec = IfaceRecord {
+            lo,
+            mid,
+            hi,
+            left_block_id: left.block_id,
+            right_block_id: right.block_id,
+            left_ctrl_out: iface.left_ctrl_out,
+            right_ctrl_in: iface.right_ctrl_in,
+            mode,
+            digest: RootFormat::default().render(&iface.boundary_writes_digest),
+        };
+        serde_json::to_writer(&mut self.w, &rec).context("write interface log")?;
+        self.w.write_all(b"\n").context("write interface log")?;
+        self.records += 1;
+        Ok(())
+    }
+
+    /// Flush the writer.
+    pub(crate) fn flush(&mut self) -> Result<()> {
+        self.w.flush().context("flush interface log")
+    }
+}
+
+/// Read an NDJSON interface log.
+///
+/// # Errors
+/// Fails on I/O errors or a malformed line (named by its 1-based number).
+pub fn read_iface_log<R: BufRead>(r: R) -> Result<Vec<IfaceRecord>> {
+    r.lines()
+        .enumerate()
+        .filter(|(_, l)| l.as_ref().map_or(true, |l| !l.trim().is_empty()))
+        .map(|(i, l)| {
+            let l = l.with_context(|| format!("read interface log line {}", i + 1))?;
+            serde_json::from_str(&l).with_context(|| format!("parse interface log line {}", i + 1))
+        })
+        .collect()
+}
+
+/// Re-derive every logged interface from `blocks` (in file order) and check
+/// it: the boundary block ids, their control values and the digest.
+///
+/// Blocks are streamed once, keeping the previous block; only the records
+/// are held in memory. Returns the number of records checked.
+///
+/// # Errors
+/// Fails on the first mismatch, on duplicate or out-of-range seams, or if
+/// a block cannot be read.
+pub fn check_iface_log<I>(blocks: I, mut records: Vec<IfaceRecord>) -> Result<usize>
+where
+    I: IntoIterator<Item = Result<BlockSummary>>,
+{
+    records.sort_by_key(|r| r.mid);
+    if let Some(w) = records.windows(2).find(|w| w[0].mid == w[1].mid) {
+        bail!("seam {} is logged more than once", w[0].mid);
+    }
+    let mut pending = records.iter().peekable();
+    let mut prev: Option<BlockSummary> = None;
+    for (pos, block) in blocks.into_iter().enumerate() {
+        let block = block.with_context(|| format!("read block {pos}"))?;
+        let pos = u32::try_from(pos).context("block position overflows u32")?;
+        if let (Some(rec), Some(left)) = (pending.next_if(|r| r.mid == pos), prev.as_ref()) {
+            check_record(rec, left, &block)
+                .with_context(|| format!("seam {} (span {}..{})", rec.mid, rec.lo, rec.hi))?;
+        }
+        prev = Some(block);
+    }
+    if let Some(rec) = pending.next() {
+        bail!("seam {} is not between two blocks of the file", rec.mid);
+    }
+    Ok(records.len())
+}
+
+fn check_record(rec: &IfaceRecord, left: &BlockSummary, right: &BlockSummary) -> Result<()> {
+    ensure!(rec.lo < rec.mid && rec.mid < rec.hi, "malformed span");
+    ensure!(
+        (rec.left_block_id, rec.right_block_id) == (left.block_id, right.block_id),
+        "logged blocks {} | {} but the file has {} | {}",
+        rec.left_block_id,
+        rec.right_block_id,
+        left.block_id,
+        right.block_id
+    );
+    ensure!(
+        rec.left_ctrl_out == u32::from(left.ctrl_out)
+            && rec.right_ctrl_in == u32::from(right.ctrl_in),
+        "logged ctrl {} -> {} but the blocks have {} -> {}",
+        rec.left_ctrl_out,
+        rec.right_ctrl_in,
+        left.ctrl_out,
+        right.ctrl_in
+    );
+    let logged = parse_root(&rec.digest).context("logged digest")?;
+    ensure!(
+        logged == interface_boundary_digest_with(left, right, rec.mode),
+        "logged digest does not match the blocks"
+    );
+    Ok(())
+}
@@ -0,0 +1,199 @@
+//! Crash consistency for CBOR-seq proof streams.
+//!
+//! A streaming proof can run for hours, and by default its output is only as
+//! durable as the OS page cache: a power loss or kernel crash can drop the
+//! tail of the file or leave a torn CBOR value in it. The
+//! [`SyncPolicy`](crate::api::SyncPolicy) in
+//! [`DriverOptions::sync`](crate::api::DriverOptions::sync) picks one of
+//! three [`Durability`] levels:
+//!
+//! - **Buffered**: nothing extra (the default).
+//! - **Fsync**: at each *sync point* the stream is flushed and `fsync`ed, so
+//!   everything before the last sync point survives a crash.
+//! - **Journal**: as `Fsync`, and a small write-ahead [`Checkpoint`] of the
+//!   driver stack is then written next to the stream (see [`wal_path`]).
+//!
+//! Sync points fall between blocks, after every item a block produced has
+//! been written, once `every_items` items or `every_secs` seconds have
+//! passed since the last one (every block if neither is set).
+//!
+//! After a crash, [`recover`] truncates the stream to the offset recorded in
+//! the journal and returns the checkpoint;
+//! [`StreamDriverSink::resume`](crate::driver::StreamDriverSink::resume)
+//! then continues the stream from it (or
+//! [`StreamDriverSink::resume_replaying`](crate::driver::StreamDriverSink::resume_replaying)
+//! for a stream with a final wrap, which re-hashes the kept prefix into the
+//! digest the wrap attests). The journal is replaced atomically
+//! (write, `fsync`, rename), so it always names a complete sync point, and
+//! it is removed once the stream is finished with a complete footer.
+
+use anyhow::{ensure, Context, Result};
+use serde::{Deserialize, Serialize};
+use sezkp_core::BlockSummary;
+use sezkp_gadgets::F1;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::api::Commitment;
+pub use crate::api::Durability;
+use crate::are::{Pi, Q};
+use crate::driver::StreamHeader;
+
+/// Journal format version ([`Checkpoint::ver`]).
+pub const JOURNAL_VERSION: u16 = 1;
+
+/// Writers whose buffered bytes can be forced to stable storage.
+pub trait SyncData: Write {
+    /// Flush and `fsync` the data written so far.
+    ///
+    /// # Errors
+    /// Fails if flushing or syncing fails.
+    fn sync_data(&mut self) -> std::io::Result<()>;
+}
+
+impl SyncData for File {
+    fn sync_data(&mut self) -> std::io::Result<()> {
+        Self::sync_data(self)
+    }
+}
+
+impl SyncData for BufWriter<File> {
+    fn sync_data(&mut self) -> std::io::Result<()> {
+        self.flush()?;
+        self.get_ref().sync_data()
+    }
+}
+
+/// One live subtree of the streaming driver's stack.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub(crate) struct Node {
+    pub(crate) lo: u32,
+    pub(crate) hi: u32,
+    pub(crate) c: Commitment,
+    /// [`Pi`] as `(ctrl_in, ctrl_out, flags, acc)`.
+    pub(crate) pi: (u32, u32, u32, [u64; Q]),
+    pub(crate) first: BlockSummary,
+    pub(crate) last: BlockSummary,
+}
+
+impl Node {
+    pub(crate) fn pi_words(p: &Pi) -> (u32, u32, u32, [u64; Q]) {
+        (p.ctrl_in, p.ctrl_out, p.flags, p.acc.map(|x| x.0))
+    }
+
+    pub(crate) fn pi(&self) -> Pi {
+        let (ctrl_in, ctrl_out, flags, acc) = self.pi;
+        Pi {
+            ctrl_in,
+            ctrl_out,
+            flags,
+            acc: acc.map(F1::from_u64),
+        }
+    }
+}
+
+/// Write-ahead record of a streaming driver at a sync point: everything
+/// needed to continue the stream after its first `offset` bytes.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Checkpoint {
+    /// Journal format version ([`JOURNAL_VERSION`]).
+    pub ver: u16,
+    /// Length of the durable stream prefix, in bytes.
+    pub offset: u64,
+    /// Header of the stream, to match the resuming driver's options against.
+    pub header: StreamHeader,
+    /// Blocks proved into the stream prefix; a resumed stream continues
+    /// with the block at this index.
+    pub next_idx: u32,
+    /// Leaves in the stream prefix.
+    pub n_leaves: u64,
+    /// Folds in the stream prefix (drives the wrap cadence).
+    pub folds_emitted: u64,
+    /// Live subtrees, bottom of the stack first.
+    pub(crate) stack: Vec<Node>,
+}
+
+/// Journal of the stream at `stream`: the same path with `.wal` appended.
+#[must_use]
+pub fn wal_path(stream: &Path) -> PathBuf {
+    let mut name = stream.as_os_str().to_owned();
+    name.push(".wal");
+    PathBuf::from(name)
+}
+
+/// Atomically replace the journal at `path` with `cp`.
+///
+/// # Errors
+/// Fails if the journal cannot be written, synced or renamed into place.
+pub fn write_checkpoint(path: &Path, cp: &Checkpoint) -> Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    {
+        let mut w = BufWriter::new(
+            File::create(&tmp).with_context(|| format!("create {}", tmp.display()))?,
+        );
+        ciborium::ser::into_writer(cp, &mut w)
+            .with_context(|| format!("write journal {}", tmp.display()))?;
+        w.sync_data()?;
+    }
+    std::fs::rename(&tmp, path)
+        .with_context(|| format!("rename {} to {}", tmp.display(), path.display()))?;
+    // Make the rename itself durable where directories can be synced.
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        if let Ok(d) = File::open(dir) {
+            let _ = d.sync_all();
+        }
+    }
+    Ok(())
+}
+
+/// Read the journal at `path`.
+///
+/// # Errors
+/// Fails if the journal cannot be read or decoded, or has another version.
+pub fn read_checkpoint(path: &Path) -> Result<Checkpoint> {
+    let f = File::open(path).with_context(|| format!("open journal {}", path.display()))?;
+    let cp: Checkpoint = ciborium::de::from_reader(std::io::BufReader::new(f))
+        .with_context(|| format!("decode journal {}", path.display()))?;
+    ensure!(
+        cp.ver == JOURNAL_VERSION,
+        "unsupported journal version {} in {}",
+        cp.ver,
+        path.display()
+    );
+    Ok(cp)
+}
+
+/// Roll the stream at `stream` back to its last journaled sync point.
+///
+/// Returns `None` if there is no journal (the stream never reached a sync
+/// point, or was finished). Otherwise the stream is truncated to the
+/// checkpoint's offset, dropping anything written after it (a torn value,
+/// or the footer of a cancelled run), and the checkpoint is returned.
+///
+/// # Errors
+/// Fails if the journal is unreadable or the stream is shorter than the
+/// journal claims (it was not written under this journal).
+pub fn recover(stream: &Path) -> Result<Option<Checkpoint>> {
+    let wal = wal_path(stream);
+    if !wal.exists() {
+        return Ok(None);
+    }
+    let cp = read_checkpoint(&wal)?;
+    let f = OpenOptions::new()
+        .write(true)
+        .open(stream)
+        .with_context(|| format!("open proof stream {}", stream.display()))?;
+    let len = f.metadata()?.len();
+    ensure!(
+        len >= cp.offset,
+        "proof stream {} has {len} bytes but its journal expects at least {}",
+        stream.display(),
+        cp.offset
+    );
+    f.set_len(cp.offset)?;
+    f.sync_all()?;
+    Ok(Some(cp))
+}
@@ -3,7 +3,8 @@
 //! Leaf commitment **must** match `sezkp_merkle::leaf_hash` exactly.
 //! The proof consists of a micro-proof binding the π limbs + boundary digests
 //! and an outer transcript MAC under `DS_LEAF` that binds
-//! `(C, π-commitment, digests, proof)`.
+//! `(manifest root, C, π-commitment, digests, proof)`, plus the sampled interior steps
+//! ([`crate::are_sample`]) when the proof carries them.
 
 #![forbid(unsafe_code)]
 #![deny(rust_2018_idioms)]
@@ -16,15 +17,16 @@
 )]
 
 use serde::{Deserialize, Serialize};
-use sezkp_core::BlockSummary;
+use sezkp_core::{canonical, BlockSummary};
 use sezkp_crypto::{Blake3Transcript, Transcript};
 use sezkp_merkle::leaf_hash;
 
-use crate::api::{commit_pi, Commitment, Leaf, PiCommitment, DS_LEAF};
+use crate::api::{commit_pi, Commitment, Leaf, PiCommitment, DS_LEAF, LABEL_MANIFEST_ROOT};
 use crate::are::Pi;
+use crate::are_sample::StepSamples;
 
-use sezkp_stark::v1::air::{prove_leaf_pi, verify_leaf_pi, PiPublic, StarkProofV1};
-use sezkp_stark::v1::field::F1;
+use sezkp_gadgets::leaf_pi::{prove_leaf_pi, verify_leaf_pi, PiPublic, StarkProofV1};
+use sezkp_gadgets::F1;
 
 /// Proof object for the leaf.
 ///
@@ -33,6 +35,7 @@ use sezkp_stark::v1::field::F1;
 /// - `proof` is the micro-proof attesting those public inputs.
 /// - `mac` binds everything (including the micro-proof bytes) to the transcript
 ///   domain `DS_LEAF`, so the caller doesn't have to re-hash.
+/// - `samples` opens transcript-sampled interior steps of the block.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CryptoLeafProof {
     /// Public inputs to LeafPiAir (π limbs + boundary digests).
@@ -41,6 +44,87 @@ pub struct CryptoLeafProof {
     pub proof: StarkProofV1,
     /// Outer transcript MAC under `DS_LEAF` (binds C, π-commitment, digests, proof).
     pub mac: [u8; 32],
+    /// Sampled interior steps (absent when proved with `are_samples = 0`).
+    #[serde(default)]
+    pub samples: Option<StepSamples>,
+}
+
+/// Domain prefix of the samples digest absorbed into the MAC.
+const DS_SAMPLES_DIGEST: &[u8] = b"sezkp-fold/leaf/samples/v1";
+
+/// Outer transcript MAC binding `(manifest root, C, π-commitment, digests,
+/// micro-proof, samples)`.
+fn leaf_mac(
+    manifest_root: &[u8; 32],
+    c: &Commitment,
+    pi_cmt: &PiCommitment,
+    public: &PiPublic,
+    inner: &StarkProofV1,
+    samples: Option<&[u8; 32]>,
+) -> [u8; 32] {
+    let mut tr = Blake3Transcript::new(DS_LEAF);
+    tr.absorb(LABEL_MANIFEST_ROOT, manifest_root);
+    tr.absorb("c.root", &c.root);
+    tr.absorb_u64("c.len", c.len as u64);
+    tr.absorb("pi.commit", &pi_cmt.0);
+    tr.absorb("left_tail", &public.left_tail_digest);
+    tr.absorb("right_head", &public.right_head_digest);
+    tr.absorb("leaf_pi.mac", &inner.mac);
+    if let Some(d) = samples {
+        tr.absorb("are.samples", d);
+    }
+
+    let mac_vec = tr.challenge_bytes("mac", 32);
+    let mut mac = [0u8; 32];
+    mac.copy_from_slice(&mac_vec);
+    mac
+}
+
+fn samples_digest(s: &StepSamples) -> Option<[u8; 32]> {
+    canonical::digest(DS_SAMPLES_DIGEST, s).ok()
+}
+
+/// Digest of the proof's sampled steps, `Err` if they fail to encode.
+fn proof_samples_digest(proof: &CryptoLeafProof) -> Result<Option<[u8; 32]>, ()> {
+    proof
+        .samples
+        .as_ref()
+        .map(|s| samples_digest(s).ok_or(()))
+        .transpose()
+}
+
+impl CryptoLeaf {
+    /// π commitment, micro-proof and MAC checks (everything but the samples).
+    fn verify_core(
+        manifest_root: &[u8; 32],
+        commit: &Commitment,
+        pi_cmt: &PiCommitment,
+        proof: &CryptoLeafProof,
+    ) -> bool {
+        // 1) Reconstruct π from the public inputs and check its commitment.
+        let pi_rebuilt = pi_from_public(&proof.public);
+        if commit_pi(&pi_rebuilt) != *pi_cmt {
+            return false;
+        }
+
+        // 2) Verify the inner micro-proof over the public inputs.
+        if !verify_leaf_pi(&proof.public, &proof.proof) {
+            return false;
+        }
+
+        // 3) Rebuild the outer transcript and check the MAC (binding to π commitment).
+        let Ok(samples) = proof_samples_digest(proof) else {
+            return false;
+        };
+        leaf_mac(
+            manifest_root,
+            commit,
+            pi_cmt,
+            &proof.public,
+            &proof.proof,
+            samples.as_ref(),
+        ) == proof.mac
+    }
 }
 
 /// Assemble a `Pi` from `PiPublic` (pack limbs into 4 F1 registers).
@@ -62,7 +146,17 @@ pub struct CryptoLeaf;
 impl Leaf for CryptoLeaf {
     type Proof = CryptoLeafProof;
 
-    fn prove_leaf(block: &BlockSummary) -> (Pi, Commitment, Self::Proof) {
+    fn prove_leaf(manifest_root: &[u8; 32], block: &BlockSummary) -> (Pi, Commitment, Self::Proof) {
+        Self::prove_leaf_sampled(manifest_root, block, 0)
+    }
+
+    // Rows and samples are plain serde data that always encode.
+    #[allow(clippy::expect_used)]
+    fn prove_leaf_sampled(
+        manifest_root: &[u8; 32],
+        block: &BlockSummary,
+        samples: u32,
+    ) -> (Pi, Commitment, Self::Proof) {
         // 1) Inner micro-proof: produces public view + proof
         let (public, inner) = prove_leaf_pi(block).expect("leaf π proof");
 
@@ -72,46 +166,84 @@ impl Leaf for CryptoLeaf {
         // 3) Manifest-compatible commitment to the block's public shape
         let c = Commitment::new(leaf_hash(block), 1);
 
-        // 4) Outer transcript MAC binding (C, π-commitment, boundary digests, micro-proof)
-        let pi_cmt = commit_pi(&pi);
-        let mut tr = Blake3Transcript::new(DS_LEAF);
-        tr.absorb("c.root", &c.root);
-        tr.absorb_u64("c.len", c.len as u64);
-        tr.absorb("pi.commit", &pi_cmt.0);
-        tr.absorb("left_tail", &public.left_tail_digest);
-        tr.absorb("right_head", &public.right_head_digest);
-        tr.absorb("leaf_pi.mac", &inner.mac);
-
-        let mac_vec = tr.challenge_bytes("mac", 32);
-        let mut mac = [0u8; 32];
-        mac.copy_from_slice(&mac_vec);
-
-        (pi, c, CryptoLeafProof { public, proof: inner, mac })
+        // 4) Sampled interior steps, if requested
+        let samples = (samples > 0)
+            .then(|| StepSamples::prove(block, &c, samples).expect("encode sampled steps"));
+        let digest = samples
+            .as_ref()
+            .map(|s| samples_digest(s).expect("encode sampled steps"));
+
+        // 5) Outer transcript MAC binding (manifest root, C, π-commitment,
+        //    boundary digests, micro-proof, samples)
+        let mac = leaf_mac(
+            manifest_root,
+            &c,
+            &commit_pi(&pi),
+            &public,
+            &inner,
+            digest.as_ref(),
+        );
+
+        (
+            pi,
+            c,
+            CryptoLeafProof {
+                public,
+                proof: inner,
+                mac,
+                samples,
+            },
+        )
     }
 
     // Verifier sees only the π commitment, not the raw π.
-    fn verify_leaf(commit: &Commitment, pi_cmt: &PiCommitment, proof: &Self::Proof) -> bool {
-        // 1) Reconstruct π from the public inputs and check its commitment.
-        let pi_rebuilt = pi_from_public(&proof.public);
-        if commit_pi(&pi_rebuilt) != *pi_cmt {
-            return false;
-        }
+    fn verify_leaf(
+        manifest_root: &[u8; 32],
+        commit: &Commitment,
+        pi_cmt: &PiCommitment,
+        proof: &Self::Proof,
+    ) -> bool {
+        Self::verify_core(manifest_root, commit, pi_cmt, proof)
+            && proof.samples.as_ref().is_none_or(|s| {
+                // Without a required count, check as many draws as were opened.
+                let r = u32::try_from(s.openings.len()).unwrap_or(u32::MAX);
+                s.verify(commit, r).is_ok()
+            })
+    }
 
-        // 2) Verify the inner micro-proof over the public inputs.
-        if !verify_leaf_pi(&proof.public, &proof.proof) {
-            return false;
-        }
+    fn verify_leaf_sampled(
+        manifest_root: &[u8; 32],
+        commit: &Commitment,
+        pi_cmt: &PiCommitment,
+        proof: &Self::Proof,
+        samples: u32,
+    ) -> bool {
+        proof.samples.as_ref().map_or_else(
+            || samples == 0 && Self::verify_core(manifest_root, commit, pi_cmt, proof),
+            |s| {
+                samples > 0
+                    && Self::verify_core(manifest_root, commit, pi_cmt, proof)
+                    && s.verify(commit, samples).is_ok()
+            },
+        )
+    }
 
-        // 3) Rebuild the outer transcript and check the MAC (binding to π commitment).
-        let mut tr = Blake3Transcript::new(DS_LEAF);
-        tr.absorb("c.root", &commit.root);
-        tr.absorb_u64("c.len", commit.len as u64);
-        tr.absorb("pi.commit", &pi_cmt.0);
-        tr.absorb("left_tail", &proof.public.left_tail_digest);
-        tr.absorb("right_head", &proof.public.right_head_digest);
-        tr.absorb("leaf_pi.mac", &proof.proof.mac);
-
-        let mac_vec = tr.challenge_bytes("mac", 32);
-        mac_vec.as_slice() == &proof.mac
+    fn rebind_leaf(
+        manifest_root: &[u8; 32],
+        commit: &Commitment,
+        pi_cmt: &PiCommitment,
+        proof: &mut Self::Proof,
+    ) {
+        // A digest that fails to encode leaves the proof unverifiable anyway.
+        if let Ok(samples) = proof_samples_digest(proof) {
+            proof.mac = leaf_mac(
+                manifest_root,
+                commit,
+                pi_cmt,
+                &proof.public,
+                &proof.proof,
+                samples.as_ref(),
+            );
+        }
     }
 }
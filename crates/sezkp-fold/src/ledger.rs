@@ -0,0 +1,255 @@
+//! Endpoint ledger for the Balanced batch driver.
+//!
+//! Balanced mode records the endpoint `(C, π)` of every live subtree at its
+//! leftmost leaf index, so the ledger has one slot per block. Kept in RAM
+//! that is an `O(T)` vector; for very large `T` it can instead live in a
+//! paged file of fixed-size records, with only a handful of pages resident.
+//!
+//! ## Disk format
+//! Slot `i` is the `RECORD_BYTES`-byte record at offset `i · RECORD_BYTES`:
+//! a presence byte, then `C.root`, `C.len`, `π.ctrl_in`, `π.ctrl_out`,
+//! `π.flags` and the `π.acc` registers, all little-endian. The file is
+//! pre-sized with zeros (every slot empty), lives in [`std::env::temp_dir`]
+//! and is removed when the ledger is dropped.
+//!
+//! The DFS touches slots near the current leaf and at the left edges of the
+//! live subtrees, so a small LRU of pages absorbs almost all accesses.
+
+#![forbid(unsafe_code)]
+#![deny(rust_2018_idioms)]
+#![warn(
+    missing_docs,
+    clippy::all,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::unwrap_used,
+    clippy::expect_used
+)]
+
+use anyhow::{Context, Result};
+use std::collections::{HashMap, VecDeque};
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::api::{Commitment, LedgerStore};
+use crate::are::{Pi, Q};
+use sezkp_gadgets::F1;
+
+/// Bytes per on-disk ledger record.
+pub const RECORD_BYTES: usize = 1 + 32 + 4 + 3 * 4 + Q * 8;
+/// Records per page.
+pub const PAGE_RECORDS: usize = 64;
+/// Pages kept resident by the disk ledger.
+pub const RESIDENT_PAGES: usize = 8;
+
+/// Endpoint slots indexed by leaf position, in RAM or on disk.
+pub enum EndpointLedger {
+    /// One `Option<(C, π)>` per block, in memory.
+    Memory(Vec<Option<(Commitment, Pi)>>),
+    /// Paged file of fixed-size records.
+    Disk(DiskLedger),
+}
+
+impl EndpointLedger {
+    /// Create a ledger of `n` empty slots in the requested store.
+    ///
+    /// # Errors
+    /// Fails if the disk ledger's backing file cannot be created.
+    pub fn new(n: usize, store: LedgerStore) -> Result<Self> {
+        Ok(match store {
+            LedgerStore::Memory => Self::Memory(vec![None; n]),
+            LedgerStore::Disk => Self::Disk(DiskLedger::create(n)?),
+        })
+    }
+
+    /// Read slot `i`.
+    ///
+    /// # Errors
+    /// Fails on I/O errors of the disk ledger.
+    pub fn get(&mut self, i: usize) -> Result<Option<(Commitment, Pi)>> {
+        match self {
+            Self::Memory(v) => Ok(v[i]),
+            Self::Disk(d) => d.get(i),
+        }
+    }
+
+    /// Overwrite slot `i`.
+    ///
+    /// # Errors
+    /// Fails on I/O errors of the disk ledger.
+    pub fn set(&mut self, i: usize, ep: Option<(Commitment, Pi)>) -> Result<()> {
+        match self {
+            Self::Memory(v) => v[i] = ep,
+            Self::Disk(d) => d.set(i, ep)?,
+        }
+        Ok(())
+    }
+}
+
+/* ------------------------------- disk ledger ------------------------------- */
+
+struct Page {
+    buf: Vec<u8>,
+    dirty: bool,
+}
+
+/// File-backed ledger with a small LRU of resident pages.
+pub struct DiskLedger {
+    path: PathBuf,
+    file: File,
+    n: usize,
+    pages: HashMap<usize, Page>,
+    /// Ordering deque: front = LRU, back = MRU.
+    order: VecDeque<usize>,
+}
+
+impl DiskLedger {
+    /// Create a zero-filled ledger file of `n` slots in the temp directory.
+    ///
+    /// # Errors
+    /// Fails if the file cannot be created or sized.
+    pub fn create(n: usize) -> Result<Self> {
+        static SEQ: AtomicU64 = AtomicU64::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "sezkp_fold_ledger_{}_{}.bin",
+            std::process::id(),
+            SEQ.fetch_add(1, Ordering::Relaxed)
+        ));
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .with_context(|| format!("creating ledger file {}", path.display()))?;
+        file.set_len((n * RECORD_BYTES) as u64)
+            .with_context(|| format!("sizing ledger file {}", path.display()))?;
+        Ok(Self {
+            path,
+            file,
+            n,
+            pages: HashMap::new(),
+            order: VecDeque::new(),
+        })
+    }
+
+    /// Read slot `i`.
+    ///
+    /// # Errors
+    /// Fails if the page holding slot `i` cannot be read.
+    pub fn get(&mut self, i: usize) -> Result<Option<(Commitment, Pi)>> {
+        let off = (i % PAGE_RECORDS) * RECORD_BYTES;
+        let page = self.page(i / PAGE_RECORDS)?;
+        Ok(decode(&page.buf[off..off + RECORD_BYTES]))
+    }
+
+    /// Overwrite slot `i`.
+    ///
+    /// # Errors
+    /// Fails if the page holding slot `i` cannot be loaded.
+    pub fn set(&mut self, i: usize, ep: Option<(Commitment, Pi)>) -> Result<()> {
+        let off = (i % PAGE_RECORDS) * RECORD_BYTES;
+        let page = self.page(i / PAGE_RECORDS)?;
+        encode(ep, &mut page.buf[off..off + RECORD_BYTES]);
+        page.dirty = true;
+        Ok(())
+    }
+
+    /// Byte range `(offset, len)` of page `p` within the file.
+    fn extent(&self, p: usize) -> (u64, usize) {
+        let lo = p * PAGE_RECORDS;
+        let hi = (lo + PAGE_RECORDS).min(self.n);
+        ((lo * RECORD_BYTES) as u64, (hi - lo) * RECORD_BYTES)
+    }
+
+    fn page(&mut self, p: usize) -> Result<&mut Page> {
+        if let Some(pos) = self.order.iter().position(|&x| x == p) {
+            self.order.remove(pos);
+        } else {
+            if self.pages.len() == RESIDENT_PAGES {
+                if let Some(evict) = self.order.pop_front() {
+                    self.write_back(evict)?;
+                    self.pages.remove(&evict);
+                }
+            }
+            let (off, len) = self.extent(p);
+            let mut buf = vec![0u8; len];
+            self.file.seek(SeekFrom::Start(off))?;
+            self.file
+                .read_exact(&mut buf)
+                .with_context(|| format!("reading ledger page {p}"))?;
+            self.pages.insert(p, Page { buf, dirty: false });
+        }
+        self.order.push_back(p);
+        self.pages
+            .get_mut(&p)
+            .context("ledger page vanished after load")
+    }
+
+    fn write_back(&mut self, p: usize) -> Result<()> {
+        let (off, _) = self.extent(p);
+        if let Some(page) = self.pages.get_mut(&p).filter(|pg| pg.dirty) {
+            self.file.seek(SeekFrom::Start(off))?;
+            self.file
+                .write_all(&page.buf)
+                .with_context(|| format!("writing ledger page {p}"))?;
+            page.dirty = false;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for DiskLedger {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn encode(ep: Option<(Commitment, Pi)>, out: &mut [u8]) {
+    out.fill(0);
+    let Some((c, pi)) = ep else {
+        return;
+    };
+    out[0] = 1;
+    out[1..33].copy_from_slice(&c.root);
+    let mut at = 33;
+    let words = [c.len, pi.ctrl_in, pi.ctrl_out, pi.flags];
+    for w in words {
+        out[at..at + 4].copy_from_slice(&w.to_le_bytes());
+        at += 4;
+    }
+    for a in &pi.acc {
+        out[at..at + 8].copy_from_slice(&a.to_le_bytes());
+        at += 8;
+    }
+}
+
+fn decode(rec: &[u8]) -> Option<(Commitment, Pi)> {
+    if rec[0] == 0 {
+        return None;
+    }
+    let u32_at = |at: usize| {
+        let mut b = [0u8; 4];
+        b.copy_from_slice(&rec[at..at + 4]);
+        u32::from_le_bytes(b)
+    };
+    let mut root = [0u8; 32];
+    root.copy_from_slice(&rec[1..33]);
+    let mut acc = [F1::from_u64(0); Q];
+    for (k, a) in acc.iter_mut().enumerate() {
+        let at = 49 + 8 * k;
+        let mut b = [0u8; 8];
+        b.copy_from_slice(&rec[at..at + 8]);
+        *a = F1::from_u64(u64::from_le_bytes(b));
+    }
+    Some((
+        Commitment::new(root, u32_at(33)),
+        Pi {
+            ctrl_in: u32_at(37),
+            ctrl_out: u32_at(41),
+            flags: u32_at(45),
+            acc,
+        },
+    ))
+}
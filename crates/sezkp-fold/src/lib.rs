@@ -21,62 +21,330 @@
     clippy::expect_used
 )]
 
+/// Stream digests for external anchoring.
+pub mod anchor;
 /// Public API traits and small types.
 pub mod api;
 /// Algebraic Replay Engine types (Pi, InterfaceWitness, …).
 pub mod are;
 /// Micro-proof for interface replay (MAC today; micro-STARK later).
 pub mod are_replay;
+/// Transcript-sampled openings of interior block steps.
+pub mod are_sample;
+/// Persistent subtree proof cache (incremental proving).
+pub mod cache;
+/// Gadget census of batch artifacts (which proof variants a bundle uses).
+pub mod census;
+/// Time-travel debugging: snapshot and diff driver states at block N.
+pub mod debug;
 /// Scheduler driver glue + bundle/streaming format.
 pub mod driver;
+/// NDJSON logs of the streaming driver's interface witnesses, for audit.
+pub mod iface_log;
+/// Proof and stream size estimates calibrated on the real gadgets.
+pub mod estimate;
 /// Concrete gadgets: Fold & Wrap.
 pub mod fold;
+/// Fsync policies and a write-ahead journal for proof streams.
+pub mod journal;
 /// Concrete gadget: Leaf.
 pub mod leaf;
+/// Balanced-mode endpoint ledger (in memory or paged on disk).
+pub mod ledger;
+/// Artifact envelope versions, byte layout and migration to V3.
+pub mod migrate;
+/// Upfront peak-memory estimates for the fold drivers.
+pub mod plan;
+/// Optional final wrap stage with pluggable backends.
+pub mod final_wrap;
+/// Manifest-inclusion spot checks for batch bundles.
+pub mod spot;
 /// Bundle verifier (bottom-up) and streaming verifier.
 pub mod verify;
 
 pub use crate::driver::run_pipeline;
+pub use crate::estimate::estimate_proof_size;
 pub use crate::fold::{CryptoFold, CryptoWrap, CryptoWrapProof};
 pub use crate::leaf::{CryptoLeaf, CryptoLeafProof};
 
-use anyhow::{anyhow, ensure, Context, Result};
+use anyhow::{anyhow, bail, ensure, Context, Result};
 use serde::{Deserialize, Serialize};
-use sezkp_core::{BackendKind, BlockSummary, ProofArtifact, ProvingBackend};
-use sezkp_core::ProvingBackendStream;
-use std::fs::File;
-use std::io::BufWriter;
+use sezkp_core::{
+    BackendKind, BlockSummary, ConfiguredBackend, ProofArtifact, ProvingBackend, Replay,
+    ReplayConfig,
+};
+use sezkp_core::claims::{self, Claim};
+use sezkp_core::{ConfiguredBackendStream, ProvingBackendStream};
+use std::borrow::Borrow;
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
 
-use crate::api::{Commitment, DriverOptions, FoldMode};
+use crate::api::{
+    Commitment, DriverOptions, Durability, ExpectedParams, FoldMode, FoldParams, LedgerStore,
+};
 use crate::are::Pi;
+use crate::verify::BundleSummary;
+use sezkp_gadgets::F1;
 
 /* ------------------------- versioned payload envelope ---------------------- */
 
+/// Envelope versions. V1 and V2 are bincode-framed (see [`migrate`]); V3 is
+/// a canonical CBOR map.
 #[repr(u16)]
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
 enum WireVersion {
     V1 = 1,
     V2 = 2,
+    V3 = 3,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-struct PayloadV1 {
-    bundle_json: Vec<u8>,
+#[derive(Clone, Debug, Deserialize)]
+struct PayloadV1<'a> {
+    bundle_json: &'a [u8],
     root_c: Commitment,
     root_pi: Pi,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-struct PayloadV2 {
-    bundle_cbor: Vec<u8>,
+#[derive(Clone, Debug, Deserialize)]
+struct PayloadV2<'a> {
+    bundle_cbor: &'a [u8],
     root_c: Commitment,
     root_pi: Pi,
 }
 
+/// Outer layout of bincode-framed envelopes (V1 and V2), with the bundle
+/// borrowed from the proof bytes. Nothing writes these any more.
+#[derive(Clone, Debug, Deserialize)]
+enum WireEnvelope<'a> {
+    #[serde(borrow)]
+    V1(PayloadV1<'a>),
+    #[serde(borrow)]
+    V2(PayloadV2<'a>),
+}
+
+/// V3 envelope: a canonical CBOR map (see [`migrate`] for the byte layout).
 #[derive(Clone, Debug, Serialize, Deserialize)]
-enum WireEnvelope {
-    V1(PayloadV1),
-    V2(PayloadV2),
+struct EnvelopeV3 {
+    version: u16,
+    root_c: RootV3,
+    root_pi: PiV3,
+    #[serde(with = "bstr")]
+    bundle: Vec<u8>,
+}
+
+/// [`EnvelopeV3`] with the bundle borrowed from the proof bytes (the version
+/// is read first, by [`envelope_version`]).
+#[derive(Deserialize)]
+struct EnvelopeV3Ref<'a> {
+    root_c: RootV3,
+    root_pi: PiV3,
+    bundle: &'a [u8],
+}
+
+/// Top commitment in a V3 envelope.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+struct RootV3 {
+    #[serde(with = "bstr")]
+    root: [u8; 32],
+    len: u32,
+}
+
+/// Top projection in a V3 envelope; `acc` is the four accumulator registers
+/// as little-endian `u64`s, concatenated.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+struct PiV3 {
+    ctrl_in: u32,
+    ctrl_out: u32,
+    flags: u32,
+    #[serde(with = "bstr")]
+    acc: [u8; 8 * are::Q],
+}
+
+impl From<Pi> for PiV3 {
+    fn from(pi: Pi) -> Self {
+        let mut acc = [0u8; 8 * are::Q];
+        for (dst, a) in acc.chunks_exact_mut(8).zip(pi.acc) {
+            dst.copy_from_slice(&a.to_le_bytes());
+        }
+        Self {
+            ctrl_in: pi.ctrl_in,
+            ctrl_out: pi.ctrl_out,
+            flags: pi.flags,
+            acc,
+        }
+    }
+}
+
+impl From<PiV3> for Pi {
+    fn from(w: PiV3) -> Self {
+        let mut pi = Self {
+            ctrl_in: w.ctrl_in,
+            ctrl_out: w.ctrl_out,
+            flags: w.flags,
+            ..Self::default()
+        };
+        for (a, src) in pi.acc.iter_mut().zip(w.acc.chunks_exact(8)) {
+            let mut le = [0u8; 8];
+            le.copy_from_slice(src);
+            *a = F1::from_u64(u64::from_le_bytes(le));
+        }
+        pi
+    }
+}
+
+/// Just the version of a V3 envelope, for reading it ahead of the payload.
+#[derive(Deserialize)]
+struct VersionOnly {
+    version: u16,
+}
+
+/// Serde adapter writing byte arrays as CBOR byte strings (not arrays of
+/// integers), so non-Rust readers see `bstr` fields.
+mod bstr {
+    use serde::de::{Deserializer, Error, Visitor};
+    use serde::Serializer;
+    use std::fmt;
+
+    pub fn serialize<S: Serializer, T: AsRef<[u8]>>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_bytes(v.as_ref())
+    }
+
+    pub fn deserialize<'de, D, T>(d: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: TryFrom<Vec<u8>>,
+    {
+        struct Bytes;
+        impl Visitor<'_> for Bytes {
+            type Value = Vec<u8>;
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a byte string")
+            }
+            fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+                Ok(v.to_vec())
+            }
+            fn visit_byte_buf<E: Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+                Ok(v)
+            }
+        }
+        let bytes = d.deserialize_byte_buf(Bytes)?;
+        let n = bytes.len();
+        T::try_from(bytes).map_err(|_| D::Error::custom(format!("unexpected length {n}")))
+    }
+}
+
+/// The bundle type carried by batch artifacts.
+type CryptoBundle =
+    driver::FoldProofBundle<leaf::CryptoLeafProof, fold::CryptoFoldProof, fold::CryptoWrapProof>;
+
+/// A batch envelope with its bundle still encoded (JSON in V1, CBOR since).
+struct EnvelopeView<'a> {
+    ver: WireVersion,
+    root_c: Commitment,
+    root_pi: Pi,
+    bundle: &'a [u8],
+}
+
+/// A decoded batch envelope: version, declared top `(C, π)` and bundle.
+struct DecodedEnvelope {
+    ver: WireVersion,
+    root_c: Commitment,
+    root_pi: Pi,
+    bundle: CryptoBundle,
+}
+
+/// Whether `proof_bytes` start with a CBOR map head (a V3 or later
+/// envelope); bincode envelopes start with a little-endian `u32` version.
+fn is_cbor_envelope(proof_bytes: &[u8]) -> bool {
+    proof_bytes.first().is_some_and(|b| b >> 5 == 5)
+}
+
+/// Envelope version of `proof_bytes`, leaving the payload undecoded.
+fn envelope_version(proof_bytes: &[u8]) -> Result<WireVersion> {
+    if !is_cbor_envelope(proof_bytes) {
+        return bincode::deserialize(proof_bytes).context("decoding fold envelope (bincode)");
+    }
+    let VersionOnly { version } =
+        ciborium::de::from_reader(proof_bytes).context("decoding fold envelope")?;
+    match version {
+        3 => Ok(WireVersion::V3),
+        v => bail!("unsupported fold envelope version {v}"),
+    }
+}
+
+/// Read the frame of a batch envelope of any supported version, borrowing
+/// the encoded bundle from `proof_bytes`.
+fn view_envelope(proof_bytes: &[u8]) -> Result<EnvelopeView<'_>> {
+    let ver = envelope_version(proof_bytes)?;
+    if ver == WireVersion::V3 {
+        let env: EnvelopeV3Ref<'_> =
+            serde_cbor::from_slice(proof_bytes).context("decoding fold envelope")?;
+        return Ok(EnvelopeView {
+            ver,
+            root_c: Commitment::new(env.root_c.root, env.root_c.len),
+            root_pi: Pi::from(env.root_pi),
+            bundle: env.bundle,
+        });
+    }
+    let (_, env): (WireVersion, WireEnvelope<'_>) =
+        bincode::deserialize(proof_bytes).context("decoding fold envelope (bincode)")?;
+    let (root_c, root_pi, bundle) = match env {
+        WireEnvelope::V1(p) => (p.root_c, p.root_pi, p.bundle_json),
+        WireEnvelope::V2(p) => (p.root_c, p.root_pi, p.bundle_cbor),
+    };
+    Ok(EnvelopeView {
+        ver,
+        root_c,
+        root_pi,
+        bundle,
+    })
+}
+
+/// Decode a batch envelope of any supported version (see [`migrate`] for how
+/// long the bincode-framed ones stay supported).
+fn decode_envelope(proof_bytes: &[u8]) -> Result<DecodedEnvelope> {
+    let env = view_envelope(proof_bytes)?;
+    let bundle = if env.ver == WireVersion::V1 {
+        serde_json::from_slice(env.bundle).context("decoding JSON bundle")?
+    } else {
+        serde_cbor::from_slice(env.bundle).context("decoding CBOR bundle")?
+    };
+    Ok(DecodedEnvelope {
+        ver: env.ver,
+        root_c: env.root_c,
+        root_pi: env.root_pi,
+        bundle,
+    })
+}
+
+/// Encode `bundle` in a V3 envelope with top `(root_c, root_pi)`.
+fn encode_envelope_v3<Lp, Fp, Wp>(
+    bundle: &driver::FoldProofBundle<Lp, Fp, Wp>,
+    root_c: Commitment,
+    root_pi: Pi,
+) -> Result<Vec<u8>>
+where
+    Lp: Serialize,
+    Fp: Serialize,
+    Wp: Serialize,
+{
+    let bundle = serde_cbor::to_vec(bundle).context("serializing bundle (CBOR)")?;
+    wrap_envelope_v3(bundle, root_c, root_pi)
+}
+
+/// Frame already-encoded `bundle` bytes as a V3 envelope.
+fn wrap_envelope_v3(bundle: Vec<u8>, root_c: Commitment, root_pi: Pi) -> Result<Vec<u8>> {
+    sezkp_core::canonical::to_vec(&EnvelopeV3 {
+        version: WireVersion::V3 as u16,
+        root_c: RootV3 {
+            root: root_c.root,
+            len: root_c.len,
+        },
+        root_pi: root_pi.into(),
+        bundle,
+    })
+    .context("serializing fold envelope")
 }
 
 /// Extract the top `(Commitment, Pi)` from a bundle (last fold if present,
@@ -87,7 +355,7 @@ fn bundle_top<Lp, Fp, Wp>(b: &driver::FoldProofBundle<Lp, Fp, Wp>) -> (Commitmen
     } else if let Some((c, p, _)) = b.leaves.last() {
         (*c, *p)
     } else {
-        (Commitment::new([0u8; 32], 0), Pi::default())
+        (Commitment::new(sezkp_core::EMPTY_ROOT, 0), Pi::default())
     }
 }
 
@@ -99,7 +367,25 @@ fn bundle_top<Lp, Fp, Wp>(b: &driver::FoldProofBundle<Lp, Fp, Wp>) -> (Commitmen
 /// - `SEZKP_FOLD_MODE` = `balanced` | `minram`
 /// - `SEZKP_WRAP_CADENCE` = `<u32>`
 /// - `SEZKP_FOLD_CACHE` = `<u32>` (endpoint cache capacity in MinRam)
-fn opts_from_env(mut opts: DriverOptions) -> DriverOptions {
+/// - `SEZKP_FOLD_LEDGER` = `memory` | `disk` (endpoint ledger in Balanced)
+/// - `SEZKP_FOLD_FINAL_WRAP` = `<backend id>` (see [`final_wrap`])
+/// - `SEZKP_FOLD_ARE_SAMPLES` = `<u32>` (sampled steps per leaf; see [`are_sample`])
+/// - `SEZKP_FOLD_IFACE_DIGEST` = `full` | `heads` | `writes-only` (seam digest mode)
+/// - `SEZKP_FOLD_SPOT_CHECKS` = `<u32>` (blocks embedded for spot checks; see [`spot`])
+/// - `SEZKP_FOLD_DURABILITY` = `buffered` | `fsync` | `journal` (see [`journal`])
+/// - `SEZKP_FOLD_SYNC_ITEMS` = `<u32>` (stream items between sync points)
+/// - `SEZKP_FOLD_SYNC_SECS` = `<u32>` (seconds between sync points)
+/// - `SEZKP_FOLD_ALLOW_REWINDOW` = `1` (tapes may be re-windowed between
+///   blocks; declared in the proof)
+///
+/// The persistent subtree cache is configured separately (see
+/// [`subtree_cache_from_env`]). Only the stateless [`FoldBackend`] reads
+/// any of these; a [`ConfiguredFold`] takes its options as given.
+///
+/// # Errors
+/// Fails on an unknown seam digest mode, or if the final wrap backend is not
+/// registered.
+fn opts_from_env(mut opts: DriverOptions) -> Result<DriverOptions> {
     if let Ok(mode) = std::env::var("SEZKP_FOLD_MODE") {
         match mode.to_ascii_lowercase().as_str() {
             "balanced" => opts.fold_mode = FoldMode::Balanced,
@@ -117,11 +403,187 @@ fn opts_from_env(mut opts: DriverOptions) -> DriverOptions {
             opts.endpoint_cache = v;
         }
     }
-    opts
+    if let Ok(l) = std::env::var("SEZKP_FOLD_LEDGER") {
+        match l.to_ascii_lowercase().as_str() {
+            "memory" => opts.ledger = LedgerStore::Memory,
+            "disk" => opts.ledger = LedgerStore::Disk,
+            _ => {}
+        }
+    }
+    if let Ok(r) = std::env::var("SEZKP_FOLD_ARE_SAMPLES") {
+        if let Ok(v) = r.parse::<u32>() {
+            opts.are_samples = v;
+        }
+    }
+    if let Ok(m) = std::env::var("SEZKP_FOLD_IFACE_DIGEST") {
+        opts.iface_digest = m
+            .parse()
+            .with_context(|| format!("invalid SEZKP_FOLD_IFACE_DIGEST={m:?}"))?;
+    }
+    if let Ok(k) = std::env::var("SEZKP_FOLD_SPOT_CHECKS") {
+        if let Ok(v) = k.parse::<u32>() {
+            opts.spot_checks = v;
+        }
+    }
+    if let Ok(d) = std::env::var("SEZKP_FOLD_DURABILITY") {
+        match d.to_ascii_lowercase().as_str() {
+            "buffered" => opts.sync.durability = Durability::Buffered,
+            "fsync" => opts.sync.durability = Durability::Fsync,
+            "journal" => opts.sync.durability = Durability::Journal,
+            _ => {}
+        }
+    }
+    if let Ok(n) = std::env::var("SEZKP_FOLD_SYNC_ITEMS") {
+        if let Ok(v) = n.parse::<u32>() {
+            opts.sync.every_items = v;
+        }
+    }
+    if let Ok(n) = std::env::var("SEZKP_FOLD_SYNC_SECS") {
+        if let Ok(v) = n.parse::<u32>() {
+            opts.sync.every_secs = v;
+        }
+    }
+    if let Ok(id) = std::env::var("SEZKP_FOLD_FINAL_WRAP") {
+        if !id.is_empty() {
+            opts.final_wrap = Some(
+                final_wrap::wrap_backend(&id)
+                    .ok_or_else(|| anyhow!("unknown SEZKP_FOLD_FINAL_WRAP={id:?}"))?,
+            );
+        }
+    }
+    if let Ok(v) = std::env::var("SEZKP_FOLD_REVEAL_ROOT_PI") {
+        opts.reveal_root_pi = v == "1";
+    }
+    if let Ok(v) = std::env::var("SEZKP_FOLD_ALLOW_REWINDOW") {
+        opts.allow_rewindow = v == "1";
+    }
+    Ok(opts)
+}
+
+/// Parameter expectations for verification, from the environment.
+///
+/// Recognized variables:
+/// - `SEZKP_EXPECT_FOLD_MODE` = `balanced` | `minram`
+/// - `SEZKP_EXPECT_WRAP_CADENCE` = `<u32>`
+/// - `SEZKP_EXPECT_ARE_SAMPLES` = `<u32>` (minimum sampled steps per leaf)
+/// - `SEZKP_EXPECT_IFACE_DIGEST` = `full` | `heads` | `writes-only`
+/// - `SEZKP_FOLD_ALLOW_REWINDOW` = `1` (accept proofs that declare
+///   re-windowed tapes; the prover's switch, read on both sides)
+///
+/// Unlike the prover overrides, unparsable values are errors: silently
+/// dropping an expectation would weaken the check.
+fn expected_params_from_env() -> Result<ExpectedParams> {
+    let mut exp = ExpectedParams::default();
+    if let Ok(mode) = std::env::var("SEZKP_EXPECT_FOLD_MODE") {
+        exp.fold_mode = Some(match mode.to_ascii_lowercase().as_str() {
+            "balanced" => FoldMode::Balanced,
+            "minram" => FoldMode::MinRam,
+            _ => bail!("invalid SEZKP_EXPECT_FOLD_MODE={mode:?} (expected balanced or minram)"),
+        });
+    }
+    if let Ok(k) = std::env::var("SEZKP_EXPECT_WRAP_CADENCE") {
+        exp.wrap_cadence = Some(
+            k.parse()
+                .with_context(|| format!("invalid SEZKP_EXPECT_WRAP_CADENCE={k:?}"))?,
+        );
+    }
+    if let Ok(r) = std::env::var("SEZKP_EXPECT_ARE_SAMPLES") {
+        exp.are_samples = Some(
+            r.parse()
+                .with_context(|| format!("invalid SEZKP_EXPECT_ARE_SAMPLES={r:?}"))?,
+        );
+    }
+    if let Ok(m) = std::env::var("SEZKP_EXPECT_IFACE_DIGEST") {
+        exp.iface_digest = Some(
+            m.parse()
+                .with_context(|| format!("invalid SEZKP_EXPECT_IFACE_DIGEST={m:?}"))?,
+        );
+    }
+    exp.allow_rewindow = std::env::var("SEZKP_FOLD_ALLOW_REWINDOW").is_ok_and(|v| v == "1");
+    Ok(exp)
+}
+
+/// Open the persistent subtree cache requested via the environment, if any.
+///
+/// Recognized variables:
+/// - `SEZKP_FOLD_CACHE_DIR` = `<path>` (enables the cache)
+/// - `SEZKP_FOLD_CACHE_GRANULE` = `<u32>` (leaves per cached subtree; power of two)
+fn subtree_cache_from_env() -> Result<Option<cache::SubtreeCache>> {
+    let Ok(dir) = std::env::var("SEZKP_FOLD_CACHE_DIR") else {
+        return Ok(None);
+    };
+    if dir.is_empty() {
+        return Ok(None);
+    }
+    let granule = match std::env::var("SEZKP_FOLD_CACHE_GRANULE") {
+        Ok(g) => g
+            .parse::<u32>()
+            .with_context(|| format!("invalid SEZKP_FOLD_CACHE_GRANULE={g:?}"))?,
+        Err(_) => cache::DEFAULT_GRANULE,
+    };
+    cache::SubtreeCache::open(dir, granule).map(Some)
+}
+
+/// Open the interface witness log requested via `SEZKP_FOLD_IFACE_LOG` =
+/// `<path>`, if any (see [`iface_log`]). The file is truncated.
+fn iface_log_from_env() -> Result<Option<iface_log::IfaceLog>> {
+    match std::env::var("SEZKP_FOLD_IFACE_LOG") {
+        Ok(path) if !path.is_empty() => {
+            let file = File::create(&path).with_context(|| format!("create {path}"))?;
+            Ok(Some(iface_log::IfaceLog::new(BufWriter::new(file))))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Whether a streaming proof should continue the stream journaled at its
+/// output path (see [`journal::recover`]), via `SEZKP_FOLD_RESUME` = `1`.
+/// Without a journal the stream starts over.
+fn resume_from_env() -> bool {
+    std::env::var("SEZKP_FOLD_RESUME").is_ok_and(|v| v == "1")
 }
 
 /* --------------------------- ProvingBackend (batch) ------------------------ */
 
+/// Prove `blocks` in one batch bundle with `opts`, bound to `manifest_root`
+/// and `claims`.
+fn prove_with_options(
+    mut opts: DriverOptions,
+    blocks: &[BlockSummary],
+    manifest_root: [u8; 32],
+    claims: &[Claim],
+) -> Result<ProofArtifact> {
+    claims::check_claims(claims)?;
+    opts.manifest_root = manifest_root;
+    opts.claims = claims::claims_digest(claims);
+    let bundle =
+        run_pipeline::<leaf::CryptoLeaf, fold::CryptoFold, fold::CryptoWrap>(blocks, &opts);
+    let (root_c, root_pi) = bundle_top(&bundle);
+
+    // Serialize the bundle with CBOR (V3 envelope).
+    let proof_bytes = encode_envelope_v3(&bundle, root_c, root_pi)?;
+
+    let mut art = ProofArtifact::new(
+        BackendKind::Fold,
+        root_c.root,
+        proof_bytes,
+        serde_json::json!({
+            "proto": "fold-v3",
+            "n_blocks": bundle.n_blocks,
+            "wraps": bundle.wraps.len(),
+            "mode": format!("{:?}", opts.fold_mode),
+            "params": opts.params(),
+            "final_wrap": opts.final_wrap.map(api::WrapBackend::id),
+            "spot_checks": bundle.spot_checks.as_ref().map_or(0, |s| s.checks.len()),
+        }),
+    );
+    if opts.reveal_root_pi {
+        art.meta["root_pi"] = serde_json::to_value(RootPi::from(root_pi))?;
+    }
+    art.claims = claims.to_vec();
+    Ok(art)
+}
+
 /// Default folding backend: uses `CryptoLeaf`, `CryptoFold`, and `CryptoWrap`.
 #[derive(Debug, Clone, Copy, Default)]
 pub struct FoldBackend;
@@ -130,116 +592,415 @@ pub struct FoldBackend;
 pub use FoldBackend as FoldAgg;
 
 impl ProvingBackend for FoldBackend {
-    fn prove(blocks: &[BlockSummary], _manifest_root: [u8; 32]) -> Result<ProofArtifact> {
-        let opts = opts_from_env(api::DriverOptions::default());
-        let bundle = run_pipeline::<leaf::CryptoLeaf, fold::CryptoFold, fold::CryptoWrap>(
-            blocks,
-            &opts,
-        );
-        let (root_c, root_pi) = bundle_top(&bundle);
-
-        // Serialize the bundle with CBOR (V2 envelope).
-        let bundle_cbor = serde_cbor::to_vec(&bundle).context("serializing bundle (CBOR)")?;
-        let payload = WireEnvelope::V2(PayloadV2 {
-            bundle_cbor,
-            root_c,
-            root_pi,
-        });
-        let proof_bytes =
-            bincode::serialize(&(WireVersion::V2, &payload)).context("serializing fold envelope")?;
-
-        Ok(ProofArtifact {
-            backend: BackendKind::Stark, // reuse enum; payload carries version
-            manifest_root: root_c.root,
-            proof_bytes,
-            meta: serde_json::json!({
-                "proto": "fold-v2",
-                "n_blocks": bundle.n_blocks,
-                "wraps": bundle.wraps.len(),
-                "mode": format!("{:?}", opts.fold_mode),
-            }),
-        })
+    fn prove(blocks: &[BlockSummary], manifest_root: [u8; 32]) -> Result<ProofArtifact> {
+        Self::prove_with_claims(blocks, manifest_root, &[])
+    }
+
+    fn prove_with_claims(
+        blocks: &[BlockSummary],
+        manifest_root: [u8; 32],
+        claims: &[Claim],
+    ) -> Result<ProofArtifact> {
+        let opts = opts_from_env(api::DriverOptions::default())?;
+        prove_with_options(opts, blocks, manifest_root, claims)
     }
 
+    /// Sampled interior steps and window continuity are checked against
+    /// `blocks` when given (see [`check_blocks`]); the rest of the proof
+    /// needs only the root. Batches use the default
+    /// [`ProvingBackend::verify_batch`], a plain loop over this: fold proofs
+    /// share no work across items.
     fn verify(
         artifact: &ProofArtifact,
-        _blocks: &[BlockSummary],
+        blocks: &[BlockSummary],
         manifest_root: [u8; 32],
     ) -> Result<()> {
-        // If this is a streaming artifact, verify via streaming reader.
-        if let Some(fmt) = artifact.meta.get("stream_format").and_then(|v| v.as_str()) {
-            if fmt == "fold-seq-v1" {
-                let p = artifact
-                    .meta
-                    .get("stream_path")
-                    .and_then(|v| v.as_str())
-                    .ok_or_else(|| anyhow!("streaming artifact missing 'stream_path'"))?;
-                let f = File::open(p).with_context(|| format!("open proof stream {}", p))?;
-                verify::verify_stream::<leaf::CryptoLeaf, fold::CryptoFold, CryptoWrap, _>(f)?;
-                // Bind artifact manifest root to stream footer root (the streaming
-                // verifier already ensures internal consistency).
-                ensure!(
-                    artifact.manifest_root == manifest_root,
-                    "manifest root mismatch"
-                );
-                return Ok(());
+        let params = verify_with_expected(artifact, manifest_root, &expected_params_from_env()?)?;
+        check_blocks(artifact, params.as_ref(), blocks.iter().map(Ok))
+    }
+
+    /// The proof is checked against the manifest root, then the blocks are
+    /// streamed past the sampled leaves one at a time, never held.
+    fn verify_iter<I>(artifact: &ProofArtifact, blocks: I, manifest_root: [u8; 32]) -> Result<()>
+    where
+        I: IntoIterator<Item = Result<BlockSummary>>,
+    {
+        let params = verify_with_expected(artifact, manifest_root, &expected_params_from_env()?)?;
+        check_blocks(artifact, params.as_ref(), blocks)
+    }
+}
+
+impl FoldBackend {
+    /// Verify a batch artifact without its blocks file, spot-checking the
+    /// blocks embedded in the bundle instead (see [`spot`]).
+    ///
+    /// Requires at least `min_samples` embedded blocks (fewer only if the
+    /// proof covers fewer), each replayed with `replay`. Returns the number
+    /// of blocks checked.
+    ///
+    /// # Errors
+    /// Fails for streaming artifacts, if the bundle does not verify, or if
+    /// the spot checks are missing, too few or fail.
+    pub fn verify_spot_checked(
+        artifact: &ProofArtifact,
+        manifest_root: [u8; 32],
+        min_samples: u32,
+        replay: &Replay,
+    ) -> Result<usize> {
+        check_backend(artifact)?;
+        ensure!(
+            artifact.meta.get("stream_format").is_none(),
+            "spot checks need a batch fold proof; streaming proofs carry no embedded blocks"
+        );
+        let expected = expected_params_from_env()?;
+        let bundle = verify_bundle_artifact_decoded(artifact, manifest_root, &expected)?;
+        let n = spot::verify_spot_checks(&bundle, replay, min_samples).context("spot checks")?;
+        // The embedded blocks are held, so their sampled steps can be checked.
+        for check in bundle.spot_checks.iter().flat_map(|s| &s.checks) {
+            let i = check.path.index;
+            let samples = usize::try_from(i)
+                .ok()
+                .and_then(|i| bundle.leaves.get(i))
+                .and_then(|(_, _, lp)| lp.samples.as_ref());
+            if let Some(s) = samples {
+                s.check_block(&check.block)
+                    .with_context(|| format!("spot check {i}"))?;
             }
         }
+        Ok(n)
+    }
+}
 
-        // Fallback: legacy in-memory bundle.
-        // Decode outer envelope.
-        let (ver, env): (WireVersion, WireEnvelope) =
-            bincode::deserialize(&artifact.proof_bytes).context("decoding fold envelope")?;
+/// Path of a streaming artifact's proof stream (`None` for batch artifacts).
+fn stream_path(artifact: &ProofArtifact) -> Result<Option<&str>> {
+    if artifact.meta.get("stream_format").and_then(|v| v.as_str()) != Some("fold-seq-v1") {
+        return Ok(None);
+    }
+    artifact
+        .meta
+        .get("stream_path")
+        .and_then(|v| v.as_str())
+        .map(Some)
+        .ok_or_else(|| anyhow!("streaming artifact missing 'stream_path'"))
+}
 
-        // Decode bundle depending on version.
-        let (bundle_root_c, bundle_root_pi, bundle_bytes, is_cbor) = match env {
-            WireEnvelope::V1(p) => (p.root_c, p.root_pi, p.bundle_json, false),
-            WireEnvelope::V2(p) => (p.root_c, p.root_pi, p.bundle_cbor, true),
-        };
+/// Check the blocks a verifier holds against a verified artifact whose
+/// declared parameters are `params`, taking `blocks` in leaf order.
+///
+/// Every sampled leaf is checked against its block (see
+/// [`are_sample::StepSamples::check_block`]), and every seam for window
+/// continuity (see [`Replay::check_seam`]) unless the proof declares
+/// [`FoldParams::allow_rewindow`]. The leaf commitments bind only each log's
+/// length and the seam digests are opaque here, so without this a proof
+/// could open steps of a made-up log or fold blocks whose tapes jump between
+/// windows. No blocks means none are held: the proof then stands on the
+/// manifest root alone. Otherwise every sampled leaf needs its block, and
+/// leftover blocks are still checked so that stream errors surface.
+fn check_blocks<B, I>(
+    artifact: &ProofArtifact,
+    params: Option<&FoldParams>,
+    blocks: I,
+) -> Result<()>
+where
+    B: Borrow<BlockSummary>,
+    I: IntoIterator<Item = Result<B>>,
+{
+    let mut blocks = blocks.into_iter().peekable();
+    if blocks.peek().is_none() {
+        return Ok(());
+    }
+    let mut held = HeldBlocks {
+        blocks,
+        prev: None,
+        seams: Replay {
+            cfg: ReplayConfig {
+                allow_rewindow: params.is_some_and(|p| p.allow_rewindow),
+                ..ReplayConfig::default()
+            },
+        },
+        n: 0,
+    };
+    let mut i = 0u64;
+    let mut check = |proof: &leaf::CryptoLeafProof| -> Result<()> {
+        let block = held.next()?;
+        if let Some(s) = &proof.samples {
+            let block = block.ok_or_else(|| anyhow!("no block for sampled leaf {i}"))?;
+            s.check_block(block).with_context(|| format!("leaf {i}"))?;
+        }
+        i += 1;
+        Ok(())
+    };
+    if let Some(p) = stream_path(artifact)? {
+        let f = File::open(p).with_context(|| format!("open proof stream {p}"))?;
+        verify::for_each_stream_leaf(BufReader::new(f), &mut check)?;
+    } else {
+        for (_, _, lp) in &decode_envelope(&artifact.proof_bytes)?.bundle.leaves {
+            check(lp)?;
+        }
+    }
+    while held.next()?.is_some() {}
+    Ok(())
+}
 
-        // Decode inner bundle.
-        let bundle: driver::FoldProofBundle<
-            leaf::CryptoLeafProof,
-            fold::CryptoFoldProof,
-            fold::CryptoWrapProof,
-        > = if is_cbor {
-            serde_cbor::from_slice(&bundle_bytes).context("decoding CBOR bundle")?
-        } else {
-            serde_json::from_slice(&bundle_bytes).context("decoding JSON bundle")?
+/// Blocks a verifier holds, taken one at a time with window continuity
+/// checked across each seam (see [`check_blocks`]).
+struct HeldBlocks<B, I> {
+    blocks: I,
+    prev: Option<B>,
+    seams: Replay,
+    n: u64,
+}
+
+impl<B, I> HeldBlocks<B, I>
+where
+    B: Borrow<BlockSummary>,
+    I: Iterator<Item = Result<B>>,
+{
+    fn next(&mut self) -> Result<Option<&BlockSummary>> {
+        let Some(block) = self.blocks.next().transpose()? else {
+            return Ok(None);
         };
+        if let Some(prev) = &self.prev {
+            self.seams
+                .check_seam(prev.borrow(), block.borrow())
+                .with_context(|| format!("seam before block {}", self.n))?;
+        }
+        self.n += 1;
+        let block: &B = self.prev.insert(block);
+        Ok(Some(block.borrow()))
+    }
+}
 
-        // Cryptographic verification.
-        verify::verify_bundle::<leaf::CryptoLeaf, fold::CryptoFold, CryptoWrap>(&bundle)?;
+/// Verify a fold artifact (batch or streaming) against `manifest_root`,
+/// requiring the parameters in `expected`. Returns the parameters the proof
+/// declares.
+fn verify_with_expected(
+    artifact: &ProofArtifact,
+    manifest_root: [u8; 32],
+    expected: &ExpectedParams,
+) -> Result<Option<FoldParams>> {
+    check_backend(artifact)?;
 
-        // Top consistency.
-        let (top_c, top_pi) = bundle_top(&bundle);
+    // If this is a streaming artifact, verify via streaming reader.
+    if let Some(p) = stream_path(artifact)? {
+        // An anchored digest is checked in one cheap pass before the
+        // proofs themselves (absent in older artifacts).
+        if let Some(want) = artifact.meta.get("stream_digest").and_then(|v| v.as_str()) {
+            let want = anchor::parse_digest_hex(want)?;
+            let f = File::open(p).with_context(|| format!("open proof stream {p}"))?;
+            anchor::check_stream_digest(BufReader::new(f), &want)?;
+        }
+        let f = File::open(p).with_context(|| format!("open proof stream {p}"))?;
+        // Block count hint for progress reporting (absent in older artifacts).
+        let n_blocks = artifact
+            .meta
+            .get("n_blocks")
+            .and_then(serde_json::Value::as_u64);
+        let footer = verify::verify_stream_with_progress::<
+            leaf::CryptoLeaf,
+            fold::CryptoFold,
+            CryptoWrap,
+            _,
+        >(f, n_blocks)?;
+        expected
+            .check(footer.params.as_ref())
+            .context("fold parameters")?;
+        // Bind artifact manifest root to stream footer root (the streaming
+        // verifier already ensures internal consistency).
         ensure!(
-            top_c == bundle_root_c && top_pi == bundle_root_pi,
-            "root mismatch in payload vs bundle"
+            artifact.manifest_root == footer.root_c.root,
+            "artifact.manifest_root does not match stream footer root"
         );
-
-        // Bind artifact + CLI-provided manifest root to the bundle root.
         ensure!(
-            artifact.manifest_root == top_c.root,
-            "artifact.manifest_root does not match final fold root"
+            artifact.manifest_root == manifest_root,
+            "manifest root mismatch"
         );
         ensure!(
-            manifest_root == top_c.root,
-            "CLI manifest root does not match final fold root"
+            footer.manifest_root == manifest_root,
+            "proof is bound to a different manifest root"
         );
+        claims::check_bound(&artifact.claims, footer.claims.as_ref())?;
+        sezkp_core::check_empty_root(footer.n_blocks == 0, &footer.root_c.root)?;
+        check_revealed_root_pi(artifact, footer.root_pi)?;
+        return Ok(footer.params);
+    }
 
-        ensure!(
-            matches!(ver, WireVersion::V1 | WireVersion::V2),
-            "unsupported fold payload version"
-        );
-        Ok(())
+    // Fallback: in-memory bundle in a V1, V2 or V3 envelope.
+    verify_bundle_artifact(artifact, manifest_root, expected)
+}
+
+/// The root projection `π` of a fold proof in plain integers, as an artifact
+/// reveals it in `meta.root_pi` ([`DriverOptions::reveal_root_pi`]): the
+/// end-of-trace control state and the four accumulator registers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RootPi {
+    /// Control state at the start of the trace.
+    pub ctrl_in: u32,
+    /// Control state at the end of the trace.
+    pub ctrl_out: u32,
+    /// Flags (bit 0 ⇒ boundary digests present).
+    pub flags: u32,
+    /// Accumulator registers over the boundary digests, as canonical field
+    /// elements.
+    pub acc: [u64; are::Q],
+}
+
+impl From<Pi> for RootPi {
+    fn from(pi: Pi) -> Self {
+        Self {
+            ctrl_in: pi.ctrl_in,
+            ctrl_out: pi.ctrl_out,
+            flags: pi.flags,
+            acc: pi.acc.map(|a| a.0),
+        }
     }
 }
 
+/// The root `π` an artifact reveals in `meta.root_pi`, if any.
+///
+/// Verification ([`FoldBackend::verify`]) checks it against the proof, so a
+/// value read from a verified artifact is the proven end state.
+///
+/// # Errors
+/// Fails if `meta.root_pi` is present but malformed.
+pub fn revealed_root_pi(artifact: &ProofArtifact) -> Result<Option<RootPi>> {
+    artifact
+        .meta
+        .get("root_pi")
+        .map(|v| serde_json::from_value(v.clone()).context("decoding meta.root_pi"))
+        .transpose()
+}
+
+/// Require an artifact's `meta.root_pi`, if present, to equal the root `π`
+/// its proof reveals (`proved`, already checked against its commitment).
+fn check_revealed_root_pi(artifact: &ProofArtifact, proved: Option<Pi>) -> Result<()> {
+    let Some(meta) = revealed_root_pi(artifact)? else {
+        return Ok(());
+    };
+    let proved = proved
+        .ok_or_else(|| anyhow!("meta.root_pi is set but the proof does not reveal its root π"))?;
+    ensure!(
+        RootPi::from(proved) == meta,
+        "meta.root_pi does not match the proof's root π"
+    );
+    Ok(())
+}
+
+/// Reject artifacts produced by another backend. Fold artifacts from releases
+/// that labelled them `Stark` are accepted (see
+/// [`ProofArtifact::resolved_backend`]).
+fn check_backend(artifact: &ProofArtifact) -> Result<()> {
+    let kind = artifact.resolved_backend();
+    ensure!(
+        kind == BackendKind::Fold,
+        "backend kind mismatch: expected fold, artifact is from the {kind} backend"
+    );
+    Ok(())
+}
+
+/// Verify an in-memory bundle artifact against `manifest_root`.
+///
+/// CBOR bundles (V2 and V3 envelopes) are verified in place, one record at a
+/// time (see [`verify::verify_bundle_cbor`]), so memory stays bounded however
+/// large the proof; only the deprecated V1 JSON bundles are decoded whole.
+/// Returns the parameters the bundle declares.
+fn verify_bundle_artifact(
+    artifact: &ProofArtifact,
+    manifest_root: [u8; 32],
+    expected: &ExpectedParams,
+) -> Result<Option<FoldParams>> {
+    let env = view_envelope(&artifact.proof_bytes)?;
+    let summary = if env.ver == WireVersion::V1 {
+        let bundle: CryptoBundle =
+            serde_json::from_slice(env.bundle).context("decoding JSON bundle")?;
+        verify::verify_bundle::<leaf::CryptoLeaf, fold::CryptoFold, CryptoWrap>(&bundle)?;
+        BundleSummary::of(&bundle)
+    } else {
+        verify::verify_bundle_cbor::<leaf::CryptoLeaf, fold::CryptoFold, CryptoWrap>(env.bundle)?
+    };
+    check_batch(
+        artifact,
+        manifest_root,
+        expected,
+        (env.root_c, env.root_pi),
+        &summary,
+    )?;
+    Ok(summary.params)
+}
+
+/// [`verify_bundle_artifact`] on the decoded bundle, which is returned (for checks that
+/// need the records, such as [`spot`]).
+fn verify_bundle_artifact_decoded(
+    artifact: &ProofArtifact,
+    manifest_root: [u8; 32],
+    expected: &ExpectedParams,
+) -> Result<CryptoBundle> {
+    let DecodedEnvelope {
+        root_c,
+        root_pi,
+        bundle,
+        ..
+    } = decode_envelope(&artifact.proof_bytes)?;
+    verify::verify_bundle::<leaf::CryptoLeaf, fold::CryptoFold, CryptoWrap>(&bundle)?;
+    check_batch(
+        artifact,
+        manifest_root,
+        expected,
+        (root_c, root_pi),
+        &BundleSummary::of(&bundle),
+    )?;
+    Ok(bundle)
+}
+
+/// Check what a verified bundle declares against its envelope's top
+/// `declared`, the artifact, `manifest_root` and the expected parameters.
+fn check_batch(
+    artifact: &ProofArtifact,
+    manifest_root: [u8; 32],
+    expected: &ExpectedParams,
+    declared: (Commitment, Pi),
+    bundle: &BundleSummary,
+) -> Result<()> {
+    expected
+        .check(bundle.params.as_ref())
+        .context("fold parameters")?;
+
+    // Top consistency (an empty bundle's top is the empty root).
+    let (top_c, top_pi) = bundle.top;
+    sezkp_core::check_empty_root(bundle.n_blocks == 0, &top_c.root)?;
+    ensure!(
+        top_c == declared.0 && top_pi == declared.1,
+        "root mismatch in payload vs bundle"
+    );
+
+    // Bind artifact + CLI-provided manifest root to the bundle root.
+    ensure!(
+        artifact.manifest_root == top_c.root,
+        "artifact.manifest_root does not match final fold root"
+    );
+    ensure!(
+        manifest_root == top_c.root,
+        "CLI manifest root does not match final fold root"
+    );
+    ensure!(
+        bundle.manifest_root == manifest_root,
+        "proof is bound to a different manifest root"
+    );
+    claims::check_bound(&artifact.claims, bundle.claims.as_ref())?;
+    check_revealed_root_pi(artifact, Some(top_pi))
+}
+
 /* ---------------------- ProvingBackendStream (streaming) ------------------- */
 
-/// Backend streaming state: emits CBOR-seq directly to a file specified in
-/// `SEZKP_PROOF_STREAM_PATH`. The returned `ProofArtifact` references this file.
+/// Backend streaming state: emits CBOR-seq directly to a file, given by
+/// `SEZKP_PROOF_STREAM_PATH` (or the [`ConfiguredFold`]'s stream path). The
+/// returned `ProofArtifact` references this file.
+///
+/// Gadget proofs are bound to the manifest root (and any claims) given to
+/// `begin_stream`, and ingested blocks are checked on the fly against the
+/// root (see [`driver::StreamDriverSink::with_expected_root`]). A session
+/// from `begin_stream_late_bound` binds the root its blocks hash to instead
+/// (see [`driver::StreamHeader::late_bound`]).
 pub struct StreamState {
     drv: driver::StreamDriverSink<
         CryptoLeaf,
@@ -249,26 +1010,116 @@ pub struct StreamState {
     >,
     /// Where we wrote the stream (absolute or user-specified).
     stream_path: String,
+    /// Claims bound into the stream, recorded in the artifact.
+    claims: Vec<Claim>,
+}
+
+impl StreamState {
+    /// Start a stream driver for `opts`, writing to `path`. With `from_env`,
+    /// the subtree cache, interface log and resume requested through the
+    /// environment apply as well.
+    fn open(opts: DriverOptions, claims: &[Claim], path: String, from_env: bool) -> Result<Self> {
+        let wal = (opts.sync.durability == Durability::Journal)
+            .then(|| journal::wal_path(Path::new(&path)));
+        let resume = if from_env && resume_from_env() {
+            journal::recover(Path::new(&path))?
+        } else {
+            None
+        };
+        let mut drv = if let Some(cp) = resume {
+            let file = OpenOptions::new()
+                .append(true)
+                .open(&path)
+                .with_context(|| format!("open {}", &path))?;
+            let sink = driver::CborSeqSink::durable(BufWriter::new(file), wal).resumed(cp.offset);
+            let prefix = File::open(&path).with_context(|| format!("open {}", &path))?;
+            driver::StreamDriverSink::<CryptoLeaf, CryptoFold, CryptoWrap, _>::resume_replaying(
+                sink, opts, cp, prefix,
+            )?
+        } else {
+            // A journal left by an earlier run does not describe this stream.
+            let stale = journal::wal_path(Path::new(&path));
+            if stale.exists() {
+                std::fs::remove_file(&stale)
+                    .with_context(|| format!("remove {}", stale.display()))?;
+            }
+            let file = File::create(&path).with_context(|| format!("create {}", &path))?;
+            let sink = driver::CborSeqSink::durable(BufWriter::new(file), wal);
+            driver::StreamDriverSink::<CryptoLeaf, CryptoFold, CryptoWrap, _>::new(sink, opts)?
+        };
+        if from_env {
+            if let Some(cache) = subtree_cache_from_env()? {
+                drv = drv.with_cache(cache);
+            }
+            if let Some(log) = iface_log_from_env()? {
+                drv = drv.with_iface_log(log);
+            }
+        }
+        Ok(Self {
+            drv,
+            stream_path: path,
+            claims: claims.to_vec(),
+        })
+    }
+
+    /// [`Self::open`] for a session bound to `manifest_root` up front, which
+    /// checks each ingested block against it.
+    fn open_bound(
+        mut opts: DriverOptions,
+        manifest_root: [u8; 32],
+        claims: &[Claim],
+        path: String,
+        from_env: bool,
+    ) -> Result<Self> {
+        claims::check_claims(claims)?;
+        opts.manifest_root = manifest_root;
+        opts.claims = claims::claims_digest(claims);
+        let state = Self::open(opts, claims, path, from_env)?;
+        Ok(Self {
+            drv: state.drv.with_expected_root(manifest_root),
+            ..state
+        })
+    }
+
+    /// [`Self::open`] for a session binding the root its blocks hash to.
+    fn open_late_bound(
+        mut opts: DriverOptions,
+        claims: &[Claim],
+        path: String,
+        from_env: bool,
+    ) -> Result<Self> {
+        claims::check_claims(claims)?;
+        opts.late_bound = true;
+        opts.claims = claims::claims_digest(claims);
+        Self::open(opts, claims, path, from_env)
+    }
+}
+
+/// Output path of a streaming proof, from `SEZKP_PROOF_STREAM_PATH`.
+fn stream_path_from_env() -> Result<String> {
+    std::env::var("SEZKP_PROOF_STREAM_PATH").context(
+        "SEZKP_PROOF_STREAM_PATH not set (CLI must provide output path for streaming proofs)",
+    )
 }
 
 impl ProvingBackendStream for FoldBackend {
     type StreamState = StreamState;
 
-    fn begin_stream(_manifest_root: [u8; 32]) -> Result<Self::StreamState> {
-        let opts = opts_from_env(api::DriverOptions::default());
+    fn begin_stream(manifest_root: [u8; 32]) -> Result<Self::StreamState> {
+        Self::begin_stream_with_claims(manifest_root, &[])
+    }
 
-        // Determine output path from env; require it for true sublinear memory.
-        let path = std::env::var("SEZKP_PROOF_STREAM_PATH")
-            .context("SEZKP_PROOF_STREAM_PATH not set (CLI must provide output path for streaming proofs)")?;
+    fn begin_stream_with_claims(
+        manifest_root: [u8; 32],
+        claims: &[Claim],
+    ) -> Result<Self::StreamState> {
+        let opts = opts_from_env(api::DriverOptions::default())?;
+        StreamState::open_bound(opts, manifest_root, claims, stream_path_from_env()?, true)
+    }
 
-        let file = File::create(&path).with_context(|| format!("create {}", &path))?;
-        let sink = driver::CborSeqSink::new(BufWriter::new(file));
-        let drv =
-            driver::StreamDriverSink::<CryptoLeaf, CryptoFold, CryptoWrap, _>::new(sink, opts)?;
-        Ok(StreamState {
-            drv,
-            stream_path: path,
-        })
+    fn begin_stream_late_bound(claims: &[Claim]) -> Result<Self::StreamState> {
+        let opts = opts_from_env(api::DriverOptions::default())?;
+        StreamState::open_late_bound(opts, claims, stream_path_from_env()?, true)
     }
 
     fn ingest_block(state: &mut Self::StreamState, block: BlockSummary) -> Result<()> {
@@ -276,19 +1127,150 @@ impl ProvingBackendStream for FoldBackend {
     }
 
     fn finish_stream(state: Self::StreamState) -> Result<ProofArtifact> {
-        let (root_c, _root_pi) = state.drv.finish()?;
+        let cache_stats = state.drv.cache_stats();
+        let params = state.drv.options().params();
+        let final_wrap = state.drv.options().final_wrap.map(api::WrapBackend::id);
+        let late_bound = state.drv.options().late_bound;
+        let reveal_root_pi = state.drv.options().reveal_root_pi;
+        let (root_c, root_pi) = state.drv.finish()?;
+        let f = File::open(&state.stream_path)
+            .with_context(|| format!("open proof stream {}", state.stream_path))?;
+        let stream_digest = anchor::stream_digest(BufReader::new(f))?;
 
         // Produce a tiny artifact that *references* the external stream file.
-        Ok(ProofArtifact {
-            backend: BackendKind::Stark, // reuse enum tag
-            manifest_root: root_c.root,
-            proof_bytes: Vec::new(), // streaming proof lives on disk
-            meta: serde_json::json!({
+        let mut art = ProofArtifact::new(
+            BackendKind::Fold,
+            root_c.root,
+            Vec::new(), // streaming proof lives on disk
+            serde_json::json!({
                 "proto": "fold-stream",
                 "stream_format": "fold-seq-v1",
                 "stream_path": state.stream_path,
-                "streaming": true
+                "stream_digest": anchor::digest_hex(&stream_digest),
+                "streaming": true,
+                "n_blocks": root_c.len,
+                "params": params,
+                "final_wrap": final_wrap,
+                "cache": cache_stats,
+                "late_bound": late_bound,
             }),
-        })
+        );
+        if reveal_root_pi {
+            art.meta["root_pi"] = serde_json::to_value(RootPi::from(root_pi))?;
+        }
+        art.claims = state.claims;
+        Ok(art)
+    }
+
+    /// Ends the stream file with a truncated footer.
+    fn cancel_stream(state: Self::StreamState) -> Result<()> {
+        state.drv.finish_truncated()?;
+        Ok(())
+    }
+}
+
+/* ------------------------- configured backend value ------------------------ */
+
+/// A [`FoldBackend`] carrying its options (see [`FoldBackend::with_options`]).
+///
+/// It proves with exactly the [`DriverOptions`] it holds and verifies
+/// against the [`ExpectedParams`] it holds, reading no `SEZKP_FOLD_*`,
+/// `SEZKP_EXPECT_*` or `SEZKP_PROOF_STREAM_PATH` variable, so hosts can
+/// prove concurrently with different settings. The manifest root, claims and
+/// late binding in the options are set per proof. The subtree cache,
+/// interface log and resume remain features of the environment-driven
+/// [`FoldBackend`].
+#[derive(Debug, Clone)]
+pub struct ConfiguredFold {
+    opts: DriverOptions,
+    stream_path: Option<PathBuf>,
+    expected: ExpectedParams,
+}
+
+impl FoldBackend {
+    /// A fold backend proving with `opts`, streaming proofs (if any) to
+    /// `stream_path`, instead of reading its settings from the environment.
+    #[must_use]
+    pub fn with_options(opts: DriverOptions, stream_path: Option<PathBuf>) -> ConfiguredFold {
+        ConfiguredFold {
+            opts,
+            stream_path,
+            expected: ExpectedParams::default(),
+        }
+    }
+}
+
+impl ConfiguredFold {
+    /// Require `expected` of the proofs this instance verifies.
+    #[must_use]
+    pub const fn expecting(mut self, expected: ExpectedParams) -> Self {
+        self.expected = expected;
+        self
+    }
+
+    /// The driver options proofs are made with.
+    #[must_use]
+    pub const fn options(&self) -> &DriverOptions {
+        &self.opts
+    }
+
+    fn stream_path(&self) -> Result<String> {
+        let path = self.stream_path.as_ref().ok_or_else(|| {
+            anyhow!("streaming proofs need a stream path (FoldBackend::with_options)")
+        })?;
+        Ok(path.to_string_lossy().into_owned())
+    }
+}
+
+impl ConfiguredBackend for ConfiguredFold {
+    fn prove(&self, blocks: &[BlockSummary], manifest_root: [u8; 32]) -> Result<ProofArtifact> {
+        self.prove_with_claims(blocks, manifest_root, &[])
+    }
+
+    fn prove_with_claims(
+        &self,
+        blocks: &[BlockSummary],
+        manifest_root: [u8; 32],
+        claims: &[Claim],
+    ) -> Result<ProofArtifact> {
+        prove_with_options(self.opts, blocks, manifest_root, claims)
+    }
+
+    fn verify(
+        &self,
+        artifact: &ProofArtifact,
+        blocks: &[BlockSummary],
+        manifest_root: [u8; 32],
+    ) -> Result<()> {
+        let params = verify_with_expected(artifact, manifest_root, &self.expected)?;
+        check_blocks(artifact, params.as_ref(), blocks.iter().map(Ok))
+    }
+}
+
+impl ConfiguredBackendStream for ConfiguredFold {
+    type StreamState = StreamState;
+
+    fn begin_stream_with_claims(
+        &self,
+        manifest_root: [u8; 32],
+        claims: &[Claim],
+    ) -> Result<Self::StreamState> {
+        StreamState::open_bound(self.opts, manifest_root, claims, self.stream_path()?, false)
+    }
+
+    fn begin_stream_late_bound(&self, claims: &[Claim]) -> Result<Self::StreamState> {
+        StreamState::open_late_bound(self.opts, claims, self.stream_path()?, false)
+    }
+
+    fn ingest_block(&self, state: &mut Self::StreamState, block: BlockSummary) -> Result<()> {
+        <FoldBackend as ProvingBackendStream>::ingest_block(state, block)
+    }
+
+    fn finish_stream(&self, state: Self::StreamState) -> Result<ProofArtifact> {
+        <FoldBackend as ProvingBackendStream>::finish_stream(state)
+    }
+
+    fn cancel_stream(&self, state: Self::StreamState) -> Result<()> {
+        <FoldBackend as ProvingBackendStream>::cancel_stream(state)
     }
 }
@@ -0,0 +1,187 @@
+//! Batch artifact envelopes: versions, byte layout, the support window for
+//! older versions, and migration.
+//!
+//! A batch fold artifact wraps its proof bundle in a versioned envelope:
+//!
+//! - **V1:** bincode-framed, the bundle as JSON, written by early releases;
+//! - **V2:** bincode-framed, the bundle as CBOR;
+//! - **V3:** a canonical CBOR map, the bundle as CBOR, written since.
+//!
+//! All three carry the top `(C, π)` next to the bundle. V1 and V2 are
+//! deprecated: they still decode and verify in every release before
+//! [`V1_REMOVED_IN`] and [`V2_REMOVED_IN`] respectively, after which only V3
+//! is read. [`migrate_to_v3`] (and `sezkp-cli migrate-proof`) re-encodes an
+//! older artifact as V3 with the same bundle and roots.
+//!
+//! # V3 byte layout
+//!
+//! `proof_bytes` is one CBOR map (RFC 8949) in the canonical form of
+//! [`sezkp_core::canonical`]: text keys, entries sorted by their encoded key
+//! bytes, and integers at the full width of their type (`u16` as `0x19` plus
+//! 2 bytes, `u32` as `0x1a` plus 4). The map has four entries, so it starts
+//! with `0xa4`; bincode envelopes start with a little-endian `u32` version
+//! (`01 00 00 00` or `02 00 00 00`), which is how readers tell them apart.
+//!
+//! | key        | type                | contents                                   |
+//! |------------|---------------------|--------------------------------------------|
+//! | `"bundle"` | bstr                | the proof bundle as CBOR (below)           |
+//! | `"root_c"` | map                 | top commitment: `"len"` u32, `"root"` bstr(32) |
+//! | `"root_pi"`| map                 | top projection: `"acc"` bstr(32), `"flags"`, `"ctrl_in"`, `"ctrl_out"` u32 |
+//! | `"version"`| u16                 | `3`                                        |
+//!
+//! In encoded order the entries are `bundle`, `root_c`, `root_pi`,
+//! `version` (shorter keys first, then bytewise), and inside `root_pi`:
+//! `acc`, `flags`, `ctrl_in`, `ctrl_out`. `acc` holds the four accumulator
+//! registers as little-endian `u64`s, concatenated. Readers should accept
+//! the entries in any order and reject versions they do not know.
+//!
+//! The bundle is `FoldProofBundle` as serde CBOR: a map keyed by field name
+//! (`n_blocks`, `tree_span`, `leaves`, `folds`, `wraps`, `params`,
+//! `manifest_root`, and `claims`, `final_wrap`, `spot_checks` when present),
+//! with tuples as arrays and byte arrays as arrays of integers. It is the
+//! same encoding the V2 envelope carried.
+//!
+//! # Backend label
+//!
+//! Releases before `fold-v3` labelled fold artifacts with
+//! [`BackendKind::Stark`] and told them apart by `meta.proto`. Verifiers
+//! still accept that label (see [`ProofArtifact::resolved_backend`]);
+//! migration rewrites it to [`BackendKind::Fold`], including for V3
+//! artifacts that otherwise need no change.
+//!
+//! Streaming artifacts reference a CBOR-seq proof file instead of carrying an
+//! envelope, so there is nothing to migrate for them.
+
+#![forbid(unsafe_code)]
+#![deny(rust_2018_idioms)]
+#![warn(
+    clippy::all,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::unwrap_used,
+    clippy::expect_used
+)]
+
+use anyhow::{bail, ensure, Result};
+use sezkp_core::{BackendKind, ProofArtifact};
+
+use crate::{bundle_top, decode_envelope, encode_envelope_v3, DecodedEnvelope, WireVersion};
+
+/// First release (`major.minor`) that no longer decodes V1 envelopes.
+pub const V1_REMOVED_IN: &str = "0.3";
+
+/// First release (`major.minor`) that no longer decodes V2 envelopes, and
+/// with them the bincode read path.
+pub const V2_REMOVED_IN: &str = "0.4";
+
+/// Current envelope version.
+pub const CURRENT_VERSION: u16 = WireVersion::V3 as u16;
+
+/// A migrated artifact.
+#[derive(Clone, Debug)]
+pub struct Migrated {
+    /// The artifact in a V3 envelope.
+    pub artifact: ProofArtifact,
+    /// Envelope version of the input (`3` if it needed no re-encoding).
+    pub from: u16,
+    /// Whether the input carried the legacy `Stark` backend label.
+    pub relabelled: bool,
+    /// Whether the input's timestamp token was dropped. Tokens cover the
+    /// backend label and the proof bytes, which migration changes.
+    pub dropped_timestamp: bool,
+}
+
+/// `true` for artifacts that reference a proof stream instead of carrying an
+/// envelope.
+fn is_streaming(artifact: &ProofArtifact) -> bool {
+    artifact.meta.get("stream_format").is_some()
+}
+
+/// Envelope version of a batch fold artifact (`None` for streaming ones).
+///
+/// # Errors
+/// Fails if the proof bytes are not a fold envelope.
+pub fn envelope_version(artifact: &ProofArtifact) -> Result<Option<u16>> {
+    if is_streaming(artifact) {
+        return Ok(None);
+    }
+    Ok(Some(crate::envelope_version(&artifact.proof_bytes)? as u16))
+}
+
+/// Re-encode a V1 or V2 artifact as V3 labelled [`BackendKind::Fold`].
+///
+/// The bundle, roots, claims and metadata are kept (`meta.proto` becomes
+/// `fold-v3`, and `meta.migrated_from` records the old protocol). V3
+/// artifacts are only relabelled if needed, and otherwise returned unchanged.
+///
+/// The bundle is not re-verified; run the verifier on the result as usual.
+///
+/// # Errors
+/// Fails for streaming artifacts, artifacts of another backend, undecodable
+/// envelopes, or an old envelope whose declared top or artifact root
+/// disagrees with its bundle.
+pub fn migrate_to_v3(artifact: &ProofArtifact) -> Result<Migrated> {
+    if is_streaming(artifact) {
+        bail!("streaming fold artifacts carry no envelope; nothing to migrate");
+    }
+    // Legacy fold artifacts are labelled `Stark`; a real STARK proof fails
+    // to decode as an envelope below.
+    ensure!(
+        matches!(artifact.backend, BackendKind::Fold | BackendKind::Stark),
+        "not a fold artifact (backend {})",
+        artifact.backend
+    );
+    let relabelled = artifact.backend != BackendKind::Fold;
+    let DecodedEnvelope {
+        ver,
+        root_c,
+        root_pi,
+        bundle,
+    } = decode_envelope(&artifact.proof_bytes)?;
+    if ver == WireVersion::V3 {
+        let mut out = artifact.clone();
+        if relabelled {
+            out.backend = BackendKind::Fold;
+            out.timestamp = None;
+        }
+        return Ok(Migrated {
+            artifact: out,
+            from: CURRENT_VERSION,
+            relabelled,
+            dropped_timestamp: relabelled && artifact.timestamp.is_some(),
+        });
+    }
+
+    // Roots are carried over, so they must already agree with the bundle.
+    ensure!(
+        bundle_top(&bundle) == (root_c, root_pi),
+        "V{} envelope top does not match its bundle",
+        ver as u16
+    );
+    ensure!(
+        artifact.manifest_root == root_c.root,
+        "artifact.manifest_root does not match the V{} bundle root",
+        ver as u16
+    );
+
+    let mut meta = artifact.meta.clone();
+    if let Some(m) = meta.as_object_mut() {
+        let old = m
+            .insert("proto".to_owned(), "fold-v3".into())
+            .unwrap_or_else(|| format!("fold-v{}", ver as u16).into());
+        m.insert("migrated_from".to_owned(), old);
+    }
+    let mut out = ProofArtifact::new(
+        BackendKind::Fold,
+        artifact.manifest_root,
+        encode_envelope_v3(&bundle, root_c, root_pi)?,
+        meta,
+    );
+    out.claims.clone_from(&artifact.claims);
+    Ok(Migrated {
+        artifact: out,
+        from: ver as u16,
+        relabelled,
+        dropped_timestamp: artifact.timestamp.is_some(),
+    })
+}
@@ -0,0 +1,128 @@
+//! Upfront peak-memory estimates for the fold drivers.
+//!
+//! The estimate adds up the data structures each driver keeps live: the
+//! blocks (all of them, or only the current one when they are streamed), the
+//! endpoint ledger of the Balanced batch driver, the MinRam endpoint cache,
+//! and one stack frame per live subtree. The frame count comes from
+//! [`sezkp_scheduler::max_live_frames`], the deepest stack the
+//! height-compressed traversal reaches over `n` blocks.
+//!
+//! Figures are **order-of-magnitude estimates**: they are meant to catch
+//! "this will not fit" mistakes before a long run, not to predict RSS.
+
+use sezkp_merkle::CommitManifest;
+use sezkp_scheduler::max_live_frames;
+
+use crate::api::{DriverOptions, FoldMode, LedgerStore};
+use crate::ledger::{PAGE_RECORDS, RECORD_BYTES, RESIDENT_PAGES};
+
+/// Rough size of one fold endpoint `(Commitment, Pi)` plus bookkeeping.
+pub const FOLD_ENDPOINT_BYTES: u64 = 128;
+
+/// Rough in-memory size of one `BlockSummary` with `steps` steps and τ tapes.
+#[must_use]
+pub const fn block_bytes(steps: u64, tau: usize) -> u64 {
+    // Fixed header + per-tape windows/offsets/tags + per-step projection.
+    let tau = tau as u64;
+    256 + tau * (16 + 8 + 32) + steps * (24 + tau * 4)
+}
+
+/// Shape of the blocks a proof covers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BlockStats {
+    /// Number of blocks (leaves).
+    pub n_blocks: u64,
+    /// Total steps across all blocks.
+    pub total_steps: u64,
+    /// Largest single block, in steps.
+    pub max_block_steps: u64,
+    /// Number of work tapes τ.
+    pub tau: usize,
+}
+
+impl BlockStats {
+    /// Stats recorded in a v2 manifest, or `None` for a v1 manifest.
+    ///
+    /// Manifests do not record the largest block, so the mean block size
+    /// stands in for it.
+    #[must_use]
+    pub fn from_manifest(m: &CommitManifest) -> Option<Self> {
+        if !m.has_shape() {
+            return None;
+        }
+        let n_blocks = u64::from(m.n_leaves);
+        Some(Self {
+            n_blocks,
+            total_steps: m.total_steps,
+            max_block_steps: m.total_steps.div_ceil(n_blocks.max(1)),
+            tau: m.tau as usize,
+        })
+    }
+
+    /// Mean steps per block.
+    #[must_use]
+    pub const fn mean_block_steps(&self) -> u64 {
+        self.total_steps / if self.n_blocks == 0 { 1 } else { self.n_blocks }
+    }
+}
+
+/// Peak memory of one fold run, broken down by what holds it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoryEstimate {
+    /// Deepest scheduler stack over the blocks.
+    pub live_frames: u64,
+    /// Blocks held at once (all of them, or the current one when streamed).
+    pub blocks_bytes: u64,
+    /// Driver state: ledger, endpoint cache and live subtree frames.
+    pub driver_bytes: u64,
+}
+
+impl MemoryEstimate {
+    /// Estimated peak, in bytes.
+    #[must_use]
+    pub const fn peak_bytes(&self) -> u64 {
+        self.blocks_bytes + self.driver_bytes
+    }
+}
+
+/// Estimate the peak memory of proving `stats` with `opts`.
+///
+/// `stream` selects the streaming driver (which is also what verification
+/// runs); `blocks_in_memory` says whether the input is materialized whole
+/// rather than read one block at a time.
+#[must_use]
+pub fn estimate_memory(
+    stats: &BlockStats,
+    opts: &DriverOptions,
+    stream: bool,
+    blocks_in_memory: bool,
+) -> MemoryEstimate {
+    let live_frames = usize::try_from(stats.n_blocks)
+        .map_or_else(|_| u64::from(u64::BITS), |n| max_live_frames(n) as u64);
+    let blocks_bytes = if blocks_in_memory {
+        stats.n_blocks * block_bytes(stats.mean_block_steps(), stats.tau)
+    } else {
+        block_bytes(stats.max_block_steps, stats.tau)
+    };
+
+    let driver_bytes = if stream {
+        // Streaming keeps the first/last block of every live subtree.
+        live_frames * (FOLD_ENDPOINT_BYTES + 2 * block_bytes(stats.max_block_steps, stats.tau))
+    } else {
+        match (opts.fold_mode, opts.ledger) {
+            (FoldMode::Balanced, LedgerStore::Memory) => stats.n_blocks * FOLD_ENDPOINT_BYTES,
+            (FoldMode::Balanced, LedgerStore::Disk) => {
+                (RESIDENT_PAGES * PAGE_RECORDS * RECORD_BYTES) as u64
+            }
+            (FoldMode::MinRam, _) => {
+                (u64::from(opts.endpoint_cache) + live_frames) * FOLD_ENDPOINT_BYTES
+            }
+        }
+    };
+
+    MemoryEstimate {
+        live_frames,
+        blocks_bytes,
+        driver_bytes,
+    }
+}
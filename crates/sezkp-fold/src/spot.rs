@@ -0,0 +1,140 @@
+//! Manifest-inclusion spot checks for batch bundles.
+//!
+//! A batch bundle verifies without its blocks, so the only reason a verifier
+//! reads the blocks file is to check that the blocks it was handed commit to
+//! the manifest root. With `spot_checks = k`
+//! ([`crate::api::DriverOptions::spot_checks`]) the prover instead embeds
+//! [`SpotChecks`] in the bundle: `k` blocks at indices drawn from a
+//! [`DS_SPOT_CHECK`] transcript over the manifest root and the block count,
+//! each with a Merkle [`InclusionProof`] to that root.
+//!
+//! The verifier re-derives the indices and, for each sampled block, checks
+//! the inclusion proof, that the bundle's leaf record commits to the same
+//! block, and that the block replays within its windows. Its I/O is `k`
+//! blocks instead of all `n`; a set of `m` bad blocks escapes detection with
+//! probability about `(1 - m/n)^k`.
+//!
+//! The draw does not depend on `k`, so a bundle carrying `k` checks also
+//! satisfies any verifier asking for at most `k`.
+
+use anyhow::{ensure, Context, Result};
+use serde::{Deserialize, Serialize};
+use sezkp_core::{BlockSummary, Replay};
+use sezkp_crypto::{Blake3Transcript, Transcript, TranscriptExt};
+use sezkp_merkle::{inclusion_proof, leaf_hash, InclusionProof};
+
+use crate::api::DS_SPOT_CHECK;
+use crate::driver::FoldProofBundle;
+
+/// One sampled block and its path to the manifest root.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpotCheck {
+    /// The block at [`InclusionProof::index`].
+    pub block: BlockSummary,
+    /// Path from the block's leaf hash to the manifest root.
+    pub path: InclusionProof,
+}
+
+/// Sampled blocks carried by a bundle, in draw order.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpotChecks {
+    /// One entry per sampled block.
+    pub checks: Vec<SpotCheck>,
+}
+
+/// Block indices to sample: the first `min(k, n_blocks)` distinct draws from
+/// a transcript over `manifest_root` and `n_blocks`.
+#[must_use]
+pub fn sample_indices(manifest_root: &[u8; 32], n_blocks: u64, k: u32) -> Vec<u64> {
+    let want = usize::try_from(n_blocks.min(u64::from(k))).unwrap_or(usize::MAX);
+    let mut tr = Blake3Transcript::new(DS_SPOT_CHECK);
+    tr.absorb("manifest.root", manifest_root);
+    tr.absorb_u64("n_blocks", n_blocks);
+    let mut out = Vec::with_capacity(want);
+    while out.len() < want {
+        let i = tr.challenge_u64("index") % n_blocks;
+        if !out.contains(&i) {
+            out.push(i);
+        }
+    }
+    out
+}
+
+/// Build `k` spot checks over `blocks` for a proof bound to `manifest_root`.
+#[must_use]
+pub fn spot_checks(blocks: &[BlockSummary], manifest_root: &[u8; 32], k: u32) -> SpotChecks {
+    let leaves: Vec<[u8; 32]> = blocks.iter().map(leaf_hash).collect();
+    let checks = sample_indices(manifest_root, blocks.len() as u64, k)
+        .into_iter()
+        .filter_map(|i| {
+            let i = usize::try_from(i).ok()?;
+            Some(SpotCheck {
+                block: blocks[i].clone(),
+                path: inclusion_proof(&leaves, i)?,
+            })
+        })
+        .collect();
+    SpotChecks { checks }
+}
+
+/// Check the spot checks in `bundle` against its manifest root, requiring at
+/// least `min_samples` of them (fewer only if the bundle has fewer blocks).
+/// Returns the number of blocks checked.
+///
+/// The bundle itself must be verified separately
+/// ([`crate::verify::verify_bundle`]).
+///
+/// # Errors
+/// Fails if checks are missing or too few, drawn at the wrong indices, or if
+/// a sampled block is not included under the root, differs from the
+/// bundle's leaf record, or does not replay.
+pub fn verify_spot_checks<Lp, Fp, Wp>(
+    bundle: &FoldProofBundle<Lp, Fp, Wp>,
+    replay: &Replay,
+    min_samples: u32,
+) -> Result<usize> {
+    let n_blocks = bundle.n_blocks as u64;
+    let checks = bundle
+        .spot_checks
+        .as_ref()
+        .map_or(&[][..], |s| s.checks.as_slice());
+    let need = n_blocks.min(u64::from(min_samples));
+    ensure!(
+        checks.len() as u64 >= need,
+        "bundle carries {} spot checks, need {need}",
+        checks.len()
+    );
+
+    let k = u32::try_from(checks.len()).context("too many spot checks")?;
+    let want = sample_indices(&bundle.manifest_root, n_blocks, k);
+    ensure!(
+        want.len() == checks.len(),
+        "bundle carries {} spot checks over {n_blocks} blocks",
+        checks.len()
+    );
+    for (check, &i) in checks.iter().zip(&want) {
+        ensure!(
+            check.path.index == i && check.path.n_leaves == n_blocks,
+            "spot check for block {} of {}, expected block {i} of {n_blocks}",
+            check.path.index,
+            check.path.n_leaves
+        );
+        let leaf = leaf_hash(&check.block);
+        check
+            .path
+            .verify(&leaf, &bundle.manifest_root)
+            .with_context(|| format!("spot check {i}"))?;
+        let idx = usize::try_from(i).context("spot check index")?;
+        ensure!(
+            bundle
+                .leaves
+                .get(idx)
+                .is_some_and(|(c, _, _)| c.root == leaf),
+            "spot check {i}: block differs from the bundle's leaf record"
+        );
+        replay
+            .replay_block(&check.block)
+            .with_context(|| format!("spot check {i}: replay"))?;
+    }
+    Ok(checks.len())
+}
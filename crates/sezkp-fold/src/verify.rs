@@ -2,8 +2,28 @@
 //!
 //! Supports two formats:
 //! - **In-memory bundle:** a single serialized object with all leaves/folds/wraps.
+//!   [`verify_bundle`] checks a decoded bundle; [`verify_bundle_cbor`] checks
+//!   the CBOR bytes directly, decoding one record at a time, so bundles verify
+//!   in the same bounded memory as streams.
 //! - **Streaming (CBOR-seq):** `Header, Item*, Footer` where each element is a
 //!   single CBOR value; verification proceeds incrementally with O(1) memory.
+//!
+//! Both check the proof's shape against the parameters it declares (see
+//! [`crate::api::FoldParams`]): a wrap of the just-folded parent after every
+//! `wrap_cadence`-th fold and nowhere else, and a boundary window this build
+//! implements. Legacy proofs that declare nothing are checked against the
+//! stream header's cadence where there is one. Every leaf proof must open
+//! exactly the declared number of sampled interior steps
+//! ([`crate::are_sample`]).
+//!
+//! A [`crate::final_wrap::FinalWrap`], where present, is checked against the
+//! recomputed digest of the bundle or stream.
+//!
+//! Gadget proofs are checked under the manifest root the proof records
+//! ([`FoldProofBundle::manifest_root`], [`StreamHeader::manifest_root`]),
+//! combined with the recorded claims digest where there is one; callers
+//! compare that root with the manifest they were given and the digest with
+//! the claims they expect.
 
 #![forbid(unsafe_code)]
 #![deny(rust_2018_idioms)]
@@ -16,11 +36,29 @@
 )]
 
 use anyhow::{anyhow, ensure, Context, Result};
-use serde::de::DeserializeOwned;
+use serde::de::{self, DeserializeOwned, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::Deserialize;
+use std::fmt;
 use std::io::Read;
+use std::marker::PhantomData;
 
-use crate::api::{commit_pi, Fold as FoldT, Leaf as LeafT, PiCommitment, Wrap as WrapT};
-use crate::driver::{FoldProofBundle, StreamFooter, StreamHeader, StreamItem};
+use sezkp_core::{frontier::MerkleFrontier, progress::ProgressTracker, EMPTY_ROOT};
+
+use crate::api::{
+    commit_pi, Commitment, Fold as FoldT, FoldParams, Leaf as LeafT, PiCommitment, Wrap as WrapT,
+};
+use crate::are::Pi;
+use crate::driver::{
+    FoldProofBundle, StreamFooter, StreamHeader, StreamItem, STREAM_MAGIC, STREAM_VERSION,
+};
+use crate::final_wrap::{bundle_digest, BundleDigest, FinalWrap, StreamDigest};
+
+/// A leaf record of a bundle: `(C, π, proof)`.
+type LeafRecord<Lp> = (Commitment, Pi, Lp);
+/// A fold record of a bundle: `(parent, left, right, proof)`.
+type FoldRecord<Fp> = ((Commitment, Pi), (Commitment, Pi), (Commitment, Pi), Fp);
+/// A wrap record of a bundle: `(root, proof)`.
+type WrapRecord<Wp> = ((Commitment, Pi), Wp);
 
 /// Verify an in-memory folding bundle using the given gadgets.
 ///
@@ -32,29 +70,490 @@ where
     F: FoldT,
     W: WrapT,
 {
+    // 0) Shape: one leaf record per block; nothing to fold without leaves.
+    ensure!(
+        bundle.leaves.len() == bundle.n_blocks,
+        "bundle has {} leaf records for {} blocks",
+        bundle.leaves.len(),
+        bundle.n_blocks
+    );
+    ensure!(
+        bundle.n_blocks > 0 || (bundle.folds.is_empty() && bundle.wraps.is_empty()),
+        "empty bundle carries fold/wrap records"
+    );
+    if let Some(params) = &bundle.params {
+        params.check_supported()?;
+        check_wrap_placement(bundle, params.wrap_cadence)?;
+    }
+
+    let _verify = sezkp_core::phase_span!(
+        "fold.verify_bundle",
+        leaves = bundle.leaves.len(),
+        folds = bundle.folds.len(),
+        wraps = bundle.wraps.len()
+    );
+
     // 1) Leaves
-    for (c, pi, lp) in &bundle.leaves {
-        let pi_cmt = commit_pi(pi);
-        ensure!(L::verify_leaf(c, &pi_cmt, lp), "leaf proof failed");
+    let root = &sezkp_core::claims::bind_root(&bundle.manifest_root, bundle.claims.as_ref());
+    let samples = bundle.params.map_or(0, |p| p.are_samples);
+    for leaf in &bundle.leaves {
+        check_leaf::<L>(root, leaf, samples)?;
     }
 
     // 2) Folds (bottom-up)
-    for ((c_par, pi_par), (c_l, pi_l), (c_r, pi_r), pf) in &bundle.folds {
-        let parent = (c_par, commit_pi(pi_par));
-        let left = (c_l, commit_pi(pi_l));
-        let right = (c_r, commit_pi(pi_r));
+    for fold in &bundle.folds {
+        check_fold::<F>(root, fold)?;
+    }
+
+    // 3) Wraps (if any)
+    for wrap in &bundle.wraps {
+        check_wrap::<W>(root, wrap)?;
+    }
+
+    // 4) Final wrap (if any) over the root and the bundle digest
+    if let Some(fw) = &bundle.final_wrap {
+        let (c, pi) = crate::bundle_top(bundle);
+        fw.verify((&c, &commit_pi(&pi)), &bundle_digest(bundle)?)?;
+    }
+
+    Ok(())
+}
+
+/// Leaf record `(C, π, proof)` under the bound manifest `root`.
+fn check_leaf<L: LeafT>(root: &[u8; 32], (c, pi, lp): &LeafRecord<L::Proof>, samples: u32) -> Result<()> {
+    ensure!(
+        L::verify_leaf_sampled(root, c, &commit_pi(pi), lp, samples),
+        "leaf proof failed"
+    );
+    Ok(())
+}
+
+/// Fold record `(parent, left, right, proof)` under the bound manifest `root`.
+fn check_fold<F: FoldT>(root: &[u8; 32], (parent, left, right, pf): &FoldRecord<F::Proof>) -> Result<()> {
+    let parent = (&parent.0, &commit_pi(&parent.1));
+    let left = (&left.0, &commit_pi(&left.1));
+    let right = (&right.0, &commit_pi(&right.1));
+    ensure!(F::verify_fold(root, parent, left, right, pf), "fold proof failed");
+    Ok(())
+}
+
+/// Wrap record `(root(C, π), proof)` under the bound manifest `root`.
+fn check_wrap<W: WrapT>(root: &[u8; 32], ((c, pi), wp): &WrapRecord<W::Proof>) -> Result<()> {
+    ensure!(W::verify_wrap(root, (c, &commit_pi(pi)), wp), "wrap proof failed");
+    Ok(())
+}
+
+/// Wraps a cadence of `k` requires over `n_folds` folds.
+const fn wraps_due(n_folds: usize, k: u32) -> usize {
+    match k {
+        0 => 0,
+        k => n_folds / k as usize,
+    }
+}
+
+/// A bundle must carry exactly the wraps its cadence requires.
+fn check_wrap_count(n_wraps: usize, n_folds: usize, k: u32) -> Result<()> {
+    let due = wraps_due(n_folds, k);
+    ensure!(
+        n_wraps == due,
+        "bundle has {n_wraps} wraps; wrap cadence {k} over {n_folds} folds requires {due}"
+    );
+    Ok(())
+}
+
+/// Wraps must attest the parent of every `k`-th fold, in order, and nothing else.
+fn check_wrap_placement<Lp, Fp, Wp>(bundle: &FoldProofBundle<Lp, Fp, Wp>, k: u32) -> Result<()> {
+    check_wrap_count(bundle.wraps.len(), bundle.folds.len(), k)?;
+    if k == 0 {
+        return Ok(());
+    }
+    let due = bundle
+        .folds
+        .iter()
+        .skip(k as usize - 1)
+        .step_by(k as usize)
+        .map(|(parent, _, _, _)| parent);
+    for (i, (((c, pi), _), (pc, ppi))) in bundle.wraps.iter().zip(due).enumerate() {
         ensure!(
-            F::verify_fold((&parent.0, &parent.1), (&left.0, &left.1), (&right.0, &right.1), pf),
-            "fold proof failed"
+            c == pc && pi == ppi,
+            "wrap {i} does not attest the parent of fold {}",
+            (i + 1) * k as usize
         );
     }
+    Ok(())
+}
 
-    // 3) Wraps (if any)
-    for ((c, pi), wp) in &bundle.wraps {
-        let pi_cmt = commit_pi(pi);
-        ensure!(W::verify_wrap((c, &pi_cmt), wp), "wrap proof failed");
+/// What a verified bundle declares, for callers to check against the
+/// artifact and their expectations.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BundleSummary {
+    /// Number of input blocks.
+    pub n_blocks: usize,
+    /// Declared parameters (absent in older bundles).
+    pub params: Option<FoldParams>,
+    /// Manifest root the gadget proofs are bound to.
+    pub manifest_root: [u8; 32],
+    /// Claims digest bound into the gadget proofs, if any.
+    pub claims: Option<[u8; 32]>,
+    /// Top `(C, π)`: the last fold's parent, else the last leaf, else empty.
+    pub top: (Commitment, Pi),
+}
+
+impl BundleSummary {
+    /// Summary of a decoded bundle.
+    #[must_use]
+    pub fn of<Lp, Fp, Wp>(bundle: &FoldProofBundle<Lp, Fp, Wp>) -> Self {
+        Self {
+            n_blocks: bundle.n_blocks,
+            params: bundle.params,
+            manifest_root: bundle.manifest_root,
+            claims: bundle.claims,
+            top: crate::bundle_top(bundle),
+        }
+    }
+}
+
+/// Verify a bundle from its CBOR encoding (as carried by V2 and V3
+/// envelopes) without decoding it whole.
+///
+/// Makes the same checks as [`verify_bundle`] in two passes over `bytes`:
+/// the first reads the scalar fields and counts the records, skipping the
+/// proofs; the second decodes, checks and drops one record at a time. Extra
+/// memory is one record plus constant state, however large the bundle.
+/// Wrap placement is checked by comparing running hashes of the due fold
+/// parents and of the wrapped roots, so a misplaced wrap is reported without
+/// its index. Records must be listed leaves, folds, wraps, the order the
+/// provers write.
+///
+/// Embedded spot checks are skipped; use [`crate::spot`] on a decoded bundle.
+///
+/// # Errors
+/// Fails if `bytes` are not a CBOR bundle or any check of [`verify_bundle`]
+/// fails.
+pub fn verify_bundle_cbor<L, F, W>(bytes: &[u8]) -> Result<BundleSummary>
+where
+    L: LeafT,
+    F: FoldT,
+    W: WrapT,
+{
+    // 1) Scalars and record counts; shape checks before any proof.
+    let head: BundleHead = serde_cbor::from_slice(bytes).context("decoding CBOR bundle")?;
+    let (n_leaves, n_folds, n_wraps) = (head.leaves.0, head.folds.0, head.wraps.0);
+    ensure!(
+        n_leaves == head.n_blocks,
+        "bundle has {n_leaves} leaf records for {} blocks",
+        head.n_blocks
+    );
+    ensure!(
+        head.n_blocks > 0 || (n_folds == 0 && n_wraps == 0),
+        "empty bundle carries fold/wrap records"
+    );
+    if let Some(params) = &head.params {
+        params.check_supported()?;
+        check_wrap_count(n_wraps, n_folds, params.wrap_cadence)?;
+    }
+
+    let _verify = sezkp_core::phase_span!(
+        "fold.verify_bundle",
+        leaves = n_leaves,
+        folds = n_folds,
+        wraps = n_wraps
+    );
+
+    // 2) Records, one at a time.
+    let mut lazy = LazyBundle::<L, F, W> {
+        root: sezkp_core::claims::bind_root(&head.manifest_root, head.claims.as_ref()),
+        samples: head.params.map_or(0, |p| p.are_samples),
+        cadence: head.params.map(|p| p.wrap_cadence),
+        lens: [n_leaves, n_folds, n_wraps],
+        next: 0,
+        n_folds: 0,
+        top: None,
+        due: blake3::Hasher::new(),
+        wrapped: blake3::Hasher::new(),
+        digest: match head.final_wrap {
+            Some(_) => Some(BundleDigest::new(head.n_blocks, head.tree_span)?),
+            None => None,
+        },
+        failure: None,
+        _gadgets: PhantomData,
+    };
+    let mut de = serde_cbor::Deserializer::from_slice(bytes);
+    let walked = Records(&mut lazy).deserialize(&mut de).and_then(|()| de.end());
+    if let Some(e) = lazy.failure.take() {
+        return Err(e);
+    }
+    walked.context("decoding CBOR bundle")?;
+    lazy.finish(&head)
+}
+
+/// First pass of [`verify_bundle_cbor`]: everything but the records, which
+/// are only counted. Unknown fields (spot checks) are skipped.
+#[derive(Deserialize)]
+struct BundleHead {
+    n_blocks: usize,
+    tree_span: (u32, u32),
+    leaves: SeqLen,
+    folds: SeqLen,
+    wraps: SeqLen,
+    #[serde(default)]
+    params: Option<FoldParams>,
+    #[serde(default)]
+    manifest_root: [u8; 32],
+    #[serde(default)]
+    claims: Option<[u8; 32]>,
+    #[serde(default)]
+    final_wrap: Option<FinalWrap>,
+}
+
+/// Length of a sequence whose elements are skipped undecoded.
+struct SeqLen(usize);
+
+impl<'de> Deserialize<'de> for SeqLen {
+    fn deserialize<D: de::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        struct Count;
+        impl<'de> Visitor<'de> for Count {
+            type Value = SeqLen;
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a sequence of records")
+            }
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<SeqLen, A::Error> {
+                let mut n = 0;
+                while seq.next_element::<IgnoredAny>()?.is_some() {
+                    n += 1;
+                }
+                Ok(SeqLen(n))
+            }
+        }
+        d.deserialize_seq(Count)
+    }
+}
+
+/// Record sections in the order they must appear.
+const SECTIONS: [&str; 3] = ["leaves", "folds", "wraps"];
+
+/// Second-pass state of [`verify_bundle_cbor`].
+struct LazyBundle<L, F, W> {
+    /// Manifest root bound with the claims digest.
+    root: [u8; 32],
+    samples: u32,
+    /// Declared wrap cadence (placement is unchecked without parameters).
+    cadence: Option<u32>,
+    /// Record counts from the first pass, by section.
+    lens: [usize; 3],
+    /// Index of the next section expected.
+    next: usize,
+    n_folds: usize,
+    top: Option<(Commitment, Pi)>,
+    /// Running hash of the parents wraps are due for.
+    due: blake3::Hasher,
+    /// Running hash of the roots the wraps attest.
+    wrapped: blake3::Hasher,
+    /// Bundle digest, when there is a final wrap to check.
+    digest: Option<BundleDigest>,
+    /// The check that stopped the walk (serde errors only carry text).
+    failure: Option<anyhow::Error>,
+    _gadgets: PhantomData<(L, F, W)>,
+}
+
+impl<L: LeafT, F: FoldT, W: WrapT> LazyBundle<L, F, W> {
+    /// Enter section `i`, which must be the next one.
+    fn enter(&mut self, i: usize) -> Result<()> {
+        ensure!(
+            i == self.next,
+            "bundle lists its {} before its {}",
+            SECTIONS[i],
+            SECTIONS[self.next.min(2)]
+        );
+        self.next += 1;
+        if let Some(d) = self.digest.as_mut() {
+            d.section(self.lens[i]);
+        }
+        Ok(())
+    }
+
+    fn absorb<T: serde::Serialize>(&mut self, record: &T) -> Result<()> {
+        self.digest.as_mut().map_or(Ok(()), |d| d.absorb(record))
+    }
+
+    fn leaf(&mut self, rec: &LeafRecord<L::Proof>) -> Result<()> {
+        check_leaf::<L>(&self.root, rec, self.samples)?;
+        self.absorb(rec)?;
+        self.top = Some((rec.0, rec.1));
+        Ok(())
+    }
+
+    fn fold(&mut self, rec: &FoldRecord<F::Proof>) -> Result<()> {
+        check_fold::<F>(&self.root, rec)?;
+        self.absorb(rec)?;
+        self.n_folds += 1;
+        if let Some(k) = self.cadence.filter(|&k| k != 0) {
+            if self.n_folds.is_multiple_of(k as usize) {
+                self.due.update(&sezkp_core::canonical::to_vec(&rec.0)?);
+            }
+        }
+        self.top = Some(rec.0);
+        Ok(())
+    }
+
+    fn wrap(&mut self, rec: &WrapRecord<W::Proof>) -> Result<()> {
+        check_wrap::<W>(&self.root, rec)?;
+        self.absorb(rec)?;
+        self.wrapped.update(&sezkp_core::canonical::to_vec(&rec.0)?);
+        Ok(())
     }
 
+    /// Checks that need every record: wrap placement and the final wrap.
+    fn finish(self, head: &BundleHead) -> Result<BundleSummary> {
+        ensure!(self.next == SECTIONS.len(), "bundle is missing records");
+        if let Some(k) = self.cadence {
+            ensure!(
+                self.due.finalize() == self.wrapped.finalize(),
+                "wraps do not attest the parents of the folds due under wrap cadence {k}"
+            );
+        }
+        let top = self
+            .top
+            .unwrap_or_else(|| (Commitment::new(EMPTY_ROOT, 0), Pi::default()));
+        if let (Some(fw), Some(d)) = (&head.final_wrap, self.digest) {
+            let digest = d.finalize(head.params.as_ref(), &head.manifest_root)?;
+            fw.verify((&top.0, &commit_pi(&top.1)), &digest)?;
+        }
+        Ok(BundleSummary {
+            n_blocks: head.n_blocks,
+            params: head.params,
+            manifest_root: head.manifest_root,
+            claims: head.claims,
+            top,
+        })
+    }
+
+    /// Record `e` as the reason the walk stops, returning a serde error.
+    fn fail<E: de::Error>(&mut self, e: anyhow::Error) -> E {
+        let err = E::custom(format!("{e:#}"));
+        self.failure = Some(e);
+        err
+    }
+}
+
+/// Second-pass walk over the bundle map, handing records to [`LazyBundle`].
+struct Records<'a, L, F, W>(&'a mut LazyBundle<L, F, W>);
+
+impl<'de, L: LeafT, F: FoldT, W: WrapT> DeserializeSeed<'de> for Records<'_, L, F, W> {
+    type Value = ();
+
+    fn deserialize<D: de::Deserializer<'de>>(self, d: D) -> Result<(), D::Error> {
+        d.deserialize_map(self)
+    }
+}
+
+impl<'de, L: LeafT, F: FoldT, W: WrapT> Visitor<'de> for Records<'_, L, F, W> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a fold proof bundle")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        while let Some(key) = map.next_key::<String>()? {
+            let Some(i) = SECTIONS.iter().position(|s| *s == key) else {
+                map.next_value::<IgnoredAny>()?;
+                continue;
+            };
+            self.0.enter(i).map_err(|e| self.0.fail(e))?;
+            map.next_value_seed(Section(&mut *self.0, i))?;
+        }
+        Ok(())
+    }
+}
+
+/// One record section (index into [`SECTIONS`]).
+struct Section<'a, L, F, W>(&'a mut LazyBundle<L, F, W>, usize);
+
+impl<'de, L: LeafT, F: FoldT, W: WrapT> DeserializeSeed<'de> for Section<'_, L, F, W> {
+    type Value = ();
+
+    fn deserialize<D: de::Deserializer<'de>>(self, d: D) -> Result<(), D::Error> {
+        d.deserialize_seq(self)
+    }
+}
+
+impl<'de, L: LeafT, F: FoldT, W: WrapT> Visitor<'de> for Section<'_, L, F, W> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a sequence of {}", SECTIONS[self.1])
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        let lazy = self.0;
+        match self.1 {
+            0 => {
+                while let Some(rec) = seq.next_element::<LeafRecord<L::Proof>>()? {
+                    lazy.leaf(&rec).map_err(|e| lazy.fail(e))?;
+                }
+            }
+            1 => {
+                while let Some(rec) = seq.next_element::<FoldRecord<F::Proof>>()? {
+                    lazy.fold(&rec).map_err(|e| lazy.fail(e))?;
+                }
+            }
+            _ => {
+                while let Some(rec) = seq.next_element::<WrapRecord<W::Proof>>()? {
+                    lazy.wrap(&rec).map_err(|e| lazy.fail(e))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The footer must be complete (not [`StreamFooter::truncated`]), count the
+/// `n_leaves` leaves seen, and repeat the header's parameters, manifest root
+/// and claims; a late-bound footer instead names the Merkle root of the leaf
+/// commitments, accumulated in `leaf_root`.
+fn check_footer(
+    header: &StreamHeader,
+    footer: &StreamFooter,
+    n_leaves: u64,
+    leaf_root: Option<&MerkleFrontier>,
+) -> Result<()> {
+    ensure!(
+        !footer.truncated,
+        "proof stream was cancelled after {} blocks and is not a proof",
+        footer.n_blocks
+    );
+    ensure!(
+        footer.n_blocks == n_leaves,
+        "footer.n_blocks ({}) != counted leaves ({})",
+        footer.n_blocks,
+        n_leaves
+    );
+    let declared = header.params();
+    ensure!(
+        footer.params == declared,
+        "footer parameters {:?} differ from header parameters {declared:?}",
+        footer.params
+    );
+    if header.late_bound {
+        ensure!(
+            header.manifest_root == [0u8; 32],
+            "late-bound stream header names a manifest root"
+        );
+        ensure!(
+            Some(footer.manifest_root) == leaf_root.map(MerkleFrontier::root),
+            "late-bound footer manifest root differs from the root of the leaves"
+        );
+    } else {
+        ensure!(
+            footer.manifest_root == header.manifest_root,
+            "footer manifest root differs from the header's"
+        );
+    }
+    ensure!(
+        footer.claims == header.claims,
+        "footer claims digest differs from the header's"
+    );
     Ok(())
 }
 
@@ -65,7 +564,39 @@ where
 /// `ciborium::de::from_reader`, then convert it into either `StreamFooter`
 /// or `StreamItem<…>` via `Value::deserialized::<T>()`. This avoids a dedicated
 /// streaming-deserializer type and never rewinds.
-pub fn verify_stream<L, F, W, R>(mut reader: R) -> Result<()>
+///
+/// Returns the verified footer. The footer root must equal the last root seen
+/// in the stream (a lone leaf is its own root); an empty stream must end in
+/// the empty root (see [`sezkp_core::EMPTY_ROOT`]). The footer's parameters
+/// and manifest root must equal the header's; callers can check them against
+/// expectations via [`StreamFooter::params`] and
+/// [`StreamFooter::manifest_root`]. Gadget proofs made before version 3 are
+/// not bound to a manifest root and fail verification.
+pub fn verify_stream<L, F, W, R>(reader: R) -> Result<StreamFooter>
+where
+    L: LeafT,
+    F: FoldT,
+    W: WrapT,
+    L::Proof: DeserializeOwned,
+    F::Proof: DeserializeOwned,
+    W::Proof: DeserializeOwned,
+    R: Read,
+{
+    verify_stream_with_progress::<L, F, W, R>(reader, None)
+}
+
+/// [`verify_stream`], reporting verified leaves to the process progress sink
+/// (see [`sezkp_core::progress`]) out of `n_blocks` when the count is known.
+///
+/// The hint only drives percentage/ETA; the footer count is still checked
+/// against the leaves actually seen.
+///
+/// # Errors
+/// Same as [`verify_stream`].
+pub fn verify_stream_with_progress<L, F, W, R>(
+    mut reader: R,
+    n_blocks: Option<u64>,
+) -> Result<StreamFooter>
 where
     L: LeafT,
     F: FoldT,
@@ -77,16 +608,33 @@ where
 {
     use ciborium::{de, value::Value};
 
+    let _verify = sezkp_core::phase_span!("fold.verify_stream");
+    let mut progress = ProgressTracker::new("fold.verify_stream", n_blocks);
+
     // 1) Header
     let header: StreamHeader = de::from_reader(&mut reader).context("decoding stream header")?;
     ensure!(
-        header.magic == "sezkp-fold-seq" && header.ver == 1,
+        header.magic == STREAM_MAGIC && (1..=STREAM_VERSION).contains(&header.ver),
         "unsupported stream format"
     );
+    let manifest_root = sezkp_core::claims::bind_root(&header.manifest_root, header.claims.as_ref());
+    let declared = header.params();
+    if let Some(p) = &declared {
+        p.check_supported()?;
+    }
+    let cadence = u64::from(header.wrap_cadence);
+    let samples = declared.map_or(0, |p| p.are_samples);
+    let mut digest = StreamDigest::new();
+    digest.absorb(&header)?;
 
     // 2) Items until we reach a footer.
     let mut n_leaves: u64 = 0;
+    let mut n_folds: u64 = 0;
     let mut final_root: Option<(crate::api::Commitment, PiCommitment)> = None;
+    // Parent of the last fold while its cadence wrap is outstanding.
+    let mut wrap_due: Option<(Commitment, PiCommitment)> = None;
+    // Manifest root recomputed from the leaves, for a late-bound footer.
+    let mut leaf_root = header.late_bound.then(MerkleFrontier::new);
 
     loop {
         // Pull the next raw CBOR value (either Item or Footer).
@@ -95,30 +643,58 @@ where
 
         // Try Footer first.
         if let Ok(footer) = v.deserialized::<StreamFooter>() {
+            check_footer(&header, &footer, n_leaves, leaf_root.as_ref())?;
+            ensure!(
+                wrap_due.is_none(),
+                "stream ends without the wrap due after fold {n_folds}"
+            );
+            let (c, pi_cmt) = final_root.unwrap_or_else(|| {
+                (
+                    Commitment::new(EMPTY_ROOT, 0),
+                    commit_pi(&crate::are::Pi::default()),
+                )
+            });
             ensure!(
-                footer.n_blocks == n_leaves,
-                "footer.n_blocks ({}) != counted leaves ({})",
-                footer.n_blocks,
-                n_leaves
+                c == footer.root_c && pi_cmt == footer.root_pi_cmt,
+                "footer root does not match last root seen"
             );
-            if let Some((c, pi_cmt)) = final_root {
+            if let Some(pi) = &footer.root_pi {
                 ensure!(
-                    c == footer.root_c && pi_cmt == footer.root_pi_cmt,
-                    "footer root does not match last root seen"
+                    commit_pi(pi) == footer.root_pi_cmt,
+                    "revealed root π does not match its commitment"
                 );
             }
-            break; // footer terminates the stream
+            if let Some(fw) = &footer.final_wrap {
+                fw.verify((&footer.root_c, &footer.root_pi_cmt), &digest.finalize())?;
+            }
+            progress.finish();
+            return Ok(footer); // footer terminates the stream
         }
 
         // Otherwise, it must be an item.
         let item: StreamItem<L::Proof, F::Proof, W::Proof> = v
             .deserialized()
             .map_err(|e| anyhow!("decoding stream item: {e}"))?;
+        digest.absorb(&item)?;
+
+        let is_wrap = matches!(item, StreamItem::Wrap { .. });
+        ensure!(
+            wrap_due.is_none() || is_wrap,
+            "missing wrap after fold {n_folds} (wrap cadence {cadence})"
+        );
 
         match item {
             StreamItem::Leaf { c, pi_cmt, proof } => {
-                ensure!(L::verify_leaf(&c, &pi_cmt, &proof), "leaf proof failed");
+                ensure!(
+                    L::verify_leaf_sampled(&manifest_root, &c, &pi_cmt, &proof, samples),
+                    "leaf proof failed"
+                );
+                if let Some(f) = leaf_root.as_mut() {
+                    f.push(c.root);
+                }
                 n_leaves = n_leaves.saturating_add(1);
+                progress.tick(1);
+                final_root = Some((c, pi_cmt));
             }
             StreamItem::Fold {
                 parent,
@@ -127,17 +703,58 @@ where
                 proof,
             } => {
                 ensure!(
-                    F::verify_fold((&parent.0, &parent.1), (&left.0, &left.1), (&right.0, &right.1), &proof),
+                    F::verify_fold(&manifest_root, (&parent.0, &parent.1), (&left.0, &left.1), (&right.0, &right.1), &proof),
                     "fold proof failed"
                 );
+                n_folds = n_folds.saturating_add(1);
+                if cadence != 0 && n_folds.is_multiple_of(cadence) {
+                    wrap_due = Some(parent);
+                }
                 final_root = Some(parent);
             }
             StreamItem::Wrap { root, proof } => {
-                ensure!(W::verify_wrap((&root.0, &root.1), &proof), "wrap proof failed");
+                ensure!(
+                    wrap_due.take() == Some(root),
+                    "unexpected wrap after fold {n_folds} (wrap cadence {cadence})"
+                );
+                ensure!(
+                    W::verify_wrap(&manifest_root, (&root.0, &root.1), &proof),
+                    "wrap proof failed"
+                );
                 final_root = Some(root);
             }
         }
     }
+}
 
-    Ok(())
+/// Call `f` on every leaf proof of the CBOR-seq stream read from `reader`, in
+/// block order, up to the footer.
+///
+/// Fold and wrap proofs are skipped undecoded and nothing is verified; run
+/// [`verify_stream`] first.
+///
+/// # Errors
+/// Fails if the stream does not decode, ends before its footer, or `f` fails.
+pub fn for_each_stream_leaf<Lp, R, G>(mut reader: R, mut f: G) -> Result<()>
+where
+    Lp: DeserializeOwned,
+    R: Read,
+    G: FnMut(&Lp) -> Result<()>,
+{
+    use ciborium::{de, value::Value};
+
+    let _header: StreamHeader = de::from_reader(&mut reader).context("decoding stream header")?;
+    loop {
+        let v: Value = de::from_reader(&mut reader)
+            .map_err(|e| anyhow!("reading next CBOR value in fold stream: {e}"))?;
+        if v.deserialized::<StreamFooter>().is_ok() {
+            return Ok(());
+        }
+        let item: StreamItem<Lp, IgnoredAny, IgnoredAny> = v
+            .deserialized()
+            .map_err(|e| anyhow!("decoding stream item: {e}"))?;
+        if let StreamItem::Leaf { proof, .. } = item {
+            f(&proof)?;
+        }
+    }
 }
@@ -11,11 +11,11 @@
 use sezkp_fold::api::Leaf;
 use sezkp_fold::are::Pi;
 use sezkp_fold::are_replay::{prove_replay_from_children, verify_replay_from_children, AreProof};
-use sezkp_stark::v1::air::{prove_iface_replay, verify_iface_replay, LeafIfacePublic};
+use sezkp_gadgets::iface::{prove_iface_replay, verify_iface_replay, LeafIfacePublic};
 use sezkp_trace::{generator::generate_trace, partition::partition_trace};
 
 #[inline]
-fn le_to_u64(x: &sezkp_stark::v1::field::F1) -> u64 {
+fn le_to_u64(x: &sezkp_gadgets::F1) -> u64 {
     u64::from_le_bytes(x.to_le_bytes())
 }
 
@@ -28,8 +28,8 @@ fn are_iface_stark_happy_and_adversarial() {
     let right = &blocks[1];
 
     // Build π for both leaves using the leaf gadget (to get packed limbs).
-    let (pi_l, _, _) = sezkp_fold::leaf::CryptoLeaf::prove_leaf(left);
-    let (pi_r, _, _) = sezkp_fold::leaf::CryptoLeaf::prove_leaf(right);
+    let (pi_l, _, _) = sezkp_fold::leaf::CryptoLeaf::prove_leaf(&[0u8; 32], left);
+    let (pi_r, _, _) = sezkp_fold::leaf::CryptoLeaf::prove_leaf(&[0u8; 32], right);
 
     // Extract prefixes as the AreIfaceAir expects.
     let _lt_l = [le_to_u64(&pi_l.acc[0]), le_to_u64(&pi_l.acc[1])];
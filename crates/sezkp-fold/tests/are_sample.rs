@@ -0,0 +1,210 @@
+//! ARE sampling: leaf proofs open transcript-chosen interior steps, bound to
+//! the leaf commitment, and verifiers hold them to the declared count.
+
+#![allow(clippy::unwrap_used)]
+
+mod utils;
+
+use sezkp_core::{BlockSummary, ConfiguredBackend, ConfiguredBackendStream, ProvingBackend};
+use sezkp_fold::api::{commit_pi, Commitment, DriverOptions, ExpectedParams, FoldMode, Leaf};
+use sezkp_fold::are_sample::StepSamples;
+use sezkp_fold::cache::SubtreeCache;
+use sezkp_fold::driver::run_pipeline;
+use sezkp_fold::{verify, CryptoFold, CryptoLeaf, CryptoWrap, FoldBackend};
+use sezkp_merkle::commit_blocks;
+use utils::{blocks_of, stream_with, temp_path, verify_bytes};
+
+/// Manifest root the standalone leaf proofs are bound to.
+const ROOT: [u8; 32] = [7; 32];
+
+/// Blocks of `b` steps (the last one shorter when `b` does not divide 200).
+fn blocks(b: u32) -> Vec<BlockSummary> {
+    blocks_of(200, b)
+}
+
+fn opts(are_samples: u32) -> DriverOptions {
+    utils::opts(|o| o.are_samples = are_samples)
+}
+
+fn stream(blocks: &[BlockSummary], opts: DriverOptions, cache: Option<SubtreeCache>) -> Vec<u8> {
+    stream_with(blocks, opts, |d| match cache {
+        Some(cache) => d.with_cache(cache),
+        None => d,
+    })
+}
+
+#[test]
+fn sampled_leaves_verify_in_bundles_and_streams() {
+    // Uneven step counts exercise promoted nodes in the rows tree.
+    let blocks = blocks(37);
+    for mode in [FoldMode::Balanced, FoldMode::MinRam] {
+        let opts = DriverOptions {
+            fold_mode: mode,
+            ..opts(8)
+        };
+        let bundle = run_pipeline::<CryptoLeaf, CryptoFold, CryptoWrap>(&blocks, &opts);
+        assert_eq!(bundle.params.unwrap().are_samples, 8);
+        for (_, _, lp) in &bundle.leaves {
+            assert_eq!(lp.samples.as_ref().unwrap().openings.len(), 8);
+        }
+        verify::verify_bundle::<CryptoLeaf, CryptoFold, CryptoWrap>(&bundle).unwrap();
+    }
+
+    let bytes = stream(&blocks, opts(8), None);
+    let footer = verify_bytes(&bytes).unwrap();
+    assert_eq!(footer.params.unwrap().are_samples, 8);
+
+    // Without sampling the leaf proofs are unchanged.
+    let plain = run_pipeline::<CryptoLeaf, CryptoFold, CryptoWrap>(&blocks, &opts(0));
+    assert!(plain.leaves.iter().all(|(_, _, lp)| lp.samples.is_none()));
+}
+
+#[test]
+fn forged_openings_are_rejected() {
+    let block = &blocks(37)[1];
+    let (pi, c, proof) = CryptoLeaf::prove_leaf_sampled(&ROOT, block, 8);
+    let pi_cmt = commit_pi(&pi);
+    assert!(CryptoLeaf::verify_leaf_sampled(&ROOT, &c, &pi_cmt, &proof, 8));
+    let samples = proof.samples.clone().unwrap();
+
+    // Any change to the samples breaks the leaf MAC.
+    let mut forged = proof.clone();
+    forged.samples.as_mut().unwrap().openings[0]
+        .at
+        .row
+        .step
+        .tapes[0]
+        .mv ^= 1;
+    assert!(!CryptoLeaf::verify_leaf_sampled(&ROOT, &c, &pi_cmt, &forged, 8));
+
+    // On their own, edited rows no longer open the rows root ...
+    let mut rows = samples.clone();
+    rows.openings[0].at.row.heads[0] += 1;
+    let err = rows.verify(&c, 8).unwrap_err();
+    assert!(
+        format!("{err:#}").contains("does not open the rows root"),
+        "{err:#}"
+    );
+
+    // ... openings must be the transcript's draws ...
+    let mut moved = samples.clone();
+    moved.openings.swap(0, 1);
+    assert!(moved.openings[0].at.index != samples.openings[0].at.index);
+    assert!(moved.verify(&c, 8).is_err());
+
+    // ... and the shape must be the committed block's.
+    let mut shape = samples;
+    shape.shape.ctrl_out ^= 1;
+    let err = shape.verify(&c, 8).unwrap_err();
+    assert!(err.to_string().contains("leaf commitment"), "{err}");
+}
+
+#[test]
+fn forged_step_logs_are_rejected_given_the_blocks() {
+    // Same shapes and log lengths, so the same leaf hashes and manifest
+    // root, but no step writes anything.
+    let blocks = blocks(25);
+    let mut forged = blocks.clone();
+    for s in forged.iter_mut().flat_map(|b| &mut b.movement_log.steps) {
+        for op in &mut s.tapes {
+            op.write = None;
+        }
+    }
+    assert_ne!(forged, blocks);
+    let root = commit_blocks(&blocks).root;
+    assert_eq!(commit_blocks(&forged).root, root);
+
+    let art = FoldBackend::with_options(opts(8), None)
+        .prove(&forged, root)
+        .unwrap();
+    // Against the root alone the forgery passes; against the blocks it is
+    // caught, in memory and streamed.
+    FoldBackend::verify(&art, &[], root).unwrap();
+    FoldBackend::verify(&art, &forged, root).unwrap();
+    let err = FoldBackend::verify(&art, &blocks, root).unwrap_err();
+    assert!(format!("{err:#}").contains("movement log"), "{err:#}");
+    let err = FoldBackend::verify_iter(&art, blocks.iter().cloned().map(Ok), root).unwrap_err();
+    assert!(format!("{err:#}").contains("movement log"), "{err:#}");
+
+    // Streaming artifacts read their leaves back from the proof stream.
+    let path = temp_path("are_sample_forged");
+    let backend = FoldBackend::with_options(opts(8), Some(path.clone()));
+    let mut st = backend.begin_stream_with_claims(root, &[]).unwrap();
+    for b in &forged {
+        backend.ingest_block(&mut st, b.clone()).unwrap();
+    }
+    let art = backend.finish_stream(st).unwrap();
+    FoldBackend::verify(&art, &forged, root).unwrap();
+    let err = FoldBackend::verify(&art, &blocks, root).unwrap_err();
+    assert!(format!("{err:#}").contains("movement log"), "{err:#}");
+    let _ = std::fs::remove_file(path);
+}
+
+#[test]
+fn inconsistent_movement_logs_are_caught() {
+    // Four-step blocks: 32 draws cover every transition.
+    let mut block = blocks(4)[1].clone();
+    let c = Commitment::new(sezkp_merkle::leaf_hash(&block), 1);
+    StepSamples::prove(&block, &c, 32)
+        .unwrap()
+        .verify(&c, 32)
+        .unwrap();
+
+    // A log that does not end at the declared exit head.
+    let w = block.windows[0];
+    let out = &mut block.head_out_offsets[0];
+    *out = if i64::from(*out) < w.right - w.left {
+        *out + 1
+    } else {
+        *out - 1
+    };
+    let c = Commitment::new(sezkp_merkle::leaf_hash(&block), 1);
+    let err = StepSamples::prove(&block, &c, 32)
+        .unwrap()
+        .verify(&c, 32)
+        .unwrap_err();
+    assert!(format!("{err:#}").contains("exit head"), "{err:#}");
+}
+
+#[test]
+fn sample_counts_must_match_the_parameters() {
+    let verify_bundle = verify::verify_bundle::<CryptoLeaf, CryptoFold, CryptoWrap>;
+    let blocks = blocks(37);
+
+    // Leaves without samples under a sampling declaration, and vice versa.
+    let mut bundle = run_pipeline::<CryptoLeaf, CryptoFold, CryptoWrap>(&blocks, &opts(0));
+    bundle.params.as_mut().unwrap().are_samples = 4;
+    assert!(verify_bundle(&bundle).is_err());
+
+    let mut bundle = run_pipeline::<CryptoLeaf, CryptoFold, CryptoWrap>(&blocks, &opts(4));
+    for declared in [0, 8] {
+        bundle.params.as_mut().unwrap().are_samples = declared;
+        assert!(verify_bundle(&bundle).is_err());
+    }
+    bundle.params.as_mut().unwrap().are_samples = 4;
+    verify_bundle(&bundle).unwrap();
+
+    // Expectations set a minimum.
+    let at_least = |r| ExpectedParams {
+        are_samples: Some(r),
+        ..ExpectedParams::default()
+    };
+    at_least(2).check(bundle.params.as_ref()).unwrap();
+    at_least(4).check(bundle.params.as_ref()).unwrap();
+    let err = at_least(8).check(bundle.params.as_ref()).unwrap_err();
+    assert!(err.to_string().contains("expected at least 8"), "{err}");
+}
+
+#[test]
+fn cached_granules_are_keyed_by_sample_count() {
+    let blocks = &blocks(25)[..8];
+    let dir = temp_path("are_sample");
+
+    let plain = stream(blocks, opts(0), Some(SubtreeCache::open(&dir, 4).unwrap()));
+    let cold = stream(blocks, opts(4), None);
+    let cached = stream(blocks, opts(4), Some(SubtreeCache::open(&dir, 4).unwrap()));
+    assert_ne!(plain, cached);
+    assert_eq!(cold, cached);
+    verify_bytes(&cached).unwrap();
+    let _ = std::fs::remove_dir_all(dir);
+}
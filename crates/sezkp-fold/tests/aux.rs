@@ -0,0 +1,63 @@
+//! Auxiliary commitments: they are part of each block's leaf hash, which is
+//! the public commitment of its leaf gadget, so fold proofs carry them up to
+//! the manifest root.
+
+#![allow(clippy::unwrap_used)]
+
+mod utils;
+
+use sezkp_core::auxiliary::aux_commitment;
+use sezkp_core::{BlockSummary, ProvingBackend};
+use sezkp_fold::driver::run_pipeline;
+use sezkp_fold::{CryptoFold, CryptoLeaf, CryptoWrap, FoldBackend};
+use sezkp_merkle::{commit_blocks, leaf_hash};
+use utils::{driver, opts};
+
+/// Four blocks, the second and third with adapter commitments.
+fn blocks() -> Vec<BlockSummary> {
+    let mut blocks = utils::blocks();
+    blocks[1].aux_commitments = vec![aux_commitment("pc-histogram", &[1, 2])];
+    blocks[2].aux_commitments = vec![
+        aux_commitment("pc-histogram", &[3]),
+        aux_commitment("syscalls", b"exit"),
+    ];
+    blocks
+}
+
+#[test]
+fn leaf_gadgets_commit_to_the_aux_commitments() {
+    let blocks = blocks();
+    let root = commit_blocks(&blocks).root;
+    let opts = opts(|o| o.manifest_root = root);
+    let bundle = run_pipeline::<CryptoLeaf, CryptoFold, CryptoWrap>(&blocks, &opts);
+    for ((c, _, _), b) in bundle.leaves.iter().zip(&blocks) {
+        assert_eq!(c.root, leaf_hash(b));
+    }
+
+    let art = FoldBackend::prove(&blocks, root).unwrap();
+    assert_eq!(art.manifest_root, root);
+    FoldBackend::verify(&art, &blocks, root).unwrap();
+
+    // The same trace without (or with other) commitments has another root.
+    let mut edited = blocks.clone();
+    edited[2].aux_commitments.pop();
+    let other = commit_blocks(&edited).root;
+    assert_ne!(other, root);
+    assert!(FoldBackend::verify(&art, &edited, other).is_err());
+}
+
+#[test]
+fn streaming_rejects_blocks_with_other_aux_commitments() {
+    let blocks = blocks();
+    let root = commit_blocks(&blocks).root;
+    let opts = opts(|o| o.manifest_root = root);
+
+    let mut edited = blocks;
+    edited[1].aux_commitments[0][0] ^= 1;
+    let mut bytes = Vec::new();
+    let mut drv = driver(&mut bytes, opts).with_expected_root(root);
+    for b in edited {
+        drv.push_block(b).unwrap();
+    }
+    assert!(drv.finish().is_err());
+}
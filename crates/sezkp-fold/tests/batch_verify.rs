@@ -0,0 +1,47 @@
+//! Batch verification: one result per artifact, each exactly what verifying
+//! it alone returns.
+
+#![allow(clippy::unwrap_used)]
+
+use sezkp_core::{BatchItem, BlockSummary, ProvingBackend};
+use sezkp_fold::FoldBackend;
+use sezkp_merkle::commit_blocks;
+use sezkp_trace::{generator::generate_trace, partition::partition_trace};
+
+fn blocks(t: u64) -> Vec<BlockSummary> {
+    partition_trace(&generate_trace(t, 2), 4)
+}
+
+#[test]
+fn batches_report_each_artifact() {
+    let (a, b) = (blocks(64), blocks(32));
+    let (ra, rb) = (commit_blocks(&a).root, commit_blocks(&b).root);
+    let pa = FoldBackend::prove(&a, ra).unwrap();
+    let pb = FoldBackend::prove(&b, rb).unwrap();
+    let mut bad = pa.clone();
+    let mid = bad.proof_bytes.len() / 2;
+    bad.proof_bytes[mid] ^= 1;
+
+    let item = |artifact, blocks, manifest_root| BatchItem {
+        artifact,
+        blocks,
+        manifest_root,
+    };
+    let items = [
+        item(&pa, &a[..], ra),
+        item(&bad, &a[..], ra),
+        item(&pb, &b[..], rb),
+        item(&pa, &a[..], rb),
+        item(&pa, &[][..], ra),
+    ];
+    let results = FoldBackend::verify_batch(&items);
+    let ok: Vec<bool> = results.iter().map(Result::is_ok).collect();
+    assert_eq!(ok, [true, false, true, false, true]);
+    for (it, got) in items.iter().zip(&results) {
+        let alone = FoldBackend::verify(it.artifact, it.blocks, it.manifest_root);
+        assert_eq!(
+            got.as_ref().map_err(|e| format!("{e:#}")),
+            alone.as_ref().map_err(|e| format!("{e:#}"))
+        );
+    }
+}
@@ -6,8 +6,13 @@
 
 #![deny(rust_2018_idioms)]
 
+use std::time::Instant;
+
+use sezkp_fold::api::{DriverOptions, FoldMode, LedgerStore};
 use sezkp_fold::are::InterfaceWitness;
 use sezkp_fold::are_replay::prove_replay;
+use sezkp_fold::{run_pipeline, CryptoFold, CryptoLeaf, CryptoWrap};
+use sezkp_trace::{generator::generate_trace, partition::partition_trace};
 
 fn are_bytes(pr: &sezkp_fold::are_replay::AreProof) -> Vec<u8> {
     bincode::serialize(pr).expect("serialize are proof")
@@ -38,3 +43,36 @@ fn micro_perf_smoke() {
     // Trivial assertion to use `acc` and avoid “unused” warnings.
     assert_eq!(acc.len(), 32);
 }
+
+/// Batch driver timings: Balanced with the RAM ledger, Balanced with the
+/// disk ledger, and MinRam recomputation at two cache sizes.
+///
+/// Ignored by default; run with
+/// `cargo test -p sezkp-fold --release --test benchmarks -- --ignored --nocapture`.
+#[test]
+#[ignore = "timing comparison; run manually"]
+fn ledger_store_vs_minram() {
+    let blocks = partition_trace(&generate_trace(1 << 16, 2), 16);
+    let t = blocks.len();
+
+    let configs = [
+        ("balanced/memory", FoldMode::Balanced, LedgerStore::Memory, 0),
+        ("balanced/disk", FoldMode::Balanced, LedgerStore::Disk, 0),
+        ("minram/cache=0", FoldMode::MinRam, LedgerStore::Memory, 0),
+        ("minram/cache=64", FoldMode::MinRam, LedgerStore::Memory, 64),
+    ];
+    let mut tops = Vec::new();
+    for (name, fold_mode, ledger, endpoint_cache) in configs {
+        let opts = DriverOptions {
+            fold_mode,
+            ledger,
+            endpoint_cache,
+            ..DriverOptions::default()
+        };
+        let t0 = Instant::now();
+        let bundle = run_pipeline::<CryptoLeaf, CryptoFold, CryptoWrap>(&blocks, &opts);
+        println!("{name:>16}: T={t} blocks in {:?}", t0.elapsed());
+        tops.push(bundle.folds.last().map(|f| f.0));
+    }
+    assert!(tops.windows(2).all(|w| w[0] == w[1]));
+}
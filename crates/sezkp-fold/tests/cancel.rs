@@ -0,0 +1,66 @@
+//! Cancelled streaming proofs: the stream ends with a truncated footer that
+//! verifiers reject.
+
+#![allow(clippy::unwrap_used)]
+
+mod utils;
+
+use sezkp_core::cancel::{self, Cancelled};
+use sezkp_core::prover::StreamingProver;
+use sezkp_fold::FoldAgg;
+use sezkp_merkle::commit_blocks;
+use utils::{blocks_of, driver, opts, temp_path, verify_bytes};
+
+#[test]
+fn truncated_streams_do_not_verify() {
+    let blocks = blocks_of(128, 16);
+    let opts = opts(|o| o.manifest_root = commit_blocks(&blocks).root);
+    let mut out = Vec::new();
+    let mut drv = driver(&mut out, opts);
+    for b in &blocks[..3] {
+        drv.push_block(b.clone()).unwrap();
+    }
+    assert_eq!(drv.finish_truncated().unwrap(), 3);
+
+    let err = verify_bytes(&out).unwrap_err();
+    assert!(
+        err.to_string().contains("cancelled after 3 blocks"),
+        "{err}"
+    );
+}
+
+// The only test here that touches the process-wide cancel flag.
+#[test]
+fn cancel_requests_stop_the_streaming_prover() {
+    let blocks = blocks_of(128, 16);
+    let root = commit_blocks(&blocks).root;
+    let path = temp_path("cancel");
+    std::env::set_var("SEZKP_PROOF_STREAM_PATH", &path);
+
+    // Ask for a stop while the third block is being read.
+    let iter = blocks.iter().cloned().enumerate().map(|(i, b)| {
+        if i == 2 {
+            cancel::request();
+        }
+        Ok(b)
+    });
+    let err = StreamingProver::<FoldAgg>::default()
+        .prove_stream(iter, root)
+        .unwrap_err();
+    cancel::reset();
+    assert_eq!(
+        err.downcast_ref::<Cancelled>(),
+        Some(&Cancelled {
+            blocks_done: Some(2)
+        })
+    );
+
+    // The partial stream ends cleanly and is rejected as incomplete.
+    let bytes = std::fs::read(&path).unwrap();
+    let err = verify_bytes(&bytes).unwrap_err();
+    assert!(
+        err.to_string().contains("cancelled after 2 blocks"),
+        "{err}"
+    );
+    let _ = std::fs::remove_file(path);
+}
@@ -0,0 +1,56 @@
+//! Stream census: record counts and footer of a CBOR-seq stream, whole,
+//! cancelled or cut short.
+
+#![allow(clippy::unwrap_used)]
+
+mod utils;
+
+use sezkp_fold::api::DriverOptions;
+use sezkp_fold::census::stream_census;
+use sezkp_merkle::commit_blocks;
+use utils::{blocks_of, driver};
+
+/// Stream the first `n` of 8 blocks; `cancel` ends it with a truncated footer.
+fn stream(n: usize, cancel: bool) -> (Vec<u8>, DriverOptions) {
+    let blocks = blocks_of(128, 16);
+    let opts = utils::opts(|o| {
+        o.manifest_root = commit_blocks(&blocks).root;
+        o.wrap_cadence = 2;
+    });
+    let mut out = Vec::new();
+    let mut drv = driver(&mut out, opts);
+    for b in &blocks[..n] {
+        drv.push_block(b.clone()).unwrap();
+    }
+    if cancel {
+        drv.finish_truncated().unwrap();
+    } else {
+        drv.finish().unwrap();
+    }
+    (out, opts)
+}
+
+#[test]
+fn census_counts_every_record() {
+    let (out, opts) = stream(8, false);
+    let c = stream_census(&out[..]).unwrap();
+    assert_eq!(c.header.manifest_root, opts.manifest_root);
+    assert_eq!((c.leaves, c.folds, c.wraps), (8, 7, 3));
+    let footer = c.footer.unwrap();
+    assert_eq!(footer.n_blocks, 8);
+    assert!(!footer.truncated);
+}
+
+#[test]
+fn census_reports_cancelled_and_cut_streams() {
+    let (out, _) = stream(3, true);
+    let c = stream_census(&out[..]).unwrap();
+    assert_eq!(c.leaves, 3);
+    assert!(c.footer.unwrap().truncated);
+
+    // Cut inside the footer: the items are still counted.
+    let (out, _) = stream(8, false);
+    let c = stream_census(&out[..out.len() - 4]).unwrap();
+    assert_eq!((c.leaves, c.folds, c.wraps), (8, 7, 3));
+    assert!(c.footer.is_none());
+}
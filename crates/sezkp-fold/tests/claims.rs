@@ -0,0 +1,81 @@
+//! Public claims: their digest is bound into every gadget proof alongside
+//! the manifest root, so claims cannot be edited or stripped after proving.
+
+#![allow(clippy::unwrap_used)]
+
+mod utils;
+
+use sezkp_core::claims::{claims_digest, Claim};
+use sezkp_core::{BlockSummary, ProvingBackend};
+use sezkp_fold::api::DriverOptions;
+use sezkp_fold::driver::run_pipeline;
+use sezkp_fold::{verify, CryptoFold, CryptoLeaf, CryptoWrap, FoldBackend};
+use sezkp_merkle::commit_blocks;
+use utils::{blocks, rewrite, stream, verify_bytes};
+
+fn claims() -> Vec<Claim> {
+    vec![Claim::new("output", "42"), Claim::new("program", "ab12")]
+}
+
+fn opts(blocks: &[BlockSummary]) -> DriverOptions {
+    utils::opts(|o| {
+        o.wrap_cadence = 2;
+        o.manifest_root = commit_blocks(blocks).root;
+        o.claims = claims_digest(&claims());
+    })
+}
+
+#[test]
+fn backend_binds_and_checks_claims() {
+    let blocks = blocks();
+    let root = commit_blocks(&blocks).root;
+
+    let art = FoldBackend::prove_with_claims(&blocks, root, &claims()).unwrap();
+    assert_eq!(art.claims, claims());
+    FoldBackend::verify(&art, &blocks, root).unwrap();
+
+    let mut edited = art.clone();
+    edited.claims[1].value = "cd34".into();
+    assert!(FoldBackend::verify(&edited, &blocks, root).is_err());
+    let mut dropped = art;
+    dropped.claims.clear();
+    assert!(FoldBackend::verify(&dropped, &blocks, root).is_err());
+
+    let mut plain = FoldBackend::prove(&blocks, root).unwrap();
+    FoldBackend::verify(&plain, &blocks, root).unwrap();
+    plain.claims = claims();
+    assert!(FoldBackend::verify(&plain, &blocks, root).is_err());
+}
+
+#[test]
+fn bundle_gadgets_are_bound_to_the_claims_digest() {
+    let blocks = blocks();
+    let mut bundle = run_pipeline::<CryptoLeaf, CryptoFold, CryptoWrap>(&blocks, &opts(&blocks));
+    assert_eq!(bundle.claims, claims_digest(&claims()));
+    verify::verify_bundle::<CryptoLeaf, CryptoFold, CryptoWrap>(&bundle).unwrap();
+
+    // Swapping or stripping the digest leaves the gadget proofs unbound.
+    bundle.claims = claims_digest(&[Claim::new("output", "43")]);
+    assert!(verify::verify_bundle::<CryptoLeaf, CryptoFold, CryptoWrap>(&bundle).is_err());
+    bundle.claims = None;
+    assert!(verify::verify_bundle::<CryptoLeaf, CryptoFold, CryptoWrap>(&bundle).is_err());
+}
+
+/// Rewrite the header and footer claims digests of a stream.
+fn rewrite_claims(bytes: &[u8], header: Option<[u8; 32]>, footer: Option<[u8; 32]>) -> Vec<u8> {
+    rewrite(bytes, |h, f| {
+        h.claims = header;
+        f.claims = footer;
+    })
+}
+
+#[test]
+fn stream_gadgets_are_bound_to_the_claims_digest() {
+    let blocks = blocks();
+    let bytes = stream(&blocks, opts(&blocks));
+    let digest = claims_digest(&claims());
+    assert_eq!(verify_bytes(&bytes).unwrap().claims, digest);
+
+    assert!(verify_bytes(&rewrite_claims(&bytes, None, None)).is_err());
+    assert!(verify_bytes(&rewrite_claims(&bytes, digest, None)).is_err());
+}
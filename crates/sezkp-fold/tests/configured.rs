@@ -0,0 +1,131 @@
+//! Configured fold backends: options travel with the backend value, so
+//! threads proving side by side with different settings do not share any
+//! environment.
+
+#![allow(clippy::unwrap_used)]
+
+use sezkp_core::{BlockSummary, ConfiguredBackend, StreamingProver, Window};
+use sezkp_fold::api::{DriverOptions, ExpectedParams, FoldMode};
+use sezkp_fold::FoldBackend;
+use sezkp_merkle::commit_blocks;
+use sezkp_trace::{generator::generate_trace, partition::partition_trace};
+
+fn blocks() -> Vec<BlockSummary> {
+    partition_trace(&generate_trace(64, 2), 8)
+}
+
+fn opts(fold_mode: FoldMode, wrap_cadence: u32) -> DriverOptions {
+    DriverOptions {
+        fold_mode,
+        wrap_cadence,
+        ..DriverOptions::default()
+    }
+}
+
+#[test]
+fn concurrent_provers_keep_their_own_options() {
+    let blocks = blocks();
+    let root = commit_blocks(&blocks).root;
+    let dir = std::env::temp_dir().join(format!("sezkp_configured_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let settings = [
+        opts(FoldMode::Balanced, 0),
+        opts(FoldMode::MinRam, 2),
+        opts(FoldMode::MinRam, 1),
+    ];
+    std::thread::scope(|s| {
+        for (i, o) in settings.into_iter().enumerate() {
+            let (blocks, dir) = (&blocks, &dir);
+            s.spawn(move || {
+                let path = dir.join(format!("{i}.cborseq"));
+                let backend = FoldBackend::with_options(o, Some(path));
+                let want = serde_json::to_value(o.params()).unwrap();
+
+                let batch = backend.prove(blocks, root).unwrap();
+                assert_eq!(batch.meta["params"], want);
+                backend.verify(&batch, blocks, root).unwrap();
+
+                let prover = StreamingProver::<FoldBackend>::default();
+                let streamed = prover
+                    .prove_stream_with(&backend, blocks.iter().cloned().map(Ok), root)
+                    .unwrap();
+                assert_eq!(streamed.meta["params"], want);
+                backend.verify(&streamed, blocks, root).unwrap();
+            });
+        }
+    });
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn expectations_come_from_the_instance() {
+    let blocks = blocks();
+    let root = commit_blocks(&blocks).root;
+    let backend = FoldBackend::with_options(opts(FoldMode::MinRam, 2), None);
+    let art = backend.prove(&blocks, root).unwrap();
+
+    let expect = |fold_mode, wrap_cadence| ExpectedParams {
+        fold_mode: Some(fold_mode),
+        wrap_cadence: Some(wrap_cadence),
+        ..ExpectedParams::default()
+    };
+    backend
+        .clone()
+        .expecting(expect(FoldMode::MinRam, 2))
+        .verify(&art, &blocks, root)
+        .unwrap();
+    let err = backend
+        .expecting(expect(FoldMode::Balanced, 2))
+        .verify(&art, &blocks, root)
+        .unwrap_err();
+    assert!(format!("{err:#}").contains("fold parameters"), "{err:#}");
+}
+
+#[test]
+fn streaming_needs_a_stream_path() {
+    let blocks = blocks();
+    let root = commit_blocks(&blocks).root;
+    let backend = FoldBackend::with_options(DriverOptions::default(), None);
+    let err = StreamingProver::<FoldBackend>::default()
+        .prove_stream_with(&backend, blocks.into_iter().map(Ok), root)
+        .unwrap_err();
+    assert!(err.to_string().contains("stream path"), "{err:#}");
+}
+
+#[test]
+fn rewindowed_proofs_declare_it_and_seams_are_checked() {
+    // Move block 1's first window clear of the heads block 0 exits with.
+    let mut blocks = blocks();
+    let w = blocks[1].windows[0];
+    blocks[1].windows[0] = Window {
+        left: w.left + 1000,
+        right: w.right + 1000,
+    };
+    let root = commit_blocks(&blocks).root;
+
+    // A proof made without the policy stands on the root alone, but a
+    // verifier holding the blocks sees the jump.
+    let strict = FoldBackend::with_options(opts(FoldMode::MinRam, 2), None);
+    let art = strict.prove(&blocks, root).unwrap();
+    strict.verify(&art, &[], root).unwrap();
+    let err = strict.verify(&art, &blocks, root).unwrap_err();
+    assert!(format!("{err:#}").contains("seam before block 1"), "{err:#}");
+
+    // With it, the proof says so and only a permissive verifier accepts it.
+    let o = DriverOptions {
+        allow_rewindow: true,
+        ..opts(FoldMode::MinRam, 2)
+    };
+    let art = FoldBackend::with_options(o, None).prove(&blocks, root).unwrap();
+    assert_eq!(art.meta["params"]["allow_rewindow"], true);
+    let err = strict.verify(&art, &blocks, root).unwrap_err();
+    assert!(format!("{err:#}").contains("re-windowed"), "{err:#}");
+    strict
+        .expecting(ExpectedParams {
+            allow_rewindow: true,
+            ..ExpectedParams::default()
+        })
+        .verify(&art, &blocks, root)
+        .unwrap();
+}
@@ -0,0 +1,55 @@
+//! Edge-case inputs for the folding line: zero blocks and a single block.
+//!
+//! - Zero blocks: both driver modes and the streaming driver produce an empty
+//!   proof whose root is `EMPTY_ROOT`; verifiers accept it only for that root.
+//! - One block: the leaf is the root, in the bundle and in the stream footer.
+
+mod utils;
+
+use sezkp_core::{BlockSummary, ProvingBackend, EMPTY_ROOT};
+use sezkp_fold::api::{commit_pi, Commitment, DriverOptions, FoldMode, Leaf};
+use sezkp_fold::driver::{run_pipeline, StreamFooter};
+use sezkp_fold::{verify, CryptoFold, CryptoLeaf, CryptoWrap, FoldBackend};
+use utils::{blocks_of, opts, stream, verify_bytes};
+
+/// Stream `blocks` through the sink driver and verify the resulting bytes.
+fn stream_and_verify(blocks: &[BlockSummary]) -> StreamFooter {
+    verify_bytes(&stream(blocks, DriverOptions::default())).unwrap()
+}
+
+#[test]
+fn empty_input_proves_the_empty_root() {
+    for mode in [FoldMode::Balanced, FoldMode::MinRam] {
+        let opts = opts(|o| o.fold_mode = mode);
+        let bundle = run_pipeline::<CryptoLeaf, CryptoFold, CryptoWrap>(&[], &opts);
+        assert_eq!(bundle.n_blocks, 0);
+        verify::verify_bundle::<CryptoLeaf, CryptoFold, CryptoWrap>(&bundle).unwrap();
+    }
+
+    let art = FoldBackend::prove(&[], EMPTY_ROOT).unwrap();
+    assert_eq!(art.manifest_root, EMPTY_ROOT);
+    FoldBackend::verify(&art, &[], EMPTY_ROOT).unwrap();
+    assert!(FoldBackend::verify(&art, &[], [1u8; 32]).is_err());
+
+    let footer = stream_and_verify(&[]);
+    assert_eq!(footer.n_blocks, 0);
+    assert_eq!(footer.root_c, Commitment::new(EMPTY_ROOT, 0));
+}
+
+#[test]
+fn single_block_root_is_its_leaf() {
+    let blocks = blocks_of(5, 8);
+    assert_eq!(blocks.len(), 1);
+    let (pi, c, _) = CryptoLeaf::prove_leaf(&EMPTY_ROOT, &blocks[0]);
+    assert_eq!(c.root, sezkp_merkle::leaf_hash(&blocks[0]));
+
+    let art = FoldBackend::prove(&blocks, c.root).unwrap();
+    assert_eq!(art.manifest_root, c.root);
+    FoldBackend::verify(&art, &blocks, c.root).unwrap();
+    assert!(FoldBackend::verify(&art, &blocks, EMPTY_ROOT).is_err());
+
+    let footer = stream_and_verify(&blocks);
+    assert_eq!(footer.n_blocks, 1);
+    assert_eq!(footer.root_c, c);
+    assert_eq!(footer.root_pi_cmt, commit_pi(&pi));
+}
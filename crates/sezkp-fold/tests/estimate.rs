@@ -0,0 +1,56 @@
+//! Size estimates: `estimate_proof_size` predicts batch `proof_bytes` and
+//! stream sizes of real fold proofs to within a percent, with exact counts.
+
+#![allow(clippy::unwrap_used)]
+
+mod utils;
+
+use sezkp_core::{BlockSummary, ProvingBackend};
+use sezkp_fold::api::{DriverOptions, FoldMode};
+use sezkp_fold::driver::run_pipeline;
+use sezkp_fold::{estimate_proof_size, CryptoFold, CryptoLeaf, CryptoWrap, FoldBackend};
+use utils::{blocks_of, opts, stream};
+
+fn blocks() -> Vec<BlockSummary> {
+    blocks_of(200, 4)
+}
+
+fn stream_len(blocks: &[BlockSummary], opts: DriverOptions) -> u64 {
+    stream(blocks, opts).len() as u64
+}
+
+fn assert_close(estimate: u64, actual: u64) {
+    let diff = estimate.abs_diff(actual);
+    assert!(
+        diff * 100 <= actual,
+        "estimate {estimate} vs actual {actual} (off by {diff})"
+    );
+}
+
+#[test]
+fn batch_estimate_is_within_a_percent() {
+    let blocks = blocks();
+    let n = blocks.len() as u64;
+    let art = FoldBackend::prove(&blocks, [0u8; 32]).unwrap();
+    let est = estimate_proof_size(n, 4, 2, &DriverOptions::default().params()).unwrap();
+    assert!(!est.exact);
+    assert_close(est.proof_bytes, art.proof_bytes.len() as u64);
+}
+
+#[test]
+fn stream_estimate_counts_wraps_by_cadence() {
+    let blocks = blocks();
+    let n = blocks.len() as u64;
+    let opts = opts(|o| {
+        o.fold_mode = FoldMode::MinRam;
+        o.wrap_cadence = 3;
+    });
+    let est = estimate_proof_size(n, 4, 2, &opts.params()).unwrap();
+    assert_close(est.stream_bytes.unwrap(), stream_len(&blocks, opts));
+
+    let bundle = run_pipeline::<CryptoLeaf, CryptoFold, CryptoWrap>(&blocks, &opts);
+    let count = |name: &str| est.items.iter().find(|i| i.name == name).unwrap().count;
+    assert_eq!(count("leaves"), bundle.leaves.len() as u64);
+    assert_eq!(count("folds"), bundle.folds.len() as u64);
+    assert_eq!(count("wraps"), bundle.wraps.len() as u64);
+}
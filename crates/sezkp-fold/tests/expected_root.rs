@@ -0,0 +1,85 @@
+//! Expected-root checks in the streaming driver: the blocks are hashed into a
+//! frontier as they arrive, and `finish` refuses a stream over blocks the
+//! manifest does not commit to.
+
+#![allow(clippy::unwrap_used)]
+
+mod utils;
+
+use sezkp_core::{BlockSummary, EMPTY_ROOT};
+use sezkp_fold::api::DriverOptions;
+use sezkp_fold::cache::SubtreeCache;
+use sezkp_merkle::commit_blocks;
+use utils::{blocks_of, driver, temp_path, verify_bytes};
+
+/// Stream `blocks`, optionally checking them against `expected`.
+fn stream(
+    blocks: &[BlockSummary],
+    expected: Option<[u8; 32]>,
+    cache: Option<SubtreeCache>,
+) -> (anyhow::Result<()>, Vec<u8>) {
+    let mut out = Vec::new();
+    let mut drv = driver(&mut out, DriverOptions::default());
+    if let Some(root) = expected {
+        drv = drv.with_expected_root(root);
+    }
+    if let Some(cache) = cache {
+        drv = drv.with_cache(cache);
+    }
+    let res = blocks
+        .iter()
+        .try_for_each(|b| drv.push_block(b.clone()))
+        .and_then(|()| drv.finish().map(drop));
+    (res, out)
+}
+
+#[test]
+fn matching_roots_leave_the_stream_unchanged() {
+    // Six blocks: the manifest promotes an odd node, unlike a full tree.
+    let blocks = blocks_of(96, 16);
+    assert_eq!(blocks.len(), 6);
+    let root = commit_blocks(&blocks).root;
+
+    let (res, plain) = stream(&blocks, None, None);
+    res.unwrap();
+    let (res, checked) = stream(&blocks, Some(root), None);
+    res.unwrap();
+    assert_eq!(plain, checked);
+    verify_bytes(&checked).unwrap();
+
+    // Cached granules see every block too.
+    let dir = temp_path("expected_root");
+    let (res, cached) = stream(
+        &blocks,
+        Some(root),
+        Some(SubtreeCache::open(&dir, 4).unwrap()),
+    );
+    res.unwrap();
+    assert_eq!(plain, cached);
+    let _ = std::fs::remove_dir_all(dir);
+
+    let (res, _) = stream(&[], Some(EMPTY_ROOT), None);
+    res.unwrap();
+}
+
+#[test]
+fn mismatched_roots_fail_before_the_footer() {
+    let blocks = blocks_of(96, 16);
+    let root = commit_blocks(&blocks).root;
+
+    let mut edited = blocks.clone();
+    edited[3].ctrl_out ^= 1;
+    let (res, out) = stream(&edited, Some(root), None);
+    let err = res.unwrap_err();
+    assert!(err.to_string().contains("expected manifest root"), "{err}");
+    // Without a footer the partial stream does not verify.
+    assert!(verify_bytes(&out).is_err());
+
+    // Missing and reordered blocks are caught the same way.
+    let (res, _) = stream(&blocks[..5], Some(root), None);
+    assert!(res.is_err());
+    let mut swapped = blocks;
+    swapped.swap(1, 2);
+    let (res, _) = stream(&swapped, Some(root), None);
+    assert!(res.is_err());
+}
@@ -0,0 +1,178 @@
+//! Final wrap: a pluggable backend attests the final root and a digest of the
+//! whole bundle or stream, and verifiers hold it to exactly that proof.
+
+#![allow(clippy::unwrap_used)]
+
+mod utils;
+
+use ciborium::value::Value;
+use sezkp_core::BlockSummary;
+use sezkp_fold::api::{Commitment, DriverOptions, PiCommitment, WrapBackend};
+use sezkp_fold::are::Pi;
+use sezkp_fold::driver::{run_pipeline, FoldProofBundle, StreamFooter};
+use sezkp_fold::final_wrap::{register_wrap_backend, wrap_backend, MacWrapBackend};
+use sezkp_fold::{verify, CryptoFold, CryptoLeaf, CryptoWrap};
+use utils::{blocks_of, rewrite_values, stream, values, verify_values};
+
+fn blocks(steps: u64) -> Vec<BlockSummary> {
+    blocks_of(steps, 4)
+}
+
+fn opts(backend: &'static dyn WrapBackend) -> DriverOptions {
+    utils::opts(|o| {
+        o.wrap_cadence = 2;
+        o.final_wrap = Some(backend);
+    })
+}
+
+fn footer_of(values: &[Value]) -> StreamFooter {
+    values[values.len() - 1].deserialized().unwrap()
+}
+
+/// Replace the stream's footer with `f(footer)`.
+fn edit_footer(values: &mut [Value], f: impl FnOnce(&mut StreamFooter)) {
+    rewrite_values(values, |_, footer| f(footer));
+}
+
+/// Test backend: the digest XORed with the root.
+#[derive(Debug)]
+struct XorBackend;
+
+impl XorBackend {
+    fn tag(c: &Commitment, digest: &[u8; 32]) -> Vec<u8> {
+        c.root.iter().zip(digest).map(|(a, b)| a ^ b).collect()
+    }
+}
+
+impl WrapBackend for XorBackend {
+    fn id(&self) -> &'static str {
+        "test-xor"
+    }
+
+    fn prove(&self, root: (&Commitment, &Pi), digest: &[u8; 32]) -> Vec<u8> {
+        Self::tag(root.0, digest)
+    }
+
+    fn verify(&self, root: (&Commitment, &PiCommitment), digest: &[u8; 32], proof: &[u8]) -> bool {
+        Self::tag(root.0, digest) == proof
+    }
+}
+
+/// Another backend claiming the same id.
+#[derive(Debug)]
+struct Impostor;
+
+impl WrapBackend for Impostor {
+    fn id(&self) -> &'static str {
+        "test-xor"
+    }
+
+    fn prove(&self, _root: (&Commitment, &Pi), _digest: &[u8; 32]) -> Vec<u8> {
+        Vec::new()
+    }
+
+    fn verify(&self, _: (&Commitment, &PiCommitment), _: &[u8; 32], _: &[u8]) -> bool {
+        true
+    }
+}
+
+#[test]
+fn bundle_final_wrap_is_bound_to_its_bundle() {
+    let verify_bundle = verify::verify_bundle::<CryptoLeaf, CryptoFold, CryptoWrap>;
+    let bundle =
+        run_pipeline::<CryptoLeaf, CryptoFold, CryptoWrap>(&blocks(64), &opts(&MacWrapBackend));
+    let fw = bundle.final_wrap.clone().unwrap();
+    assert_eq!(fw.backend, MacWrapBackend::ID);
+    verify_bundle(&bundle).unwrap();
+
+    let mut forged = bundle.clone();
+    forged.final_wrap.as_mut().unwrap().proof[0] ^= 1;
+    let err = verify_bundle(&forged).unwrap_err();
+    assert!(
+        err.to_string().contains("final wrap (mac-v1) failed"),
+        "{err}"
+    );
+
+    // A final wrap made for another proof does not carry over.
+    let mut other =
+        run_pipeline::<CryptoLeaf, CryptoFold, CryptoWrap>(&blocks(128), &opts(&MacWrapBackend));
+    other.final_wrap = Some(fw);
+    let err = verify_bundle(&other).unwrap_err();
+    assert!(err.to_string().contains("digest mismatch"), "{err}");
+
+    // Without a final wrap requested the bundle carries none.
+    let plain =
+        run_pipeline::<CryptoLeaf, CryptoFold, CryptoWrap>(&blocks(64), &DriverOptions::default());
+    assert!(plain.final_wrap.is_none());
+}
+
+#[test]
+fn stream_final_wrap_sits_in_the_footer() {
+    let blocks = blocks(64);
+    let wrapped = values(&stream(&blocks, opts(&MacWrapBackend)));
+    let footer = verify_values(&wrapped).unwrap();
+    assert_eq!(footer.final_wrap.unwrap().backend, MacWrapBackend::ID);
+
+    // Only the footer differs from a stream without a final wrap.
+    let plain_opts = DriverOptions {
+        final_wrap: None,
+        ..opts(&MacWrapBackend)
+    };
+    let plain = values(&stream(&blocks, plain_opts));
+    assert_eq!(plain[..plain.len() - 1], wrapped[..wrapped.len() - 1]);
+    assert!(verify_values(&plain).unwrap().final_wrap.is_none());
+
+    let mut forged = wrapped.clone();
+    edit_footer(&mut forged, |f| {
+        f.final_wrap.as_mut().unwrap().proof[0] ^= 1
+    });
+    assert!(verify_values(&forged).is_err());
+
+    // A final wrap made for another stream does not carry over.
+    let mut other = values(&stream(&blocks[..blocks.len() / 2], opts(&MacWrapBackend)));
+    let fw = footer_of(&wrapped).final_wrap;
+    edit_footer(&mut other, |f| f.final_wrap = fw);
+    let err = verify_values(&other).unwrap_err();
+    assert!(err.to_string().contains("digest mismatch"), "{err}");
+}
+
+#[test]
+fn external_backends_plug_in_by_id() {
+    register_wrap_backend(&XorBackend).unwrap();
+    assert!(register_wrap_backend(&XorBackend).is_err());
+    assert!(register_wrap_backend(&Impostor).is_err());
+    assert!(register_wrap_backend(&MacWrapBackend).is_err());
+    assert_eq!(wrap_backend("test-xor").unwrap().id(), "test-xor");
+
+    let blocks = blocks(64);
+    let bundle = run_pipeline::<CryptoLeaf, CryptoFold, CryptoWrap>(&blocks, &opts(&XorBackend));
+    verify::verify_bundle::<CryptoLeaf, CryptoFold, CryptoWrap>(&bundle).unwrap();
+    let mut wrapped = values(&stream(&blocks, opts(&XorBackend)));
+    assert_eq!(
+        verify_values(&wrapped).unwrap().final_wrap.unwrap().backend,
+        "test-xor"
+    );
+
+    // Verifiers only accept backends they know.
+    edit_footer(&mut wrapped, |f| {
+        f.final_wrap.as_mut().unwrap().backend = "snark-unknown".to_owned();
+    });
+    let err = verify_values(&wrapped).unwrap_err();
+    assert!(
+        err.to_string().contains("unknown final wrap backend"),
+        "{err}"
+    );
+}
+
+#[test]
+fn bundle_digest_does_not_depend_on_the_codec() {
+    let bundle =
+        run_pipeline::<CryptoLeaf, CryptoFold, CryptoWrap>(&blocks(64), &opts(&MacWrapBackend));
+    let via_json: FoldProofBundle<_, _, _> =
+        serde_json::from_slice(&serde_json::to_vec(&bundle).unwrap()).unwrap();
+    let via_cbor: FoldProofBundle<_, _, _> =
+        serde_cbor::from_slice(&serde_cbor::to_vec(&bundle).unwrap()).unwrap();
+    for b in [via_json, via_cbor] {
+        verify::verify_bundle::<CryptoLeaf, CryptoFold, CryptoWrap>(&b).unwrap();
+    }
+}
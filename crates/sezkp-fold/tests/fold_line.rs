@@ -8,7 +8,8 @@
 
 #![allow(dead_code)]
 
-use sezkp_fold::api::{DriverOptions, FoldMode};
+use sezkp_core::DigestMode;
+use sezkp_fold::api::{DriverOptions, FoldMode, LedgerStore, SyncPolicy};
 use sezkp_fold::{driver::run_pipeline, verify};
 use sezkp_trace::{generator::generate_trace, partition::partition_trace};
 
@@ -44,6 +45,17 @@ fn fold_line_balanced_vs_minram_top_matches_and_verifies() {
             fold_mode: FoldMode::Balanced,
             wrap_cadence: 0,
             endpoint_cache: 0,
+            ledger: LedgerStore::Memory,
+            are_samples: 0,
+            iface_digest: DigestMode::Full,
+            final_wrap: None,
+            manifest_root: [0u8; 32],
+            claims: None,
+            spot_checks: 0,
+            late_bound: false,
+            sync: SyncPolicy::default(),
+            reveal_root_pi: false,
+            allow_rewindow: false,
         };
         let bundle_bal = run_pipeline::<
             sezkp_fold::leaf::CryptoLeaf,
@@ -64,6 +76,17 @@ fn fold_line_balanced_vs_minram_top_matches_and_verifies() {
                 fold_mode: FoldMode::MinRam,
                 wrap_cadence: 0,
                 endpoint_cache: cap,
+                ledger: LedgerStore::Memory,
+                are_samples: 0,
+                iface_digest: DigestMode::Full,
+                final_wrap: None,
+                manifest_root: [0u8; 32],
+                claims: None,
+                spot_checks: 0,
+                late_bound: false,
+                sync: SyncPolicy::default(),
+                reveal_root_pi: false,
+                allow_rewindow: false,
             };
             let bundle_min = run_pipeline::<
                 sezkp_fold::leaf::CryptoLeaf,
@@ -86,3 +109,34 @@ fn fold_line_balanced_vs_minram_top_matches_and_verifies() {
         }
     }
 }
+
+#[test]
+fn fold_line_disk_ledger_matches_memory_ledger() {
+    // Enough blocks to cycle pages through the disk ledger's resident set.
+    let blocks = partition_trace(&generate_trace(1200, 2), 1);
+
+    let bundle_bytes = |ledger| {
+        let opts = DriverOptions {
+            wrap_cadence: 3,
+            ledger,
+            ..DriverOptions::default()
+        };
+        let bundle = run_pipeline::<
+            sezkp_fold::leaf::CryptoLeaf,
+            sezkp_fold::fold::CryptoFold,
+            sezkp_fold::fold::CryptoWrap,
+        >(&blocks, &opts);
+        verify::verify_bundle::<
+            sezkp_fold::leaf::CryptoLeaf,
+            sezkp_fold::fold::CryptoFold,
+            sezkp_fold::fold::CryptoWrap,
+        >(&bundle)
+        .expect("bundle verify");
+        bincode::serialize(&bundle).expect("serialize bundle")
+    };
+
+    assert_eq!(
+        bundle_bytes(LedgerStore::Memory),
+        bundle_bytes(LedgerStore::Disk)
+    );
+}
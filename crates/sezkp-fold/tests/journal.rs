@@ -0,0 +1,260 @@
+//! Journaled proof streams: a run that stops between sync points (crash,
+//! torn write or cancel) resumes from its journal into the same stream an
+//! uninterrupted run writes.
+
+#![allow(clippy::unwrap_used)]
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+mod utils;
+
+use sezkp_core::prover::StreamingProver;
+use sezkp_core::BlockSummary;
+use sezkp_fold::api::{DriverOptions, Durability, SyncPolicy};
+use sezkp_fold::driver::{CborSeqSink, StreamDriverSink};
+use sezkp_fold::final_wrap::MacWrapBackend;
+use sezkp_fold::journal::{self, Checkpoint};
+use sezkp_fold::{CryptoFold, CryptoLeaf, CryptoWrap, FoldAgg};
+use sezkp_merkle::commit_blocks;
+use utils::{blocks_of, stream, temp_path, verify_bytes};
+
+type Driver = StreamDriverSink<CryptoLeaf, CryptoFold, CryptoWrap, CborSeqSink<BufWriter<File>>>;
+
+fn temp(name: &str) -> PathBuf {
+    let p = temp_path(&format!("journal_{name}"));
+    let _ = std::fs::remove_file(journal::wal_path(&p));
+    p
+}
+
+fn blocks() -> Vec<BlockSummary> {
+    blocks_of(176, 16)
+}
+
+fn opts(blocks: &[BlockSummary], every_items: u32) -> DriverOptions {
+    DriverOptions {
+        wrap_cadence: 2,
+        manifest_root: commit_blocks(blocks).root,
+        sync: SyncPolicy {
+            durability: Durability::Journal,
+            every_items,
+            every_secs: 0,
+        },
+        ..DriverOptions::default()
+    }
+}
+
+fn start(path: &Path, opts: DriverOptions) -> Driver {
+    let sink = CborSeqSink::durable(
+        BufWriter::new(File::create(path).unwrap()),
+        Some(journal::wal_path(path)),
+    );
+    StreamDriverSink::new(sink, opts).unwrap()
+}
+
+fn resume(path: &Path, opts: DriverOptions, cp: Checkpoint) -> anyhow::Result<Driver> {
+    let file = OpenOptions::new().append(true).open(path).unwrap();
+    let sink = CborSeqSink::durable(BufWriter::new(file), Some(journal::wal_path(path)))
+        .resumed(cp.offset);
+    StreamDriverSink::resume(sink, opts, cp)
+}
+
+/// The stream an uninterrupted run writes.
+fn reference(blocks: &[BlockSummary]) -> Vec<u8> {
+    reference_with(blocks, opts(blocks, 0))
+}
+
+/// The stream an uninterrupted, unjournaled run with `opts` writes.
+fn reference_with(blocks: &[BlockSummary], opts: DriverOptions) -> Vec<u8> {
+    let opts = DriverOptions {
+        sync: SyncPolicy::default(),
+        ..opts
+    };
+    stream(blocks, opts)
+}
+
+#[test]
+fn crashed_streams_resume_into_the_uninterrupted_stream() {
+    let blocks = blocks();
+    let expected = reference(&blocks);
+    for every_items in [0, 3] {
+        let path = temp(&format!("crash{every_items}"));
+        let opts = opts(&blocks, every_items);
+
+        // Crash after seven blocks, mid-way through writing an item.
+        let mut drv = start(&path, opts);
+        for b in &blocks[..7] {
+            drv.push_block(b.clone()).unwrap();
+        }
+        drop(drv);
+        OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(&[0xa5, 0x01])
+            .unwrap();
+
+        let cp = journal::recover(&path).unwrap().unwrap();
+        assert!(cp.next_idx > 0 && cp.next_idx <= 7);
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), cp.offset);
+
+        let mut drv = resume(&path, opts, cp).unwrap();
+        for b in &blocks {
+            drv.push_block(b.clone()).unwrap();
+        }
+        drv.finish().unwrap();
+
+        assert_eq!(std::fs::read(&path).unwrap(), expected);
+        assert!(!journal::wal_path(&path).exists());
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+#[test]
+fn cancelled_streams_resume_past_their_truncated_footer() {
+    let blocks = blocks();
+    let path = temp("cancel");
+    let opts = opts(&blocks, 0);
+    let mut drv = start(&path, opts);
+    for b in &blocks[..4] {
+        drv.push_block(b.clone()).unwrap();
+    }
+    assert_eq!(drv.finish_truncated().unwrap(), 4);
+
+    let cp = journal::recover(&path).unwrap().unwrap();
+    assert_eq!(cp.next_idx, 4);
+    let mut drv = resume(&path, opts, cp).unwrap();
+    for b in &blocks {
+        drv.push_block(b.clone()).unwrap();
+    }
+    drv.finish().unwrap();
+    assert_eq!(std::fs::read(&path).unwrap(), reference(&blocks));
+    let _ = std::fs::remove_file(path);
+}
+
+#[test]
+fn resuming_checks_options_and_replayed_blocks() {
+    let blocks = blocks();
+    let path = temp("mismatch");
+    let opts = opts(&blocks, 0);
+    let mut drv = start(&path, opts);
+    for b in &blocks[..5] {
+        drv.push_block(b.clone()).unwrap();
+    }
+    drop(drv);
+    let cp = journal::recover(&path).unwrap().unwrap();
+
+    let other = DriverOptions {
+        wrap_cadence: 3,
+        ..opts
+    };
+    let err = resume(&path, other, cp.clone()).err().unwrap();
+    assert!(err.to_string().contains("other parameters"), "{err}");
+
+    let mut drv = resume(&path, opts, cp).unwrap();
+    let err = drv.push_block(blocks[1].clone()).unwrap_err();
+    assert!(err.to_string().contains("block 0 differs"), "{err}");
+    let _ = std::fs::remove_file(path);
+}
+
+#[test]
+fn buffered_and_fsync_streams_write_no_journal() {
+    let blocks = blocks();
+    for durability in [Durability::Buffered, Durability::Fsync] {
+        let path = temp(&format!("{durability:?}"));
+        let mut drv = start(
+            &path,
+            DriverOptions {
+                sync: SyncPolicy {
+                    durability,
+                    every_items: 1,
+                    every_secs: 0,
+                },
+                ..opts(&blocks, 0)
+            },
+        );
+        for b in &blocks[..5] {
+            drv.push_block(b.clone()).unwrap();
+        }
+        assert!(!journal::wal_path(&path).exists());
+        drop(drv);
+        assert!(journal::recover(&path).unwrap().is_none());
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+#[test]
+fn final_wrap_streams_resume_from_their_prefix() {
+    let blocks = blocks();
+    let path = temp("final_wrap");
+    let opts = DriverOptions {
+        final_wrap: Some(&MacWrapBackend),
+        ..opts(&blocks, 0)
+    };
+    let mut drv = start(&path, opts);
+    for b in &blocks[..6] {
+        drv.push_block(b.clone()).unwrap();
+    }
+    drop(drv);
+    let cp = journal::recover(&path).unwrap().unwrap();
+
+    // The digest is not journaled, so only the prefix can restore it.
+    let err = resume(&path, opts, cp.clone()).err().unwrap();
+    assert!(err.to_string().contains("resume_replaying"), "{err}");
+
+    let file = OpenOptions::new().append(true).open(&path).unwrap();
+    let sink = CborSeqSink::durable(BufWriter::new(file), Some(journal::wal_path(&path)))
+        .resumed(cp.offset);
+    let prefix = File::open(&path).unwrap();
+    let mut drv = Driver::resume_replaying(sink, opts, cp, prefix).unwrap();
+    for b in &blocks {
+        drv.push_block(b.clone()).unwrap();
+    }
+    drv.finish().unwrap();
+
+    let bytes = std::fs::read(&path).unwrap();
+    assert_eq!(bytes, reference_with(&blocks, opts));
+    verify_bytes(&bytes).unwrap();
+    let _ = std::fs::remove_file(path);
+}
+
+// The only test here that touches the process environment.
+#[test]
+fn streaming_prover_resumes_from_the_environment() {
+    let blocks = blocks();
+    let root = commit_blocks(&blocks).root;
+    let path = temp("env");
+    std::env::set_var("SEZKP_PROOF_STREAM_PATH", &path);
+    std::env::set_var("SEZKP_FOLD_DURABILITY", "journal");
+    std::env::set_var("SEZKP_WRAP_CADENCE", "2");
+
+    // The block source fails part-way through, leaving a partial stream.
+    let failing = blocks.iter().cloned().enumerate().map(|(i, b)| {
+        if i == 6 {
+            anyhow::bail!("source went away");
+        }
+        Ok(b)
+    });
+    assert!(StreamingProver::<FoldAgg>::default()
+        .prove_stream(failing, root)
+        .is_err());
+    assert!(journal::wal_path(&path).exists());
+
+    std::env::set_var("SEZKP_FOLD_RESUME", "1");
+    StreamingProver::<FoldAgg>::default()
+        .prove_stream(blocks.iter().cloned().map(Ok), root)
+        .unwrap();
+    for var in [
+        "SEZKP_FOLD_RESUME",
+        "SEZKP_FOLD_DURABILITY",
+        "SEZKP_WRAP_CADENCE",
+    ] {
+        std::env::remove_var(var);
+    }
+
+    assert_eq!(std::fs::read(&path).unwrap(), reference(&blocks));
+    let bytes = std::fs::read(&path).unwrap();
+    verify_bytes(&bytes).unwrap();
+    let _ = std::fs::remove_file(path);
+}
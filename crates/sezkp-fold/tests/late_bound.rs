@@ -0,0 +1,88 @@
+//! Late-bound streams: started before the manifest root is known, they bind
+//! the root their blocks hash to in the footer.
+
+#![allow(clippy::unwrap_used)]
+
+mod utils;
+
+use sezkp_core::{BlockSummary, ProvingBackend};
+use sezkp_fold::api::DriverOptions;
+use sezkp_fold::driver::{CborSeqSink, StreamDriverSink};
+use sezkp_fold::{CryptoFold, CryptoLeaf, CryptoWrap, FoldBackend};
+use sezkp_merkle::commit_blocks;
+use utils::{blocks_of, opts, rewrite, stream, temp_path, verify_bytes};
+
+/// Six blocks: the manifest promotes an odd node.
+fn blocks() -> Vec<BlockSummary> {
+    blocks_of(96, 16)
+}
+
+fn late() -> DriverOptions {
+    opts(|o| {
+        o.late_bound = true;
+        o.wrap_cadence = 2;
+    })
+}
+
+#[test]
+fn footer_binds_the_root_the_blocks_hash_to() {
+    let blocks = blocks();
+    let root = commit_blocks(&blocks).root;
+    let bytes = stream(&blocks, late());
+
+    assert_eq!(verify_bytes(&bytes).unwrap().manifest_root, root);
+
+    // The footer cannot name another root, nor can the flag be dropped or
+    // the header claim a root after the fact.
+    assert!(verify_bytes(&rewrite(&bytes, |_, f| f.manifest_root = [1u8; 32])).is_err());
+    assert!(verify_bytes(&rewrite(&bytes, |h, _| h.late_bound = false)).is_err());
+    assert!(verify_bytes(&rewrite(&bytes, |h, f| {
+        h.late_bound = false;
+        h.manifest_root = f.manifest_root;
+    }))
+    .is_err());
+    assert!(verify_bytes(&rewrite(&bytes, |h, _| h.manifest_root = root)).is_err());
+
+    let empty = stream(&[], late());
+    assert_eq!(
+        verify_bytes(&empty).unwrap().manifest_root,
+        sezkp_core::EMPTY_ROOT
+    );
+}
+
+#[test]
+fn late_binding_excludes_an_upfront_root() {
+    let mut out = Vec::new();
+    let opts = DriverOptions {
+        manifest_root: [7u8; 32],
+        ..late()
+    };
+    assert!(
+        StreamDriverSink::<CryptoLeaf, CryptoFold, CryptoWrap, _>::new(
+            CborSeqSink::new(&mut out),
+            opts,
+        )
+        .is_err()
+    );
+}
+
+#[test]
+fn backend_verifies_late_bound_artifacts() {
+    use sezkp_core::ProvingBackendStream;
+
+    let blocks = blocks_of(128, 16);
+    let root = commit_blocks(&blocks).root;
+    let path = temp_path("late_bound");
+    std::env::set_var("SEZKP_PROOF_STREAM_PATH", &path);
+
+    let mut st = FoldBackend::begin_stream_late_bound(&[]).unwrap();
+    for b in &blocks {
+        FoldBackend::ingest_block(&mut st, b.clone()).unwrap();
+    }
+    let art = FoldBackend::finish_stream(st).unwrap();
+    assert_eq!(art.manifest_root, root);
+    assert_eq!(art.meta["late_bound"], true);
+    FoldBackend::verify(&art, &[], root).unwrap();
+    assert!(FoldBackend::verify(&art, &[], [1u8; 32]).is_err());
+    let _ = std::fs::remove_file(path);
+}
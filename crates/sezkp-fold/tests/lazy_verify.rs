@@ -0,0 +1,116 @@
+//! Bounded-memory bundle verification: `verify_bundle_cbor` checks the CBOR
+//! bytes record by record and must agree with `verify_bundle` on the decoded
+//! bundle, accepting and rejecting the same proofs.
+
+#![allow(clippy::unwrap_used)]
+
+use ciborium::value::Value;
+use sezkp_core::BlockSummary;
+use sezkp_fold::api::DriverOptions;
+use sezkp_fold::driver::{run_pipeline, FoldProofBundle};
+use sezkp_fold::final_wrap::MacWrapBackend;
+use sezkp_fold::fold::{CryptoFoldProof, CryptoWrapProof};
+use sezkp_fold::leaf::CryptoLeafProof;
+use sezkp_fold::verify::{self, BundleSummary};
+use sezkp_fold::{CryptoFold, CryptoLeaf, CryptoWrap};
+use sezkp_trace::{generator::generate_trace, partition::partition_trace};
+
+type Bundle = FoldProofBundle<CryptoLeafProof, CryptoFoldProof, CryptoWrapProof>;
+
+fn bundle(steps: u64) -> Bundle {
+    let blocks: Vec<BlockSummary> = partition_trace(&generate_trace(steps, 2), 4);
+    let opts = DriverOptions {
+        wrap_cadence: 2,
+        are_samples: 1,
+        final_wrap: Some(&MacWrapBackend),
+        ..DriverOptions::default()
+    };
+    run_pipeline::<CryptoLeaf, CryptoFold, CryptoWrap>(&blocks, &opts)
+}
+
+fn lazy(bundle: &Bundle) -> anyhow::Result<BundleSummary> {
+    let bytes = serde_cbor::to_vec(bundle).unwrap();
+    verify::verify_bundle_cbor::<CryptoLeaf, CryptoFold, CryptoWrap>(&bytes)
+}
+
+fn decoded(bundle: &Bundle) -> anyhow::Result<()> {
+    verify::verify_bundle::<CryptoLeaf, CryptoFold, CryptoWrap>(bundle)
+}
+
+#[test]
+fn cbor_bundles_verify_in_place() {
+    for steps in [0, 16, 64, 96] {
+        let b = bundle(steps);
+        decoded(&b).unwrap();
+        assert_eq!(lazy(&b).unwrap(), BundleSummary::of(&b), "{steps} steps");
+    }
+}
+
+#[test]
+fn cbor_bundles_reject_what_decoded_ones_reject() {
+    let b = bundle(96);
+    assert!(b.wraps.len() >= 2 && b.final_wrap.is_some());
+
+    let mut tampered: Vec<(&str, Bundle)> = Vec::new();
+    let mut t = b.clone();
+    t.manifest_root[0] ^= 1;
+    tampered.push(("manifest root", t));
+    let mut t = b.clone();
+    t.leaves.pop();
+    tampered.push(("dropped leaf", t));
+    let mut t = b.clone();
+    t.wraps.swap(0, 1);
+    tampered.push(("swapped wraps", t));
+    let mut t = b.clone();
+    t.wraps.pop();
+    tampered.push(("dropped wrap", t));
+    let mut t = b.clone();
+    t.folds.swap(0, 1);
+    tampered.push(("reordered folds", t));
+    let mut t = b.clone();
+    t.final_wrap.as_mut().unwrap().proof[0] ^= 1;
+    tampered.push(("final wrap", t));
+    let mut t = b;
+    t.params.as_mut().unwrap().are_samples = 0;
+    tampered.push(("sample count", t));
+
+    for (what, t) in &tampered {
+        assert!(decoded(t).is_err(), "decoded: {what}");
+        assert!(lazy(t).is_err(), "lazy: {what}");
+    }
+}
+
+#[test]
+fn gadget_failures_keep_their_message() {
+    let mut b = bundle(64);
+    b.claims = Some([7; 32]);
+    let err = lazy(&b).unwrap_err();
+    assert!(err.to_string().contains("leaf proof failed"), "{err:#}");
+}
+
+#[test]
+fn records_must_be_listed_in_order() {
+    let b = bundle(64);
+    let Value::Map(mut entries) = Value::serialized(&b).unwrap() else {
+        panic!("bundle is not a map");
+    };
+    let at = |entries: &[(Value, Value)], key: &str| {
+        entries
+            .iter()
+            .position(|(k, _)| k.as_text() == Some(key))
+            .unwrap()
+    };
+    let (folds, wraps) = (at(&entries, "folds"), at(&entries, "wraps"));
+    entries.swap(folds, wraps);
+    let mut bytes = Vec::new();
+    ciborium::ser::into_writer(&Value::Map(entries), &mut bytes).unwrap();
+
+    // The same records decode fine; only the one-pass walk needs the order.
+    let reordered: Bundle = serde_cbor::from_slice(&bytes).unwrap();
+    decoded(&reordered).unwrap();
+    let err = verify::verify_bundle_cbor::<CryptoLeaf, CryptoFold, CryptoWrap>(&bytes).unwrap_err();
+    assert!(
+        err.to_string().contains("lists its wraps before its folds"),
+        "{err:#}"
+    );
+}
@@ -9,11 +9,14 @@
 #![allow(unused_variables)]
 #![allow(dead_code)]
 
-use sezkp_fold::api::Leaf;
+use sezkp_fold::api::{commit_pi, Leaf};
 use sezkp_fold::are::Pi;
 use sezkp_fold::leaf::{CryptoLeaf, CryptoLeafProof};
 use sezkp_trace::{generator::generate_trace, partition::partition_trace};
 
+/// Manifest root the leaf proofs are bound to.
+const ROOT: [u8; 32] = [7; 32];
+
 /// Convenience: extract (root, len) to sanity-check leaf commitment.
 #[inline]
 fn commit_summary((c, _p, _pr): &(sezkp_fold::api::Commitment, Pi, CryptoLeafProof)) -> ([u8; 32], u32) {
@@ -28,8 +31,8 @@ fn leaf_pi_prove_verify_roundtrip_and_tamper() {
     let blk = &blocks[0];
 
     // Prove leaf and verify.
-    let (pi, c, pr) = CryptoLeaf::prove_leaf(blk);
-    assert!(CryptoLeaf::verify_leaf(&c, &pi, &pr), "leaf verify should pass");
+    let (pi, c, pr) = CryptoLeaf::prove_leaf(&ROOT, blk);
+    assert!(CryptoLeaf::verify_leaf(&ROOT, &c, &commit_pi(&pi), &pr), "leaf verify should pass");
 
     // Commitment sanity: single-leaf commitment with a nonzero digest.
     let (root, len) = commit_summary(&(c, pi, pr.clone()));
@@ -40,9 +43,9 @@ fn leaf_pi_prove_verify_roundtrip_and_tamper() {
     let mut pi_bad = pi;
     let mut le = pi_bad.acc[0].to_le_bytes();
     le[0] ^= 0x01;
-    pi_bad.acc[0] = sezkp_stark::v1::field::F1::from_u64(u64::from_le_bytes(le));
+    pi_bad.acc[0] = sezkp_gadgets::F1::from_u64(u64::from_le_bytes(le));
     assert!(
-        !CryptoLeaf::verify_leaf(&c, &pi_bad, &pr),
+        !CryptoLeaf::verify_leaf(&ROOT, &c, &commit_pi(&pi_bad), &pr),
         "tampered π must fail verification"
     );
 
@@ -51,7 +54,7 @@ fn leaf_pi_prove_verify_roundtrip_and_tamper() {
     pi_swap.acc.swap(0, 2);
     pi_swap.acc.swap(1, 3);
     assert!(
-        !CryptoLeaf::verify_leaf(&c, &pi_swap, &pr),
+        !CryptoLeaf::verify_leaf(&ROOT, &c, &commit_pi(&pi_swap), &pr),
         "swapped boundary limbs must fail"
     );
 
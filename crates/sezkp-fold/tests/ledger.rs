@@ -0,0 +1,48 @@
+//! Disk-backed endpoint ledger: paging must be invisible to the driver.
+
+use sezkp_fold::api::{Commitment, LedgerStore};
+use sezkp_fold::are::Pi;
+use sezkp_fold::ledger::{EndpointLedger, PAGE_RECORDS, RESIDENT_PAGES};
+use sezkp_gadgets::F1;
+
+fn endpoint(i: usize) -> (Commitment, Pi) {
+    let mut root = [0u8; 32];
+    root[..8].copy_from_slice(&(i as u64).to_le_bytes());
+    let x = i as u32;
+    let pi = Pi {
+        ctrl_in: x,
+        ctrl_out: x.wrapping_mul(3),
+        flags: x & 1,
+        acc: [
+            F1::from_u64(i as u64),
+            F1::from_u64(u64::MAX - i as u64),
+            F1::from_u64(7),
+            F1::from_u64(0),
+        ],
+    };
+    (Commitment::new(root, x + 1), pi)
+}
+
+#[test]
+fn disk_ledger_matches_memory_model_across_pages() {
+    // More pages than stay resident, plus a ragged final page.
+    let n = PAGE_RECORDS * (RESIDENT_PAGES * 3) + 5;
+    let mut disk = EndpointLedger::new(n, LedgerStore::Disk).expect("create disk ledger");
+    let mut model = EndpointLedger::new(n, LedgerStore::Memory).expect("create memory ledger");
+
+    // Strided writes touch every page repeatedly, forcing evictions.
+    for step in [1usize, 97, 13] {
+        for i in (0..n).step_by(step) {
+            let ep = (i % 5 != 0).then(|| endpoint(i * step));
+            disk.set(i, ep).expect("disk set");
+            model.set(i, ep).expect("model set");
+        }
+    }
+    for i in (0..n).rev() {
+        assert_eq!(
+            disk.get(i).expect("disk get"),
+            model.get(i).expect("model get"),
+            "slot {i}"
+        );
+    }
+}
@@ -0,0 +1,143 @@
+//! Manifest binding: every gadget proof is bound to the manifest root it was
+//! made for, so a proof (or any part of one) does not verify under another.
+
+#![allow(clippy::unwrap_used)]
+
+mod utils;
+
+use sezkp_core::{BlockSummary, ProvingBackend};
+use sezkp_fold::api::{commit_pi, DriverOptions, Fold, Leaf, Wrap};
+use sezkp_fold::cache::{CacheStats, SubtreeCache};
+use sezkp_fold::driver::run_pipeline;
+use sezkp_fold::{verify, CryptoFold, CryptoLeaf, CryptoWrap, FoldBackend};
+use sezkp_merkle::commit_blocks;
+use std::path::Path;
+use utils::{blocks, blocks_of, driver, rewrite, temp_path, verify_bytes};
+
+const OTHER: [u8; 32] = [0xab; 32];
+
+fn opts(manifest_root: [u8; 32]) -> DriverOptions {
+    utils::opts(|o| {
+        o.wrap_cadence = 2;
+        o.manifest_root = manifest_root;
+    })
+}
+
+/// Stream `blocks` bound to `root`; return the CBOR-seq bytes and cache stats.
+fn stream(
+    blocks: &[BlockSummary],
+    root: [u8; 32],
+    cache: Option<&Path>,
+) -> (Vec<u8>, Option<CacheStats>) {
+    let mut out = Vec::new();
+    let mut drv = driver(&mut out, opts(root));
+    if let Some(dir) = cache {
+        drv = drv.with_cache(SubtreeCache::open(dir, 4).unwrap());
+    }
+    for b in blocks {
+        drv.push_block(b.clone()).unwrap();
+    }
+    let stats = drv.cache_stats();
+    drv.finish().unwrap();
+    (out, stats)
+}
+
+/// Rewrite the header and footer manifest roots of a stream.
+fn rebind_ends(bytes: &[u8], header: [u8; 32], footer: [u8; 32]) -> Vec<u8> {
+    rewrite(bytes, |h, f| {
+        h.manifest_root = header;
+        f.manifest_root = footer;
+    })
+}
+
+#[test]
+fn gadget_proofs_do_not_verify_under_another_root() {
+    let blocks = blocks();
+    let root = commit_blocks(&blocks).root;
+
+    let (pi_l, c_l, leaf) = CryptoLeaf::prove_leaf(&root, &blocks[0]);
+    let (pi_r, c_r, _) = CryptoLeaf::prove_leaf(&root, &blocks[1]);
+    let (cmt_l, cmt_r) = (commit_pi(&pi_l), commit_pi(&pi_r));
+    assert!(CryptoLeaf::verify_leaf(&root, &c_l, &cmt_l, &leaf));
+    assert!(!CryptoLeaf::verify_leaf(&OTHER, &c_l, &cmt_l, &leaf));
+
+    let bundle = run_pipeline::<CryptoLeaf, CryptoFold, CryptoWrap>(&blocks[..2], &opts(root));
+    let ((c_p, pi_p), _, _, fold) = &bundle.folds[0];
+    let parent = (c_p, &commit_pi(pi_p));
+    assert!(CryptoFold::verify_fold(
+        &root,
+        parent,
+        (&c_l, &cmt_l),
+        (&c_r, &cmt_r),
+        fold
+    ));
+    assert!(!CryptoFold::verify_fold(
+        &OTHER,
+        parent,
+        (&c_l, &cmt_l),
+        (&c_r, &cmt_r),
+        fold
+    ));
+
+    let wrap = CryptoWrap::wrap(&root, (c_p, pi_p));
+    assert!(CryptoWrap::verify_wrap(&root, parent, &wrap));
+    assert!(!CryptoWrap::verify_wrap(&OTHER, parent, &wrap));
+}
+
+#[test]
+fn bundles_and_streams_record_their_root() {
+    let blocks = blocks();
+    let root = commit_blocks(&blocks).root;
+
+    let mut bundle = run_pipeline::<CryptoLeaf, CryptoFold, CryptoWrap>(&blocks, &opts(root));
+    assert_eq!(bundle.manifest_root, root);
+    verify::verify_bundle::<CryptoLeaf, CryptoFold, CryptoWrap>(&bundle).unwrap();
+    bundle.manifest_root = OTHER;
+    assert!(verify::verify_bundle::<CryptoLeaf, CryptoFold, CryptoWrap>(&bundle).is_err());
+
+    let (bytes, _) = stream(&blocks, root, None);
+    assert_eq!(verify_bytes(&bytes).unwrap().manifest_root, root);
+    // Relabelling the stream does not move its proofs to another root, and
+    // the footer must repeat the header's root.
+    assert!(verify_bytes(&rebind_ends(&bytes, OTHER, OTHER)).is_err());
+    assert!(verify_bytes(&rebind_ends(&bytes, root, OTHER)).is_err());
+}
+
+#[test]
+fn backend_rejects_a_proof_bound_to_another_root() {
+    let blocks = blocks();
+    let root = commit_blocks(&blocks).root;
+    let art = FoldBackend::prove(&blocks, root).unwrap();
+    FoldBackend::verify(&art, &blocks, root).unwrap();
+
+    // Proved for another manifest, the tree root still matches but the
+    // gadget proofs are bound elsewhere.
+    let mut stray = FoldBackend::prove(&blocks, OTHER).unwrap();
+    stray.manifest_root = root;
+    let err = FoldBackend::verify(&stray, &blocks, root).unwrap_err();
+    assert!(
+        err.to_string().contains("different manifest root"),
+        "{err:#}"
+    );
+}
+
+#[test]
+fn cached_granules_are_rebound_to_the_current_root() {
+    let blocks = blocks_of(80, 4);
+    let dir = temp_path("binding_grow");
+
+    let prefix_root = commit_blocks(&blocks[..8]).root;
+    let (_, s) = stream(&blocks[..8], prefix_root, Some(&dir));
+    assert_eq!(s.unwrap().misses, 2);
+
+    // The grown trace has a new root; its prefix granules still hit and
+    // replay exactly what a cold run under the new root emits.
+    let root = commit_blocks(&blocks[..16]).root;
+    let (cached, s) = stream(&blocks[..16], root, Some(&dir));
+    let s = s.unwrap();
+    assert_eq!((s.hits, s.misses, s.rejected), (2, 2, 0));
+    let (cold, _) = stream(&blocks[..16], root, None);
+    assert_eq!(cached, cold);
+    assert_eq!(verify_bytes(&cached).unwrap().manifest_root, root);
+    let _ = std::fs::remove_dir_all(dir);
+}
@@ -0,0 +1,248 @@
+//! Envelope versions: bincode-framed V1 (JSON bundle) and V2 artifacts still
+//! verify, upgrade to V3 with the same roots, and stop being read once their
+//! support windows close; V3 follows its documented CBOR layout. Legacy
+//! `Stark` backend labels are accepted and rewritten to `Fold`.
+
+#![allow(clippy::unwrap_used)]
+
+use serde::Serialize;
+use sezkp_core::timestamp::TimeAuthority;
+use sezkp_core::{BackendKind, BlockSummary, ProofArtifact, ProvingBackend};
+use sezkp_fold::api::{Commitment, DriverOptions};
+use sezkp_fold::are::Pi;
+use sezkp_fold::driver::{run_pipeline, FoldProofBundle};
+use sezkp_fold::fold::CryptoFoldProof;
+use sezkp_fold::migrate::{envelope_version, migrate_to_v3, V1_REMOVED_IN, V2_REMOVED_IN};
+use sezkp_fold::{
+    CryptoFold, CryptoLeaf, CryptoLeafProof, CryptoWrap, CryptoWrapProof, FoldBackend,
+};
+use sezkp_merkle::commit_blocks;
+use sezkp_trace::{generator::generate_trace, partition::partition_trace};
+
+/// The bincode wire layouts, as written by earlier releases.
+#[derive(Serialize)]
+enum WireVersion {
+    V1,
+    V2,
+}
+
+#[derive(Serialize)]
+struct Payload {
+    bundle: Vec<u8>,
+    root_c: Commitment,
+    root_pi: Pi,
+}
+
+#[derive(Serialize)]
+enum WireEnvelope {
+    V1(Payload),
+    V2(Payload),
+}
+
+fn blocks() -> Vec<BlockSummary> {
+    partition_trace(&generate_trace(64, 2), 4)
+}
+
+/// The bundle a batch proof over `blocks` carries.
+fn bundle(
+    blocks: &[BlockSummary],
+) -> FoldProofBundle<CryptoLeafProof, CryptoFoldProof, CryptoWrapProof> {
+    let opts = DriverOptions {
+        manifest_root: commit_blocks(blocks).root,
+        ..DriverOptions::default()
+    };
+    run_pipeline::<CryptoLeaf, CryptoFold, CryptoWrap>(blocks, &opts)
+}
+
+/// A V1 artifact over `blocks`, with the old metadata.
+fn v1_artifact(blocks: &[BlockSummary]) -> ProofArtifact {
+    legacy_artifact(blocks, WireVersion::V1)
+}
+
+/// A bincode-framed artifact of version `ver` over `blocks`.
+fn legacy_artifact(blocks: &[BlockSummary], ver: WireVersion) -> ProofArtifact {
+    let bundle = bundle(blocks);
+    let ((root_c, root_pi), _, _, _) = *bundle.folds.last().unwrap();
+    let (payload, proto) = match ver {
+        WireVersion::V1 => (
+            WireEnvelope::V1(Payload {
+                bundle: serde_json::to_vec(&bundle).unwrap(),
+                root_c,
+                root_pi,
+            }),
+            "fold-v1",
+        ),
+        WireVersion::V2 => (
+            WireEnvelope::V2(Payload {
+                bundle: serde_cbor::to_vec(&bundle).unwrap(),
+                root_c,
+                root_pi,
+            }),
+            "fold-v2",
+        ),
+    };
+    ProofArtifact {
+        backend: BackendKind::Stark,
+        manifest_root: root_c.root,
+        proof_digest: None,
+        proof_bytes: bincode::serialize(&(ver, &payload)).unwrap(),
+        meta: serde_json::json!({ "proto": proto, "n_blocks": blocks.len() }),
+        timestamp: None,
+        claims: Vec::new(),
+    }
+}
+
+#[test]
+fn legacy_artifacts_migrate_to_v3_with_the_same_roots() {
+    let blocks = blocks();
+    let fresh = FoldBackend::prove(&blocks, commit_blocks(&blocks).root).unwrap();
+    for (ver, old) in [(1, WireVersion::V1), (2, WireVersion::V2)] {
+        let legacy = legacy_artifact(&blocks, old);
+        let root = legacy.manifest_root;
+        assert_eq!(envelope_version(&legacy).unwrap(), Some(ver));
+        FoldBackend::verify(&legacy, &blocks, root).unwrap();
+
+        let m = migrate_to_v3(&legacy).unwrap();
+        assert_eq!(m.from, ver);
+        assert!(m.relabelled);
+        assert!(!m.dropped_timestamp);
+        let v3 = m.artifact;
+        assert_eq!(v3.backend, BackendKind::Fold);
+        assert_eq!(envelope_version(&v3).unwrap(), Some(3));
+        assert_eq!(v3.manifest_root, root);
+        assert_eq!(v3.meta["proto"], "fold-v3");
+        assert_eq!(v3.meta["migrated_from"], format!("fold-v{ver}"));
+        assert_eq!(v3.meta["n_blocks"], blocks.len());
+        FoldBackend::verify(&v3, &blocks, root).unwrap();
+        // Same bundle, same bytes as a fresh proof.
+        assert_eq!(v3.proof_bytes, fresh.proof_bytes);
+    }
+
+    // V3 artifacts pass through unchanged.
+    let again = migrate_to_v3(&fresh).unwrap();
+    assert_eq!(again.from, 3);
+    assert!(!again.relabelled);
+    assert_eq!(again.artifact.proof_bytes, fresh.proof_bytes);
+}
+
+#[test]
+fn backend_labels_are_checked_and_legacy_ones_relabelled() {
+    let blocks = blocks();
+    let root = commit_blocks(&blocks).root;
+    let fresh = FoldBackend::prove(&blocks, root).unwrap();
+    assert_eq!(fresh.backend, BackendKind::Fold);
+    let err = sezkp_stark::StarkV1::verify(&fresh, &blocks, root).unwrap_err();
+    assert!(err.to_string().contains("fold backend"), "{err}");
+
+    // A V3 artifact carrying the old label still verifies, and migrating it
+    // only rewrites the label.
+    let mut legacy = fresh.clone();
+    legacy.backend = BackendKind::Stark;
+    FoldBackend::verify(&legacy, &blocks, root).unwrap();
+    let m = migrate_to_v3(&legacy).unwrap();
+    assert!(m.relabelled);
+    assert_eq!(m.artifact.backend, BackendKind::Fold);
+    assert_eq!(m.artifact.proof_bytes, fresh.proof_bytes);
+
+    // Without a fold protocol the label is taken at its word.
+    legacy.meta = serde_json::json!({ "proto": "stark-v1" });
+    let err = FoldBackend::verify(&legacy, &blocks, root).unwrap_err();
+    assert!(err.to_string().contains("stark backend"), "{err}");
+    legacy.backend = BackendKind::from_proto("supernova");
+    assert!(migrate_to_v3(&legacy).is_err());
+}
+
+#[test]
+fn v3_envelopes_follow_the_documented_layout() {
+    use ciborium::Value;
+
+    let blocks = blocks();
+    let art = FoldBackend::prove(&blocks, commit_blocks(&blocks).root).unwrap();
+    assert_eq!(art.proof_bytes[0], 0xa4);
+    let Value::Map(entries) = ciborium::de::from_reader(&art.proof_bytes[..]).unwrap() else {
+        panic!("envelope is not a map");
+    };
+    let keys: Vec<_> = entries.iter().map(|(k, _)| k.as_text().unwrap()).collect();
+    assert_eq!(keys, ["bundle", "root_c", "root_pi", "version"]);
+    assert_eq!(entries[3].1, Value::Integer(3.into()));
+
+    let field = |i: usize, key: &str| -> Value {
+        let map = entries[i].1.as_map().unwrap();
+        map.iter()
+            .find(|(k, _)| k.as_text() == Some(key))
+            .unwrap()
+            .1
+            .clone()
+    };
+    assert_eq!(
+        field(1, "root").as_bytes().unwrap().as_slice(),
+        art.manifest_root
+    );
+    assert_eq!(field(2, "acc").as_bytes().unwrap().len(), 32);
+
+    // The bundle is the V2 bundle encoding, byte for byte.
+    assert_eq!(
+        entries[0].1.as_bytes().unwrap(),
+        &serde_cbor::to_vec(&bundle(&blocks)).unwrap()
+    );
+
+    // Unknown versions are refused rather than guessed at.
+    let mut future = art;
+    let at = future.proof_bytes.len() - 2;
+    future.proof_bytes[at..].copy_from_slice(&4u16.to_be_bytes());
+    let err = envelope_version(&future).unwrap_err();
+    assert!(err.to_string().contains("version 4"), "{err}");
+}
+
+#[test]
+fn migration_refuses_inconsistent_or_envelope_less_artifacts() {
+    let blocks = blocks();
+    let v1 = v1_artifact(&blocks);
+
+    let mut wrong_root = v1.clone();
+    wrong_root.manifest_root[0] ^= 1;
+    let err = migrate_to_v3(&wrong_root).unwrap_err();
+    assert!(err.to_string().contains("V1 bundle root"), "{err}");
+
+    let mut garbage = v1.clone();
+    garbage.proof_bytes.truncate(16);
+    assert!(migrate_to_v3(&garbage).is_err());
+
+    let mut streaming = v1;
+    streaming.proof_bytes.clear();
+    streaming.meta = serde_json::json!({ "stream_format": "fold-seq-v1" });
+    assert_eq!(envelope_version(&streaming).unwrap(), None);
+    let err = migrate_to_v3(&streaming).unwrap_err();
+    assert!(err.to_string().contains("nothing to migrate"), "{err}");
+}
+
+#[test]
+fn migration_drops_timestamps_over_the_old_bytes() {
+    let mut v1 = v1_artifact(&blocks());
+    let tsa = TimeAuthority::new("test", [7u8; 32]);
+    v1.attach_timestamp(&tsa, 1_700_000_000);
+
+    let m = migrate_to_v3(&v1).unwrap();
+    assert!(m.dropped_timestamp);
+    assert!(m.artifact.timestamp.is_none());
+}
+
+#[test]
+fn legacy_support_windows_are_still_open() {
+    // Once this fails, the release is due to drop V1 (or V2 and with it the
+    // bincode read path): remove the variant from the decoder, this file's
+    // tests of it, and the README's migration note.
+    let major_minor = |v: &str| -> (u64, u64) {
+        let mut parts = v.split('.').map(|p| p.parse::<u64>().unwrap());
+        (parts.next().unwrap(), parts.next().unwrap())
+    };
+    let current = major_minor(env!("CARGO_PKG_VERSION"));
+    assert!(
+        current < major_minor(V1_REMOVED_IN),
+        "V1 envelope decoding was scheduled for removal in {V1_REMOVED_IN}"
+    );
+    assert!(
+        current < major_minor(V2_REMOVED_IN),
+        "V2 envelope decoding was scheduled for removal in {V2_REMOVED_IN}"
+    );
+}
@@ -0,0 +1,184 @@
+//! Declared fold parameters: proofs carry the mode, wrap cadence, boundary
+//! window and seam digest mode they were made with, and verifiers hold the
+//! proof's shape to them.
+
+#![allow(clippy::unwrap_used)]
+
+mod utils;
+
+use ciborium::value::Value;
+use sezkp_core::{BlockSummary, DigestMode};
+use sezkp_fold::api::{DriverOptions, ExpectedParams, FoldMode};
+use sezkp_fold::driver::{run_pipeline, StreamFooter, StreamHeader};
+use sezkp_fold::{verify, CryptoFold, CryptoLeaf, CryptoWrap};
+use utils::{blocks, rewrite_values, stream, values, verify_values};
+
+fn opts(wrap_cadence: u32) -> DriverOptions {
+    utils::opts(|o| {
+        o.fold_mode = FoldMode::MinRam;
+        o.wrap_cadence = wrap_cadence;
+    })
+}
+
+/// Stream `blocks` and split the CBOR sequence into its values.
+fn stream_values(blocks: &[BlockSummary], opts: DriverOptions) -> Vec<Value> {
+    values(&stream(blocks, opts))
+}
+
+fn is_wrap(v: &Value) -> bool {
+    matches!(v, Value::Map(m) if m.iter().any(|(k, _)| k.as_text() == Some("Wrap")))
+}
+
+/// Rewrite the header and footer of a stream.
+fn edit_ends(
+    values: &mut [Value],
+    header: impl FnOnce(&mut StreamHeader),
+    footer: impl FnOnce(&mut StreamFooter),
+) {
+    rewrite_values(values, |h, f| {
+        header(h);
+        footer(f);
+    });
+}
+
+#[test]
+fn stream_declares_and_enforces_params() {
+    let blocks = blocks();
+    let values = stream_values(&blocks, opts(2));
+    assert!(values.iter().filter(|v| is_wrap(v)).count() > 1);
+
+    let footer = verify_values(&values).unwrap();
+    assert_eq!(footer.params, Some(opts(2).params()));
+    let exp = ExpectedParams {
+        fold_mode: Some(FoldMode::MinRam),
+        wrap_cadence: Some(2),
+        are_samples: None,
+        iface_digest: None,
+        allow_rewindow: false,
+    };
+    exp.check(footer.params.as_ref()).unwrap();
+    let wrong = ExpectedParams {
+        wrap_cadence: Some(4),
+        ..exp
+    };
+    assert!(wrong.check(footer.params.as_ref()).is_err());
+
+    // Dropping a wrap, or claiming another cadence, breaks the shape.
+    let mut dropped = values.clone();
+    let i = dropped.iter().position(is_wrap).unwrap();
+    dropped.remove(i);
+    assert!(verify_values(&dropped).is_err());
+
+    let mut relabeled = values.clone();
+    edit_ends(
+        &mut relabeled,
+        |h| h.wrap_cadence = 4,
+        |f| f.params.as_mut().unwrap().wrap_cadence = 4,
+    );
+    assert!(verify_values(&relabeled).is_err());
+
+    // Header and footer must agree.
+    let mut split = values.clone();
+    edit_ends(&mut split, |h| h.mode = FoldMode::Balanced, |_| {});
+    assert!(verify_values(&split).is_err());
+
+    // A window this build does not implement is rejected.
+    let mut window = values;
+    edit_ends(
+        &mut window,
+        |h| h.iface_window = 16,
+        |f| f.params.as_mut().unwrap().iface_window = 16,
+    );
+    assert!(verify_values(&window).is_err());
+}
+
+#[test]
+fn version_1_streams_still_verify() {
+    let mut values = stream_values(&blocks(), opts(3));
+    edit_ends(&mut values, |h| h.ver = 1, |f| f.params = None);
+    let footer = verify_values(&values).unwrap();
+    assert_eq!(footer.params, None);
+
+    // The v1 header's cadence is still enforced; expectations cannot be met.
+    assert!(ExpectedParams::default().check(None).is_ok());
+    let exp = ExpectedParams {
+        wrap_cadence: Some(3),
+        ..ExpectedParams::default()
+    };
+    assert!(exp.check(None).is_err());
+    edit_ends(&mut values, |h| h.wrap_cadence = 0, |_| {});
+    assert!(verify_values(&values).is_err());
+}
+
+#[test]
+fn bundle_wraps_follow_declared_cadence() {
+    let blocks = blocks();
+    let bundle = run_pipeline::<CryptoLeaf, CryptoFold, CryptoWrap>(&blocks, &opts(2));
+    assert_eq!(bundle.params, Some(opts(2).params()));
+    verify::verify_bundle::<CryptoLeaf, CryptoFold, CryptoWrap>(&bundle).unwrap();
+
+    let mut dropped = bundle.clone();
+    dropped.wraps.pop();
+    assert!(verify::verify_bundle::<CryptoLeaf, CryptoFold, CryptoWrap>(&dropped).is_err());
+
+    let mut relabeled = bundle.clone();
+    relabeled.params.as_mut().unwrap().wrap_cadence = 3;
+    assert!(verify::verify_bundle::<CryptoLeaf, CryptoFold, CryptoWrap>(&relabeled).is_err());
+
+    let mut swapped = bundle.clone();
+    swapped.wraps.swap(0, 1);
+    assert!(verify::verify_bundle::<CryptoLeaf, CryptoFold, CryptoWrap>(&swapped).is_err());
+
+    // Legacy bundles declare nothing and are not held to a cadence.
+    let mut legacy = bundle;
+    legacy.params = None;
+    legacy.wraps.pop();
+    verify::verify_bundle::<CryptoLeaf, CryptoFold, CryptoWrap>(&legacy).unwrap();
+}
+
+#[test]
+fn seam_digest_modes_are_declared_and_pinned() {
+    let blocks = blocks();
+    let heads = DriverOptions {
+        iface_digest: DigestMode::Heads,
+        ..opts(0)
+    };
+
+    // Older streams and the default mode carry no `iface_digest` key.
+    let full = stream_values(&blocks, opts(0));
+    let key = |v: &Value| matches!(v, Value::Map(m) if m.iter().any(|(k, _)| k.as_text() == Some("iface_digest")));
+    assert!(!key(&full[0]));
+
+    let values = stream_values(&blocks, heads);
+    assert!(key(&values[0]));
+    let footer = verify_values(&values).unwrap();
+    assert_eq!(footer.params.unwrap().iface_digest, DigestMode::Heads);
+    let exp = ExpectedParams {
+        iface_digest: Some(DigestMode::Full),
+        ..ExpectedParams::default()
+    };
+    let err = exp.check(footer.params.as_ref()).unwrap_err();
+    assert!(
+        err.to_string().contains("heads mode, expected full"),
+        "{err}"
+    );
+
+    // Header and footer must agree on the mode.
+    let mut split = values;
+    edit_ends(
+        &mut split,
+        |h| h.iface_digest = DigestMode::WritesOnly,
+        |_| {},
+    );
+    assert!(verify_values(&split).is_err());
+
+    // Each mode digests the seams differently.
+    let a = run_pipeline::<CryptoLeaf, CryptoFold, CryptoWrap>(&blocks, &opts(0));
+    let b = run_pipeline::<CryptoLeaf, CryptoFold, CryptoWrap>(&blocks, &heads);
+    verify::verify_bundle::<CryptoLeaf, CryptoFold, CryptoWrap>(&b).unwrap();
+    assert_eq!(b.params.unwrap().iface_digest, DigestMode::Heads);
+    assert_ne!(
+        a.folds[0].3.iface.boundary_writes_digest,
+        b.folds[0].3.iface.boundary_writes_digest
+    );
+}
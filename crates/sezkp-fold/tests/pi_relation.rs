@@ -0,0 +1,97 @@
+//! π relation of fold items: each fold proof opens its children's π, and the
+//! verifier ties the openings to the child commitments, chains them through
+//! the interface and requires the parent to commit to their combination.
+
+#![allow(clippy::unwrap_used)]
+
+mod utils;
+
+use ciborium::Value;
+use sezkp_core::BlockSummary;
+use sezkp_fold::api::{commit_pi, DriverOptions, Fold, Leaf};
+use sezkp_fold::are::{self, CombineAux};
+use sezkp_fold::fold::CryptoFoldProof;
+use sezkp_fold::{CryptoFold, CryptoLeaf, CryptoLeafProof, CryptoWrapProof};
+use sezkp_gadgets::F1;
+use utils::{blocks_of, stream, values, verify_bytes, verify_values};
+
+const ROOT: [u8; 32] = [3u8; 32];
+
+fn blocks() -> Vec<BlockSummary> {
+    blocks_of(64, 8)
+}
+
+#[test]
+fn fold_proofs_open_and_check_the_child_pi() {
+    let blocks = blocks();
+    let (pi_l, c_l, _) = CryptoLeaf::prove_leaf(&ROOT, &blocks[0]);
+    let (pi_r, c_r, _) = CryptoLeaf::prove_leaf(&ROOT, &blocks[1]);
+    let iface = are::InterfaceWitness::trivial(0);
+    let (c_p, pi_p, proof) = CryptoFold::fold(&ROOT, (&c_l, &pi_l), (&c_r, &pi_r), &iface);
+    assert_eq!(pi_p, are::combine(&pi_l, &pi_r, &CombineAux::default()));
+
+    let (l, r) = ((&c_l, &commit_pi(&pi_l)), (&c_r, &commit_pi(&pi_r)));
+    let p_cmt = commit_pi(&pi_p);
+    let verify =
+        |p_cmt, proof: &CryptoFoldProof| CryptoFold::verify_fold(&ROOT, (&c_p, p_cmt), l, r, proof);
+    assert!(verify(&p_cmt, &proof));
+
+    // The MAC is public, so a prover can re-bind it to any parent π; the
+    // openings no longer combine into that parent.
+    let mut forged_pi = pi_p;
+    forged_pi.acc[0] = forged_pi.acc[0] + F1::from_u64(1);
+    let forged = commit_pi(&forged_pi);
+    let mut rebound = proof.clone();
+    CryptoFold::rebind_fold(&ROOT, (&c_p, &forged), l, r, &mut rebound);
+    assert!(!verify(&forged, &rebound));
+
+    // Openings that do not match the child commitments.
+    let mut bad = proof.clone();
+    let o = bad.pi.as_mut().unwrap();
+    o.left.acc[1] = o.left.acc[1] + F1::from_u64(1);
+    assert!(!verify(&p_cmt, &bad));
+
+    // Control must chain through the interface.
+    let mut bad = proof.clone();
+    bad.iface.left_ctrl_out = 1;
+    CryptoFold::rebind_fold(&ROOT, (&c_p, &p_cmt), l, r, &mut bad);
+    assert!(!verify(&p_cmt, &bad));
+
+    // Proofs without openings are rejected.
+    let mut bad = proof;
+    bad.pi = None;
+    assert!(!verify(&p_cmt, &bad));
+}
+
+type Item = sezkp_fold::driver::StreamItem<CryptoLeafProof, CryptoFoldProof, CryptoWrapProof>;
+
+#[test]
+fn stream_verifier_checks_pi_per_fold_item() {
+    let bytes = stream(&blocks(), DriverOptions::default());
+    verify_bytes(&bytes).unwrap();
+
+    // Strip the openings from the first fold item.
+    let mut values = values(&bytes);
+    let at = values
+        .iter()
+        .position(|v| matches!(v.deserialized::<Item>(), Ok(Item::Fold { .. })))
+        .unwrap();
+    let Ok(Item::Fold {
+        parent,
+        left,
+        right,
+        mut proof,
+    }) = values[at].deserialized::<Item>()
+    else {
+        unreachable!()
+    };
+    proof.pi = None;
+    values[at] = Value::serialized(&Item::Fold {
+        parent,
+        left,
+        right,
+        proof,
+    })
+    .unwrap();
+    assert!(verify_values(&values).is_err());
+}
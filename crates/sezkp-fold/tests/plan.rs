@@ -0,0 +1,54 @@
+//! Memory planning: streaming state grows with the scheduler depth, and
+//! MinRam or a disk ledger drop the per-block endpoints of Balanced mode.
+
+#![allow(clippy::unwrap_used)]
+
+use sezkp_fold::api::{DriverOptions, FoldMode, LedgerStore};
+use sezkp_fold::plan::{estimate_memory, BlockStats, FOLD_ENDPOINT_BYTES};
+use sezkp_merkle::commit_blocks;
+use sezkp_trace::{generator::generate_trace, partition::partition_trace};
+
+const fn stats(n_blocks: u64) -> BlockStats {
+    BlockStats {
+        n_blocks,
+        total_steps: n_blocks * 64,
+        max_block_steps: 64,
+        tau: 2,
+    }
+}
+
+#[test]
+fn streaming_driver_state_is_logarithmic_in_blocks() {
+    let est = |n| estimate_memory(&stats(n), &DriverOptions::default(), true, false);
+    let (small, big) = (est(1 << 10), est(1 << 20));
+    assert_eq!(small.blocks_bytes, big.blocks_bytes);
+    assert!(big.live_frames <= 2 * small.live_frames);
+    assert!(big.peak_bytes() < 4 * small.peak_bytes());
+}
+
+#[test]
+fn minram_and_disk_ledger_drop_per_block_endpoints() {
+    let s = stats(1 << 20);
+    let with = |fold_mode, ledger| {
+        let opts = DriverOptions {
+            fold_mode,
+            ledger,
+            ..DriverOptions::default()
+        };
+        estimate_memory(&s, &opts, false, true).driver_bytes
+    };
+    let balanced = with(FoldMode::Balanced, LedgerStore::Memory);
+    assert_eq!(balanced, s.n_blocks * FOLD_ENDPOINT_BYTES);
+    assert!(with(FoldMode::MinRam, LedgerStore::Memory) < balanced / 1000);
+    assert!(with(FoldMode::Balanced, LedgerStore::Disk) < balanced);
+}
+
+#[test]
+fn manifest_stats_use_the_mean_block() {
+    let blocks = partition_trace(&generate_trace(100, 2), 8);
+    let s = BlockStats::from_manifest(&commit_blocks(&blocks)).unwrap();
+    assert_eq!(s.n_blocks, blocks.len() as u64);
+    assert_eq!(s.total_steps, 100);
+    assert_eq!(s.max_block_steps, 8);
+    assert_eq!(s.tau, 2);
+}
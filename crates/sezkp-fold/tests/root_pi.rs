@@ -0,0 +1,82 @@
+//! Revealed root `π`: a prover may publish the root projection next to its
+//! commitment, and verifiers hold the revealed value to that commitment.
+
+#![allow(clippy::unwrap_used)]
+
+mod utils;
+
+use ciborium::value::Value;
+use sezkp_core::{BlockSummary, ConfiguredBackend, ProvingBackend, StreamingProver};
+use sezkp_fold::api::{commit_pi, DriverOptions};
+use sezkp_fold::{revealed_root_pi, FoldBackend};
+use sezkp_merkle::commit_blocks;
+use utils::{blocks, opts, rewrite_values, stream, temp_path, values, verify_values};
+
+fn revealing() -> DriverOptions {
+    opts(|o| o.reveal_root_pi = true)
+}
+
+fn stream_values(blocks: &[BlockSummary], opts: DriverOptions) -> Vec<Value> {
+    values(&stream(blocks, opts))
+}
+
+#[test]
+fn stream_footers_reveal_the_committed_root_pi() {
+    let blocks = blocks();
+    let values = stream_values(&blocks, revealing());
+    let footer = verify_values(&values).unwrap();
+    let pi = footer.root_pi.unwrap();
+    assert_eq!(commit_pi(&pi), footer.root_pi_cmt);
+
+    // Everything but the footer is what a stream without it carries.
+    let plain = stream_values(&blocks, DriverOptions::default());
+    assert_eq!(plain[..plain.len() - 1], values[..values.len() - 1]);
+    assert!(verify_values(&plain).unwrap().root_pi.is_none());
+
+    let mut forged = values;
+    rewrite_values(&mut forged, |_, f| {
+        f.root_pi.as_mut().unwrap().ctrl_out ^= 1;
+    });
+    let err = verify_values(&forged).unwrap_err();
+    assert!(
+        err.to_string().contains("does not match its commitment"),
+        "{err}"
+    );
+}
+
+#[test]
+fn artifacts_reveal_root_pi_in_meta() {
+    let blocks = blocks();
+    let root = commit_blocks(&blocks).root;
+    let dir = temp_path("root_pi");
+    std::fs::create_dir_all(&dir).unwrap();
+    let backend = FoldBackend::with_options(revealing(), Some(dir.join("s.cborseq")));
+
+    let batch = backend.prove(&blocks, root).unwrap();
+    let streamed = StreamingProver::<FoldBackend>::default()
+        .prove_stream_with(&backend, blocks.iter().cloned().map(Ok), root)
+        .unwrap();
+    let pi = revealed_root_pi(&batch).unwrap().unwrap();
+    assert_eq!(revealed_root_pi(&streamed).unwrap(), Some(pi));
+
+    for art in [&batch, &streamed] {
+        FoldBackend::verify(art, &blocks, root).unwrap();
+
+        let mut forged = art.clone();
+        forged.meta["root_pi"]["acc"][0] = (pi.acc[0] ^ 1).into();
+        let err = FoldBackend::verify(&forged, &blocks, root).unwrap_err();
+        assert!(format!("{err:#}").contains("meta.root_pi"), "{err:#}");
+    }
+
+    // Without the option nothing is revealed, and a value added later is
+    // rejected where the proof does not reveal one to compare with.
+    let plain = FoldBackend::with_options(DriverOptions::default(), Some(dir.join("p.cborseq")));
+    let mut streamed = StreamingProver::<FoldBackend>::default()
+        .prove_stream_with(&plain, blocks.iter().cloned().map(Ok), root)
+        .unwrap();
+    assert_eq!(revealed_root_pi(&streamed).unwrap(), None);
+    streamed.meta["root_pi"] = serde_json::to_value(pi).unwrap();
+    let err = FoldBackend::verify(&streamed, &blocks, root).unwrap_err();
+    assert!(format!("{err:#}").contains("does not reveal"), "{err:#}");
+    let _ = std::fs::remove_dir_all(dir);
+}
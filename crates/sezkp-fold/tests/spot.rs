@@ -0,0 +1,101 @@
+//! Spot checks: batch bundles can embed transcript-sampled blocks with
+//! inclusion proofs to the manifest root, letting a verifier skip the
+//! blocks file.
+
+#![allow(clippy::unwrap_used)]
+
+use sezkp_core::{BlockSummary, ProvingBackend, Replay};
+use sezkp_fold::api::DriverOptions;
+use sezkp_fold::driver::run_pipeline;
+use sezkp_fold::spot::{sample_indices, verify_spot_checks};
+use sezkp_fold::{verify, CryptoFold, CryptoLeaf, CryptoWrap, FoldBackend};
+use sezkp_merkle::commit_blocks;
+use sezkp_trace::{generator::generate_trace, partition::partition_trace};
+
+fn blocks() -> Vec<BlockSummary> {
+    partition_trace(&generate_trace(128, 2), 16)
+}
+
+fn opts(blocks: &[BlockSummary], k: u32) -> DriverOptions {
+    DriverOptions {
+        manifest_root: commit_blocks(blocks).root,
+        spot_checks: k,
+        ..DriverOptions::default()
+    }
+}
+
+#[test]
+fn sampled_indices_are_distinct_and_nested() {
+    let root = [7u8; 32];
+    let few = sample_indices(&root, 12, 3);
+    let more = sample_indices(&root, 12, 6);
+    assert_eq!(few.len(), 3);
+    assert_eq!(&more[..3], &few[..]);
+    let mut all = sample_indices(&root, 12, 50);
+    all.sort_unstable();
+    assert_eq!(all, (0..12).collect::<Vec<_>>());
+    assert!(sample_indices(&root, 0, 4).is_empty());
+}
+
+#[test]
+fn bundle_spot_checks_verify_and_catch_tampering() {
+    let blocks = blocks();
+    let bundle = run_pipeline::<CryptoLeaf, CryptoFold, CryptoWrap>(&blocks, &opts(&blocks, 4));
+    verify::verify_bundle::<CryptoLeaf, CryptoFold, CryptoWrap>(&bundle).unwrap();
+    let replay = Replay::new();
+    assert_eq!(verify_spot_checks(&bundle, &replay, 4).unwrap(), 4);
+    assert_eq!(verify_spot_checks(&bundle, &replay, 2).unwrap(), 4);
+    assert!(verify_spot_checks(&bundle, &replay, 5).is_err());
+
+    // A block that is not the committed one fails its inclusion proof.
+    let mut edited = bundle.clone();
+    let check = &mut edited.spot_checks.as_mut().unwrap().checks[0];
+    check.block.ctrl_out ^= 1;
+    assert!(verify_spot_checks(&edited, &replay, 4).is_err());
+
+    // Checks must sit at the transcript-drawn indices.
+    let mut swapped = bundle.clone();
+    swapped.spot_checks.as_mut().unwrap().checks.swap(0, 1);
+    assert!(verify_spot_checks(&swapped, &replay, 4).is_err());
+
+    let mut stripped = bundle;
+    stripped.spot_checks = None;
+    assert!(verify_spot_checks(&stripped, &replay, 1).is_err());
+    assert_eq!(verify_spot_checks(&stripped, &replay, 0).unwrap(), 0);
+}
+
+#[test]
+fn small_inputs_check_every_block() {
+    let blocks = partition_trace(&generate_trace(16, 2), 8);
+    assert_eq!(blocks.len(), 2);
+    let bundle = run_pipeline::<CryptoLeaf, CryptoFold, CryptoWrap>(&blocks, &opts(&blocks, 5));
+    assert_eq!(verify_spot_checks(&bundle, &Replay::new(), 5).unwrap(), 2);
+
+    let empty = run_pipeline::<CryptoLeaf, CryptoFold, CryptoWrap>(&[], &opts(&[], 5));
+    assert_eq!(verify_spot_checks(&empty, &Replay::new(), 5).unwrap(), 0);
+}
+
+// The only test here that touches the environment.
+#[test]
+fn backend_verifies_without_blocks() {
+    let blocks = blocks();
+    let root = commit_blocks(&blocks).root;
+
+    std::env::set_var("SEZKP_FOLD_SPOT_CHECKS", "3");
+    let art = FoldBackend::prove(&blocks, root).unwrap();
+    std::env::remove_var("SEZKP_FOLD_SPOT_CHECKS");
+    assert_eq!(art.meta["spot_checks"], 3);
+
+    let replay = Replay::new();
+    assert_eq!(
+        FoldBackend::verify_spot_checked(&art, root, 3, &replay).unwrap(),
+        3
+    );
+    assert!(FoldBackend::verify_spot_checked(&art, root, 4, &replay).is_err());
+    assert!(FoldBackend::verify_spot_checked(&art, [1u8; 32], 3, &replay).is_err());
+    // The full verifier ignores the embedded blocks.
+    FoldBackend::verify(&art, &blocks, root).unwrap();
+
+    let plain = FoldBackend::prove(&blocks, root).unwrap();
+    assert!(FoldBackend::verify_spot_checked(&plain, root, 1, &replay).is_err());
+}
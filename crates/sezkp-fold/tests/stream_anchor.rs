@@ -0,0 +1,73 @@
+//! Stream digests: one hash over a finished fold stream that external
+//! systems can anchor, recorded in streaming artifacts and checked before
+//! full verification.
+
+#![allow(clippy::unwrap_used)]
+
+mod utils;
+
+use sezkp_core::{BlockSummary, ProvingBackend, ProvingBackendStream};
+use sezkp_fold::anchor::{check_stream_digest, digest_hex, parse_digest_hex, stream_digest};
+use sezkp_fold::FoldBackend;
+use sezkp_merkle::commit_blocks;
+use utils::{blocks, opts, temp_path};
+
+fn stream(blocks: &[BlockSummary], wrap_cadence: u32) -> Vec<u8> {
+    utils::stream(blocks, opts(|o| o.wrap_cadence = wrap_cadence))
+}
+
+#[test]
+fn digest_pins_the_exact_stream() {
+    let blocks = blocks();
+    let bytes = stream(&blocks, 2);
+    let d = stream_digest(bytes.as_slice()).unwrap();
+    assert_eq!(stream_digest(stream(&blocks, 2).as_slice()).unwrap(), d);
+    check_stream_digest(bytes.as_slice(), &d).unwrap();
+    assert_eq!(parse_digest_hex(&digest_hex(&d)).unwrap(), d);
+
+    // Another schedule over the same blocks is another stream.
+    let other = stream(&blocks, 4);
+    assert!(check_stream_digest(other.as_slice(), &d).is_err());
+
+    // Any flipped byte changes the digest or breaks the encoding.
+    for at in [bytes.len() / 3, bytes.len() / 2, bytes.len() - 1] {
+        let mut bad = bytes.clone();
+        bad[at] ^= 0x01;
+        assert!(check_stream_digest(bad.as_slice(), &d).is_err(), "byte {at}");
+    }
+
+    // Truncated or extended streams no longer end with one footer.
+    assert!(stream_digest(&bytes[..bytes.len() - 1]).is_err());
+    let mut longer = bytes.clone();
+    longer.extend_from_slice(&bytes[..bytes.len() / 2]);
+    assert!(stream_digest(longer.as_slice()).is_err());
+    assert!(stream_digest(&[][..]).is_err());
+}
+
+#[test]
+fn streaming_artifacts_record_and_check_the_digest() {
+    let blocks = blocks();
+    let root = commit_blocks(&blocks).root;
+    let path = temp_path("anchor");
+    std::env::set_var("SEZKP_PROOF_STREAM_PATH", &path);
+
+    let mut st = <FoldBackend as ProvingBackendStream>::begin_stream(root).unwrap();
+    for b in &blocks {
+        FoldBackend::ingest_block(&mut st, b.clone()).unwrap();
+    }
+    let art = FoldBackend::finish_stream(st).unwrap();
+    let recorded = art.meta["stream_digest"].as_str().unwrap();
+    let on_disk = std::fs::read(&path).unwrap();
+    assert_eq!(
+        recorded,
+        digest_hex(&stream_digest(on_disk.as_slice()).unwrap())
+    );
+    FoldBackend::verify(&art, &blocks, root).unwrap();
+
+    // An artifact anchored to another stream is refused up front.
+    let mut stray = art.clone();
+    stray.meta["stream_digest"] = digest_hex(&[0x5a; 32]).into();
+    let err = FoldBackend::verify(&stray, &blocks, root).unwrap_err();
+    assert!(err.to_string().contains("stream digest"), "{err:#}");
+    let _ = std::fs::remove_file(path);
+}
@@ -0,0 +1,97 @@
+//! Persistent subtree cache: replayed granules must reproduce the exact
+//! proof stream of a cold run, and damaged entries must be ignored.
+
+mod utils;
+
+use sezkp_core::BlockSummary;
+use sezkp_fold::cache::{CacheStats, SubtreeCache};
+use std::path::{Path, PathBuf};
+use utils::{blocks_of, driver, opts, verify_bytes};
+
+fn cache_dir(tag: &str) -> PathBuf {
+    utils::temp_path(&format!("cache_{tag}"))
+}
+
+/// Stream `blocks` through the sink driver; return the CBOR-seq bytes and cache stats.
+fn prove_stream(
+    blocks: &[BlockSummary],
+    cache: Option<&Path>,
+    wrap_cadence: u32,
+) -> (Vec<u8>, Option<CacheStats>) {
+    let mut out = Vec::new();
+    let mut drv = driver(&mut out, opts(|o| o.wrap_cadence = wrap_cadence));
+    if let Some(dir) = cache {
+        drv = drv.with_cache(SubtreeCache::open(dir, 4).unwrap());
+    }
+    for b in blocks {
+        drv.push_block(b.clone()).unwrap();
+    }
+    let stats = drv.cache_stats();
+    drv.finish().unwrap();
+    (out, stats)
+}
+
+#[test]
+fn cached_stream_matches_cold_stream_and_hits_on_rerun() {
+    let blocks = blocks_of(96, 4);
+    assert_eq!(blocks.len(), 24);
+    let dir = cache_dir("rerun");
+
+    for cadence in [0u32, 3] {
+        let (cold, _) = prove_stream(&blocks, None, cadence);
+        let (first, s1) = prove_stream(&blocks, Some(&dir), cadence);
+        let (second, s2) = prove_stream(&blocks, Some(&dir), cadence);
+
+        assert_eq!(first, cold, "cache-miss run must match cold run");
+        assert_eq!(second, cold, "cache-hit run must match cold run");
+        verify_bytes(&second).unwrap();
+
+        let s2 = s2.unwrap();
+        assert_eq!(s2.hits, 6);
+        assert_eq!(s2.misses, 0);
+        if cadence == 0 {
+            assert_eq!(s1.unwrap().misses, 6);
+        }
+    }
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn growing_trace_reuses_prefix_granules() {
+    let blocks = blocks_of(80, 4);
+    let dir = cache_dir("grow");
+
+    let (_, s) = prove_stream(&blocks[..8], Some(&dir), 0);
+    assert_eq!(s.unwrap().misses, 2);
+
+    // 19 blocks: 4 full granules (2 cached) + a 3-block uncached tail.
+    let (bytes, s) = prove_stream(&blocks[..19], Some(&dir), 0);
+    let s = s.unwrap();
+    assert_eq!((s.hits, s.misses), (2, 2));
+    assert_eq!(bytes, prove_stream(&blocks[..19], None, 0).0);
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn corrupted_entry_is_rejected_and_rewritten() {
+    let blocks = blocks_of(32, 4);
+    let dir = cache_dir("corrupt");
+
+    let (cold, _) = prove_stream(&blocks, Some(&dir), 0);
+    for e in std::fs::read_dir(&dir).unwrap() {
+        let p = e.unwrap().path();
+        let mut bytes = std::fs::read(&p).unwrap();
+        let mid = bytes.len() / 2;
+        bytes[mid] ^= 0x5a;
+        std::fs::write(&p, bytes).unwrap();
+    }
+
+    let (again, s) = prove_stream(&blocks, Some(&dir), 0);
+    let s = s.unwrap();
+    assert_eq!((s.hits, s.rejected), (0, 2));
+    assert_eq!(again, cold);
+
+    let (_, s) = prove_stream(&blocks, Some(&dir), 0);
+    assert_eq!(s.unwrap().hits, 2);
+    let _ = std::fs::remove_dir_all(dir);
+}
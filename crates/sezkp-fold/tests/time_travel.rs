@@ -0,0 +1,70 @@
+//! Time-travel debugging: streaming vs Balanced snapshots at block N.
+
+use sezkp_fold::api::DriverOptions;
+use sezkp_fold::debug::{
+    balanced_state_at, diff_states, render_state, streaming_state_at, StateDiff,
+};
+use sezkp_fold::driver::run_pipeline;
+use sezkp_fold::{CryptoFold, CryptoLeaf, CryptoWrap};
+use sezkp_trace::{generator::generate_trace, partition::partition_trace};
+
+#[test]
+fn power_of_two_inputs_agree_at_every_block() {
+    let blocks = partition_trace(&generate_trace(32, 2), 4);
+    assert_eq!(blocks.len(), 8);
+    for n in 0..=blocks.len() {
+        let s = streaming_state_at::<CryptoLeaf, CryptoFold, CryptoWrap>(
+            &blocks,
+            n,
+            DriverOptions::default(),
+        )
+        .unwrap();
+        let b = balanced_state_at::<CryptoLeaf, CryptoFold>(&blocks, n).unwrap();
+        assert!(
+            diff_states(&s, &b).is_empty(),
+            "n={n}:\n{}",
+            render_state(&s)
+        );
+    }
+}
+
+#[test]
+fn uneven_inputs_show_shape_divergence() {
+    let blocks = partition_trace(&generate_trace(24, 2), 4);
+    assert_eq!(blocks.len(), 6);
+
+    // Balanced splits [0,6) as [0,3)+[3,6); streaming builds [0,2)+[2,3) first.
+    let s = streaming_state_at::<CryptoLeaf, CryptoFold, CryptoWrap>(
+        &blocks,
+        3,
+        DriverOptions::default(),
+    )
+    .unwrap();
+    let b = balanced_state_at::<CryptoLeaf, CryptoFold>(&blocks, 3).unwrap();
+    assert_eq!(
+        diff_states(&s, &b)[0],
+        StateDiff::Span {
+            pos: 0,
+            stream: (0, 2),
+            batch: (0, 3)
+        }
+    );
+    assert!(streaming_state_at::<CryptoLeaf, CryptoFold, CryptoWrap>(
+        &blocks,
+        7,
+        DriverOptions::default()
+    )
+    .is_err());
+}
+
+#[test]
+fn balanced_snapshot_at_end_is_pipeline_root() {
+    let blocks = partition_trace(&generate_trace(40, 2), 4);
+    let b = balanced_state_at::<CryptoLeaf, CryptoFold>(&blocks, blocks.len()).unwrap();
+    assert_eq!(b.len(), 1);
+    let bundle =
+        run_pipeline::<CryptoLeaf, CryptoFold, CryptoWrap>(&blocks, &DriverOptions::default());
+    let ((c, pi), _, _, _) = bundle.folds.last().unwrap();
+    assert_eq!((b[0].c, b[0].pi), (*c, *pi));
+    assert_eq!((b[0].lo, b[0].hi), (0, blocks.len() as u32));
+}
@@ -0,0 +1,120 @@
+//! Shared fixtures for the fold tests: synthetic blocks, driver options and
+//! the streaming driver round trip most tests start from. Test files pull
+//! them in with `mod utils;`.
+
+#![allow(dead_code, clippy::unwrap_used)]
+
+use std::path::PathBuf;
+
+use ciborium::value::Value;
+use sezkp_core::BlockSummary;
+use sezkp_fold::api::DriverOptions;
+use sezkp_fold::driver::{CborSeqSink, StreamDriverSink, StreamFooter, StreamHeader};
+use sezkp_fold::{verify, CryptoFold, CryptoLeaf, CryptoWrap};
+use sezkp_trace::{generator::generate_trace, partition::partition_trace};
+
+/// The streaming driver the tests run, writing its CBOR sequence to `out`.
+pub type Driver<'a> =
+    StreamDriverSink<CryptoLeaf, CryptoFold, CryptoWrap, CborSeqSink<&'a mut Vec<u8>>>;
+
+/// A `steps`-step trace on two work tapes, cut into blocks of `b` steps.
+pub fn blocks_of(steps: u64, b: u32) -> Vec<BlockSummary> {
+    partition_trace(&generate_trace(steps, 2), b)
+}
+
+/// The usual fixture: 64 steps in 16 blocks of 4.
+pub fn blocks() -> Vec<BlockSummary> {
+    blocks_of(64, 4)
+}
+
+/// Default driver options with `edit` applied.
+pub fn opts(edit: impl FnOnce(&mut DriverOptions)) -> DriverOptions {
+    let mut o = DriverOptions::default();
+    edit(&mut o);
+    o
+}
+
+/// A streaming driver with `opts`, writing to `out`.
+pub fn driver(out: &mut Vec<u8>, opts: DriverOptions) -> Driver<'_> {
+    StreamDriverSink::new(CborSeqSink::new(out), opts).unwrap()
+}
+
+/// Stream `blocks` with `opts`; returns the CBOR sequence.
+pub fn stream(blocks: &[BlockSummary], opts: DriverOptions) -> Vec<u8> {
+    stream_with(blocks, opts, |d| d)
+}
+
+/// [`stream`], letting `setup` configure the driver (a cache, an expected
+/// root, …) before the first block.
+pub fn stream_with(
+    blocks: &[BlockSummary],
+    opts: DriverOptions,
+    setup: impl FnOnce(Driver<'_>) -> Driver<'_>,
+) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut drv = setup(driver(&mut out, opts));
+    for b in blocks {
+        drv.push_block(b.clone()).unwrap();
+    }
+    drv.finish().unwrap();
+    out
+}
+
+/// Split a CBOR sequence into its values.
+pub fn values(bytes: &[u8]) -> Vec<Value> {
+    let mut rd = bytes;
+    let mut values = Vec::new();
+    while !rd.is_empty() {
+        values.push(ciborium::de::from_reader(&mut rd).unwrap());
+    }
+    values
+}
+
+/// Encode `values` as a CBOR sequence.
+pub fn encode(values: &[Value]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for v in values {
+        ciborium::ser::into_writer(v, &mut out).unwrap();
+    }
+    out
+}
+
+/// Verify a proof stream.
+pub fn verify_bytes(bytes: &[u8]) -> anyhow::Result<StreamFooter> {
+    verify::verify_stream::<CryptoLeaf, CryptoFold, CryptoWrap, _>(bytes)
+}
+
+/// Verify a proof stream given as its values.
+pub fn verify_values(values: &[Value]) -> anyhow::Result<StreamFooter> {
+    verify_bytes(&encode(values))
+}
+
+/// Decode a stream, let `edit` change its header and footer, re-encode.
+pub fn rewrite(bytes: &[u8], edit: impl FnOnce(&mut StreamHeader, &mut StreamFooter)) -> Vec<u8> {
+    let mut values = values(bytes);
+    rewrite_values(&mut values, edit);
+    encode(&values)
+}
+
+/// [`rewrite`] on a stream already split into its values.
+pub fn rewrite_values(
+    values: &mut [Value],
+    edit: impl FnOnce(&mut StreamHeader, &mut StreamFooter),
+) {
+    let last = values.len() - 1;
+    let mut h: StreamHeader = values[0].deserialized().unwrap();
+    let mut f: StreamFooter = values[last].deserialized().unwrap();
+    edit(&mut h, &mut f);
+    values[0] = Value::serialized(&h).unwrap();
+    values[last] = Value::serialized(&f).unwrap();
+}
+
+/// A fresh temp path for `tag`, unique to this process; anything already
+/// there is removed.
+pub fn temp_path(tag: &str) -> PathBuf {
+    let mut p = std::env::temp_dir();
+    p.push(format!("sezkp_fold_{tag}_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&p);
+    let _ = std::fs::remove_file(&p);
+    p
+}
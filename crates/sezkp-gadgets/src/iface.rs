@@ -0,0 +1,60 @@
+//! **AreIfaceAir** — binds the **interface** between siblings using only
+//! public bits exported by leaves (no reaccess to blocks).
+
+use blake3::Hasher;
+use serde::{Deserialize, Serialize};
+
+/// Domain separator for the ARE interface micro-proof.
+pub const DS_ARE_V2: &str = "stark/are_iface/v2";
+
+/// Public bits exported by a leaf/subtree for the interface check.
+/// We re-use a single struct; `prove_iface_replay` will consume:
+///   - from `li`: `r_head_prefix`, `ctrl_out`
+///   - from `ri`: `l_tail_prefix`, `ctrl_in`
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LeafIfacePublic {
+    pub l_tail_prefix: [u64; 2],
+    pub r_head_prefix: [u64; 2],
+    pub ctrl_out: u32,
+    pub ctrl_in: u32,
+}
+
+/// Minimal ARE proof object (MAC over the public tuple).
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AreProofStark {
+    pub mac: [u8; 32],
+}
+
+/// MAC over exactly the tuple the verifier checks, under [`DS_ARE_V2`].
+fn mac(li: &LeafIfacePublic, ri: &LeafIfacePublic) -> [u8; 32] {
+    let mut h = Hasher::new();
+    h.update(DS_ARE_V2.as_bytes());
+    for x in li.r_head_prefix {
+        h.update(&x.to_le_bytes());
+    }
+    h.update(&li.ctrl_out.to_le_bytes());
+    for x in ri.l_tail_prefix {
+        h.update(&x.to_le_bytes());
+    }
+    h.update(&ri.ctrl_in.to_le_bytes());
+    *h.finalize().as_bytes()
+}
+
+/// Prove the sibling interface from public leaf/subtree views.
+///
+/// # Errors
+/// Never at present; kept fallible for the micro-STARK upgrade.
+#[allow(clippy::unnecessary_wraps)]
+pub fn prove_iface_replay(
+    li: &LeafIfacePublic,
+    ri: &LeafIfacePublic,
+) -> anyhow::Result<AreProofStark> {
+    Ok(AreProofStark { mac: mac(li, ri) })
+}
+
+/// Verify the sibling interface proof.
+#[must_use]
+pub fn verify_iface_replay(li: &LeafIfacePublic, ri: &LeafIfacePublic, p: &AreProofStark) -> bool {
+    // Enforce control continuity at verification time.
+    li.ctrl_out == ri.ctrl_in && p.mac == mac(li, ri)
+}
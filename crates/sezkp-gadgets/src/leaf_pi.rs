@@ -0,0 +1,88 @@
+//! **LeafPiAir** — binds a leaf's π capsule to its per-block boundary
+//! digests, exposing each digest in the public inputs.
+
+use blake3::Hasher;
+use serde::{Deserialize, Serialize};
+use sezkp_core::boundary::{
+    boundary_left_tail_digest, boundary_right_head_digest, IFACE_WINDOW_STEPS,
+};
+
+/// Domain separator for the Leaf π micro-proof.
+pub const DS_LEAF_PI_V1: &str = "stark/leaf_pi/v1";
+
+/// Public inputs for `LeafPiAir`.
+///
+/// NOTE: `acc_limbs` **exposes each digest** as two u64 limbs (LSB-first):
+///   `acc_limbs = [L_tail[0..8], L_tail[8..16], R_head[0..8], R_head[8..16]]`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PiPublic {
+    pub ctrl_in: u32,
+    pub ctrl_out: u32,
+    pub flags: u32,
+    pub acc_limbs: [u64; 4],
+    pub left_tail_digest: [u8; 32],
+    pub right_head_digest: [u8; 32],
+}
+
+/// Minimal proof object (MAC over the public inputs).
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StarkProofV1 {
+    pub mac: [u8; 32],
+}
+
+/// Helper: pack boundary digests as 4 limbs (two limbs per digest, LSB-first).
+#[must_use]
+pub fn pack_boundary_limbs(left: [u8; 32], right: [u8; 32]) -> [u64; 4] {
+    let get2 = |d: [u8; 32]| -> [u64; 2] {
+        let mut a = [0u8; 8];
+        let mut b = [0u8; 8];
+        a.copy_from_slice(&d[0..8]);
+        b.copy_from_slice(&d[8..16]);
+        [u64::from_le_bytes(a), u64::from_le_bytes(b)]
+    };
+    let l2 = get2(left);
+    let r2 = get2(right);
+    [l2[0], l2[1], r2[0], r2[1]]
+}
+
+/// MAC over the public inputs under [`DS_LEAF_PI_V1`].
+fn mac(public: &PiPublic) -> [u8; 32] {
+    let mut h = Hasher::new();
+    h.update(DS_LEAF_PI_V1.as_bytes());
+    h.update(&public.ctrl_in.to_le_bytes());
+    h.update(&public.ctrl_out.to_le_bytes());
+    h.update(&public.flags.to_le_bytes());
+    for limb in public.acc_limbs {
+        h.update(&limb.to_le_bytes());
+    }
+    h.update(&public.left_tail_digest);
+    h.update(&public.right_head_digest);
+    *h.finalize().as_bytes()
+}
+
+/// Produce a public view and a compact commitment (MAC) to it.
+///
+/// # Errors
+/// Never at present; kept fallible for the micro-STARK upgrade.
+#[allow(clippy::unnecessary_wraps)]
+pub fn prove_leaf_pi(block: &sezkp_core::BlockSummary) -> anyhow::Result<(PiPublic, StarkProofV1)> {
+    let l_tail = boundary_left_tail_digest(block, IFACE_WINDOW_STEPS);
+    let r_head = boundary_right_head_digest(block, IFACE_WINDOW_STEPS);
+
+    let public = PiPublic {
+        ctrl_in: 0,
+        ctrl_out: 0,
+        flags: 1, // leaf-present + boundary-packed
+        acc_limbs: pack_boundary_limbs(l_tail, r_head),
+        left_tail_digest: l_tail,
+        right_head_digest: r_head,
+    };
+    let proof = StarkProofV1 { mac: mac(&public) };
+    Ok((public, proof))
+}
+
+/// Verify the compact commitment (MAC) against the public inputs.
+#[must_use]
+pub fn verify_leaf_pi(public: &PiPublic, proof: &StarkProofV1) -> bool {
+    proof.mac == mac(public)
+}
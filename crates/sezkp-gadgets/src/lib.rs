@@ -0,0 +1,33 @@
+//! Micro-proofs and the field shared by the STARK v1 and fold backends.
+//!
+//! The fold line proves each leaf's π capsule and each sibling interface with
+//! small gadgets that need nothing of the STARK prover: the field, the block
+//! boundary digests of [`sezkp_core::boundary`] and a hash. They live here so
+//! that `sezkp-fold` does not depend on `sezkp-stark`, which re-exports them
+//! from `v1::field` and `v1::air` unchanged.
+//!
+//! - [`F1`] is the Goldilocks field both backends compute in.
+//! - [`leaf_pi`] binds a leaf's π capsule to its block's boundary digests.
+//! - [`iface`] binds the interface between siblings using only the public
+//!   bits their leaves export.
+//!
+//! NOTE: Each proof is a compact commitment (MAC). Upgrading to a
+//! micro-STARK keeps the same public input structs and helper functions.
+
+#![forbid(unsafe_code)]
+#![deny(rust_2018_idioms)]
+#![warn(
+    clippy::all,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::unwrap_used,
+    clippy::expect_used
+)]
+
+/// Interface micro-proof between sibling leaves/subtrees.
+pub mod iface;
+/// Leaf π micro-proof over a block's boundary digests.
+pub mod leaf_pi;
+
+/// Field used by STARK v1 and the fold line (Goldilocks from `sezkp-ffts`).
+pub use sezkp_ffts::Goldilocks as F1;
@@ -0,0 +1,116 @@
+//! Differential checks across the workspace's Merkle tree implementations.
+//!
+//! Four code paths commit to the same left-balanced, odd-promotion BLAKE3
+//! tree and must agree root-for-root:
+//! - [`sezkp_merkle::merkle_root`] (batch, manifests),
+//! - [`sezkp_core::frontier::MerkleFrontier`] (streaming, shared),
+//! - `sezkp_stark::v1::merkle::MerkleTree` (batch, FRI layers and chunks),
+//! - `sezkp_stark::v1::fri_stream::StreamingLayerBuilder` (streaming FRI
+//!   layer 0, hashing 8-byte field encodings itself).
+//!
+//! FRI layer trees hash their inner nodes in a node domain of their own, so
+//! the last two are also checked against each other with that domain.
+//!
+//! The checks are plain functions so the property tests in `tests/` and an
+//! external fuzzer share them; a `cargo fuzz` target is one line:
+//! `fuzz_target!(|data: &[u8]| sezkp_merkle_fuzz::fuzz_one(data).unwrap());`
+
+#![forbid(unsafe_code)]
+#![deny(rust_2018_idioms)]
+#![warn(
+    missing_docs,
+    clippy::all,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::unwrap_used,
+    clippy::expect_used
+)]
+
+use anyhow::{ensure, Result};
+use sezkp_core::frontier::MerkleFrontier;
+use sezkp_stark::v1::{
+    fri_stream::StreamingLayerBuilder,
+    merkle::{fri_layer_tree, hash_fri_leaves, MerkleTree, FRI_NODE_DOMAIN},
+};
+
+/// Root of `leaves` through the shared streaming frontier.
+#[must_use]
+pub fn frontier_root(leaves: &[[u8; 32]]) -> [u8; 32] {
+    frontier_root_in(MerkleFrontier::new(), leaves)
+}
+
+fn frontier_root_in(mut f: MerkleFrontier, leaves: &[[u8; 32]]) -> [u8; 32] {
+    for l in leaves {
+        f.push(*l);
+    }
+    f.root()
+}
+
+/// Check that every implementation over 32-byte leaves yields one root.
+///
+/// # Errors
+/// Names the first implementation that disagrees with the batch root.
+pub fn check_hashed_leaves(leaves: &[[u8; 32]]) -> Result<()> {
+    let n = leaves.len();
+    let batch = sezkp_merkle::merkle_root(leaves.to_vec());
+    ensure!(
+        frontier_root(leaves) == batch,
+        "MerkleFrontier disagrees with merkle_root for {n} leaves"
+    );
+    ensure!(
+        MerkleTree::from_leaves(leaves).root() == batch,
+        "stark MerkleTree disagrees with merkle_root for {n} leaves"
+    );
+    Ok(())
+}
+
+/// Check the streaming FRI layer builder over field encodings `values`,
+/// absorbed in chunks of `chunk` values, against the batch trees.
+///
+/// # Errors
+/// Fails if the streamed root differs from the batch FRI tree over the same
+/// values, if the batch FRI tree differs from the frontier in the FRI node
+/// domain, or if the FRI leaves fail [`check_hashed_leaves`].
+pub fn check_field_layer(values: &[[u8; 8]], chunk: usize) -> Result<()> {
+    let leaves = hash_fri_leaves(values);
+    check_hashed_leaves(&leaves)?;
+
+    let batch = fri_layer_tree(values).root();
+    ensure!(
+        frontier_root_in(MerkleFrontier::with_node_domain(FRI_NODE_DOMAIN), &leaves) == batch,
+        "FRI MerkleTree disagrees with MerkleFrontier for {} values",
+        values.len()
+    );
+    let mut b = StreamingLayerBuilder::new(values.len());
+    for c in values.chunks(chunk.max(1)) {
+        b.absorb_leaves(c);
+    }
+    ensure!(
+        b.finalize() == batch,
+        "StreamingLayerBuilder disagrees with MerkleTree for {} values (chunk {chunk})",
+        values.len()
+    );
+    Ok(())
+}
+
+/// Fuzz entry point: arbitrary bytes → one layer check.
+///
+/// The first byte picks the absorb chunk size; the rest is split into 8-byte
+/// field encodings (a short tail is zero-padded).
+///
+/// # Errors
+/// Propagates any disagreement found by [`check_field_layer`].
+pub fn fuzz_one(data: &[u8]) -> Result<()> {
+    let Some((&chunk, rest)) = data.split_first() else {
+        return check_field_layer(&[], 1);
+    };
+    let values: Vec<[u8; 8]> = rest
+        .chunks(8)
+        .map(|c| {
+            let mut v = [0u8; 8];
+            v[..c.len()].copy_from_slice(c);
+            v
+        })
+        .collect();
+    check_field_layer(&values, usize::from(chunk))
+}
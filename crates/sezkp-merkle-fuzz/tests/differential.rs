@@ -0,0 +1,63 @@
+//! Property tests: all Merkle implementations agree on random inputs.
+
+#![allow(clippy::unwrap_used)]
+
+use proptest::prelude::*;
+use sezkp_merkle_fuzz::{check_field_layer, check_hashed_leaves, fuzz_one};
+
+fn leaves(n: usize, seed: u8) -> Vec<[u8; 32]> {
+    (0..n)
+        .map(|i| {
+            let mut l = [seed; 32];
+            l[..8].copy_from_slice(&(i as u64).to_le_bytes());
+            l
+        })
+        .collect()
+}
+
+#[test]
+fn every_size_up_to_300() {
+    // Exhaustive small sizes hit every promotion pattern up to 2^8 + 44.
+    for n in 0..=300 {
+        check_hashed_leaves(&leaves(n, 7)).unwrap();
+    }
+}
+
+#[test]
+fn field_layer_sizes_and_chunkings() {
+    for n in [0usize, 1, 2, 3, 5, 7, 8, 9, 63, 64, 65, 100, 255, 256, 257] {
+        let values: Vec<[u8; 8]> = (0..n as u64).map(u64::to_le_bytes).collect();
+        for chunk in [1usize, 3, 8, 1000] {
+            check_field_layer(&values, chunk).unwrap();
+        }
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        cases: 128,
+        .. ProptestConfig::default()
+    })]
+
+    #[test]
+    fn random_hashed_leaves_agree(
+        ls in prop::collection::vec(any::<[u8; 32]>(), 0..400),
+    ) {
+        prop_assert!(check_hashed_leaves(&ls).is_ok());
+    }
+
+    #[test]
+    fn random_field_layers_agree(
+        values in prop::collection::vec(any::<[u8; 8]>(), 0..400),
+        chunk in 1usize..64,
+    ) {
+        prop_assert!(check_field_layer(&values, chunk).is_ok());
+    }
+
+    #[test]
+    fn fuzz_entry_accepts_arbitrary_bytes(
+        data in prop::collection::vec(any::<u8>(), 0..2048),
+    ) {
+        prop_assert!(fuzz_one(&data).is_ok());
+    }
+}
@@ -7,9 +7,19 @@
 //! - A **canonical leaf hash** (exported) used consistently across the
 //!   workspace. Other components (e.g., folding leaf gadget) must bind to the
 //!   same byte layout to remain compatible.
-//! - A small [`CommitManifest`] containing `{root, n_leaves, version}`.
+//! - A small [`CommitManifest`] containing `{root, n_leaves, version}` plus
+//!   (since v2) the trace shape `{total_steps, tau}`, the leaf-schema version,
+//!   the hash scheme, and optional creator metadata.
 //! - Helpers to commit blocks from disk (JSON/CBOR/JSONL), validate a blocks
 //!   file against a manifest, and read/write manifests in **JSON** or **CBOR**.
+//! - Leaf [`InclusionProof`]s, exportable in a self-describing JSON/CBOR
+//!   schema ([`portable`]) that other languages can verify.
+//! - [`commit_trace_file`], which partitions a `sezkp-trace` trace and hashes
+//!   each block as it is produced, so no blocks file is ever written.
+//! - With the `parallel` feature, leaf hashing on the thread pool configured
+//!   in [`sezkp_core::parallelism`] ([`commit_blocks_parallel`],
+//!   [`ManifestBuilder::push_all`] and the file commit helpers); the tree is
+//!   still built in order, so roots are byte-identical.
 //!
 //! ## Canonical leaf schema (v1)
 //! The leaf hash is `BLAKE3` over raw little-endian fields in this order:
@@ -32,9 +42,42 @@
 //!   carries the blocks includes them. If you change what the leaf hash binds,
 //!   you must bump the manifest schema version.
 //!
+//! ## Leaf schema v2 (redacted blocks, auxiliary commitments)
+//! Blocks exported with their written symbols redacted (`version ==`
+//! [`sezkp_core::redact::REDACTED_BLOCK_VERSION`]) hash the v1 fields above
+//! followed by
+//!
+//! 13. `write_commitments.len(): u64`, then each 32-byte commitment,
+//!
+//! so the root binds the hidden symbols. Blocks carrying VM-adapter
+//! commitments ([`sezkp_core::auxiliary`]) then append
+//!
+//! 14. `aux_commitments.len(): u64`, then each 32-byte commitment
+//!
+//! (after the v1 fields when the block is not redacted). Other blocks keep
+//! the v1 layout; a manifest records `leaf_schema = 2` as soon as any of its
+//! blocks is redacted or carries auxiliary commitments.
+//!
+//! ## Manifest versions
+//! - **v1**: `{version, root, n_leaves}`.
+//! - **v2**: adds `total_steps`, `tau`, `leaf_schema`, `hash_scheme` and an
+//!   optional `creator`. All additions are `#[serde(default)]`, so v1 files
+//!   still decode (with `total_steps = tau = 0` meaning "not recorded") and the
+//!   shape checks below are skipped for them.
+//! - An optional `input_len` (declared input tape length) may accompany any
+//!   version. Like `creator` it is not bound by the root.
+//! - A v2 manifest may carry `checkpoints` ([`SubtreeRoots`]): the root of
+//!   every aligned run of `2^k` leaves. These are exactly the level-`k` nodes
+//!   of the tree, so they must fold back into `root`. Verification compares
+//!   them as it streams and names the first run that diverges instead of
+//!   only reporting a final root mismatch.
+//!
 //! ## Merkle tree shape
 //! - Odd leaves are **promoted** at each level (left-balanced tree). We do not
 //!   duplicate the last leaf. This choice is deterministic and tested here.
+//! - The streaming paths use the shared [`MerkleFrontier`]; the
+//!   `sezkp-merkle-fuzz` crate differentially tests it against
+//!   [`merkle_root`] and the STARK backend's trees.
 
 #![forbid(unsafe_code)]
 #![deny(rust_2018_idioms)]
@@ -50,19 +93,87 @@
 use anyhow::{anyhow, Context, Result};
 use blake3::Hasher;
 use serde::{Deserialize, Serialize};
-use sezkp_core::{io as core_io, BlockSummary};
-use sezkp_core::io_jsonl::stream_block_summaries_jsonl;
+use sezkp_core::frontier::MerkleFrontier;
+use sezkp_core::progress::ProgressTracker;
+use sezkp_core::{io as core_io, BlockSummary, EMPTY_ROOT};
+use sezkp_core::io_format::{resolve_read_format, resolve_write_format, FileFormat};
+use sezkp_core::render::render_root;
+use sezkp_trace::format::TraceFile;
+use sezkp_trace::partition::{partition_iter, PartitionStrategy};
 use std::fs::File;
 use std::io::{BufReader, BufWriter, Write};
 use std::path::Path;
 
+/// Inclusion proofs in a documented JSON/CBOR exchange schema.
+pub mod portable;
+
 /// Format version for the current `CommitManifest` wire schema.
-pub const MANIFEST_VERSION: u32 = 1;
+pub const MANIFEST_VERSION: u32 = 2;
+
+/// Version of the canonical leaf byte layout hashed by [`leaf_hash`].
+pub const LEAF_SCHEMA_VERSION: u32 = 1;
+
+/// Leaf layout of files containing redacted blocks or auxiliary commitments
+/// (v1 plus those commitments; see the module docs).
+pub const LEAF_SCHEMA_REDACTED: u32 = 2;
+
+/// Blocks read and hashed together by the file commit helpers.
+const COMMIT_CHUNK: usize = 4096;
+
+/// Identifier of the leaf/node hash used by this crate.
+pub const HASH_SCHEME: &str = "blake3";
+
+/// Optional provenance recorded by whoever produced the manifest.
+///
+/// Purely informational: it is not bound by `root` and verifiers ignore it.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CreatorInfo {
+    /// Producing tool (e.g. `"sezkp-cli"`).
+    pub tool: String,
+    /// Producing tool version.
+    pub tool_version: String,
+    /// Free-form note supplied by the operator.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// Intermediate roots recorded in a manifest (see the module docs).
+///
+/// `roots[i]` is the Merkle root of leaves `[i·2^k, (i+1)·2^k)`, the last run
+/// possibly shorter, with `k = span_log2`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SubtreeRoots {
+    /// `k`: each root covers `2^k` leaves.
+    pub span_log2: u32,
+    /// One root per run, left to right.
+    pub roots: Vec<[u8; 32]>,
+}
+
+/// Largest accepted [`SubtreeRoots::span_log2`].
+pub const MAX_CHECKPOINT_LOG2: u32 = 31;
+
+impl SubtreeRoots {
+    /// Leaves per run.
+    #[must_use]
+    pub const fn span(&self) -> u64 {
+        1 << self.span_log2
+    }
+
+    /// Leaf range `[lo, hi)` covered by run `i` of a tree with `n_leaves`.
+    #[must_use]
+    pub fn range(&self, i: usize, n_leaves: u64) -> (u64, u64) {
+        let lo = (i as u64) << self.span_log2;
+        (lo, (lo + self.span()).min(n_leaves.max(lo)))
+    }
+}
 
 /// Compact commitment over a set of `BlockSummary` leaves.
 ///
 /// This is the object typically written to disk and consumed by other
 /// subsystems (e.g., the folding line) to bind to a specific blocks file.
+///
+/// The manifest of an **empty** blocks file is valid: `n_leaves == 0`,
+/// `root ==` [`EMPTY_ROOT`], and (v2) `total_steps == tau == 0`.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct CommitManifest {
     /// Schema/encoding version of this manifest (see [`MANIFEST_VERSION`]).
@@ -71,18 +182,342 @@ pub struct CommitManifest {
     pub root: [u8; 32],
     /// Number of leaves (blocks) bound by `root`.
     pub n_leaves: u32,
+    /// Total replay steps across all blocks (v2+; `0` when not recorded).
+    #[serde(default)]
+    pub total_steps: u64,
+    /// Number of work tapes τ (v2+; `0` when not recorded).
+    #[serde(default)]
+    pub tau: u32,
+    /// Leaf byte-layout version (see [`LEAF_SCHEMA_VERSION`]).
+    #[serde(default = "default_leaf_schema")]
+    pub leaf_schema: u32,
+    /// Hash scheme used for leaves and nodes (see [`HASH_SCHEME`]).
+    #[serde(default = "default_hash_scheme")]
+    pub hash_scheme: String,
+    /// Optional provenance metadata (not bound by `root`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub creator: Option<CreatorInfo>,
+    /// Declared input tape length (not bound by `root`). Replay keeps the
+    /// input head within `[0, input_len)` when proving against this manifest.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_len: Option<u64>,
+    /// Optional intermediate subtree roots (v2+), checked against `root`
+    /// and used to locate a mismatch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkpoints: Option<SubtreeRoots>,
+}
+
+const fn default_leaf_schema() -> u32 {
+    LEAF_SCHEMA_VERSION
+}
+
+fn default_hash_scheme() -> String {
+    HASH_SCHEME.to_owned()
+}
+
+impl CommitManifest {
+    /// Whether this manifest commits to zero blocks.
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.n_leaves == 0
+    }
+
+    /// Check internal consistency: the root is [`EMPTY_ROOT`] exactly when
+    /// there are no leaves, an empty v2 manifest records no steps or tapes,
+    /// and checkpoints, if any, cover every leaf and fold into the root.
+    ///
+    /// # Errors
+    /// Returns an error describing the first inconsistency found.
+    pub fn check_well_formed(&self) -> Result<()> {
+        sezkp_core::check_empty_root(self.is_empty(), &self.root)
+            .with_context(|| format!("malformed manifest ({} leaves)", self.n_leaves))?;
+        if self.is_empty() && self.has_shape() && (self.total_steps != 0 || self.tau != 0) {
+            anyhow::bail!(
+                "malformed manifest: no leaves but steps={} tau={}",
+                self.total_steps,
+                self.tau
+            );
+        }
+        if let Some(cp) = &self.checkpoints {
+            if cp.span_log2 > MAX_CHECKPOINT_LOG2 {
+                anyhow::bail!(
+                    "malformed manifest: checkpoint span 2^{} exceeds 2^{MAX_CHECKPOINT_LOG2}",
+                    cp.span_log2
+                );
+            }
+            let want = u64::from(self.n_leaves).div_ceil(cp.span());
+            if cp.roots.len() as u64 != want {
+                anyhow::bail!(
+                    "malformed manifest: {} checkpoints for {} leaves (expected {want})",
+                    cp.roots.len(),
+                    self.n_leaves
+                );
+            }
+            if merkle_root(cp.roots.clone()) != self.root {
+                anyhow::bail!("malformed manifest: checkpoints do not fold into the root");
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether this manifest records the trace shape (`total_steps`, `tau`).
+    #[inline]
+    #[must_use]
+    pub const fn has_shape(&self) -> bool {
+        self.version >= 2
+    }
+
+    /// Attach creator metadata (builder style).
+    #[must_use]
+    pub fn with_creator(mut self, creator: CreatorInfo) -> Self {
+        self.creator = Some(creator);
+        self
+    }
+
+    /// Declare the input tape length (builder style).
+    #[must_use]
+    pub const fn with_input_len(mut self, input_len: u64) -> Self {
+        self.input_len = Some(input_len);
+        self
+    }
+
+    /// Check that this manifest was produced with the leaf schema and hash
+    /// scheme this crate implements.
+    ///
+    /// # Errors
+    /// Returns an error for a newer manifest version, another leaf schema, or
+    /// another hash scheme.
+    pub fn check_compatible(&self) -> Result<()> {
+        if self.version > MANIFEST_VERSION {
+            anyhow::bail!(
+                "unsupported manifest version {} (this build understands up to {})",
+                self.version,
+                MANIFEST_VERSION
+            );
+        }
+        if self.leaf_schema != LEAF_SCHEMA_VERSION && self.leaf_schema != LEAF_SCHEMA_REDACTED {
+            anyhow::bail!(
+                "unsupported leaf schema v{} (expected v{} or v{})",
+                self.leaf_schema,
+                LEAF_SCHEMA_VERSION,
+                LEAF_SCHEMA_REDACTED
+            );
+        }
+        if self.hash_scheme != HASH_SCHEME {
+            anyhow::bail!(
+                "unsupported hash scheme {:?} (expected {:?})",
+                self.hash_scheme,
+                HASH_SCHEME
+            );
+        }
+        Ok(())
+    }
+
+    /// Encode the manifest as CBOR: the exact bytes [`write_manifest_cbor`]
+    /// writes, for callers (the FFI bindings) that store blobs themselves.
+    ///
+    /// # Errors
+    /// Fails only if encoding fails.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        core_io::to_cbor(self).context("encoding manifest")
+    }
+
+    /// Decode a manifest from the bytes of a `.cbor` manifest file. Nothing
+    /// is checked beyond the encoding; see [`Self::check_well_formed`] and
+    /// [`Self::check_compatible`].
+    ///
+    /// # Errors
+    /// Fails on malformed CBOR.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        core_io::from_cbor(bytes).context("decoding manifest")
+    }
+
+    /// One-line human-friendly summary.
+    #[must_use]
+    pub fn summary(&self) -> String {
+        use std::fmt::Write as _;
+        let mut s = format!(
+            "manifest v{}: root={} leaves={}",
+            self.version,
+            render_root(&self.root),
+            self.n_leaves
+        );
+        if self.has_shape() {
+            let _ = write!(s, " steps={} tau={}", self.total_steps, self.tau);
+        }
+        let _ = write!(
+            s,
+            " leaf_schema=v{} hash={}",
+            self.leaf_schema, self.hash_scheme
+        );
+        if let Some(n) = self.input_len {
+            let _ = write!(s, " input_len={n}");
+        }
+        if let Some(cp) = &self.checkpoints {
+            let _ = write!(s, " checkpoints={}x2^{}", cp.roots.len(), cp.span_log2);
+        }
+        if let Some(c) = &self.creator {
+            let _ = write!(s, " creator={} {}", c.tool, c.tool_version);
+        }
+        s
+    }
+}
+
+/// Incremental manifest builder.
+///
+/// Push blocks left→right; memory stays `O(log n)` (a Merkle frontier plus a
+/// few counters), so this is what the streaming commit path uses.
+#[derive(Default)]
+pub struct ManifestBuilder {
+    frontier: MerkleFrontier,
+    n_leaves: u32,
+    total_steps: u64,
+    tau: Option<u32>,
+    leaf_schema: u32,
+    checkpoints: Option<RunFrontier>,
+    checkpoint_roots: Vec<[u8; 32]>,
+}
+
+impl ManifestBuilder {
+    /// Fresh builder.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also record the root of every aligned run of `2^span_log2` leaves
+    /// (builder style; see [`SubtreeRoots`]).
+    ///
+    /// # Errors
+    /// Returns an error if `span_log2` exceeds [`MAX_CHECKPOINT_LOG2`].
+    pub fn with_checkpoints(mut self, span_log2: u32) -> Result<Self> {
+        if span_log2 > MAX_CHECKPOINT_LOG2 {
+            return Err(anyhow!(
+                "checkpoint span 2^{span_log2} exceeds 2^{MAX_CHECKPOINT_LOG2}"
+            ));
+        }
+        self.checkpoints = Some(RunFrontier::new(span_log2));
+        Ok(self)
+    }
+
+    /// Absorb the next block.
+    pub fn push(&mut self, b: &BlockSummary) {
+        self.push_hashed(b, leaf_hash(b));
+    }
+
+    /// Absorb `blocks` in order. With the `parallel` feature their leaves
+    /// are hashed on the rayon pool first; the result is the same as
+    /// pushing them one by one.
+    pub fn push_all(&mut self, blocks: &[BlockSummary]) {
+        for (b, leaf) in blocks.iter().zip(leaf_hashes(blocks)) {
+            self.push_hashed(b, leaf);
+        }
+    }
+
+    /// [`Self::push`] with the block's leaf hash already computed.
+    fn push_hashed(&mut self, b: &BlockSummary, leaf: [u8; 32]) {
+        self.frontier.push(leaf);
+        if let Some(run) = self.checkpoints.as_mut().and_then(|c| c.push(leaf)) {
+            self.checkpoint_roots.push(run);
+        }
+        self.n_leaves = self.n_leaves.saturating_add(1);
+        self.total_steps = self
+            .total_steps
+            .saturating_add(b.movement_log.steps.len() as u64);
+        if self.tau.is_none() {
+            self.tau = Some(u32::try_from(b.windows.len()).unwrap_or(u32::MAX));
+        }
+        self.leaf_schema = self.leaf_schema.max(leaf_schema_of(b));
+    }
+
+    /// Finish and return the manifest.
+    #[must_use]
+    pub fn finish(mut self) -> CommitManifest {
+        let checkpoints = self.checkpoints.take().map(|c| {
+            let span_log2 = c.span_log2;
+            self.checkpoint_roots.extend(c.finish());
+            SubtreeRoots {
+                span_log2,
+                roots: std::mem::take(&mut self.checkpoint_roots),
+            }
+        });
+        CommitManifest {
+            version: MANIFEST_VERSION,
+            root: self.frontier.root(),
+            n_leaves: self.n_leaves,
+            total_steps: self.total_steps,
+            tau: self.tau.unwrap_or(0),
+            leaf_schema: self.leaf_schema.max(LEAF_SCHEMA_VERSION),
+            hash_scheme: HASH_SCHEME.to_owned(),
+            creator: None,
+            input_len: None,
+            checkpoints,
+        }
+    }
+}
+
+/// Merkle frontier over the current run of `2^span_log2` leaves.
+#[derive(Debug, Clone)]
+struct RunFrontier {
+    span_log2: u32,
+    cur: MerkleFrontier,
+}
+
+impl RunFrontier {
+    fn new(span_log2: u32) -> Self {
+        Self {
+            span_log2,
+            cur: MerkleFrontier::new(),
+        }
+    }
+
+    /// Absorb a leaf; returns the run's root when this leaf completes it.
+    fn push(&mut self, leaf: [u8; 32]) -> Option<[u8; 32]> {
+        self.cur.push(leaf);
+        (self.cur.len() == 1 << self.span_log2).then(|| std::mem::take(&mut self.cur).root())
+    }
+
+    /// Root of the trailing partial run, if any.
+    fn finish(self) -> Option<[u8; 32]> {
+        (!self.cur.is_empty()).then(|| self.cur.root())
+    }
 }
 
 /* -------------------------- Leaf/node hashing -------------------------- */
 
+/// Leaf schema a block hashes under: [`LEAF_SCHEMA_REDACTED`] for redacted
+/// blocks and blocks with auxiliary commitments, [`LEAF_SCHEMA_VERSION`]
+/// otherwise.
+#[must_use]
+pub fn leaf_schema_of(b: &BlockSummary) -> u32 {
+    if b.is_redacted() || b.has_aux() {
+        LEAF_SCHEMA_REDACTED
+    } else {
+        LEAF_SCHEMA_VERSION
+    }
+}
+
 /// Compute the **canonical** leaf hash for a `BlockSummary`.
 ///
 /// The byte layout is intentionally duplicated in the folding leaf gadget and
 /// must remain byte-for-byte identical across the workspace.
 ///
-/// See the module-level docs for the exact encoding.
+/// See the module-level docs for the exact encoding (v1, or v2 for redacted
+/// blocks and blocks with auxiliary commitments).
 #[must_use]
 pub fn leaf_hash(b: &BlockSummary) -> [u8; 32] {
+    leaf_hash_of_shape(b, b.movement_log.steps.len() as u64)
+}
+
+/// [`leaf_hash`] of a block whose movement log has been dropped, given the
+/// log's original length.
+///
+/// The leaf hash binds only the length of the log, so this lets a verifier
+/// tie a block's shape (windows, offsets, step range) to its leaf without
+/// the steps. `shape.movement_log` is ignored.
+#[must_use]
+pub fn leaf_hash_of_shape(b: &BlockSummary, n_steps: u64) -> [u8; 32] {
     let mut h = Hasher::new();
 
     // Core scalars (raw little-endian)
@@ -111,21 +546,55 @@ pub fn leaf_hash(b: &BlockSummary) -> [u8; 32] {
     }
 
     // Movement log: bind **length only** in v1
-    h.update(&(b.movement_log.steps.len() as u64).to_le_bytes());
+    h.update(&n_steps.to_le_bytes());
+
+    // v2: commitments standing in for the redacted symbols
+    if b.is_redacted() {
+        h.update(&(b.write_commitments.len() as u64).to_le_bytes());
+        for c in &b.write_commitments {
+            h.update(c);
+        }
+    }
+
+    // v2: adapter-defined side commitments
+    if b.has_aux() {
+        h.update(&(b.aux_commitments.len() as u64).to_le_bytes());
+        for c in &b.aux_commitments {
+            h.update(c);
+        }
+    }
 
     *h.finalize().as_bytes()
 }
 
+/// [`leaf_hash`] of every block, in order (with the `parallel` feature, on
+/// the pool configured in [`sezkp_core::parallelism`]).
+#[must_use]
+pub fn leaf_hashes(blocks: &[BlockSummary]) -> Vec<[u8; 32]> {
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        use sezkp_core::parallelism;
+        let min_len = parallelism::chunking().min_len();
+        parallelism::install(|| {
+            blocks
+                .par_iter()
+                .with_min_len(min_len)
+                .map(leaf_hash)
+                .collect()
+        })
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        blocks.iter().map(leaf_hash).collect()
+    }
+}
+
 /// Public node combiner used everywhere that needs to hash two children.
 ///
-/// This **must** match the manifest/Merkle combiner and the fold crate.
-#[inline]
-pub fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
-    let mut h = Hasher::new();
-    h.update(left);
-    h.update(right);
-    *h.finalize().as_bytes()
-}
+/// This **must** match the manifest/Merkle combiner and the fold crate; it is
+/// the shared [`sezkp_core::frontier::node_hash`].
+pub use sezkp_core::frontier::node_hash;
 
 #[inline]
 fn merkle_parent(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
@@ -134,12 +603,12 @@ fn merkle_parent(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
 
 /// Compute a left-balanced Merkle root from a vector of leaf hashes.
 ///
-/// - Empty input → all-zero root.
+/// - Empty input → [`EMPTY_ROOT`].
 /// - Odd leaf at a level → **promote** (carry up unchanged).
 #[must_use]
 pub fn merkle_root(mut leaves: Vec<[u8; 32]>) -> [u8; 32] {
     if leaves.is_empty() {
-        return [0u8; 32];
+        return EMPTY_ROOT;
     }
     while leaves.len() > 1 {
         let mut next = Vec::with_capacity((leaves.len() + 1) / 2);
@@ -156,60 +625,216 @@ pub fn merkle_root(mut leaves: Vec<[u8; 32]>) -> [u8; 32] {
     leaves[0]
 }
 
-/* --------------------------- Streaming frontier --------------------------- */
+/* ---------------------------- Inclusion proofs ---------------------------- */
 
-/// O(log n) frontier that maintains a left-balanced Merkle root incrementally.
+/// Authentication path linking one leaf to the root of a left-balanced tree
+/// of `n_leaves` leaves (see [`merkle_root`]).
 ///
-/// Push leaves one-by-one with [`Frontier::push_leaf`], then call
-/// [`Frontier::finalize_root`] to obtain the root. Memory is bounded by the
-/// number of levels (~`floor(log2(n)) + 1`).
-#[derive(Default)]
-struct Frontier {
-    // One slot per level; slot[i] is the pending promoted node at that level.
-    slots: Vec<Option<[u8; 32]>>,
+/// `siblings` lists the sibling of the leaf's ancestor at every level where
+/// it has one, bottom-up; levels where the ancestor is the promoted odd node
+/// contribute nothing.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct InclusionProof {
+    /// Position of the leaf (0-based).
+    pub index: u64,
+    /// Number of leaves under the root.
+    pub n_leaves: u64,
+    /// Sibling hashes, bottom-up.
+    pub siblings: Vec<[u8; 32]>,
 }
 
-impl Frontier {
-    #[inline]
-    fn push_leaf(&mut self, mut h: [u8; 32]) {
-        let mut lvl = 0usize;
-        loop {
-            if self.slots.len() <= lvl {
-                self.slots.resize(lvl + 1, None);
-            }
-            match self.slots[lvl] {
-                None => {
-                    self.slots[lvl] = Some(h);
-                    break;
-                }
-                Some(left) => {
-                    // Pair with the waiting left node; carry to next level.
-                    self.slots[lvl] = None;
-                    h = merkle_parent(left, h);
-                    lvl += 1;
-                }
+impl InclusionProof {
+    /// Root this path yields for `leaf`, or `None` if the path does not fit
+    /// its tree (index out of range, too few or too many siblings).
+    #[must_use]
+    pub fn root(&self, leaf: &[u8; 32]) -> Option<[u8; 32]> {
+        if self.index >= self.n_leaves {
+            return None;
+        }
+        let (mut pos, mut width) = (self.index, self.n_leaves);
+        let mut siblings = self.siblings.iter();
+        let mut node = *leaf;
+        while width > 1 {
+            if pos % 2 == 1 {
+                node = merkle_parent(*siblings.next()?, node);
+            } else if pos + 1 < width {
+                node = merkle_parent(node, *siblings.next()?);
             }
+            pos /= 2;
+            width = width.div_ceil(2);
         }
+        siblings.next().is_none().then_some(node)
     }
 
-    #[inline]
-    fn finalize_root(&self) -> [u8; 32] {
-        // Start from the highest non-empty level and fold downward,
-        // pairing current accumulator (higher) with lower-level nodes.
-        let mut acc: Option<[u8; 32]> = None;
-        for node in self.slots.iter().rev().filter_map(|x| *x) {
-            acc = Some(match acc {
-                None => node,
-                Some(higher) => merkle_parent(higher, node),
-            });
+    /// Check that `leaf` sits at [`Self::index`] under `root`.
+    ///
+    /// # Errors
+    /// Fails if the path is malformed or leads to another root.
+    pub fn verify(&self, leaf: &[u8; 32], root: &[u8; 32]) -> Result<()> {
+        let got = self
+            .root(leaf)
+            .ok_or_else(|| anyhow!("malformed inclusion proof for leaf {}", self.index))?;
+        if &got != root {
+            anyhow::bail!(
+                "leaf {} of {} is not included under root {}",
+                self.index,
+                self.n_leaves,
+                render_root(root)
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Inclusion proof for `leaves[index]` under the [`merkle_root`] of `leaves`, or
+/// `None` if `index` is out of range.
+#[must_use]
+pub fn inclusion_proof(leaves: &[[u8; 32]], index: usize) -> Option<InclusionProof> {
+    if index >= leaves.len() {
+        return None;
+    }
+    let mut siblings = Vec::new();
+    let mut level = leaves.to_vec();
+    let mut pos = index;
+    while level.len() > 1 {
+        if pos % 2 == 1 {
+            siblings.push(level[pos - 1]);
+        } else if pos + 1 < level.len() {
+            siblings.push(level[pos + 1]);
+        }
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [l, r] => merkle_parent(*l, *r),
+                _ => pair[0],
+            })
+            .collect();
+        pos /= 2;
+    }
+    Some(InclusionProof {
+        index: index as u64,
+        n_leaves: leaves.len() as u64,
+        siblings,
+    })
+}
+
+/// Inclusion proof of one block under a manifest: the block's leaf hash and
+/// its path to the root. Serializable to JSON/CBOR like [`InclusionProof`].
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LeafProof {
+    /// Leaf hash of the proven block (see [`leaf_hash`]).
+    pub leaf: [u8; 32],
+    /// Path from `leaf` to the root.
+    pub path: InclusionProof,
+}
+
+impl LeafProof {
+    /// Root the proof leads to, or `None` if the path is malformed.
+    #[must_use]
+    pub fn root(&self) -> Option<[u8; 32]> {
+        self.path.root(&self.leaf)
+    }
+}
+
+/// Prove that block `index` of the blocks file at `path` is committed to by
+/// the file's root.
+///
+/// Streams the file once, keeping one [`MerkleFrontier`] per tree level, so
+/// memory is `O(log² n)` hashes rather than one per block. The proof checks
+/// against a manifest of the same file with [`verify_leaf`].
+///
+/// # Errors
+/// Fails if the file cannot be read or `index` is out of range.
+pub fn prove_leaf<P: AsRef<Path>>(path: P, index: u64) -> Result<LeafProof> {
+    let path = path.as_ref();
+    prove_leaf_stream(core_io::stream_block_summaries_auto(path)?, index)
+        .with_context(|| format!("proving leaf {index} of {}", display(path)))
+}
+
+/// [`prove_leaf`] over a stream of blocks in file order.
+///
+/// The sibling of the leaf's ancestor at level `l` covers the aligned run
+/// of `2^l` leaves next to it, so every other leaf `j` belongs to the
+/// sibling at level `⌊log₂(index ⊕ j)⌋`; each sibling is the root of its
+/// run, which the promotion rule keeps even when the run is cut short.
+///
+/// # Errors
+/// Fails if a block cannot be read or `index` is out of range.
+pub fn prove_leaf_stream<I>(blocks: I, index: u64) -> Result<LeafProof>
+where
+    I: IntoIterator<Item = Result<BlockSummary>>,
+{
+    let mut leaf = None;
+    let mut levels: Vec<MerkleFrontier> = Vec::new();
+    let mut n_leaves = 0u64;
+    for blk in blocks {
+        let h = leaf_hash(&blk?);
+        let j = n_leaves;
+        n_leaves += 1;
+        if j == index {
+            leaf = Some(h);
+            continue;
+        }
+        let lvl = (index ^ j).ilog2() as usize;
+        if levels.len() <= lvl {
+            levels.resize_with(lvl + 1, MerkleFrontier::new);
+        }
+        levels[lvl].push(h);
+    }
+    let leaf = leaf.ok_or_else(|| anyhow!("block {index} out of range ({n_leaves} blocks)"))?;
+    let mut siblings = Vec::new();
+    let (mut pos, mut width) = (index, n_leaves);
+    for run in &levels {
+        if width <= 1 {
+            break;
+        }
+        if pos % 2 == 1 || pos + 1 < width {
+            siblings.push(run.root());
         }
-        acc.unwrap_or([0u8; 32])
+        pos /= 2;
+        width = width.div_ceil(2);
+    }
+    Ok(LeafProof {
+        leaf,
+        path: InclusionProof {
+            index,
+            n_leaves,
+            siblings,
+        },
+    })
+}
+
+/// Check that `block` is leaf [`InclusionProof::index`] of the blocks
+/// committed to by `man`, without the other blocks.
+///
+/// # Errors
+/// Fails if the manifest is incompatible, the block does not hash to the
+/// proven leaf, the proof is for a tree of another size, or the path does
+/// not lead to the manifest root.
+pub fn verify_leaf(man: &CommitManifest, block: &BlockSummary, proof: &LeafProof) -> Result<()> {
+    man.check_compatible()?;
+    let got = leaf_hash(block);
+    if got != proof.leaf {
+        anyhow::bail!(
+            "block {} hashes to {}, not the proven leaf {}",
+            block.block_id,
+            render_root(&got),
+            render_root(&proof.leaf)
+        );
     }
+    if proof.path.n_leaves != u64::from(man.n_leaves) {
+        anyhow::bail!(
+            "leaf proof is for a tree of {} leaves; the manifest has {}",
+            proof.path.n_leaves,
+            man.n_leaves
+        );
+    }
+    proof.path.verify(&proof.leaf, &man.root)
 }
 
 /* ------------------------------ In-memory API ------------------------------ */
 
-/// Compute a manifest (root + leaf count) from an in-memory slice of blocks.
+/// Compute a manifest (root, leaf count, trace shape) from an in-memory slice of blocks.
 #[must_use]
 pub fn commit_blocks(blocks: &[BlockSummary]) -> CommitManifest {
     let leaves: Vec<[u8; 32]> = blocks.iter().map(leaf_hash).collect();
@@ -218,22 +843,54 @@ pub fn commit_blocks(blocks: &[BlockSummary]) -> CommitManifest {
         version: MANIFEST_VERSION,
         root,
         n_leaves: blocks.len() as u32,
+        total_steps: blocks
+            .iter()
+            .map(|b| b.movement_log.steps.len() as u64)
+            .sum(),
+        tau: blocks
+            .first()
+            .map_or(0, |b| u32::try_from(b.windows.len()).unwrap_or(u32::MAX)),
+        leaf_schema: blocks
+            .iter()
+            .map(leaf_schema_of)
+            .max()
+            .unwrap_or(LEAF_SCHEMA_VERSION),
+        hash_scheme: HASH_SCHEME.to_owned(),
+        creator: None,
+        input_len: None,
+        checkpoints: None,
     }
 }
 
-/// In-memory validator: recompute and compare root and leaf count.
+/// [`commit_blocks`] with the leaves hashed on the rayon pool; the manifest
+/// is identical.
+#[cfg(feature = "parallel")]
+#[must_use]
+pub fn commit_blocks_parallel(blocks: &[BlockSummary]) -> CommitManifest {
+    let mut builder = ManifestBuilder::new();
+    builder.push_all(blocks);
+    builder.finish()
+}
+
+/// Compare a recomputed manifest against a stored one.
 ///
-/// Returns `Ok(())` if the manifest matches the provided blocks.
-pub fn validate_blocks_against_manifest(
-    blocks: &[BlockSummary],
-    man: &CommitManifest,
-) -> Result<()> {
-    let recomputed = commit_blocks(blocks);
+/// Root and leaf count are always checked; the trace shape only when the
+/// stored manifest records it (v2+).
+fn check_recomputed(recomputed: &CommitManifest, man: &CommitManifest) -> Result<()> {
+    man.check_compatible()?;
+    man.check_well_formed()?;
     if recomputed.root != man.root {
         return Err(anyhow!(
             "root mismatch: manifest={}, recomputed={}",
-            hex::encode(man.root),
-            hex::encode(recomputed.root)
+            render_root(&man.root),
+            render_root(&recomputed.root)
+        ));
+    }
+    if recomputed.leaf_schema != man.leaf_schema {
+        return Err(anyhow!(
+            "leaf schema mismatch: manifest=v{}, recomputed=v{}",
+            man.leaf_schema,
+            recomputed.leaf_schema
         ));
     }
     if recomputed.n_leaves != man.n_leaves {
@@ -243,6 +900,102 @@ pub fn validate_blocks_against_manifest(
             recomputed.n_leaves
         ));
     }
+    if man.has_shape() {
+        if recomputed.total_steps != man.total_steps {
+            return Err(anyhow!(
+                "total step mismatch: manifest={}, recomputed={}",
+                man.total_steps,
+                recomputed.total_steps
+            ));
+        }
+        if recomputed.tau != man.tau {
+            return Err(anyhow!(
+                "tau mismatch: manifest={}, recomputed={}",
+                man.tau,
+                recomputed.tau
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// In-memory validator: recompute and compare root and leaf count.
+///
+/// Returns `Ok(())` if the manifest matches the provided blocks.
+pub fn validate_blocks_against_manifest(
+    blocks: &[BlockSummary],
+    man: &CommitManifest,
+) -> Result<()> {
+    check_recomputed(&commit_blocks(blocks), man)
+}
+
+/// Stream `blocks` against `man`, reporting progress (see
+/// [`sezkp_core::progress`]) per block.
+///
+/// When the manifest carries [`SubtreeRoots`], each completed run is compared
+/// as soon as its last leaf arrives and the first diverging run fails the
+/// check with its leaf range; otherwise only the final manifest is compared.
+///
+/// # Errors
+/// Returns an error if a block cannot be read, a run diverges, or the
+/// recomputed manifest differs.
+pub fn verify_block_stream<I>(blocks: I, man: &CommitManifest) -> Result<()>
+where
+    I: IntoIterator<Item = Result<BlockSummary>>,
+{
+    man.check_compatible()?;
+    man.check_well_formed()?;
+    let n_leaves = u64::from(man.n_leaves);
+    let mut progress = ProgressTracker::new("merkle.verify_commit", Some(n_leaves));
+    let mut builder = ManifestBuilder::new();
+    let mut runs = man
+        .checkpoints
+        .as_ref()
+        .map(|cp| (cp, RunFrontier::new(cp.span_log2), 0usize));
+    for blk in blocks {
+        let blk = blk?;
+        let leaf = leaf_hash(&blk);
+        builder.push_hashed(&blk, leaf);
+        if let Some((cp, run, i)) = runs.as_mut() {
+            if progress.done() == n_leaves {
+                return Err(overrun(n_leaves));
+            }
+            if let Some(root) = run.push(leaf) {
+                check_run(cp, *i, &root, n_leaves)?;
+                *i += 1;
+            }
+        }
+        progress.tick(1);
+    }
+    if let Some((cp, run, i)) = runs {
+        if let Some(root) = run.finish() {
+            check_run(cp, i, &root, n_leaves)?;
+        }
+    }
+    progress.finish();
+    check_recomputed(&builder.finish(), man)
+}
+
+fn overrun(n_leaves: u64) -> anyhow::Error {
+    anyhow!("blocks continue past the {n_leaves} leaves the manifest commits to")
+}
+
+/// Compare the recomputed root of run `i` with the manifest's.
+fn check_run(cp: &SubtreeRoots, i: usize, root: &[u8; 32], n_leaves: u64) -> Result<()> {
+    let (lo, hi) = cp.range(i, n_leaves);
+    let Some(want) = cp.roots.get(i) else {
+        return Err(overrun(n_leaves));
+    };
+    if want != root {
+        return Err(anyhow!(
+            "first diverging subtree: leaves [{lo}, {hi}) (run {i} of {}, 2^{} leaves each): \
+             manifest={}, recomputed={}",
+            cp.roots.len(),
+            cp.span_log2,
+            render_root(want),
+            render_root(root)
+        ));
+    }
     Ok(())
 }
 
@@ -250,44 +1003,98 @@ pub fn validate_blocks_against_manifest(
 
 /// Commit a blocks file to a manifest, write it to `out_manifest_path`, and return it.
 ///
-/// - Supports `.json`, `.cbor`, or line-delimited JSON as `.jsonl`/`.ndjson`.
-/// - JSONL/NDJSON is processed **streamingly** with an O(log n) frontier.
-///   JSON/CBOR are loaded via `sezkp-core` helpers.
+/// - Supports `.json`, `.cbor`, or line-delimited JSON as `.jsonl`/`.ndjson`;
+///   misnamed files are detected from their content.
+/// - With the `zstd` feature, also their compressed forms (`.jsonl.zst`,
+///   `.cbor.zst`, ...), decompressed as they are read.
+/// - JSONL/NDJSON and CBOR are processed **streamingly** with an O(log n)
+///   frontier. JSON is loaded via `sezkp-core` helpers.
 ///
 /// This function also prints a one-line summary (root/leaf count) for UX.
 /// Library users that prefer no output can wrap/redirect stdout.
 pub fn commit_block_file<P: AsRef<Path>, Q: AsRef<Path>>(
     blocks_path: P,
     out_manifest_path: Q,
+) -> Result<CommitManifest> {
+    commit_block_file_with_creator(blocks_path, out_manifest_path, None)
+}
+
+/// Like [`commit_block_file`], additionally recording `creator` metadata.
+///
+/// # Errors
+/// Returns an error if the blocks cannot be read or the manifest cannot be written.
+pub fn commit_block_file_with_creator<P: AsRef<Path>, Q: AsRef<Path>>(
+    blocks_path: P,
+    out_manifest_path: Q,
+    creator: Option<CreatorInfo>,
+) -> Result<CommitManifest> {
+    let opts = CommitOptions {
+        creator,
+        ..CommitOptions::default()
+    };
+    commit_block_file_with(blocks_path, out_manifest_path, &opts)
+}
+
+/// Options for the file commit helpers.
+#[derive(Clone, Debug, Default)]
+pub struct CommitOptions {
+    /// Provenance recorded in the manifest.
+    pub creator: Option<CreatorInfo>,
+    /// Record [`SubtreeRoots`] over runs of `2^k` leaves.
+    pub checkpoint_log2: Option<u32>,
+}
+
+impl CommitOptions {
+    fn builder(&self) -> Result<ManifestBuilder> {
+        self.checkpoint_log2.map_or_else(
+            || Ok(ManifestBuilder::new()),
+            |k| ManifestBuilder::new().with_checkpoints(k),
+        )
+    }
+}
+
+/// Like [`commit_block_file`], with [`CommitOptions`].
+///
+/// # Errors
+/// Returns an error if the blocks cannot be read, the checkpoint span is out
+/// of range, or the manifest cannot be written.
+pub fn commit_block_file_with<P: AsRef<Path>, Q: AsRef<Path>>(
+    blocks_path: P,
+    out_manifest_path: Q,
+    opts: &CommitOptions,
 ) -> Result<CommitManifest> {
     let path = blocks_path.as_ref();
 
-    let manifest = if is_jsonl_like(path) {
-        // Stream leaves in one pass using a frontier.
-        let mut frontier = Frontier::default();
-        let mut n = 0u32;
-        for blk in stream_block_summaries_jsonl(path)? {
-            frontier.push_leaf(leaf_hash(&blk?));
-            n = n.saturating_add(1);
-        }
-        let root = frontier.finalize_root();
-        CommitManifest {
-            version: MANIFEST_VERSION,
-            root,
-            n_leaves: n,
-        }
-    } else {
-        // Use sezkp-core auto-reader for JSON/CBOR files that contain Vec<BlockSummary>.
+    // Leaves (and shape counters) go through a frontier in one pass.
+    let mut builder = opts.builder()?;
+    if core_io::block_file_format(path)? == FileFormat::Json {
+        // A JSON array is parsed whole by the sezkp-core auto-reader.
         let blocks = core_io::read_block_summaries_auto(&blocks_path)
             .with_context(|| format!("read blocks {}", display(path)))?;
-        commit_blocks(&blocks)
-    };
+        builder.push_all(&blocks);
+    } else {
+        // Hashed a chunk at a time, so the `parallel` feature can use every
+        // core while memory stays bounded by the chunk.
+        let mut chunk = Vec::with_capacity(COMMIT_CHUNK);
+        for blk in core_io::stream_block_summaries_auto(path)? {
+            chunk.push(blk?);
+            if chunk.len() == COMMIT_CHUNK {
+                builder.push_all(&chunk);
+                chunk.clear();
+            }
+        }
+        builder.push_all(&chunk);
+    }
+    let mut manifest = builder.finish();
+    manifest.creator.clone_from(&opts.creator);
 
     write_manifest_auto(&out_manifest_path, &manifest)?;
     println!(
-        "Committed {} leaves, root={}, wrote manifest {}",
+        "Committed {} leaves ({} steps, tau={}), root={}, wrote manifest {}",
         manifest.n_leaves,
-        hex::encode(manifest.root),
+        manifest.total_steps,
+        manifest.tau,
+        render_root(&manifest.root),
         out_manifest_path.as_ref().display()
     );
 
@@ -296,9 +1103,13 @@ pub fn commit_block_file<P: AsRef<Path>, Q: AsRef<Path>>(
 
 /// Verify that a blocks file matches a manifest file by recomputing the root.
 ///
-/// - For `.jsonl`/`.ndjson` inputs, this streams the file and uses an O(log n)
-///   frontier; it does **not** materialize all blocks.
-/// - For `.json`/`.cbor`, it uses `sezkp-core` helpers to load all blocks.
+/// - For JSON Lines and CBOR inputs (by extension or content), this streams
+///   the file and uses an O(log n) frontier; it does **not** materialize all
+///   blocks.
+/// - For JSON, it uses `sezkp-core` helpers to load all blocks.
+///
+/// Either way the blocks go through [`verify_block_stream`], so progress is
+/// reported and manifest checkpoints narrow a mismatch down to a leaf range.
 pub fn verify_block_file_against_manifest<P: AsRef<Path>, Q: AsRef<Path>>(
     blocks_path: P,
     manifest_path: Q,
@@ -306,36 +1117,137 @@ pub fn verify_block_file_against_manifest<P: AsRef<Path>, Q: AsRef<Path>>(
     let path = blocks_path.as_ref();
     let man = read_manifest_auto(&manifest_path)?;
 
-    if is_jsonl_like(path) {
-        let mut frontier = Frontier::default();
-        let mut n = 0u32;
-        for blk in stream_block_summaries_jsonl(path)? {
-            frontier.push_leaf(leaf_hash(&blk?));
-            n = n.saturating_add(1);
-        }
-        let root = frontier.finalize_root();
-        if root != man.root {
-            anyhow::bail!(
-                "root mismatch: manifest={}, recomputed={}",
-                hex::encode(man.root),
-                hex::encode(root)
-            );
-        }
-        if n != man.n_leaves {
-            anyhow::bail!(
-                "leaf count mismatch: manifest={}, recomputed={}",
-                man.n_leaves,
-                n
-            );
-        }
-        Ok(())
-    } else {
+    if core_io::block_file_format(path)? == FileFormat::Json {
         let blocks = core_io::read_block_summaries_auto(&blocks_path)
             .with_context(|| format!("read blocks {}", display(path)))?;
-        validate_blocks_against_manifest(&blocks, &man)
+        verify_block_stream(blocks.into_iter().map(Ok), &man)
+    } else {
+        verify_block_stream(core_io::stream_block_summaries_auto(path)?, &man)
     }
 }
 
+/* ------------------------------ Trace inputs ------------------------------- */
+
+/// Compute the manifest of the blocks `partition_iter(tf, b, strategy)` would
+/// produce, hashing each block as it is cut (`O(b)` block memory).
+///
+/// The root equals [`commit_blocks`] over the materialized partition; the
+/// trace's declared `input_len` carries over.
+///
+/// # Errors
+/// Returns an error if `b == 0`.
+pub fn commit_trace(tf: &TraceFile, b: u32, strategy: PartitionStrategy) -> Result<CommitManifest> {
+    commit_partition(tf, b, strategy, ManifestBuilder::new())
+}
+
+/// [`commit_trace`] through `builder`.
+fn commit_partition(
+    tf: &TraceFile,
+    b: u32,
+    strategy: PartitionStrategy,
+    mut builder: ManifestBuilder,
+) -> Result<CommitManifest> {
+    if b == 0 {
+        return Err(anyhow!("block size b must be > 0"));
+    }
+    for blk in partition_iter(tf, b, strategy) {
+        builder.push(&blk);
+    }
+    Ok(CommitManifest {
+        input_len: tf.input_len,
+        ..builder.finish()
+    })
+}
+
+/// Commit a trace file (CBOR/JSON) without writing its blocks anywhere:
+/// partition on the fly, write the manifest to `out_manifest_path`, return it.
+///
+/// Prints the same one-line summary as [`commit_block_file`].
+///
+/// # Errors
+/// Returns an error if the trace cannot be read, `b == 0`, or the manifest
+/// cannot be written.
+pub fn commit_trace_file<P: AsRef<Path>, Q: AsRef<Path>>(
+    trace_path: P,
+    b: u32,
+    strategy: PartitionStrategy,
+    out_manifest_path: Q,
+) -> Result<CommitManifest> {
+    commit_trace_file_with_creator(trace_path, b, strategy, out_manifest_path, None)
+}
+
+/// Like [`commit_trace_file`], additionally recording `creator` metadata.
+///
+/// # Errors
+/// Returns an error if the trace cannot be read, `b == 0`, or the manifest
+/// cannot be written.
+pub fn commit_trace_file_with_creator<P: AsRef<Path>, Q: AsRef<Path>>(
+    trace_path: P,
+    b: u32,
+    strategy: PartitionStrategy,
+    out_manifest_path: Q,
+    creator: Option<CreatorInfo>,
+) -> Result<CommitManifest> {
+    let opts = CommitOptions {
+        creator,
+        ..CommitOptions::default()
+    };
+    commit_trace_file_with(trace_path, b, strategy, out_manifest_path, &opts)
+}
+
+/// Like [`commit_trace_file`], with [`CommitOptions`].
+///
+/// # Errors
+/// Returns an error if the trace cannot be read, `b == 0`, the checkpoint
+/// span is out of range, or the manifest cannot be written.
+pub fn commit_trace_file_with<P: AsRef<Path>, Q: AsRef<Path>>(
+    trace_path: P,
+    b: u32,
+    strategy: PartitionStrategy,
+    out_manifest_path: Q,
+    opts: &CommitOptions,
+) -> Result<CommitManifest> {
+    let path = trace_path.as_ref();
+    let tf = sezkp_trace::io::read_trace_auto(path)
+        .with_context(|| format!("read trace {}", display(path)))?;
+    let mut manifest = commit_partition(&tf, b, strategy, opts.builder()?)?;
+    manifest.creator.clone_from(&opts.creator);
+
+    write_manifest_auto(&out_manifest_path, &manifest)?;
+    println!(
+        "Committed {} leaves ({} steps, tau={}, b={b}), root={}, wrote manifest {}",
+        manifest.n_leaves,
+        manifest.total_steps,
+        manifest.tau,
+        render_root(&manifest.root),
+        out_manifest_path.as_ref().display()
+    );
+
+    Ok(manifest)
+}
+
+/// Verify that partitioning a trace file with `b`/`strategy` reproduces a
+/// manifest file.
+///
+/// # Errors
+/// Returns an error if either file cannot be read or the recomputed manifest
+/// differs (a wrong `b` or strategy shows up as a root mismatch).
+pub fn verify_trace_file_against_manifest<P: AsRef<Path>, Q: AsRef<Path>>(
+    trace_path: P,
+    b: u32,
+    strategy: PartitionStrategy,
+    manifest_path: Q,
+) -> Result<()> {
+    let path = trace_path.as_ref();
+    let man = read_manifest_auto(&manifest_path)?;
+    let tf = sezkp_trace::io::read_trace_auto(path)
+        .with_context(|| format!("read trace {}", display(path)))?;
+    if b == 0 {
+        return Err(anyhow!("block size b must be > 0"));
+    }
+    verify_block_stream(partition_iter(&tf, b, strategy).map(Ok), &man)
+}
+
 /* ------------------------------ Manifest I/O ------------------------------- */
 
 /// Read a manifest from **JSON**.
@@ -380,36 +1292,25 @@ pub fn write_manifest_cbor<P: AsRef<Path>>(path: P, v: &CommitManifest) -> Resul
 
 /// Auto-detect **read** by extension: `.json` / `.cbor` (case-insensitive).
 pub fn read_manifest_auto<P: AsRef<Path>>(path: P) -> Result<CommitManifest> {
-    match ext_lower(path.as_ref()).as_deref() {
-        Some("json") => read_manifest_json(path),
-        Some("cbor") => read_manifest_cbor(path),
-        Some(other) => anyhow::bail!("unsupported manifest extension: {}", other),
-        None => anyhow::bail!("path has no extension (expected .json or .cbor)"),
+    match resolve_read_format(path.as_ref(), "manifest", &MANIFEST_FORMATS)? {
+        FileFormat::Cbor => read_manifest_cbor(path),
+        _ => read_manifest_json(path),
     }
 }
 
-/// Auto-detect **write** by extension: `.json` / `.cbor` (defaults to JSON).
+/// Auto-detect **write** by extension: `.json` / `.cbor` (defaults to JSON
+/// unless strict I/O is enabled).
 pub fn write_manifest_auto<P: AsRef<Path>>(path: P, v: &CommitManifest) -> Result<()> {
-    match ext_lower(path.as_ref()).as_deref() {
-        Some("json") => write_manifest_json(path, v),
-        Some("cbor") => write_manifest_cbor(path, v),
+    match resolve_write_format(path.as_ref(), "manifest", &MANIFEST_FORMATS)? {
+        FileFormat::Cbor => write_manifest_cbor(path, v),
         _ => write_manifest_json(path, v),
     }
 }
 
-/* --------------------------------- Helpers -------------------------------- */
+/// Formats accepted for manifest files.
+const MANIFEST_FORMATS: [FileFormat; 2] = [FileFormat::Json, FileFormat::Cbor];
 
-#[inline]
-fn ext_lower(path: &Path) -> Option<String> {
-    path.extension()
-        .and_then(std::ffi::OsStr::to_str)
-        .map(|s| s.to_ascii_lowercase())
-}
-
-#[inline]
-fn is_jsonl_like(path: &Path) -> bool {
-    matches!(ext_lower(path).as_deref(), Some("jsonl") | Some("ndjson"))
-}
+/* --------------------------------- Helpers -------------------------------- */
 
 #[inline]
 fn display(path: &Path) -> String {
@@ -446,6 +1347,8 @@ mod tests {
             movement_log: MovementLog { steps },
             pre_tags: vec![[0u8; 16]; 1],
             post_tags: vec![[0u8; 16]; 1],
+            write_commitments: Vec::new(),
+            aux_commitments: Vec::new(),
         }
     }
 
@@ -463,6 +1366,15 @@ mod tests {
         assert_eq!(root, r2);
     }
 
+    #[test]
+    fn shape_hash_needs_only_the_log_length() {
+        let b = mk_block(2, 5);
+        let mut shape = b.clone();
+        shape.movement_log.steps.clear();
+        assert_eq!(leaf_hash_of_shape(&shape, 5), leaf_hash(&b));
+        assert_ne!(leaf_hash_of_shape(&shape, 4), leaf_hash(&b));
+    }
+
     #[test]
     fn commit_and_validate_roundtrip() {
         let blocks = vec![mk_block(1, 4), mk_block(2, 4), mk_block(3, 2)];
@@ -470,6 +1382,381 @@ mod tests {
         validate_blocks_against_manifest(&blocks, &man).unwrap();
     }
 
+    #[test]
+    fn manifest_records_shape_and_checks_it() {
+        let blocks = vec![mk_block(1, 4), mk_block(2, 4), mk_block(3, 2)];
+        let man = commit_blocks(&blocks);
+        assert_eq!(man.version, MANIFEST_VERSION);
+        assert_eq!(man.total_steps, 10);
+        assert_eq!(man.tau, 1);
+        assert_eq!(man.hash_scheme, HASH_SCHEME);
+
+        let mut bad = man.clone();
+        bad.total_steps += 1;
+        assert!(validate_blocks_against_manifest(&blocks, &bad).is_err());
+
+        let mut bad = man;
+        bad.leaf_schema = LEAF_SCHEMA_REDACTED + 1;
+        assert!(validate_blocks_against_manifest(&blocks, &bad).is_err());
+    }
+
+    #[test]
+    fn redacted_blocks_commit_under_leaf_schema_v2() {
+        use sezkp_core::redact::redact_block;
+
+        let mut blocks = vec![mk_block(1, 4), mk_block(2, 4)];
+        for b in &mut blocks {
+            b.movement_log.steps[1].tapes[0].write = Some(9);
+        }
+        let plain = commit_blocks(&blocks);
+        assert_eq!(plain.leaf_schema, LEAF_SCHEMA_VERSION);
+
+        let mut redacted: Vec<_> = blocks
+            .iter()
+            .map(|b| redact_block(b, &[1u8; 32]).unwrap())
+            .collect();
+        let man = commit_blocks(&redacted);
+        assert_eq!(man.leaf_schema, LEAF_SCHEMA_REDACTED);
+        assert_ne!(man.root, plain.root);
+        validate_blocks_against_manifest(&redacted, &man).unwrap();
+        assert!(validate_blocks_against_manifest(&blocks, &man).is_err());
+
+        let mut builder = ManifestBuilder::new();
+        redacted.iter().for_each(|b| builder.push(b));
+        assert_eq!(builder.finish(), man);
+
+        // The root binds the commitments standing in for the symbols.
+        redacted[1].write_commitments[0][0] ^= 1;
+        assert!(validate_blocks_against_manifest(&redacted, &man).is_err());
+    }
+
+    #[test]
+    fn inclusion_proofs_match_merkle_root() {
+        for n in [1usize, 2, 3, 5, 8, 13] {
+            let leaves: Vec<[u8; 32]> = (0..n).map(|i| [i as u8 + 1; 32]).collect();
+            let root = merkle_root(leaves.clone());
+            for (i, leaf) in leaves.iter().enumerate() {
+                let p = inclusion_proof(&leaves, i).unwrap();
+                p.verify(leaf, &root).unwrap();
+                assert!(p.verify(&[0u8; 32], &root).is_err());
+
+                let mut moved = p.clone();
+                moved.index = (moved.index + 1) % n as u64;
+                assert!(n == 1 || moved.verify(leaf, &root).is_err());
+                let mut extra = p.clone();
+                extra.siblings.push([0u8; 32]);
+                assert!(extra.verify(leaf, &root).is_err());
+            }
+            assert!(inclusion_proof(&leaves, n).is_none());
+        }
+    }
+
+    #[test]
+    fn streamed_leaf_proofs_match_batch_and_verify() {
+        for n in [1u32, 2, 3, 5, 6, 8, 11, 17] {
+            let blocks: Vec<_> = (1..=n)
+                .map(|id| mk_block(id, 2 + id as usize % 3))
+                .collect();
+            let man = commit_blocks(&blocks);
+            let leaves: Vec<_> = blocks.iter().map(leaf_hash).collect();
+            for (i, b) in blocks.iter().enumerate() {
+                let p = prove_leaf_stream(blocks.iter().cloned().map(Ok), i as u64).unwrap();
+                assert_eq!(p.path, inclusion_proof(&leaves, i).unwrap(), "n={n} i={i}");
+                verify_leaf(&man, b, &p).unwrap();
+
+                let other = &blocks[(i + 1) % blocks.len()];
+                assert!(n == 1 || verify_leaf(&man, other, &p).is_err());
+                let mut bigger = man.clone();
+                bigger.n_leaves += 1;
+                assert!(verify_leaf(&bigger, b, &p).is_err());
+            }
+            assert!(prove_leaf_stream(blocks.iter().cloned().map(Ok), u64::from(n)).is_err());
+        }
+
+        // A proof from a file travels as JSON.
+        let blocks: Vec<_> = (1..=5).map(|id| mk_block(id, 3)).collect();
+        let path = std::env::temp_dir().join(format!("sezkp_leaf_{}.json", std::process::id()));
+        core_io::write_block_summaries_json(&path, &blocks).unwrap();
+        let p = prove_leaf(&path, 3).unwrap();
+        let _ = std::fs::remove_file(&path);
+        let back: LeafProof = serde_json::from_str(&serde_json::to_string(&p).unwrap()).unwrap();
+        verify_leaf(&commit_blocks(&blocks), &blocks[3], &back).unwrap();
+    }
+
+    #[test]
+    fn manifest_bytes_match_the_cbor_file() {
+        let blocks: Vec<_> = (1..=3).map(|id| mk_block(id, 4)).collect();
+        let man = commit_blocks(&blocks).with_input_len(9);
+        let path = std::env::temp_dir().join(format!("sezkp_man_{}.cbor", std::process::id()));
+        write_manifest_cbor(&path, &man).unwrap();
+        let file = std::fs::read(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        let bytes = man.to_bytes().unwrap();
+        assert_eq!(bytes, file);
+        assert_eq!(CommitManifest::from_bytes(&bytes).unwrap(), man);
+        assert!(CommitManifest::from_bytes(&bytes[..bytes.len() / 2]).is_err());
+    }
+
+    #[test]
+    fn aux_commitments_commit_under_leaf_schema_v2() {
+        use sezkp_core::auxiliary::aux_commitment;
+
+        let blocks = vec![mk_block(1, 4), mk_block(2, 4)];
+        let plain = commit_blocks(&blocks);
+
+        let mut with_aux = blocks.clone();
+        with_aux[1].aux_commitments = vec![aux_commitment("pc-histogram", b"\x07")];
+        let man = commit_blocks(&with_aux);
+        assert_eq!(man.leaf_schema, LEAF_SCHEMA_REDACTED);
+        assert_eq!(leaf_hash(&with_aux[0]), leaf_hash(&blocks[0]));
+        assert_ne!(man.root, plain.root);
+        validate_blocks_against_manifest(&with_aux, &man).unwrap();
+        assert!(validate_blocks_against_manifest(&blocks, &man).is_err());
+
+        with_aux[1].aux_commitments[0][0] ^= 1;
+        assert!(validate_blocks_against_manifest(&with_aux, &man).is_err());
+    }
+
+    #[test]
+    fn v1_manifest_still_decodes_and_validates() {
+        let blocks = vec![mk_block(1, 4), mk_block(2, 4)];
+        let v2 = commit_blocks(&blocks);
+        let v1_json = format!(
+            r#"{{"version":1,"root":{},"n_leaves":2}}"#,
+            serde_json::to_string(&v2.root).unwrap()
+        );
+        let v1: CommitManifest = serde_json::from_str(&v1_json).unwrap();
+        assert!(!v1.has_shape());
+        assert_eq!(v1.leaf_schema, LEAF_SCHEMA_VERSION);
+        assert_eq!(v1.hash_scheme, HASH_SCHEME);
+        assert!(v1.creator.is_none());
+        validate_blocks_against_manifest(&blocks, &v1).unwrap();
+    }
+
+    #[test]
+    fn empty_and_single_block_manifests() {
+        let empty = commit_blocks(&[]);
+        assert!(empty.is_empty());
+        assert_eq!(empty.root, EMPTY_ROOT);
+        assert_eq!((empty.total_steps, empty.tau), (0, 0));
+        validate_blocks_against_manifest(&[], &empty).unwrap();
+
+        // A lone leaf is its own root.
+        let b = mk_block(1, 4);
+        let one = commit_blocks(std::slice::from_ref(&b));
+        assert_eq!(one.root, leaf_hash(&b));
+        validate_blocks_against_manifest(std::slice::from_ref(&b), &one).unwrap();
+        assert!(validate_blocks_against_manifest(&[], &one).is_err());
+
+        // The empty root is reserved for the empty set, and vice versa.
+        let bad = CommitManifest {
+            n_leaves: 0,
+            ..one.clone()
+        };
+        assert!(bad.check_well_formed().is_err());
+        let bad = CommitManifest {
+            root: EMPTY_ROOT,
+            ..one
+        };
+        assert!(bad.check_well_formed().is_err());
+    }
+
+    #[test]
+    fn misnamed_files_are_read_by_content() {
+        let dir = std::env::temp_dir().join(format!("sezkp_merkle_sniff_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let blocks: Vec<_> = (1..=4).map(|i| mk_block(i, 4)).collect();
+
+        // JSON Lines content behind a `.json` extension and behind no extension.
+        let mut lines = String::new();
+        for b in &blocks {
+            lines.push_str(&serde_json::to_string(b).unwrap());
+            lines.push('\n');
+        }
+        for name in ["blocks.json", "blocks"] {
+            let p = dir.join(name);
+            std::fs::write(&p, &lines).unwrap();
+            let m = commit_block_file(&p, dir.join("m.cbor")).unwrap();
+            assert_eq!(m.root, commit_blocks(&blocks).root);
+            verify_block_file_against_manifest(&p, dir.join("m.cbor")).unwrap();
+        }
+
+        // A CBOR manifest renamed to `.json` still loads.
+        std::fs::copy(dir.join("m.cbor"), dir.join("m.json")).unwrap();
+        assert_eq!(read_manifest_auto(dir.join("m.json")).unwrap().n_leaves, 4);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn trace_commit_matches_materialized_blocks() {
+        use sezkp_trace::{generator::generate_trace, partition::partition_trace};
+
+        let dir = std::env::temp_dir().join(format!("sezkp_merkle_trace_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let tf = generate_trace(100, 2);
+        let trace = dir.join("trace.cbor");
+        sezkp_trace::io::write_trace_auto(&trace, &tf).unwrap();
+
+        let m =
+            commit_trace_file(&trace, 16, PartitionStrategy::Fixed, dir.join("m.cbor")).unwrap();
+        let want = commit_blocks(&partition_trace(&tf, 16));
+        assert_eq!((m.root, m.n_leaves, m.total_steps), (want.root, 7, 100));
+        verify_trace_file_against_manifest(
+            &trace,
+            16,
+            PartitionStrategy::Fixed,
+            dir.join("m.cbor"),
+        )
+        .unwrap();
+
+        // Another block size or strategy is another commitment.
+        for (b, strategy) in [
+            (15, PartitionStrategy::Fixed),
+            (16, PartitionStrategy::Balanced),
+        ] {
+            let err = verify_trace_file_against_manifest(&trace, b, strategy, dir.join("m.cbor"))
+                .unwrap_err();
+            assert!(err.to_string().contains("mismatch"), "{err}");
+        }
+        assert!(commit_trace(&tf, 0, PartitionStrategy::Fixed).is_err());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn declared_input_len_carries_into_the_manifest() {
+        use sezkp_trace::generator::generate_trace;
+
+        let mut tf = generate_trace(20, 2);
+        let m = commit_trace(&tf, 4, PartitionStrategy::Fixed).unwrap();
+        assert_eq!(m.input_len, None);
+        assert!(!serde_json::to_string(&m).unwrap().contains("input_len"));
+
+        tf.input_len = Some(64);
+        let m = commit_trace(&tf, 4, PartitionStrategy::Fixed).unwrap();
+        assert_eq!(m.input_len, Some(64));
+        assert!(m.summary().contains("input_len=64"));
+        let back: CommitManifest =
+            serde_json::from_str(&serde_json::to_string(&m).unwrap()).unwrap();
+        assert_eq!(back, m.clone().with_input_len(64));
+    }
+
+    #[test]
+    fn checkpoints_fold_into_the_root_and_locate_divergence() {
+        let blocks: Vec<_> = (1..=10).map(|i| mk_block(i, 4)).collect();
+        let stream = |bs: &[BlockSummary]| bs.to_vec().into_iter().map(Ok);
+        let mut builder = ManifestBuilder::new().with_checkpoints(2).unwrap();
+        blocks.iter().for_each(|b| builder.push(b));
+        let man = builder.finish();
+        assert_eq!(man.root, commit_blocks(&blocks).root);
+        let cp = man.checkpoints.clone().unwrap();
+        let leaves: Vec<_> = blocks.iter().map(leaf_hash).collect();
+        let runs: Vec<_> = leaves.chunks(4).map(|c| merkle_root(c.to_vec())).collect();
+        assert_eq!(cp.roots, runs);
+        assert!(man.summary().contains("checkpoints=3x2^2"));
+        verify_block_stream(stream(&blocks), &man).unwrap();
+
+        // A corrupted block is pinned to its run, a truncated file to the last.
+        let mut bad = blocks.clone();
+        bad[5].ctrl_out = 1;
+        let err = verify_block_stream(stream(&bad), &man).unwrap_err();
+        assert!(
+            err.to_string().contains("leaves [4, 8) (run 1 of 3"),
+            "{err}"
+        );
+        let err = verify_block_stream(stream(&blocks[..9]), &man).unwrap_err();
+        assert!(err.to_string().contains("leaves [8, 10)"), "{err}");
+        let mut extra = blocks.clone();
+        extra.extend((11..=13).map(|i| mk_block(i, 4)));
+        let err = verify_block_stream(stream(&extra), &man).unwrap_err();
+        assert!(
+            err.to_string().contains("continue past the 10 leaves"),
+            "{err}"
+        );
+
+        // Checkpoints must cover every leaf and fold into the root.
+        let mut forged = man.clone();
+        forged.checkpoints.as_mut().unwrap().roots[0][0] ^= 1;
+        assert!(forged.check_well_formed().is_err());
+        forged.checkpoints.as_mut().unwrap().roots.pop();
+        assert!(forged.check_well_formed().is_err());
+        assert!(ManifestBuilder::new()
+            .with_checkpoints(MAX_CHECKPOINT_LOG2 + 1)
+            .is_err());
+
+        // Optional on the wire: absent unless recorded.
+        let plain = commit_blocks(&blocks);
+        assert!(!serde_json::to_string(&plain)
+            .unwrap()
+            .contains("checkpoints"));
+        let back: CommitManifest =
+            serde_json::from_str(&serde_json::to_string(&man).unwrap()).unwrap();
+        assert_eq!(back, man);
+    }
+
+    #[test]
+    fn chunked_file_commit_matches_in_memory() {
+        // Crosses two chunk boundaries, leaving a partial chunk.
+        let blocks: Vec<_> = (1..=2 * COMMIT_CHUNK as u32 + 5)
+            .map(|id| mk_block(id, 1 + id as usize % 4))
+            .collect();
+        let man = commit_blocks(&blocks);
+        let mut builder = ManifestBuilder::new();
+        builder.push_all(&blocks);
+        assert_eq!(builder.finish(), man);
+        #[cfg(feature = "parallel")]
+        assert_eq!(commit_blocks_parallel(&blocks), man);
+
+        let dir = std::env::temp_dir();
+        let tag = std::process::id();
+        let (inp, out) = (
+            dir.join(format!("sezkp_chunked_{tag}.jsonl")),
+            dir.join(format!("sezkp_chunked_{tag}.manifest.json")),
+        );
+        core_io::write_block_summaries_auto(&inp, &blocks).unwrap();
+        let got = commit_block_file(&inp, &out).unwrap();
+        let _ = (std::fs::remove_file(&inp), std::fs::remove_file(&out));
+        assert_eq!(got, man);
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn compressed_block_files_commit_like_plain_ones() {
+        let blocks: Vec<_> = (1..=40).map(|id| mk_block(id, 1 + id as usize % 4)).collect();
+        let man = commit_blocks(&blocks);
+        let dir = std::env::temp_dir();
+        let tag = std::process::id();
+        let out = dir.join(format!("sezkp_zst_{tag}.manifest.json"));
+        for ext in ["jsonl.zst", "cbor.zst", "json.zst"] {
+            let inp = dir.join(format!("sezkp_zst_{tag}.{ext}"));
+            core_io::write_block_summaries_auto(&inp, &blocks).unwrap();
+            let got = commit_block_file(&inp, &out).unwrap();
+            assert_eq!(got, man, "{ext}");
+            verify_block_file_against_manifest(&inp, &out).unwrap();
+            let _ = std::fs::remove_file(&inp);
+        }
+        let _ = std::fs::remove_file(&out);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_roots_match_across_thread_counts() {
+        use sezkp_core::parallelism::{set_parallelism, ChunkPolicy, ParallelismConfig};
+
+        let blocks: Vec<_> = (1..=300).map(|id| mk_block(id, 1 + id as usize % 5)).collect();
+        let man = commit_blocks(&blocks);
+        for threads in [1, 2, 4, 7] {
+            for chunking in [ChunkPolicy::Adaptive, ChunkPolicy::MinItems(64.try_into().unwrap())] {
+                set_parallelism(ParallelismConfig {
+                    threads: threads.try_into().ok(),
+                    chunking,
+                });
+                assert_eq!(commit_blocks_parallel(&blocks), man, "{threads} threads");
+            }
+        }
+        set_parallelism(ParallelismConfig::default());
+    }
+
     #[test]
     fn frontier_matches_batch_merkle() {
         // Random-ish sizes to hit many promotion patterns.
@@ -486,11 +1773,11 @@ mod tests {
             let batch = merkle_root(leaves.clone());
 
             // Streaming frontier root.
-            let mut f = Frontier::default();
+            let mut f = MerkleFrontier::new();
             for l in leaves {
-                f.push_leaf(l);
+                f.push(l);
             }
-            let stream = f.finalize_root();
+            let stream = f.root();
 
             assert_eq!(batch, stream);
         }
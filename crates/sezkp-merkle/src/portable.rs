@@ -0,0 +1,412 @@
+//! Self-describing export of leaf inclusion proofs.
+//!
+//! A [`PortableProof`] carries everything needed to check that a block is
+//! committed to by a manifest root without linking this crate, in JSON or
+//! CBOR. The schema (`format = "sezkp-inclusion-v1"`):
+//!
+//! | field      | JSON                      | CBOR          | meaning                          |
+//! |------------|---------------------------|---------------|----------------------------------|
+//! | `format`   | string                    | text          | `"sezkp-inclusion-v1"`           |
+//! | `hash`     | string                    | text          | node hash, `"blake3"`            |
+//! | `index`    | integer                   | uint          | leaf position, 0-based           |
+//! | `n_leaves` | integer                   | uint          | leaves under `root`              |
+//! | `leaf`     | 64 hex digits             | 32-byte bstr  | leaf hash (see [`crate::leaf_hash`]) |
+//! | `path`     | array of `{side, hash}`   | array of maps | sibling steps, bottom-up         |
+//! | `root`     | 64 hex digits             | 32-byte bstr  | manifest root                    |
+//!
+//! To verify, start from `leaf` and for each step compute
+//! `node = BLAKE3(hash || node)` if `side` is `"left"` (the sibling is the
+//! left child) or `node = BLAKE3(node || hash)` if it is `"right"`; the
+//! result must equal `root`. There is no domain tag or length prefix in the
+//! node hash, and an empty tree has no inclusion proofs.
+//!
+//! The steps read as OpenTimestamps-style `prepend`/`append` operations
+//! (`left` = prepend, `right` = append) each followed by the node hash, but
+//! with BLAKE3, which OpenTimestamps does not define.
+//!
+//! The sides are implied by `index` and `n_leaves` (levels where the node is
+//! the promoted odd one have no step), so an importer that knows the tree
+//! shape can cross-check them: [`PortableProof::to_inclusion_proof`] does.
+
+use anyhow::{bail, ensure, Context, Result};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sezkp_core::io_format::{resolve_read_format, resolve_write_format, FileFormat};
+use sezkp_core::render::{parse_root, render_root, RootEncoding, RootFormat};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+
+use crate::{node_hash, InclusionProof, HASH_SCHEME};
+
+/// [`PortableProof::format`] of this schema.
+pub const PORTABLE_FORMAT: &str = "sezkp-inclusion-v1";
+
+/// Which child of the next node a sibling is.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    /// The sibling is the left child: `BLAKE3(sibling || node)`.
+    Left,
+    /// The sibling is the right child: `BLAKE3(node || sibling)`.
+    Right,
+}
+
+/// One step up the path.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PathStep {
+    /// Position of the sibling.
+    pub side: Side,
+    /// Sibling hash.
+    #[serde(with = "hash32")]
+    pub hash: [u8; 32],
+}
+
+/// Leaf inclusion proof in the documented exchange schema (see the module
+/// docs).
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PortableProof {
+    /// Schema identifier ([`PORTABLE_FORMAT`]).
+    pub format: String,
+    /// Node hash function ([`HASH_SCHEME`]).
+    pub hash: String,
+    /// Position of the leaf (0-based).
+    pub index: u64,
+    /// Number of leaves under [`Self::root`].
+    pub n_leaves: u64,
+    /// Leaf hash.
+    #[serde(with = "hash32")]
+    pub leaf: [u8; 32],
+    /// Sibling steps, bottom-up.
+    pub path: Vec<PathStep>,
+    /// Root the path leads to.
+    #[serde(with = "hash32")]
+    pub root: [u8; 32],
+}
+
+impl InclusionProof {
+    /// Export this path for `leaf` in the portable schema.
+    ///
+    /// # Errors
+    /// Fails if the path does not fit its tree (see [`Self::root`]).
+    pub fn to_portable(&self, leaf: &[u8; 32]) -> Result<PortableProof> {
+        let root = self
+            .root(leaf)
+            .with_context(|| format!("malformed inclusion proof for leaf {}", self.index))?;
+        let path = self
+            .siblings
+            .iter()
+            .zip(sides(self.index, self.n_leaves))
+            .map(|(hash, side)| PathStep { side, hash: *hash })
+            .collect();
+        Ok(PortableProof {
+            format: PORTABLE_FORMAT.to_owned(),
+            hash: HASH_SCHEME.to_owned(),
+            index: self.index,
+            n_leaves: self.n_leaves,
+            leaf: *leaf,
+            path,
+            root,
+        })
+    }
+}
+
+impl PortableProof {
+    /// Root the path yields for [`Self::leaf`], following the side markers
+    /// alone (what a foreign verifier computes).
+    #[must_use]
+    pub fn computed_root(&self) -> [u8; 32] {
+        self.path
+            .iter()
+            .fold(self.leaf, |node, step| match step.side {
+                Side::Left => node_hash(&step.hash, &node),
+                Side::Right => node_hash(&node, &step.hash),
+            })
+    }
+
+    /// Check the schema identifiers and that the path leads to `root`.
+    ///
+    /// # Errors
+    /// Fails on another format or hash, or if the path leads elsewhere.
+    pub fn verify(&self, root: &[u8; 32]) -> Result<()> {
+        self.check_format()?;
+        ensure!(
+            &self.root == root,
+            "inclusion proof is for root {}, expected {}",
+            render_root(&self.root),
+            render_root(root)
+        );
+        ensure!(
+            self.computed_root() == self.root,
+            "leaf {} of {} does not lead to root {}",
+            self.index,
+            self.n_leaves,
+            render_root(root)
+        );
+        Ok(())
+    }
+
+    /// Convert back to an [`InclusionProof`], checking that the side
+    /// markers match the tree shape `index`/`n_leaves` imply and that the
+    /// path leads to [`Self::root`].
+    ///
+    /// # Errors
+    /// Fails on another format or hash, on markers or a step count that do
+    /// not fit the tree, or if the path leads elsewhere.
+    pub fn to_inclusion_proof(&self) -> Result<InclusionProof> {
+        self.check_format()?;
+        ensure!(
+            self.index < self.n_leaves,
+            "leaf index {} out of range for {} leaves",
+            self.index,
+            self.n_leaves
+        );
+        let expected = sides(self.index, self.n_leaves);
+        ensure!(
+            expected.len() == self.path.len(),
+            "inclusion path has {} steps; leaf {} of {} needs {}",
+            self.path.len(),
+            self.index,
+            self.n_leaves,
+            expected.len()
+        );
+        if let Some(level) = self
+            .path
+            .iter()
+            .zip(&expected)
+            .position(|(step, side)| step.side != *side)
+        {
+            bail!(
+                "inclusion path step {level} is marked {:?}, but leaf {} of {} has its sibling on the {:?}",
+                self.path[level].side,
+                self.index,
+                self.n_leaves,
+                expected[level]
+            );
+        }
+        let proof = InclusionProof {
+            index: self.index,
+            n_leaves: self.n_leaves,
+            siblings: self.path.iter().map(|s| s.hash).collect(),
+        };
+        proof.verify(&self.leaf, &self.root)?;
+        Ok(proof)
+    }
+
+    fn check_format(&self) -> Result<()> {
+        ensure!(
+            self.format == PORTABLE_FORMAT,
+            "unsupported inclusion proof format {:?} (expected {PORTABLE_FORMAT:?})",
+            self.format
+        );
+        ensure!(
+            self.hash == HASH_SCHEME,
+            "unsupported inclusion proof hash {:?} (expected {HASH_SCHEME:?})",
+            self.hash
+        );
+        Ok(())
+    }
+}
+
+/// Sibling sides, bottom-up, on the path to leaf `index` of `n_leaves`.
+fn sides(index: u64, n_leaves: u64) -> Vec<Side> {
+    let (mut pos, mut width) = (index, n_leaves);
+    let mut out = Vec::new();
+    while width > 1 {
+        if pos % 2 == 1 {
+            out.push(Side::Left);
+        } else if pos + 1 < width {
+            out.push(Side::Right);
+        }
+        pos /= 2;
+        width = width.div_ceil(2);
+    }
+    out
+}
+
+/* ----------------------------------- I/O ----------------------------------- */
+
+/// Formats accepted for inclusion proof files.
+const PROOF_FORMATS: [FileFormat; 2] = [FileFormat::Json, FileFormat::Cbor];
+
+/// Read an inclusion proof, JSON or CBOR by extension.
+///
+/// # Errors
+/// Fails if the file cannot be read or decoded.
+pub fn read_portable_auto<P: AsRef<Path>>(path: P) -> Result<PortableProof> {
+    let path = path.as_ref();
+    let f = File::open(path).with_context(|| format!("open {}", path.display()))?;
+    let rdr = BufReader::new(f);
+    match resolve_read_format(path, "inclusion proof", &PROOF_FORMATS)? {
+        FileFormat::Cbor => {
+            ciborium::de::from_reader(rdr).context("deserialize CBOR inclusion proof")
+        }
+        _ => serde_json::from_reader(rdr).context("deserialize JSON inclusion proof"),
+    }
+}
+
+/// Write an inclusion proof, JSON (pretty) or CBOR by extension.
+///
+/// # Errors
+/// Fails if the file cannot be written.
+pub fn write_portable_auto<P: AsRef<Path>>(path: P, proof: &PortableProof) -> Result<()> {
+    let path = path.as_ref();
+    let f = File::create(path).with_context(|| format!("create {}", path.display()))?;
+    let mut w = BufWriter::new(f);
+    match resolve_write_format(path, "inclusion proof", &PROOF_FORMATS)? {
+        FileFormat::Cbor => {
+            ciborium::ser::into_writer(proof, &mut w).context("serialize CBOR inclusion proof")?;
+        }
+        _ => {
+            serde_json::to_writer_pretty(&mut w, proof)
+                .context("serialize JSON inclusion proof")?;
+        }
+    }
+    w.flush().context("flush inclusion proof")?;
+    Ok(())
+}
+
+/// 32-byte hashes as lowercase hex in human-readable formats (JSON) and as
+/// byte strings otherwise (CBOR).
+mod hash32 {
+    use super::{parse_root, Deserialize, Deserializer, RootEncoding, RootFormat, Serializer};
+
+    pub fn serialize<S: Serializer>(h: &[u8; 32], s: S) -> Result<S::Ok, S::Error> {
+        if s.is_human_readable() {
+            let hex = RootFormat {
+                encoding: RootEncoding::Hex,
+                checksum: false,
+            };
+            s.serialize_str(&hex.render(h))
+        } else {
+            s.serialize_bytes(h)
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<[u8; 32], D::Error> {
+        use serde::de::Error;
+        if d.is_human_readable() {
+            let s = String::deserialize(d)?;
+            parse_root(&s).map_err(|e| D::Error::custom(format!("{e:#}")))
+        } else {
+            let b = byte_buf(d)?;
+            <[u8; 32]>::try_from(b.as_slice())
+                .map_err(|_| D::Error::invalid_length(b.len(), &"32 bytes"))
+        }
+    }
+
+    /// A CBOR byte string (or an array of byte values).
+    fn byte_buf<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<u8>, D::Error> {
+        struct V;
+        impl<'de> serde::de::Visitor<'de> for V {
+            type Value = Vec<u8>;
+            fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str("a 32-byte string")
+            }
+            fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> Result<Vec<u8>, E> {
+                Ok(v.to_vec())
+            }
+            fn visit_byte_buf<E: serde::de::Error>(self, v: Vec<u8>) -> Result<Vec<u8>, E> {
+                Ok(v)
+            }
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> Result<Vec<u8>, A::Error> {
+                let mut out = Vec::with_capacity(32);
+                while let Some(b) = seq.next_element::<u8>()? {
+                    out.push(b);
+                }
+                Ok(out)
+            }
+        }
+        d.deserialize_bytes(V)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{inclusion_proof, merkle_root};
+
+    fn leaves(n: u64) -> Vec<[u8; 32]> {
+        (0..n)
+            .map(|i| *blake3::hash(&i.to_le_bytes()).as_bytes())
+            .collect()
+    }
+
+    #[test]
+    fn exported_paths_verify_by_their_markers_alone() {
+        for n in 1..=40u64 {
+            let leaves = leaves(n);
+            let root = merkle_root(leaves.clone());
+            for i in 0..n as usize {
+                let p = inclusion_proof(&leaves, i)
+                    .unwrap()
+                    .to_portable(&leaves[i])
+                    .unwrap();
+                assert_eq!(p.root, root);
+                assert_eq!(p.computed_root(), root);
+                p.verify(&root).unwrap();
+                assert_eq!(
+                    p.to_inclusion_proof().unwrap(),
+                    inclusion_proof(&leaves, i).unwrap()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn json_and_cbor_round_trip() {
+        let leaves = leaves(11);
+        let p = inclusion_proof(&leaves, 6)
+            .unwrap()
+            .to_portable(&leaves[6])
+            .unwrap();
+
+        let json = serde_json::to_value(&p).unwrap();
+        assert_eq!(json["format"], PORTABLE_FORMAT);
+        assert_eq!(json["path"][0]["side"], "right");
+        assert_eq!(json["leaf"].as_str().unwrap().len(), 64);
+        assert_eq!(serde_json::from_value::<PortableProof>(json).unwrap(), p);
+
+        let mut cbor = Vec::new();
+        ciborium::ser::into_writer(&p, &mut cbor).unwrap();
+        // Hashes are 32-byte strings (major type 2, header 0x58 0x20).
+        assert!(cbor
+            .windows(34)
+            .any(|w| w[..2] == [0x58, 0x20] && w[2..] == p.leaf));
+        let back: PortableProof = ciborium::de::from_reader(&cbor[..]).unwrap();
+        assert_eq!(back, p);
+    }
+
+    #[test]
+    fn tampered_proofs_are_rejected() {
+        let leaves = leaves(11);
+        let root = merkle_root(leaves.clone());
+        let honest = inclusion_proof(&leaves, 10)
+            .unwrap()
+            .to_portable(&leaves[10])
+            .unwrap();
+
+        let mut p = honest.clone();
+        p.leaf[0] ^= 1;
+        assert!(p.verify(&root).is_err());
+        assert!(p.to_inclusion_proof().is_err());
+
+        let mut p = honest.clone();
+        p.path[0].side = Side::Right;
+        assert!(p.verify(&root).is_err());
+        assert!(p.to_inclusion_proof().is_err());
+
+        let mut p = honest.clone();
+        p.hash = "sha256".into();
+        assert!(p.verify(&root).is_err());
+
+        // Leaf 10 of 11 is promoted twice: a lie about the width that keeps
+        // the same steps leads to the same root, but not to the same shape.
+        let mut p = honest;
+        p.n_leaves = 12;
+        p.verify(&root).unwrap();
+        assert!(p.to_inclusion_proof().is_err());
+    }
+}
@@ -5,6 +5,10 @@
 //!   without Node toolchains.
 //! - When the `node` feature is enabled, we compile a minimal N-API addon that
 //!   exposes `version()` to JavaScript.
+//! - Exposes the canonical commitment hashes (see [`digests`]) so JS trace
+//!   tooling can precompute and cross-check commitments without the CLI.
+//! - Converts manifests and proof artifacts between raw bytes and JSON (see
+//!   [`blobs`]), so JS callers can store them as blobs.
 //!
 //! ## Building the addon (locally)
 //! ```bash
@@ -31,11 +35,165 @@
     clippy::expect_used
 )]
 
+pub mod digests {
+    //! Canonical hash helpers over JSON-encoded inputs, hex-encoded outputs.
+    //!
+    //! Blocks use the same JSON shape as a `.json` blocks file entry. These
+    //! are plain Rust functions so the default (stub) build can test them;
+    //! the `node` feature wraps each one as a `#[napi]` export.
+
+    use anyhow::{ensure, Context, Result};
+    use sezkp_core::BlockSummary;
+    use sezkp_fold::are::{Pi, Q};
+    use sezkp_stark::v1::field::F1;
+
+    /// Parse a block and check its per-tape vectors agree on `τ`, so the
+    /// hashers below never index out of range.
+    fn block(json: &str) -> Result<BlockSummary> {
+        let b: BlockSummary = serde_json::from_str(json).context("parsing block summary JSON")?;
+        let tau = b.windows.len();
+        ensure!(
+            b.head_in_offsets.len() == tau
+                && b.head_out_offsets.len() == tau
+                && b.movement_log.steps.iter().all(|s| s.tapes.len() == tau),
+            "block {} has inconsistent tape count (windows: {tau})",
+            b.block_id
+        );
+        Ok(b)
+    }
+
+    /// Merkle leaf hash of one block (`sezkp_merkle::leaf_hash`).
+    ///
+    /// # Errors
+    /// Fails if `block_json` is not a valid block summary.
+    pub fn leaf_hash(block_json: &str) -> Result<String> {
+        Ok(hex::encode(sezkp_merkle::leaf_hash(&block(block_json)?)))
+    }
+
+    /// Interface digest between two adjacent blocks
+    /// (`sezkp_core::boundary::interface_boundary_digest`).
+    ///
+    /// # Errors
+    /// Fails if either input is not a valid block summary, or the blocks
+    /// disagree on the number of tapes.
+    pub fn interface_boundary_digest(left_json: &str, right_json: &str) -> Result<String> {
+        let (left, right) = (block(left_json)?, block(right_json)?);
+        ensure!(
+            left.windows.len() == right.windows.len(),
+            "tape count mismatch: {} vs {}",
+            left.windows.len(),
+            right.windows.len()
+        );
+        Ok(hex::encode(
+            sezkp_core::boundary::interface_boundary_digest(&left, &right),
+        ))
+    }
+
+    /// Commitment to a fold projection `π` (`sezkp_fold::api::commit_pi`).
+    ///
+    /// `acc` holds the accumulator registers as canonical field values.
+    ///
+    /// # Errors
+    /// Fails unless `acc` has exactly [`Q`] entries.
+    pub fn commit_pi(ctrl_in: u32, ctrl_out: u32, flags: u32, acc: &[u64]) -> Result<String> {
+        ensure!(
+            acc.len() == Q,
+            "acc must have {Q} entries, got {}",
+            acc.len()
+        );
+        let mut regs = [F1::from_u64(0); Q];
+        for (r, &a) in regs.iter_mut().zip(acc) {
+            *r = F1::from_u64(a);
+        }
+        let pi = Pi {
+            ctrl_in,
+            ctrl_out,
+            flags,
+            acc: regs,
+        };
+        Ok(hex::encode(sezkp_fold::api::commit_pi(&pi).0))
+    }
+
+    /// Parse one accumulator register given as a decimal or `0x`-prefixed
+    /// hex string, for hosts without a native 64-bit integer.
+    ///
+    /// # Errors
+    /// Fails unless `s` is a `u64` in one of those two forms.
+    pub fn parse_reg(s: &str) -> Result<u64> {
+        let s = s.trim();
+        let v = s
+            .strip_prefix("0x")
+            .or_else(|| s.strip_prefix("0X"))
+            .map_or_else(|| s.parse(), |hex| u64::from_str_radix(hex, 16));
+        v.with_context(|| format!("invalid accumulator register {s:?}"))
+    }
+}
+
+pub mod blobs {
+    //! Manifests and proof artifacts as raw CBOR bytes, the exact contents of
+    //! `.cbor` files.
+    //!
+    //! Host languages keep the bytes in their own storage and convert to JSON
+    //! only for inspection. Plain Rust functions, as in [`super::digests`]; the `node` feature wraps each one as a `#[napi]` export.
+
+    use anyhow::{Context, Result};
+    use sezkp_core::ProofArtifact;
+    use sezkp_merkle::CommitManifest;
+
+    /// JSON rendering of manifest bytes.
+    ///
+    /// # Errors
+    /// Fails if `bytes` are not an encoded manifest.
+    pub fn manifest_to_json(bytes: &[u8]) -> Result<String> {
+        let man = CommitManifest::from_bytes(bytes)?;
+        serde_json::to_string(&man).context("serializing manifest JSON")
+    }
+
+    /// Manifest bytes from JSON, checked to be well formed and produced by a
+    /// compatible build.
+    ///
+    /// # Errors
+    /// Fails on invalid JSON or a malformed or incompatible manifest.
+    pub fn manifest_from_json(json: &str) -> Result<Vec<u8>> {
+        let man: CommitManifest = serde_json::from_str(json).context("parsing manifest JSON")?;
+        man.check_well_formed()?;
+        man.check_compatible()?;
+        man.to_bytes()
+    }
+
+    /// JSON rendering of proof artifact bytes.
+    ///
+    /// # Errors
+    /// Fails if `bytes` are not an encoded artifact or its proof bytes do not
+    /// match the recorded digest.
+    pub fn proof_to_json(bytes: &[u8]) -> Result<String> {
+        let art = ProofArtifact::from_bytes(bytes)?;
+        serde_json::to_string(&art).context("serializing proof artifact JSON")
+    }
+
+    /// Proof artifact bytes from JSON.
+    ///
+    /// # Errors
+    /// Fails on invalid JSON or an artifact whose proof bytes do not match
+    /// the recorded digest.
+    pub fn proof_from_json(json: &str) -> Result<Vec<u8>> {
+        let art: ProofArtifact =
+            serde_json::from_str(json).context("parsing proof artifact JSON")?;
+        art.to_bytes()
+    }
+}
+
 #[cfg(feature = "node")]
 mod node_api {
     use napi::bindgen_prelude::*;
     use napi_derive::napi;
 
+    use crate::{blobs, digests};
+
+    fn js_err(e: anyhow::Error) -> Error {
+        Error::from_reason(format!("{e:#}"))
+    }
+
     /// Return the crate version as a JavaScript string.
     ///
     /// ### JS usage (after building addon)
@@ -47,10 +205,78 @@ mod node_api {
     pub fn version() -> String {
         env!("CARGO_PKG_VERSION").to_string()
     }
+
+    /// Hex leaf hash of a block given as a JSON string.
+    ///
+    /// ```js
+    /// leafHash(JSON.stringify(block));
+    /// ```
+    #[napi(js_name = "leafHash")]
+    pub fn leaf_hash(block_json: String) -> Result<String> {
+        digests::leaf_hash(&block_json).map_err(js_err)
+    }
+
+    /// Hex interface digest between two adjacent blocks (JSON strings).
+    #[napi(js_name = "interfaceBoundaryDigest")]
+    pub fn interface_boundary_digest(left_json: String, right_json: String) -> Result<String> {
+        digests::interface_boundary_digest(&left_json, &right_json).map_err(js_err)
+    }
+
+    /// Hex commitment to a fold projection `π`.
+    ///
+    /// Accumulator registers are passed as decimal or `0x`-hex strings since
+    /// field values exceed `Number.MAX_SAFE_INTEGER`:
+    /// ```js
+    /// commitPi(ctrlIn, ctrlOut, flags, ['0', '0', '0', '0x1f']);
+    /// ```
+    #[napi(js_name = "commitPi")]
+    pub fn commit_pi(ctrl_in: u32, ctrl_out: u32, flags: u32, acc: Vec<String>) -> Result<String> {
+        let acc = acc
+            .iter()
+            .map(|s| digests::parse_reg(s))
+            .collect::<anyhow::Result<Vec<u64>>>()
+            .map_err(js_err)?;
+        digests::commit_pi(ctrl_in, ctrl_out, flags, &acc).map_err(js_err)
+    }
+
+    /// JSON string of a manifest given as a `Buffer` (a `.cbor` manifest file).
+    ///
+    /// ```js
+    /// JSON.parse(manifestToJson(fs.readFileSync('manifest.cbor'))).root;
+    /// ```
+    #[napi(js_name = "manifestToJson")]
+    pub fn manifest_to_json(bytes: Buffer) -> Result<String> {
+        blobs::manifest_to_json(&bytes).map_err(js_err)
+    }
+
+    /// Manifest `Buffer` from a JSON string.
+    #[napi(js_name = "manifestFromJson")]
+    pub fn manifest_from_json(json: String) -> Result<Buffer> {
+        blobs::manifest_from_json(&json)
+            .map(Buffer::from)
+            .map_err(js_err)
+    }
+
+    /// JSON string of a proof artifact given as a `Buffer` (a `.cbor` proof file).
+    #[napi(js_name = "proofToJson")]
+    pub fn proof_to_json(bytes: Buffer) -> Result<String> {
+        blobs::proof_to_json(&bytes).map_err(js_err)
+    }
+
+    /// Proof artifact `Buffer` from a JSON string.
+    #[napi(js_name = "proofFromJson")]
+    pub fn proof_from_json(json: String) -> Result<Buffer> {
+        blobs::proof_from_json(&json)
+            .map(Buffer::from)
+            .map_err(js_err)
+    }
 }
 
 #[cfg(feature = "node")]
-pub use node_api::version;
+pub use node_api::{
+    commit_pi, interface_boundary_digest, leaf_hash, manifest_from_json, manifest_to_json,
+    proof_from_json, proof_to_json, version,
+};
 
 #[cfg(not(feature = "node"))]
 mod no_node {
@@ -4,6 +4,10 @@
 //! - Builds as a normal Rust library by default (no Python toolchain needed).
 //! - With the `python` feature enabled, compiles a minimal CPython extension
 //!   module exposing `version()` using PyO3 (stable abi3 for Python ≥ 3.8).
+//! - Exposes the canonical commitment hashes (see [`digests`]) so Python trace
+//!   tooling can precompute and cross-check commitments without the CLI.
+//! - Converts manifests and proof artifacts between raw bytes and JSON (see
+//!   [`blobs`]), so Python callers can store them as blobs.
 //!
 //! ## Building the extension (locally)
 //! ```bash
@@ -33,9 +37,244 @@
     clippy::expect_used
 )]
 
+pub mod digests {
+    //! Canonical hash helpers over JSON-encoded inputs, hex-encoded outputs.
+    //!
+    //! Blocks use the same JSON shape as a `.json` blocks file entry. These
+    //! are plain Rust functions so the default (stub) build can test them;
+    //! the `python` feature wraps each one as a `#[pyfunction]`.
+
+    use anyhow::{ensure, Context, Result};
+    use sezkp_core::BlockSummary;
+    use sezkp_fold::are::{Pi, Q};
+    use sezkp_stark::v1::field::F1;
+
+    /// Parse a block and check its per-tape vectors agree on `τ`, so the
+    /// hashers below never index out of range.
+    fn block(json: &str) -> Result<BlockSummary> {
+        let b: BlockSummary = serde_json::from_str(json).context("parsing block summary JSON")?;
+        let tau = b.windows.len();
+        ensure!(
+            b.head_in_offsets.len() == tau
+                && b.head_out_offsets.len() == tau
+                && b.movement_log.steps.iter().all(|s| s.tapes.len() == tau),
+            "block {} has inconsistent tape count (windows: {tau})",
+            b.block_id
+        );
+        Ok(b)
+    }
+
+    /// Merkle leaf hash of one block (`sezkp_merkle::leaf_hash`).
+    ///
+    /// # Errors
+    /// Fails if `block_json` is not a valid block summary.
+    pub fn leaf_hash(block_json: &str) -> Result<String> {
+        Ok(hex::encode(sezkp_merkle::leaf_hash(&block(block_json)?)))
+    }
+
+    /// Interface digest between two adjacent blocks
+    /// (`sezkp_core::boundary::interface_boundary_digest`).
+    ///
+    /// # Errors
+    /// Fails if either input is not a valid block summary, or the blocks
+    /// disagree on the number of tapes.
+    pub fn interface_boundary_digest(left_json: &str, right_json: &str) -> Result<String> {
+        let (left, right) = (block(left_json)?, block(right_json)?);
+        ensure!(
+            left.windows.len() == right.windows.len(),
+            "tape count mismatch: {} vs {}",
+            left.windows.len(),
+            right.windows.len()
+        );
+        Ok(hex::encode(
+            sezkp_core::boundary::interface_boundary_digest(&left, &right),
+        ))
+    }
+
+    /// Commitment to a fold projection `π` (`sezkp_fold::api::commit_pi`).
+    ///
+    /// `acc` holds the accumulator registers as canonical field values.
+    ///
+    /// # Errors
+    /// Fails unless `acc` has exactly [`Q`] entries.
+    pub fn commit_pi(ctrl_in: u32, ctrl_out: u32, flags: u32, acc: &[u64]) -> Result<String> {
+        ensure!(
+            acc.len() == Q,
+            "acc must have {Q} entries, got {}",
+            acc.len()
+        );
+        let mut regs = [F1::from_u64(0); Q];
+        for (r, &a) in regs.iter_mut().zip(acc) {
+            *r = F1::from_u64(a);
+        }
+        let pi = Pi {
+            ctrl_in,
+            ctrl_out,
+            flags,
+            acc: regs,
+        };
+        Ok(hex::encode(sezkp_fold::api::commit_pi(&pi).0))
+    }
+
+    #[cfg(test)]
+    mod tests {
+        #![allow(clippy::unwrap_used)]
+
+        use super::*;
+        use sezkp_core::{MovementLog, StepProjection, TapeOp, Window};
+
+        fn demo(id: u32, mv: i8) -> BlockSummary {
+            BlockSummary {
+                version: 1,
+                block_id: id,
+                step_lo: 1,
+                step_hi: 1,
+                ctrl_in: 0,
+                ctrl_out: 0,
+                in_head_in: 0,
+                in_head_out: 0,
+                windows: vec![Window { left: 0, right: 1 }],
+                head_in_offsets: vec![0],
+                head_out_offsets: vec![1],
+                movement_log: MovementLog {
+                    steps: vec![StepProjection {
+                        input_mv: 0,
+                        tapes: vec![TapeOp { write: None, mv }],
+                    }],
+                },
+                pre_tags: vec![[0u8; 16]; 1],
+                post_tags: vec![[0u8; 16]; 1],
+                write_commitments: Vec::new(),
+                aux_commitments: Vec::new(),
+            }
+        }
+
+        #[test]
+        fn json_helpers_match_native_hashes() {
+            let (a, b) = (demo(1, 1), demo(2, 0));
+            let (ja, jb) = (
+                serde_json::to_string(&a).unwrap(),
+                serde_json::to_string(&b).unwrap(),
+            );
+            assert_eq!(
+                leaf_hash(&ja).unwrap(),
+                hex::encode(sezkp_merkle::leaf_hash(&a))
+            );
+            assert_eq!(
+                interface_boundary_digest(&ja, &jb).unwrap(),
+                hex::encode(sezkp_core::boundary::interface_boundary_digest(&a, &b))
+            );
+            assert_eq!(
+                commit_pi(0, 0, 0, &[0; Q]).unwrap(),
+                hex::encode(sezkp_fold::api::commit_pi(&Pi::default()).0)
+            );
+
+            assert!(leaf_hash("{}").is_err());
+            assert!(commit_pi(0, 0, 0, &[0; 3]).is_err());
+            let mut bad = a;
+            bad.head_out_offsets.clear();
+            assert!(leaf_hash(&serde_json::to_string(&bad).unwrap()).is_err());
+        }
+    }
+}
+
+pub mod blobs {
+    //! Manifests and proof artifacts as raw CBOR bytes, the exact contents of
+    //! `.cbor` files.
+    //!
+    //! Host languages keep the bytes in their own storage and convert to JSON
+    //! only for inspection. Plain Rust functions, as in [`super::digests`]; the `python` feature wraps each one as a `#[pyfunction]`.
+
+    use anyhow::{Context, Result};
+    use sezkp_core::ProofArtifact;
+    use sezkp_merkle::CommitManifest;
+
+    /// JSON rendering of manifest bytes.
+    ///
+    /// # Errors
+    /// Fails if `bytes` are not an encoded manifest.
+    pub fn manifest_to_json(bytes: &[u8]) -> Result<String> {
+        let man = CommitManifest::from_bytes(bytes)?;
+        serde_json::to_string(&man).context("serializing manifest JSON")
+    }
+
+    /// Manifest bytes from JSON, checked to be well formed and produced by a
+    /// compatible build.
+    ///
+    /// # Errors
+    /// Fails on invalid JSON or a malformed or incompatible manifest.
+    pub fn manifest_from_json(json: &str) -> Result<Vec<u8>> {
+        let man: CommitManifest = serde_json::from_str(json).context("parsing manifest JSON")?;
+        man.check_well_formed()?;
+        man.check_compatible()?;
+        man.to_bytes()
+    }
+
+    /// JSON rendering of proof artifact bytes.
+    ///
+    /// # Errors
+    /// Fails if `bytes` are not an encoded artifact or its proof bytes do not
+    /// match the recorded digest.
+    pub fn proof_to_json(bytes: &[u8]) -> Result<String> {
+        let art = ProofArtifact::from_bytes(bytes)?;
+        serde_json::to_string(&art).context("serializing proof artifact JSON")
+    }
+
+    /// Proof artifact bytes from JSON.
+    ///
+    /// # Errors
+    /// Fails on invalid JSON or an artifact whose proof bytes do not match
+    /// the recorded digest.
+    pub fn proof_from_json(json: &str) -> Result<Vec<u8>> {
+        let art: ProofArtifact =
+            serde_json::from_str(json).context("parsing proof artifact JSON")?;
+        art.to_bytes()
+    }
+
+    #[cfg(test)]
+    mod tests {
+        #![allow(clippy::unwrap_used)]
+
+        use super::*;
+        use sezkp_core::{BackendKind, EMPTY_ROOT};
+
+        #[test]
+        fn blobs_roundtrip_through_json() {
+            let man = sezkp_merkle::commit_blocks(&[]);
+            let bytes = man.to_bytes().unwrap();
+            let json = manifest_to_json(&bytes).unwrap();
+            assert_eq!(manifest_from_json(&json).unwrap(), bytes);
+
+            let art = ProofArtifact::new(
+                BackendKind::Stark,
+                EMPTY_ROOT,
+                vec![1, 2, 3],
+                serde_json::json!({"proto": "stark-v1"}),
+            );
+            let bytes = art.to_bytes().unwrap();
+            let json = proof_to_json(&bytes).unwrap();
+            assert_eq!(proof_from_json(&json).unwrap(), bytes);
+
+            assert!(manifest_to_json(&bytes[..4]).is_err());
+            assert!(proof_to_json(b"not cbor").is_err());
+            let mut bad = man;
+            bad.root = [1u8; 32];
+            assert!(manifest_from_json(&serde_json::to_string(&bad).unwrap()).is_err());
+        }
+    }
+}
+
 #[cfg(feature = "python")]
 mod py {
+    use pyo3::exceptions::PyValueError;
     use pyo3::prelude::*;
+    use pyo3::types::PyBytes;
+
+    use crate::{blobs, digests};
+
+    fn py_err(e: anyhow::Error) -> PyErr {
+        PyValueError::new_err(format!("{e:#}"))
+    }
 
     /// Return the crate version as a Python string.
     ///
@@ -49,12 +288,75 @@ mod py {
         env!("CARGO_PKG_VERSION")
     }
 
+    /// Hex leaf hash of a block given as a JSON string.
+    ///
+    /// ```python
+    /// sezkp_py.leaf_hash(json.dumps(block))
+    /// ```
+    #[pyfunction]
+    fn leaf_hash(block_json: &str) -> PyResult<String> {
+        digests::leaf_hash(block_json).map_err(py_err)
+    }
+
+    /// Hex interface digest between two adjacent blocks (JSON strings).
+    #[pyfunction]
+    fn interface_boundary_digest(left_json: &str, right_json: &str) -> PyResult<String> {
+        digests::interface_boundary_digest(left_json, right_json).map_err(py_err)
+    }
+
+    /// Hex commitment to a fold projection `π`.
+    ///
+    /// ```python
+    /// sezkp_py.commit_pi(ctrl_in, ctrl_out, flags, [a0, a1, a2, a3])
+    /// ```
+    #[pyfunction]
+    fn commit_pi(ctrl_in: u32, ctrl_out: u32, flags: u32, acc: Vec<u64>) -> PyResult<String> {
+        digests::commit_pi(ctrl_in, ctrl_out, flags, &acc).map_err(py_err)
+    }
+
+    /// JSON string of a manifest given as `bytes` (a `.cbor` manifest file).
+    ///
+    /// ```python
+    /// json.loads(sezkp_py.manifest_to_json(blob))["root"]
+    /// ```
+    #[pyfunction]
+    fn manifest_to_json(bytes: &[u8]) -> PyResult<String> {
+        blobs::manifest_to_json(bytes).map_err(py_err)
+    }
+
+    /// Manifest `bytes` from a JSON string.
+    #[pyfunction]
+    fn manifest_from_json<'py>(py: Python<'py>, json: &str) -> PyResult<Bound<'py, PyBytes>> {
+        let bytes = blobs::manifest_from_json(json).map_err(py_err)?;
+        Ok(PyBytes::new_bound(py, &bytes))
+    }
+
+    /// JSON string of a proof artifact given as `bytes` (a `.cbor` proof file).
+    #[pyfunction]
+    fn proof_to_json(bytes: &[u8]) -> PyResult<String> {
+        blobs::proof_to_json(bytes).map_err(py_err)
+    }
+
+    /// Proof artifact `bytes` from a JSON string.
+    #[pyfunction]
+    fn proof_from_json<'py>(py: Python<'py>, json: &str) -> PyResult<Bound<'py, PyBytes>> {
+        let bytes = blobs::proof_from_json(json).map_err(py_err)?;
+        Ok(PyBytes::new_bound(py, &bytes))
+    }
+
     /// Python module `sezkp_py`.
     ///
     /// This name determines the `import` path from Python.
     #[pymodule]
     fn sezkp_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
         m.add_function(wrap_pyfunction!(version, m)?)?;
+        m.add_function(wrap_pyfunction!(leaf_hash, m)?)?;
+        m.add_function(wrap_pyfunction!(interface_boundary_digest, m)?)?;
+        m.add_function(wrap_pyfunction!(commit_pi, m)?)?;
+        m.add_function(wrap_pyfunction!(manifest_to_json, m)?)?;
+        m.add_function(wrap_pyfunction!(manifest_from_json, m)?)?;
+        m.add_function(wrap_pyfunction!(proof_to_json, m)?)?;
+        m.add_function(wrap_pyfunction!(proof_from_json, m)?)?;
         Ok(())
     }
 }
@@ -0,0 +1,221 @@
+//! Criterion bench: scalar vs batch i64/u64 → field conversions.
+//!
+//! - `field_conv/*`: the conversions alone over contiguous inputs, per-element
+//!   `F1::from_*` vs the `field::*_slice` helpers.
+//! - `rows/*`: per-row cell encoding as done by `ColumnRowIter` and the
+//!   openings `RowIter`. `per_cell` converts every cell on every row (the
+//!   previous shape); `block_consts` batch-encodes the block constants
+//!   (`win_len`, `in_off`, `out_off`) once per block and copies them per row.
+//!
+//! Measured on one x86-64 core, the constant hoist saves ~18% at τ = 8 and
+//! costs ~25% at τ = 2 (two tiny copies outweigh six conversions). The
+//! conversions themselves are already vectorized by the compiler, so the
+//! slice helpers run at parity; gathering per-row values into scratch just to
+//! batch them was measured ~1.1–2× slower and is deliberately not used.
+//!
+//! Throughput is reported in **elements** (cells for `rows/*`).
+
+#![forbid(unsafe_code)]
+#![deny(rust_2018_idioms)]
+#![warn(clippy::all, clippy::pedantic, clippy::nursery)]
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use sezkp_core::{BlockSummary, MovementLog, StepProjection, TapeOp, Window};
+use sezkp_stark::v1::field::{from_i64_slice, le_from_i64_slice, le_from_u64_slice, F1};
+
+const N: usize = 1 << 16;
+
+/// Deterministic trace-like inputs (LCG).
+fn inputs() -> (Vec<i64>, Vec<u64>) {
+    let mut a = 0x2024_u64;
+    let mut next = || {
+        a = a.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+        a >> 33
+    };
+    let is = (0..N).map(|_| (next() % 2049) as i64 - 1024).collect();
+    let us = (0..N).map(|_| next() % 4096).collect();
+    (is, us)
+}
+
+fn bench_conv(c: &mut Criterion) {
+    let (is, us) = inputs();
+    let mut group = c.benchmark_group("field_conv");
+    group.throughput(Throughput::Elements(N as u64));
+
+    let mut le = vec![[0u8; 8]; N];
+    group.bench_function(BenchmarkId::new("le_i64", "scalar"), |b| {
+        b.iter(|| {
+            for (o, &x) in le.iter_mut().zip(black_box(&is)) {
+                *o = F1::from_i64(x).to_le_bytes();
+            }
+        });
+    });
+    group.bench_function(BenchmarkId::new("le_i64", "batch"), |b| {
+        b.iter(|| le_from_i64_slice(black_box(&is), &mut le));
+    });
+
+    group.bench_function(BenchmarkId::new("le_u64", "scalar"), |b| {
+        b.iter(|| {
+            for (o, &x) in le.iter_mut().zip(black_box(&us)) {
+                *o = F1::from_u64(x).to_le_bytes();
+            }
+        });
+    });
+    group.bench_function(BenchmarkId::new("le_u64", "batch"), |b| {
+        b.iter(|| le_from_u64_slice(black_box(&us), &mut le));
+    });
+
+    let mut fs = vec![F1::from_u64(0); N];
+    group.bench_function(BenchmarkId::new("f_i64", "scalar"), |b| {
+        b.iter(|| {
+            for (o, &x) in fs.iter_mut().zip(black_box(&is)) {
+                *o = F1::from_i64(x);
+            }
+        });
+    });
+    group.bench_function(BenchmarkId::new("f_i64", "batch"), |b| {
+        b.iter(|| from_i64_slice(black_box(&is), &mut fs));
+    });
+
+    group.finish();
+}
+
+/// `n_blocks` blocks of `len` steps over `tau` tapes.
+fn blocks(n_blocks: usize, len: usize, tau: usize) -> Vec<BlockSummary> {
+    (0..n_blocks)
+        .map(|k| {
+            let steps = (0..len)
+                .map(|i| StepProjection {
+                    input_mv: 0,
+                    tapes: (0..tau)
+                        .map(|r| TapeOp {
+                            write: ((i + r) % 3 == 0).then_some((i * 7 + r) as u16),
+                            mv: i8::from((i + r) % 2 == 0),
+                        })
+                        .collect(),
+                })
+                .collect();
+            let lo = (k * len) as u64 + 1;
+            BlockSummary {
+                version: 1,
+                block_id: k as u32 + 1,
+                step_lo: lo,
+                step_hi: lo + len as u64 - 1,
+                ctrl_in: 0,
+                ctrl_out: 0,
+                in_head_in: 0,
+                in_head_out: 0,
+                windows: vec![
+                    Window {
+                        left: 0,
+                        right: len as i64 - 1,
+                    };
+                    tau
+                ],
+                head_in_offsets: vec![0; tau],
+                head_out_offsets: vec![len.div_ceil(2) as u32; tau],
+                movement_log: MovementLog { steps },
+                pre_tags: vec![[0u8; 16]; tau],
+                post_tags: vec![[0u8; 16]; tau],
+                write_commitments: Vec::new(),
+                aux_commitments: Vec::new(),
+            }
+        })
+        .collect()
+}
+
+/// Row cells as the row builders encoded them before: every per-tape cell,
+/// including the block constants, converted on every row.
+fn rows_per_cell(b: &BlockSummary, row: &mut [Vec<[u8; 8]>; 7]) -> u64 {
+    let tau = b.windows.len();
+    let mut heads = vec![0i64; tau];
+    let mut acc = 0;
+    for step in &b.movement_log.steps {
+        for (r, op) in step.tapes.iter().enumerate() {
+            heads[r] += i64::from(op.mv);
+            let w = &b.windows[r];
+            row[0][r] = F1::from_i64(i64::from(op.mv)).to_le_bytes();
+            row[1][r] = F1::from_u64(u64::from(op.write.is_some())).to_le_bytes();
+            row[2][r] = F1::from_u64(u64::from(op.write.unwrap_or(0))).to_le_bytes();
+            row[3][r] = F1::from_i64(heads[r]).to_le_bytes();
+            row[4][r] = F1::from_u64((w.right - w.left).unsigned_abs() + 1).to_le_bytes();
+            row[5][r] = F1::from_u64(u64::from(b.head_in_offsets[r])).to_le_bytes();
+            row[6][r] = F1::from_u64(u64::from(b.head_out_offsets[r])).to_le_bytes();
+        }
+        acc = checksum(acc, row);
+    }
+    acc
+}
+
+/// Row cells as the row builders encode them now: block constants batch-encoded
+/// once on entry and copied into each row.
+fn rows_block_consts(b: &BlockSummary, row: &mut [Vec<[u8; 8]>; 7]) -> u64 {
+    let tau = b.windows.len();
+    let mut consts = [
+        vec![[0u8; 8]; tau],
+        vec![[0u8; 8]; tau],
+        vec![[0u8; 8]; tau],
+    ];
+    let wl: Vec<u64> = b
+        .windows
+        .iter()
+        .map(|w| (w.right - w.left).unsigned_abs() + 1)
+        .collect();
+    le_from_u64_slice(&wl, &mut consts[0]);
+    let io: Vec<u64> = b.head_in_offsets.iter().map(|&o| u64::from(o)).collect();
+    le_from_u64_slice(&io, &mut consts[1]);
+    let oo: Vec<u64> = b.head_out_offsets.iter().map(|&o| u64::from(o)).collect();
+    le_from_u64_slice(&oo, &mut consts[2]);
+
+    let mut heads = vec![0i64; tau];
+    let mut acc = 0;
+    for step in &b.movement_log.steps {
+        for (r, op) in step.tapes.iter().enumerate() {
+            heads[r] += i64::from(op.mv);
+            row[0][r] = F1::from_i64(i64::from(op.mv)).to_le_bytes();
+            row[1][r] = F1::from_u64(u64::from(op.write.is_some())).to_le_bytes();
+            row[2][r] = F1::from_u64(u64::from(op.write.unwrap_or(0))).to_le_bytes();
+            row[3][r] = F1::from_i64(heads[r]).to_le_bytes();
+        }
+        for (dst, src) in row[4..].iter_mut().zip(&consts) {
+            dst.copy_from_slice(src);
+        }
+        acc = checksum(acc, row);
+    }
+    acc
+}
+
+fn checksum(acc: u64, row: &[Vec<[u8; 8]>; 7]) -> u64 {
+    row.iter()
+        .flatten()
+        .fold(acc, |a, c| a.rotate_left(1) ^ u64::from_le_bytes(*c))
+}
+
+fn bench_rows(c: &mut Criterion) {
+    const LEN: usize = 4096;
+    let mut group = c.benchmark_group("rows");
+    for tau in [2usize, 8] {
+        let b = &blocks(1, LEN, tau)[0];
+        let mut row: [Vec<[u8; 8]>; 7] = std::array::from_fn(|_| vec![[0u8; 8]; tau]);
+        assert_eq!(
+            rows_per_cell(b, &mut row),
+            rows_block_consts(b, &mut row),
+            "row encoders disagree"
+        );
+
+        group.throughput(Throughput::Elements((LEN * tau * 7) as u64));
+        group.bench_function(BenchmarkId::new("per_cell", format!("tau={tau}")), |bch| {
+            bch.iter(|| rows_per_cell(black_box(b), &mut row));
+        });
+        group.bench_function(
+            BenchmarkId::new("block_consts", format!("tau={tau}")),
+            |bch| {
+                bch.iter(|| rows_block_consts(black_box(b), &mut row));
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_conv, bench_rows);
+criterion_main!(benches);
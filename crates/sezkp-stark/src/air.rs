@@ -103,6 +103,8 @@ mod tests {
             movement_log: MovementLog { steps },
             pre_tags: vec![[0; 16]; 1],
             post_tags: vec![[0; 16]; 1],
+            write_commitments: Vec::new(),
+            aux_commitments: Vec::new(),
         };
         check_block_invariants(&b).unwrap();
         assert_eq!(block_rows(&b), 2);
@@ -126,6 +128,8 @@ mod tests {
             movement_log: MovementLog { steps },
             pre_tags: vec![[0; 16]; 1],
             post_tags: vec![[0; 16]; 1],
+            write_commitments: Vec::new(),
+            aux_commitments: Vec::new(),
         };
         assert!(check_block_invariants(&b).is_err());
     }
@@ -111,6 +111,8 @@ mod tests {
             movement_log: MovementLog { steps },
             pre_tags: vec![[0; 16]; 1],
             post_tags: vec![[0; 16]; 1],
+            write_commitments: Vec::new(),
+            aux_commitments: Vec::new(),
         }
     }
 
@@ -12,6 +12,18 @@
 //!
 //! The top-level types implement the workspace’s [`sezkp_core::ProvingBackend`]
 //! trait so they can be selected by the CLI or other hosts.
+//!
+//! ## Features
+//!
+//! - `v0` — the scaffold backend ([`StarkIOP`]).
+//! - `v1` — the v1 proof format, batch verifier ([`StarkV1::verify_artifact`]),
+//!   size estimates and the leaf/interface/wrap gadgets the fold backend
+//!   builds on; pulls in `sezkp-ffts`, `bincode` and `blake3`.
+//! - `streaming` (implies `v1`) — the v1 prover and the streaming verifier,
+//!   with the column, opening, LDE and FRI-layer streams they are built from.
+//!
+//! All three are on by default. A verifier-only embedder can depend on
+//! `default-features = false, features = ["v1"]`.
 
 #![forbid(unsafe_code)]
 #![deny(rust_2018_idioms)]
@@ -24,51 +36,93 @@
     clippy::expect_used
 )]
 
+#[cfg(feature = "v0")]
 mod air;      // v0 scaffold checks (local, minimal)
+#[cfg(feature = "v0")]
 mod commit;   // v0 streaming row-commit
+#[cfg(feature = "v0")]
 mod iop;      // v0 mock IOP bits (Fiat–Shamir over interfaces)
+#[cfg(feature = "v0")]
 mod verify;   // v0 verifier (recomputes transcript challenges)
+#[cfg(feature = "v0")]
 mod witness;  // v0 row witness encoding / chunking
 
 /// v1 modules (kept separate to avoid mixing concerns with the v0 scaffold).
+///
+/// The prover and the streaming internals it is built from need the
+/// `streaming` feature; the rest is the proof format, the batch verifier,
+/// size estimates and the gadgets the fold backend uses.
+#[cfg(feature = "v1")]
 pub mod v1 {
     #![allow(missing_docs, dead_code)]
     pub mod air;
+    pub mod challenges;
     pub mod columns;
+    pub mod estimate;
     pub mod field;
     pub mod fri;
     pub mod merkle;
     pub mod params;
     pub mod proof;
+    #[cfg(feature = "streaming")]
     pub mod prover;
     pub mod verify;
+    #[cfg(feature = "streaming")]
     pub mod columns_stream;
+    #[cfg(feature = "streaming")]
     pub mod openings;
+    #[cfg(feature = "streaming")]
     pub mod lde;
+    #[cfg(feature = "streaming")]
     pub mod fri_stream;
     pub mod masking;
+    pub mod profile;
 }
 
+#[cfg(any(feature = "v0", feature = "v1"))]
 use anyhow::{ensure, Result};
+#[cfg(feature = "v0")]
+use anyhow::anyhow;
+#[cfg(any(feature = "v0", feature = "streaming"))]
+use sezkp_core::BatchItem;
+#[cfg(any(feature = "v0", feature = "streaming"))]
+use std::collections::HashMap;
+#[cfg(feature = "v1")]
+use sezkp_core::claims;
+#[cfg(feature = "streaming")]
+use sezkp_core::claims::Claim;
 pub use sezkp_core::{BackendKind, BlockSummary, ProofArtifact, ProvingBackend};
+#[cfg(feature = "v0")]
 use sezkp_crypto::{Blake3Transcript, Transcript};
 
 /// Re-export v1 parameters so downstream code can depend on a single path:
 /// `sezkp_stark::params::...`.
+#[cfg(feature = "v1")]
 pub use v1::params;
 
+/// Exact v1 proof size for a trace shape (see [`v1::estimate`]).
+#[cfg(feature = "v1")]
+pub use v1::estimate::estimate_proof_size;
+
 /// v0 marker backend (streaming transcript scaffold).
 ///
 /// This is intentionally tiny: it commits to the row stream with a transcript
 /// and returns two squeezed challenge blocks as the “proof”. The verifier
 /// recomputes the same transcript and checks equality.
+#[cfg(feature = "v0")]
 #[derive(Debug, Clone, Copy, Default)]
 pub struct StarkIOP;
 
+#[cfg(feature = "v0")]
 impl ProvingBackend for StarkIOP {
     fn prove(blocks: &[BlockSummary], manifest_root: [u8; 32]) -> Result<ProofArtifact> {
+        sezkp_core::check_empty_root(blocks.is_empty(), &manifest_root)?;
+
         // 1) Row-stream commitment with minimal AIR checks.
+        let span = sezkp_core::phase_span!("stark.v0_commit", blocks = blocks.len());
         let com = commit::commit_blocks(blocks)?;
+        sezkp_core::phase_event!(rows = com.n_rows, "row stream committed");
+        drop(span);
 
         // 2) Fiat–Shamir transcript for the proof envelope.
         let mut tr = Blake3Transcript::new("sezkp-stark-v0");
@@ -82,16 +136,16 @@ impl ProvingBackend for StarkIOP {
         proof.extend(tr.challenge_bytes("alpha", 32));
         proof.extend(tr.challenge_bytes("beta", 32));
 
-        Ok(ProofArtifact {
-            backend: BackendKind::Stark,
+        Ok(ProofArtifact::new(
+            BackendKind::Stark,
             manifest_root,
-            proof_bytes: proof,
-            meta: serde_json::json!({
+            proof,
+            serde_json::json!({
                 "proto": "stark-v0",
                 "n_rows": com.n_rows,
                 "tau": com.tau
             }),
-        })
+        ))
     }
 
     fn verify(
@@ -99,16 +153,39 @@ impl ProvingBackend for StarkIOP {
         blocks: &[BlockSummary],
         manifest_root: [u8; 32],
     ) -> Result<()> {
-        ensure!(
-            artifact.backend == BackendKind::Stark,
-            "backend kind mismatch: expected STARK"
-        );
+        check_backend(artifact)?;
         ensure!(
             artifact.manifest_root == manifest_root,
             "manifest root mismatch"
         );
+        sezkp_core::check_empty_root(blocks.is_empty(), &manifest_root)?;
         verify::verify_artifact(artifact, blocks, manifest_root)
     }
+
+    /// Recomputes the row-stream commit once per distinct block slice.
+    fn verify_batch(items: &[BatchItem<'_>]) -> Vec<Result<()>> {
+        let mut commits = HashMap::new();
+        items
+            .iter()
+            .map(|it| {
+                check_backend(it.artifact)?;
+                ensure!(
+                    it.artifact.manifest_root == it.manifest_root,
+                    "manifest root mismatch"
+                );
+                sezkp_core::check_empty_root(it.blocks.is_empty(), &it.manifest_root)?;
+                verify::verify_with_commit(it.artifact, it.manifest_root, || {
+                    commits
+                        .entry(it.blocks_key())
+                        .or_insert_with(|| {
+                            commit::commit_blocks(it.blocks).map_err(|e| format!("{e:#}"))
+                        })
+                        .as_ref()
+                        .map_err(|e| anyhow!("{e}"))
+                })
+            })
+            .collect()
+    }
 }
 
 /// v1 backend (columnar PIOP + FRI; streaming-friendly).
@@ -122,23 +199,25 @@ impl ProvingBackend for StarkIOP {
 /// streaming internals (`columns_stream`, `openings`, `fri_stream`). The exact
 /// memory profile depends on parameter sizes, but both proving and verifying
 /// avoid materializing Θ(T) state at once.
+///
+/// Proving (and so the [`ProvingBackend`] impl) needs the `streaming`
+/// feature; without it, verify with [`Self::verify_artifact`].
+#[cfg(feature = "v1")]
 #[derive(Debug, Clone, Copy, Default)]
 pub struct StarkV1;
 
+#[cfg(feature = "streaming")]
 impl ProvingBackend for StarkV1 {
     fn prove(blocks: &[BlockSummary], manifest_root: [u8; 32]) -> Result<ProofArtifact> {
-        let proof = v1::prover::prove_v1(blocks, manifest_root)?;
-        let bytes = bincode::serialize(&proof)?;
-        Ok(ProofArtifact {
-            backend: BackendKind::Stark,
-            manifest_root,
-            proof_bytes: bytes,
-            meta: serde_json::json!({
-                "proto": "stark-v1",
-                "domain_n": proof.domain_n,
-                "tau": proof.tau
-            }),
-        })
+        Self::prove_artifact(blocks, manifest_root, None, &[], serde_json::Map::new())
+    }
+
+    fn prove_with_claims(
+        blocks: &[BlockSummary],
+        manifest_root: [u8; 32],
+        claims: &[Claim],
+    ) -> Result<ProofArtifact> {
+        Self::prove_bound(blocks, manifest_root, None, claims)
     }
 
     fn verify(
@@ -146,22 +225,104 @@ impl ProvingBackend for StarkV1 {
         blocks: &[BlockSummary],
         manifest_root: [u8; 32],
     ) -> Result<()> {
-        // Defensive checks mirror v0 path so callers can mix backends safely.
-        ensure!(
-            artifact.backend == BackendKind::Stark,
-            "backend kind mismatch: expected STARK"
-        );
+        Self::verify_artifact(artifact, blocks, manifest_root, None)
+    }
+
+    /// Derives the [`v1::verify::BlockFacts`] once per distinct block slice.
+    fn verify_batch(items: &[BatchItem<'_>]) -> Vec<Result<()>> {
+        let mut facts = HashMap::new();
+        items
+            .iter()
+            .map(|it| {
+                check_backend(it.artifact)?;
+                ensure!(
+                    it.artifact.manifest_root == it.manifest_root,
+                    "manifest root mismatch"
+                );
+                let proof = Self::decode_proof(it.artifact, it.manifest_root)?;
+                let req = v1::verify::Requirements::from_env()?;
+                let facts = facts
+                    .entry(it.blocks_key())
+                    .or_insert_with(|| v1::verify::BlockFacts::of(it.blocks));
+                v1::verify::verify_v1_under(&proof, facts, None, &req)
+            })
+            .collect()
+    }
+
+    /// [`Self::verify_stream`] without a beacon, so
+    /// [`sezkp_core::prover::StreamingProver::verify_stream_iter`] checks a
+    /// STARK proof in O(chunk) memory.
+    fn verify_iter<I>(artifact: &ProofArtifact, blocks: I, manifest_root: [u8; 32]) -> Result<()>
+    where
+        I: IntoIterator<Item = Result<BlockSummary>>,
+    {
+        Self::verify_stream(artifact, blocks, manifest_root, None)
+    }
+}
+
+#[cfg(feature = "streaming")]
+impl StarkV1 {
+    /// Like [`ProvingBackend::prove`], but absorbs an external randomness
+    /// `beacon` (e.g. a block hash) into the transcript before query sampling.
+    ///
+    /// The beacon is recorded in the proof, so the artifact demonstrates it was
+    /// produced after the beacon value became known.
+    pub fn prove_with_beacon(
+        blocks: &[BlockSummary],
+        manifest_root: [u8; 32],
+        beacon: &[u8],
+    ) -> Result<ProofArtifact> {
+        Self::prove_bound(blocks, manifest_root, Some(beacon), &[])
+    }
+
+    /// [`ProvingBackend::prove`] with an optional `beacon` (see
+    /// [`Self::prove_with_beacon`]) and public `claims` (see
+    /// [`ProvingBackend::prove_with_claims`]) together. The claims digest is
+    /// absorbed into the transcript prelude and recorded in
+    /// [`v1::proof::PublicInputs::claims`].
+    ///
+    /// # Errors
+    /// Fails on claims outside the limits of [`claims::check_claims`] and
+    /// wherever [`ProvingBackend::prove`] does.
+    pub fn prove_bound(
+        blocks: &[BlockSummary],
+        manifest_root: [u8; 32],
+        beacon: Option<&[u8]>,
+        claims: &[Claim],
+    ) -> Result<ProofArtifact> {
+        let mut extra = serde_json::Map::new();
+        if let Some(b) = beacon {
+            extra.insert("beacon".into(), beacon_hex(b).into());
+        }
+        Self::prove_artifact(blocks, manifest_root, beacon, claims, extra)
+    }
+
+    /// Streaming counterpart of [`Self::verify_with_beacon`] (`beacon`
+    /// optional): reads `blocks` once, one at a time, and also recomputes
+    /// every column root from them (see [`v1::verify::verify_v1_stream`]).
+    ///
+    /// # Errors
+    /// Fails on a backend or root mismatch, an undecodable proof, or wherever
+    /// [`v1::verify::verify_v1_stream`] does.
+    pub fn verify_stream<I>(
+        artifact: &ProofArtifact,
+        blocks: I,
+        manifest_root: [u8; 32],
+        beacon: Option<&[u8]>,
+    ) -> Result<()>
+    where
+        I: IntoIterator<Item = Result<BlockSummary>>,
+    {
+        check_backend(artifact)?;
         ensure!(
             artifact.manifest_root == manifest_root,
             "manifest root mismatch"
         );
-
-        let proof: v1::proof::ProofV1 = bincode::deserialize(&artifact.proof_bytes)?;
-        v1::verify::verify_v1(&proof, blocks)
+        let proof = Self::decode_proof(artifact, manifest_root)?;
+        let req = v1::verify::Requirements::from_env()?;
+        v1::verify::verify_v1_stream_under(&proof, blocks, beacon, &req)
     }
-}
 
-impl StarkV1 {
     /// Explicit streaming entrypoint for the CLI `--stream` flag.
     ///
     /// Internally engages the streaming-friendly code paths (column roots,
@@ -174,18 +335,169 @@ impl StarkV1 {
         // Current prover implementation already leverages streaming components.
         // Kept as a dedicated method so call sites can intentionally select
         // the streaming profile and we can diverge implementations later.
-        let proof = v1::prover::prove_v1(blocks, manifest_root)?;
-        let bytes = bincode::serialize(&proof)?;
-        Ok(ProofArtifact {
-            backend: BackendKind::Stark,
+        let mut extra = serde_json::Map::new();
+        extra.insert("mode".into(), "streaming".into());
+        Self::prove_artifact(blocks, manifest_root, None, &[], extra)
+    }
+
+    /// Run the v1 prover and wrap the proof. `meta` records the protocol,
+    /// shape, per-phase [`v1::profile::ProveProfile`] and the streaming
+    /// buffer sizes (taken from the environment, see
+    /// [`params::StreamTuning::from_env`]) plus `extra` keys. The FRI query
+    /// schedule also comes from the environment
+    /// ([`params::FriQuerySchedule::from_env`]) and is recorded in the proof,
+    /// as is a declared input tape length ([`params::input_len_from_env`]).
+    /// A target security level ([`params::security_bits_from_env`]) sizes
+    /// the query count; `meta.security` records the target and the estimate
+    /// the proof achieves. Non-empty `claims` are bound into the proof and
+    /// recorded in the artifact.
+    fn prove_artifact(
+        blocks: &[BlockSummary],
+        manifest_root: [u8; 32],
+        beacon: Option<&[u8]>,
+        claims: &[Claim],
+        mut extra: serde_json::Map<String, serde_json::Value>,
+    ) -> Result<ProofArtifact> {
+        claims::check_claims(claims)?;
+        let tuning = params::StreamTuning::from_env()?;
+        let schedule = params::FriQuerySchedule::from_env()?;
+        let input_len = params::input_len_from_env()?;
+        let target_bits = params::security_bits_from_env()?;
+        let num_queries = match target_bits {
+            Some(bits) => {
+                params::queries_for_security(bits, params::BLOWUP, params::GRINDING_BITS)?
+            }
+            None => params::NUM_QUERIES,
+        };
+        let (proof, profile) = v1::prover::prove_v1_with_queries(
+            blocks,
             manifest_root,
-            proof_bytes: bytes,
-            meta: serde_json::json!({
-                "proto": "stark-v1",
-                "mode": "streaming",
-                "domain_n": proof.domain_n,
-                "tau": proof.tau
+            beacon,
+            tuning,
+            &schedule,
+            input_len,
+            claims::claims_digest(claims),
+            num_queries,
+        )?;
+        let achieved_bits = proof.fri_params.security_bits();
+        if let Some(bits) = target_bits {
+            // Only a per-layer schedule with fewer queries can fall short.
+            ensure!(
+                achieved_bits >= bits || proof.is_empty_proof(),
+                "FRI schedule {schedule} reaches only {achieved_bits} of the {bits} requested security bits"
+            );
+        }
+        let bytes = bincode::serialize(&proof)?;
+        extra.insert("proto".into(), "stark-v1".into());
+        extra.insert("domain_n".into(), proof.domain_n.into());
+        extra.insert("tau".into(), proof.public.tau.into());
+        extra.insert("profile".into(), serde_json::to_value(&profile)?);
+        extra.insert("tuning".into(), serde_json::to_value(tuning)?);
+        extra.insert(
+            "security".into(),
+            serde_json::json!({
+                "target_bits": target_bits,
+                "achieved_bits": achieved_bits,
+                "num_queries": proof.fri_params.num_queries,
+                "blowup": params::BLOWUP,
+                "grinding_bits": params::GRINDING_BITS,
             }),
-        })
+        );
+        let mut art = ProofArtifact::new(
+            BackendKind::Stark,
+            manifest_root,
+            bytes,
+            serde_json::Value::Object(extra),
+        );
+        art.claims = claims.to_vec();
+        Ok(art)
+    }
+}
+
+#[cfg(feature = "v1")]
+impl StarkV1 {
+    /// [`ProvingBackend::verify`] with an optional `beacon` the proof must
+    /// be bound to; available without the `streaming` feature.
+    ///
+    /// # Errors
+    /// Fails on a backend or root mismatch, an undecodable proof, or
+    /// wherever [`v1::verify::verify_v1_with_beacon`] does.
+    pub fn verify_artifact(
+        artifact: &ProofArtifact,
+        blocks: &[BlockSummary],
+        manifest_root: [u8; 32],
+        beacon: Option<&[u8]>,
+    ) -> Result<()> {
+        // Defensive checks mirror v0 path so callers can mix backends safely.
+        check_backend(artifact)?;
+        ensure!(
+            artifact.manifest_root == manifest_root,
+            "manifest root mismatch"
+        );
+        let proof = Self::decode_proof(artifact, manifest_root)?;
+        let req = v1::verify::Requirements::from_env()?;
+        v1::verify::verify_v1_under(&proof, &v1::verify::BlockFacts::of(blocks), beacon, &req)
+    }
+
+    /// Like [`ProvingBackend::verify`], but additionally requires the proof to
+    /// be bound to exactly `beacon`.
+    pub fn verify_with_beacon(
+        artifact: &ProofArtifact,
+        blocks: &[BlockSummary],
+        manifest_root: [u8; 32],
+        beacon: &[u8],
+    ) -> Result<()> {
+        Self::verify_artifact(artifact, blocks, manifest_root, Some(beacon))
     }
+
+    fn decode_proof(
+        artifact: &ProofArtifact,
+        manifest_root: [u8; 32],
+    ) -> Result<v1::proof::ProofV1> {
+        let proof = v1::proof::ProofV1::decode(&artifact.proof_bytes)?;
+        ensure!(
+            proof.public.manifest_root == manifest_root,
+            "proof is bound to a different manifest root"
+        );
+        claims::check_bound(&artifact.claims, proof.public.claims.as_ref())?;
+        // A caller that knows the input tape length requires the proof to
+        // have bounded the input head by exactly that length.
+        if let Some(want) = params::input_len_from_env()? {
+            ensure!(
+                proof.params.input_len == Some(want),
+                "proof declares input length {:?}, expected {want}",
+                proof.params.input_len
+            );
+        }
+        if let Some(want) = params::FriQuerySchedule::expected_from_env()? {
+            ensure!(
+                want.matches(&proof.fri_params),
+                "proof uses FRI layer queries {:?}, expected schedule {want}",
+                proof.fri_params.layer_queries
+            );
+        }
+        Ok(proof)
+    }
+}
+
+/// Reject artifacts produced by another backend, including fold artifacts
+/// from releases that labelled them `Stark`.
+#[cfg(any(feature = "v0", feature = "v1"))]
+fn check_backend(artifact: &ProofArtifact) -> Result<()> {
+    let kind = artifact.resolved_backend();
+    ensure!(
+        kind == BackendKind::Stark,
+        "backend kind mismatch: expected STARK, artifact is from the {kind} backend"
+    );
+    Ok(())
+}
+
+/// Lowercase hex rendering of a beacon for artifact metadata.
+#[cfg(feature = "streaming")]
+fn beacon_hex(beacon: &[u8]) -> String {
+    use std::fmt::Write as _;
+    beacon.iter().fold(String::new(), |mut s, b| {
+        let _ = write!(s, "{b:02x}");
+        s
+    })
 }
@@ -10,6 +10,8 @@
 //!  2) **AreIfaceAir** — binds the **interface** between siblings using
 //!     only public bits exported by leaves (no reaccess to blocks).
 //!
+//! Both live in `sezkp-gadgets` and are re-exported here.
+//!
 //!  3) **WrapAir** — succinct attestation for (C_root, π_root).
 //!
 //! NOTE: Each proof below is a compact commitment (MAC). Upgrading to a
@@ -30,10 +32,7 @@
 use blake3::Hasher;
 use serde::{Deserialize, Serialize};
 
-use crate::v1::columns::{
-    boundary_left_tail_digest, boundary_right_head_digest, IFACE_WINDOW_STEPS, TraceColumns,
-    HEAD_BITS, SYM_BITS,
-};
+use crate::v1::columns::{TraceColumns, HEAD_BITS, SYM_BITS};
 use crate::v1::field::F1;
 // For openings-only evaluation.
 use crate::v1::proof::RowOpenings;
@@ -45,8 +44,20 @@ fn f1(x: u64) -> F1 {
     F1::from_u64(x)
 }
 
-/// Transition composition at row i: Σ_j α_j · C_j(i) using full columns.
-pub fn compose_row(tc: &TraceColumns, i: usize, a: &Alphas) -> F1 {
+/// Movement-domain constraint: `Π_{k=-m..=m} (mv - k)` for `m = max_move`,
+/// zero exactly when `|mv| ≤ m` (degree `2m + 1`).
+#[must_use]
+pub fn mv_domain(mv: F1, max_move: u8) -> F1 {
+    let mut acc = mv;
+    for k in 1..=u64::from(max_move) {
+        acc *= (mv - f1(k)) * (mv + f1(k));
+    }
+    acc
+}
+
+/// Transition composition at row i: Σ_j α_j · C_j(i) using full columns,
+/// with moves bounded by `max_move`.
+pub fn compose_row(tc: &TraceColumns, i: usize, a: &Alphas, max_move: u8) -> F1 {
     let mut acc = F1::from_u64(0);
     let two = f1(2);
 
@@ -63,8 +74,8 @@ pub fn compose_row(tc: &TraceColumns, i: usize, a: &Alphas) -> F1 {
         // C1: boolean flag
         acc += a.bool_flag * flg * (flg - f1(1));
 
-        // C2: mv in {-1,0,1}
-        acc += a.mv_domain * mv * (mv - f1(1)) * (mv + f1(1));
+        // C2: mv in [-max_move, max_move]
+        acc += a.mv_domain * mv_domain(mv, max_move);
 
         // C3: head update (masked by !is_last)
         let one_minus_last = f1(1) - tc.is_last[i];
@@ -112,6 +123,15 @@ pub fn compose_row(tc: &TraceColumns, i: usize, a: &Alphas) -> F1 {
         acc += a.sym_reconstruct * flg * (tc.write_sym[r][i] - sym_bits_sum);
     }
 
+    // Input-head update, if committed (masked by !is_last; shares the
+    // head-update alpha).
+    if !tc.in_head.is_empty() {
+        let ip1 = (i + 1) % tc.n;
+        acc += a.head_update
+            * (f1(1) - tc.is_last[i])
+            * (tc.in_head[ip1] - tc.in_head[i] - tc.input_mv[ip1]);
+    }
+
     acc
 }
 
@@ -132,17 +152,25 @@ pub fn compose_boundary(tc: &TraceColumns, i: usize, a: &Alphas) -> F1 {
         // Last row: head == off_out
         acc += a.boundary_last * is_last * (head - off_out);
     }
+
+    // The input head, if committed, enters at `in_head_in` and leaves at
+    // `in_head_out`.
+    if !tc.in_head.is_empty() {
+        let in_head = tc.in_head[i];
+        acc += a.boundary_first * is_first * (in_head - tc.input_mv[i] - tc.in_head_in[i]);
+        acc += a.boundary_last * is_last * (in_head - tc.in_head_out[i]);
+    }
     acc
 }
 
 /// LDE composition over a blowup-extended domain by periodicity (prototype).
-pub fn compose_lde(tc: &TraceColumns, a: &Alphas, blow_log2: usize) -> Vec<F1> {
+pub fn compose_lde(tc: &TraceColumns, a: &Alphas, max_move: u8, blow_log2: usize) -> Vec<F1> {
     let base_n = tc.n;
     let lde_n = base_n << blow_log2;
     let mut out = Vec::with_capacity(lde_n);
     for i in 0..lde_n {
         let base = i % base_n;
-        out.push(compose_row(tc, base, a) + compose_boundary(tc, base, a));
+        out.push(compose_row(tc, base, a, max_move) + compose_boundary(tc, base, a));
     }
     out
 }
@@ -174,39 +202,56 @@ pub struct RowView {
     pub is_first: F1,
     pub is_last: F1,
     pub input_mv: F1,
+    pub input_head: Option<InputHeadView>,
     pub tapes: Vec<TapeOpenView>,
 }
 
+#[derive(Clone, Debug)]
+pub struct InputHeadView {
+    pub in_head: F1,
+    pub next_in_head: F1,
+    pub next_input_mv: F1,
+    pub in_head_in: F1,
+    pub in_head_out: F1,
+}
+
 impl RowView {
     #[must_use]
     pub fn from_openings(q: &RowOpenings) -> Self {
         let mut tapes = Vec::with_capacity(q.per_tape.len());
         for t in &q.per_tape {
             tapes.push(TapeOpenView {
-                mv: f_from_le(t.mv.value_le),
-                next_mv: f_from_le(t.next_mv.value_le),
-                write_flag: f_from_le(t.write_flag.value_le),
-                write_sym: f_from_le(t.write_sym.value_le),
-                head: f_from_le(t.head.value_le),
-                next_head: f_from_le(t.next_head.value_le),
-                win_len: f_from_le(t.win_len.value_le),
-                in_off: f_from_le(t.in_off.value_le),
-                out_off: f_from_le(t.out_off.value_le),
+                mv: f_from_le(t.mv.value_le()),
+                next_mv: f_from_le(t.next_mv.value_le()),
+                write_flag: f_from_le(t.write_flag.value_le()),
+                write_sym: f_from_le(t.write_sym.value_le()),
+                head: f_from_le(t.head.value_le()),
+                next_head: f_from_le(t.next_head.value_le()),
+                win_len: f_from_le(t.win_len.value_le()),
+                in_off: f_from_le(t.in_off.value_le()),
+                out_off: f_from_le(t.out_off.value_le()),
             });
         }
         Self {
             row: q.row,
             tau: q.per_tape.len(),
-            is_first: f_from_le(q.is_first.value_le),
-            is_last: f_from_le(q.is_last.value_le),
-            input_mv: f_from_le(q.input_mv.value_le),
+            is_first: f_from_le(q.is_first.value_le()),
+            is_last: f_from_le(q.is_last.value_le()),
+            input_mv: f_from_le(q.input_mv.value_le()),
+            input_head: q.input_head.as_ref().map(|h| InputHeadView {
+                in_head: f_from_le(h.in_head.value_le()),
+                next_in_head: f_from_le(h.next_in_head.value_le()),
+                next_input_mv: f_from_le(h.next_input_mv.value_le()),
+                in_head_in: f_from_le(h.in_head_in.value_le()),
+                in_head_out: f_from_le(h.in_head_out.value_le()),
+            }),
             tapes,
         }
     }
 }
 
 #[must_use]
-pub fn compose_row_from_openings(view: &RowView, a: &Alphas) -> F1 {
+pub fn compose_row_from_openings(view: &RowView, a: &Alphas, max_move: u8) -> F1 {
     let mut acc = F1::from_u64(0);
 
     for t in &view.tapes {
@@ -216,11 +261,16 @@ pub fn compose_row_from_openings(view: &RowView, a: &Alphas) -> F1 {
         let head_next = t.next_head;
 
         acc += a.bool_flag * flg * (flg - f1(1));
-        acc += a.mv_domain * mv * (mv - f1(1)) * (mv + f1(1));
+        acc += a.mv_domain * mv_domain(mv, max_move);
         let one_minus_last = f1(1) - view.is_last;
         acc += a.head_update * one_minus_last * (head_next - head - t.next_mv);
     }
 
+    if let Some(h) = &view.input_head {
+        let one_minus_last = f1(1) - view.is_last;
+        acc += a.head_update * one_minus_last * (h.next_in_head - h.in_head - h.next_input_mv);
+    }
+
     acc
 }
 
@@ -234,13 +284,24 @@ pub fn compose_boundary_from_openings(view: &RowView, a: &Alphas) -> F1 {
         acc += a.boundary_first * is_first * (t.head - t.mv - t.in_off);
         acc += a.boundary_last * is_last * (t.head - t.out_off);
     }
+
+    if let Some(h) = &view.input_head {
+        acc += a.boundary_first * is_first * (h.in_head - view.input_mv - h.in_head_in);
+        acc += a.boundary_last * is_last * (h.in_head - h.in_head_out);
+    }
     acc
 }
 
 /// Evaluate the (opened) composition at an x and fold with an OOD point z.
 #[must_use]
-pub fn deep_evaluate_row_from_openings(view: &RowView, x: F1, z: F1, a: &Alphas) -> F1 {
-    let c = compose_row_from_openings(view, a) + compose_boundary_from_openings(view, a);
+pub fn deep_evaluate_row_from_openings(
+    view: &RowView,
+    x: F1,
+    z: F1,
+    a: &Alphas,
+    max_move: u8,
+) -> F1 {
+    let c = compose_row_from_openings(view, a, max_move) + compose_boundary_from_openings(view, a);
     let denom = x - z;
     c * denom.inv()
 }
@@ -260,143 +321,15 @@ pub struct Alphas {
     pub boundary_last: F1,
 }
 
-/* --------------------------- LeafPiAir (micro) ----------------------------- */
-
-/// Domain separator for the Leaf π micro-proof.
-pub const DS_LEAF_PI_V1: &str = "stark/leaf_pi/v1";
-
-/// Public inputs for LeafPiAir.
-///
-/// NOTE: `acc_limbs` now **exposes each digest** as two u64 limbs (LSB-first):
-///   acc_limbs = [ L_tail[0..8], L_tail[8..16], R_head[0..8], R_head[8..16] ].
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
-pub struct PiPublic {
-    pub ctrl_in: u32,
-    pub ctrl_out: u32,
-    pub flags: u32,
-    pub acc_limbs: [u64; 4],
-    pub left_tail_digest: [u8; 32],
-    pub right_head_digest: [u8; 32],
-}
-
-/// Minimal proof object (MAC over the public inputs).
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
-pub struct StarkProofV1 {
-    pub mac: [u8; 32],
-}
+/* ------------------------ LeafPiAir / AreIfaceAir ------------------------- */
 
-/// Helper: pack boundary digests as 4 limbs (two limbs per digest, LSB-first).
-#[must_use]
-pub fn pack_boundary_limbs(left: [u8; 32], right: [u8; 32]) -> [u64; 4] {
-    let mut get2 = |d: [u8; 32]| -> [u64; 2] {
-        let mut a = [0u8; 8];
-        let mut b = [0u8; 8];
-        a.copy_from_slice(&d[0..8]);
-        b.copy_from_slice(&d[8..16]);
-        [u64::from_le_bytes(a), u64::from_le_bytes(b)]
-    };
-    let l2 = get2(left);
-    let r2 = get2(right);
-    [l2[0], l2[1], r2[0], r2[1]]
-}
-
-/// Produce a public view and a compact commitment (MAC) to it.
-pub fn prove_leaf_pi(block: &sezkp_core::BlockSummary) -> anyhow::Result<(PiPublic, StarkProofV1)> {
-    let l_tail = boundary_left_tail_digest(block, IFACE_WINDOW_STEPS);
-    let r_head = boundary_right_head_digest(block, IFACE_WINDOW_STEPS);
-
-    let limbs = pack_boundary_limbs(l_tail, r_head);
-    let public = PiPublic {
-        ctrl_in: 0,
-        ctrl_out: 0,
-        flags: 1, // leaf-present + boundary-packed
-        acc_limbs: limbs,
-        left_tail_digest: l_tail,
-        right_head_digest: r_head,
-    };
-
-    let mut h = Hasher::new();
-    h.update(DS_LEAF_PI_V1.as_bytes());
-    h.update(&public.ctrl_in.to_le_bytes());
-    h.update(&public.ctrl_out.to_le_bytes());
-    h.update(&public.flags.to_le_bytes());
-    for limb in public.acc_limbs {
-        h.update(&limb.to_le_bytes());
-    }
-    h.update(&public.left_tail_digest);
-    h.update(&public.right_head_digest);
-    let proof = StarkProofV1 {
-        mac: *h.finalize().as_bytes(),
-    };
-
-    Ok((public, proof))
-}
-
-/// Verify the compact commitment (MAC) against the public inputs.
-#[must_use]
-pub fn verify_leaf_pi(public: &PiPublic, proof: &StarkProofV1) -> bool {
-    let mut h = Hasher::new();
-    h.update(DS_LEAF_PI_V1.as_bytes());
-    h.update(&public.ctrl_in.to_le_bytes());
-    h.update(&public.ctrl_out.to_le_bytes());
-    h.update(&public.flags.to_le_bytes());
-    for limb in public.acc_limbs {
-        h.update(&limb.to_le_bytes());
-    }
-    h.update(&public.left_tail_digest);
-    h.update(&public.right_head_digest);
-    proof.mac == *h.finalize().as_bytes()
-}
-
-/* ---------------------------- AreIfaceAir (micro) -------------------------- */
-
-/// Domain separator for the ARE interface micro-proof.
-pub const DS_ARE_V2: &str = "stark/are_iface/v2";
-
-/// Public bits exported by a leaf/subtree for the interface check.
-/// We re-use a single struct; `prove_iface_replay` will consume:
-///   - from `li`: `r_head_prefix`, `ctrl_out`
-///   - from `ri`: `l_tail_prefix`, `ctrl_in`
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
-pub struct LeafIfacePublic {
-    pub l_tail_prefix: [u64; 2],
-    pub r_head_prefix: [u64; 2],
-    pub ctrl_out: u32,
-    pub ctrl_in: u32,
-}
-
-/// Minimal ARE proof object (MAC over the public tuple).
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
-pub struct AreProofStark {
-    pub mac: [u8; 32],
-}
-
-/// Prove the sibling interface from public leaf/subtree views.
-pub fn prove_iface_replay(li: &LeafIfacePublic, ri: &LeafIfacePublic) -> anyhow::Result<AreProofStark> {
-    // Bind exactly the tuple the verifier will check.
-    let mut h = Hasher::new();
-    h.update(DS_ARE_V2.as_bytes());
-    for x in li.r_head_prefix { h.update(&x.to_le_bytes()); }
-    h.update(&li.ctrl_out.to_le_bytes());
-    for x in ri.l_tail_prefix { h.update(&x.to_le_bytes()); }
-    h.update(&ri.ctrl_in.to_le_bytes());
-    Ok(AreProofStark { mac: *h.finalize().as_bytes() })
-}
-
-/// Verify the sibling interface proof.
-pub fn verify_iface_replay(li: &LeafIfacePublic, ri: &LeafIfacePublic, p: &AreProofStark) -> bool {
-    // Enforce control continuity at verification time.
-    if li.ctrl_out != ri.ctrl_in {
-        return false;
-    }
-    let mut h = Hasher::new();
-    h.update(DS_ARE_V2.as_bytes());
-    for x in li.r_head_prefix { h.update(&x.to_le_bytes()); }
-    h.update(&li.ctrl_out.to_le_bytes());
-    for x in ri.l_tail_prefix { h.update(&x.to_le_bytes()); }
-    h.update(&ri.ctrl_in.to_le_bytes());
-    p.mac == *h.finalize().as_bytes()
-}
+// Shared with the fold line, which does not depend on this crate.
+pub use sezkp_gadgets::iface::{
+    prove_iface_replay, verify_iface_replay, AreProofStark, LeafIfacePublic, DS_ARE_V2,
+};
+pub use sezkp_gadgets::leaf_pi::{
+    pack_boundary_limbs, prove_leaf_pi, verify_leaf_pi, PiPublic, StarkProofV1, DS_LEAF_PI_V1,
+};
 
 /* -------------------------------- WrapAir ---------------------------------- */
 
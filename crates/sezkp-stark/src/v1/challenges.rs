@@ -0,0 +1,170 @@
+//! Fiat–Shamir challenge traces for STARK v1.
+//!
+//! [`replay`] re-runs a proof's transcript and records every value drawn
+//! from it: the composition alphas, the ZK mask coefficients, the
+//! out-of-domain point, the FRI betas and all query positions. The verifier
+//! draws its challenges through the same schedule (`draw`), so a recorded
+//! [`ChallengeTrace`] lists exactly the values a proof was checked against.
+//! The fixtures keep one trace per STARK v1 proof; a change to the schedule
+//! then shows up as a diff of values, and protocol notes can quote real
+//! draws instead of made-up ones.
+
+#![forbid(unsafe_code)]
+#![deny(rust_2018_idioms)]
+#![warn(
+    missing_docs,
+    clippy::all,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::unwrap_used,
+    clippy::expect_used
+)]
+
+use anyhow::{ensure, Result};
+use serde::{Deserialize, Serialize};
+use sezkp_crypto::{Blake3Transcript, Transcript};
+
+use crate::v1::{
+    field::F1,
+    masking::{derive_mask_coeffs, DEFAULT_MASK_DEG, DEFAULT_N_MASKS},
+    params,
+    proof::ProofV1,
+};
+
+/// Every challenge a v1 verifier draws for one proof, in draw order.
+///
+/// Field elements are canonical Goldilocks values. The empty proof draws
+/// nothing and has the default (empty) trace.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChallengeTrace {
+    /// Composition weights ([`params::NUM_ALPHAS`] of them).
+    pub alphas: Vec<u64>,
+    /// ZK mask coefficients, one row per mask. Drawn to stay aligned with
+    /// the prover; the openings-only check does not use them.
+    pub mask_coeffs: Vec<Vec<u64>>,
+    /// Out-of-domain point, drawn for alignment like the masks.
+    pub ood_point: Option<u64>,
+    /// FRI folding coefficients, one per fold.
+    pub fri_betas: Vec<u64>,
+    /// Trace rows the proof must open for the AIR check.
+    pub row_queries: Vec<usize>,
+    /// Layer-0 FRI positions of the chained schedule (empty otherwise).
+    pub fri_queries: Vec<usize>,
+    /// FRI positions per layer of a per-layer schedule (empty when chained).
+    pub fri_layer_queries: Vec<Vec<usize>>,
+}
+
+/// Replay the transcript of `proof` and record its challenges.
+///
+/// Only the proof's shape is checked (domain and query counts), not its
+/// openings: a proof that fails verification still has a well-defined
+/// trace, which is what a failing test wants to look at.
+///
+/// # Errors
+/// Fails if the domain or the FRI parameters are malformed.
+pub fn replay(proof: &ProofV1) -> Result<ChallengeTrace> {
+    if proof.domain_n == 0 {
+        return Ok(ChallengeTrace::default());
+    }
+    let n = trace_rows(proof)?;
+    proof
+        .fri_params
+        .check(proof.domain_n.trailing_zeros() as usize)?;
+    Ok(draw(proof, n).0)
+}
+
+/// Trace length `n` of a non-empty proof: `domain_n / BLOWUP`, a power of
+/// two, and the row count its public inputs declare.
+pub(crate) fn trace_rows(proof: &ProofV1) -> Result<usize> {
+    let blow = params::BLOWUP;
+    ensure!(blow.is_power_of_two(), "BLOWUP must be a power of two");
+    ensure!(
+        proof.domain_n.is_multiple_of(blow),
+        "FRI domain_n not multiple of blowup"
+    );
+    let n = proof.domain_n / blow;
+    ensure!(n.is_power_of_two(), "trace length n must be a power of two");
+    ensure!(
+        proof.public.n_rows == n,
+        "public inputs declare {} rows, FRI domain covers {n}",
+        proof.public.n_rows
+    );
+    Ok(n)
+}
+
+/// Run the verifier's transcript schedule for a non-empty proof over `n`
+/// rows, starting from its public inputs. Also returns the transcript as it stands after the out-of-domain
+/// point, which is where the FRI check picks up (it binds the FRI roots and
+/// draws the betas again itself).
+pub(crate) fn draw(proof: &ProofV1, n: usize) -> (ChallengeTrace, Blake3Transcript) {
+    /* --------------------- Public inputs + column roots --------------------- */
+
+    let mut tr = Blake3Transcript::new(params::DS_V1_DOMAIN);
+    params::absorb_public_inputs(&mut tr, &proof.public);
+    tr.absorb_u64(params::DS_N_COLS, proof.col_roots.len() as u64);
+    for cr in &proof.col_roots {
+        tr.absorb(params::DS_COL_ROOT, &cr.root);
+        tr.absorb_u64(params::DS_COL_PER_BLOCK, u64::from(cr.per_block));
+    }
+
+    /* --------------------- Alphas, masks and OOD point ---------------------- */
+
+    let alphas = params::derive_alphas(&mut tr).map(value).to_vec();
+    let mask_coeffs = derive_mask_coeffs(&mut tr, DEFAULT_MASK_DEG, DEFAULT_N_MASKS)
+        .into_iter()
+        .map(|m| m.into_iter().map(value).collect())
+        .collect();
+    let ood_point = Some(value(params::derive_ood_point(&mut tr)));
+
+    /* ------------------------- FRI betas + queries -------------------------- */
+
+    // The prover had absorbed the FRI roots and drawn the betas before any
+    // query; the queries come from a copy that does the same.
+    let fri_tr = tr.clone();
+    let roots = &proof.fri_roots.roots;
+    let mut fri_betas = Vec::new();
+    if let Some((first, rest)) = roots.split_first() {
+        tr.absorb(params::DS_FRI_LAYER_ROOT, first);
+        fri_betas = params::derive_betas_for_fri(&mut tr, rest.len())
+            .into_iter()
+            .map(value)
+            .collect();
+        for r in rest {
+            tr.absorb(params::DS_FRI_LAYER_ROOT, r);
+        }
+    }
+
+    params::absorb_beacon(&mut tr, proof.beacon.as_deref());
+    let k = proof.fri_params.num_queries;
+    let row_queries = params::derive_queries(&mut tr, n, k);
+    // FRI positions come next: layer-0 positions for the chained schedule,
+    // or one set per layer.
+    let fri_queries = if proof.fri_params.is_chained() {
+        params::derive_queries(&mut tr, proof.domain_n, k)
+    } else {
+        Vec::new()
+    };
+    let fri_layer_queries = proof
+        .fri_params
+        .layer_queries
+        .iter()
+        .enumerate()
+        .map(|(l, &k)| params::derive_layer_queries(&mut tr, l, proof.domain_n >> l, k))
+        .collect();
+
+    let trace = ChallengeTrace {
+        alphas,
+        mask_coeffs,
+        ood_point,
+        fri_betas,
+        row_queries,
+        fri_queries,
+        fri_layer_queries,
+    };
+    (trace, fri_tr)
+}
+
+/// Canonical value of a field element.
+const fn value(x: F1) -> u64 {
+    x.0
+}
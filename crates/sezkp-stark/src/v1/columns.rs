@@ -5,12 +5,12 @@
 //!  - Bit-decomposition for `head` and for `slack = win_len - 1 - head`
 //!    using `HEAD_BITS` bits; this powers a non-negativity comparison
 //!    to enforce `0 <= head <= win_len - 1` when `write_flag = 1`.
-//!  - A canonical, bounded **interface digest** utility
-//!    [`interface_boundary_digest`] for the folding line.
-//!  - Per-block boundary helpers for leaves:
-//!      * [`boundary_left_tail_digest`]
-//!      * [`boundary_right_head_digest`]
-//!      * (optional) raw boundary windows: [`left_tail_window`], [`right_head_window`].
+//!  - Input-head columns: `in_head` (absolute, post-move) and the block
+//!    constants `in_head_in` / `in_head_out`, tying each block's input moves
+//!    to its declared endpoints.
+//!  - Re-exports of the bounded boundary helpers from `sezkp_core::boundary`:
+//!    the folding line's [`interface_boundary_digest`] and the per-block
+//!    [`boundary_left_tail_digest`] / [`boundary_right_head_digest`] leaves use.
 
 #![forbid(unsafe_code)]
 #![deny(rust_2018_idioms)]
@@ -23,193 +23,59 @@
 )]
 
 use anyhow::Result;
-use blake3::Hasher;
 use sezkp_core::BlockSummary;
 
-use crate::v1::field::F1;
+use crate::v1::field::{from_u64_slice, F1};
 
 /// Number of bits used to decompose the written symbol.
 pub const SYM_BITS: usize = 4;
 /// Number of bits used for head/slack range checks.
 pub const HEAD_BITS: usize = 16;
 
-/// Default bounded window (steps) taken from each side of the interface.
-pub const IFACE_WINDOW_STEPS: usize = 32;
-
-/// Canonical, bounded interface digest.
-///
-/// This digest is intentionally simple and deterministic. It includes:
-///  - tape count `tau`,
-///  - static in/out head offsets for both blocks, and
-///  - the last `K` steps from the **left** block and first `K` steps from the
-///    **right** block (where `K = IFACE_WINDOW_STEPS`), for each tape: `(mv,
-///    write_flag, write_sym)`.
-///
-/// The folding line (B-line) can feed this digest into its ARE micro-proof.
-///
-#[must_use]
-pub fn interface_boundary_digest(left: &BlockSummary, right: &BlockSummary) -> [u8; 32] {
-    let tau = left.windows.len();
-    let mut h = Hasher::new();
-    h.update(b"sezkp/iface/v1");
-    h.update(&(tau as u32).to_le_bytes());
-
-    // Static offsets per tape (from block metadata). Use i32 encodings.
+// The boundary encodings are shared with the folding line and live in core;
+// re-exported here for existing `v1::columns` users.
+pub use sezkp_core::boundary::{
+    boundary_left_tail_digest, boundary_right_head_digest, interface_boundary_digest,
+    left_tail_window, right_head_window, BoundaryRow, IFACE_WINDOW_STEPS,
+};
+
+/* ------------------------- Committed column labels ------------------------- */
+
+/// Input-head columns, committed only by proofs that declare an input length.
+const INPUT_HEAD_LABELS: [&str; 3] = ["in_head", "in_head_in", "in_head_out"];
+
+/// Public label order (must match the verifier / transcript binding); the
+/// input-head columns come last when `input_head` is set.
+pub(crate) fn all_labels(tau: usize, input_head: bool) -> Vec<String> {
+    let mut out = Vec::<String>::new();
+    out.push("input_mv".into());
+    out.push("is_first".into());
+    out.push("is_last".into());
     for r in 0..tau {
-        h.update(&(left.head_in_offsets[r] as i32).to_le_bytes());
-        h.update(&(left.head_out_offsets[r] as i32).to_le_bytes());
-        h.update(&(right.head_in_offsets[r] as i32).to_le_bytes());
-        h.update(&(right.head_out_offsets[r] as i32).to_le_bytes());
+        out.push(format!("mv_{r}"));
     }
-
-    // Last K steps from left
-    let left_steps = &left.movement_log.steps;
-    let k_l = IFACE_WINDOW_STEPS.min(left_steps.len());
-    for step in &left_steps[left_steps.len().saturating_sub(k_l)..] {
-        for r in 0..tau {
-            let op = &step.tapes[r];
-            let wflag = u32::from(op.write.is_some());
-            let wsym = op.write.unwrap_or(0) as u32;
-            h.update(&(op.mv as i32).to_le_bytes());
-            h.update(&wflag.to_le_bytes());
-            h.update(&wsym.to_le_bytes());
-        }
-    }
-
-    // First K steps from right
-    let right_steps = &right.movement_log.steps;
-    let k_r = IFACE_WINDOW_STEPS.min(right_steps.len());
-    for step in &right_steps[..k_r] {
-        for r in 0..tau {
-            let op = &step.tapes[r];
-            let wflag = u32::from(op.write.is_some());
-            let wsym = op.write.unwrap_or(0) as u32;
-            h.update(&(op.mv as i32).to_le_bytes());
-            h.update(&wflag.to_le_bytes());
-            h.update(&wsym.to_le_bytes());
-        }
+    for r in 0..tau {
+        out.push(format!("wflag_{r}"));
     }
-
-    *h.finalize().as_bytes()
-}
-
-/* ----------------------------- Leaf helpers -------------------------------- */
-
-/// A single “boundary row” used by the per-block boundary windows.
-#[derive(Clone, Copy, Debug)]
-pub struct BoundaryRow {
-    /// Move delta for this tape at this step (post-move semantics overall).
-    pub mv: i32,
-    /// 0/1 flag indicating writes.
-    pub write_flag: u32,
-    /// Written symbol (0 if none).
-    pub write_sym: u32,
-}
-
-/// Return the last `k` steps (all tapes) of `block` in boundary-row encoding.
-///
-/// This materializes the **left tail** the leaf proof may wish to commit to.
-#[must_use]
-pub fn left_tail_window(block: &BlockSummary, k: usize) -> Vec<Vec<BoundaryRow>> {
-    let tau = block.windows.len();
-    let steps = &block.movement_log.steps;
-    let take = IFACE_WINDOW_STEPS.min(k).min(steps.len());
-    let mut out = vec![Vec::with_capacity(take); tau];
-    for step in &steps[steps.len().saturating_sub(take)..] {
-        for r in 0..tau {
-            let op = &step.tapes[r];
-            out[r].push(BoundaryRow {
-                mv: op.mv as i32,
-                write_flag: u32::from(op.write.is_some()),
-                write_sym: op.write.unwrap_or(0) as u32,
-            });
-        }
+    for r in 0..tau {
+        out.push(format!("wsym_{r}"));
     }
-    out
-}
-
-/// Return the first `k` steps (all tapes) of `block` in boundary-row encoding.
-///
-/// This materializes the **right head** the leaf proof may wish to commit to.
-#[must_use]
-pub fn right_head_window(block: &BlockSummary, k: usize) -> Vec<Vec<BoundaryRow>> {
-    let tau = block.windows.len();
-    let steps = &block.movement_log.steps;
-    let take = IFACE_WINDOW_STEPS.min(k).min(steps.len());
-    let mut out = vec![Vec::with_capacity(take); tau];
-    for step in &steps[..take] {
-        for r in 0..tau {
-            let op = &step.tapes[r];
-            out[r].push(BoundaryRow {
-                mv: op.mv as i32,
-                write_flag: u32::from(op.write.is_some()),
-                write_sym: op.write.unwrap_or(0) as u32,
-            });
-        }
+    for r in 0..tau {
+        out.push(format!("head_{r}"));
     }
-    out
-}
-
-/// Deterministic digest of the **left tail** (last `k` steps) of a single block.
-#[must_use]
-pub fn boundary_left_tail_digest(block: &BlockSummary, k: usize) -> [u8; 32] {
-    let tau = block.windows.len();
-    let mut h = Hasher::new();
-    h.update(b"sezkp/iface/left_tail/v1");
-    h.update(&(tau as u32).to_le_bytes());
-
-    // Static offsets for this block
     for r in 0..tau {
-        h.update(&(block.head_in_offsets[r] as i32).to_le_bytes());
-        h.update(&(block.head_out_offsets[r] as i32).to_le_bytes());
+        out.push(format!("winlen_{r}"));
     }
-
-    // Last K steps
-    let steps = &block.movement_log.steps;
-    let take = IFACE_WINDOW_STEPS.min(k).min(steps.len());
-    for step in &steps[steps.len().saturating_sub(take)..] {
-        for r in 0..tau {
-            let op = &step.tapes[r];
-            let wflag = u32::from(op.write.is_some());
-            let wsym = op.write.unwrap_or(0) as u32;
-            h.update(&(op.mv as i32).to_le_bytes());
-            h.update(&wflag.to_le_bytes());
-            h.update(&wsym.to_le_bytes());
-        }
+    for r in 0..tau {
+        out.push(format!("in_off_{r}"));
     }
-
-    *h.finalize().as_bytes()
-}
-
-/// Deterministic digest of the **right head** (first `k` steps) of a single block.
-#[must_use]
-pub fn boundary_right_head_digest(block: &BlockSummary, k: usize) -> [u8; 32] {
-    let tau = block.windows.len();
-    let mut h = Hasher::new();
-    h.update(b"sezkp/iface/right_head/v1");
-    h.update(&(tau as u32).to_le_bytes());
-
-    // Static offsets for this block
     for r in 0..tau {
-        h.update(&(block.head_in_offsets[r] as i32).to_le_bytes());
-        h.update(&(block.head_out_offsets[r] as i32).to_le_bytes());
+        out.push(format!("out_off_{r}"));
     }
-
-    // First K steps
-    let steps = &block.movement_log.steps;
-    let take = IFACE_WINDOW_STEPS.min(k).min(steps.len());
-    for step in &steps[..take] {
-        for r in 0..tau {
-            let op = &step.tapes[r];
-            let wflag = u32::from(op.write.is_some());
-            let wsym = op.write.unwrap_or(0) as u32;
-            h.update(&(op.mv as i32).to_le_bytes());
-            h.update(&wflag.to_le_bytes());
-            h.update(&wsym.to_le_bytes());
-        }
+    if input_head {
+        out.extend(INPUT_HEAD_LABELS.map(String::from));
     }
-
-    *h.finalize().as_bytes()
+    out
 }
 
 /* ----------------------------- Full trace view ----------------------------- */
@@ -226,6 +92,12 @@ pub struct TraceColumns {
     pub input_mv: Vec<F1>,
     pub is_first: Vec<F1>,
     pub is_last: Vec<F1>,
+    /// Absolute input-head position after the row's move; the input-head
+    /// columns are empty unless built with [`Self::build_with_input_head`].
+    pub in_head: Vec<F1>,
+    /// Block constants: declared input-head entry / exit positions.
+    pub in_head_in: Vec<F1>,
+    pub in_head_out: Vec<F1>,
 
     /* per-tape columns, length tau; each inner Vec has length n */
     pub mv: Vec<Vec<F1>>,
@@ -248,6 +120,43 @@ pub struct TraceColumns {
 }
 
 impl TraceColumns {
+    /// Heap bytes held by the column vectors.
+    #[must_use]
+    pub fn heap_bytes(&self) -> u64 {
+        let flat = |v: &Vec<Vec<F1>>| v.iter().map(Vec::len).sum::<usize>();
+        let bits = |v: &Vec<Vec<Vec<F1>>>| v.iter().flatten().map(Vec::len).sum::<usize>();
+        let cells = self.input_mv.len()
+            + self.is_first.len()
+            + self.is_last.len()
+            + self.in_head.len()
+            + self.in_head_in.len()
+            + self.in_head_out.len()
+            + flat(&self.mv)
+            + flat(&self.write_flag)
+            + flat(&self.write_sym)
+            + flat(&self.head)
+            + flat(&self.win_len)
+            + flat(&self.in_off)
+            + flat(&self.out_off)
+            + bits(&self.sym_bits)
+            + bits(&self.head_bits)
+            + bits(&self.slack_bits);
+        (cells * std::mem::size_of::<F1>()) as u64
+    }
+
+    /// [`Self::build`], also filling the input-head columns if `input_head`
+    /// is set (proofs that declare an input length).
+    ///
+    /// # Errors
+    /// Fails like [`Self::build`].
+    pub fn build_with_input_head(blocks: &[BlockSummary], input_head: bool) -> Result<Self> {
+        let mut tc = Self::build(blocks)?;
+        if input_head {
+            (tc.in_head, tc.in_head_in, tc.in_head_out) = input_head_columns(blocks, tc.n);
+        }
+        Ok(tc)
+    }
+
     /// Build the columnar view from block summaries.
     pub fn build(blocks: &[BlockSummary]) -> Result<Self> {
         // Total rows = sum over blocks of (block_len)
@@ -275,6 +184,12 @@ impl TraceColumns {
         let mut head_bits = vec![vec![vec![F1::from_u64(0); n]; HEAD_BITS]; tau];
         let mut slack_bits = vec![vec![vec![F1::from_u64(0); n]; HEAD_BITS]; tau];
 
+        // Per-block constants (reused across blocks)
+        let mut raw: Vec<u64> = Vec::with_capacity(tau);
+        let mut wlen = vec![F1::from_u64(0); tau];
+        let mut in_offs = vec![F1::from_u64(0); tau];
+        let mut out_offs = vec![F1::from_u64(0); tau];
+
         // Fill row-wise across blocks
         let mut row = 0usize;
         for b in blocks {
@@ -286,17 +201,27 @@ impl TraceColumns {
             is_first[row] = F1::from_u64(1);
             is_last[row + len - 1] = F1::from_u64(1);
 
-            // precompute window lengths
-            let mut wlen: Vec<u64> = Vec::with_capacity(tau);
-            for r in 0..tau {
-                let left = b.windows[r].left;
-                let right = b.windows[r].right;
-                let wl = (right - left).unsigned_abs() + 1;
-                wlen.push(wl);
-            }
-
-            // head running positions relative to window-left = 0 at entry
-            let mut cur_heads = vec![0i64; tau];
+            // precompute the block constants: window lengths and in/out offsets
+            raw.clear();
+            raw.extend(
+                b.windows[..tau]
+                    .iter()
+                    .map(|w| (w.right - w.left).unsigned_abs() + 1),
+            );
+            from_u64_slice(&raw, &mut wlen);
+            raw.clear();
+            raw.extend(b.head_in_offsets[..tau].iter().map(|&o| u64::from(o)));
+            from_u64_slice(&raw, &mut in_offs);
+            raw.clear();
+            raw.extend(b.head_out_offsets[..tau].iter().map(|&o| u64::from(o)));
+            from_u64_slice(&raw, &mut out_offs);
+
+            // head running positions relative to window-left, starting at
+            // the declared entry offset
+            let mut cur_heads: Vec<i64> = b.head_in_offsets[..tau]
+                .iter()
+                .map(|&o| i64::from(o))
+                .collect();
 
             for (j, step) in b.movement_log.steps.iter().enumerate() {
                 input_mv[row + j] = F1::from_i64(step.input_mv as i64);
@@ -311,13 +236,13 @@ impl TraceColumns {
                     // move then write semantics: head is post-move
                     cur_heads[r] += i64::from(op.mv);
 
-                    // head is relative to left bound (so 0 at entry)
+                    // head is relative to the left bound
                     head[r][row + j] = F1::from_i64(cur_heads[r]);
-                    win_len[r][row + j] = F1::from_u64(wlen[r]);
+                    win_len[r][row + j] = wlen[r];
 
                     // in/out offsets are constant inside the block
-                    in_off[r][row + j] = F1::from_u64(b.head_in_offsets[r] as u64);
-                    out_off[r][row + j] = F1::from_u64(b.head_out_offsets[r] as u64);
+                    in_off[r][row + j] = in_offs[r];
+                    out_off[r][row + j] = out_offs[r];
 
                     // --------- bit decompositions ----------
                     // write_sym bits
@@ -351,6 +276,9 @@ impl TraceColumns {
             input_mv,
             is_first,
             is_last,
+            in_head: Vec::new(),
+            in_head_in: Vec::new(),
+            in_head_out: Vec::new(),
             mv,
             write_flag,
             write_sym,
@@ -364,3 +292,22 @@ impl TraceColumns {
         })
     }
 }
+
+/// Input-head columns over `n` rows: the absolute post-move position
+/// (starting from each block's declared entry) and the block's declared
+/// entry / exit positions.
+fn input_head_columns(blocks: &[BlockSummary], n: usize) -> (Vec<F1>, Vec<F1>, Vec<F1>) {
+    let mut in_head = Vec::with_capacity(n);
+    let mut in_head_in = Vec::with_capacity(n);
+    let mut in_head_out = Vec::with_capacity(n);
+    for b in blocks {
+        let mut cur = b.in_head_in;
+        for step in &b.movement_log.steps {
+            cur += i64::from(step.input_mv);
+            in_head.push(F1::from_i64(cur));
+            in_head_in.push(F1::from_i64(b.in_head_in));
+            in_head_out.push(F1::from_i64(b.in_head_out));
+        }
+    }
+    (in_head, in_head_in, in_head_out)
+}
@@ -7,6 +7,8 @@
 //!
 //! The values produced **exactly match** those in `TraceColumns::build` for:
 //!   - `input_mv`, `is_first`, `is_last`,
+//!   - `in_head` (absolute, post-move) and `in_head_in`, `in_head_out`
+//!     (when built with the input head),
 //!   - per-tape columns: `mv`, `write_flag`, `write_sym`,
 //!   - `head` (post-move semantics, relative to window-left),
 //!   - `win_len`, `in_off`, `out_off`.
@@ -25,7 +27,7 @@
 
 use sezkp_core::BlockSummary;
 
-use crate::v1::field::F1;
+use crate::v1::field::{le_from_u64_slice, F1};
 
 /// Per-row snapshot of the committed columns (as 8-byte LE field elements).
 #[derive(Clone, Debug)]
@@ -33,6 +35,9 @@ pub struct RowColsSnapshot {
     pub input_mv: [u8; 8],
     pub is_first: [u8; 8],
     pub is_last: [u8; 8],
+    pub in_head: [u8; 8],
+    pub in_head_in: [u8; 8],
+    pub in_head_out: [u8; 8],
 
     pub mv: Vec<[u8; 8]>,
     pub write_flag: Vec<[u8; 8]>,
@@ -50,6 +55,9 @@ impl RowColsSnapshot {
             input_mv: [0u8; 8],
             is_first: [0u8; 8],
             is_last: [0u8; 8],
+            in_head: [0u8; 8],
+            in_head_in: [0u8; 8],
+            in_head_out: [0u8; 8],
             mv: vec![[0u8; 8]; tau],
             write_flag: vec![[0u8; 8]; tau],
             write_sym: vec![[0u8; 8]; tau],
@@ -71,10 +79,61 @@ fn f_le_i64(x: i64) -> [u8; 8] {
     F1::from_i64(x).to_le_bytes()
 }
 
+/// Per-block constant columns (`win_len`, `in_off`, `out_off`, and the
+/// input-head endpoints), encoded once on block entry and copied into every
+/// row of the block.
+#[derive(Debug, Default)]
+pub(crate) struct BlockConsts {
+    pub(crate) in_head_in: [u8; 8],
+    pub(crate) in_head_out: [u8; 8],
+    pub(crate) win_len: Vec<[u8; 8]>,
+    pub(crate) in_off: Vec<[u8; 8]>,
+    pub(crate) out_off: Vec<[u8; 8]>,
+    raw: Vec<u64>,
+}
+
+impl BlockConsts {
+    pub(crate) fn new(tau: usize) -> Self {
+        Self {
+            in_head_in: [0u8; 8],
+            in_head_out: [0u8; 8],
+            win_len: vec![[0u8; 8]; tau],
+            in_off: vec![[0u8; 8]; tau],
+            out_off: vec![[0u8; 8]; tau],
+            raw: Vec::with_capacity(tau),
+        }
+    }
+
+    /// Re-encode the constants for block `b` (first `tau` tapes).
+    pub(crate) fn load(&mut self, b: &BlockSummary) {
+        let tau = self.win_len.len();
+        self.in_head_in = f_le_i64(b.in_head_in);
+        self.in_head_out = f_le_i64(b.in_head_out);
+
+        self.raw.clear();
+        self.raw.extend(
+            b.windows[..tau]
+                .iter()
+                .map(|w| (w.right - w.left).unsigned_abs() + 1),
+        );
+        le_from_u64_slice(&self.raw, &mut self.win_len);
+
+        self.raw.clear();
+        self.raw
+            .extend(b.head_in_offsets[..tau].iter().map(|&o| u64::from(o)));
+        le_from_u64_slice(&self.raw, &mut self.in_off);
+
+        self.raw.clear();
+        self.raw
+            .extend(b.head_out_offsets[..tau].iter().map(|&o| u64::from(o)));
+        le_from_u64_slice(&self.raw, &mut self.out_off);
+    }
+}
+
 /// Row-wise iterator over all committed columns derived from `blocks`.
 ///
 /// Semantics are identical to `TraceColumns::build` (move-then-write; head is
-/// post-move; head is relative to window-left at block entry).
+/// post-move; head is relative to window-left, starting at the entry offset).
 pub struct ColumnRowIter<'a> {
     blocks: &'a [BlockSummary],
     tau: usize,
@@ -85,8 +144,9 @@ pub struct ColumnRowIter<'a> {
 
     // Per-block cached data
     blk_len: usize,
-    wlen: Vec<u64>,
+    consts: BlockConsts,
     cur_heads: Vec<i64>,
+    cur_in: i64,
 }
 
 impl<'a> ColumnRowIter<'a> {
@@ -101,8 +161,9 @@ impl<'a> ColumnRowIter<'a> {
             row_in_blk: 0,
             row_global: 0,
             blk_len: 0,
-            wlen: vec![0u64; tau],
+            consts: BlockConsts::new(tau),
             cur_heads: vec![0i64; tau],
+            cur_in: 0,
         };
         it.enter_block();
         it
@@ -113,18 +174,14 @@ impl<'a> ColumnRowIter<'a> {
         if let Some(b) = self.blocks.get(self.blk_idx) {
             self.blk_len = (b.step_hi - b.step_lo + 1) as usize;
 
-            // Pre-compute window lengths per tape.
-            for r in 0..self.tau {
-                let left = b.windows[r].left;
-                let right = b.windows[r].right;
-                let wl = (right - left).abs() as u64 + 1;
-                self.wlen[r] = wl;
-            }
+            // Pre-encode window lengths and in/out offsets per tape.
+            self.consts.load(b);
 
-            // Reset running heads to 0 (relative to left bound at entry).
-            for h in &mut self.cur_heads {
-                *h = 0;
+            // Reset running heads to the entry offsets (relative to the left bound).
+            for (h, &o) in self.cur_heads.iter_mut().zip(&b.head_in_offsets) {
+                *h = i64::from(o);
             }
+            self.cur_in = b.in_head_in;
         } else {
             self.blk_len = 0;
         }
@@ -163,6 +220,12 @@ impl<'a> Iterator for ColumnRowIter<'a> {
         let step = &b.movement_log.steps[self.row_in_blk];
         row.input_mv = f_le_i64(step.input_mv as i64);
 
+        // Absolute input head (post-move) and the block's declared endpoints
+        self.cur_in += i64::from(step.input_mv);
+        row.in_head = f_le_i64(self.cur_in);
+        row.in_head_in = self.consts.in_head_in;
+        row.in_head_out = self.consts.in_head_out;
+
         // Per-tape fields
         for r in 0..self.tau {
             let op = &step.tapes[r];
@@ -179,15 +242,13 @@ impl<'a> Iterator for ColumnRowIter<'a> {
             // move-then-write semantics: head is post-move
             self.cur_heads[r] += op.mv as i64;
             row.head[r] = f_le_i64(self.cur_heads[r]);
-
-            // window length (constant across block)
-            row.win_len[r] = f_le_u64(self.wlen[r]);
-
-            // in/out offsets (constant inside the block)
-            row.in_off[r] = f_le_u64(b.head_in_offsets[r] as u64);
-            row.out_off[r] = f_le_u64(b.head_out_offsets[r] as u64);
         }
 
+        // window length and in/out offsets (constant inside the block)
+        row.win_len.copy_from_slice(&self.consts.win_len);
+        row.in_off.copy_from_slice(&self.consts.in_off);
+        row.out_off.copy_from_slice(&self.consts.out_off);
+
         // Advance iterators
         self.row_in_blk += 1;
         self.row_global += 1;
@@ -0,0 +1,207 @@
+//! Exact v1 proof sizes from the trace shape alone.
+//!
+//! A v1 proof's shape is fixed by the trace length `n`, `tau`, the parameter
+//! constants and each column's layout: one root per column, [`NUM_QUERIES`]
+//! row openings with chunked Merkle paths (columns committed per block: one
+//! path over the blocks, padded to a power of two), `log2(n·BLOWUP) + 1` FRI
+//! layer roots and [`NUM_QUERIES`] FRI queries with one opened pair per
+//! folded layer. Every field has a fixed bincode width, so a zero-filled
+//! proof of that shape serializes to exactly as many bytes as the real one.
+//!
+//! Layouts follow the values: window lengths, offsets and input-head
+//! endpoints are constant within every block, the other columns usually are
+//! not. With one row per block every column is committed per block.
+
+#![forbid(unsafe_code)]
+#![deny(rust_2018_idioms)]
+#![warn(
+    clippy::all,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::unwrap_used,
+    clippy::expect_used
+)]
+
+use anyhow::{ensure, Context, Result};
+use serde::Serialize;
+use sezkp_core::estimate::{SizeEstimate, SizeItem};
+
+use crate::v1::{
+    columns::all_labels,
+    params::{BLOWUP, COL_CHUNK_LOG2, NUM_QUERIES},
+    proof::{
+        BlockOpening, ColumnOpening, ColumnRoot, FriParams, FriQuery, FriRoots, InputHeadOpen,
+        Opening, PerTapeOpen, ProofParams, ProofV1, PublicInputs, RowOpenings,
+    },
+};
+
+/// Predict the `proof_bytes` of a v1 proof over `n_blocks` blocks of `rows`
+/// steps each on `tau` tapes.
+///
+/// The figure is exact for single-row blocks and otherwise an upper bound,
+/// reached when every step column varies within some block (one that does
+/// not is committed per block, with shorter openings). Both hold for proofs
+/// without a beacon under the chained FRI schedule with [`NUM_QUERIES`]
+/// queries; a beacon adds its length plus 8 bytes. `params` is encoded as
+/// given: the movement model does not change the size, a declared input
+/// length adds its input-head columns and openings. STARK proofs have no
+/// side stream.
+///
+/// # Errors
+/// Fails if the total row count overflows or is not a power of two (the
+/// prover rejects such traces too).
+pub fn estimate_proof_size(
+    n_blocks: u64,
+    rows: u64,
+    tau: usize,
+    params: &ProofParams,
+) -> Result<SizeEstimate> {
+    let n = n_blocks
+        .checked_mul(rows)
+        .context("total row count overflows")?;
+    let proof = if n == 0 {
+        ProofV1::empty()
+    } else {
+        ensure!(
+            n.is_power_of_two(),
+            "STARK traces need a power-of-two row count; {n_blocks} blocks x {rows} rows = {n}"
+        );
+        let n = usize::try_from(n).context("row count exceeds the address space")?;
+        let n_blocks =
+            usize::try_from(n_blocks).context("block count exceeds the address space")?;
+        shape_proof(n, n_blocks, tau, *params)
+    };
+
+    let items = vec![
+        SizeItem::new(
+            "column roots",
+            proof.col_roots.len() as u64,
+            encoded_len(&proof.col_roots)?,
+        ),
+        SizeItem::new(
+            "row openings",
+            proof.queries.len() as u64,
+            encoded_len(&proof.queries)?,
+        ),
+        SizeItem::new(
+            "fri layer roots",
+            proof.fri_roots.roots.len() as u64,
+            encoded_len(&proof.fri_roots)?,
+        ),
+        SizeItem::new(
+            "fri queries",
+            proof.fri_queries.len() as u64,
+            encoded_len(&proof.fri_queries)?,
+        ),
+    ];
+    Ok(SizeEstimate {
+        proof_bytes: encoded_len(&proof)?,
+        stream_bytes: None,
+        exact: rows <= 1,
+        items,
+    })
+}
+
+/// bincode size of `v`, as written into `proof_bytes`.
+fn encoded_len<T: Serialize>(v: &T) -> Result<u64> {
+    bincode::serialized_size(v).context("sizing proof encoding")
+}
+
+/// A zero-filled proof with the largest shape the prover emits for `n` rows
+/// in `n_blocks` blocks (the exact one for single-row blocks).
+fn shape_proof(n: usize, n_blocks: usize, tau: usize, params: ProofParams) -> ProofV1 {
+    let base_log2 = n.trailing_zeros() as usize;
+    let lde_log2 = base_log2 + BLOWUP.trailing_zeros() as usize;
+    let n_layers = lde_log2 + 1;
+
+    // Chunks hold 2^COL_CHUNK_LOG2 rows; shorter traces are a single chunk.
+    let in_chunk = base_log2.min(COL_CHUNK_LOG2);
+    let opening = Opening {
+        value_le: [0; 8],
+        index: 0,
+        chunk_index: 0,
+        index_in_chunk: 0,
+        chunk_root: [0; 32],
+        path_in_chunk: vec![[0; 32]; in_chunk],
+        path_to_chunk: vec![[0; 32]; base_log2 - in_chunk],
+    };
+    let block_opening = BlockOpening {
+        value_le: [0; 8],
+        index: 0,
+        block_index: 0,
+        row_lo: 0,
+        row_hi: 0,
+        path: vec![[0; 32]; n_blocks.next_power_of_two().trailing_zeros() as usize],
+    };
+    let block_opening = ColumnOpening::Block(block_opening);
+    let opening = if n == n_blocks {
+        block_opening.clone()
+    } else {
+        ColumnOpening::Chunked(opening)
+    };
+    let per_tape = PerTapeOpen {
+        mv: opening.clone(),
+        next_mv: opening.clone(),
+        write_flag: opening.clone(),
+        write_sym: opening.clone(),
+        head: opening.clone(),
+        next_head: opening.clone(),
+        win_len: block_opening.clone(),
+        in_off: block_opening.clone(),
+        out_off: block_opening.clone(),
+    };
+    let row = RowOpenings {
+        row: 0,
+        per_tape: vec![per_tape; tau],
+        is_first: opening.clone(),
+        is_last: opening.clone(),
+        input_mv: opening.clone(),
+        input_head: params.input_len.map(|_| InputHeadOpen {
+            in_head: opening.clone(),
+            next_in_head: opening.clone(),
+            next_input_mv: opening.clone(),
+            in_head_in: block_opening.clone(),
+            in_head_out: block_opening.clone(),
+        }),
+    };
+
+    // Layer r has 2^(lde_log2 - r) leaves; every layer but the last opens a pair.
+    let fri_query = FriQuery {
+        positions: vec![0; n_layers],
+        pairs: (0..n_layers - 1)
+            .map(|r| {
+                let path = vec![[0; 32]; lde_log2 - r];
+                ([0; 8], path.clone(), [0; 8], path)
+            })
+            .collect(),
+    };
+
+    ProofV1 {
+        public: PublicInputs {
+            n_rows: n,
+            tau,
+            manifest_root: [0; 32],
+            params_digest: [0; 32],
+            claims: None,
+        },
+        domain_n: 1 << lde_log2,
+        col_roots: all_labels(tau, params.input_len.is_some())
+            .into_iter()
+            .map(|label| ColumnRoot {
+                label,
+                root: [0; 32],
+                per_block: false,
+            })
+            .collect(),
+        queries: vec![row; NUM_QUERIES],
+        fri_roots: FriRoots {
+            roots: vec![[0; 32]; n_layers],
+        },
+        fri_queries: vec![fri_query; NUM_QUERIES],
+        fri_final_value_le: [0; 8],
+        fri_params: FriParams::default(),
+        fri_layer_queries: Vec::new(),
+        beacon: None,
+        params,
+    }
+}
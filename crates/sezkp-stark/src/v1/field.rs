@@ -10,8 +10,9 @@
     clippy::expect_used
 )]
 
-/// Field used by v1 (Goldilocks from `sezkp-ffts`).
-pub use sezkp_ffts::Goldilocks as F1;
+/// Field used by v1 (Goldilocks from `sezkp-ffts`, shared through
+/// `sezkp-gadgets` with the fold line).
+pub use sezkp_gadgets::F1;
 
 use sezkp_ffts::{Fp64, GOLDILOCKS};
 
@@ -35,3 +36,73 @@ pub fn f_from_i64(x: i64) -> F1 {
         Fp64::<GOLDILOCKS>(v)
     }
 }
+
+/* --------------------------- Batch conversions ---------------------------- */
+
+// Slices in, slices out, for the row builders' column runs and per-block
+// constants. `Fp64::from_u64` reduces with `%` and `from_i64` goes through
+// `i128::rem_euclid`; the helpers use the exact cheap forms instead:
+// - any `u64` is `< 2p`, so one conditional subtraction reduces it;
+// - any `i64` has `|x| ≤ 2^63 < p`, so it needs no reduction at all.
+
+#[inline]
+const fn reduce_u64(x: u64) -> u64 {
+    if x >= GOLDILOCKS {
+        x - GOLDILOCKS
+    } else {
+        x
+    }
+}
+
+#[inline]
+const fn reduce_i64(x: i64) -> u64 {
+    if x >= 0 {
+        x.unsigned_abs()
+    } else {
+        GOLDILOCKS - x.unsigned_abs()
+    }
+}
+
+/// Convert `xs` into field elements, writing `out[i] = xs[i] mod p`.
+///
+/// # Panics
+/// Panics if the slices differ in length.
+pub fn from_u64_slice(xs: &[u64], out: &mut [F1]) {
+    assert_eq!(xs.len(), out.len(), "from_u64_slice length mismatch");
+    for (o, &x) in out.iter_mut().zip(xs) {
+        *o = Fp64::<GOLDILOCKS>(reduce_u64(x));
+    }
+}
+
+/// Convert `xs` into field elements, writing `out[i] = xs[i] mod p`.
+///
+/// # Panics
+/// Panics if the slices differ in length.
+pub fn from_i64_slice(xs: &[i64], out: &mut [F1]) {
+    assert_eq!(xs.len(), out.len(), "from_i64_slice length mismatch");
+    for (o, &x) in out.iter_mut().zip(xs) {
+        *o = Fp64::<GOLDILOCKS>(reduce_i64(x));
+    }
+}
+
+/// Encode `xs` as canonical 8-byte LE field elements (`F1::from_u64(x).to_le_bytes()`).
+///
+/// # Panics
+/// Panics if the slices differ in length.
+pub fn le_from_u64_slice(xs: &[u64], out: &mut [[u8; 8]]) {
+    assert_eq!(xs.len(), out.len(), "le_from_u64_slice length mismatch");
+    for (o, &x) in out.iter_mut().zip(xs) {
+        *o = reduce_u64(x).to_le_bytes();
+    }
+}
+
+/// Encode `xs` as canonical 8-byte LE field elements (`F1::from_i64(x).to_le_bytes()`).
+///
+/// # Panics
+/// Panics if the slices differ in length.
+pub fn le_from_i64_slice(xs: &[i64], out: &mut [[u8; 8]]) {
+    assert_eq!(xs.len(), out.len(), "le_from_i64_slice length mismatch");
+    for (o, &x) in out.iter_mut().zip(xs) {
+        *o = reduce_i64(x).to_le_bytes();
+    }
+}
@@ -20,9 +20,9 @@ use sezkp_crypto::Transcript;
 
 use crate::v1::{
     field::F1,
-    merkle::{hash_field_leaves, MerkleProof, MerkleTree},
+    merkle::{fri_layer_tree, hash_fri_leaf, verify_fri_open},
     params,
-    proof::FriQuery,
+    proof::{FriLayerQuery, FriQuery},
 };
 
 #[inline]
@@ -49,8 +49,7 @@ pub fn fri_commit<T: Transcript>(
 
     // Layer 0 root, absorb before sampling betas.
     let root0 = {
-        let leaves0 = hash_field_leaves(&to_le_vec(&layers[0]));
-        let mt0 = MerkleTree::from_leaves(&leaves0);
+        let mt0 = fri_layer_tree(&to_le_vec(&layers[0]));
         let r0 = mt0.root();
         tr.absorb(params::DS_FRI_LAYER_ROOT, &r0);
         r0
@@ -83,8 +82,7 @@ pub fn fri_commit<T: Transcript>(
     let mut roots = Vec::<[u8; 32]>::with_capacity(layers.len());
     roots.push(root0);
     for layer in layers.iter().skip(1) {
-        let leaves = hash_field_leaves(&to_le_vec(layer));
-        let mt = MerkleTree::from_leaves(&leaves);
+        let mt = fri_layer_tree(&to_le_vec(layer));
         let root = mt.root();
         tr.absorb(params::DS_FRI_LAYER_ROOT, &root);
         roots.push(root);
@@ -111,8 +109,7 @@ pub fn fri_open_query(layers: &[Vec<F1>], _roots: &[[u8; 32]], mut idx: usize) -
         let half = layer.len() / 2;
         let j = idx ^ half; // sibling position
 
-        let leaves = hash_field_leaves(&to_le_vec(layer));
-        let mt = MerkleTree::from_leaves(&leaves);
+        let mt = fri_layer_tree(&to_le_vec(layer));
         let pi = mt.open(idx);
         let pj = mt.open(j);
 
@@ -127,10 +124,15 @@ pub fn fri_open_query(layers: &[Vec<F1>], _roots: &[[u8; 32]], mut idx: usize) -
 }
 
 /// Verify FRI queries end-to-end against provided roots and final value.
+///
+/// `queries` are chained queries and `layer_queries` holds one list of
+/// per-layer queries per folded layer (see [`crate::v1::proof::FriParams`]);
+/// a proof uses one kind or the other.
 pub fn fri_verify<T: Transcript>(
     tr: &mut T,
     roots: &[[u8; 32]],
     queries: &[FriQuery],
+    layer_queries: &[Vec<FriLayerQuery>],
     final_value_le: [u8; 8],
 ) -> Result<()> {
     ensure!(!roots.is_empty(), "no FRI roots");
@@ -142,10 +144,10 @@ pub fn fri_verify<T: Transcript>(
     // Re-derive betas (number of folds = roots.len() - 1).
     let betas = params::derive_betas_for_fri(tr, n_layers.saturating_sub(1));
 
-    // Last-layer root must equal hash(final_value).
+    // Last-layer root must equal the leaf of final_value.
     {
         let last = roots[n_layers - 1];
-        let final_hash = hash_field_leaves(&[final_value_le])[0];
+        let final_hash = hash_fri_leaf(&final_value_le);
         ensure!(last == final_hash, "final FRI value mismatch with last root");
     }
 
@@ -164,27 +166,8 @@ pub fn fri_verify<T: Transcript>(
             let (vi_le, path_i, vj_le, path_j) = &q.pairs[l];
 
             // Verify the two Merkle paths against the root of layer l.
-            let leaf_i = hash_field_leaves(&[*vi_le])[0];
-            let leaf_j = hash_field_leaves(&[*vj_le])[0];
-
-            let ok_i = MerkleTree::verify(
-                roots[l],
-                leaf_i,
-                idx,
-                &MerkleProof {
-                    sibs: path_i.clone(),
-                    index: idx,
-                },
-            );
-            let ok_j = MerkleTree::verify(
-                roots[l],
-                leaf_j,
-                j,
-                &MerkleProof {
-                    sibs: path_j.clone(),
-                    index: j,
-                },
-            );
+            let ok_i = verify_fri_open(roots[l], *vi_le, idx, path_i);
+            let ok_j = verify_fri_open(roots[l], *vj_le, j, path_j);
             ensure!(ok_i && ok_j, "FRI Merkle path failed at layer {}", l);
 
             // Fold check against the first value of the next layer's pair (contract).
@@ -218,5 +201,54 @@ pub fn fri_verify<T: Transcript>(
         }
     }
 
+    verify_layer_queries(roots, &betas, layer_queries)
+}
+
+/// Check per-layer queries: both pair paths against layer ℓ, the folded
+/// value's path against layer ℓ+1 (the last root already pins the final
+/// value), and the fold itself.
+fn verify_layer_queries(
+    roots: &[[u8; 32]],
+    betas: &[F1],
+    layer_queries: &[Vec<FriLayerQuery>],
+) -> Result<()> {
+    let n_layers = roots.len();
+    ensure!(
+        layer_queries.is_empty() || layer_queries.len() == n_layers - 1,
+        "per-layer FRI queries cover {} layers, expected {}",
+        layer_queries.len(),
+        n_layers - 1
+    );
+
+    for (l, queries) in layer_queries.iter().enumerate() {
+        let half = 1usize << (n_layers - 2 - l);
+        for q in queries {
+            let idx = q.position;
+            ensure!(idx < 2 * half, "FRI layer {l} position {idx} out of range");
+            let j = idx ^ half;
+            let next = idx % half;
+
+            let (vi_le, path_i, vj_le, path_j) = &q.pair;
+            let (folded_le, folded_path) = &q.next;
+            ensure!(
+                verify_fri_open(roots[l], *vi_le, idx, path_i)
+                    && verify_fri_open(roots[l], *vj_le, j, path_j),
+                "FRI Merkle path failed at layer {l}"
+            );
+            ensure!(
+                verify_fri_open(roots[l + 1], *folded_le, next, folded_path),
+                "FRI Merkle path failed at layer {}",
+                l + 1
+            );
+
+            let vi = F1::from_u64(u64::from_le_bytes(*vi_le));
+            let vj = F1::from_u64(u64::from_le_bytes(*vj_le));
+            let (lower, upper) = if idx < half { (vi, vj) } else { (vj, vi) };
+            ensure!(
+                (lower + betas[l] * upper).to_le_bytes() == *folded_le,
+                "FRI fold mismatch at layer {l}"
+            );
+        }
+    }
     Ok(())
 }
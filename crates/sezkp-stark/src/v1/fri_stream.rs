@@ -6,16 +6,23 @@
 //! - `fold_stream`: a convenience function that consumes an iterator of pairs
 //!   `(y[i], y[i+half])`, applies `y'[i] = y[i] + beta * y[i+half]`, and feeds
 //!   the results to a `StreamingLayerBuilder`.
+//! - `commit_layer`: the root of an in-memory layer, hashed through a bounded
+//!   staging buffer rather than a full leaf array.
 //! - `merkle_path_from_le_chunker`: compute a Merkle path **streaming** from an
 //!   out-of-core layer-0 leaf producer; no arrays are materialized.
+//!   `merkle_paths_from_le_chunker` does the same for many indices, streaming
+//!   each tree level once for all of them.
 //!
 //! Notes
 //! -----
 //! * The Merkle combination rule matches our non-streaming `MerkleTree`
 //!   implementation: when a level has an odd count of nodes, the last node is
-//!   carried up unchanged (odd promotion).
-//! * The layer-0 leaves here are **unlabeled** 8-byte field encodings; this
-//!   matches the back-compat `hash_field_leaves` used for FRI layers.
+//!   carried up unchanged (odd promotion). The layer builder delegates to the
+//!   shared [`sezkp_core::frontier::MerkleFrontier`].
+//! * Leaves and inner nodes are hashed in the FRI domains
+//!   ([`hash_fri_leaf`], [`FRI_NODE_DOMAIN`]), matching
+//!   [`crate::v1::merkle::fri_layer_tree`]; see the hash domains in
+//!   [`crate::v1::merkle`].
 
 #![forbid(unsafe_code)]
 #![deny(rust_2018_idioms)]
@@ -28,25 +35,17 @@
     clippy::expect_used
 )]
 
-use blake3::Hasher;
+use std::collections::BTreeMap;
 
-use crate::v1::field::F1;
+use sezkp_core::frontier::{node_hash_in, MerkleFrontier};
 
-/// Hash a single 8-byte little-endian value into a 32-byte leaf.
-#[inline]
-fn hash_leaf(le: &[u8; 8]) -> [u8; 32] {
-    let mut h = Hasher::new();
-    h.update(le);
-    *h.finalize().as_bytes()
-}
+use crate::v1::field::F1;
+use crate::v1::merkle::{hash_fri_leaf, FRI_NODE_DOMAIN};
 
-/// Hash two 32-byte nodes into their parent (left then right).
+/// Parent of two FRI layer nodes.
 #[inline]
 fn hash_nodes(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
-    let mut h = Hasher::new();
-    h.update(left);
-    h.update(right);
-    *h.finalize().as_bytes()
+    node_hash_in(FRI_NODE_DOMAIN, left, right)
 }
 
 /// Streaming Merkle builder for a single layer.
@@ -55,8 +54,7 @@ fn hash_nodes(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
 pub struct StreamingLayerBuilder {
     expected_len: usize,
     seen: usize,
-    /// One optional node per level; `stack[lvl]` is a node waiting for its right sibling.
-    stack: Vec<Option<[u8; 32]>>,
+    frontier: MerkleFrontier,
 }
 
 impl StreamingLayerBuilder {
@@ -66,7 +64,7 @@ impl StreamingLayerBuilder {
         Self {
             expected_len: layer_len,
             seen: 0,
-            stack: Vec::new(),
+            frontier: MerkleFrontier::with_node_domain(FRI_NODE_DOMAIN),
         }
     }
 
@@ -75,49 +73,21 @@ impl StreamingLayerBuilder {
     pub fn absorb_leaves(&mut self, chunk: &[[u8; 8]]) {
         for le in chunk {
             self.seen += 1;
-            let mut cur = hash_leaf(le);
-            let mut lvl = 0usize;
-            loop {
-                if self.stack.len() <= lvl {
-                    self.stack.push(None);
-                }
-                if let Some(left) = self.stack[lvl].take() {
-                    // Pair available ⇒ combine and propagate upward.
-                    cur = hash_nodes(&left, &cur);
-                    lvl += 1;
-                } else {
-                    // First in its pair at this level; wait for the sibling.
-                    self.stack[lvl] = Some(cur);
-                    break;
-                }
-            }
+            self.frontier.push(hash_fri_leaf(le));
         }
     }
 
-    /// Finalize and return the Merkle root. Panics if absorb count mismatches.
+    /// Finalize and return the Merkle root (all-zero for an empty layer).
+    /// Panics if absorb count mismatches.
     #[must_use]
-    pub fn finalize(mut self) -> [u8; 32] {
+    pub fn finalize(self) -> [u8; 32] {
         assert_eq!(
             self.seen, self.expected_len,
             "StreamingLayerBuilder absorbed {} leaves, expected {}",
             self.seen, self.expected_len
         );
 
-        // Reduce remaining nodes from highest level down, treating the higher
-        // level node as the *left* input and the accumulated hash as the *right*.
-        // This matches odd-promotion semantics, e.g. for 3 leaves:
-        // stack[0]=c, stack[1]=hash(a,b) ⇒ root = hash(hash(a,b), c).
-        let mut cur: Option<[u8; 32]> = None;
-        for opt in self.stack.into_iter().rev() {
-            if let Some(node) = opt {
-                cur = Some(match cur {
-                    None => node,
-                    Some(acc) => hash_nodes(&node, &acc),
-                });
-            }
-        }
-
-        cur.unwrap_or([0u8; 32])
+        self.frontier.root()
     }
 }
 
@@ -125,16 +95,18 @@ impl StreamingLayerBuilder {
 /// feed the folded stream to `out` as layer-(ℓ+1) leaves.
 ///
 /// This helper does **not** create or store any arrays; callers control how
-/// pairs are produced (e.g., via an out-of-core pairing adaptor).
+/// pairs are produced (e.g., via an out-of-core pairing adaptor). Outputs are
+/// staged `1 << buf_log2` at a time (see
+/// [`crate::v1::params::StreamTuning::fri_buf_log2`]).
 pub fn fold_stream(
     beta: F1,
     mut in_pairs: impl Iterator<Item = ([u8; 8], [u8; 8])>,
     out: &mut StreamingLayerBuilder,
+    buf_log2: u8,
 ) {
-    // Small staging buffer to amortize per-call overhead of `absorb_leaves`.
-    // This buffer holds encoded 8-byte outputs; tune 1<<12 (~4K) by default.
-    const BUF_CAP: usize = 1 << 12;
-    let mut buf: Vec<[u8; 8]> = Vec::with_capacity(BUF_CAP);
+    // Staging buffer to amortize per-call overhead of `absorb_leaves`.
+    let buf_cap = 1usize << buf_log2;
+    let mut buf: Vec<[u8; 8]> = Vec::with_capacity(buf_cap);
 
     while let Some((a_le, b_le)) = in_pairs.next() {
         let a = F1::from_u64(u64::from_le_bytes(a_le));
@@ -142,7 +114,7 @@ pub fn fold_stream(
         let folded = (a + beta * b).to_le_bytes();
         buf.push(folded);
 
-        if buf.len() == BUF_CAP {
+        if buf.len() == buf_cap {
             out.absorb_leaves(&buf);
             buf.clear();
         }
@@ -153,6 +125,24 @@ pub fn fold_stream(
     }
 }
 
+/// Merkle root of the layer `vals`, encoding and hashing `1 << buf_log2`
+/// values at a time.
+///
+/// Equal to the root of [`crate::v1::merkle::fri_layer_tree`] over the same
+/// values, without its `O(len)` leaf and node arrays.
+#[must_use]
+pub fn commit_layer(vals: &[F1], buf_log2: u8) -> [u8; 32] {
+    let chunk = 1usize << buf_log2;
+    let mut out = StreamingLayerBuilder::new(vals.len());
+    let mut buf: Vec<[u8; 8]> = Vec::with_capacity(chunk.min(vals.len()));
+    for part in vals.chunks(chunk) {
+        buf.clear();
+        buf.extend(part.iter().map(|v| v.to_le_bytes()));
+        out.absorb_leaves(&buf);
+    }
+    out.finalize()
+}
+
 /* -------------------------------------------------------------------------- */
 /*                     Streaming Merkle path (layer-0)                        */
 /* -------------------------------------------------------------------------- */
@@ -181,7 +171,7 @@ fn emit_level_nodes_from_le_chunker(
         let mut pos = 0usize;
         chunker(&mut |chunk| {
             for le in chunk {
-                let h = hash_leaf(le);
+                let h = hash_fri_leaf(le);
                 on_node(pos, h, Some(*le));
                 pos += 1;
             }
@@ -197,7 +187,7 @@ fn emit_level_nodes_from_le_chunker(
     // a node at `target_level`, emit it.
     chunker(&mut |chunk| {
         for le in chunk {
-            let mut cur = hash_leaf(le);
+            let mut cur = hash_fri_leaf(le);
             let mut lvl = 0usize;
             loop {
                 if stack.len() <= lvl {
@@ -310,3 +300,56 @@ pub fn merkle_path_from_le_chunker(
 
     (val_le.expect("leaf value present"), path)
 }
+
+/// [`merkle_path_from_le_chunker`] for every index in `idxs`, in order.
+///
+/// Each tree level is streamed once for all indices rather than once per
+/// index, so opening `k` positions costs as many passes over `chunker` as
+/// opening one; memory stays `O(k)` per level.
+///
+/// # Panics
+/// If the layer is empty or an index is out of bounds.
+#[must_use]
+pub fn merkle_paths_from_le_chunker(
+    layer_len: usize,
+    mut chunker: impl FnMut(&mut dyn FnMut(&[[u8; 8]])),
+    idxs: &[usize],
+) -> Vec<([u8; 8], Vec<[u8; 32]>)> {
+    assert!(layer_len > 0, "empty layer not supported");
+    assert!(
+        idxs.iter().all(|&i| i < layer_len),
+        "index out of bounds for layer"
+    );
+
+    let mut cur: Vec<usize> = idxs.to_vec();
+    let mut values: BTreeMap<usize, [u8; 8]> = idxs.iter().map(|&i| (i, [0u8; 8])).collect();
+    let mut paths: Vec<Vec<[u8; 32]>> = vec![Vec::new(); idxs.len()];
+    let mut cur_len = layer_len;
+    let mut level = 0usize;
+
+    while level == 0 || cur_len > 1 {
+        // The node each index needs at this level: its sibling, or itself
+        // when odd promotion leaves it without one.
+        let node = |i: usize| if (i ^ 1) < cur_len { i ^ 1 } else { i };
+        let mut hashes: BTreeMap<usize, [u8; 32]> =
+            cur.iter().map(|&i| (node(i), [0u8; 32])).collect();
+        emit_level_nodes_from_le_chunker(layer_len, level, &mut chunker, |pos, hash, leaf| {
+            if let Some(h) = hashes.get_mut(&pos) {
+                *h = hash;
+            }
+            if let (Some(le), Some(v)) = (leaf, values.get_mut(&pos)) {
+                *v = le;
+            }
+        });
+        if cur_len > 1 {
+            for (path, i) in paths.iter_mut().zip(&mut cur) {
+                path.push(hashes[&node(*i)]);
+                *i >>= 1;
+            }
+        }
+        cur_len = cur_len.div_ceil(2);
+        level += 1;
+    }
+
+    idxs.iter().map(|i| values[i]).zip(paths).collect()
+}
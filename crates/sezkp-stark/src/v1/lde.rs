@@ -22,7 +22,8 @@
 
 use crate::v1::field::F1;
 use sezkp_ffts::{
-    coset::evaluate_on_coset_pow2, goldilocks_primitive_root_2exp, ntt::interpolate_from_evals,
+    batch_inverse, coset::evaluate_on_coset_pow2, goldilocks_primitive_root_2exp,
+    ntt::interpolate_from_evals,
 };
 
 /// Drives: base-domain -> (interpolate) -> LDE on coset -> DEEP divide,
@@ -66,32 +67,38 @@ pub fn deep_coset_lde_stream<F: FnMut(&[[u8; 8]])>(
 
     /* ---------------- Evaluate on multiplicative coset --------------------- */
     // y[i] = C(x_i) with x_i = shift * ω^i, |domain| = lde_n
-    let mut y = evaluate_on_coset_pow2(&coeffs, lde_k_log2, shift);
+    let y = evaluate_on_coset_pow2(&coeffs, lde_k_log2, shift);
 
     /* ---------------- Apply DEEP: divide by (x_i - z) ---------------------- */
     // Emit results in caller-specified chunk sizes without exposing `F1`.
+    // Denominators are inverted per chunk with Montgomery's trick, so the
+    // whole pass costs one field inversion per chunk instead of one per row.
     let chunk = 1usize << out_chunk_log2;
     let mut buf: Vec<[u8; 8]> = Vec::with_capacity(chunk);
+    let mut denoms: Vec<F1> = Vec::with_capacity(chunk);
 
-    let one = F1::from_u64(1);
     let w = goldilocks_primitive_root_2exp(lde_k_log2 as u32);
-    let mut w_pow = one; // ω^0
+    let mut x = shift; // shift · ω^0
 
-    for i in 0..lde_n {
-        let x = shift * w_pow;
-        let denom = x - z;
-        debug_assert!(denom != F1::from_u64(0), "OOD point z must not lie on the coset");
-        let out = y[i] * denom.inv();
+    let mut start = 0usize;
+    while start < lde_n {
+        let end = (start + chunk).min(lde_n);
 
-        buf.push(out.to_le_bytes());
-        if buf.len() == buf.capacity() {
-            consume(&buf);
-            buf.clear();
+        denoms.clear();
+        for _ in start..end {
+            let denom = x - z;
+            debug_assert!(denom != F1::from_u64(0), "OOD point z must not lie on the coset");
+            denoms.push(denom);
+            x *= w;
         }
+        batch_inverse(&mut denoms);
 
-        w_pow *= w;
-    }
-    if !buf.is_empty() {
+        buf.clear();
+        for (yi, d_inv) in y[start..end].iter().zip(&denoms) {
+            buf.push((*yi * *d_inv).to_le_bytes());
+        }
         consume(&buf);
+
+        start = end;
     }
 }
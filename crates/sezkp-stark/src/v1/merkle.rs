@@ -10,6 +10,25 @@
 //!    in the streaming prover path; see A2 in the roadmap).
 //!
 //! Verifier code (Merkle path verification) remains unchanged.
+//!
+//! ## Hash domains
+//! Every tree of a v1 proof is BLAKE3 with odd promotion (a lone last node
+//! is carried up unchanged). Leaves always carry a domain prefix, so no two
+//! kinds of leaf can share a preimage:
+//!
+//! | tree                  | leaf                                                      | inner node                    |
+//! |-----------------------|-----------------------------------------------------------|-------------------------------|
+//! | column (row values)   | `DS_COL_LEAF ‖ len(label) ‖ label ‖ value`                | `left ‖ right`                |
+//! | column (block values) | `DS_COL_BLOCK_LEAF ‖ len(label) ‖ label ‖ lo ‖ hi ‖ value` | `left ‖ right`                |
+//! | FRI layer             | `DS_FRI_LEAF ‖ value`                                     | `DS_FRI_NODE ‖ left ‖ right`  |
+//!
+//! `len(label)` is a `u32`, `lo`/`hi` are `u64` and `value` is the 8-byte
+//! field encoding, all little-endian. Column trees are chunked (an inner tree
+//! per chunk, an outer tree over chunk roots) with the same plain node hash
+//! as the manifest (`sezkp_core::frontier::node_hash`); FRI layer trees use
+//! their own node domain, so a FRI root or path never verifies as a column
+//! or manifest one. The last FRI layer holds one value and its root is that
+//! value's leaf. The `DS_FRI_*` strings carry the protocol version.
 
 #![forbid(unsafe_code)]
 #![deny(rust_2018_idioms)]
@@ -22,6 +41,7 @@
 )]
 
 use blake3::Hasher;
+use sezkp_core::frontier::node_hash_in;
 
 use crate::v1::params;
 
@@ -31,7 +51,8 @@ use crate::v1::params;
 #[derive(Clone, Debug)]
 pub struct MerkleTree {
     leaves: Vec<[u8; 32]>,
-    nodes: Vec<[u8; 32]>, // heap-ordered, nodes.last() = root
+    nodes: Vec<[u8; 32]>,  // heap-ordered, nodes.last() = root
+    domain: &'static [u8], // inner-node prefix, see `node_hash_in`
 }
 
 /// Merkle proof: siblings bottom→top plus the leaf index.
@@ -44,6 +65,12 @@ pub struct MerkleProof {
 impl MerkleTree {
     #[must_use]
     pub fn from_leaves(leaves_raw: &[[u8; 32]]) -> Self {
+        Self::from_leaves_in(b"", leaves_raw)
+    }
+
+    /// Tree whose inner nodes hash with the node domain `domain`.
+    #[must_use]
+    pub fn from_leaves_in(domain: &'static [u8], leaves_raw: &[[u8; 32]]) -> Self {
         let mut leaves = leaves_raw.to_vec();
         if leaves.is_empty() {
             leaves.push([0u8; 32]);
@@ -55,10 +82,7 @@ impl MerkleTree {
             let mut next = Vec::<[u8; 32]>::with_capacity((lvl.len() + 1) / 2);
             for i in (0..lvl.len()).step_by(2) {
                 if i + 1 < lvl.len() {
-                    let mut h = Hasher::new();
-                    h.update(&lvl[i]);
-                    h.update(&lvl[i + 1]);
-                    next.push(*h.finalize().as_bytes());
+                    next.push(node_hash_in(domain, &lvl[i], &lvl[i + 1]));
                 } else {
                     // odd promotion: carry the last node up unchanged
                     next.push(lvl[i]);
@@ -67,7 +91,11 @@ impl MerkleTree {
             nodes.extend(next.iter().copied());
             lvl = next;
         }
-        Self { leaves, nodes }
+        Self {
+            leaves,
+            nodes,
+            domain,
+        }
     }
 
     #[inline]
@@ -93,10 +121,7 @@ impl MerkleTree {
             let mut next = Vec::<[u8; 32]>::with_capacity((lvl.len() + 1) / 2);
             for i in (0..lvl.len()).step_by(2) {
                 if i + 1 < lvl.len() {
-                    let mut h = Hasher::new();
-                    h.update(&lvl[i]);
-                    h.update(&lvl[i + 1]);
-                    next.push(*h.finalize().as_bytes());
+                    next.push(node_hash_in(self.domain, &lvl[i], &lvl[i + 1]));
                 } else {
                     next.push(lvl[i]);
                 }
@@ -108,18 +133,26 @@ impl MerkleTree {
     }
 
     #[must_use]
-    pub fn verify(root: [u8; 32], leaf: [u8; 32], mut idx: usize, proof: &MerkleProof) -> bool {
+    pub fn verify(root: [u8; 32], leaf: [u8; 32], idx: usize, proof: &MerkleProof) -> bool {
+        Self::verify_in(b"", root, leaf, idx, proof)
+    }
+
+    /// [`MerkleTree::verify`] for a tree built with [`MerkleTree::from_leaves_in`].
+    #[must_use]
+    pub fn verify_in(
+        domain: &[u8],
+        root: [u8; 32],
+        leaf: [u8; 32],
+        mut idx: usize,
+        proof: &MerkleProof,
+    ) -> bool {
         let mut cur = leaf;
         for s in &proof.sibs {
-            let mut h = Hasher::new();
-            if idx & 1 == 0 {
-                h.update(&cur);
-                h.update(s);
+            cur = if idx & 1 == 0 {
+                node_hash_in(domain, &cur, s)
             } else {
-                h.update(s);
-                h.update(&cur);
-            }
-            cur = *h.finalize().as_bytes();
+                node_hash_in(domain, s, &cur)
+            };
             idx >>= 1;
         }
         cur == root
@@ -145,7 +178,8 @@ pub fn hash_field_leaves_labeled(le_elems: &[[u8; 8]], col_label: &str) -> Vec<[
         .collect()
 }
 
-/// Backwards-compatible helper (kept for FRI/demo uses).
+/// Unlabeled leaves `BLAKE3(value)`; no v1 proof tree uses them any more
+/// (FRI layers hash with [`hash_fri_leaves`]). Kept for demos and fuzzing.
 #[must_use]
 pub fn hash_field_leaves(le_elems: &[[u8; 8]]) -> Vec<[u8; 32]> {
     le_elems
@@ -158,6 +192,49 @@ pub fn hash_field_leaves(le_elems: &[[u8; 8]]) -> Vec<[u8; 32]> {
         .collect()
 }
 
+/* --------------------------- FRI layer commitments -------------------------- */
+
+/// Node domain of FRI layer trees.
+pub const FRI_NODE_DOMAIN: &[u8] = params::DS_FRI_NODE.as_bytes();
+
+/// Leaf of a FRI layer value: `BLAKE3(DS_FRI_LEAF || value)`.
+#[inline]
+#[must_use]
+pub fn hash_fri_leaf(le: &[u8; 8]) -> [u8; 32] {
+    let mut h = Hasher::new();
+    h.update(params::DS_FRI_LEAF.as_bytes());
+    h.update(le);
+    *h.finalize().as_bytes()
+}
+
+/// [`hash_fri_leaf`] over a whole layer.
+#[must_use]
+pub fn hash_fri_leaves(le_elems: &[[u8; 8]]) -> Vec<[u8; 32]> {
+    le_elems.iter().map(hash_fri_leaf).collect()
+}
+
+/// In-memory tree over a FRI layer's values (leaves and nodes in the FRI
+/// domains).
+#[must_use]
+pub fn fri_layer_tree(le_elems: &[[u8; 8]]) -> MerkleTree {
+    MerkleTree::from_leaves_in(FRI_NODE_DOMAIN, &hash_fri_leaves(le_elems))
+}
+
+/// Verify that `value_le` sits at `idx` under the FRI layer root `root`.
+#[must_use]
+pub fn verify_fri_open(root: [u8; 32], value_le: [u8; 8], idx: usize, path: &[[u8; 32]]) -> bool {
+    MerkleTree::verify_in(
+        FRI_NODE_DOMAIN,
+        root,
+        hash_fri_leaf(&value_le),
+        idx,
+        &MerkleProof {
+            sibs: path.to_vec(),
+            index: idx,
+        },
+    )
+}
+
 /* ------------------------ Chunked column commitments ------------------------ */
 
 /// Chunked column commitment: Merkle-of-chunks (each chunk is a Merkle tree of
@@ -279,6 +356,61 @@ pub fn verify_chunked_open(
     )
 }
 
+/* ------------------------ Per-block column commitments ---------------------- */
+
+/// Leaf of a column committed per block: `value_le` holds on every row in
+/// `row_lo..=row_hi`, with the same label separation as row leaves.
+#[must_use]
+pub fn hash_block_leaf(
+    col_label: &str,
+    row_lo: usize,
+    row_hi: usize,
+    value_le: [u8; 8],
+) -> [u8; 32] {
+    let mut h = Hasher::new();
+    // Domain sep: DS_COL_BLOCK_LEAF || len(label) || label || lo || hi || value
+    h.update(params::DS_COL_BLOCK_LEAF.as_bytes());
+    let llen: u32 = col_label.len() as u32;
+    h.update(&llen.to_le_bytes());
+    h.update(col_label.as_bytes());
+    h.update(&(row_lo as u64).to_le_bytes());
+    h.update(&(row_hi as u64).to_le_bytes());
+    h.update(&value_le);
+    *h.finalize().as_bytes()
+}
+
+/// Tree over the block leaves of one column, padded with zero leaves to a
+/// power of two so that every block's path has the same length.
+#[must_use]
+pub fn block_column_tree(block_leaves: &[[u8; 32]]) -> MerkleTree {
+    let mut leaves = block_leaves.to_vec();
+    leaves.resize(leaves.len().next_power_of_two(), [0u8; 32]);
+    MerkleTree::from_leaves(&leaves)
+}
+
+/// Verify a block opening against a per-block column root and label.
+#[must_use]
+pub fn verify_block_open(
+    root: [u8; 32],
+    col_label: &str,
+    value_le: [u8; 8],
+    row_lo: usize,
+    row_hi: usize,
+    block_idx: usize,
+    path: &[[u8; 32]],
+) -> bool {
+    let leaf = hash_block_leaf(col_label, row_lo, row_hi, value_le);
+    MerkleTree::verify(
+        root,
+        leaf,
+        block_idx,
+        &MerkleProof {
+            sibs: path.to_vec(),
+            index: block_idx,
+        },
+    )
+}
+
 /* ------------------------- Streaming column commitments --------------------- */
 
 /// Minimal metadata retained for a streaming column commitment.
@@ -4,10 +4,26 @@
 //! answering openings by recomputing only the required chunk plus the outer
 //! Merkle path over chunk-roots.
 //!
+//! Columns whose value is constant within every block (in practice the
+//! window lengths, head offsets and input-head endpoints, but whichever the
+//! values make so) are committed with one leaf per block instead: its row
+//! range and value. They cost one hash per block to commit and open with a
+//! single path over the blocks. A column is held per block until a block
+//! shows two values; its rows so far are then replayed into chunk leaves
+//! from the recorded runs, so the layout costs no second pass.
+//!
 //! Memory profile
-//! - Building roots: O(chunk) per label (pending leaves) and O(1) otherwise.
-//! - Opening one (label, row): O(chunk) to rebuild that chunk; we cache all
-//!   chunk-roots per label for reuse across multiple opens.
+//! - Building roots: O(chunk) per label (pending leaves), or one run per
+//!   block while the label is held per block;
+//!   [`ColumnRootBuilder`] does the same from a block stream.
+//! - Opening one (label, row): O(chunk) to rebuild that chunk from the rows
+//!   of the last chunk touched, which are kept. The chunk roots of every
+//!   label are computed in one pass over the trace on the first open and
+//!   cached, so the cost of opening does not grow with the column count
+//!   (`3 + 7τ` labels) beyond the hashing itself. The cache keeps every
+//!   label's outer tree unless bounded with
+//!   [`OnDemandOpenings::with_outer_cache`]; an evicted tree costs another
+//!   pass when its label is opened again.
 
 #![forbid(unsafe_code)]
 #![deny(rust_2018_idioms)]
@@ -21,12 +37,16 @@
 
 use std::collections::HashMap;
 
+use anyhow::{ensure, Result};
+use sezkp_core::lru::Lru;
 use sezkp_core::BlockSummary;
 
 use crate::v1::{
+    columns::all_labels,
+    columns_stream::BlockConsts,
     field::F1,
-    merkle::{hash_field_leaves_labeled, MerkleTree},
-    proof::{ColumnRoot, Opening},
+    merkle::{block_column_tree, hash_block_leaf, hash_field_leaves_labeled, MerkleTree},
+    proof::{BlockOpening, ColumnOpening, ColumnRoot, Opening},
 };
 
 /// Number of rows across all blocks.
@@ -56,6 +76,9 @@ enum LabelKind {
     InputMv,
     IsFirst,
     IsLast,
+    InHead,
+    InHeadIn,
+    InHeadOut,
     Tape { kind: TapeColKind, r: usize },
 }
 
@@ -64,6 +87,9 @@ fn parse_label(label: &str, tau: usize) -> LabelKind {
         "input_mv" => LabelKind::InputMv,
         "is_first" => LabelKind::IsFirst,
         "is_last" => LabelKind::IsLast,
+        "in_head" => LabelKind::InHead,
+        "in_head_in" => LabelKind::InHeadIn,
+        "in_head_out" => LabelKind::InHeadOut,
         _ => {
             let (name, idx_str) = label
                 .rsplit_once('_')
@@ -85,36 +111,6 @@ fn parse_label(label: &str, tau: usize) -> LabelKind {
     }
 }
 
-/// Public label order (must match the verifier / transcript binding).
-fn all_labels(tau: usize) -> Vec<String> {
-    let mut out = Vec::<String>::new();
-    out.push("input_mv".into());
-    out.push("is_first".into());
-    out.push("is_last".into());
-    for r in 0..tau {
-        out.push(format!("mv_{r}"));
-    }
-    for r in 0..tau {
-        out.push(format!("wflag_{r}"));
-    }
-    for r in 0..tau {
-        out.push(format!("wsym_{r}"));
-    }
-    for r in 0..tau {
-        out.push(format!("head_{r}"));
-    }
-    for r in 0..tau {
-        out.push(format!("winlen_{r}"));
-    }
-    for r in 0..tau {
-        out.push(format!("in_off_{r}"));
-    }
-    for r in 0..tau {
-        out.push(format!("out_off_{r}"));
-    }
-    out
-}
-
 /* ----------------------------- Small helpers ------------------------------- */
 
 #[inline]
@@ -134,6 +130,9 @@ struct RowSnapshot {
     input_mv: [u8; 8],
     is_first: [u8; 8],
     is_last: [u8; 8],
+    in_head: [u8; 8],
+    in_head_in: [u8; 8],
+    in_head_out: [u8; 8],
     mv: Vec<[u8; 8]>,
     wflag: Vec<[u8; 8]>,
     wsym: Vec<[u8; 8]>,
@@ -149,6 +148,9 @@ impl RowSnapshot {
             input_mv: [0; 8],
             is_first: [0; 8],
             is_last: [0; 8],
+            in_head: [0; 8],
+            in_head_in: [0; 8],
+            in_head_out: [0; 8],
             mv: vec![[0; 8]; tau],
             wflag: vec![[0; 8]; tau],
             wsym: vec![[0; 8]; tau],
@@ -164,6 +166,9 @@ impl RowSnapshot {
             LabelKind::InputMv => self.input_mv,
             LabelKind::IsFirst => self.is_first,
             LabelKind::IsLast => self.is_last,
+            LabelKind::InHead => self.in_head,
+            LabelKind::InHeadIn => self.in_head_in,
+            LabelKind::InHeadOut => self.in_head_out,
             LabelKind::Tape { kind, r } => match kind {
                 TapeColKind::Mv => self.mv[*r],
                 TapeColKind::WFlag => self.wflag[*r],
@@ -186,8 +191,9 @@ struct RowIter<'a> {
     row_in_blk: usize,
     blk_len: usize,
     // per-block caches
-    wlen: Vec<u64>,
+    consts: BlockConsts,
     cur_heads: Vec<i64>,
+    cur_in: i64,
 }
 
 impl<'a> RowIter<'a> {
@@ -199,25 +205,40 @@ impl<'a> RowIter<'a> {
             blk_idx: 0,
             row_in_blk: 0,
             blk_len: 0,
-            wlen: vec![0; tau],
+            consts: BlockConsts::new(tau),
             cur_heads: vec![0; tau],
+            cur_in: 0,
         };
         it.enter_block();
         it
     }
 
+    /// Iterator positioned at global row `row`: whole blocks are skipped
+    /// without reconstructing their rows.
+    fn starting_at(blocks: &'a [BlockSummary], row: usize) -> Self {
+        let mut it = Self::new(blocks);
+        let mut skip = row;
+        while it.blk_idx < blocks.len() && skip >= it.blk_len {
+            skip -= it.blk_len;
+            it.blk_idx += 1;
+            it.enter_block();
+        }
+        for _ in 0..skip {
+            let _ = it.next();
+        }
+        it
+    }
+
     fn enter_block(&mut self) {
         if let Some(b) = self.blocks.get(self.blk_idx) {
             self.blk_len = (b.step_hi - b.step_lo + 1) as usize;
 
-            // window lengths are constant within a block
-            for r in 0..self.tau {
-                let left = b.windows[r].left;
-                let right = b.windows[r].right;
-                let wl = (right - left).abs() as u64 + 1;
-                self.wlen[r] = wl;
-                self.cur_heads[r] = 0;
+            // window lengths and offsets are constant within a block
+            self.consts.load(b);
+            for (h, &o) in self.cur_heads.iter_mut().zip(&b.head_in_offsets) {
+                *h = i64::from(o);
             }
+            self.cur_in = b.in_head_in;
         } else {
             self.blk_len = 0;
         }
@@ -247,6 +268,12 @@ impl<'a> Iterator for RowIter<'a> {
         let step = &b.movement_log.steps[self.row_in_blk];
         row.input_mv = f_le_i64(step.input_mv as i64);
 
+        // absolute input head (post-move) and the block's declared endpoints
+        self.cur_in += i64::from(step.input_mv);
+        row.in_head = f_le_i64(self.cur_in);
+        row.in_head_in = self.consts.in_head_in;
+        row.in_head_out = self.consts.in_head_out;
+
         for r in 0..self.tau {
             let op = &step.tapes[r];
 
@@ -260,18 +287,235 @@ impl<'a> Iterator for RowIter<'a> {
             // move-then-write: head is post-move, relative to the left bound
             self.cur_heads[r] += op.mv as i64;
             row.head[r] = f_le_i64(self.cur_heads[r]);
-
-            // block constants
-            row.winlen[r] = f_le_u64(self.wlen[r]);
-            row.in_off[r] = f_le_u64(b.head_in_offsets[r] as u64);
-            row.out_off[r] = f_le_u64(b.head_out_offsets[r] as u64);
         }
 
+        // block constants
+        row.winlen.copy_from_slice(&self.consts.win_len);
+        row.in_off.copy_from_slice(&self.consts.in_off);
+        row.out_off.copy_from_slice(&self.consts.out_off);
+
         self.row_in_blk += 1;
         Some(row)
     }
 }
 
+/* ------------------------- Streamed column roots --------------------------- */
+
+/// Commitment state of one column in a [`ColumnRootBuilder`].
+enum Layout {
+    /// Constant within every block so far: one `(row_lo, row_hi, value)`
+    /// run per block, the last one still growing.
+    PerBlock(Vec<(usize, usize, [u8; 8])>),
+    /// Leaves of the open chunk and roots of the closed ones.
+    Chunked {
+        pending: Vec<[u8; 32]>,
+        closed: Vec<[u8; 32]>,
+    },
+}
+
+impl Layout {
+    /// Chunked leaves for the rows `runs` cover, once a block of the column
+    /// shows a second value.
+    fn chunked(label: &str, runs: &[(usize, usize, [u8; 8])], chunk_size: usize) -> Self {
+        let mut col = Self::Chunked {
+            pending: Vec::new(),
+            closed: Vec::new(),
+        };
+        for &(lo, hi, v) in runs {
+            let leaf = hash_field_leaves_labeled(&[v], label)[0];
+            for _ in lo..=hi {
+                col.push_leaf(leaf, chunk_size);
+            }
+        }
+        col
+    }
+
+    /// Append a row leaf to a chunked column, closing full chunks.
+    fn push_leaf(&mut self, leaf: [u8; 32], chunk_size: usize) {
+        if let Self::Chunked { pending, closed } = self {
+            pending.push(leaf);
+            if pending.len() == chunk_size {
+                closed.push(MerkleTree::from_leaves(pending).root());
+                pending.clear();
+            }
+        }
+    }
+
+    /// Whether the column is committed per block, and its outer tree (over
+    /// its block leaves, or its chunk roots).
+    fn finish(self, label: &str) -> (bool, MerkleTree) {
+        match self {
+            Self::PerBlock(runs) => {
+                let leaves: Vec<_> = runs
+                    .iter()
+                    .map(|&(lo, hi, v)| hash_block_leaf(label, lo, hi, v))
+                    .collect();
+                (true, block_column_tree(&leaves))
+            }
+            Self::Chunked {
+                pending,
+                mut closed,
+            } => {
+                if !pending.is_empty() {
+                    closed.push(MerkleTree::from_leaves(&pending).root());
+                }
+                (false, MerkleTree::from_leaves(&closed))
+            }
+        }
+    }
+}
+
+/// Incremental column-root builder: feed blocks in trace order, read the
+/// public roots at the end.
+///
+/// This is the hashing behind [`OnDemandOpenings::build_roots`], exposed so a
+/// verifier can recompute the roots from a block stream with only one block
+/// and one pending chunk per label in memory (plus one run per block for
+/// columns still constant within every block, which add one leaf per block
+/// instead of one per row).
+pub struct ColumnRootBuilder {
+    tau: usize,
+    chunk_size: usize,
+    n_rows: usize,
+    // First row of the block being absorbed.
+    block_lo: usize,
+    labels: Vec<(String, LabelKind)>,
+    cols: Vec<Layout>,
+}
+
+impl ColumnRootBuilder {
+    /// Builder for `tau` tapes and chunks of `1 << chunk_log2` rows.
+    #[must_use]
+    pub fn new(tau: usize, chunk_log2: usize) -> Self {
+        Self::with_input_head(tau, chunk_log2, false)
+    }
+
+    /// [`Self::new`], also committing the input-head columns if `input_head`
+    /// is set (proofs that declare an input length).
+    #[must_use]
+    pub fn with_input_head(tau: usize, chunk_log2: usize, input_head: bool) -> Self {
+        let chunk_size = 1usize << chunk_log2;
+        let labels: Vec<_> = all_labels(tau, input_head)
+            .into_iter()
+            .map(|l| {
+                let kind = parse_label(&l, tau);
+                (l, kind)
+            })
+            .collect();
+        Self {
+            tau,
+            chunk_size,
+            n_rows: 0,
+            block_lo: 0,
+            // Every column starts out per block; chunk buffers are allocated
+            // only once a column needs them.
+            cols: labels
+                .iter()
+                .map(|_| Layout::PerBlock(Vec::new()))
+                .collect(),
+            labels,
+        }
+    }
+
+    /// Rows absorbed so far.
+    #[must_use]
+    pub const fn n_rows(&self) -> usize {
+        self.n_rows
+    }
+
+    /// Absorb the rows of the next block.
+    ///
+    /// # Errors
+    /// Fails if the block's shape does not match `tau` or its own step range
+    /// (blocks may come from an untrusted stream).
+    pub fn push_block(&mut self, b: &BlockSummary) -> Result<()> {
+        let tau = self.tau;
+        ensure!(
+            b.windows.len() == tau,
+            "block {}: {} windows, expected tau={tau}",
+            b.block_id,
+            b.windows.len()
+        );
+        ensure!(
+            b.head_in_offsets.len() >= tau && b.head_out_offsets.len() >= tau,
+            "block {}: missing head offsets",
+            b.block_id
+        );
+        ensure!(
+            b.step_hi >= b.step_lo
+                && b.step_hi - b.step_lo + 1 == b.movement_log.steps.len() as u64,
+            "block {}: step range [{}, {}] does not match {} logged steps",
+            b.block_id,
+            b.step_lo,
+            b.step_hi,
+            b.movement_log.steps.len()
+        );
+        ensure!(
+            b.movement_log.steps.iter().all(|s| s.tapes.len() >= tau),
+            "block {}: step with fewer than tau={tau} tapes",
+            b.block_id
+        );
+        self.absorb(b);
+        Ok(())
+    }
+
+    /// Absorb a block already known to be well-formed (at least one row).
+    fn absorb(&mut self, b: &BlockSummary) {
+        self.block_lo = self.n_rows;
+        for row in RowIter::new(std::slice::from_ref(b)) {
+            self.push_row(&row);
+        }
+    }
+
+    fn push_row(&mut self, row: &RowSnapshot) {
+        let i = self.n_rows;
+        let first = i == self.block_lo;
+        for ((label, kind), col) in self.labels.iter().zip(&mut self.cols) {
+            let v = row.get_for_label(kind);
+            if let Layout::PerBlock(runs) = col {
+                if first {
+                    runs.push((i, i, v));
+                    continue;
+                }
+                if let Some(run) = runs.last_mut().filter(|run| run.2 == v) {
+                    run.1 = i;
+                    continue;
+                }
+                // A second value within the block: replay the rows so far.
+                *col = Layout::chunked(label, runs, self.chunk_size);
+            }
+            col.push_leaf(hash_field_leaves_labeled(&[v], label)[0], self.chunk_size);
+        }
+        self.n_rows += 1;
+    }
+
+    /// Flush partial chunks and return the outer roots in canonical order.
+    #[must_use]
+    pub fn finish(self) -> Vec<ColumnRoot> {
+        self.finish_trees()
+            .into_iter()
+            .map(|(label, (per_block, outer))| ColumnRoot {
+                label,
+                root: outer.root(),
+                per_block,
+            })
+            .collect()
+    }
+
+    /// Flush partial chunks and return each label's layout and outer tree,
+    /// in canonical order.
+    fn finish_trees(self) -> Vec<(String, (bool, MerkleTree))> {
+        self.labels
+            .into_iter()
+            .zip(self.cols)
+            .map(|((label, _), col)| {
+                let tree = col.finish(&label);
+                (label, tree)
+            })
+            .collect()
+    }
+}
+
 /* --------------------------- On-demand openings ---------------------------- */
 
 /// On-demand openings over streamed column commitments.
@@ -281,8 +525,15 @@ pub struct OnDemandOpenings<'a> {
     n_rows: usize,
     chunk_log2: usize,
     chunk_size: usize,
-    // Cache per column label: (chunk_roots, outer_tree)
-    outer_cache: HashMap<String, (Vec<[u8; 32]>, MerkleTree)>,
+    input_head: bool,
+    // First row of every block.
+    block_starts: Vec<usize>,
+    // Layout (per block or not) and outer tree over the block leaves or
+    // chunk roots, per column label (filled in one pass, least recently
+    // opened labels evicted first).
+    outer_cache: Lru<String, (bool, MerkleTree)>,
+    // Rows of the last chunk opened, by chunk index.
+    chunk_rows: Option<(usize, Vec<RowSnapshot>)>,
 }
 
 impl<'a> OnDemandOpenings<'a> {
@@ -291,134 +542,76 @@ impl<'a> OnDemandOpenings<'a> {
     pub fn new(blocks: &'a [BlockSummary], chunk_log2: usize) -> Self {
         let tau = blocks.first().map(|b| b.windows.len()).unwrap_or(0);
         let n_rows = total_rows(blocks);
+        let block_starts = blocks
+            .iter()
+            .scan(0usize, |next, b| {
+                let start = *next;
+                *next += (b.step_hi - b.step_lo + 1) as usize;
+                Some(start)
+            })
+            .collect();
         Self {
             blocks,
             tau,
             n_rows,
             chunk_log2,
             chunk_size: 1usize << chunk_log2,
-            outer_cache: HashMap::new(),
+            input_head: false,
+            block_starts,
+            outer_cache: Lru::new(usize::MAX),
+            chunk_rows: None,
         }
     }
 
-    /// Build public column roots in canonical order using O(chunk) memory.
+    /// Also commit the input-head columns in [`Self::build_roots`].
     #[must_use]
-    pub fn build_roots(&self) -> Vec<ColumnRoot> {
-        let labels = all_labels(self.tau);
-
-        // One pending chunk per label.
-        let mut pending: Vec<Vec<[u8; 32]>> = labels
-            .iter()
-            .map(|_| Vec::with_capacity(self.chunk_size))
-            .collect();
-        let mut chunk_roots_per_label: Vec<Vec<[u8; 32]>> =
-            labels.iter().map(|_| Vec::new()).collect();
-
-        for row in RowIter::new(self.blocks) {
-            // Scalars
-            pending[0].push(hash_field_leaves_labeled(&[row.input_mv], "input_mv")[0]);
-            pending[1].push(hash_field_leaves_labeled(&[row.is_first], "is_first")[0]);
-            pending[2].push(hash_field_leaves_labeled(&[row.is_last], "is_last")[0]);
-
-            let mut idx = 3usize;
-
-            // mv_r
-            for r in 0..self.tau {
-                pending[idx + r].push(hash_field_leaves_labeled(&[row.mv[r]], &format!("mv_{r}"))[0]);
-            }
-            idx += self.tau;
-
-            // wflag_r
-            for r in 0..self.tau {
-                pending[idx + r].push(
-                    hash_field_leaves_labeled(&[row.wflag[r]], &format!("wflag_{r}"))[0],
-                );
-            }
-            idx += self.tau;
-
-            // wsym_r
-            for r in 0..self.tau {
-                pending[idx + r]
-                    .push(hash_field_leaves_labeled(&[row.wsym[r]], &format!("wsym_{r}"))[0]);
-            }
-            idx += self.tau;
-
-            // head_r
-            for r in 0..self.tau {
-                pending[idx + r]
-                    .push(hash_field_leaves_labeled(&[row.head[r]], &format!("head_{r}"))[0]);
-            }
-            idx += self.tau;
-
-            // winlen_r
-            for r in 0..self.tau {
-                pending[idx + r].push(
-                    hash_field_leaves_labeled(&[row.winlen[r]], &format!("winlen_{r}"))[0],
-                );
-            }
-            idx += self.tau;
-
-            // in_off_r
-            for r in 0..self.tau {
-                pending[idx + r]
-                    .push(hash_field_leaves_labeled(&[row.in_off[r]], &format!("in_off_{r}"))[0]);
-            }
-            idx += self.tau;
-
-            // out_off_r
-            for r in 0..self.tau {
-                pending[idx + r]
-                    .push(hash_field_leaves_labeled(&[row.out_off[r]], &format!("out_off_{r}"))[0]);
-            }
+    pub const fn with_input_head(mut self, input_head: bool) -> Self {
+        self.input_head = input_head;
+        self
+    }
 
-            // Close full chunks.
-            for (lix, buf) in pending.iter_mut().enumerate() {
-                if buf.len() == self.chunk_size {
-                    let mt = MerkleTree::from_leaves(buf);
-                    chunk_roots_per_label[lix].push(mt.root());
-                    buf.clear();
-                }
-            }
-        }
+    /// Keep the outer trees of at most `cap` labels (at least one) between
+    /// opens (default: all of them). A smaller cache bounds memory for wide traces at the
+    /// cost of a pass over the trace whenever an evicted label is opened.
+    #[must_use]
+    pub fn with_outer_cache(mut self, cap: usize) -> Self {
+        self.outer_cache = Lru::new(cap.max(1));
+        self
+    }
 
-        // Flush partials and build outer roots.
-        let mut out = Vec::<ColumnRoot>::with_capacity(labels.len());
-        for (lix, label) in labels.into_iter().enumerate() {
-            if !pending[lix].is_empty() {
-                let mt = MerkleTree::from_leaves(&pending[lix]);
-                chunk_roots_per_label[lix].push(mt.root());
-            }
-            let outer = MerkleTree::from_leaves(&chunk_roots_per_label[lix]);
-            out.push(ColumnRoot {
-                label,
-                root: outer.root(),
-            });
-        }
-        out
+    /// Build public column roots in canonical order using O(chunk) memory.
+    #[must_use]
+    pub fn build_roots(&self) -> Vec<ColumnRoot> {
+        self.root_builder().finish()
     }
 
-    /// Open (`label`, `row_idx`) by recomputing the target chunk and using a
-    /// cached outer tree (per label).
+    /// Open (`label`, `row_idx`) in the layout the column was committed
+    /// with (see [`ColumnRoot::per_block`]).
+    ///
+    /// The first call hashes every column once to fill the outer trees;
+    /// consecutive opens in the same chunk reuse its rows.
     #[must_use]
-    pub fn open(&mut self, label: &str, row_idx: usize) -> Opening {
+    pub fn open(&mut self, label: &str, row_idx: usize) -> ColumnOpening {
         assert!(row_idx < self.n_rows, "row index out of range");
         let kind = parse_label(label, self.tau);
+        if self.outer_tree(label).0 {
+            self.open_block(&kind, label, row_idx).into()
+        } else {
+            self.open_chunked(&kind, label, row_idx).into()
+        }
+    }
 
+    /// Open a chunked column by recomputing the target chunk and using the
+    /// cached outer tree.
+    fn open_chunked(&mut self, kind: &LabelKind, label: &str, row_idx: usize) -> Opening {
         let chunk_idx = row_idx / self.chunk_size;
         let idx_in_chunk = row_idx - chunk_idx * self.chunk_size;
 
         // Inner chunk data (recomputed).
         let (value_le, chunk_root, path_in_chunk) =
-            self.open_within_chunk(&kind, label, chunk_idx, idx_in_chunk);
-
-        // Ensure we have cached outer tree for this label (avoid E0502).
-        if !self.outer_cache.contains_key(label) {
-            let (roots, tree) = self.compute_all_chunk_roots_for_label(&kind, label);
-            self.outer_cache.insert(label.to_string(), (roots, tree));
-        }
-        let (_roots, outer_tree) = self.outer_cache.get(label).expect("cached");
+            self.open_within_chunk(kind, label, chunk_idx, idx_in_chunk);
 
-        let path_to_chunk = outer_tree.open(chunk_idx).sibs.clone();
+        let path_to_chunk = self.outer_tree(label).1.open(chunk_idx).sibs;
 
         Opening {
             value_le,
@@ -431,63 +624,80 @@ impl<'a> OnDemandOpenings<'a> {
         }
     }
 
-    /// Recompute **all** chunk-roots for a column (cached per label), and the
-    /// outer Merkle tree.
-    fn compute_all_chunk_roots_for_label(
-        &self,
-        kind: &LabelKind,
-        label: &str,
-    ) -> (Vec<[u8; 32]>, MerkleTree) {
-        let mut chunk_roots = Vec::<[u8; 32]>::new();
-        let mut cur = Vec::<[u8; 32]>::with_capacity(self.chunk_size);
-
-        for row in RowIter::new(self.blocks) {
-            let v = row.get_for_label(kind);
-            cur.push(hash_field_leaves_labeled(&[v], label)[0]);
-            if cur.len() == self.chunk_size {
-                let mt = MerkleTree::from_leaves(&cur);
-                chunk_roots.push(mt.root());
-                cur.clear();
-            }
+    /// Open a per-block column: the value and row range of the block holding
+    /// `row_idx`, with its path to the column root.
+    fn open_block(&mut self, kind: &LabelKind, label: &str, row_idx: usize) -> BlockOpening {
+        let block_idx = self.block_starts.partition_point(|&s| s <= row_idx) - 1;
+        let b = &self.blocks[block_idx];
+        let row_lo = self.block_starts[block_idx];
+        let row_hi = row_lo + (b.step_hi - b.step_lo) as usize;
+        // Constant over the block, so its first row carries the value.
+        let value_le = RowIter::new(&self.blocks[block_idx..])
+            .next()
+            .map_or([0; 8], |row| row.get_for_label(kind));
+
+        let path = self.outer_tree(label).1.open(block_idx).sibs;
+
+        BlockOpening {
+            value_le,
+            index: row_idx,
+            block_index: block_idx,
+            row_lo,
+            row_hi,
+            path,
         }
-        if !cur.is_empty() {
-            let mt = MerkleTree::from_leaves(&cur);
-            chunk_roots.push(mt.root());
+    }
+
+    /// Root builder fed with every block of the trace.
+    fn root_builder(&self) -> ColumnRootBuilder {
+        let mut builder =
+            ColumnRootBuilder::with_input_head(self.tau, self.chunk_log2, self.input_head);
+        for b in self.blocks {
+            builder.absorb(b);
         }
+        builder
+    }
 
-        let outer = MerkleTree::from_leaves(&chunk_roots);
-        (chunk_roots, outer)
+    /// The cached layout and outer tree of `label`, filling the cache on a
+    /// miss.
+    fn outer_tree(&mut self, label: &str) -> &(bool, MerkleTree) {
+        if !self.outer_cache.contains(label) {
+            // One pass yields every tree; the requested one goes in last so
+            // it is never the one evicted.
+            let mut trees: HashMap<String, (bool, MerkleTree)> =
+                self.root_builder().finish_trees().into_iter().collect();
+            let wanted = trees.remove_entry(label);
+            for (l, t) in trees.into_iter().chain(wanted) {
+                if !self.outer_cache.contains(&l) {
+                    self.outer_cache.put(l, t);
+                }
+            }
+        }
+        self.outer_cache.get(label).expect("cached")
     }
 
     /// Build the **inner** chunk tree for (`label`, `chunk_idx`) and return
     /// the opening data for `idx_in_chunk` (including the raw value bytes).
     fn open_within_chunk(
-        &self,
+        &mut self,
         kind: &LabelKind,
         label: &str,
         chunk_idx: usize,
         idx_in_chunk: usize,
     ) -> ([u8; 8], [u8; 32], Vec<[u8; 32]>) {
-        let start = chunk_idx * self.chunk_size;
-        let end = (start + self.chunk_size).min(self.n_rows);
-
-        let mut cur_leaves = Vec::<[u8; 32]>::with_capacity(end - start);
-        let mut value_le = [0u8; 8];
-
-        // Advance an iterator to `start`.
-        let mut it = RowIter::new(self.blocks);
-        for _ in 0..start {
-            let _ = it.next();
+        if self.chunk_rows.as_ref().map(|(c, _)| *c) != Some(chunk_idx) {
+            let start = chunk_idx * self.chunk_size;
+            let end = (start + self.chunk_size).min(self.n_rows);
+            let rows = RowIter::starting_at(self.blocks, start)
+                .take(end - start)
+                .collect();
+            self.chunk_rows = Some((chunk_idx, rows));
         }
+        let rows = self.chunk_rows.as_ref().map_or(&[][..], |(_, r)| &r[..]);
 
-        for i in start..end {
-            let row = it.next().expect("row exists");
-            let v = row.get_for_label(kind);
-            if i == start + idx_in_chunk {
-                value_le = v;
-            }
-            cur_leaves.push(hash_field_leaves_labeled(&[v], label)[0]);
-        }
+        let values: Vec<[u8; 8]> = rows.iter().map(|row| row.get_for_label(kind)).collect();
+        let value_le = values[idx_in_chunk];
+        let cur_leaves = hash_field_leaves_labeled(&values, label);
 
         let chunk_tree = MerkleTree::from_leaves(&cur_leaves);
         let chunk_root = chunk_tree.root();
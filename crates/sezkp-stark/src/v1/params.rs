@@ -12,13 +12,23 @@
     clippy::expect_used
 )]
 
-use sezkp_crypto::Transcript;
+use anyhow::{ensure, Context, Result};
+use serde::{Deserialize, Serialize};
+use sezkp_crypto::{Blake3Transcript, Transcript};
 
-use crate::v1::field::F1;
+use crate::v1::{
+    field::F1,
+    proof::{FriParams, ProofParams, PublicInputs},
+};
 
 /* ------------------------------ Security knobs ------------------------------ */
 
-/// Target soundness (bits) for the prototype.
+/// Modulus of the v1 field ([`F1`], Goldilocks).
+pub const FIELD_MODULUS: u64 = sezkp_ffts::GOLDILOCKS;
+
+/// Target soundness (bits) for the prototype. The default [`NUM_QUERIES`]
+/// reaches 90 of them; [`ENV_SECURITY_BITS`] sizes the query count for a
+/// target instead.
 pub const SOUNDNESS_BITS: usize = 100;
 
 /// FRI folding rate (2 means halve domain each layer).
@@ -27,9 +37,20 @@ pub const FRI_RATE: usize = 2;
 /// Trace-domain blowup (evaluation domain size / trace length).
 pub const BLOWUP: usize = 8;
 
-/// Number of random query positions sampled from the transcript.
+/// Default number of random query positions sampled from the transcript
+/// (row openings, and layer-0 positions of the chained FRI schedule).
 pub const NUM_QUERIES: usize = 30;
 
+/// Largest query count a v1 proof may declare ([`FriParams::num_queries`]).
+pub const MAX_NUM_QUERIES: usize = 256;
+
+/// Proof-of-work bits the prover grinds before query sampling. v1 does no
+/// grinding, so all of its soundness comes from the queries.
+pub const GRINDING_BITS: usize = 0;
+
+/// Largest query count a v1 proof may declare for one FRI layer.
+pub const MAX_FRI_LAYER_QUERIES: usize = 256;
+
 /// Minimum log2 domain size (2^k). Useful to avoid tiny domains in tests.
 pub const DOMAIN_MIN_LOG2: usize = 12;
 
@@ -40,26 +61,428 @@ pub const COL_CHUNK_LOG2: usize = 10; // 1024 rows per chunk
 /// (Reserved; current streaming keeps only one layer in memory.)
 pub const STREAM_CHUNK_LOG2: usize = 14; // 16,384
 
+/// Largest per-step move bound a v1 proof may declare. The mv-domain
+/// constraint has degree `2·max_move + 1`, so this caps it at 17.
+pub const MAX_MOVE_LIMIT: u8 = 8;
+
+/* ------------------------------ Streaming tuning ---------------------------- */
+
+/// Default log2 chunk (values) in which the LDE/DEEP engine emits layer 0.
+pub const DEFAULT_LDE_CHUNK_LOG2: u8 = 12; // 4096 values
+
+/// Default log2 staging buffer (values) for hashing FRI layers into roots.
+pub const DEFAULT_FRI_BUF_LOG2: u8 = 12; // 4096 values
+
+/// Smallest accepted tuning exponent.
+pub const TUNING_LOG2_MIN: u8 = 4;
+
+/// Largest accepted tuning exponent (a 2^24-value chunk is 128 MiB of
+/// encodings plus as much again for the denominators).
+pub const TUNING_LOG2_MAX: u8 = 24;
+
+/// Environment override for [`StreamTuning::lde_chunk_log2`].
+pub const ENV_LDE_CHUNK_LOG2: &str = "SEZKP_STARK_LDE_CHUNK_LOG2";
+
+/// Environment override for [`StreamTuning::fri_buf_log2`].
+pub const ENV_FRI_BUF_LOG2: &str = "SEZKP_STARK_FRI_BUF_LOG2";
+
+/// Prover-side buffer sizes of the streaming LDE and FRI builders.
+///
+/// Larger chunks mean fewer batch inversions and builder calls (faster) at
+/// the cost of bigger transient buffers. The emitted values and roots are the
+/// same for every setting, so proofs are byte-identical and verifiers never
+/// see these knobs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StreamTuning {
+    /// log2 of the LDE/DEEP output chunk (values per batch inversion).
+    pub lde_chunk_log2: u8,
+    /// log2 of the staging buffer used to hash FRI layers.
+    pub fri_buf_log2: u8,
+}
+
+impl Default for StreamTuning {
+    fn default() -> Self {
+        Self {
+            lde_chunk_log2: DEFAULT_LDE_CHUNK_LOG2,
+            fri_buf_log2: DEFAULT_FRI_BUF_LOG2,
+        }
+    }
+}
+
+impl StreamTuning {
+    /// Defaults overridden by [`ENV_LDE_CHUNK_LOG2`] / [`ENV_FRI_BUF_LOG2`].
+    ///
+    /// # Errors
+    /// Fails on an unparsable or out-of-range value.
+    pub fn from_env() -> Result<Self> {
+        let read = |var: &str, default: u8| -> Result<u8> {
+            std::env::var(var).map_or(Ok(default), |v| {
+                v.trim()
+                    .parse()
+                    .with_context(|| format!("invalid {var}={v:?}"))
+            })
+        };
+        let t = Self {
+            lde_chunk_log2: read(ENV_LDE_CHUNK_LOG2, DEFAULT_LDE_CHUNK_LOG2)?,
+            fri_buf_log2: read(ENV_FRI_BUF_LOG2, DEFAULT_FRI_BUF_LOG2)?,
+        };
+        t.check()?;
+        Ok(t)
+    }
+
+    /// Values per LDE/DEEP output chunk.
+    #[must_use]
+    pub const fn lde_chunk(&self) -> usize {
+        1 << self.lde_chunk_log2
+    }
+
+    /// Values per FRI staging buffer.
+    #[must_use]
+    pub const fn fri_buf(&self) -> usize {
+        1 << self.fri_buf_log2
+    }
+
+    /// Check both exponents lie in `TUNING_LOG2_MIN..=TUNING_LOG2_MAX`.
+    ///
+    /// # Errors
+    /// Names the first knob out of range.
+    pub fn check(&self) -> Result<()> {
+        for (name, v) in [
+            ("lde_chunk_log2", self.lde_chunk_log2),
+            ("fri_buf_log2", self.fri_buf_log2),
+        ] {
+            ensure!(
+                (TUNING_LOG2_MIN..=TUNING_LOG2_MAX).contains(&v),
+                "{name} = {v} is outside {TUNING_LOG2_MIN}..={TUNING_LOG2_MAX}"
+            );
+        }
+        Ok(())
+    }
+}
+
+/* ------------------------------ Security level ------------------------------ */
+
+/// Environment variable asking the prover for a security level (bits), see
+/// [`queries_for_security`].
+pub const ENV_SECURITY_BITS: &str = "SEZKP_STARK_SECURITY_BITS";
+
+/// Environment variable raising the security level (bits, as
+/// [`FriParams::security_bits`] estimates it) the backend verifier requires
+/// above [`DEFAULT_MIN_SECURITY_BITS`].
+pub const ENV_MIN_SECURITY_BITS: &str = "SEZKP_STARK_MIN_SECURITY_BITS";
+
+/// Environment variable (`1`) letting [`ENV_MIN_SECURITY_BITS`] lower the
+/// required security level below [`DEFAULT_MIN_SECURITY_BITS`].
+pub const ENV_ALLOW_LOW_SECURITY: &str = "SEZKP_STARK_ALLOW_LOW_SECURITY";
+
+/// Security level (bits) every verifier requires unless explicitly told
+/// otherwise: the estimate for the default [`NUM_QUERIES`] queries.
+pub const DEFAULT_MIN_SECURITY_BITS: usize = security_bits(NUM_QUERIES, BLOWUP, GRINDING_BITS);
+
+/// Conjectured soundness bits one query contributes at `blowup`: `log2` of
+/// the blowup (the FRI proximity-gap conjecture the prototype relies on).
+#[must_use]
+pub const fn bits_per_query(blowup: usize) -> usize {
+    blowup.trailing_zeros() as usize
+}
+
+/// Conjectured soundness (bits) of `queries` queries at `blowup` after
+/// `grinding_bits` of proof-of-work.
+#[must_use]
+pub const fn security_bits(queries: usize, blowup: usize, grinding_bits: usize) -> usize {
+    queries * bits_per_query(blowup) + grinding_bits
+}
+
+/// Fewest queries reaching `bits` of conjectured soundness at `blowup` after
+/// `grinding_bits` of proof-of-work (never fewer than one).
+///
+/// # Errors
+/// Fails if `blowup` is not a power of two above one, or the count would
+/// exceed [`MAX_NUM_QUERIES`].
+pub fn queries_for_security(bits: usize, blowup: usize, grinding_bits: usize) -> Result<usize> {
+    ensure!(
+        blowup > 1 && blowup.is_power_of_two(),
+        "blowup {blowup} is not a power of two above 1"
+    );
+    let k = bits
+        .saturating_sub(grinding_bits)
+        .div_ceil(bits_per_query(blowup))
+        .max(1);
+    ensure!(
+        k <= MAX_NUM_QUERIES,
+        "{bits} security bits need {k} queries at blowup {blowup} (supported: 1..={MAX_NUM_QUERIES})"
+    );
+    Ok(k)
+}
+
+/// Target security level from [`ENV_SECURITY_BITS`], if set.
+///
+/// # Errors
+/// Fails on a value that is not a non-negative integer.
+pub fn security_bits_from_env() -> Result<Option<usize>> {
+    bits_from_env(ENV_SECURITY_BITS)
+}
+
+/// Minimum security level from [`ENV_MIN_SECURITY_BITS`], if set.
+///
+/// # Errors
+/// Fails on a value that is not a non-negative integer, or one below
+/// [`DEFAULT_MIN_SECURITY_BITS`] unless [`ENV_ALLOW_LOW_SECURITY`] is `1`.
+pub fn min_security_bits_from_env() -> Result<Option<usize>> {
+    let min = bits_from_env(ENV_MIN_SECURITY_BITS)?;
+    if let Some(bits) = min {
+        ensure!(
+            bits >= DEFAULT_MIN_SECURITY_BITS
+                || std::env::var(ENV_ALLOW_LOW_SECURITY).is_ok_and(|v| v == "1"),
+            "{ENV_MIN_SECURITY_BITS}={bits} is below the default {DEFAULT_MIN_SECURITY_BITS} bits \
+             (set {ENV_ALLOW_LOW_SECURITY}=1 to lower it)"
+        );
+    }
+    Ok(min)
+}
+
+fn bits_from_env(var: &str) -> Result<Option<usize>> {
+    let Ok(v) = std::env::var(var) else {
+        return Ok(None);
+    };
+    v.trim()
+        .parse()
+        .map(Some)
+        .with_context(|| format!("invalid {var}={v:?}"))
+}
+
+/// Environment override for [`FriQuerySchedule::from_env`].
+pub const ENV_FRI_LAYER_QUERIES: &str = "SEZKP_STARK_FRI_LAYER_QUERIES";
+
+/// How the prover samples FRI query positions.
+///
+/// Unlike [`StreamTuning`] this changes the proof: the resolved per-layer
+/// counts are recorded in [`FriParams`] and bound into the transcript.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum FriQuerySchedule {
+    /// [`NUM_QUERIES`] layer-0 positions, each followed through every layer
+    /// (`idx % half`), so all layers share one set of queries.
+    #[default]
+    Chained,
+    /// Independent positions per layer, each checking one fold. A single
+    /// count applies to every layer; otherwise one count per folded layer,
+    /// layer 0 first.
+    PerLayer(Vec<usize>),
+}
+
+impl FriQuerySchedule {
+    /// [`Self::Chained`] unless [`ENV_FRI_LAYER_QUERIES`] holds a
+    /// comma-separated list of counts.
+    ///
+    /// # Errors
+    /// Fails on an unparsable or empty list.
+    pub fn from_env() -> Result<Self> {
+        Self::read_env(ENV_FRI_LAYER_QUERIES).map(Option::unwrap_or_default)
+    }
+
+    /// The schedule a verifier requires, from [`ENV_EXPECT_FRI_LAYER_QUERIES`]
+    /// (`chained`, or counts as for [`ENV_FRI_LAYER_QUERIES`]), if set.
+    ///
+    /// # Errors
+    /// Fails on an unparsable or empty list.
+    pub fn expected_from_env() -> Result<Option<Self>> {
+        Self::read_env(ENV_EXPECT_FRI_LAYER_QUERIES)
+    }
+
+    fn read_env(var: &str) -> Result<Option<Self>> {
+        let Ok(v) = std::env::var(var) else {
+            return Ok(None);
+        };
+        v.parse()
+            .map(Some)
+            .with_context(|| format!("invalid {var}={v:?}"))
+    }
+
+    /// Whether a proof declaring `fp` was made with this schedule.
+    #[must_use]
+    pub fn matches(&self, fp: &FriParams) -> bool {
+        match self {
+            Self::Chained => fp.layer_queries.is_empty(),
+            Self::PerLayer(c) if c.len() == 1 => fp.layer_queries.iter().all(|&q| q == c[0]),
+            Self::PerLayer(c) => fp.layer_queries == *c,
+        }
+    }
+
+    /// Resolve the schedule for a proof with `n_folds` folded layers and
+    /// `num_queries` row (and chained FRI) queries.
+    ///
+    /// # Errors
+    /// Fails if the number of counts is neither 1 nor `n_folds`, a count is
+    /// outside `1..=MAX_FRI_LAYER_QUERIES`, or `num_queries` is outside
+    /// `1..=MAX_NUM_QUERIES`.
+    pub fn resolve(&self, n_folds: usize, num_queries: usize) -> Result<FriParams> {
+        let layer_queries = match self {
+            Self::Chained => Vec::new(),
+            Self::PerLayer(c) if c.len() == 1 => vec![c[0]; n_folds],
+            Self::PerLayer(c) => c.clone(),
+        };
+        let fp = FriParams {
+            num_queries,
+            layer_queries,
+        };
+        fp.check(n_folds)?;
+        Ok(fp)
+    }
+}
+
+impl std::str::FromStr for FriQuerySchedule {
+    type Err = anyhow::Error;
+
+    /// `chained`, or a comma-separated list of per-layer counts.
+    fn from_str(s: &str) -> Result<Self> {
+        if s.trim() == "chained" {
+            return Ok(Self::Chained);
+        }
+        let counts = s
+            .split(',')
+            .map(|c| c.trim().parse())
+            .collect::<Result<Vec<usize>, _>>()?;
+        Ok(Self::PerLayer(counts))
+    }
+}
+
+impl std::fmt::Display for FriQuerySchedule {
+    /// The spelling [`std::str::FromStr`] accepts.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Chained => f.write_str("chained"),
+            Self::PerLayer(c) => {
+                let counts: Vec<String> = c.iter().map(ToString::to_string).collect();
+                f.write_str(&counts.join(","))
+            }
+        }
+    }
+}
+
+/// Environment variable making the backend verifier require a FRI query
+/// schedule ([`FriQuerySchedule::expected_from_env`]).
+pub const ENV_EXPECT_FRI_LAYER_QUERIES: &str = "SEZKP_STARK_EXPECT_FRI_LAYER_QUERIES";
+
+/// Environment variable making the backend verifier require a movement
+/// model other than [`sezkp_core::DEFAULT_MAX_MOVE`] (see
+/// [`expected_max_move_from_env`]).
+pub const ENV_EXPECT_MAX_MOVE: &str = "SEZKP_STARK_EXPECT_MAX_MOVE";
+
+/// Movement bound the verifier requires, from [`ENV_EXPECT_MAX_MOVE`], if set.
+///
+/// The prover declares the smallest bound covering the blocks' moves
+/// ([`crate::v1::proof::ProofParams::for_blocks`]); a verifier expecting
+/// anything but unit moves has to say so.
+///
+/// # Errors
+/// Fails on a value outside `1..=MAX_MOVE_LIMIT`.
+pub fn expected_max_move_from_env() -> Result<Option<u8>> {
+    let Ok(v) = std::env::var(ENV_EXPECT_MAX_MOVE) else {
+        return Ok(None);
+    };
+    let max_move: u8 = v
+        .trim()
+        .parse()
+        .with_context(|| format!("invalid {ENV_EXPECT_MAX_MOVE}={v:?}"))?;
+    ensure!(
+        (1..=MAX_MOVE_LIMIT).contains(&max_move),
+        "{ENV_EXPECT_MAX_MOVE}={max_move} is outside 1..={MAX_MOVE_LIMIT}"
+    );
+    Ok(Some(max_move))
+}
+
+/// Environment variable declaring the input tape length (see
+/// [`input_len_from_env`]).
+pub const ENV_INPUT_LEN: &str = "SEZKP_STARK_INPUT_LEN";
+
+/// Declared input tape length from [`ENV_INPUT_LEN`], if set. The prover
+/// records it in [`crate::v1::proof::ProofParams::input_len`]; the backend
+/// verifier requires a proof to declare the same length.
+///
+/// # Errors
+/// Fails on a value that is not a `u64`.
+pub fn input_len_from_env() -> Result<Option<u64>> {
+    let Ok(v) = std::env::var(ENV_INPUT_LEN) else {
+        return Ok(None);
+    };
+    v.trim()
+        .parse()
+        .map(Some)
+        .with_context(|| format!("invalid {ENV_INPUT_LEN}={v:?}"))
+}
+
 /* -------------------------- Transcript label strings ------------------------ */
 
 /// Top-level protocol domain string for v1.
 pub const DS_V1_DOMAIN: &str = "sezkp-stark/v1";
 
+/// Domain string of the transcript [`params_digest`] hashes the declared
+/// parameters with.
+pub const DS_PARAMS_DOMAIN: &str = "sezkp-stark/v1/params";
+
+/// Label for binding the manifest root into the transcript.
+pub const DS_MANIFEST_ROOT: &str = "manifest_root";
+
+/// Label for binding the trace row count into the transcript.
+pub const DS_N_ROWS: &str = "n";
+
+/// Label for binding the number of work tapes into the transcript.
+pub const DS_TAU: &str = "tau";
+
+/// Label for binding the digest of the declared parameters into the
+/// transcript (and for squeezing it out of [`DS_PARAMS_DOMAIN`]).
+pub const DS_PARAMS_DIGEST: &str = "params_digest";
+
 /// Label for binding the number of columns into the transcript.
 pub const DS_N_COLS: &str = "n_cols";
 
 /// Label used when absorbing per-column Merkle roots into the transcript.
 pub const DS_COL_ROOT: &str = "col_root";
 
+/// Label used when absorbing each column's layout (1 if committed per
+/// block) into the transcript, after its root.
+pub const DS_COL_PER_BLOCK: &str = "col_per_block";
+
 /// Domain-sep for *leaf hashing* of column commitments (used inside Merkle).
 pub const DS_COL_LEAF: &str = "col_leaf";
 
+/// Domain-sep for the leaves of columns committed per block.
+pub const DS_COL_BLOCK_LEAF: &str = "col_block_leaf";
+
+/// Domain-sep for FRI layer leaves (see the hash domains in
+/// [`crate::v1::merkle`]).
+pub const DS_FRI_LEAF: &str = "sezkp-stark/v1/fri_leaf";
+
+/// Domain-sep for inner nodes of FRI layer trees.
+pub const DS_FRI_NODE: &str = "sezkp-stark/v1/fri_node";
+
+/// Label for binding the declared movement bound into the transcript.
+pub const DS_MAX_MOVE: &str = "max_move";
+
+/// Label for binding a declared input tape length into the transcript.
+pub const DS_INPUT_LEN: &str = "input_len";
+
+/// Label for binding the digest of the caller's claims into the transcript.
+pub const DS_CLAIMS: &str = "claims";
+
+/// Label for binding the digest of the blocks' auxiliary commitments.
+pub const DS_AUX: &str = "aux";
+
 /// Label to derive AIR linear-combination coefficients (alphas).
 pub const DS_ALPHAS: &str = "alphas";
 
 /// Label to derive random row query indices (AIR).
 pub const DS_QUERIES: &str = "row_queries";
 
+/// Label for binding a non-default query count into the transcript.
+pub const DS_NUM_QUERIES: &str = "num_queries";
+
+/// Label for binding per-layer FRI query counts into the transcript.
+pub const DS_FRI_SCHEDULE: &str = "fri_schedule";
+
+/// Label to derive per-layer FRI query positions.
+pub const DS_FRI_LAYER_QUERIES: &str = "fri_layer_queries";
+
 /// Label to derive FRI layer folding coefficients (betas).
 pub const DS_FRI_BETAS: &str = "fri_betas";
 
@@ -72,6 +495,10 @@ pub const DS_OOD_POINT: &str = "ood_point";
 /// (Optional) Mixer for DEEP or mask terms if needed.
 pub const DS_DEEP_ALPHA: &str = "deep_alpha";
 
+/// Label for an optional caller-supplied randomness beacon, absorbed after
+/// the FRI roots and before any query index is drawn.
+pub const DS_BEACON: &str = "external_beacon";
+
 /* ------------------------------- Derivers ---------------------------------- */
 
 /// Number of alphas used in the composition polynomial.
@@ -90,18 +517,116 @@ pub fn derive_alphas<T: Transcript>(tr: &mut T) -> [F1; NUM_ALPHAS] {
     out
 }
 
+/// Bind an external randomness beacon (e.g. a block hash) if one was given.
+///
+/// Called right before the first [`derive_queries`], so every query index
+/// depends on the beacon: a proof carrying it cannot predate the beacon.
+pub fn absorb_beacon<T: Transcript>(tr: &mut T, beacon: Option<&[u8]>) {
+    if let Some(b) = beacon {
+        tr.absorb(DS_BEACON, b);
+    }
+}
+
+/// Digest of a proof's declared parameters and query schedule, recorded in
+/// [`PublicInputs::params_digest`].
+///
+/// Each optional parameter is absorbed only when present, like the
+/// individual `absorb_*` helpers below.
+#[must_use]
+pub fn params_digest(params: &ProofParams, fri: &FriParams) -> [u8; 32] {
+    let mut tr = Blake3Transcript::new(DS_PARAMS_DOMAIN);
+    tr.absorb_u64(DS_MAX_MOVE, u64::from(params.max_move));
+    absorb_input_len(&mut tr, params.input_len);
+    absorb_aux(&mut tr, params.aux.as_ref());
+    absorb_fri_schedule(&mut tr, fri);
+    let mut out = [0u8; 32];
+    out.copy_from_slice(&tr.challenge_bytes(DS_PARAMS_DIGEST, 32));
+    out
+}
+
+/// Bind a proof's public inputs: the first thing either side absorbs.
+pub fn absorb_public_inputs<T: Transcript>(tr: &mut T, public: &PublicInputs) {
+    tr.absorb(DS_MANIFEST_ROOT, &public.manifest_root);
+    tr.absorb_u64(DS_N_ROWS, public.n_rows as u64);
+    tr.absorb_u64(DS_TAU, public.tau as u64);
+    tr.absorb(DS_PARAMS_DIGEST, &public.params_digest);
+    absorb_claims(tr, public.claims.as_ref());
+}
+
+/// Bind the query counts of a proof. The default [`NUM_QUERIES`] and the
+/// chained schedule (no per-layer counts) absorb nothing, so proofs using
+/// both keep their transcript unchanged.
+pub fn absorb_fri_schedule<T: Transcript>(tr: &mut T, fri: &FriParams) {
+    if fri.num_queries != NUM_QUERIES {
+        tr.absorb_u64(DS_NUM_QUERIES, fri.num_queries as u64);
+    }
+    if fri.is_chained() {
+        return;
+    }
+    tr.absorb_u64(DS_FRI_SCHEDULE, fri.layer_queries.len() as u64);
+    for &k in &fri.layer_queries {
+        tr.absorb_u64(DS_FRI_SCHEDULE, k as u64);
+    }
+}
+
+/// Bind a declared input tape length; an undeclared one absorbs nothing.
+pub fn absorb_input_len<T: Transcript>(tr: &mut T, input_len: Option<u64>) {
+    if let Some(len) = input_len {
+        tr.absorb_u64(DS_INPUT_LEN, len);
+    }
+}
+
+/// Bind the digest of the caller's claims; a proof without claims absorbs
+/// nothing.
+pub fn absorb_claims<T: Transcript>(tr: &mut T, claims: Option<&[u8; 32]>) {
+    if let Some(d) = claims {
+        tr.absorb(DS_CLAIMS, d);
+    }
+}
+
+/// Bind the digest of the blocks' auxiliary commitments; blocks without any
+/// absorb nothing.
+pub fn absorb_aux<T: Transcript>(tr: &mut T, aux: Option<&[u8; 32]>) {
+    if let Some(d) = aux {
+        tr.absorb(DS_AUX, d);
+    }
+}
+
 /// Derive `k` query positions in `[0, n)` using `DS_QUERIES`.
 #[must_use]
 pub fn derive_queries<T: Transcript>(tr: &mut T, n: usize, k: usize) -> Vec<usize> {
-    let bytes = tr.challenge_bytes(DS_QUERIES, 8 * k);
-    let mut out = Vec::with_capacity(k);
-    for i in 0..k {
-        let mut le = [0u8; 8];
-        le.copy_from_slice(&bytes[i * 8..(i + 1) * 8]);
-        let v = u64::from_le_bytes(le) as usize;
-        out.push(v % n.max(1));
-    }
-    out
+    positions(&tr.challenge_bytes(DS_QUERIES, 8 * k), n)
+}
+
+/// Derive `k` query positions in `[0, n)` for FRI layer `layer` using
+/// `DS_FRI_LAYER_QUERIES`. The layer index is absorbed first, so every layer
+/// draws its own positions.
+#[must_use]
+pub fn derive_layer_queries<T: Transcript>(
+    tr: &mut T,
+    layer: usize,
+    n: usize,
+    k: usize,
+) -> Vec<usize> {
+    tr.absorb_u64(DS_FRI_LAYER_QUERIES, layer as u64);
+    positions(&tr.challenge_bytes(DS_FRI_LAYER_QUERIES, 8 * k), n)
+}
+
+/// Reduce little-endian `u64` words of `bytes` modulo `n`.
+///
+/// The reduction happens in `u64`, so a 32-bit `usize` cannot truncate the
+/// word first and draw different positions than a 64-bit verifier.
+#[allow(clippy::cast_possible_truncation)] // below `n`, which came from a `usize`
+fn positions(bytes: &[u8], n: usize) -> Vec<usize> {
+    let n = n.max(1) as u64;
+    bytes
+        .chunks_exact(8)
+        .map(|w| {
+            let mut le = [0u8; 8];
+            le.copy_from_slice(w);
+            (u64::from_le_bytes(le) % n) as usize
+        })
+        .collect()
 }
 
 /// Derive exactly `n_layers` FRI folding coefficients (betas) using `DS_FRI_BETAS`.
@@ -0,0 +1,142 @@
+//! Per-phase prover timing and temporary-memory counters for v1.
+//!
+//! The prover records a [`ProveProfile`] next to its `phase_span!` hooks, so
+//! the breakdown is available without a tracing subscriber: the backends
+//! store it under `meta.profile` in the artifact and the CLI's `inspect`
+//! prints it. Phases, in order:
+//!
+//! - `columns` — trace columns and streamed column commitments,
+//! - `lde_layer0` — composition + masked LDE/DEEP stream and the layer-0 root,
+//! - `fri_folds` — folding and committing the upper FRI layers,
+//! - `air_openings` — column openings at the AIR query rows,
+//! - `fri_openings` — FRI query paths (layer 0 re-streamed).
+//!
+//! Memory counters are byte sizes of the prover's large temporary buffers
+//! (trace columns, LDE values, fold scratch, per-layer Merkle leaves and
+//! nodes), not allocator measurements: they are deterministic for a given
+//! input, which is what release-over-release comparisons want.
+
+#![forbid(unsafe_code)]
+#![deny(rust_2018_idioms)]
+#![warn(
+    clippy::all,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::unwrap_used,
+    clippy::expect_used
+)]
+
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+/// Timing and buffer high-water mark of one prover phase.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PhaseTiming {
+    /// Phase name (see the module docs).
+    pub phase: String,
+    /// Wall-clock milliseconds.
+    pub ms: f64,
+    /// Largest temporary-buffer footprint while the phase ran, in bytes.
+    pub peak_temp_bytes: u64,
+}
+
+/// Per-phase breakdown of one v1 proof.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProveProfile {
+    /// Phases in execution order (empty for the zero-block proof).
+    pub phases: Vec<PhaseTiming>,
+    /// Largest temporary-buffer footprint over the whole proof, in bytes.
+    pub peak_temp_bytes: u64,
+}
+
+impl ProveProfile {
+    /// Sum of the phase timings.
+    #[must_use]
+    pub fn total_ms(&self) -> f64 {
+        self.phases.iter().map(|p| p.ms).sum()
+    }
+
+    /// The profile recorded in an artifact's `meta`, if any.
+    #[must_use]
+    pub fn from_meta(meta: &serde_json::Value) -> Option<Self> {
+        serde_json::from_value(meta.get("profile")?.clone()).ok()
+    }
+}
+
+/// Bytes of `n` values of `T`.
+pub(crate) const fn bytes_of<T>(n: usize) -> u64 {
+    (n * std::mem::size_of::<T>()) as u64
+}
+
+/// Records phase timings and buffer sizes while a proof is built.
+#[derive(Debug)]
+pub(crate) struct Profiler {
+    start: Instant,
+    live: u64,
+    phase_peak: u64,
+    profile: ProveProfile,
+}
+
+impl Profiler {
+    pub(crate) fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            live: 0,
+            phase_peak: 0,
+            profile: ProveProfile::default(),
+        }
+    }
+
+    /// A buffer of `bytes` now stays alive for the rest of the proof.
+    pub(crate) fn alloc(&mut self, bytes: u64) {
+        self.live += bytes;
+        self.phase_peak = self.phase_peak.max(self.live);
+    }
+
+    /// A short-lived buffer of `bytes` on top of the live ones.
+    pub(crate) fn transient(&mut self, bytes: u64) {
+        self.phase_peak = self.phase_peak.max(self.live + bytes);
+    }
+
+    /// Close the running phase as `phase` and start the next one.
+    pub(crate) fn end_phase(&mut self, phase: &str) {
+        let now = Instant::now();
+        self.profile.phases.push(PhaseTiming {
+            phase: phase.to_owned(),
+            ms: now.duration_since(self.start).as_secs_f64() * 1e3,
+            peak_temp_bytes: self.phase_peak,
+        });
+        self.profile.peak_temp_bytes = self.profile.peak_temp_bytes.max(self.phase_peak);
+        self.start = now;
+        self.phase_peak = self.live;
+    }
+
+    pub(crate) fn finish(self) -> ProveProfile {
+        self.profile
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn peaks_follow_live_and_transient_buffers() {
+        let mut p = Profiler::new();
+        p.alloc(100);
+        p.end_phase("a");
+        p.transient(50);
+        p.end_phase("b");
+        p.end_phase("c");
+        let prof = p.finish();
+
+        // Transient buffers count once; live ones carry into later phases.
+        let peaks: Vec<_> = prof.phases.iter().map(|t| t.peak_temp_bytes).collect();
+        assert_eq!(peaks, [100, 150, 100]);
+        assert_eq!(prof.peak_temp_bytes, 150);
+
+        let meta = serde_json::json!({ "profile": prof });
+        assert_eq!(ProveProfile::from_meta(&meta), Some(prof));
+        assert_eq!(ProveProfile::from_meta(&serde_json::Value::Null), None);
+    }
+}
@@ -10,13 +10,235 @@
     clippy::expect_used
 )]
 
+use anyhow::{ensure, Context, Result};
 use serde::{Deserialize, Serialize};
+use sezkp_core::{BlockSummary, Replay, ReplayConfig, DEFAULT_MAX_MOVE};
 
-/// Per-column outer Merkle root bound into the transcript.
+use crate::v1::params::{
+    self, BLOWUP, GRINDING_BITS, MAX_FRI_LAYER_QUERIES, MAX_MOVE_LIMIT, MAX_NUM_QUERIES,
+    NUM_QUERIES,
+};
+
+/// Statement parameters a proof declares and its verifier enforces.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofParams {
+    /// Movement model: every head move lies in `-max_move..=max_move`. The
+    /// AIR's mv-domain constraint is the product of `(mv - k)` over that range.
+    pub max_move: u8,
+    /// Declared input tape length: the proof then commits the input-head
+    /// columns and every opened position must lie in `[0, input_len)`.
+    /// `None` leaves the input head out of the proof.
+    pub input_len: Option<u64>,
+    /// Digest of the blocks' auxiliary commitments
+    /// ([`sezkp_core::auxiliary::aux_digest`]), bound into the transcript and
+    /// recomputed by the verifier; `None` when no block carries any.
+    pub aux: Option<[u8; 32]>,
+}
+
+impl Default for ProofParams {
+    fn default() -> Self {
+        Self::UNIT
+    }
+}
+
+impl ProofParams {
+    /// The classic `{-1, 0, 1}` movement model.
+    pub const UNIT: Self = Self {
+        max_move: DEFAULT_MAX_MOVE,
+        input_len: None,
+        aux: None,
+    };
+
+    /// Smallest parameters covering every move in `blocks` (never below
+    /// [`Self::UNIT`]).
+    #[must_use]
+    pub fn for_blocks(blocks: &[BlockSummary]) -> Self {
+        let max_move = blocks
+            .iter()
+            .flat_map(|b| &b.movement_log.steps)
+            .flat_map(|s| std::iter::once(s.input_mv).chain(s.tapes.iter().map(|op| op.mv)))
+            .map(i8::unsigned_abs)
+            .fold(DEFAULT_MAX_MOVE, u8::max);
+        Self {
+            max_move,
+            ..Self::UNIT
+        }
+    }
+
+    /// Degree of the mv-domain constraint under these parameters.
+    #[must_use]
+    pub const fn mv_domain_degree(&self) -> usize {
+        2 * self.max_move as usize + 1
+    }
+
+    /// Check the parameters are within what this build proves and verifies.
+    ///
+    /// # Errors
+    /// Fails if `max_move` is zero or above [`MAX_MOVE_LIMIT`].
+    pub fn check_supported(&self) -> Result<()> {
+        ensure!(
+            (1..=MAX_MOVE_LIMIT).contains(&self.max_move),
+            "unsupported movement bound ±{} (supported: ±1..=±{MAX_MOVE_LIMIT})",
+            self.max_move
+        );
+        Ok(())
+    }
+
+    /// Check that `blocks` keep the input head on the declared tape and chain
+    /// it exactly (the replay checks of [`ReplayConfig::input_len`]); a no-op
+    /// without a declared length.
+    ///
+    /// # Errors
+    /// Fails on the first block whose input head leaves `[0, input_len)` or
+    /// whose moves do not lead from `in_head_in` to `in_head_out`.
+    pub fn check_input_head(&self, blocks: &[BlockSummary]) -> Result<()> {
+        if self.input_len.is_none() {
+            return Ok(());
+        }
+        let replay = Replay {
+            cfg: ReplayConfig {
+                max_move: self.max_move,
+                input_len: self.input_len,
+                ..ReplayConfig::default()
+            },
+        };
+        for b in blocks {
+            replay.replay_block(b)?;
+        }
+        Ok(())
+    }
+
+    /// Whether an opened input-head position (canonical field encoding) lies
+    /// on the declared input tape.
+    #[must_use]
+    pub fn input_pos_ok(&self, value_le: [u8; 8]) -> bool {
+        self.input_len
+            .is_none_or(|len| u64::from_le_bytes(value_le) < len)
+    }
+}
+
+/// Query schedule a proof declares, bound into the transcript.
+///
+/// Empty `layer_queries` is the chained schedule: `num_queries` layer-0
+/// positions followed through every layer (`fri_queries`). Otherwise layer
+/// ℓ draws `layer_queries[ℓ]` positions of its own, each checking the fold
+/// ℓ → ℓ+1 (`fri_layer_queries[ℓ]`).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FriParams {
+    /// Rows opened for the AIR check, and layer-0 positions of the chained
+    /// schedule ([`NUM_QUERIES`] unless sized for a security level).
+    pub num_queries: usize,
+    /// Query count per folded layer (layer 0 first).
+    pub layer_queries: Vec<usize>,
+}
+
+impl Default for FriParams {
+    fn default() -> Self {
+        Self {
+            num_queries: NUM_QUERIES,
+            layer_queries: Vec::new(),
+        }
+    }
+}
+
+impl FriParams {
+    /// Whether this is the chained schedule.
+    #[must_use]
+    pub const fn is_chained(&self) -> bool {
+        self.layer_queries.is_empty()
+    }
+
+    /// Conjectured soundness (bits) of the schedule: its weakest query set,
+    /// the row queries or one FRI layer's, at [`BLOWUP`] and
+    /// [`GRINDING_BITS`].
+    #[must_use]
+    pub fn security_bits(&self) -> usize {
+        let weakest = self
+            .layer_queries
+            .iter()
+            .copied()
+            .fold(self.num_queries, usize::min);
+        params::security_bits(weakest, BLOWUP, GRINDING_BITS)
+    }
+
+    /// Check the schedule fits a proof with `n_folds` folded layers.
+    ///
+    /// # Errors
+    /// Fails on a query count outside `1..=MAX_NUM_QUERIES`, a per-layer
+    /// schedule with other than `n_folds` counts, or a layer count outside
+    /// `1..=MAX_FRI_LAYER_QUERIES`.
+    pub fn check(&self, n_folds: usize) -> Result<()> {
+        ensure!(
+            (1..=MAX_NUM_QUERIES).contains(&self.num_queries),
+            "proof declares {} queries (supported: 1..={MAX_NUM_QUERIES})",
+            self.num_queries
+        );
+        if self.is_chained() {
+            return Ok(());
+        }
+        ensure!(
+            self.layer_queries.len() == n_folds,
+            "FRI schedule lists {} layers, proof folds {n_folds}",
+            self.layer_queries.len()
+        );
+        for (l, &k) in self.layer_queries.iter().enumerate() {
+            ensure!(
+                (1..=MAX_FRI_LAYER_QUERIES).contains(&k),
+                "FRI layer {l} declares {k} queries (supported: 1..={MAX_FRI_LAYER_QUERIES})"
+            );
+        }
+        Ok(())
+    }
+}
+
+/// The statement a proof attests, serialized at the head of the proof and
+/// absorbed first into its transcript.
+///
+/// Everything else a verifier is told about the statement is committed to
+/// here: the declared [`ProofParams`] and [`FriParams`] through
+/// `params_digest`. Tooling reads it with [`Self::peek`] without decoding
+/// the rest of the proof.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublicInputs {
+    /// Trace rows `n` (`domain_n / BLOWUP`; zero for the empty proof).
+    pub n_rows: usize,
+    /// Number of work tapes τ.
+    pub tau: usize,
+    /// Merkle root of the block-summaries manifest.
+    pub manifest_root: [u8; 32],
+    /// [`params::params_digest`] of the proof's declared parameters and
+    /// query schedule.
+    pub params_digest: [u8; 32],
+    /// Digest of the caller's public claims ([`sezkp_core::claims`]); `None`
+    /// when the proof carries no claims.
+    pub claims: Option<[u8; 32]>,
+}
+
+impl PublicInputs {
+    /// Decode only the public inputs at the head of an encoded proof.
+    ///
+    /// Nothing is checked: the inputs are what the proof *claims* to attest,
+    /// and only [`crate::v1::verify`] ties them to the rest of it.
+    ///
+    /// # Errors
+    /// Fails if `bytes` do not start with an encoded [`PublicInputs`].
+    pub fn peek(bytes: &[u8]) -> Result<Self> {
+        bincode::deserialize(bytes).context("decoding STARK v1 public inputs")
+    }
+}
+
+/// Per-column outer Merkle root bound into the transcript, with the
+/// column's layout.
+///
+/// A column whose value is constant within every block is committed with
+/// one leaf per block (`per_block`); any other column with chunked row
+/// leaves. The prover decides from the values, and every opening of the
+/// column must use that layout.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ColumnRoot {
     pub label: String,
     pub root: [u8; 32],
+    pub per_block: bool,
 }
 
 /// Wrapper for FRI layer roots (layer-0..last).
@@ -43,19 +265,83 @@ pub struct Opening {
     pub path_to_chunk: Vec<[u8; 32]>,
 }
 
+/// Opening of a column committed per block (see [`ColumnRoot::per_block`]):
+/// the value of the block holding row `index`, with a single Merkle path
+/// over the column's per-block leaves.
+///
+/// The leaf binds the block's row range, so the verifier checks that the
+/// range covers the queried row instead of a per-row index.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BlockOpening {
+    pub value_le: [u8; 8],
+    pub index: usize,
+
+    // Block commitment proof data:
+    pub block_index: usize,
+    pub row_lo: usize,
+    pub row_hi: usize,
+    pub path: Vec<[u8; 32]>,
+}
+
+/// Opening of one column value, in the layout its [`ColumnRoot`] declares.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum ColumnOpening {
+    /// Chunked row leaves.
+    Chunked(Opening),
+    /// One leaf per block.
+    Block(BlockOpening),
+}
+
+impl ColumnOpening {
+    /// The opened value (little-endian).
+    #[must_use]
+    pub const fn value_le(&self) -> [u8; 8] {
+        match self {
+            Self::Chunked(o) => o.value_le,
+            Self::Block(o) => o.value_le,
+        }
+    }
+
+    /// Mutable access to the opened value.
+    pub const fn value_le_mut(&mut self) -> &mut [u8; 8] {
+        match self {
+            Self::Chunked(o) => &mut o.value_le,
+            Self::Block(o) => &mut o.value_le,
+        }
+    }
+
+    /// Whether this opening is of a column committed per block.
+    #[must_use]
+    pub const fn is_block(&self) -> bool {
+        matches!(self, Self::Block(_))
+    }
+}
+
+impl From<Opening> for ColumnOpening {
+    fn from(o: Opening) -> Self {
+        Self::Chunked(o)
+    }
+}
+
+impl From<BlockOpening> for ColumnOpening {
+    fn from(o: BlockOpening) -> Self {
+        Self::Block(o)
+    }
+}
+
 /// Per-tape set of openings for a single queried row.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PerTapeOpen {
-    pub mv: Opening,
-    pub next_mv: Opening, // used by head-update constraint from openings
-    pub write_flag: Opening,
-    pub write_sym: Opening,
-    pub head: Opening,
-    pub next_head: Opening,
-    pub win_len: Opening,
+    pub mv: ColumnOpening,
+    pub next_mv: ColumnOpening, // used by head-update constraint from openings
+    pub write_flag: ColumnOpening,
+    pub write_sym: ColumnOpening,
+    pub head: ColumnOpening,
+    pub next_head: ColumnOpening,
+    pub win_len: ColumnOpening,
     // Offsets needed for boundary checks from openings only
-    pub in_off: Opening,
-    pub out_off: Opening,
+    pub in_off: ColumnOpening,
+    pub out_off: ColumnOpening,
 }
 
 /// Row-level set of openings (tape-wise + scalar flags).
@@ -63,9 +349,22 @@ pub struct PerTapeOpen {
 pub struct RowOpenings {
     pub row: usize,
     pub per_tape: Vec<PerTapeOpen>,
-    pub is_first: Opening,
-    pub is_last: Opening,
-    pub input_mv: Opening,
+    pub is_first: ColumnOpening,
+    pub is_last: ColumnOpening,
+    pub input_mv: ColumnOpening,
+    // Present iff the proof declares an input length
+    pub input_head: Option<InputHeadOpen>,
+}
+
+/// Input-head openings for a single queried row.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct InputHeadOpen {
+    pub in_head: ColumnOpening,
+    pub next_in_head: ColumnOpening, // used by the update constraint with next_input_mv
+    pub next_input_mv: ColumnOpening,
+    // Block endpoints needed for boundary checks from openings only
+    pub in_head_in: ColumnOpening,
+    pub in_head_out: ColumnOpening,
 }
 
 /// FRI query: indices per layer + pairs of leaves for each layer (except last).
@@ -75,12 +374,23 @@ pub struct FriQuery {
     pub pairs: Vec<([u8; 8], Vec<[u8; 32]>, [u8; 8], Vec<[u8; 32]>)>, // (v_i, path_i, v_j, path_j)
 }
 
+/// Per-layer FRI query on layer ℓ: the pair at `position` and its sibling,
+/// plus the folded value at `position % half` on layer ℓ+1.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FriLayerQuery {
+    pub position: usize,
+    pub pair: ([u8; 8], Vec<[u8; 32]>, [u8; 8], Vec<[u8; 32]>), // (v_i, path_i, v_j, path_j)
+    pub next: ([u8; 8], Vec<[u8; 32]>),                          // (v, path) on layer ℓ+1
+}
+
 /// Complete proof object for v1 (columnar PIOP + FRI).
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ProofV1 {
+    /// Statement the proof attests (encoded first, absorbed first).
+    pub public: PublicInputs,
+
     /// LDE domain size used by FRI (may be > number of trace rows).
     pub domain_n: usize,
-    pub tau: usize,
 
     /// Column commitments (outer roots) in transcript order.
     pub col_roots: Vec<ColumnRoot>,
@@ -93,6 +403,68 @@ pub struct ProofV1 {
     pub fri_queries: Vec<FriQuery>,
     pub fri_final_value_le: [u8; 8],
 
-    /// Merkle root of the block-summaries manifest (bound at the top).
-    pub manifest_root: [u8; 32],
+    /// FRI query schedule; per-layer queries (one list per folded layer)
+    /// replace `fri_queries` when it is not chained.
+    pub fri_params: FriParams,
+    pub fri_layer_queries: Vec<Vec<FriLayerQuery>>,
+
+    /// External randomness beacon absorbed before query sampling, if any.
+    pub beacon: Option<Vec<u8>>,
+
+    /// Declared statement parameters (movement model, input tape length),
+    /// bound into the transcript through [`PublicInputs::params_digest`].
+    pub params: ProofParams,
+}
+
+impl ProofV1 {
+    /// The canonical proof over **zero** blocks: no rows, no columns, no FRI
+    /// layers, bound to [`sezkp_core::EMPTY_ROOT`].
+    #[must_use]
+    pub fn empty() -> Self {
+        let fri_params = FriParams::default();
+        Self {
+            public: PublicInputs {
+                n_rows: 0,
+                tau: 0,
+                manifest_root: sezkp_core::EMPTY_ROOT,
+                params_digest: params::params_digest(&ProofParams::UNIT, &fri_params),
+                claims: None,
+            },
+            domain_n: 0,
+            col_roots: Vec::new(),
+            queries: Vec::new(),
+            fri_roots: FriRoots { roots: Vec::new() },
+            fri_queries: Vec::new(),
+            fri_final_value_le: [0u8; 8],
+            fri_params,
+            fri_layer_queries: Vec::new(),
+            beacon: None,
+            params: ProofParams::UNIT,
+        }
+    }
+
+    /// Decode a proof from an artifact's `proof_bytes`, without checking
+    /// anything it binds.
+    ///
+    /// # Errors
+    /// Fails if `bytes` are not a bincode-encoded v1 proof.
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        bincode::deserialize(bytes).context("decoding STARK v1 proof")
+    }
+
+    /// Whether this proof has the exact shape of [`Self::empty`] (any beacon
+    /// is only recorded: there are no queries for it to influence).
+    #[must_use]
+    pub fn is_empty_proof(&self) -> bool {
+        self.domain_n == 0
+            && self.public.n_rows == 0
+            && self.public.tau == 0
+            && self.col_roots.is_empty()
+            && self.queries.is_empty()
+            && self.fri_roots.roots.is_empty()
+            && self.fri_queries.is_empty()
+            && self.fri_final_value_le == [0u8; 8]
+            && self.fri_params.is_chained()
+            && self.fri_layer_queries.is_empty()
+    }
 }
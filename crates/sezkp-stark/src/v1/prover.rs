@@ -8,8 +8,10 @@
 //!
 //! This file also implements **1A** of the fully-streaming FRI queries: the
 //! layer-0 Merkle paths for FRI are extracted directly from the streamed
-//! layer-0 codeword using `fri_stream::merkle_path_from_le_chunker`, without
-//! materializing a `MerkleTree` for layer-0.
+//! layer-0 codeword using `fri_stream::merkle_paths_from_le_chunker`, without
+//! materializing a `MerkleTree` for layer-0. All layer-0 positions are opened
+//! together, re-streaming the codeword once per tree level rather than once
+//! per level and position.
 
 #![forbid(unsafe_code)]
 #![deny(rust_2018_idioms)]
@@ -30,13 +32,17 @@ use crate::v1::{
     air::{compose_boundary, compose_row, Alphas},
     columns::TraceColumns,
     field::F1,
-    fri_stream::{merkle_path_from_le_chunker, StreamingLayerBuilder},
+    fri_stream::{commit_layer, merkle_paths_from_le_chunker, StreamingLayerBuilder},
     lde::deep_coset_lde_stream,
     masking::{derive_mask_coeffs, eval_masks_sum_at, DEFAULT_MASK_DEG, DEFAULT_N_MASKS},
-    merkle::{hash_field_leaves, MerkleTree},
+    merkle::fri_layer_tree,
     openings::OnDemandOpenings,
-    params,
-    proof::{FriRoots, PerTapeOpen, ProofV1, RowOpenings},
+    params::{self, FriQuerySchedule, StreamTuning},
+    profile::{bytes_of, Profiler, ProveProfile},
+    proof::{
+        FriLayerQuery, FriRoots, InputHeadOpen, PerTapeOpen, ProofParams, ProofV1, PublicInputs,
+        RowOpenings,
+    },
 };
 
 use sezkp_ffts::goldilocks_primitive_root_2exp;
@@ -46,6 +52,17 @@ fn to_le_vec(vals: &[F1]) -> Vec<[u8; 8]> {
     vals.iter().map(|v| v.to_le_bytes()).collect()
 }
 
+/// Temporary bytes to commit one FRI layer of `len` values: LE encodings,
+/// hashed leaves, and the tree's copy of the leaves plus its inner nodes.
+const fn layer_tree_bytes(len: usize) -> u64 {
+    bytes_of::<[u8; 8]>(len) + 3 * bytes_of::<[u8; 32]>(len)
+}
+
+/// Temporary bytes of one LDE/DEEP output chunk: encodings and denominators.
+const fn lde_chunk_bytes(chunk: usize) -> u64 {
+    bytes_of::<[u8; 8]>(chunk) + bytes_of::<F1>(chunk)
+}
+
 #[inline]
 fn next_wrap(idx: usize, len: usize) -> usize {
     if len == 0 {
@@ -58,26 +75,191 @@ fn next_wrap(idx: usize, len: usize) -> usize {
 }
 
 /// Produce a v1 proof (streaming layer-0 root + on-demand column openings + ZK masks).
+///
+/// Zero blocks yield [`ProofV1::empty`] (the manifest root must then be
+/// [`sezkp_core::EMPTY_ROOT`]); a single one-step block yields a proof over a
+/// one-row trace. The proof declares the smallest movement model covering
+/// the blocks' moves ([`ProofParams::for_blocks`]).
 pub fn prove_v1(blocks: &[BlockSummary], manifest_root: [u8; 32]) -> Result<ProofV1> {
+    prove_v1_with_beacon(blocks, manifest_root, None)
+}
+
+/// [`prove_v1`], additionally binding an external randomness `beacon` into
+/// the transcript before any query index is sampled. The beacon is recorded
+/// in the proof so verifiers can compare it with the value they expect.
+pub fn prove_v1_with_beacon(
+    blocks: &[BlockSummary],
+    manifest_root: [u8; 32],
+    beacon: Option<&[u8]>,
+) -> Result<ProofV1> {
+    prove_v1_profiled(blocks, manifest_root, beacon, StreamTuning::default())
+        .map(|(proof, _)| proof)
+}
+
+/// [`prove_v1_with_beacon`] with explicit streaming buffer sizes, also
+/// returning the per-phase timing and temporary-memory breakdown (see
+/// [`crate::v1::profile`]). `tuning` never changes the proof.
+///
+/// # Errors
+/// Fails like [`prove_v1_with_beacon`]: on a manifest root that does not fit
+/// the block count, malformed blocks, or an unsupported movement bound; and
+/// on out-of-range `tuning`.
+pub fn prove_v1_profiled(
+    blocks: &[BlockSummary],
+    manifest_root: [u8; 32],
+    beacon: Option<&[u8]>,
+    tuning: StreamTuning,
+) -> Result<(ProofV1, ProveProfile)> {
+    prove_v1_scheduled(
+        blocks,
+        manifest_root,
+        beacon,
+        tuning,
+        &FriQuerySchedule::Chained,
+    )
+}
+
+/// [`prove_v1_profiled`] with an explicit FRI query `schedule`. The
+/// resolved per-layer counts are recorded in [`ProofV1::fri_params`].
+///
+/// # Errors
+/// Fails like [`prove_v1_profiled`], and on a schedule that does not fit
+/// the proof's layer count (see [`FriQuerySchedule::resolve`]).
+pub fn prove_v1_scheduled(
+    blocks: &[BlockSummary],
+    manifest_root: [u8; 32],
+    beacon: Option<&[u8]>,
+    tuning: StreamTuning,
+    schedule: &FriQuerySchedule,
+) -> Result<(ProofV1, ProveProfile)> {
+    prove_v1_with_input_len(blocks, manifest_root, beacon, tuning, schedule, None)
+}
+
+/// [`prove_v1_scheduled`] for a trace with a declared input tape length,
+/// recorded in [`ProofParams::input_len`] and bound into the transcript.
+///
+/// # Errors
+/// Fails like [`prove_v1_scheduled`], and when `input_len` is given on a
+/// block whose input head leaves `[0, input_len)` or does not chain from
+/// `in_head_in` to `in_head_out` ([`ProofParams::check_input_head`]).
+pub fn prove_v1_with_input_len(
+    blocks: &[BlockSummary],
+    manifest_root: [u8; 32],
+    beacon: Option<&[u8]>,
+    tuning: StreamTuning,
+    schedule: &FriQuerySchedule,
+    input_len: Option<u64>,
+) -> Result<(ProofV1, ProveProfile)> {
+    prove_v1_with_claims(blocks, manifest_root, beacon, tuning, schedule, input_len, None)
+}
+
+/// [`prove_v1_with_input_len`], also binding the digest of the caller's
+/// public claims ([`sezkp_core::claims::claims_digest`]) into the transcript
+/// prelude and recording it in [`PublicInputs::claims`].
+///
+/// # Errors
+/// Fails like [`prove_v1_with_input_len`].
+pub fn prove_v1_with_claims(
+    blocks: &[BlockSummary],
+    manifest_root: [u8; 32],
+    beacon: Option<&[u8]>,
+    tuning: StreamTuning,
+    schedule: &FriQuerySchedule,
+    input_len: Option<u64>,
+    claims: Option<[u8; 32]>,
+) -> Result<(ProofV1, ProveProfile)> {
+    prove_v1_with_queries(
+        blocks,
+        manifest_root,
+        beacon,
+        tuning,
+        schedule,
+        input_len,
+        claims,
+        params::NUM_QUERIES,
+    )
+}
+
+/// [`prove_v1_with_claims`] opening `num_queries` rows (and as many chained
+/// FRI positions) instead of [`params::NUM_QUERIES`].
+///
+/// The count typically comes from [`params::queries_for_security`]. It is
+/// recorded in [`crate::v1::proof::FriParams::num_queries`] and, unless it
+/// is the default, bound into the transcript.
+///
+/// # Errors
+/// Fails like [`prove_v1_with_claims`], and on `num_queries` outside
+/// `1..=MAX_NUM_QUERIES`.
+#[allow(clippy::too_many_arguments)]
+pub fn prove_v1_with_queries(
+    blocks: &[BlockSummary],
+    manifest_root: [u8; 32],
+    beacon: Option<&[u8]>,
+    tuning: StreamTuning,
+    schedule: &FriQuerySchedule,
+    input_len: Option<u64>,
+    claims: Option<[u8; 32]>,
+    num_queries: usize,
+) -> Result<(ProofV1, ProveProfile)> {
+    let _prove = sezkp_core::phase_span!("stark.prove", blocks = blocks.len());
+    tuning.check()?;
+    sezkp_core::check_empty_root(blocks.is_empty(), &manifest_root)?;
+    if blocks.is_empty() {
+        let mut proof = ProofV1 {
+            beacon: beacon.map(<[u8]>::to_vec),
+            ..ProofV1::empty()
+        };
+        proof.public.claims = claims;
+        return Ok((proof, ProveProfile::default()));
+    }
+    let mut profiler = Profiler::new();
+
+    // Movement model declared by the proof (sizes the mv-domain constraint).
+    let proof_params = ProofParams {
+        input_len,
+        aux: sezkp_core::auxiliary::aux_digest(blocks),
+        ..ProofParams::for_blocks(blocks)
+    };
+    proof_params.check_supported()?;
+    proof_params.check_input_head(blocks)?;
+    let max_move = proof_params.max_move;
+
     // 1) Columnar view for AIR composition only.
     // We do NOT commit this view directly; column commitments are streamed.
-    let tc = TraceColumns::build(blocks)?;
+    let tc = TraceColumns::build_with_input_head(blocks, input_len.is_some())?;
+    profiler.alloc(tc.heap_bytes());
+
+    // FRI query schedule; one fold per halving of the LDE domain.
+    let n_folds = (tc.n * params::BLOWUP).trailing_zeros() as usize;
+    let fri_params = schedule.resolve(n_folds, num_queries)?;
 
-    // Transcript prelude.
+    // Transcript prelude: the public inputs, which commit to the declared
+    // parameters and schedule through their digest.
+    let public = PublicInputs {
+        n_rows: tc.n,
+        tau: tc.tau,
+        manifest_root,
+        params_digest: params::params_digest(&proof_params, &fri_params),
+        claims,
+    };
     let mut tr = Blake3Transcript::new(params::DS_V1_DOMAIN);
-    tr.absorb("manifest_root", &manifest_root);
-    tr.absorb_u64("n", tc.n as u64);
-    tr.absorb_u64("tau", tc.tau as u64);
+    params::absorb_public_inputs(&mut tr, &public);
 
     /* ------------------- Column commitments (streamed roots) ---------------- */
 
     // Streamed, chunked column commitments; returns outer roots per label.
-    let mut odo = OnDemandOpenings::new(blocks, params::COL_CHUNK_LOG2);
+    let span = sezkp_core::phase_span!("stark.columns", n = tc.n, tau = tc.tau);
+    let mut odo = OnDemandOpenings::new(blocks, params::COL_CHUNK_LOG2)
+        .with_input_head(input_len.is_some());
     let col_roots = odo.build_roots();
+    sezkp_core::phase_event!(columns = col_roots.len(), "column roots committed");
+    drop(span);
+    profiler.end_phase("columns");
 
     tr.absorb_u64(params::DS_N_COLS, col_roots.len() as u64);
     for r in &col_roots {
         tr.absorb(params::DS_COL_ROOT, &r.root);
+        tr.absorb_u64(params::DS_COL_PER_BLOCK, u64::from(r.per_block));
     }
 
     /* ------------------------- Derive AIR alphas ---------------------------- */
@@ -122,20 +304,17 @@ pub fn prove_v1(blocks: &[BlockSummary], manifest_root: [u8; 32]) -> Result<Proo
         let one = F1::from_u64(1);
         let shift_inv = shift.inv();
         // z lies on the coset iff (z/shift)^(2^k) == 1
-        let is_on_coset = |zz: F1| {
-            let mut t = zz * shift_inv; // z/shift
-            for _ in 0..lde_k_log2 {
-                t = t * t;
-            }
-            t == one
-        };
+        let is_on_coset = |zz: F1| (zz * shift_inv).exp_power_of_2(lde_k_log2) == one;
         while is_on_coset(z) {
             z = z + one; // deterministic nudge off the coset
         }
     }
 
+    let span = sezkp_core::phase_span!("stark.lde", lde_n);
+
     // Keep layer-0 root streaming-only, but also collect values for higher layers.
     let mut lde_vals: Vec<F1> = Vec::with_capacity(lde_n);
+    profiler.alloc(bytes_of::<F1>(lde_n));
     let mut l0_builder = StreamingLayerBuilder::new(lde_n);
 
     // Base-domain composition with ZK mask R(ω^i), streamed into LDE/DEEP engine.
@@ -152,13 +331,14 @@ pub fn prove_v1(blocks: &[BlockSummary], manifest_root: [u8; 32]) -> Result<Proo
         }
         last_i = i;
 
-        let comp = compose_row(&tc, i, &alphas) + compose_boundary(&tc, i, &alphas);
+        let comp = compose_row(&tc, i, &alphas, max_move) + compose_boundary(&tc, i, &alphas);
         let mask = eval_masks_sum_at(&mask_coeffs, x_pow);
         (comp + mask).to_le_bytes()
     };
 
     // Emit layer-0 values in chunks (elements), keeping memory flat.
-    let out_chunk_log2 = 12usize; // 4096 elems/chunk
+    let out_chunk_log2 = usize::from(tuning.lde_chunk_log2);
+    profiler.transient(lde_chunk_bytes(tuning.lde_chunk().min(lde_n)));
     deep_coset_lde_stream(
         &mut base_eval,
         tc.n,
@@ -177,9 +357,13 @@ pub fn prove_v1(blocks: &[BlockSummary], manifest_root: [u8; 32]) -> Result<Proo
         },
     );
     debug_assert_eq!(lde_vals.len(), lde_n, "LDE stream size mismatch");
+    drop(span);
+    profiler.end_phase("lde_layer0");
 
     /* ------------------- FRI: commit roots with O(n/2) scratch -------------- */
 
+    let span = sezkp_core::phase_span!("stark.fri_commit", lde_n);
+
     // Bind layer-0 root BEFORE sampling β.
     let mut fri_roots_vec = Vec::<[u8; 32]>::with_capacity(lde_k_log2 + 1);
     {
@@ -188,18 +372,14 @@ pub fn prove_v1(blocks: &[BlockSummary], manifest_root: [u8; 32]) -> Result<Proo
         fri_roots_vec.push(root0);
     }
 
-    // Number of folds and betas (after binding root0).
-    let mut tmp = lde_n;
-    let mut n_folds = 0usize;
-    while tmp > 1 {
-        tmp >>= 1;
-        n_folds += 1;
-    }
+    // Betas for the `n_folds` folds (after binding root0).
+    debug_assert_eq!(lde_n >> n_folds, 1, "LDE domain must fold to one value");
     let betas = params::derive_betas_for_fri(&mut tr, n_folds);
 
     // Fold in-place into `scratch`, committing each layer root.
     let mut cur_len = lde_n;
     let mut scratch = vec![F1::from_u64(0); lde_n / 2];
+    profiler.alloc(bytes_of::<F1>(scratch.len()));
 
     if n_folds > 0 {
         // First fold from layer-0 → layer-1
@@ -210,14 +390,11 @@ pub fn prove_v1(blocks: &[BlockSummary], manifest_root: [u8; 32]) -> Result<Proo
         }
         cur_len = next_len;
 
-        // Root for layer 1
-        {
-            let leaves = hash_field_leaves(&to_le_vec(&scratch[..cur_len]));
-            let mt = MerkleTree::from_leaves(&leaves);
-            let root1 = mt.root();
-            tr.absorb(params::DS_FRI_LAYER_ROOT, &root1);
-            fri_roots_vec.push(root1);
-        }
+        // Layer roots are hashed through a bounded staging buffer.
+        profiler.transient(bytes_of::<[u8; 8]>(tuning.fri_buf().min(cur_len)));
+        let root1 = commit_layer(&scratch[..cur_len], tuning.fri_buf_log2);
+        tr.absorb(params::DS_FRI_LAYER_ROOT, &root1);
+        fri_roots_vec.push(root1);
 
         // Remaining folds (layer r → r+1)
         for r in 1..n_folds {
@@ -230,9 +407,7 @@ pub fn prove_v1(blocks: &[BlockSummary], manifest_root: [u8; 32]) -> Result<Proo
             }
             cur_len = half;
 
-            let leaves = hash_field_leaves(&to_le_vec(&scratch[..cur_len]));
-            let mt = MerkleTree::from_leaves(&leaves);
-            let root = mt.root();
+            let root = commit_layer(&scratch[..cur_len], tuning.fri_buf_log2);
             tr.absorb(params::DS_FRI_LAYER_ROOT, &root);
             fri_roots_vec.push(root);
         }
@@ -241,11 +416,17 @@ pub fn prove_v1(blocks: &[BlockSummary], manifest_root: [u8; 32]) -> Result<Proo
     // Final FRI value y* (the single element of the last layer).
     let final_val = if n_folds == 0 { lde_vals[0] } else { scratch[0] };
     let fri_final_value_le = final_val.to_le_bytes();
+    sezkp_core::phase_event!(layers = fri_roots_vec.len(), "FRI layer roots committed");
+    drop(span);
+    profiler.end_phase("fri_folds");
 
     /* ------------------------ AIR query row openings ------------------------ */
 
-    // Sample base-row indices AFTER FRI roots were absorbed (keeps schedule aligned).
-    let rows = params::derive_queries(&mut tr, tc.n, params::NUM_QUERIES);
+    // Sample base-row indices AFTER FRI roots (and the optional beacon) were
+    // absorbed (keeps schedule aligned).
+    params::absorb_beacon(&mut tr, beacon);
+    let rows = params::derive_queries(&mut tr, tc.n, fri_params.num_queries);
+    let span = sezkp_core::phase_span!("stark.openings", queries = rows.len());
 
     // On-demand openings against streamed column commitments.
     let mut query_openings = Vec::with_capacity(rows.len());
@@ -255,8 +436,17 @@ pub fn prove_v1(blocks: &[BlockSummary], manifest_root: [u8; 32]) -> Result<Proo
         let is_first_open = odo.open("is_first", row);
         let is_last_open = odo.open("is_last", row);
 
-        // Per-tape (also open next-row values used by head-update).
+        // Input head, if declared (plus the next-row values its update reads).
         let ip1 = next_wrap(row, tc.n);
+        let input_head = input_len.map(|_| InputHeadOpen {
+            in_head: odo.open("in_head", row),
+            next_in_head: odo.open("in_head", ip1),
+            next_input_mv: odo.open("input_mv", ip1),
+            in_head_in: odo.open("in_head_in", row),
+            in_head_out: odo.open("in_head_out", row),
+        });
+
+        // Per-tape (also open next-row values used by head-update).
         let mut per_tape = Vec::with_capacity(tc.tau);
         for r in 0..tc.tau {
             let mv_o = odo.open(&format!("mv_{r}"), row);
@@ -288,13 +478,33 @@ pub fn prove_v1(blocks: &[BlockSummary], manifest_root: [u8; 32]) -> Result<Proo
             is_first: is_first_open,
             is_last: is_last_open,
             input_mv: input_mv_open,
+            input_head,
         });
     }
 
+    drop(span);
+    profiler.end_phase("air_openings");
+
     /* ------------------- FRI queries (layer-0 streaming) -------------------- */
 
-    // After roots are bound into the transcript, derive FRI query indices.
-    let fri_rows = params::derive_queries(&mut tr, lde_n, params::NUM_QUERIES);
+    // After roots are bound into the transcript, derive FRI query indices:
+    // layer-0 positions followed through every layer (chained), or fresh
+    // positions per layer.
+    let fri_rows = if fri_params.is_chained() {
+        params::derive_queries(&mut tr, lde_n, fri_params.num_queries)
+    } else {
+        Vec::new()
+    };
+    let layer_rows: Vec<Vec<usize>> = fri_params
+        .layer_queries
+        .iter()
+        .enumerate()
+        .map(|(l, &k)| params::derive_layer_queries(&mut tr, l, lde_n >> l, k))
+        .collect();
+    let span = sezkp_core::phase_span!(
+        "stark.fri_queries",
+        queries = fri_rows.len() + layer_rows.iter().map(Vec::len).sum::<usize>()
+    );
 
     // Number of layers = roots.len(); emit exactly (n_layers - 1) pairs per query.
     let n_layers = fri_roots_vec.len();
@@ -308,84 +518,53 @@ pub fn prove_v1(blocks: &[BlockSummary], manifest_root: [u8; 32]) -> Result<Proo
         });
     }
 
+    // Open layer-0 indices via a fresh, stateless chunker.
+    let open_l0 = |idxs: &[usize]| {
+        merkle_paths_from_le_chunker(
+            lde_n,
+            |sink: &mut dyn FnMut(&[[u8; 8]])| {
+                // Fresh local state per run.
+                let mut last_i_q = 0usize;
+                let mut x_pow_q = F1::from_u64(1);
+                let mut base_eval_q = |i: usize| -> [u8; 8] {
+                    if i < last_i_q {
+                        last_i_q = 0;
+                        x_pow_q = F1::from_u64(1);
+                    }
+                    for _ in last_i_q..i {
+                        x_pow_q = x_pow_q * w_base;
+                    }
+                    last_i_q = i;
+
+                    let comp = compose_row(&tc, i, &alphas, max_move)
+                        + compose_boundary(&tc, i, &alphas);
+                    let mask = eval_masks_sum_at(&mask_coeffs, x_pow_q);
+                    (comp + mask).to_le_bytes()
+                };
+
+                deep_coset_lde_stream(
+                    &mut base_eval_q,
+                    tc.n,
+                    blow_log2,
+                    shift,
+                    z,
+                    out_chunk_log2,
+                    |chunk| sink(chunk),
+                );
+            },
+            idxs,
+        )
+    };
+
     // --- Layer 0: open **streaming** against the layer-0 codeword.
     {
-        let n0 = lde_n;
-        let half0 = n0 / 2;
-
-        for (qi, &idx0) in fri_rows.iter().enumerate() {
-            let j0 = idx0 ^ half0;
-
-            // Open idx0 via a fresh, stateless chunker.
-            let (vi0_le, pi0_sibs) = merkle_path_from_le_chunker(
-                n0,
-                |sink: &mut dyn FnMut(&[[u8; 8]])| {
-                    // Fresh local state per run.
-                    let mut last_i_q = 0usize;
-                    let mut x_pow_q = F1::from_u64(1);
-                    let mut base_eval_q = |i: usize| -> [u8; 8] {
-                        if i < last_i_q {
-                            last_i_q = 0;
-                            x_pow_q = F1::from_u64(1);
-                        }
-                        for _ in last_i_q..i {
-                            x_pow_q = x_pow_q * w_base;
-                        }
-                        last_i_q = i;
-
-                        let comp =
-                            compose_row(&tc, i, &alphas) + compose_boundary(&tc, i, &alphas);
-                        let mask = eval_masks_sum_at(&mask_coeffs, x_pow_q);
-                        (comp + mask).to_le_bytes()
-                    };
-
-                    deep_coset_lde_stream(
-                        &mut base_eval_q,
-                        tc.n,
-                        blow_log2,
-                        shift,
-                        z,
-                        out_chunk_log2,
-                        |chunk| sink(chunk),
-                    );
-                },
-                idx0,
-            );
-
-            // Open j0 via another fresh, stateless chunker.
-            let (vj0_le, pj0_sibs) = merkle_path_from_le_chunker(
-                n0,
-                |sink: &mut dyn FnMut(&[[u8; 8]])| {
-                    let mut last_i_q = 0usize;
-                    let mut x_pow_q = F1::from_u64(1);
-                    let mut base_eval_q = |i: usize| -> [u8; 8] {
-                        if i < last_i_q {
-                            last_i_q = 0;
-                            x_pow_q = F1::from_u64(1);
-                        }
-                        for _ in last_i_q..i {
-                            x_pow_q = x_pow_q * w_base;
-                        }
-                        last_i_q = i;
-
-                        let comp =
-                            compose_row(&tc, i, &alphas) + compose_boundary(&tc, i, &alphas);
-                        let mask = eval_masks_sum_at(&mask_coeffs, x_pow_q);
-                        (comp + mask).to_le_bytes()
-                    };
-
-                    deep_coset_lde_stream(
-                        &mut base_eval_q,
-                        tc.n,
-                        blow_log2,
-                        shift,
-                        z,
-                        out_chunk_log2,
-                        |chunk| sink(chunk),
-                    );
-                },
-                j0,
-            );
+        let half0 = lde_n / 2;
+
+        let idxs: Vec<usize> = fri_rows.iter().flat_map(|&i| [i, i ^ half0]).collect();
+        let opened = open_l0(&idxs);
+        for (qi, (&idx0, pair)) in fri_rows.iter().zip(opened.chunks_exact(2)).enumerate() {
+            let (vi0_le, pi0_sibs) = pair[0].clone();
+            let (vj0_le, pj0_sibs) = pair[1].clone();
 
             fri_queries[qi].positions[0] = idx0;
             if n_layers > 1 {
@@ -398,7 +577,7 @@ pub fn prove_v1(blocks: &[BlockSummary], manifest_root: [u8; 32]) -> Result<Proo
     }
 
     // --- Layers 1..(n_layers-2): open on current layer, then fold.
-    if n_layers > 1 {
+    if n_layers > 1 && !fri_rows.is_empty() {
         // Compute layer 1 values (from layer 0) once, across halves.
         let mut cur_len_q = lde_n / 2;
         {
@@ -413,8 +592,8 @@ pub fn prove_v1(blocks: &[BlockSummary], manifest_root: [u8; 32]) -> Result<Proo
             let half = cur_len_q / 2;
 
             // Open on layer r (currently in scratch[..cur_len_q]).
-            let leaves_r = hash_field_leaves(&to_le_vec(&scratch[..cur_len_q]));
-            let mt_r = MerkleTree::from_leaves(&leaves_r);
+            profiler.transient(layer_tree_bytes(cur_len_q));
+            let mt_r = fri_layer_tree(&to_le_vec(&scratch[..cur_len_q]));
 
             for qi in 0..fri_rows.len() {
                 let idx_r = fri_queries[qi].positions[r];
@@ -449,14 +628,111 @@ pub fn prove_v1(blocks: &[BlockSummary], manifest_root: [u8; 32]) -> Result<Proo
         }
     }
 
-    Ok(ProofV1 {
-        manifest_root,
-        tau: tc.tau,
+    // --- Per-layer queries: each fold ℓ → ℓ+1 opens its own positions.
+    let fri_layer_queries = if layer_rows.is_empty() {
+        Vec::new()
+    } else {
+        open_layer_queries(
+            &layer_rows,
+            open_l0,
+            &lde_vals,
+            &betas,
+            &mut scratch,
+            &mut profiler,
+        )
+    };
+
+    drop(span);
+    profiler.end_phase("fri_openings");
+
+    let proof = ProofV1 {
+        public,
         domain_n: lde_n,
         col_roots,
         queries: query_openings,
         fri_roots: FriRoots { roots: fri_roots_vec },
         fri_queries,
         fri_final_value_le,
-    })
+        fri_params,
+        fri_layer_queries,
+        beacon: beacon.map(<[u8]>::to_vec),
+        params: proof_params,
+    };
+    Ok((proof, profiler.finish()))
+}
+
+/// Open per-layer FRI queries. Layer 0 is opened through `open_l0`, all
+/// positions in one call; every later layer is folded into `scratch` and
+/// committed once, serving both the pairs drawn on it and the folded values
+/// of the layer below.
+fn open_layer_queries(
+    layer_rows: &[Vec<usize>],
+    open_l0: impl Fn(&[usize]) -> Vec<([u8; 8], Vec<[u8; 32]>)>,
+    lde_vals: &[F1],
+    betas: &[F1],
+    scratch: &mut [F1],
+    profiler: &mut Profiler,
+) -> Vec<Vec<FriLayerQuery>> {
+    let half0 = lde_vals.len() / 2;
+    let mut out: Vec<Vec<FriLayerQuery>> = Vec::with_capacity(layer_rows.len());
+    let idxs: Vec<usize> = layer_rows[0].iter().flat_map(|&i| [i, i ^ half0]).collect();
+    let opened = open_l0(&idxs);
+    out.push(
+        layer_rows[0]
+            .iter()
+            .zip(opened.chunks_exact(2))
+            .map(|(&idx, pair)| {
+                let ((vi, pi), (vj, pj)) = (pair[0].clone(), pair[1].clone());
+                FriLayerQuery {
+                    position: idx,
+                    pair: (vi, pi, vj, pj),
+                    next: ([0u8; 8], Vec::new()),
+                }
+            })
+            .collect(),
+    );
+
+    // Layer 1 from layer 0, then one layer per iteration (the last is the
+    // single final value).
+    let mut cur_len = half0;
+    for i in 0..cur_len {
+        scratch[i] = lde_vals[i] + betas[0] * lde_vals[i + cur_len];
+    }
+    for r in 1..=layer_rows.len() {
+        profiler.transient(layer_tree_bytes(cur_len));
+        let layer = &scratch[..cur_len];
+        let mt = fri_layer_tree(&to_le_vec(layer));
+
+        // Folded values of layer r - 1's queries.
+        for q in &mut out[r - 1] {
+            let idx = q.position % cur_len;
+            q.next = (layer[idx].to_le_bytes(), mt.open(idx).sibs);
+        }
+        let Some(rows) = layer_rows.get(r) else {
+            break;
+        };
+
+        let half = cur_len / 2;
+        out.push(
+            rows.iter()
+                .map(|&idx| FriLayerQuery {
+                    position: idx,
+                    pair: (
+                        layer[idx].to_le_bytes(),
+                        mt.open(idx).sibs,
+                        layer[idx ^ half].to_le_bytes(),
+                        mt.open(idx ^ half).sibs,
+                    ),
+                    next: ([0u8; 8], Vec::new()),
+                })
+                .collect(),
+        );
+
+        // Fold r → r+1
+        for i in 0..half {
+            scratch[i] = scratch[i] + betas[r] * scratch[i + half];
+        }
+        cur_len = half;
+    }
+    out
 }
@@ -15,31 +15,54 @@
     clippy::expect_used
 )]
 
+#[cfg(feature = "streaming")]
+use anyhow::Context;
 use anyhow::{bail, ensure, Result};
-use sezkp_core::BlockSummary;
-use sezkp_crypto::{Blake3Transcript, Transcript};
+#[cfg(feature = "streaming")]
+use sezkp_core::auxiliary::AuxDigest;
+use sezkp_core::{BlockSummary, DEFAULT_MAX_MOVE};
 use std::collections::HashMap;
 
+#[cfg(feature = "streaming")]
+use crate::v1::openings::ColumnRootBuilder;
 use crate::v1::{
     air::{compose_boundary_from_openings, compose_row_from_openings, Alphas, RowView},
+    challenges,
     field::F1,
     fri::fri_verify,
-    masking::{derive_mask_coeffs, DEFAULT_MASK_DEG, DEFAULT_N_MASKS},
-    merkle::verify_chunked_open,
+    merkle::{verify_block_open, verify_chunked_open},
     params,
-    proof::ProofV1,
+    proof::{BlockOpening, ColumnOpening, Opening, ProofV1},
 };
 
-fn verify_opening(
-    root_map: &HashMap<String, [u8; 32]>,
+/// Check `open` against the root of column `label` as its opening at `row`,
+/// in the layout the root was committed with (see
+/// [`crate::v1::proof::ColumnRoot::per_block`]).
+fn verify_column_opening(
+    root_map: &HashMap<String, (bool, [u8; 32])>,
     label: &str,
-    open: &crate::v1::proof::Opening,
+    open: &ColumnOpening,
+    row: usize,
+    bounds: Option<&[usize]>,
 ) -> Result<()> {
-    let root = root_map
+    let &(per_block, root) = root_map
         .get(label)
         .ok_or_else(|| anyhow::anyhow!("missing col root for {label}"))?;
+    match open {
+        ColumnOpening::Chunked(o) if !per_block => verify_opening(root, label, o, row),
+        ColumnOpening::Block(o) if per_block => verify_block_opening(root, label, o, row, bounds),
+        _ => bail!("opening of column {label} @ {row} does not match its committed layout"),
+    }
+}
+
+/// Check a chunked opening: it must authenticate `row` under `root`.
+fn verify_opening(root: [u8; 32], label: &str, open: &Opening, row: usize) -> Result<()> {
+    ensure!(
+        opened_row(open) == row,
+        "opening of column {label} is not at row {row}"
+    );
     let ok = verify_chunked_open(
-        *root,
+        root,
         label,
         open.value_le,
         open.chunk_root,
@@ -48,51 +71,407 @@ fn verify_opening(
         open.chunk_index,
         &open.path_to_chunk,
     );
+    ensure!(ok, "chunked merkle path failed for column {label} @ {row}");
+    Ok(())
+}
+
+/// Check a per-block opening at `row`: its leaf must cover the row and lead
+/// to `root`.
+///
+/// Given the blocks' row bounds (see [`block_bounds`]), the leaf must also be
+/// the one of the block holding `row`; otherwise the root itself must have
+/// been recomputed from the blocks, which already pins every leaf.
+fn verify_block_opening(
+    root: [u8; 32],
+    label: &str,
+    open: &BlockOpening,
+    row: usize,
+    bounds: Option<&[usize]>,
+) -> Result<()> {
     ensure!(
-        ok,
-        "chunked merkle path failed for column {label} @ {}",
-        open.index
+        (open.row_lo..=open.row_hi).contains(&row),
+        "block opening of column {label} covers rows {}..={}, not row {row}",
+        open.row_lo,
+        open.row_hi
+    );
+    if let Some(bounds) = bounds {
+        let k = bounds.partition_point(|&s| s <= row).saturating_sub(1);
+        let want = bounds.get(k).zip(bounds.get(k + 1));
+        let depth = (bounds.len() - 1).next_power_of_two().trailing_zeros() as usize;
+        // `row_hi` comes from the proof: compare without overflowing.
+        let end = open.row_hi.checked_add(1);
+        ensure!(
+            matches!(want, Some((&lo, &hi)) if lo == open.row_lo && Some(hi) == end)
+                && open.block_index == k
+                && open.path.len() == depth,
+            "block opening of column {label} @ {row} is not the one of block #{k}"
+        );
+    }
+    let ok = verify_block_open(
+        root,
+        label,
+        open.value_le,
+        open.row_lo,
+        open.row_hi,
+        open.block_index,
+        &open.path,
     );
+    ensure!(ok, "block merkle path failed for column {label} @ {row}");
     Ok(())
 }
 
+/// First row of every block, then the total row count.
+fn block_bounds(blocks: &[BlockSummary]) -> Vec<usize> {
+    let mut out = Vec::with_capacity(blocks.len() + 1);
+    out.push(0usize);
+    for b in blocks {
+        let rows = (b.step_hi.saturating_sub(b.step_lo) + 1) as usize;
+        out.push(out[out.len() - 1] + rows);
+    }
+    out
+}
+
+/// Row an opening's chunked Merkle path authenticates.
+const fn opened_row(open: &Opening) -> usize {
+    (open.chunk_index << params::COL_CHUNK_LOG2) + open.index_in_chunk
+}
+
 /// Verify a v1 proof end-to-end against block metadata (τ) and transcript schedule.
+///
+/// A beacon recorded in the proof is replayed into the transcript but not
+/// compared with anything; use [`verify_v1_with_beacon`] to require one.
 pub fn verify_v1(proof: &ProofV1, blocks: &[BlockSummary]) -> Result<()> {
-    /* -------------------------- Shape & sanity checks ----------------------- */
+    verify_v1_with_beacon(proof, blocks, None)
+}
+
+/// [`verify_v1`], additionally requiring that the proof was bound to
+/// `expected_beacon` (when given) before its queries were sampled.
+pub fn verify_v1_with_beacon(
+    proof: &ProofV1,
+    blocks: &[BlockSummary],
+    expected_beacon: Option<&[u8]>,
+) -> Result<()> {
+    verify_v1_against(proof, &BlockFacts::of(blocks), expected_beacon)
+}
+
+/// What [`verify_v1_with_beacon`] reads from the blocks: their count, the
+/// first block's tape count, the auxiliary-commitment digest and the row
+/// bounds. Derived once, it serves every proof over the same blocks.
+#[derive(Clone, Debug)]
+pub struct BlockFacts {
+    n_blocks: usize,
+    first_tau: Option<usize>,
+    aux: Option<[u8; 32]>,
+    bounds: Vec<usize>,
+}
+
+impl BlockFacts {
+    /// Derive the facts of `blocks`.
+    #[must_use]
+    pub fn of(blocks: &[BlockSummary]) -> Self {
+        Self {
+            n_blocks: blocks.len(),
+            first_tau: blocks.first().map(|b| b.windows.len()),
+            aux: sezkp_core::auxiliary::aux_digest(blocks),
+            bounds: block_bounds(blocks),
+        }
+    }
+}
+
+/// What a verifier requires of the parameters a proof declares, beyond
+/// their consistency with the proof itself.
+///
+/// The plain entry points require [`Self::default`]; the `_under` variants
+/// take it explicitly, e.g. to accept deliberately weak test proofs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Requirements {
+    /// Least security level (bits, as [`FriParams::security_bits`]
+    /// estimates it). The empty proof opens nothing and is exempt.
+    ///
+    /// [`FriParams::security_bits`]: crate::v1::proof::FriParams::security_bits
+    pub min_security_bits: usize,
+    /// Movement model the proof must declare
+    /// ([`crate::v1::proof::ProofParams::max_move`]).
+    pub max_move: u8,
+}
+
+impl Default for Requirements {
+    fn default() -> Self {
+        Self {
+            min_security_bits: params::DEFAULT_MIN_SECURITY_BITS,
+            max_move: DEFAULT_MAX_MOVE,
+        }
+    }
+}
+
+impl Requirements {
+    /// The defaults, with the minimum security level from
+    /// [`params::ENV_MIN_SECURITY_BITS`] and the movement model from
+    /// [`params::ENV_EXPECT_MAX_MOVE`] if set.
+    ///
+    /// # Errors
+    /// Fails wherever [`params::min_security_bits_from_env`] or
+    /// [`params::expected_max_move_from_env`] does.
+    pub fn from_env() -> Result<Self> {
+        let mut req = Self::default();
+        if let Some(bits) = params::min_security_bits_from_env()? {
+            req.min_security_bits = bits;
+        }
+        if let Some(max_move) = params::expected_max_move_from_env()? {
+            req.max_move = max_move;
+        }
+        Ok(req)
+    }
+
+    fn check(self, proof: &ProofV1) -> Result<()> {
+        ensure!(
+            proof.params.max_move == self.max_move,
+            "proof declares movement bound ±{}, expected ±{}",
+            proof.params.max_move,
+            self.max_move
+        );
+        // Recomputed from the proof's own query counts; the figures the
+        // prover records in an artifact's `meta` are never trusted.
+        let bits = proof.fri_params.security_bits();
+        ensure!(
+            bits >= self.min_security_bits,
+            "proof reaches an estimated {bits} security bits, below the required {}",
+            self.min_security_bits
+        );
+        Ok(())
+    }
+}
+
+/// [`verify_v1_with_beacon`] given the blocks' [`BlockFacts`].
+///
+/// # Errors
+/// Fails wherever [`verify_v1_with_beacon`] does.
+pub fn verify_v1_against(
+    proof: &ProofV1,
+    facts: &BlockFacts,
+    expected_beacon: Option<&[u8]>,
+) -> Result<()> {
+    verify_v1_under(proof, facts, expected_beacon, &Requirements::default())
+}
+
+/// [`verify_v1_against`] under explicit [`Requirements`].
+///
+/// # Errors
+/// Fails wherever [`verify_v1_with_beacon`] does, or if the proof falls
+/// short of `req`.
+pub fn verify_v1_under(
+    proof: &ProofV1,
+    facts: &BlockFacts,
+    expected_beacon: Option<&[u8]>,
+    req: &Requirements,
+) -> Result<()> {
+    verify_transcript(
+        proof,
+        facts.n_blocks,
+        facts.first_tau,
+        facts.aux,
+        Some(&facts.bounds),
+        expected_beacon,
+        *req,
+    )
+}
+
+/// [`verify_v1_with_beacon`] over a block stream, additionally recomputing
+/// every column root from the blocks (see [`verify_col_roots`]).
+///
+/// Holds one block at a time, so memory stays O(chunk) per column however
+/// long the trace is. Needs the `streaming` feature.
+///
+/// # Errors
+/// Fails on the first stream error, on a column root the blocks do not
+/// reproduce, or wherever [`verify_v1_with_beacon`] would.
+#[cfg(feature = "streaming")]
+pub fn verify_v1_stream<I>(proof: &ProofV1, blocks: I, expected_beacon: Option<&[u8]>) -> Result<()>
+where
+    I: IntoIterator<Item = Result<BlockSummary>>,
+{
+    verify_v1_stream_under(proof, blocks, expected_beacon, &Requirements::default())
+}
+
+/// [`verify_v1_stream`] under explicit [`Requirements`].
+///
+/// # Errors
+/// Fails wherever [`verify_v1_stream`] does, or if the proof falls short of
+/// `req`.
+#[cfg(feature = "streaming")]
+pub fn verify_v1_stream_under<I>(
+    proof: &ProofV1,
+    blocks: I,
+    expected_beacon: Option<&[u8]>,
+    req: &Requirements,
+) -> Result<()>
+where
+    I: IntoIterator<Item = Result<BlockSummary>>,
+{
+    let mut aux = AuxDigest::new();
+    let blocks = blocks.into_iter().inspect(|b| {
+        if let Ok(b) = b {
+            aux.push(b);
+        }
+    });
+    let n_blocks = verify_col_roots(proof, blocks)?;
+    // `verify_col_roots` already held every block to `proof.public.tau`,
+    // and recomputed every root and layout, which pins the per-block leaves
+    // to the blocks' row ranges.
+    let first_tau = (n_blocks > 0).then_some(proof.public.tau);
+    verify_transcript(
+        proof,
+        n_blocks,
+        first_tau,
+        aux.finish(),
+        None,
+        expected_beacon,
+        *req,
+    )
+}
 
-    let blow = params::BLOWUP;
-    ensure!(blow.is_power_of_two(), "BLOWUP must be a power of two");
+/// Recompute the column roots of `proof` by streaming `blocks` through the
+/// prover's chunked column hashing and compare them with `proof.col_roots`.
+/// Returns the number of blocks read.
+///
+/// The openings check in [`verify_v1`] only ties the queried rows to the
+/// committed roots; this ties the roots themselves to the blocks.
+///
+/// # Errors
+/// Fails on the first stream error, on a block whose shape does not match
+/// the proof (including its input head, under a declared input length), on a
+/// row count other than the proof's trace length, or on the first column
+/// root that differs.
+#[cfg(feature = "streaming")]
+pub fn verify_col_roots<I>(proof: &ProofV1, blocks: I) -> Result<usize>
+where
+    I: IntoIterator<Item = Result<BlockSummary>>,
+{
+    let _span = sezkp_core::phase_span!("stark.verify_col_roots", tau = proof.public.tau);
+    let mut builder = ColumnRootBuilder::with_input_head(
+        proof.public.tau,
+        params::COL_CHUNK_LOG2,
+        proof.params.input_len.is_some(),
+    );
+    // The empty proof commits to no columns; only count what it was given.
+    let empty = proof.domain_n == 0;
+    let mut n_blocks = 0usize;
+    for b in blocks {
+        let b = b.with_context(|| format!("reading block #{n_blocks}"))?;
+        if !empty {
+            builder.push_block(&b)?;
+            proof.params.check_input_head(std::slice::from_ref(&b))?;
+        }
+        n_blocks += 1;
+    }
+
+    if empty {
+        ensure!(n_blocks == 0, "empty proof presented for {n_blocks} blocks");
+        ensure!(proof.col_roots.is_empty(), "malformed empty proof");
+        return Ok(0);
+    }
+
+    let n = proof.domain_n / params::BLOWUP;
+    ensure!(
+        builder.n_rows() == n,
+        "blocks have {} rows, proof covers {n}",
+        builder.n_rows()
+    );
+    let want = builder.finish();
     ensure!(
-        proof.domain_n % blow == 0,
-        "FRI domain_n not multiple of blowup"
+        proof.col_roots.len() == want.len(),
+        "column count mismatch (expected {}, got {})",
+        want.len(),
+        proof.col_roots.len()
     );
-    let n = proof.domain_n / blow;
-    ensure!(n.is_power_of_two(), "trace length n must be a power of two");
+    for (got, want) in proof.col_roots.iter().zip(&want) {
+        ensure!(
+            got.label == want.label,
+            "column label mismatch: got {}, expected {}",
+            got.label,
+            want.label
+        );
+        ensure!(
+            got.per_block == want.per_block,
+            "column layout mismatch for {}",
+            want.label
+        );
+        ensure!(
+            got.root == want.root,
+            "column root mismatch for {}",
+            want.label
+        );
+    }
+    Ok(n_blocks)
+}
 
-    let tau = proof.tau;
-    if let Some(b0) = blocks.first() {
+/// Everything but the column-root recomputation, given only the block count,
+/// the first block's tape count, the blocks' auxiliary-commitment digest and,
+/// unless the roots were recomputed, their row bounds.
+fn verify_transcript(
+    proof: &ProofV1,
+    n_blocks: usize,
+    first_tau: Option<usize>,
+    aux: Option<[u8; 32]>,
+    bounds: Option<&[usize]>,
+    expected_beacon: Option<&[u8]>,
+    req: Requirements,
+) -> Result<()> {
+    let _verify = sezkp_core::phase_span!("stark.verify", blocks = n_blocks);
+    ensure!(
+        proof.params.aux == aux,
+        "proof binds other auxiliary commitments than the blocks carry"
+    );
+    if let Some(want) = expected_beacon {
         ensure!(
-            b0.windows.len() == tau,
-            "tau mismatch vs. block windows: got {}, expected {}",
-            tau,
-            b0.windows.len()
+            proof.beacon.as_deref() == Some(want),
+            "proof is not bound to the expected beacon"
         );
     }
+    // Only the digest is absorbed; the parameters the checks below read
+    // must be supported and be the ones it commits to.
+    proof.params.check_supported()?;
+    ensure!(
+        proof.public.params_digest == params::params_digest(&proof.params, &proof.fri_params),
+        "public inputs commit to other parameters than the proof declares"
+    );
 
-    /* --------------------- Transcript prelude + col roots ------------------- */
+    /* ------------------------------ Empty input ----------------------------- */
 
-    let mut tr = Blake3Transcript::new(params::DS_V1_DOMAIN);
-    tr.absorb("manifest_root", &proof.manifest_root);
-    tr.absorb_u64("n", n as u64);
-    tr.absorb_u64("tau", tau as u64);
-    tr.absorb_u64(params::DS_N_COLS, proof.col_roots.len() as u64);
-    for cr in &proof.col_roots {
-        tr.absorb(params::DS_COL_ROOT, &cr.root);
+    // The empty proof covers zero blocks and nothing else; every other proof
+    // must be bound to a non-empty manifest.
+    if proof.domain_n == 0 {
+        ensure!(proof.is_empty_proof(), "malformed empty proof");
+        ensure!(n_blocks == 0, "empty proof presented for {n_blocks} blocks");
+        return sezkp_core::check_empty_root(true, &proof.public.manifest_root);
     }
+    sezkp_core::check_empty_root(false, &proof.public.manifest_root)?;
 
-    /* -------------------------------- Alphas -------------------------------- */
+    /* -------------------------- Shape & sanity checks ----------------------- */
+
+    let n = challenges::trace_rows(proof)?;
 
-    let a = params::derive_alphas(&mut tr);
+    // The declared movement model sizes the mv-domain constraint below.
+    let max_move = proof.params.max_move;
+    proof.fri_params.check(proof.domain_n.trailing_zeros() as usize)?;
+    req.check(proof)?;
+
+    let tau = proof.public.tau;
+    if let Some(block_tau) = first_tau {
+        ensure!(
+            block_tau == tau,
+            "tau mismatch vs. block windows: got {}, expected {}",
+            tau,
+            block_tau
+        );
+    }
+
+    /* ------------------------------ Challenges ------------------------------ */
+
+    // Every draw goes through `challenges::draw`, so a recorded trace holds
+    // exactly the values checked below.
+    let (drawn, mut tr) = challenges::draw(proof, n);
+    let a: Vec<F1> = drawn.alphas.iter().map(|&x| F1::from_u64(x)).collect();
     let alphas = Alphas {
         bool_flag: a[0],
         mv_domain: a[1],
@@ -107,28 +486,8 @@ pub fn verify_v1(proof: &ProofV1, blocks: &[BlockSummary]) -> Result<()> {
         boundary_last: a[2],
     };
 
-    /* ------------- ZK mask draws (alignment only; not used directly) -------- */
-
-    let _mask_coeffs = derive_mask_coeffs(&mut tr, DEFAULT_MASK_DEG, DEFAULT_N_MASKS);
-
-    /* --------- Keep transcript alignment up to AIR row sampling ------------- */
-
-    // Prover derived an OOD point before binding FRI roots; mirror that.
-    let _z_sync = params::derive_ood_point(&mut tr);
-
-    // For AIR row queries, the prover had already absorbed FRI roots and betas.
-    let n_layers = proof.fri_roots.roots.len();
-    let mut tr_rows = tr.clone();
-    if n_layers > 0 {
-        tr_rows.absorb(params::DS_FRI_LAYER_ROOT, &proof.fri_roots.roots[0]);
-        let _ = params::derive_betas_for_fri(&mut tr_rows, n_layers.saturating_sub(1));
-        for r in 1..n_layers {
-            tr_rows.absorb(params::DS_FRI_LAYER_ROOT, &proof.fri_roots.roots[r]);
-        }
-    }
-
-    // Derive expected AIR query rows and cross-check with the proof.
-    let expected_rows = params::derive_queries(&mut tr_rows, n, params::NUM_QUERIES);
+    // Cross-check the opened AIR rows with the drawn ones.
+    let expected_rows = &drawn.row_queries;
     ensure!(
         expected_rows.len() == proof.queries.len(),
         "AIR query count mismatch (expected {}, got {})",
@@ -145,50 +504,119 @@ pub fn verify_v1(proof: &ProofV1, blocks: &[BlockSummary]) -> Result<()> {
         );
     }
 
+    // FRI positions: layer-0 positions for the chained schedule, or one set
+    // per layer.
+    let expected_fri = &drawn.fri_queries;
+    ensure!(
+        expected_fri.len() == proof.fri_queries.len(),
+        "FRI query count mismatch (expected {}, got {})",
+        expected_fri.len(),
+        proof.fri_queries.len()
+    );
+    for (i, (q, &want)) in proof.fri_queries.iter().zip(expected_fri).enumerate() {
+        ensure!(
+            q.positions.first() == Some(&want),
+            "FRI query position mismatch at position {i}: expected {want}"
+        );
+    }
+    ensure!(
+        proof.fri_layer_queries.len() == drawn.fri_layer_queries.len(),
+        "FRI layer query count mismatch (expected {} layers, got {})",
+        drawn.fri_layer_queries.len(),
+        proof.fri_layer_queries.len()
+    );
+    for (l, (qs, want)) in proof
+        .fri_layer_queries
+        .iter()
+        .zip(&drawn.fri_layer_queries)
+        .enumerate()
+    {
+        ensure!(
+            qs.iter().map(|q| q.position).eq(want.iter().copied()),
+            "FRI layer {l} query positions do not match the transcript"
+        );
+    }
+
     /* --------------------- Verify openings + AIR constraints ---------------- */
 
+    let span = sezkp_core::phase_span!("stark.verify_openings", queries = proof.queries.len());
     let root_map: HashMap<_, _> = proof
         .col_roots
         .iter()
-        .map(|c| (c.label.clone(), c.root))
+        .map(|c| (c.label.clone(), (c.per_block, c.root)))
         .collect();
 
     for q in &proof.queries {
+        let row = q.row;
+        let next = (row + 1) % n;
+        let check = |label: &str, open: &ColumnOpening, row: usize| {
+            verify_column_opening(&root_map, label, open, row, bounds)
+        };
+
         // Scalars
-        verify_opening(&root_map, "input_mv", &q.input_mv)?;
-        verify_opening(&root_map, "is_first", &q.is_first)?;
-        verify_opening(&root_map, "is_last", &q.is_last)?;
+        check("input_mv", &q.input_mv, row)?;
+        check("is_first", &q.is_first, row)?;
+        check("is_last", &q.is_last, row)?;
+
+        // Input head: opened iff the proof declares an input length, which
+        // then bounds every opened position.
+        ensure!(
+            q.input_head.is_some() == proof.params.input_len.is_some(),
+            "input head openings at row {row} do not match the declared input length"
+        );
+        if let Some(h) = &q.input_head {
+            check("in_head", &h.in_head, row)?;
+            check("in_head", &h.next_in_head, next)?;
+            check("input_mv", &h.next_input_mv, next)?;
+            check("in_head_in", &h.in_head_in, row)?;
+            check("in_head_out", &h.in_head_out, row)?;
+            for (what, open) in [
+                ("in_head", &h.in_head),
+                ("in_head_in", &h.in_head_in),
+                ("in_head_out", &h.in_head_out),
+            ] {
+                ensure!(
+                    proof.params.input_pos_ok(open.value_le()),
+                    "{what} at row {row} lies outside the declared input tape [0, {})",
+                    proof.params.input_len.unwrap_or_default()
+                );
+            }
+        }
 
         // Per-tape columns
         for (r, t) in q.per_tape.iter().enumerate() {
-            verify_opening(&root_map, &format!("mv_{r}"), &t.mv)?;
-            verify_opening(&root_map, &format!("mv_{r}"), &t.next_mv)?;
-            verify_opening(&root_map, &format!("wflag_{r}"), &t.write_flag)?;
-            verify_opening(&root_map, &format!("wsym_{r}"), &t.write_sym)?;
-            verify_opening(&root_map, &format!("head_{r}"), &t.head)?;
-            verify_opening(&root_map, &format!("head_{r}"), &t.next_head)?;
-            verify_opening(&root_map, &format!("winlen_{r}"), &t.win_len)?;
-            verify_opening(&root_map, &format!("in_off_{r}"), &t.in_off)?;
-            verify_opening(&root_map, &format!("out_off_{r}"), &t.out_off)?;
+            check(&format!("mv_{r}"), &t.mv, row)?;
+            check(&format!("mv_{r}"), &t.next_mv, next)?;
+            check(&format!("wflag_{r}"), &t.write_flag, row)?;
+            check(&format!("wsym_{r}"), &t.write_sym, row)?;
+            check(&format!("head_{r}"), &t.head, row)?;
+            check(&format!("head_{r}"), &t.next_head, next)?;
+            check(&format!("winlen_{r}"), &t.win_len, row)?;
+            check(&format!("in_off_{r}"), &t.in_off, row)?;
+            check(&format!("out_off_{r}"), &t.out_off, row)?;
         }
 
         // Recompute AIR composition from the opened values.
         let rv = RowView::from_openings(q);
-        let c =
-            compose_row_from_openings(&rv, &alphas) + compose_boundary_from_openings(&rv, &alphas);
+        let c = compose_row_from_openings(&rv, &alphas, max_move)
+            + compose_boundary_from_openings(&rv, &alphas);
         if c != F1::from_u64(0) {
             bail!("AIR composition non-zero at row {}", q.row);
         }
     }
 
+    drop(span);
+
     /* ------------------------------ FRI checks ------------------------------ */
 
+    let _span = sezkp_core::phase_span!("stark.verify_fri", layers = proof.fri_roots.roots.len());
+
     // Run FRI verification on a transcript aligned with the prover (for betas).
-    let mut tr_fri = tr;
     fri_verify(
-        &mut tr_fri,
+        &mut tr,
         &proof.fri_roots.roots,
         &proof.fri_queries,
+        &proof.fri_layer_queries,
         proof.fri_final_value_le,
     )?;
 
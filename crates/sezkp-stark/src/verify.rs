@@ -15,24 +15,42 @@
     clippy::expect_used
 )]
 
+use std::borrow::Borrow;
+
 use anyhow::{anyhow, Result};
 use sezkp_core::{BlockSummary, ProofArtifact};
 use sezkp_crypto::{Blake3Transcript, Transcript};
 
-use crate::commit::commit_blocks;
+use crate::commit::{commit_blocks, CommitResult};
 
 /// Verify a v0 STARK artifact by recomputing the transcript challenges.
 ///
 /// This performs the **same** streaming commitment over the blocks as the
 /// prover, then re-derives the `alpha`/`beta` challenge bytes and compares
-/// them with `artifact.proof_bytes`.
+/// them with `artifact.proof_bytes`. v0 binds no claims, so artifacts
+/// carrying any are rejected.
 pub fn verify_artifact(
     art: &ProofArtifact,
     blocks: &[BlockSummary],
     manifest_root: [u8; 32],
 ) -> Result<()> {
     // 1) Recompute the streaming commit (includes minimal AIR checks).
-    let com = commit_blocks(blocks)?;
+    verify_with_commit(art, manifest_root, || commit_blocks(blocks))
+}
+
+/// [`verify_artifact`] taking the blocks' streaming commit from `commit`, so
+/// artifacts over the same blocks can share one.
+///
+/// `commit` runs only after the claims check passes, so an artifact failing
+/// both reports the claims error whether or not its commit is shared.
+pub fn verify_with_commit<C: Borrow<CommitResult>>(
+    art: &ProofArtifact,
+    manifest_root: [u8; 32],
+    commit: impl FnOnce() -> Result<C>,
+) -> Result<()> {
+    sezkp_core::claims::check_bound(&art.claims, None)?;
+    let com = commit()?;
+    let com = com.borrow();
 
     // 2) Rebuild the Fiat–Shamir transcript.
     let mut tr = Blake3Transcript::new("sezkp-stark-v0");
@@ -78,6 +96,8 @@ mod tests {
             movement_log: MovementLog { steps },
             pre_tags: vec![[0; 16]; 1],
             post_tags: vec![[0; 16]; 1],
+            write_commitments: Vec::new(),
+            aux_commitments: Vec::new(),
         }
     }
 
@@ -100,8 +120,11 @@ mod tests {
         let art = ProofArtifact {
             backend: sezkp_core::BackendKind::Stark,
             manifest_root,
+            proof_digest: None,
             proof_bytes,
             meta: serde_json::json!({}),
+            timestamp: None,
+            claims: Vec::new(),
         };
 
         verify_artifact(&art, &blocks, manifest_root).unwrap();
@@ -141,6 +141,8 @@ mod tests {
             },
             pre_tags: vec![[0; 16]; 1],
             post_tags: vec![[0; 16]; 1],
+            write_commitments: Vec::new(),
+            aux_commitments: Vec::new(),
         };
 
         let b2 = BlockSummary {
@@ -160,6 +162,8 @@ mod tests {
             },
             pre_tags: vec![[0; 16]; 1],
             post_tags: vec![[0; 16]; 1],
+            write_commitments: Vec::new(),
+            aux_commitments: Vec::new(),
         };
 
         let mut chunks = Vec::new();
@@ -65,6 +65,8 @@ fn mk_blocks_bad_symbol(t: usize) -> Vec<BlockSummary> {
         movement_log: MovementLog { steps },
         pre_tags: vec![[0u8; 16]; 1],
         post_tags: vec![[0u8; 16]; 1],
+        write_commitments: Vec::new(),
+        aux_commitments: Vec::new(),
     }]
 }
 
@@ -111,7 +113,7 @@ fn air_fails_symbol_bit_range() {
     let tc = TraceColumns::build(&blocks).expect("trace columns");
     let a = alphas_all_ones();
     let i = t - 2; // row with write=32
-    let c = compose_row(&tc, i, &a) + compose_boundary(&tc, i, &a);
+    let c = compose_row(&tc, i, &a, 1) + compose_boundary(&tc, i, &a);
     assert_ne!(
         c,
         F1::from_u64(0),
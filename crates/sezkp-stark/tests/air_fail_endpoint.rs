@@ -42,6 +42,8 @@ fn mk_blocks_bad_endpoint(t: usize) -> Vec<BlockSummary> {
         movement_log: MovementLog { steps },
         pre_tags: vec![[0u8; 16]; 1],
         post_tags: vec![[0u8; 16]; 1],
+        write_commitments: Vec::new(),
+        aux_commitments: Vec::new(),
     };
 
     // Corrupt the entry offset (should be 0 for this walk).
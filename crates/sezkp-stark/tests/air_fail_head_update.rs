@@ -18,7 +18,7 @@
 use sezkp_stark::v1::{
     air::{compose_boundary_from_openings, compose_row_from_openings, Alphas, RowView},
     field::F1,
-    proof::{Opening, PerTapeOpen, RowOpenings},
+    proof::{BlockOpening, ColumnOpening, Opening, PerTapeOpen, RowOpenings},
 };
 
 #[inline]
@@ -26,8 +26,8 @@ fn f1(x: u64) -> F1 {
     F1::from_u64(x)
 }
 
-fn open_u64(v: u64, idx: usize) -> Opening {
-    Opening {
+fn open_u64(v: u64, idx: usize) -> ColumnOpening {
+    ColumnOpening::Chunked(Opening {
         value_le: v.to_le_bytes(),
         index: idx,
         chunk_index: 0,
@@ -35,7 +35,18 @@ fn open_u64(v: u64, idx: usize) -> Opening {
         chunk_root: [0u8; 32],
         path_in_chunk: vec![],
         path_to_chunk: vec![],
-    }
+    })
+}
+
+fn block_u64(v: u64, idx: usize) -> ColumnOpening {
+    ColumnOpening::Block(BlockOpening {
+        value_le: v.to_le_bytes(),
+        index: idx,
+        block_index: 0,
+        row_lo: idx,
+        row_hi: idx,
+        path: vec![],
+    })
 }
 
 fn alphas_all_ones() -> Alphas {
@@ -67,9 +78,9 @@ fn air_fails_head_update() {
         write_sym: open_u64(0, row),
         head: open_u64(10, row),
         next_head: open_u64(12, row + 1),
-        win_len: open_u64(16, row),
-        in_off: open_u64(0, row),
-        out_off: open_u64(0, row),
+        win_len: block_u64(16, row),
+        in_off: block_u64(0, row),
+        out_off: block_u64(0, row),
     };
 
     let q = RowOpenings {
@@ -78,10 +89,11 @@ fn air_fails_head_update() {
         is_first: open_u64(0, row),
         is_last: open_u64(0, row), // not last ⇒ head-update is enforced
         input_mv: open_u64(0, row),
+        input_head: None,
     };
 
     let view = RowView::from_openings(&q);
-    let c = compose_row_from_openings(&view, &a) + compose_boundary_from_openings(&view, &a);
+    let c = compose_row_from_openings(&view, &a, 1) + compose_boundary_from_openings(&view, &a);
 
     // Must be non-zero due to the bad head update.
     assert_ne!(c, f1(0));
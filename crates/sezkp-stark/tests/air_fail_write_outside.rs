@@ -51,6 +51,8 @@ fn mk_blocks_fail_write_outside(t: usize) -> Vec<BlockSummary> {
         movement_log: MovementLog { steps },
         pre_tags: vec![[0u8; 16]; 1],
         post_tags: vec![[0u8; 16]; 1],
+        write_commitments: Vec::new(),
+        aux_commitments: Vec::new(),
     }]
 }
 
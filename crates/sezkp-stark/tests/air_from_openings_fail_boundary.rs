@@ -14,7 +14,7 @@
 use sezkp_stark::v1::{
     air::{compose_boundary_from_openings, compose_row_from_openings, Alphas, RowView},
     field::F1,
-    proof::{Opening, PerTapeOpen, RowOpenings},
+    proof::{BlockOpening, ColumnOpening, Opening, PerTapeOpen, RowOpenings},
 };
 
 #[inline]
@@ -22,8 +22,8 @@ fn f1(x: u64) -> F1 {
     F1::from_u64(x)
 }
 
-fn open_u64(v: u64, idx: usize) -> Opening {
-    Opening {
+fn open_u64(v: u64, idx: usize) -> ColumnOpening {
+    ColumnOpening::Chunked(Opening {
         value_le: v.to_le_bytes(),
         index: idx,
         chunk_index: 0,
@@ -31,7 +31,18 @@ fn open_u64(v: u64, idx: usize) -> Opening {
         chunk_root: [0u8; 32],
         path_in_chunk: vec![],
         path_to_chunk: vec![],
-    }
+    })
+}
+
+fn block_u64(v: u64, idx: usize) -> ColumnOpening {
+    ColumnOpening::Block(BlockOpening {
+        value_le: v.to_le_bytes(),
+        index: idx,
+        block_index: 0,
+        row_lo: idx,
+        row_hi: idx,
+        path: vec![],
+    })
 }
 
 fn alphas_ones() -> Alphas {
@@ -63,14 +74,14 @@ fn tamper_in_off_triggers_boundary_failure() {
         write_sym: open_u64(0, row),
         head: open_u64(5, row),
         next_head: open_u64(6, row + 1),
-        win_len: open_u64(16, row),
-        in_off: open_u64(4, row), // 5 - 1 - 4 = 0
-        out_off: open_u64(0, row),
+        win_len: block_u64(16, row),
+        in_off: block_u64(4, row), // 5 - 1 - 4 = 0
+        out_off: block_u64(0, row),
     };
 
     // Tamper `in_off` by flipping one bit → violates first-row boundary eq.
     let tampered = PerTapeOpen {
-        in_off: open_u64(4 ^ 1, row),
+        in_off: block_u64(4 ^ 1, row),
         ..honest
     };
 
@@ -80,10 +91,11 @@ fn tamper_in_off_triggers_boundary_failure() {
         is_first: open_u64(1, row),
         is_last: open_u64(0, row),
         input_mv: open_u64(0, row),
+        input_head: None,
     };
 
     let view = RowView::from_openings(&q);
-    let c = compose_row_from_openings(&view, &a) + compose_boundary_from_openings(&view, &a);
+    let c = compose_row_from_openings(&view, &a, 1) + compose_boundary_from_openings(&view, &a);
     assert_ne!(c, f1(0), "tampered in_off should break boundary constraint");
 }
 
@@ -100,14 +112,14 @@ fn tamper_out_off_triggers_boundary_failure() {
         write_sym: open_u64(0, row),
         head: open_u64(9, row),
         next_head: open_u64(123, row + 1), // arbitrary; masked by is_last
-        win_len: open_u64(16, row),
-        in_off: open_u64(0, row),
-        out_off: open_u64(9, row), // 9 - 9 = 0
+        win_len: block_u64(16, row),
+        in_off: block_u64(0, row),
+        out_off: block_u64(9, row), // 9 - 9 = 0
     };
 
     // Tamper `out_off` → violates last-row boundary eq.
     let tampered = PerTapeOpen {
-        out_off: open_u64(8, row),
+        out_off: block_u64(8, row),
         ..honest
     };
 
@@ -117,9 +129,10 @@ fn tamper_out_off_triggers_boundary_failure() {
         is_first: open_u64(0, row),
         is_last: open_u64(1, row),
         input_mv: open_u64(0, row),
+        input_head: None,
     };
 
     let view = RowView::from_openings(&q);
-    let c = compose_row_from_openings(&view, &a) + compose_boundary_from_openings(&view, &a);
+    let c = compose_row_from_openings(&view, &a, 1) + compose_boundary_from_openings(&view, &a);
     assert_ne!(c, f1(0), "tampered out_off should break boundary constraint");
 }
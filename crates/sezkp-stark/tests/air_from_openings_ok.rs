@@ -13,7 +13,7 @@
 use sezkp_stark::v1::{
     air::{compose_boundary_from_openings, compose_row_from_openings, Alphas, RowView},
     field::F1,
-    proof::{Opening, PerTapeOpen, RowOpenings},
+    proof::{BlockOpening, ColumnOpening, Opening, PerTapeOpen, RowOpenings},
 };
 
 #[inline]
@@ -21,8 +21,8 @@ fn f1(x: u64) -> F1 {
     F1::from_u64(x)
 }
 
-fn open_u64(v: u64, idx: usize) -> Opening {
-    Opening {
+fn open_u64(v: u64, idx: usize) -> ColumnOpening {
+    ColumnOpening::Chunked(Opening {
         value_le: v.to_le_bytes(),
         index: idx,
         chunk_index: 0,
@@ -30,7 +30,18 @@ fn open_u64(v: u64, idx: usize) -> Opening {
         chunk_root: [0u8; 32],
         path_in_chunk: vec![],
         path_to_chunk: vec![],
-    }
+    })
+}
+
+fn block_u64(v: u64, idx: usize) -> ColumnOpening {
+    ColumnOpening::Block(BlockOpening {
+        value_le: v.to_le_bytes(),
+        index: idx,
+        block_index: 0,
+        row_lo: idx,
+        row_hi: idx,
+        path: vec![],
+    })
 }
 
 fn alphas_ones() -> Alphas {
@@ -64,9 +75,9 @@ fn openings_ok_middle_row_no_boundary() {
         write_sym: open_u64(0, row),
         head: open_u64(10, row),
         next_head: open_u64(11, row + 1),
-        win_len: open_u64(16, row),
-        in_off: open_u64(0, row),
-        out_off: open_u64(0, row),
+        win_len: block_u64(16, row),
+        in_off: block_u64(0, row),
+        out_off: block_u64(0, row),
     };
 
     let q = RowOpenings {
@@ -75,11 +86,12 @@ fn openings_ok_middle_row_no_boundary() {
         is_first: open_u64(0, row),
         is_last: open_u64(0, row),
         input_mv: open_u64(0, row),
+        input_head: None,
     };
 
     let view = RowView::from_openings(&q);
     let a = alphas_ones();
-    let c = compose_row_from_openings(&view, &a) + compose_boundary_from_openings(&view, &a);
+    let c = compose_row_from_openings(&view, &a, 1) + compose_boundary_from_openings(&view, &a);
     assert_eq!(c, f1(0), "middle-row openings should satisfy AIR (C=0)");
 }
 
@@ -96,9 +108,9 @@ fn openings_ok_first_and_last_rows_with_offsets() {
         write_sym: open_u64(0, row_first),
         head: open_u64(5, row_first),
         next_head: open_u64(6, row_first + 1),
-        win_len: open_u64(16, row_first),
-        in_off: open_u64(4, row_first), // 5 - 1 - 4 = 0
-        out_off: open_u64(0, row_first),
+        win_len: block_u64(16, row_first),
+        in_off: block_u64(4, row_first), // 5 - 1 - 4 = 0
+        out_off: block_u64(0, row_first),
     };
     let q_first = RowOpenings {
         row: row_first,
@@ -106,9 +118,10 @@ fn openings_ok_first_and_last_rows_with_offsets() {
         is_first: open_u64(1, row_first),
         is_last: open_u64(0, row_first),
         input_mv: open_u64(0, row_first),
+        input_head: None,
     };
     let view_first = RowView::from_openings(&q_first);
-    let c_first = compose_row_from_openings(&view_first, &a)
+    let c_first = compose_row_from_openings(&view_first, &a, 1)
         + compose_boundary_from_openings(&view_first, &a);
     assert_eq!(c_first, f1(0), "first-row openings should satisfy AIR (C=0)");
 
@@ -121,9 +134,9 @@ fn openings_ok_first_and_last_rows_with_offsets() {
         write_sym: open_u64(0, row_last),
         head: open_u64(9, row_last),
         next_head: open_u64(123, row_last + 1), // masked by is_last
-        win_len: open_u64(16, row_last),
-        in_off: open_u64(0, row_last),
-        out_off: open_u64(9, row_last), // 9 - 9 = 0
+        win_len: block_u64(16, row_last),
+        in_off: block_u64(0, row_last),
+        out_off: block_u64(9, row_last), // 9 - 9 = 0
     };
     let q_last = RowOpenings {
         row: row_last,
@@ -131,9 +144,10 @@ fn openings_ok_first_and_last_rows_with_offsets() {
         is_first: open_u64(0, row_last),
         is_last: open_u64(1, row_last),
         input_mv: open_u64(0, row_last),
+        input_head: None,
     };
     let view_last = RowView::from_openings(&q_last);
-    let c_last =
-        compose_row_from_openings(&view_last, &a) + compose_boundary_from_openings(&view_last, &a);
+    let c_last = compose_row_from_openings(&view_last, &a, 1)
+        + compose_boundary_from_openings(&view_last, &a);
     assert_eq!(c_last, f1(0), "last-row openings should satisfy AIR (C=0)");
 }
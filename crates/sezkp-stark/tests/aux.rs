@@ -0,0 +1,49 @@
+//! Auxiliary commitments carried by v1 proofs: the blocks' digest is
+//! absorbed into the transcript, recorded in the proof parameters, and
+//! recomputed by the verifier from the blocks it is given.
+
+#![allow(clippy::unwrap_used)]
+
+mod utils;
+
+use sezkp_core::auxiliary::{aux_commitment, aux_digest};
+use sezkp_stark::v1::proof::ProofV1;
+use sezkp_stark::{ProvingBackend, StarkV1};
+use utils::demo_blocks;
+
+const ROOT: [u8; 32] = [7u8; 32];
+
+fn decode(art: &sezkp_core::ProofArtifact) -> ProofV1 {
+    bincode::deserialize(&art.proof_bytes).unwrap()
+}
+
+#[test]
+fn aux_commitments_are_bound_and_checked() {
+    let plain = demo_blocks(64);
+    let mut blocks = plain.clone();
+    blocks[0].aux_commitments = vec![
+        aux_commitment("pc-histogram", &[3, 1, 4]),
+        aux_commitment("syscalls", b"write"),
+    ];
+
+    let mut art = StarkV1::prove(&blocks, ROOT).unwrap();
+    assert_eq!(decode(&art).params.aux, aux_digest(&blocks));
+    StarkV1::verify(&art, &blocks, ROOT).unwrap();
+    StarkV1::verify_stream(&art, blocks.iter().cloned().map(Ok), ROOT, None).unwrap();
+
+    // Other, missing or extra commitments no longer match the proof.
+    let mut edited = blocks.clone();
+    edited[0].aux_commitments[1][0] ^= 1;
+    assert!(StarkV1::verify(&art, &edited, ROOT).is_err());
+    assert!(StarkV1::verify_stream(&art, edited.iter().cloned().map(Ok), ROOT, None).is_err());
+    assert!(StarkV1::verify(&art, &plain, ROOT).is_err());
+    let without = StarkV1::prove(&plain, ROOT).unwrap();
+    assert_eq!(decode(&without).params.aux, None);
+    assert!(StarkV1::verify(&without, &blocks, ROOT).is_err());
+
+    // Rewriting the recorded digest as well breaks the transcript.
+    let mut proof = decode(&art);
+    proof.params.aux = aux_digest(&edited);
+    art.proof_bytes = bincode::serialize(&proof).unwrap();
+    assert!(StarkV1::verify(&art, &edited, ROOT).is_err());
+}
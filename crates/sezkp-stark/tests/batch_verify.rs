@@ -0,0 +1,81 @@
+//! Batch verification: one result per artifact, each exactly what verifying
+//! it alone returns, with block-derived work shared between artifacts that
+//! pass the same blocks.
+
+#![allow(clippy::unwrap_used)]
+
+use sezkp_core::claims::Claim;
+use sezkp_core::{BatchItem, BlockSummary, ProofArtifact};
+use sezkp_stark::{ProvingBackend, StarkIOP, StarkV1};
+use sezkp_trace::{generator::generate_trace, partition::partition_trace};
+
+const ROOT_A: [u8; 32] = [3u8; 32];
+const ROOT_B: [u8; 32] = [4u8; 32];
+
+fn tampered(art: &ProofArtifact) -> ProofArtifact {
+    let mut art = art.clone();
+    let last = art.proof_bytes.len() - 1;
+    art.proof_bytes[last] ^= 1;
+    art
+}
+
+const fn item<'a>(
+    artifact: &'a ProofArtifact,
+    blocks: &'a [BlockSummary],
+    manifest_root: [u8; 32],
+) -> BatchItem<'a> {
+    BatchItem {
+        artifact,
+        blocks,
+        manifest_root,
+    }
+}
+
+/// Good and bad items over two traces, interleaved, checked against
+/// one-at-a-time verification.
+fn check_batch<B: ProvingBackend>() {
+    let a = partition_trace(&generate_trace(128, 2), 16);
+    let b = partition_trace(&generate_trace(64, 2), 8);
+    let pa = B::prove(&a, ROOT_A).unwrap();
+    let pb = B::prove(&b, ROOT_B).unwrap();
+    let bad = tampered(&pa);
+    // Unbound claims over blocks that disagree on τ: fails two checks, and
+    // must report the same one in a batch as alone.
+    let mut claimed = pa.clone();
+    claimed.claims = vec![Claim::new("x", "1")];
+    let mut mixed = a.clone();
+    mixed.extend(partition_trace(&generate_trace(16, 3), 16));
+
+    let items = [
+        item(&pa, &a, ROOT_A),
+        item(&pb, &b, ROOT_B),
+        item(&bad, &a, ROOT_A),
+        item(&pa, &b, ROOT_B),
+        item(&pa, &b, ROOT_A),
+        item(&pb, &b, ROOT_B),
+        item(&claimed, &mixed, ROOT_A),
+        item(&claimed, &mixed, ROOT_A),
+    ];
+
+    let results = B::verify_batch(&items);
+    assert_eq!(results.len(), items.len());
+    let ok: Vec<bool> = results.iter().map(Result::is_ok).collect();
+    assert_eq!(ok, [true, true, false, false, false, true, false, false]);
+    for (it, got) in items.iter().zip(&results) {
+        let alone = B::verify(it.artifact, it.blocks, it.manifest_root);
+        assert_eq!(
+            got.as_ref().map_err(|e| format!("{e:#}")),
+            alone.as_ref().map_err(|e| format!("{e:#}"))
+        );
+    }
+}
+
+#[test]
+fn v1_batches_match_single_verification() {
+    check_batch::<StarkV1>();
+}
+
+#[test]
+fn v0_batches_match_single_verification() {
+    check_batch::<StarkIOP>();
+}
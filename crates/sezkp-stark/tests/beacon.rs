@@ -0,0 +1,48 @@
+//! External randomness beacons bound into v1 query sampling.
+//!
+//! A beacon changes the sampled rows, is recorded in the proof, and must
+//! match exactly when the verifier asks for one.
+
+#![allow(clippy::unwrap_used)]
+
+mod utils;
+
+use sezkp_stark::v1::proof::ProofV1;
+use sezkp_stark::{ProvingBackend, StarkV1};
+use utils::demo_blocks;
+
+const ROOT: [u8; 32] = [7u8; 32];
+
+fn rows(art: &sezkp_core::ProofArtifact) -> Vec<usize> {
+    let proof: ProofV1 = bincode::deserialize(&art.proof_bytes).unwrap();
+    proof.queries.iter().map(|q| q.row).collect()
+}
+
+#[test]
+fn beacon_is_bound_and_checked() {
+    let blocks = demo_blocks(64);
+    let beacon = [0xabu8; 32];
+
+    let art = StarkV1::prove_with_beacon(&blocks, ROOT, &beacon).unwrap();
+    StarkV1::verify_with_beacon(&art, &blocks, ROOT, &beacon).unwrap();
+    // Plain verification still replays the recorded beacon.
+    StarkV1::verify(&art, &blocks, ROOT).unwrap();
+    assert!(StarkV1::verify_with_beacon(&art, &blocks, ROOT, &[0xcd; 32]).is_err());
+
+    // A beacon-free proof samples different rows and cannot claim a beacon.
+    let plain = StarkV1::prove(&blocks, ROOT).unwrap();
+    assert_ne!(rows(&plain), rows(&art));
+    assert!(StarkV1::verify_with_beacon(&plain, &blocks, ROOT, &beacon).is_err());
+}
+
+#[test]
+fn swapped_beacon_breaks_query_sampling() {
+    let blocks = demo_blocks(64);
+    let art = StarkV1::prove_with_beacon(&blocks, ROOT, b"block-1").unwrap();
+
+    let mut proof: ProofV1 = bincode::deserialize(&art.proof_bytes).unwrap();
+    proof.beacon = Some(b"block-2".to_vec());
+    let mut forged = art.clone();
+    forged.proof_bytes = bincode::serialize(&proof).unwrap();
+    assert!(StarkV1::verify_with_beacon(&forged, &blocks, ROOT, b"block-2").is_err());
+}
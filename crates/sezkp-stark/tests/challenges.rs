@@ -0,0 +1,74 @@
+//! Challenge traces: `challenges::replay` records the values the verifier
+//! draws, so they match what an honest proof opens, survive a JSON round
+//! trip, and move with everything the transcript binds.
+
+#![allow(clippy::unwrap_used)]
+
+mod utils;
+
+use sezkp_core::EMPTY_ROOT;
+use sezkp_stark::v1::challenges::{replay, ChallengeTrace};
+use sezkp_stark::v1::proof::ProofV1;
+use sezkp_stark::{params, ProvingBackend, StarkV1};
+use utils::demo_blocks;
+
+const ROOT: [u8; 32] = [7u8; 32];
+
+fn proof_of(art: &sezkp_core::ProofArtifact) -> ProofV1 {
+    ProofV1::decode(&art.proof_bytes).unwrap()
+}
+
+#[test]
+fn trace_lists_what_an_honest_proof_opens() {
+    let blocks = demo_blocks(16);
+    let art = StarkV1::prove(&blocks, ROOT).unwrap();
+    StarkV1::verify(&art, &blocks, ROOT).unwrap();
+    let proof = proof_of(&art);
+    let trace = replay(&proof).unwrap();
+
+    assert_eq!(trace.alphas.len(), params::NUM_ALPHAS);
+    assert!(trace.ood_point.is_some());
+    assert_eq!(trace.fri_betas.len(), proof.fri_roots.roots.len() - 1);
+    let rows: Vec<usize> = proof.queries.iter().map(|q| q.row).collect();
+    assert_eq!(trace.row_queries, rows);
+    let fri: Vec<usize> = proof.fri_queries.iter().map(|q| q.positions[0]).collect();
+    assert_eq!(trace.fri_queries, fri);
+    assert!(trace.fri_layer_queries.is_empty());
+
+    let json = serde_json::to_string_pretty(&trace).unwrap();
+    let back: ChallengeTrace = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, trace);
+    assert_eq!(replay(&proof).unwrap(), trace, "replay is deterministic");
+}
+
+#[test]
+fn trace_moves_with_what_the_transcript_binds() {
+    let blocks = demo_blocks(16);
+    let plain = proof_of(&StarkV1::prove(&blocks, ROOT).unwrap());
+    let base = replay(&plain).unwrap();
+
+    // The beacon is absorbed right before the queries: earlier draws stay.
+    let mut beacon = plain.clone();
+    beacon.beacon = Some(b"block-1".to_vec());
+    let t = replay(&beacon).unwrap();
+    assert_eq!(t.alphas, base.alphas);
+    assert_eq!(t.fri_betas, base.fri_betas);
+    assert_ne!(t.row_queries, base.row_queries);
+
+    // The manifest root is absorbed first: everything changes.
+    let mut rooted = plain;
+    rooted.public.manifest_root[0] ^= 1;
+    let t = replay(&rooted).unwrap();
+    assert_ne!(t.alphas, base.alphas);
+    assert_ne!(t.ood_point, base.ood_point);
+}
+
+#[test]
+fn empty_and_malformed_proofs() {
+    let empty = proof_of(&StarkV1::prove(&[], EMPTY_ROOT).unwrap());
+    assert_eq!(replay(&empty).unwrap(), ChallengeTrace::default());
+
+    let mut bad = proof_of(&StarkV1::prove(&demo_blocks(16), ROOT).unwrap());
+    bad.domain_n = 3 * params::BLOWUP;
+    assert!(replay(&bad).is_err());
+}
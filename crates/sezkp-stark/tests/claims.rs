@@ -0,0 +1,60 @@
+//! Public claims bound into v1 proofs: the digest is absorbed into the
+//! transcript, recorded in the proof's public inputs, and must match the
+//! claims the artifact carries.
+
+#![allow(clippy::unwrap_used)]
+
+mod utils;
+
+use sezkp_core::claims::{claims_digest, Claim};
+use sezkp_stark::v1::proof::ProofV1;
+use sezkp_stark::{ProvingBackend, StarkV1};
+use utils::demo_blocks;
+
+const ROOT: [u8; 32] = [7u8; 32];
+
+fn decode(art: &sezkp_core::ProofArtifact) -> ProofV1 {
+    bincode::deserialize(&art.proof_bytes).unwrap()
+}
+
+#[test]
+fn claims_are_bound_and_checked() {
+    let blocks = demo_blocks(64);
+    let claims = vec![Claim::new("output", "42"), Claim::new("program", "ab12")];
+
+    let art = StarkV1::prove_with_claims(&blocks, ROOT, &claims).unwrap();
+    assert_eq!(art.claims, claims);
+    assert_eq!(decode(&art).public.claims, claims_digest(&claims));
+    StarkV1::verify(&art, &blocks, ROOT).unwrap();
+
+    // Edited, dropped or reordered claims no longer match the proof.
+    let mut edited = art.clone();
+    edited.claims[0].value = "43".into();
+    assert!(StarkV1::verify(&edited, &blocks, ROOT).is_err());
+    let mut dropped = art.clone();
+    dropped.claims.pop();
+    assert!(StarkV1::verify(&dropped, &blocks, ROOT).is_err());
+    let mut reordered = art.clone();
+    reordered.claims.reverse();
+    assert!(StarkV1::verify(&reordered, &blocks, ROOT).is_err());
+
+    // Rewriting the recorded digest as well breaks the transcript.
+    let mut proof = decode(&art);
+    proof.public.claims = claims_digest(&edited.claims);
+    edited.proof_bytes = bincode::serialize(&proof).unwrap();
+    assert!(StarkV1::verify(&edited, &blocks, ROOT).is_err());
+
+    // Claims cannot be attached to a proof that binds none.
+    let mut plain = StarkV1::prove(&blocks, ROOT).unwrap();
+    assert_eq!(decode(&plain).public.claims, None);
+    StarkV1::verify(&plain, &blocks, ROOT).unwrap();
+    plain.claims = claims;
+    assert!(StarkV1::verify(&plain, &blocks, ROOT).is_err());
+}
+
+#[test]
+fn invalid_claims_are_refused_at_prove_time() {
+    let blocks = demo_blocks(16);
+    let dup = [Claim::new("x", "1"), Claim::new("x", "2")];
+    assert!(StarkV1::prove_with_claims(&blocks, ROOT, &dup).is_err());
+}
@@ -0,0 +1,81 @@
+//! Edge-case inputs: zero blocks and a single one-row block.
+//!
+//! Semantics under test:
+//! - zero blocks prove/verify only against `EMPTY_ROOT` (v1 emits the
+//!   canonical empty proof; v0 its empty-domain transcript);
+//! - the empty root is rejected for non-empty inputs, and the empty proof is
+//!   rejected when presented for actual blocks;
+//! - a trace of exactly one row (n = 1) proves and verifies like any other.
+
+#![allow(clippy::unwrap_used)]
+
+use sezkp_core::{BlockSummary, MovementLog, StepProjection, TapeOp, Window, EMPTY_ROOT};
+use sezkp_stark::v1::proof::ProofV1;
+use sezkp_stark::{ProvingBackend, StarkIOP, StarkV1};
+
+/// One block of a single step that moves right and writes symbol 3.
+fn one_row_block() -> BlockSummary {
+    BlockSummary {
+        version: 1,
+        block_id: 1,
+        step_lo: 1,
+        step_hi: 1,
+        ctrl_in: 0,
+        ctrl_out: 0,
+        in_head_in: 0,
+        in_head_out: 0,
+        windows: vec![Window { left: 0, right: 1 }],
+        head_in_offsets: vec![0],
+        head_out_offsets: vec![1],
+        movement_log: MovementLog {
+            steps: vec![StepProjection {
+                input_mv: 0,
+                tapes: vec![TapeOp {
+                    write: Some(3),
+                    mv: 1,
+                }],
+            }],
+        },
+        pre_tags: vec![[0u8; 16]; 1],
+        post_tags: vec![[0u8; 16]; 1],
+        write_commitments: Vec::new(),
+        aux_commitments: Vec::new(),
+    }
+}
+
+#[test]
+fn v1_empty_input_yields_the_empty_proof() {
+    let art = StarkV1::prove(&[], EMPTY_ROOT).unwrap();
+    let proof: ProofV1 = bincode::deserialize(&art.proof_bytes).unwrap();
+    assert!(proof.is_empty_proof());
+    assert_eq!(art.manifest_root, EMPTY_ROOT);
+    StarkV1::verify(&art, &[], EMPTY_ROOT).unwrap();
+
+    // Not for a non-empty root, and not for real blocks.
+    assert!(StarkV1::prove(&[], [1u8; 32]).is_err());
+    assert!(StarkV1::verify(&art, &[one_row_block()], EMPTY_ROOT).is_err());
+}
+
+#[test]
+fn v1_single_row_trace_roundtrips() {
+    let blocks = vec![one_row_block()];
+    let root = [5u8; 32];
+    let art = StarkV1::prove(&blocks, root).unwrap();
+    let proof: ProofV1 = bincode::deserialize(&art.proof_bytes).unwrap();
+    assert_eq!(proof.domain_n, sezkp_stark::params::BLOWUP);
+    StarkV1::verify(&art, &blocks, root).unwrap();
+
+    assert!(StarkV1::prove(&blocks, EMPTY_ROOT).is_err());
+}
+
+#[test]
+fn v0_follows_the_same_empty_root_rule() {
+    let art = StarkIOP::prove(&[], EMPTY_ROOT).unwrap();
+    StarkIOP::verify(&art, &[], EMPTY_ROOT).unwrap();
+    assert!(StarkIOP::prove(&[], [1u8; 32]).is_err());
+
+    let blocks = vec![one_row_block()];
+    let art = StarkIOP::prove(&blocks, [2u8; 32]).unwrap();
+    StarkIOP::verify(&art, &blocks, [2u8; 32]).unwrap();
+    assert!(StarkIOP::prove(&blocks, EMPTY_ROOT).is_err());
+}
@@ -0,0 +1,98 @@
+//! Size estimates: `estimate_proof_size` bounds `proof_bytes` of real v1
+//! proofs from the trace shape alone, reaching the bound whenever every step
+//! column varies within some block.
+
+#![allow(clippy::unwrap_used)]
+
+use sezkp_core::{BlockSummary, MovementLog, StepProjection, TapeOp, Window};
+use sezkp_stark::v1::proof::{ProofParams, ProofV1};
+use sezkp_stark::{estimate_proof_size, ProvingBackend, StarkV1};
+use sezkp_trace::{generator::generate_trace, partition::partition_trace};
+
+const ROOT: [u8; 32] = [7u8; 32];
+
+/// `n` consecutive blocks of `t` idle steps (no moves, no writes) on `tau` tapes.
+fn idle_blocks(n: u32, t: u64, tau: usize) -> Vec<BlockSummary> {
+    (0..n)
+        .map(|i| {
+            let step_lo = 1 + u64::from(i) * t;
+            BlockSummary {
+                version: 1,
+                block_id: i + 1,
+                step_lo,
+                step_hi: step_lo + t - 1,
+                ctrl_in: 0,
+                ctrl_out: 0,
+                in_head_in: 0,
+                in_head_out: 0,
+                windows: vec![Window { left: 0, right: 0 }; tau],
+                head_in_offsets: vec![0; tau],
+                head_out_offsets: vec![0; tau],
+                movement_log: MovementLog {
+                    steps: (0..t)
+                        .map(|_| StepProjection {
+                            input_mv: 0,
+                            tapes: vec![TapeOp { write: None, mv: 0 }; tau],
+                        })
+                        .collect(),
+                },
+                pre_tags: vec![[0u8; 16]; tau],
+                post_tags: vec![[0u8; 16]; tau],
+                write_commitments: Vec::new(),
+                aux_commitments: Vec::new(),
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn estimate_matches_real_proofs() {
+    for (n, t, tau) in [(2u32, 8u64, 2u8), (4, 4, 3), (8, 1, 2)] {
+        // Random steps vary within every block of more than one row.
+        let blocks = partition_trace(&generate_trace(u64::from(n) * t, tau), t as u32);
+        let tau = usize::from(tau);
+        let art = StarkV1::prove(&blocks, ROOT).unwrap();
+        let est = estimate_proof_size(u64::from(n), t, tau, &ProofParams::UNIT).unwrap();
+        assert_eq!(
+            est.proof_bytes,
+            art.proof_bytes.len() as u64,
+            "{n} blocks x {t} rows, tau {tau}"
+        );
+        assert_eq!(est.exact, t == 1);
+        assert_eq!(est.stream_bytes, None);
+
+        let proof: ProofV1 = bincode::deserialize(&art.proof_bytes).unwrap();
+        let count = |name: &str| est.items.iter().find(|i| i.name == name).unwrap().count;
+        assert_eq!(count("column roots"), proof.col_roots.len() as u64);
+        assert_eq!(count("fri layer roots"), proof.fri_roots.roots.len() as u64);
+        assert_eq!(count("fri queries"), proof.fri_queries.len() as u64);
+        assert!(est.framing_bytes() < 200);
+    }
+}
+
+#[test]
+fn estimate_bounds_proofs_of_idle_blocks() {
+    // Idle steps are constant within each block, so every column is
+    // committed per block and opens with a shorter path.
+    for (n, t, tau) in [(2u32, 8u64, 2usize), (8, 1, 2)] {
+        let blocks = idle_blocks(n, t, tau);
+        let art = StarkV1::prove(&blocks, ROOT).unwrap();
+        let est = estimate_proof_size(u64::from(n), t, tau, &ProofParams::UNIT).unwrap();
+        let real = art.proof_bytes.len() as u64;
+        if t == 1 {
+            assert_eq!(est.proof_bytes, real);
+        } else {
+            assert!(est.proof_bytes > real, "{} <= {real}", est.proof_bytes);
+        }
+    }
+}
+
+#[test]
+fn estimate_rejects_non_power_of_two_traces() {
+    let err = estimate_proof_size(3, 8, 2, &ProofParams::UNIT).unwrap_err();
+    assert!(err.to_string().contains("power-of-two"), "{err}");
+
+    let empty = estimate_proof_size(0, 8, 2, &ProofParams::UNIT).unwrap();
+    let bytes = bincode::serialize(&ProofV1::empty()).unwrap();
+    assert_eq!(empty.proof_bytes, bytes.len() as u64);
+}
@@ -0,0 +1,65 @@
+//! Batch field conversions agree with the scalar `F1::from_{u64,i64}` path.
+
+#![allow(clippy::unwrap_used)]
+
+use sezkp_ffts::GOLDILOCKS;
+use sezkp_stark::v1::field::{
+    from_i64_slice, from_u64_slice, le_from_i64_slice, le_from_u64_slice, F1,
+};
+
+#[test]
+fn batch_matches_scalar_on_edge_values() {
+    let us = [
+        0,
+        1,
+        u64::from(u32::MAX),
+        GOLDILOCKS - 1,
+        GOLDILOCKS,
+        GOLDILOCKS + 1,
+        u64::MAX,
+    ];
+    let is = [
+        0,
+        1,
+        -1,
+        2,
+        -2,
+        i64::MAX,
+        i64::MIN,
+        i64::MIN + 1,
+        -(1 << 40),
+    ];
+
+    let mut fu = vec![F1::from_u64(7); us.len()];
+    let mut lu = vec![[0u8; 8]; us.len()];
+    from_u64_slice(&us, &mut fu);
+    le_from_u64_slice(&us, &mut lu);
+    for (i, &x) in us.iter().enumerate() {
+        assert_eq!(fu[i], F1::from_u64(x), "from_u64_slice({x})");
+        assert_eq!(
+            lu[i],
+            F1::from_u64(x).to_le_bytes(),
+            "le_from_u64_slice({x})"
+        );
+    }
+
+    let mut fi = vec![F1::from_u64(7); is.len()];
+    let mut li = vec![[0u8; 8]; is.len()];
+    from_i64_slice(&is, &mut fi);
+    le_from_i64_slice(&is, &mut li);
+    for (i, &x) in is.iter().enumerate() {
+        assert_eq!(fi[i], F1::from_i64(x), "from_i64_slice({x})");
+        assert_eq!(
+            li[i],
+            F1::from_i64(x).to_le_bytes(),
+            "le_from_i64_slice({x})"
+        );
+    }
+}
+
+#[test]
+#[should_panic(expected = "length mismatch")]
+fn batch_rejects_length_mismatch() {
+    let mut out = vec![[0u8; 8]; 2];
+    le_from_i64_slice(&[1, 2, 3], &mut out);
+}
@@ -0,0 +1,136 @@
+//! Per-layer FRI query schedules.
+//!
+//! Each folded layer draws its own positions, the counts are recorded in
+//! `FriParams` and bound into the transcript, and the verifier re-derives
+//! and checks every layer's queries.
+//!
+//! The schedules here are far below the default security floor, so the
+//! proofs are checked under [`Requirements`] that waive it.
+
+#![allow(clippy::unwrap_used)]
+
+mod utils;
+
+use sezkp_core::BlockSummary;
+use sezkp_stark::v1::params::{FriQuerySchedule, StreamTuning, MAX_FRI_LAYER_QUERIES};
+use sezkp_stark::v1::proof::ProofV1;
+use sezkp_stark::v1::prover::{prove_v1, prove_v1_scheduled};
+use sezkp_stark::v1::verify::{self, verify_v1_under, BlockFacts, Requirements};
+use utils::demo_blocks;
+
+const ROOT: [u8; 32] = [7u8; 32];
+
+fn prove(blocks: &[BlockSummary], schedule: FriQuerySchedule) -> anyhow::Result<ProofV1> {
+    prove_v1_scheduled(blocks, ROOT, None, StreamTuning::default(), &schedule).map(|(p, _)| p)
+}
+
+fn verify_v1(proof: &ProofV1, blocks: &[BlockSummary]) -> anyhow::Result<()> {
+    let weak = Requirements {
+        min_security_bits: 0,
+        ..Requirements::default()
+    };
+    verify_v1_under(proof, &BlockFacts::of(blocks), None, &weak)
+}
+
+#[test]
+fn per_layer_queries_are_recorded_and_verify() {
+    let blocks = demo_blocks(64);
+    // 64 rows · blowup 8 = 2^9 values: nine folds.
+    let counts: Vec<usize> = (1..=9).rev().collect();
+    let proof = prove(&blocks, FriQuerySchedule::PerLayer(counts.clone())).unwrap();
+    verify_v1(&proof, &blocks).unwrap();
+
+    assert_eq!(proof.fri_params.layer_queries, counts);
+    assert!(proof.fri_queries.is_empty());
+    let got: Vec<usize> = proof.fri_layer_queries.iter().map(Vec::len).collect();
+    assert_eq!(got, counts);
+    for (l, qs) in proof.fri_layer_queries.iter().enumerate() {
+        assert!(qs.iter().all(|q| q.position < proof.domain_n >> l));
+    }
+
+    // A single count applies to every layer.
+    let uniform = prove(&blocks, FriQuerySchedule::PerLayer(vec![4])).unwrap();
+    verify_v1(&uniform, &blocks).unwrap();
+    assert_eq!(uniform.fri_params.layer_queries, vec![4; 9]);
+
+    // The chained default is what `prove_v1` emits.
+    let chained = prove(&blocks, FriQuerySchedule::Chained).unwrap();
+    assert!(chained.fri_params.is_chained() && chained.fri_layer_queries.is_empty());
+    assert_eq!(
+        bincode::serialize(&chained).unwrap(),
+        bincode::serialize(&prove_v1(&blocks, ROOT).unwrap()).unwrap()
+    );
+}
+
+#[test]
+fn default_verifier_holds_every_layer_to_the_floor() {
+    let blocks = demo_blocks(64);
+    // A weak layer is as bad as weak row queries, even behind strong ones.
+    for counts in [
+        (1..=9).rev().collect(),
+        vec![4],
+        vec![40, 40, 40, 40, 29, 40, 40, 40, 40],
+    ] {
+        let proof = prove(&blocks, FriQuerySchedule::PerLayer(counts.clone())).unwrap();
+        let err = verify::verify_v1(&proof, &blocks).unwrap_err();
+        assert!(
+            err.to_string().contains("below the required"),
+            "{counts:?}: {err}"
+        );
+    }
+    let proof = prove(&blocks, FriQuerySchedule::PerLayer(vec![30])).unwrap();
+    verify::verify_v1(&proof, &blocks).unwrap();
+}
+
+#[test]
+fn tampered_layer_queries_are_rejected() {
+    let blocks = demo_blocks(64);
+    let proof = prove(&blocks, FriQuerySchedule::PerLayer(vec![3])).unwrap();
+
+    // A folded value that does not match its layer.
+    let mut bad = proof.clone();
+    bad.fri_layer_queries[2][0].next.0[0] ^= 1;
+    assert!(verify_v1(&bad, &blocks).is_err());
+
+    // Dropping a query, or relabelling the schedule, moves the positions.
+    let mut bad = proof.clone();
+    bad.fri_layer_queries[5].pop();
+    assert!(verify_v1(&bad, &blocks).is_err());
+    let mut bad = proof.clone();
+    bad.fri_params.layer_queries[0] = 2;
+    bad.fri_layer_queries[0].pop();
+    assert!(verify_v1(&bad, &blocks).is_err());
+
+    // Per-layer proofs carry no chained queries, and vice versa.
+    let mut bad = proof;
+    bad.fri_params.layer_queries.clear();
+    bad.fri_layer_queries.clear();
+    assert!(verify_v1(&bad, &blocks).is_err());
+}
+
+#[test]
+fn schedules_must_fit_the_proof() {
+    let blocks = demo_blocks(64);
+    for counts in [vec![4, 4], vec![0], vec![MAX_FRI_LAYER_QUERIES + 1]] {
+        assert!(prove(&blocks, FriQuerySchedule::PerLayer(counts)).is_err());
+    }
+}
+
+#[test]
+fn schedules_round_trip_and_match_their_proofs() {
+    for s in ["chained", "4", "9,8,7"] {
+        let schedule: FriQuerySchedule = s.parse().unwrap();
+        assert_eq!(schedule.to_string(), s);
+    }
+    assert!("".parse::<FriQuerySchedule>().is_err());
+    assert!("4,x".parse::<FriQuerySchedule>().is_err());
+
+    let blocks = demo_blocks(64);
+    let uniform = prove(&blocks, FriQuerySchedule::PerLayer(vec![4])).unwrap();
+    assert!(FriQuerySchedule::PerLayer(vec![4]).matches(&uniform.fri_params));
+    assert!(FriQuerySchedule::PerLayer(vec![4; 9]).matches(&uniform.fri_params));
+    assert!(!FriQuerySchedule::PerLayer(vec![5]).matches(&uniform.fri_params));
+    assert!(!FriQuerySchedule::Chained.matches(&uniform.fri_params));
+    let chained = prove(&blocks, FriQuerySchedule::Chained).unwrap();
+    assert!(FriQuerySchedule::Chained.matches(&chained.fri_params));
+}
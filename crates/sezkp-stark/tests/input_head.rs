@@ -0,0 +1,153 @@
+//! Declared input tape length: the prover commits the input-head columns,
+//! the AIR ties each block's input moves to `in_head_in` / `in_head_out`, and
+//! the verifier bounds every opened position by the declared length.
+
+#![allow(clippy::unwrap_used)]
+
+use sezkp_core::{BlockSummary, MovementLog, StepProjection, TapeOp, Window};
+use sezkp_stark::v1::air::{
+    compose_boundary_from_openings, compose_row_from_openings, Alphas, RowView,
+};
+use sezkp_stark::v1::field::F1;
+use sezkp_stark::v1::params::{FriQuerySchedule, StreamTuning};
+use sezkp_stark::v1::proof::ProofV1;
+use sezkp_stark::v1::prover::prove_v1_with_input_len;
+use sezkp_stark::v1::verify::{verify_v1, verify_v1_stream};
+
+const ROOT: [u8; 32] = [7u8; 32];
+
+/// Two τ=1 blocks of 32 steps whose input head walks right one cell per
+/// step, from 0 to 64; the work tape stays put.
+fn reading_blocks() -> Vec<BlockSummary> {
+    (0..2i64)
+        .map(|k| BlockSummary {
+            version: 1,
+            block_id: k as u32 + 1,
+            step_lo: k as u64 * 32 + 1,
+            step_hi: k as u64 * 32 + 32,
+            ctrl_in: 0,
+            ctrl_out: 0,
+            in_head_in: k * 32,
+            in_head_out: k * 32 + 32,
+            windows: vec![Window { left: 0, right: 0 }],
+            head_in_offsets: vec![0],
+            head_out_offsets: vec![0],
+            movement_log: MovementLog {
+                steps: (0..32)
+                    .map(|_| StepProjection {
+                        input_mv: 1,
+                        tapes: vec![TapeOp { write: None, mv: 0 }],
+                    })
+                    .collect(),
+            },
+            pre_tags: vec![[0u8; 16]; 1],
+            post_tags: vec![[0u8; 16]; 1],
+            write_commitments: Vec::new(),
+            aux_commitments: Vec::new(),
+        })
+        .collect()
+}
+
+fn prove(blocks: &[BlockSummary], input_len: Option<u64>) -> anyhow::Result<ProofV1> {
+    prove_v1_with_input_len(
+        blocks,
+        ROOT,
+        None,
+        StreamTuning::default(),
+        &FriQuerySchedule::default(),
+        input_len,
+    )
+    .map(|(p, _)| p)
+}
+
+fn has_input_head_columns(proof: &ProofV1) -> bool {
+    proof.col_roots.iter().any(|c| c.label == "in_head")
+}
+
+#[test]
+fn declared_input_len_is_proven_and_bound() {
+    let blocks = reading_blocks();
+    let proof = prove(&blocks, Some(65)).unwrap();
+    assert_eq!(proof.params.input_len, Some(65));
+    assert!(has_input_head_columns(&proof));
+    assert!(proof.queries.iter().all(|q| q.input_head.is_some()));
+    verify_v1(&proof, &blocks).unwrap();
+
+    // The length is bound into the transcript, and dropping it leaves
+    // openings the verifier no longer expects.
+    for input_len in [Some(1000), None] {
+        let mut forged = proof.clone();
+        forged.params.input_len = input_len;
+        assert!(verify_v1(&forged, &blocks).is_err(), "{input_len:?}");
+    }
+}
+
+#[test]
+fn undeclared_proofs_commit_no_input_head_columns() {
+    let blocks = reading_blocks();
+    let proof = prove(&blocks, None).unwrap();
+    assert!(!has_input_head_columns(&proof));
+    assert!(proof.queries.iter().all(|q| q.input_head.is_none()));
+    verify_v1(&proof, &blocks).unwrap();
+}
+
+#[test]
+fn prover_rejects_an_input_head_off_the_tape() {
+    let blocks = reading_blocks();
+    // The head ends on cell 64, which a 64-cell tape does not have.
+    let err = prove(&blocks, Some(64)).unwrap_err();
+    assert!(err.to_string().contains("leaves the input tape"), "{err:#}");
+
+    // Declared endpoints must match the moves.
+    let mut bad = blocks;
+    bad[1].in_head_out = 63;
+    let err = prove(&bad, Some(65)).unwrap_err();
+    assert!(err.to_string().contains("in_head_out is 63"), "{err:#}");
+}
+
+#[test]
+fn stream_verifier_replays_the_input_head_of_the_blocks() {
+    let blocks = reading_blocks();
+    let proof = prove(&blocks, Some(65)).unwrap();
+    verify_v1_stream(&proof, blocks.iter().cloned().map(Ok), None).unwrap();
+
+    let mut bad = blocks;
+    bad[1].in_head_in = 31;
+    let err = verify_v1_stream(&proof, bad.into_iter().map(Ok), None).unwrap_err();
+    assert!(err.to_string().contains("in_head_out is 64"), "{err:#}");
+}
+
+#[test]
+fn opened_input_head_constraints_catch_tampering() {
+    let blocks = reading_blocks();
+    let proof = prove(&blocks, Some(65)).unwrap();
+    let one = F1::from_u64(1);
+    let a = Alphas {
+        bool_flag: one,
+        mv_domain: one,
+        head_update: one,
+        head_bits_bool: one,
+        head_reconstruct: one,
+        slack_bits_bool: one,
+        slack_reconstruct: one,
+        sym_bits_bool: one,
+        sym_reconstruct: one,
+        boundary_first: one,
+        boundary_last: one,
+    };
+    let compose =
+        |v: &RowView| compose_row_from_openings(v, &a, 1) + compose_boundary_from_openings(v, &a);
+
+    for q in &proof.queries {
+        let view = RowView::from_openings(q);
+        assert_eq!(compose(&view), F1::from_u64(0), "row {}", q.row);
+
+        // A skipped cell breaks the update (or, on a block's last row, the
+        // exit boundary).
+        let mut bad = view.clone();
+        let h = bad.input_head.as_mut().unwrap();
+        h.next_in_head = h.next_in_head + one;
+        h.in_head_out = h.in_head_out + one;
+        assert_ne!(compose(&bad), F1::from_u64(0), "row {}", q.row);
+    }
+}
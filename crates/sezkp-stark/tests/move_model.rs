@@ -0,0 +1,124 @@
+//! Movement models: proofs declare the per-step move bound their AIR was
+//! built for, verifiers hold the openings to the declared bound, and the
+//! declared bound must be the one the verifier expects (unit moves unless
+//! told otherwise).
+
+#![allow(clippy::unwrap_used)]
+
+use sezkp_core::{BlockSummary, MovementLog, StepProjection, TapeOp, Window};
+use sezkp_stark::v1::params::ENV_EXPECT_MAX_MOVE;
+use sezkp_stark::v1::proof::{ProofParams, ProofV1};
+use sezkp_stark::v1::verify::{verify_v1, verify_v1_under, BlockFacts, Requirements};
+use sezkp_stark::{ProvingBackend, StarkV1};
+
+const ROOT: [u8; 32] = [7u8; 32];
+
+/// A τ=1 block of `t` steps alternating `+jump` / `-1`, never writing.
+fn jumping_blocks(t: usize, jump: i8) -> Vec<BlockSummary> {
+    let moves: Vec<i8> = (0..t).map(|i| if i % 2 == 0 { jump } else { -1 }).collect();
+    let head_out: i64 = moves.iter().map(|&m| i64::from(m)).sum();
+    let steps = moves
+        .into_iter()
+        .map(|mv| StepProjection {
+            input_mv: 0,
+            tapes: vec![TapeOp { write: None, mv }],
+        })
+        .collect();
+    vec![BlockSummary {
+        version: 1,
+        block_id: 1,
+        step_lo: 1,
+        step_hi: t as u64,
+        ctrl_in: 0,
+        ctrl_out: 0,
+        in_head_in: 0,
+        in_head_out: 0,
+        windows: vec![Window {
+            left: 0,
+            right: t as i64 * i64::from(jump),
+        }],
+        head_in_offsets: vec![0],
+        head_out_offsets: vec![u32::try_from(head_out).unwrap()],
+        movement_log: MovementLog { steps },
+        pre_tags: vec![[0u8; 16]; 1],
+        post_tags: vec![[0u8; 16]; 1],
+        write_commitments: Vec::new(),
+        aux_commitments: Vec::new(),
+    }]
+}
+
+fn decode(art: &sezkp_core::ProofArtifact) -> ProofV1 {
+    bincode::deserialize(&art.proof_bytes).unwrap()
+}
+
+fn with_params(art: &sezkp_core::ProofArtifact, params: ProofParams) -> sezkp_core::ProofArtifact {
+    let mut proof = decode(art);
+    proof.params = params;
+    let mut forged = art.clone();
+    forged.proof_bytes = bincode::serialize(&proof).unwrap();
+    forged
+}
+
+#[test]
+fn unit_moves_declare_the_unit_model() {
+    let blocks = jumping_blocks(64, 1);
+    let art = StarkV1::prove(&blocks, ROOT).unwrap();
+    assert_eq!(decode(&art).params, ProofParams::UNIT);
+    verify_v1(&decode(&art), &blocks).unwrap();
+}
+
+#[test]
+fn bounded_jumps_prove_under_a_wider_model() {
+    let blocks = jumping_blocks(64, 3);
+    let facts = BlockFacts::of(&blocks);
+    let art = StarkV1::prove(&blocks, ROOT).unwrap();
+    let params = decode(&art).params;
+    assert_eq!(params.max_move, 3);
+    assert_eq!(params.mv_domain_degree(), 7);
+    let expect = |max_move| Requirements {
+        max_move,
+        ..Requirements::default()
+    };
+    verify_v1_under(&decode(&art), &facts, None, &expect(3)).unwrap();
+
+    // The default verifier expects unit moves.
+    let err = verify_v1(&decode(&art), &blocks).unwrap_err();
+    assert!(err.to_string().contains("expected ±1"), "{err}");
+
+    // Declaring a narrower or wider model than the one proven fails, even
+    // to a verifier expecting it.
+    for max_move in [1, 2, 4] {
+        let forged = with_params(&art, ProofParams { max_move, ..ProofParams::UNIT });
+        let forged = decode(&forged);
+        assert!(
+            verify_v1_under(&forged, &facts, None, &expect(max_move)).is_err(),
+            "±{max_move}"
+        );
+    }
+}
+
+// The only test here that sets the process environment.
+#[test]
+fn backend_expects_the_configured_model() {
+    let blocks = jumping_blocks(64, 3);
+    let art = StarkV1::prove(&blocks, ROOT).unwrap();
+    assert!(StarkV1::verify(&art, &blocks, ROOT).is_err());
+    std::env::set_var(ENV_EXPECT_MAX_MOVE, "3");
+    StarkV1::verify(&art, &blocks, ROOT).unwrap();
+    std::env::set_var(ENV_EXPECT_MAX_MOVE, "9");
+    assert!(StarkV1::verify(&art, &blocks, ROOT).is_err());
+    std::env::remove_var(ENV_EXPECT_MAX_MOVE);
+}
+
+#[test]
+fn models_beyond_the_limit_are_rejected() {
+    let blocks = jumping_blocks(16, 9);
+    assert!(StarkV1::prove(&blocks, ROOT).is_err());
+
+    let art = StarkV1::prove(&jumping_blocks(16, 1), ROOT).unwrap();
+    for max_move in [0, 9] {
+        let forged = with_params(&art, ProofParams { max_move, ..ProofParams::UNIT });
+        let err = StarkV1::verify(&forged, &blocks, ROOT).unwrap_err();
+        assert!(format!("{err:#}").contains("movement bound"), "{err:#}");
+    }
+}
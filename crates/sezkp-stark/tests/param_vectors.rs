@@ -2,7 +2,8 @@
 //!
 //! Purpose:
 //! - Ensure transcript-driven parameters (alphas, query indices) are derived
-//!   deterministically from bound public inputs (manifest root, n, τ, column roots).
+//!   deterministically from bound public inputs (manifest root, n, τ, params
+//!   digest, column roots and layouts).
 //!
 //! How it runs:
 //! - Reads a JSON file with fixed inputs. If the file is missing, the test is
@@ -20,6 +21,7 @@ use std::path::PathBuf;
 use serde::Deserialize;
 use sezkp_crypto::{Blake3Transcript, Transcript};
 use sezkp_stark::v1::params;
+use sezkp_stark::v1::proof::{FriParams, ProofParams, PublicInputs};
 
 #[derive(Deserialize)]
 struct Inputs {
@@ -27,6 +29,9 @@ struct Inputs {
     n: usize,
     tau: usize,
     col_roots_hex: Vec<String>,
+    /// Layout of each column (committed per block or not); chunked if absent.
+    #[serde(default)]
+    col_per_block: Vec<bool>,
 }
 
 fn hex32(s: &str) -> [u8; 32] {
@@ -62,15 +67,21 @@ fn vectors_transcript_challenges_stable() {
     let v: Inputs = serde_json::from_str(&data).expect("parse");
 
     // Bind the same public inputs into a transcript as the protocol does.
-    let manifest_root = hex32(&v.manifest_root_hex);
+    let public = PublicInputs {
+        n_rows: v.n,
+        tau: v.tau,
+        manifest_root: hex32(&v.manifest_root_hex),
+        params_digest: params::params_digest(&ProofParams::UNIT, &FriParams::default()),
+        claims: None,
+    };
     let mut tr = Blake3Transcript::new(params::DS_V1_DOMAIN);
-    tr.absorb("manifest_root", &manifest_root);
-    tr.absorb_u64("n", v.n as u64);
-    tr.absorb_u64("tau", v.tau as u64);
+    params::absorb_public_inputs(&mut tr, &public);
     tr.absorb_u64(params::DS_N_COLS, v.col_roots_hex.len() as u64);
-    for rhex in &v.col_roots_hex {
+    for (i, rhex) in v.col_roots_hex.iter().enumerate() {
         let r = hex32(rhex);
         tr.absorb(params::DS_COL_ROOT, &r);
+        let per_block = v.col_per_block.get(i).copied().unwrap_or(false);
+        tr.absorb_u64(params::DS_COL_PER_BLOCK, u64::from(per_block));
     }
 
     // Derive alphas and query indices; basic sanity invariants.
@@ -0,0 +1,50 @@
+//! Prover profiles: STARK v1 artifacts record per-phase timing and
+//! temporary-memory counters in `meta.profile`.
+
+#![allow(clippy::unwrap_used)]
+
+mod utils;
+
+use sezkp_stark::v1::profile::ProveProfile;
+use sezkp_stark::{ProvingBackend, StarkV1};
+use utils::demo_blocks;
+
+const ROOT: [u8; 32] = [7u8; 32];
+
+#[test]
+fn artifacts_record_phase_profile() {
+    let blocks = demo_blocks(64);
+    for art in [
+        StarkV1::prove(&blocks, ROOT).unwrap(),
+        StarkV1::prove_with_beacon(&blocks, ROOT, b"beacon").unwrap(),
+        StarkV1::prove_streaming(&blocks, ROOT).unwrap(),
+    ] {
+        let profile = ProveProfile::from_meta(&art.meta).unwrap();
+        let phases: Vec<_> = profile.phases.iter().map(|p| p.phase.as_str()).collect();
+        assert_eq!(
+            phases,
+            [
+                "columns",
+                "lde_layer0",
+                "fri_folds",
+                "air_openings",
+                "fri_openings"
+            ]
+        );
+
+        // The LDE values (8 bytes per point) stay live from layer 0 on.
+        let lde_bytes = 8 * art.meta["domain_n"].as_u64().unwrap();
+        assert!(profile.phases[1].peak_temp_bytes >= lde_bytes);
+        assert_eq!(
+            profile.peak_temp_bytes,
+            profile
+                .phases
+                .iter()
+                .map(|p| p.peak_temp_bytes)
+                .max()
+                .unwrap()
+        );
+        assert!(profile.total_ms() > 0.0);
+        StarkV1::verify(&art, &blocks, ROOT).unwrap();
+    }
+}
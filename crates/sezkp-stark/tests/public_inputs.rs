@@ -0,0 +1,61 @@
+//! Public inputs at the head of v1 proofs: readable without decoding the
+//! rest of the proof, absorbed first into the transcript, and committing to
+//! the declared parameters through their digest.
+
+#![allow(clippy::unwrap_used)]
+
+mod utils;
+
+use sezkp_core::claims::{claims_digest, Claim};
+use sezkp_stark::v1::params;
+use sezkp_stark::v1::proof::{ProofV1, PublicInputs};
+use sezkp_stark::v1::verify::verify_v1;
+use sezkp_stark::{ProvingBackend, StarkV1};
+use utils::demo_blocks;
+
+const ROOT: [u8; 32] = [7u8; 32];
+
+#[test]
+fn public_inputs_head_the_encoding() {
+    let blocks = demo_blocks(64);
+    let claims = [Claim::new("output", "42")];
+    let art = StarkV1::prove_with_claims(&blocks, ROOT, &claims).unwrap();
+    let proof = ProofV1::decode(&art.proof_bytes).unwrap();
+
+    let public = PublicInputs::peek(&art.proof_bytes).unwrap();
+    assert_eq!(public, proof.public);
+    assert_eq!(public.n_rows, 64);
+    assert_eq!(public.tau, 1);
+    assert_eq!(public.manifest_root, ROOT);
+    assert_eq!(public.claims, claims_digest(&claims));
+    assert_eq!(
+        public.params_digest,
+        params::params_digest(&proof.params, &proof.fri_params)
+    );
+
+    let empty = ProofV1::empty();
+    let bytes = bincode::serialize(&empty).unwrap();
+    assert_eq!(PublicInputs::peek(&bytes).unwrap(), empty.public);
+}
+
+#[test]
+fn public_inputs_must_match_the_proof() {
+    let blocks = demo_blocks(64);
+    let art = StarkV1::prove(&blocks, ROOT).unwrap();
+    let proof = ProofV1::decode(&art.proof_bytes).unwrap();
+    verify_v1(&proof, &blocks).unwrap();
+
+    // A declared parameter the digest does not commit to.
+    let mut p = proof.clone();
+    p.params.max_move += 1;
+    assert!(verify_v1(&p, &blocks).is_err());
+
+    // A digest recomputed for the edited parameters changes the transcript.
+    p.public.params_digest = params::params_digest(&p.params, &p.fri_params);
+    assert!(verify_v1(&p, &blocks).is_err());
+
+    // A row count other than the FRI domain's.
+    let mut p = proof;
+    p.public.n_rows *= 2;
+    assert!(verify_v1(&p, &blocks).is_err());
+}
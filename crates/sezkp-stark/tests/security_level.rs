@@ -0,0 +1,153 @@
+//! Query counts sized for a target security level.
+//!
+//! The prover turns a target into a query count, records the count in
+//! `FriParams` (binding any non-default one into the transcript) and the
+//! target and estimate in the artifact's `meta`; the verifier recomputes the
+//! estimate from the proof and enforces a minimum, by default that of
+//! [`NUM_QUERIES`] queries.
+
+#![allow(clippy::unwrap_used)]
+
+mod utils;
+
+use sezkp_core::BlockSummary;
+use sezkp_stark::v1::params::{
+    self, FriQuerySchedule, StreamTuning, BLOWUP, GRINDING_BITS, NUM_QUERIES,
+};
+use sezkp_stark::v1::proof::ProofV1;
+use sezkp_stark::v1::prover::{prove_v1, prove_v1_with_queries};
+use sezkp_stark::v1::verify::{verify_v1, verify_v1_under, BlockFacts, Requirements};
+use sezkp_stark::{ProvingBackend, StarkV1};
+use utils::demo_blocks;
+
+const ROOT: [u8; 32] = [7u8; 32];
+
+fn prove(blocks: &[BlockSummary], num_queries: usize) -> anyhow::Result<ProofV1> {
+    prove_v1_with_queries(
+        blocks,
+        ROOT,
+        None,
+        StreamTuning::default(),
+        &FriQuerySchedule::Chained,
+        None,
+        None,
+        num_queries,
+    )
+    .map(|(p, _)| p)
+}
+
+#[test]
+fn query_counts_follow_the_target() {
+    // Blowup 8: three bits per query.
+    assert_eq!(params::queries_for_security(100, 8, 0).unwrap(), 34);
+    assert_eq!(params::queries_for_security(90, 8, 0).unwrap(), 30);
+    assert_eq!(params::queries_for_security(100, 8, 16).unwrap(), 28);
+    assert_eq!(params::queries_for_security(100, 16, 0).unwrap(), 25);
+    assert_eq!(params::queries_for_security(0, 8, 0).unwrap(), 1);
+    assert_eq!(params::security_bits(34, 8, 0), 102);
+    assert_eq!(params::DEFAULT_MIN_SECURITY_BITS, 90);
+    assert!(params::queries_for_security(100, 1, 0).is_err());
+    assert!(params::queries_for_security(100, 12, 0).is_err());
+    assert!(params::queries_for_security(1000, 8, 0).is_err());
+
+    // The default count under the chained schedule.
+    let fp = FriQuerySchedule::Chained.resolve(9, NUM_QUERIES).unwrap();
+    assert_eq!(fp.security_bits(), NUM_QUERIES * 3 + GRINDING_BITS);
+    // A per-layer schedule is as strong as its weakest layer.
+    let fp = FriQuerySchedule::PerLayer(vec![40, 20])
+        .resolve(2, 34)
+        .unwrap();
+    assert_eq!(fp.security_bits(), 60);
+    assert!(FriQuerySchedule::Chained.resolve(9, 0).is_err());
+}
+
+#[test]
+fn sized_proofs_verify_and_bind_their_count() {
+    let blocks = demo_blocks(64);
+    let k = params::queries_for_security(100, BLOWUP, GRINDING_BITS).unwrap();
+    let proof = prove(&blocks, k).unwrap();
+    verify_v1(&proof, &blocks).unwrap();
+    assert_eq!(proof.fri_params.num_queries, k);
+    assert_eq!((proof.queries.len(), proof.fri_queries.len()), (k, k));
+    assert!(proof.fri_params.security_bits() >= 100);
+
+    // The default count leaves the proof `prove_v1` emits unchanged.
+    assert_eq!(
+        bincode::serialize(&prove(&blocks, NUM_QUERIES).unwrap()).unwrap(),
+        bincode::serialize(&prove_v1(&blocks, ROOT).unwrap()).unwrap()
+    );
+
+    // Relabelling the count, even with the openings cut to match, moves the
+    // positions.
+    let mut bad = proof;
+    bad.fri_params.num_queries = NUM_QUERIES;
+    bad.queries.truncate(NUM_QUERIES);
+    bad.fri_queries.truncate(NUM_QUERIES);
+    assert!(verify_v1(&bad, &blocks).is_err());
+}
+
+#[test]
+fn weak_proofs_need_an_explicit_opt_out() {
+    let blocks = demo_blocks(64);
+    let facts = BlockFacts::of(&blocks);
+    let weak = prove(&blocks, 1).unwrap();
+    let err = verify_v1(&weak, &blocks).unwrap_err();
+    assert!(err.to_string().contains("below the required 90"), "{err}");
+    let below = prove(&blocks, NUM_QUERIES - 1).unwrap();
+    assert!(verify_v1(&below, &blocks).is_err());
+
+    let req = Requirements {
+        min_security_bits: 3,
+        ..Requirements::default()
+    };
+    verify_v1_under(&weak, &facts, None, &req).unwrap();
+    let req = Requirements {
+        min_security_bits: 102,
+        ..Requirements::default()
+    };
+    assert!(verify_v1_under(&below, &facts, None, &req).is_err());
+}
+
+// The only test here that touches the process environment.
+#[test]
+fn backend_records_and_enforces_security_levels() {
+    let blocks = demo_blocks(64);
+    std::env::set_var(params::ENV_SECURITY_BITS, "100");
+    let art = StarkV1::prove(&blocks, ROOT).unwrap();
+    std::env::remove_var(params::ENV_SECURITY_BITS);
+    let security = &art.meta["security"];
+    assert_eq!(security["target_bits"], 100);
+    assert_eq!(security["achieved_bits"], 102);
+    assert_eq!(security["num_queries"], 34);
+
+    std::env::set_var(params::ENV_MIN_SECURITY_BITS, "102");
+    StarkV1::verify(&art, &blocks, ROOT).unwrap();
+    let default = StarkV1::prove(&blocks, ROOT).unwrap();
+    assert_eq!(default.meta["security"]["achieved_bits"], 90);
+    let err = StarkV1::verify(&default, &blocks, ROOT).unwrap_err();
+    assert!(err.to_string().contains("below the required 102"), "{err}");
+
+    // The variable only lowers the default floor under the opt-out.
+    std::env::set_var(params::ENV_SECURITY_BITS, "60");
+    let weak = StarkV1::prove(&blocks, ROOT).unwrap();
+    std::env::remove_var(params::ENV_SECURITY_BITS);
+    std::env::set_var(params::ENV_MIN_SECURITY_BITS, "60");
+    assert!(StarkV1::verify(&weak, &blocks, ROOT).is_err());
+    std::env::set_var(params::ENV_ALLOW_LOW_SECURITY, "1");
+    StarkV1::verify(&weak, &blocks, ROOT).unwrap();
+    std::env::remove_var(params::ENV_MIN_SECURITY_BITS);
+    assert!(StarkV1::verify(&weak, &blocks, ROOT).is_err());
+
+    // A per-layer schedule too weak for the target is refused.
+    std::env::set_var(params::ENV_SECURITY_BITS, "100");
+    std::env::set_var(params::ENV_FRI_LAYER_QUERIES, "8");
+    assert!(StarkV1::prove(&blocks, ROOT).is_err());
+    for var in [
+        params::ENV_SECURITY_BITS,
+        params::ENV_MIN_SECURITY_BITS,
+        params::ENV_ALLOW_LOW_SECURITY,
+        params::ENV_FRI_LAYER_QUERIES,
+    ] {
+        std::env::remove_var(var);
+    }
+}
@@ -0,0 +1,158 @@
+//! Streaming column-root verification: the verifier re-derives every column
+//! root from a block stream and rejects blocks the proof was not built from.
+
+#![allow(clippy::unwrap_used)]
+
+use sezkp_core::{BlockSummary, MovementLog, StepProjection, TapeOp, Window, EMPTY_ROOT};
+use sezkp_stark::v1::{
+    openings::{ColumnRootBuilder, OnDemandOpenings},
+    params,
+    proof::{ColumnRoot, ProofV1},
+    verify,
+};
+use sezkp_stark::{ProvingBackend, StarkV1};
+
+const ROOT: [u8; 32] = [9u8; 32];
+
+/// A τ=1 block of `t` steps alternating right/stay, writing every third step.
+fn block(id: u32, step_lo: u64, t: usize) -> BlockSummary {
+    let steps: Vec<_> = (0..t)
+        .map(|i| StepProjection {
+            input_mv: 0,
+            tapes: vec![TapeOp {
+                write: (i % 3 == 0).then_some(5),
+                mv: i8::from(i % 2 == 0),
+            }],
+        })
+        .collect();
+    BlockSummary {
+        version: 1,
+        block_id: id,
+        step_lo,
+        step_hi: step_lo + t as u64 - 1,
+        ctrl_in: 0,
+        ctrl_out: 0,
+        in_head_in: 0,
+        in_head_out: 0,
+        windows: vec![Window {
+            left: 0,
+            right: t as i64 - 1,
+        }],
+        head_in_offsets: vec![0],
+        head_out_offsets: vec![t.div_ceil(2) as u32],
+        movement_log: MovementLog { steps },
+        pre_tags: vec![[0u8; 16]; 1],
+        post_tags: vec![[0u8; 16]; 1],
+        write_commitments: Vec::new(),
+        aux_commitments: Vec::new(),
+    }
+}
+
+/// 64 rows in uneven blocks.
+fn demo_blocks() -> Vec<BlockSummary> {
+    vec![block(1, 1, 20), block(2, 21, 20), block(3, 41, 24)]
+}
+
+fn stream(blocks: &[BlockSummary]) -> impl Iterator<Item = anyhow::Result<BlockSummary>> + '_ {
+    blocks.iter().cloned().map(Ok)
+}
+
+fn pairs(roots: &[ColumnRoot]) -> Vec<(String, [u8; 32])> {
+    roots.iter().map(|c| (c.label.clone(), c.root)).collect()
+}
+
+#[test]
+fn block_by_block_roots_match_the_prover() {
+    // 2048 rows, so a column chunk boundary falls mid-block.
+    let blocks = [block(1, 1, 700), block(2, 701, 700), block(3, 1401, 648)];
+    let mut builder = ColumnRootBuilder::new(1, params::COL_CHUNK_LOG2);
+    for b in &blocks {
+        builder.push_block(b).unwrap();
+    }
+    assert_eq!(builder.n_rows(), 2 << params::COL_CHUNK_LOG2);
+    let built = OnDemandOpenings::new(&blocks, params::COL_CHUNK_LOG2).build_roots();
+    assert_eq!(pairs(&builder.finish()), pairs(&built));
+
+    let blocks = demo_blocks();
+    let art = StarkV1::prove(&blocks, ROOT).unwrap();
+    let proof: ProofV1 = bincode::deserialize(&art.proof_bytes).unwrap();
+    assert_eq!(
+        verify::verify_col_roots(&proof, stream(&blocks)).unwrap(),
+        3
+    );
+    StarkV1::verify_stream(&art, stream(&blocks), ROOT, None).unwrap();
+}
+
+#[test]
+fn blocks_that_differ_from_the_proof_are_rejected() {
+    let blocks = demo_blocks();
+    let art = StarkV1::prove(&blocks, ROOT).unwrap();
+
+    // One changed symbol: only the column roots can notice.
+    let mut edited = blocks.clone();
+    edited[1].movement_log.steps[3].tapes[0].write = Some(6);
+    let err = StarkV1::verify_stream(&art, stream(&edited), ROOT, None).unwrap_err();
+    assert!(
+        err.to_string().contains("column root mismatch for wsym_0"),
+        "{err}"
+    );
+
+    // A missing block changes the row count.
+    let err = StarkV1::verify_stream(&art, stream(&blocks[..2]), ROOT, None).unwrap_err();
+    assert!(err.to_string().contains("blocks have 40 rows"), "{err}");
+
+    // Malformed blocks are errors, not panics.
+    let mut short = blocks.clone();
+    short[2].movement_log.steps.pop();
+    let err = StarkV1::verify_stream(&art, stream(&short), ROOT, None).unwrap_err();
+    assert!(err.to_string().contains("logged steps"), "{err}");
+
+    // Stream errors surface with the block position.
+    let failing = stream(&blocks[..1]).chain([Err(anyhow::anyhow!("truncated"))]);
+    let err = StarkV1::verify_stream(&art, failing, ROOT, None).unwrap_err();
+    assert!(
+        format!("{err:#}").contains("reading block #1: truncated"),
+        "{err:#}"
+    );
+}
+
+#[test]
+fn streaming_verify_keeps_the_beacon_and_empty_checks() {
+    let blocks = demo_blocks();
+    let art = StarkV1::prove_with_beacon(&blocks, ROOT, b"beacon").unwrap();
+    StarkV1::verify_stream(&art, stream(&blocks), ROOT, Some(b"beacon")).unwrap();
+    assert!(StarkV1::verify_stream(&art, stream(&blocks), ROOT, Some(b"other")).is_err());
+
+    let root = EMPTY_ROOT;
+    let empty = StarkV1::prove(&[], root).unwrap();
+    StarkV1::verify_stream(&empty, stream(&[]), root, None).unwrap();
+    let err = StarkV1::verify_stream(&empty, stream(&blocks), root, None).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("empty proof presented for 3 blocks"),
+        "{err}"
+    );
+}
+
+#[test]
+fn streaming_prover_verifies_stark_proofs_from_an_iterator() {
+    use sezkp_core::prover::StreamingProver;
+
+    let blocks = demo_blocks();
+    let art = StarkV1::prove(&blocks, ROOT).unwrap();
+    StreamingProver::<StarkV1>::verify_stream_iter(&art, stream(&blocks), ROOT).unwrap();
+
+    // Each block clears replay before it reaches the column roots.
+    let mut edited = blocks.clone();
+    edited[1].movement_log.steps[3].tapes[0].write = Some(6);
+    let err =
+        StreamingProver::<StarkV1>::verify_stream_iter(&art, stream(&edited), ROOT).unwrap_err();
+    assert!(
+        err.to_string().contains("column root mismatch for wsym_0"),
+        "{err}"
+    );
+    let swapped = [blocks[1].clone(), blocks[0].clone(), blocks[2].clone()];
+    let err =
+        StreamingProver::<StarkV1>::verify_stream_iter(&art, stream(&swapped), ROOT).unwrap_err();
+    assert!(format!("{err:#}").contains("out of order"), "{err:#}");
+}
@@ -4,10 +4,12 @@
 use sezkp_core::{BlockSummary, MovementLog, StepProjection, TapeOp, Window};
 use sezkp_stark::v1::{
     columns::TraceColumns,
-    merkle::{hash_field_leaves_labeled, ColumnCommit},
+    merkle::{block_column_tree, hash_block_leaf, hash_field_leaves_labeled, ColumnCommit},
     openings::OnDemandOpenings,
     params,
+    proof::ColumnRoot,
 };
+use sezkp_trace::{generator::generate_trace, partition::partition_trace};
 
 /// Build a tiny valid block set with tau=1, simple head walk and occasional writes.
 fn demo_blocks(t: usize) -> Vec<BlockSummary> {
@@ -42,19 +44,19 @@ fn demo_blocks(t: usize) -> Vec<BlockSummary> {
         movement_log: MovementLog { steps },
         pre_tags: vec![[0u8; 16]; 1],
         post_tags: vec![[0u8; 16]; 1],
+        write_commitments: Vec::new(),
+        aux_commitments: Vec::new(),
     }]
 }
 
-#[test]
-fn streamed_column_roots_equal_in_memory() {
-    let blocks = demo_blocks(32);
-
-    // Streaming roots via on-demand builder
-    let mut odo = OnDemandOpenings::new(&blocks, params::COL_CHUNK_LOG2);
+/// Compare the streamed roots of `blocks` (with the input-head columns a
+/// declared input length adds) with roots computed from in-memory columns.
+fn assert_roots_match_in_memory(blocks: &[BlockSummary]) -> Vec<ColumnRoot> {
+    let odo = OnDemandOpenings::new(blocks, params::COL_CHUNK_LOG2).with_input_head(true);
     let streamed_roots = odo.build_roots();
 
     // In-memory baseline columns
-    let tc = TraceColumns::build(&blocks).expect("trace columns");
+    let tc = TraceColumns::build_with_input_head(blocks, true).expect("trace columns");
     let mut cols: Vec<(&[sezkp_stark::v1::field::F1], String)> = Vec::new();
     cols.push((&tc.input_mv, "input_mv".into()));
     cols.push((&tc.is_first, "is_first".into()));
@@ -80,22 +82,76 @@ fn streamed_column_roots_equal_in_memory() {
     for (i, c) in tc.out_off.iter().enumerate() {
         cols.push((c, format!("out_off_{i}")));
     }
+    cols.push((&tc.in_head, "in_head".into()));
+    cols.push((&tc.in_head_in, "in_head_in".into()));
+    cols.push((&tc.in_head_out, "in_head_out".into()));
 
-    // Compute roots in-memory and compare one-by-one
+    // Compute roots in-memory and compare one-by-one: columns constant
+    // within every block commit one leaf per block (its row range and
+    // value), the others one leaf per row.
+    let mut bounds = Vec::new();
+    let mut lo = 0usize;
+    for b in blocks {
+        let hi = lo + (b.step_hi - b.step_lo) as usize;
+        bounds.push((lo, hi));
+        lo = hi + 1;
+    }
     assert_eq!(streamed_roots.len(), cols.len());
     for (i, (col, label)) in cols.iter().enumerate() {
-        let leaves8: Vec<[u8; 8]> = col.iter().map(|x| x.to_le_bytes()).collect();
-        let leaf_hashes = hash_field_leaves_labeled(&leaves8, label);
-        let commit = ColumnCommit::from_hashed_leaves(&leaf_hashes, params::COL_CHUNK_LOG2);
+        let per_block = bounds
+            .iter()
+            .all(|&(lo, hi)| col[lo..=hi].iter().all(|x| *x == col[lo]));
+        let root = if per_block {
+            let leaves: Vec<_> = bounds
+                .iter()
+                .map(|&(lo, hi)| hash_block_leaf(label, lo, hi, col[lo].to_le_bytes()))
+                .collect();
+            block_column_tree(&leaves).root()
+        } else {
+            let leaves8: Vec<[u8; 8]> = col.iter().map(|x| x.to_le_bytes()).collect();
+            let leaf_hashes = hash_field_leaves_labeled(&leaves8, label);
+            ColumnCommit::from_hashed_leaves(&leaf_hashes, params::COL_CHUNK_LOG2).root()
+        };
         assert_eq!(
             streamed_roots[i].label, *label,
             "label mismatch at index {i}"
         );
+        assert_eq!(streamed_roots[i].per_block, per_block, "layout of {label}");
         assert_eq!(
-            streamed_roots[i].root,
-            commit.root(),
+            streamed_roots[i].root, root,
             "root mismatch at column {} ({label})",
             i
         );
     }
+
+    streamed_roots
+}
+
+/// Whether `label` was committed per block.
+fn per_block(roots: &[ColumnRoot], label: &str) -> bool {
+    roots.iter().find(|c| c.label == label).unwrap().per_block
+}
+
+#[test]
+fn streamed_column_roots_equal_in_memory() {
+    let roots = assert_roots_match_in_memory(&demo_blocks(32));
+    // The structural columns are constant within a block; the head walk is not.
+    assert!(per_block(&roots, "winlen_0") && per_block(&roots, "in_head_in"));
+    assert!(!per_block(&roots, "mv_0") && !per_block(&roots, "head_0"));
+}
+
+#[test]
+fn columns_varying_after_constant_blocks_are_chunked() {
+    // Two idle blocks, then two random ones: every step column is held per
+    // block at first and must fall back to the same chunked root.
+    let mut trace = generate_trace(64, 2);
+    for step in &mut trace.steps[..32] {
+        step.input_mv = 0;
+        for op in &mut step.tapes {
+            *op = sezkp_trace::format::TapeOp { write: None, mv: 0 };
+        }
+    }
+    let roots = assert_roots_match_in_memory(&partition_trace(&trace, 16));
+    assert!(!per_block(&roots, "mv_0") && !per_block(&roots, "wflag_1"));
+    assert!(per_block(&roots, "out_off_1"));
 }
@@ -27,9 +27,10 @@ use sezkp_stark::{
         field::F1,
         fri_stream::StreamingLayerBuilder,
         lde::deep_coset_lde_stream,
-        merkle::{hash_field_leaves, MerkleTree},
+        merkle::fri_layer_tree,
         openings::OnDemandOpenings,
         params,
+        proof::{FriParams, ProofParams, PublicInputs},
     },
     ProvingBackend, StarkV1,
 };
@@ -66,6 +67,8 @@ fn demo_blocks(t: usize) -> Vec<BlockSummary> {
         movement_log: MovementLog { steps },
         pre_tags: vec![[0u8; 16]; 1],
         post_tags: vec![[0u8; 16]; 1],
+        write_commitments: Vec::new(),
+        aux_commitments: Vec::new(),
     }]
 }
 
@@ -83,12 +86,18 @@ fn fri_roots_streaming_match_incore_baseline_and_verify() {
     // Transcript prelude (exactly like the prover).
     let mut tr = Blake3Transcript::new(params::DS_V1_DOMAIN);
     let manifest_root = [7u8; 32];
-    tr.absorb("manifest_root", &manifest_root);
-    tr.absorb_u64("n", tc.n as u64);
-    tr.absorb_u64("tau", tc.tau as u64);
+    let public = PublicInputs {
+        n_rows: tc.n,
+        tau: tc.tau,
+        manifest_root,
+        params_digest: params::params_digest(&ProofParams::UNIT, &FriParams::default()),
+        claims: None,
+    };
+    params::absorb_public_inputs(&mut tr, &public);
     tr.absorb_u64(params::DS_N_COLS, col_roots.len() as u64);
     for r in &col_roots {
         tr.absorb(params::DS_COL_ROOT, &r.root);
+        tr.absorb_u64(params::DS_COL_PER_BLOCK, u64::from(r.per_block));
     }
 
     // AIR alphas.
@@ -134,7 +143,7 @@ fn fri_roots_streaming_match_incore_baseline_and_verify() {
     let mut lde_stream_vals_as_f1 = Vec::<F1>::with_capacity(lde_n); // for folds
 
     let mut base_eval = |i: usize| -> [u8; 8] {
-        let v = compose_row(&tc, i, &alphas) + compose_boundary(&tc, i, &alphas);
+        let v = compose_row(&tc, i, &alphas, 1) + compose_boundary(&tc, i, &alphas);
         v.to_le_bytes()
     };
     deep_coset_lde_stream(
@@ -158,7 +167,7 @@ fn fri_roots_streaming_match_incore_baseline_and_verify() {
     use sezkp_ffts::{coset::evaluate_on_coset_pow2, ntt::interpolate_from_evals};
     let mut base_vals = Vec::with_capacity(tc.n);
     for i in 0..tc.n {
-        base_vals.push(compose_row(&tc, i, &alphas) + compose_boundary(&tc, i, &alphas));
+        base_vals.push(compose_row(&tc, i, &alphas, 1) + compose_boundary(&tc, i, &alphas));
     }
     let coeffs = interpolate_from_evals(&base_vals);
     let mut lde_vals = evaluate_on_coset_pow2(&coeffs, lde_k_log2, shift);
@@ -171,13 +180,7 @@ fn fri_roots_streaming_match_incore_baseline_and_verify() {
         w_pow *= w;
     }
     let root0_mem = {
-        let leaves = hash_field_leaves(
-            &lde_vals
-                .iter()
-                .map(|v| v.to_le_bytes())
-                .collect::<Vec<_>>(),
-        );
-        let mt = MerkleTree::from_leaves(&leaves);
+        let mt = fri_layer_tree(&lde_vals.iter().map(|v| v.to_le_bytes()).collect::<Vec<_>>());
         mt.root()
     };
 
@@ -204,22 +207,22 @@ fn fri_roots_streaming_match_incore_baseline_and_verify() {
 
         // Compare layer-1 roots.
         let r_stream = {
-            let leaves = hash_field_leaves(
+            fri_layer_tree(
                 &fold_stream_vec
                     .iter()
                     .map(|v| v.to_le_bytes())
                     .collect::<Vec<_>>(),
-            );
-            MerkleTree::from_leaves(&leaves).root()
+            )
+            .root()
         };
         let r_mem = {
-            let leaves = hash_field_leaves(
+            fri_layer_tree(
                 &fold_mem_vec
                     .iter()
                     .map(|v| v.to_le_bytes())
                     .collect::<Vec<_>>(),
-            );
-            MerkleTree::from_leaves(&leaves).root()
+            )
+            .root()
         };
         assert_eq!(r_stream, r_mem, "layer-1 root mismatch");
     }
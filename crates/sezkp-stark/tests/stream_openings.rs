@@ -2,11 +2,20 @@
 //!
 //! We build streamed column roots and then request on-demand openings for
 //! random rows/columns, verifying each opening against the appropriate root.
+//! Columns committed per block open with the block's row range.
 
 #![allow(clippy::unwrap_used)]
 
 use sezkp_core::{BlockSummary, MovementLog, StepProjection, TapeOp, Window};
-use sezkp_stark::v1::{merkle::verify_chunked_open, openings::OnDemandOpenings, params};
+use sezkp_stark::v1::{
+    merkle::{verify_block_open, verify_chunked_open},
+    openings::OnDemandOpenings,
+    params,
+    proof::ColumnOpening,
+    prover::prove_v1,
+    verify::verify_v1,
+};
+use sezkp_trace::{generator::generate_trace, partition::partition_trace};
 
 fn demo_blocks(t: usize) -> Vec<BlockSummary> {
     let mut steps = Vec::with_capacity(t);
@@ -40,6 +49,8 @@ fn demo_blocks(t: usize) -> Vec<BlockSummary> {
         movement_log: MovementLog { steps },
         pre_tags: vec![[0u8; 16]; 1],
         post_tags: vec![[0u8; 16]; 1],
+        write_commitments: Vec::new(),
+        aux_commitments: Vec::new(),
     }]
 }
 
@@ -54,6 +65,12 @@ fn on_demand_openings_verify_for_many_rows_and_columns() {
     // Build a label->root map for quick verify.
     let root_map: std::collections::HashMap<_, _> =
         roots.iter().map(|r| (r.label.clone(), r.root)).collect();
+    // One block: the window, the offsets and the (idle) input head are
+    // constant over it.
+    for r in &roots {
+        let constant = ["input_mv", "winlen_0", "in_off_0", "out_off_0"];
+        assert_eq!(r.per_block, constant.contains(&&*r.label), "{}", r.label);
+    }
 
     // Labels are in the streaming order used by the builder.
     let tau = 1usize;
@@ -93,19 +110,108 @@ fn on_demand_openings_verify_for_many_rows_and_columns() {
         let row = rnd();
         let col_i = rnd() % labels.len();
         let label = &labels[col_i];
+        match odo.open(label, row) {
+            ColumnOpening::Block(open) => {
+                assert_eq!((open.row_lo, open.row_hi), (0, 63));
+                assert!(verify_block_open(
+                    root_map[label],
+                    label,
+                    open.value_le,
+                    open.row_lo,
+                    open.row_hi,
+                    open.block_index,
+                    &open.path,
+                ));
+            }
+            ColumnOpening::Chunked(open) => {
+                let ok = verify_chunked_open(
+                    *root_map.get(label).expect("root"),
+                    label,
+                    open.value_le,
+                    open.chunk_root,
+                    open.index_in_chunk,
+                    &open.path_in_chunk,
+                    open.chunk_index,
+                    &open.path_to_chunk,
+                );
+                assert!(ok, "verify_chunked_open failed for {label} @ row {row}");
+            }
+        }
+    }
+}
 
-        let open = odo.open(label, row);
-
-        let ok = verify_chunked_open(
-            *root_map.get(label).expect("root"),
-            label,
-            open.value_le,
-            open.chunk_root,
-            open.index_in_chunk,
-            &open.path_in_chunk,
-            open.chunk_index,
-            &open.path_to_chunk,
-        );
-        assert!(ok, "verify_chunked_open failed for {label} @ row {row}");
+#[test]
+fn per_block_columns_open_the_block_holding_the_row() {
+    // Five blocks of 16 rows: block trees pad to eight leaves.
+    let blocks = partition_trace(&generate_trace(80, 2), 16);
+    assert_eq!(blocks.len(), 5);
+    let mut odo = OnDemandOpenings::new(&blocks, 3).with_input_head(true);
+    let root_map: std::collections::HashMap<_, _> = odo
+        .build_roots()
+        .into_iter()
+        .map(|r| (r.label, (r.per_block, r.root)))
+        .collect();
+
+    for label in [
+        "winlen_1",
+        "in_off_0",
+        "out_off_1",
+        "in_head_in",
+        "in_head_out",
+    ] {
+        assert!(root_map[label].0, "{label} is committed per block");
+        for row in [0, 15, 16, 47, 79] {
+            let ColumnOpening::Block(o) = odo.open(label, row) else {
+                panic!("{label} @ row {row} opened in chunks");
+            };
+            assert_eq!(o.block_index, row / 16);
+            assert_eq!((o.row_lo, o.row_hi), (row / 16 * 16, row / 16 * 16 + 15));
+            assert_eq!(o.path.len(), 3);
+            let check = |value_le, row_lo| {
+                verify_block_open(
+                    root_map[label].1,
+                    label,
+                    value_le,
+                    row_lo,
+                    o.row_hi,
+                    o.block_index,
+                    &o.path,
+                )
+            };
+            assert!(check(o.value_le, o.row_lo), "{label} @ row {row}");
+
+            // The leaf binds both the value and the row range.
+            let mut bad = o.value_le;
+            bad[0] ^= 1;
+            assert!(!check(bad, o.row_lo));
+            assert!(!check(o.value_le, o.row_lo + 1));
+        }
+    }
+    for label in ["mv_0", "head_1", "is_first", "in_head"] {
+        assert!(!root_map[label].0, "{label} varies within a block");
+        assert!(!odo.open(label, 5).is_block());
     }
 }
+
+#[test]
+fn block_openings_with_overflowing_row_range_are_rejected() {
+    let blocks = partition_trace(&generate_trace(64, 2), 16);
+    let proof = prove_v1(&blocks, [7u8; 32]).unwrap();
+    verify_v1(&proof, &blocks).unwrap();
+
+    // A row range ending at `usize::MAX` still covers the row; the verifier
+    // must reject it rather than overflow computing the block's end.
+    let mut forged = proof.clone();
+    let ColumnOpening::Block(o) = &mut forged.queries[0].per_tape[0].win_len else {
+        panic!("window lengths are committed per block");
+    };
+    o.row_hi = usize::MAX;
+    assert!(verify_v1(&forged, &blocks).is_err());
+
+    // Nor may an opening use another layout than its column's root.
+    let mut forged = proof;
+    let q = &mut forged.queries[0];
+    q.per_tape[0].win_len = q.per_tape[0].mv.clone();
+    let err = verify_v1(&forged, &blocks).unwrap_err();
+    assert!(err.to_string().contains("committed layout"), "{err}");
+}
@@ -0,0 +1,132 @@
+//! Streaming buffer sizes: the LDE chunk and FRI staging buffer change the
+//! prover's memory/time profile but never the proof.
+
+#![allow(clippy::unwrap_used)]
+
+use sezkp_core::{BlockSummary, MovementLog, StepProjection, TapeOp, Window};
+use sezkp_stark::v1::field::F1;
+use sezkp_stark::v1::fri_stream::{commit_layer, merkle_path_from_le_chunker};
+use sezkp_stark::v1::merkle::{
+    fri_layer_tree, hash_field_leaves, hash_field_leaves_labeled, verify_fri_open, MerkleTree,
+};
+use sezkp_stark::v1::params::{StreamTuning, ENV_FRI_BUF_LOG2, ENV_LDE_CHUNK_LOG2};
+use sezkp_stark::v1::prover::prove_v1_profiled;
+use sezkp_stark::{ProvingBackend, StarkV1};
+
+const ROOT: [u8; 32] = [7u8; 32];
+
+/// A τ=1 block of `t` steps alternating right/stay, writing on every move.
+fn demo_blocks(t: usize) -> Vec<BlockSummary> {
+    let steps: Vec<_> = (0..t)
+        .map(|i| StepProjection {
+            input_mv: 0,
+            tapes: vec![TapeOp {
+                write: (i % 2 == 0).then_some(i as u16 % 5),
+                mv: i8::from(i % 2 == 0),
+            }],
+        })
+        .collect();
+    vec![BlockSummary {
+        version: 1,
+        block_id: 1,
+        step_lo: 1,
+        step_hi: t as u64,
+        ctrl_in: 0,
+        ctrl_out: 0,
+        in_head_in: 0,
+        in_head_out: 0,
+        windows: vec![Window {
+            left: 0,
+            right: t as i64 - 1,
+        }],
+        head_in_offsets: vec![0],
+        head_out_offsets: vec![t.div_ceil(2) as u32],
+        movement_log: MovementLog { steps },
+        pre_tags: vec![[0u8; 16]; 1],
+        post_tags: vec![[0u8; 16]; 1],
+        write_commitments: Vec::new(),
+        aux_commitments: Vec::new(),
+    }]
+}
+
+#[test]
+fn commit_layer_matches_in_memory_tree() {
+    for len in [1usize, 2, 5, 64, 100] {
+        let vals: Vec<F1> = (0..len as u64).map(|i| F1::from_u64(i * i + 3)).collect();
+        let le: Vec<[u8; 8]> = vals.iter().map(|v| v.to_le_bytes()).collect();
+        let want = fri_layer_tree(&le).root();
+        for buf_log2 in [0, 1, 4, 12] {
+            assert_eq!(
+                commit_layer(&vals, buf_log2),
+                want,
+                "len={len} buf=2^{buf_log2}"
+            );
+        }
+    }
+}
+
+#[test]
+fn fri_layer_paths_verify_only_in_the_fri_domain() {
+    let vals: Vec<F1> = (0..16u64).map(|i| F1::from_u64(i * 7 + 1)).collect();
+    let le: Vec<[u8; 8]> = vals.iter().map(|v| v.to_le_bytes()).collect();
+    let root = commit_layer(&vals, 2);
+    // Column and unlabeled trees over the same bytes commit to something else.
+    let col = MerkleTree::from_leaves(&hash_field_leaves_labeled(&le, ""));
+    let plain = MerkleTree::from_leaves(&hash_field_leaves(&le));
+    assert_ne!(col.root(), root);
+    assert_ne!(plain.root(), root);
+
+    for idx in [0usize, 6, 15] {
+        let (v, path) = merkle_path_from_le_chunker(le.len(), |sink| sink(&le), idx);
+        assert_eq!(v, le[idx]);
+        assert!(verify_fri_open(root, v, idx, &path), "idx={idx}");
+        let leaf = hash_field_leaves(&[v])[0];
+        assert!(!MerkleTree::verify(root, leaf, idx, &plain.open(idx)));
+    }
+}
+
+#[test]
+fn proofs_do_not_depend_on_tuning() {
+    let blocks = demo_blocks(64);
+    let (want, _) = prove_v1_profiled(&blocks, ROOT, None, StreamTuning::default()).unwrap();
+    for (lde_chunk_log2, fri_buf_log2) in [(4, 4), (6, 16), (16, 5)] {
+        let tuning = StreamTuning {
+            lde_chunk_log2,
+            fri_buf_log2,
+        };
+        let (proof, _) = prove_v1_profiled(&blocks, ROOT, None, tuning).unwrap();
+        assert_eq!(
+            bincode::serialize(&proof).unwrap(),
+            bincode::serialize(&want).unwrap(),
+            "{tuning:?}"
+        );
+    }
+
+    for bad in [3, 25] {
+        let tuning = StreamTuning {
+            lde_chunk_log2: bad,
+            ..StreamTuning::default()
+        };
+        assert!(tuning.check().is_err());
+        assert!(prove_v1_profiled(&blocks, ROOT, None, tuning).is_err());
+    }
+}
+
+#[test]
+fn artifacts_take_tuning_from_env() {
+    let blocks = demo_blocks(16);
+    std::env::set_var(ENV_LDE_CHUNK_LOG2, "6");
+    std::env::set_var(ENV_FRI_BUF_LOG2, " 8 ");
+    let art = StarkV1::prove(&blocks, ROOT).unwrap();
+    assert_eq!(art.meta["tuning"]["lde_chunk_log2"], 6);
+    assert_eq!(art.meta["tuning"]["fri_buf_log2"], 8);
+    StarkV1::verify(&art, &blocks, ROOT).unwrap();
+
+    std::env::set_var(ENV_FRI_BUF_LOG2, "big");
+    let err = StarkV1::prove(&blocks, ROOT).unwrap_err();
+    assert!(format!("{err:#}").contains(ENV_FRI_BUF_LOG2), "{err:#}");
+
+    std::env::remove_var(ENV_LDE_CHUNK_LOG2);
+    std::env::remove_var(ENV_FRI_BUF_LOG2);
+    assert_eq!(StreamTuning::from_env().unwrap(), StreamTuning::default());
+}
@@ -4,8 +4,12 @@
 //! everywhere (including Windows and minimal CI containers). If you want to
 //! inspect RSS locally, uncomment the snippet below and add `sysinfo` as a
 //! dev-dependency (already included in Cargo.toml).
+//!
+//! Shared fixtures live here too; test files pull them in with `mod utils;`.
+
+#![allow(unused_imports, dead_code)]
 
-#![allow(unused_imports)]
+use sezkp_core::{BlockSummary, MovementLog, StepProjection, TapeOp, Window};
 
 /*
 // Example implementation using `sysinfo` (Linux/macOS/Windows):
@@ -29,3 +33,37 @@
 pub fn rss_mib() -> u64 {
     0
 }
+
+/// A τ=1 block of `t` steps alternating right/stay, never writing.
+pub fn demo_blocks(t: usize) -> Vec<BlockSummary> {
+    let steps: Vec<_> = (0..t)
+        .map(|i| StepProjection {
+            input_mv: 0,
+            tapes: vec![TapeOp {
+                write: None,
+                mv: i8::from(i % 2 == 0),
+            }],
+        })
+        .collect();
+    vec![BlockSummary {
+        version: 1,
+        block_id: 1,
+        step_lo: 1,
+        step_hi: t as u64,
+        ctrl_in: 0,
+        ctrl_out: 0,
+        in_head_in: 0,
+        in_head_out: 0,
+        windows: vec![Window {
+            left: 0,
+            right: t as i64 - 1,
+        }],
+        head_in_offsets: vec![0],
+        head_out_offsets: vec![t.div_ceil(2) as u32],
+        movement_log: MovementLog { steps },
+        pre_tags: vec![[0u8; 16]; 1],
+        post_tags: vec![[0u8; 16]; 1],
+        write_commitments: Vec::new(),
+        aux_commitments: Vec::new(),
+    }]
+}
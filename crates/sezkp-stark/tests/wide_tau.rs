@@ -0,0 +1,139 @@
+//! Wide traces: at τ = 64 the prover commits `3 + 7τ = 451` columns and
+//! opens `3 + 9τ` values per query row. These checks keep that path correct
+//! (proofs verify, openings match the streamed roots across chunks) and the
+//! batched layer-0 FRI paths equal to one-at-a-time ones.
+
+#![allow(clippy::unwrap_used)]
+
+use std::collections::HashMap;
+
+use sezkp_core::BlockSummary;
+use sezkp_stark::v1::{
+    fri_stream::{merkle_path_from_le_chunker, merkle_paths_from_le_chunker},
+    merkle::{verify_block_open, verify_chunked_open},
+    openings::OnDemandOpenings,
+    proof::{ColumnOpening, ProofV1},
+    verify::verify_col_roots,
+};
+use sezkp_stark::{ProvingBackend, StarkV1};
+use sezkp_trace::{generator::generate_trace, partition::partition_trace};
+
+const TAU: u8 = 64;
+const ROOT: [u8; 32] = [5u8; 32];
+
+fn wide_blocks(t: u64, b: u32) -> Vec<BlockSummary> {
+    partition_trace(&generate_trace(t, TAU), b)
+}
+
+#[test]
+fn sixty_four_tapes_prove_and_verify() {
+    let blocks = wide_blocks(64, 16);
+    let art = StarkV1::prove(&blocks, ROOT).unwrap();
+    StarkV1::verify(&art, &blocks, ROOT).unwrap();
+
+    let proof = ProofV1::decode(&art.proof_bytes).unwrap();
+    let tau = usize::from(TAU);
+    assert_eq!(proof.public.tau, tau);
+    assert_eq!(proof.col_roots.len(), 3 + 7 * tau);
+    assert!(proof.queries.iter().all(|q| q.per_tape.len() == tau));
+    let n = verify_col_roots(&proof, blocks.iter().cloned().map(Ok)).unwrap();
+    assert_eq!(n, blocks.len());
+}
+
+#[test]
+fn wide_openings_match_roots_across_chunks() {
+    // Four-row chunks, so rows hop between chunks as they are opened. Like
+    // every proven trace, the row count is a power of two.
+    let blocks = wide_blocks(64, 8);
+    let mut odo = OnDemandOpenings::new(&blocks, 2);
+    // A bounded outer-tree cache evicts and rebuilds but opens the same.
+    let mut bounded = OnDemandOpenings::new(&blocks, 2).with_outer_cache(3);
+    let roots: HashMap<String, (bool, [u8; 32])> = odo
+        .build_roots()
+        .into_iter()
+        .map(|r| (r.label, (r.per_block, r.root)))
+        .collect();
+    assert_eq!(roots.len(), 3 + 7 * usize::from(TAU));
+
+    let mut x = 0x9e37_79b9_7f4a_7c15u64;
+    let mut rnd = |m: usize| {
+        x ^= x << 7;
+        x ^= x >> 9;
+        (x % m as u64) as usize
+    };
+    for _ in 0..64 {
+        let row = rnd(64);
+        let label = match rnd(4) {
+            0 => "input_mv".to_owned(),
+            1 => format!("head_{}", rnd(64)),
+            2 => format!("wsym_{}", rnd(64)),
+            _ => format!("out_off_{}", rnd(64)),
+        };
+        let (per_block, root) = roots[&label];
+        match (odo.open(&label, row), bounded.open(&label, row)) {
+            (ColumnOpening::Block(o), ColumnOpening::Block(b)) => {
+                assert!(per_block, "{label} opened per block");
+                assert_eq!((b.value_le, &b.path), (o.value_le, &o.path));
+                assert!(
+                    verify_block_open(
+                        root,
+                        &label,
+                        o.value_le,
+                        o.row_lo,
+                        o.row_hi,
+                        o.block_index,
+                        &o.path,
+                    ),
+                    "{label} @ row {row}"
+                );
+            }
+            (ColumnOpening::Chunked(o), ColumnOpening::Chunked(b)) => {
+                assert!(!per_block, "{label} opened in chunks");
+                assert_eq!(
+                    (b.value_le, b.chunk_root, &b.path_to_chunk),
+                    (o.value_le, o.chunk_root, &o.path_to_chunk),
+                    "{label} @ row {row}"
+                );
+                assert!(
+                    verify_chunked_open(
+                        root,
+                        &label,
+                        o.value_le,
+                        o.chunk_root,
+                        o.index_in_chunk,
+                        &o.path_in_chunk,
+                        o.chunk_index,
+                        &o.path_to_chunk,
+                    ),
+                    "{label} @ row {row}"
+                );
+            }
+            _ => panic!("{label} @ row {row}: layouts differ under a bounded cache"),
+        }
+    }
+}
+
+#[test]
+fn batched_layer_paths_match_single_paths() {
+    for len in [1usize, 2, 5, 8, 13, 64] {
+        let leaves: Vec<[u8; 8]> = (0..len as u64)
+            .map(|i| (i * 31 + 7).to_le_bytes())
+            .collect();
+        let chunker = |sink: &mut dyn FnMut(&[[u8; 8]])| {
+            for c in leaves.chunks(3) {
+                sink(c);
+            }
+        };
+        let idxs: Vec<usize> = [0, len / 2, len - 1, 0].into_iter().collect();
+        let batched = merkle_paths_from_le_chunker(len, chunker, &idxs);
+        let single: Vec<_> = if len == 1 {
+            // The single-index helper needs at least one level above the leaf.
+            vec![(leaves[0], Vec::new()); idxs.len()]
+        } else {
+            idxs.iter()
+                .map(|&i| merkle_path_from_le_chunker(len, chunker, i))
+                .collect()
+        };
+        assert_eq!(batched, single, "len {len}");
+    }
+}
@@ -11,6 +11,7 @@
 //! - Head moves live in `{-1, 0, +1}` (as `i8`).
 //! - Tape writes (if any) are `SymbolId` values that match `sezkp_core`.
 //! - The trace length is `steps.len()`; the tape count is `tau`.
+//! - The input head starts at cell `0`; `input_len`, when declared, bounds it.
 //!
 //! ## Compatibility
 //! `TapeOp` converts losslessly into `sezkp_core::types::TapeOp`.
@@ -65,6 +66,11 @@ pub struct TraceFile {
     pub steps: Vec<Step>,
     /// Optional metadata (program hash, VM id, input hash…).
     pub meta: Option<serde_json::Value>,
+    /// Declared input tape length. When present, the input head must stay
+    /// within `[0, input_len)`; commits carry it into the manifest so replay
+    /// can enforce it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_len: Option<u64>,
 }
 
 impl TraceFile {
@@ -2,8 +2,10 @@
 //!
 //! The goal is to have *reproducible* inputs for tests/benches without pulling
 //! in a full VM. The generator produces a `TraceFile` with `t` steps and `τ`
-//! work tapes. Movements are uniform over `{-1,0,+1}` and writes are sampled
-//! with a fixed probability.
+//! work tapes (up to [`MAX_TAU`], the default read limit). The first
+//! [`UNIFORM_TAPES`] tapes take uniform moves over `{-1,0,+1}` and write with
+//! a fixed probability; higher tapes cycle through [`TapeProfile`]s so wide
+//! traces mix sweeping, scratch, read-only and idle tapes.
 //!
 //! - RNG is `StdRng` seeded with a constant for reproducibility.
 //! - Symbols are small (`0..=15`) to keep demo payloads compact.
@@ -23,44 +25,109 @@ use rand::{rngs::StdRng, Rng as _, SeedableRng};
 
 use crate::format::{Step, TapeOp, TraceFile};
 
+/// Most work tapes the generator is meant for: the default
+/// `SEZKP_MAX_TAU` read limit, so generated blocks load without raising it.
+pub const MAX_TAU: u8 = 64;
+
+/// Tapes `0..UNIFORM_TAPES` all follow [`TapeProfile::Uniform`], so traces of
+/// up to eight tapes are the same as before profiles existed.
+pub const UNIFORM_TAPES: usize = 8;
+
+/// How one generated work tape behaves.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TapeProfile {
+    /// Uniform moves over `{-1,0,+1}`, writes with probability `0.4`.
+    Uniform,
+    /// An output-like tape: moves right (probability `0.9`) or stays, and
+    /// writes with probability `0.8`. Its window grows with the block.
+    Sweep,
+    /// A scratch cell: stays with probability `0.8`, otherwise steps either
+    /// way, and writes with probability `0.6`.
+    Scratch,
+    /// Uniform moves that never write.
+    ReadOnly,
+    /// Never moves or writes, so all its columns are constant.
+    Idle,
+}
+
+impl TapeProfile {
+    /// Profile of work tape `r`: uniform below [`UNIFORM_TAPES`], then
+    /// `Sweep`, `Scratch`, `ReadOnly`, `Idle` in turn.
+    #[must_use]
+    pub const fn of_tape(r: usize) -> Self {
+        if r < UNIFORM_TAPES {
+            return Self::Uniform;
+        }
+        match (r - UNIFORM_TAPES) % 4 {
+            0 => Self::Sweep,
+            1 => Self::Scratch,
+            2 => Self::ReadOnly,
+            _ => Self::Idle,
+        }
+    }
+
+    fn step(self, rng: &mut StdRng) -> TapeOp {
+        let mut write = |p: f64| rng.random_bool(p).then(|| rng.random_range(0u16..=15u16));
+        let write = match self {
+            Self::Uniform => write(0.4),
+            Self::Sweep => write(0.8),
+            Self::Scratch => write(0.6),
+            Self::ReadOnly | Self::Idle => None,
+        };
+        let mv = match self {
+            Self::Uniform | Self::ReadOnly => unit_move(rng),
+            Self::Sweep => i8::from(rng.random_bool(0.9)),
+            Self::Scratch if rng.random_bool(0.8) => 0,
+            Self::Scratch => {
+                if rng.random_bool(0.5) {
+                    -1
+                } else {
+                    1
+                }
+            }
+            Self::Idle => 0,
+        };
+        TapeOp { write, mv }
+    }
+}
+
+/// A uniform move in `{-1, 0, +1}`.
+fn unit_move(rng: &mut StdRng) -> i8 {
+    match rng.random_range(0..=2) {
+        0 => -1,
+        1 => 0,
+        _ => 1,
+    }
+}
+
 /// Generate a synthetic movement log.
 ///
 /// - `input_mv` is a random step in `{-1, 0, +1}`.
-/// - Each tape either writes (symbol in `[0..=15]`) with probability `0.4`
-///   or no-ops, and then moves in `{-1,0,+1}`.
+/// - Work tape `r` follows [`TapeProfile::of_tape`]: the first eight write
+///   (symbol in `[0..=15]`) with probability `0.4` or no-op, and then move
+///   in `{-1,0,+1}`.
 ///
 /// The output is deterministic for a given `(t, tau)` pair.
 ///
 /// # Parameters
 /// - `t`: number of steps
-/// - `tau`: number of work tapes (`≤ 255`)
+/// - `tau`: number of work tapes (up to [`MAX_TAU`] for readable blocks)
 #[must_use]
 pub fn generate_trace(t: u64, tau: u8) -> TraceFile {
-    let mut rng = StdRng::seed_from_u64(42);
+    generate_trace_seeded(t, tau, 42)
+}
+
+/// Like [`generate_trace`], but seeds the RNG with `seed` (fixtures use this
+/// to get several independent, reproducible traces of the same shape).
+#[must_use]
+pub fn generate_trace_seeded(t: u64, tau: u8, seed: u64) -> TraceFile {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let profiles: Vec<TapeProfile> = (0..usize::from(tau)).map(TapeProfile::of_tape).collect();
     let mut steps = Vec::with_capacity(t as usize);
 
     for _ in 0..t {
-        let input_mv = match rng.random_range(0..=2) {
-            0 => -1,
-            1 => 0,
-            _ => 1,
-        };
-
-        let mut tapes = Vec::with_capacity(tau as usize);
-        for _ in 0..tau {
-            let write = if rng.random_bool(0.4) {
-                Some(rng.random_range(0u16..=15u16))
-            } else {
-                None
-            };
-            let mv = match rng.random_range(0..=2) {
-                0 => -1,
-                1 => 0,
-                _ => 1,
-            };
-            tapes.push(TapeOp { write, mv });
-        }
-
+        let input_mv = unit_move(&mut rng);
+        let tapes = profiles.iter().map(|p| p.step(&mut rng)).collect();
         steps.push(Step { input_mv, tapes });
     }
 
@@ -69,5 +136,51 @@ pub fn generate_trace(t: u64, tau: u8) -> TraceFile {
         tau,        // ≤ 255
         steps,      // length t
         meta: None, // no extra metadata for the toy generator
+        input_len: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::partition::partition_trace;
+
+    #[test]
+    fn profiles_cycle_above_the_uniform_tapes() {
+        assert!((0..UNIFORM_TAPES).all(|r| TapeProfile::of_tape(r) == TapeProfile::Uniform));
+        let next: Vec<_> = (UNIFORM_TAPES..UNIFORM_TAPES + 5)
+            .map(TapeProfile::of_tape)
+            .collect();
+        assert_eq!(
+            next,
+            [
+                TapeProfile::Sweep,
+                TapeProfile::Scratch,
+                TapeProfile::ReadOnly,
+                TapeProfile::Idle,
+                TapeProfile::Sweep,
+            ]
+        );
+    }
+
+    #[test]
+    fn wide_traces_follow_their_profiles() {
+        let tf = generate_trace(256, MAX_TAU);
+        assert!(tf.steps.iter().all(|s| s.tapes.len() == 64));
+        for r in 0..usize::from(MAX_TAU) {
+            let ops: Vec<&TapeOp> = tf.steps.iter().map(|s| &s.tapes[r]).collect();
+            let writes = ops.iter().filter(|op| op.write.is_some()).count();
+            let net: i64 = ops.iter().map(|op| i64::from(op.mv)).sum();
+            match TapeProfile::of_tape(r) {
+                TapeProfile::Idle => assert!(writes == 0 && ops.iter().all(|op| op.mv == 0)),
+                TapeProfile::ReadOnly => assert_eq!(writes, 0, "tape {r}"),
+                TapeProfile::Sweep => assert!(net > 128 && writes > 128, "tape {r}"),
+                TapeProfile::Scratch | TapeProfile::Uniform => assert!(writes > 0, "tape {r}"),
+            }
+        }
+
+        let blocks = partition_trace(&tf, 64);
+        assert_eq!(blocks.len(), 4);
+        assert!(blocks.iter().all(|b| b.windows.len() == 64));
     }
 }
@@ -8,6 +8,7 @@
 //! - `partition`: a projector that slices a `TraceFile` into σ_k blocks
 //!   (`BlockSummary`) used by downstream proof pipelines.
 //! - `io`: JSON/CBOR read/write helpers for `TraceFile`.
+//! - `stats`: one-pass summary statistics over a block set.
 //!
 //! The intent is to keep the trace pipeline simple, testable, and easy to
 //! replace with production sources later (a real VM or importer).
@@ -34,6 +35,8 @@ pub mod generator;
 pub mod io;
 /// Partition a `TraceFile` into σ_k (`BlockSummary`) windows/logs.
 pub mod partition;
+/// Summary statistics over σ_k blocks (steps, write density, head excursion).
+pub mod stats;
 
 // (Intentionally no broad re-exports so downstream callers import
 // stable module paths like `sezkp_trace::partition::partition_trace`.)
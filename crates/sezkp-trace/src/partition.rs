@@ -6,12 +6,16 @@
 //!
 //! - `windows[r] = [left, right]` — the min/max head coordinate visited by
 //!   tape `r` after applying each step’s movement (write happens after move).
-//! - `head_in_offsets[r]` — entry head offset within `windows[r]` (i.e. `0 - left`).
+//! - `head_in_offsets[r]` — entry head offset within `windows[r]`
+//!   (i.e. `entry[r] - left`).
 //! - `head_out_offsets[r]` — exit head offset within `windows[r]`
 //!   (i.e. `cur_heads[r] - left`).
 //!
-//! The input head drift is tracked as an **absolute** position across the full
-//! trace and stored in `in_head_in` / `in_head_out` for each block.
+//! Work-tape heads and the input head are tracked as **absolute** positions
+//! across the full trace: each block's window starts at the cell where the
+//! previous block left the head, so adjacent windows satisfy the continuity
+//! check in `sezkp_core::replay`. The input head is stored in
+//! `in_head_in` / `in_head_out` for each block.
 //!
 //! This matches the ARE semantics of *move, then (optionally) write*.
 
@@ -27,7 +31,23 @@
 )]
 
 use crate::format::{Step as FStep, TraceFile};
-use sezkp_core::{BlockSummary, MovementLog, StepProjection, TapeOp as CoreTapeOp, Window};
+use serde::{Deserialize, Serialize};
+use sezkp_core::{
+    BlockSummary, MovementLog, StepProjection, TapeGeometry, TapeOp as CoreTapeOp, Window,
+};
+use std::borrow::Borrow;
+
+/// How a trace is cut into blocks of at most `b` steps.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PartitionStrategy {
+    /// Blocks of exactly `b` steps; the last one takes the remainder.
+    #[default]
+    Fixed,
+    /// The fewest blocks of at most `b` steps (`⌈T/b⌉`), sized within one
+    /// step of each other, so no short tail block.
+    Balanced,
+}
 
 /// Partition a trace into contiguous blocks of size `b` (last may be shorter),
 /// producing σ_k (`BlockSummary`) with per-tape windows and offsets large
@@ -38,125 +58,191 @@ use sezkp_core::{BlockSummary, MovementLog, StepProjection, TapeOp as CoreTapeOp
 /// 2) (optionally) write at the **new** position
 ///
 /// # Panics
-/// Panics if `b == 0` (invalid block size).
+/// Panics if `b == 0` (invalid block size), or if a block's window is wider
+/// than the `u32` offset range (needs `b > 2^31`).
 #[must_use]
 pub fn partition_trace(tf: &TraceFile, b: u32) -> Vec<BlockSummary> {
-    let steps = &tf.steps;
-    let t = steps.len();
-    if t == 0 {
-        return Vec::new();
+    partition_iter(tf, b, PartitionStrategy::Fixed).collect()
+}
+
+/// Lazily partition a trace: blocks are produced one at a time, so a
+/// consumer that hashes or proves them as they come (e.g.
+/// `sezkp_merkle::commit_trace`) never holds more than one block.
+///
+/// Takes the trace by reference or by value (any `Borrow<TraceFile>`); an
+/// owning iterator can outlive the caller's borrow.
+///
+/// # Panics
+/// Panics if the trace is non-empty and `b == 0`; the iterator panics like
+/// [`partition_trace`] on windows wider than the `u32` offset range.
+#[must_use]
+pub fn partition_iter<T: Borrow<TraceFile>>(
+    tf: T,
+    b: u32,
+    strategy: PartitionStrategy,
+) -> Partitioner<T> {
+    let t = tf.borrow().steps.len();
+    let tau = tf.borrow().tau as usize;
+    assert!(t == 0 || b > 0, "partition_trace: block size b must be > 0");
+    let n_blocks = if t == 0 { 0 } else { t.div_ceil(b as usize) };
+    let (base, extra) = match strategy {
+        PartitionStrategy::Fixed => (b as usize, 0),
+        PartitionStrategy::Balanced if n_blocks > 0 => (t / n_blocks, t % n_blocks),
+        PartitionStrategy::Balanced => (0, 0),
+    };
+    Partitioner {
+        tf,
+        base,
+        extra,
+        n_blocks,
+        next_start: 0,
+        k: 1,
+        global_input_head: 0,
+        global_work_heads: vec![0; tau],
     }
-    assert!(b > 0, "partition_trace: block size b must be > 0");
-
-    let tau = tf.tau as usize;
-    let b = b as usize;
-
-    // Track absolute input-head position across the entire trace
-    // so `in_head_in/out` are global (not per-block relative).
-    let mut global_input_head: i64 = 0;
-
-    let mut out = Vec::new();
-    let mut k: u32 = 1;
-
-    for chunk_start in (0..t).step_by(b) {
-        let chunk_end = (chunk_start + b).min(t);
-        let block_steps: &[FStep] = &steps[chunk_start..chunk_end];
-
-        // --- Gather per-tape head spans.
-        // Heads start at 0 (per-block relative); offsets anchor them in the window.
-        let mut cur_heads: Vec<i64> = vec![0; tau];
-        let mut min_pos: Vec<i64> = vec![0; tau];
-        let mut max_pos: Vec<i64> = vec![0; tau];
-
-        // Track input-head drift across the block (absolute).
-        let in_head_in = global_input_head;
-        for st in block_steps {
-            // Input head drift.
-            global_input_head += i64::from(st.input_mv);
-
-            // Per-tape: first move, then (potential) write at the new cell.
-            for (r, op) in st.tapes.iter().enumerate() {
-                cur_heads[r] += i64::from(op.mv);
-                if cur_heads[r] < min_pos[r] {
-                    min_pos[r] = cur_heads[r];
-                }
-                if cur_heads[r] > max_pos[r] {
-                    max_pos[r] = cur_heads[r];
-                }
-            }
-        }
-        let in_head_out = global_input_head;
-
-        // --- Build windows and entry/exit offsets.
-        let mut windows = Vec::with_capacity(tau);
-        let mut head_in_offsets = Vec::with_capacity(tau);
-        let mut head_out_offsets = Vec::with_capacity(tau);
-
-        for r in 0..tau {
-            let left = min_pos[r];
-            let right = max_pos[r];
-            windows.push(Window { left, right });
-
-            // Entry head is 0 (relative) → entry offset within window is (0 - left).
-            let off_in = 0i64 - left;
-            // Exit head is cur_heads[r] (relative) → exit offset is (cur - left).
-            let off_out = cur_heads[r] - left;
-
-            // Offsets are non-negative so long as `left <= 0`.
-            // Clamp on conversion overflow to keep this prototype total.
-            let off_in_u32 = u32::try_from(off_in).unwrap_or(u32::MAX);
-            let off_out_u32 = u32::try_from(off_out).unwrap_or(u32::MAX);
-
-            head_in_offsets.push(off_in_u32);
-            head_out_offsets.push(off_out_u32);
+}
+
+/// Iterator returned by [`partition_iter`].
+#[derive(Debug)]
+pub struct Partitioner<T> {
+    tf: T,
+    /// Steps per block, before the remainder is spread.
+    base: usize,
+    /// The first `extra` blocks take one more step ([`PartitionStrategy::Balanced`]).
+    extra: usize,
+    n_blocks: usize,
+    next_start: usize,
+    /// Next block id (1-based).
+    k: u32,
+    /// Absolute input-head position across the entire trace, so
+    /// `in_head_in/out` are global (not per-block relative).
+    global_input_head: i64,
+    /// Absolute work-tape head positions across the entire trace.
+    global_work_heads: Vec<i64>,
+}
+
+impl<T: Borrow<TraceFile>> Iterator for Partitioner<T> {
+    type Item = BlockSummary;
+
+    fn next(&mut self) -> Option<BlockSummary> {
+        let tf = self.tf.borrow();
+        let t = tf.steps.len();
+        if self.next_start >= t {
+            return None;
         }
+        let idx = (self.k - 1) as usize;
+        let len = self.base + usize::from(idx < self.extra);
+        let chunk_start = self.next_start;
+        let chunk_end = (chunk_start + len).min(t);
+        let sigma = summarize_block(
+            &tf.steps[chunk_start..chunk_end],
+            tf.tau as usize,
+            self.k,
+            chunk_start as u64 + 1,
+            &mut self.global_input_head,
+            &mut self.global_work_heads,
+        );
+        self.next_start = chunk_end;
+        self.k += 1;
+        Some(sigma)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = self.n_blocks - (self.k - 1) as usize;
+        (left, Some(left))
+    }
+}
 
-        // --- Convert steps to the runtime movement log format (core types).
-        let mut proj_steps = Vec::with_capacity(block_steps.len());
-        for st in block_steps {
-            let tapes = st
-                .tapes
-                .iter()
-                .map(|t| CoreTapeOp { write: t.write, mv: t.mv })
-                .collect::<Vec<_>>();
-            proj_steps.push(StepProjection { input_mv: st.input_mv, tapes });
+impl<T: Borrow<TraceFile>> ExactSizeIterator for Partitioner<T> {}
+
+/// Summarize `block_steps` as block `k`, starting at 1-based step `step_lo`.
+fn summarize_block(
+    block_steps: &[FStep],
+    tau: usize,
+    k: u32,
+    step_lo: u64,
+    global_input_head: &mut i64,
+    global_work_heads: &mut [i64],
+) -> BlockSummary {
+    // --- Gather per-tape head spans.
+    // Heads enter where the previous block left them (absolute); offsets
+    // anchor them in the window.
+    let entry = global_work_heads.to_vec();
+    let mut cur_heads = entry.clone();
+    let mut windows: Vec<Window> = entry.iter().map(|&h| Window::at(h)).collect();
+
+    // Track input-head drift across the block (absolute).
+    let in_head_in = *global_input_head;
+    for st in block_steps {
+        // Input head drift.
+        *global_input_head += i64::from(st.input_mv);
+
+        // Per-tape: first move, then (potential) write at the new cell.
+        for (r, op) in st.tapes.iter().enumerate() {
+            cur_heads[r] += i64::from(op.mv);
+            windows[r].include(cur_heads[r]);
         }
+    }
+    let in_head_out = *global_input_head;
+
+    global_work_heads.copy_from_slice(&cur_heads);
 
-        // --- Assemble σ_k.
-        let sigma = BlockSummary {
-            version: 1,
-            block_id: k,
-            step_lo: (chunk_start as u64) + 1, // 1-based inclusive
-            step_hi: chunk_end as u64,         // inclusive
-            // Advisory finite control for the toy pipeline.
-            ctrl_in: 0,
-            ctrl_out: 0,
-            in_head_in,
-            in_head_out,
-            windows,
-            head_in_offsets,
-            head_out_offsets,
-            movement_log: MovementLog { steps: proj_steps },
-            // Keep pre/post tags allocated to τ for shape compatibility.
-            pre_tags: vec![[0u8; 16]; tau],
-            post_tags: vec![[0u8; 16]; tau],
-        };
-
-        out.push(sigma);
-        k += 1;
+    // --- Entry head is where the block found it, exit head where it left it.
+    let geometry: Vec<TapeGeometry> = windows
+        .iter()
+        .zip(entry.iter().zip(&cur_heads))
+        .map(|(&w, (&inp, &out))| {
+            TapeGeometry::new(w, inp, out)
+                .unwrap_or_else(|e| panic!("partition_trace: block {k}: {e:#}"))
+        })
+        .collect();
+
+    // --- Convert steps to the runtime movement log format (core types).
+    let mut proj_steps = Vec::with_capacity(block_steps.len());
+    for st in block_steps {
+        let tapes = st
+            .tapes
+            .iter()
+            .map(|t| CoreTapeOp { write: t.write, mv: t.mv })
+            .collect::<Vec<_>>();
+        proj_steps.push(StepProjection { input_mv: st.input_mv, tapes });
     }
 
-    out
+    // --- Assemble σ_k.
+    let mut sigma = BlockSummary {
+        version: 1,
+        block_id: k,
+        step_lo,                                         // 1-based inclusive
+        step_hi: step_lo + block_steps.len() as u64 - 1, // inclusive
+        // Advisory finite control for the toy pipeline.
+        ctrl_in: 0,
+        ctrl_out: 0,
+        in_head_in,
+        in_head_out,
+        windows: Vec::new(),
+        head_in_offsets: Vec::new(),
+        head_out_offsets: Vec::new(),
+        movement_log: MovementLog { steps: proj_steps },
+        // Keep pre/post tags allocated to τ for shape compatibility.
+        pre_tags: vec![[0u8; 16]; tau],
+        post_tags: vec![[0u8; 16]; tau],
+        write_commitments: Vec::new(),
+        aux_commitments: Vec::new(),
+    };
+    sigma.set_geometry(&geometry);
+    sigma
 }
 
 #[cfg(test)]
 mod tests {
+    #![allow(clippy::unwrap_used)]
+
     use super::*;
     use crate::generator::generate_trace;
 
     #[test]
     fn partitions_empty_trace() {
-        let tf = TraceFile { version: 1, tau: 2, steps: vec![], meta: None };
+        let tf = TraceFile { version: 1, tau: 2, steps: vec![], meta: None, input_len: None };
         let v = partition_trace(&tf, 4);
         assert!(v.is_empty());
     }
@@ -174,4 +260,40 @@ mod tests {
         assert_eq!(v[2].step_lo, 9);
         assert_eq!(v[2].step_hi, 10);
     }
+
+    #[test]
+    fn balanced_blocks_differ_by_at_most_one_step() {
+        let tf = generate_trace(10, 2);
+        let v: Vec<_> = partition_iter(&tf, 4, PartitionStrategy::Balanced).collect();
+        // 10 steps with b=4 -> 3 blocks: [4, 3, 3]
+        let spans: Vec<_> = v.iter().map(|b| (b.step_lo, b.step_hi)).collect();
+        assert_eq!(spans, [(1, 4), (5, 7), (8, 10)]);
+        assert_eq!(
+            v.last().map(|b| b.in_head_out),
+            partition_trace(&tf, 4).last().map(|b| b.in_head_out)
+        );
+    }
+
+    #[test]
+    fn owning_iterator_matches_partition_trace() {
+        let tf = generate_trace(37, 3);
+        let want = partition_trace(&tf, 8);
+        let it = partition_iter(tf, 8, PartitionStrategy::Fixed);
+        assert_eq!(it.len(), want.len());
+        assert_eq!(it.collect::<Vec<_>>(), want);
+    }
+
+    #[test]
+    fn work_heads_carry_across_blocks() {
+        let tf = generate_trace(64, 2);
+        let v = partition_trace(&tf, 8);
+        for pair in v.windows(2) {
+            for r in 0..2 {
+                let prev = pair[0].tape_geometry(r).unwrap();
+                let next = pair[1].tape_geometry(r).unwrap();
+                assert_eq!(prev.heads().1, next.heads().0);
+                assert!(next.window.contains(prev.heads().1));
+            }
+        }
+    }
 }
@@ -0,0 +1,284 @@
+//! Summary statistics over σ_k blocks.
+//!
+//! One pass over the blocks yields:
+//!
+//! - **steps per block** — min / mean / max and a histogram;
+//! - **write density per tape** — the fraction of steps that write;
+//! - **head excursion per tape** — how far the head strays from its entry
+//!   position within a block (the larger of `head_in_offsets[r]` and the
+//!   distance to the far edge of `windows[r]`), as a histogram.
+//!
+//! Blocks are pushed one at a time through [`StatsCollector`], so the same
+//! pass works over a streamed JSONL file; [`block_stats`] covers a slice.
+//! [`TraceStats`] serializes to JSON for machine consumers and implements
+//! `Display` for the human summary printed by `simulate`.
+
+#![forbid(unsafe_code)]
+#![deny(rust_2018_idioms)]
+#![warn(
+    missing_docs,
+    clippy::all,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::unwrap_used,
+    clippy::expect_used
+)]
+
+use serde::{Deserialize, Serialize};
+use sezkp_core::BlockSummary;
+use std::fmt;
+
+/// Power-of-two histogram of `u64` samples.
+///
+/// Bucket 0 counts zeros; bucket `i ≥ 1` counts values in `[2^(i-1), 2^i)`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Histogram {
+    /// Number of samples.
+    pub count: u64,
+    /// Smallest sample (0 when empty).
+    pub min: u64,
+    /// Largest sample (0 when empty).
+    pub max: u64,
+    /// Sum of all samples.
+    pub sum: u64,
+    /// Sample count per bucket; trailing empty buckets are not stored.
+    pub buckets: Vec<u64>,
+}
+
+impl Histogram {
+    /// Add one sample.
+    pub fn record(&mut self, v: u64) {
+        self.min = if self.count == 0 { v } else { self.min.min(v) };
+        self.max = self.max.max(v);
+        self.count += 1;
+        self.sum = self.sum.saturating_add(v);
+        let i = (u64::BITS - v.leading_zeros()) as usize;
+        if self.buckets.len() <= i {
+            self.buckets.resize(i + 1, 0);
+        }
+        self.buckets[i] += 1;
+    }
+
+    /// Mean sample (0 when empty).
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn mean(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.sum as f64 / self.count as f64
+        }
+    }
+
+    /// Inclusive value range `(lo, hi)` of bucket `i`.
+    #[must_use]
+    pub const fn bucket_range(i: usize) -> (u64, u64) {
+        match i {
+            0 => (0, 0),
+            64.. => (1 << 63, u64::MAX),
+            _ => (1 << (i - 1), (1 << i) - 1),
+        }
+    }
+}
+
+/// Statistics for one work tape.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TapeStats {
+    /// Steps that write on this tape.
+    pub writes: u64,
+    /// `writes` over all steps (0 when there are none).
+    pub write_density: f64,
+    /// Per-block head excursion from the entry position, in cells.
+    pub excursion: Histogram,
+}
+
+/// Statistics over a block set (see the module docs).
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TraceStats {
+    /// Number of blocks.
+    pub blocks: u64,
+    /// Total steps over all blocks.
+    pub steps: u64,
+    /// Steps per block.
+    pub steps_per_block: Histogram,
+    /// One entry per work tape (the widest τ seen).
+    pub tapes: Vec<TapeStats>,
+}
+
+/// Accumulates [`TraceStats`] one block at a time.
+#[derive(Clone, Debug, Default)]
+pub struct StatsCollector {
+    steps_per_block: Histogram,
+    writes: Vec<u64>,
+    excursion: Vec<Histogram>,
+}
+
+impl StatsCollector {
+    /// Empty collector.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Account for one block.
+    pub fn push(&mut self, b: &BlockSummary) {
+        self.steps_per_block
+            .record(b.movement_log.steps.len() as u64);
+
+        let tau = b.windows.len();
+        if self.writes.len() < tau {
+            self.writes.resize(tau, 0);
+            self.excursion.resize(tau, Histogram::default());
+        }
+        for step in &b.movement_log.steps {
+            for (r, op) in step.tapes.iter().enumerate().take(tau) {
+                self.writes[r] += u64::from(op.write.is_some());
+            }
+        }
+        for (r, w) in b.windows.iter().enumerate() {
+            let span = w.right.abs_diff(w.left);
+            let entry = b.head_in_offsets.get(r).map_or(0, |&o| u64::from(o));
+            self.excursion[r].record(entry.max(span.saturating_sub(entry)));
+        }
+    }
+
+    /// Finish the pass.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn finish(self) -> TraceStats {
+        let steps = self.steps_per_block.sum;
+        let tapes = self
+            .writes
+            .into_iter()
+            .zip(self.excursion)
+            .map(|(writes, excursion)| TapeStats {
+                writes,
+                write_density: if steps == 0 {
+                    0.0
+                } else {
+                    writes as f64 / steps as f64
+                },
+                excursion,
+            })
+            .collect();
+        TraceStats {
+            blocks: self.steps_per_block.count,
+            steps,
+            steps_per_block: self.steps_per_block,
+            tapes,
+        }
+    }
+}
+
+/// Statistics over a slice of blocks.
+#[must_use]
+pub fn block_stats(blocks: &[BlockSummary]) -> TraceStats {
+    let mut c = StatsCollector::new();
+    for b in blocks {
+        c.push(b);
+    }
+    c.finish()
+}
+
+/// Bucket lines of a histogram, indented under its heading.
+fn fmt_buckets(f: &mut fmt::Formatter<'_>, h: &Histogram) -> fmt::Result {
+    for (i, &n) in h.buckets.iter().enumerate().filter(|(_, &n)| n > 0) {
+        let (lo, hi) = Histogram::bucket_range(i);
+        let range = if lo == hi {
+            lo.to_string()
+        } else {
+            format!("{lo}..={hi}")
+        };
+        writeln!(f, "    {range:<14} {n}")?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for TraceStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let h = &self.steps_per_block;
+        writeln!(f, "Blocks:        {} ({} steps)", self.blocks, self.steps)?;
+        writeln!(
+            f,
+            "Steps/block:   min {}, mean {:.1}, max {}",
+            h.min,
+            h.mean(),
+            h.max
+        )?;
+        fmt_buckets(f, h)?;
+        for (r, t) in self.tapes.iter().enumerate() {
+            let e = &t.excursion;
+            writeln!(
+                f,
+                "Tape {r}:        {} writes ({:.1}% of steps); excursion min {}, mean {:.1}, max {}",
+                t.writes,
+                100.0 * t.write_density,
+                e.min,
+                e.mean(),
+                e.max
+            )?;
+            fmt_buckets(f, e)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used, clippy::float_cmp)]
+
+    use super::*;
+    use crate::format::{Step, TapeOp, TraceFile};
+    use crate::partition::partition_trace;
+
+    fn step(mv: i8, write: Option<u16>) -> Step {
+        Step {
+            input_mv: 0,
+            tapes: vec![TapeOp { write, mv }, TapeOp { write: None, mv: 0 }],
+        }
+    }
+
+    #[test]
+    fn histogram_buckets_are_powers_of_two() {
+        let mut h = Histogram::default();
+        for v in [0, 1, 2, 3, 4, 7, 8] {
+            h.record(v);
+        }
+        assert_eq!(h.buckets, [1, 1, 2, 2, 1]);
+        assert_eq!((h.min, h.max, h.sum, h.count), (0, 8, 25, 7));
+        assert_eq!(Histogram::bucket_range(3), (4, 7));
+        assert_eq!(Histogram::bucket_range(64), (1 << 63, u64::MAX));
+    }
+
+    #[test]
+    fn stats_follow_the_trace() {
+        // Tape 0 walks right 3 cells, back 1, writing on every other step;
+        // tape 1 never moves or writes.
+        let moves = [1, 1, 1, -1, 0, 0];
+        let tf = TraceFile {
+            version: 1,
+            tau: 2,
+            steps: moves
+                .iter()
+                .enumerate()
+                .map(|(i, &mv)| step(mv, (i % 2 == 0).then_some(1)))
+                .collect(),
+            meta: None,
+            input_len: None,
+        };
+        let s = block_stats(&partition_trace(&tf, 4));
+        assert_eq!((s.blocks, s.steps), (2, 6));
+        assert_eq!((s.steps_per_block.min, s.steps_per_block.max), (2, 4));
+        assert_eq!(s.tapes.len(), 2);
+        assert_eq!(s.tapes[0].writes, 3);
+        assert_eq!(s.tapes[0].write_density, 0.5);
+        // Block 1 reaches 3 cells right of its entry; block 2 stays put.
+        assert_eq!((s.tapes[0].excursion.min, s.tapes[0].excursion.max), (0, 3));
+        assert_eq!(s.tapes[1].writes, 0);
+        assert_eq!(s.tapes[1].excursion.max, 0);
+
+        let back: TraceStats = serde_json::from_str(&serde_json::to_string(&s).unwrap()).unwrap();
+        assert_eq!(back, s);
+        assert!(s.to_string().contains("Tape 0:"));
+    }
+}
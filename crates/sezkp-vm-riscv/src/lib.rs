@@ -7,6 +7,10 @@
 //! - `make_trace(steps)`: build a toy 2-tape trace using `sezkp-trace`’s generator.
 //! - `RiscvAdapter::demo_block(...)`: synthesize a single block summary σ_k.
 //!
+//! A real adapter would also attach per-block side data it wants proofs to
+//! carry (e.g. a program-counter histogram) as
+//! [`BlockSummary::aux_commitments`], see [`sezkp_core::auxiliary`].
+//!
 //! Production note: this crate intentionally stays tiny and dependency-light so it
 //! can be used in examples and tests without requiring a full VM toolchain.
 
@@ -22,7 +26,7 @@
 )]
 
 use anyhow::Result;
-use sezkp_core::{BlockSummary, MovementLog, StepProjection, TapeOp, Window};
+use sezkp_core::{BlockSummary, MovementLog, StepProjection, TapeGeometry, TapeOp, Window};
 use sezkp_trace::{format::TraceFile, generator::generate_trace};
 
 /// Produce a toy trace with `τ = 2` tapes and `steps` rows.
@@ -44,6 +48,10 @@ impl RiscvAdapter {
     ///
     /// The block contains `len` steps, two tapes, and zero control changes.
     /// Values here are deterministic and simple so tests remain readable.
+    ///
+    /// # Errors
+    /// Fails if `len < 2`, which leaves the second tape's exit head outside
+    /// its window.
     pub fn demo_block(block_id: u32, len: usize) -> Result<BlockSummary> {
         let steps = vec![
             StepProjection {
@@ -56,7 +64,13 @@ impl RiscvAdapter {
             len
         ];
 
-        Ok(BlockSummary {
+        let last = len as i64 - 1;
+        let geometry = [
+            TapeGeometry::new(Window::try_new(0, last)?, 0, last)?,
+            TapeGeometry::new(Window::try_new(-1, last - 1)?, -1, last - 2)?,
+        ];
+
+        let mut sigma = BlockSummary {
             version: 1,
             block_id,
             step_lo: 1 + (block_id as u64 - 1) * len as u64,
@@ -65,16 +79,17 @@ impl RiscvAdapter {
             ctrl_out: 0,
             in_head_in: 0,
             in_head_out: len as i64,
-            windows: vec![
-                Window { left: 0, right: len as i64 - 1 },
-                Window { left: -1, right: len as i64 - 2 },
-            ],
-            head_in_offsets: vec![0, 0],
-            head_out_offsets: vec![(len - 1) as u32, (len - 2) as u32],
+            windows: Vec::new(),
+            head_in_offsets: Vec::new(),
+            head_out_offsets: Vec::new(),
             movement_log: MovementLog { steps },
             pre_tags: vec![[0u8; 16]; 2],
             post_tags: vec![[0u8; 16]; 2],
-        })
+            write_commitments: Vec::new(),
+            aux_commitments: Vec::new(),
+        };
+        sigma.set_geometry(&geometry);
+        Ok(sigma)
     }
 }
 
@@ -95,5 +110,7 @@ mod tests {
         assert_eq!(b.windows.len(), 2);
         assert_eq!(b.step_lo, 1);
         assert_eq!(b.step_hi, 4);
+        assert_eq!(b.head_out_offsets, [3, 2]);
+        assert!(RiscvAdapter::demo_block(1, 1).is_err());
     }
 }
@@ -7,7 +7,7 @@
 //! 4) Prove with selected backend (`--proto v0|v1|fold`) → `proof.cbor`.
 //! 5) Verify: checks blocks vs manifest, and verifies the proof.
 //!
-//! Folding backend knobs (forwarded via env to `sezkp-fold`):
+//! Folding backend knobs (passed to `FoldBackend::with_options`):
 //!   --fold-mode balanced|minram
 //!   --wrap-cadence N
 //!
@@ -31,7 +31,8 @@ use std::fs;
 use std::path::PathBuf;
 
 use anyhow::{bail, Context, Result};
-use sezkp_core::ProvingBackend;
+use sezkp_core::{ConfiguredBackend, ProvingBackend};
+use sezkp_fold::api::{DriverOptions, FoldMode};
 use sezkp_fold::FoldBackend;
 use sezkp_merkle::{commit_block_file, verify_block_file_against_manifest};
 use sezkp_stark::{StarkIOP, StarkV1};
@@ -76,7 +77,7 @@ fn main() -> Result<()> {
     let _chunk_log2: usize = parse_arg("chunk-log2", 12);
     let _fri_out_chunk_log2: usize = parse_arg("fri-out-chunk-log2", 12);
 
-    // Folding knobs (forwarded via env to sezkp-fold).
+    // Folding knobs (carried by the configured fold backend).
     let fold_mode = parse_str("fold-mode", "balanced"); // balanced | minram
     let wrap_cadence: u32 = parse_arg("wrap-cadence", 0);
 
@@ -112,12 +113,19 @@ fn main() -> Result<()> {
         manifest_path.display()
     );
 
-    // 4) Prove per backend. For folding, forward CLI knobs via env so the
-    //    backend can pick them up (`opts_from_env` inside sezkp-fold).
-    if matches!(proto.as_str(), "fold" | "v2") {
-        env::set_var("SEZKP_FOLD_MODE", fold_mode.clone());
-        env::set_var("SEZKP_WRAP_CADENCE", wrap_cadence.to_string());
-    }
+    // 4) Prove per backend. The fold knobs travel with the backend value.
+    let fold = FoldBackend::with_options(
+        DriverOptions {
+            fold_mode: if fold_mode.eq_ignore_ascii_case("minram") {
+                FoldMode::MinRam
+            } else {
+                FoldMode::Balanced
+            },
+            wrap_cadence,
+            ..DriverOptions::default()
+        },
+        None,
+    );
 
     let artifact = match proto.as_str() {
         "v0" => {
@@ -131,9 +139,9 @@ fn main() -> Result<()> {
             art
         }
         "fold" | "v2" => {
-            let art = FoldBackend::prove(&blocks, manifest.root)?;
+            let art = fold.prove(&blocks, manifest.root)?;
             println!(
-                "Proved (fold-v2) [mode={}, wrap-cadence={}]",
+                "Proved (fold-v3) [mode={}, wrap-cadence={}]",
                 fold_mode, wrap_cadence
             );
             art
@@ -150,7 +158,7 @@ fn main() -> Result<()> {
     match proto.as_str() {
         "v0" => StarkIOP::verify(&artifact, &blocks, manifest.root)?,
         "v1" => StarkV1::verify(&artifact, &blocks, manifest.root)?,
-        "fold" | "v2" => FoldBackend::verify(&artifact, &blocks, manifest.root)?,
+        "fold" | "v2" => fold.verify(&artifact, &blocks, manifest.root)?,
         _ => unreachable!(),
     }
     println!("Verified OK.");
@@ -1,3 +1,6 @@
 #!/usr/bin/env sh
 set -eu
 cargo build --workspace
+# The N-API addon is feature-gated out of the workspace build; type-check it
+# so binding signatures cannot break unnoticed.
+cargo check -p sezkp-node --features node